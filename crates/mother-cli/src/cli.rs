@@ -0,0 +1,463 @@
+//! Command-line interface: the clap definition of the `mother` binary
+//!
+//! Lives in the library so tools like `mother completions` and `mother
+//! manpages` can generate from the same definition the binary parses.
+
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Parser, Subcommand};
+use clap_complete::Shell;
+use mother_core::enrich::parse_kind;
+use mother_core::graph::export::ExportFormat;
+use mother_core::graph::ids::SymbolIdScheme;
+use mother_core::graph::model::SymbolKind;
+use mother_core::scanner::{parse_size, Language};
+use mother_scan::{parse_duration, ChaosConfig};
+
+use crate::config::StoreArgs;
+use crate::telemetry::LogFormat;
+use crate::types::{
+    DoctorCommands, ImportCommands, MetricsArgs, OutputFormat, QueryCommands, RunsCommands,
+    SelfTestCommands, ServeCommands,
+};
+
+#[derive(Parser)]
+#[command(name = "mother")]
+#[command(author, version, about = "AST graph ingestion tool", long_about = None)]
+pub struct Cli {
+    /// Enable verbose output
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Config file to use instead of mother.toml or .mother/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Format of log lines on stderr [default: text]
+    #[arg(long, global = true, value_enum, env = "MOTHER_LOG_FORMAT")]
+    pub log_format: Option<LogFormat>,
+
+    /// OTLP/HTTP collector to export traces to, e.g. http://localhost:4318
+    /// (needs the `otel` feature)
+    #[arg(long, global = true, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+// Parsed once per run, so the size of `Scan` and its many flags doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Scan a repository and store AST in the graph store
+    Scan {
+        /// Path to the repository to scan
+        #[arg(required_unless_present = "bare")]
+        path: Option<PathBuf>,
+
+        /// Scan a commit of this bare repository instead of a worktree; the
+        /// commit is checked out to a temporary directory for the scan
+        #[arg(
+            long,
+            value_name = "REPO",
+            conflicts_with_all = ["path", "estimate", "dry_run"],
+            requires = "commit"
+        )]
+        bare: Option<PathBuf>,
+
+        /// Commit, branch or tag of the bare repository to scan
+        #[arg(long, requires = "bare")]
+        commit: Option<String>,
+
+        #[command(flatten)]
+        store: StoreArgs,
+
+        /// Version tag for this scan
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Comma-separated enrichers to run in Phase 2, in order
+        /// (available: hover, signature, visibility; default: hover)
+        #[arg(long, value_delimiter = ',')]
+        enrichers: Option<Vec<String>>,
+
+        /// Skip the hover enricher, trading doc comments and type signatures
+        /// for a faster Phase 2
+        #[arg(long)]
+        no_hover: bool,
+
+        /// Comma-separated kinds of symbols to hover, e.g. `functions,methods`;
+        /// other symbols get no hover request [default: all]
+        #[arg(long, value_delimiter = ',', value_parser = parse_kind, conflicts_with = "no_hover")]
+        hover_kinds: Option<Vec<SymbolKind>>,
+
+        /// Most hover requests in flight at once for a file [default: 8]
+        #[arg(long, value_name = "N", conflicts_with = "no_hover")]
+        hover_concurrency: Option<NonZeroUsize>,
+
+        /// Estimate graph size and scan duration from a sample instead of scanning
+        #[arg(long)]
+        estimate: bool,
+
+        /// Number of files to sample when estimating
+        #[arg(long, default_value_t = 25, requires = "estimate")]
+        sample_size: usize,
+
+        /// Run discovery and symbol extraction and print a report without writing to the store
+        #[arg(long, conflicts_with = "estimate")]
+        dry_run: bool,
+
+        /// Store at most this many references per symbol, sampling the rest;
+        /// the symbol records the total and `is_truncated`
+        #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        max_references: Option<usize>,
+
+        /// Skip files larger than this, e.g. `512KB` or `2MB`, such as
+        /// vendored bundles language servers time out on
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        max_file_size: Option<u64>,
+
+        /// Hold at most this many symbols in memory between Phases 2 and 3,
+        /// spilling the rest to a temporary file to bound memory on very
+        /// large repositories
+        #[arg(long, value_name = "N", conflicts_with_all = ["estimate", "dry_run"])]
+        symbol_buffer: Option<NonZeroUsize>,
+
+        /// How stored symbols are named: content-hash (default),
+        /// qualified-name for readable IDs that match across machines, or
+        /// uuid
+        #[arg(long, value_name = "SCHEME")]
+        symbol_ids: Option<SymbolIdScheme>,
+
+        /// Write per-phase throughput metrics to this JSON file
+        #[arg(long, conflicts_with_all = ["estimate", "dry_run"])]
+        report: Option<PathBuf>,
+
+        /// Write the files and symbols the scan failed on, with the failed
+        /// request and error, to this JSON file
+        #[arg(long, conflicts_with_all = ["estimate", "dry_run"])]
+        report_file: Option<PathBuf>,
+
+        /// Store the diagnostics (errors, warnings) language servers report
+        /// for each file, linked to the symbols they occur in
+        #[arg(long, conflicts_with_all = ["estimate", "dry_run"])]
+        diagnostics: bool,
+
+        /// Look up call sites no reference covered with go-to-definition and
+        /// link calls into the standard library and dependencies to
+        /// ExternalSymbol placeholders (one LSP request per call site)
+        #[arg(long, conflicts_with_all = ["estimate", "dry_run"])]
+        external_refs: bool,
+
+        /// Log the JSON-RPC traffic with each language server to
+        /// `<DIR>/<language>.jsonl`, with secrets redacted
+        #[arg(long, value_name = "DIR")]
+        lsp_trace: Option<PathBuf>,
+
+        /// Answer LSP requests from traces recorded with `--lsp-trace` in
+        /// this directory instead of starting language servers, to reproduce
+        /// a scan
+        #[arg(long, value_name = "DIR")]
+        lsp_replay: Option<PathBuf>,
+
+        /// Write the graph of the scan to this file, in the format of its
+        /// extension; for `--backend memory`, which keeps nothing afterwards
+        #[arg(long, value_name = "FILE", conflicts_with = "estimate")]
+        export: Option<PathBuf>,
+
+        /// Show per-phase progress bars (default: when stderr is a terminal)
+        #[arg(long, overrides_with = "no_progress")]
+        progress: bool,
+
+        /// Never show progress bars
+        #[arg(long, overrides_with = "progress")]
+        no_progress: bool,
+
+        /// Stop starting new LSP work after this long, e.g. `30m` or `1h30m`,
+        /// and end successfully with the run marked partial
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = parse_duration,
+            conflicts_with_all = ["estimate", "dry_run"]
+        )]
+        max_duration: Option<Duration>,
+
+        /// Inject faults for resilience testing, e.g.
+        /// `lsp-timeout=0.05,store-failure=0.01,kill=0.0001,seed=7`
+        #[arg(long, hide = true, value_name = "FAULTS")]
+        chaos: Option<ChaosConfig>,
+    },
+
+    /// Scan a stretch of git history one commit at a time, linking each scan
+    /// run to the previous one
+    ScanHistory {
+        /// Path to the repository, bare or not
+        path: PathBuf,
+
+        /// Oldest commit, branch or tag to scan
+        #[arg(long)]
+        from: String,
+
+        /// Newest commit, branch or tag to scan
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+
+        /// Scan every Nth commit along first parents; the newest is always
+        /// scanned
+        #[arg(long, default_value = "1")]
+        step: NonZeroUsize,
+
+        #[command(flatten)]
+        store: StoreArgs,
+
+        /// Comma-separated enrichers to run in Phase 2, in order
+        /// (available: hover, signature, visibility; default: hover)
+        #[arg(long, value_delimiter = ',')]
+        enrichers: Option<Vec<String>>,
+    },
+
+    /// Scan a repository, then keep its graph updated as files change
+    Watch {
+        /// Path to the repository to watch
+        path: PathBuf,
+
+        #[command(flatten)]
+        store: StoreArgs,
+
+        /// Version tag for the initial scan
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Comma-separated enrichers to run in Phase 2, in order
+        /// (available: hover, signature, visibility; default: hover)
+        #[arg(long, value_delimiter = ',')]
+        enrichers: Option<Vec<String>>,
+
+        #[command(flatten)]
+        metrics: MetricsArgs,
+    },
+
+    /// Query the graph store
+    Query {
+        #[command(subcommand)]
+        query_cmd: QueryCommands,
+
+        /// Output format: table, json or csv
+        #[arg(long, global = true, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Import code intelligence other tools produced into the graph store
+    Import {
+        #[command(subcommand)]
+        import_cmd: ImportCommands,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// List, show and delete recorded scan runs, or prune old ones
+    Runs {
+        #[command(subcommand)]
+        runs_cmd: RunsCommands,
+
+        /// Output format: table, json or csv
+        #[arg(long, global = true, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Check the stored graph for problems ingestion left behind
+    Doctor {
+        #[command(subcommand)]
+        doctor_cmd: DoctorCommands,
+
+        /// Output format: table, json or csv
+        #[arg(long, global = true, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Compare the symbols of two scan versions: added, removed, modified
+    /// (signature or visibility) and moved symbols, grouped per file
+    Diff {
+        /// Version tag, `tag:<name>` added by `runs tag`, or commit SHA
+        /// prefix to compare from
+        #[arg(long)]
+        from: String,
+
+        /// Version tag, `tag:<name>` added by `runs tag`, or commit SHA
+        /// prefix to compare to
+        #[arg(long)]
+        to: String,
+
+        /// Output format: table, json or csv
+        #[arg(long, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Only show breaking changes (removed public symbols, changed public
+        /// signatures, narrowed visibility) and exit nonzero if there are any
+        #[arg(long)]
+        breaking: bool,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Browse the graph of a scan in a terminal UI: files, their symbols,
+    /// the references to and from each symbol and its hover docs
+    Explore {
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Export the graph of a scan to GraphML, DOT, JSON lines, LSIF, SCIP or
+    /// symbol chunks for embedding pipelines
+    Export {
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only export the nodes and edges added, changed or deleted since
+        /// this scan (version tag or commit SHA prefix), as JSON lines with
+        /// `upsert`/`delete` operation markers
+        #[arg(long, value_name = "VERSION")]
+        since: Option<String>,
+
+        /// Output format: graphml, dot, jsonl, lsif, scip or chunks (default:
+        /// from the output extension)
+        #[arg(long)]
+        format: Option<ExportFormat>,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Write everything the store holds to a portable backup file
+    Backup {
+        /// Backup file to write
+        file: PathBuf,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Load a backup file into an empty store of the backend it was taken
+    /// from
+    Restore {
+        /// Backup file to read
+        file: PathBuf,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Apply pending schema migrations to the graph and show its schema
+    /// version
+    Migrate {
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Search symbols live with the language servers' workspace/symbol,
+    /// without a prior scan (useful to check LSP configuration)
+    LspSearch {
+        /// Symbol name or fragment to search for (matching is up to the server)
+        query: String,
+
+        /// Repository to search
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Comma-separated languages whose servers to ask (default: every
+        /// language found in the repository)
+        #[arg(long, value_delimiter = ',')]
+        languages: Vec<Language>,
+
+        /// Output format: table, json or csv
+        #[arg(long, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Serve the graph to other programs: MCP tools for agents or a JSON API
+    Serve {
+        #[command(subcommand)]
+        mode: ServeCommands,
+
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+
+    /// Check a new tool version against output of an earlier one
+    SelfTest {
+        #[command(subcommand)]
+        test_cmd: SelfTestCommands,
+    },
+
+    /// Manage the Neo4j password stored in the OS keyring
+    #[cfg(feature = "keyring")]
+    Credentials {
+        #[command(subcommand)]
+        action: CredentialsAction,
+
+        /// Neo4j connection URI the password belongs to [default: bolt://localhost:7687]
+        #[arg(long, env = "MOTHER_NEO4J_URI", global = true)]
+        neo4j_uri: Option<String>,
+
+        /// Neo4j username the password belongs to [default: neo4j]
+        #[arg(long, env = "MOTHER_NEO4J_USER", global = true)]
+        neo4j_user: Option<String>,
+    },
+
+    /// Print a shell completion script for mother to stdout
+    Completions {
+        /// Shell to complete in
+        shell: Shell,
+    },
+
+    /// Write man pages for mother and each subcommand to a directory
+    Manpages {
+        /// Directory for the pages (created if missing), e.g. `man/man1`
+        dir: PathBuf,
+    },
+
+    /// Install missing LSP servers (versions pinned via [lsp.<language>] in mother.toml)
+    InstallServers {
+        /// Comma-separated languages to install servers for (default: all)
+        #[arg(long, value_delimiter = ',')]
+        languages: Vec<Language>,
+
+        /// Install without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[cfg(feature = "keyring")]
+#[derive(Subcommand)]
+pub enum CredentialsAction {
+    /// Prompt for the password and store it in the keyring
+    Set,
+    /// Remove the stored password
+    Delete,
+}
@@ -0,0 +1,9 @@
+//! Backup module: Dump a graph store to a portable file and restore it into
+//! an empty store
+
+mod run;
+
+pub use run::{backup, backup_with_store, restore, restore_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+//! Backup and restore commands: Move a whole graph between stores of the
+//! same backend
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mother_core::graph::backup::{read_backup, write_backup};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+/// Run the backup command
+///
+/// # Errors
+/// Returns an error if connecting, querying or writing the file fails.
+pub async fn backup(neo4j: &Neo4jConfig, output: &Path) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    backup_with_store(&client, output).await
+}
+
+/// Write everything an open graph store holds to a backup file
+///
+/// # Errors
+/// Returns an error if querying or writing the file fails.
+pub async fn backup_with_store(client: &dyn GraphStore, output: &Path) -> Result<()> {
+    info!("Backing up the graph...");
+    let backup = client.backup().await?;
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    write_backup(&backup, BufWriter::new(file))?;
+    info!(
+        "✓ Backed up {} records to {}",
+        backup.records.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Run the restore command
+///
+/// # Errors
+/// Returns an error if the file is not a valid backup, connecting fails,
+/// or the graph cannot be restored.
+pub async fn restore(neo4j: &Neo4jConfig, input: &Path) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    restore_with_store(&client, input).await
+}
+
+/// Load a backup file into an empty graph store of the backend it was taken
+/// from
+///
+/// # Errors
+/// Returns an error if the file is not a valid backup, was taken from
+/// another backend, or the store is not empty.
+pub async fn restore_with_store(client: &dyn GraphStore, input: &Path) -> Result<()> {
+    let file = File::open(input).with_context(|| format!("Failed to open {}", input.display()))?;
+    let backup = read_backup(BufReader::new(file))
+        .with_context(|| format!("Failed to read backup {}", input.display()))?;
+    info!("Restoring {} records...", backup.records.len());
+    let count = client.restore(&backup).await?;
+    info!("✓ Restored {} records from {}", count, input.display());
+    Ok(())
+}
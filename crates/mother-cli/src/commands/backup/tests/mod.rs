@@ -0,0 +1,3 @@
+//! Tests for backup module
+
+mod tests_run;
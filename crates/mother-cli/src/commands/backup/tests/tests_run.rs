@@ -0,0 +1,75 @@
+//! Tests for the backup and restore commands
+
+#![allow(clippy::unwrap_used)]
+
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+use crate::commands::backup::{backup_with_store, restore_with_store};
+
+fn symbol(id: &str, name: &str) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("lib::{name}"),
+        doc_comment: Some(format!("Does {name}")),
+        ..SymbolNode::new(id, name, SymbolKind::Function, "/repo/src/lib.rs", 1, 3)
+    }
+}
+
+async fn scanned_store() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo")
+        .with_commit("aaa111")
+        .with_version("v1");
+    store.create_scan_run(&run).await.unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-1", "rust", "aaa111")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(&[symbol("s1", "parse"), symbol("s2", "render")], "hash-1")
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_restore_recreates_a_backed_up_store() {
+    let source = scanned_store().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("graph.backup");
+    backup_with_store(&source, &file).await.unwrap();
+
+    let target = SqliteStore::open_in_memory().unwrap();
+    restore_with_store(&target, &file).await.unwrap();
+
+    let names =
+        |symbols: Vec<SymbolNode>| -> Vec<String> { symbols.into_iter().map(|s| s.name).collect() };
+    assert_eq!(
+        names(target.symbols_at_commit("aaa111").await.unwrap()),
+        names(source.symbols_at_commit("aaa111").await.unwrap())
+    );
+    assert_eq!(
+        target.resolve_commit(Some("v1")).await.unwrap().as_deref(),
+        Some("aaa111")
+    );
+    // The full-text index is rebuilt from the restored symbols
+    let page = mother_core::graph::queries::Page::default();
+    assert_eq!(
+        target.search_symbols("render", page).await.unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_restore_refuses_non_empty_stores_and_invalid_files() {
+    let store = scanned_store().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("graph.backup");
+    backup_with_store(&store, &file).await.unwrap();
+    assert!(restore_with_store(&store, &file).await.is_err());
+
+    let invalid = dir.path().join("invalid.backup");
+    std::fs::write(&invalid, "not a backup\n").unwrap();
+    let empty = SqliteStore::open_in_memory().unwrap();
+    assert!(restore_with_store(&empty, &invalid).await.is_err());
+}
@@ -1,8 +1,8 @@
-//! Diff module: Compare commits or branches
+//! Diff module: Symbol-level comparison of two scanned versions
 
 mod run;
 
-pub use run::run;
+pub use run::{run, run_with_store};
 
 #[cfg(test)]
 mod tests;
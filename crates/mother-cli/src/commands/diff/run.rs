@@ -1,24 +1,140 @@
-//! Diff command: Compare commits or branches
+//! Diff command: Compare the symbols of two scanned versions
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use mother_core::graph::diff::{diff_symbols, ChangeKind, SymbolChange, SymbolDiff};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
 use tracing::info;
 
+use crate::commands::query::{print_row, print_rows};
+use crate::types::OutputFormat;
+
 /// Run the diff command
 ///
 /// # Errors
-/// Returns an error if the diff operation fails.
+/// Returns an error if the connection fails, a version has no scan, the
+/// diff cannot be printed, or `breaking` is set and breaking changes exist.
 pub async fn run(
     from: &str,
     to: &str,
-    _neo4j_uri: &str,
-    _neo4j_user: &str,
-    _neo4j_password: &str,
+    neo4j: &Neo4jConfig,
+    format: OutputFormat,
+    breaking: bool,
 ) -> Result<()> {
-    info!("Comparing {} to {}", from, to);
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(&client, from, to, format, breaking).await
+}
 
-    // TODO: Connect to Neo4j and compare commits/branches
-    // TODO: Show symbol changes between versions
+/// Run the diff command against an open graph store
+///
+/// `from` and `to` are version tags or commit SHA prefixes. JSON prints the
+/// whole diff grouped per file; CSV prints one row per changed symbol. With
+/// `breaking`, only breaking changes are printed and finding any is an
+/// error, so CI jobs fail on accidental API breakage.
+///
+/// # Errors
+/// Returns an error if a version has no scan, querying fails, the diff
+/// cannot be printed, or `breaking` is set and breaking changes exist.
+pub async fn run_with_store(
+    client: &dyn GraphStore,
+    from: &str,
+    to: &str,
+    format: OutputFormat,
+    breaking: bool,
+) -> Result<()> {
+    let from_sha = resolve(client, from).await?;
+    let to_sha = resolve(client, to).await?;
+    info!(
+        "Comparing {} ({}) to {} ({})",
+        from,
+        short_sha(&from_sha),
+        to,
+        short_sha(&to_sha)
+    );
+
+    let old = client.symbols_at_commit(&from_sha).await?;
+    let new = client.symbols_at_commit(&to_sha).await?;
+    let mut diff = diff_symbols(&from_sha, &old, &to_sha, &new);
+    if breaking {
+        diff = diff.breaking_only();
+    }
 
-    info!("Diff not yet implemented");
+    match format {
+        OutputFormat::Csv => {
+            let changes: Vec<&SymbolChange> = diff.changes().collect();
+            print_rows(&changes, format, |_| {})?;
+        }
+        _ => print_row(&diff, format, print_diff_table)?,
+    }
+
+    if breaking && diff.breaking > 0 {
+        bail!("Found {} breaking changes", diff.breaking);
+    }
     Ok(())
 }
+
+async fn resolve(client: &dyn GraphStore, version: &str) -> Result<String> {
+    client
+        .resolve_commit(Some(version))
+        .await?
+        .with_context(|| format!("No scan found for version '{version}'"))
+}
+
+fn print_diff_table(diff: &SymbolDiff) {
+    println!(
+        "\n=== {} → {} ===",
+        short_sha(&diff.from),
+        short_sha(&diff.to)
+    );
+    if diff.is_empty() {
+        println!("\nNo symbol changes");
+        return;
+    }
+
+    for file in &diff.files {
+        println!("\n{}", file.path);
+        for change in &file.changes {
+            print_change(change);
+        }
+    }
+
+    println!(
+        "\n{} added, {} removed, {} modified, {} moved, {} renamed ({} breaking)",
+        diff.added, diff.removed, diff.modified, diff.moved, diff.renamed, diff.breaking
+    );
+}
+
+fn print_change(change: &SymbolChange) {
+    let marker = match change.change {
+        ChangeKind::Added => '+',
+        ChangeKind::Removed => '-',
+        ChangeKind::Modified => '~',
+        ChangeKind::Moved => '>',
+        ChangeKind::Renamed => '*',
+    };
+    let breaking = if change.breaking { " [breaking]" } else { "" };
+    println!(
+        "  {} {} ({}) line {}{}",
+        marker, change.qualified_name, change.kind, change.line, breaking
+    );
+    if let Some(old_path) = &change.old_file_path {
+        println!("      moved from {old_path}");
+    }
+    if let Some(old_name) = &change.old_name {
+        println!("      renamed from {old_name}");
+    }
+    if let (Some(old), Some(new)) = (&change.old_signature, &change.new_signature) {
+        println!("      signature: {} → {}", first_line(old), first_line(new));
+    }
+    if let (Some(old), Some(new)) = (&change.old_visibility, &change.new_visibility) {
+        println!("      visibility: {old} → {new}");
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..8).unwrap_or(sha)
+}
@@ -1,696 +1,139 @@
 //! Tests for the diff run function
 //!
-//! These tests verify the behavior of the `mother::commands::diff::run` function
-//! through the public API. The function is currently a stub implementation that
-//! logs its parameters and returns Ok(()).
+//! These tests verify the behavior of the `mother::commands::diff` run
+//! functions against an unreachable Neo4j server and an in-memory SQLite
+//! store.
 
-use crate::commands::diff::run;
+#![allow(clippy::unwrap_used)]
 
-// ============================================================================
-// Basic Functionality Tests
-// ============================================================================
-
-/// Test that run function executes successfully with valid parameters
-#[tokio::test]
-async fn test_run_with_valid_parameters() {
-    let from = "main";
-    let to = "feature-branch";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently returns Ok(()) as it's not yet implemented
-    assert!(result.is_ok(), "Expected successful execution");
-}
-
-/// Test that run function handles commit SHAs as parameters
-#[tokio::test]
-async fn test_run_with_commit_shas() {
-    let from = "abc123def456";
-    let to = "789ghi012jkl";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with commit SHAs"
-    );
-}
-
-/// Test that run function handles branch names with slashes
-#[tokio::test]
-async fn test_run_with_branch_names_containing_slashes() {
-    let from = "feature/new-feature";
-    let to = "hotfix/urgent-fix";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with branch names containing slashes"
-    );
-}
-
-/// Test that run function handles tag names
-#[tokio::test]
-async fn test_run_with_tag_names() {
-    let from = "v1.0.0";
-    let to = "v2.0.0";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with tag names"
-    );
-}
-
-/// Test that run function handles same from and to references
-#[tokio::test]
-async fn test_run_with_same_from_and_to() {
-    let from = "main";
-    let to = "main";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution even when from and to are the same"
-    );
-}
-
-// ============================================================================
-// Edge Cases - Empty Strings
-// ============================================================================
-
-/// Test that run function handles empty from parameter
-#[tokio::test]
-async fn test_run_with_empty_from() {
-    let from = "";
-    let to = "main";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty strings as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts empty from parameter");
-}
-
-/// Test that run function handles empty to parameter
-#[tokio::test]
-async fn test_run_with_empty_to() {
-    let from = "main";
-    let to = "";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty strings as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts empty to parameter");
-}
-
-/// Test that run function handles both empty from and to parameters
-#[tokio::test]
-async fn test_run_with_empty_from_and_to() {
-    let from = "";
-    let to = "";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty strings as it's not yet implemented
-    assert!(
-        result.is_ok(),
-        "Function accepts empty from and to parameters"
-    );
-}
-
-/// Test that run function handles empty Neo4j URI
-#[tokio::test]
-async fn test_run_with_empty_neo4j_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty URI as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts empty URI parameter");
-}
-
-/// Test that run function handles empty Neo4j user
-#[tokio::test]
-async fn test_run_with_empty_neo4j_user() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = "";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty user as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts empty user parameter");
-}
-
-/// Test that run function handles empty Neo4j password
-#[tokio::test]
-async fn test_run_with_empty_neo4j_password() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts empty password as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts empty password parameter");
-}
-
-/// Test that run function handles all empty parameters
-#[tokio::test]
-async fn test_run_with_all_empty_parameters() {
-    let from = "";
-    let to = "";
-    let uri = "";
-    let user = "";
-    let password = "";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts all empty parameters as it's not yet implemented
-    assert!(
-        result.is_ok(),
-        "Function accepts all empty parameters in current stub implementation"
-    );
-}
-
-// ============================================================================
-// Edge Cases - Special Characters
-// ============================================================================
-
-/// Test that run function handles special characters in branch names
-#[tokio::test]
-async fn test_run_with_special_chars_in_branch_names() {
-    let from = "feature/user-#123";
-    let to = "hotfix/issue-#456";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with special characters in branch names"
-    );
-}
-
-/// Test that run function handles unicode characters in branch names
-#[tokio::test]
-async fn test_run_with_unicode_in_branch_names() {
-    let from = "feature/новая-ветка";
-    let to = "feature/分支";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with unicode characters in branch names"
-    );
-}
-
-/// Test that run function handles special characters in Neo4j password
-#[tokio::test]
-async fn test_run_with_special_chars_in_password() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "p@ssw0rd!#$%^&*()";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with special characters in password"
-    );
-}
-
-/// Test that run function handles unicode in Neo4j credentials
-#[tokio::test]
-async fn test_run_with_unicode_in_credentials() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = "用户";
-    let password = "пароль";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with unicode in credentials"
-    );
-}
-
-/// Test that run function handles whitespace in parameters
-#[tokio::test]
-async fn test_run_with_whitespace_in_parameters() {
-    let from = " main ";
-    let to = " feature ";
-    let uri = " bolt://localhost:7687 ";
-    let user = " neo4j ";
-    let password = " password ";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts whitespace as it's not yet implemented
-    assert!(result.is_ok(), "Function accepts whitespace in parameters");
-}
-
-// ============================================================================
-// Neo4j URI Format Tests
-// ============================================================================
-
-/// Test that run function handles bolt:// URI format
-#[tokio::test]
-async fn test_run_with_bolt_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://neo4j.example.com:7687";
-    let user = "testuser";
-    let password = "testpass";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with bolt:// URI"
-    );
-}
-
-/// Test that run function handles neo4j:// URI format
-#[tokio::test]
-async fn test_run_with_neo4j_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "neo4j://localhost:7687";
-    let user = "testuser";
-    let password = "testpass";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with neo4j:// URI"
-    );
-}
-
-/// Test that run function handles bolt+s:// URI format (secure)
-#[tokio::test]
-async fn test_run_with_secure_bolt_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt+s://neo4j.example.com:7687";
-    let user = "testuser";
-    let password = "testpass";
+use crate::commands::diff::{run, run_with_store};
+use crate::types::OutputFormat;
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::neo4j::Neo4jConfig;
+use mother_core::graph::{GraphStore, SqliteStore};
 
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with bolt+s:// URI"
-    );
+fn symbol(id: &str, name: &str, signature: &str) -> SymbolNode {
+    SymbolNode {
+        visibility: Some("pub".to_string()),
+        signature: Some(signature.to_string()),
+        ..SymbolNode::new(id, name, SymbolKind::Function, "/repo/src/lib.rs", 1, 3)
+    }
 }
 
-/// Test that run function handles neo4j+s:// URI format (secure)
-#[tokio::test]
-async fn test_run_with_secure_neo4j_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "neo4j+s://neo4j.example.com:7687";
-    let user = "testuser";
-    let password = "testpass";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with neo4j+s:// URI"
-    );
-}
-
-/// Test that run function handles invalid URI format
-#[tokio::test]
-async fn test_run_with_invalid_uri_format() {
-    let from = "main";
-    let to = "feature";
-    let uri = "not-a-valid-uri";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts invalid URI as it's not yet implemented
-    assert!(
-        result.is_ok(),
-        "Function accepts invalid URI in stub implementation"
-    );
-}
-
-/// Test that run function handles HTTP URI
-#[tokio::test]
-async fn test_run_with_http_uri() {
-    let from = "main";
-    let to = "feature";
-    let uri = "http://localhost:7474";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently accepts HTTP URI as it's not yet implemented
-    assert!(
-        result.is_ok(),
-        "Function accepts HTTP URI in stub implementation"
-    );
+/// A store with scans `v1` and `v2` of one file whose function changed
+/// signature and gained a sibling
+async fn two_versions() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    for (sha, version, hash, symbols) in [
+        (
+            "aaa111",
+            "v1",
+            "hash-1",
+            vec![symbol("p1", "parse", "fn parse()")],
+        ),
+        (
+            "bbb222",
+            "v2",
+            "hash-2",
+            vec![
+                symbol("p2", "parse", "fn parse(input: &str)"),
+                symbol("r2", "render", "fn render()"),
+            ],
+        ),
+    ] {
+        let run = ScanRun::new("/repo").with_commit(sha).with_version(version);
+        store.create_scan_run(&run).await.unwrap();
+        store
+            .create_file_if_new("/repo/src/lib.rs", hash, "rust", sha)
+            .await
+            .unwrap();
+        store.create_symbols_batch(&symbols, hash).await.unwrap();
+    }
+    store
 }
 
 // ============================================================================
-// Hostname and Port Variation Tests
+// Neo4j
 // ============================================================================
 
-/// Test that run function handles IPv4 address
+/// Test that run fails when Neo4j is unreachable
 #[tokio::test]
-async fn test_run_with_ipv4_address() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://192.168.1.100:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with IPv4 address"
-    );
-}
-
-/// Test that run function handles IPv6 address
-#[tokio::test]
-async fn test_run_with_ipv6_address() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://[::1]:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
+async fn test_run_with_invalid_neo4j_connection() {
+    let config = Neo4jConfig::new("bolt://invalid-host:7687", "neo4j", "invalid_password");
+    let result = run("v1", "v2", &config, OutputFormat::Table, false).await;
 
     assert!(
-        result.is_ok(),
-        "Expected successful execution with IPv6 address"
-    );
-}
-
-/// Test that run function handles fully qualified domain name
-#[tokio::test]
-async fn test_run_with_fqdn() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://neo4j.production.example.com:7687";
-    let user = "produser";
-    let password = "prodpassword";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(result.is_ok(), "Expected successful execution with FQDN");
-}
-
-/// Test that run function handles non-standard port
-#[tokio::test]
-async fn test_run_with_non_standard_port() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:9999";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with non-standard port"
-    );
-}
-
-/// Test that run function handles URI without explicit port
-#[tokio::test]
-async fn test_run_without_port() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with URI without explicit port"
-    );
-}
-
-// ============================================================================
-// Long String Tests
-// ============================================================================
-
-/// Test that run function handles very long branch names
-#[tokio::test]
-async fn test_run_with_long_branch_names() {
-    let from = &"a".repeat(1000);
-    let to = &"b".repeat(1000);
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with very long branch names"
-    );
-}
-
-/// Test that run function handles very long username
-#[tokio::test]
-async fn test_run_with_long_username() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = &"u".repeat(1000);
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with very long username"
-    );
-}
-
-/// Test that run function handles very long password
-#[tokio::test]
-async fn test_run_with_long_password() {
-    let from = "main";
-    let to = "feature";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = &"p".repeat(1000);
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with very long password"
+        result.is_err(),
+        "Expected error with invalid Neo4j connection"
     );
 }
 
 // ============================================================================
-// Parameter Boundary Tests
+// Graph store
 // ============================================================================
 
-/// Test that run function handles single character parameters
+/// Test that a diff prints in every output format
 #[tokio::test]
-async fn test_run_with_single_char_parameters() {
-    let from = "a";
-    let to = "b";
-    let uri = "bolt://localhost:7687";
-    let user = "u";
-    let password = "p";
+async fn test_run_with_store_in_all_formats() {
+    let store = two_versions().await;
 
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with single character parameters"
-    );
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let result = run_with_store(&store, "v1", "v2", format, false).await;
+        assert!(result.is_ok(), "diff as {format} failed");
+    }
 }
 
-/// Test that run function handles HEAD as reference
+/// Test that versions resolve by tag and SHA prefix, in either order
 #[tokio::test]
-async fn test_run_with_head_reference() {
-    let from = "HEAD";
-    let to = "main";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
+async fn test_run_with_store_accepts_sha_prefixes() {
+    let store = two_versions().await;
 
     assert!(
-        result.is_ok(),
-        "Expected successful execution with HEAD reference"
+        run_with_store(&store, "bbb", "aaa1", OutputFormat::Json, false)
+            .await
+            .is_ok()
     );
-}
-
-/// Test that run function handles HEAD~N references
-#[tokio::test]
-async fn test_run_with_head_tilde_reference() {
-    let from = "HEAD~1";
-    let to = "HEAD~5";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
     assert!(
-        result.is_ok(),
-        "Expected successful execution with HEAD~N references"
+        run_with_store(&store, "v1", "v1", OutputFormat::Table, false)
+            .await
+            .is_ok()
     );
 }
 
-/// Test that run function handles HEAD^ references
+/// Test that an unknown version is an error
 #[tokio::test]
-async fn test_run_with_head_caret_reference() {
-    let from = "HEAD^";
-    let to = "HEAD^^";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
+async fn test_run_with_store_unknown_version() {
+    let store = two_versions().await;
 
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with HEAD^ references"
-    );
+    let result = run_with_store(&store, "v1", "v9", OutputFormat::Table, false).await;
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("v9"));
 }
 
 // ============================================================================
-// Order and Symmetry Tests
+// Breaking changes
 // ============================================================================
 
-/// Test that run function handles reversed order of references
+/// Test that breaking changes fail the diff in every output format
 #[tokio::test]
-async fn test_run_with_reversed_order() {
-    let from = "feature";
-    let to = "main";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result = run(from, to, uri, user, password).await;
-
-    assert!(
-        result.is_ok(),
-        "Expected successful execution with reversed reference order"
-    );
-}
-
-/// Test that run function is symmetric for same parameters
-#[tokio::test]
-async fn test_run_symmetry() {
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    let result1 = run("main", "feature", uri, user, password).await;
-    let result2 = run("feature", "main", uri, user, password).await;
-
-    // Both should succeed
-    assert!(result1.is_ok(), "First call should succeed");
-    assert!(result2.is_ok(), "Second call should succeed");
-}
-
-// ============================================================================
-// Documentation Tests
-// ============================================================================
-
-/// Test that demonstrates the expected usage pattern
-///
-/// This test documents how the function is expected to be used within the CLI.
-/// Note: The function currently logs parameters and returns Ok(()) as it's
-/// not yet implemented.
-#[tokio::test]
-async fn test_run_usage_documentation() {
-    // This test serves as documentation for proper usage
-    let from = "v1.0.0";
-    let to = "v2.0.0";
-    let uri = "bolt://localhost:7687";
-    let user = "neo4j";
-    let password = "password";
-
-    // Function is async and returns anyhow::Result<()>
-    let result = run(from, to, uri, user, password).await;
-
-    // Currently returns Ok(()) as implementation is pending
-    assert!(result.is_ok());
+async fn test_run_with_store_breaking_changes_are_an_error() {
+    let store = two_versions().await;
 
-    // When implemented, the function should:
-    // - Connect to Neo4j using the provided credentials
-    // - Compare the 'from' and 'to' references
-    // - Identify symbol changes between the two versions
-    // - Display or return the differences
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let result = run_with_store(&store, "v1", "v2", format, true).await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("1 breaking"), "unexpected error: {error}");
+    }
 }
 
-/// Test parameter acceptance to document current behavior
+/// Test that a diff without breaking changes passes
 #[tokio::test]
-async fn test_run_accepts_all_string_slices() {
-    // The function accepts &str for all parameters
-    let result = run("from", "to", "uri", "user", "pass").await;
+async fn test_run_with_store_without_breaking_changes() {
+    let store = two_versions().await;
 
     assert!(
-        result.is_ok(),
-        "Function accepts string slices for all parameters"
+        run_with_store(&store, "v1", "v1", OutputFormat::Table, true)
+            .await
+            .is_ok()
     );
 }
@@ -0,0 +1,351 @@
+//! Doctor env: Check the prerequisites of a scan
+//!
+//! Finds the language server of every language in the repository (or of
+//! the requested languages), starts it once with a version flag to see that
+//! it runs, and suggests an install command for missing servers. Then
+//! connects to the configured graph store. Any failed check makes the
+//! command fail, before a scan would fail halfway through.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::SqliteStore;
+use mother_core::lsp::{LspServerConfig, LspServerDefaults};
+use mother_core::scanner::Language;
+use mother_scan::ScanOptions;
+use serde::Serialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::info;
+
+use crate::commands::install::{find_in_path, install_plan, INSTALLABLE_LANGUAGES};
+use crate::commands::query::print_rows;
+use crate::types::OutputFormat;
+
+/// How long a server or the graph store gets to answer
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Graph store to check
+pub enum StoreTarget {
+    /// Neo4j server, or why its settings are incomplete
+    Neo4j(Result<Neo4jConfig>),
+    /// SQLite database file
+    Sqlite(PathBuf),
+    /// Private in-memory database, always available
+    Memory,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CheckStatus {
+    Ok,
+    /// The server executable was not found
+    Missing,
+    /// The server did not start or the store could not be opened
+    Failed,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Missing => write!(f, "missing"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Result of checking one language server or the graph store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct EnvCheck {
+    /// Server command, or `neo4j` / `sqlite`
+    pub component: String,
+    /// Languages the server is used for; empty for the graph store
+    pub languages: Vec<String>,
+    pub status: CheckStatus,
+    /// Version the server reported, empty if unknown
+    pub version: String,
+    /// Executable, database URI or file; empty if not found
+    pub location: String,
+    /// Install command for a missing server or the error of a failed check
+    pub hint: String,
+}
+
+/// Run the environment check
+///
+/// `languages` limits the servers checked; empty checks the servers of the
+/// languages found under `path`, or every installable server if none is.
+/// `versions` are the pinned server versions used in install suggestions.
+///
+/// # Errors
+/// Returns an error if the include/exclude globs are invalid, the results
+/// cannot be printed, or a check failed.
+pub async fn env(
+    path: &Path,
+    languages: &[Language],
+    options: &ScanOptions,
+    versions: &HashMap<Language, String>,
+    store: StoreTarget,
+    format: OutputFormat,
+) -> Result<()> {
+    let root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let languages = if languages.is_empty() {
+        repository_languages(&root, options)?
+    } else {
+        languages.to_vec()
+    };
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let mut checks = Vec::new();
+    for (server, languages) in servers(&languages, &root, options) {
+        info!("Checking {}...", server.command);
+        checks.push(check_server(&server, &languages, versions, &path_var).await);
+    }
+    info!("Checking the graph store...");
+    checks.push(check_store(store).await);
+
+    print_rows(&checks, format, print_checks_table)?;
+    let failed = checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Ok)
+        .count();
+    if failed > 0 {
+        bail!("{failed} environment checks failed");
+    }
+    Ok(())
+}
+
+/// Languages of the files under `root`, or every installable language if
+/// there are none
+fn repository_languages(root: &Path, options: &ScanOptions) -> Result<Vec<Language>> {
+    let mut found: Vec<Language> = Vec::new();
+    for file in options.discover_files(root)? {
+        if !found.contains(&file.language) {
+            found.push(file.language);
+        }
+    }
+    if found.is_empty() {
+        info!("No source files found, checking every installable server");
+        return Ok(INSTALLABLE_LANGUAGES.to_vec());
+    }
+    Ok(found)
+}
+
+/// The server of each language, configured or default, with the languages
+/// sharing it, ordered by command
+pub(crate) fn servers(
+    languages: &[Language],
+    root: &Path,
+    options: &ScanOptions,
+) -> Vec<(LspServerConfig, Vec<Language>)> {
+    let mut servers: BTreeMap<String, (LspServerConfig, Vec<Language>)> = BTreeMap::new();
+    for language in languages {
+        let server = options
+            .lsp_servers
+            .iter()
+            .find(|s| s.language == *language)
+            .cloned()
+            .unwrap_or_else(|| LspServerDefaults::for_language(language, root));
+        let entry = servers
+            .entry(server.command.clone())
+            .or_insert_with(|| (server, Vec::new()));
+        entry.1.push(language.clone());
+    }
+    servers.into_values().collect()
+}
+
+/// Locate a server and ask it for its version
+pub(crate) async fn check_server(
+    server: &LspServerConfig,
+    languages: &[Language],
+    versions: &HashMap<Language, String>,
+    path_var: &std::ffi::OsStr,
+) -> EnvCheck {
+    let mut check = EnvCheck {
+        component: server.command.clone(),
+        languages: languages.iter().map(ToString::to_string).collect(),
+        status: CheckStatus::Ok,
+        version: String::new(),
+        location: String::new(),
+        hint: String::new(),
+    };
+
+    let command = Path::new(&server.command);
+    let location = if command.components().count() > 1 {
+        command.is_file().then(|| command.to_path_buf())
+    } else {
+        find_in_path(&server.command, path_var)
+    };
+    let Some(location) = location else {
+        check.status = CheckStatus::Missing;
+        check.hint = languages
+            .iter()
+            .find_map(|l| install_plan(l, versions.get(l).map(String::as_str)))
+            .filter(|plan| plan.server == server.command)
+            .map(|plan| plan.command_line())
+            .unwrap_or_else(|| format!("install {} and put it on PATH", server.command));
+        return check;
+    };
+    check.location = location.display().to_string();
+
+    match server_version(&location).await {
+        Ok(version) => check.version = version.unwrap_or_default(),
+        Err(e) => {
+            check.status = CheckStatus::Failed;
+            check.hint = e;
+        }
+    }
+    check
+}
+
+/// Version arguments of default servers known to answer them
+///
+/// Other servers, such as `pyright-langserver`, refuse to run without a
+/// transport flag, so only their start is checked.
+const VERSION_ARGS: [(&str, &str); 4] = [
+    ("rust-analyzer", "--version"),
+    ("typescript-language-server", "--version"),
+    ("gopls", "version"),
+    ("solargraph", "--version"),
+];
+
+/// Run a server with its version argument
+///
+/// Servers in [`VERSION_ARGS`] must exit successfully, which catches
+/// wrappers such as the rustup proxy of an uninstalled `rust-analyzer`.
+/// Any other server that starts is runnable even if it rejects the
+/// argument or does not exit in time; its version is then unknown.
+async fn server_version(executable: &Path) -> Result<Option<String>, String> {
+    let name = executable
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_end_matches(".exe").to_string())
+        .unwrap_or_default();
+    let known = VERSION_ARGS.iter().find(|(server, _)| *server == name);
+    let arg = known.map_or("--version", |(_, arg)| arg);
+    let child = Command::new(executable)
+        .arg(arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("cannot start: {e}"))?;
+
+    let output = match timeout(CHECK_TIMEOUT, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("cannot run: {e}")),
+        Err(_) => return Ok(None),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        Ok(version_line(&stdout).or_else(|| version_line(&stderr)))
+    } else if known.is_some() {
+        let reason = stderr.lines().map(str::trim).find(|l| !l.is_empty());
+        Err(format!(
+            "`{name} {arg}` failed: {}",
+            reason.unwrap_or("no error output")
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+/// First non-empty output line that mentions a digit
+pub(crate) fn version_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.chars().any(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// Connect to the graph store
+async fn check_store(store: StoreTarget) -> EnvCheck {
+    let (component, location, result) = match store {
+        StoreTarget::Neo4j(Err(e)) => ("neo4j", String::new(), Err(format!("{e:#}"))),
+        StoreTarget::Neo4j(Ok(config)) => {
+            let result = match timeout(CHECK_TIMEOUT, Neo4jClient::connect(&config)).await {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err(format!(
+                    "no answer within {} seconds",
+                    CHECK_TIMEOUT.as_secs()
+                )),
+            };
+            ("neo4j", config.uri, result)
+        }
+        StoreTarget::Sqlite(path) => {
+            let result = SqliteStore::open(&path)
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            ("sqlite", path.display().to_string(), result)
+        }
+        StoreTarget::Memory => ("memory", String::new(), Ok(())),
+    };
+    EnvCheck {
+        component: component.to_string(),
+        languages: Vec::new(),
+        status: if result.is_ok() {
+            CheckStatus::Ok
+        } else {
+            CheckStatus::Failed
+        },
+        version: String::new(),
+        location,
+        hint: result.err().unwrap_or_default(),
+    }
+}
+
+fn print_checks_table(checks: &[EnvCheck]) {
+    println!(
+        "\n{:<28} {:<22} {:<8} {:<24} {:<40}",
+        "COMPONENT", "LANGUAGES", "STATUS", "VERSION", "LOCATION"
+    );
+    println!("{}", "-".repeat(126));
+    for c in checks {
+        println!(
+            "{:<28} {:<22} {:<8} {:<24} {:<40}",
+            c.component,
+            c.languages.join(", "),
+            c.status.to_string(),
+            truncate_str(&c.version, 24),
+            c.location
+        );
+        if !c.hint.is_empty() {
+            let label = if c.status == CheckStatus::Missing {
+                "install with"
+            } else {
+                "error"
+            };
+            println!("  {label}: {}", c.hint);
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Ok)
+        .count();
+    if failed == 0 {
+        println!("\nAll {} checks passed", checks.len());
+    } else {
+        println!("\n{failed} of {} checks failed", checks.len());
+        if checks.iter().any(|c| c.status == CheckStatus::Missing) {
+            println!("Run `mother install-servers` to install the missing servers");
+        }
+    }
+}
+
+fn truncate_str(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let kept: String = s.chars().take(max_len - 3).collect();
+        format!("{kept}...")
+    }
+}
@@ -0,0 +1,95 @@
+//! Doctor graph: Validate the stored graph
+//!
+//! Lists dangling reference and call edges, files no commit contains,
+//! symbols with inverted line ranges and duplicate symbol IDs. With `--fix`,
+//! dangling edges and orphan files are deleted first and only the remaining
+//! problems are listed. Problems left over make the command fail, so it can
+//! guard CI jobs.
+
+use anyhow::{bail, Result};
+use mother_core::graph::integrity::{IntegrityIssue, IntegrityRepair};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use crate::commands::query::print_rows;
+use crate::types::OutputFormat;
+
+/// Run the graph check
+///
+/// # Errors
+/// Returns an error if the connection fails or the check fails.
+pub async fn graph(neo4j: &Neo4jConfig, fix: bool, format: OutputFormat) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    graph_with_store(&client, fix, format).await
+}
+
+/// Run the graph check against an open graph store
+///
+/// # Errors
+/// Returns an error if the store fails, the results cannot be printed, or
+/// problems remain.
+pub async fn graph_with_store(
+    client: &dyn GraphStore,
+    fix: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut issues = client.check_integrity().await?;
+    if fix && issues.iter().any(|i| i.check.repairable()) {
+        info!("Repairing dangling edges and orphan files...");
+        let repair = client.repair_integrity().await?;
+        if format == OutputFormat::Table {
+            print_repair(&repair);
+        }
+        issues = client.check_integrity().await?;
+    }
+
+    print_rows(&issues, format, |issues| print_issues_table(issues, fix))?;
+    if !issues.is_empty() {
+        bail!("Found {} graph integrity problems", issues.len());
+    }
+    Ok(())
+}
+
+fn print_repair(repair: &IntegrityRepair) {
+    println!(
+        "Deleted {} dangling edges, {} orphan files and {} symbols",
+        repair.edges, repair.files, repair.symbols
+    );
+}
+
+fn print_issues_table(issues: &[IntegrityIssue], fixed: bool) {
+    if issues.is_empty() {
+        println!("No graph integrity problems found");
+        return;
+    }
+
+    println!("\n{:<20} {:<60} {:<40}", "PROBLEM", "SUBJECT", "DETAIL");
+    println!("{}", "-".repeat(122));
+    for issue in issues {
+        println!(
+            "{:<20} {:<60} {:<40}",
+            issue.check.to_string(),
+            truncate_str(&issue.subject, 60),
+            issue.detail
+        );
+    }
+
+    let repairable = issues.iter().filter(|i| i.check.repairable()).count();
+    println!("\nTotal: {} problems", issues.len());
+    if repairable > 0 && !fixed {
+        println!("{repairable} can be repaired with --fix");
+    }
+    if repairable < issues.len() {
+        println!("Rescan the affected commits to replace symbols with bad ranges or IDs");
+    }
+}
+
+fn truncate_str(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let kept: String = s.chars().take(max_len - 3).collect();
+        format!("{kept}...")
+    }
+}
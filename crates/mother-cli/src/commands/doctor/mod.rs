@@ -0,0 +1,10 @@
+//! Doctor module: Check the environment and the stored graph for problems
+
+mod env;
+mod graph;
+
+pub use env::{env, StoreTarget};
+pub use graph::{graph, graph_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,4 @@
+//! Tests for doctor module
+
+mod tests_env;
+mod tests_graph;
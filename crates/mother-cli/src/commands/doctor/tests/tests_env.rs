@@ -0,0 +1,176 @@
+//! Tests for the doctor environment check
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use mother_core::lsp::{LspServerConfig, LspServerDefaults};
+use mother_core::scanner::Language;
+use mother_scan::ScanOptions;
+use tempfile::TempDir;
+
+use super::super::env::{check_server, servers, version_line, CheckStatus};
+use crate::commands::doctor::{env, StoreTarget};
+use crate::types::OutputFormat;
+
+/// Options whose Rust server is `command`
+fn rust_server(command: &str) -> ScanOptions {
+    ScanOptions {
+        lsp_servers: vec![LspServerConfig {
+            command: command.to_string(),
+            ..LspServerDefaults::for_language(&Language::Rust, Path::new("/repo"))
+        }],
+        ..ScanOptions::default()
+    }
+}
+
+/// An executable script named `name` in `dir` running `body`
+#[cfg(unix)]
+fn script(dir: &TempDir, name: &str, body: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.path().join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.display().to_string()
+}
+
+/// A server that prints a version banner
+#[cfg(unix)]
+fn fake_server(dir: &TempDir) -> String {
+    script(dir, "fake-ls", "echo 'fake-ls 1.2.3 (abc)'")
+}
+
+#[test]
+fn test_version_line_skips_lines_without_digits() {
+    assert_eq!(
+        version_line("\nrust-analyzer\n  rust-analyzer 1.80.0 (0514789 2024-07-21)\n"),
+        Some("rust-analyzer 1.80.0 (0514789 2024-07-21)".to_string())
+    );
+    assert_eq!(version_line("usage: server [options]"), None);
+}
+
+#[test]
+fn test_servers_are_shared_and_overridable() {
+    let options = rust_server("/opt/ra");
+    let found = servers(
+        &[Language::TypeScript, Language::Rust, Language::JavaScript],
+        Path::new("/repo"),
+        &options,
+    );
+
+    let summary: Vec<(&str, Vec<Language>)> = found
+        .iter()
+        .map(|(server, languages)| (server.command.as_str(), languages.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("/opt/ra", vec![Language::Rust]),
+            (
+                "typescript-language-server",
+                vec![Language::TypeScript, Language::JavaScript]
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_missing_server_suggests_its_install_command() {
+    let server = LspServerDefaults::for_language(&Language::Go, Path::new("/repo"));
+    let versions = HashMap::from([(Language::Go, "v0.16.1".to_string())]);
+
+    let check = check_server(&server, &[Language::Go], &versions, OsStr::new("")).await;
+
+    assert_eq!(check.status, CheckStatus::Missing);
+    assert_eq!(check.hint, "go install golang.org/x/tools/gopls@v0.16.1");
+    assert!(check.location.is_empty());
+}
+
+#[tokio::test]
+async fn test_missing_custom_server_has_no_install_command() {
+    let options = rust_server("/nonexistent/ra");
+    let (server, languages) = servers(&[Language::Rust], Path::new("/repo"), &options)
+        .pop()
+        .unwrap();
+
+    let check = check_server(&server, &languages, &HashMap::new(), OsStr::new("")).await;
+
+    assert_eq!(check.status, CheckStatus::Missing);
+    assert_eq!(check.hint, "install /nonexistent/ra and put it on PATH");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_runnable_server_reports_its_version() {
+    let dir = TempDir::new().unwrap();
+    let options = rust_server(&fake_server(&dir));
+    let (server, languages) = servers(&[Language::Rust], Path::new("/repo"), &options)
+        .pop()
+        .unwrap();
+
+    let check = check_server(&server, &languages, &HashMap::new(), OsStr::new("")).await;
+
+    assert_eq!(check.status, CheckStatus::Ok);
+    assert_eq!(check.version, "fake-ls 1.2.3 (abc)");
+    assert_eq!(check.languages, ["rust"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_known_server_must_answer_its_version_argument() {
+    let dir = TempDir::new().unwrap();
+    let proxy = script(
+        &dir,
+        "rust-analyzer",
+        "echo \"error: Unknown binary 'rust-analyzer'\" >&2; exit 1",
+    );
+    let other = script(&dir, "other-ls", "echo 'needs --stdio' >&2; exit 1");
+
+    for (command, status) in [(proxy, CheckStatus::Failed), (other, CheckStatus::Ok)] {
+        let options = rust_server(&command);
+        let (server, languages) = servers(&[Language::Rust], Path::new("/repo"), &options)
+            .pop()
+            .unwrap();
+        let check = check_server(&server, &languages, &HashMap::new(), OsStr::new("")).await;
+        assert_eq!(check.status, status, "{command}");
+        assert!(check.version.is_empty());
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_env_fails_when_the_store_is_unreachable() {
+    let dir = TempDir::new().unwrap();
+    let options = rust_server(&fake_server(&dir));
+    let languages = [Language::Rust];
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let sqlite = StoreTarget::Sqlite(dir.path().join("graph.db"));
+        let result = env(
+            dir.path(),
+            &languages,
+            &options,
+            &HashMap::new(),
+            sqlite,
+            format,
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    let neo4j = StoreTarget::Neo4j(Err(anyhow::anyhow!("Neo4j password required")));
+    let err = env(
+        dir.path(),
+        &languages,
+        &options,
+        &HashMap::new(),
+        neo4j,
+        OutputFormat::Table,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.to_string(), "1 environment checks failed");
+}
@@ -0,0 +1,92 @@
+//! Tests for the doctor graph check
+//!
+//! These tests verify the behavior of `mother::commands::doctor` graph
+//! checks against an in-memory SQLite store.
+
+#![allow(clippy::unwrap_used)]
+
+use crate::commands::doctor::graph_with_store;
+use crate::types::OutputFormat;
+use mother_core::graph::integrity::IntegrityCheck;
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, Page, QueryParams, SqliteStore};
+
+/// A store with one scanned file and one file no commit contains
+async fn store_with_orphan_file() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("aaa111"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "aaa111")
+        .await
+        .unwrap();
+    store
+        .execute_raw(
+            "INSERT INTO files (content_hash, path, language)
+             VALUES ('hash-old', '/repo/src/old.rs', 'rust')",
+            &QueryParams::new(),
+        )
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_problems_fail_the_check_in_every_format() {
+    let store = store_with_orphan_file().await;
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let err = graph_with_store(&store, false, format).await.unwrap_err();
+        assert!(err.to_string().contains("1 graph integrity problems"));
+    }
+    assert_eq!(store.check_integrity().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_fix_repairs_the_graph() {
+    let store = store_with_orphan_file().await;
+
+    assert!(graph_with_store(&store, true, OutputFormat::Table)
+        .await
+        .is_ok());
+    assert!(store.check_integrity().await.unwrap().is_empty());
+    assert_eq!(
+        store.list_files(None, Page::default()).await.unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_fix_leaves_problems_it_cannot_repair() {
+    let store = store_with_orphan_file().await;
+    let symbol = SymbolNode {
+        qualified_name: "crate::run".to_string(),
+        ..SymbolNode::new(
+            "s-run",
+            "run",
+            SymbolKind::Function,
+            "/repo/src/lib.rs",
+            9,
+            3,
+        )
+    };
+    store
+        .create_symbols_batch(&[symbol], "hash-lib")
+        .await
+        .unwrap();
+
+    let err = graph_with_store(&store, true, OutputFormat::Table)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("1 graph integrity problems"));
+    let left: Vec<IntegrityCheck> = store
+        .check_integrity()
+        .await
+        .unwrap()
+        .iter()
+        .map(|i| i.check)
+        .collect();
+    assert_eq!(left, [IntegrityCheck::InvertedRange]);
+}
@@ -0,0 +1,13 @@
+//! Explore module: Browse the graph of a scan in a terminal UI, from files
+//! to symbols and along references and calls
+
+mod model;
+mod run;
+mod view;
+
+pub use model::{Action, ExploreData, Explorer, Pane, Use};
+pub use run::{action_for, load, run, run_with_store};
+pub use view::draw;
+
+#[cfg(test)]
+mod tests;
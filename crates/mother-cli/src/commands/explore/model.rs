@@ -0,0 +1,287 @@
+//! Explorer state: the loaded graph of one commit and where the user is in it
+
+use std::collections::{BTreeMap, HashMap};
+
+use mother_core::graph::export::GraphExport;
+use mother_core::graph::model::SymbolNode;
+use mother_core::graph::ReferenceDirection;
+
+/// Edge kinds listed as references between symbols
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// One end of a reference or call, seen from the other end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Use {
+    /// ID of the symbol at the other end
+    pub symbol_id: String,
+    /// `REFERENCES` or `CALLS`
+    pub kind: String,
+    /// Line of the using symbol's file the use is on
+    pub line: i64,
+}
+
+/// Files, symbols and reference edges of one scanned commit
+#[derive(Debug, Default)]
+pub struct ExploreData {
+    /// Files with symbols, sorted by path
+    files: Vec<String>,
+    /// Symbols of each file, by start line
+    symbols: BTreeMap<String, Vec<SymbolNode>>,
+    /// Where each symbol is: its file's index and its index in the file
+    locations: HashMap<String, (usize, usize)>,
+    /// Symbols each symbol uses
+    uses: HashMap<String, Vec<Use>>,
+    /// Symbols using each symbol
+    used_by: HashMap<String, Vec<Use>>,
+}
+
+impl ExploreData {
+    /// Index the symbols of a commit and the `REFERENCES` and `CALLS` edges
+    /// of its export
+    #[must_use]
+    pub fn new(symbols: Vec<SymbolNode>, graph: &GraphExport) -> Self {
+        let mut by_file: BTreeMap<String, Vec<SymbolNode>> = BTreeMap::new();
+        for symbol in symbols {
+            by_file
+                .entry(symbol.file_path.clone())
+                .or_default()
+                .push(symbol);
+        }
+        let mut locations = HashMap::new();
+        for (file, symbols) in by_file.values_mut().enumerate() {
+            symbols.sort_by(|a, b| (a.start_line, &a.name).cmp(&(b.start_line, &b.name)));
+            for (index, symbol) in symbols.iter().enumerate() {
+                locations.insert(symbol.id.clone(), (file, index));
+            }
+        }
+
+        let mut uses: HashMap<String, Vec<Use>> = HashMap::new();
+        let mut used_by: HashMap<String, Vec<Use>> = HashMap::new();
+        for edge in &graph.edges {
+            if !USE_EDGES.contains(&edge.kind.as_str())
+                || !locations.contains_key(&edge.source)
+                || !locations.contains_key(&edge.target)
+            {
+                continue;
+            }
+            let line = edge
+                .properties
+                .get("line")
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or(0);
+            let to = |symbol_id: &str| Use {
+                symbol_id: symbol_id.to_string(),
+                kind: edge.kind.clone(),
+                line,
+            };
+            uses.entry(edge.source.clone())
+                .or_default()
+                .push(to(&edge.target));
+            used_by
+                .entry(edge.target.clone())
+                .or_default()
+                .push(to(&edge.source));
+        }
+        for list in uses.values_mut().chain(used_by.values_mut()) {
+            list.sort_by(|a, b| (a.line, &a.symbol_id).cmp(&(b.line, &b.symbol_id)));
+            list.dedup();
+        }
+
+        Self {
+            files: by_file.keys().cloned().collect(),
+            symbols: by_file,
+            locations,
+            uses,
+            used_by,
+        }
+    }
+
+    /// Paths of the files with symbols, sorted
+    #[must_use]
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Symbols of the file at an index, by start line
+    #[must_use]
+    pub fn symbols_of(&self, file: usize) -> &[SymbolNode] {
+        self.files
+            .get(file)
+            .and_then(|path| self.symbols.get(path))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The symbol with an ID
+    #[must_use]
+    pub fn symbol(&self, id: &str) -> Option<&SymbolNode> {
+        let &(file, index) = self.locations.get(id)?;
+        self.symbols_of(file).get(index)
+    }
+
+    /// Uses of a symbol: the symbols referencing it (`To`) or those it
+    /// references (`From`), by line
+    #[must_use]
+    pub fn references(&self, id: &str, direction: ReferenceDirection) -> &[Use] {
+        let map = match direction {
+            ReferenceDirection::To => &self.used_by,
+            ReferenceDirection::From => &self.uses,
+        };
+        map.get(id).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The list that has the keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Files,
+    Symbols,
+    References,
+}
+
+/// What a key press asks the explorer to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    /// Open the selected file, list the selected symbol's references or jump
+    /// to the selected reference
+    Open,
+    /// Move focus back to the list on the left
+    Back,
+    /// Switch between references to and from the selected symbol
+    ToggleDirection,
+    /// Return to where the last jump started
+    JumpBack,
+    Quit,
+}
+
+/// Navigation state over the loaded data
+#[derive(Debug)]
+pub struct Explorer {
+    pub data: ExploreData,
+    pub pane: Pane,
+    pub file: usize,
+    pub symbol: usize,
+    pub reference: usize,
+    /// References to the selected symbol (its users) or from it
+    pub direction: ReferenceDirection,
+    /// File and symbol indexes each jump started from
+    history: Vec<(usize, usize)>,
+    pub quit: bool,
+}
+
+impl Explorer {
+    /// Start in the file list, showing who uses the selected symbol
+    #[must_use]
+    pub fn new(data: ExploreData) -> Self {
+        Self {
+            data,
+            pane: Pane::Files,
+            file: 0,
+            symbol: 0,
+            reference: 0,
+            direction: ReferenceDirection::To,
+            history: Vec::new(),
+            quit: false,
+        }
+    }
+
+    /// Symbols of the selected file
+    #[must_use]
+    pub fn symbols(&self) -> &[SymbolNode] {
+        self.data.symbols_of(self.file)
+    }
+
+    /// The selected symbol, if its file has any
+    #[must_use]
+    pub fn selected_symbol(&self) -> Option<&SymbolNode> {
+        self.symbols().get(self.symbol)
+    }
+
+    /// References of the selected symbol in the current direction
+    #[must_use]
+    pub fn references(&self) -> &[Use] {
+        self.selected_symbol()
+            .map_or(&[], |s| self.data.references(&s.id, self.direction))
+    }
+
+    /// Apply a key press
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::Up => self.step(false),
+            Action::Down => self.step(true),
+            Action::Open => self.open(),
+            Action::Back => self.back(),
+            Action::ToggleDirection => {
+                self.direction = match self.direction {
+                    ReferenceDirection::To => ReferenceDirection::From,
+                    ReferenceDirection::From => ReferenceDirection::To,
+                };
+                self.reference = 0;
+            }
+            Action::JumpBack => {
+                if let Some((file, symbol)) = self.history.pop() {
+                    self.select(file, symbol);
+                }
+            }
+            Action::Quit => self.quit = true,
+        }
+    }
+
+    /// Move the selection of the focused list one row, staying in bounds
+    fn step(&mut self, down: bool) {
+        let (selected, len) = match self.pane {
+            Pane::Files => (&mut self.file, self.data.files().len()),
+            Pane::Symbols => (&mut self.symbol, self.data.symbols_of(self.file).len()),
+            Pane::References => {
+                let len = self.references().len();
+                (&mut self.reference, len)
+            }
+        };
+        *selected = if down {
+            (*selected + 1).min(len.saturating_sub(1))
+        } else {
+            selected.saturating_sub(1)
+        };
+        match self.pane {
+            Pane::Files => {
+                self.symbol = 0;
+                self.reference = 0;
+            }
+            Pane::Symbols => self.reference = 0,
+            Pane::References => {}
+        }
+    }
+
+    fn open(&mut self) {
+        match self.pane {
+            Pane::Files if !self.symbols().is_empty() => self.pane = Pane::Symbols,
+            Pane::Symbols if self.selected_symbol().is_some() => self.pane = Pane::References,
+            Pane::References => {
+                let Some(target) = self.references().get(self.reference) else {
+                    return;
+                };
+                if let Some(&(file, symbol)) = self.data.locations.get(&target.symbol_id) {
+                    self.history.push((self.file, self.symbol));
+                    self.select(file, symbol);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn back(&mut self) {
+        self.pane = match self.pane {
+            Pane::Files | Pane::Symbols => Pane::Files,
+            Pane::References => Pane::Symbols,
+        };
+    }
+
+    /// Focus a symbol in the symbol list
+    fn select(&mut self, file: usize, symbol: usize) {
+        self.file = file;
+        self.symbol = symbol;
+        self.reference = 0;
+        self.pane = Pane::Symbols;
+    }
+}
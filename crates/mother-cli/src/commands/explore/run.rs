@@ -0,0 +1,86 @@
+//! Explore command: Load a scanned commit and run the terminal UI over it
+
+use anyhow::{Context, Result};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::DefaultTerminal;
+use tracing::info;
+
+use super::model::{Action, ExploreData, Explorer};
+use super::view::draw;
+
+/// Run the explore command
+///
+/// # Errors
+/// Returns an error if no scan matches `version`, loading the graph fails
+/// or the terminal cannot be drawn.
+pub async fn run(neo4j: &Neo4jConfig, version: Option<&str>) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(&client, version).await
+}
+
+/// Run the explore command against an open graph store
+///
+/// # Errors
+/// Returns an error if no scan matches `version`, loading the graph fails
+/// or the terminal cannot be drawn.
+pub async fn run_with_store(client: &dyn GraphStore, version: Option<&str>) -> Result<()> {
+    let (explorer, commit) = load(client, version).await?;
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, explorer, &commit);
+    ratatui::restore();
+    result
+}
+
+/// Load the symbols and reference edges of the scan matching `version`
+/// (default: latest), returning the explorer and the short commit SHA
+///
+/// # Errors
+/// Returns an error if no scan matches or a query fails.
+pub async fn load(client: &dyn GraphStore, version: Option<&str>) -> Result<(Explorer, String)> {
+    let commit_sha = client
+        .resolve_commit(version)
+        .await?
+        .with_context(|| match version {
+            Some(v) => format!("No scan found for version '{v}'"),
+            None => "No scans found".to_string(),
+        })?;
+    info!("Loading the graph of commit {}...", &commit_sha);
+
+    let symbols = client.symbols_at_commit(&commit_sha).await?;
+    let graph = client.export_commit(&commit_sha).await?;
+    let data = ExploreData::new(symbols, &graph);
+    let short = commit_sha.chars().take(8).collect();
+    Ok((Explorer::new(data), short))
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut explorer: Explorer, commit: &str) -> Result<()> {
+    while !explorer.quit {
+        terminal.draw(|frame| draw(frame, &explorer, commit))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(action) = action_for(key.code) {
+                explorer.apply(action);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The action bound to a key, if any
+#[must_use]
+pub fn action_for(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => Some(Action::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(Action::Down),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => Some(Action::Open),
+        KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => Some(Action::Back),
+        KeyCode::Tab => Some(Action::ToggleDirection),
+        KeyCode::Backspace | KeyCode::Char('b') => Some(Action::JumpBack),
+        KeyCode::Char('q') => Some(Action::Quit),
+        _ => None,
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests for the explore module
+
+mod tests_model;
+mod tests_view;
+
+use std::collections::BTreeMap;
+
+use mother_core::graph::export::{ExportEdge, GraphExport};
+use mother_core::graph::model::{SymbolKind, SymbolNode};
+
+use super::ExploreData;
+
+fn symbol(id: &str, name: &str, file: &str, line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("app::{name}"),
+        visibility: Some("pub".to_string()),
+        signature: Some(format!("fn {name}()")),
+        doc_comment: Some(format!("Docs of {name}")),
+        ..SymbolNode::new(id, name, SymbolKind::Function, file, line, line + 4)
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, line: i64) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: BTreeMap::from([("line".to_string(), line.into())]),
+    }
+}
+
+/// `src/main.rs` with `main` calling `parse` and `run` of `src/lib.rs`,
+/// where `run` also references `parse`
+fn sample() -> ExploreData {
+    let symbols = vec![
+        symbol("s-run", "run", "src/lib.rs", 20),
+        symbol("s-parse", "parse", "src/lib.rs", 1),
+        symbol("s-main", "main", "src/main.rs", 1),
+    ];
+    let graph = GraphExport {
+        nodes: Vec::new(),
+        edges: vec![
+            edge("s-main", "s-parse", "CALLS", 2),
+            edge("s-main", "s-run", "CALLS", 3),
+            edge("s-run", "s-parse", "REFERENCES", 21),
+            edge("s-main", "f-main", "DEFINED_IN", 0),
+            edge("s-main", "s-gone", "CALLS", 4),
+        ],
+    };
+    ExploreData::new(symbols, &graph)
+}
@@ -0,0 +1,130 @@
+//! Tests for explorer navigation
+
+#![allow(clippy::unwrap_used)]
+
+use mother_core::graph::ReferenceDirection;
+use ratatui::crossterm::event::KeyCode;
+
+use super::sample;
+use crate::commands::explore::{action_for, Action, Explorer, Pane};
+
+fn names(explorer: &Explorer) -> Vec<String> {
+    explorer
+        .references()
+        .iter()
+        .map(|r| explorer.data.symbol(&r.symbol_id).unwrap().name.clone())
+        .collect()
+}
+
+#[test]
+fn test_data_groups_symbols_by_file_and_line() {
+    let data = sample();
+
+    assert_eq!(data.files(), ["src/lib.rs", "src/main.rs"]);
+    let lib: Vec<&str> = data.symbols_of(0).iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(lib, ["parse", "run"]);
+    assert!(data.symbols_of(5).is_empty());
+    assert_eq!(data.symbol("s-main").unwrap().file_path, "src/main.rs");
+}
+
+#[test]
+fn test_data_keeps_uses_between_known_symbols() {
+    let data = sample();
+
+    let from: Vec<(&str, i64)> = data
+        .references("s-main", ReferenceDirection::From)
+        .iter()
+        .map(|u| (u.symbol_id.as_str(), u.line))
+        .collect();
+    assert_eq!(from, [("s-parse", 2), ("s-run", 3)]);
+
+    let to: Vec<&str> = data
+        .references("s-parse", ReferenceDirection::To)
+        .iter()
+        .map(|u| u.kind.as_str())
+        .collect();
+    assert_eq!(to, ["CALLS", "REFERENCES"]);
+    assert!(data.references("s-main", ReferenceDirection::To).is_empty());
+}
+
+#[test]
+fn test_explorer_opens_file_symbol_and_references() {
+    let mut explorer = Explorer::new(sample());
+
+    explorer.apply(Action::Open);
+    assert_eq!(explorer.pane, Pane::Symbols);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "parse");
+    assert_eq!(names(&explorer), ["main", "run"]);
+
+    explorer.apply(Action::Down);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "run");
+    explorer.apply(Action::Down);
+    assert_eq!(explorer.symbol, 1, "selection stays on the last row");
+
+    explorer.apply(Action::Open);
+    assert_eq!(explorer.pane, Pane::References);
+    explorer.apply(Action::Back);
+    explorer.apply(Action::Back);
+    assert_eq!(explorer.pane, Pane::Files);
+}
+
+#[test]
+fn test_explorer_jumps_along_references_and_back() {
+    let mut explorer = Explorer::new(sample());
+    explorer.apply(Action::Open);
+    explorer.apply(Action::Open);
+
+    // parse is used by main (line 2) and run (line 21): jump to run
+    explorer.apply(Action::Down);
+    explorer.apply(Action::Open);
+    assert_eq!(explorer.pane, Pane::Symbols);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "run");
+
+    explorer.apply(Action::ToggleDirection);
+    assert_eq!(explorer.direction, ReferenceDirection::From);
+    assert_eq!(names(&explorer), ["parse"]);
+
+    explorer.apply(Action::JumpBack);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "parse");
+    explorer.apply(Action::JumpBack);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "parse");
+}
+
+#[test]
+fn test_explorer_changing_file_resets_selection() {
+    let mut explorer = Explorer::new(sample());
+    explorer.apply(Action::Open);
+    explorer.apply(Action::Down);
+    explorer.apply(Action::Back);
+
+    explorer.apply(Action::Down);
+    assert_eq!(explorer.file, 1);
+    assert_eq!(explorer.symbol, 0);
+    assert_eq!(explorer.selected_symbol().unwrap().name, "main");
+
+    explorer.apply(Action::Quit);
+    assert!(explorer.quit);
+}
+
+#[test]
+fn test_explorer_on_empty_graph_does_nothing() {
+    let mut explorer = Explorer::new(Default::default());
+    for action in [Action::Down, Action::Open, Action::Up, Action::Open] {
+        explorer.apply(action);
+    }
+    assert_eq!(explorer.pane, Pane::Files);
+    assert!(explorer.selected_symbol().is_none());
+    assert!(explorer.references().is_empty());
+}
+
+#[test]
+fn test_action_for_keys() {
+    assert_eq!(action_for(KeyCode::Char('j')), Some(Action::Down));
+    assert_eq!(action_for(KeyCode::Up), Some(Action::Up));
+    assert_eq!(action_for(KeyCode::Enter), Some(Action::Open));
+    assert_eq!(action_for(KeyCode::Esc), Some(Action::Back));
+    assert_eq!(action_for(KeyCode::Tab), Some(Action::ToggleDirection));
+    assert_eq!(action_for(KeyCode::Backspace), Some(Action::JumpBack));
+    assert_eq!(action_for(KeyCode::Char('q')), Some(Action::Quit));
+    assert_eq!(action_for(KeyCode::Char('x')), None);
+}
@@ -0,0 +1,65 @@
+//! Tests for drawing the explorer
+
+#![allow(clippy::unwrap_used)]
+
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use super::sample;
+use crate::commands::explore::{draw, Action, Explorer};
+
+fn render(explorer: &Explorer) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+    terminal
+        .draw(|frame| draw(frame, explorer, "abc12345"))
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_draw_shows_lists_and_details() {
+    let mut explorer = Explorer::new(sample());
+    explorer.apply(Action::Open);
+
+    let screen = render(&explorer);
+
+    assert!(screen.contains("Files @ abc12345"));
+    assert!(screen.contains("src/main.rs"));
+    assert!(screen.contains("function parse"));
+    assert!(screen.contains("Used by"));
+    assert!(screen.contains("main  line 2  calls"));
+    assert!(screen.contains("app::parse"));
+    assert!(screen.contains("pub function"));
+    assert!(screen.contains("src/lib.rs:1-5"));
+    assert!(screen.contains("signature: fn parse()"));
+    assert!(screen.contains("Docs of parse"));
+    assert!(screen.contains("q quit"));
+}
+
+#[test]
+fn test_draw_shows_uses_after_toggle() {
+    let mut explorer = Explorer::new(sample());
+    explorer.apply(Action::Down);
+    explorer.apply(Action::ToggleDirection);
+
+    let screen = render(&explorer);
+
+    assert!(screen.contains("Uses"));
+    assert!(screen.contains("run  line 3  calls"));
+}
+
+#[test]
+fn test_draw_empty_graph() {
+    let screen = render(&Explorer::new(Default::default()));
+
+    assert!(screen.contains("Files @ abc12345"));
+    assert!(screen.contains("Details"));
+}
@@ -0,0 +1,150 @@
+//! Explorer screen: file, symbol and reference lists beside the hover
+//! details of the selected symbol
+
+use mother_core::graph::model::SymbolNode;
+use mother_core::graph::ReferenceDirection;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::model::{Explorer, Pane};
+
+/// Keys listed in the footer
+const HELP: &str = "↑↓ move  ⏎ open/jump  esc back  tab to/from  ⌫ jump back  q quit";
+
+/// Draw the whole screen
+pub fn draw(frame: &mut Frame<'_>, explorer: &Explorer, commit: &str) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [files, symbols, right] = Layout::horizontal([
+        Constraint::Percentage(30),
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+    ])
+    .areas(main);
+    let [references, details] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+
+    let file_items = explorer
+        .data
+        .files()
+        .iter()
+        .map(|path| ListItem::new(path.as_str()));
+    let title = format!("Files @ {commit}");
+    draw_list(
+        frame,
+        files,
+        &title,
+        file_items,
+        explorer.file,
+        explorer.pane == Pane::Files,
+    );
+
+    let symbol_items = explorer
+        .symbols()
+        .iter()
+        .map(|s| ListItem::new(format!("{} {}  :{}", s.kind, s.name, s.start_line)));
+    let focused = explorer.pane == Pane::Symbols;
+    draw_list(
+        frame,
+        symbols,
+        "Symbols",
+        symbol_items,
+        explorer.symbol,
+        focused,
+    );
+
+    let reference_items = explorer.references().iter().map(|r| {
+        let name = explorer.data.symbol(&r.symbol_id).map_or("?", |s| &s.name);
+        ListItem::new(format!(
+            "{name}  line {}  {}",
+            r.line,
+            r.kind.to_lowercase()
+        ))
+    });
+    let title = match explorer.direction {
+        ReferenceDirection::To => "Used by",
+        ReferenceDirection::From => "Uses",
+    };
+    let focused = explorer.pane == Pane::References;
+    draw_list(
+        frame,
+        references,
+        title,
+        reference_items,
+        explorer.reference,
+        focused,
+    );
+
+    let lines = explorer
+        .selected_symbol()
+        .map(detail_lines)
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title("Details")),
+        details,
+    );
+
+    frame.render_widget(Line::from(HELP).dim(), footer);
+}
+
+fn draw_list<'a>(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    title: &str,
+    items: impl Iterator<Item = ListItem<'a>>,
+    selected: usize,
+    focused: bool,
+) {
+    let border = if focused {
+        Style::new().yellow()
+    } else {
+        Style::new()
+    };
+    let list = List::new(items)
+        .block(
+            Block::bordered()
+                .title(title.to_string())
+                .border_style(border),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Hover information of a symbol: name, kind, location, signatures and docs
+fn detail_lines(symbol: &SymbolNode) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(symbol.qualified_name.clone().bold()),
+        Line::from(format!(
+            "{} {}",
+            symbol.visibility.as_deref().unwrap_or("unknown visibility"),
+            symbol.kind
+        )),
+        Line::from(format!(
+            "{}:{}-{}",
+            symbol.file_path, symbol.start_line, symbol.end_line
+        )),
+    ];
+    for (label, value) in [
+        ("signature", &symbol.signature),
+        ("type", &symbol.type_signature),
+        ("resolved", &symbol.resolved_type),
+    ] {
+        if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+            lines.push(Line::from(vec![
+                Span::from(format!("{label}: ")).dim(),
+                Span::from(value.to_string()),
+            ]));
+        }
+    }
+    if let Some(doc) = symbol.doc_comment.as_deref().filter(|d| !d.is_empty()) {
+        lines.push(Line::default());
+        lines.extend(doc.lines().map(|line| Line::from(line.to_string())));
+    }
+    lines
+}
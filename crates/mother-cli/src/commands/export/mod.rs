@@ -0,0 +1,8 @@
+//! Export module: Write a scanned graph to GraphML, DOT or JSON lines
+
+mod run;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
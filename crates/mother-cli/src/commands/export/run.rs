@@ -0,0 +1,200 @@
+//! Export command: Write the graph of one scan to a file or stdout
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use futures::TryStreamExt;
+use mother_core::graph::export::delta::{write_delta, GraphDelta};
+use mother_core::graph::export::{write_graph, ExportFormat, ExportRecord, RecordWriter};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::{GraphReader, GraphStore};
+use tracing::info;
+
+/// Run the export command
+///
+/// Exports the scan selected by `version` (a version tag or commit SHA
+/// prefix, defaulting to the latest scan). With `since`, only the changes
+/// since that scan are exported, as JSON lines with operation markers.
+/// Writes to stdout when no output path is given.
+///
+/// # Errors
+/// Returns an error if no scan matches, the format cannot be determined,
+/// or querying or writing fails.
+pub async fn run(
+    neo4j: &Neo4jConfig,
+    version: Option<&str>,
+    since: Option<&str>,
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let format = resolve_export_format(format, output, since.is_some())?;
+    let client = Neo4jClient::connect(neo4j).await?;
+    export(&client, version, since, format, output).await
+}
+
+/// Run the export command against an open graph store
+///
+/// # Errors
+/// Returns an error if no scan matches, the format cannot be determined,
+/// or querying or writing fails.
+pub async fn run_with_store(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    since: Option<&str>,
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let format = resolve_export_format(format, output, since.is_some())?;
+    export(client, version, since, format, output).await
+}
+
+async fn export(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    since: Option<&str>,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    match since {
+        Some(since) => export_delta(client, version, since, output).await,
+        None => export_from_store(client, version, format, output).await,
+    }
+}
+
+/// Export a scan from an open graph store
+///
+/// JSON lines and chunk exports are written as the store streams records,
+/// so memory stays bounded however large the scan; other formats need the
+/// whole graph first.
+///
+/// # Errors
+/// Returns an error if no scan matches or querying or writing fails.
+pub async fn export_from_store(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let commit_sha = resolve_commit(client, version).await?;
+
+    info!("Exporting commit {} as {}...", commit_sha, format);
+    let mut out = open_output(output)?;
+    let (nodes, edges) = match RecordWriter::new(format) {
+        Some(writer) => stream_records(client, &commit_sha, writer, &mut out).await?,
+        None => {
+            let graph = client.export_commit(&commit_sha).await?;
+            write_graph(&graph, format, &mut out)?;
+            (graph.nodes.len(), graph.edges.len())
+        }
+    };
+    out.flush()?;
+    if let Some(path) = output {
+        info!(
+            "✓ Exported {} nodes and {} edges to {}",
+            nodes,
+            edges,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Write each record of a commit as the store streams it, returning the
+/// number of nodes and edges written
+async fn stream_records(
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    mut writer: RecordWriter,
+    out: &mut impl Write,
+) -> Result<(usize, usize)> {
+    let mut records = GraphReader::new(client).stream_export(commit_sha);
+    let (mut nodes, mut edges) = (0, 0);
+    while let Some(record) = records.try_next().await? {
+        writer.write(&record, out)?;
+        match record {
+            ExportRecord::Node(_) => nodes += 1,
+            ExportRecord::Edge(_) => edges += 1,
+        }
+    }
+    Ok((nodes, edges))
+}
+
+/// Export the changes between the scan `since` and the scan `version` of an
+/// open graph store
+///
+/// # Errors
+/// Returns an error if either scan is missing or querying or writing fails.
+pub async fn export_delta(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    since: &str,
+    output: Option<&Path>,
+) -> Result<()> {
+    let base_sha = resolve_commit(client, Some(since)).await?;
+    let commit_sha = resolve_commit(client, version).await?;
+
+    info!("Exporting changes from {} to {}...", base_sha, commit_sha);
+    let base = client.export_commit(&base_sha).await?;
+    let current = client.export_commit(&commit_sha).await?;
+    let delta = GraphDelta::between(&base, &current);
+
+    let mut out = open_output(output)?;
+    write_delta(&delta, &mut out)?;
+    out.flush()?;
+    info!(
+        "✓ Exported {} upserts and {} deletes",
+        delta.upserted_nodes.len() + delta.upserted_edges.len(),
+        delta.deleted_nodes.len() + delta.deleted_edges.len()
+    );
+    Ok(())
+}
+
+async fn resolve_commit(client: &dyn GraphStore, version: Option<&str>) -> Result<String> {
+    client
+        .resolve_commit(version)
+        .await?
+        .with_context(|| match version {
+            Some(v) => format!("No scan found for version '{v}'"),
+            None => "No scans found".to_string(),
+        })
+}
+
+/// The output file, or stdout when no path is given
+fn open_output(output: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    })
+}
+
+/// Resolve the format of a full export, or check the format of a delta
+/// export, which is always JSON lines
+pub(crate) fn resolve_export_format(
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+    delta: bool,
+) -> Result<ExportFormat> {
+    if !delta {
+        return resolve_format(format, output);
+    }
+    match resolve_format(format, output).unwrap_or(ExportFormat::JsonLines) {
+        ExportFormat::JsonLines => Ok(ExportFormat::JsonLines),
+        other => bail!("--since only supports the jsonl format, not {other}"),
+    }
+}
+
+/// Use the explicit format, or infer it from the output file extension
+pub(crate) fn resolve_format(
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+) -> Result<ExportFormat> {
+    format
+        .or_else(|| output.and_then(ExportFormat::from_path))
+        .context("Cannot infer export format; pass --format graphml|dot|jsonl|lsif|scip|chunks")
+}
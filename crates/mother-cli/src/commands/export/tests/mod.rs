@@ -0,0 +1,3 @@
+//! Tests for export module
+
+mod tests_run;
@@ -0,0 +1,150 @@
+//! Tests for export format resolution and the export command
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use mother_core::graph::export::ExportFormat;
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::neo4j::Neo4jConfig;
+use mother_core::graph::{GraphStore, SqliteStore};
+use serde_json::Value;
+
+use super::super::run::{resolve_export_format, resolve_format};
+use crate::commands::export::{run, run_with_store};
+
+#[test]
+fn test_resolve_format_prefers_explicit() {
+    let format = resolve_format(Some(ExportFormat::Dot), Some(Path::new("graph.graphml")));
+    assert_eq!(format.unwrap(), ExportFormat::Dot);
+}
+
+#[test]
+fn test_resolve_format_infers_from_extension() {
+    let format = resolve_format(None, Some(Path::new("graph.graphml")));
+    assert_eq!(format.unwrap(), ExportFormat::GraphMl);
+}
+
+#[test]
+fn test_resolve_format_requires_format_for_stdout() {
+    assert!(resolve_format(None, None).is_err());
+    assert!(resolve_format(None, Some(Path::new("graph.txt"))).is_err());
+}
+
+#[test]
+fn test_delta_exports_default_to_json_lines() {
+    let format = resolve_export_format(None, None, true);
+    assert_eq!(format.unwrap(), ExportFormat::JsonLines);
+    let format = resolve_export_format(None, Some(Path::new("changes.ndjson")), true);
+    assert_eq!(format.unwrap(), ExportFormat::JsonLines);
+}
+
+#[test]
+fn test_delta_exports_reject_other_formats() {
+    assert!(resolve_export_format(Some(ExportFormat::Dot), None, true).is_err());
+    assert!(resolve_export_format(None, Some(Path::new("graph.graphml")), true).is_err());
+}
+
+#[tokio::test]
+async fn test_run_without_format_fails_before_connecting() {
+    // Fails fast on the format, so no Neo4j instance is needed
+    let config = Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password");
+    let result = run(&config, None, None, None, None).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+#[ignore] // Requires Neo4j instance
+async fn test_run_exports_latest_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("graph.jsonl");
+    let config = Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password");
+
+    let result = run(&config, None, None, None, Some(&output)).await;
+
+    assert!(result.is_ok());
+    assert!(output.exists());
+}
+
+// ============================================================================
+// Delta exports
+// ============================================================================
+
+fn symbol(id: &str, name: &str) -> SymbolNode {
+    SymbolNode::new(id, name, SymbolKind::Function, "/repo/src/lib.rs", 1, 3)
+}
+
+#[tokio::test]
+async fn test_run_with_store_streams_json_lines() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo")
+        .with_commit("aaa111")
+        .with_version("v1");
+    store.create_scan_run(&run).await.unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-1", "rust", "aaa111")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(&[symbol("p1", "parse"), symbol("r1", "run")], "hash-1")
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("graph.jsonl");
+
+    run_with_store(&store, None, None, None, Some(&output))
+        .await
+        .unwrap();
+
+    let types: Vec<String> = std::fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["type"].to_string())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            r#""node""#,
+            r#""node""#,
+            r#""node""#,
+            r#""edge""#,
+            r#""edge""#
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_run_with_store_since_writes_only_changes() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    for (sha, version, hash, symbols) in [
+        ("aaa111", "v1", "hash-1", vec![symbol("p1", "parse")]),
+        ("bbb222", "v2", "hash-2", vec![symbol("p2", "parse")]),
+    ] {
+        let run = ScanRun::new("/repo").with_commit(sha).with_version(version);
+        store.create_scan_run(&run).await.unwrap();
+        store
+            .create_file_if_new("/repo/src/lib.rs", hash, "rust", sha)
+            .await
+            .unwrap();
+        store.create_symbols_batch(&symbols, hash).await.unwrap();
+    }
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("changes.jsonl");
+
+    run_with_store(&store, Some("v2"), Some("v1"), None, Some(&output))
+        .await
+        .unwrap();
+
+    let lines: Vec<Value> = std::fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let ops = |op: &str| lines.iter().filter(|l| l["op"] == op).count();
+    // The new file, its symbol and DEFINED_IN edge replace the old ones
+    assert_eq!(ops("upsert"), 3);
+    assert_eq!(ops("delete"), 3);
+    assert!(run_with_store(&store, None, Some("v9"), None, None)
+        .await
+        .is_err());
+}
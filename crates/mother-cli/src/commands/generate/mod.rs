@@ -0,0 +1,8 @@
+//! Generate module: Shell completion scripts and man pages for packaging
+
+mod run;
+
+pub use run::{completions, manpages};
+
+#[cfg(test)]
+mod tests;
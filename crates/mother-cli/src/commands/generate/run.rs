@@ -0,0 +1,53 @@
+//! Completion and man page generation from the clap definition
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Command, CommandFactory};
+use clap_complete::Shell;
+use clap_mangen::Man;
+use tracing::info;
+
+use crate::cli::Cli;
+
+/// Name the completion scripts complete and the man pages document
+const BIN_NAME: &str = "mother";
+
+/// Write the completion script of `shell` for the `mother` binary
+pub fn completions(shell: Shell, out: &mut dyn Write) {
+    let mut cmd = Cli::command();
+    clap_complete::generate(shell, &mut cmd, BIN_NAME, out);
+}
+
+/// Write a man page for `mother` and one for each visible subcommand, named
+/// like `mother-query-symbols.1`, creating `dir` if needed
+///
+/// Returns the written files, parents before their subcommands.
+///
+/// # Errors
+/// Returns an error if the directory or a page cannot be written.
+pub fn manpages(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut cmd = Cli::command().name(BIN_NAME).disable_help_subcommand(true);
+    cmd.build();
+    let mut pages = Vec::new();
+    write_pages(cmd, dir, &mut pages)
+        .with_context(|| format!("Failed to write man pages to {}", dir.display()))?;
+    info!("Wrote {} man pages to {}", pages.len(), dir.display());
+    Ok(pages)
+}
+
+fn write_pages(cmd: Command, dir: &Path, pages: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let subcommands: Vec<Command> = cmd
+        .get_subcommands()
+        .filter(|s| !s.is_hide_set())
+        .cloned()
+        .collect();
+    pages.push(Man::new(cmd).generate_to(dir)?);
+    for sub in subcommands {
+        write_pages(sub, dir, pages)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,3 @@
+//! Tests for the generate module
+
+mod tests_run;
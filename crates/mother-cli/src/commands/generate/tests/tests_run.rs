@@ -0,0 +1,42 @@
+//! Tests for completion and man page generation
+
+#![allow(clippy::unwrap_used)]
+
+use clap_complete::Shell;
+
+use crate::commands::generate::{completions, manpages};
+
+#[test]
+fn test_completions_cover_nested_subcommands() {
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let mut script = Vec::new();
+        completions(shell, &mut script);
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("mother"), "{shell}");
+        assert!(script.contains("refs-to"), "{shell}");
+        assert!(script.contains("fuzzy"), "{shell}");
+    }
+}
+
+#[test]
+fn test_manpages_write_one_page_per_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("man1");
+
+    let pages = manpages(&out).unwrap();
+
+    let names: Vec<String> = pages
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names[0], "mother.1");
+    assert!(names.contains(&"mother-query.1".to_string()));
+    assert!(names.contains(&"mother-query-symbols.1".to_string()));
+    // Hidden and help subcommands get no page
+    assert!(!names.iter().any(|n| n.contains("help")));
+    for page in &pages {
+        let text = std::fs::read_to_string(page).unwrap();
+        assert!(text.starts_with(".ie"), "{}", page.display());
+    }
+}
@@ -0,0 +1,8 @@
+//! Import module: Store code intelligence dumps other tools produced
+
+mod run;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,179 @@
+//! Import command: Store an LSIF dump as a scan run, without language
+//! servers, or annotate a scan with a coverage report
+//!
+//! The dump's documents become files of the scanned commit, their symbols
+//! are stored like Phase 2 stores them and its references become REFERENCES
+//! edges. A commit already in the store is linked to the new run instead.
+//!
+//! Coverage reports annotate the symbols of an existing scan run with the
+//! line and branch coverage over their ranges.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mother_core::graph::model::{FileWrite, ScanRun, SymbolNode};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+use mother_core::import::coverage::{read_coverage, symbol_coverage, CoverageReport};
+use mother_core::import::{read_lsif, LsifImport};
+use mother_scan::{begin_scan_run, finish_scan_run};
+use tracing::{info, warn};
+
+use crate::commands::runs::find_run;
+use crate::types::ImportCommands;
+
+/// Run the import command
+///
+/// # Errors
+/// Returns an error if the connection fails or the import fails.
+pub async fn run(cmd: ImportCommands, neo4j: &Neo4jConfig) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(cmd, &client).await
+}
+
+/// Run the import command against an open graph store
+///
+/// # Errors
+/// Returns an error if the dump or report cannot be read or parsed, the
+/// scan run is not found, or a store write fails.
+pub async fn run_with_store(cmd: ImportCommands, client: &dyn GraphStore) -> Result<()> {
+    match cmd {
+        ImportCommands::Lsif {
+            file,
+            path,
+            commit,
+            version,
+        } => {
+            let scan_run = import_scan_run(&path, commit.as_deref(), version.as_deref());
+            import_lsif(&file, scan_run, client).await
+        }
+        ImportCommands::Coverage { file, scan } => import_coverage(&file, &scan, client).await,
+    }
+}
+
+/// The scan run an import records: the repository's git info, with the
+/// commit overridden if one is given
+fn import_scan_run(path: &Path, commit: Option<&str>, version: Option<&str>) -> ScanRun {
+    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut scan_run = ScanRun::new(abs_path.display().to_string()).with_git_info();
+    if let Some(sha) = commit {
+        scan_run = scan_run.with_commit(sha);
+    }
+    if let Some(v) = version {
+        scan_run = scan_run.with_version(v);
+    }
+    scan_run
+}
+
+/// Read an LSIF dump and store it under `scan_run`
+async fn import_lsif(file: &Path, scan_run: ScanRun, client: &dyn GraphStore) -> Result<()> {
+    info!("Reading LSIF dump: {}", file.display());
+    let reader = File::open(file)
+        .map(BufReader::new)
+        .with_context(|| format!("Failed to open {}", file.display()))?;
+    let import = read_lsif(reader)
+        .with_context(|| format!("Failed to read LSIF dump {}", file.display()))?;
+    for uri in &import.skipped {
+        warn!("Skipped {}: content neither embedded nor on disk", uri);
+    }
+
+    if !begin_scan_run(client, &scan_run).await? {
+        return Ok(());
+    }
+    let commit_sha = scan_run.commit_sha.as_deref().unwrap_or_default();
+    let result = store_import(&import, client, commit_sha).await;
+    finish_scan_run(client, &scan_run.id, result).await
+}
+
+/// Store the files, symbols and references of an import in a new commit
+///
+/// Like a scan, only files whose content is new get symbols and edges;
+/// reused content keeps what was stored for it before.
+async fn store_import(
+    import: &LsifImport,
+    client: &dyn GraphStore,
+    commit_sha: &str,
+) -> Result<()> {
+    let mut new_symbols = HashSet::new();
+    let mut reused = 0;
+    for document in &import.documents {
+        let file = FileWrite {
+            path: document.path.clone(),
+            content_hash: document.content_hash.clone(),
+            language: document.language.to_string(),
+            commit_sha: commit_sha.to_string(),
+            metrics: Some(document.metrics.clone()),
+            symbols: document.symbols.clone(),
+            ..FileWrite::default()
+        };
+        if !client.write_file(&file).await? {
+            reused += 1;
+            continue;
+        }
+        new_symbols.extend(document.symbols.iter().map(|s| s.id.as_str()));
+    }
+
+    let mut references = 0;
+    for edge in &import.edges {
+        if new_symbols.contains(edge.source_id.as_str()) {
+            client.create_edge(edge).await?;
+            references += 1;
+        }
+    }
+
+    info!(
+        "✓ Import completed: {} new files, {} reused, {} symbols, {} references",
+        import.documents.len() - reused,
+        reused,
+        new_symbols.len(),
+        references
+    );
+    Ok(())
+}
+
+/// Read a coverage report and store the coverage of the symbols of a scan
+/// run
+async fn import_coverage(file: &Path, scan: &str, client: &dyn GraphStore) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let run = find_run(&runs, scan)?;
+    let report = read_report(file)?;
+
+    let symbols = client.symbols_at_commit(&run.commit_sha).await?;
+    warn_unmatched(&report, &symbols, &run.id);
+    let coverage = symbol_coverage(&report, &symbols);
+    client.set_symbol_coverage(&coverage).await?;
+
+    info!(
+        "✓ Coverage imported: {} of {} symbols annotated from {} files",
+        coverage.len(),
+        symbols.len(),
+        report.files.len()
+    );
+    Ok(())
+}
+
+fn read_report(file: &Path) -> Result<CoverageReport> {
+    info!("Reading coverage report: {}", file.display());
+    let reader = File::open(file)
+        .map(BufReader::new)
+        .with_context(|| format!("Failed to open {}", file.display()))?;
+    read_coverage(reader)
+        .with_context(|| format!("Failed to read coverage report {}", file.display()))
+}
+
+/// Warn about report files matching none of the scanned files, usually a
+/// sign the report was produced for other code
+fn warn_unmatched(report: &CoverageReport, symbols: &[SymbolNode], scan_run_id: &str) {
+    let paths: HashSet<&Path> = symbols.iter().map(|s| Path::new(&s.file_path)).collect();
+    for covered in &report.files {
+        if !paths.iter().any(|path| path.ends_with(&covered.path)) {
+            warn!(
+                "Skipped {}: not a file of scan run {}",
+                covered.path, scan_run_id
+            );
+        }
+    }
+}
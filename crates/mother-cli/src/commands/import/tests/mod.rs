@@ -0,0 +1,3 @@
+//! Tests for import module
+
+mod tests_run;
@@ -0,0 +1,151 @@
+//! Tests for importing LSIF dumps
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::{Path, PathBuf};
+
+use mother_core::graph::{GraphStore, Page, QueryParams, SqliteStore};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+use crate::commands::import::run_with_store;
+use crate::types::ImportCommands;
+
+/// `lib.rs` defines `load`, which `main.rs` calls; contents are embedded
+/// as base64
+fn write_dump(dir: &Path) -> PathBuf {
+    let pos = |line: u32, character: u32| json!({ "line": line, "character": character });
+    let definition = |id: u32, name: &str, line: u32, col: u32, end: u32| {
+        json!({
+            "id": id, "type": "vertex", "label": "range",
+            "start": pos(line, col), "end": pos(line, col + 4),
+            "tag": {
+                "type": "definition", "text": name, "kind": 12,
+                "fullRange": { "start": pos(line, 0), "end": pos(end, 1) },
+            },
+        })
+    };
+    let elements: Vec<Value> = vec![
+        json!({ "id": 1, "type": "vertex", "label": "document", "uri": "file:///repo/src/lib.rs",
+                "languageId": "rust", "contents": "cHViIGZuIGxvYWQoKSB7fQo=" }),
+        json!({ "id": 2, "type": "vertex", "label": "document", "uri": "file:///repo/src/main.rs",
+                "languageId": "rust", "contents": "Zm4gbWFpbigpIHsKICAgIGxpYjo6bG9hZCgpOwp9Cg==" }),
+        definition(3, "load", 0, 7, 0),
+        definition(4, "main", 0, 3, 2),
+        json!({ "id": 5, "type": "vertex", "label": "range", "start": pos(1, 9), "end": pos(1, 13) }),
+        json!({ "id": 6, "type": "edge", "label": "contains", "outV": 1, "inVs": [3] }),
+        json!({ "id": 7, "type": "edge", "label": "contains", "outV": 2, "inVs": [4, 5] }),
+        json!({ "id": 8, "type": "vertex", "label": "referenceResult" }),
+        json!({ "id": 9, "type": "edge", "label": "textDocument/references", "outV": 3, "inV": 8 }),
+        json!({ "id": 10, "type": "edge", "label": "item", "outV": 8, "inVs": [3],
+                "document": 1, "property": "definitions" }),
+        json!({ "id": 11, "type": "edge", "label": "item", "outV": 8, "inVs": [5],
+                "document": 2, "property": "references" }),
+    ];
+    let lines: Vec<String> = elements.iter().map(Value::to_string).collect();
+    let path = dir.join("dump.lsif");
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    path
+}
+
+fn lsif(file: PathBuf, commit: &str) -> ImportCommands {
+    ImportCommands::Lsif {
+        file,
+        path: PathBuf::from("."),
+        commit: Some(commit.to_string()),
+        version: Some("ci".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn test_import_lsif_stores_files_symbols_and_references() {
+    let temp = TempDir::new().unwrap();
+    let dump = write_dump(temp.path());
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    run_with_store(lsif(dump, "abc123"), &store).await.unwrap();
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.files, 2);
+    assert_eq!(stats.symbols, 2);
+    assert_eq!(stats.references, 1);
+
+    let symbols = store.symbols_at_commit("abc123").await.unwrap();
+    let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["load", "main"]);
+
+    let references = store
+        .find_references_to("load", Page::default())
+        .await
+        .unwrap();
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].source_name, "main");
+    assert_eq!(references[0].source_file, "/repo/src/main.rs");
+}
+
+#[tokio::test]
+async fn test_import_lsif_links_already_scanned_commit() {
+    let temp = TempDir::new().unwrap();
+    let dump = write_dump(temp.path());
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    run_with_store(lsif(dump.clone(), "abc123"), &store)
+        .await
+        .unwrap();
+    run_with_store(lsif(dump, "abc123"), &store).await.unwrap();
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.scan_runs, 2);
+    assert_eq!(stats.symbols, 2);
+    assert_eq!(stats.references, 1);
+}
+
+#[tokio::test]
+async fn test_import_lsif_missing_file_fails() {
+    let temp = TempDir::new().unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let result = run_with_store(lsif(temp.path().join("missing.lsif"), "abc123"), &store).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_import_coverage_annotates_symbols_of_scan() {
+    let temp = TempDir::new().unwrap();
+    let dump = write_dump(temp.path());
+    let store = SqliteStore::open_in_memory().unwrap();
+    run_with_store(lsif(dump, "abc123"), &store).await.unwrap();
+    let report = temp.path().join("lcov.info");
+    std::fs::write(
+        &report,
+        "SF:src/main.rs\nDA:1,1\nDA:2,0\nend_of_record\nSF:src/other.rs\nDA:1,1\nend_of_record\n",
+    )
+    .unwrap();
+    let scan = store.scan_runs().await.unwrap()[0].id.clone();
+
+    let cmd = ImportCommands::Coverage {
+        file: report.clone(),
+        scan: scan[..8].to_string(),
+    };
+    run_with_store(cmd, &store).await.unwrap();
+
+    let params = QueryParams::new();
+    let count = |query: &'static str| store.execute_raw(query, &params);
+    assert_eq!(count("SELECT * FROM symbol_coverage").await.unwrap(), 1);
+    assert_eq!(
+        count(
+            "SELECT * FROM symbol_coverage WHERE lines_covered = 1 AND lines_total = 2 \
+             AND symbol_id IN (SELECT id FROM symbols WHERE name = 'main')"
+        )
+        .await
+        .unwrap(),
+        1
+    );
+
+    let missing = ImportCommands::Coverage {
+        file: report,
+        scan: "no-such-run".to_string(),
+    };
+    assert!(run_with_store(missing, &store).await.is_err());
+}
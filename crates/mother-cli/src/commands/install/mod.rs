@@ -0,0 +1,11 @@
+//! Install module: Install missing LSP servers
+
+mod plan;
+mod run;
+
+pub(crate) use plan::{find_in_path, install_plan};
+pub use run::run;
+pub(crate) use run::INSTALLABLE_LANGUAGES;
+
+#[cfg(test)]
+mod tests;
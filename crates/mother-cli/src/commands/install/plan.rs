@@ -0,0 +1,109 @@
+//! Install plans: How each language server is installed
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use mother_core::scanner::Language;
+
+/// A command that installs the language server for a language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallPlan {
+    pub language: Language,
+    /// Executable the install provides (e.g. `rust-analyzer`)
+    pub server: String,
+    /// Installer program (e.g. `rustup`, `npm`)
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl InstallPlan {
+    /// The install command as a single shell-style line
+    #[must_use]
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Build the install plan for a language's default server
+///
+/// `version` pins the server version; for Rust it selects the toolchain
+/// whose `rust-analyzer` component is installed. Languages registered in the
+/// config have no plan: their server is whatever the config names.
+#[must_use]
+pub fn install_plan(language: &Language, version: Option<&str>) -> Option<InstallPlan> {
+    let (server, program, args): (&str, &str, Vec<String>) = match language {
+        Language::Rust => {
+            let mut args = vec!["component".into(), "add".into(), "rust-analyzer".into()];
+            if let Some(toolchain) = version {
+                args.extend(["--toolchain".into(), toolchain.into()]);
+            }
+            ("rust-analyzer", "rustup", args)
+        }
+        Language::Python => (
+            "pyright-langserver",
+            "pip",
+            vec!["install".into(), pinned("pyright", "==", version)],
+        ),
+        Language::TypeScript | Language::JavaScript => (
+            "typescript-language-server",
+            "npm",
+            vec![
+                "install".into(),
+                "-g".into(),
+                pinned("typescript-language-server", "@", version),
+                "typescript".into(),
+            ],
+        ),
+        Language::Go => (
+            "gopls",
+            "go",
+            vec![
+                "install".into(),
+                format!("golang.org/x/tools/gopls@{}", version.unwrap_or("latest")),
+            ],
+        ),
+        Language::Ruby => {
+            let mut args = vec!["install".into(), "solargraph".into()];
+            if let Some(v) = version {
+                args.extend(["--version".into(), v.into()]);
+            }
+            ("solargraph", "gem", args)
+        }
+        Language::SysML | Language::KerML => {
+            let mut args = vec!["install".into(), "syster-lsp".into()];
+            if let Some(v) = version {
+                args.extend(["--version".into(), v.into()]);
+            }
+            ("syster-lsp", "cargo", args)
+        }
+        Language::Other(_) => return None,
+    };
+
+    Some(InstallPlan {
+        language: language.clone(),
+        server: server.to_string(),
+        program: program.to_string(),
+        args,
+    })
+}
+
+fn pinned(package: &str, separator: &str, version: Option<&str>) -> String {
+    match version {
+        Some(v) => format!("{package}{separator}{v}"),
+        None => package.to_string(),
+    }
+}
+
+/// Find an executable in a `PATH`-style list of directories
+#[must_use]
+pub fn find_in_path(command: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .flat_map(|dir| {
+            let candidate = dir.join(command);
+            [candidate.with_extension("exe"), candidate]
+        })
+        .find(|p| p.is_file())
+}
@@ -0,0 +1,116 @@
+//! Install-servers command: Install missing LSP servers after confirmation
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::process::Command;
+
+use anyhow::{bail, Result};
+use mother_core::scanner::Language;
+use tracing::info;
+
+use super::plan::{find_in_path, install_plan, InstallPlan};
+
+/// Languages with an installable default server, one per server
+pub const INSTALLABLE_LANGUAGES: &[Language] = &[
+    Language::Rust,
+    Language::Python,
+    Language::TypeScript,
+    Language::Go,
+    Language::Ruby,
+    Language::SysML,
+];
+
+/// Run the install-servers command
+///
+/// Servers already on `PATH` are skipped. Each install asks for confirmation
+/// unless `assume_yes` is set.
+///
+/// # Errors
+/// Returns an error if an installer cannot be started or exits unsuccessfully.
+pub fn run(
+    languages: &[Language],
+    versions: &HashMap<Language, String>,
+    assume_yes: bool,
+) -> Result<()> {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let plans = plan_installs(languages, versions);
+
+    let missing: Vec<&InstallPlan> = plans
+        .iter()
+        .filter(|plan| match find_in_path(&plan.server, &path_var) {
+            Some(found) => {
+                info!("✓ {} already installed at {}", plan.server, found.display());
+                false
+            }
+            None => true,
+        })
+        .collect();
+
+    if missing.is_empty() {
+        info!("All language servers are installed");
+        return Ok(());
+    }
+
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    for plan in missing {
+        if !assume_yes && !confirm(&format!("Install {}", plan.server), plan, &mut input)? {
+            info!("Skipped {}", plan.server);
+            continue;
+        }
+        install(plan)?;
+    }
+
+    Ok(())
+}
+
+/// Install plans for the requested languages, one per distinct server
+#[must_use]
+pub fn plan_installs(
+    languages: &[Language],
+    versions: &HashMap<Language, String>,
+) -> Vec<InstallPlan> {
+    let languages = if languages.is_empty() {
+        INSTALLABLE_LANGUAGES
+    } else {
+        languages
+    };
+
+    let mut plans: Vec<InstallPlan> = Vec::new();
+    for language in languages {
+        let Some(plan) = install_plan(language, versions.get(language).map(String::as_str)) else {
+            continue;
+        };
+        if !plans.iter().any(|p| p.server == plan.server) {
+            plans.push(plan);
+        }
+    }
+    plans
+}
+
+/// Ask whether to run an install plan; anything but `y`/`yes` declines
+///
+/// # Errors
+/// Returns an error if reading the answer fails.
+pub fn confirm(prompt: &str, plan: &InstallPlan, input: &mut impl BufRead) -> Result<bool> {
+    print!("{} via `{}`? [y/N] ", prompt, plan.command_line());
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn install(plan: &InstallPlan) -> Result<()> {
+    info!("Running {}", plan.command_line());
+    let status = Command::new(&plan.program)
+        .args(&plan.args)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", plan.program, e))?;
+
+    if !status.success() {
+        bail!("`{}` failed with {}", plan.command_line(), status);
+    }
+    info!("✓ Installed {}", plan.server);
+    Ok(())
+}
@@ -0,0 +1,4 @@
+//! Tests for install module
+
+mod tests_plan;
+mod tests_run;
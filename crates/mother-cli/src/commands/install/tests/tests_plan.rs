@@ -0,0 +1,92 @@
+//! Tests for install plans and PATH lookup
+
+#![allow(clippy::unwrap_used)]
+
+use std::ffi::OsString;
+use std::fs;
+
+use mother_core::scanner::Language;
+use tempfile::TempDir;
+
+use super::super::plan::{find_in_path, install_plan};
+
+#[test]
+fn test_rust_plan_uses_rustup_component() {
+    let plan = install_plan(&Language::Rust, None).unwrap();
+    assert_eq!(plan.server, "rust-analyzer");
+    assert_eq!(plan.command_line(), "rustup component add rust-analyzer");
+}
+
+#[test]
+fn test_rust_plan_pins_toolchain() {
+    let plan = install_plan(&Language::Rust, Some("1.85.0")).unwrap();
+    assert_eq!(
+        plan.command_line(),
+        "rustup component add rust-analyzer --toolchain 1.85.0"
+    );
+}
+
+#[test]
+fn test_python_plan_pins_with_pip_syntax() {
+    let plan = install_plan(&Language::Python, Some("1.1.380")).unwrap();
+    assert_eq!(plan.server, "pyright-langserver");
+    assert_eq!(plan.command_line(), "pip install pyright==1.1.380");
+}
+
+#[test]
+fn test_typescript_and_javascript_share_server() {
+    let ts = install_plan(&Language::TypeScript, None).unwrap();
+    let js = install_plan(&Language::JavaScript, None).unwrap();
+    assert_eq!(ts.server, js.server);
+    assert_eq!(
+        ts.command_line(),
+        "npm install -g typescript-language-server typescript"
+    );
+}
+
+#[test]
+fn test_go_plan_defaults_to_latest() {
+    assert_eq!(
+        install_plan(&Language::Go, None).unwrap().command_line(),
+        "go install golang.org/x/tools/gopls@latest"
+    );
+    assert_eq!(
+        install_plan(&Language::Go, Some("v0.16.1"))
+            .unwrap()
+            .command_line(),
+        "go install golang.org/x/tools/gopls@v0.16.1"
+    );
+}
+
+#[test]
+fn test_ruby_plan_installs_solargraph_gem() {
+    let plan = install_plan(&Language::Ruby, Some("0.50.0")).unwrap();
+    assert_eq!(plan.server, "solargraph");
+    assert_eq!(
+        plan.command_line(),
+        "gem install solargraph --version 0.50.0"
+    );
+}
+
+#[test]
+fn test_find_in_path_finds_executable() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("gopls"), "").unwrap();
+    let path_var = OsString::from(temp_dir.path());
+
+    assert_eq!(
+        find_in_path("gopls", &path_var),
+        Some(temp_dir.path().join("gopls"))
+    );
+    assert!(find_in_path("rust-analyzer", &path_var).is_none());
+}
+
+#[test]
+fn test_find_in_path_empty_path() {
+    assert!(find_in_path("gopls", &OsString::new()).is_none());
+}
+
+#[test]
+fn test_install_plan_none_for_configured_language() {
+    assert_eq!(install_plan(&Language::Other("elixir".into()), None), None);
+}
@@ -0,0 +1,57 @@
+//! Tests for install-servers planning and confirmation
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use mother_core::scanner::Language;
+
+use super::super::plan::install_plan;
+use super::super::run::{confirm, plan_installs, INSTALLABLE_LANGUAGES};
+
+#[test]
+fn test_plan_installs_defaults_to_all_servers() {
+    let plans = plan_installs(&[], &HashMap::new());
+    assert_eq!(plans.len(), INSTALLABLE_LANGUAGES.len());
+}
+
+#[test]
+fn test_plan_installs_deduplicates_shared_servers() {
+    let plans = plan_installs(
+        &[
+            Language::TypeScript,
+            Language::JavaScript,
+            Language::SysML,
+            Language::KerML,
+        ],
+        &HashMap::new(),
+    );
+    let servers: Vec<_> = plans.iter().map(|p| p.server.as_str()).collect();
+    assert_eq!(servers, vec!["typescript-language-server", "syster-lsp"]);
+}
+
+#[test]
+fn test_plan_installs_applies_pinned_versions() {
+    let versions = HashMap::from([(Language::Python, "1.1.380".to_string())]);
+    let plans = plan_installs(&[Language::Python], &versions);
+    assert_eq!(plans[0].args, vec!["install", "pyright==1.1.380"]);
+}
+
+#[test]
+fn test_confirm_accepts_yes() {
+    let plan = install_plan(&Language::Go, None).unwrap();
+    for answer in ["y\n", "YES\n", " yes \n"] {
+        let mut input = Cursor::new(answer);
+        assert!(confirm("Install gopls", &plan, &mut input).unwrap());
+    }
+}
+
+#[test]
+fn test_confirm_declines_by_default() {
+    let plan = install_plan(&Language::Go, None).unwrap();
+    for answer in ["\n", "n\n", "maybe\n", ""] {
+        let mut input = Cursor::new(answer);
+        assert!(!confirm("Install gopls", &plan, &mut input).unwrap());
+    }
+}
@@ -0,0 +1,8 @@
+//! LSP search module: Live workspace symbol lookups without a scan
+
+mod run;
+
+pub use run::run;
+
+#[cfg(test)]
+mod tests;
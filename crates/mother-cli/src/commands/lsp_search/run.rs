@@ -0,0 +1,166 @@
+//! LSP search command: Ask the language servers for workspace symbols
+//!
+//! Starts the configured server of every language found in the repository
+//! and sends it a `workspace/symbol` request, so results come live from the
+//! servers and nothing is read from or written to a graph store. Servers
+//! such as `typescript-language-server` only search projects they have
+//! loaded, so one file per language is opened first.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use mother_core::lsp::{LspServerManager, LspSymbol, LspSymbolKind};
+use mother_core::scanner::{DiscoveredFile, Language};
+use mother_scan::{shutdown_lsp, ScanOptions};
+use serde::Serialize;
+use tracing::info;
+
+use crate::commands::query::print_rows;
+use crate::types::OutputFormat;
+
+/// A symbol a language server found
+///
+/// `file_path` is relative to the searched repository when inside it.
+/// `line` and `column` are 1-based; `container` is empty when the server
+/// does not report one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SearchResult {
+    pub name: String,
+    pub kind: String,
+    pub container: String,
+    pub language: String,
+    pub file_path: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl SearchResult {
+    pub(crate) fn new(symbol: &LspSymbol, language: Language, root: &Path) -> Self {
+        let file_path = symbol.file.strip_prefix(root).unwrap_or(&symbol.file);
+        Self {
+            name: symbol.name.clone(),
+            kind: kind_name(symbol.kind),
+            container: symbol.container_name.clone().unwrap_or_default(),
+            language: language.to_string(),
+            file_path: file_path.display().to_string(),
+            line: symbol.start_line + 1,
+            column: symbol.start_col + 1,
+        }
+    }
+}
+
+/// Run the LSP search command
+///
+/// `languages` limits the servers asked; empty asks one per language found
+/// under `path`. A server that fails to start or answer is logged and
+/// skipped.
+///
+/// # Errors
+/// Returns an error if the include/exclude globs are invalid, no file of
+/// the requested languages exists, or no server answered.
+pub async fn run(
+    query: &str,
+    path: &Path,
+    languages: &[Language],
+    options: &ScanOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let files = options.discover_files(&root)?;
+    let targets = search_targets(&files, languages);
+    if targets.is_empty() {
+        bail!("No source files to search in {}", root.display());
+    }
+
+    let lsp_manager = options.lsp_manager(&root);
+    let mut results = Vec::new();
+    let mut answered = 0;
+    for file in &targets {
+        info!("Searching {} symbols for '{}'...", file.language, query);
+        match search_language(file, query, &lsp_manager).await {
+            Ok(symbols) => {
+                answered += 1;
+                let found = symbols
+                    .iter()
+                    .map(|s| SearchResult::new(s, file.language.clone(), &root));
+                results.extend(found);
+            }
+            Err(e) => tracing::warn!("{} server search failed: {}", file.language, e),
+        }
+    }
+    shutdown_lsp(&lsp_manager).await;
+
+    if answered == 0 {
+        bail!("No language server answered the search");
+    }
+    print_rows(&results, format, |results| {
+        print_results_table(results, query)
+    })
+}
+
+/// One file per language to search, in discovery order
+///
+/// With `languages` given only those are kept; requested languages without
+/// files are logged.
+pub(crate) fn search_targets<'a>(
+    files: &'a [DiscoveredFile],
+    languages: &[Language],
+) -> Vec<&'a DiscoveredFile> {
+    let mut targets: Vec<&DiscoveredFile> = Vec::new();
+    for file in files {
+        let wanted = languages.is_empty() || languages.contains(&file.language);
+        if wanted && !targets.iter().any(|t| t.language == file.language) {
+            targets.push(file);
+        }
+    }
+    for language in languages {
+        if !targets.iter().any(|t| t.language == *language) {
+            tracing::warn!("No {} files found, skipping its server", language);
+        }
+    }
+    targets
+}
+
+/// Open `file` in its language's server and search the workspace
+async fn search_language(
+    file: &DiscoveredFile,
+    query: &str,
+    lsp_manager: &LspServerManager,
+) -> Result<Vec<LspSymbol>> {
+    let file_uri = format!("file://{}", file.path.display());
+    let content = std::fs::read_to_string(&file.path)?;
+
+    let lsp_client = lsp_manager.get_client(&file.language).await?;
+    lsp_client
+        .did_open(&file_uri, &file.language.to_string(), &content)
+        .await?;
+    lsp_client.workspace_symbols(query).await
+}
+
+/// Lowercase name of a symbol kind, as serialized
+fn kind_name(kind: LspSymbolKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn print_results_table(results: &[SearchResult], query: &str) {
+    if results.is_empty() {
+        println!("No symbols found matching '{}'", query);
+        return;
+    }
+
+    println!(
+        "\n{:<32} {:<24} {:<14} {:<12} {:<50} LINE",
+        "NAME", "CONTAINER", "KIND", "LANGUAGE", "FILE"
+    );
+    println!("{}", "-".repeat(140));
+    for r in results {
+        println!(
+            "{:<32} {:<24} {:<14} {:<12} {:<50} {}",
+            r.name, r.container, r.kind, r.language, r.file_path, r.line
+        );
+    }
+    println!("\nFound {} symbols", results.len());
+}
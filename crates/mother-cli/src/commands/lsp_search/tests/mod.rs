@@ -0,0 +1,3 @@
+//! Tests for lsp_search module
+
+mod tests_run;
@@ -0,0 +1,94 @@
+//! Tests for choosing servers and shaping LSP search results
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::{Path, PathBuf};
+
+use mother_core::lsp::{LspSymbol, LspSymbolKind};
+use mother_core::scanner::{DiscoveredFile, Language};
+use mother_scan::ScanOptions;
+use tempfile::TempDir;
+
+use super::super::run::{run, search_targets, SearchResult};
+use crate::types::OutputFormat;
+
+fn file(path: &str, language: Language) -> DiscoveredFile {
+    DiscoveredFile {
+        path: PathBuf::from(path),
+        language,
+    }
+}
+
+fn files() -> Vec<DiscoveredFile> {
+    vec![
+        file("/repo/src/main.rs", Language::Rust),
+        file("/repo/src/lib.rs", Language::Rust),
+        file("/repo/tools/gen.py", Language::Python),
+    ]
+}
+
+#[test]
+fn test_search_targets_take_the_first_file_of_each_language() {
+    let files = files();
+    let targets = search_targets(&files, &[]);
+
+    let paths: Vec<_> = targets.iter().map(|f| f.path.as_path()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            Path::new("/repo/src/main.rs"),
+            Path::new("/repo/tools/gen.py")
+        ]
+    );
+}
+
+#[test]
+fn test_search_targets_keep_requested_languages() {
+    let files = files();
+    let targets = search_targets(&files, &[Language::Python, Language::Go]);
+
+    assert_eq!(targets.len(), 1);
+    assert_eq!(targets[0].language, Language::Python);
+}
+
+#[test]
+fn test_search_result_is_relative_and_one_based() {
+    let symbol = LspSymbol {
+        name: "parse".to_string(),
+        kind: LspSymbolKind::Function,
+        detail: None,
+        container_name: Some("Parser".to_string()),
+        file: PathBuf::from("/repo/src/lib.rs"),
+        start_line: 9,
+        end_line: 20,
+        start_col: 4,
+        end_col: 9,
+        children: vec![],
+    };
+
+    let result = SearchResult::new(&symbol, Language::Rust, Path::new("/repo"));
+    assert_eq!(result.kind, "function");
+    assert_eq!(result.container, "Parser");
+    assert_eq!(result.file_path, "src/lib.rs");
+    assert_eq!((result.line, result.column), (10, 5));
+
+    let outside = SearchResult::new(&symbol, Language::Rust, Path::new("/other"));
+    assert_eq!(outside.file_path, "/repo/src/lib.rs");
+}
+
+#[tokio::test]
+async fn test_run_without_source_files_is_an_error() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("README.md"), "# Demo").unwrap();
+
+    let result = run(
+        "parse",
+        temp.path(),
+        &[],
+        &ScanOptions::default(),
+        OutputFormat::Table,
+    )
+    .await;
+
+    assert!(result.is_err());
+}
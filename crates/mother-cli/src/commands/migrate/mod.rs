@@ -0,0 +1,8 @@
+//! Migrate module: Bring a stored graph up to the current schema version
+
+mod run;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+//! Migrate command: Apply pending schema migrations and show the version
+
+use anyhow::Result;
+use mother_core::graph::migrations::MigrationRunner;
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+
+/// Run the migrate command
+///
+/// Connecting applies the pending migrations, so this only reports the
+/// result.
+///
+/// # Errors
+/// Returns an error if the connection or a migration fails, or the graph
+/// was migrated by a newer release.
+pub async fn run(neo4j: &Neo4jConfig) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(&client).await
+}
+
+/// Show the schema version of an open graph store and the migrations it has
+///
+/// # Errors
+/// Returns an error if the version cannot be read.
+pub async fn run_with_store(client: &dyn GraphStore) -> Result<()> {
+    let version = client.schema_version().await?;
+    let runner = MigrationRunner::default();
+    println!("{}", status_line(version, runner.latest()));
+    for migration in runner.migrations() {
+        let mark = if migration.version <= version {
+            '✓'
+        } else {
+            ' '
+        };
+        println!(
+            "  {} {:>3}  {}",
+            mark, migration.version, migration.description
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn status_line(version: u32, latest: u32) -> String {
+    if version >= latest {
+        format!("Schema version {version} (up to date)")
+    } else {
+        format!("Schema version {version} of {latest}")
+    }
+}
@@ -0,0 +1,3 @@
+//! Tests for migrate module
+
+mod tests_run;
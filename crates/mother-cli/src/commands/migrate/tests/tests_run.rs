@@ -0,0 +1,25 @@
+//! Tests for the migrate command
+
+#![allow(clippy::unwrap_used)]
+
+use mother_core::graph::migrations::MigrationRunner;
+use mother_core::graph::{GraphStore, SqliteStore};
+
+use super::super::run::status_line;
+use crate::commands::migrate::run_with_store;
+
+#[tokio::test]
+async fn test_opened_stores_are_migrated_to_the_latest_version() {
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    run_with_store(&store).await.unwrap();
+
+    let latest = MigrationRunner::default().latest();
+    assert_eq!(store.schema_version().await.unwrap(), latest);
+}
+
+#[test]
+fn test_status_line_reports_pending_versions() {
+    assert_eq!(status_line(2, 2), "Schema version 2 (up to date)");
+    assert_eq!(status_line(1, 3), "Schema version 1 of 3");
+}
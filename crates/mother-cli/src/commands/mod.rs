@@ -1,5 +1,19 @@
 //! CLI commands
 
+pub mod backup;
 pub mod diff;
+pub mod doctor;
+pub mod explore;
+pub mod export;
+pub mod generate;
+pub mod import;
+pub mod install;
+pub mod lsp_search;
+pub mod migrate;
 pub mod query;
+pub mod runs;
 pub mod scan;
+pub mod scan_history;
+pub mod self_test;
+pub mod serve;
+pub mod watch;
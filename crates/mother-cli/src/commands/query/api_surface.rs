@@ -0,0 +1,69 @@
+//! API surface: The symbols reachable from outside a module and the internal
+//! symbols they expose
+
+use anyhow::Result;
+use mother_core::graph::api_surface::{api_surface, ApiSymbol};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::{resolve_scope, resolve_version};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    path: &str,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    let scope = resolve_scope(path)?;
+    info!(
+        "Collecting API surface of {} at commit {}...",
+        path,
+        short_sha(&commit_sha)
+    );
+
+    let graph = client.export_commit(&commit_sha).await?;
+    let surface = api_surface(&graph, &scope);
+    print_rows(&surface, format, |surface| {
+        print_api_surface_table(surface, path);
+    })
+}
+
+fn print_api_surface_table(surface: &[ApiSymbol], path: &str) {
+    let public = surface.iter().filter(|s| s.is_public()).count();
+    if public == 0 {
+        println!("No public symbols found in '{}'", path);
+        return;
+    }
+
+    println!(
+        "\n{:<40} {:<12} {:<12} {:<50} {:<6}",
+        "SYMBOL", "KIND", "VISIBILITY", "FILE", "LINE"
+    );
+    println!("{}", "-".repeat(124));
+    for (i, s) in surface.iter().enumerate() {
+        if i == public {
+            println!("\nExposed internal symbols:");
+            println!("{}", "-".repeat(124));
+        }
+        println!(
+            "{:<40} {:<12} {:<12} {:<50} {:<6}",
+            truncate_str(&s.qualified_name, 40),
+            s.kind,
+            s.visibility,
+            truncate_path(&s.file_path, 50),
+            s.start_line,
+        );
+        if !s.exposed_by.is_empty() {
+            println!("  via {}", s.exposed_by.join(", "));
+        }
+    }
+
+    println!(
+        "\n{} public symbols, {} internal symbols exposed",
+        public,
+        surface.len() - public
+    );
+}
@@ -0,0 +1,90 @@
+//! Churn: Symbols or files ranked by how often the last scans of a branch
+//! added or modified them
+
+use anyhow::Result;
+use mother_core::graph::churn::{
+    by_file as churn_by_file, by_symbol as churn_by_symbol, FileChurn, ScanSymbols, SymbolChurn,
+};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::oldest_scans_first;
+use super::output::{print_rows, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    last: usize,
+    branch: Option<&str>,
+    level: &str,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    // One more scan than counted: the oldest is what the first is compared with
+    let scans = oldest_scans_first(client, branch, last + 1).await?;
+    info!("Counting changes over {} scans...", scans.len() - 1);
+
+    let mut history = Vec::with_capacity(scans.len());
+    for scan in &scans {
+        history.push(ScanSymbols {
+            symbols: client.symbols_at_commit(&scan.commit_sha).await?,
+            modified: client.modified_symbols(&scan.commit_sha).await?,
+        });
+    }
+    let symbols = churn_by_symbol(&history);
+    if level == "file" {
+        let files: Vec<FileChurn> = churn_by_file(&symbols).into_iter().take(limit).collect();
+        return print_rows(&files, format, print_file_churn_table);
+    }
+    let symbols: Vec<SymbolChurn> = symbols.into_iter().take(limit).collect();
+    print_rows(&symbols, format, print_symbol_churn_table)
+}
+
+fn print_symbol_churn_table(symbols: &[SymbolChurn]) {
+    if symbols.is_empty() {
+        println!("No changed symbols found");
+        return;
+    }
+
+    println!(
+        "\n{:<8} {:<8} {:<8} {:<12} {:<40} FILE",
+        "CHANGES", "INSERTS", "UPDATES", "KIND", "SYMBOL"
+    );
+    println!("{}", "-".repeat(130));
+    for s in symbols {
+        println!(
+            "{:<8} {:<8} {:<8} {:<12} {:<40} {}",
+            s.changes(),
+            s.inserts,
+            s.updates,
+            s.kind,
+            truncate_str(&s.qualified_name, 40),
+            truncate_path(&s.file_path, 50)
+        );
+    }
+    println!("\nTotal: {} symbols", symbols.len());
+}
+
+fn print_file_churn_table(files: &[FileChurn]) {
+    if files.is_empty() {
+        println!("No changed files found");
+        return;
+    }
+
+    println!(
+        "\n{:<8} {:<8} {:<8} {:<8} PATH",
+        "CHANGES", "INSERTS", "UPDATES", "SYMBOLS"
+    );
+    println!("{}", "-".repeat(100));
+    for f in files {
+        println!(
+            "{:<8} {:<8} {:<8} {:<8} {}",
+            f.changes(),
+            f.inserts,
+            f.updates,
+            f.symbols,
+            truncate_path(&f.path, 60)
+        );
+    }
+    println!("\nTotal: {} files", files.len());
+}
@@ -0,0 +1,113 @@
+//! Contracts: API endpoints and messages with the symbols serving them
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use mother_core::graph::model::SymbolNode;
+use mother_core::graph::GraphStore;
+use serde::Serialize;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+/// An API contract of `query contracts` with the symbols serving it
+#[derive(Debug, Clone, Serialize)]
+struct ContractRow {
+    kind: String,
+    name: String,
+    file_path: String,
+    line: u32,
+    handlers: Vec<ContractHandler>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ContractHandler {
+    qualified_name: String,
+    file_path: String,
+    start_line: u32,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    name: Option<&str>,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!(
+        "Listing API contracts at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    let name = name.map(str::to_lowercase);
+    let contracts = client.contracts_at_commit(&commit_sha).await?;
+    let symbols = client.symbols_at_commit(&commit_sha).await?;
+    let symbols: BTreeMap<&str, &SymbolNode> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let rows: Vec<ContractRow> = contracts
+        .into_iter()
+        .filter(|c| {
+            name.as_ref()
+                .is_none_or(|name| c.name.to_lowercase().contains(name))
+        })
+        .map(|c| ContractRow {
+            handlers: c
+                .handler_ids
+                .iter()
+                .filter_map(|id| symbols.get(id.as_str()))
+                .map(|s| ContractHandler {
+                    qualified_name: s.qualified_name.clone(),
+                    file_path: s.file_path.clone(),
+                    start_line: s.start_line,
+                })
+                .collect(),
+            kind: c.kind,
+            name: c.name,
+            file_path: c.file_path,
+            line: c.line,
+        })
+        .collect();
+    print_rows(&rows, format, |rows| {
+        print_contracts_table(rows, &commit_sha);
+    })
+}
+
+fn print_contracts_table(rows: &[ContractRow], commit_sha: &str) {
+    if rows.is_empty() {
+        println!("No API contracts found at commit {}", short_sha(commit_sha));
+        return;
+    }
+
+    println!(
+        "\n{:<9} {:<40} {:<40} HANDLED BY",
+        "KIND", "CONTRACT", "DECLARED IN"
+    );
+    println!("{}", "-".repeat(130));
+    for r in rows {
+        let handlers: Vec<String> = r
+            .handlers
+            .iter()
+            .map(|h| {
+                format!(
+                    "{} ({}:{})",
+                    h.qualified_name,
+                    truncate_path(&h.file_path, 40),
+                    h.start_line
+                )
+            })
+            .collect();
+        println!(
+            "{:<9} {:<40} {:<40} {}",
+            r.kind,
+            truncate_str(&r.name, 40),
+            format!("{}:{}", truncate_path(&r.file_path, 34), r.line),
+            if handlers.is_empty() {
+                "-".to_string()
+            } else {
+                handlers.join(", ")
+            }
+        );
+    }
+    println!("\nTotal: {} contracts", rows.len());
+}
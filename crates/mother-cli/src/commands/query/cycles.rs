@@ -0,0 +1,66 @@
+//! Cycles: Dependency cycles between files or modules
+
+use anyhow::Result;
+use mother_core::graph::cycles::{dependency_cycles, CycleLevel, DependencyCycle};
+use mother_core::graph::{GraphStore, PathScope};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    level: &str,
+    version: Option<&str>,
+    scope: Option<&PathScope>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!(
+        "Finding dependency cycles at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    let level = match level {
+        "module" => CycleLevel::Module,
+        _ => CycleLevel::File,
+    };
+    let graph = client.export_commit(&commit_sha).await?;
+    let cycles: Vec<DependencyCycle> = dependency_cycles(&graph, level)
+        .into_iter()
+        .filter(|c| scope.is_none_or(|scope| c.members.iter().any(|m| scope.matches(m))))
+        .collect();
+    let members = match level {
+        CycleLevel::File => "files",
+        CycleLevel::Module => "modules",
+    };
+    print_rows(&cycles, format, |cycles| {
+        print_cycles_table(cycles, members);
+    })
+}
+
+fn print_cycles_table(cycles: &[DependencyCycle], members: &str) {
+    if cycles.is_empty() {
+        println!("No dependency cycles found");
+        return;
+    }
+
+    for (i, cycle) in cycles.iter().enumerate() {
+        println!("\nCycle {} ({} {}):", i + 1, cycle.members.len(), members);
+        for member in &cycle.members {
+            println!("  {}", truncate_path(member, 100));
+        }
+        println!("  through:");
+        for link in &cycle.links {
+            println!(
+                "    {}:{} -> {}  {}",
+                truncate_path(&link.from, 40),
+                link.line,
+                truncate_path(&link.to, 40),
+                truncate_str(&link.to_string(), 80)
+            );
+        }
+    }
+    println!("\nTotal: {} cycles", cycles.len());
+}
@@ -0,0 +1,59 @@
+//! Diagnostics: What the language servers reported during a scan
+
+use anyhow::Result;
+use mother_core::graph::{DiagnosticResult, GraphStore, PathScope};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+/// Which diagnostics `query diagnostics` lists
+pub(super) struct DiagnosticFilter<'a> {
+    pub(super) version: Option<&'a str>,
+    pub(super) severity: Option<&'a str>,
+    pub(super) scope: Option<&'a PathScope>,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    filter: DiagnosticFilter<'_>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, filter.version).await?;
+    info!(
+        "Listing diagnostics at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    let diagnostics = client
+        .find_diagnostics(&commit_sha, filter.severity, filter.scope)
+        .await?;
+    print_rows(&diagnostics, format, print_diagnostics_table)
+}
+
+fn print_diagnostics_table(diagnostics: &[DiagnosticResult]) {
+    if diagnostics.is_empty() {
+        println!("No diagnostics found");
+        return;
+    }
+
+    println!(
+        "\n{:<12} {:<12} {:<50} {:<30} MESSAGE",
+        "SEVERITY", "CODE", "LOCATION", "SYMBOL"
+    );
+    println!("{}", "-".repeat(130));
+    for d in diagnostics {
+        let location = format!("{}:{}", d.file_path, d.start_line);
+        println!(
+            "{:<12} {:<12} {:<50} {:<30} {}",
+            d.severity,
+            truncate_str(&d.code, 12),
+            truncate_path(&location, 50),
+            truncate_str(&d.symbol, 30),
+            d.message.lines().next().unwrap_or_default()
+        );
+    }
+
+    println!("\nFound {} diagnostics", diagnostics.len());
+}
@@ -0,0 +1,47 @@
+//! Dir: The summary of one directory
+
+use anyhow::{Context, Result};
+use mother_core::graph::model::DirectorySummary;
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_row, short_sha, truncate_path};
+use super::{absolute_path, resolve_version};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    path: &str,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    let dir = absolute_path(path)?;
+    info!(
+        "Summarizing {} at commit {}...",
+        dir,
+        short_sha(&commit_sha)
+    );
+
+    let summary = client
+        .directory_summary(&dir, &commit_sha)
+        .await?
+        .with_context(|| format!("No directory summary for {dir}"))?;
+    print_row(&summary, format, print_directory_table)
+}
+
+fn print_directory_table(summary: &DirectorySummary) {
+    println!("\n=== {} ===\n", summary.path);
+    if let Some(readme) = &summary.readme {
+        println!("{readme}\n");
+    }
+    println!("Files:     {}", summary.file_count);
+    println!("Lines:     {}", summary.line_count);
+    println!("Languages: {}", summary.languages.join(", "));
+    if !summary.files.is_empty() {
+        println!("\nFiles directly in this directory:");
+        for file in &summary.files {
+            println!("  {}", truncate_path(file, 100));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Dirs: Roll-ups of the subdirectories of a directory
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mother_core::graph::directories::{
+    rollup as rollup_directories, root as directory_root, DirectoryRollup,
+};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path};
+use super::{absolute_path, resolve_version};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    path: Option<&str>,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    let directories = client.directories_at_commit(&commit_sha).await?;
+    let parent = match path {
+        Some(path) => absolute_path(path)?,
+        None => directory_root(&directories)
+            .with_context(|| format!("No directories stored for {}", short_sha(&commit_sha)))?
+            .path
+            .clone(),
+    };
+    info!(
+        "Rolling up subdirectories of {} at commit {}...",
+        parent,
+        short_sha(&commit_sha)
+    );
+
+    let symbols = client.symbols_at_commit(&commit_sha).await?;
+    let modified = client.modified_symbols(&commit_sha).await?;
+    let modified: HashSet<&str> = modified.iter().map(|s| s.id.as_str()).collect();
+    let graph = client.export_commit(&commit_sha).await?;
+    let rollups = rollup_directories(&parent, &directories, &symbols, &modified, &graph);
+    print_rows(&rollups, format, |rollups| {
+        print_directory_rollup_table(rollups, &parent);
+    })
+}
+
+fn print_directory_rollup_table(rollups: &[DirectoryRollup], parent: &str) {
+    if rollups.is_empty() {
+        println!("No subdirectories found in {parent}");
+        return;
+    }
+
+    println!(
+        "\n{:<40} {:>6} {:>8} {:>8} {:>8}  DEPENDS ON",
+        "DIRECTORY", "FILES", "LINES", "SYMBOLS", "MODIFIED"
+    );
+    println!("{}", "-".repeat(110));
+
+    let name = |path: &str| {
+        Path::new(path)
+            .strip_prefix(parent)
+            .map_or_else(|_| path.to_string(), |p| p.display().to_string())
+    };
+    for r in rollups {
+        let depends_on: Vec<String> = r
+            .depends_on
+            .iter()
+            .map(|d| format!("{} ({})", name(&d.path), d.links))
+            .collect();
+        println!(
+            "{:<40} {:>6} {:>8} {:>8} {:>8}  {}",
+            truncate_path(&name(&r.path), 40),
+            r.file_count,
+            r.line_count,
+            r.symbols,
+            r.modified,
+            depends_on.join(", ")
+        );
+    }
+}
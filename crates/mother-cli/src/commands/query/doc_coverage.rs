@@ -0,0 +1,83 @@
+//! Doc coverage: The share of public symbols with a doc comment per file or
+//! module
+
+use anyhow::{bail, Result};
+use mother_core::graph::doc_coverage::{
+    by_file as doc_coverage_by_file, by_module as doc_coverage_by_module, overall_percent,
+    DocCoverage,
+};
+use mother_core::graph::model::SymbolNode;
+use mother_core::graph::{GraphStore, PathScope};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+/// What `query doc-coverage` reports on, and the minimum it enforces
+pub(super) struct DocCoverageFilter<'a> {
+    pub(super) level: &'a str,
+    pub(super) version: Option<&'a str>,
+    pub(super) scope: Option<&'a PathScope>,
+    pub(super) min: Option<u8>,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    filter: DocCoverageFilter<'_>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, filter.version).await?;
+    info!(
+        "Measuring documentation coverage at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    let symbols: Vec<SymbolNode> = client
+        .symbols_at_commit(&commit_sha)
+        .await?
+        .into_iter()
+        .filter(|s| filter.scope.is_none_or(|scope| scope.matches(&s.file_path)))
+        .collect();
+    let rows = if filter.level == "module" {
+        let modules = client.modules_at_commit(&commit_sha).await?;
+        doc_coverage_by_module(&symbols, &modules)
+    } else {
+        doc_coverage_by_file(&symbols)
+    };
+    print_rows(&rows, format, print_doc_coverage_table)?;
+
+    let overall = overall_percent(&rows);
+    if let Some(min) = filter.min.filter(|min| overall < f64::from(*min)) {
+        bail!("Documentation coverage {overall:.1}% is below the minimum of {min}%");
+    }
+    Ok(())
+}
+
+fn print_doc_coverage_table(rows: &[DocCoverage]) {
+    if rows.is_empty() {
+        println!("No public symbols found");
+        return;
+    }
+
+    println!(
+        "\n{:<70} {:>10} {:>8} {:>8}",
+        "PATH", "DOCUMENTED", "PUBLIC", "PERCENT"
+    );
+    println!("{}", "-".repeat(99));
+    for r in rows {
+        println!(
+            "{:<70} {:>10} {:>8} {:>7.1}%",
+            truncate_path(&r.path, 70),
+            r.documented,
+            r.public,
+            r.percent
+        );
+    }
+    let documented: usize = rows.iter().map(|r| r.documented).sum();
+    let public: usize = rows.iter().map(|r| r.public).sum();
+    println!(
+        "\nTotal: {documented} of {public} public symbols documented ({:.1}%)",
+        overall_percent(rows)
+    );
+}
@@ -0,0 +1,41 @@
+//! File: The symbols of the files matching a path
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, SymbolResult};
+use tracing::info;
+
+use super::output::{print_rows, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(client: &dyn GraphStore, path: &str, format: OutputFormat) -> Result<()> {
+    info!("Finding symbols in file matching '{}'...", path);
+    let symbols = client.symbols_in_file(path).await?;
+    print_rows(&symbols, format, |symbols| {
+        print_file_symbols_table(symbols, path);
+    })
+}
+
+fn print_file_symbols_table(symbols: &[SymbolResult], path: &str) {
+    if symbols.is_empty() {
+        println!("No symbols found in files matching '{}'", path);
+        return;
+    }
+
+    println!(
+        "\n{:<6} {:<40} {:<15} QUALIFIED NAME",
+        "LINE", "NAME", "KIND"
+    );
+    println!("{}", "-".repeat(100));
+
+    for s in symbols {
+        println!(
+            "{:<6} {:<40} {:<15} {}",
+            s.start_line,
+            truncate_str(&s.name, 40),
+            truncate_str(&s.kind, 15),
+            truncate_str(&s.qualified_name, 60),
+        );
+    }
+
+    println!("\nFound {} symbols", symbols.len());
+}
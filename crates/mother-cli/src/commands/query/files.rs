@@ -0,0 +1,40 @@
+//! Files: The scanned files, optionally matching a pattern
+
+use anyhow::Result;
+use mother_core::graph::{FileResult, GraphStore, Page};
+use tracing::info;
+
+use super::output::{print_rows, truncate_path};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    pattern: Option<&str>,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Listing files...");
+    let files = client.list_files(pattern, page).await?;
+    print_rows(&files, format, print_files_table)
+}
+
+fn print_files_table(files: &[FileResult]) {
+    if files.is_empty() {
+        println!("No files found");
+        return;
+    }
+
+    println!("\n{:<60} {:<15} SYMBOLS", "PATH", "LANGUAGE");
+    println!("{}", "-".repeat(85));
+
+    for f in files {
+        println!(
+            "{:<60} {:<15} {}",
+            truncate_path(&f.path, 60),
+            f.language,
+            f.symbol_count,
+        );
+    }
+
+    println!("\nFound {} files", files.len());
+}
@@ -0,0 +1,43 @@
+//! History: Every stored version of a symbol across commits
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, SymbolVersionResult};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(client: &dyn GraphStore, symbol: &str, format: OutputFormat) -> Result<()> {
+    info!("Finding history of '{}'...", symbol);
+    let versions = client.symbol_history(symbol).await?;
+    print_rows(&versions, format, |versions| {
+        print_history_table(versions, symbol);
+    })
+}
+
+fn print_history_table(versions: &[SymbolVersionResult], symbol: &str) {
+    if versions.is_empty() {
+        println!("No versions found for '{}'", symbol);
+        return;
+    }
+
+    let mut current_identity = "";
+    for v in versions {
+        if v.identity_key != current_identity {
+            current_identity = &v.identity_key;
+            println!("\n{} ({})", v.qualified_name, v.kind);
+            println!("  {}", truncate_path(&v.file_path, 80));
+            println!("  {:<10} {:<12} {:<50}", "LINES", "COMMITS", "SIGNATURE");
+            println!("  {}", "-".repeat(90));
+        }
+        let commits: Vec<&str> = v.commit_shas.iter().map(|c| short_sha(c)).collect();
+        println!(
+            "  {:<10} {:<12} {}",
+            format!("{}-{}", v.start_line, v.end_line),
+            truncate_str(&commits.join(","), 12),
+            truncate_str(v.signature.lines().next().unwrap_or_default(), 60),
+        );
+    }
+
+    println!("\nFound {} versions", versions.len());
+}
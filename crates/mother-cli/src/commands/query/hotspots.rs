@@ -0,0 +1,56 @@
+//! Hotspots: The most connected symbols of a commit
+
+use anyhow::Result;
+use mother_core::graph::complexity::{symbol_complexity, SymbolComplexity};
+use mother_core::graph::{GraphStore, PathScope};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    limit: usize,
+    version: Option<&str>,
+    scope: Option<&PathScope>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!("Ranking symbols at commit {}...", short_sha(&commit_sha));
+
+    let graph = client.export_commit(&commit_sha).await?;
+    let hotspots: Vec<SymbolComplexity> = symbol_complexity(&graph)
+        .into_iter()
+        .filter(|s| s.score > 0)
+        .filter(|s| scope.is_none_or(|scope| scope.matches(&s.file_path)))
+        .take(limit)
+        .collect();
+    print_rows(&hotspots, format, print_hotspots_table)
+}
+
+fn print_hotspots_table(hotspots: &[SymbolComplexity]) {
+    if hotspots.is_empty() {
+        println!("No connected symbols found");
+        return;
+    }
+
+    println!(
+        "\n{:<6} {:<7} {:<8} {:<6} {:<6} {:<40} {:<50}",
+        "SCORE", "FAN-IN", "FAN-OUT", "CALLS", "REFS", "SYMBOL", "LOCATION"
+    );
+    println!("{}", "-".repeat(128));
+    for s in hotspots {
+        println!(
+            "{:<6} {:<7} {:<8} {:<6} {:<6} {:<40} {:<50}",
+            s.score,
+            s.fan_in,
+            s.fan_out,
+            s.outgoing_calls,
+            s.incoming_references,
+            truncate_str(&s.qualified_name, 40),
+            format!("{}:{}", truncate_path(&s.file_path, 44), s.start_line)
+        );
+    }
+    println!("\nTotal: {} symbols", hotspots.len());
+}
@@ -0,0 +1,37 @@
+//! Importers: The files importing a module or file
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, ImporterResult};
+use tracing::info;
+
+use super::output::{print_rows, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(client: &dyn GraphStore, module: &str, format: OutputFormat) -> Result<()> {
+    info!("Finding files importing '{}'...", module);
+    let importers = client.find_importers(module).await?;
+    print_rows(&importers, format, |importers| {
+        print_importers_table(importers, module);
+    })
+}
+
+fn print_importers_table(importers: &[ImporterResult], module: &str) {
+    if importers.is_empty() {
+        println!("No files import '{}'", module);
+        return;
+    }
+
+    println!("\n{:<60} {:<15} IMPORTS", "PATH", "LANGUAGE");
+    println!("{}", "-".repeat(100));
+
+    for i in importers {
+        println!(
+            "{:<60} {:<15} {}",
+            truncate_path(&i.path, 60),
+            i.language,
+            truncate_str(&i.modules.join(", "), 60),
+        );
+    }
+
+    println!("\nFound {} importing files", importers.len());
+}
@@ -0,0 +1,109 @@
+//! Metrics: Line, byte and symbol counts per file of a commit
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use mother_core::graph::GraphStore;
+use serde::Serialize;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+/// Size measures of one file in `query metrics`
+///
+/// Symbol counts by kind are flattened into `symbols_<kind>` fields, zero for
+/// kinds the file has none of, so CSV columns line up.
+#[derive(Debug, Clone, Serialize)]
+struct MetricsRow {
+    path: String,
+    language: String,
+    lines: i64,
+    bytes: i64,
+    symbols: i64,
+    #[serde(flatten)]
+    by_kind: BTreeMap<String, i64>,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    pattern: Option<&str>,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!("Measuring files at commit {}...", short_sha(&commit_sha));
+
+    let files = client.file_metrics(&commit_sha, pattern).await?;
+    let kinds: BTreeMap<String, i64> = files
+        .iter()
+        .flat_map(|f| f.symbol_counts.keys())
+        .map(|kind| (format!("symbols_{kind}"), 0))
+        .collect();
+    let rows: Vec<MetricsRow> = files
+        .into_iter()
+        .map(|f| {
+            let mut by_kind = kinds.clone();
+            for (kind, count) in f.symbol_counts {
+                by_kind.insert(format!("symbols_{kind}"), count);
+            }
+            MetricsRow {
+                path: f.path,
+                language: f.language,
+                lines: f.line_count,
+                bytes: f.byte_size,
+                symbols: f.symbol_count,
+                by_kind,
+            }
+        })
+        .collect();
+    print_rows(&rows, format, print_metrics_table)
+}
+
+fn print_metrics_table(rows: &[MetricsRow]) {
+    if rows.is_empty() {
+        println!("No files found");
+        return;
+    }
+
+    println!(
+        "\n{:<60} {:<12} {:>8} {:>10} {:>8}",
+        "PATH", "LANGUAGE", "LINES", "BYTES", "SYMBOLS"
+    );
+    println!("{}", "-".repeat(102));
+    let mut languages: BTreeMap<&str, [i64; 4]> = BTreeMap::new();
+    for r in rows {
+        println!(
+            "{:<60} {:<12} {:>8} {:>10} {:>8}",
+            truncate_path(&r.path, 60),
+            r.language,
+            r.lines,
+            r.bytes,
+            r.symbols
+        );
+        let totals = languages.entry(&r.language).or_default();
+        for (total, n) in totals.iter_mut().zip([1, r.lines, r.bytes, r.symbols]) {
+            *total += n;
+        }
+    }
+
+    println!(
+        "\n{:<12} {:>8} {:>10} {:>12} {:>8}",
+        "LANGUAGE", "FILES", "LINES", "BYTES", "SYMBOLS"
+    );
+    println!("{}", "-".repeat(54));
+    for (language, [files, lines, bytes, symbols]) in &languages {
+        println!("{language:<12} {files:>8} {lines:>10} {bytes:>12} {symbols:>8}");
+    }
+    let [files, lines, bytes, symbols] = languages.values().fold([0; 4], |mut sum, totals| {
+        for (s, t) in sum.iter_mut().zip(totals) {
+            *s += t;
+        }
+        sum
+    });
+    println!(
+        "{:<12} {files:>8} {lines:>10} {bytes:>12} {symbols:>8}",
+        "total"
+    );
+}
@@ -1,8 +1,333 @@
-//! Query module: Execute queries against Neo4j graph
+//! Query command: Execute queries against the graph store
+//!
+//! Each subcommand has a module whose `run` prints its results. This module
+//! dispatches to them and resolves the versions, scopes and paths they take.
 
-mod run;
-
-pub use run::run;
+mod api_surface;
+mod churn;
+mod contracts;
+mod cycles;
+mod diagnostics;
+mod dir;
+mod dirs;
+mod doc_coverage;
+mod file;
+mod files;
+mod history;
+mod hotspots;
+mod importers;
+mod metrics;
+mod modified;
+mod modules;
+mod output;
+mod paths;
+mod raw;
+mod references;
+mod repos;
+mod saved;
+mod search;
+mod similar;
+mod stats;
+mod symbols;
+mod trends;
+mod type_usage;
+mod untested;
 
 #[cfg(test)]
 mod tests;
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::{GraphStore, PathScope, ReferenceDirection, ScanResult, TypeUsage};
+
+use crate::types::{OutputFormat, QueryCommands};
+
+use diagnostics::DiagnosticFilter;
+use doc_coverage::DocCoverageFilter;
+pub(crate) use output::{print_row, print_rows};
+use saved::run_saved;
+pub use saved::{builtin_queries, SavedQuery};
+
+/// Run the query command
+///
+/// # Errors
+/// Returns an error if the query fails.
+pub async fn run(cmd: QueryCommands, neo4j: &Neo4jConfig, format: OutputFormat) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(cmd, &client, format).await
+}
+
+/// Run the query command against an open graph store
+///
+/// # Errors
+/// Returns an error if the query fails or its results cannot be printed.
+pub async fn run_with_store(
+    cmd: QueryCommands,
+    client: &dyn GraphStore,
+    format: OutputFormat,
+) -> Result<()> {
+    match cmd {
+        QueryCommands::Symbols {
+            pattern,
+            commit,
+            scope,
+            sort,
+            fuzzy,
+            page,
+        } => {
+            let page = page.page(sort.as_deref());
+            let (commit, scope) = (commit.as_deref(), scope.as_deref());
+            symbols::run(client, &pattern, commit, scope, fuzzy, page, format).await
+        }
+        QueryCommands::Search { text, page } => {
+            search::run(client, &text, page.page(None), format).await
+        }
+        QueryCommands::Similar {
+            symbol,
+            version,
+            limit,
+        } => similar::run(client, &symbol, version.as_deref(), limit, format).await,
+        QueryCommands::File { path } => file::run(client, &path, format).await,
+        QueryCommands::RefsTo {
+            symbol,
+            options,
+            sort,
+            page,
+        } => {
+            let page = page.page(sort.as_deref());
+            let direction = ReferenceDirection::To;
+            references::run(client, &symbol, direction, options.grouping(), page, format).await
+        }
+        QueryCommands::RefsFrom {
+            symbol,
+            options,
+            sort,
+            page,
+        } => {
+            let page = page.page(sort.as_deref());
+            let direction = ReferenceDirection::From;
+            references::run(client, &symbol, direction, options.grouping(), page, format).await
+        }
+        QueryCommands::History { symbol } => history::run(client, &symbol, format).await,
+        QueryCommands::Modified { version } => {
+            modified::run(client, version.as_deref(), format).await
+        }
+        QueryCommands::Files {
+            pattern,
+            sort,
+            page,
+        } => {
+            let page = page.page(sort.as_deref());
+            files::run(client, pattern.as_deref(), page, format).await
+        }
+        QueryCommands::Metrics { pattern, version } => {
+            metrics::run(client, pattern.as_deref(), version.as_deref(), format).await
+        }
+        cmd @ (QueryCommands::Dir { .. }
+        | QueryCommands::Dirs { .. }
+        | QueryCommands::Modules { .. }
+        | QueryCommands::Contracts { .. }
+        | QueryCommands::ApiSurface { .. }
+        | QueryCommands::DocCoverage { .. }) => run_structure(cmd, client, format).await,
+        cmd @ (QueryCommands::Hotspots { .. }
+        | QueryCommands::Cycles { .. }
+        | QueryCommands::Path { .. }
+        | QueryCommands::Diagnostics { .. }
+        | QueryCommands::Takes { .. }
+        | QueryCommands::Returns { .. }
+        | QueryCommands::Untested { .. }) => run_analysis(cmd, client, format).await,
+        QueryCommands::Importers { module } => importers::run(client, &module, format).await,
+        QueryCommands::Repos { path } => repos::run(client, path.as_deref(), format).await,
+        QueryCommands::Stats => stats::run(client, format).await,
+        QueryCommands::Trends { last, branch } => {
+            trends::run(client, last, branch.as_deref(), format).await
+        }
+        QueryCommands::Churn {
+            last,
+            branch,
+            level,
+            limit,
+        } => churn::run(client, last, branch.as_deref(), &level, limit, format).await,
+        QueryCommands::Raw { query, params } => raw::run(client, &query, params, format).await,
+        QueryCommands::Saved { name, args, custom } => {
+            run_saved(client, name.as_deref(), args, &custom, format).await
+        }
+    }
+}
+
+/// Run one of the queries over the structure of a commit: directories,
+/// modules, API contracts, API surface and documentation coverage
+async fn run_structure(
+    cmd: QueryCommands,
+    client: &dyn GraphStore,
+    format: OutputFormat,
+) -> Result<()> {
+    match cmd {
+        QueryCommands::Dir { path, version } => {
+            dir::run(client, &path, version.as_deref(), format).await
+        }
+        QueryCommands::Dirs { path, version } => {
+            dirs::run(client, path.as_deref(), version.as_deref(), format).await
+        }
+        QueryCommands::Modules { prefix, version } => {
+            modules::run(client, prefix.as_deref(), version.as_deref(), format).await
+        }
+        QueryCommands::Contracts { name, version } => {
+            contracts::run(client, name.as_deref(), version.as_deref(), format).await
+        }
+        QueryCommands::ApiSurface { path, version } => {
+            api_surface::run(client, &path, version.as_deref(), format).await
+        }
+        QueryCommands::DocCoverage {
+            level,
+            version,
+            scope,
+            min,
+        } => {
+            let scope = scope.as_deref().map(resolve_scope).transpose()?;
+            let filter = DocCoverageFilter {
+                level: &level,
+                version: version.as_deref(),
+                scope: scope.as_ref(),
+                min,
+            };
+            doc_coverage::run(client, filter, format).await
+        }
+        _ => unreachable!("not a structure query: {cmd:?}"),
+    }
+}
+
+/// Run one of the analysis queries over the code of a commit: hotspots,
+/// cycles, paths, diagnostics, functions by type and untested functions
+async fn run_analysis(
+    cmd: QueryCommands,
+    client: &dyn GraphStore,
+    format: OutputFormat,
+) -> Result<()> {
+    match cmd {
+        QueryCommands::Hotspots {
+            limit,
+            version,
+            scope,
+        } => {
+            let scope = scope.as_deref().map(resolve_scope).transpose()?;
+            hotspots::run(client, limit, version.as_deref(), scope.as_ref(), format).await
+        }
+        QueryCommands::Cycles {
+            level,
+            version,
+            scope,
+        } => {
+            let scope = scope.as_deref().map(resolve_scope).transpose()?;
+            cycles::run(client, &level, version.as_deref(), scope.as_ref(), format).await
+        }
+        QueryCommands::Path {
+            from,
+            to,
+            limit,
+            version,
+        } => paths::run(client, &from, &to, limit, version.as_deref(), format).await,
+        QueryCommands::Diagnostics {
+            version,
+            severity,
+            scope,
+        } => {
+            let scope = scope.as_deref().map(resolve_scope).transpose()?;
+            let filter = DiagnosticFilter {
+                version: version.as_deref(),
+                severity: severity.as_deref(),
+                scope: scope.as_ref(),
+            };
+            diagnostics::run(client, filter, format).await
+        }
+        QueryCommands::Takes {
+            type_name,
+            version,
+            scope,
+        } => {
+            let usage = TypeUsage::Parameter;
+            type_usage::run(client, &type_name, usage, version, scope, format).await
+        }
+        QueryCommands::Returns {
+            type_name,
+            version,
+            scope,
+        } => {
+            let usage = TypeUsage::Return;
+            type_usage::run(client, &type_name, usage, version, scope, format).await
+        }
+        QueryCommands::Untested { version, scope } => {
+            let scope = scope.as_deref().map(resolve_scope).transpose()?;
+            untested::run(client, version.as_deref(), scope.as_ref(), format).await
+        }
+        _ => unreachable!("not an analysis query: {cmd:?}"),
+    }
+}
+
+/// Build the scope of `query symbols --in`
+///
+/// Scanned file paths are stored absolute, so a relative path or glob is
+/// taken relative to the current directory.
+fn resolve_scope(scope: &str) -> Result<PathScope> {
+    let scope = if Path::new(scope).is_absolute() {
+        scope.to_string()
+    } else {
+        let cwd = std::env::current_dir()?;
+        let cwd = cwd.canonicalize().unwrap_or(cwd);
+        let relative = scope.strip_prefix("./").unwrap_or(scope);
+        format!(
+            "{}/{}",
+            cwd.display().to_string().trim_end_matches('/'),
+            relative
+        )
+    };
+    PathScope::new(&scope).with_context(|| format!("Invalid --in pattern: {scope}"))
+}
+
+/// Resolve a version tag or SHA prefix, or the latest scan, to a commit
+async fn resolve_version(client: &dyn GraphStore, version: Option<&str>) -> Result<String> {
+    client
+        .resolve_commit(version)
+        .await?
+        .with_context(|| match version {
+            Some(v) => format!("No scan found for version '{v}'"),
+            None => "No scans found".to_string(),
+        })
+}
+
+/// A path as stored by scans: absolute, canonical when it exists
+fn absolute_path(path: &str) -> Result<String> {
+    let path = Path::new(path);
+    let absolute = match path.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(_) if path.is_absolute() => path.to_path_buf(),
+        Err(_) => std::env::current_dir()?.join(path),
+    };
+    let absolute = absolute.display().to_string();
+    match absolute.trim_end_matches('/') {
+        "" => Ok(absolute),
+        trimmed => Ok(trimmed.to_string()),
+    }
+}
+
+/// The `limit` most recently scanned commits of a branch, oldest first
+///
+/// # Errors
+/// Returns an error if the branch has no scans.
+async fn oldest_scans_first(
+    client: &dyn GraphStore,
+    branch: Option<&str>,
+    limit: usize,
+) -> Result<Vec<ScanResult>> {
+    let mut scans = client.recent_scans(branch, limit).await?;
+    if scans.is_empty() {
+        match branch {
+            Some(b) => bail!("No scans found for branch '{b}'"),
+            None => bail!("No scans found"),
+        }
+    }
+    scans.reverse();
+    Ok(scans)
+}
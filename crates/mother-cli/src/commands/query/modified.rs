@@ -0,0 +1,49 @@
+//! Modified: The symbols a commit changed
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, SymbolResult};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!("Finding symbols modified by {}...", short_sha(&commit_sha));
+    let symbols = client.modified_symbols(&commit_sha).await?;
+    print_rows(&symbols, format, |symbols| {
+        print_modified_table(symbols, &commit_sha);
+    })
+}
+
+fn print_modified_table(symbols: &[SymbolResult], commit_sha: &str) {
+    if symbols.is_empty() {
+        println!("Commit {} modified no symbols", short_sha(commit_sha));
+        return;
+    }
+
+    println!("\n{:<40} {:<15} {:<50} LINES", "NAME", "KIND", "FILE");
+    println!("{}", "-".repeat(110));
+
+    for s in symbols {
+        println!(
+            "{:<40} {:<15} {:<50} {}-{}",
+            truncate_str(&s.name, 40),
+            truncate_str(&s.kind, 15),
+            truncate_path(&s.file_path, 50),
+            s.start_line,
+            s.end_line
+        );
+    }
+
+    println!(
+        "\nCommit {} modified {} symbols",
+        short_sha(commit_sha),
+        symbols.len()
+    );
+}
@@ -0,0 +1,57 @@
+//! Modules: Symbol counts per module, rolled up over submodules
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use mother_core::graph::modules::{summarize as summarize_modules, ModuleSummary};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    prefix: Option<&str>,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!(
+        "Summarizing modules at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    let modules = client.modules_at_commit(&commit_sha).await?;
+    let modified = client.modified_symbols(&commit_sha).await?;
+    let modified: HashSet<&str> = modified.iter().map(|s| s.id.as_str()).collect();
+    let summaries = summarize_modules(&modules, &modified, prefix);
+    print_rows(&summaries, format, |summaries| {
+        print_modules_table(summaries, &commit_sha);
+    })
+}
+
+fn print_modules_table(summaries: &[ModuleSummary], commit_sha: &str) {
+    if summaries.is_empty() {
+        println!("No modules found at commit {}", short_sha(commit_sha));
+        return;
+    }
+
+    println!(
+        "\n{:<60} {:>10} {:>8} {:>8} {:>8}",
+        "MODULE", "SUBMODULES", "SYMBOLS", "TOTAL", "MODIFIED"
+    );
+    println!("{}", "-".repeat(98));
+
+    for s in summaries {
+        println!(
+            "{:<60} {:>10} {:>8} {:>8} {:>8}",
+            truncate_str(&s.path, 60),
+            s.submodules,
+            s.symbols,
+            s.total_symbols,
+            s.modified
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Query output: Print query results as a table, JSON or CSV
+//!
+//! Results are serialized with serde for JSON and CSV, so the field names of
+//! the result types are the keys and column headers. Table output stays
+//! hand-formatted per query, with the truncation helpers below.
+
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::types::OutputFormat;
+
+/// Print query result rows in the requested format
+///
+/// `table` prints the rows for [`OutputFormat::Table`].
+///
+/// # Errors
+/// Returns an error if the rows cannot be serialized or written.
+pub(crate) fn print_rows<T: Serialize>(
+    rows: &[T],
+    format: OutputFormat,
+    table: impl FnOnce(&[T]),
+) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Table => {
+            table(rows);
+            return Ok(());
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, rows)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Csv => write_csv(rows, &mut out)?,
+    }
+    Ok(())
+}
+
+/// Print a single query result in the requested format
+///
+/// JSON prints the object itself rather than a one-element array.
+///
+/// # Errors
+/// Returns an error if the result cannot be serialized or written.
+pub(crate) fn print_row<T: Serialize>(
+    row: &T,
+    format: OutputFormat,
+    table: impl FnOnce(&T),
+) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Table => {
+            table(row);
+            return Ok(());
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, row)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Csv => write_csv(std::slice::from_ref(row), &mut out)?,
+    }
+    Ok(())
+}
+
+/// Write rows as CSV with a header row taken from the first row's fields
+///
+/// Lists are joined with `;` so each record stays on one line. No rows
+/// prints nothing, not even a header.
+///
+/// # Errors
+/// Returns an error if a row is not a struct or writing fails.
+pub(crate) fn write_csv<T: Serialize>(rows: &[T], out: &mut impl Write) -> Result<()> {
+    let mut header_written = false;
+    for row in rows {
+        let Value::Object(fields) = serde_json::to_value(row)? else {
+            anyhow::bail!("CSV rows must serialize to objects");
+        };
+        if !header_written {
+            let header: Vec<String> = fields.keys().map(|k| csv_field(k)).collect();
+            writeln!(out, "{}", header.join(","))?;
+            header_written = true;
+        }
+        let values: Vec<String> = fields.values().map(|v| csv_field(&csv_value(v))).collect();
+        writeln!(out, "{}", values.join(","))?;
+    }
+    Ok(())
+}
+
+/// Plain text of a JSON value for a CSV cell
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(csv_value).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a CSV cell if it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// `s` cut to `max_len` bytes for a table cell, ending in `...` if cut
+pub(super) fn truncate_str(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
+        s.to_string()
+    } else {
+        format!("{}...", &s[..max_len - 3])
+    }
+}
+
+/// The first 8 characters of a commit SHA
+pub(super) fn short_sha(sha: &str) -> &str {
+    sha.get(..8).unwrap_or(sha)
+}
+
+/// `path` cut to `max_len` bytes for a table cell, keeping its end
+pub(super) fn truncate_path(path: &str, max_len: usize) -> String {
+    if path.len() <= max_len {
+        path.to_string()
+    } else {
+        // Show the end of the path (more useful)
+        format!("...{}", &path[path.len() - max_len + 3..])
+    }
+}
@@ -0,0 +1,56 @@
+//! Paths: The shortest reference and call chains between two symbols
+
+use anyhow::Result;
+use mother_core::graph::paths::{shortest_paths, SymbolPath};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    from: &str,
+    to: &str,
+    limit: usize,
+    version: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!(
+        "Finding paths from {} to {} at commit {}...",
+        from,
+        to,
+        short_sha(&commit_sha)
+    );
+
+    let graph = client.export_commit(&commit_sha).await?;
+    let paths = shortest_paths(&graph, from, to, limit);
+    print_rows(&paths, format, |paths| print_path_table(paths, from, to))
+}
+
+fn print_path_table(paths: &[SymbolPath], from: &str, to: &str) {
+    if paths.is_empty() {
+        println!("No path found from '{}' to '{}'", from, to);
+        return;
+    }
+
+    for (i, path) in paths.iter().enumerate() {
+        println!("\nPath {} ({} hops):", i + 1, path.hops.len() - 1);
+        for hop in &path.hops {
+            let via = match (&hop.via, hop.line) {
+                (Some(via), Some(line)) => format!("-> {via} at line {line}"),
+                _ => "start".to_string(),
+            };
+            println!(
+                "  {:<26} {:<40} {}:{}",
+                via,
+                truncate_str(&hop.qualified_name, 40),
+                truncate_path(&hop.file_path, 50),
+                hop.start_line
+            );
+        }
+    }
+    println!("\nTotal: {} paths", paths.len());
+}
@@ -0,0 +1,37 @@
+//! Raw: A query in the store's own language
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, ParamValue, QueryParams};
+use serde::Serialize;
+use tracing::info;
+
+use super::output::print_row;
+use crate::types::OutputFormat;
+
+/// Result of a raw query
+#[derive(Debug, Clone, Serialize)]
+struct RawResult {
+    rows: usize,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    query: &str,
+    assignments: Vec<(String, ParamValue)>,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Executing raw query...");
+    let mut params = QueryParams::new();
+    for (name, value) in assignments {
+        params.insert(&name, value).map_err(anyhow::Error::msg)?;
+    }
+    let result = RawResult {
+        rows: client.execute_raw(query, &params).await?,
+    };
+    print_row(&result, format, |result| {
+        println!(
+            "Query executed successfully. {} rows returned.",
+            result.rows
+        );
+    })
+}
@@ -0,0 +1,159 @@
+//! References: What refers to a symbol and what it refers to, one row each
+//! or grouped
+
+use anyhow::Result;
+use mother_core::graph::{
+    GraphStore, Page, ReferenceDirection, ReferenceGroup, ReferenceGrouping, ReferenceResult,
+};
+use tracing::info;
+
+use super::output::{print_rows, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+/// List the references to or from a symbol, one row each or grouped
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    symbol: &str,
+    direction: ReferenceDirection,
+    grouping: Option<ReferenceGrouping>,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    match (grouping, direction) {
+        (Some(grouping), _) => {
+            run_reference_groups(client, symbol, direction, grouping, page, format).await
+        }
+        (None, ReferenceDirection::To) => run_refs_to(client, symbol, page, format).await,
+        (None, ReferenceDirection::From) => run_refs_from(client, symbol, page, format).await,
+    }
+}
+
+async fn run_refs_to(
+    client: &dyn GraphStore,
+    symbol: &str,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Finding references to '{}'...", symbol);
+    let refs = client.find_references_to(symbol, page).await?;
+    print_rows(&refs, format, |refs| print_refs_to_table(refs, symbol))
+}
+
+fn print_refs_to_table(refs: &[ReferenceResult], symbol: &str) {
+    if refs.is_empty() {
+        println!("No references found to '{}'", symbol);
+        return;
+    }
+
+    println!("\n{:<40} {:<50} {:<6}", "FROM SYMBOL", "FILE", "LINE");
+    println!("{}", "-".repeat(100));
+
+    for r in refs {
+        println!(
+            "{:<40} {:<50} {:<6}",
+            truncate_str(&r.source_name, 40),
+            truncate_path(&r.source_file, 50),
+            r.source_line,
+        );
+    }
+
+    println!("\nFound {} references to '{}'", refs.len(), symbol);
+}
+
+async fn run_refs_from(
+    client: &dyn GraphStore,
+    symbol: &str,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Finding references from '{}'...", symbol);
+    let refs = client.find_references_from(symbol, page).await?;
+    print_rows(&refs, format, |refs| print_refs_from_table(refs, symbol))
+}
+
+fn print_refs_from_table(refs: &[ReferenceResult], symbol: &str) {
+    if refs.is_empty() {
+        println!("'{}' doesn't reference any symbols", symbol);
+        return;
+    }
+
+    println!("\n{:<40} {:<50} {:<6}", "TO SYMBOL", "FILE", "LINE");
+    println!("{}", "-".repeat(100));
+
+    for r in refs {
+        println!(
+            "{:<40} {:<50} {:<6}",
+            truncate_str(&r.target_name, 40),
+            truncate_path(&r.target_file, 50),
+            r.target_line,
+        );
+    }
+
+    println!("\n'{}' references {} symbols", symbol, refs.len());
+}
+
+async fn run_reference_groups(
+    client: &dyn GraphStore,
+    symbol: &str,
+    direction: ReferenceDirection,
+    grouping: ReferenceGrouping,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Aggregating references of '{}'...", symbol);
+    let groups = client
+        .find_reference_groups(symbol, direction, grouping, page)
+        .await?;
+    print_rows(&groups, format, |groups| {
+        print_reference_groups_table(groups, symbol, direction);
+    })
+}
+
+fn print_reference_groups_table(
+    groups: &[ReferenceGroup],
+    symbol: &str,
+    direction: ReferenceDirection,
+) {
+    let heading = match direction {
+        ReferenceDirection::To if groups.is_empty() => {
+            println!("No references found to '{}'", symbol);
+            return;
+        }
+        ReferenceDirection::From if groups.is_empty() => {
+            println!("'{}' doesn't reference any symbols", symbol);
+            return;
+        }
+        ReferenceDirection::To => "FROM SYMBOL",
+        ReferenceDirection::From => "TO SYMBOL",
+    };
+
+    println!("\n{:<40} {:<50} {:<20} COUNT", heading, "FILE", "LINES");
+    println!("{}", "-".repeat(118));
+
+    for g in groups {
+        let lines: Vec<String> = g.lines.iter().map(ToString::to_string).collect();
+        println!(
+            "{:<40} {:<50} {:<20} {}",
+            truncate_str(&g.symbols.join(", "), 40),
+            truncate_path(&g.file, 50),
+            truncate_str(&lines.join(","), 20),
+            g.count,
+        );
+    }
+
+    let total: i64 = groups.iter().map(|g| g.count).sum();
+    match direction {
+        ReferenceDirection::To => println!(
+            "\nFound {} references to '{}' in {} rows",
+            total,
+            symbol,
+            groups.len()
+        ),
+        ReferenceDirection::From => println!(
+            "\n'{}' makes {} references in {} rows",
+            symbol,
+            total,
+            groups.len()
+        ),
+    }
+}
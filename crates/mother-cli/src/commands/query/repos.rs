@@ -0,0 +1,45 @@
+//! Repos: The DEPENDS_ON links between scanned repositories
+
+use anyhow::Result;
+use mother_core::graph::model::RepositoryDependency;
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    path: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Listing repository dependencies...");
+    let dependencies: Vec<RepositoryDependency> = client
+        .repository_dependencies()
+        .await?
+        .into_iter()
+        .filter(|d| path.is_none_or(|path| d.from.contains(path) || d.to.contains(path)))
+        .collect();
+    print_rows(&dependencies, format, print_repos_table)
+}
+
+fn print_repos_table(dependencies: &[RepositoryDependency]) {
+    if dependencies.is_empty() {
+        println!("No dependencies between scanned repositories found");
+        return;
+    }
+
+    println!("\n{:<40} {:<40} PACKAGES", "REPOSITORY", "DEPENDS ON");
+    println!("{}", "-".repeat(120));
+
+    for d in dependencies {
+        println!(
+            "{:<40} {:<40} {}",
+            truncate_path(&d.from, 40),
+            truncate_path(&d.to, 40),
+            truncate_str(&d.packages.join(", "), 60),
+        );
+    }
+
+    println!("\nFound {} repository dependencies", dependencies.len());
+}
@@ -0,0 +1,261 @@
+//! Saved queries: Run named query templates instead of pasting Cypher around
+//!
+//! A few templates are built in; teams add their own under
+//! `[queries.<name>]` in `mother.toml`, replacing built-ins of the same name.
+//! Each template holds Cypher for Neo4j and SQL for SQLite, and receives
+//! `--arg key=value` values as bound parameters, never spliced into the text.
+//!
+//! ```toml
+//! [queries.large-files]
+//! description = "Files longer than a line count"
+//! args = ["lines"]
+//! cypher = "MATCH (f:File) WHERE f.line_count > $lines RETURN f.path AS path"
+//! sql = "SELECT path FROM files WHERE line_count > $lines"
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use mother_core::graph::{GraphStore, ParamValue, QueryLanguage, QueryParams, RawRow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::output::print_rows;
+use crate::types::{OutputFormat, QueryCommands};
+
+/// Widest column of the saved query table output
+const MAX_COLUMN_WIDTH: usize = 60;
+
+/// A named query template
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SavedQuery {
+    pub description: String,
+    /// Parameters the query needs, each given as `--arg name=value`
+    pub args: Vec<String>,
+    /// Query for the Neo4j backend
+    pub cypher: Option<String>,
+    /// Query for the SQLite backend
+    pub sql: Option<String>,
+}
+
+impl SavedQuery {
+    /// Query text in `language`, if the template has one
+    #[must_use]
+    pub fn text(&self, language: QueryLanguage) -> Option<&str> {
+        match language {
+            QueryLanguage::Cypher => self.cypher.as_deref(),
+            QueryLanguage::Sql => self.sql.as_deref(),
+        }
+    }
+}
+
+impl QueryCommands {
+    /// Give `query saved` the queries defined in the config file
+    #[must_use]
+    pub fn with_saved_queries(self, queries: &BTreeMap<String, SavedQuery>) -> Self {
+        match self {
+            Self::Saved { name, args, .. } => Self::Saved {
+                name,
+                args,
+                custom: queries.clone(),
+            },
+            cmd => cmd,
+        }
+    }
+}
+
+/// Built-in templates, keyed by name
+#[must_use]
+pub fn builtin_queries() -> BTreeMap<String, SavedQuery> {
+    let query = |description: &str, args: &[&str], cypher: &str, sql: &str| SavedQuery {
+        description: description.to_string(),
+        args: args.iter().map(ToString::to_string).collect(),
+        cypher: Some(cypher.to_string()),
+        sql: Some(sql.to_string()),
+    };
+    BTreeMap::from([
+        (
+            "callers-of".to_string(),
+            query(
+                "Symbols referencing a symbol, by name",
+                &["symbol"],
+                "MATCH (caller:Symbol)-[r:REFERENCES]->(:Symbol {name: $symbol}) \
+                 RETURN DISTINCT caller.name AS caller, caller.kind AS kind, \
+                 caller.file_path AS file, r.line AS line ORDER BY file, line",
+                "SELECT DISTINCT src.name AS caller, src.kind AS kind, src.file_path AS file, \
+                 e.line AS line FROM edges e \
+                 JOIN symbols src ON src.id = e.source_id \
+                 JOIN symbols tgt ON tgt.id = e.target_id \
+                 WHERE e.kind = 'REFERENCES' AND tgt.name = $symbol ORDER BY file, line",
+            ),
+        ),
+        (
+            "files-by-language".to_string(),
+            query(
+                "Files of a language, like rust or python",
+                &["language"],
+                "MATCH (f:File {language: $language}) \
+                 RETURN f.path AS path, max(coalesce(f.line_count, 0)) AS lines ORDER BY path",
+                "SELECT path, MAX(line_count) AS lines FROM files WHERE language = $language \
+                 GROUP BY path ORDER BY path",
+            ),
+        ),
+        (
+            "unused-exports".to_string(),
+            query(
+                "Public symbols no other file references",
+                &[],
+                "MATCH (s:Symbol) WHERE s.visibility IN ['pub', 'public'] \
+                 AND NOT EXISTS { \
+                 MATCH (user:Symbol)-[:REFERENCES]->(t:Symbol \
+                 {name: s.name, kind: s.kind, file_path: s.file_path}) \
+                 WHERE user.file_path <> t.file_path } \
+                 RETURN DISTINCT s.name AS name, s.kind AS kind, s.file_path AS file \
+                 ORDER BY file, name",
+                "SELECT DISTINCT s.name AS name, s.kind AS kind, s.file_path AS file \
+                 FROM symbols s WHERE s.visibility IN ('pub', 'public') \
+                 AND NOT EXISTS (SELECT 1 FROM edges e \
+                 JOIN symbols src ON src.id = e.source_id \
+                 JOIN symbols tgt ON tgt.id = e.target_id \
+                 WHERE e.kind = 'REFERENCES' AND tgt.name = s.name AND tgt.kind = s.kind \
+                 AND tgt.file_path = s.file_path AND src.file_path <> tgt.file_path) \
+                 ORDER BY file, name",
+            ),
+        ),
+    ])
+}
+
+/// A saved query as listed by `query saved` without a name
+#[derive(Debug, Serialize)]
+struct SavedQueryEntry {
+    name: String,
+    args: Vec<String>,
+    description: String,
+}
+
+/// Run the saved query `name` with `args`, or list the saved queries when no
+/// name is given
+pub(super) async fn run_saved(
+    client: &dyn GraphStore,
+    name: Option<&str>,
+    args: Vec<(String, ParamValue)>,
+    custom: &BTreeMap<String, SavedQuery>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut queries = builtin_queries();
+    queries.extend(custom.clone());
+    let Some(name) = name else {
+        let entries: Vec<SavedQueryEntry> = queries
+            .into_iter()
+            .map(|(name, query)| SavedQueryEntry {
+                name,
+                args: query.args,
+                description: query.description,
+            })
+            .collect();
+        return print_rows(&entries, format, print_saved_table);
+    };
+    let Some(query) = queries.get(name) else {
+        let available: Vec<&str> = queries.keys().map(String::as_str).collect();
+        bail!(
+            "Unknown saved query '{name}' (available: {})",
+            available.join(", ")
+        );
+    };
+    let language = client.query_language();
+    let Some(text) = query.text(language) else {
+        bail!("Saved query '{name}' has no {language} query for this backend");
+    };
+
+    let mut params = QueryParams::new();
+    for (arg, value) in args {
+        params.insert(&arg, value).map_err(anyhow::Error::msg)?;
+    }
+    if let Some(missing) = query.args.iter().find(|arg| params.get(arg).is_none()) {
+        bail!("Saved query '{name}' needs --arg {missing}=<value>");
+    }
+    let rows = client.fetch_raw(text, &params).await?;
+    print_rows(&rows, format, print_raw_table)
+}
+
+fn print_saved_table(entries: &[SavedQueryEntry]) {
+    println!("\n{:<24} {:<24} DESCRIPTION", "NAME", "ARGS");
+    println!("{}", "-".repeat(90));
+    for entry in entries {
+        println!(
+            "{:<24} {:<24} {}",
+            entry.name,
+            entry.args.join(", "),
+            entry.description
+        );
+    }
+}
+
+/// Print raw rows as a table with the first row's columns
+fn print_raw_table(rows: &[RawRow]) {
+    let Some(first) = rows.first() else {
+        println!("No rows returned");
+        return;
+    };
+    let columns: Vec<&String> = first.keys().collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| cell_text(row.get(*column)))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.len()])
+                .max()
+                .unwrap_or_default()
+                .min(MAX_COLUMN_WIDTH)
+        })
+        .collect();
+
+    let line = |values: Vec<String>| {
+        let padded: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:<width$}", truncate_cell(value, *width)))
+            .collect();
+        println!("{}", padded.join(" ").trim_end());
+    };
+    println!();
+    line(columns.iter().map(|c| c.to_uppercase()).collect());
+    println!(
+        "{}",
+        "-".repeat(widths.iter().sum::<usize>() + widths.len() - 1)
+    );
+    for row in cells {
+        line(row);
+    }
+    println!("\n{} rows", rows.len());
+}
+
+/// Plain text of a raw query value for a table cell
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Cut a cell to `width` characters, marking the cut with `...`
+fn truncate_cell(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(3)).collect();
+    format!("{kept}...")
+}
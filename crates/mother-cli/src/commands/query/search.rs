@@ -0,0 +1,48 @@
+//! Search: Ranked full-text search of symbols
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, Page, SymbolMatch};
+use tracing::info;
+
+use super::output::{print_rows, truncate_path, truncate_str};
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    text: &str,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Searching symbols for '{}'...", text);
+    let matches = client.search_symbols(text, page).await?;
+    print_rows(&matches, format, |matches| {
+        print_search_table(matches, text)
+    })
+}
+
+pub(super) fn print_search_table(matches: &[SymbolMatch], text: &str) {
+    if matches.is_empty() {
+        println!("No symbols found for '{}'", text);
+        return;
+    }
+
+    println!(
+        "\n{:<8} {:<40} {:<15} {:<50} LINES",
+        "SCORE", "NAME", "KIND", "FILE"
+    );
+    println!("{}", "-".repeat(119));
+
+    for m in matches {
+        println!(
+            "{:<8.2} {:<40} {:<15} {:<50} {}-{}",
+            m.score,
+            truncate_str(&m.name, 40),
+            truncate_str(&m.kind, 15),
+            truncate_path(&m.file_path, 50),
+            m.start_line,
+            m.end_line
+        );
+    }
+
+    println!("\nFound {} symbols", matches.len());
+}
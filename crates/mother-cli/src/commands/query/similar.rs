@@ -0,0 +1,38 @@
+//! Similar: The symbols whose embeddings are closest to a symbol's
+
+use anyhow::{Context, Result};
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::output::{print_rows, short_sha};
+use super::resolve_version;
+use super::search::print_search_table;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    symbol: &str,
+    version: Option<&str>,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    let symbols = client.symbols_at_commit(&commit_sha).await?;
+    let target = symbols
+        .iter()
+        .find(|s| s.qualified_name == symbol)
+        .or_else(|| symbols.iter().find(|s| s.name == symbol))
+        .with_context(|| format!("No symbol '{symbol}' at commit {}", short_sha(&commit_sha)))?;
+    info!(
+        "Finding symbols similar to {} at commit {}...",
+        target.qualified_name,
+        short_sha(&commit_sha)
+    );
+
+    let matches = client
+        .similar_symbols(&target.id, &commit_sha, limit)
+        .await?;
+    print_rows(&matches, format, |matches| {
+        print_search_table(matches, symbol);
+    })
+}
@@ -0,0 +1,27 @@
+//! Stats: Node and relationship counts of the store
+
+use anyhow::Result;
+use mother_core::graph::{GraphStats, GraphStore};
+use tracing::info;
+
+use super::output::print_row;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(client: &dyn GraphStore, format: OutputFormat) -> Result<()> {
+    info!("Getting graph statistics...");
+    let stats = client.stats().await?;
+    print_row(&stats, format, print_stats_table)
+}
+
+fn print_stats_table(stats: &GraphStats) {
+    println!("\n=== Graph Statistics ===\n");
+    println!("Nodes:");
+    println!("  Commits:   {}", stats.commits);
+    println!("  Files:     {}", stats.files);
+    println!("  Symbols:   {}", stats.symbols);
+    println!("  ScanRuns:  {}", stats.scan_runs);
+    println!("\nRelationships:");
+    println!("  REFERENCES: {}", stats.references);
+    println!("  DEFINED_IN: {}", stats.defined_in);
+    println!("  CONTAINS:   {}", stats.contains);
+}
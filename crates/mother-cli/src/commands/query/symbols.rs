@@ -0,0 +1,107 @@
+//! Symbols: Symbols matching a name pattern, or fuzzily
+
+use anyhow::Result;
+use mother_core::graph::fuzzy::fuzzy_matches;
+use mother_core::graph::model::SymbolNode;
+use mother_core::graph::{GraphStore, Page, PathScope, SymbolMatch, SymbolResult};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::search::print_search_table;
+use super::{resolve_scope, resolve_version};
+use crate::types::OutputFormat;
+
+/// Run `query symbols`, by pattern or fuzzy matching
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    pattern: &str,
+    commit: Option<&str>,
+    scope: Option<&str>,
+    fuzzy: bool,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    let scope = scope.map(resolve_scope).transpose()?;
+    if fuzzy {
+        run_fuzzy_symbols(client, pattern, commit, scope.as_ref(), page, format).await
+    } else {
+        run_find_symbols(client, pattern, commit, scope.as_ref(), page, format).await
+    }
+}
+
+async fn run_find_symbols(
+    client: &dyn GraphStore,
+    pattern: &str,
+    commit: Option<&str>,
+    scope: Option<&PathScope>,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Finding symbols matching '{}'...", pattern);
+    let symbols = match commit {
+        Some(sha) => {
+            client
+                .find_symbols_at_commit(pattern, sha, scope, page)
+                .await?
+        }
+        None => client.find_symbols(pattern, scope, page).await?,
+    };
+    print_rows(&symbols, format, |symbols| {
+        print_symbols_table(symbols, pattern);
+    })
+}
+
+fn print_symbols_table(symbols: &[SymbolResult], pattern: &str) {
+    if symbols.is_empty() {
+        println!("No symbols found matching '{}'", pattern);
+        return;
+    }
+
+    println!("\n{:<40} {:<15} {:<50} LINES", "NAME", "KIND", "FILE");
+    println!("{}", "-".repeat(110));
+
+    for s in symbols {
+        let file = truncate_path(&s.file_path, 50);
+        println!(
+            "{:<40} {:<15} {:<50} {}-{}",
+            truncate_str(&s.name, 40),
+            truncate_str(&s.kind, 15),
+            file,
+            s.start_line,
+            s.end_line
+        );
+    }
+
+    println!("\nFound {} symbols", symbols.len());
+}
+
+async fn run_fuzzy_symbols(
+    client: &dyn GraphStore,
+    pattern: &str,
+    commit: Option<&str>,
+    scope: Option<&PathScope>,
+    page: Page,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, commit).await?;
+    info!(
+        "Fuzzy matching symbols to '{}' at commit {}...",
+        pattern,
+        short_sha(&commit_sha)
+    );
+
+    let symbols: Vec<SymbolNode> = client
+        .symbols_at_commit(&commit_sha)
+        .await?
+        .into_iter()
+        .filter(|s| scope.is_none_or(|scope| scope.matches(&s.file_path)))
+        .collect();
+    let matches: Vec<SymbolMatch> = fuzzy_matches(&symbols, pattern)
+        .into_iter()
+        .skip(page.offset)
+        .take(page.limit())
+        .collect();
+    print_rows(&matches, format, |matches| {
+        print_search_table(matches, pattern);
+    })
+}
@@ -1,3 +1,5 @@
 //! Tests for query module
 
+mod tests_output;
 mod tests_run;
+mod tests_saved;
@@ -0,0 +1,166 @@
+//! Tests for query output formats
+
+#![allow(clippy::unwrap_used)]
+
+use serde::Serialize;
+
+use crate::commands::query::output::{truncate_path, truncate_str, write_csv};
+use crate::types::OutputFormat;
+
+#[derive(Serialize)]
+struct Row {
+    name: String,
+    line: u32,
+    modules: Vec<String>,
+    doc: Option<String>,
+}
+
+fn row(name: &str, modules: &[&str]) -> Row {
+    Row {
+        name: name.to_string(),
+        line: 7,
+        modules: modules.iter().map(ToString::to_string).collect(),
+        doc: None,
+    }
+}
+
+fn csv(rows: &[Row]) -> String {
+    let mut out = Vec::new();
+    write_csv(rows, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// ============================================================================
+// OutputFormat
+// ============================================================================
+
+#[test]
+fn test_output_format_parses_case_insensitively() {
+    assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    assert_eq!("CSV".parse::<OutputFormat>(), Ok(OutputFormat::Csv));
+    assert_eq!("Table".parse::<OutputFormat>(), Ok(OutputFormat::Table));
+}
+
+#[test]
+fn test_output_format_rejects_unknown() {
+    let err = "yaml".parse::<OutputFormat>().unwrap_err();
+    assert!(err.contains("yaml"));
+}
+
+#[test]
+fn test_output_format_display_round_trips() {
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        assert_eq!(format.to_string().parse::<OutputFormat>(), Ok(format));
+    }
+}
+
+#[test]
+fn test_output_format_defaults_to_table() {
+    assert_eq!(OutputFormat::default(), OutputFormat::Table);
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+#[test]
+fn test_csv_header_follows_field_order() {
+    let out = csv(&[row("main", &[])]);
+    assert_eq!(out.lines().next(), Some("name,line,modules,doc"));
+}
+
+#[test]
+fn test_csv_one_line_per_row() {
+    let out = csv(&[row("a", &[]), row("b", &[])]);
+    assert_eq!(out, "name,line,modules,doc\na,7,,\nb,7,,\n");
+}
+
+#[test]
+fn test_csv_joins_lists() {
+    let out = csv(&[row("main", &["std::io", "serde"])]);
+    assert_eq!(out.lines().nth(1), Some("main,7,std::io;serde,"));
+}
+
+#[test]
+fn test_csv_quotes_separators_and_quotes() {
+    let out = csv(&[row("fn f(a, b) -> \"x\"", &[])]);
+    assert_eq!(out.lines().nth(1), Some("\"fn f(a, b) -> \"\"x\"\"\",7,,"));
+}
+
+#[test]
+fn test_csv_empty_rows_print_nothing() {
+    assert_eq!(csv(&[]), "");
+}
+
+#[test]
+fn test_csv_rejects_non_object_rows() {
+    let mut out = Vec::new();
+    assert!(write_csv(&[1, 2], &mut out).is_err());
+}
+
+// ============================================================================
+// Truncation
+// ============================================================================
+
+#[test]
+fn test_truncate_str_shorter_than_max() {
+    assert_eq!(truncate_str("hello", 10), "hello");
+}
+
+#[test]
+fn test_truncate_str_equal_to_max() {
+    assert_eq!(truncate_str("hello", 5), "hello");
+}
+
+#[test]
+fn test_truncate_str_longer_than_max() {
+    assert_eq!(truncate_str("hello_world", 8), "hello...");
+}
+
+#[test]
+fn test_truncate_str_with_exactly_max_plus_three() {
+    // Edge case: string length == max_len, no truncation
+    assert_eq!(truncate_str("hello", 5), "hello");
+    // String longer by 1
+    assert_eq!(truncate_str("hello!", 5), "he...");
+}
+
+#[test]
+fn test_truncate_str_empty() {
+    assert_eq!(truncate_str("", 10), "");
+}
+
+#[test]
+fn test_truncate_path_shorter_than_max() {
+    assert_eq!(truncate_path("/usr/local/bin", 20), "/usr/local/bin");
+}
+
+#[test]
+fn test_truncate_path_equal_to_max() {
+    let path = "/usr/bin";
+    assert_eq!(truncate_path(path, path.len()), path);
+}
+
+#[test]
+fn test_truncate_path_longer_than_max() {
+    // Path: /very/long/path/to/some/file.rs (31 chars)
+    // max_len: 20, so we show last 17 chars with "..." prefix
+    let path = "/very/long/path/to/some/file.rs";
+    let result = truncate_path(path, 20);
+    assert_eq!(result, "...h/to/some/file.rs");
+    assert_eq!(result.len(), 20);
+}
+
+#[test]
+fn test_truncate_path_shows_end_of_path() {
+    let path = "/home/user/projects/rust/mother/src/commands/query.rs";
+    let result = truncate_path(path, 30);
+    assert!(result.starts_with("..."));
+    assert!(result.ends_with("query.rs"));
+    assert_eq!(result.len(), 30);
+}
+
+#[test]
+fn test_truncate_path_empty() {
+    assert_eq!(truncate_path("", 10), "");
+}
@@ -3,15 +3,28 @@
 //! These tests verify the behavior of the `mother::commands::query::run` function
 //! and its interaction with Neo4j through the public API.
 
-use crate::commands::query::run;
-use crate::types::QueryCommands;
+#![allow(clippy::unwrap_used)]
+
+use crate::commands::query::{run, run_with_store};
+use crate::types::{OutputFormat, PageArgs, QueryCommands, ReferenceArgs};
+use mother_core::graph::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, EdgeKind, FileMetrics, FunctionSignature,
+    ModuleNode, ParameterNode, RepositoryDependency, RepositoryNode, ScanRun, SymbolKind,
+    SymbolNode,
+};
 use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::{GraphStore, ParamValue, ReferenceGrouping, SortKey, SqliteStore};
 
 /// Test that the run function properly handles connection errors with invalid credentials
 #[tokio::test]
 async fn test_run_with_invalid_neo4j_connection() {
     let cmd = QueryCommands::Stats;
-    let result = run(cmd, "bolt://invalid-host:7687", "neo4j", "invalid_password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://invalid-host:7687", "neo4j", "invalid_password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     // Should fail because the host is invalid
     assert!(
@@ -27,6 +40,11 @@ async fn test_run_with_invalid_neo4j_connection() {
 async fn test_run_symbols_with_empty_pattern() {
     let cmd = QueryCommands::Symbols {
         pattern: String::new(),
+        commit: None,
+        scope: None,
+        sort: None,
+        fuzzy: false,
+        page: PageArgs::default(),
     };
 
     // This test would need a real Neo4j instance
@@ -34,7 +52,12 @@ async fn test_run_symbols_with_empty_pattern() {
     // - Connect successfully
     // - Execute the query
     // - Return all symbols (or handle empty pattern appropriately)
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     // With a real instance, this should succeed
     assert!(result.is_ok());
@@ -48,7 +71,12 @@ async fn test_run_file_command() {
         path: "test.rs".to_string(),
     };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     // Should handle empty results gracefully
     assert!(result.is_ok());
@@ -60,9 +88,17 @@ async fn test_run_file_command() {
 async fn test_run_refs_to_command() {
     let cmd = QueryCommands::RefsTo {
         symbol: "TestSymbol".to_string(),
+        options: ReferenceArgs::default(),
+        sort: None,
+        page: PageArgs::default(),
     };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -73,9 +109,17 @@ async fn test_run_refs_to_command() {
 async fn test_run_refs_from_command() {
     let cmd = QueryCommands::RefsFrom {
         symbol: "TestSymbol".to_string(),
+        options: ReferenceArgs::default(),
+        sort: None,
+        page: PageArgs::default(),
     };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -84,9 +128,18 @@ async fn test_run_refs_from_command() {
 #[tokio::test]
 #[ignore] // Requires Neo4j instance
 async fn test_run_files_without_pattern() {
-    let cmd = QueryCommands::Files { pattern: None };
+    let cmd = QueryCommands::Files {
+        pattern: None,
+        sort: None,
+        page: PageArgs::default(),
+    };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -97,9 +150,34 @@ async fn test_run_files_without_pattern() {
 async fn test_run_files_with_pattern() {
     let cmd = QueryCommands::Files {
         pattern: Some("*.rs".to_string()),
+        sort: None,
+        page: PageArgs::default(),
+    };
+
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
+
+    assert!(result.is_ok());
+}
+
+/// Test that run handles Importers command
+#[tokio::test]
+#[ignore] // Requires Neo4j instance
+async fn test_run_importers() {
+    let cmd = QueryCommands::Importers {
+        module: "crate::graph".to_string(),
     };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -110,7 +188,12 @@ async fn test_run_files_with_pattern() {
 async fn test_run_stats_command() {
     let cmd = QueryCommands::Stats;
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -121,9 +204,15 @@ async fn test_run_stats_command() {
 async fn test_run_raw_command() {
     let cmd = QueryCommands::Raw {
         query: "MATCH (n) RETURN count(n) as total".to_string(),
+        params: vec![],
     };
 
-    let result = run(cmd, "bolt://localhost:7687", "neo4j", "password").await;
+    let result = run(
+        cmd,
+        &Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password"),
+        OutputFormat::Table,
+    )
+    .await;
 
     assert!(result.is_ok());
 }
@@ -166,8 +255,13 @@ fn test_query_commands_variants() {
     // Test Symbols variant
     let symbols_cmd = QueryCommands::Symbols {
         pattern: "test".to_string(),
+        commit: None,
+        scope: None,
+        sort: None,
+        fuzzy: false,
+        page: PageArgs::default(),
     };
-    if let QueryCommands::Symbols { pattern } = symbols_cmd {
+    if let QueryCommands::Symbols { pattern, .. } = symbols_cmd {
         assert_eq!(pattern, "test");
     } else {
         unreachable!("Expected Symbols variant");
@@ -186,8 +280,11 @@ fn test_query_commands_variants() {
     // Test RefsTo variant
     let refs_to_cmd = QueryCommands::RefsTo {
         symbol: "TestFn".to_string(),
+        options: ReferenceArgs::default(),
+        sort: None,
+        page: PageArgs::default(),
     };
-    if let QueryCommands::RefsTo { symbol } = refs_to_cmd {
+    if let QueryCommands::RefsTo { symbol, .. } = refs_to_cmd {
         assert_eq!(symbol, "TestFn");
     } else {
         unreachable!("Expected RefsTo variant");
@@ -196,8 +293,11 @@ fn test_query_commands_variants() {
     // Test RefsFrom variant
     let refs_from_cmd = QueryCommands::RefsFrom {
         symbol: "TestStruct".to_string(),
+        options: ReferenceArgs::default(),
+        sort: None,
+        page: PageArgs::default(),
     };
-    if let QueryCommands::RefsFrom { symbol } = refs_from_cmd {
+    if let QueryCommands::RefsFrom { symbol, .. } = refs_from_cmd {
         assert_eq!(symbol, "TestStruct");
     } else {
         unreachable!("Expected RefsFrom variant");
@@ -206,16 +306,34 @@ fn test_query_commands_variants() {
     // Test Files variant with pattern
     let files_with_pattern = QueryCommands::Files {
         pattern: Some("*.rs".to_string()),
+        sort: Some("symbols".to_string()),
+        page: PageArgs {
+            limit: Some(20),
+            offset: 40,
+        },
     };
-    if let QueryCommands::Files { pattern } = files_with_pattern {
+    if let QueryCommands::Files {
+        pattern,
+        sort,
+        page,
+    } = files_with_pattern
+    {
         assert_eq!(pattern, Some("*.rs".to_string()));
+        let page = page.page(sort.as_deref());
+        assert_eq!(page.limit(), 20);
+        assert_eq!(page.offset, 40);
+        assert_eq!(page.sort, Some(SortKey::Symbols));
     } else {
         unreachable!("Expected Files variant");
     }
 
     // Test Files variant without pattern
-    let files_without_pattern = QueryCommands::Files { pattern: None };
-    if let QueryCommands::Files { pattern } = files_without_pattern {
+    let files_without_pattern = QueryCommands::Files {
+        pattern: None,
+        sort: None,
+        page: PageArgs::default(),
+    };
+    if let QueryCommands::Files { pattern, .. } = files_without_pattern {
         assert!(pattern.is_none());
     } else {
         unreachable!("Expected Files variant");
@@ -228,8 +346,9 @@ fn test_query_commands_variants() {
     // Test Raw variant
     let raw_cmd = QueryCommands::Raw {
         query: "MATCH (n) RETURN n".to_string(),
+        params: vec![],
     };
-    if let QueryCommands::Raw { query } = raw_cmd {
+    if let QueryCommands::Raw { query, .. } = raw_cmd {
         assert_eq!(query, "MATCH (n) RETURN n");
     } else {
         unreachable!("Expected Raw variant");
@@ -241,8 +360,13 @@ fn test_query_commands_variants() {
 fn test_symbols_command_empty_pattern() {
     let cmd = QueryCommands::Symbols {
         pattern: String::new(),
+        commit: None,
+        scope: None,
+        sort: None,
+        fuzzy: false,
+        page: PageArgs::default(),
     };
-    if let QueryCommands::Symbols { pattern } = cmd {
+    if let QueryCommands::Symbols { pattern, .. } = cmd {
         assert_eq!(pattern, "");
     } else {
         unreachable!("Expected Symbols variant");
@@ -262,15 +386,1054 @@ fn test_file_command_empty_path() {
     }
 }
 
+/// Test that the reference flags select the aggregation
+#[test]
+fn test_reference_args_grouping() {
+    let args = |group_by: Option<&str>, unique| ReferenceArgs {
+        group_by: group_by.map(str::to_string),
+        unique,
+    };
+    assert_eq!(args(None, false).grouping(), None);
+    assert_eq!(args(None, true).grouping(), Some(ReferenceGrouping::Unique));
+    assert_eq!(
+        args(Some("symbol"), false).grouping(),
+        Some(ReferenceGrouping::Symbol)
+    );
+    assert_eq!(
+        args(Some("file"), false).grouping(),
+        Some(ReferenceGrouping::File)
+    );
+}
+
 /// Test edge case: empty query in Raw command
 #[test]
 fn test_raw_command_empty_query() {
     let cmd = QueryCommands::Raw {
         query: String::new(),
+        params: vec![],
     };
-    if let QueryCommands::Raw { query } = cmd {
+    if let QueryCommands::Raw { query, .. } = cmd {
         assert_eq!(query, "");
     } else {
         unreachable!("Expected Raw variant");
     }
 }
+
+// ============================================================================
+// SQLite backend
+// ============================================================================
+
+#[tokio::test]
+async fn test_symbols_rejects_invalid_scope() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let cmd = QueryCommands::Symbols {
+        pattern: "main".to_string(),
+        commit: None,
+        scope: Some("src/[z-a].rs".to_string()),
+        sort: None,
+        fuzzy: false,
+        page: PageArgs::default(),
+    };
+    let result = run_with_store(cmd, &store, OutputFormat::Table).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_raw_rejects_writes_to_a_read_only_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    SqliteStore::open(&path).unwrap();
+    let store = SqliteStore::open_read_only(&path).unwrap();
+
+    let raw = |query: &str| QueryCommands::Raw {
+        query: query.to_string(),
+        params: vec![],
+    };
+    let read = run_with_store(raw("SELECT * FROM commits"), &store, OutputFormat::Json);
+    assert!(read.await.is_ok());
+    let write = run_with_store(raw("DELETE FROM commits"), &store, OutputFormat::Json);
+    let error = write.await.unwrap_err();
+    assert!(error.to_string().contains("read-only"), "{error}");
+}
+
+#[tokio::test]
+async fn test_raw_binds_params() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let raw = |params: Vec<(String, ParamValue)>| QueryCommands::Raw {
+        query: "SELECT sha FROM commits WHERE sha = $sha".to_string(),
+        params,
+    };
+    let sha = ("sha".to_string(), ParamValue::String("abc123".to_string()));
+    let bound = run_with_store(raw(vec![sha]), &store, OutputFormat::Json);
+    assert!(bound.await.is_ok());
+    let missing = run_with_store(raw(vec![]), &store, OutputFormat::Json);
+    let error = missing.await.unwrap_err();
+    assert!(error.to_string().contains("Missing parameter"), "{error}");
+}
+
+#[tokio::test]
+async fn test_every_query_runs_against_sqlite() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+
+    let commands = vec![
+        QueryCommands::Symbols {
+            pattern: "main".to_string(),
+            commit: None,
+            scope: None,
+            sort: None,
+            fuzzy: false,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Symbols {
+            pattern: "main".to_string(),
+            commit: Some("abc".to_string()),
+            scope: None,
+            sort: None,
+            fuzzy: false,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Symbols {
+            pattern: "main".to_string(),
+            commit: None,
+            scope: Some("src/**/*.rs".to_string()),
+            sort: None,
+            fuzzy: false,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Symbols {
+            pattern: "mian".to_string(),
+            commit: Some("abc".to_string()),
+            scope: Some("src".to_string()),
+            sort: None,
+            fuzzy: true,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Search {
+            text: "graph store".to_string(),
+            page: PageArgs::default(),
+        },
+        QueryCommands::File {
+            path: "main.rs".to_string(),
+        },
+        QueryCommands::RefsTo {
+            symbol: "main".to_string(),
+            options: ReferenceArgs::default(),
+            sort: None,
+            page: PageArgs::default(),
+        },
+        QueryCommands::RefsFrom {
+            symbol: "main".to_string(),
+            options: ReferenceArgs::default(),
+            sort: None,
+            page: PageArgs::default(),
+        },
+        QueryCommands::RefsTo {
+            symbol: "main".to_string(),
+            options: ReferenceArgs {
+                group_by: Some("file".to_string()),
+                unique: false,
+            },
+            sort: None,
+            page: PageArgs::default(),
+        },
+        QueryCommands::RefsFrom {
+            symbol: "main".to_string(),
+            options: ReferenceArgs {
+                group_by: None,
+                unique: true,
+            },
+            sort: None,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Symbols {
+            pattern: "main".to_string(),
+            commit: None,
+            scope: Some("src/**/*.rs".to_string()),
+            sort: Some("kind".to_string()),
+            fuzzy: false,
+            page: PageArgs {
+                limit: Some(20),
+                offset: 40,
+            },
+        },
+        QueryCommands::RefsTo {
+            symbol: "main".to_string(),
+            options: ReferenceArgs::default(),
+            sort: Some("name".to_string()),
+            page: PageArgs {
+                limit: Some(1),
+                offset: 1,
+            },
+        },
+        QueryCommands::Files {
+            pattern: None,
+            sort: Some("symbols".to_string()),
+            page: PageArgs {
+                limit: Some(5),
+                offset: 0,
+            },
+        },
+        QueryCommands::History {
+            symbol: "crate::main".to_string(),
+        },
+        QueryCommands::Modified { version: None },
+        QueryCommands::Files {
+            pattern: None,
+            sort: None,
+            page: PageArgs::default(),
+        },
+        QueryCommands::Importers {
+            module: "crate::graph".to_string(),
+        },
+        QueryCommands::Stats,
+        QueryCommands::Trends {
+            last: 5,
+            branch: None,
+        },
+        QueryCommands::Churn {
+            last: 5,
+            branch: None,
+            level: "file".to_string(),
+            limit: 20,
+        },
+        QueryCommands::Raw {
+            query: "SELECT sha FROM commits".to_string(),
+            params: vec![],
+        },
+    ];
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        for cmd in commands.clone() {
+            let name = format!("{cmd:?} as {format}");
+            assert!(
+                run_with_store(cmd, &store, format).await.is_ok(),
+                "{name} failed"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_dir_query_reads_summary_of_latest_scan() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let summary = DirectorySummary {
+        path: "/repo/src".to_string(),
+        file_count: 3,
+        ..DirectorySummary::default()
+    };
+    store
+        .create_directory_summaries("abc123", &[summary])
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Dir {
+            path: "/repo/src/".to_string(),
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::Dir {
+        path: "/repo/lib".to_string(),
+        version: Some("abc".to_string()),
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_dirs_query_rolls_up_subdirectories_of_scan_root() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+
+    let missing_root = QueryCommands::Dirs {
+        path: None,
+        version: None,
+    };
+    assert!(run_with_store(missing_root, &store, OutputFormat::Table)
+        .await
+        .is_err());
+
+    let directory = |path: &str, parent: Option<&str>| DirectorySummary {
+        path: path.to_string(),
+        parent: parent.map(str::to_string),
+        file_count: 1,
+        ..DirectorySummary::default()
+    };
+    store
+        .create_directory_summaries(
+            "abc123",
+            &[
+                directory("/repo", None),
+                directory("/repo/src", Some("/repo")),
+                directory("/repo/src/graph", Some("/repo/src")),
+            ],
+        )
+        .await
+        .unwrap();
+
+    for (path, format) in [
+        (None, OutputFormat::Table),
+        (Some("/repo/src"), OutputFormat::Json),
+        (Some("/repo/src/graph"), OutputFormat::Table),
+    ] {
+        let cmd = QueryCommands::Dirs {
+            path: path.map(str::to_string),
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_api_surface_query_lists_public_and_leaked_symbols() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |id: &str, name: &str, kind, visibility: &str, line| SymbolNode {
+        visibility: Some(visibility.to_string()),
+        ..SymbolNode::new(id, name, kind, "/repo/src/lib.rs", line, line)
+    };
+    store
+        .create_symbols_batch(
+            &[
+                symbol("field", "inner", SymbolKind::Field, "pub", 2),
+                symbol("inner", "Inner", SymbolKind::Struct, "private", 5),
+            ],
+            "hash-lib",
+        )
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "field".to_string(),
+            target_id: "inner".to_string(),
+            kind: EdgeKind::References,
+            line: Some(1),
+            column: Some(11),
+        })
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::ApiSurface {
+            path: "/repo/src".to_string(),
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::ApiSurface {
+        path: "/repo/src".to_string(),
+        version: Some("v9".to_string()),
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_hotspots_query_ranks_connected_symbols() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |id: &str, line| {
+        SymbolNode::new(id, id, SymbolKind::Function, "/repo/src/lib.rs", line, line)
+    };
+    store
+        .create_symbols_batch(&[symbol("run", 1), symbol("parse", 5)], "hash-lib")
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "run".to_string(),
+            target_id: "parse".to_string(),
+            kind: EdgeKind::References,
+            line: Some(2),
+            column: Some(4),
+        })
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Hotspots {
+            limit: 20,
+            version: None,
+            scope: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+    let scoped = QueryCommands::Hotspots {
+        limit: 1,
+        version: None,
+        scope: Some("/repo/tests".to_string()),
+    };
+    assert!(run_with_store(scoped, &store, OutputFormat::Table)
+        .await
+        .is_ok());
+
+    let missing = QueryCommands::Hotspots {
+        limit: 20,
+        version: Some("v9".to_string()),
+        scope: None,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_untested_query_lists_public_functions_without_tests() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let symbol = |id: &str, path: &str| SymbolNode {
+        visibility: Some("pub".to_string()),
+        ..SymbolNode::new(id, id, SymbolKind::Function, path, 1, 3)
+    };
+    for (path, hash, ids) in [
+        ("/repo/src/lib.rs", "hash-lib", vec!["parse", "render"]),
+        ("/repo/tests/lib.rs", "hash-test", vec!["test_parse"]),
+    ] {
+        store
+            .create_file_if_new(path, hash, "rust", "abc123")
+            .await
+            .unwrap();
+        let symbols: Vec<SymbolNode> = ids.iter().map(|id| symbol(id, path)).collect();
+        store.create_symbols_batch(&symbols, hash).await.unwrap();
+    }
+    store
+        .create_edge(&Edge {
+            source_id: "test_parse".to_string(),
+            target_id: "parse".to_string(),
+            kind: EdgeKind::Tests,
+            line: None,
+            column: None,
+        })
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Untested {
+            version: None,
+            scope: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+    let scoped = QueryCommands::Untested {
+        version: None,
+        scope: Some("/repo/src".to_string()),
+    };
+    assert!(run_with_store(scoped, &store, OutputFormat::Table)
+        .await
+        .is_ok());
+
+    let missing = QueryCommands::Untested {
+        version: Some("v9".to_string()),
+        scope: None,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_cycles_query_lists_mutually_dependent_files() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let symbol = |id: &str, path: &str| SymbolNode::new(id, id, SymbolKind::Function, path, 1, 3);
+    for (id, path, hash) in [
+        ("run", "/repo/src/a/mod.rs", "hash-a"),
+        ("load", "/repo/src/b/mod.rs", "hash-b"),
+    ] {
+        store
+            .create_file_if_new(path, hash, "rust", "abc123")
+            .await
+            .unwrap();
+        store
+            .create_symbols_batch(&[symbol(id, path)], hash)
+            .await
+            .unwrap();
+    }
+    for (source, target) in [("run", "load"), ("load", "run")] {
+        store
+            .create_edge(&Edge {
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                kind: EdgeKind::Calls,
+                line: Some(2),
+                column: Some(4),
+            })
+            .await
+            .unwrap();
+    }
+
+    for level in ["file", "module"] {
+        for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+            let cmd = QueryCommands::Cycles {
+                level: level.to_string(),
+                version: None,
+                scope: None,
+            };
+            assert!(run_with_store(cmd, &store, format).await.is_ok());
+        }
+    }
+    let scoped = QueryCommands::Cycles {
+        level: "file".to_string(),
+        version: None,
+        scope: Some("/repo/tests".to_string()),
+    };
+    assert!(run_with_store(scoped, &store, OutputFormat::Table)
+        .await
+        .is_ok());
+
+    let missing = QueryCommands::Cycles {
+        level: "file".to_string(),
+        version: Some("v9".to_string()),
+        scope: None,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_path_query_lists_chains_between_symbols() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/server.rs", "hash-server", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |id: &str, line| SymbolNode {
+        qualified_name: format!("server::{id}"),
+        ..SymbolNode::new(
+            id,
+            id,
+            SymbolKind::Function,
+            "/repo/src/server.rs",
+            line,
+            line + 5,
+        )
+    };
+    store
+        .create_symbols_batch(
+            &[symbol("handle", 1), symbol("load", 10), symbol("query", 20)],
+            "hash-server",
+        )
+        .await
+        .unwrap();
+    for (source, target, line) in [("handle", "load", 2), ("load", "query", 11)] {
+        store
+            .create_edge(&Edge {
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                kind: EdgeKind::Calls,
+                line: Some(line),
+                column: Some(4),
+            })
+            .await
+            .unwrap();
+    }
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Path {
+            from: "handle".to_string(),
+            to: "server::query".to_string(),
+            limit: 5,
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+    let unreachable = QueryCommands::Path {
+        from: "query".to_string(),
+        to: "handle".to_string(),
+        limit: 5,
+        version: None,
+    };
+    assert!(run_with_store(unreachable, &store, OutputFormat::Table)
+        .await
+        .is_ok());
+
+    let missing = QueryCommands::Path {
+        from: "handle".to_string(),
+        to: "query".to_string(),
+        limit: 5,
+        version: Some("v9".to_string()),
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_diagnostics_query_filters_by_severity_and_scope() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let diagnostic = DiagnosticNode {
+        severity: "error".to_string(),
+        message: "mismatched types\nexpected `u32`".to_string(),
+        start_line: 3,
+        ..DiagnosticNode::default()
+    };
+    store
+        .create_diagnostics("hash-lib", &[diagnostic])
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Diagnostics {
+            version: None,
+            severity: Some("error".to_string()),
+            scope: Some("/repo/src".to_string()),
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::Diagnostics {
+        version: Some("v9".to_string()),
+        severity: None,
+        scope: None,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_takes_and_returns_queries_list_typed_functions() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let connect = SymbolNode::new(
+        "connect",
+        "connect",
+        SymbolKind::Function,
+        "/repo/src/lib.rs",
+        3,
+        9,
+    );
+    store
+        .create_symbols_batch(&[connect], "hash-lib")
+        .await
+        .unwrap();
+    let signature = FunctionSignature {
+        symbol_id: "connect".to_string(),
+        parameters: vec![ParameterNode {
+            name: "config".to_string(),
+            type_name: Some("&Config".to_string()),
+            position: 0,
+        }],
+        return_type: Some("Result<Client>".to_string()),
+    };
+    store
+        .create_function_signatures(&[signature])
+        .await
+        .unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let takes = QueryCommands::Takes {
+            type_name: "&Config".to_string(),
+            version: None,
+            scope: Some("/repo/src".to_string()),
+        };
+        assert!(run_with_store(takes, &store, format).await.is_ok());
+        let returns = QueryCommands::Returns {
+            type_name: "Client".to_string(),
+            version: None,
+            scope: None,
+        };
+        assert!(run_with_store(returns, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::Takes {
+        type_name: "&Config".to_string(),
+        version: Some("v9".to_string()),
+        scope: None,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_metrics_query_lists_file_sizes() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    for (path, hash, language) in [
+        ("/repo/src/lib.rs", "hash-lib", "rust"),
+        ("/repo/tools/gen.py", "hash-gen", "python"),
+    ] {
+        store
+            .create_file_if_new(path, hash, language, "abc123")
+            .await
+            .unwrap();
+    }
+    let mut metrics = FileMetrics::of_content("pub fn run() {}\n");
+    metrics.symbol_counts.insert("function".to_string(), 1);
+    store.set_file_metrics("hash-lib", &metrics).await.unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Metrics {
+            pattern: None,
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+    let filtered = QueryCommands::Metrics {
+        pattern: Some("src/".to_string()),
+        version: None,
+    };
+    assert!(run_with_store(filtered, &store, OutputFormat::Table)
+        .await
+        .is_ok());
+
+    let missing = QueryCommands::Metrics {
+        pattern: None,
+        version: Some("v9".to_string()),
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_trends_query_compares_scans_of_a_branch() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    for (sha, path) in [
+        ("abc123", "/repo/src/lib.rs"),
+        ("def456", "/repo/src/main.rs"),
+    ] {
+        store
+            .create_scan_run(&ScanRun::new("/repo").with_commit(sha).with_branch("main"))
+            .await
+            .unwrap();
+        store
+            .create_file_if_new(path, &format!("hash-{sha}"), "rust", sha)
+            .await
+            .unwrap();
+    }
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = QueryCommands::Trends {
+            last: 2,
+            branch: Some("main".to_string()),
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::Trends {
+        last: 2,
+        branch: Some("release".to_string()),
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_churn_query_ranks_symbols_and_files_of_a_branch() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    for (sha, lines) in [("abc123", 3), ("def456", 5)] {
+        store
+            .create_scan_run(&ScanRun::new("/repo").with_commit(sha).with_branch("main"))
+            .await
+            .unwrap();
+        let hash = format!("hash-{sha}");
+        store
+            .create_file_if_new("/repo/src/lib.rs", &hash, "rust", sha)
+            .await
+            .unwrap();
+        let parse = SymbolNode::new(
+            format!("parse-{sha}"),
+            "parse",
+            SymbolKind::Function,
+            "/repo/src/lib.rs",
+            1,
+            lines,
+        );
+        store.create_symbols_batch(&[parse], &hash).await.unwrap();
+    }
+    store
+        .create_modified_edges("def456", &["parse-def456".to_string()])
+        .await
+        .unwrap();
+
+    for level in ["symbol", "file"] {
+        for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+            let cmd = QueryCommands::Churn {
+                last: 1,
+                branch: Some("main".to_string()),
+                level: level.to_string(),
+                limit: 20,
+            };
+            assert!(run_with_store(cmd, &store, format).await.is_ok());
+        }
+    }
+
+    let missing = QueryCommands::Churn {
+        last: 1,
+        branch: Some("release".to_string()),
+        level: "symbol".to_string(),
+        limit: 20,
+    };
+    assert!(run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_similar_query_finds_symbol_by_name() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |name: &str, line, embedding: Vec<f32>| SymbolNode {
+        qualified_name: format!("lib::{name}"),
+        embedding: Some(embedding),
+        ..SymbolNode::new(
+            name,
+            name,
+            SymbolKind::Function,
+            "/repo/src/lib.rs",
+            line,
+            line,
+        )
+    };
+    store
+        .create_symbols_batch(
+            &[
+                symbol("load", 1, vec![1.0, 0.0]),
+                symbol("read", 5, vec![0.8, 0.2]),
+            ],
+            "hash-lib",
+        )
+        .await
+        .unwrap();
+
+    for (name, format) in [
+        ("load", OutputFormat::Table),
+        ("lib::load", OutputFormat::Json),
+    ] {
+        let cmd = QueryCommands::Similar {
+            symbol: name.to_string(),
+            version: None,
+            limit: 5,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+
+    let missing = QueryCommands::Similar {
+        symbol: "save".to_string(),
+        version: Some("abc".to_string()),
+        limit: 5,
+    };
+    let error = run_with_store(missing, &store, OutputFormat::Table)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("No symbol 'save'"), "{error}");
+}
+
+#[tokio::test]
+async fn test_modules_query_rolls_up_module_counts() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let module = |path: &str, parent: Option<&str>| ModuleNode {
+        path: path.to_string(),
+        name: path.rsplit("::").next().unwrap().to_string(),
+        parent: parent.map(str::to_string),
+        language: "rust".to_string(),
+        symbol_ids: Vec::new(),
+    };
+    store
+        .create_modules(
+            "abc123",
+            &[module("crate", None), module("crate::graph", Some("crate"))],
+        )
+        .await
+        .unwrap();
+
+    for (prefix, format) in [
+        (None, OutputFormat::Table),
+        (Some("crate::graph"), OutputFormat::Json),
+        (Some("crate::missing"), OutputFormat::Table),
+    ] {
+        let cmd = QueryCommands::Modules {
+            prefix: prefix.map(str::to_string),
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_contracts_query_lists_handlers() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_contracts(
+            "abc123",
+            &[ContractNode {
+                kind: "endpoint".to_string(),
+                name: "GET /orders".to_string(),
+                identifier: "listOrders".to_string(),
+                file_path: "/repo/openapi.yaml".to_string(),
+                line: 4,
+                handler_ids: Vec::new(),
+            }],
+        )
+        .await
+        .unwrap();
+
+    for (name, format) in [
+        (None, OutputFormat::Table),
+        (Some("/ORDERS"), OutputFormat::Json),
+        (Some("/users"), OutputFormat::Table),
+    ] {
+        let cmd = QueryCommands::Contracts {
+            name: name.map(str::to_string),
+            version: None,
+        };
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_doc_coverage_query_fails_below_minimum() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |id: &str, doc: Option<&str>| SymbolNode {
+        visibility: Some("pub".to_string()),
+        doc_comment: doc.map(str::to_string),
+        ..SymbolNode::new(id, id, SymbolKind::Function, "/repo/src/lib.rs", 1, 3)
+    };
+    store
+        .create_symbols_batch(
+            &[symbol("parse", Some("Parse input")), symbol("render", None)],
+            "hash-lib",
+        )
+        .await
+        .unwrap();
+    let query = |level: &str, min: Option<u8>| QueryCommands::DocCoverage {
+        level: level.to_string(),
+        version: None,
+        scope: None,
+        min,
+    };
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        assert!(run_with_store(query("file", None), &store, format)
+            .await
+            .is_ok());
+    }
+    assert!(
+        run_with_store(query("module", Some(0)), &store, OutputFormat::Table)
+            .await
+            .is_ok()
+    );
+    assert!(
+        run_with_store(query("file", Some(50)), &store, OutputFormat::Table)
+            .await
+            .is_ok()
+    );
+    assert!(
+        run_with_store(query("file", Some(80)), &store, OutputFormat::Table)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_repos_query_filters_by_path() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    for path in ["/src/app", "/src/lib"] {
+        store
+            .upsert_repository(&RepositoryNode {
+                path: path.to_string(),
+                ..RepositoryNode::default()
+            })
+            .await
+            .unwrap();
+    }
+    store
+        .set_repository_dependencies(&[RepositoryDependency {
+            from: "/src/app".to_string(),
+            to: "/src/lib".to_string(),
+            packages: vec!["cargo:lib".to_string()],
+        }])
+        .await
+        .unwrap();
+
+    for path in [None, Some("lib".to_string()), Some("other".to_string())] {
+        let cmd = QueryCommands::Repos { path };
+        let result = run_with_store(cmd, &store, OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,159 @@
+//! Tests for saved query templates
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use mother_core::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, ParamValue, QueryLanguage, QueryParams, SqliteStore};
+
+use crate::commands::query::{builtin_queries, run_with_store, SavedQuery};
+use crate::types::{OutputFormat, QueryCommands};
+
+async fn seeded_store() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    for (path, hash) in [
+        ("/repo/src/lib.rs", "hash-lib"),
+        ("/repo/src/main.rs", "hash-main"),
+    ] {
+        store
+            .create_file_if_new(path, hash, "rust", "abc123")
+            .await
+            .unwrap();
+    }
+    let symbol = |id: &str, name: &str, file_path: &str| SymbolNode {
+        visibility: Some("pub".to_string()),
+        ..SymbolNode::new(id, name, SymbolKind::Function, file_path, 1, 3)
+    };
+    store
+        .create_symbols_batch(
+            &[
+                symbol("parse", "parse", "/repo/src/lib.rs"),
+                symbol("unused", "unused", "/repo/src/lib.rs"),
+            ],
+            "hash-lib",
+        )
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(&[symbol("main", "main", "/repo/src/main.rs")], "hash-main")
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "main".to_string(),
+            target_id: "parse".to_string(),
+            kind: EdgeKind::References,
+            line: Some(2),
+            column: Some(4),
+        })
+        .await
+        .unwrap();
+    store
+}
+
+fn saved(name: Option<&str>, args: &[(&str, &str)]) -> QueryCommands {
+    QueryCommands::Saved {
+        name: name.map(ToString::to_string),
+        args: args
+            .iter()
+            .map(|(k, v)| (k.to_string(), ParamValue::from_text(v)))
+            .collect(),
+        custom: BTreeMap::new(),
+    }
+}
+
+async fn builtin_rows(store: &SqliteStore, name: &str, args: &[(&str, &str)]) -> Vec<String> {
+    let queries = builtin_queries();
+    let text = queries[name].text(QueryLanguage::Sql).unwrap();
+    let mut params = QueryParams::new();
+    for (arg, value) in args {
+        params.insert(arg, ParamValue::from_text(value)).unwrap();
+    }
+    let rows = store.fetch_raw(text, &params).await.unwrap();
+    rows.iter()
+        .map(|row| row.values().next().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_builtin_queries_answer_against_sqlite() {
+    let store = seeded_store().await;
+
+    let callers = builtin_rows(&store, "callers-of", &[("symbol", "parse")]).await;
+    assert_eq!(callers, ["\"main\""]);
+    let files = builtin_rows(&store, "files-by-language", &[("language", "rust")]).await;
+    assert_eq!(files, ["\"/repo/src/lib.rs\"", "\"/repo/src/main.rs\""]);
+    let unused = builtin_rows(&store, "unused-exports", &[]).await;
+    assert_eq!(unused, ["\"unused\"", "\"main\""]);
+}
+
+#[test]
+fn test_builtin_queries_have_both_languages() {
+    for (name, query) in builtin_queries() {
+        assert!(query.text(QueryLanguage::Cypher).is_some(), "{name}");
+        assert!(query.text(QueryLanguage::Sql).is_some(), "{name}");
+        assert!(!query.description.is_empty(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_saved_runs_and_lists_queries() {
+    let store = seeded_store().await;
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        let cmd = saved(Some("callers-of"), &[("symbol", "parse")]);
+        assert!(run_with_store(cmd, &store, format).await.is_ok());
+        assert!(run_with_store(saved(None, &[]), &store, format)
+            .await
+            .is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_saved_rejects_unknown_names_and_missing_args() {
+    let store = seeded_store().await;
+
+    let unknown = run_with_store(saved(Some("nope"), &[]), &store, OutputFormat::Json);
+    let error = unknown.await.unwrap_err().to_string();
+    assert!(error.contains("callers-of"), "{error}");
+
+    let missing = run_with_store(saved(Some("callers-of"), &[]), &store, OutputFormat::Json);
+    let error = missing.await.unwrap_err().to_string();
+    assert!(error.contains("--arg symbol"), "{error}");
+}
+
+#[tokio::test]
+async fn test_config_queries_replace_builtins() {
+    let store = seeded_store().await;
+    let custom = BTreeMap::from([
+        (
+            "callers-of".to_string(),
+            SavedQuery {
+                sql: Some("SELECT name FROM symbols WHERE name = $name".to_string()),
+                args: vec!["name".to_string()],
+                ..SavedQuery::default()
+            },
+        ),
+        (
+            "graph-only".to_string(),
+            SavedQuery {
+                cypher: Some("MATCH (n) RETURN n".to_string()),
+                ..SavedQuery::default()
+            },
+        ),
+    ]);
+    let cmd =
+        |name: &str, args: &[(&str, &str)]| saved(Some(name), args).with_saved_queries(&custom);
+
+    let replaced = cmd("callers-of", &[("name", "main")]);
+    assert!(run_with_store(replaced, &store, OutputFormat::Json)
+        .await
+        .is_ok());
+    let no_sql = run_with_store(cmd("graph-only", &[]), &store, OutputFormat::Json);
+    let error = no_sql.await.unwrap_err().to_string();
+    assert!(error.contains("no sql query"), "{error}");
+}
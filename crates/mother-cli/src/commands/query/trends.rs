@@ -0,0 +1,155 @@
+//! Trends: Graph metrics across the last scans of a branch
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use mother_core::graph::trends::{sparkline, GraphMetrics};
+use mother_core::graph::GraphStore;
+use serde::Serialize;
+use tracing::info;
+
+use super::oldest_scans_first;
+use super::output::{print_rows, short_sha, truncate_str};
+use crate::types::OutputFormat;
+
+/// Metrics of one scanned commit in `query trends`
+///
+/// Counts by kind are flattened into `symbols_<kind>` and `edges_<kind>`
+/// fields. Every row has the same fields, so CSV columns line up.
+#[derive(Debug, Clone, Serialize)]
+struct TrendRow {
+    commit_sha: String,
+    branch: String,
+    version: String,
+    scanned_at: String,
+    files: usize,
+    symbols: usize,
+    edges: usize,
+    dead_code: usize,
+    module_cycles: usize,
+    #[serde(flatten)]
+    by_kind: BTreeMap<String, usize>,
+}
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    last: usize,
+    branch: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let scans = oldest_scans_first(client, branch, last).await?;
+    info!("Computing trends over {} scans...", scans.len());
+
+    let mut metrics = Vec::with_capacity(scans.len());
+    for scan in &scans {
+        metrics.push(GraphMetrics::of(
+            &client.export_commit(&scan.commit_sha).await?,
+        ));
+    }
+    // Kinds missing from a scan count as zero there
+    let mut kinds = BTreeMap::new();
+    for m in &metrics {
+        for kind in m.symbols.keys() {
+            kinds.insert(format!("symbols_{kind}"), 0);
+        }
+        for kind in m.edges.keys() {
+            kinds.insert(format!("edges_{}", kind.to_lowercase()), 0);
+        }
+    }
+
+    let rows: Vec<TrendRow> = scans
+        .into_iter()
+        .zip(&metrics)
+        .map(|(scan, m)| {
+            let mut by_kind = kinds.clone();
+            for (kind, count) in &m.symbols {
+                by_kind.insert(format!("symbols_{kind}"), *count);
+            }
+            for (kind, count) in &m.edges {
+                by_kind.insert(format!("edges_{}", kind.to_lowercase()), *count);
+            }
+            TrendRow {
+                commit_sha: scan.commit_sha,
+                branch: scan.branch,
+                version: scan.version,
+                scanned_at: scan.scanned_at,
+                files: m.files,
+                symbols: m.symbol_count(),
+                edges: m.edge_count(),
+                dead_code: m.dead_code,
+                module_cycles: m.module_cycles,
+                by_kind,
+            }
+        })
+        .collect();
+    print_rows(&rows, format, print_trends_table)
+}
+
+fn print_trends_table(rows: &[TrendRow]) {
+    let Some(first) = rows.first() else {
+        return;
+    };
+    println!(
+        "\nLast {} scans of branch '{}', oldest first",
+        rows.len(),
+        first.branch
+    );
+    println!(
+        "\n{:<10} {:<16} {:<12} {:>8} {:>10} {:>10} {:>8}",
+        "COMMIT", "VERSION", "SCANNED", "FILES", "SYMBOLS", "EDGES", "DEAD"
+    );
+    println!("{}", "-".repeat(80));
+    for r in rows {
+        println!(
+            "{:<10} {:<16} {:<12} {:>8} {:>10} {:>10} {:>8}",
+            short_sha(&r.commit_sha),
+            truncate_str(&r.version, 16),
+            r.scanned_at.get(..10).unwrap_or(&r.scanned_at),
+            r.files,
+            r.symbols,
+            r.edges,
+            r.dead_code
+        );
+    }
+
+    let series = |value: fn(&TrendRow) -> usize| rows.iter().map(value).collect::<Vec<_>>();
+    let by_kind = |key: &str| {
+        rows.iter()
+            .map(|r| r.by_kind.get(key).copied().unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+    println!(
+        "\n{:<24} {:<12} {:>8} {:>8} {:>8}",
+        "METRIC", "TREND", "FIRST", "LAST", "CHANGE"
+    );
+    println!("{}", "-".repeat(64));
+    print_trend_line("files", &series(|r| r.files));
+    print_trend_line("symbols", &series(|r| r.symbols));
+    for key in first.by_kind.keys() {
+        if let Some(kind) = key.strip_prefix("symbols_") {
+            print_trend_line(&format!("  {kind}"), &by_kind(key));
+        }
+    }
+    print_trend_line("edges", &series(|r| r.edges));
+    for key in first.by_kind.keys() {
+        if let Some(kind) = key.strip_prefix("edges_") {
+            print_trend_line(&format!("  {kind}"), &by_kind(key));
+        }
+    }
+    print_trend_line("dead code", &series(|r| r.dead_code));
+    print_trend_line("module cycles", &series(|r| r.module_cycles));
+}
+
+fn print_trend_line(metric: &str, values: &[usize]) {
+    let first = values.first().copied().unwrap_or_default();
+    let last = values.last().copied().unwrap_or_default();
+    let change = last as i64 - first as i64;
+    println!(
+        "{:<24} {:<12} {:>8} {:>8} {:>+8}",
+        truncate_str(metric, 24),
+        sparkline(values),
+        first,
+        last,
+        change
+    );
+}
@@ -0,0 +1,85 @@
+//! Type usage: Functions taking or returning a type, for `query takes` and
+//! `query returns`
+
+use anyhow::Result;
+use mother_core::graph::{GraphStore, PathScope, TypeUsage, TypeUsageResult};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::{resolve_scope, resolve_version};
+use crate::types::OutputFormat;
+
+/// Which functions `query takes` and `query returns` list
+struct TypeFilter<'a> {
+    type_name: &'a str,
+    usage: TypeUsage,
+    version: Option<&'a str>,
+    scope: Option<&'a PathScope>,
+}
+
+/// Run `query takes` or `query returns` from its command-line arguments
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    type_name: &str,
+    usage: TypeUsage,
+    version: Option<String>,
+    scope: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let scope = scope.as_deref().map(resolve_scope).transpose()?;
+    let filter = TypeFilter {
+        type_name,
+        usage,
+        version: version.as_deref(),
+        scope: scope.as_ref(),
+    };
+    run_functions_by_type(client, filter, format).await
+}
+
+async fn run_functions_by_type(
+    client: &dyn GraphStore,
+    filter: TypeFilter<'_>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, filter.version).await?;
+    info!(
+        "Finding functions using '{}' at commit {}...",
+        filter.type_name,
+        short_sha(&commit_sha)
+    );
+
+    let functions = client
+        .find_functions_by_type(&commit_sha, filter.type_name, filter.usage, filter.scope)
+        .await?;
+    print_rows(&functions, format, print_functions_by_type_table)
+}
+
+fn print_functions_by_type_table(functions: &[TypeUsageResult]) {
+    if functions.is_empty() {
+        println!("No functions found");
+        return;
+    }
+
+    println!(
+        "\n{:<40} {:<10} {:<50} {:<20} TYPE",
+        "FUNCTION", "KIND", "LOCATION", "PARAMETER"
+    );
+    println!("{}", "-".repeat(130));
+    for f in functions {
+        let location = format!("{}:{}", f.file_path, f.start_line);
+        let parameter = match (&f.parameter, f.position) {
+            (Some(name), Some(position)) => format!("#{position} {name}"),
+            _ => "(returns)".to_string(),
+        };
+        println!(
+            "{:<40} {:<10} {:<50} {:<20} {}",
+            truncate_str(&f.qualified_name, 40),
+            f.kind,
+            truncate_path(&location, 50),
+            truncate_str(&parameter, 20),
+            f.type_name
+        );
+    }
+
+    println!("\nFound {} functions", functions.len());
+}
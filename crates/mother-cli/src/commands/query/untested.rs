@@ -0,0 +1,62 @@
+//! Untested: Public functions no test references or calls
+
+use std::path::Path;
+
+use anyhow::Result;
+use mother_core::graph::directories::root as directory_root;
+use mother_core::graph::testing::{untested, UntestedSymbol};
+use mother_core::graph::{GraphStore, PathScope};
+use tracing::info;
+
+use super::output::{print_rows, short_sha, truncate_path, truncate_str};
+use super::resolve_version;
+use crate::types::OutputFormat;
+
+pub(super) async fn run(
+    client: &dyn GraphStore,
+    version: Option<&str>,
+    scope: Option<&PathScope>,
+    format: OutputFormat,
+) -> Result<()> {
+    let commit_sha = resolve_version(client, version).await?;
+    info!(
+        "Finding untested functions at commit {}...",
+        short_sha(&commit_sha)
+    );
+
+    // Tests are recognized by their path under the scanned root
+    let directories = client.directories_at_commit(&commit_sha).await?;
+    let root = directory_root(&directories).map_or("", |root| root.path.as_str());
+    let symbols = client.symbols_at_commit(&commit_sha).await?;
+    let graph = client.export_commit(&commit_sha).await?;
+    let functions: Vec<UntestedSymbol> = untested(Path::new(root), &symbols, &graph)
+        .into_iter()
+        .filter(|s| scope.is_none_or(|scope| scope.matches(&s.file_path)))
+        .collect();
+    print_rows(&functions, format, print_untested_table)
+}
+
+fn print_untested_table(functions: &[UntestedSymbol]) {
+    if functions.is_empty() {
+        println!("No untested functions found");
+        return;
+    }
+
+    println!(
+        "\n{:<6} {:<40} {:<10} {:<50}",
+        "REFS", "FUNCTION", "KIND", "LOCATION"
+    );
+    println!("{}", "-".repeat(110));
+    for f in functions {
+        let location = format!("{}:{}", f.file_path, f.start_line);
+        println!(
+            "{:<6} {:<40} {:<10} {:<50}",
+            f.references,
+            truncate_str(&f.qualified_name, 40),
+            f.kind,
+            truncate_path(&location, 50)
+        );
+    }
+
+    println!("\nFound {} untested functions", functions.len());
+}
@@ -0,0 +1,9 @@
+//! Runs module: List, inspect and delete recorded scan runs
+
+mod run;
+
+pub(crate) use run::find_run;
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,277 @@
+//! Runs command: Manage the scan runs recorded in the graph store
+//!
+//! Runs are addressed by ID or an unambiguous ID prefix. Tags and notes can
+//! be added to a run after the scan; a tag is then accepted as a version in
+//! the form `tag:<name>`. Runs that failed, ran out of time or were left in
+//! progress by a scan that was killed are listed with their status and can
+//! be deleted together; runs of scans still going are kept. Deleting
+//! a run also deletes its commit once no other run is left for it, and the
+//! files and symbols no remaining commit contains.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use mother_core::graph::model::{FileCacheStats, ScanFailure, SkippedFile};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::{DeleteSummary, GraphStore, ScanRunResult};
+use mother_scan::{describe_failure, is_incomplete_run};
+use serde::Serialize;
+use tracing::info;
+
+use crate::commands::query::{print_row, print_rows};
+use crate::commands::scan::format_bytes;
+use crate::types::{OutputFormat, RunsCommands};
+
+/// A scan run with the size of the commit it scanned, for `runs show`
+#[derive(Debug, Clone, Serialize)]
+struct RunDetails {
+    #[serde(flatten)]
+    run: ScanRunResult,
+    files: usize,
+    lines: i64,
+    symbols: i64,
+    /// IDs of the other runs of the same commit
+    other_runs: Vec<String>,
+    /// Percentage of the scan done before its time budget ran out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completion: Option<u8>,
+    /// Reads of file contents through the scan's cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_cache: Option<FileCacheStats>,
+    /// Files and symbols the scan failed on
+    failures: Vec<ScanFailure>,
+    /// Files left out for their content
+    skipped: Vec<SkippedFile>,
+}
+
+/// Run the runs command
+///
+/// # Errors
+/// Returns an error if the connection fails or the command fails.
+pub async fn run(cmd: RunsCommands, neo4j: &Neo4jConfig, format: OutputFormat) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(cmd, &client, format).await
+}
+
+/// Run the runs command against an open graph store
+///
+/// # Errors
+/// Returns an error if a run ID matches no run or several, the store fails,
+/// or the results cannot be printed.
+pub async fn run_with_store(
+    cmd: RunsCommands,
+    client: &dyn GraphStore,
+    format: OutputFormat,
+) -> Result<()> {
+    match cmd {
+        RunsCommands::List => {
+            let runs = client.scan_runs().await?;
+            print_rows(&runs, format, print_runs_table)
+        }
+        RunsCommands::Show { id } => run_show(client, &id, format).await,
+        RunsCommands::Tag { id, tag } => run_tag(client, &id, &tag, format).await,
+        RunsCommands::Annotate { id, note } => run_annotate(client, &id, &note, format).await,
+        RunsCommands::Delete { id, failed } => run_delete(client, id, failed, format).await,
+        RunsCommands::Prune { keep_last } => {
+            let runs = client.scan_runs().await?;
+            let mut summary = DeleteSummary::default();
+            for run in runs.iter().skip(keep_last) {
+                info!(
+                    "Deleting scan run {} ({})...",
+                    run.id,
+                    short_sha(&run.commit_sha)
+                );
+                summary += client.delete_scan_run(&run.id).await?;
+            }
+            print_row(&summary, format, print_summary)
+        }
+    }
+}
+
+async fn run_delete(
+    client: &dyn GraphStore,
+    id: Option<String>,
+    failed: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let selected: Vec<&ScanRunResult> = if failed {
+        let now = Utc::now();
+        runs.iter().filter(|r| is_incomplete_run(r, now)).collect()
+    } else {
+        vec![find_run(&runs, id.as_deref().unwrap_or_default())?]
+    };
+    let mut summary = DeleteSummary::default();
+    for run in selected {
+        info!("Deleting {} scan run {}...", run.status, run.id);
+        summary += client.delete_scan_run(&run.id).await?;
+    }
+    print_row(&summary, format, print_summary)
+}
+
+async fn run_show(client: &dyn GraphStore, id: &str, format: OutputFormat) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let run = find_run(&runs, id)?;
+    let files = client.file_metrics(&run.commit_sha, None).await?;
+    let report = client.scan_report(&run.id).await?;
+    let details = RunDetails {
+        run: run.clone(),
+        files: files.len(),
+        lines: files.iter().map(|f| f.line_count).sum(),
+        symbols: files.iter().map(|f| f.symbol_count).sum(),
+        other_runs: runs
+            .iter()
+            .filter(|r| r.commit_sha == run.commit_sha && r.id != run.id)
+            .map(|r| r.id.clone())
+            .collect(),
+        completion: report.as_ref().and_then(|report| report.completion),
+        file_cache: report.as_ref().and_then(|report| report.file_cache),
+        skipped: report
+            .as_ref()
+            .map(|report| report.skipped.clone())
+            .unwrap_or_default(),
+        failures: report.map(|report| report.failures).unwrap_or_default(),
+    };
+    print_row(&details, format, print_details)
+}
+
+async fn run_tag(client: &dyn GraphStore, id: &str, tag: &str, format: OutputFormat) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let run = find_run(&runs, id)?;
+    info!("Tagging scan run {} as {}...", run.id, tag);
+    client.tag_scan_run(&run.id, tag).await?;
+    print_updated(client, &run.id, format).await
+}
+
+async fn run_annotate(
+    client: &dyn GraphStore,
+    id: &str,
+    note: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let run = find_run(&runs, id)?;
+    info!("Annotating scan run {}...", run.id);
+    client.annotate_scan_run(&run.id, note).await?;
+    print_updated(client, &run.id, format).await
+}
+
+/// Print a run again after tagging or annotating it
+async fn print_updated(client: &dyn GraphStore, id: &str, format: OutputFormat) -> Result<()> {
+    let runs = client.scan_runs().await?;
+    let run = find_run(&runs, id)?;
+    print_row(run, format, |run| {
+        println!("Scan run {}", run.id);
+        println!("  Tags: {}", run.tags.join(", "));
+        println!("  Note: {}", run.note);
+    })
+}
+
+/// The run whose ID is `id` or starts with it
+pub(crate) fn find_run<'a>(runs: &'a [ScanRunResult], id: &str) -> Result<&'a ScanRunResult> {
+    if let Some(run) = runs.iter().find(|r| r.id == id) {
+        return Ok(run);
+    }
+    let matching: Vec<&ScanRunResult> = runs.iter().filter(|r| r.id.starts_with(id)).collect();
+    match matching.as_slice() {
+        [run] => Ok(run),
+        [] => bail!("No scan run found with ID '{id}'"),
+        _ => bail!(
+            "Scan run ID '{id}' is ambiguous: it matches {} runs",
+            matching.len()
+        ),
+    }
+}
+
+fn print_runs_table(runs: &[ScanRunResult]) {
+    if runs.is_empty() {
+        println!("No scan runs found");
+        return;
+    }
+
+    println!(
+        "\n{:<38} {:<10} {:<20} {:<16} {:<20} {:<12} TAGS",
+        "ID", "COMMIT", "BRANCH", "VERSION", "SCANNED", "STATUS"
+    );
+    println!("{}", "-".repeat(133));
+    for r in runs {
+        println!(
+            "{:<38} {:<10} {:<20} {:<16} {:<20} {:<12} {}",
+            r.id,
+            short_sha(&r.commit_sha),
+            r.branch,
+            r.version,
+            r.scanned_at.get(..19).unwrap_or(&r.scanned_at),
+            r.status,
+            r.tags.join(", ")
+        );
+    }
+    println!("\nTotal: {} runs", runs.len());
+}
+
+fn print_details(details: &RunDetails) {
+    let run = &details.run;
+    println!("\nScan run {}", run.id);
+    println!("  Repository: {}", run.repo_path);
+    println!("  Commit:     {}", run.commit_sha);
+    println!("  Branch:     {}", run.branch);
+    println!("  Version:    {}", run.version);
+    println!("  Scanned at: {}", run.scanned_at);
+    println!("  Status:     {}", run.status);
+    if let Some(completion) = details.completion {
+        println!("  Completion: {completion}% (ran out of time)");
+    }
+    if !run.previous_run.is_empty() {
+        println!("  Previous:   {}", run.previous_run);
+    }
+    if !run.tags.is_empty() {
+        println!("  Tags:       {}", run.tags.join(", "));
+    }
+    if !run.note.is_empty() {
+        println!("  Note:       {}", run.note);
+    }
+    println!("  Files:      {}", details.files);
+    println!("  Lines:      {}", details.lines);
+    println!("  Symbols:    {}", details.symbols);
+    if let Some(cache) = &details.file_cache {
+        println!(
+            "  File cache: {} hits, {} misses ({:.0}% hit rate), {} read",
+            cache.hits,
+            cache.misses,
+            cache.hit_rate() * 100.0,
+            format_bytes(cache.bytes_read as f64)
+        );
+    }
+    println!("  Failures:   {}", details.failures.len());
+    if !details.skipped.is_empty() {
+        println!("  Skipped:    {}", details.skipped.len());
+    }
+    if !details.other_runs.is_empty() {
+        println!("\nOther runs of the same commit:");
+        for id in &details.other_runs {
+            println!("  {id}");
+        }
+    }
+    if !details.failures.is_empty() {
+        println!("\nFailures:");
+        for failure in &details.failures {
+            println!("  [{}] {}", failure.phase, describe_failure(failure));
+        }
+    }
+    if !details.skipped.is_empty() {
+        println!("\nSkipped files:");
+        for skipped in &details.skipped {
+            println!("  {}: {}", skipped.file, skipped.reason);
+        }
+    }
+}
+
+fn print_summary(summary: &DeleteSummary) {
+    println!(
+        "Deleted {} scan runs, {} commits, {} files and {} symbols",
+        summary.runs, summary.commits, summary.files, summary.symbols
+    );
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..8).unwrap_or(sha)
+}
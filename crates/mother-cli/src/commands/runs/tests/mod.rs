@@ -0,0 +1,3 @@
+//! Tests for runs module
+
+mod tests_run;
@@ -0,0 +1,224 @@
+//! Tests for the runs run function
+//!
+//! These tests verify the behavior of the `mother::commands::runs` run
+//! functions against an in-memory SQLite store.
+
+#![allow(clippy::unwrap_used)]
+
+use crate::commands::runs::run_with_store;
+use crate::types::{OutputFormat, RunsCommands};
+use mother_core::graph::model::{
+    FileCacheStats, ScanFailure, ScanReport, ScanRun, ScanStatus, SkipReason, SkippedFile,
+};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+/// A store with one file in each of three scanned commits, oldest first
+async fn three_runs() -> (SqliteStore, Vec<String>) {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let mut ids = Vec::new();
+    for (i, sha) in ["aaa111", "bbb222", "ccc333"].into_iter().enumerate() {
+        let mut run = ScanRun::new("/repo").with_commit(sha);
+        run.scanned_at += chrono::Duration::seconds(i64::try_from(i).unwrap());
+        store.create_scan_run(&run).await.unwrap();
+        store
+            .create_file_if_new("/repo/src/lib.rs", &format!("hash-{sha}"), "rust", sha)
+            .await
+            .unwrap();
+        ids.push(run.id);
+    }
+    (store, ids)
+}
+
+#[tokio::test]
+async fn test_list_and_show_print_runs() {
+    let (store, ids) = three_runs().await;
+    let report = ScanReport {
+        scan_run_id: ids[1].clone(),
+        commit_sha: "bbb222".to_string(),
+        failures: vec![ScanFailure {
+            phase: "Phase 2".to_string(),
+            file: "/repo/src/lib.rs".to_string(),
+            symbol: None,
+            request: "textDocument/documentSymbol".to_string(),
+            message: "server exited".to_string(),
+        }],
+        completion: Some(60),
+        file_cache: Some(FileCacheStats {
+            hits: 120,
+            misses: 80,
+            bytes_read: 1 << 20,
+            mapped: 0,
+        }),
+        skipped: vec![SkippedFile {
+            file: "/repo/vendor/app.min.js".to_string(),
+            reason: SkipReason::TooLarge {
+                size: 4 << 20,
+                limit: 1 << 20,
+            },
+        }],
+    };
+    store.set_scan_report(&report).await.unwrap();
+
+    for format in [OutputFormat::Table, OutputFormat::Json, OutputFormat::Csv] {
+        assert!(run_with_store(RunsCommands::List, &store, format)
+            .await
+            .is_ok());
+        let show = RunsCommands::Show {
+            id: ids[1][..8].to_string(),
+        };
+        assert!(run_with_store(show, &store, format).await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_run_id_is_an_error() {
+    let (store, _) = three_runs().await;
+
+    for cmd in [
+        RunsCommands::Show {
+            id: "missing".to_string(),
+        },
+        RunsCommands::Delete {
+            id: Some("missing".to_string()),
+            failed: false,
+        },
+        // An empty prefix matches every run
+        RunsCommands::Show { id: String::new() },
+    ] {
+        assert!(run_with_store(cmd, &store, OutputFormat::Table)
+            .await
+            .is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_delete_removes_the_run_and_its_commit() {
+    let (store, ids) = three_runs().await;
+
+    let delete = RunsCommands::Delete {
+        id: Some(ids[0].clone()),
+        failed: false,
+    };
+    run_with_store(delete, &store, OutputFormat::Json)
+        .await
+        .unwrap();
+
+    let remaining: Vec<String> = store
+        .scan_runs()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    assert_eq!(remaining, vec![ids[2].clone(), ids[1].clone()]);
+    let stats = store.stats().await.unwrap();
+    assert_eq!((stats.commits, stats.files), (2, 2));
+}
+
+#[tokio::test]
+async fn test_delete_failed_removes_runs_that_did_not_complete() {
+    let (store, ids) = three_runs().await;
+    store
+        .set_scan_status(&ids[1], ScanStatus::Failed)
+        .await
+        .unwrap();
+    store
+        .set_scan_status(&ids[2], ScanStatus::InProgress)
+        .await
+        .unwrap();
+    // A run a killed scan left in progress the day before
+    let mut killed = ScanRun::new("/repo")
+        .with_commit("ddd444")
+        .with_status(ScanStatus::InProgress);
+    killed.scanned_at -= chrono::Duration::days(1);
+    store.create_scan_run(&killed).await.unwrap();
+    assert_eq!(store.resolve_commit(None).await.unwrap().unwrap(), "aaa111");
+
+    let delete = RunsCommands::Delete {
+        id: None,
+        failed: true,
+    };
+    run_with_store(delete, &store, OutputFormat::Table)
+        .await
+        .unwrap();
+
+    // The scan still running keeps its run
+    let runs = store.scan_runs().await.unwrap();
+    let statuses: Vec<(&str, &str)> = runs
+        .iter()
+        .map(|r| (r.id.as_str(), r.status.as_str()))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (ids[2].as_str(), "in_progress"),
+            (ids[0].as_str(), "completed")
+        ]
+    );
+    assert_eq!(store.stats().await.unwrap().commits, 2);
+}
+
+#[tokio::test]
+async fn test_prune_keeps_the_most_recent_runs() {
+    let (store, ids) = three_runs().await;
+
+    let prune = RunsCommands::Prune { keep_last: 1 };
+    run_with_store(prune, &store, OutputFormat::Table)
+        .await
+        .unwrap();
+
+    let runs = store.scan_runs().await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].id, ids[2]);
+    assert_eq!(store.resolve_commit(None).await.unwrap().unwrap(), "ccc333");
+    assert_eq!(store.stats().await.unwrap().files, 1);
+}
+
+#[tokio::test]
+async fn test_tag_and_annotate_update_the_run() {
+    let (store, ids) = three_runs().await;
+
+    for tag in ["release-1.2", "lts", "lts"] {
+        let cmd = RunsCommands::Tag {
+            id: ids[0][..8].to_string(),
+            tag: tag.to_string(),
+        };
+        run_with_store(cmd, &store, OutputFormat::Table)
+            .await
+            .unwrap();
+    }
+    let annotate = RunsCommands::Annotate {
+        id: ids[0].clone(),
+        note: "Shipped to customers on Friday".to_string(),
+    };
+    run_with_store(annotate, &store, OutputFormat::Json)
+        .await
+        .unwrap();
+
+    let runs = store.scan_runs().await.unwrap();
+    let run = runs.iter().find(|r| r.id == ids[0]).unwrap();
+    assert_eq!(run.tags, ["lts", "release-1.2"]);
+    assert_eq!(run.note, "Shipped to customers on Friday");
+    assert_eq!(
+        store
+            .resolve_commit(Some("tag:release-1.2"))
+            .await
+            .unwrap()
+            .unwrap(),
+        "aaa111"
+    );
+}
+
+#[tokio::test]
+async fn test_tagging_an_unknown_run_is_an_error() {
+    let (store, _) = three_runs().await;
+
+    let cmd = RunsCommands::Tag {
+        id: "missing".to_string(),
+        tag: "release-1.2".to_string(),
+    };
+
+    assert!(run_with_store(cmd, &store, OutputFormat::Table)
+        .await
+        .is_err());
+}
@@ -1,155 +1,174 @@
-//! Scan command: Scan a repository and store in Neo4j
+//! Scan command: Scan a repository and print what the pipeline found
 //!
-//! This module implements a 3-phase scanning process:
-//! 1. Phase 1: Discover files, open in LSP, create in Neo4j
-//! 2. Phase 2: Extract symbols from LSP, enrich with hover, store in Neo4j
-//! 3. Phase 3: Extract references, create symbol-to-symbol edges
+//! The scan pipeline itself lives in the `mother-scan` crate. This module
+//! adds what only the command line needs: in-memory scans that print their
+//! statistics, the printed reports of dry runs and estimates, and the
+//! `progress` submodule rendering progress events as terminal bars.
 
-mod phase1;
-mod phase2;
-mod phase3;
-
-#[cfg(test)]
-mod tests;
+mod progress;
 
 use std::path::Path;
 
 use anyhow::Result;
-use mother_core::graph::model::ScanRun;
-use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
-use mother_core::lsp::LspServerManager;
-use mother_core::scanner::{DiscoveredFile, Language, Scanner};
+use mother_core::graph::SqliteStore;
+use mother_scan::{
+    run_bare_with_store, run_with_store, Bounded, DryRunReport, ScanEstimate, ScanOptions,
+};
 use tracing::info;
 
-pub(crate) use phase1::Phase1Result;
-pub(crate) use phase2::Phase2Result;
-pub(crate) use phase3::Phase3Result;
-
-// ============================================================================
-// Types shared across phases
-// ============================================================================
+use crate::commands::{export, query};
+use crate::types::{OutputFormat, QueryCommands};
 
-/// A file that needs symbol extraction (output from Phase 1)
-pub struct FileToProcess {
-    pub path: std::path::PathBuf,
-    pub file_uri: String,
-    pub content_hash: String,
-    pub language: Language,
-}
-
-/// Symbol position info for reference extraction (output from Phase 2)
-pub struct SymbolInfo {
-    pub id: String,
-    pub file_uri: String,
-    pub start_line: u32,
-    pub end_line: u32,
-    pub start_col: u32,
-    pub language: Language,
-}
+pub use progress::ProgressBars;
 
-// ============================================================================
-// Main entry point
-// ============================================================================
-
-/// Run the scan command
+/// Scan into a private in-memory store, then print the graph statistics and
+/// write an export of the scan to `export` when given
+///
+/// Nothing is kept afterwards. With `bare`, a repository and commit, the
+/// commit is scanned instead of `path`.
 ///
 /// # Errors
-/// Returns an error if scanning or Neo4j operations fail.
-pub async fn run(
+/// Returns an error if scanning, the statistics query or the export fails.
+pub async fn run_in_memory(
     path: &Path,
-    neo4j_uri: &str,
-    neo4j_user: &str,
-    neo4j_password: &str,
-    version: Option<&str>,
+    bare: Option<(&Path, &str)>,
+    options: &ScanOptions,
+    export: Option<&Path>,
 ) -> Result<()> {
-    info!("Scanning repository: {}", path.display());
-
-    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-    let (scan_run, commit_sha) = create_scan_run(&abs_path, version);
-
-    log_scan_run_info(&scan_run, &commit_sha);
-
-    let client = connect_neo4j(neo4j_uri, neo4j_user, neo4j_password).await?;
-
-    if !client.create_scan_run(&scan_run).await? {
-        info!("✓ Commit already scanned, linked scan run to existing data");
-        return Ok(());
+    let store = SqliteStore::open_in_memory()?;
+    match bare {
+        Some((repo, commit)) => run_bare_with_store(repo, commit, &store, options).await?,
+        None => run_with_store(path, &store, options).await?,
     }
-
-    execute_scan(&abs_path, &client, &commit_sha).await
+    query::run_with_store(QueryCommands::Stats, &store, OutputFormat::Table).await?;
+    if let Some(output) = export {
+        export::run_with_store(&store, None, None, None, Some(output)).await?;
+        info!("Exported the scan to {}", output.display());
+    }
+    Ok(())
 }
 
-/// Execute the scan workflow after determining a new commit needs scanning
-async fn execute_scan(abs_path: &Path, client: &Neo4jClient, commit_sha: &str) -> Result<()> {
-    info!("New commit detected, scanning files...");
-
-    let files: Vec<DiscoveredFile> = Scanner::new(abs_path).scan().collect();
-    info!("Found {} files to process", files.len());
-
-    let mut lsp_manager = LspServerManager::new(abs_path);
-
-    let phase1 = phase1::run(&files, client, &mut lsp_manager, commit_sha).await?;
-    let phase2 = phase2::run(&phase1.files_to_process, client, &mut lsp_manager).await?;
-    let phase3 = phase3::run(&phase2.symbols, client, &mut lsp_manager).await?;
-
-    shutdown_lsp(&mut lsp_manager).await;
+/// Run a scan without touching Neo4j and print what it would write
+///
+/// # Errors
+/// Returns an error if the enricher config or include/exclude globs are
+/// invalid; files the LSP cannot process are counted and skipped.
+pub async fn dry_run(path: &Path, options: &ScanOptions) -> Result<()> {
+    print_report(&mother_scan::dry_run(path, options).await?);
+    Ok(())
+}
 
-    log_scan_summary(&phase1, &phase2, &phase3);
+/// Estimate a scan of a repository from a sample of `sample_size` files and
+/// print the estimate
+///
+/// # Errors
+/// Returns an error if the include/exclude globs are invalid; files that fail
+/// to sample are logged and skipped.
+pub async fn estimate(path: &Path, sample_size: usize, options: &ScanOptions) -> Result<()> {
+    print_estimate(&mother_scan::estimate(path, sample_size, options).await?);
     Ok(())
 }
 
-fn log_scan_summary(phase1: &Phase1Result, phase2: &Phase2Result, phase3: &Phase3Result) {
-    let total_errors = phase1.error_count + phase2.error_count + phase3.error_count;
-
-    if total_errors > 0 {
-        info!(
-            "✓ Scan completed: {} new files, {} reused, {} symbols, {} references ({} errors)",
-            phase1.new_file_count,
-            phase1.reused_file_count,
-            phase2.symbol_count,
-            phase3.reference_count,
-            total_errors
-        );
-    } else {
-        info!(
-            "✓ Scan completed: {} new files, {} reused, {} symbols, {} references",
-            phase1.new_file_count,
-            phase1.reused_file_count,
-            phase2.symbol_count,
-            phase3.reference_count
-        );
+fn print_report(report: &DryRunReport) {
+    println!("\n=== Dry Run (nothing written) ===\n");
+    println!("Files:      {}", report.file_count());
+    let mut languages: Vec<_> = report
+        .files_by_language
+        .iter()
+        .map(|(language, count)| (language.to_string(), *count))
+        .collect();
+    print_counts(&mut languages);
+
+    println!("\nSymbols:    {}", report.symbol_count());
+    let mut kinds: Vec<_> = report
+        .symbols_by_kind
+        .iter()
+        .map(|(kind, count)| (kind.clone(), *count))
+        .collect();
+    print_counts(&mut kinds);
+
+    println!("\nEdges:      ~{}", report.estimated_edges());
+    println!("  {:<12} {}", "references", report.reference_edges);
+    println!("  {:<12} {}", "imports", report.import_edges);
+
+    if report.error_count > 0 {
+        println!("\nErrors:     {}", report.error_count);
+    }
+    if !report.enricher_errors.is_empty() {
+        println!("\nEnricher failures:");
+        let mut failures: Vec<_> = report
+            .enricher_errors
+            .iter()
+            .map(|(name, count)| (name.to_string(), *count))
+            .collect();
+        print_counts(&mut failures);
+    }
+}
+
+/// Print label counts, largest first
+fn print_counts(counts: &mut [(String, usize)]) {
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (label, count) in counts.iter() {
+        println!("  {:<12} {}", label, count);
     }
 }
 
-fn create_scan_run(abs_path: &Path, version: Option<&str>) -> (ScanRun, String) {
-    let mut scan_run = ScanRun::new(abs_path.display().to_string()).with_git_info();
-    if let Some(v) = version {
-        scan_run = scan_run.with_version(v);
+fn print_estimate(estimate: &ScanEstimate) {
+    println!("\n=== Scan Estimate ===\n");
+    println!(
+        "Files:      {} ({} sampled)",
+        estimate.total_files, estimate.sampled_files
+    );
+
+    let mut languages: Vec<_> = estimate.files_by_language.iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(a.1));
+    for (language, count) in languages {
+        println!("  {:<12} {}", language.to_string(), count);
     }
-    let commit_sha = scan_run.commit_sha.clone().unwrap_or_default();
-    (scan_run, commit_sha)
+
+    println!("\nEstimates (95% confidence):");
+    print_bounded("Symbols", estimate.symbols, format_count);
+    print_bounded("References", estimate.references, format_count);
+    print_bounded("Nodes", estimate.nodes, format_count);
+    print_bounded("Edges", estimate.edges, format_count);
+    print_bounded("DB size", estimate.database_bytes, format_bytes);
+    print_bounded("Duration", estimate.duration_secs, format_duration);
 }
 
-fn log_scan_run_info(scan_run: &ScanRun, commit_sha: &str) {
-    info!(
-        "Created scan run: {} (commit: {}, branch: {:?})",
-        scan_run.id,
-        if commit_sha.is_empty() {
-            "none"
-        } else {
-            commit_sha
-        },
-        scan_run.branch
+fn print_bounded(label: &str, value: Bounded, fmt: fn(f64) -> String) {
+    println!(
+        "  {:<12} {:>12}  ({} – {})",
+        label,
+        fmt(value.mid),
+        fmt(value.low),
+        fmt(value.high)
     );
 }
 
-pub(crate) async fn connect_neo4j(uri: &str, user: &str, password: &str) -> Result<Neo4jClient> {
-    let config = Neo4jConfig::new(uri, user, password);
-    Ok(Neo4jClient::connect(&config).await?)
+fn format_count(value: f64) -> String {
+    format!("{:.0}", value.round())
 }
 
-pub(crate) async fn shutdown_lsp(lsp_manager: &mut LspServerManager) {
-    if let Err(e) = lsp_manager.shutdown_all().await {
-        tracing::warn!("Failed to shutdown LSP servers: {}", e);
+/// A byte count in the largest unit it reaches, e.g. `1.5 MB`
+pub(crate) fn format_bytes(value: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = value;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
     }
 }
@@ -0,0 +1,102 @@
+//! Progress bars: Render scan progress events in the terminal
+//!
+//! [`ProgressBars`] turns the library's [`ProgressEvent`]s into one bar per
+//! phase with counts, produced symbols/references and an ETA. Logging
+//! suspends the same [`MultiProgress`] so log lines print above the bars.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use mother_core::progress::{ProgressEvent, ProgressReporter, ScanPhase};
+
+/// Bar layout shared by all phases
+const TEMPLATE: &str =
+    "{prefix:>20} [{bar:30.cyan/blue}] {pos}/{len} {unit}{msg} ({elapsed}, ETA {eta})";
+
+/// Terminal progress bars for a scan, one per phase
+pub struct ProgressBars {
+    multi: MultiProgress,
+    phases: Mutex<HashMap<ScanPhase, PhaseBar>>,
+}
+
+/// A phase's bar and what it produced so far
+struct PhaseBar {
+    bar: ProgressBar,
+    symbols: u64,
+    references: u64,
+}
+
+impl ProgressBars {
+    /// Draw bars through `multi`
+    #[must_use]
+    pub fn new(multi: MultiProgress) -> Self {
+        Self {
+            multi,
+            phases: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn phases(&self) -> MutexGuard<'_, HashMap<ScanPhase, PhaseBar>> {
+        self.phases
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl ProgressReporter for ProgressBars {
+    fn report(&self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::Started { phase, total } => {
+                let bar = self.multi.add(ProgressBar::new(total));
+                bar.set_style(style(phase));
+                bar.set_prefix(phase.label());
+                self.phases().insert(
+                    phase,
+                    PhaseBar {
+                        bar,
+                        symbols: 0,
+                        references: 0,
+                    },
+                );
+            }
+            ProgressEvent::Advanced {
+                phase,
+                symbols,
+                references,
+            } => {
+                if let Some(state) = self.phases().get_mut(&phase) {
+                    state.symbols += symbols;
+                    state.references += references;
+                    state.bar.set_message(produced(phase, state));
+                    state.bar.inc(1);
+                }
+            }
+            ProgressEvent::Finished { phase } => {
+                if let Some(state) = self.phases().get(&phase) {
+                    state.bar.finish();
+                }
+            }
+        }
+    }
+}
+
+/// Style of a phase's bar, naming its unit of work
+fn style(phase: ScanPhase) -> ProgressStyle {
+    let template = TEMPLATE.replace("{unit}", phase.unit());
+    ProgressStyle::with_template(&template)
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> ")
+}
+
+/// What a phase produced so far, appended to its bar
+fn produced(phase: ScanPhase, state: &PhaseBar) -> String {
+    match phase {
+        ScanPhase::Symbols => format!(", {} symbols", state.symbols),
+        ScanPhase::References => format!(", {} references", state.references),
+        ScanPhase::Files | ScanPhase::Imports => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
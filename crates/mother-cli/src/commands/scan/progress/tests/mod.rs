@@ -0,0 +1,3 @@
+//! Tests for progress module
+
+mod tests_progress;
@@ -0,0 +1,88 @@
+//! Tests for scan progress bars
+
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use mother_core::progress::{ProgressEvent, ProgressReporter, ScanPhase};
+
+use crate::commands::scan::progress::ProgressBars;
+
+fn hidden_bars() -> ProgressBars {
+    ProgressBars::new(MultiProgress::with_draw_target(ProgressDrawTarget::hidden()))
+}
+
+fn advance(bars: &ProgressBars, phase: ScanPhase, symbols: u64, references: u64) {
+    bars.report(ProgressEvent::Advanced {
+        phase,
+        symbols,
+        references,
+    });
+}
+
+#[test]
+fn test_started_phase_gets_a_bar() {
+    let bars = hidden_bars();
+    bars.report(ProgressEvent::Started {
+        phase: ScanPhase::Files,
+        total: 10,
+    });
+
+    let phases = bars.phases();
+    let state = phases.get(&ScanPhase::Files);
+    assert!(state.is_some_and(|s| s.bar.length() == Some(10) && s.bar.position() == 0));
+}
+
+#[test]
+fn test_advanced_moves_bar_and_sums_symbols() {
+    let bars = hidden_bars();
+    bars.report(ProgressEvent::Started {
+        phase: ScanPhase::Symbols,
+        total: 3,
+    });
+    advance(&bars, ScanPhase::Symbols, 4, 0);
+    advance(&bars, ScanPhase::Symbols, 6, 0);
+
+    let phases = bars.phases();
+    let state = phases.get(&ScanPhase::Symbols);
+    assert!(state.is_some_and(|s| s.bar.position() == 2 && s.symbols == 10));
+    assert!(state.is_some_and(|s| s.bar.message() == ", 10 symbols"));
+}
+
+#[test]
+fn test_reference_phase_shows_references() {
+    let bars = hidden_bars();
+    bars.report(ProgressEvent::Started {
+        phase: ScanPhase::References,
+        total: 2,
+    });
+    advance(&bars, ScanPhase::References, 0, 7);
+
+    let phases = bars.phases();
+    let state = phases.get(&ScanPhase::References);
+    assert!(state.is_some_and(|s| s.bar.message() == ", 7 references"));
+}
+
+#[test]
+fn test_finished_phase_bar_is_finished() {
+    let bars = hidden_bars();
+    bars.report(ProgressEvent::Started {
+        phase: ScanPhase::Imports,
+        total: 1,
+    });
+    bars.report(ProgressEvent::Finished {
+        phase: ScanPhase::Imports,
+    });
+
+    let phases = bars.phases();
+    assert!(phases
+        .get(&ScanPhase::Imports)
+        .is_some_and(|s| s.bar.is_finished()));
+}
+
+#[test]
+fn test_events_for_unknown_phase_are_ignored() {
+    let bars = hidden_bars();
+    advance(&bars, ScanPhase::Files, 0, 0);
+    bars.report(ProgressEvent::Finished {
+        phase: ScanPhase::Files,
+    });
+    assert!(bars.phases().is_empty());
+}
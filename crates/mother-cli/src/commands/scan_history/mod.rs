@@ -0,0 +1,13 @@
+//! Scan history command: Backfill the graph with a stretch of git history
+//!
+//! Each commit between two revisions is checked out to a temporary
+//! directory and scanned in turn, oldest first. Every scan run is linked to
+//! the run before it, so the runs form a chain that longitudinal queries,
+//! such as symbol counts over time, can follow.
+
+mod run;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
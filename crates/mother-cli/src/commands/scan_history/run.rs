@@ -0,0 +1,66 @@
+//! Scan history command implementation
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mother_core::graph::neo4j::Neo4jConfig;
+use mother_core::graph::GraphStore;
+use mother_core::scanner::commit_history;
+use mother_scan::ScanOptions;
+use tracing::info;
+
+/// Run the scan history command
+///
+/// # Errors
+/// Returns an error if the connection fails or the history scan fails.
+pub async fn run(
+    repo: &Path,
+    from: &str,
+    to: &str,
+    step: NonZeroUsize,
+    neo4j: &Neo4jConfig,
+    options: &ScanOptions,
+) -> Result<()> {
+    let client = mother_scan::connect_neo4j(neo4j).await?;
+    run_with_store(repo, from, to, step, &client, options).await
+}
+
+/// Scan every `step`th commit from `from` to `to` against an open graph
+/// store, linking each scan run to the one before it
+///
+/// Stops at the first commit that fails to scan; the runs recorded so far
+/// are kept.
+///
+/// # Errors
+/// Returns an error if the revisions cannot be resolved, `from` is not an
+/// ancestor of `to`, or a commit cannot be checked out or scanned.
+pub async fn run_with_store(
+    repo: &Path,
+    from: &str,
+    to: &str,
+    step: NonZeroUsize,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+) -> Result<()> {
+    let commits = commit_history(repo, from, to, step)?;
+    info!(
+        "Scanning {} commits from {} to {} of {}",
+        commits.len(),
+        from,
+        to,
+        repo.display()
+    );
+
+    let mut previous: Option<String> = None;
+    for (i, sha) in commits.iter().enumerate() {
+        info!("[{}/{}] Scanning commit {}", i + 1, commits.len(), sha);
+        let id = mother_scan::scan_commit(repo, sha, client, options, previous.as_deref())
+            .await
+            .with_context(|| format!("Failed to scan commit {sha}"))?;
+        previous = Some(id);
+    }
+
+    info!("✓ Scanned {} commits", commits.len());
+    Ok(())
+}
@@ -0,0 +1,3 @@
+//! Tests for scan_history module
+
+mod tests_run;
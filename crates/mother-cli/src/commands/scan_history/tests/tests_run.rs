@@ -0,0 +1,100 @@
+//! Tests for scanning a stretch of history
+//!
+//! The repositories hold no source files, so the scans need no language
+//! servers.
+
+#![allow(clippy::unwrap_used)]
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_scan::ScanOptions;
+use tempfile::TempDir;
+
+use crate::commands::scan_history::run_with_store;
+
+/// Create a bare repository with one commit per README content
+///
+/// Returns the commit SHAs in order; `main` points at the last commit.
+fn bare_repo(dir: &Path, readmes: &[&str]) -> Vec<String> {
+    let repo = git2::Repository::init_bare(dir).unwrap();
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let mut shas: Vec<String> = Vec::new();
+
+    for readme in readmes {
+        let blob = repo.blob(readme.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("README.md", blob, 0o100_644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parent = shas
+            .last()
+            .map(|sha| repo.find_commit(git2::Oid::from_str(sha).unwrap()).unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo
+            .commit(
+                Some("refs/heads/main"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        shas.push(oid.to_string());
+    }
+    shas
+}
+
+fn step(n: usize) -> NonZeroUsize {
+    NonZeroUsize::new(n).unwrap()
+}
+
+#[tokio::test]
+async fn test_history_scans_chain_runs_oldest_first() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &["# One", "# Two", "# Three", "# Four"]);
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    run_with_store(
+        temp.path(),
+        &shas[0],
+        "main",
+        step(2),
+        &store,
+        &ScanOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    // Newest first: the last commit, then every second one from the start
+    let runs = store.scan_runs().await.unwrap();
+    let scanned: Vec<&str> = runs.iter().map(|r| r.commit_sha.as_str()).collect();
+    assert_eq!(scanned, [&shas[3], &shas[2], &shas[0]]);
+
+    let run_of = |sha: &str| runs.iter().find(|r| r.commit_sha == sha).unwrap();
+    assert_eq!(run_of(&shas[0]).previous_run, "");
+    assert_eq!(run_of(&shas[2]).previous_run, run_of(&shas[0]).id);
+    assert_eq!(run_of(&shas[3]).previous_run, run_of(&shas[2]).id);
+}
+
+#[tokio::test]
+async fn test_history_with_reversed_range_scans_nothing() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &["# One", "# Two"]);
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let err = run_with_store(
+        temp.path(),
+        &shas[1],
+        &shas[0],
+        step(1),
+        &store,
+        &ScanOptions::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("is not an ancestor of"));
+    assert!(store.scan_runs().await.unwrap().is_empty());
+}
@@ -0,0 +1,59 @@
+//! Compare command: Diff two exports of the same commit
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use mother_core::graph::export::compare::{compare, CompareReport};
+use mother_core::graph::export::{read_jsonl, GraphExport};
+
+/// Run the compare command
+///
+/// # Errors
+/// Returns an error if an export cannot be read, or if `strict` is set and
+/// the exports differ.
+pub fn run(baseline: &Path, against: &Path, examples: usize, strict: bool) -> Result<()> {
+    let old = load_export(baseline)?;
+    let new = load_export(against)?;
+
+    let report = compare(&old, &new);
+    print!("{}", format_report(&report, examples));
+
+    if strict && !report.is_identical() {
+        bail!("Exports differ in {} places", report.differences.len());
+    }
+    Ok(())
+}
+
+/// Read a JSON lines export
+pub(crate) fn load_export(path: &Path) -> Result<GraphExport> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    read_jsonl(BufReader::new(file))
+        .with_context(|| format!("{} is not a JSON lines export", path.display()))
+}
+
+/// Render a report with up to `examples` differences per cause
+pub(crate) fn format_report(report: &CompareReport, examples: usize) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Baseline: {} nodes, {} edges\nAgainst:  {} nodes, {} edges\n",
+        report.baseline.nodes, report.baseline.edges, report.against.nodes, report.against.edges
+    ));
+
+    if report.is_identical() {
+        out.push_str("\n✓ No differences\n");
+        return out;
+    }
+
+    for (cause, differences) in report.by_cause() {
+        out.push_str(&format!("\n{} ({}):\n", cause, differences.len()));
+        for difference in differences.iter().take(examples) {
+            out.push_str(&format!("  {}  {}\n", difference.key, difference.detail));
+        }
+        if differences.len() > examples {
+            out.push_str(&format!("  … {} more\n", differences.len() - examples));
+        }
+    }
+    out
+}
@@ -0,0 +1,8 @@
+//! Self-test module: Validate tool upgrades against earlier scan output
+
+mod compare;
+
+pub use compare::run as compare;
+
+#[cfg(test)]
+mod tests;
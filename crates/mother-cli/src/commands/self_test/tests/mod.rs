@@ -0,0 +1,3 @@
+//! Tests for self-test commands
+
+mod tests_compare;
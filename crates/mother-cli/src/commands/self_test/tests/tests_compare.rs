@@ -0,0 +1,114 @@
+//! Tests for the export compare command
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use mother_core::graph::export::compare::compare;
+use mother_core::graph::export::{write_graph, ExportFormat, ExportNode, GraphExport};
+use serde_json::json;
+use tempfile::TempDir;
+
+use super::super::compare::{format_report, load_export, run};
+
+fn symbol(id: &str, name: &str) -> ExportNode {
+    let properties: BTreeMap<String, serde_json::Value> = [
+        ("name", json!(name)),
+        ("file_path", json!("src/lib.rs")),
+        ("start_line", json!(1)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties,
+    }
+}
+
+fn write_export(dir: &TempDir, name: &str, graph: &GraphExport) -> std::path::PathBuf {
+    let path = dir.path().join(name);
+    let mut out = Vec::new();
+    write_graph(graph, ExportFormat::JsonLines, &mut out).unwrap();
+    std::fs::write(&path, out).unwrap();
+    path
+}
+
+// ============================================================================
+// load_export
+// ============================================================================
+
+#[test]
+fn test_load_export_reads_jsonl() {
+    let dir = TempDir::new().unwrap();
+    let graph = GraphExport {
+        nodes: vec![symbol("a", "run")],
+        edges: vec![],
+    };
+    let path = write_export(&dir, "old.jsonl", &graph);
+    assert_eq!(load_export(&path).unwrap(), graph);
+}
+
+#[test]
+fn test_load_export_rejects_other_formats() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("graph.graphml");
+    std::fs::write(&path, "<?xml version=\"1.0\"?>\n<graphml/>\n").unwrap();
+    assert!(load_export(&path).is_err());
+}
+
+#[test]
+fn test_load_export_missing_file() {
+    assert!(load_export(std::path::Path::new("/nonexistent/mother/old.jsonl")).is_err());
+}
+
+// ============================================================================
+// format_report
+// ============================================================================
+
+#[test]
+fn test_format_report_identical() {
+    let graph = GraphExport {
+        nodes: vec![symbol("a", "run")],
+        edges: vec![],
+    };
+    let text = format_report(&compare(&graph, &graph), 10);
+    assert!(text.contains("No differences"));
+}
+
+#[test]
+fn test_format_report_limits_examples() {
+    let old = GraphExport::default();
+    let new = GraphExport {
+        nodes: (0..3)
+            .map(|i| symbol(&i.to_string(), &format!("f{i}")))
+            .collect(),
+        edges: vec![],
+    };
+    let text = format_report(&compare(&old, &new), 1);
+    assert!(text.contains("node only in new export (3):"));
+    assert!(text.contains("… 2 more"));
+}
+
+// ============================================================================
+// run
+// ============================================================================
+
+#[test]
+fn test_run_strict_fails_on_differences() {
+    let dir = TempDir::new().unwrap();
+    let old = write_export(&dir, "old.jsonl", &GraphExport::default());
+    let new = write_export(
+        &dir,
+        "new.jsonl",
+        &GraphExport {
+            nodes: vec![symbol("a", "run")],
+            edges: vec![],
+        },
+    );
+
+    assert!(run(&old, &new, 10, false).is_ok());
+    assert!(run(&old, &new, 10, true).is_err());
+    assert!(run(&old, &old, 10, true).is_ok());
+}
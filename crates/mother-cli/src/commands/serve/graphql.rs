@@ -0,0 +1,331 @@
+//! GraphQL server: A typed schema over the graph at `/graphql`
+//!
+//! `POST /graphql` executes queries; `GET /graphql` opens GraphiQL to
+//! explore the schema. Nested fields are resolved with further store
+//! queries, so a client can ask for a function, its callers and their files
+//! in one request:
+//!
+//! ```graphql
+//! { symbols(name: "parse", exact: true) {
+//!     qualifiedName
+//!     callers { sourceLine source { name file { path } } } } }
+//! ```
+//!
+//! References are matched by name in the store; nested fields narrow them
+//! down to the symbol's own file. Query depth is limited to keep nested
+//! queries from fanning out without bound.
+
+use std::sync::Arc;
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use axum::extract::State;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use mother_core::graph::{
+    FileResult, GraphStats, GraphStore, Page, PathScope, ReferenceResult, ScanResult, SymbolResult,
+};
+
+type Store = Arc<dyn GraphStore>;
+
+/// The executable schema, holding the store as context data
+pub(crate) type GraphSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deepest selection a query may nest
+const MAX_DEPTH: usize = 12;
+
+/// Build the schema over a store
+pub(crate) fn schema(client: Store) -> GraphSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(client)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+/// Routes of the GraphQL endpoint
+pub(crate) fn router(client: Store) -> Router {
+    Router::new()
+        .route("/graphql", get(graphiql).post(execute))
+        .with_state(schema(client))
+}
+
+async fn execute(
+    State(schema): State<GraphSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+fn store<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a dyn GraphStore> {
+    Ok(ctx.data::<Store>()?.as_ref())
+}
+
+/// Entry points of the schema
+pub(crate) struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Symbols whose name contains `name` (case-insensitive), or equals it
+    /// with `exact`, optionally only in files under a directory or glob
+    async fn symbols(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        #[graphql(default)] exact: bool,
+        #[graphql(name = "in")] scope: Option<String>,
+    ) -> async_graphql::Result<Vec<Symbol>> {
+        let scope = scope.as_deref().map(PathScope::new).transpose()?;
+        let symbols = store(ctx)?
+            .find_symbols(&name, scope.as_ref(), Page::default())
+            .await?;
+        Ok(symbols
+            .into_iter()
+            .filter(|s| !exact || s.name == name)
+            .map(Symbol)
+            .collect())
+    }
+
+    /// Files whose path contains `pattern`
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        pattern: Option<String>,
+    ) -> async_graphql::Result<Vec<File>> {
+        let files = store(ctx)?
+            .list_files(pattern.as_deref(), Page::default())
+            .await?;
+        Ok(files.into_iter().map(File).collect())
+    }
+
+    /// The file at exactly this path
+    async fn file(&self, ctx: &Context<'_>, path: String) -> async_graphql::Result<Option<File>> {
+        file_at(store(ctx)?, &path).await
+    }
+
+    /// Latest scans of a branch, newest first (default: the branch of the
+    /// latest scan)
+    async fn scans(
+        &self,
+        ctx: &Context<'_>,
+        branch: Option<String>,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 100))] last: u32,
+    ) -> async_graphql::Result<Vec<ScanRun>> {
+        let scans = store(ctx)?
+            .recent_scans(branch.as_deref(), last as usize)
+            .await?;
+        Ok(scans.into_iter().map(ScanRun).collect())
+    }
+
+    /// Node and edge counts of the whole graph
+    async fn stats(&self, ctx: &Context<'_>) -> async_graphql::Result<Stats> {
+        Ok(Stats(store(ctx)?.stats().await?))
+    }
+}
+
+async fn file_at(client: &dyn GraphStore, path: &str) -> async_graphql::Result<Option<File>> {
+    let files = client.list_files(Some(path), Page::default()).await?;
+    Ok(files.into_iter().find(|f| f.path == path).map(File))
+}
+
+/// A symbol (function, type, field, ...) defined in a file
+pub(crate) struct Symbol(SymbolResult);
+
+#[Object]
+impl Symbol {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn qualified_name(&self) -> &str {
+        &self.0.qualified_name
+    }
+
+    async fn kind(&self) -> &str {
+        &self.0.kind
+    }
+
+    async fn file_path(&self) -> &str {
+        &self.0.file_path
+    }
+
+    async fn start_line(&self) -> i64 {
+        self.0.start_line
+    }
+
+    async fn end_line(&self) -> i64 {
+        self.0.end_line
+    }
+
+    /// The file defining the symbol
+    async fn file(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<File>> {
+        file_at(store(ctx)?, &self.0.file_path).await
+    }
+
+    /// References to this symbol
+    async fn callers(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Edge>> {
+        let refs = store(ctx)?
+            .find_references_to(&self.0.name, Page::default())
+            .await?;
+        Ok(refs
+            .into_iter()
+            .filter(|r| r.target_file == self.0.file_path)
+            .map(Edge)
+            .collect())
+    }
+
+    /// References made by this symbol
+    async fn callees(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Edge>> {
+        let refs = store(ctx)?
+            .find_references_from(&self.0.name, Page::default())
+            .await?;
+        Ok(refs
+            .into_iter()
+            .filter(|r| r.source_file == self.0.file_path)
+            .map(Edge)
+            .collect())
+    }
+}
+
+/// A scanned source file
+pub(crate) struct File(FileResult);
+
+#[Object]
+impl File {
+    async fn path(&self) -> &str {
+        &self.0.path
+    }
+
+    async fn language(&self) -> &str {
+        &self.0.language
+    }
+
+    async fn symbol_count(&self) -> i64 {
+        self.0.symbol_count
+    }
+
+    /// Symbols defined in the file, ordered by line
+    async fn symbols(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Symbol>> {
+        let symbols = store(ctx)?.symbols_in_file(&self.0.path).await?;
+        Ok(symbols
+            .into_iter()
+            .filter(|s| s.file_path == self.0.path)
+            .map(Symbol)
+            .collect())
+    }
+}
+
+/// A reference from one symbol to another
+pub(crate) struct Edge(ReferenceResult);
+
+#[Object]
+impl Edge {
+    async fn source_name(&self) -> &str {
+        &self.0.source_name
+    }
+
+    async fn source_file(&self) -> &str {
+        &self.0.source_file
+    }
+
+    /// Line of the reference in the source file
+    async fn source_line(&self) -> i64 {
+        self.0.source_line
+    }
+
+    async fn target_name(&self) -> &str {
+        &self.0.target_name
+    }
+
+    async fn target_file(&self) -> &str {
+        &self.0.target_file
+    }
+
+    /// First line of the target's definition
+    async fn target_line(&self) -> i64 {
+        self.0.target_line
+    }
+
+    /// The referencing symbol, the one enclosing the reference
+    async fn source(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Symbol>> {
+        let line = self.0.source_line;
+        let symbols = store(ctx)?.symbols_in_file(&self.0.source_file).await?;
+        Ok(symbols
+            .into_iter()
+            .find(|s| {
+                s.name == self.0.source_name
+                    && s.file_path == self.0.source_file
+                    && (s.start_line..=s.end_line).contains(&line)
+            })
+            .map(Symbol))
+    }
+
+    /// The referenced symbol
+    async fn target(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Symbol>> {
+        let symbols = store(ctx)?.symbols_in_file(&self.0.target_file).await?;
+        Ok(symbols
+            .into_iter()
+            .find(|s| {
+                s.name == self.0.target_name
+                    && s.file_path == self.0.target_file
+                    && s.start_line == self.0.target_line
+            })
+            .map(Symbol))
+    }
+}
+
+/// A scan of a commit
+pub(crate) struct ScanRun(ScanResult);
+
+#[Object]
+impl ScanRun {
+    async fn commit_sha(&self) -> &str {
+        &self.0.commit_sha
+    }
+
+    async fn branch(&self) -> &str {
+        &self.0.branch
+    }
+
+    async fn version(&self) -> &str {
+        &self.0.version
+    }
+
+    async fn scanned_at(&self) -> &str {
+        &self.0.scanned_at
+    }
+}
+
+/// Node and edge counts
+pub(crate) struct Stats(GraphStats);
+
+#[Object]
+impl Stats {
+    async fn commits(&self) -> i64 {
+        self.0.commits
+    }
+
+    async fn files(&self) -> i64 {
+        self.0.files
+    }
+
+    async fn symbols(&self) -> i64 {
+        self.0.symbols
+    }
+
+    async fn scan_runs(&self) -> i64 {
+        self.0.scan_runs
+    }
+
+    async fn references(&self) -> i64 {
+        self.0.references
+    }
+}
@@ -0,0 +1,184 @@
+//! HTTP server: Read-only JSON endpoints over the graph
+//!
+//! Every endpoint answers with JSON. Errors are `{"error": "..."}` with
+//! status 400 for bad parameters, 404 for unknown versions or routes and
+//! 500 when the store fails.
+//!
+//! | Endpoint | Answer |
+//! |---|---|
+//! | `GET /symbols?name=&in=` | Symbols whose name contains `name` |
+//! | `GET /files?pattern=` | Files of the latest scan |
+//! | `GET /refs-to/{symbol}` | References to a symbol |
+//! | `GET /refs-from/{symbol}` | References from a symbol |
+//! | `GET /stats` | Graph statistics |
+//! | `GET /diff?from=&to=` | Symbol diff between two versions |
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use mother_core::graph::diff::{diff_symbols, SymbolDiff};
+use mother_core::graph::{
+    FileResult, GraphStats, GraphStore, Page, PathScope, ReferenceResult, StoreError, SymbolResult,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+type Store = Arc<dyn GraphStore>;
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Serve `app` on `addr` until the process is stopped
+///
+/// # Errors
+/// Returns an error if the address cannot be bound or serving fails.
+pub(super) async fn listen(app: Router, addr: SocketAddr) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("HTTP server listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Routes of the API
+pub(crate) fn router(client: Store) -> Router {
+    Router::new()
+        .route("/symbols", get(symbols))
+        .route("/files", get(files))
+        .route("/refs-to/{symbol}", get(refs_to))
+        .route("/refs-from/{symbol}", get(refs_from))
+        .route("/stats", get(stats))
+        .route("/diff", get(diff))
+        .fallback(|| async { ApiError::new(StatusCode::NOT_FOUND, "No such endpoint") })
+        .with_state(client)
+}
+
+/// An error answered as JSON
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<StoreError> for ApiError {
+    fn from(e: StoreError) -> Self {
+        tracing::warn!("Query failed: {}", e);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({"error": self.message}))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolsParams {
+    name: Option<String>,
+    #[serde(rename = "in")]
+    scope: Option<String>,
+}
+
+async fn symbols(
+    State(client): State<Store>,
+    Query(params): Query<SymbolsParams>,
+) -> ApiResult<Vec<SymbolResult>> {
+    let name = params
+        .name
+        .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST, "Missing parameter 'name'"))?;
+    let scope = params
+        .scope
+        .as_deref()
+        .map(PathScope::new)
+        .transpose()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, format!("Invalid 'in': {e}")))?;
+    Ok(Json(
+        client
+            .find_symbols(&name, scope.as_ref(), Page::default())
+            .await?,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct FilesParams {
+    pattern: Option<String>,
+}
+
+async fn files(
+    State(client): State<Store>,
+    Query(params): Query<FilesParams>,
+) -> ApiResult<Vec<FileResult>> {
+    Ok(Json(
+        client
+            .list_files(params.pattern.as_deref(), Page::default())
+            .await?,
+    ))
+}
+
+async fn refs_to(
+    State(client): State<Store>,
+    Path(symbol): Path<String>,
+) -> ApiResult<Vec<ReferenceResult>> {
+    Ok(Json(
+        client.find_references_to(&symbol, Page::default()).await?,
+    ))
+}
+
+async fn refs_from(
+    State(client): State<Store>,
+    Path(symbol): Path<String>,
+) -> ApiResult<Vec<ReferenceResult>> {
+    Ok(Json(
+        client
+            .find_references_from(&symbol, Page::default())
+            .await?,
+    ))
+}
+
+async fn stats(State(client): State<Store>) -> ApiResult<GraphStats> {
+    Ok(Json(client.stats().await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffParams {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+async fn diff(
+    State(client): State<Store>,
+    Query(params): Query<DiffParams>,
+) -> ApiResult<SymbolDiff> {
+    let (Some(from), Some(to)) = (params.from, params.to) else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Parameters 'from' and 'to' are required",
+        ));
+    };
+    let from_sha = resolve(client.as_ref(), &from).await?;
+    let to_sha = resolve(client.as_ref(), &to).await?;
+    let old = client.symbols_at_commit(&from_sha).await?;
+    let new = client.symbols_at_commit(&to_sha).await?;
+    Ok(Json(diff_symbols(&from_sha, &old, &to_sha, &new)))
+}
+
+async fn resolve(client: &dyn GraphStore, version: &str) -> Result<String, ApiError> {
+    client.resolve_commit(Some(version)).await?.ok_or_else(|| {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("No scan found for version '{version}'"),
+        )
+    })
+}
@@ -0,0 +1,121 @@
+//! MCP server: JSON-RPC 2.0 over stdio, one message per line
+//!
+//! Implements the part of the Model Context Protocol a tool server needs:
+//! `initialize`, `ping`, `tools/list` and `tools/call`. Notifications are
+//! accepted and ignored. A tool that fails answers with `isError` so the
+//! agent sees the message; protocol errors answer with JSON-RPC errors.
+
+use anyhow::Result;
+use mother_core::graph::GraphStore;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::tools;
+
+/// Protocol versions the server speaks, oldest first
+const PROTOCOL_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", "2025-06-18"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Answer messages read from `reader` on `writer` until `reader` ends
+///
+/// # Errors
+/// Returns an error if reading or writing fails.
+pub(super) async fn serve(
+    client: &dyn GraphStore,
+    reader: impl AsyncBufRead + Unpin,
+    mut writer: impl AsyncWrite + Unpin,
+) -> Result<()> {
+    tracing::info!("MCP server ready on stdio");
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(client, &line).await {
+            writer.write_all(response.to_string().as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Answer one message; notifications get no answer
+pub(crate) async fn handle_message(client: &dyn GraphStore, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error(&Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let id = message.get("id")?.clone();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error(&id, INVALID_REQUEST, "Missing method"));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => initialize(&params),
+        "ping" => json!({}),
+        "tools/list" => json!({"tools": tools::definitions()}),
+        "tools/call" => match call_tool(client, &params).await {
+            Ok(result) => result,
+            Err(message) => return Some(error(&id, INVALID_PARAMS, &message)),
+        },
+        _ => {
+            let message = format!("Unknown method '{method}'");
+            return Some(error(&id, METHOD_NOT_FOUND, &message));
+        }
+    };
+    Some(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+}
+
+/// Agree on the client's protocol version if known, else offer the latest
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[PROTOCOL_VERSIONS.len() - 1]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": {"tools": {}},
+        "serverInfo": {"name": "mother", "version": env!("CARGO_PKG_VERSION")}
+    })
+}
+
+/// Run a tool; unknown tools are protocol errors, failing tools are results
+async fn call_tool(client: &dyn GraphStore, params: &Value) -> Result<Value, String> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !tools::exists(name) {
+        return Err(format!("Unknown tool '{name}'"));
+    }
+    let args = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    let (text, is_error) = match tools::call(client, name, &args).await {
+        Ok(value) => (
+            serde_json::to_string_pretty(&value).unwrap_or_default(),
+            false,
+        ),
+        Err(e) => (format!("{e:#}"), true),
+    };
+    Ok(json!({
+        "content": [{"type": "text", "text": text}],
+        "isError": is_error
+    }))
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message}
+    })
+}
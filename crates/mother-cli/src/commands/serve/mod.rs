@@ -0,0 +1,17 @@
+//! Serve module: Expose the graph to other programs
+//!
+//! [`mcp`] speaks the Model Context Protocol on stdin/stdout. The queries it
+//! offers live in [`tools`], independent of the protocol. [`http`] answers
+//! the same kind of queries as a JSON API, and [`graphql`] as a typed schema
+//! whose nested fields follow the graph.
+
+mod graphql;
+mod http;
+mod mcp;
+mod run;
+mod tools;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
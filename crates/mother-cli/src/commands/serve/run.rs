@@ -0,0 +1,37 @@
+//! Serve command: Run a server over the graph store until its client leaves
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+
+use super::{graphql, http, mcp};
+use crate::types::ServeCommands;
+
+/// Run the serve command
+///
+/// # Errors
+/// Returns an error if the connection fails or the server stops on an I/O
+/// error.
+pub async fn run(mode: ServeCommands, neo4j: &Neo4jConfig) -> Result<()> {
+    let client = Neo4jClient::connect(neo4j).await?;
+    run_with_store(mode, Arc::new(client)).await
+}
+
+/// Run the serve command against an open graph store
+///
+/// # Errors
+/// Returns an error if the server cannot listen or stops on an I/O error.
+pub async fn run_with_store(mode: ServeCommands, client: Arc<dyn GraphStore>) -> Result<()> {
+    match mode {
+        ServeCommands::Mcp => {
+            let (stdin, stdout) = (tokio::io::stdin(), tokio::io::stdout());
+            mcp::serve(client.as_ref(), tokio::io::BufReader::new(stdin), stdout).await
+        }
+        ServeCommands::Http { listen } => http::listen(http::router(client), listen.addr()).await,
+        ServeCommands::Graphql { listen } => {
+            http::listen(graphql::router(client), listen.addr()).await
+        }
+    }
+}
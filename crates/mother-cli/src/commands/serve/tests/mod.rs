@@ -0,0 +1,47 @@
+//! Tests for serve module
+
+#![allow(clippy::unwrap_used)]
+
+use mother_core::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+mod tests_graphql;
+mod tests_http;
+mod tests_mcp;
+
+/// Store with `main` calling `run` calling `parse`, defined in `path`
+pub(super) async fn seeded_store(path: &str) -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new(path, "hash-main", "rust", "abc123")
+        .await
+        .unwrap();
+    let symbol = |name: &str, line| SymbolNode {
+        qualified_name: format!("crate::{name}"),
+        ..SymbolNode::new(name, name, SymbolKind::Function, path, line, line + 2)
+    };
+    store
+        .create_symbols_batch(
+            &[symbol("main", 1), symbol("run", 5), symbol("parse", 9)],
+            "hash-main",
+        )
+        .await
+        .unwrap();
+    for (source, target, line) in [("main", "run", 2), ("run", "parse", 6)] {
+        store
+            .create_edge(&Edge {
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                kind: EdgeKind::References,
+                line: Some(line),
+                column: Some(4),
+            })
+            .await
+            .unwrap();
+    }
+    store
+}
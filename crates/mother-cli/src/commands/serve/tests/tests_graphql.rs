@@ -0,0 +1,109 @@
+//! Tests for the GraphQL schema and endpoint
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::seeded_store;
+use crate::commands::serve::graphql::{router, schema};
+
+/// Run a query against the seeded store and return its data
+async fn query(query: &str) -> Value {
+    let store = seeded_store("/repo/src/main.rs").await;
+    let response = schema(Arc::new(store)).execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[tokio::test]
+async fn test_symbol_with_callers_and_their_files_in_one_query() {
+    let data = query(
+        r#"{ symbols(name: "run", exact: true) {
+                qualifiedName
+                callers { sourceLine source { name file { path } } }
+                callees { target { name startLine } } } }"#,
+    )
+    .await;
+
+    assert_eq!(
+        data["symbols"],
+        json!([{
+            "qualifiedName": "crate::run",
+            "callers": [{
+                "sourceLine": 2,
+                "source": {"name": "main", "file": {"path": "/repo/src/main.rs"}}
+            }],
+            "callees": [{"target": {"name": "parse", "startLine": 9}}]
+        }])
+    );
+}
+
+#[tokio::test]
+async fn test_files_scans_and_stats() {
+    let data = query(
+        r#"{ file(path: "/repo/src/main.rs") { language symbols { name } }
+             files(pattern: "nowhere") { path }
+             scans { commitSha }
+             stats { symbols references } }"#,
+    )
+    .await;
+
+    assert_eq!(data["file"]["language"], "rust");
+    let names: Vec<&str> = data["file"]["symbols"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["main", "run", "parse"]);
+    assert_eq!(data["files"], json!([]));
+    assert_eq!(data["scans"], json!([{"commitSha": "abc123"}]));
+    assert_eq!(data["stats"], json!({"symbols": 3, "references": 2}));
+}
+
+#[tokio::test]
+async fn test_invalid_queries_report_errors() {
+    let store = Arc::new(seeded_store("/repo/src/main.rs").await);
+    let schema = schema(store);
+
+    let scope = schema
+        .execute(r#"{ symbols(name: "x", in: "src/[z-a].rs") { name } }"#)
+        .await;
+    assert_eq!(scope.errors.len(), 1);
+
+    let last = schema.execute("{ scans(last: 0) { commitSha } }").await;
+    assert_eq!(last.errors.len(), 1);
+
+    let deep = "{ symbols(name: \"run\") { callers { source { callers { source { callers { \
+        source { callers { source { callers { source { name } } } } } } } } } } }";
+    assert!(!schema.execute(deep).await.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_endpoint_executes_posted_queries() {
+    let store = seeded_store("/repo/src/main.rs").await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = router(Arc::new(store));
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let body = json!({"query": "{ stats { files } }"}).to_string();
+    let request = format!(
+        "POST /graphql HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    let body: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["data"]["stats"]["files"], 1);
+}
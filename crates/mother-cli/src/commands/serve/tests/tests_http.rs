@@ -0,0 +1,83 @@
+//! Tests for the HTTP server, over a real socket
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::seeded_store;
+use crate::commands::serve::http::router;
+
+/// Serve the seeded store on a free port and return its address
+async fn start() -> std::net::SocketAddr {
+    let store = seeded_store("/repo/src/main.rs").await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = router(Arc::new(store));
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    addr
+}
+
+/// GET a path and return the status code and JSON body
+async fn get(addr: std::net::SocketAddr, path: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[tokio::test]
+async fn test_endpoints_answer_json() {
+    let addr = start().await;
+
+    let (status, symbols) = get(addr, "/symbols?name=par").await;
+    assert_eq!(status, 200);
+    assert_eq!(symbols[0]["qualified_name"], "crate::parse");
+
+    let (_, files) = get(addr, "/files").await;
+    assert_eq!(files[0]["path"], "/repo/src/main.rs");
+
+    let (_, refs_to) = get(addr, "/refs-to/run").await;
+    assert_eq!(refs_to[0]["source_name"], "main");
+
+    let (_, refs_from) = get(addr, "/refs-from/run").await;
+    assert_eq!(refs_from[0]["target_name"], "parse");
+
+    let (status, stats) = get(addr, "/stats").await;
+    assert_eq!(status, 200);
+    assert!(stats.is_object());
+
+    let (status, diff) = get(addr, "/diff?from=abc&to=abc123").await;
+    assert_eq!(status, 200);
+    assert_eq!(diff["from"], "abc123");
+}
+
+#[tokio::test]
+async fn test_errors_answer_json_with_status() {
+    let addr = start().await;
+
+    let (status, body) = get(addr, "/symbols").await;
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("name"));
+
+    let (status, _) = get(addr, "/symbols?name=x&in=src/[z-a].rs").await;
+    assert_eq!(status, 400);
+
+    let (status, _) = get(addr, "/diff?from=abc").await;
+    assert_eq!(status, 400);
+
+    let (status, body) = get(addr, "/diff?from=v9&to=abc").await;
+    assert_eq!(status, 404);
+    assert!(body["error"].as_str().unwrap().contains("v9"));
+
+    let (status, _) = get(addr, "/nowhere").await;
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,196 @@
+//! Tests for the MCP server and its graph tools
+
+#![allow(clippy::unwrap_used)]
+
+use mother_core::graph::SqliteStore;
+use serde_json::{json, Value};
+
+use super::seeded_store;
+use crate::commands::serve::mcp::{handle_message, serve};
+
+async fn request(store: &SqliteStore, method: &str, params: Value) -> Value {
+    let message = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    handle_message(store, &message.to_string()).await.unwrap()
+}
+
+/// Call a tool and parse the JSON text of its result
+async fn call_tool(store: &SqliteStore, name: &str, arguments: Value) -> Value {
+    let response = request(
+        store,
+        "tools/call",
+        json!({"name": name, "arguments": arguments}),
+    )
+    .await;
+    let result = &response["result"];
+    assert_eq!(result["isError"], false, "{name} failed: {result}");
+    serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_initialize_agrees_on_protocol_version() {
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let known = request(
+        &store,
+        "initialize",
+        json!({"protocolVersion": "2024-11-05"}),
+    )
+    .await;
+    assert_eq!(known["id"], 1);
+    assert_eq!(known["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(known["result"]["serverInfo"]["name"], "mother");
+    assert!(known["result"]["capabilities"]["tools"].is_object());
+
+    let unknown = request(
+        &store,
+        "initialize",
+        json!({"protocolVersion": "1999-01-01"}),
+    )
+    .await;
+    assert_eq!(unknown["result"]["protocolVersion"], "2025-06-18");
+}
+
+#[tokio::test]
+async fn test_tools_list_describes_every_tool() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let response = request(&store, "tools/list", json!({})).await;
+
+    let tools = response["result"]["tools"].as_array().unwrap();
+    let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(
+        names,
+        [
+            "find_symbols",
+            "symbols_in_file",
+            "references_to",
+            "references_from",
+            "call_graph",
+            "symbol_source",
+            "stats"
+        ]
+    );
+    assert!(tools
+        .iter()
+        .all(|t| t["inputSchema"]["type"] == "object" && t["description"].is_string()));
+}
+
+#[tokio::test]
+async fn test_query_tools_answer_from_the_store() {
+    let store = seeded_store("/repo/src/main.rs").await;
+
+    let symbols = call_tool(&store, "find_symbols", json!({"pattern": "par"})).await;
+    assert_eq!(symbols[0]["qualified_name"], "crate::parse");
+
+    let in_file = call_tool(&store, "symbols_in_file", json!({"path": "main.rs"})).await;
+    assert_eq!(in_file.as_array().unwrap().len(), 3);
+
+    let callers = call_tool(&store, "references_to", json!({"symbol": "run"})).await;
+    assert_eq!(callers[0]["source_name"], "main");
+
+    let callees = call_tool(&store, "references_from", json!({"symbol": "run"})).await;
+    assert_eq!(callees[0]["target_name"], "parse");
+
+    let stats = call_tool(&store, "stats", json!({})).await;
+    assert!(stats.is_object());
+}
+
+#[tokio::test]
+async fn test_call_graph_follows_references_to_depth() {
+    let store = seeded_store("/repo/src/main.rs").await;
+
+    let callees = call_tool(&store, "call_graph", json!({"symbol": "main"})).await;
+    let targets: Vec<&str> = callees["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["target_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(targets, ["run", "parse"]);
+
+    let shallow = call_tool(&store, "call_graph", json!({"symbol": "main", "depth": 1})).await;
+    assert_eq!(shallow["edges"].as_array().unwrap().len(), 1);
+
+    let callers = call_tool(
+        &store,
+        "call_graph",
+        json!({"symbol": "parse", "direction": "callers"}),
+    )
+    .await;
+    assert_eq!(callers["edges"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_symbol_source_reads_lines_from_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.rs");
+    let text: Vec<String> = (1..=12).map(|n| format!("line {n}")).collect();
+    std::fs::write(&path, text.join("\n")).unwrap();
+    let store = seeded_store(path.to_str().unwrap()).await;
+
+    let sources = call_tool(&store, "symbol_source", json!({"symbol": "run"})).await;
+    assert_eq!(sources[0]["source"], "line 5\nline 6\nline 7");
+}
+
+#[tokio::test]
+async fn test_failing_tool_reports_error_result() {
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let missing = request(&store, "tools/call", json!({"name": "references_to"})).await;
+    assert_eq!(missing["result"]["isError"], true);
+    let text = missing["result"]["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("symbol"), "{text}");
+
+    let depth = request(
+        &store,
+        "tools/call",
+        json!({"name": "call_graph", "arguments": {"symbol": "main", "depth": 9}}),
+    )
+    .await;
+    assert_eq!(depth["result"]["isError"], true);
+}
+
+#[tokio::test]
+async fn test_protocol_errors() {
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let unknown_tool = request(&store, "tools/call", json!({"name": "rm_rf"})).await;
+    assert_eq!(unknown_tool["error"]["code"], -32602);
+
+    let unknown_method = request(&store, "resources/list", json!({})).await;
+    assert_eq!(unknown_method["error"]["code"], -32601);
+
+    let parse_error = handle_message(&store, "{not json").await.unwrap();
+    assert_eq!(parse_error["error"]["code"], -32700);
+    assert_eq!(parse_error["id"], Value::Null);
+
+    let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+    assert!(handle_message(&store, &notification.to_string())
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_serve_answers_each_request_on_its_own_line() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let input = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}),
+    ]
+    .map(|m| m.to_string())
+    .join("\n");
+    let mut output = Vec::new();
+
+    serve(&store, input.as_bytes(), &mut output).await.unwrap();
+
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[1],
+        json!({"jsonrpc": "2.0", "id": 2, "result": {}})
+    );
+}
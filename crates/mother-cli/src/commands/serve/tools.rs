@@ -0,0 +1,245 @@
+//! Graph tools: The queries servers offer, with JSON arguments and results
+//!
+//! Each tool has a name, a description and a JSON schema of its arguments,
+//! as listed by [`definitions`], and is run by [`call`]. Symbols are
+//! matched by name as in the `query` command. Line numbers are 1-based.
+
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{bail, Context, Result};
+use mother_core::graph::{GraphStore, Page, PathScope, ReferenceResult, SymbolResult};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Deepest call graph a tool call may ask for
+const MAX_CALL_GRAPH_DEPTH: u64 = 5;
+
+/// Most symbol definitions `symbol_source` returns
+const MAX_SOURCES: usize = 10;
+
+/// Names, descriptions and argument schemas of the tools
+#[must_use]
+pub(crate) fn definitions() -> Value {
+    let symbol = json!({
+        "type": "object",
+        "properties": {
+            "symbol": {"type": "string", "description": "Exact symbol name"}
+        },
+        "required": ["symbol"]
+    });
+    json!([
+        {
+            "name": "find_symbols",
+            "description": "Find symbols whose name contains a pattern (case-insensitive). \
+                Returns id, name, qualified name, kind, file and line range.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": {"type": "string", "description": "Part of the symbol name"},
+                    "in": {
+                        "type": "string",
+                        "description": "Only symbols in files under this absolute directory \
+                            or matching this glob"
+                    }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "symbols_in_file",
+            "description": "List the symbols defined in a file, ordered by line.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path or path suffix"}
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "references_to",
+            "description": "Find the symbols that reference a symbol, with file and line.",
+            "inputSchema": symbol
+        },
+        {
+            "name": "references_from",
+            "description": "Find the symbols a symbol references, with file and line.",
+            "inputSchema": symbol
+        },
+        {
+            "name": "call_graph",
+            "description": "Follow references from a symbol (callees) or to it (callers) \
+                up to a depth and return the edges found.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "symbol": {"type": "string", "description": "Exact symbol name"},
+                    "direction": {"type": "string", "enum": ["callees", "callers"]},
+                    "depth": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_CALL_GRAPH_DEPTH,
+                        "description": "Levels to follow (default 2)"
+                    }
+                },
+                "required": ["symbol"]
+            }
+        },
+        {
+            "name": "symbol_source",
+            "description": "Read the source code of the symbols with a name or qualified \
+                name from the scanned files on disk.",
+            "inputSchema": symbol
+        },
+        {
+            "name": "stats",
+            "description": "Count the commits, files, symbols and edges in the graph.",
+            "inputSchema": {"type": "object", "properties": {}}
+        }
+    ])
+}
+
+/// Whether a tool of this name exists
+#[must_use]
+pub(crate) fn exists(name: &str) -> bool {
+    definitions()
+        .as_array()
+        .is_some_and(|tools| tools.iter().any(|t| t["name"] == name))
+}
+
+/// Run a tool and return its result
+///
+/// # Errors
+/// Returns an error if the tool does not exist, an argument is missing or
+/// invalid, or the query fails.
+pub(crate) async fn call(client: &dyn GraphStore, name: &str, args: &Value) -> Result<Value> {
+    match name {
+        "find_symbols" => {
+            let scope = optional_arg(args, "in")?
+                .map(PathScope::new)
+                .transpose()
+                .context("Invalid 'in' scope")?;
+            let pattern = string_arg(args, "pattern")?;
+            to_json(
+                &client
+                    .find_symbols(pattern, scope.as_ref(), Page::default())
+                    .await?,
+            )
+        }
+        "symbols_in_file" => to_json(&client.symbols_in_file(string_arg(args, "path")?).await?),
+        "references_to" => to_json(
+            &client
+                .find_references_to(string_arg(args, "symbol")?, Page::default())
+                .await?,
+        ),
+        "references_from" => to_json(
+            &client
+                .find_references_from(string_arg(args, "symbol")?, Page::default())
+                .await?,
+        ),
+        "call_graph" => call_graph(client, args).await,
+        "symbol_source" => symbol_source(client, string_arg(args, "symbol")?).await,
+        "stats" => to_json(&client.stats().await?),
+        _ => bail!("Unknown tool '{name}'"),
+    }
+}
+
+/// Breadth-first walk over references, one query per symbol reached
+async fn call_graph(client: &dyn GraphStore, args: &Value) -> Result<Value> {
+    let symbol = string_arg(args, "symbol")?;
+    let callers = match optional_arg(args, "direction")? {
+        None | Some("callees") => false,
+        Some("callers") => true,
+        Some(other) => bail!("Invalid direction '{other}', expected callees or callers"),
+    };
+    let depth = match args.get("depth") {
+        None => 2,
+        Some(depth) => depth
+            .as_u64()
+            .filter(|d| (1..=MAX_CALL_GRAPH_DEPTH).contains(d))
+            .with_context(|| format!("'depth' must be 1 to {MAX_CALL_GRAPH_DEPTH}"))?,
+    };
+
+    let mut edges: Vec<ReferenceResult> = Vec::new();
+    let mut seen = HashSet::from([symbol.to_string()]);
+    let mut queue = VecDeque::from([(symbol.to_string(), 1)]);
+    while let Some((name, level)) = queue.pop_front() {
+        let refs = match callers {
+            true => client.find_references_to(&name, Page::default()).await?,
+            false => client.find_references_from(&name, Page::default()).await?,
+        };
+        for r in refs {
+            let next = if callers {
+                &r.source_name
+            } else {
+                &r.target_name
+            };
+            if level < depth && seen.insert(next.clone()) {
+                queue.push_back((next.clone(), level + 1));
+            }
+            edges.push(r);
+        }
+    }
+    Ok(json!({"symbol": symbol, "depth": depth, "edges": edges}))
+}
+
+/// A symbol's definition as read from disk
+#[derive(Debug, Serialize)]
+struct SymbolSource {
+    qualified_name: String,
+    kind: String,
+    file_path: String,
+    start_line: i64,
+    end_line: i64,
+    source: String,
+}
+
+/// Read the lines of the symbols named `symbol` from their files
+async fn symbol_source(client: &dyn GraphStore, symbol: &str) -> Result<Value> {
+    let matches: Vec<SymbolResult> = client
+        .find_symbols(symbol, None, Page::default())
+        .await?
+        .into_iter()
+        .filter(|s| s.name == symbol || s.qualified_name == symbol)
+        .take(MAX_SOURCES)
+        .collect();
+
+    let mut sources = Vec::with_capacity(matches.len());
+    for s in matches {
+        let text = tokio::fs::read_to_string(&s.file_path)
+            .await
+            .with_context(|| format!("Failed to read {}", s.file_path))?;
+        let skip = usize::try_from(s.start_line.saturating_sub(1)).unwrap_or_default();
+        let take = usize::try_from(s.end_line - s.start_line + 1).unwrap_or_default();
+        sources.push(SymbolSource {
+            source: text
+                .lines()
+                .skip(skip)
+                .take(take)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            qualified_name: s.qualified_name,
+            kind: s.kind,
+            file_path: s.file_path,
+            start_line: s.start_line,
+            end_line: s.end_line,
+        });
+    }
+    to_json(&sources)
+}
+
+fn string_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str> {
+    optional_arg(args, name)?.with_context(|| format!("Missing argument '{name}'"))
+}
+
+fn optional_arg<'a>(args: &'a Value, name: &str) -> Result<Option<&'a str>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => bail!("Argument '{name}' must be a string"),
+    }
+}
+
+fn to_json(value: &impl Serialize) -> Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
@@ -0,0 +1,13 @@
+//! Watch command: Keep the graph of a repository up to date as files change
+//!
+//! After an initial scan, filesystem notifications are collected into
+//! batches that end once no change arrives for a short quiet period. Each
+//! batch re-runs the scan phases for just the changed files and updates the
+//! scanned commit in place.
+
+mod run;
+
+pub use run::{run, run_with_store};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,164 @@
+//! Watch command implementation
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::neo4j::Neo4jConfig;
+use mother_core::graph::GraphStore;
+use mother_core::lsp::LspServerManager;
+use mother_core::scanner::Language;
+use mother_scan::ScanOptions;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::info;
+
+/// Quiet period after the last change before a batch is rescanned
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Filesystem notifications as delivered by the watcher
+pub(super) type EventReceiver = UnboundedReceiver<notify::Result<Event>>;
+
+/// Run the watch command
+///
+/// # Errors
+/// Returns an error if the initial scan, Neo4j operations or the file
+/// watcher fail.
+pub async fn run(path: &Path, neo4j: &Neo4jConfig, options: &ScanOptions) -> Result<()> {
+    let client = mother_scan::connect_neo4j(neo4j).await?;
+    run_with_store(path, &client, options).await
+}
+
+/// Run the watch command against an open graph store
+///
+/// Returns when interrupted with Ctrl+C.
+///
+/// # Errors
+/// Returns an error if the initial scan or the file watcher fail.
+pub async fn run_with_store(
+    path: &Path,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+) -> Result<()> {
+    mother_scan::run_with_store(path, client, options).await?;
+
+    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let commit_sha = ScanRun::new(abs_path.display().to_string())
+        .with_git_info()
+        .commit_sha
+        .unwrap_or_default();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(&abs_path, RecursiveMode::Recursive)?;
+    info!(
+        "Watching {} for changes (Ctrl+C to stop)",
+        abs_path.display()
+    );
+
+    let lsp_manager = options.lsp_manager(&abs_path);
+    let watch = Watch {
+        abs_path: &abs_path,
+        client,
+        commit_sha: &commit_sha,
+        lsp_manager: &lsp_manager,
+        options,
+    };
+    watch.run(&mut rx).await;
+    mother_scan::shutdown_lsp(&lsp_manager).await;
+    Ok(())
+}
+
+/// Everything a batch rescan needs
+struct Watch<'a> {
+    abs_path: &'a Path,
+    client: &'a dyn GraphStore,
+    commit_sha: &'a str,
+    lsp_manager: &'a LspServerManager,
+    options: &'a ScanOptions,
+}
+
+impl Watch<'_> {
+    /// Rescan batches of changes until Ctrl+C or the watcher stops
+    async fn run(&self, rx: &mut EventReceiver) {
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            let batch = tokio::select! {
+                batch = next_batch(rx, DEBOUNCE) => batch,
+                _ = &mut ctrl_c => {
+                    info!("Stopping watch");
+                    return;
+                }
+            };
+            match batch {
+                Some(paths) if paths.is_empty() => {}
+                Some(paths) => self.rescan(&paths).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Update the graph for one batch, logging failures so watching goes on
+    async fn rescan(&self, paths: &[PathBuf]) {
+        info!("{} files changed, updating graph...", paths.len());
+        match mother_scan::rescan_paths(
+            self.abs_path,
+            self.client,
+            self.commit_sha,
+            self.lsp_manager,
+            self.options,
+            paths,
+        )
+        .await
+        {
+            Ok(summary) => info!(
+                "✓ Graph updated: {} files rescanned, {} removed",
+                summary.updated, summary.removed
+            ),
+            Err(e) => tracing::warn!("Failed to update graph: {}", e),
+        }
+    }
+}
+
+/// Wait for the next batch of changed source files
+///
+/// After the first event, further events join the batch until none arrive
+/// for `quiet`. Returns `None` once the watcher is gone.
+pub(super) async fn next_batch(rx: &mut EventReceiver, quiet: Duration) -> Option<Vec<PathBuf>> {
+    let mut paths = BTreeSet::new();
+    collect(&mut paths, rx.recv().await?);
+    while let Ok(Some(event)) = tokio::time::timeout(quiet, rx.recv()).await {
+        collect(&mut paths, event);
+    }
+    Some(paths.into_iter().collect())
+}
+
+fn collect(paths: &mut BTreeSet<PathBuf>, event: notify::Result<Event>) {
+    match event {
+        Ok(event) => paths.extend(changed_paths(&event).cloned()),
+        Err(e) => tracing::warn!("File watch error: {}", e),
+    }
+}
+
+/// Source files whose content an event created, changed or removed
+pub(super) fn changed_paths(event: &Event) -> impl Iterator<Item = &PathBuf> {
+    let relevant = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+    event
+        .paths
+        .iter()
+        .filter(move |path| relevant && is_source_file(path))
+}
+
+/// Whether a path has a scanned language and is outside `.git`
+fn is_source_file(path: &Path) -> bool {
+    Language::from_path(path).is_some() && !path.components().any(|c| c.as_os_str() == ".git")
+}
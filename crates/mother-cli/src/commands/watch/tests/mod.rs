@@ -0,0 +1,3 @@
+//! Tests for watch module
+
+mod tests_run;
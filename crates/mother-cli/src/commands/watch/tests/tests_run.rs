@@ -0,0 +1,161 @@
+//! Tests for change batching and in-place rescans
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::{LspServerConfig, LspServerManager};
+use mother_core::scanner::Language;
+use mother_scan::{rescan_paths, RescanSummary, ScanOptions};
+use notify::event::{AccessKind, CreateKind, ModifyKind, RemoveKind};
+use notify::{Event, EventKind};
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+use super::super::run::{changed_paths, next_batch};
+
+const SHA: &str = "abc123";
+
+fn event(kind: EventKind, path: &str) -> Event {
+    Event::new(kind).add_path(PathBuf::from(path))
+}
+
+// ============================================================================
+// Tests for changed_paths
+// ============================================================================
+
+#[test]
+fn test_changed_paths_keeps_source_file_changes() {
+    let modified = event(EventKind::Modify(ModifyKind::Any), "/repo/src/lib.rs");
+    let removed = event(EventKind::Remove(RemoveKind::File), "/repo/app.py");
+
+    assert_eq!(changed_paths(&modified).count(), 1);
+    assert_eq!(changed_paths(&removed).count(), 1);
+}
+
+#[test]
+fn test_changed_paths_skips_access_and_other_files() {
+    let access = event(EventKind::Access(AccessKind::Any), "/repo/src/lib.rs");
+    let notes = event(EventKind::Create(CreateKind::File), "/repo/notes.txt");
+    let git = event(
+        EventKind::Modify(ModifyKind::Any),
+        "/repo/.git/hooks/hook.py",
+    );
+
+    assert_eq!(changed_paths(&access).count(), 0);
+    assert_eq!(changed_paths(&notes).count(), 0);
+    assert_eq!(changed_paths(&git).count(), 0);
+}
+
+// ============================================================================
+// Tests for next_batch
+// ============================================================================
+
+#[tokio::test]
+async fn test_next_batch_merges_events_until_quiet() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tx.send(Ok(event(EventKind::Modify(ModifyKind::Any), "/repo/b.rs")))
+        .unwrap();
+    tx.send(Ok(event(EventKind::Modify(ModifyKind::Any), "/repo/a.rs")))
+        .unwrap();
+    tx.send(Ok(event(EventKind::Modify(ModifyKind::Any), "/repo/b.rs")))
+        .unwrap();
+
+    let batch = next_batch(&mut rx, Duration::from_millis(20))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        batch,
+        vec![PathBuf::from("/repo/a.rs"), PathBuf::from("/repo/b.rs")]
+    );
+}
+
+#[tokio::test]
+async fn test_next_batch_ends_when_watcher_is_gone() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    drop(tx);
+
+    assert!(next_batch(&mut rx, Duration::from_millis(20))
+        .await
+        .is_none());
+}
+
+// ============================================================================
+// Tests for rescan_paths
+// ============================================================================
+
+/// A manager whose Rust server cannot start, so no real server is spawned
+fn offline_lsp(root: &Path) -> LspServerManager {
+    let mut manager = LspServerManager::new(root);
+    manager.register_server(LspServerConfig {
+        language: Language::Rust,
+        command: "mother-test-nonexistent-lsp-server".to_string(),
+        args: vec![],
+        root_path: root.to_path_buf(),
+        init_options: None,
+    });
+    manager
+}
+
+/// A store whose commit contains `path` with outdated content
+async fn store_with_file(path: &Path) -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit(SHA);
+    store.create_scan_run(&run).await.unwrap();
+    store
+        .create_file_if_new(&path.display().to_string(), "old-hash", "rust", SHA)
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_rescan_links_new_content_of_changed_file() {
+    let repo = TempDir::new().unwrap();
+    let root = repo.path().canonicalize().unwrap();
+    let path = root.join("lib.rs");
+    std::fs::write(&path, "fn changed() {}\n").unwrap();
+    let store = store_with_file(&path).await;
+    let lsp = offline_lsp(&root);
+
+    let summary = rescan_paths(&root, &store, SHA, &lsp, &ScanOptions::default(), &[path])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        RescanSummary {
+            updated: 1,
+            removed: 0
+        }
+    );
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.files, 2);
+    assert_eq!(stats.contains, 1);
+}
+
+#[tokio::test]
+async fn test_rescan_unlinks_deleted_file() {
+    let repo = TempDir::new().unwrap();
+    let root = repo.path().canonicalize().unwrap();
+    let path = root.join("gone.rs");
+    let store = store_with_file(&path).await;
+    let lsp = offline_lsp(&root);
+
+    let summary = rescan_paths(&root, &store, SHA, &lsp, &ScanOptions::default(), &[path])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        RescanSummary {
+            updated: 0,
+            removed: 1
+        }
+    );
+    assert_eq!(store.stats().await.unwrap().contains, 0);
+}
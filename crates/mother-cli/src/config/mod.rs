@@ -0,0 +1,750 @@
+//! Config module: Load `mother.toml` settings for scans and connections
+//!
+//! The config file lives in the repository root as `mother.toml` or
+//! `.mother/config.toml`. Every setting is optional; command-line flags
+//! always take precedence over values from the file.
+//!
+//! ```toml
+//! [storage]
+//! backend = "neo4j"     # or "sqlite"
+//! db_path = "graph.db"  # SQLite database file
+//! read_only = true      # refuse writes, e.g. for dashboard credentials
+//!
+//! [neo4j]
+//! uri = "bolt://neo4j.internal:7687"
+//! user = "mother"
+//! database = "code"
+//!
+//! [scan]
+//! include = ["src/**"]
+//! exclude = ["**/generated/**"]
+//! enrichers = ["hover", "signature", "visibility"]
+//! hover_kinds = ["functions", "methods"]  # hover only these symbols
+//! hover_concurrency = 8                   # hover requests in flight per file
+//!
+//! [embedding]          # embed symbols during scans for `query similar`
+//! provider = "openai"   # or "onnx" (needs the matching build feature)
+//! model = "text-embedding-3-small"
+//!
+//! [linking]            # link symbols across languages during scans
+//! qualified_names = true
+//!
+//! [linking.patterns]    # shared identifiers: first capture group or match
+//! route = '(?:route|fetch)\("([^"]+)"\)'
+//!
+//! [lsp.rust]
+//! command = "ra-multiplex"
+//! args = ["client"]
+//!
+//! [lsp.python]
+//! version = "1.1.380"   # pinned by `mother install-servers`
+//! max_concurrent_requests = 4
+//! max_open_documents = 200  # close the least recently opened beyond this
+//! readiness = "symbols"     # or "indexing", "progress", "immediate"
+//!
+//! [lsp.servers.elixir]  # a language mother has no built-in support for
+//! command = "elixir-ls"
+//! extensions = ["ex", "exs"]
+//!
+//! [telemetry]             # for scans in containers
+//! log_format = "json"     # or "text"
+//! otlp_endpoint = "http://otel-collector:4318"  # needs the `otel` feature
+//!
+//! [queries.public-structs]  # run with `mother query saved public-structs`
+//! description = "Public structs"
+//! cypher = "MATCH (s:Symbol {kind: 'struct', visibility: 'pub'}) RETURN s.name AS name"
+//! sql = "SELECT name FROM symbols WHERE kind = 'struct' AND visibility = 'pub'"
+//! ```
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use mother_core::embed::Embedder;
+#[cfg(feature = "onnx")]
+use mother_core::embed::OnnxEmbedder;
+#[cfg(feature = "openai")]
+use mother_core::embed::OpenAiEmbedder;
+use mother_core::enrich::{parse_kind, HoverSettings, DEFAULT_HOVER_CONCURRENCY};
+use mother_core::graph::ids::SymbolIdScheme;
+use mother_core::graph::linking::{LinkConventions, LinkPattern};
+use mother_core::graph::neo4j::Neo4jConfig;
+use mother_core::graph::SqliteStore;
+use mother_core::lsp::{LspServerConfig, Readiness};
+use mother_core::scanner::{parse_size, Language};
+use serde::Deserialize;
+
+use crate::commands::query::SavedQuery;
+use crate::credentials;
+use crate::telemetry::{LogFormat, LogSettings};
+
+/// Default Neo4j connection URI
+pub const DEFAULT_NEO4J_URI: &str = "bolt://localhost:7687";
+
+/// Default Neo4j username
+pub const DEFAULT_NEO4J_USER: &str = "neo4j";
+
+/// Default SQLite database file for the `sqlite` backend
+pub const DEFAULT_SQLITE_PATH: &str = "mother.db";
+
+/// Config file locations, relative to the repository root, in lookup order
+pub const CONFIG_FILE_NAMES: &[&str] = &["mother.toml", ".mother/config.toml"];
+
+/// Contents of a `mother.toml` file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MotherConfig {
+    pub storage: StorageSettings,
+    pub neo4j: Neo4jSettings,
+    pub scan: ScanSettings,
+    pub embedding: Option<EmbeddingSettings>,
+    pub linking: Option<LinkingSettings>,
+    pub lsp: LspSettings,
+    pub telemetry: TelemetrySettings,
+    /// Saved queries for `query saved`, keyed by name
+    pub queries: BTreeMap<String, SavedQuery>,
+}
+
+/// Graph storage backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Neo4j server over Bolt
+    #[default]
+    Neo4j,
+    /// Embedded SQLite database file
+    Sqlite,
+    /// Private in-memory database, gone when the command exits; for scans
+    /// that only print statistics or write an export
+    Memory,
+}
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "neo4j" => Ok(Self::Neo4j),
+            "sqlite" => Ok(Self::Sqlite),
+            "memory" => Ok(Self::Memory),
+            _ => Err(format!(
+                "Unknown backend: {s} (expected neo4j, sqlite or memory)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Neo4j => write!(f, "neo4j"),
+            Self::Sqlite => write!(f, "sqlite"),
+            Self::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+/// `[storage]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSettings {
+    pub backend: Option<Backend>,
+    /// SQLite database file, relative to the working directory
+    pub db_path: Option<PathBuf>,
+    /// Refuse writes to the store, like `--read-only`
+    pub read_only: bool,
+}
+
+/// `[telemetry]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    pub log_format: Option<LogFormat>,
+    /// OTLP/HTTP collector to export traces to
+    pub otlp_endpoint: Option<String>,
+}
+
+/// `[neo4j]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Neo4jSettings {
+    pub uri: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+}
+
+/// `[scan]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanSettings {
+    /// Globs of files to scan; empty scans everything
+    pub include: Vec<String>,
+    /// Globs of files to skip
+    pub exclude: Vec<String>,
+    /// Largest file scanned, e.g. `"2MB"`; larger files are skipped
+    pub max_file_size: Option<String>,
+    /// Markers in the first lines of a file that skip it as generated;
+    /// unset uses `@generated` and `DO NOT EDIT`, empty keeps every file
+    pub generated_markers: Option<Vec<String>>,
+    /// Enrichers to run in Phase 2, in order
+    pub enrichers: Option<Vec<String>>,
+    /// Most references stored per symbol; above it a sample is stored
+    pub max_references: Option<usize>,
+    /// Most symbols held in memory between Phases 2 and 3; beyond it they
+    /// are spilled to a temporary file
+    pub symbol_buffer: Option<NonZeroUsize>,
+    /// How stored symbols are named: `content-hash`, `qualified-name` or
+    /// `uuid`
+    pub symbol_ids: Option<SymbolIdScheme>,
+    /// Kinds of symbols the hover enricher asks about, e.g. `["functions",
+    /// "methods"]`; unset hovers every symbol
+    pub hover_kinds: Option<Vec<String>>,
+    /// Most hover requests in flight at once for a file
+    pub hover_concurrency: Option<NonZeroUsize>,
+}
+
+impl ScanSettings {
+    /// Largest file scanned, in bytes
+    ///
+    /// # Errors
+    /// Returns an error if `max_file_size` is not a size.
+    pub fn max_file_size(&self) -> Result<Option<u64>> {
+        self.max_file_size
+            .as_deref()
+            .map(|size| parse_size(size).map_err(|e| anyhow::anyhow!("[scan] max_file_size: {e}")))
+            .transpose()
+    }
+
+    /// Settings of the hover enricher
+    ///
+    /// # Errors
+    /// Returns an error if `hover_kinds` names an unknown symbol kind.
+    pub fn hover(&self) -> Result<HoverSettings> {
+        let kinds = self
+            .hover_kinds
+            .iter()
+            .flatten()
+            .map(|name| parse_kind(name).map_err(|e| anyhow::anyhow!("[scan] hover_kinds: {e}")))
+            .collect::<Result<_>>()?;
+        Ok(HoverSettings {
+            kinds,
+            concurrency: self.hover_concurrency.unwrap_or(DEFAULT_HOVER_CONCURRENCY),
+        })
+    }
+}
+
+/// Service or runtime computing symbol embeddings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// OpenAI-compatible embeddings API (`openai` feature)
+    OpenAi,
+    /// Local ONNX model (`onnx` feature)
+    Onnx,
+}
+
+/// `[embedding]` section; scans embed symbols when it is present
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddingSettings {
+    pub provider: EmbeddingProvider,
+    /// Model name for `openai`, model file for `onnx`
+    pub model: String,
+    /// Embeddings endpoint for `openai` [default: OpenAI's]
+    pub url: Option<String>,
+    /// `tokenizer.json` of the model for `onnx` [default: next to the model]
+    pub tokenizer: Option<PathBuf>,
+}
+
+/// `[linking]` section; scans link symbols of different languages with
+/// LINKS_TO edges when it is present
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkingSettings {
+    /// Link symbols with identical qualified names [default: true]
+    pub qualified_names: bool,
+    /// Regular expressions extracting shared identifiers from source code,
+    /// keyed by name; only matches of the same pattern link
+    pub patterns: BTreeMap<String, String>,
+}
+
+impl Default for LinkingSettings {
+    fn default() -> Self {
+        Self {
+            qualified_names: true,
+            patterns: BTreeMap::new(),
+        }
+    }
+}
+
+/// `[lsp]` section
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LspSettings {
+    /// Servers for languages without built-in support, keyed by name
+    pub servers: BTreeMap<String, CustomServerSettings>,
+    /// Server overrides keyed by language name (e.g. `rust`, `python`)
+    #[serde(flatten)]
+    pub languages: BTreeMap<String, LspServerSettings>,
+}
+
+/// `[lsp.<language>]` section
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LspServerSettings {
+    /// Server command replacing the default; unset keeps the default server
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub init_options: Option<serde_json::Value>,
+    /// Server version to install with `install-servers`
+    pub version: Option<String>,
+    /// Most requests in flight on the server at once; unset is unbounded
+    pub max_concurrent_requests: Option<NonZeroUsize>,
+    /// Most documents open on the server at once; unset keeps every scanned
+    /// file open until its phases are done
+    pub max_open_documents: Option<NonZeroUsize>,
+    /// How to wait for the server to load the workspace; unset uses the
+    /// language's default
+    pub readiness: Option<Readiness>,
+}
+
+/// `[lsp.servers.<name>]` section
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomServerSettings {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// File extensions the server handles, without the leading dot
+    pub extensions: Vec<String>,
+    /// LSP language id sent when opening files; defaults to the section name
+    pub language_id: Option<String>,
+    pub init_options: Option<serde_json::Value>,
+    /// Most requests in flight on the server at once; unset is unbounded
+    pub max_concurrent_requests: Option<NonZeroUsize>,
+    /// Most documents open on the server at once; unset keeps every scanned
+    /// file open until its phases are done
+    pub max_open_documents: Option<NonZeroUsize>,
+    /// How to wait for the server to load the workspace; unset uses the
+    /// language's default
+    pub readiness: Option<Readiness>,
+}
+
+/// Neo4j connection flags given on the command line or environment
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Neo4jArgs {
+    /// Neo4j connection URI [default: bolt://localhost:7687]
+    #[arg(long = "neo4j-uri", env = "MOTHER_NEO4J_URI")]
+    pub uri: Option<String>,
+
+    /// Neo4j username [default: neo4j]
+    #[arg(long = "neo4j-user", env = "MOTHER_NEO4J_USER")]
+    pub user: Option<String>,
+
+    /// Neo4j password (prefer the environment variable or keyring over the flag)
+    #[arg(
+        long = "neo4j-password",
+        env = "MOTHER_NEO4J_PASSWORD",
+        hide_env_values = true
+    )]
+    pub password: Option<String>,
+
+    /// Neo4j database name
+    #[arg(long = "neo4j-database", env = "MOTHER_NEO4J_DATABASE")]
+    pub database: Option<String>,
+}
+
+impl LinkingSettings {
+    /// Compile the configured linking conventions
+    ///
+    /// # Errors
+    /// Returns an error if a pattern is not a valid regular expression.
+    pub fn conventions(&self) -> Result<LinkConventions> {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|(name, pattern)| {
+                LinkPattern::new(name, pattern)
+                    .with_context(|| format!("Invalid linking pattern '{name}'"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(LinkConventions {
+            qualified_names: self.qualified_names,
+            patterns,
+        })
+    }
+}
+
+impl EmbeddingSettings {
+    /// Create the configured embedder
+    ///
+    /// The `openai` provider reads its API key from `MOTHER_EMBEDDING_API_KEY`,
+    /// then `OPENAI_API_KEY`; local servers may need none.
+    ///
+    /// # Errors
+    /// Returns an error if mother was built without the provider's feature
+    /// or the model cannot be loaded.
+    pub fn embedder(&self) -> Result<Arc<dyn Embedder>> {
+        match self.provider {
+            EmbeddingProvider::OpenAi => self.openai_embedder(),
+            EmbeddingProvider::Onnx => self.onnx_embedder(),
+        }
+    }
+
+    #[cfg(feature = "openai")]
+    #[allow(clippy::unnecessary_wraps)]
+    fn openai_embedder(&self) -> Result<Arc<dyn Embedder>> {
+        let mut embedder = OpenAiEmbedder::new(&self.model);
+        if let Some(url) = &self.url {
+            embedder = embedder.with_url(url);
+        }
+        let api_key =
+            std::env::var("MOTHER_EMBEDDING_API_KEY").or_else(|_| std::env::var("OPENAI_API_KEY"));
+        if let Ok(api_key) = api_key {
+            embedder = embedder.with_api_key(api_key);
+        }
+        Ok(Arc::new(embedder))
+    }
+
+    #[cfg(not(feature = "openai"))]
+    #[allow(clippy::unused_self)]
+    fn openai_embedder(&self) -> Result<Arc<dyn Embedder>> {
+        anyhow::bail!("The openai embedding provider needs mother built with the `openai` feature")
+    }
+
+    #[cfg(feature = "onnx")]
+    fn onnx_embedder(&self) -> Result<Arc<dyn Embedder>> {
+        let model = PathBuf::from(&self.model);
+        let tokenizer = self
+            .tokenizer
+            .clone()
+            .unwrap_or_else(|| model.with_file_name("tokenizer.json"));
+        Ok(Arc::new(OnnxEmbedder::from_files(&model, &tokenizer)?))
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    #[allow(clippy::unused_self)]
+    fn onnx_embedder(&self) -> Result<Arc<dyn Embedder>> {
+        anyhow::bail!("The onnx embedding provider needs mother built with the `onnx` feature")
+    }
+}
+
+/// Storage backend flags, including the Neo4j connection flags
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct StoreArgs {
+    /// Graph storage backend: neo4j, sqlite, or memory for scans that keep
+    /// nothing [default: neo4j]
+    #[arg(long, env = "MOTHER_BACKEND")]
+    pub backend: Option<Backend>,
+
+    /// SQLite database file for the sqlite backend [default: mother.db]
+    #[arg(long, env = "MOTHER_DB_PATH")]
+    pub db_path: Option<PathBuf>,
+
+    /// Refuse writes: stored data, indexes, migrations and mutating raw
+    /// queries
+    #[arg(long, env = "MOTHER_READ_ONLY")]
+    pub read_only: bool,
+
+    #[command(flatten)]
+    pub neo4j: Neo4jArgs,
+}
+
+impl MotherConfig {
+    /// Find the config file for a repository root
+    #[must_use]
+    pub fn discover(root: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Parse a config file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not valid config.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parse config from TOML text
+    ///
+    /// # Errors
+    /// Returns an error if the text is not valid config.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Load the explicit config file, or discover one in `root`
+    ///
+    /// Returns the default (empty) config when no file is found.
+    ///
+    /// # Errors
+    /// Returns an error if the config file exists but cannot be loaded.
+    pub fn resolve(explicit: Option<&Path>, root: &Path) -> Result<Self> {
+        match explicit
+            .map(Path::to_path_buf)
+            .or_else(|| Self::discover(root))
+        {
+            Some(path) => {
+                tracing::debug!("Loading config from {}", path.display());
+                Self::load(&path)
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Build the Neo4j connection config, letting flags override the file
+    ///
+    /// Flags may also come from `MOTHER_NEO4J_*` environment variables. The
+    /// password falls back to the OS keyring when neither source sets it.
+    ///
+    /// # Errors
+    /// Returns an error if no password is found or the keyring fails.
+    pub fn neo4j_config(&self, args: Neo4jArgs) -> Result<Neo4jConfig> {
+        let settings = &self.neo4j;
+        let uri = args
+            .uri
+            .or_else(|| settings.uri.clone())
+            .unwrap_or_else(|| DEFAULT_NEO4J_URI.to_string());
+        let user = args
+            .user
+            .or_else(|| settings.user.clone())
+            .unwrap_or_else(|| DEFAULT_NEO4J_USER.to_string());
+
+        let password = match args.password.or_else(|| settings.password.clone()) {
+            Some(password) => password,
+            None => credentials::load_password(&uri, &user)?.context(
+                "Neo4j password required (--neo4j-password, MOTHER_NEO4J_PASSWORD, \
+                 neo4j.password in mother.toml, or the OS keyring)",
+            )?,
+        };
+
+        let mut config = Neo4jConfig::new(uri, user, password);
+        if let Some(database) = args.database.or_else(|| settings.database.clone()) {
+            config = config.with_database(database);
+        }
+        Ok(config)
+    }
+
+    /// Build the Neo4j connection config for the store flags, read-only if
+    /// they or the `[storage]` section ask for it
+    ///
+    /// # Errors
+    /// Returns an error if no password is found or the keyring fails.
+    pub fn neo4j_store_config(&self, args: &StoreArgs) -> Result<Neo4jConfig> {
+        let config = self.neo4j_config(args.neo4j.clone())?;
+        Ok(config.with_read_only(self.read_only(args)))
+    }
+
+    /// Whether the flags or the `[storage]` section make the store
+    /// read-only
+    #[must_use]
+    pub fn read_only(&self, args: &StoreArgs) -> bool {
+        args.read_only || self.storage.read_only
+    }
+
+    /// Logging selected by flags, then the `[telemetry]` section
+    ///
+    /// Flags may also come from `MOTHER_LOG_FORMAT` and
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    #[must_use]
+    pub fn log_settings(
+        &self,
+        verbose: bool,
+        format: Option<LogFormat>,
+        otlp_endpoint: Option<String>,
+    ) -> LogSettings {
+        LogSettings {
+            verbose,
+            format: format.or(self.telemetry.log_format).unwrap_or_default(),
+            otlp_endpoint: otlp_endpoint.or_else(|| self.telemetry.otlp_endpoint.clone()),
+        }
+    }
+
+    /// Storage backend selected by flags, then the `[storage]` section
+    #[must_use]
+    pub fn backend(&self, args: &StoreArgs) -> Backend {
+        args.backend.or(self.storage.backend).unwrap_or_default()
+    }
+
+    /// SQLite database file selected by flags, then the `[storage]` section
+    #[must_use]
+    pub fn sqlite_path(&self, args: &StoreArgs) -> PathBuf {
+        args.db_path
+            .clone()
+            .or_else(|| self.storage.db_path.clone())
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SQLITE_PATH))
+    }
+
+    /// Open the SQLite store for the `sqlite` backend
+    ///
+    /// Creates the database file and schema if they don't exist yet, unless
+    /// the store is read-only.
+    ///
+    /// # Errors
+    /// Returns an error if the database cannot be opened, or the backend is
+    /// `memory`, which holds nothing for other commands to read.
+    pub fn open_sqlite(&self, args: &StoreArgs) -> Result<SqliteStore> {
+        if self.backend(args) == Backend::Memory {
+            anyhow::bail!(
+                "The memory backend keeps nothing between commands; only `scan` can use it"
+            );
+        }
+        let path = self.sqlite_path(args);
+        tracing::debug!("Opening SQLite graph store at {}", path.display());
+        let store = if self.read_only(args) {
+            SqliteStore::open_read_only(&path)
+        } else {
+            SqliteStore::open(&path)
+        };
+        store.with_context(|| format!("Failed to open {}", path.display()))
+    }
+
+    /// LSP server overrides and custom servers as server configs rooted at
+    /// `root`
+    ///
+    /// Sections without a `command` keep the default server.
+    ///
+    /// # Errors
+    /// Returns an error if a section names an unknown language or a custom
+    /// server is invalid.
+    pub fn lsp_servers(&self, root: &Path) -> Result<Vec<LspServerConfig>> {
+        let mut servers = Vec::new();
+        for (language, settings) in self.lsp_settings()? {
+            if let Some(command) = &settings.command {
+                servers.push(LspServerConfig {
+                    language,
+                    command: command.clone(),
+                    args: settings.args.clone(),
+                    root_path: root.to_path_buf(),
+                    init_options: settings.init_options.clone(),
+                });
+            }
+        }
+        for (language, settings) in self.custom_servers()? {
+            servers.push(LspServerConfig {
+                language,
+                command: settings.command.clone(),
+                args: settings.args.clone(),
+                root_path: root.to_path_buf(),
+                init_options: settings.init_options.clone(),
+            });
+        }
+        Ok(servers)
+    }
+
+    /// `[lsp.*]` sections keyed by their parsed language
+    ///
+    /// # Errors
+    /// Returns an error if a section names an unknown language.
+    pub fn lsp_settings(&self) -> Result<Vec<(Language, &LspServerSettings)>> {
+        self.lsp
+            .languages
+            .iter()
+            .map(|(name, settings)| {
+                let language: Language = name
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid [lsp.{name}] section: {e}"))?;
+                Ok((language, settings))
+            })
+            .collect()
+    }
+
+    /// Request limits set by `max_concurrent_requests`, per language
+    ///
+    /// # Errors
+    /// Returns an error if a section names an unknown language or a custom
+    /// server is invalid.
+    pub fn request_limits(&self) -> Result<Vec<(Language, NonZeroUsize)>> {
+        let builtin = self
+            .lsp_settings()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.max_concurrent_requests?)));
+        let custom = self
+            .custom_servers()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.max_concurrent_requests?)));
+        Ok(builtin.chain(custom).collect())
+    }
+
+    /// Open document limits set by `max_open_documents`, per language
+    ///
+    /// # Errors
+    /// Returns an error if a section names an unknown language or a custom
+    /// server is invalid.
+    pub fn open_document_limits(&self) -> Result<Vec<(Language, NonZeroUsize)>> {
+        let builtin = self
+            .lsp_settings()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.max_open_documents?)));
+        let custom = self
+            .custom_servers()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.max_open_documents?)));
+        Ok(builtin.chain(custom).collect())
+    }
+
+    /// Readiness strategies set by `readiness`, per language
+    ///
+    /// # Errors
+    /// Returns an error if a section names an unknown language or a custom
+    /// server is invalid.
+    pub fn readiness(&self) -> Result<Vec<(Language, Readiness)>> {
+        let builtin = self
+            .lsp_settings()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.readiness?)));
+        let custom = self
+            .custom_servers()?
+            .into_iter()
+            .filter_map(|(language, settings)| Some((language, settings.readiness?)));
+        Ok(builtin.chain(custom).collect())
+    }
+
+    /// Languages registered in `[lsp.servers.*]` with the file extensions
+    /// they claim
+    ///
+    /// # Errors
+    /// Returns an error if a custom server is invalid.
+    pub fn custom_languages(&self) -> Result<Vec<(Language, Vec<String>)>> {
+        Ok(self
+            .custom_servers()?
+            .into_iter()
+            .map(|(language, settings)| (language, settings.extensions.clone()))
+            .collect())
+    }
+
+    /// `[lsp.servers.*]` sections keyed by their language
+    ///
+    /// Built-in languages are configured in `[lsp.<language>]` instead, so a
+    /// language id naming one is rejected, as is a server without extensions.
+    fn custom_servers(&self) -> Result<Vec<(Language, &CustomServerSettings)>> {
+        self.lsp
+            .servers
+            .iter()
+            .map(|(name, settings)| {
+                let id = settings.language_id.as_deref().unwrap_or(name);
+                if let Ok(builtin) = id.parse::<Language>() {
+                    anyhow::bail!(
+                        "Invalid [lsp.servers.{name}] section: {builtin} is built in, \
+                         configure its server in [lsp.{builtin}]"
+                    );
+                }
+                if settings.extensions.is_empty() {
+                    anyhow::bail!("Invalid [lsp.servers.{name}] section: no extensions given");
+                }
+                Ok((Language::Other(id.to_string()), settings))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
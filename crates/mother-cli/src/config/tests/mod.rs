@@ -0,0 +1,4 @@
+//! Tests for config module
+
+mod tests_load;
+mod tests_merge;
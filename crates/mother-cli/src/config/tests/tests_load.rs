@@ -0,0 +1,250 @@
+//! Tests for config discovery and parsing
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+use std::num::NonZeroUsize;
+
+use tempfile::TempDir;
+
+use mother_core::graph::ids::SymbolIdScheme;
+use mother_core::graph::model::SymbolKind;
+
+use crate::config::{EmbeddingProvider, MotherConfig};
+
+#[test]
+fn test_parse_empty_config() {
+    assert_eq!(MotherConfig::parse("").unwrap(), MotherConfig::default());
+}
+
+#[test]
+fn test_hover_settings_parse_plural_kinds() {
+    let config = MotherConfig::parse(
+        r#"
+        [scan]
+        hover_kinds = ["functions", "method"]
+        hover_concurrency = 2
+        "#,
+    )
+    .unwrap();
+    let hover = config.scan.hover().unwrap();
+    assert_eq!(hover.kinds, vec![SymbolKind::Function, SymbolKind::Method]);
+    assert_eq!(hover.concurrency.get(), 2);
+
+    let config = MotherConfig::parse("[scan]\nhover_kinds = [\"gadgets\"]").unwrap();
+    assert!(config.scan.hover().is_err());
+    assert!(MotherConfig::parse("[scan]\nhover_concurrency = 0").is_err());
+}
+
+#[test]
+fn test_parse_full_config() {
+    let config = MotherConfig::parse(
+        r#"
+        [neo4j]
+        uri = "bolt://db:7687"
+        user = "mother"
+        database = "code"
+
+        [scan]
+        include = ["src/**"]
+        exclude = ["**/generated/**"]
+        enrichers = ["hover", "signature"]
+        max_references = 500
+        symbol_buffer = 100000
+        max_file_size = "2MB"
+        generated_markers = ["Code generated"]
+        symbol_ids = "qualified-name"
+
+        [lsp.rust]
+        command = "ra-multiplex"
+        args = ["client"]
+
+        [lsp.python]
+        command = "pylsp"
+        init_options = { plugins = { pycodestyle = { enabled = false } } }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.neo4j.uri.as_deref(), Some("bolt://db:7687"));
+    assert_eq!(config.neo4j.database.as_deref(), Some("code"));
+    assert!(config.neo4j.password.is_none());
+    assert_eq!(config.scan.include, vec!["src/**"]);
+    assert_eq!(config.scan.exclude, vec!["**/generated/**"]);
+    assert_eq!(
+        config.scan.enrichers,
+        Some(vec!["hover".to_string(), "signature".to_string()])
+    );
+    assert_eq!(config.scan.max_references, Some(500));
+    assert_eq!(config.scan.symbol_buffer, NonZeroUsize::new(100_000));
+    assert_eq!(config.scan.max_file_size().unwrap(), Some(2 << 20));
+    assert_eq!(
+        config.scan.generated_markers,
+        Some(vec!["Code generated".to_string()])
+    );
+    assert_eq!(config.scan.symbol_ids, Some(SymbolIdScheme::QualifiedName));
+    assert_eq!(config.lsp.languages["rust"].args, vec!["client"]);
+    assert!(config.lsp.languages["python"].args.is_empty());
+    assert!(config.lsp.languages["python"].init_options.is_some());
+}
+
+#[test]
+fn test_parse_embedding_section() {
+    let config = MotherConfig::parse(
+        r#"
+        [embedding]
+        provider = "onnx"
+        model = "models/minilm.onnx"
+        "#,
+    )
+    .unwrap();
+
+    let embedding = config.embedding.unwrap();
+    assert_eq!(embedding.provider, EmbeddingProvider::Onnx);
+    assert_eq!(embedding.model, "models/minilm.onnx");
+    assert!(embedding.url.is_none() && embedding.tokenizer.is_none());
+    assert!(MotherConfig::default().embedding.is_none());
+    assert!(MotherConfig::parse("[embedding]\nprovider = \"cohere\"\nmodel = \"m\"").is_err());
+}
+
+#[cfg(not(feature = "onnx"))]
+#[test]
+fn test_embedder_needs_provider_feature() {
+    let config =
+        MotherConfig::parse("[embedding]\nprovider = \"onnx\"\nmodel = \"m.onnx\"").unwrap();
+    let error = config.embedding.unwrap().embedder().unwrap_err();
+    assert!(error.to_string().contains("`onnx` feature"), "{error}");
+}
+
+#[test]
+fn test_parse_queries_section() {
+    let config = MotherConfig::parse(
+        r#"
+        [queries.large-files]
+        description = "Files longer than a line count"
+        args = ["lines"]
+        sql = "SELECT path FROM files WHERE line_count > $lines"
+        "#,
+    )
+    .unwrap();
+
+    let query = &config.queries["large-files"];
+    assert_eq!(query.args, ["lines"]);
+    assert!(query.cypher.is_none());
+    assert!(query.sql.is_some());
+    assert!(MotherConfig::parse("[queries.x]\nquery = \"RETURN 1\"").is_err());
+}
+
+#[test]
+fn test_parse_linking_section() {
+    let config = MotherConfig::parse(
+        r#"
+        [linking.patterns]
+        route = '(?:route|fetch)\("([^"]+)"\)'
+        "#,
+    )
+    .unwrap();
+
+    let linking = config.linking.unwrap();
+    assert!(linking.qualified_names);
+    let conventions = linking.conventions().unwrap();
+    assert_eq!(conventions.patterns.len(), 1);
+    assert_eq!(conventions.patterns[0].name, "route");
+    assert!(MotherConfig::default().linking.is_none());
+
+    let config = MotherConfig::parse(
+        "[linking]
+qualified_names = false",
+    )
+    .unwrap();
+    assert!(!config.linking.unwrap().qualified_names);
+}
+
+#[test]
+fn test_linking_rejects_invalid_pattern() {
+    let config = MotherConfig::parse(
+        "[linking.patterns]
+broken = 'route('",
+    )
+    .unwrap();
+    let error = config.linking.unwrap().conventions().unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Invalid linking pattern 'broken'"),
+        "{error}"
+    );
+}
+
+#[test]
+fn test_parse_rejects_unknown_fields() {
+    assert!(MotherConfig::parse("[neo4j]\nhost = \"db\"").is_err());
+    assert!(MotherConfig::parse("[scanner]\ninclude = []").is_err());
+}
+
+#[test]
+fn test_discover_prefers_root_file() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".mother")).unwrap();
+    fs::write(temp_dir.path().join(".mother/config.toml"), "").unwrap();
+    fs::write(temp_dir.path().join("mother.toml"), "").unwrap();
+
+    assert_eq!(
+        MotherConfig::discover(temp_dir.path()),
+        Some(temp_dir.path().join("mother.toml"))
+    );
+}
+
+#[test]
+fn test_discover_dot_mother_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".mother")).unwrap();
+    fs::write(temp_dir.path().join(".mother/config.toml"), "").unwrap();
+
+    assert_eq!(
+        MotherConfig::discover(temp_dir.path()),
+        Some(temp_dir.path().join(".mother/config.toml"))
+    );
+}
+
+#[test]
+fn test_resolve_without_file_is_default() {
+    let temp_dir = TempDir::new().unwrap();
+    assert_eq!(
+        MotherConfig::resolve(None, temp_dir.path()).unwrap(),
+        MotherConfig::default()
+    );
+}
+
+#[test]
+fn test_resolve_explicit_missing_file_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let missing = temp_dir.path().join("missing.toml");
+    assert!(MotherConfig::resolve(Some(&missing), temp_dir.path()).is_err());
+}
+
+#[test]
+fn test_resolve_explicit_file_wins() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("mother.toml"),
+        "[neo4j]\nuser = \"repo\"",
+    )
+    .unwrap();
+    let explicit = temp_dir.path().join("ci.toml");
+    fs::write(&explicit, "[neo4j]\nuser = \"ci\"").unwrap();
+
+    let config = MotherConfig::resolve(Some(&explicit), temp_dir.path()).unwrap();
+    assert_eq!(config.neo4j.user.as_deref(), Some("ci"));
+}
+
+#[test]
+fn test_invalid_max_file_size_names_the_setting() {
+    let config = MotherConfig::parse("[scan]\nmax_file_size = \"huge\"").unwrap();
+    let error = config.scan.max_file_size().unwrap_err();
+    assert!(
+        error.to_string().contains("[scan] max_file_size"),
+        "{error}"
+    );
+    assert_eq!(MotherConfig::default().scan.max_file_size().unwrap(), None);
+}
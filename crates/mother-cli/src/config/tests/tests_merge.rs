@@ -0,0 +1,415 @@
+//! Tests for merging config values with command-line flags
+
+#![allow(clippy::unwrap_used)]
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use clap::Parser;
+use mother_core::lsp::Readiness;
+use mother_core::scanner::Language;
+use serial_test::serial;
+
+use crate::config::{
+    Backend, MotherConfig, Neo4jArgs, StoreArgs, DEFAULT_NEO4J_URI, DEFAULT_NEO4J_USER,
+    DEFAULT_SQLITE_PATH,
+};
+use crate::telemetry::{LogFormat, LogSettings};
+
+fn config_with_neo4j() -> MotherConfig {
+    MotherConfig::parse(
+        r#"
+        [neo4j]
+        uri = "bolt://db:7687"
+        user = "mother"
+        password = "from-file"
+        database = "code"
+        "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_neo4j_config_uses_file_values() {
+    let neo4j = config_with_neo4j()
+        .neo4j_config(Neo4jArgs::default())
+        .unwrap();
+
+    assert_eq!(neo4j.uri, "bolt://db:7687");
+    assert_eq!(neo4j.user, "mother");
+    assert_eq!(neo4j.password, "from-file");
+    assert_eq!(neo4j.database.as_deref(), Some("code"));
+}
+
+#[test]
+fn test_neo4j_config_flags_override_file() {
+    let args = Neo4jArgs {
+        uri: Some("bolt://other:7687".to_string()),
+        user: None,
+        password: Some("from-flag".to_string()),
+        database: None,
+    };
+    let neo4j = config_with_neo4j().neo4j_config(args).unwrap();
+
+    assert_eq!(neo4j.uri, "bolt://other:7687");
+    assert_eq!(neo4j.user, "mother");
+    assert_eq!(neo4j.password, "from-flag");
+}
+
+#[test]
+fn test_neo4j_config_defaults() {
+    let args = Neo4jArgs {
+        password: Some("pw".to_string()),
+        ..Neo4jArgs::default()
+    };
+    let neo4j = MotherConfig::default().neo4j_config(args).unwrap();
+
+    assert_eq!(neo4j.uri, DEFAULT_NEO4J_URI);
+    assert_eq!(neo4j.user, DEFAULT_NEO4J_USER);
+    assert!(neo4j.database.is_none());
+}
+
+#[test]
+fn test_neo4j_config_requires_password() {
+    let result = MotherConfig::default().neo4j_config(Neo4jArgs::default());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_lsp_servers_rooted_at_repo() {
+    let config = MotherConfig::parse("[lsp.rust]\ncommand = \"ra-multiplex\"").unwrap();
+    let servers = config.lsp_servers(Path::new("/repo")).unwrap();
+
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].language, Language::Rust);
+    assert_eq!(servers[0].command, "ra-multiplex");
+    assert_eq!(servers[0].root_path, Path::new("/repo"));
+}
+
+#[test]
+fn test_lsp_servers_unknown_language() {
+    let config = MotherConfig::parse("[lsp.cobol]\ncommand = \"cobol-ls\"").unwrap();
+    assert!(config.lsp_servers(Path::new("/repo")).is_err());
+}
+
+#[test]
+fn test_lsp_servers_skip_sections_without_command() {
+    let config = MotherConfig::parse("[lsp.python]\nversion = \"1.1.380\"").unwrap();
+    assert!(config.lsp_servers(Path::new("/repo")).unwrap().is_empty());
+    assert_eq!(
+        config.lsp.languages["python"].version.as_deref(),
+        Some("1.1.380")
+    );
+}
+
+#[test]
+fn test_lsp_servers_include_custom_servers() {
+    let config = MotherConfig::parse(
+        r#"
+        [lsp.servers.elixir]
+        command = "elixir-ls"
+        extensions = ["ex", "exs"]
+
+        [lsp.servers.templates]
+        command = "jinja-lsp"
+        args = ["--stdio"]
+        extensions = ["j2"]
+        language_id = "jinja"
+        "#,
+    )
+    .unwrap();
+    let servers = config.lsp_servers(Path::new("/repo")).unwrap();
+
+    assert_eq!(servers.len(), 2);
+    assert_eq!(servers[0].language, Language::Other("elixir".into()));
+    assert_eq!(servers[0].command, "elixir-ls");
+    assert_eq!(servers[1].language, Language::Other("jinja".into()));
+    assert_eq!(servers[1].args, vec!["--stdio"]);
+    assert_eq!(
+        config.custom_languages().unwrap(),
+        vec![
+            (
+                Language::Other("elixir".into()),
+                vec!["ex".into(), "exs".into()]
+            ),
+            (Language::Other("jinja".into()), vec!["j2".into()]),
+        ]
+    );
+}
+
+#[test]
+fn test_custom_servers_reject_builtin_languages() {
+    let config = MotherConfig::parse(
+        "[lsp.servers.rust]\ncommand = \"ra-multiplex\"\nextensions = [\"rs\"]",
+    )
+    .unwrap();
+    let err = config.lsp_servers(Path::new("/repo")).unwrap_err();
+    assert!(err.to_string().contains("[lsp.rust]"));
+}
+
+#[test]
+fn test_custom_servers_require_extensions() {
+    let config =
+        MotherConfig::parse("[lsp.servers.elixir]\ncommand = \"elixir-ls\"\nextensions = []")
+            .unwrap();
+    assert!(config.custom_languages().is_err());
+    assert!(MotherConfig::parse("[lsp.servers.elixir]\ncommand = \"elixir-ls\"").is_err());
+}
+
+// ============================================================================
+// Storage backend
+// ============================================================================
+
+#[test]
+fn test_backend_defaults_to_neo4j() {
+    let config = MotherConfig::default();
+    let args = StoreArgs::default();
+    assert_eq!(config.backend(&args), Backend::Neo4j);
+    assert_eq!(config.sqlite_path(&args), Path::new(DEFAULT_SQLITE_PATH));
+}
+
+#[test]
+fn test_backend_from_storage_section() {
+    let config =
+        MotherConfig::parse("[storage]\nbackend = \"sqlite\"\ndb_path = \"data/graph.db\"")
+            .unwrap();
+    let args = StoreArgs::default();
+    assert_eq!(config.backend(&args), Backend::Sqlite);
+    assert_eq!(config.sqlite_path(&args), Path::new("data/graph.db"));
+}
+
+#[test]
+fn test_backend_flags_override_storage_section() {
+    let config =
+        MotherConfig::parse("[storage]\nbackend = \"sqlite\"\ndb_path = \"a.db\"").unwrap();
+    let args = StoreArgs {
+        backend: Some(Backend::Neo4j),
+        db_path: Some("b.db".into()),
+        ..StoreArgs::default()
+    };
+    assert_eq!(config.backend(&args), Backend::Neo4j);
+    assert_eq!(config.sqlite_path(&args), Path::new("b.db"));
+}
+
+#[test]
+fn test_unknown_backend_is_rejected() {
+    assert!("sqlite".parse::<Backend>().is_ok());
+    assert_eq!("memory".parse::<Backend>().unwrap(), Backend::Memory);
+    assert!("postgres".parse::<Backend>().is_err());
+    assert!(MotherConfig::parse("[storage]\nbackend = \"postgres\"").is_err());
+}
+
+#[test]
+fn test_log_settings_flags_override_telemetry_section() {
+    let config = MotherConfig::parse(
+        r#"
+        [telemetry]
+        log_format = "json"
+        otlp_endpoint = "http://collector:4318"
+        "#,
+    )
+    .unwrap();
+
+    let settings = config.log_settings(false, None, None);
+    assert_eq!(settings.format, LogFormat::Json);
+    assert_eq!(
+        settings.otlp_endpoint.as_deref(),
+        Some("http://collector:4318")
+    );
+
+    let settings = config.log_settings(
+        true,
+        Some(LogFormat::Text),
+        Some("http://local:4318".into()),
+    );
+    assert!(settings.verbose);
+    assert_eq!(settings.format, LogFormat::Text);
+    assert_eq!(settings.otlp_endpoint.as_deref(), Some("http://local:4318"));
+    assert_eq!(
+        MotherConfig::default().log_settings(false, None, None),
+        LogSettings::default()
+    );
+}
+
+#[test]
+fn test_open_sqlite_creates_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let args = StoreArgs {
+        db_path: Some(path.clone()),
+        ..StoreArgs::default()
+    };
+
+    assert!(MotherConfig::default().open_sqlite(&args).is_ok());
+    assert!(path.exists());
+}
+
+#[test]
+fn test_read_only_from_flag_or_storage_section() {
+    let args = StoreArgs {
+        neo4j: Neo4jArgs {
+            password: Some("pw".to_string()),
+            ..Neo4jArgs::default()
+        },
+        ..StoreArgs::default()
+    };
+    let config = MotherConfig::default();
+    assert!(!config.neo4j_store_config(&args).unwrap().read_only);
+
+    let flagged = StoreArgs {
+        read_only: true,
+        ..args.clone()
+    };
+    assert!(config.neo4j_store_config(&flagged).unwrap().read_only);
+
+    let config = MotherConfig::parse("[storage]\nread_only = true").unwrap();
+    assert!(config.neo4j_store_config(&args).unwrap().read_only);
+}
+
+#[test]
+fn test_open_sqlite_read_only_needs_an_existing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let args = StoreArgs {
+        db_path: Some(path.clone()),
+        read_only: true,
+        ..StoreArgs::default()
+    };
+
+    assert!(MotherConfig::default().open_sqlite(&args).is_err());
+    assert!(!path.exists());
+    let writable = StoreArgs {
+        read_only: false,
+        ..args.clone()
+    };
+    MotherConfig::default().open_sqlite(&writable).unwrap();
+    assert!(MotherConfig::default().open_sqlite(&args).is_ok());
+}
+
+#[derive(Parser)]
+struct StoreCli {
+    #[command(flatten)]
+    store: StoreArgs,
+}
+
+#[test]
+fn test_store_args_parse_backend_flags() {
+    let cli = StoreCli::try_parse_from(["mother", "--backend", "sqlite", "--db-path", "graph.db"])
+        .unwrap();
+    assert_eq!(cli.store.backend, Some(Backend::Sqlite));
+    assert_eq!(cli.store.db_path.as_deref(), Some(Path::new("graph.db")));
+}
+
+#[derive(Parser)]
+struct TestCli {
+    #[command(flatten)]
+    neo4j: Neo4jArgs,
+}
+
+#[test]
+#[serial]
+fn test_neo4j_args_read_environment() {
+    std::env::set_var("MOTHER_NEO4J_PASSWORD", "from-env");
+    std::env::set_var("MOTHER_NEO4J_URI", "bolt://env:7687");
+    let cli = TestCli::try_parse_from(["mother"]);
+    std::env::remove_var("MOTHER_NEO4J_PASSWORD");
+    std::env::remove_var("MOTHER_NEO4J_URI");
+
+    let neo4j = cli.unwrap().neo4j;
+    assert_eq!(neo4j.password.as_deref(), Some("from-env"));
+    assert_eq!(neo4j.uri.as_deref(), Some("bolt://env:7687"));
+}
+
+#[test]
+#[serial]
+fn test_neo4j_args_flag_overrides_environment() {
+    std::env::set_var("MOTHER_NEO4J_PASSWORD", "from-env");
+    let cli = TestCli::try_parse_from(["mother", "--neo4j-password", "from-flag"]);
+    std::env::remove_var("MOTHER_NEO4J_PASSWORD");
+
+    assert_eq!(cli.unwrap().neo4j.password.as_deref(), Some("from-flag"));
+}
+
+#[test]
+fn test_request_limits_from_lsp_sections() {
+    let config = MotherConfig::parse(
+        r#"
+        [lsp.python]
+        max_concurrent_requests = 4
+
+        [lsp.rust]
+        command = "ra-multiplex"
+
+        [lsp.servers.elixir]
+        command = "elixir-ls"
+        extensions = ["ex"]
+        max_concurrent_requests = 2
+        "#,
+    )
+    .unwrap();
+    let limits = config.request_limits().unwrap();
+
+    assert_eq!(
+        limits,
+        vec![
+            (Language::Python, NonZeroUsize::new(4).unwrap()),
+            (
+                Language::Other("elixir".into()),
+                NonZeroUsize::new(2).unwrap()
+            ),
+        ]
+    );
+    assert!(MotherConfig::parse("[lsp.python]\nmax_concurrent_requests = 0").is_err());
+}
+
+#[test]
+fn test_open_document_limits_from_lsp_sections() {
+    let config = MotherConfig::parse(
+        r#"
+        [lsp.typescript]
+        max_open_documents = 100
+
+        [lsp.servers.elixir]
+        command = "elixir-ls"
+        extensions = ["ex"]
+        max_open_documents = 20
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.open_document_limits().unwrap(),
+        vec![
+            (Language::TypeScript, NonZeroUsize::new(100).unwrap()),
+            (
+                Language::Other("elixir".into()),
+                NonZeroUsize::new(20).unwrap()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_readiness_from_lsp_sections() {
+    let config = MotherConfig::parse(
+        r#"
+        [lsp.python]
+        readiness = "symbols"
+
+        [lsp.servers.elixir]
+        command = "elixir-ls"
+        extensions = ["ex"]
+        readiness = "immediate"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.readiness().unwrap(),
+        vec![
+            (Language::Python, Readiness::Symbols),
+            (Language::Other("elixir".into()), Readiness::Immediate),
+        ]
+    );
+    assert!(MotherConfig::parse("[lsp.python]\nreadiness = \"eventually\"").is_err());
+}
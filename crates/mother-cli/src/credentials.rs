@@ -0,0 +1,65 @@
+//! Credentials: Neo4j passwords stored in the OS keyring
+//!
+//! Passwords are stored under the `mother` service with `<user>@<uri>` as the
+//! account name, so different servers and users keep separate entries.
+//! Without the `keyring` feature lookups find nothing and storing fails.
+
+use anyhow::Result;
+
+/// Keyring service name for Neo4j passwords
+pub const KEYRING_SERVICE: &str = "mother";
+
+/// Keyring account name for a Neo4j user on a server
+#[must_use]
+pub fn keyring_account(uri: &str, user: &str) -> String {
+    format!("{user}@{uri}")
+}
+
+/// Look up the stored password for a Neo4j user on a server
+///
+/// # Errors
+/// Returns an error if the keyring is unavailable.
+#[cfg(feature = "keyring")]
+pub fn load_password(uri: &str, user: &str) -> Result<Option<String>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(uri, user))?;
+    match entry.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Look up the stored password for a Neo4j user on a server
+///
+/// # Errors
+/// Never fails; built without keyring support.
+#[cfg(not(feature = "keyring"))]
+pub fn load_password(_uri: &str, _user: &str) -> Result<Option<String>> {
+    Ok(None)
+}
+
+/// Store the password for a Neo4j user on a server
+///
+/// # Errors
+/// Returns an error if the keyring is unavailable.
+#[cfg(feature = "keyring")]
+pub fn store_password(uri: &str, user: &str, password: &str) -> Result<()> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(uri, user))?;
+    entry.set_password(password)?;
+    Ok(())
+}
+
+/// Remove the stored password for a Neo4j user on a server
+///
+/// Succeeds if there was no stored password.
+///
+/// # Errors
+/// Returns an error if the keyring is unavailable.
+#[cfg(feature = "keyring")]
+pub fn delete_password(uri: &str, user: &str) -> Result<()> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(uri, user))?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,24 +1,43 @@
 //! mother-cli library
 //!
 //! This module exposes the internal functionality of mother-cli for testing purposes.
+//! It is not a stable API; library users should depend on the `mother` crate.
 
+use std::io::{self, Write};
+
+use indicatif::MultiProgress;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use telemetry::{BoxedLayer, LogFormat, LogSettings, Telemetry};
+
 // Make commands module available for internal tests only
 #[doc(hidden)]
 pub mod commands;
 
+pub mod cli;
+pub mod config;
+pub mod credentials;
+pub mod telemetry;
+
 pub mod types;
-pub use types::QueryCommands;
+pub use types::{
+    DoctorCommands, ListenArgs, OutputFormat, QueryCommands, ReferenceArgs, RunsCommands,
+    SelfTestCommands, ServeCommands,
+};
 
 /// Sets up the tracing subscriber for logging.
 ///
 /// This function initializes the global tracing subscriber with a format layer
-/// and an environment filter. The verbosity level determines the minimum log level.
+/// writing to stderr, so command output on stdout can be piped, and an
+/// environment filter. The verbosity level determines the minimum log level,
+/// and the format whether lines are text or JSON. With an OTLP endpoint, and
+/// the `otel` feature, spans are also exported to that collector.
 ///
-/// # Arguments
-///
-/// * `verbose` - If `true`, sets the log level to "debug". If `false`, sets it to "info".
+/// The returned [`Telemetry`] flushes exported spans when dropped, so keep
+/// it until the command is done.
 ///
 /// # Panics
 ///
@@ -28,24 +47,88 @@ pub use types::QueryCommands;
 ///
 /// ```no_run
 /// use mother_cli::setup_logging;
+/// use mother_cli::telemetry::{LogFormat, LogSettings};
 ///
-/// // Set up logging with info level
-/// setup_logging(false);
-///
-/// // Set up logging with debug level
-/// setup_logging(true);
+/// // Set up JSON logging with debug level
+/// let _telemetry = setup_logging(&LogSettings {
+///     verbose: true,
+///     format: LogFormat::Json,
+///     otlp_endpoint: None,
+/// });
 /// ```
-pub fn setup_logging(verbose: bool) {
-    let filter = if verbose {
+pub fn setup_logging(settings: &LogSettings) -> Telemetry {
+    init_logging(settings, BoxMakeWriter::new(io::stderr))
+}
+
+/// Sets up logging while progress bars are drawn on stderr.
+///
+/// Like [`setup_logging`], but each log line suspends `bars` while it is
+/// written, so lines appear above the bars instead of through them.
+///
+/// # Panics
+///
+/// This function will panic if the global subscriber has already been set.
+pub fn setup_logging_with_progress(settings: &LogSettings, bars: MultiProgress) -> Telemetry {
+    init_logging(
+        settings,
+        BoxMakeWriter::new(move || AboveProgressBars(bars.clone())),
+    )
+}
+
+fn init_logging(settings: &LogSettings, writer: BoxMakeWriter) -> Telemetry {
+    let filter = if settings.verbose {
         EnvFilter::new("debug")
     } else {
         EnvFilter::new("info")
     };
+    let format = format_layer(settings.format, writer);
+    let exporter = settings
+        .otlp_endpoint
+        .as_deref()
+        .map(|endpoint| (endpoint, telemetry::otlp_layer(endpoint)));
+    let (export, telemetry, failure) = match exporter {
+        Some((_, Ok((layer, telemetry)))) => (Some(layer), telemetry, None),
+        Some((endpoint, Err(e))) => (None, Telemetry::default(), Some((endpoint, e))),
+        None => (None, Telemetry::default(), None),
+    };
 
     tracing_subscriber::registry()
-        .with(fmt::layer())
+        .with(format)
+        .with(export)
         .with(filter)
         .init();
+    if let Some((endpoint, e)) = failure {
+        tracing::warn!("Not exporting traces to {endpoint}: {e:#}");
+    }
+    telemetry
+}
+
+/// Layer writing log lines in `format` to `writer`
+fn format_layer<S>(format: LogFormat, writer: BoxMakeWriter) -> BoxedLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match format {
+        LogFormat::Text => fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// Stderr writer that hides progress bars while writing
+struct AboveProgressBars(MultiProgress);
+
+impl Write for AboveProgressBars {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.suspend(|| io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
 }
 
 #[cfg(test)]
@@ -1,131 +1,542 @@
 //! mother-cli: CLI for AST graph ingestion
 
-use clap::{Parser, Subcommand};
-use mother_cli::setup_logging;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Arc;
 
-mod commands;
-mod types;
+use clap::Parser;
+use indicatif::MultiProgress;
+#[cfg(feature = "keyring")]
+use mother_cli::cli::CredentialsAction;
+use mother_cli::cli::{Cli, Commands};
+use mother_cli::commands;
+use mother_cli::config::{Backend, EmbeddingSettings, LinkingSettings, MotherConfig, StoreArgs};
+#[cfg(feature = "keyring")]
+use mother_cli::config::{DEFAULT_NEO4J_URI, DEFAULT_NEO4J_USER};
+#[cfg(feature = "keyring")]
+use mother_cli::credentials;
+use mother_cli::{setup_logging, setup_logging_with_progress};
+use mother_core::enrich::{EnrichmentConfig, HoverEnricher};
+use mother_core::graph::ids::SymbolIdScheme;
+use mother_core::progress::Progress;
+use mother_core::scanner::Language;
+use mother_scan::{MetricsExporter, MetricsRegistry, ScanOptions};
 
-use types::QueryCommands;
-
-#[derive(Parser)]
-#[command(name = "mother")]
-#[command(author, version, about = "AST graph ingestion tool", long_about = None)]
-struct Cli {
-    /// Enable verbose output
-    #[arg(short, long, global = true)]
-    verbose: bool,
-
-    #[command(subcommand)]
-    command: Commands,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Scan a repository and store AST in Neo4j
-    Scan {
-        /// Path to the repository to scan
-        path: std::path::PathBuf,
-
-        /// Neo4j connection URI
-        #[arg(long, default_value = "bolt://localhost:7687")]
-        neo4j_uri: String,
-
-        /// Neo4j username
-        #[arg(long, default_value = "neo4j")]
-        neo4j_user: String,
-
-        /// Neo4j password
-        #[arg(long)]
-        neo4j_password: String,
-
-        /// Version tag for this scan
-        #[arg(long)]
-        version: Option<String>,
-    },
-
-    /// Query the Neo4j graph
-    Query {
-        #[command(subcommand)]
-        query_cmd: QueryCommands,
-
-        /// Neo4j connection URI
-        #[arg(long, default_value = "bolt://localhost:7687")]
-        neo4j_uri: String,
-
-        /// Neo4j username
-        #[arg(long, default_value = "neo4j")]
-        neo4j_user: String,
-
-        /// Neo4j password
-        #[arg(long)]
-        neo4j_password: String,
-    },
-
-    /// Compare two scan versions
-    Diff {
-        /// First version to compare
-        #[arg(long)]
-        from: String,
-
-        /// Second version to compare
-        #[arg(long)]
-        to: String,
-
-        /// Neo4j connection URI
-        #[arg(long, default_value = "bolt://localhost:7687")]
-        neo4j_uri: String,
-
-        /// Neo4j username
-        #[arg(long, default_value = "neo4j")]
-        neo4j_user: String,
-
-        /// Neo4j password
-        #[arg(long)]
-        neo4j_password: String,
-    },
-}
+use commands::doctor::StoreTarget;
+use mother_cli::types::{DoctorCommands, MetricsArgs, OutputFormat, SelfTestCommands};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    setup_logging(cli.verbose);
+    let bars = progress_bars(&cli.command);
+    // A config with errors is reported by the command that needs it
+    let log_settings = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))
+        .unwrap_or_default()
+        .log_settings(cli.verbose, cli.log_format, cli.otlp_endpoint.clone());
+    let _telemetry = match &bars {
+        Some(multi) => setup_logging_with_progress(&log_settings, multi.clone()),
+        None => setup_logging(&log_settings),
+    };
 
     match cli.command {
         Commands::Scan {
             path,
-            neo4j_uri,
-            neo4j_user,
-            neo4j_password,
+            bare,
+            commit,
+            store,
+            version,
+            enrichers,
+            no_hover,
+            hover_kinds,
+            hover_concurrency,
+            estimate,
+            sample_size,
+            dry_run,
+            max_references,
+            max_file_size,
+            symbol_buffer,
+            symbol_ids,
+            report,
+            report_file,
+            diagnostics,
+            external_refs,
+            lsp_trace,
+            lsp_replay,
+            export,
+            max_duration,
+            chaos,
+            ..
+        } => {
+            let path = path.or_else(|| bare.clone()).unwrap_or_default();
+            let config = MotherConfig::resolve(cli.config.as_deref(), &path)?;
+            let defaults = scan_options(&config, &path, version, enrichers)?;
+            let mut enrichment = defaults.enrichment.clone();
+            if no_hover {
+                enrichment = enrichment.without(HoverEnricher::NAME);
+            }
+            enrichment.hover.kinds = hover_kinds.unwrap_or(enrichment.hover.kinds);
+            enrichment.hover.concurrency =
+                hover_concurrency.unwrap_or(enrichment.hover.concurrency);
+            let options = ScanOptions {
+                enrichment,
+                report,
+                report_file,
+                max_references: max_references.or(defaults.max_references),
+                max_file_size: max_file_size.or(defaults.max_file_size),
+                symbol_buffer: symbol_buffer.or(defaults.symbol_buffer),
+                id_strategy: symbol_ids
+                    .map(SymbolIdScheme::strategy)
+                    .or(defaults.id_strategy),
+                progress: bars
+                    .map(|multi| Progress::new(commands::scan::ProgressBars::new(multi)))
+                    .unwrap_or_default(),
+                max_duration,
+                chaos,
+                diagnostics,
+                external_refs,
+                lsp_trace,
+                lsp_replay,
+                ..defaults
+            };
+            let in_memory = config.backend(&store) == Backend::Memory;
+            if export.is_some() && !in_memory {
+                anyhow::bail!(
+                    "--export needs --backend memory; export stored scans with `mother export`"
+                );
+            }
+            if estimate {
+                commands::scan::estimate(&path, sample_size, &options).await?;
+            } else if in_memory {
+                let bare = bare.as_deref().zip(commit.as_deref());
+                commands::scan::run_in_memory(&path, bare, &options, export.as_deref()).await?;
+            } else if dry_run {
+                commands::scan::dry_run(&path, &options).await?;
+            } else if let (Some(repo), Some(commit)) = (bare, commit) {
+                match config.backend(&store) {
+                    Backend::Neo4j => {
+                        let neo4j = config.neo4j_store_config(&store)?;
+                        mother_scan::run_bare(&repo, &commit, &neo4j, &options).await?;
+                    }
+                    Backend::Sqlite | Backend::Memory => {
+                        let sqlite = config.open_sqlite(&store)?;
+                        mother_scan::run_bare_with_store(&repo, &commit, &sqlite, &options).await?;
+                    }
+                }
+            } else {
+                match config.backend(&store) {
+                    Backend::Neo4j => {
+                        let neo4j = config.neo4j_store_config(&store)?;
+                        mother_scan::run(&path, &neo4j, &options).await?;
+                    }
+                    Backend::Sqlite | Backend::Memory => {
+                        let sqlite = config.open_sqlite(&store)?;
+                        mother_scan::run_with_store(&path, &sqlite, &options).await?;
+                    }
+                }
+            }
+        }
+        Commands::ScanHistory {
+            path,
+            from,
+            to,
+            step,
+            store,
+            enrichers,
+        } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), &path)?;
+            let options = scan_options(&config, &path, None, enrichers)?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::scan_history::run(&path, &from, &to, step, &neo4j, &options).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::scan_history::run_with_store(
+                        &path, &from, &to, step, &sqlite, &options,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Commands::Watch {
+            path,
+            store,
             version,
+            enrichers,
+            metrics,
         } => {
-            commands::scan::run(
-                &path,
-                &neo4j_uri,
-                &neo4j_user,
-                &neo4j_password,
-                version.as_deref(),
-            )
-            .await?;
+            let config = MotherConfig::resolve(cli.config.as_deref(), &path)?;
+            let mut options = scan_options(&config, &path, version, enrichers)?;
+            let exporter = export_metrics(&metrics, &mut options).await?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::watch::run(&path, &neo4j, &options).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::watch::run_with_store(&path, &sqlite, &options).await?;
+                }
+            }
+            if let Some(exporter) = exporter {
+                exporter.finish()?;
+            }
         }
         Commands::Query {
             query_cmd,
-            neo4j_uri,
-            neo4j_user,
-            neo4j_password,
+            format,
+            store,
         } => {
-            commands::query::run(query_cmd, &neo4j_uri, &neo4j_user, &neo4j_password).await?;
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            let query_cmd = query_cmd.with_saved_queries(&config.queries);
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::query::run(query_cmd, &config.neo4j_store_config(&store)?, format)
+                        .await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::query::run_with_store(query_cmd, &sqlite, format).await?;
+                }
+            }
+        }
+        Commands::Import { import_cmd, store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::import::run(import_cmd, &config.neo4j_store_config(&store)?).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::import::run_with_store(import_cmd, &sqlite).await?;
+                }
+            }
         }
+        Commands::Runs {
+            runs_cmd,
+            format,
+            store,
+        } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::runs::run(runs_cmd, &config.neo4j_store_config(&store)?, format)
+                        .await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::runs::run_with_store(runs_cmd, &sqlite, format).await?;
+                }
+            }
+        }
+        Commands::Doctor {
+            doctor_cmd,
+            format,
+            store,
+        } => run_doctor(cli.config.as_deref(), doctor_cmd, format, store).await?,
         Commands::Diff {
             from,
             to,
+            format,
+            breaking,
+            store,
+        } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::diff::run(&from, &to, &neo4j, format, breaking).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::diff::run_with_store(&sqlite, &from, &to, format, breaking).await?;
+                }
+            }
+        }
+        Commands::Explore { version, store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::explore::run(&neo4j, version.as_deref()).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::explore::run_with_store(&sqlite, version.as_deref()).await?;
+                }
+            }
+        }
+        Commands::Export {
+            version,
+            since,
+            format,
+            output,
+            store,
+        } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            let (version, since) = (version.as_deref(), since.as_deref());
+            let output = output.as_deref();
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::export::run(&neo4j, version, since, format, output).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::export::run_with_store(&sqlite, version, since, format, output)
+                        .await?;
+                }
+            }
+        }
+        Commands::Backup { file, store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::backup::backup(&config.neo4j_store_config(&store)?, &file).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::backup::backup_with_store(&sqlite, &file).await?;
+                }
+            }
+        }
+        Commands::Restore { file, store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::backup::restore(&config.neo4j_store_config(&store)?, &file).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::backup::restore_with_store(&sqlite, &file).await?;
+                }
+            }
+        }
+        Commands::Migrate { store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::migrate::run(&config.neo4j_store_config(&store)?).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::migrate::run_with_store(&sqlite).await?;
+                }
+            }
+        }
+        Commands::LspSearch {
+            query,
+            path,
+            languages,
+            format,
+        } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), &path)?;
+            let options = scan_options(&config, &path, None, None)?;
+            commands::lsp_search::run(&query, &path, &languages, &options, format).await?;
+        }
+        Commands::Serve { mode, store } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    commands::serve::run(mode, &config.neo4j_store_config(&store)?).await?;
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::serve::run_with_store(mode, Arc::new(sqlite)).await?;
+                }
+            }
+        }
+        Commands::SelfTest { test_cmd } => match test_cmd {
+            SelfTestCommands::Compare {
+                baseline,
+                against,
+                examples,
+                strict,
+            } => commands::self_test::compare(&baseline, &against, examples, strict)?,
+        },
+        #[cfg(feature = "keyring")]
+        Commands::Credentials {
+            action,
             neo4j_uri,
             neo4j_user,
-            neo4j_password,
         } => {
-            commands::diff::run(&from, &to, &neo4j_uri, &neo4j_user, &neo4j_password).await?;
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            let uri = neo4j_uri
+                .or(config.neo4j.uri)
+                .unwrap_or_else(|| DEFAULT_NEO4J_URI.to_string());
+            let user = neo4j_user
+                .or(config.neo4j.user)
+                .unwrap_or_else(|| DEFAULT_NEO4J_USER.to_string());
+            run_credentials(&action, &uri, &user)?;
+        }
+        Commands::Completions { shell } => {
+            commands::generate::completions(shell, &mut std::io::stdout());
+        }
+        Commands::Manpages { dir } => {
+            commands::generate::manpages(&dir)?;
+        }
+        Commands::InstallServers { languages, yes } => {
+            let config = MotherConfig::resolve(cli.config.as_deref(), Path::new("."))?;
+            commands::install::run(&languages, &pinned_versions(&config)?, yes)?;
         }
     }
 
     Ok(())
 }
+
+/// Store or remove the keyring password for a Neo4j user on a server
+#[cfg(feature = "keyring")]
+fn run_credentials(action: &CredentialsAction, uri: &str, user: &str) -> anyhow::Result<()> {
+    match action {
+        CredentialsAction::Set => {
+            let password =
+                rpassword::prompt_password(format!("Neo4j password for {user}@{uri}: "))?;
+            if password.is_empty() {
+                anyhow::bail!("Password must not be empty");
+            }
+            credentials::store_password(uri, user, &password)?;
+            println!("✓ Stored password for {user}@{uri} in the OS keyring");
+        }
+        CredentialsAction::Delete => {
+            credentials::delete_password(uri, user)?;
+            println!("✓ Removed password for {user}@{uri} from the OS keyring");
+        }
+    }
+    Ok(())
+}
+
+/// Run a doctor check against the configured backend
+async fn run_doctor(
+    config_path: Option<&Path>,
+    cmd: DoctorCommands,
+    format: OutputFormat,
+    store: StoreArgs,
+) -> anyhow::Result<()> {
+    match cmd {
+        DoctorCommands::Graph { fix } => {
+            let config = MotherConfig::resolve(config_path, Path::new("."))?;
+            match config.backend(&store) {
+                Backend::Neo4j => {
+                    let neo4j = config.neo4j_store_config(&store)?;
+                    commands::doctor::graph(&neo4j, fix, format).await
+                }
+                Backend::Sqlite | Backend::Memory => {
+                    let sqlite = config.open_sqlite(&store)?;
+                    commands::doctor::graph_with_store(&sqlite, fix, format).await
+                }
+            }
+        }
+        DoctorCommands::Env { path, languages } => {
+            let config = MotherConfig::resolve(config_path, &path)?;
+            let options = scan_options(&config, &path, None, None)?;
+            let target = match config.backend(&store) {
+                Backend::Neo4j => StoreTarget::Neo4j(config.neo4j_store_config(&store)),
+                Backend::Sqlite => StoreTarget::Sqlite(config.sqlite_path(&store)),
+                Backend::Memory => StoreTarget::Memory,
+            };
+            let versions = pinned_versions(&config)?;
+            commands::doctor::env(&path, &languages, &options, &versions, target, format).await
+        }
+    }
+}
+
+/// Server versions pinned in `[lsp.<language>]` config sections
+fn pinned_versions(config: &MotherConfig) -> anyhow::Result<HashMap<Language, String>> {
+    Ok(config
+        .lsp_settings()?
+        .into_iter()
+        .filter_map(|(language, settings)| settings.version.clone().map(|v| (language, v)))
+        .collect())
+}
+
+/// Merge scan flags with the `[scan]` and `[lsp]` config sections
+fn scan_options(
+    config: &MotherConfig,
+    path: &Path,
+    version: Option<String>,
+    enrichers: Option<Vec<String>>,
+) -> anyhow::Result<ScanOptions> {
+    let root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let enrichment = enrichers
+        .or_else(|| config.scan.enrichers.clone())
+        .map(|names| names.into_iter().filter(|n| !n.is_empty()).collect())
+        .map(EnrichmentConfig::new)
+        .unwrap_or_default()
+        .with_hover(config.scan.hover()?);
+
+    Ok(ScanOptions {
+        version,
+        enrichment,
+        include: config.scan.include.clone(),
+        exclude: config.scan.exclude.clone(),
+        max_file_size: config.scan.max_file_size()?,
+        generated_markers: config.scan.generated_markers.clone(),
+        lsp_servers: config.lsp_servers(&root)?,
+        custom_languages: config.custom_languages()?,
+        request_limits: config.request_limits()?,
+        open_document_limits: config.open_document_limits()?,
+        readiness: config.readiness()?,
+        lsp_trace: None,
+        lsp_replay: None,
+        report: None,
+        report_file: None,
+        max_references: config.scan.max_references,
+        symbol_buffer: config.scan.symbol_buffer,
+        progress: Progress::default(),
+        metrics: None,
+        max_duration: None,
+        chaos: None,
+        diagnostics: false,
+        external_refs: false,
+        embedder: config
+            .embedding
+            .as_ref()
+            .map(EmbeddingSettings::embedder)
+            .transpose()?,
+        linking: config
+            .linking
+            .as_ref()
+            .map(LinkingSettings::conventions)
+            .transpose()?,
+        id_strategy: config.scan.symbol_ids.map(SymbolIdScheme::strategy),
+    })
+}
+
+/// Count the scans of `options` into a registry and start exporting it, if
+/// asked to
+async fn export_metrics(
+    args: &MetricsArgs,
+    options: &mut ScanOptions,
+) -> anyhow::Result<Option<MetricsExporter>> {
+    if !args.enabled() {
+        return Ok(None);
+    }
+    let registry = Arc::new(MetricsRegistry::default());
+    options.progress = registry.progress(options.progress.clone());
+    options.metrics = Some(Arc::clone(&registry));
+    let exporter =
+        MetricsExporter::start(registry, args.metrics_addr, args.metrics_file.clone()).await?;
+    Ok(Some(exporter))
+}
+
+/// Progress bars for a full scan, if enabled
+///
+/// Bars are shown with `--progress`, or by default when stderr is a
+/// terminal, unless `--no-progress` is given. Estimates and dry runs have
+/// no bars.
+fn progress_bars(command: &Commands) -> Option<MultiProgress> {
+    let Commands::Scan {
+        estimate,
+        dry_run,
+        progress,
+        no_progress,
+        ..
+    } = command
+    else {
+        return None;
+    };
+    let enabled = *progress || (!*no_progress && std::io::stderr().is_terminal());
+    (enabled && !*estimate && !*dry_run).then(MultiProgress::new)
+}
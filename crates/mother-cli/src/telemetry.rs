@@ -0,0 +1,117 @@
+//! Telemetry: Log formats and OpenTelemetry trace export
+//!
+//! Logs go to stderr as text or, with `--log-format json`, as one JSON
+//! object per line for log collectors. Builds with the `otel` feature also
+//! export spans to an OTLP/HTTP collector when an endpoint is set: one per
+//! scan, per phase, per LSP request and per Neo4j batch write.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Deserialize;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Service name reported to the collector unless `OTEL_SERVICE_NAME` is set
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "mother";
+
+/// Format of log lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with the spans it was logged in
+    Json,
+}
+
+/// How `mother` logs and traces
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogSettings {
+    /// Log at debug level instead of info
+    pub verbose: bool,
+    pub format: LogFormat,
+    /// OTLP/HTTP collector to export spans to, e.g. `http://localhost:4318`
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Exports spans while alive and flushes the rest when dropped
+#[derive(Default)]
+#[must_use = "spans are only flushed when the telemetry is dropped"]
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush traces: {e}");
+            }
+        }
+    }
+}
+
+/// Boxed layer of a subscriber
+pub(crate) type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// Layer exporting spans to the OTLP/HTTP collector at `endpoint`
+///
+/// Like `OTEL_EXPORTER_OTLP_ENDPOINT`, the endpoint is the collector's base
+/// URL; spans are posted to its `/v1/traces`.
+///
+/// # Errors
+/// Returns an error if the exporter cannot be built.
+#[cfg(feature = "otel")]
+pub(crate) fn otlp_layer<S>(endpoint: &str) -> Result<(BoxedLayer<S>, Telemetry)>
+where
+    S: Subscriber + Send + Sync + for<'a> LookupSpan<'a>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::{Protocol, WithExportConfig};
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::Resource;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(traces_url(endpoint))
+        .build()?;
+    let mut resource = Resource::builder();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+        resource = resource.with_service_name(SERVICE_NAME);
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+
+    let tracer = provider.tracer(SERVICE_NAME);
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer).boxed();
+    let telemetry = Telemetry {
+        provider: Some(provider),
+    };
+    Ok((layer, telemetry))
+}
+
+/// Without the `otel` feature there is nothing to export with
+///
+/// # Errors
+/// Always returns an error saying so.
+#[cfg(not(feature = "otel"))]
+pub(crate) fn otlp_layer<S>(_endpoint: &str) -> Result<(BoxedLayer<S>, Telemetry)>
+where
+    S: Subscriber + Send + Sync + for<'a> LookupSpan<'a>,
+{
+    anyhow::bail!("mother was built without the `otel` feature")
+}
+
+/// Trace export URL of a collector's base URL
+#[must_use]
+pub fn traces_url(endpoint: &str) -> String {
+    format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+}
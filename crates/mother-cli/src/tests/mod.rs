@@ -1,3 +1,6 @@
 //! Tests for mother-cli library
 
+mod tests_cli;
+mod tests_credentials;
 mod tests_setup_logging;
+mod tests_telemetry;
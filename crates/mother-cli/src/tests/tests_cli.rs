@@ -0,0 +1,86 @@
+//! Tests for the clap definition of the binary
+
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+
+#[test]
+fn test_cli_definition_is_consistent() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_sort_conflicts_with_fuzzy() {
+    let args = [
+        "mother", "query", "symbols", "Parser", "--fuzzy", "--sort", "kind",
+    ];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_raw_params_are_parsed() {
+    let args = [
+        "mother",
+        "query",
+        "raw",
+        "RETURN $name, $limit",
+        "--param",
+        "name=parse",
+        "--param",
+        "limit=10",
+    ];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "query", "raw", "RETURN 1", "--param", "no-equals"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_symbol_id_scheme_is_parsed() {
+    let args = ["mother", "scan", ".", "--symbol-ids", "qualified-name"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "scan", ".", "--symbol-ids", "sequential"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_hover_options_are_parsed() {
+    let args = ["mother", "scan", ".", "--hover-kinds", "functions,methods"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "scan", ".", "--hover-kinds", "gadgets"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+    let args = [
+        "mother",
+        "scan",
+        ".",
+        "--no-hover",
+        "--hover-concurrency",
+        "4",
+    ];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_max_duration_is_parsed() {
+    let args = ["mother", "scan", ".", "--max-duration", "1h30m"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "scan", ".", "--max-duration", "soon"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+    let args = ["mother", "scan", ".", "--max-duration", "5m", "--dry-run"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_symbol_buffer_must_be_positive() {
+    let args = ["mother", "scan", ".", "--symbol-buffer", "500000"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "scan", ".", "--symbol-buffer", "0"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_max_file_size_is_parsed() {
+    let args = ["mother", "scan", ".", "--max-file-size", "512KB"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_ok());
+    let args = ["mother", "scan", ".", "--max-file-size", "big"];
+    assert!(<Cli as clap::Parser>::try_parse_from(args).is_err());
+}
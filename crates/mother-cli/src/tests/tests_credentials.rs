@@ -0,0 +1,19 @@
+//! Tests for keyring credential naming
+
+use crate::credentials::keyring_account;
+
+#[test]
+fn test_keyring_account_combines_user_and_uri() {
+    assert_eq!(
+        keyring_account("bolt://localhost:7687", "neo4j"),
+        "neo4j@bolt://localhost:7687"
+    );
+}
+
+#[test]
+fn test_keyring_account_distinguishes_servers() {
+    assert_ne!(
+        keyring_account("bolt://a:7687", "neo4j"),
+        keyring_account("bolt://b:7687", "neo4j")
+    );
+}
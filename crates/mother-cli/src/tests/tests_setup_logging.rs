@@ -453,3 +453,17 @@ fn test_layer_ordering() {
 
     let _subscriber1 = tracing_subscriber::registry().with(filter).with(layer);
 }
+
+/// Test that log lines written above progress bars are passed through whole
+#[test]
+fn test_above_progress_bars_writes_everything() {
+    use std::io::Write;
+
+    use indicatif::{MultiProgress, ProgressDrawTarget};
+
+    let bars = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+    let mut writer = crate::AboveProgressBars(bars);
+    let line = b"log line\n";
+    assert_eq!(writer.write(line).unwrap(), line.len());
+    assert!(writer.flush().is_ok());
+}
@@ -0,0 +1,90 @@
+//! Tests for log formats and trace export settings
+
+#![allow(clippy::unwrap_used)]
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use serde_json::Value;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::Registry;
+
+use crate::cli::Cli;
+use crate::format_layer;
+use crate::telemetry::otlp_layer;
+use crate::telemetry::{traces_url, LogFormat};
+
+/// Writer appending to a shared buffer
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_traces_url_appends_the_traces_path() {
+    assert_eq!(
+        traces_url("http://collector:4318"),
+        "http://collector:4318/v1/traces"
+    );
+    assert_eq!(
+        traces_url("http://collector:4318/"),
+        "http://collector:4318/v1/traces"
+    );
+}
+
+#[test]
+fn test_json_format_writes_one_object_per_line_with_spans() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let layer = format_layer(LogFormat::Json, BoxMakeWriter::new(move || writer.clone()));
+    let subscriber = tracing_subscriber::registry().with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("phase", phase = "Phase 1");
+        let _entered = span.enter();
+        tracing::info!(files = 3, "Opened files");
+    });
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["message"], "Opened files");
+    assert_eq!(lines[0]["files"], 3);
+    assert_eq!(lines[0]["span"]["phase"], "Phase 1");
+}
+
+#[test]
+fn test_log_format_flag() {
+    let cli = Cli::try_parse_from(["mother", "--log-format", "json", "runs", "list"]).unwrap();
+    assert_eq!(cli.log_format, Some(LogFormat::Json));
+    assert!(Cli::try_parse_from(["mother", "--log-format", "xml", "runs", "list"]).is_err());
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn test_otlp_layer_builds_for_an_endpoint() {
+    let (_layer, telemetry) = otlp_layer::<Registry>("http://localhost:4318").unwrap();
+    drop(telemetry);
+}
+
+#[cfg(not(feature = "otel"))]
+#[test]
+fn test_otlp_export_needs_the_otel_feature() {
+    let result = otlp_layer::<Registry>("http://localhost:4318");
+    let error = result.err().unwrap().to_string();
+    assert!(error.contains("otel"), "{error}");
+}
@@ -1,6 +1,52 @@
 //! CLI types shared between binary and library
 
-use clap::Subcommand;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+
+use clap::builder::{NonEmptyStringValueParser, RangedU64ValueParser};
+use clap::{Args, Subcommand};
+use mother_core::graph::{parse_assignment, Page, ParamValue, ReferenceGrouping, SortKey};
+use mother_core::scanner::Language;
+
+use crate::commands::query::SavedQuery;
+
+/// Output format of query results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns for reading in a terminal
+    #[default]
+    Table,
+    /// A JSON array of result objects (a single object for `stats` and `raw`)
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "Unknown output format: {s} (expected json, table or csv)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Table => write!(f, "table"),
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
 
 /// Query command variants
 #[derive(Subcommand, Debug, Clone)]
@@ -9,6 +55,50 @@ pub enum QueryCommands {
     Symbols {
         /// Pattern to search for (case-insensitive)
         pattern: String,
+
+        /// Only return symbol versions present in this commit
+        #[arg(long)]
+        commit: Option<String>,
+
+        /// Only return symbols in files under a directory or matching a glob
+        /// (`*` within a segment, `**` across segments)
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+
+        /// Order of the results
+        #[arg(long, value_parser = ["name", "kind", "file"], conflicts_with = "fuzzy")]
+        sort: Option<String>,
+
+        /// Tolerate typos, case and separator differences, ranking symbols
+        /// by how close their names are to the pattern
+        #[arg(long)]
+        fuzzy: bool,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Ranked full-text search of symbol names, qualified names and doc
+    /// comments; words match by prefix and, in Neo4j, fuzzily
+    Search {
+        /// Words to search for; a symbol matching any of them is listed
+        text: String,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Find symbols whose embeddings are closest to a symbol's, i.e. code
+    /// that does something similar; needs a scan with `[embedding]` set
+    Similar {
+        /// Qualified name (or plain name) of the symbol
+        symbol: String,
+
+        /// Version tag or commit SHA prefix [default: latest scan]
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
     },
     /// List symbols in a file
     File {
@@ -19,22 +109,581 @@ pub enum QueryCommands {
     RefsTo {
         /// Symbol name to find references to
         symbol: String,
+
+        #[command(flatten)]
+        options: ReferenceArgs,
+
+        /// Order of the references, by file and line or by referencing
+        /// symbol name
+        #[arg(long, value_parser = ["file", "name"])]
+        sort: Option<String>,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
     /// Find what a symbol references
     RefsFrom {
         /// Symbol name to find outgoing references from
         symbol: String,
+
+        #[command(flatten)]
+        options: ReferenceArgs,
+
+        /// Order of the references, by file and line or by referenced
+        /// symbol name
+        #[arg(long, value_parser = ["file", "name"])]
+        sort: Option<String>,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Show every stored version of a symbol across commits, following renames
+    History {
+        /// Qualified name (or plain name) of the symbol
+        symbol: String,
+    },
+    /// List the symbols a commit changed relative to the commit scanned
+    /// before it
+    Modified {
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
     },
     /// List files in the graph
     Files {
         /// Optional pattern to filter files
         pattern: Option<String>,
+
+        /// Order of the files, by path, language or symbol count (most first)
+        #[arg(long, value_parser = ["file", "language", "symbols"])]
+        sort: Option<String>,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Show line counts, byte sizes and symbol counts by kind per file, with
+    /// totals per language
+    Metrics {
+        /// Optional pattern the file paths must contain
+        pattern: Option<String>,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Summarize a directory: file and line counts, languages and README blurb
+    Dir {
+        /// Directory path (relative to the current directory or absolute)
+        path: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Roll up the subdirectories of a directory: symbols, symbols the commit
+    /// modified and dependencies between the subdirectories
+    Dirs {
+        /// Directory path (relative to the current directory or absolute;
+        /// default: the scan root)
+        path: Option<String>,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List modules with their symbol counts, rolled up over submodules,
+    /// and how many of those symbols the commit modified
+    Modules {
+        /// Only this module and the modules below it (e.g. `mother_core::graph`)
+        prefix: Option<String>,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List the API endpoints and messages of the OpenAPI specs and `.proto`
+    /// files, with the symbols serving them
+    Contracts {
+        /// Only contracts whose name contains this text, case-insensitively
+        /// (e.g. `/orders` or `CreateOrder`)
+        name: Option<String>,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List the symbols reachable from outside a module and the internal
+    /// symbols they expose through public fields, signatures and type aliases
+    ApiSurface {
+        /// Module or crate directory, file or glob (relative to the current
+        /// directory or absolute)
+        #[arg(value_name = "MODULE_OR_CRATE")]
+        path: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Report the share of public symbols with a doc comment per file or
+    /// module, failing below a minimum
+    DocCoverage {
+        /// Whether files or the modules directly containing the symbols are
+        /// reported
+        #[arg(long, default_value = "file", value_parser = ["file", "module"])]
+        level: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only count symbols in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+
+        /// Fail if less than this percentage of all public symbols is
+        /// documented, for CI
+        #[arg(long, value_name = "PERCENT")]
+        #[arg(value_parser = RangedU64ValueParser::<u8>::new().range(0..=100))]
+        min: Option<u8>,
+    },
+    /// Rank the most connected symbols by fan-in plus fan-out, the likeliest
+    /// refactoring hotspots
+    Hotspots {
+        /// Number of symbols to list
+        #[arg(long, default_value_t = 20)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only rank symbols in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// Find dependency cycles between files or modules through imports,
+    /// references and calls, with the symbols involved
+    Cycles {
+        /// Whether files or their directories are the nodes of a cycle
+        #[arg(long, default_value = "file", value_parser = ["file", "module"])]
+        level: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only list cycles with a member under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// Find the shortest reference and call chains from one symbol to
+    /// another, with the file and line of each hop
+    Path {
+        /// Name or qualified name of the symbol the chains start at
+        #[arg(long)]
+        from: String,
+
+        /// Name or qualified name of the symbol the chains end at
+        #[arg(long)]
+        to: String,
+
+        /// Most chains to list
+        #[arg(long, default_value_t = 5)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List the diagnostics language servers reported during a
+    /// `scan --diagnostics`
+    Diagnostics {
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only list diagnostics of this severity
+        #[arg(long, value_parser = ["error", "warning", "information", "hint"])]
+        severity: Option<String>,
+
+        /// Only list diagnostics in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// Find functions with a parameter whose type contains a type name
+    /// (needs the hover enricher)
+    Takes {
+        /// Type name or fragment as written in signatures, e.g. `&Neo4jClient`
+        #[arg(value_name = "TYPE")]
+        type_name: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only list functions in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// Find functions whose return type contains a type name (needs the
+    /// hover enricher)
+    Returns {
+        /// Type name or fragment as written in signatures, e.g. `Result<`
+        #[arg(value_name = "TYPE")]
+        type_name: String,
+
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only list functions in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// List public functions and methods no test references or calls, the
+    /// most referenced first
+    Untested {
+        /// Scan version tag or commit SHA prefix (default: latest scan)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Only list functions in files under a directory or matching a glob
+        #[arg(long = "in", value_name = "PATH_OR_GLOB")]
+        scope: Option<String>,
+    },
+    /// Find files that import a module or file
+    Importers {
+        /// Module path (e.g. `crate::graph`, `os.path`) or file path suffix
+        module: String,
+    },
+    /// List the DEPENDS_ON links between scanned repositories: which
+    /// repositories use packages another one defines
+    Repos {
+        /// Only links from or to repositories whose path contains this text
+        path: Option<String>,
     },
     /// Show graph statistics
     Stats,
+    /// Chart file, symbol, edge and dead-code counts across the last scans
+    /// of a branch
+    Trends {
+        /// Number of most recently scanned commits to compare
+        #[arg(long, default_value_t = 5)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        last: usize,
+
+        /// Branch whose scans to compare (default: branch of the latest scan)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+    /// Rank symbols or files by how often they changed across the last
+    /// scans of a branch: scans that added them and scans that modified them
+    Churn {
+        /// Number of most recently scanned commits to count changes in
+        #[arg(long, default_value_t = 10)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        last: usize,
+
+        /// Branch whose scans to count (default: branch of the latest scan)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Whether to rank symbols or files
+        #[arg(long, default_value = "symbol", value_parser = ["symbol", "file"])]
+        level: String,
+
+        /// Number of symbols or files to list
+        #[arg(long, default_value_t = 20)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+    },
     /// Execute raw Cypher query
     Raw {
         /// Cypher query to execute
         query: String,
+
+        /// Bind a query parameter, referenced as `$key` in the query
+        /// (repeatable). Values are parsed as null, booleans and numbers;
+        /// quote them to pass a string
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_assignment)]
+        params: Vec<(String, ParamValue)>,
+    },
+    /// Run a saved query template, or list them when no name is given
+    ///
+    /// Built-in: callers-of, files-by-language, unused-exports. Define more
+    /// under `[queries.<name>]` in the config file.
+    Saved {
+        /// Name of the saved query
+        name: Option<String>,
+
+        /// Argument of the query, referenced as `$key` in its text
+        /// (repeatable)
+        #[arg(long = "arg", value_name = "KEY=VALUE", value_parser = parse_assignment)]
+        args: Vec<(String, ParamValue)>,
+
+        /// Queries from the config file, filled in after parsing
+        #[arg(skip)]
+        custom: BTreeMap<String, SavedQuery>,
+    },
+}
+
+/// Aggregation flags of the reference queries
+#[derive(Args, Debug, Clone, Default)]
+pub struct ReferenceArgs {
+    /// Collapse references into one row per symbol or per file, with
+    /// occurrence counts
+    #[arg(long, value_parser = ["symbol", "file"], conflicts_with = "unique")]
+    pub group_by: Option<String>,
+
+    /// Merge references from the same symbol on the same line, with
+    /// occurrence counts
+    #[arg(long)]
+    pub unique: bool,
+}
+
+impl ReferenceArgs {
+    /// The aggregation to apply, or `None` to list every reference
+    #[must_use]
+    pub fn grouping(&self) -> Option<ReferenceGrouping> {
+        match self.group_by.as_deref() {
+            Some("symbol") => Some(ReferenceGrouping::Symbol),
+            Some("file") => Some(ReferenceGrouping::File),
+            _ if self.unique => Some(ReferenceGrouping::Unique),
+            _ => None,
+        }
+    }
+}
+
+/// Paging flags of the list queries
+#[derive(Args, Debug, Clone, Default)]
+pub struct PageArgs {
+    /// Most results to print (default: 100)
+    #[arg(long)]
+    #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub limit: Option<usize>,
+
+    /// Number of results to skip before the first one printed
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+}
+
+impl PageArgs {
+    /// The page to request, ordered by a `--sort` value
+    #[must_use]
+    pub fn page(&self, sort: Option<&str>) -> Page {
+        let sort = match sort {
+            Some("name") => Some(SortKey::Name),
+            Some("kind") => Some(SortKey::Kind),
+            Some("file") => Some(SortKey::File),
+            Some("language") => Some(SortKey::Language),
+            Some("symbols") => Some(SortKey::Symbols),
+            _ => None,
+        };
+        Page {
+            limit: self.limit,
+            offset: self.offset,
+            sort,
+        }
+    }
+}
+
+/// Ways to serve the graph to other programs
+#[derive(Subcommand, Debug, Clone)]
+pub enum ServeCommands {
+    /// Model Context Protocol server on stdin/stdout, exposing graph queries
+    /// as tools for LLM agents
+    Mcp,
+
+    /// Read-only JSON API over HTTP, for dashboards without database
+    /// credentials
+    Http {
+        #[command(flatten)]
+        listen: ListenArgs,
+    },
+
+    /// GraphQL endpoint at /graphql with typed, nested symbols, files,
+    /// references and scans; GET opens GraphiQL
+    Graphql {
+        #[command(flatten)]
+        listen: ListenArgs,
+    },
+}
+
+/// Where network servers listen
+#[derive(Args, Debug, Clone)]
+pub struct ListenArgs {
+    /// Port to listen on
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// Address to listen on (0.0.0.0 for every interface)
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: IpAddr,
+}
+
+impl ListenArgs {
+    /// Socket address to bind
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        SocketAddr::new(self.host, self.port)
+    }
+}
+
+/// Where long-running commands export their scan metrics for Prometheus
+#[derive(Args, Debug, Clone, Default)]
+pub struct MetricsArgs {
+    /// Serve Prometheus metrics at http://<ADDR>/metrics, e.g.
+    /// 127.0.0.1:9464
+    #[arg(long, value_name = "ADDR")]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Keep FILE updated with Prometheus metrics, for node_exporter's
+    /// textfile collector
+    #[arg(long, value_name = "FILE")]
+    pub metrics_file: Option<PathBuf>,
+}
+
+impl MetricsArgs {
+    /// Whether metrics are exported at all
+    #[must_use]
+    pub const fn enabled(&self) -> bool {
+        self.metrics_addr.is_some() || self.metrics_file.is_some()
+    }
+}
+
+/// Scan run management commands
+#[derive(Subcommand, Debug, Clone)]
+pub enum RunsCommands {
+    /// List every scan run, newest first
+    List,
+    /// Show a scan run with the size of the commit it scanned
+    Show {
+        /// Scan run ID or an unambiguous prefix of it
+        id: String,
+    },
+    /// Delete a scan run, its commit if no other run is left for it, and
+    /// the files and symbols no remaining commit contains
+    Delete {
+        /// Scan run ID or an unambiguous prefix of it
+        #[arg(required_unless_present = "failed", conflicts_with = "failed")]
+        id: Option<String>,
+
+        /// Delete every run that failed or ran out of time instead, and runs
+        /// left in progress for over six hours by a scan that was killed
+        #[arg(long)]
+        failed: bool,
+    },
+    /// Tag a scan run after the fact, for example with a release number
+    /// assigned later; `tag:<name>` then resolves to its commit wherever a
+    /// version is accepted
+    Tag {
+        /// Scan run ID or an unambiguous prefix of it
+        id: String,
+
+        /// Tag to add
+        #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+        tag: String,
+    },
+    /// Attach a free-form note to a scan run, replacing any earlier one
+    Annotate {
+        /// Scan run ID or an unambiguous prefix of it
+        id: String,
+
+        /// Note to attach
+        #[arg(long)]
+        note: String,
+    },
+    /// Delete every scan run but the most recent ones, like `delete`
+    Prune {
+        /// Number of most recent scan runs to keep
+        #[arg(long)]
+        #[arg(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        keep_last: usize,
+    },
+}
+
+/// Import command variants
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportCommands {
+    /// Store an LSIF dump, such as one produced in CI, as a scan of a
+    /// commit, without running language servers
+    Lsif {
+        /// LSIF dump, as JSON lines or a JSON array
+        file: PathBuf,
+
+        /// Repository the dump was generated from, for the scan run's path
+        /// and git info
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Commit SHA the dump was generated at (default: HEAD of --path)
+        #[arg(long)]
+        commit: Option<String>,
+
+        /// Version tag for the import's scan run
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Annotate the symbols of a scan with line and branch coverage from an
+    /// lcov tracefile or Cobertura XML report
+    Coverage {
+        /// lcov tracefile or Cobertura XML report
+        file: PathBuf,
+
+        /// Scan run ID or an unambiguous prefix of it, whose symbols the
+        /// report covers
+        #[arg(long)]
+        scan: String,
+    },
+}
+
+/// Health check command variants
+#[derive(Subcommand, Debug, Clone)]
+pub enum DoctorCommands {
+    /// Validate the stored graph: dangling edges, files without commits,
+    /// symbols with inverted line ranges and duplicate symbol IDs
+    Graph {
+        /// Delete dangling edges and files without commits before reporting
+        /// what is left
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check that the language servers a scan needs are installed and run,
+    /// and that the graph store is reachable
+    Env {
+        /// Repository whose languages to check
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Comma-separated languages whose servers to check (default: every
+        /// language found in the repository)
+        #[arg(long, value_delimiter = ',')]
+        languages: Vec<Language>,
+    },
+}
+
+/// Self-test command variants
+#[derive(Subcommand, Debug, Clone)]
+pub enum SelfTestCommands {
+    /// Compare two JSON lines exports of the same commit made by different
+    /// tool versions
+    Compare {
+        /// Export produced by the trusted (older) version
+        #[arg(long)]
+        baseline: PathBuf,
+
+        /// Export produced by the version under test
+        #[arg(long)]
+        against: PathBuf,
+
+        /// Number of example differences to show per cause
+        #[arg(long, default_value_t = 10)]
+        examples: usize,
+
+        /// Exit with an error if the exports differ
+        #[arg(long)]
+        strict: bool,
     },
 }
@@ -0,0 +1,13 @@
+//! Generic adapter: languages registered in the config
+
+use super::LanguageAdapter;
+
+/// Adapter for [`crate::scanner::Language::Other`]: the trait defaults, with
+/// visibility left unknown since nothing is known about the language
+pub struct GenericAdapter;
+
+impl LanguageAdapter for GenericAdapter {
+    fn detect_visibility(&self, _signature: Option<&str>, _name: &str) -> Option<String> {
+        None
+    }
+}
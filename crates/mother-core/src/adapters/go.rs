@@ -0,0 +1,95 @@
+//! Go adapter: gopls symbols
+
+use std::path::Path;
+
+use super::{file_name, is_function, path_segments, EntryPointRule, LanguageAdapter};
+use crate::graph::model::{SymbolKind, SymbolNode};
+
+/// Adapter for Go
+pub struct GoAdapter;
+
+const ENTRY_POINTS: &[EntryPointRule] = &[
+    EntryPointRule {
+        kinds: &[SymbolKind::Function],
+        name: "main",
+        file_name: None,
+    },
+    EntryPointRule {
+        kinds: &[SymbolKind::Function],
+        name: "init",
+        file_name: None,
+    },
+];
+
+impl LanguageAdapter for GoAdapter {
+    fn separator(&self) -> &'static str {
+        "."
+    }
+
+    /// Go packages are directories; every file of one shares its module
+    fn module_path(&self, relative: &Path) -> Vec<String> {
+        let mut segments = path_segments(relative);
+        segments.pop();
+        segments
+    }
+
+    /// Strip the receiver gopls puts on method names, `(*Server).Start`
+    /// becoming `Server.Start`
+    fn normalize_name<'a>(&self, name: &'a str) -> &'a str {
+        name.strip_prefix("(*")
+            .or_else(|| name.strip_prefix('('))
+            .and_then(|rest| rest.split_once(").").map(|(_, method)| method))
+            .unwrap_or(name)
+    }
+
+    fn normalize_qualified_name(&self, parent: Option<&str>, name: &str) -> String {
+        let receiver = name
+            .strip_prefix("(*")
+            .or_else(|| name.strip_prefix('('))
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(receiver, _)| receiver);
+        let name = self.normalize_name(name);
+        let prefix = match (parent, receiver) {
+            (Some(parent), Some(receiver)) if !parent.is_empty() => {
+                Some(format!("{parent}.{receiver}"))
+            }
+            (Some(parent), None) if !parent.is_empty() => Some(parent.to_string()),
+            (_, Some(receiver)) => Some(receiver.to_string()),
+            _ => None,
+        };
+        match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_string(),
+        }
+    }
+
+    /// Exported identifiers start with an upper-case letter
+    fn detect_visibility(&self, _signature: Option<&str>, name: &str) -> Option<String> {
+        self.normalize_name(name).chars().next().map(|c| {
+            if c.is_uppercase() {
+                "public"
+            } else {
+                "private"
+            }
+            .to_string()
+        })
+    }
+
+    fn entry_point_rules(&self) -> &'static [EntryPointRule] {
+        ENTRY_POINTS
+    }
+
+    fn is_test_file(&self, relative: &Path) -> bool {
+        file_name(relative).ends_with("_test.go")
+    }
+
+    /// The `Test`, `Benchmark`, `Fuzz` and `Example` functions `go test`
+    /// runs from `_test.go` files
+    fn is_test(&self, symbol: &SymbolNode, relative: &Path) -> bool {
+        is_function(symbol)
+            && ["Test", "Benchmark", "Fuzz", "Example"]
+                .iter()
+                .any(|prefix| symbol.name.starts_with(prefix))
+            && self.is_test_file(relative)
+    }
+}
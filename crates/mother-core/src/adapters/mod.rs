@@ -0,0 +1,212 @@
+//! Language adapters: Per-language rules for turning LSP symbols into graph nodes
+//!
+//! Each supported language implements [`LanguageAdapter`] in its own file.
+//! [`adapter_for`] is the single registry mapping a [`Language`] to its
+//! adapter, so supporting a new language means adding one file and one
+//! match arm here.
+
+mod generic;
+mod go;
+mod python;
+mod ruby;
+mod rust;
+mod sysml;
+mod typescript;
+
+use std::path::Path;
+
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::lsp::LspSymbolKind;
+use crate::scanner::Language;
+
+pub use generic::GenericAdapter;
+pub use go::GoAdapter;
+pub use python::PythonAdapter;
+pub use ruby::RubyAdapter;
+pub use rust::RustAdapter;
+pub use sysml::SysmlAdapter;
+pub use typescript::TypeScriptAdapter;
+
+/// Language-specific normalization of symbols extracted from an LSP server
+pub trait LanguageAdapter: Send + Sync {
+    /// Separator between qualified name segments (e.g. `::` or `.`)
+    fn separator(&self) -> &'static str {
+        "::"
+    }
+
+    /// Normalize one name segment as reported by the LSP server
+    ///
+    /// Used to strip server-specific decoration such as Rust `impl` headers
+    /// or Go method receivers.
+    fn normalize_name<'a>(&self, name: &'a str) -> &'a str {
+        name
+    }
+
+    /// Build the qualified name of a symbol nested in `parent`
+    fn normalize_qualified_name(&self, parent: Option<&str>, name: &str) -> String {
+        let name = self.normalize_name(name);
+        match parent {
+            Some(parent) if !parent.is_empty() => {
+                format!("{}{}{}", parent, self.separator(), name)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// Segments of the module a file forms, from its path relative to the
+    /// scan root
+    ///
+    /// Defaults to the file's directories and stem.
+    fn module_path(&self, relative: &Path) -> Vec<String> {
+        path_segments(relative)
+    }
+
+    /// Map an LSP symbol kind to a graph symbol kind
+    fn map_symbol_kind(&self, kind: LspSymbolKind) -> SymbolKind {
+        default_symbol_kind(kind)
+    }
+
+    /// Infer visibility from a symbol's signature and name
+    fn detect_visibility(&self, signature: Option<&str>, name: &str) -> Option<String>;
+
+    /// Rules marking symbols that are called from outside the code base
+    fn entry_point_rules(&self) -> &'static [EntryPointRule] {
+        &[]
+    }
+
+    /// Whether a symbol matches any of the entry point rules
+    fn is_entry_point(&self, symbol: &SymbolNode) -> bool {
+        self.entry_point_rules()
+            .iter()
+            .any(|rule| rule.matches(symbol))
+    }
+
+    /// Whether a file holds tests, by its path relative to the scan root
+    fn is_test_file(&self, _relative: &Path) -> bool {
+        false
+    }
+
+    /// Whether a symbol is a test, by naming convention and its file's path
+    /// relative to the scan root
+    ///
+    /// Defaults to the functions and methods of test files.
+    fn is_test(&self, symbol: &SymbolNode, relative: &Path) -> bool {
+        is_function(symbol) && self.is_test_file(relative)
+    }
+}
+
+/// A symbol that is invoked by a runtime or tool rather than by other code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPointRule {
+    /// Symbol kinds the rule applies to
+    pub kinds: &'static [SymbolKind],
+    /// Exact symbol name
+    pub name: &'static str,
+    /// File name the symbol must be defined in, if restricted
+    pub file_name: Option<&'static str>,
+}
+
+impl EntryPointRule {
+    /// Whether a symbol matches this rule
+    #[must_use]
+    pub fn matches(&self, symbol: &SymbolNode) -> bool {
+        self.kinds.contains(&symbol.kind)
+            && symbol.name == self.name
+            && self.file_name.is_none_or(|file_name| {
+                Path::new(&symbol.file_path)
+                    .file_name()
+                    .is_some_and(|f| f == file_name)
+            })
+    }
+}
+
+/// Get the adapter for a language
+#[must_use]
+pub fn adapter_for(language: &Language) -> &'static dyn LanguageAdapter {
+    match language {
+        Language::Rust => &RustAdapter,
+        Language::Python => &PythonAdapter,
+        Language::TypeScript | Language::JavaScript => &TypeScriptAdapter,
+        Language::Go => &GoAdapter,
+        Language::Ruby => &RubyAdapter,
+        Language::SysML | Language::KerML => &SysmlAdapter,
+        Language::Other(_) => &GenericAdapter,
+    }
+}
+
+/// Get the adapter for a file, based on its extension
+///
+/// Files of unknown languages use the Rust adapter's `::` naming, matching
+/// how symbols were named before adapters existed.
+#[must_use]
+pub fn adapter_for_path(path: &Path) -> &'static dyn LanguageAdapter {
+    Language::from_path(path).map_or(&RustAdapter, |language| adapter_for(&language))
+}
+
+/// Directories and stem of a relative file path, the default module path
+#[must_use]
+pub fn path_segments(relative: &Path) -> Vec<String> {
+    let mut segments: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    segments.retain(|s| !s.is_empty());
+    segments
+}
+
+/// Whether a symbol is a function or method
+fn is_function(symbol: &SymbolNode) -> bool {
+    matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+/// Whether a directory of a relative path has one of `names`
+fn in_directory(relative: &Path, names: &[&str]) -> bool {
+    relative.parent().is_some_and(|dir| {
+        dir.components()
+            .any(|c| c.as_os_str().to_str().is_some_and(|c| names.contains(&c)))
+    })
+}
+
+/// File name of a path, empty if it has none
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+}
+
+/// LSP to graph kind mapping shared by all languages
+#[must_use]
+pub fn default_symbol_kind(kind: LspSymbolKind) -> SymbolKind {
+    match kind {
+        LspSymbolKind::Module | LspSymbolKind::Namespace | LspSymbolKind::Package => {
+            SymbolKind::Module
+        }
+        LspSymbolKind::Class => SymbolKind::Class,
+        LspSymbolKind::Struct => SymbolKind::Struct,
+        LspSymbolKind::Enum => SymbolKind::Enum,
+        LspSymbolKind::Interface => SymbolKind::Interface,
+        LspSymbolKind::Function | LspSymbolKind::Constructor => SymbolKind::Function,
+        LspSymbolKind::Method => SymbolKind::Method,
+        LspSymbolKind::Variable => SymbolKind::Variable,
+        LspSymbolKind::Constant => SymbolKind::Constant,
+        LspSymbolKind::Field | LspSymbolKind::Property => SymbolKind::Field,
+        LspSymbolKind::TypeParameter => SymbolKind::TypeAlias,
+        LspSymbolKind::EnumMember => SymbolKind::Constant,
+        _ => SymbolKind::Variable,
+    }
+}
+
+/// Find the first of `keywords` appearing as a whole word in a signature
+fn find_keyword(signature: &str, keywords: &[&'static str]) -> Option<&'static str> {
+    keywords
+        .iter()
+        .copied()
+        .find(|kw| signature.split_whitespace().any(|word| word == *kw))
+}
+
+#[cfg(test)]
+mod tests;
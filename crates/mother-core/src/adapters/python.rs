@@ -0,0 +1,73 @@
+//! Python adapter: pyright symbols
+
+use std::path::Path;
+
+use super::{file_name, in_directory, is_function, path_segments, EntryPointRule, LanguageAdapter};
+use crate::graph::model::{SymbolKind, SymbolNode};
+
+/// Adapter for Python
+pub struct PythonAdapter;
+
+const ENTRY_POINTS: &[EntryPointRule] = &[
+    EntryPointRule {
+        kinds: &[SymbolKind::Function],
+        name: "main",
+        file_name: None,
+    },
+    EntryPointRule {
+        kinds: &[SymbolKind::Module, SymbolKind::Function],
+        name: "__main__",
+        file_name: None,
+    },
+];
+
+impl LanguageAdapter for PythonAdapter {
+    fn separator(&self) -> &'static str {
+        "."
+    }
+
+    /// Packages are named by their directory (`__init__` files) and a leading
+    /// `src` layout directory is dropped
+    fn module_path(&self, relative: &Path) -> Vec<String> {
+        let mut segments = path_segments(relative);
+        if segments.len() > 1 && segments.first().is_some_and(|s| s == "src") {
+            segments.remove(0);
+        }
+        if segments.len() > 1 && segments.last().is_some_and(|s| s == "__init__") {
+            segments.pop();
+        }
+        segments
+    }
+
+    /// Dunder names are public protocol methods; a leading underscore marks
+    /// everything else private
+    fn detect_visibility(&self, _signature: Option<&str>, name: &str) -> Option<String> {
+        Some(if name.starts_with("__") && name.ends_with("__") {
+            "public".to_string()
+        } else if name.starts_with('_') {
+            "private".to_string()
+        } else {
+            "public".to_string()
+        })
+    }
+
+    fn entry_point_rules(&self) -> &'static [EntryPointRule] {
+        ENTRY_POINTS
+    }
+
+    /// pytest's `test_*.py` and `*_test.py` files, `conftest.py` and files
+    /// in `tests` directories
+    fn is_test_file(&self, relative: &Path) -> bool {
+        let name = file_name(relative);
+        in_directory(relative, &["tests", "test"])
+            || name.starts_with("test_")
+            || name.ends_with("_test.py")
+            || name == "conftest.py"
+    }
+
+    /// Functions and methods named `test*` in test files, as pytest and
+    /// unittest collect them
+    fn is_test(&self, symbol: &SymbolNode, relative: &Path) -> bool {
+        is_function(symbol) && symbol.name.starts_with("test") && self.is_test_file(relative)
+    }
+}
@@ -0,0 +1,57 @@
+//! Ruby adapter: solargraph symbols
+
+use std::path::Path;
+
+use super::{file_name, in_directory, EntryPointRule, LanguageAdapter};
+use crate::graph::model::SymbolKind;
+
+/// Adapter for Ruby
+pub struct RubyAdapter;
+
+/// Hooks the interpreter calls: `initialize` through `new`, the others on
+/// dynamic dispatch
+const ENTRY_POINTS: &[EntryPointRule] = &[
+    EntryPointRule {
+        kinds: &[SymbolKind::Method, SymbolKind::Function],
+        name: "initialize",
+        file_name: None,
+    },
+    EntryPointRule {
+        kinds: &[SymbolKind::Method, SymbolKind::Function],
+        name: "method_missing",
+        file_name: None,
+    },
+    EntryPointRule {
+        kinds: &[SymbolKind::Method, SymbolKind::Function],
+        name: "respond_to_missing?",
+        file_name: None,
+    },
+];
+
+impl LanguageAdapter for RubyAdapter {
+    /// Singleton methods are named `self.name` by some servers; the name is
+    /// the part after the receiver
+    fn normalize_name<'a>(&self, name: &'a str) -> &'a str {
+        name.strip_prefix("self.").unwrap_or(name)
+    }
+
+    /// Ruby sets visibility with `private`/`protected` sections rather than
+    /// on the definition, so neither signature nor name tells
+    fn detect_visibility(&self, _signature: Option<&str>, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn entry_point_rules(&self) -> &'static [EntryPointRule] {
+        ENTRY_POINTS
+    }
+
+    /// RSpec's `*_spec.rb` and Minitest's `test_*.rb` and `*_test.rb` files,
+    /// and files in `spec` and `test` directories
+    fn is_test_file(&self, relative: &Path) -> bool {
+        let name = file_name(relative);
+        in_directory(relative, &["spec", "test"])
+            || name.ends_with("_spec.rb")
+            || name.ends_with("_test.rb")
+            || name.starts_with("test_")
+    }
+}
@@ -0,0 +1,128 @@
+//! Rust adapter: rust-analyzer symbols
+
+use std::path::Path;
+
+use super::{file_name, in_directory, is_function, path_segments, EntryPointRule, LanguageAdapter};
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::lsp::LspSymbolKind;
+
+/// Adapter for Rust
+pub struct RustAdapter;
+
+const ENTRY_POINTS: &[EntryPointRule] = &[EntryPointRule {
+    kinds: &[SymbolKind::Function],
+    name: "main",
+    file_name: None,
+}];
+
+impl LanguageAdapter for RustAdapter {
+    /// The crate-relative module path: segments after the last `src`
+    /// directory under the crate name (the directory holding `src`, or
+    /// `crate` at the scan root), with `lib`, `main` and `mod` files naming
+    /// their directory's module
+    fn module_path(&self, relative: &Path) -> Vec<String> {
+        let segments = path_segments(relative);
+        let mut module = match segments.iter().rposition(|s| s == "src") {
+            Some(src) => {
+                let krate = src
+                    .checked_sub(1)
+                    .map_or_else(|| "crate".to_string(), |i| segments[i].replace('-', "_"));
+                std::iter::once(krate)
+                    .chain(segments[src + 1..].iter().cloned())
+                    .collect()
+            }
+            None => segments,
+        };
+        if module.len() > 1
+            && module
+                .last()
+                .is_some_and(|stem| ["lib", "main", "mod"].contains(&stem.as_str()))
+        {
+            module.pop();
+        }
+        module
+    }
+    /// Reduce `impl` headers to the implementing type, so methods are named
+    /// `Type::method` whether or not they come from a trait impl
+    fn normalize_name<'a>(&self, name: &'a str) -> &'a str {
+        impl_self_type(name).unwrap_or(name)
+    }
+
+    fn map_symbol_kind(&self, kind: LspSymbolKind) -> SymbolKind {
+        match kind {
+            // rust-analyzer reports traits as interfaces
+            LspSymbolKind::Interface => SymbolKind::Trait,
+            _ => super::default_symbol_kind(kind),
+        }
+    }
+
+    fn detect_visibility(&self, signature: Option<&str>, _name: &str) -> Option<String> {
+        let sig = signature?.trim_start();
+        if let Some(rest) = sig.strip_prefix("pub") {
+            if rest.starts_with('(') {
+                let end = rest.find(')')?;
+                return Some(format!("pub{}", &rest[..=end]));
+            }
+            if rest.starts_with(char::is_whitespace) {
+                return Some("pub".to_string());
+            }
+        }
+        Some("private".to_string())
+    }
+
+    fn entry_point_rules(&self) -> &'static [EntryPointRule] {
+        ENTRY_POINTS
+    }
+
+    /// Integration tests and benchmarks, and `tests.rs`, `tests_*.rs` and
+    /// `*_test(s).rs` files
+    fn is_test_file(&self, relative: &Path) -> bool {
+        let stem = file_name(relative).trim_end_matches(".rs");
+        in_directory(relative, &["tests", "benches"])
+            || stem == "tests"
+            || stem.starts_with("tests_")
+            || stem.ends_with("_test")
+            || stem.ends_with("_tests")
+    }
+
+    /// Functions of test files, of `tests` modules and named `test_*`
+    fn is_test(&self, symbol: &SymbolNode, relative: &Path) -> bool {
+        is_function(symbol)
+            && (self.is_test_file(relative)
+                || symbol.name.starts_with("test_")
+                || symbol.qualified_name.split("::").any(|s| s == "tests"))
+    }
+}
+
+/// The self type of an `impl` header, without generic arguments
+///
+/// `impl<T> Display for Wrapper<T>` yields `Wrapper`.
+fn impl_self_type(name: &str) -> Option<&str> {
+    let rest = name.strip_prefix("impl")?;
+    let rest = match rest.chars().next()? {
+        '<' => skip_generics(rest)?,
+        c if c.is_whitespace() => rest,
+        _ => return None,
+    };
+    let target = rest.rsplit(" for ").next()?.trim();
+    let target = target.split('<').next()?.trim();
+    (!target.is_empty()).then_some(target)
+}
+
+/// Skip a leading balanced `<...>` group
+fn skip_generics(s: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(&s[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
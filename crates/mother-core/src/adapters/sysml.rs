@@ -0,0 +1,12 @@
+//! SysML adapter: syster-lsp symbols for SysML and KerML
+
+use super::{find_keyword, LanguageAdapter};
+
+/// Adapter for SysML and KerML
+pub struct SysmlAdapter;
+
+impl LanguageAdapter for SysmlAdapter {
+    fn detect_visibility(&self, signature: Option<&str>, _name: &str) -> Option<String> {
+        find_keyword(signature?, &["private", "protected", "public"]).map(str::to_string)
+    }
+}
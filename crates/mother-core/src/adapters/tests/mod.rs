@@ -0,0 +1,5 @@
+//! Tests for language adapters
+
+mod tests_entry_points;
+mod tests_normalize;
+mod tests_test_detection;
@@ -0,0 +1,88 @@
+//! Tests for entry point rules
+
+use crate::adapters::{adapter_for, EntryPointRule};
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::scanner::Language;
+
+fn make_symbol(name: &str, kind: SymbolKind, file_path: &str) -> SymbolNode {
+    SymbolNode::new("id", name, kind, file_path, 1, 1)
+}
+
+// ============================================================================
+// EntryPointRule::matches
+// ============================================================================
+
+#[test]
+fn test_rule_matches_name_and_kind() {
+    let rule = EntryPointRule {
+        kinds: &[SymbolKind::Function],
+        name: "main",
+        file_name: None,
+    };
+    assert!(rule.matches(&make_symbol("main", SymbolKind::Function, "/a.rs")));
+    assert!(!rule.matches(&make_symbol("main", SymbolKind::Method, "/a.rs")));
+    assert!(!rule.matches(&make_symbol("run", SymbolKind::Function, "/a.rs")));
+}
+
+#[test]
+fn test_rule_restricted_to_file_name() {
+    let rule = EntryPointRule {
+        kinds: &[SymbolKind::Function],
+        name: "main",
+        file_name: Some("main.rs"),
+    };
+    assert!(rule.matches(&make_symbol(
+        "main",
+        SymbolKind::Function,
+        "/repo/src/main.rs"
+    )));
+    assert!(!rule.matches(&make_symbol(
+        "main",
+        SymbolKind::Function,
+        "/repo/src/lib.rs"
+    )));
+}
+
+// ============================================================================
+// Per-language rules
+// ============================================================================
+
+#[test]
+fn test_rust_main_is_entry_point() {
+    let adapter = adapter_for(&Language::Rust);
+    assert!(adapter.is_entry_point(&make_symbol("main", SymbolKind::Function, "/src/main.rs")));
+    assert!(!adapter.is_entry_point(&make_symbol("helper", SymbolKind::Function, "/src/main.rs")));
+}
+
+#[test]
+fn test_go_init_is_entry_point() {
+    let adapter = adapter_for(&Language::Go);
+    assert!(adapter.is_entry_point(&make_symbol("init", SymbolKind::Function, "/cmd/app.go")));
+    assert!(adapter.is_entry_point(&make_symbol("main", SymbolKind::Function, "/cmd/app.go")));
+}
+
+#[test]
+fn test_ruby_interpreter_hooks_are_entry_points() {
+    let adapter = adapter_for(&Language::Ruby);
+    assert!(adapter.is_entry_point(&make_symbol("initialize", SymbolKind::Method, "/user.rb")));
+    assert!(adapter.is_entry_point(&make_symbol(
+        "method_missing",
+        SymbolKind::Method,
+        "/proxy.rb"
+    )));
+    assert!(!adapter.is_entry_point(&make_symbol("main", SymbolKind::Method, "/user.rb")));
+}
+
+#[test]
+fn test_python_main_is_entry_point() {
+    let adapter = adapter_for(&Language::Python);
+    assert!(adapter.is_entry_point(&make_symbol("main", SymbolKind::Function, "/cli.py")));
+    assert!(!adapter.is_entry_point(&make_symbol("main", SymbolKind::Variable, "/cli.py")));
+}
+
+#[test]
+fn test_sysml_has_no_entry_points() {
+    let adapter = adapter_for(&Language::SysML);
+    assert!(adapter.entry_point_rules().is_empty());
+    assert!(!adapter.is_entry_point(&make_symbol("main", SymbolKind::Function, "/m.sysml")));
+}
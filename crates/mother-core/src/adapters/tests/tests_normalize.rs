@@ -0,0 +1,255 @@
+//! Tests for qualified name and kind normalization
+
+use std::path::Path;
+
+use crate::adapters::{adapter_for, adapter_for_path};
+use crate::graph::model::SymbolKind;
+use crate::lsp::LspSymbolKind;
+use crate::scanner::Language;
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+#[test]
+fn test_adapter_for_uses_language_separator() {
+    assert_eq!(adapter_for(&Language::Rust).separator(), "::");
+    assert_eq!(adapter_for(&Language::Python).separator(), ".");
+    assert_eq!(adapter_for(&Language::TypeScript).separator(), ".");
+    assert_eq!(adapter_for(&Language::JavaScript).separator(), ".");
+    assert_eq!(adapter_for(&Language::Go).separator(), ".");
+    assert_eq!(adapter_for(&Language::SysML).separator(), "::");
+    assert_eq!(adapter_for(&Language::KerML).separator(), "::");
+}
+
+#[test]
+fn test_adapter_for_path_detects_language() {
+    assert_eq!(adapter_for_path(Path::new("/repo/app.py")).separator(), ".");
+    assert_eq!(
+        adapter_for_path(Path::new("/repo/lib.rs")).separator(),
+        "::"
+    );
+}
+
+#[test]
+fn test_adapter_for_unknown_path_keeps_double_colon() {
+    let adapter = adapter_for_path(Path::new("/repo/README"));
+    assert_eq!(adapter.normalize_qualified_name(Some("a"), "b"), "a::b");
+}
+
+// ============================================================================
+// Qualified names
+// ============================================================================
+
+#[test]
+fn test_qualified_name_without_parent() {
+    let adapter = adapter_for(&Language::Python);
+    assert_eq!(adapter.normalize_qualified_name(None, "run"), "run");
+    assert_eq!(adapter.normalize_qualified_name(Some(""), "run"), "run");
+}
+
+#[test]
+fn test_python_qualified_name_uses_dots() {
+    let adapter = adapter_for(&Language::Python);
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("Server"), "start"),
+        "Server.start"
+    );
+}
+
+#[test]
+fn test_rust_impl_block_named_after_self_type() {
+    let adapter = adapter_for(&Language::Rust);
+    assert_eq!(
+        adapter.normalize_qualified_name(None, "impl Server"),
+        "Server"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(None, "impl Display for Server"),
+        "Server"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("net"), "impl<T> From<T> for Wrapper<T>"),
+        "net::Wrapper"
+    );
+}
+
+#[test]
+fn test_rust_non_impl_names_unchanged() {
+    let adapter = adapter_for(&Language::Rust);
+    assert_eq!(
+        adapter.normalize_qualified_name(None, "implement"),
+        "implement"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("Server"), "new"),
+        "Server::new"
+    );
+}
+
+#[test]
+fn test_go_method_receiver_becomes_qualifier() {
+    let adapter = adapter_for(&Language::Go);
+    assert_eq!(
+        adapter.normalize_qualified_name(None, "(*Server).Start"),
+        "Server.Start"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(None, "(Point).String"),
+        "Point.String"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("main"), "(*Server).Start"),
+        "main.Server.Start"
+    );
+}
+
+#[test]
+fn test_go_plain_names() {
+    let adapter = adapter_for(&Language::Go);
+    assert_eq!(adapter.normalize_qualified_name(None, "Run"), "Run");
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("Server"), "addr"),
+        "Server.addr"
+    );
+}
+
+#[test]
+fn test_ruby_singleton_methods_lose_receiver() {
+    let adapter = adapter_for(&Language::Ruby);
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("Billing::Invoice"), "self.create"),
+        "Billing::Invoice::create"
+    );
+    assert_eq!(
+        adapter.normalize_qualified_name(Some("Invoice"), "total"),
+        "Invoice::total"
+    );
+}
+
+// ============================================================================
+// Symbol kinds
+// ============================================================================
+
+#[test]
+fn test_rust_interfaces_are_traits() {
+    assert_eq!(
+        adapter_for(&Language::Rust).map_symbol_kind(LspSymbolKind::Interface),
+        SymbolKind::Trait
+    );
+}
+
+#[test]
+fn test_typescript_constructors_are_methods() {
+    assert_eq!(
+        adapter_for(&Language::TypeScript).map_symbol_kind(LspSymbolKind::Constructor),
+        SymbolKind::Method
+    );
+    assert_eq!(
+        adapter_for(&Language::TypeScript).map_symbol_kind(LspSymbolKind::Interface),
+        SymbolKind::Interface
+    );
+}
+
+#[test]
+fn test_default_kinds_shared_across_languages() {
+    for language in [Language::Python, Language::Go, Language::SysML] {
+        let adapter = adapter_for(&language);
+        assert_eq!(
+            adapter.map_symbol_kind(LspSymbolKind::Function),
+            SymbolKind::Function
+        );
+        assert_eq!(
+            adapter.map_symbol_kind(LspSymbolKind::Interface),
+            SymbolKind::Interface
+        );
+        assert_eq!(
+            adapter.map_symbol_kind(LspSymbolKind::Null),
+            SymbolKind::Variable
+        );
+    }
+}
+
+// ============================================================================
+// Visibility
+// ============================================================================
+
+#[test]
+fn test_go_visibility_ignores_receiver() {
+    let adapter = adapter_for(&Language::Go);
+    assert_eq!(
+        adapter.detect_visibility(None, "(*Server).Start"),
+        Some("public".to_string())
+    );
+    assert_eq!(
+        adapter.detect_visibility(None, "(*Server).start"),
+        Some("private".to_string())
+    );
+}
+
+#[test]
+fn test_ruby_visibility_is_unknown() {
+    let adapter = adapter_for(&Language::Ruby);
+    assert_eq!(adapter.detect_visibility(Some("def total"), "total"), None);
+}
+
+// ============================================================================
+// Module paths
+// ============================================================================
+
+fn module_path(language: Language, path: &str) -> Vec<String> {
+    adapter_for(&language).module_path(Path::new(path))
+}
+
+#[test]
+fn test_rust_module_path_starts_at_the_crate() {
+    assert_eq!(
+        module_path(
+            Language::Rust,
+            "crates/mother-core/src/graph/queries/read.rs"
+        ),
+        vec!["mother_core", "graph", "queries", "read"]
+    );
+    assert_eq!(
+        module_path(Language::Rust, "src/graph/mod.rs"),
+        vec!["crate", "graph"]
+    );
+    assert_eq!(module_path(Language::Rust, "src/lib.rs"), vec!["crate"]);
+    assert_eq!(
+        module_path(Language::Rust, "tests/cli.rs"),
+        vec!["tests", "cli"]
+    );
+}
+
+#[test]
+fn test_python_and_typescript_module_paths_drop_package_files() {
+    assert_eq!(
+        module_path(Language::Python, "src/app/models/__init__.py"),
+        vec!["app", "models"]
+    );
+    assert_eq!(
+        module_path(Language::Python, "app/views.py"),
+        vec!["app", "views"]
+    );
+    assert_eq!(
+        module_path(Language::TypeScript, "src/components/index.ts"),
+        vec!["components"]
+    );
+}
+
+#[test]
+fn test_go_module_path_is_the_package_directory() {
+    assert_eq!(
+        module_path(Language::Go, "internal/server/handler.go"),
+        vec!["internal", "server"]
+    );
+    assert!(module_path(Language::Go, "main.go").is_empty());
+}
+
+#[test]
+fn test_default_module_path_is_directories_and_stem() {
+    assert_eq!(
+        module_path(Language::Ruby, "lib/shop/cart.rb"),
+        vec!["lib", "shop", "cart"]
+    );
+}
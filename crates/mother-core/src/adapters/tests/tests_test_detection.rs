@@ -0,0 +1,144 @@
+//! Tests for recognizing test symbols
+
+use std::path::Path;
+
+use crate::adapters::adapter_for;
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::scanner::Language;
+
+fn make_symbol(name: &str, qualified_name: &str, kind: SymbolKind) -> SymbolNode {
+    SymbolNode {
+        qualified_name: qualified_name.to_string(),
+        ..SymbolNode::new("id", name, kind, String::new(), 1, 1)
+    }
+}
+
+fn is_test(language: Language, name: &str, qualified_name: &str, relative: &str) -> bool {
+    let symbol = make_symbol(name, qualified_name, SymbolKind::Function);
+    adapter_for(&language).is_test(&symbol, Path::new(relative))
+}
+
+#[test]
+fn test_rust_tests_by_module_name_and_location() {
+    assert!(is_test(
+        Language::Rust,
+        "parses",
+        "graph::tests::parses",
+        "src/graph.rs"
+    ));
+    assert!(is_test(
+        Language::Rust,
+        "test_load",
+        "load::test_load",
+        "src/load.rs"
+    ));
+    assert!(is_test(
+        Language::Rust,
+        "helper",
+        "helper",
+        "crates/core/tests/common.rs"
+    ));
+    assert!(is_test(
+        Language::Rust,
+        "scan",
+        "scan",
+        "src/graph/tests/tests_scan.rs"
+    ));
+    assert!(!is_test(
+        Language::Rust,
+        "load",
+        "config::load",
+        "src/config.rs"
+    ));
+
+    let adapter = adapter_for(&Language::Rust);
+    let tests_struct = make_symbol("Fixture", "tests::Fixture", SymbolKind::Struct);
+    assert!(!adapter.is_test(&tests_struct, Path::new("tests/common.rs")));
+}
+
+#[test]
+fn test_python_tests_need_name_and_file() {
+    assert!(is_test(
+        Language::Python,
+        "test_load",
+        "test_load",
+        "tests/test_config.py"
+    ));
+    assert!(is_test(
+        Language::Python,
+        "test_load",
+        "TestConfig.test_load",
+        "config_test.py"
+    ));
+    assert!(!is_test(
+        Language::Python,
+        "fixture",
+        "fixture",
+        "tests/conftest.py"
+    ));
+    assert!(!is_test(
+        Language::Python,
+        "test_mode",
+        "test_mode",
+        "app/config.py"
+    ));
+}
+
+#[test]
+fn test_typescript_go_and_ruby_test_files() {
+    assert!(is_test(
+        Language::TypeScript,
+        "it() callback",
+        "it() callback",
+        "src/app.test.ts"
+    ));
+    assert!(is_test(
+        Language::JavaScript,
+        "setup",
+        "setup",
+        "src/__tests__/app.js"
+    ));
+    assert!(!is_test(Language::TypeScript, "load", "load", "src/app.ts"));
+
+    assert!(is_test(
+        Language::Go,
+        "TestLoad",
+        "config.TestLoad",
+        "config/config_test.go"
+    ));
+    assert!(!is_test(
+        Language::Go,
+        "newFixture",
+        "config.newFixture",
+        "config/config_test.go"
+    ));
+    assert!(!is_test(
+        Language::Go,
+        "TestMode",
+        "config.TestMode",
+        "config/config.go"
+    ));
+
+    assert!(is_test(
+        Language::Ruby,
+        "block",
+        "block",
+        "spec/models/user_spec.rb"
+    ));
+    assert!(!is_test(
+        Language::Ruby,
+        "save",
+        "User#save",
+        "app/models/user.rb"
+    ));
+}
+
+#[test]
+fn test_other_languages_have_no_tests() {
+    assert!(!is_test(
+        Language::Other("elixir".to_string()),
+        "test_load",
+        "test_load",
+        "test/config_test.exs"
+    ));
+}
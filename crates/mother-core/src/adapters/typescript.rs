@@ -0,0 +1,49 @@
+//! TypeScript adapter: typescript-language-server symbols for TS and JS
+
+use std::path::Path;
+
+use super::{file_name, find_keyword, in_directory, path_segments, LanguageAdapter};
+use crate::graph::model::SymbolKind;
+use crate::lsp::LspSymbolKind;
+
+/// Adapter for TypeScript and JavaScript
+pub struct TypeScriptAdapter;
+
+impl LanguageAdapter for TypeScriptAdapter {
+    fn separator(&self) -> &'static str {
+        "."
+    }
+
+    /// `index` files name their directory's module and a leading `src`
+    /// directory is dropped
+    fn module_path(&self, relative: &Path) -> Vec<String> {
+        let mut segments = path_segments(relative);
+        if segments.len() > 1 && segments.first().is_some_and(|s| s == "src") {
+            segments.remove(0);
+        }
+        if segments.len() > 1 && segments.last().is_some_and(|s| s == "index") {
+            segments.pop();
+        }
+        segments
+    }
+
+    fn map_symbol_kind(&self, kind: LspSymbolKind) -> SymbolKind {
+        match kind {
+            // Class constructors are members, not free functions
+            LspSymbolKind::Constructor => SymbolKind::Method,
+            _ => super::default_symbol_kind(kind),
+        }
+    }
+
+    fn detect_visibility(&self, signature: Option<&str>, _name: &str) -> Option<String> {
+        find_keyword(signature?, &["private", "protected", "public", "export"])
+            .map(|kw| if kw == "export" { "public" } else { kw }.to_string())
+    }
+
+    /// `*.test.*` and `*.spec.*` files and files in `__tests__` directories;
+    /// their functions include the callbacks of `describe` and `it`
+    fn is_test_file(&self, relative: &Path) -> bool {
+        let name = file_name(relative);
+        in_directory(relative, &["__tests__"]) || name.contains(".test.") || name.contains(".spec.")
+    }
+}
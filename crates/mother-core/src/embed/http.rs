@@ -0,0 +1,98 @@
+//! OpenAI embedder: Embeddings from an OpenAI-compatible HTTP API
+//!
+//! Works with OpenAI itself and with servers that mirror its
+//! `POST /v1/embeddings` endpoint (Ollama, vLLM, LiteLLM, ...).
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::Embedder;
+
+/// Endpoint used when none is configured
+pub const DEFAULT_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+
+/// Embedder calling an OpenAI-compatible embeddings endpoint
+pub struct OpenAiEmbedder {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiEmbedder {
+    /// Create an embedder for `model` at the default OpenAI endpoint
+    #[must_use]
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: DEFAULT_EMBEDDINGS_URL.to_string(),
+            model: model.into(),
+            api_key: None,
+        }
+    }
+
+    /// Send requests to another OpenAI-compatible endpoint
+    #[must_use]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Authenticate with a bearer token
+    #[must_use]
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut request = self.client.post(&self.url).json(&EmbeddingRequest {
+            model: &self.model,
+            input: texts,
+        });
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response: EmbeddingResponse = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to call {}", self.url))?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Invalid embeddings response")?;
+
+        // Entries carry their input index and need not come in order
+        let mut data = response.data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
@@ -0,0 +1,128 @@
+//! Embed module: Vector embeddings of symbols for semantic search
+//!
+//! An [`Embedder`] turns text into vectors. During a scan, the
+//! [`EmbeddingEnricher`] embeds each symbol's kind, qualified name,
+//! signature and docs and keeps the vector on the [`SymbolNode`], which the
+//! store saves with the symbol (a vector-indexed `embedding` property in
+//! Neo4j). Symbols close to each other in that space do similar things,
+//! which `query similar` uses to find related code by meaning rather than
+//! by name.
+//!
+//! Embedders are behind features: `openai` calls an OpenAI-compatible
+//! embeddings API over HTTP, `onnx` runs a local ONNX sentence embedding
+//! model.
+
+#[cfg(feature = "openai")]
+mod http;
+#[cfg(feature = "onnx")]
+mod onnx;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::enrich::{EnrichContext, Enricher};
+use crate::graph::model::SymbolNode;
+
+#[cfg(feature = "openai")]
+pub use http::OpenAiEmbedder;
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxEmbedder;
+
+/// Most texts sent to an embedder in one call
+pub const EMBED_BATCH_SIZE: usize = 64;
+
+/// Computes vector embeddings of texts
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Name of the model, for logs
+    fn model(&self) -> &str;
+
+    /// Embed each text, returning one vector per text in the same order;
+    /// every vector of a model has the same length
+    ///
+    /// # Errors
+    /// Returns an error if the model cannot be run or the service fails.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+impl std::fmt::Debug for dyn Embedder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Embedder")
+            .field("model", &self.model())
+            .finish()
+    }
+}
+
+/// Text embedded for a symbol: its kind and qualified name, then its
+/// signature and doc comment when it has them
+#[must_use]
+pub fn embedding_text(symbol: &SymbolNode) -> String {
+    let mut parts = vec![format!("{} {}", symbol.kind, symbol.qualified_name)];
+    parts.extend(symbol.signature.clone());
+    parts.extend(symbol.doc_comment.clone());
+    parts.join("\n")
+}
+
+/// Cosine similarity of two vectors, 0 if either is zero or their lengths
+/// differ
+#[must_use]
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// Embeds the symbols of each file; runs last so it sees the signatures
+/// and docs earlier enrichers filled in
+pub struct EmbeddingEnricher {
+    embedder: Arc<dyn Embedder>,
+}
+
+impl EmbeddingEnricher {
+    pub const NAME: &'static str = "embedding";
+
+    /// Create an enricher embedding symbols with `embedder`
+    #[must_use]
+    pub fn new(embedder: Arc<dyn Embedder>) -> Self {
+        Self { embedder }
+    }
+}
+
+#[async_trait]
+impl Enricher for EmbeddingEnricher {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn enrich(&self, ctx: &mut EnrichContext<'_>) -> Result<()> {
+        for symbols in ctx.symbols.chunks_mut(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = symbols.iter().map(embedding_text).collect();
+            let embeddings = self.embedder.embed(&texts).await?;
+            if embeddings.len() != texts.len() {
+                anyhow::bail!(
+                    "{} returned {} embeddings for {} texts",
+                    self.embedder.model(),
+                    embeddings.len(),
+                    texts.len()
+                );
+            }
+            for (symbol, embedding) in symbols.iter_mut().zip(embeddings) {
+                symbol.embedding = Some(embedding);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,127 @@
+//! ONNX embedder: Embeddings from a local sentence embedding model
+//!
+//! Runs a transformer exported to ONNX (e.g. `all-MiniLM-L6-v2`) with its
+//! Hugging Face `tokenizer.json`, so no text leaves the machine. Token
+//! embeddings are mean-pooled over the attention mask and normalized.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use ort::session::Session;
+use ort::value::Tensor;
+use tokenizers::Tokenizer;
+
+use super::Embedder;
+
+/// Embedder running an ONNX model in process
+pub struct OnnxEmbedder {
+    model: String,
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    /// Whether the model takes a `token_type_ids` input (BERT-style models)
+    token_types: bool,
+}
+
+impl OnnxEmbedder {
+    /// Load a model and the tokenizer it was trained with
+    ///
+    /// # Errors
+    /// Returns an error if either file cannot be loaded.
+    pub fn from_files(model: &Path, tokenizer: &Path) -> Result<Self> {
+        let session = Session::builder()?
+            .commit_from_file(model)
+            .with_context(|| format!("Failed to load ONNX model {}", model.display()))?;
+        let tokenizer = Tokenizer::from_file(tokenizer)
+            .map_err(|e| anyhow!("Failed to load tokenizer {}: {e}", tokenizer.display()))?;
+        let token_types = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+        Ok(Self {
+            model: model.display().to_string(),
+            session: Mutex::new(session),
+            tokenizer,
+            token_types,
+        })
+    }
+}
+
+#[async_trait]
+impl Embedder for OnnxEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow!("Failed to tokenize: {e}"))?;
+        let rows = encodings.len();
+        let width = encodings.iter().map(|e| e.len()).max().unwrap_or(0);
+
+        // Pad every row to the longest; padding is masked out
+        let mut ids = vec![0_i64; rows * width];
+        let mut mask = vec![0_i64; rows * width];
+        for (row, encoding) in encodings.iter().enumerate() {
+            for (col, id) in encoding.get_ids().iter().enumerate() {
+                ids[row * width + col] = i64::from(*id);
+                mask[row * width + col] = 1;
+            }
+        }
+        let shape = [rows, width];
+
+        // The model runs on this thread; sessions are not shared across runs
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| anyhow!("ONNX session poisoned"))?;
+        let mut inputs = ort::inputs![
+            "input_ids" => Tensor::from_array((shape, ids))?,
+            "attention_mask" => Tensor::from_array((shape, mask.clone()))?,
+        ];
+        if self.token_types {
+            let types = vec![0_i64; rows * width];
+            inputs.push((
+                "token_type_ids".into(),
+                Tensor::from_array((shape, types))?.into(),
+            ));
+        }
+        let outputs = session.run(inputs)?;
+        let (output_shape, hidden) = outputs[0].try_extract_tensor::<f32>()?;
+        let dimensions = usize::try_from(output_shape[2])?;
+
+        Ok((0..rows)
+            .map(|row| mean_pool(hidden, &mask, row, width, dimensions))
+            .collect())
+    }
+}
+
+/// Average the token vectors of one row over its mask, then normalize;
+/// normalizing makes dividing by the token count unnecessary
+fn mean_pool(
+    hidden: &[f32],
+    mask: &[i64],
+    row: usize,
+    width: usize,
+    dimensions: usize,
+) -> Vec<f32> {
+    let mut pooled = vec![0.0_f32; dimensions];
+    for col in 0..width {
+        if mask[row * width + col] == 0 {
+            continue;
+        }
+        let start = (row * width + col) * dimensions;
+        for (sum, value) in pooled.iter_mut().zip(&hidden[start..start + dimensions]) {
+            *sum += value;
+        }
+    }
+    let norm = pooled.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let scale = if norm == 0.0 { 0.0 } else { 1.0 / norm };
+    pooled.iter().map(|x| x * scale).collect()
+}
@@ -0,0 +1,3 @@
+//! Tests for embed module
+
+mod tests_embed;
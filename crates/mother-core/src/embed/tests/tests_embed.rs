@@ -0,0 +1,171 @@
+//! Tests for embedding text, similarity and the embedding enricher
+
+#![allow(clippy::expect_used)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::embed::{
+    cosine_similarity, embedding_text, Embedder, EmbeddingEnricher, EMBED_BATCH_SIZE,
+};
+use crate::enrich::{EnrichContext, Enricher};
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::lsp::{LspClient, LspServerConfig};
+use crate::scanner::Language;
+
+/// Start a placeholder process in place of an LSP server
+///
+/// The embedding enricher never talks to the server.
+async fn idle_client() -> LspClient {
+    let config = LspServerConfig {
+        language: Language::Rust,
+        command: "sleep".to_string(),
+        args: vec!["30".to_string()],
+        root_path: std::env::temp_dir(),
+        init_options: None,
+    };
+    LspClient::start(config)
+        .await
+        .expect("Failed to start placeholder process")
+}
+
+fn make_symbol(name: &str) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("crate::{name}"),
+        ..SymbolNode::new(name, name, SymbolKind::Function, "/test.rs", 1, 2)
+    }
+}
+
+/// Embeds each text as `[length, call number]`, counting its calls
+#[derive(Default)]
+struct FakeEmbedder {
+    calls: AtomicUsize,
+    /// Drop the last embedding of each batch, like a misbehaving service
+    short: bool,
+}
+
+#[async_trait]
+impl Embedder for FakeEmbedder {
+    fn model(&self) -> &str {
+        "fake"
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        let mut embeddings: Vec<Vec<f32>> = texts
+            .iter()
+            .map(|t| vec![t.len() as f32, call as f32])
+            .collect();
+        if self.short {
+            embeddings.pop();
+        }
+        Ok(embeddings)
+    }
+}
+
+// ============================================================================
+// Embedding text
+// ============================================================================
+
+#[test]
+fn test_embedding_text_of_bare_symbol_is_kind_and_name() {
+    assert_eq!(embedding_text(&make_symbol("load")), "function crate::load");
+}
+
+#[test]
+fn test_embedding_text_appends_signature_and_docs() {
+    let mut symbol = make_symbol("load");
+    symbol.signature = Some("fn load(path: &Path)".to_string());
+    symbol.doc_comment = Some("Load a config file".to_string());
+
+    assert_eq!(
+        embedding_text(&symbol),
+        "function crate::load\nfn load(path: &Path)\nLoad a config file"
+    );
+}
+
+// ============================================================================
+// Cosine similarity
+// ============================================================================
+
+#[test]
+fn test_cosine_similarity_ignores_magnitude() {
+    let similarity = cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]);
+    assert!((similarity - 1.0).abs() < 1e-6, "{similarity}");
+}
+
+#[test]
+fn test_cosine_similarity_of_orthogonal_vectors_is_zero() {
+    assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+}
+
+#[test]
+fn test_cosine_similarity_of_zero_or_mismatched_vectors_is_zero() {
+    assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    assert_eq!(cosine_similarity(&[1.0], &[1.0, 1.0]), 0.0);
+}
+
+// ============================================================================
+// Enricher
+// ============================================================================
+
+#[tokio::test]
+async fn test_enricher_embeds_every_symbol_in_batches() {
+    let client = idle_client().await;
+    let embedder = Arc::new(FakeEmbedder::default());
+    let enricher = EmbeddingEnricher::new(embedder.clone());
+    let mut symbols: Vec<SymbolNode> = (0..=EMBED_BATCH_SIZE)
+        .map(|i| make_symbol(&format!("f{i}")))
+        .collect();
+
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///test.rs",
+        language: Language::Rust,
+    };
+    enricher.enrich(&mut ctx).await.expect("enrich succeeds");
+
+    assert_eq!(embedder.calls.load(Ordering::Relaxed), 2);
+    let first = symbols[0].embedding.as_ref().expect("first embedded");
+    assert_eq!(first, &vec![embedding_text(&symbols[0]).len() as f32, 0.0]);
+    let last = symbols[EMBED_BATCH_SIZE]
+        .embedding
+        .as_ref()
+        .expect("last embedded");
+    assert_eq!(last[1], 1.0);
+}
+
+#[tokio::test]
+async fn test_enricher_rejects_missing_embeddings() {
+    let client = idle_client().await;
+    let embedder = Arc::new(FakeEmbedder {
+        short: true,
+        ..FakeEmbedder::default()
+    });
+    let enricher = EmbeddingEnricher::new(embedder);
+    let mut symbols = vec![make_symbol("a"), make_symbol("b")];
+
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///test.rs",
+        language: Language::Rust,
+    };
+    let error = enricher
+        .enrich(&mut ctx)
+        .await
+        .expect_err("short batch fails")
+        .to_string();
+
+    assert!(
+        error.contains("fake returned 1 embeddings for 2 texts"),
+        "{error}"
+    );
+    assert!(symbols.iter().all(|s| s.embedding.is_none()));
+}
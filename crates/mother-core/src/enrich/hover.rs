@@ -0,0 +1,371 @@
+//! Hover enricher: Attach hover documentation and type information to symbols
+
+use std::num::NonZeroUsize;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+
+use super::signature::signature_from_hover;
+use super::{EnrichContext, Enricher};
+use crate::graph::model::{ParameterNode, SymbolKind};
+use crate::lsp::collect_symbol_positions;
+
+/// Hover requests a file has in flight at once unless configured otherwise
+pub const DEFAULT_HOVER_CONCURRENCY: NonZeroUsize = match NonZeroUsize::new(8) {
+    Some(n) => n,
+    None => unreachable!(),
+};
+
+/// Which symbols the hover enricher asks about, and how many at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverSettings {
+    /// Kinds of symbols to hover; empty hovers every symbol
+    pub kinds: Vec<SymbolKind>,
+    /// Most hover requests in flight at once for a file
+    pub concurrency: NonZeroUsize,
+}
+
+impl Default for HoverSettings {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            concurrency: DEFAULT_HOVER_CONCURRENCY,
+        }
+    }
+}
+
+impl HoverSettings {
+    /// Whether symbols of `kind` are hovered
+    #[must_use]
+    pub fn selects(&self, kind: SymbolKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// A symbol kind by name, singular or plural: `function`, `methods`,
+/// `classes`, `type_aliases`
+///
+/// # Errors
+/// Returns an error if the name is no symbol kind.
+pub fn parse_kind(name: &str) -> Result<SymbolKind, String> {
+    let name = name.trim().to_lowercase();
+    let kind = [
+        Some(name.as_str()),
+        name.strip_suffix("es"),
+        name.strip_suffix('s'),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|singular| singular.parse().ok());
+    kind.ok_or_else(|| format!("Unknown symbol kind: {name}"))
+}
+
+/// Stores the LSP hover content of each symbol, split into its parts
+///
+/// The declaration code block becomes the type signature, which the resolved
+/// type is read from; the markdown around it becomes the doc comment. Only
+/// symbols of the [selected kinds](HoverSettings::kinds) are hovered, with
+/// up to [`concurrency`](HoverSettings::concurrency) requests in flight.
+#[derive(Debug, Clone, Default)]
+pub struct HoverEnricher {
+    settings: HoverSettings,
+}
+
+impl HoverEnricher {
+    pub const NAME: &'static str = "hover";
+
+    #[must_use]
+    pub fn new(settings: HoverSettings) -> Self {
+        Self { settings }
+    }
+}
+
+#[async_trait]
+impl Enricher for HoverEnricher {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn enrich(&self, ctx: &mut EnrichContext<'_>) -> Result<()> {
+        let lsp_positions = collect_symbol_positions(ctx.lsp_symbols);
+        let requests: Vec<(usize, u32, u32)> = ctx
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, symbol)| self.settings.selects(symbol.kind))
+            .map(|(i, symbol)| {
+                let col = lsp_positions.get(i).map(|p| p.1).unwrap_or(0);
+                // Use 0-indexed line for hover (symbol.start_line is 1-indexed)
+                (i, symbol.start_line.saturating_sub(1), col)
+            })
+            .collect();
+
+        let (lsp_client, file_uri) = (ctx.lsp_client, ctx.file_uri);
+        let hovers: Vec<(usize, Option<String>)> = stream::iter(requests)
+            .map(|(i, line, col)| async move {
+                (
+                    i,
+                    lsp_client.hover(file_uri, line, col).await.ok().flatten(),
+                )
+            })
+            .buffer_unordered(self.settings.concurrency.get())
+            .collect()
+            .await;
+
+        for (i, hover_content) in hovers {
+            let (Some(symbol), Some(hover_content)) = (ctx.symbols.get_mut(i), hover_content)
+            else {
+                continue;
+            };
+            let parts = HoverParts::parse(&hover_content, &symbol.name);
+            symbol.resolved_type = parts
+                .type_signature
+                .as_deref()
+                .and_then(|signature| resolved_type(signature, &symbol.name));
+            symbol.type_signature = parts.type_signature;
+            symbol.doc_comment = parts.documentation;
+        }
+
+        Ok(())
+    }
+}
+
+/// Hover markdown split into the declaration and the documentation around it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HoverParts {
+    /// The code block declaring the symbol
+    pub type_signature: Option<String>,
+    /// Text outside code blocks, without separator lines
+    pub documentation: Option<String>,
+}
+
+impl HoverParts {
+    /// Split hover markdown for the symbol `name`
+    #[must_use]
+    pub fn parse(hover: &str, name: &str) -> Self {
+        Self {
+            type_signature: signature_from_hover(hover, name),
+            documentation: documentation(hover),
+        }
+    }
+}
+
+/// The markdown outside fenced code blocks, with `---` separators dropped and
+/// runs of blank lines collapsed
+fn documentation(markdown: &str) -> Option<String> {
+    let mut in_block = false;
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_block = !in_block;
+            continue;
+        }
+        let separator = trimmed.len() >= 3 && trimmed.chars().all(|c| matches!(c, '-' | '*' | '_'));
+        let repeated_blank = trimmed.is_empty() && lines.last().is_none_or(|l| l.trim().is_empty());
+        if !in_block && !separator && !repeated_blank {
+            lines.push(line);
+        }
+    }
+
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The type a declaration gives its symbol
+///
+/// For values (`let count: usize`, `(variable) path: str`) this is the
+/// declared type; for functions (`fn parse(s: &str) -> Ast`,
+/// `function f(): string`, `func f() (int, error)`) the return type. `None`
+/// when the declaration names no type, e.g. for structs or `fn main()`.
+#[must_use]
+pub fn resolved_type(type_signature: &str, name: &str) -> Option<String> {
+    let flat = type_signature
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let after_name = &flat[find_word(&flat, name)? + name.len()..];
+
+    let ty = match after_name.trim_start().strip_prefix(':') {
+        Some(rest) if !rest.starts_with(':') => value_type(rest),
+        _ => return_type(after_name)?,
+    };
+    let ty = ty.trim().trim_end_matches([';', ',']).trim();
+    (!ty.is_empty()).then(|| ty.to_string())
+}
+
+/// Declared type of a value: up to an initializer, if any
+fn value_type(rest: &str) -> &str {
+    rest.split(" = ").next().unwrap_or(rest)
+}
+
+/// What follows a function's parameter list, without the `->`/`:` before
+/// it and any `where` clause or body after it
+fn return_type(after_name: &str) -> Option<&str> {
+    let (_, close) = parameter_list(after_name)?;
+    let rest = after_name[close + 1..].trim_start();
+    let rest = rest
+        .strip_prefix("->")
+        .or_else(|| rest.strip_prefix(':'))
+        .unwrap_or(rest);
+    let end = [rest.find(" where "), rest.find('{')]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+/// The parameters a function declaration lists, in order
+///
+/// Receivers (`self`, `&mut self`, Python's `self` and `cls`) are skipped.
+/// A parameter's type is what follows its `:` (Rust, Python, TypeScript) or
+/// its name (Go), without any default value; `None` when it has none. Empty
+/// when the declaration has no parameter list.
+#[must_use]
+pub fn parameters(type_signature: &str, name: &str) -> Vec<ParameterNode> {
+    let flat = type_signature
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let Some(start) = find_word(&flat, name) else {
+        return Vec::new();
+    };
+    let after_name = &flat[start + name.len()..];
+    let Some((open, close)) = parameter_list(after_name) else {
+        return Vec::new();
+    };
+
+    split_top_level(&after_name[open + 1..close])
+        .into_iter()
+        .enumerate()
+        .filter(|(i, param)| *i > 0 || !is_receiver(param))
+        .filter_map(|(_, param)| parameter(param))
+        .zip(0u32..)
+        .map(|((name, type_name), position)| ParameterNode {
+            name,
+            type_name,
+            position,
+        })
+        .collect()
+}
+
+/// Byte offsets of the parentheses around the parameter list, skipping
+/// generic parameters such as `<F: Fn(u8)>` before it
+fn parameter_list(after_name: &str) -> Option<(usize, usize)> {
+    let mut angle = 0usize;
+    let mut prev = ' ';
+    for (i, c) in after_name.char_indices() {
+        match c {
+            '<' => angle += 1,
+            '>' if prev != '-' && prev != '=' => angle = angle.saturating_sub(1),
+            '(' if angle == 0 => {
+                let close = matching_paren(&after_name[i..])? + i;
+                return Some((i, close));
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
+/// Split a parameter list at the commas outside brackets and generics
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' || prev == '=' => {}
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    parts.push(list[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Whether a parameter is the receiver of a method
+fn is_receiver(param: &str) -> bool {
+    let name = param.split(':').next().unwrap_or(param);
+    let name = name.trim_start_matches(['&', ' ']);
+    let name = match name.strip_prefix('\'') {
+        Some(lifetime) => lifetime.split_once(' ').map_or("", |(_, rest)| rest),
+        None => name,
+    };
+    let name = name.trim_start_matches("mut ").trim();
+    matches!(name, "self" | "cls")
+}
+
+/// Name and type of one parameter; `None` for Python's bare `*` and `/`
+/// markers
+fn parameter(param: &str) -> Option<(String, Option<String>)> {
+    let (name, type_name) = match type_colon(param) {
+        Some(colon) => {
+            let type_name = value_type(&param[colon + 1..]).trim();
+            (&param[..colon], Some(type_name))
+        }
+        None => {
+            let param = param.split('=').next().unwrap_or(param).trim();
+            match param.split_once(' ') {
+                Some((name, type_name)) => (name, Some(type_name.trim())),
+                None => (param, None),
+            }
+        }
+    };
+    let name = name.trim().trim_start_matches("mut ").trim_end_matches('?');
+    if name.is_empty() || name == "*" || name == "/" {
+        return None;
+    }
+    let type_name = type_name.filter(|t| !t.is_empty()).map(str::to_string);
+    Some((name.to_string(), type_name))
+}
+
+/// Byte offset of the `:` separating a parameter from its type, ignoring
+/// `::` path separators
+fn type_colon(param: &str) -> Option<usize> {
+    let bytes = param.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':')
+    })
+}
+
+/// Byte offset of the `)` closing the `(` that `text` starts with
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte offset of the first occurrence of `word` not inside a longer
+/// identifier
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).map(|(i, _)| i).find(|&i| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
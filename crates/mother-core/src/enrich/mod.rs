@@ -0,0 +1,244 @@
+//! Enrich module: Ordered, configurable symbol enrichment pipeline
+//!
+//! Enrichment runs after symbols are extracted from a file and before they are
+//! stored. Each [`Enricher`] adds information to the converted [`SymbolNode`]s
+//! (hover docs, signatures, visibility, ...). Enrichers run in the configured
+//! order, so later enrichers can build on the output of earlier ones. A failing
+//! enricher is isolated: its error is recorded and the pipeline moves on.
+
+mod hover;
+mod signature;
+mod visibility;
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::graph::model::SymbolNode;
+use crate::lsp::{LspClient, LspSymbol};
+use crate::scanner::Language;
+
+pub use hover::{
+    parameters, parse_kind, resolved_type, HoverEnricher, HoverParts, HoverSettings,
+    DEFAULT_HOVER_CONCURRENCY,
+};
+pub use signature::SignatureEnricher;
+pub(crate) use visibility::infer_visibility;
+pub use visibility::VisibilityEnricher;
+
+/// Everything an enricher can read or modify for a single file
+pub struct EnrichContext<'a> {
+    /// Graph symbols converted from `lsp_symbols` (same depth-first order)
+    pub symbols: &'a mut [SymbolNode],
+    /// Raw LSP symbols for the file
+    pub lsp_symbols: &'a [LspSymbol],
+    /// LSP client for the file's language
+    pub lsp_client: &'a LspClient,
+    /// URI of the file being enriched
+    pub file_uri: &'a str,
+    /// Language of the file being enriched
+    pub language: Language,
+}
+
+/// A single enrichment step in the pipeline
+#[async_trait]
+pub trait Enricher: Send + Sync {
+    /// Stable name used in configuration and reports
+    fn name(&self) -> &'static str;
+
+    /// Enrich the symbols of one file
+    ///
+    /// # Errors
+    /// Returns an error if the enrichment fails; the pipeline records it and continues.
+    async fn enrich(&self, ctx: &mut EnrichContext<'_>) -> Result<()>;
+}
+
+/// Configuration for the enrichment pipeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrichmentConfig {
+    /// Enricher names in the order they should run
+    pub enrichers: Vec<String>,
+    /// Settings of the hover enricher, if it runs
+    pub hover: HoverSettings,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self::new(vec![HoverEnricher::NAME.to_string()])
+    }
+}
+
+impl EnrichmentConfig {
+    /// Create a config from an ordered list of enricher names
+    #[must_use]
+    pub fn new(enrichers: Vec<String>) -> Self {
+        Self {
+            enrichers,
+            hover: HoverSettings::default(),
+        }
+    }
+
+    /// Configure the hover enricher
+    #[must_use]
+    pub fn with_hover(mut self, hover: HoverSettings) -> Self {
+        self.hover = hover;
+        self
+    }
+
+    /// Drop an enricher from the pipeline
+    #[must_use]
+    pub fn without(mut self, name: &str) -> Self {
+        self.enrichers.retain(|enricher| enricher.trim() != name);
+        self
+    }
+}
+
+/// Outcome of running one enricher on one file
+#[derive(Debug, Clone)]
+pub struct EnricherReport {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+/// Time and failures of one enricher, summed over the files it ran on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnricherTotal {
+    pub name: &'static str,
+    pub files: usize,
+    pub elapsed: Duration,
+    pub errors: usize,
+}
+
+/// Per-enricher totals over a scan, in pipeline order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnricherTotals {
+    totals: Vec<EnricherTotal>,
+}
+
+impl EnricherTotals {
+    /// Add the reports of running the pipeline on one file
+    pub fn add(&mut self, reports: &[EnricherReport]) {
+        for report in reports {
+            let errors = usize::from(report.error.is_some());
+            match self.totals.iter_mut().find(|t| t.name == report.name) {
+                Some(total) => {
+                    total.files += 1;
+                    total.elapsed += report.elapsed;
+                    total.errors += errors;
+                }
+                None => self.totals.push(EnricherTotal {
+                    name: report.name,
+                    files: 1,
+                    elapsed: report.elapsed,
+                    errors,
+                }),
+            }
+        }
+    }
+
+    /// Totals of each enricher that ran
+    pub fn iter(&self) -> impl Iterator<Item = &EnricherTotal> {
+        self.totals.iter()
+    }
+
+    /// Files an enricher failed on, over all enrichers
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.totals.iter().map(|t| t.errors).sum()
+    }
+}
+
+/// Ordered list of enrichers applied to each file
+#[derive(Default)]
+pub struct EnrichmentPipeline {
+    enrichers: Vec<Box<dyn Enricher>>,
+}
+
+impl EnrichmentPipeline {
+    /// Create an empty pipeline
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a pipeline from configuration
+    ///
+    /// # Errors
+    /// Returns an error if a configured enricher name is unknown.
+    pub fn from_config(config: &EnrichmentConfig) -> Result<Self> {
+        let mut pipeline = Self::new();
+        for name in &config.enrichers {
+            let enricher = match name.trim() {
+                HoverEnricher::NAME => Box::new(HoverEnricher::new(config.hover.clone())),
+                name => builtin_enricher(name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown enricher: {}", name))?,
+            };
+            pipeline = pipeline.with(enricher);
+        }
+        Ok(pipeline)
+    }
+
+    /// Append an enricher to the end of the pipeline
+    #[must_use]
+    pub fn with(mut self, enricher: Box<dyn Enricher>) -> Self {
+        self.enrichers.push(enricher);
+        self
+    }
+
+    /// Names of the enrichers in execution order
+    #[must_use]
+    pub fn names(&self) -> Vec<&'static str> {
+        self.enrichers.iter().map(|e| e.name()).collect()
+    }
+
+    /// Run every enricher in order, isolating failures
+    pub async fn run(&self, ctx: &mut EnrichContext<'_>) -> Vec<EnricherReport> {
+        let mut reports = Vec::with_capacity(self.enrichers.len());
+
+        for enricher in &self.enrichers {
+            let started = Instant::now();
+            let error = match enricher.enrich(ctx).await {
+                Ok(()) => None,
+                Err(e) => {
+                    tracing::warn!(
+                        "Enricher '{}' failed for {}: {}",
+                        enricher.name(),
+                        ctx.file_uri,
+                        e
+                    );
+                    Some(e.to_string())
+                }
+            };
+            reports.push(EnricherReport {
+                name: enricher.name(),
+                elapsed: started.elapsed(),
+                error,
+            });
+        }
+
+        reports
+    }
+}
+
+/// Names of all built-in enrichers
+pub const BUILTIN_ENRICHERS: &[&str] = &[
+    HoverEnricher::NAME,
+    SignatureEnricher::NAME,
+    VisibilityEnricher::NAME,
+];
+
+/// Look up a built-in enricher by name
+#[must_use]
+pub fn builtin_enricher(name: &str) -> Option<Box<dyn Enricher>> {
+    match name {
+        HoverEnricher::NAME => Some(Box::new(HoverEnricher::default())),
+        SignatureEnricher::NAME => Some(Box::new(SignatureEnricher)),
+        VisibilityEnricher::NAME => Some(Box::new(VisibilityEnricher)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+//! Signature enricher: Fill in missing signatures from hover code blocks
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{EnrichContext, Enricher};
+
+/// Uses the hover type signature for symbols without LSP `detail`
+///
+/// Must run after the hover enricher, since it reads `type_signature`.
+pub struct SignatureEnricher;
+
+impl SignatureEnricher {
+    pub const NAME: &'static str = "signature";
+}
+
+#[async_trait]
+impl Enricher for SignatureEnricher {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn enrich(&self, ctx: &mut EnrichContext<'_>) -> Result<()> {
+        for symbol in ctx.symbols.iter_mut() {
+            if symbol.signature.is_some() {
+                continue;
+            }
+            symbol.signature.clone_from(&symbol.type_signature);
+        }
+        Ok(())
+    }
+}
+
+/// Extract the signature code block from hover markdown
+///
+/// Returns the contents of the first fenced code block that mentions the
+/// symbol name, which skips module-path blocks such as rust-analyzer's
+/// leading ```` ```rust\ncrate::module\n``` ```` block.
+#[must_use]
+pub fn signature_from_hover(hover: &str, name: &str) -> Option<String> {
+    code_blocks(hover)
+        .into_iter()
+        .find(|block| block.contains(name))
+        .map(|block| block.trim().to_string())
+        .filter(|block| !block.is_empty())
+}
+
+/// Collect the contents of all fenced code blocks in markdown text
+fn code_blocks(markdown: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => blocks.push(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+
+    blocks
+}
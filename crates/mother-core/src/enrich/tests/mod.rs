@@ -0,0 +1,6 @@
+//! Tests for enrich module
+
+mod tests_hover;
+mod tests_pipeline;
+mod tests_signature;
+mod tests_visibility;
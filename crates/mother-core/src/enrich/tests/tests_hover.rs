@@ -0,0 +1,159 @@
+//! Tests for splitting hover content into type signature and documentation
+
+use crate::enrich::hover::{parameters, parse_kind, resolved_type, HoverParts, HoverSettings};
+use crate::graph::model::SymbolKind;
+
+#[test]
+fn test_hover_parts_separates_declaration_from_docs() {
+    let hover = "```rust\nmother_core::graph\n```\n\n```rust\npub fn connect(config: &Config) -> Result<Self>\n```\n\n---\n\nConnect to Neo4j\n\n\nRetries once.";
+    let parts = HoverParts::parse(hover, "connect");
+    assert_eq!(
+        parts.type_signature.as_deref(),
+        Some("pub fn connect(config: &Config) -> Result<Self>")
+    );
+    assert_eq!(
+        parts.documentation.as_deref(),
+        Some("Connect to Neo4j\n\nRetries once.")
+    );
+}
+
+#[test]
+fn test_hover_parts_without_docs() {
+    let parts = HoverParts::parse("```python\n(variable) path: str\n```", "path");
+    assert_eq!(
+        parts.type_signature.as_deref(),
+        Some("(variable) path: str")
+    );
+    assert_eq!(parts.documentation, None);
+}
+
+#[test]
+fn test_resolved_type_of_function_return() {
+    assert_eq!(
+        resolved_type("pub fn parse(s: &str) -> Result<Ast, Error>", "parse").as_deref(),
+        Some("Result<Ast, Error>")
+    );
+    assert_eq!(
+        resolved_type("fn keys<K>(map: &M) -> Vec<K> where K: Clone", "keys").as_deref(),
+        Some("Vec<K>")
+    );
+    assert_eq!(
+        resolved_type("def run(\n    path: str,\n) -> None", "run").as_deref(),
+        Some("None")
+    );
+    assert_eq!(
+        resolved_type("function greet(name: string): string", "greet").as_deref(),
+        Some("string")
+    );
+    assert_eq!(
+        resolved_type("func Open(name string) (*File, error)", "Open").as_deref(),
+        Some("(*File, error)")
+    );
+}
+
+#[test]
+fn test_resolved_type_of_value() {
+    assert_eq!(
+        resolved_type("const LIMIT: usize = 10", "LIMIT").as_deref(),
+        Some("usize")
+    );
+    assert_eq!(
+        resolved_type("(variable) path: str", "path").as_deref(),
+        Some("str")
+    );
+}
+
+#[test]
+fn test_resolved_type_none_without_a_type() {
+    assert_eq!(resolved_type("pub struct Parser", "Parser"), None);
+    assert_eq!(resolved_type("fn main()", "main"), None);
+    assert_eq!(resolved_type("fn other() -> u8", "main"), None);
+}
+
+/// `(name, type)` of each parameter, for compact assertions
+fn params(type_signature: &str, name: &str) -> Vec<(String, Option<String>)> {
+    parameters(type_signature, name)
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            assert_eq!(p.position as usize, i);
+            (p.name, p.type_name)
+        })
+        .collect()
+}
+
+fn typed(name: &str, type_name: &str) -> (String, Option<String>) {
+    (name.to_string(), Some(type_name.to_string()))
+}
+
+#[test]
+fn test_parameters_of_rust_method_skip_receiver() {
+    assert_eq!(
+        params(
+            "pub async fn run(&mut self, client: &Neo4jClient, items: HashMap<String, u32>) -> Result<()>",
+            "run"
+        ),
+        vec![
+            typed("client", "&Neo4jClient"),
+            typed("items", "HashMap<String, u32>")
+        ]
+    );
+    assert_eq!(
+        params("fn apply<F: Fn(u8) -> u8>(f: F, mut n: u8)", "apply"),
+        vec![typed("f", "F"), typed("n", "u8")]
+    );
+    assert!(params("fn main()", "main").is_empty());
+}
+
+#[test]
+fn test_parameters_of_python_typescript_and_go() {
+    assert_eq!(
+        params(
+            "(method) def run(self, path: str = \".\", *, depth=2) -> None",
+            "run"
+        ),
+        vec![typed("path", "str"), ("depth".to_string(), None)]
+    );
+    assert_eq!(
+        params(
+            "function greet(name?: string, cb: (a: number) => void): string",
+            "greet"
+        ),
+        vec![typed("name", "string"), typed("cb", "(a: number) => void")]
+    );
+    assert_eq!(
+        params(
+            "func (c *Client) Get(ctx context.Context, url string) (*Response, error)",
+            "Get"
+        ),
+        vec![typed("ctx", "context.Context"), typed("url", "string")]
+    );
+}
+
+#[test]
+fn test_parameters_without_declaration() {
+    assert!(params("pub struct Parser", "Parser").is_empty());
+    assert!(params("fn other(x: u8)", "main").is_empty());
+}
+
+#[test]
+fn test_parse_kind_accepts_singular_and_plural_names() {
+    assert_eq!(parse_kind("function"), Ok(SymbolKind::Function));
+    assert_eq!(parse_kind("Methods"), Ok(SymbolKind::Method));
+    assert_eq!(parse_kind("classes"), Ok(SymbolKind::Class));
+    assert_eq!(parse_kind("type_aliases"), Ok(SymbolKind::TypeAlias));
+    assert!(parse_kind("widgets").is_err());
+}
+
+#[test]
+fn test_hover_settings_select_every_kind_unless_listed() {
+    let all = HoverSettings::default();
+    assert!(all.selects(SymbolKind::Field));
+
+    let some = HoverSettings {
+        kinds: vec![SymbolKind::Function, SymbolKind::Method],
+        ..HoverSettings::default()
+    };
+    assert!(some.selects(SymbolKind::Method));
+    assert!(!some.selects(SymbolKind::Field));
+}
@@ -0,0 +1,224 @@
+//! Tests for EnrichmentPipeline configuration and execution
+
+#![allow(clippy::expect_used)]
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::enrich::{
+    EnrichContext, Enricher, EnricherReport, EnricherTotals, EnrichmentConfig, EnrichmentPipeline,
+    HoverSettings, BUILTIN_ENRICHERS,
+};
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::lsp::{LspClient, LspServerConfig};
+use crate::scanner::Language;
+
+/// Start a placeholder process in place of an LSP server
+///
+/// The enrichers used in these tests never talk to the server.
+async fn idle_client() -> LspClient {
+    let config = LspServerConfig {
+        language: Language::Rust,
+        command: "sleep".to_string(),
+        args: vec!["30".to_string()],
+        root_path: std::env::temp_dir(),
+        init_options: None,
+    };
+    LspClient::start(config)
+        .await
+        .expect("Failed to start placeholder process")
+}
+
+fn make_symbol(name: &str, signature: Option<&str>) -> SymbolNode {
+    SymbolNode {
+        signature: signature.map(str::to_string),
+        ..SymbolNode::new(name, name, SymbolKind::Function, "/test.rs", 1, 2)
+    }
+}
+
+struct FailingEnricher;
+
+#[async_trait]
+impl Enricher for FailingEnricher {
+    fn name(&self) -> &'static str {
+        "failing"
+    }
+
+    async fn enrich(&self, _ctx: &mut EnrichContext<'_>) -> Result<()> {
+        anyhow::bail!("boom")
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[test]
+fn test_default_config_runs_hover_only() {
+    let pipeline =
+        EnrichmentPipeline::from_config(&EnrichmentConfig::default()).expect("valid config");
+    assert_eq!(pipeline.names(), vec!["hover"]);
+}
+
+#[test]
+fn test_from_config_preserves_order() {
+    let config = EnrichmentConfig::new(vec![
+        "visibility".to_string(),
+        "hover".to_string(),
+        "signature".to_string(),
+    ]);
+    let pipeline = EnrichmentPipeline::from_config(&config).expect("valid config");
+    assert_eq!(pipeline.names(), vec!["visibility", "hover", "signature"]);
+}
+
+#[test]
+fn test_from_config_rejects_unknown_enricher() {
+    let config = EnrichmentConfig::new(vec!["hover".to_string(), "magic".to_string()]);
+    let result = EnrichmentPipeline::from_config(&config);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_from_config_empty_disables_enrichment() {
+    let pipeline =
+        EnrichmentPipeline::from_config(&EnrichmentConfig::new(vec![])).expect("valid config");
+    assert!(pipeline.names().is_empty());
+}
+
+#[test]
+fn test_without_drops_an_enricher() {
+    let config =
+        EnrichmentConfig::new(vec!["hover".to_string(), "visibility".to_string()]).without("hover");
+    let pipeline = EnrichmentPipeline::from_config(&config).expect("valid config");
+    assert_eq!(pipeline.names(), vec!["visibility"]);
+}
+
+#[test]
+fn test_builtin_enrichers_are_all_constructible() {
+    let config = EnrichmentConfig::new(BUILTIN_ENRICHERS.iter().map(|s| s.to_string()).collect());
+    let pipeline = EnrichmentPipeline::from_config(&config).expect("valid config");
+    assert_eq!(pipeline.names(), BUILTIN_ENRICHERS.to_vec());
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+#[tokio::test]
+async fn test_run_isolates_failing_enricher() {
+    let client = idle_client().await;
+    let mut symbols = vec![make_symbol("run", Some("pub fn run()"))];
+
+    let pipeline = EnrichmentPipeline::new()
+        .with(Box::new(FailingEnricher))
+        .with(Box::new(crate::enrich::VisibilityEnricher));
+
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///test.rs",
+        language: Language::Rust,
+    };
+    let reports = pipeline.run(&mut ctx).await;
+
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].name, "failing");
+    assert_eq!(reports[0].error.as_deref(), Some("boom"));
+    assert!(reports[1].error.is_none());
+    // The enricher after the failure still ran
+    assert_eq!(symbols[0].visibility.as_deref(), Some("pub"));
+}
+
+#[tokio::test]
+async fn test_run_order_lets_later_enrichers_use_earlier_output() {
+    let client = idle_client().await;
+    let mut symbols = vec![make_symbol("run", None).with_type_signature("pub fn run()", None)];
+
+    let config = EnrichmentConfig::new(vec!["signature".to_string(), "visibility".to_string()]);
+    let pipeline = EnrichmentPipeline::from_config(&config).expect("valid config");
+
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///test.rs",
+        language: Language::Rust,
+    };
+    pipeline.run(&mut ctx).await;
+
+    assert_eq!(symbols[0].signature.as_deref(), Some("pub fn run()"));
+    assert_eq!(symbols[0].visibility.as_deref(), Some("pub"));
+}
+
+#[tokio::test]
+async fn test_hover_skips_symbols_of_unselected_kinds() {
+    let client = idle_client().await;
+    let mut symbols = vec![make_symbol("run", None)];
+    let config = EnrichmentConfig::default().with_hover(HoverSettings {
+        kinds: vec![SymbolKind::Method],
+        ..HoverSettings::default()
+    });
+    let pipeline = EnrichmentPipeline::from_config(&config).expect("valid config");
+
+    // The placeholder server never answers, so a hover request would hang
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///test.rs",
+        language: Language::Rust,
+    };
+    let reports = pipeline.run(&mut ctx).await;
+
+    assert!(reports[0].error.is_none());
+    assert_eq!(symbols[0].doc_comment, None);
+}
+
+#[tokio::test]
+async fn test_run_reports_timing_per_enricher() {
+    let client = idle_client().await;
+    let mut symbols: Vec<SymbolNode> = Vec::new();
+    let pipeline =
+        EnrichmentPipeline::from_config(&EnrichmentConfig::new(vec!["signature".to_string()]))
+            .expect("valid config");
+
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &[],
+        lsp_client: &client,
+        file_uri: "file:///empty.rs",
+        language: Language::Rust,
+    };
+    let reports = pipeline.run(&mut ctx).await;
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].name, "signature");
+}
+
+#[test]
+fn test_totals_sum_reports_per_enricher() {
+    let report = |name, millis, error: Option<&str>| EnricherReport {
+        name,
+        elapsed: std::time::Duration::from_millis(millis),
+        error: error.map(str::to_string),
+    };
+    let mut totals = EnricherTotals::default();
+    totals.add(&[
+        report("hover", 30, None),
+        report("failing", 1, Some("boom")),
+    ]);
+    totals.add(&[
+        report("hover", 20, Some("timeout")),
+        report("failing", 1, Some("boom")),
+    ]);
+
+    let totals: Vec<_> = totals.iter().collect();
+    assert_eq!(totals.len(), 2);
+    assert_eq!(
+        (totals[0].name, totals[0].files, totals[0].errors),
+        ("hover", 2, 1)
+    );
+    assert_eq!(totals[0].elapsed, std::time::Duration::from_millis(50));
+    assert_eq!((totals[1].name, totals[1].errors), ("failing", 2));
+}
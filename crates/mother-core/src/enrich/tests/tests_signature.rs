@@ -0,0 +1,38 @@
+//! Tests for signature extraction from hover content
+
+use crate::enrich::signature::signature_from_hover;
+
+#[test]
+fn test_signature_from_hover_skips_module_path_block() {
+    let hover = "```rust\nmother_core::graph\n```\n\n```rust\npub fn connect(config: &Config) -> Result<Self>\n```\n\nConnect to Neo4j";
+    assert_eq!(
+        signature_from_hover(hover, "connect"),
+        Some("pub fn connect(config: &Config) -> Result<Self>".to_string())
+    );
+}
+
+#[test]
+fn test_signature_from_hover_multiline_block() {
+    let hover = "```python\ndef run(\n    path: str,\n) -> None\n```";
+    assert_eq!(
+        signature_from_hover(hover, "run"),
+        Some("def run(\n    path: str,\n) -> None".to_string())
+    );
+}
+
+#[test]
+fn test_signature_from_hover_without_code_blocks() {
+    assert_eq!(signature_from_hover("Just some documentation", "run"), None);
+}
+
+#[test]
+fn test_signature_from_hover_name_not_in_any_block() {
+    let hover = "```rust\ncrate::module\n```";
+    assert_eq!(signature_from_hover(hover, "other"), None);
+}
+
+#[test]
+fn test_signature_from_hover_unterminated_block_is_ignored() {
+    let hover = "```rust\nfn broken(";
+    assert_eq!(signature_from_hover(hover, "broken"), None);
+}
@@ -0,0 +1,90 @@
+//! Tests for visibility inference
+
+use crate::enrich::visibility::infer_visibility;
+use crate::scanner::Language;
+
+#[test]
+fn test_infer_visibility_rust_pub() {
+    assert_eq!(
+        infer_visibility(&Language::Rust, Some("pub fn run()"), "run"),
+        Some("pub".to_string())
+    );
+}
+
+#[test]
+fn test_infer_visibility_rust_restricted() {
+    assert_eq!(
+        infer_visibility(&Language::Rust, Some("pub(crate) fn run()"), "run"),
+        Some("pub(crate)".to_string())
+    );
+}
+
+#[test]
+fn test_infer_visibility_rust_private() {
+    assert_eq!(
+        infer_visibility(&Language::Rust, Some("fn run()"), "run"),
+        Some("private".to_string())
+    );
+    // An identifier that merely starts with "pub" is not a visibility keyword
+    assert_eq!(
+        infer_visibility(&Language::Rust, Some("publish()"), "publish"),
+        Some("private".to_string())
+    );
+}
+
+#[test]
+fn test_infer_visibility_rust_without_signature() {
+    assert_eq!(infer_visibility(&Language::Rust, None, "run"), None);
+}
+
+#[test]
+fn test_infer_visibility_python_naming() {
+    assert_eq!(
+        infer_visibility(&Language::Python, None, "_helper"),
+        Some("private".to_string())
+    );
+    assert_eq!(
+        infer_visibility(&Language::Python, None, "__init__"),
+        Some("public".to_string())
+    );
+    assert_eq!(
+        infer_visibility(&Language::Python, None, "run"),
+        Some("public".to_string())
+    );
+}
+
+#[test]
+fn test_infer_visibility_typescript_keywords() {
+    assert_eq!(
+        infer_visibility(
+            &Language::TypeScript,
+            Some("private handle(): void"),
+            "handle"
+        ),
+        Some("private".to_string())
+    );
+    assert_eq!(
+        infer_visibility(
+            &Language::TypeScript,
+            Some("export function handle()"),
+            "handle"
+        ),
+        Some("public".to_string())
+    );
+    assert_eq!(
+        infer_visibility(&Language::JavaScript, Some("function handle()"), "handle"),
+        None
+    );
+}
+
+#[test]
+fn test_infer_visibility_go_capitalization() {
+    assert_eq!(
+        infer_visibility(&Language::Go, None, "Handle"),
+        Some("public".to_string())
+    );
+    assert_eq!(
+        infer_visibility(&Language::Go, None, "handle"),
+        Some("private".to_string())
+    );
+}
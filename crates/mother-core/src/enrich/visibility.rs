@@ -0,0 +1,45 @@
+//! Visibility enricher: Infer symbol visibility from signatures and names
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{EnrichContext, Enricher};
+use crate::adapters::adapter_for;
+use crate::graph::convert::infer_visibilities;
+use crate::scanner::Language;
+
+/// Fills in `visibility` for symbols the LSP didn't annotate
+///
+/// Reads the hover declaration when an earlier enricher stored one, and the
+/// symbol detail otherwise, through [`infer_visibilities`]. Scans also call
+/// that after enrichment; listing this enricher makes the result visible to
+/// enrichers after it.
+pub struct VisibilityEnricher;
+
+impl VisibilityEnricher {
+    pub const NAME: &'static str = "visibility";
+}
+
+#[async_trait]
+impl Enricher for VisibilityEnricher {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn enrich(&self, ctx: &mut EnrichContext<'_>) -> Result<()> {
+        infer_visibilities(ctx.symbols, &ctx.language);
+        Ok(())
+    }
+}
+
+/// Infer visibility using each language's conventions
+///
+/// Delegates to the language's [`crate::adapters::LanguageAdapter::detect_visibility`].
+#[must_use]
+pub fn infer_visibility(
+    language: &Language,
+    signature: Option<&str>,
+    name: &str,
+) -> Option<String> {
+    adapter_for(language).detect_visibility(signature, name)
+}
@@ -0,0 +1,222 @@
+//! API surface: Symbols reachable from outside a module
+//!
+//! Works on a [`GraphExport`] of one commit. The roots are the public (and
+//! re-exported) symbols of the files inside a [`PathScope`]. From each root,
+//! REFERENCES edges that are part of its declaration are followed to find
+//! internal symbols the public API leaks, such as a private type used in a
+//! public field, signature or type alias. Leaked symbols are followed in
+//! turn, together with their public members, so leaks are found
+//! transitively.
+//!
+//! A reference is part of a declaration if it comes from a field, type
+//! alias, constant, variable or import, or if the referenced name appears in
+//! the source symbol's signature. References from function bodies therefore
+//! do not count as leaks.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use super::export::{ExportNode, GraphExport};
+use super::scope::PathScope;
+
+/// Symbol kinds whose whole declaration is visible API
+const DECLARATION_KINDS: [&str; 5] = ["field", "type_alias", "constant", "variable", "import"];
+
+/// A symbol that is part of a module's API surface
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiSymbol {
+    pub qualified_name: String,
+    pub kind: String,
+    pub visibility: String,
+    pub file_path: String,
+    pub start_line: i64,
+    /// Internal symbols this public symbol exposes, directly or transitively
+    pub exposes: Vec<String>,
+    /// Public symbols through which this internal symbol is exposed
+    pub exposed_by: Vec<String>,
+}
+
+impl ApiSymbol {
+    /// Whether the symbol is declared public rather than leaked
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        is_public(&self.visibility)
+    }
+}
+
+/// Whether a stored visibility makes a symbol usable from other modules
+///
+/// Restricted Rust visibilities such as `pub(crate)` do not count.
+#[must_use]
+pub fn is_public(visibility: &str) -> bool {
+    matches!(visibility, "pub" | "public")
+}
+
+/// Collect the API surface of the files inside `scope`
+///
+/// Public symbols come first, then the internal symbols they expose, each
+/// ordered by file path and line.
+#[must_use]
+pub fn api_surface(graph: &GraphExport, scope: &PathScope) -> Vec<ApiSymbol> {
+    let symbols: HashMap<&str, &ExportNode> = graph
+        .nodes
+        .iter()
+        .filter(|n| n.label == "Symbol")
+        .map(|n| (n.id.as_str(), n))
+        .collect();
+    let exposure = exposure_edges(graph, &symbols);
+
+    let mut roots: Vec<&ExportNode> = symbols
+        .values()
+        .copied()
+        .filter(|n| is_public(text(n, "visibility")) && scope.matches(text(n, "file_path")))
+        .collect();
+    roots.sort_by_key(|n| position(n));
+
+    // Internal symbol id -> the symbol and the roots exposing it
+    let mut leaked: BTreeMap<&str, (&ExportNode, Vec<String>)> = BTreeMap::new();
+    let mut surface: Vec<ApiSymbol> = Vec::new();
+    for root in roots {
+        let mut entry = api_symbol(root);
+        for node in reachable_internals(root, &exposure, &symbols) {
+            entry.exposes.push(text(node, "qualified_name").to_string());
+            leaked
+                .entry(node.id.as_str())
+                .or_insert_with(|| (node, Vec::new()))
+                .1
+                .push(entry.qualified_name.clone());
+        }
+        surface.push(entry);
+    }
+
+    let mut internals: Vec<ApiSymbol> = leaked
+        .into_values()
+        .map(|(node, roots)| ApiSymbol {
+            exposed_by: roots,
+            ..api_symbol(node)
+        })
+        .collect();
+    internals.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+    surface.extend(internals);
+    surface
+}
+
+/// Declaration references by source symbol id
+fn exposure_edges<'a>(
+    graph: &'a GraphExport,
+    symbols: &HashMap<&str, &'a ExportNode>,
+) -> HashMap<&'a str, Vec<&'a str>> {
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| e.kind == "REFERENCES") {
+        let (Some(source), Some(target)) = (
+            symbols.get(edge.source.as_str()),
+            symbols.get(edge.target.as_str()),
+        ) else {
+            continue;
+        };
+        if is_declaration_reference(source, text(target, "name")) {
+            edges
+                .entry(edge.source.as_str())
+                .or_default()
+                .push(edge.target.as_str());
+        }
+    }
+    edges
+}
+
+/// Whether a reference to `target_name` from `source` is part of its declaration
+fn is_declaration_reference(source: &ExportNode, target_name: &str) -> bool {
+    DECLARATION_KINDS.contains(&text(source, "kind"))
+        || text(source, "signature")
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| !word.is_empty() && word == target_name)
+}
+
+/// Internal symbols a root exposes, in breadth-first order
+fn reachable_internals<'a>(
+    root: &'a ExportNode,
+    exposure: &HashMap<&'a str, Vec<&'a str>>,
+    symbols: &HashMap<&str, &'a ExportNode>,
+) -> Vec<&'a ExportNode> {
+    let mut seen: HashSet<&str> = HashSet::from([root.id.as_str()]);
+    let mut queue: VecDeque<&str> = VecDeque::from([root.id.as_str()]);
+    let mut found = Vec::new();
+
+    while let Some(id) = queue.pop_front() {
+        let Some(targets) = exposure.get(id) else {
+            continue;
+        };
+        for &target in targets {
+            let Some(&node) = symbols.get(target) else {
+                continue;
+            };
+            if is_public(text(node, "visibility")) || !seen.insert(target) {
+                continue;
+            }
+            found.push(node);
+            queue.push_back(target);
+            // Public members of a leaked symbol are reachable through it
+            for member in public_members(node, symbols) {
+                if seen.insert(member) {
+                    queue.push_back(member);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Public symbols declared inside `parent`'s range in the same file
+fn public_members<'a>(
+    parent: &ExportNode,
+    symbols: &HashMap<&str, &'a ExportNode>,
+) -> Vec<&'a str> {
+    let (file, start, end) = position(parent);
+    let mut members: Vec<&'a ExportNode> = symbols
+        .values()
+        .copied()
+        .filter(|n| {
+            let (f, s, e) = position(n);
+            f == file && s >= start && e <= end && (s, e) != (start, end)
+        })
+        .filter(|n| is_public(text(n, "visibility")))
+        .collect();
+    members.sort_by_key(|n| position(n));
+    members.into_iter().map(|n| n.id.as_str()).collect()
+}
+
+fn api_symbol(node: &ExportNode) -> ApiSymbol {
+    ApiSymbol {
+        qualified_name: text(node, "qualified_name").to_string(),
+        kind: text(node, "kind").to_string(),
+        visibility: text(node, "visibility").to_string(),
+        file_path: text(node, "file_path").to_string(),
+        start_line: number(node, "start_line"),
+        exposes: Vec::new(),
+        exposed_by: Vec::new(),
+    }
+}
+
+/// File path, start line and end line of a symbol node
+fn position(node: &ExportNode) -> (&str, i64, i64) {
+    (
+        text(node, "file_path"),
+        number(node, "start_line"),
+        number(node, "end_line"),
+    )
+}
+
+fn text<'a>(node: &'a ExportNode, key: &str) -> &'a str {
+    node.properties
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+}
+
+fn number(node: &ExportNode, key: &str) -> i64 {
+    node.properties
+        .get(key)
+        .and_then(serde_json::Value::as_i64)
+        .unwrap_or(0)
+}
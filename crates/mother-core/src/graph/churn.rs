@@ -0,0 +1,172 @@
+//! Churn: How often symbols and files changed across scans
+//!
+//! [`by_symbol`] walks the scans of a branch from oldest to newest and
+//! compares each with the scan before it. A symbol the symbol diff reports
+//! as added counts as an insert; one whose signature or visibility changed,
+//! or that the scan's commit links to with a MODIFIED edge, as an update.
+//! Symbols are tracked by identity (file path, kind and qualified name), and
+//! a move or rename carries the counts over to the new identity, so a
+//! symbol's history stays in one row. The oldest scan is only compared
+//! against, so `n + 1` scans give the churn of the last `n`.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use super::diff::{diff_symbols, ChangeKind, SymbolChange};
+use super::model::SymbolNode;
+use super::queries::SymbolResult;
+
+/// The symbols of a scanned commit and those it modified
+#[derive(Debug, Clone, Default)]
+pub struct ScanSymbols {
+    pub symbols: Vec<SymbolNode>,
+    /// Symbols the commit links to with MODIFIED edges
+    pub modified: Vec<SymbolResult>,
+}
+
+/// How often a symbol changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolChurn {
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    /// Scans that added the symbol
+    pub inserts: usize,
+    /// Scans that changed the symbol
+    pub updates: usize,
+}
+
+impl SymbolChurn {
+    /// Inserts and updates together
+    #[must_use]
+    pub fn changes(&self) -> usize {
+        self.inserts + self.updates
+    }
+}
+
+/// How often the symbols of a file changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChurn {
+    pub path: String,
+    /// Symbols that changed at least once
+    pub symbols: usize,
+    pub inserts: usize,
+    pub updates: usize,
+}
+
+impl FileChurn {
+    /// Inserts and updates together
+    #[must_use]
+    pub fn changes(&self) -> usize {
+        self.inserts + self.updates
+    }
+}
+
+/// File path, kind and qualified name of a symbol
+type Identity = (String, String, String);
+
+/// Changes per symbol across `scans`, oldest scan first; most changed
+/// first, then by file and name
+#[must_use]
+pub fn by_symbol(scans: &[ScanSymbols]) -> Vec<SymbolChurn> {
+    let mut counts: BTreeMap<Identity, (usize, usize)> = BTreeMap::new();
+    for pair in scans.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+        let diff = diff_symbols("", &before.symbols, "", &after.symbols);
+        let mut inserted = HashSet::new();
+        let mut updated = HashSet::new();
+        for change in diff.changes() {
+            let identity = change_identity(change);
+            match change.change {
+                ChangeKind::Added => {
+                    counts.entry(identity.clone()).or_default().0 += 1;
+                    inserted.insert(identity);
+                }
+                ChangeKind::Removed => {}
+                ChangeKind::Modified => {
+                    updated.insert(identity);
+                }
+                ChangeKind::Moved | ChangeKind::Renamed => {
+                    if let Some(carried) = counts.remove(&previous_identity(change)) {
+                        counts.insert(identity.clone(), carried);
+                    }
+                    updated.insert(identity);
+                }
+            }
+        }
+        for symbol in &after.modified {
+            let identity = (
+                symbol.file_path.clone(),
+                symbol.kind.clone(),
+                symbol.qualified_name.clone(),
+            );
+            if !inserted.contains(&identity) {
+                updated.insert(identity);
+            }
+        }
+        for identity in updated {
+            counts.entry(identity).or_default().1 += 1;
+        }
+    }
+
+    let mut rows: Vec<SymbolChurn> = counts
+        .into_iter()
+        .map(
+            |((file_path, kind, qualified_name), (inserts, updates))| SymbolChurn {
+                qualified_name,
+                kind,
+                file_path,
+                inserts,
+                updates,
+            },
+        )
+        .collect();
+    // Stable, so ties stay ordered by file and name
+    rows.sort_by_key(|row| std::cmp::Reverse(row.changes()));
+    rows
+}
+
+/// Changes per file, summed over the symbols of [`by_symbol`]; most changed
+/// first, then by path
+#[must_use]
+pub fn by_file(symbols: &[SymbolChurn]) -> Vec<FileChurn> {
+    let mut files: BTreeMap<&str, FileChurn> = BTreeMap::new();
+    for symbol in symbols {
+        let file = files.entry(&symbol.file_path).or_insert_with(|| FileChurn {
+            path: symbol.file_path.clone(),
+            symbols: 0,
+            inserts: 0,
+            updates: 0,
+        });
+        file.symbols += 1;
+        file.inserts += symbol.inserts;
+        file.updates += symbol.updates;
+    }
+    let mut rows: Vec<FileChurn> = files.into_values().collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(row.changes()));
+    rows
+}
+
+fn change_identity(change: &SymbolChange) -> Identity {
+    (
+        change.file_path.clone(),
+        change.kind.clone(),
+        change.qualified_name.clone(),
+    )
+}
+
+/// Identity of a moved or renamed symbol before the change
+fn previous_identity(change: &SymbolChange) -> Identity {
+    (
+        change
+            .old_file_path
+            .clone()
+            .unwrap_or_else(|| change.file_path.clone()),
+        change.kind.clone(),
+        change
+            .old_name
+            .clone()
+            .unwrap_or_else(|| change.qualified_name.clone()),
+    )
+}
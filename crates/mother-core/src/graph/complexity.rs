@@ -0,0 +1,117 @@
+//! Complexity: Connectivity proxies per symbol for spotting refactoring hotspots
+//!
+//! Works on a [`GraphExport`] of one commit. For each symbol, the
+//! `REFERENCES` and `CALLS` edges between symbols of the commit give its
+//! outgoing calls (edges out), fan-out (distinct symbols it uses), fan-in
+//! (distinct symbols using it) and incoming references (edges in, or the
+//! total reported by the language server when references were sampled).
+//! Self-references are ignored. The score, fan-in plus fan-out, ranks the
+//! most connected symbols first.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::export::{ExportNode, GraphExport};
+
+/// Edge kinds that make one symbol use another
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// Connectivity of one symbol within a commit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolComplexity {
+    pub symbol_id: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: i64,
+    /// Use edges leaving the symbol
+    pub outgoing_calls: usize,
+    /// Distinct symbols the symbol uses
+    pub fan_out: usize,
+    /// Distinct symbols using the symbol
+    pub fan_in: usize,
+    /// Use edges reaching the symbol, or the reported total if sampled
+    pub incoming_references: usize,
+    /// `fan_in + fan_out`
+    pub score: usize,
+}
+
+/// Compute the complexity proxies of every symbol of an exported commit
+///
+/// Ordered by descending score, then by file path and line.
+#[must_use]
+pub fn symbol_complexity(graph: &GraphExport) -> Vec<SymbolComplexity> {
+    let mut outgoing: HashMap<&str, usize> = HashMap::new();
+    let mut incoming: HashMap<&str, usize> = HashMap::new();
+    let mut targets: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut sources: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        if !USE_EDGES.contains(&edge.kind.as_str()) || edge.source == edge.target {
+            continue;
+        }
+        *outgoing.entry(&edge.source).or_default() += 1;
+        *incoming.entry(&edge.target).or_default() += 1;
+        targets
+            .entry(&edge.source)
+            .or_default()
+            .insert(&edge.target);
+        sources
+            .entry(&edge.target)
+            .or_default()
+            .insert(&edge.source);
+    }
+
+    let distinct =
+        |map: &HashMap<&str, HashSet<&str>>, id: &str| map.get(id).map_or(0, HashSet::len);
+    let mut symbols: Vec<SymbolComplexity> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.label == "Symbol")
+        .map(|node| {
+            let id = node.id.as_str();
+            let fan_in = distinct(&sources, id);
+            let fan_out = distinct(&targets, id);
+            let reported = integer(node, "reference_count");
+            SymbolComplexity {
+                symbol_id: node.id.clone(),
+                qualified_name: string(node, "qualified_name"),
+                kind: string(node, "kind"),
+                file_path: string(node, "file_path"),
+                start_line: i64::try_from(integer(node, "start_line")).unwrap_or(i64::MAX),
+                outgoing_calls: outgoing.get(id).copied().unwrap_or(0),
+                fan_out,
+                fan_in,
+                incoming_references: incoming.get(id).copied().unwrap_or(0).max(reported),
+                score: fan_in + fan_out,
+            }
+        })
+        .collect();
+    symbols.sort_by(|a, b| {
+        (Reverse(a.score), &a.file_path, a.start_line).cmp(&(
+            Reverse(b.score),
+            &b.file_path,
+            b.start_line,
+        ))
+    });
+    symbols
+}
+
+/// A string property of a node, empty if missing
+fn string(node: &ExportNode, key: &str) -> String {
+    node.properties
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// A non-negative integer property of a node, 0 if missing
+fn integer(node: &ExportNode, key: &str) -> usize {
+    node.properties
+        .get(key)
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|n| usize::try_from(n).ok())
+        .unwrap_or(0)
+}
@@ -1,65 +1,60 @@
 //! Conversion utilities between LSP types and graph model types
 
+use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
-use super::model::{SymbolKind, SymbolNode};
-use crate::lsp::{LspSymbol, LspSymbolKind};
+use super::ids::{ContentHashIds, SymbolIdStrategy};
+use super::model::{
+    external_symbol_id, DiagnosticNode, ExternalSymbolNode, FunctionSignature, SymbolKind,
+    SymbolNode,
+};
+use crate::adapters::{adapter_for_path, default_symbol_kind};
+use crate::enrich::{infer_visibility, parameters};
+use crate::lsp::{LspDiagnostic, LspReference, LspSymbol, LspSymbolKind};
+use crate::scanner::Language;
 
 /// Convert an LSP symbol kind to a graph symbol kind
+///
+/// This is the language-independent mapping; symbol conversion goes through
+/// [`crate::adapters::LanguageAdapter::map_symbol_kind`], which may refine it per language.
 #[must_use]
 pub fn convert_symbol_kind(lsp_kind: LspSymbolKind) -> SymbolKind {
-    match lsp_kind {
-        LspSymbolKind::Module | LspSymbolKind::Namespace | LspSymbolKind::Package => {
-            SymbolKind::Module
-        }
-        LspSymbolKind::Class => SymbolKind::Class,
-        LspSymbolKind::Struct => SymbolKind::Struct,
-        LspSymbolKind::Enum => SymbolKind::Enum,
-        LspSymbolKind::Interface => SymbolKind::Interface,
-        LspSymbolKind::Function | LspSymbolKind::Constructor => SymbolKind::Function,
-        LspSymbolKind::Method => SymbolKind::Method,
-        LspSymbolKind::Variable => SymbolKind::Variable,
-        LspSymbolKind::Constant => SymbolKind::Constant,
-        LspSymbolKind::Field | LspSymbolKind::Property => SymbolKind::Field,
-        LspSymbolKind::TypeParameter => SymbolKind::TypeAlias,
-        LspSymbolKind::EnumMember => SymbolKind::Constant,
-        _ => SymbolKind::Variable,
-    }
+    default_symbol_kind(lsp_kind)
 }
 
 /// Convert an LSP symbol to a graph symbol node
+///
+/// The file's language adapter decides how the qualified name is built and
+/// which graph kind the symbol gets.
 #[must_use]
 pub fn lsp_symbol_to_node(
     symbol: &LspSymbol,
     file_path: &Path,
     parent_qualified_name: Option<&str>,
 ) -> SymbolNode {
-    // Build qualified name from either:
+    let adapter = adapter_for_path(file_path);
+
+    // Qualify the name with either:
     // 1. Parent qualified name (for nested DocumentSymbol format)
     // 2. Container name (for flat SymbolInformation format)
-    // 3. Just the symbol name if neither is available
-    let qualified_name = match parent_qualified_name {
-        Some(parent) => format!("{}::{}", parent, symbol.name),
-        None => match &symbol.container_name {
-            Some(container) if !container.is_empty() => {
-                format!("{}::{}", container, symbol.name)
-            }
-            _ => symbol.name.clone(),
-        },
-    };
+    // 3. Nothing, if neither is available
+    let parent = parent_qualified_name.or(symbol.container_name.as_deref());
+    let qualified_name = adapter.normalize_qualified_name(parent, &symbol.name);
 
+    // Visibility and docs are not in the document symbols; the enrichers
+    // add them. Lines are converted from 0-indexed to 1-indexed.
     SymbolNode {
-        id: Uuid::new_v4().to_string(),
-        name: symbol.name.clone(),
         qualified_name,
-        kind: convert_symbol_kind(symbol.kind),
-        visibility: None, // LSP doesn't provide this directly
-        file_path: file_path.display().to_string(),
-        start_line: symbol.start_line + 1, // Convert 0-indexed to 1-indexed
-        end_line: symbol.end_line + 1,
         signature: symbol.detail.clone(),
-        doc_comment: None, // Would need additional LSP request for hover
+        ..SymbolNode::new(
+            Uuid::new_v4().to_string(),
+            symbol.name.clone(),
+            adapter.map_symbol_kind(symbol.kind),
+            file_path.display().to_string(),
+            symbol.start_line + 1,
+            symbol.end_line + 1,
+        )
     }
 }
 
@@ -94,6 +89,139 @@ pub fn convert_symbols(symbols: &[LspSymbol], file_path: &Path) -> Vec<SymbolNod
     result
 }
 
+/// Convert an LSP diagnostic to a graph diagnostic node
+///
+/// `symbol_id` is the innermost symbol containing the diagnostic, if any.
+#[must_use]
+pub fn lsp_diagnostic_to_node(
+    diagnostic: &LspDiagnostic,
+    symbol_id: Option<String>,
+) -> DiagnosticNode {
+    DiagnosticNode {
+        severity: diagnostic.severity.clone(),
+        code: diagnostic.code.clone(),
+        source: diagnostic.source.clone(),
+        message: diagnostic.message.clone(),
+        start_line: diagnostic.start_line + 1, // Convert 0-indexed to 1-indexed
+        start_col: diagnostic.start_col,
+        end_line: diagnostic.end_line + 1,
+        end_col: diagnostic.end_col,
+        symbol_id,
+    }
+}
+
+/// Fill in the visibility of symbols nothing has annotated yet
+///
+/// Runs after enrichment. The language adapter reads the hover declaration
+/// when there is one, since symbol details often leave out modifiers such as
+/// Rust's `pub`, and the detail otherwise.
+pub fn infer_visibilities(symbols: &mut [SymbolNode], language: &Language) {
+    for symbol in symbols.iter_mut().filter(|s| s.visibility.is_none()) {
+        let declaration = symbol
+            .type_signature
+            .as_deref()
+            .or(symbol.signature.as_deref());
+        symbol.visibility = infer_visibility(language, declaration, &symbol.name);
+    }
+}
+
+/// Parameters and return types of the functions and methods among `symbols`
+///
+/// Parsed from the type signatures the hover enricher stores; symbols without
+/// one are skipped. The return type is the symbol's resolved type.
+#[must_use]
+pub fn function_signatures(symbols: &[SymbolNode]) -> Vec<FunctionSignature> {
+    symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .filter_map(|s| {
+            let type_signature = s.type_signature.as_deref()?;
+            Some(FunctionSignature {
+                symbol_id: s.id.clone(),
+                parameters: parameters(type_signature, &s.name),
+                return_type: s.resolved_type.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Path fragments of dependency sources: Cargo, pip, npm, Go modules
+const DEPENDENCY_PATHS: &[&str] = &[
+    "/.cargo/registry/",
+    "/.cargo/git/",
+    "/site-packages/",
+    "/dist-packages/",
+    "/node_modules/",
+    "/pkg/mod/",
+];
+
+/// Path fragments of standard library sources and stubs
+const STD_PATHS: &[&str] = &[
+    "/rustlib/",
+    "/typeshed/",
+    "/lib/python",
+    "/libexec/src/",
+    "/go/src/",
+];
+
+/// Convert the definition a call site resolved to into an external symbol
+///
+/// `name` is the identifier used at the call site.
+#[must_use]
+pub fn lsp_definition_to_external(name: &str, definition: &LspReference) -> ExternalSymbolNode {
+    let file_path = definition.file.display().to_string();
+    ExternalSymbolNode {
+        id: external_symbol_id(&file_path, definition.line, definition.start_col),
+        name: name.to_string(),
+        origin: external_origin(&file_path).to_string(),
+        line: definition.line + 1, // Convert 0-indexed to 1-indexed
+        file_path,
+    }
+}
+
+/// Guess where an external definition comes from by its path
+///
+/// TypeScript's bundled `lib.*.d.ts` files are the standard library even
+/// though they live under `node_modules`.
+#[must_use]
+pub fn external_origin(file_path: &str) -> &'static str {
+    if file_path.contains("/node_modules/typescript/lib/") {
+        "std"
+    } else if DEPENDENCY_PATHS.iter().any(|p| file_path.contains(p)) {
+        "dependency"
+    } else if STD_PATHS.iter().any(|p| file_path.contains(p)) {
+        "std"
+    } else {
+        "other"
+    }
+}
+
+/// Replace the IDs of a file's symbols with content-derived version IDs
+///
+/// Converted symbols get random IDs; once the file's content hash is known,
+/// this makes them stable across scans of the same content. Symbols that
+/// share an identity key are told apart by their order in the file.
+pub fn assign_version_ids(symbols: &mut [SymbolNode], content_hash: &str) {
+    assign_symbol_ids(symbols, &ContentHashIds, "", content_hash);
+}
+
+/// Replace the IDs of a file's symbols with the IDs `strategy` derives
+///
+/// `path` is the file's path relative to the scanned root.
+pub fn assign_symbol_ids(
+    symbols: &mut [SymbolNode],
+    strategy: &dyn SymbolIdStrategy,
+    path: &str,
+    content_hash: &str,
+) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for symbol in symbols {
+        let ordinal = seen.entry(symbol.identity_key()).or_insert(0);
+        symbol.id = strategy.symbol_id(symbol, path, content_hash, *ordinal);
+        *ordinal += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +277,136 @@ mod tests {
         assert_eq!(nodes[1].name, "method");
         assert_eq!(nodes[1].qualified_name, "MyClass::method");
     }
+
+    #[test]
+    fn test_assign_version_ids_stable_and_unique() {
+        let symbol = LspSymbol {
+            name: "new".to_string(),
+            kind: LspSymbolKind::Function,
+            detail: None,
+            file: PathBuf::new(),
+            start_line: 0,
+            end_line: 3,
+            start_col: 0,
+            end_col: 0,
+            children: vec![],
+            container_name: None,
+        };
+        let path = PathBuf::from("/test/file.rs");
+        let symbols = vec![symbol.clone(), symbol];
+
+        let mut first = convert_symbols(&symbols, &path);
+        let mut second = convert_symbols(&symbols, &path);
+        assign_version_ids(&mut first, "hash1");
+        assign_version_ids(&mut second, "hash1");
+
+        // Same content gives the same IDs; duplicate names stay distinct
+        assert_eq!(first[0].id, second[0].id);
+        assert_eq!(first[1].id, second[1].id);
+        assert_ne!(first[0].id, first[1].id);
+
+        assign_version_ids(&mut second, "hash2");
+        assert_ne!(first[0].id, second[0].id);
+    }
+
+    #[test]
+    fn test_lsp_definition_to_external_is_keyed_by_location() {
+        let definition = LspReference {
+            file: "/home/dev/.cargo/registry/src/serde-1.0/src/de.rs".into(),
+            line: 9,
+            start_col: 4,
+            end_col: 15,
+        };
+
+        let node = lsp_definition_to_external("from_str", &definition);
+        let again = lsp_definition_to_external("parse", &definition);
+
+        assert_eq!(node.line, 10);
+        assert_eq!(node.origin, "dependency");
+        assert_eq!(node.name, "from_str");
+        assert_eq!(node.id, again.id);
+    }
+
+    #[test]
+    fn test_external_origin_from_path() {
+        let cases = [
+            (
+                "/rustup/toolchains/x/lib/rustlib/src/rust/library/core/src/option.rs",
+                "std",
+            ),
+            ("/usr/lib/python3.12/json/__init__.py", "std"),
+            (
+                "/venv/lib/python3.12/site-packages/requests/api.py",
+                "dependency",
+            ),
+            ("/app/node_modules/typescript/lib/lib.es5.d.ts", "std"),
+            ("/app/node_modules/lodash/index.d.ts", "dependency"),
+            ("/repo/generated/schema.rs", "other"),
+        ];
+        for (path, origin) in cases {
+            assert_eq!(external_origin(path), origin, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_lsp_diagnostic_to_node_uses_1_indexed_lines() {
+        let diagnostic = LspDiagnostic {
+            severity: "warning".to_string(),
+            code: Some("unused_variables".to_string()),
+            source: Some("rustc".to_string()),
+            message: "unused variable: `x`".to_string(),
+            start_line: 4,
+            start_col: 8,
+            end_line: 4,
+            end_col: 9,
+        };
+
+        let node = lsp_diagnostic_to_node(&diagnostic, Some("sym".to_string()));
+
+        assert_eq!(node.start_line, 5);
+        assert_eq!(node.end_line, 5);
+        assert_eq!(node.start_col, 8);
+        assert_eq!(node.code.as_deref(), Some("unused_variables"));
+        assert_eq!(node.symbol_id.as_deref(), Some("sym"));
+    }
+
+    #[test]
+    fn test_infer_visibilities_prefers_hover_declaration() {
+        let lsp = |name: &str, detail: &str| LspSymbol {
+            name: name.to_string(),
+            kind: LspSymbolKind::Function,
+            detail: Some(detail.to_string()),
+            file: PathBuf::new(),
+            start_line: 0,
+            end_line: 1,
+            start_col: 0,
+            end_col: 0,
+            children: vec![],
+            container_name: None,
+        };
+        let symbols = vec![lsp("connect", "fn(&Config)"), lsp("helper", "fn()")];
+        let mut nodes = convert_symbols(&symbols, &PathBuf::from("/test/lib.rs"));
+        nodes[0].type_signature = Some("pub fn connect(config: &Config)".to_string());
+        nodes[1].visibility = Some("pub(crate)".to_string());
+
+        infer_visibilities(&mut nodes, &Language::Rust);
+
+        assert_eq!(nodes[0].visibility.as_deref(), Some("pub"));
+        // Visibility set by the LSP or an enricher is kept
+        assert_eq!(nodes[1].visibility.as_deref(), Some("pub(crate)"));
+    }
+
+    #[test]
+    fn test_infer_visibilities_by_language_convention() {
+        let node =
+            |name: &str| SymbolNode::new(name, name, SymbolKind::Function, "/test/app.py", 1, 2);
+        let mut nodes = vec![node("run"), node("_helper")];
+        infer_visibilities(&mut nodes, &Language::Python);
+        assert_eq!(nodes[0].visibility.as_deref(), Some("public"));
+        assert_eq!(nodes[1].visibility.as_deref(), Some("private"));
+
+        let mut other = vec![node("run")];
+        infer_visibilities(&mut other, &Language::Other("elixir".to_string()));
+        assert_eq!(other[0].visibility, None);
+    }
 }
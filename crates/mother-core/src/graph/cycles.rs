@@ -0,0 +1,263 @@
+//! Cycles: Dependency cycles between files or modules
+//!
+//! Works on a [`GraphExport`] of one commit. Files depend on each other
+//! through `IMPORTS` edges and through `REFERENCES` and `CALLS` edges between
+//! their symbols; at the module level, each file counts as its directory.
+//! Strongly connected components of more than one file or module are
+//! cycles. Dependencies within one file or module are ignored.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use super::export::{ExportEdge, ExportNode, GraphExport};
+
+/// Edge kinds that make one symbol depend on another
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// Granularity of the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleLevel {
+    /// Each file is a node
+    File,
+    /// Each directory is a node
+    Module,
+}
+
+/// A set of files or modules that all depend on each other
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyCycle {
+    /// File or directory paths in the cycle, sorted
+    pub members: Vec<String>,
+    /// Dependencies between members, ordered by source, target and line
+    pub links: Vec<CycleLink>,
+}
+
+/// One dependency between two members of a cycle
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CycleLink {
+    pub from: String,
+    pub to: String,
+    /// `IMPORTS`, `REFERENCES` or `CALLS`
+    pub kind: String,
+    /// Importing file for imports, otherwise the qualified name of the using
+    /// symbol
+    pub source: String,
+    /// Imported module path for imports, otherwise the qualified name of the
+    /// used symbol
+    pub target: String,
+    pub line: i64,
+}
+
+impl std::fmt::Display for CycleLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -[{}]-> {}", self.source, self.kind, self.target)
+    }
+}
+
+/// Find the dependency cycles of an exported commit
+///
+/// Larger cycles come first, then cycles are ordered by their first member.
+#[must_use]
+pub fn dependency_cycles(graph: &GraphExport, level: CycleLevel) -> Vec<DependencyCycle> {
+    let links = dependency_links(graph, |path| match level {
+        CycleLevel::File => Some(path.to_string()),
+        CycleLevel::Module => Some(directory(path)),
+    });
+
+    let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in &links {
+        dependencies.entry(&link.from).or_default().insert(&link.to);
+        dependencies.entry(&link.to).or_default();
+    }
+
+    let mut cycles: Vec<DependencyCycle> = strongly_connected(&dependencies)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|component| {
+            let members: BTreeSet<&str> = component.into_iter().collect();
+            let mut cycle_links: Vec<CycleLink> = links
+                .iter()
+                .filter(|l| members.contains(l.from.as_str()) && members.contains(l.to.as_str()))
+                .cloned()
+                .collect();
+            cycle_links.sort();
+            cycle_links.dedup();
+            DependencyCycle {
+                members: members.into_iter().map(str::to_string).collect(),
+                links: cycle_links,
+            }
+        })
+        .collect();
+    cycles.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then_with(|| a.members.cmp(&b.members))
+    });
+    cycles
+}
+
+/// Dependencies between the members of an exported commit's files
+///
+/// `member` maps a file path to the member it counts as, or `None` to leave
+/// the file out. Dependencies within one member are dropped.
+pub(crate) fn dependency_links(
+    graph: &GraphExport,
+    member: impl Fn(&str) -> Option<String>,
+) -> Vec<CycleLink> {
+    let nodes: HashMap<&str, &ExportNode> =
+        graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let file_paths: HashMap<&str, String> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.label == "File")
+        .map(|node| (node.id.as_str(), string(node, "path")))
+        .collect();
+    let files_of_symbols: HashMap<&str, &str> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == "DEFINED_IN")
+        .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+        .collect();
+    let member_of_file = |file_id: &str| file_paths.get(file_id).and_then(|path| member(path));
+
+    let mut links = Vec::new();
+    for edge in &graph.edges {
+        let (from_file, to_file) = if edge.kind == "IMPORTS" {
+            (edge.source.as_str(), edge.target.as_str())
+        } else if USE_EDGES.contains(&edge.kind.as_str()) {
+            match (
+                files_of_symbols.get(edge.source.as_str()),
+                files_of_symbols.get(edge.target.as_str()),
+            ) {
+                (Some(from), Some(to)) => (*from, *to),
+                _ => continue,
+            }
+        } else {
+            continue;
+        };
+        let (Some(from), Some(to)) = (member_of_file(from_file), member_of_file(to_file)) else {
+            continue;
+        };
+        if from != to {
+            links.push(link(edge, from, to, &nodes, &file_paths));
+        }
+    }
+    links
+}
+
+/// Describe one dependency edge between two members
+fn link(
+    edge: &ExportEdge,
+    from: String,
+    to: String,
+    nodes: &HashMap<&str, &ExportNode>,
+    file_paths: &HashMap<&str, String>,
+) -> CycleLink {
+    let line = edge
+        .properties
+        .get("line")
+        .and_then(serde_json::Value::as_i64)
+        .unwrap_or(0);
+    let (source, target) = if edge.kind == "IMPORTS" {
+        let module = edge
+            .properties
+            .get("module")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let importer = file_paths.get(edge.source.as_str()).cloned();
+        (importer.unwrap_or_default(), module.to_string())
+    } else {
+        let name = |id: &str| {
+            nodes
+                .get(id)
+                .map(|n| string(n, "qualified_name"))
+                .unwrap_or_default()
+        };
+        (name(&edge.source), name(&edge.target))
+    };
+    CycleLink {
+        from,
+        to,
+        kind: edge.kind.clone(),
+        source,
+        target,
+        line,
+    }
+}
+
+/// Strongly connected components of a directed graph (Tarjan's algorithm)
+///
+/// Iterative, so long dependency chains cannot overflow the stack. Every
+/// node must be a key of `graph`.
+fn strongly_connected<'a>(graph: &BTreeMap<&'a str, BTreeSet<&'a str>>) -> Vec<Vec<&'a str>> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut low: HashMap<&str, usize> = HashMap::new();
+    let mut on_stack: BTreeSet<&str> = BTreeSet::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut components = Vec::new();
+
+    for &root in graph.keys() {
+        if index.contains_key(root) {
+            continue;
+        }
+        // Each frame is a node and the position of its next neighbour
+        let mut frames: Vec<(&str, usize)> = vec![(root, 0)];
+        while let Some(&mut (node, ref mut next)) = frames.last_mut() {
+            if *next == 0 && !index.contains_key(node) {
+                let i = index.len();
+                index.insert(node, i);
+                low.insert(node, i);
+                stack.push(node);
+                on_stack.insert(node);
+            }
+            if let Some(&neighbour) = graph[node].iter().nth(*next) {
+                *next += 1;
+                if !index.contains_key(neighbour) {
+                    frames.push((neighbour, 0));
+                } else if on_stack.contains(neighbour) {
+                    let lowest = low[node].min(index[neighbour]);
+                    low.insert(node, lowest);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                let lowest = low[parent].min(low[node]);
+                low.insert(parent, lowest);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Directory of a file path, `.` for a bare file name
+fn directory(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) => "/".to_string(),
+        Some(i) => path[..i].to_string(),
+        None => ".".to_string(),
+    }
+}
+
+/// A string property of a node, empty if missing
+fn string(node: &ExportNode, key: &str) -> String {
+    node.properties
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
@@ -0,0 +1,322 @@
+//! Symbol diff: Added, removed, modified, moved and renamed symbols between
+//! commits
+//!
+//! Symbols of two commits are matched by identity key (file path, kind and
+//! qualified name). A matched symbol is modified when its signature or
+//! visibility changed; a field only counts as changed when both commits
+//! know it, so scans with different enrichers don't report noise. An
+//! unmatched symbol in the old commit and one in the new commit with the
+//! same kind and qualified name but another file is a move. Of the
+//! symbols left, one in the new commit that takes the place of one in the
+//! same file of the old commit is a rename (see [`find_renamed`]). Line
+//! shifts alone are not changes.
+//!
+//! A change is breaking when it can break users of a public symbol: the
+//! symbol was removed or renamed, its signature changed, or it is no longer
+//! public.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use serde::Serialize;
+
+use super::api_surface::is_public;
+use super::model::SymbolNode;
+
+/// How a symbol changed between two commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+    Moved,
+    Renamed,
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+            Self::Moved => "moved",
+            Self::Renamed => "renamed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// One changed symbol
+///
+/// `file_path` and `line` locate the symbol in the new commit, or in the old
+/// one if it was removed. The `old_*`/`new_*` pairs are only set for values
+/// that changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolChange {
+    pub change: ChangeKind,
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub line: u32,
+    /// File the symbol was in before a move
+    pub old_file_path: Option<String>,
+    /// Qualified name the symbol had before a rename
+    pub old_name: Option<String>,
+    pub old_signature: Option<String>,
+    pub new_signature: Option<String>,
+    pub old_visibility: Option<String>,
+    pub new_visibility: Option<String>,
+    /// Whether the change can break users of a public symbol
+    pub breaking: bool,
+}
+
+/// Changes to the symbols of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub changes: Vec<SymbolChange>,
+}
+
+/// Symbol-level differences between two commits, grouped per file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolDiff {
+    pub from: String,
+    pub to: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub moved: usize,
+    pub renamed: usize,
+    /// Number of changes that are breaking
+    pub breaking: usize,
+    pub files: Vec<FileDiff>,
+}
+
+impl SymbolDiff {
+    /// Whether the commits have the same symbols
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Every change, in file order
+    pub fn changes(&self) -> impl Iterator<Item = &SymbolChange> {
+        self.files.iter().flat_map(|f| f.changes.iter())
+    }
+
+    /// The same diff restricted to breaking changes
+    #[must_use]
+    pub fn breaking_only(&self) -> Self {
+        let changes = self.changes().filter(|c| c.breaking).cloned().collect();
+        group(&self.from, &self.to, changes)
+    }
+}
+
+/// Compare the symbols of commit `from` with those of commit `to`
+#[must_use]
+pub fn diff_symbols(
+    from_commit: &str,
+    from: &[SymbolNode],
+    to_commit: &str,
+    to: &[SymbolNode],
+) -> SymbolDiff {
+    let mut old_by_key = by_identity(from);
+    let mut changes = Vec::new();
+    let mut added = Vec::new();
+
+    for new in to {
+        match old_by_key
+            .get_mut(&new.identity_key())
+            .and_then(VecDeque::pop_front)
+        {
+            Some(old) => changes.extend(modification(old, new)),
+            None => added.push(new),
+        }
+    }
+    let mut removed: Vec<&SymbolNode> = old_by_key.into_values().flatten().collect();
+    removed.sort_by_key(|s| (&s.file_path, s.start_line));
+
+    let mut unmoved = Vec::new();
+    for new in added {
+        let moved_from = removed
+            .iter()
+            .position(|old| old.kind == new.kind && old.qualified_name == new.qualified_name);
+        match moved_from {
+            Some(i) => changes.push(moved(removed.remove(i), new)),
+            None => unmoved.push(new),
+        }
+    }
+    for new in unmoved {
+        let same_file: Vec<(usize, &SymbolNode)> = removed
+            .iter()
+            .enumerate()
+            .filter(|(_, old)| old.file_path == new.file_path)
+            .map(|(i, old)| (i, *old))
+            .collect();
+        let candidates: Vec<&SymbolNode> = same_file.iter().map(|(_, old)| *old).collect();
+        match find_renamed(new, &candidates) {
+            Some(i) => changes.push(renamed(removed.remove(same_file[i].0), new)),
+            None => changes.push(change(ChangeKind::Added, new)),
+        }
+    }
+    changes.extend(removed.into_iter().map(|old| {
+        let mut entry = change(ChangeKind::Removed, old);
+        entry.breaking = old.visibility.as_deref().is_some_and(is_public);
+        entry
+    }));
+
+    group(from_commit, to_commit, changes)
+}
+
+/// Index of the symbol among `gone`, symbols of the same file that
+/// disappeared, that `new` most likely was renamed from
+///
+/// A rename keeps the symbol's kind and either its definition range, its
+/// signature with the name swapped, or, if only one candidate qualifies,
+/// its number of lines.
+#[must_use]
+pub fn find_renamed(new: &SymbolNode, gone: &[&SymbolNode]) -> Option<usize> {
+    let same_range = gone.iter().position(|old| {
+        old.kind == new.kind && old.start_line == new.start_line && old.end_line == new.end_line
+    });
+    if same_range.is_some() {
+        return same_range;
+    }
+
+    let span = |s: &SymbolNode| s.end_line.saturating_sub(s.start_line);
+    only_match(new, gone, |old| same_signature(old, new))
+        .or_else(|| only_match(new, gone, |old| span(old) == span(new)))
+}
+
+/// Index of the only symbol of `new`'s kind among `gone` that is `similar`
+fn only_match(
+    new: &SymbolNode,
+    gone: &[&SymbolNode],
+    similar: impl Fn(&SymbolNode) -> bool,
+) -> Option<usize> {
+    let mut matches = gone
+        .iter()
+        .enumerate()
+        .filter(|(_, old)| old.kind == new.kind && similar(old));
+    match (matches.next(), matches.next()) {
+        (Some((i, _)), None) => Some(i),
+        _ => None,
+    }
+}
+
+/// Whether the signatures of two symbols only differ by their names
+fn same_signature(old: &SymbolNode, new: &SymbolNode) -> bool {
+    match (&old.signature, &new.signature) {
+        (Some(before), Some(after)) => before.replace(&old.name, &new.name) == *after,
+        _ => false,
+    }
+}
+
+fn by_identity(symbols: &[SymbolNode]) -> HashMap<String, VecDeque<&SymbolNode>> {
+    let mut map: HashMap<String, VecDeque<&SymbolNode>> = HashMap::new();
+    for symbol in symbols {
+        map.entry(symbol.identity_key())
+            .or_default()
+            .push_back(symbol);
+    }
+    map
+}
+
+fn change(kind: ChangeKind, symbol: &SymbolNode) -> SymbolChange {
+    SymbolChange {
+        change: kind,
+        qualified_name: symbol.qualified_name.clone(),
+        kind: symbol.kind.to_string(),
+        file_path: symbol.file_path.clone(),
+        line: symbol.start_line,
+        old_file_path: None,
+        old_name: None,
+        old_signature: None,
+        new_signature: None,
+        old_visibility: None,
+        new_visibility: None,
+        breaking: false,
+    }
+}
+
+/// The change between two versions of a symbol, if any
+fn modification(old: &SymbolNode, new: &SymbolNode) -> Option<SymbolChange> {
+    let mut entry = change(ChangeKind::Modified, new);
+    record_fields(&mut entry, old, new);
+    let changed = entry.new_signature.is_some() || entry.new_visibility.is_some();
+    changed.then_some(entry)
+}
+
+fn moved(old: &SymbolNode, new: &SymbolNode) -> SymbolChange {
+    let mut entry = change(ChangeKind::Moved, new);
+    entry.old_file_path = Some(old.file_path.clone());
+    record_fields(&mut entry, old, new);
+    entry
+}
+
+fn renamed(old: &SymbolNode, new: &SymbolNode) -> SymbolChange {
+    let mut entry = change(ChangeKind::Renamed, new);
+    entry.old_name = Some(old.qualified_name.clone());
+    entry.breaking = old.visibility.as_deref().is_some_and(is_public);
+    record_fields(&mut entry, old, new);
+    if same_signature(old, new) {
+        entry.old_signature = None;
+        entry.new_signature = None;
+    }
+    entry
+}
+
+/// Fill in the signature and visibility pairs that changed, and whether
+/// that breaks the symbol's public API
+fn record_fields(entry: &mut SymbolChange, old: &SymbolNode, new: &SymbolNode) {
+    let was_public = old.visibility.as_deref().is_some_and(is_public);
+    if let Some((before, after)) = changed(&old.signature, &new.signature) {
+        entry.old_signature = Some(before);
+        entry.new_signature = Some(after);
+        entry.breaking |= was_public;
+    }
+    if let Some((before, after)) = changed(&old.visibility, &new.visibility) {
+        entry.breaking |= was_public && !is_public(&after);
+        entry.old_visibility = Some(before);
+        entry.new_visibility = Some(after);
+    }
+}
+
+/// Both values, if both are known and differ
+fn changed(old: &Option<String>, new: &Option<String>) -> Option<(String, String)> {
+    match (old, new) {
+        (Some(old), Some(new)) if old != new => Some((old.clone(), new.clone())),
+        _ => None,
+    }
+}
+
+fn group(from_commit: &str, to_commit: &str, changes: Vec<SymbolChange>) -> SymbolDiff {
+    let mut diff = SymbolDiff {
+        from: from_commit.to_string(),
+        to: to_commit.to_string(),
+        ..SymbolDiff::default()
+    };
+    let mut files: BTreeMap<String, Vec<SymbolChange>> = BTreeMap::new();
+    for entry in changes {
+        match entry.change {
+            ChangeKind::Added => diff.added += 1,
+            ChangeKind::Removed => diff.removed += 1,
+            ChangeKind::Modified => diff.modified += 1,
+            ChangeKind::Moved => diff.moved += 1,
+            ChangeKind::Renamed => diff.renamed += 1,
+        }
+        diff.breaking += usize::from(entry.breaking);
+        files
+            .entry(entry.file_path.clone())
+            .or_default()
+            .push(entry);
+    }
+    diff.files = files
+        .into_iter()
+        .map(|(path, mut changes)| {
+            changes.sort_by(|a, b| (a.line, &a.qualified_name).cmp(&(b.line, &b.qualified_name)));
+            FileDiff { path, changes }
+        })
+        .collect();
+    diff
+}
@@ -0,0 +1,112 @@
+//! Directories: Roll-ups over the directory tree of a commit
+//!
+//! Works on the DirectorySummary nodes of a commit, which form a tree
+//! through their parents. [`rollup`] totals each subdirectory of a
+//! directory: the symbols defined below it, how many of them the commit
+//! modified, and which of its sibling directories it depends on through
+//! imports, references and calls. Questions like "which top-level folder
+//! changed most" or "what does `crates/cli` depend on" then need no string
+//! matching on file paths.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::cycles::dependency_links;
+use super::export::GraphExport;
+use super::model::{DirectorySummary, SymbolNode};
+
+/// Totals of one subdirectory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DirectoryRollup {
+    pub path: String,
+    /// Scanned files in the directory and below
+    pub file_count: usize,
+    /// Lines in those files
+    pub line_count: usize,
+    /// Symbols defined in those files
+    pub symbols: usize,
+    /// Of `symbols`, those in the `modified` set
+    pub modified: usize,
+    /// Sibling directories this one depends on, ordered by path
+    pub depends_on: Vec<DirectoryDependency>,
+}
+
+/// Dependencies of one directory on a sibling
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DirectoryDependency {
+    pub path: String,
+    /// Imports, references and calls from files below one directory to
+    /// files below the other
+    pub links: usize,
+}
+
+/// Roll up the subdirectories of `parent`
+///
+/// `modified` holds the IDs of the symbols the commit changed and `graph`
+/// is the export of the commit, for the dependencies. Files directly in
+/// `parent` belong to none of its subdirectories and are left out.
+/// Roll-ups are sorted by path.
+#[must_use]
+pub fn rollup(
+    parent: &str,
+    directories: &[DirectorySummary],
+    symbols: &[SymbolNode],
+    modified: &HashSet<&str>,
+    graph: &GraphExport,
+) -> Vec<DirectoryRollup> {
+    let children: Vec<&DirectorySummary> = directories
+        .iter()
+        .filter(|d| d.parent.as_deref() == Some(parent))
+        .collect();
+    let child_of = |file_path: &str| {
+        children
+            .iter()
+            .find(|d| Path::new(file_path).starts_with(&d.path))
+            .map(|d| d.path.clone())
+    };
+
+    let mut rollups: BTreeMap<String, DirectoryRollup> = children
+        .iter()
+        .map(|d| {
+            let rollup = DirectoryRollup {
+                path: d.path.clone(),
+                file_count: d.file_count,
+                line_count: d.line_count,
+                ..DirectoryRollup::default()
+            };
+            (d.path.clone(), rollup)
+        })
+        .collect();
+
+    for symbol in symbols {
+        let Some(rollup) = child_of(&symbol.file_path).and_then(|c| rollups.get_mut(&c)) else {
+            continue;
+        };
+        rollup.symbols += 1;
+        if modified.contains(symbol.id.as_str()) {
+            rollup.modified += 1;
+        }
+    }
+
+    let mut dependencies: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for link in dependency_links(graph, child_of) {
+        *dependencies.entry((link.from, link.to)).or_default() += 1;
+    }
+    for ((from, to), links) in dependencies {
+        if let Some(rollup) = rollups.get_mut(&from) {
+            rollup
+                .depends_on
+                .push(DirectoryDependency { path: to, links });
+        }
+    }
+
+    rollups.into_values().collect()
+}
+
+/// The scan root among a commit's directories: the one without a parent
+#[must_use]
+pub fn root(directories: &[DirectorySummary]) -> Option<&DirectorySummary> {
+    directories.iter().find(|d| d.parent.is_none())
+}
@@ -0,0 +1,96 @@
+//! Documentation coverage: Share of public symbols with a doc comment
+//!
+//! Public symbols are those declared `pub` or `public` (see
+//! [`is_public`]), of any kind. [`by_file`] and [`by_module`] count how many
+//! of them have a doc comment that is not blank, per file or per module
+//! directly containing them, so undocumented APIs show up where they live.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::api_surface::is_public;
+use super::model::{ModuleNode, SymbolNode};
+
+/// Documentation coverage of the public symbols of a file or module
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocCoverage {
+    /// File path or module path
+    pub path: String,
+    pub public: usize,
+    /// Of `public`, those with a doc comment
+    pub documented: usize,
+    /// `documented` as a percentage of `public`
+    pub percent: f64,
+}
+
+/// Coverage per file, least documented first
+#[must_use]
+pub fn by_file(symbols: &[SymbolNode]) -> Vec<DocCoverage> {
+    coverage(symbols, |symbol| Some(symbol.file_path.as_str()))
+}
+
+/// Coverage per module, counting the symbols directly in each module;
+/// symbols in no module are left out. Least documented first.
+#[must_use]
+pub fn by_module(symbols: &[SymbolNode], modules: &[ModuleNode]) -> Vec<DocCoverage> {
+    let module_of: HashMap<&str, &str> = modules
+        .iter()
+        .flat_map(|m| m.symbol_ids.iter().map(|id| (id.as_str(), m.path.as_str())))
+        .collect();
+    coverage(symbols, |symbol| module_of.get(symbol.id.as_str()).copied())
+}
+
+/// Percentage of the public symbols of all `rows` that are documented, 100
+/// if there are none
+#[must_use]
+pub fn overall_percent(rows: &[DocCoverage]) -> f64 {
+    let public = rows.iter().map(|r| r.public).sum();
+    let documented = rows.iter().map(|r| r.documented).sum();
+    percent(documented, public)
+}
+
+fn coverage<'a>(
+    symbols: &'a [SymbolNode],
+    group: impl Fn(&'a SymbolNode) -> Option<&'a str>,
+) -> Vec<DocCoverage> {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for symbol in symbols {
+        if !symbol.visibility.as_deref().is_some_and(is_public) {
+            continue;
+        }
+        let Some(path) = group(symbol) else {
+            continue;
+        };
+        let documented = symbol
+            .doc_comment
+            .as_deref()
+            .is_some_and(|doc| !doc.trim().is_empty());
+        let (public, with_docs) = counts.entry(path).or_default();
+        *public += 1;
+        *with_docs += usize::from(documented);
+    }
+
+    let mut rows: Vec<DocCoverage> = counts
+        .into_iter()
+        .map(|(path, (public, documented))| DocCoverage {
+            path: path.to_string(),
+            public,
+            documented,
+            percent: percent(documented, public),
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.percent
+            .total_cmp(&b.percent)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    rows
+}
+
+fn percent(documented: usize, public: usize) -> f64 {
+    if public == 0 {
+        return 100.0;
+    }
+    documented as f64 * 100.0 / public as f64
+}
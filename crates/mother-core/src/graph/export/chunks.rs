@@ -0,0 +1,173 @@
+//! Chunks writer: one JSON record per symbol, ready for embedding pipelines
+//!
+//! Each record carries the symbol's identity, signature and docs, the file
+//! and line range it spans, and its source read from disk. `text` joins the
+//! parts an embedding model should see, so pipelines can embed it as is.
+//! Files that cannot be read leave `source` out; the record is still written.
+//! [`ChunkWriter`] writes the same records from a streamed export.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{ExportNode, ExportRecord, GraphExport};
+
+/// One symbol of an export as a retrieval chunk
+#[derive(Serialize)]
+struct Chunk<'a> {
+    id: &'a str,
+    name: &'a str,
+    qualified_name: &'a str,
+    kind: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc: Option<&'a str>,
+    file: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'a str>,
+    start_line: u64,
+    end_line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    text: String,
+}
+
+/// Write the symbols of a graph as JSON lines chunks, in export order
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    let nodes: HashMap<&str, &ExportNode> =
+        graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let files: HashMap<&str, &ExportNode> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == "DEFINED_IN")
+        .filter_map(|e| Some((e.source.as_str(), *nodes.get(e.target.as_str())?)))
+        .collect();
+
+    let mut sources = SourceCache::default();
+    for node in graph.nodes.iter().filter(|n| n.label == "Symbol") {
+        let file = files.get(node.id.as_str()).copied();
+        write_chunk(node, file, &mut sources, out)?;
+    }
+    Ok(())
+}
+
+/// Writes chunks as the records of a streamed export arrive
+///
+/// Symbols find their file by path among the File nodes seen before them.
+#[derive(Default)]
+pub(super) struct ChunkWriter {
+    files: HashMap<String, ExportNode>,
+    sources: SourceCache,
+}
+
+impl ChunkWriter {
+    /// Remember a File node or write a Symbol node's chunk; edges are skipped
+    pub(super) fn write(&mut self, record: &ExportRecord, out: &mut impl Write) -> io::Result<()> {
+        let ExportRecord::Node(node) = record else {
+            return Ok(());
+        };
+        match node.label.as_str() {
+            "File" => {
+                if let Some(path) = text(node, "path") {
+                    self.files.insert(path.to_string(), node.clone());
+                }
+                Ok(())
+            }
+            "Symbol" => {
+                let file = text(node, "file_path").and_then(|path| self.files.get(path));
+                write_chunk(node, file, &mut self.sources, out)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write the chunk of one symbol defined in `file`
+fn write_chunk(
+    node: &ExportNode,
+    file: Option<&ExportNode>,
+    sources: &mut SourceCache,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let path = text(node, "file_path")
+        .or_else(|| file.and_then(|f| text(f, "path")))
+        .unwrap_or_default();
+    let start_line = number(node, "start_line");
+    let end_line = number(node, "end_line").max(start_line);
+    let name = text(node, "name").unwrap_or(&node.id);
+    let mut chunk = Chunk {
+        id: &node.id,
+        name,
+        qualified_name: text(node, "qualified_name").unwrap_or(name),
+        kind: text(node, "kind").unwrap_or_default(),
+        signature: text(node, "signature"),
+        doc: text(node, "doc_comment"),
+        file: path,
+        language: file.and_then(|f| text(f, "language")),
+        start_line,
+        end_line,
+        source: sources.lines(path, start_line, end_line),
+        text: String::new(),
+    };
+    chunk.text = embedding_text(&chunk);
+    serde_json::to_writer(&mut *out, &chunk)?;
+    writeln!(out)
+}
+
+/// The text to embed: a header naming the symbol, then its docs and its
+/// source (or its signature when the source is unavailable)
+fn embedding_text(chunk: &Chunk<'_>) -> String {
+    let mut parts = vec![format!(
+        "{} {} ({})",
+        chunk.kind, chunk.qualified_name, chunk.file
+    )];
+    parts.extend(chunk.doc.map(str::to_string));
+    match (&chunk.source, chunk.signature) {
+        (Some(source), _) => parts.push(source.clone()),
+        (None, Some(signature)) => parts.push(signature.to_string()),
+        (None, None) => {}
+    }
+    parts.join("\n\n")
+}
+
+/// Contents of the file read last; `None` for an unreadable file
+///
+/// Symbols arrive grouped by file, so one file is enough to read each once
+/// without keeping every file of a large export in memory.
+#[derive(Default)]
+struct SourceCache {
+    file: Option<(String, Option<Vec<String>>)>,
+}
+
+impl SourceCache {
+    /// Lines `start..=end` (1-based) of a file, if it can be read
+    fn lines(&mut self, path: &str, start: u64, end: u64) -> Option<String> {
+        if self.file.as_ref().map(|(cached, _)| cached.as_str()) != Some(path) {
+            let lines = std::fs::read_to_string(path)
+                .ok()
+                .map(|content| content.lines().map(str::to_string).collect());
+            self.file = Some((path.to_string(), lines));
+        }
+        let lines = self.file.as_ref()?.1.as_ref()?;
+        let first = usize::try_from(start.max(1) - 1).ok()?;
+        let last = usize::try_from(end).ok()?.min(lines.len());
+        (first < last).then(|| lines[first..last].join("\n"))
+    }
+}
+
+fn text<'a>(node: &'a ExportNode, key: &str) -> Option<&'a str> {
+    node.properties
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+fn number(node: &ExportNode, key: &str) -> u64 {
+    node.properties
+        .get(key)
+        .and_then(Value::as_u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,310 @@
+//! Export comparison: Diff two exports of the same commit
+//!
+//! Used to validate tool upgrades: scan a commit with the old and the new
+//! release, export both, and compare. Symbol ids are regenerated on every
+//! scan, so nodes are matched on a natural key (file path for files; file
+//! path, start line and name for symbols) and edges on their endpoints' keys.
+//! Differences are classified by their most likely cause.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::{ExportNode, GraphExport};
+
+/// Properties filled in by enrichers rather than by symbol extraction
+pub const ENRICHMENT_PROPERTIES: &[&str] = &[
+    "signature",
+    "visibility",
+    "doc_comment",
+    "type_signature",
+    "resolved_type",
+];
+
+/// Why two exports differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiffCause {
+    /// Matching nodes use differently shaped ids
+    IdScheme,
+    /// A matching symbol got a different kind
+    KindMapping,
+    /// A matching symbol got a different qualified name
+    QualifiedName,
+    /// An enriched property is present on one side only
+    MissingEnrichment,
+    /// Any other property differs
+    PropertyChange,
+    /// A node exists in the baseline only
+    NodeMissing,
+    /// A node exists in the compared export only
+    NodeAdded,
+    /// An edge exists in the baseline only
+    EdgeMissing,
+    /// An edge exists in the compared export only
+    EdgeAdded,
+}
+
+impl DiffCause {
+    /// Short explanation shown next to each group of differences
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::IdScheme => "ID scheme change",
+            Self::KindMapping => "kind mapping change",
+            Self::QualifiedName => "qualified name change",
+            Self::MissingEnrichment => "missing enrichment",
+            Self::PropertyChange => "property change",
+            Self::NodeMissing => "node only in baseline",
+            Self::NodeAdded => "node only in new export",
+            Self::EdgeMissing => "edge only in baseline",
+            Self::EdgeAdded => "edge only in new export",
+        }
+    }
+}
+
+impl std::fmt::Display for DiffCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+/// A single node- or edge-level difference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub cause: DiffCause,
+    /// Natural key of the node or edge
+    pub key: String,
+    pub detail: String,
+}
+
+/// Node and edge totals of one export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphCounts {
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+/// Result of comparing two exports
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompareReport {
+    pub baseline: GraphCounts,
+    pub against: GraphCounts,
+    pub differences: Vec<Difference>,
+}
+
+impl CompareReport {
+    /// Whether the exports are equivalent
+    #[must_use]
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Differences grouped by cause, in cause order
+    #[must_use]
+    pub fn by_cause(&self) -> BTreeMap<DiffCause, Vec<&Difference>> {
+        let mut groups: BTreeMap<DiffCause, Vec<&Difference>> = BTreeMap::new();
+        for difference in &self.differences {
+            groups.entry(difference.cause).or_default().push(difference);
+        }
+        groups
+    }
+}
+
+/// Compare a baseline export with a newer export of the same commit
+#[must_use]
+pub fn compare(baseline: &GraphExport, against: &GraphExport) -> CompareReport {
+    let mut report = CompareReport {
+        baseline: counts(baseline),
+        against: counts(against),
+        differences: Vec::new(),
+    };
+
+    let old_nodes = index_nodes(baseline);
+    let new_nodes = index_nodes(against);
+
+    for (key, old) in &old_nodes {
+        match new_nodes.get(key) {
+            Some(new) => compare_nodes(key, old, new, &mut report.differences),
+            None => report.differences.push(Difference {
+                cause: DiffCause::NodeMissing,
+                key: key.clone(),
+                detail: old.label.clone(),
+            }),
+        }
+    }
+    for (key, new) in &new_nodes {
+        if !old_nodes.contains_key(key) {
+            report.differences.push(Difference {
+                cause: DiffCause::NodeAdded,
+                key: key.clone(),
+                detail: new.label.clone(),
+            });
+        }
+    }
+
+    compare_edges(baseline, against, &mut report.differences);
+    report
+}
+
+/// Natural key of a node, stable across scans of the same commit
+#[must_use]
+pub fn node_key(node: &ExportNode) -> String {
+    let prop = |key: &str| node.properties.get(key).map(value_text);
+    match node.label.as_str() {
+        "File" => format!("File {}", prop("path").unwrap_or_else(|| node.id.clone())),
+        "Symbol" => format!(
+            "Symbol {}:{}:{}",
+            prop("file_path").unwrap_or_default(),
+            prop("start_line").unwrap_or_default(),
+            prop("name").unwrap_or_default()
+        ),
+        label => format!("{label} {}", node.id),
+    }
+}
+
+/// Shape of an id, used to detect id scheme changes
+#[must_use]
+pub fn id_scheme(id: &str) -> &'static str {
+    if let Some((prefix, _)) = id.split_once(':') {
+        return match prefix {
+            "file" => "file-hash",
+            _ => "prefixed",
+        };
+    }
+    let groups: Vec<usize> = id.split('-').map(str::len).collect();
+    let is_hex = id.chars().all(|c| c == '-' || c.is_ascii_hexdigit());
+    if is_hex && groups == [8, 4, 4, 4, 12] {
+        "uuid"
+    } else if is_hex {
+        "hex"
+    } else {
+        "other"
+    }
+}
+
+fn counts(graph: &GraphExport) -> GraphCounts {
+    GraphCounts {
+        nodes: graph.nodes.len(),
+        edges: graph.edges.len(),
+    }
+}
+
+fn index_nodes(graph: &GraphExport) -> BTreeMap<String, &ExportNode> {
+    graph
+        .nodes
+        .iter()
+        .map(|node| (node_key(node), node))
+        .collect()
+}
+
+fn compare_nodes(key: &str, old: &ExportNode, new: &ExportNode, out: &mut Vec<Difference>) {
+    let mut push = |cause, detail: String| {
+        out.push(Difference {
+            cause,
+            key: key.to_string(),
+            detail,
+        });
+    };
+
+    let (old_scheme, new_scheme) = (id_scheme(&old.id), id_scheme(&new.id));
+    if old_scheme != new_scheme {
+        push(DiffCause::IdScheme, format!("{old_scheme} → {new_scheme}"));
+    }
+
+    let mut names: Vec<&String> = old.properties.keys().collect();
+    names.extend(
+        new.properties
+            .keys()
+            .filter(|k| !old.properties.contains_key(*k)),
+    );
+
+    for name in names {
+        let (before, after) = (old.properties.get(name), new.properties.get(name));
+        if before == after {
+            continue;
+        }
+        let cause = match name.as_str() {
+            "kind" => DiffCause::KindMapping,
+            "qualified_name" => DiffCause::QualifiedName,
+            n if ENRICHMENT_PROPERTIES.contains(&n) && (before.is_none() || after.is_none()) => {
+                DiffCause::MissingEnrichment
+            }
+            _ => DiffCause::PropertyChange,
+        };
+        push(
+            cause,
+            format!(
+                "{name}: {} → {}",
+                optional_text(before),
+                optional_text(after)
+            ),
+        );
+    }
+}
+
+fn compare_edges(baseline: &GraphExport, against: &GraphExport, out: &mut Vec<Difference>) {
+    let old_edges = edge_counts(baseline);
+    let new_edges = edge_counts(against);
+
+    for (key, old_count) in &old_edges {
+        let new_count = new_edges.get(key).copied().unwrap_or(0);
+        if *old_count > new_count {
+            out.push(edge_difference(
+                DiffCause::EdgeMissing,
+                key,
+                old_count - new_count,
+            ));
+        }
+    }
+    for (key, new_count) in &new_edges {
+        let old_count = old_edges.get(key).copied().unwrap_or(0);
+        if *new_count > old_count {
+            out.push(edge_difference(
+                DiffCause::EdgeAdded,
+                key,
+                new_count - old_count,
+            ));
+        }
+    }
+}
+
+fn edge_difference(cause: DiffCause, key: &str, count: usize) -> Difference {
+    Difference {
+        cause,
+        key: key.to_string(),
+        detail: format!("×{count}"),
+    }
+}
+
+/// Count edges by `source -[KIND]-> target` using node natural keys
+fn edge_counts(graph: &GraphExport) -> BTreeMap<String, usize> {
+    let keys: BTreeMap<&str, String> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node_key(node)))
+        .collect();
+    let key_of = |id: &str| keys.get(id).cloned().unwrap_or_else(|| id.to_string());
+
+    let mut edges = BTreeMap::new();
+    for edge in &graph.edges {
+        let key = format!(
+            "{} -[{}]-> {}",
+            key_of(&edge.source),
+            edge.kind,
+            key_of(&edge.target)
+        );
+        *edges.entry(key).or_insert(0) += 1;
+    }
+    edges
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn optional_text(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(none)".to_string(), value_text)
+}
@@ -0,0 +1,138 @@
+//! Export deltas: The changes to a graph since an earlier scan
+//!
+//! Systems mirroring the graph apply a [`GraphDelta`] instead of reloading
+//! full dumps. Nodes are matched by id; file and symbol ids derive from
+//! content, so unchanged code keeps its ids across scans. Edges have no id
+//! and are matched on all their fields. New nodes and nodes whose label or
+//! properties changed are upserted, as are new edges; nodes and edges gone
+//! since the earlier scan are deleted.
+//!
+//! Deltas are written as JSON lines with an `op` marker (`upsert` or
+//! `delete`) next to the usual `type` marker. Upserts come first, then edge
+//! deletions and finally node deletions, so a consumer applying lines in
+//! order never holds an edge to a missing node. Deleted nodes and edges
+//! carry their last known properties.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use super::jsonl::Line;
+use super::{ExportEdge, ExportNode, GraphExport};
+
+/// What a consumer does with a delta line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeltaOp {
+    Upsert,
+    Delete,
+}
+
+/// Nodes and edges that changed between two exports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDelta {
+    pub upserted_nodes: Vec<ExportNode>,
+    pub upserted_edges: Vec<ExportEdge>,
+    pub deleted_edges: Vec<ExportEdge>,
+    pub deleted_nodes: Vec<ExportNode>,
+}
+
+impl GraphDelta {
+    /// The changes that turn `base` into `current`
+    #[must_use]
+    pub fn between(base: &GraphExport, current: &GraphExport) -> Self {
+        let base_nodes: HashMap<&str, &ExportNode> =
+            base.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let current_ids: HashSet<&str> = current.nodes.iter().map(|n| n.id.as_str()).collect();
+
+        let upserted_nodes = current
+            .nodes
+            .iter()
+            .filter(|n| base_nodes.get(n.id.as_str()) != Some(n))
+            .cloned()
+            .collect();
+        let deleted_nodes = base
+            .nodes
+            .iter()
+            .filter(|n| !current_ids.contains(n.id.as_str()))
+            .cloned()
+            .collect();
+
+        Self {
+            upserted_nodes,
+            upserted_edges: unmatched_edges(&current.edges, &base.edges),
+            deleted_edges: unmatched_edges(&base.edges, &current.edges),
+            deleted_nodes,
+        }
+    }
+
+    /// Whether the exports have the same nodes and edges
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.upserted_nodes.is_empty()
+            && self.upserted_edges.is_empty()
+            && self.deleted_edges.is_empty()
+            && self.deleted_nodes.is_empty()
+    }
+}
+
+/// Edges of `edges` without an identical edge in `others`, counting
+/// duplicates
+fn unmatched_edges(edges: &[ExportEdge], others: &[ExportEdge]) -> Vec<ExportEdge> {
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for edge in others {
+        *remaining.entry(edge_key(edge)).or_default() += 1;
+    }
+    edges
+        .iter()
+        .filter(|edge| match remaining.get_mut(&edge_key(edge)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+fn edge_key(edge: &ExportEdge) -> String {
+    let properties = serde_json::to_string(&edge.properties).unwrap_or_default();
+    format!(
+        "{}\u{0}{}\u{0}{}\u{0}{}",
+        edge.source, edge.target, edge.kind, properties
+    )
+}
+
+#[derive(Serialize)]
+struct DeltaLine<'a> {
+    op: DeltaOp,
+    #[serde(flatten)]
+    line: Line<'a>,
+}
+
+/// Write a delta as JSON lines with operation markers
+///
+/// # Errors
+/// Returns an error if writing fails.
+pub fn write_delta(delta: &GraphDelta, out: &mut impl Write) -> io::Result<()> {
+    let upserts = delta
+        .upserted_nodes
+        .iter()
+        .map(Line::Node)
+        .chain(delta.upserted_edges.iter().map(Line::Edge))
+        .map(|line| (DeltaOp::Upsert, line));
+    let deletes = delta
+        .deleted_edges
+        .iter()
+        .map(Line::Edge)
+        .chain(delta.deleted_nodes.iter().map(Line::Node))
+        .map(|line| (DeltaOp::Delete, line));
+
+    for (op, line) in upserts.chain(deletes) {
+        serde_json::to_writer(&mut *out, &DeltaLine { op, line })?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! DOT writer (readable by Graphviz)
+
+use std::io::{self, Write};
+
+use super::GraphExport;
+
+/// Write a graph in Graphviz DOT syntax
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "digraph mother {{")?;
+    writeln!(out, "  rankdir=LR;")?;
+    writeln!(out, "  node [fontname=\"Helvetica\"];")?;
+
+    for node in &graph.nodes {
+        let shape = if node.label == "File" {
+            "folder"
+        } else {
+            "box"
+        };
+        writeln!(
+            out,
+            "  {} [label={}, shape={}];",
+            quote(&node.id),
+            quote(node.display_name()),
+            shape
+        )?;
+    }
+    for edge in &graph.edges {
+        writeln!(
+            out,
+            "  {} -> {} [label={}];",
+            quote(&edge.source),
+            quote(&edge.target),
+            quote(&edge.kind)
+        )?;
+    }
+
+    writeln!(out, "}}")
+}
+
+/// Quote a DOT identifier, escaping quotes and backslashes
+pub(super) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
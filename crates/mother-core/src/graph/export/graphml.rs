@@ -0,0 +1,139 @@
+//! GraphML writer (readable by Gephi, yEd and Cytoscape)
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use super::GraphExport;
+
+/// Write a graph as GraphML
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    let node_keys = attribute_types(graph.nodes.iter().map(|n| &n.properties));
+    let edge_keys = attribute_types(graph.edges.iter().map(|e| &e.properties));
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="n_label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    for (name, ty) in &node_keys {
+        writeln!(
+            out,
+            r#"  <key id="n_{0}" for="node" attr.name="{0}" attr.type="{1}"/>"#,
+            escape(name),
+            ty
+        )?;
+    }
+    writeln!(
+        out,
+        r#"  <key id="e_kind" for="edge" attr.name="kind" attr.type="string"/>"#
+    )?;
+    for (name, ty) in &edge_keys {
+        writeln!(
+            out,
+            r#"  <key id="e_{0}" for="edge" attr.name="{0}" attr.type="{1}"/>"#,
+            escape(name),
+            ty
+        )?;
+    }
+
+    writeln!(out, r#"  <graph id="mother" edgedefault="directed">"#)?;
+    for node in &graph.nodes {
+        writeln!(out, r#"    <node id="{}">"#, escape(&node.id))?;
+        writeln!(
+            out,
+            r#"      <data key="n_label">{}</data>"#,
+            escape(&node.label)
+        )?;
+        write_data(out, "n", &node.properties)?;
+        writeln!(out, "    </node>")?;
+    }
+    for edge in &graph.edges {
+        writeln!(
+            out,
+            r#"    <edge source="{}" target="{}">"#,
+            escape(&edge.source),
+            escape(&edge.target)
+        )?;
+        writeln!(
+            out,
+            r#"      <data key="e_kind">{}</data>"#,
+            escape(&edge.kind)
+        )?;
+        write_data(out, "e", &edge.properties)?;
+        writeln!(out, "    </edge>")?;
+    }
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+fn write_data(
+    out: &mut impl Write,
+    prefix: &str,
+    properties: &BTreeMap<String, serde_json::Value>,
+) -> io::Result<()> {
+    for (name, value) in properties {
+        if value.is_null() {
+            continue;
+        }
+        let text = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        writeln!(
+            out,
+            r#"      <data key="{}_{}">{}</data>"#,
+            prefix,
+            escape(name),
+            escape(&text)
+        )?;
+    }
+    Ok(())
+}
+
+/// GraphML attribute type of every property name in use
+fn attribute_types<'a>(
+    properties: impl Iterator<Item = &'a BTreeMap<String, serde_json::Value>>,
+) -> BTreeMap<String, &'static str> {
+    let mut types = BTreeMap::new();
+    for props in properties {
+        for (name, value) in props {
+            let ty = match value {
+                serde_json::Value::Bool(_) => "boolean",
+                serde_json::Value::Number(n) if n.is_f64() => "double",
+                serde_json::Value::Number(_) => "long",
+                serde_json::Value::Null => continue,
+                _ => "string",
+            };
+            // Conflicting types across nodes fall back to string
+            types
+                .entry(name.clone())
+                .and_modify(|existing| {
+                    if *existing != ty {
+                        *existing = "string";
+                    }
+                })
+                .or_insert(ty);
+        }
+    }
+    types
+}
+
+/// Escape text for XML content and attribute values
+pub(super) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,207 @@
+//! Per-document view of an export, shared by the LSIF and SCIP writers
+//!
+//! Code intelligence formats are organized by document: each file lists the
+//! symbols it defines and the references made from it. Symbols are stored
+//! without columns, so their definitions are placed at the start of their
+//! first line.
+
+use std::collections::HashMap;
+
+use async_lsp::lsp_types::Url;
+use serde_json::Value;
+
+use super::{ExportNode, GraphExport};
+
+/// A file of the export with its symbols and outgoing references
+pub(super) struct IndexDocument<'a> {
+    pub path: &'a str,
+    pub language: &'a str,
+    /// Symbols defined in the file, ordered by line
+    pub symbols: Vec<IndexSymbol<'a>>,
+    /// References made from symbols of the file, ordered by position
+    pub references: Vec<IndexReference>,
+}
+
+/// A symbol definition; lines are 0-based
+pub(super) struct IndexSymbol<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub qualified_name: &'a str,
+    pub kind: &'a str,
+    pub signature: Option<&'a str>,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// A reference to a symbol; positions are 0-based
+pub(super) struct IndexReference {
+    /// Index of the referenced symbol's document
+    pub document: usize,
+    /// Index of the referenced symbol in its document
+    pub symbol: usize,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Documents of an export and the directory containing all of them
+pub(super) struct CodeIndex<'a> {
+    pub project_root: String,
+    pub documents: Vec<IndexDocument<'a>>,
+}
+
+impl<'a> CodeIndex<'a> {
+    /// Group the symbols of an export by file through their `DEFINED_IN`
+    /// edges, and their `REFERENCES` and `CALLS` edges by the referencing
+    /// file
+    pub fn of(graph: &'a GraphExport) -> Self {
+        let nodes: HashMap<&str, &ExportNode> =
+            graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let mut documents: Vec<IndexDocument<'a>> = Vec::new();
+        let mut file_documents: HashMap<&str, usize> = HashMap::new();
+        for node in graph.nodes.iter().filter(|n| n.label == "File") {
+            file_documents.insert(&node.id, documents.len());
+            documents.push(IndexDocument {
+                path: text(node, "path").unwrap_or(&node.id),
+                language: text(node, "language").unwrap_or_default(),
+                symbols: Vec::new(),
+                references: Vec::new(),
+            });
+        }
+
+        for edge in graph.edges.iter().filter(|e| e.kind == "DEFINED_IN") {
+            let (Some(node), Some(&document)) = (
+                nodes.get(edge.source.as_str()),
+                file_documents.get(edge.target.as_str()),
+            ) else {
+                continue;
+            };
+            documents[document].symbols.push(IndexSymbol::of(node));
+        }
+        for document in &mut documents {
+            document.symbols.sort_by_key(|s| (s.start_line, s.end_line));
+        }
+
+        let locations: HashMap<&str, (usize, usize)> = documents
+            .iter()
+            .enumerate()
+            .flat_map(|(d, document)| {
+                document
+                    .symbols
+                    .iter()
+                    .enumerate()
+                    .map(move |(s, symbol)| (symbol.id, (d, s)))
+            })
+            .collect();
+        for edge in &graph.edges {
+            if edge.kind != "REFERENCES" && edge.kind != "CALLS" {
+                continue;
+            }
+            let (Some(&(from, _)), Some(&(document, symbol))) = (
+                locations.get(edge.source.as_str()),
+                locations.get(edge.target.as_str()),
+            ) else {
+                continue;
+            };
+            let position = |key: &str| {
+                edge.properties
+                    .get(key)
+                    .and_then(Value::as_u64)
+                    .and_then(|n| u32::try_from(n).ok())
+            };
+            let (Some(line), Some(column)) = (position("line"), position("column")) else {
+                continue;
+            };
+            documents[from].references.push(IndexReference {
+                document,
+                symbol,
+                line,
+                column,
+            });
+        }
+        for document in &mut documents {
+            document.references.sort_by_key(|r| (r.line, r.column));
+        }
+
+        let project_root = common_directory(documents.iter().map(|d| d.path));
+        Self {
+            project_root,
+            documents,
+        }
+    }
+
+    /// The symbol a reference points to
+    pub fn target(&self, reference: &IndexReference) -> &IndexSymbol<'a> {
+        &self.documents[reference.document].symbols[reference.symbol]
+    }
+}
+
+impl<'a> IndexSymbol<'a> {
+    fn of(node: &'a ExportNode) -> Self {
+        let line = |key: &str| {
+            node.properties
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(1)
+                .saturating_sub(1)
+        };
+        let name = text(node, "name").unwrap_or(&node.id);
+        Self {
+            id: &node.id,
+            name,
+            qualified_name: text(node, "qualified_name").unwrap_or(name),
+            kind: text(node, "kind").unwrap_or_default(),
+            signature: text(node, "signature"),
+            start_line: line("start_line"),
+            end_line: line("end_line"),
+        }
+    }
+
+    /// LSP `SymbolKind` number of the symbol's kind
+    pub fn lsp_kind(&self) -> u32 {
+        match self.kind {
+            "module" | "import" => 2,
+            "class" => 5,
+            "method" => 6,
+            "field" => 8,
+            "enum" => 10,
+            "interface" | "trait" => 11,
+            "function" => 12,
+            "constant" => 14,
+            "struct" => 23,
+            "type_alias" => 26,
+            _ => 13,
+        }
+    }
+}
+
+/// `file:` URI of a path, or the path itself if it is not absolute
+pub(super) fn file_uri(path: &str) -> String {
+    Url::from_file_path(path).map_or_else(|()| path.to_string(), String::from)
+}
+
+fn text<'a>(node: &'a ExportNode, key: &str) -> Option<&'a str> {
+    node.properties.get(key).and_then(Value::as_str)
+}
+
+/// Longest directory that contains every path, without a trailing slash
+fn common_directory<'a>(paths: impl Iterator<Item = &'a str>) -> String {
+    let mut common: Option<Vec<&str>> = None;
+    for path in paths {
+        let mut parents: Vec<&str> = path.split('/').collect();
+        parents.pop();
+        common = Some(match common {
+            None => parents,
+            Some(prefix) => prefix
+                .iter()
+                .zip(&parents)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| *a)
+                .collect(),
+        });
+    }
+    match common {
+        Some(parts) if parts == [""] => "/".to_string(),
+        parts => parts.unwrap_or_default().join("/"),
+    }
+}
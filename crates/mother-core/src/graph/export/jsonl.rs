@@ -0,0 +1,71 @@
+//! JSON lines writer and reader: one node or edge object per line
+
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ExportEdge, ExportNode, ExportRecord, GraphExport};
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(super) enum Line<'a> {
+    Node(&'a ExportNode),
+    Edge(&'a ExportEdge),
+}
+
+/// Write a graph as JSON lines, nodes first
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    let lines = graph
+        .nodes
+        .iter()
+        .map(Line::Node)
+        .chain(graph.edges.iter().map(Line::Edge));
+
+    for line in lines {
+        write_line(&line, out)?;
+    }
+    Ok(())
+}
+
+/// Write one streamed record as a line
+pub(super) fn write_record(record: &ExportRecord, out: &mut impl Write) -> io::Result<()> {
+    let line = match record {
+        ExportRecord::Node(node) => Line::Node(node),
+        ExportRecord::Edge(edge) => Line::Edge(edge),
+    };
+    write_line(&line, out)
+}
+
+fn write_line(line: &Line<'_>, out: &mut impl Write) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, line)?;
+    writeln!(out)
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum OwnedLine {
+    Node(ExportNode),
+    Edge(ExportEdge),
+}
+
+/// Read a graph from JSON lines, skipping blank lines
+pub(super) fn read(input: impl BufRead) -> io::Result<GraphExport> {
+    let mut graph = GraphExport::default();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed: OwnedLine = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {e}", index + 1),
+            )
+        })?;
+        match parsed {
+            OwnedLine::Node(node) => graph.nodes.push(node),
+            OwnedLine::Edge(edge) => graph.edges.push(edge),
+        }
+    }
+    Ok(graph)
+}
@@ -0,0 +1,191 @@
+//! LSIF writer: one vertex or edge per line, readable by LSIF consumers and
+//! by `mother import lsif`
+//!
+//! Each symbol gets a definition range tagged with its name, kind and full
+//! range, and a result set with its definition, references and, if it has a
+//! signature, hover. Document contents are not embedded, since the graph
+//! does not keep them.
+
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use super::index::{file_uri, CodeIndex, IndexSymbol};
+use super::GraphExport;
+
+/// Write a graph as an LSIF dump in JSON lines
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    let index = CodeIndex::of(graph);
+    let mut dump = Dump { out, next_id: 0 };
+    dump.vertex(
+        "metaData",
+        json!({
+            "version": "0.5.0",
+            "positionEncoding": "utf-16",
+            "projectRoot": file_uri(&index.project_root),
+            "toolInfo": { "name": "mother", "version": env!("CARGO_PKG_VERSION") },
+        }),
+    )?;
+
+    // Vertices must come before the edges using them, so every definition
+    // is written before the references to it
+    let mut documents = Vec::new();
+    let mut definitions: Vec<Vec<Definition>> = Vec::new();
+    for document in &index.documents {
+        let id = dump.vertex(
+            "document",
+            json!({ "uri": file_uri(document.path), "languageId": document.language }),
+        )?;
+        documents.push(id);
+        let mut defined = Vec::new();
+        for symbol in &document.symbols {
+            defined.push(dump.definition(symbol, id, document.language)?);
+        }
+        definitions.push(defined);
+    }
+
+    for (d, document) in index.documents.iter().enumerate() {
+        let mut ranges: Vec<u64> = definitions[d].iter().map(|def| def.range).collect();
+        for reference in &document.references {
+            let target = index.target(reference);
+            let definition = &definitions[reference.document][reference.symbol];
+            let start = json!({ "line": reference.line, "character": reference.column });
+            let end = json!({
+                "line": reference.line,
+                "character": reference.column + utf16_len(target.name),
+            });
+            let range = dump.vertex(
+                "range",
+                json!({
+                    "start": start,
+                    "end": end,
+                    "tag": { "type": "reference", "text": target.name },
+                }),
+            )?;
+            dump.edge(
+                "next",
+                json!({ "outV": range, "inV": definition.result_set }),
+            )?;
+            dump.edge(
+                "item",
+                json!({
+                    "outV": definition.references,
+                    "inVs": [range],
+                    "document": documents[d],
+                    "property": "references",
+                }),
+            )?;
+            ranges.push(range);
+        }
+        if !ranges.is_empty() {
+            dump.edge("contains", json!({ "outV": documents[d], "inVs": ranges }))?;
+        }
+    }
+    Ok(())
+}
+
+/// IDs of the vertices written for a symbol's definition
+struct Definition {
+    range: u64,
+    result_set: u64,
+    references: u64,
+}
+
+struct Dump<'a, W: Write> {
+    out: &'a mut W,
+    next_id: u64,
+}
+
+impl<W: Write> Dump<'_, W> {
+    fn element(&mut self, kind: &str, label: &str, fields: Value) -> io::Result<u64> {
+        self.next_id += 1;
+        let mut element = json!({ "id": self.next_id, "type": kind, "label": label });
+        if let (Value::Object(element), Value::Object(fields)) = (&mut element, fields) {
+            element.extend(fields);
+        }
+        serde_json::to_writer(&mut *self.out, &element)?;
+        writeln!(self.out)?;
+        Ok(self.next_id)
+    }
+
+    fn vertex(&mut self, label: &str, fields: Value) -> io::Result<u64> {
+        self.element("vertex", label, fields)
+    }
+
+    fn edge(&mut self, label: &str, fields: Value) -> io::Result<u64> {
+        self.element("edge", label, fields)
+    }
+
+    /// Write a symbol's definition range, its result set and results
+    fn definition(
+        &mut self,
+        symbol: &IndexSymbol<'_>,
+        document: u64,
+        language: &str,
+    ) -> io::Result<Definition> {
+        let start = json!({ "line": symbol.start_line, "character": 0 });
+        let mut tag = json!({
+            "type": "definition",
+            "text": symbol.name,
+            "kind": symbol.lsp_kind(),
+            "fullRange": {
+                "start": start,
+                "end": { "line": symbol.end_line, "character": 0 },
+            },
+        });
+        if let Some(signature) = symbol.signature {
+            tag["detail"] = json!(signature);
+        }
+        let end = json!({ "line": symbol.start_line, "character": utf16_len(symbol.name) });
+        let range = self.vertex("range", json!({ "start": start, "end": end, "tag": tag }))?;
+        let result_set = self.vertex("resultSet", json!({}))?;
+        self.edge("next", json!({ "outV": range, "inV": result_set }))?;
+
+        let definitions = self.vertex("definitionResult", json!({}))?;
+        self.edge(
+            "textDocument/definition",
+            json!({ "outV": result_set, "inV": definitions }),
+        )?;
+        self.edge(
+            "item",
+            json!({ "outV": definitions, "inVs": [range], "document": document }),
+        )?;
+
+        let references = self.vertex("referenceResult", json!({}))?;
+        self.edge(
+            "textDocument/references",
+            json!({ "outV": result_set, "inV": references }),
+        )?;
+        self.edge(
+            "item",
+            json!({
+                "outV": references,
+                "inVs": [range],
+                "document": document,
+                "property": "definitions",
+            }),
+        )?;
+
+        if let Some(signature) = symbol.signature {
+            let hover = self.vertex(
+                "hoverResult",
+                json!({ "result": { "contents": [{ "language": language, "value": signature }] } }),
+            )?;
+            self.edge(
+                "textDocument/hover",
+                json!({ "outV": result_set, "inV": hover }),
+            )?;
+        }
+
+        Ok(Definition {
+            range,
+            result_set,
+            references,
+        })
+    }
+}
+
+/// Length in UTF-16 code units, the LSIF position encoding
+fn utf16_len(text: &str) -> u32 {
+    u32::try_from(text.encode_utf16().count()).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,213 @@
+//! Export module: Serialize a scanned graph to GraphML, DOT, JSON lines, LSIF,
+//! SCIP or retrieval chunks
+//!
+//! A [`GraphExport`] is a flat snapshot of the File and Symbol nodes of one
+//! commit and the edges between them, as read by
+//! [`Neo4jClient::export_commit`](crate::graph::neo4j::Neo4jClient::export_commit).
+//! The writers only depend on this snapshot, not on Neo4j. JSON lines exports
+//! can be read back with [`read_jsonl`] and compared with [`compare::compare`].
+//! The changes between two scans are computed and written by [`delta`].
+//! LSIF and SCIP exports let other code intelligence tools use the scanned
+//! symbols and references; LSIF dumps can be read back with
+//! [`read_lsif`](crate::import::read_lsif). Chunk exports write one record
+//! per symbol with its source, for embedding pipelines.
+//!
+//! JSON lines and chunk exports can also be written record by record with a
+//! [`RecordWriter`] as [`GraphStore::stream_export`](crate::graph::GraphStore::stream_export)
+//! yields them, so large commits are never held in memory whole.
+
+mod chunks;
+pub mod compare;
+pub mod delta;
+mod dot;
+mod graphml;
+mod index;
+mod jsonl;
+mod lsif;
+mod scip;
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// A node in an exported graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportNode {
+    /// Unique node identifier within the export
+    pub id: String,
+    /// Node label (`File` or `Symbol`)
+    pub label: String,
+    pub properties: BTreeMap<String, serde_json::Value>,
+}
+
+/// An edge in an exported graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportEdge {
+    pub source: String,
+    pub target: String,
+    /// Relationship type (e.g. `DEFINED_IN`, `REFERENCES`, `IMPORTS`)
+    pub kind: String,
+    pub properties: BTreeMap<String, serde_json::Value>,
+}
+
+/// Snapshot of the nodes and edges of one scanned commit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub nodes: Vec<ExportNode>,
+    pub edges: Vec<ExportEdge>,
+}
+
+/// A node or edge of an export, as streamed by
+/// [`GraphStore::stream_export`](crate::graph::GraphStore::stream_export)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportRecord {
+    Node(ExportNode),
+    Edge(ExportEdge),
+}
+
+impl GraphExport {
+    /// Add a streamed record to the snapshot
+    pub fn push(&mut self, record: ExportRecord) {
+        match record {
+            ExportRecord::Node(node) => self.nodes.push(node),
+            ExportRecord::Edge(edge) => self.edges.push(edge),
+        }
+    }
+}
+
+impl ExportNode {
+    /// Human-readable name used for visual labels
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        ["name", "path"]
+            .iter()
+            .find_map(|key| self.properties.get(*key).and_then(|v| v.as_str()))
+            .unwrap_or(&self.id)
+    }
+}
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    GraphMl,
+    Dot,
+    JsonLines,
+    Lsif,
+    Scip,
+    /// One JSON record per symbol with its source, for embedding
+    Chunks,
+}
+
+impl ExportFormat {
+    /// Infer the format from a file extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "graphml" | "xml" => Ok(Self::GraphMl),
+            "dot" | "gv" => Ok(Self::Dot),
+            "jsonl" | "ndjson" | "json-lines" => Ok(Self::JsonLines),
+            "lsif" => Ok(Self::Lsif),
+            "scip" => Ok(Self::Scip),
+            "chunks" => Ok(Self::Chunks),
+            _ => Err(format!("Unknown export format: {s}")),
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GraphMl => write!(f, "graphml"),
+            Self::Dot => write!(f, "dot"),
+            Self::JsonLines => write!(f, "jsonl"),
+            Self::Lsif => write!(f, "lsif"),
+            Self::Scip => write!(f, "scip"),
+            Self::Chunks => write!(f, "chunks"),
+        }
+    }
+}
+
+/// Write a graph in the given format
+///
+/// Chunk exports read each symbol's source from its file path; files that
+/// cannot be read are exported without source.
+///
+/// # Errors
+/// Returns an error if writing fails.
+pub fn write_graph(
+    graph: &GraphExport,
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::GraphMl => graphml::write(graph, out),
+        ExportFormat::Dot => dot::write(graph, out),
+        ExportFormat::JsonLines => jsonl::write(graph, out),
+        ExportFormat::Lsif => lsif::write(graph, out),
+        ExportFormat::Scip => scip::write(graph, out),
+        ExportFormat::Chunks => chunks::write(graph, out),
+    }
+}
+
+/// Writes an export one record at a time, for the formats that need no view
+/// of the whole graph
+///
+/// Records must arrive nodes first, with File nodes before the symbols they
+/// define, as stores stream them.
+pub struct RecordWriter {
+    inner: Writer,
+}
+
+enum Writer {
+    JsonLines,
+    Chunks(chunks::ChunkWriter),
+}
+
+impl RecordWriter {
+    /// A writer for `format`, or `None` if the format needs the whole graph
+    #[must_use]
+    pub fn new(format: ExportFormat) -> Option<Self> {
+        let inner = match format {
+            ExportFormat::JsonLines => Writer::JsonLines,
+            ExportFormat::Chunks => Writer::Chunks(chunks::ChunkWriter::default()),
+            ExportFormat::GraphMl | ExportFormat::Dot | ExportFormat::Lsif | ExportFormat::Scip => {
+                return None
+            }
+        };
+        Some(Self { inner })
+    }
+
+    /// Write one record
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn write(&mut self, record: &ExportRecord, out: &mut impl Write) -> io::Result<()> {
+        match &mut self.inner {
+            Writer::JsonLines => jsonl::write_record(record, out),
+            Writer::Chunks(chunks) => chunks.write(record, out),
+        }
+    }
+}
+
+/// Read a graph written in the JSON lines format
+///
+/// # Errors
+/// Returns an error if reading fails or a line is not a node or edge object.
+pub fn read_jsonl(input: impl BufRead) -> io::Result<GraphExport> {
+    jsonl::read(input)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,188 @@
+//! SCIP writer: a protobuf `Index` message, readable by the `scip` CLI and
+//! SCIP consumers
+//!
+//! Documents are listed with paths relative to the common directory of all
+//! files. Each symbol gets a global SCIP symbol under the `mother` scheme,
+//! built from its file path and qualified name, with a definition occurrence
+//! spanning its lines and a reference occurrence per stored reference.
+
+use std::io::{self, Write};
+
+use super::index::{file_uri, CodeIndex, IndexSymbol};
+use super::GraphExport;
+
+/// `SymbolRole.Definition`
+const DEFINITION_ROLE: u64 = 1;
+/// `TextEncoding.UTF8`
+const UTF8_ENCODING: u64 = 1;
+
+/// Write a graph as a SCIP index
+pub(super) fn write(graph: &GraphExport, out: &mut impl Write) -> io::Result<()> {
+    let index = CodeIndex::of(graph);
+    let symbols: Vec<Vec<String>> = index
+        .documents
+        .iter()
+        .map(|document| {
+            let path = relative_path(document.path, &index.project_root);
+            document
+                .symbols
+                .iter()
+                .map(|symbol| scip_symbol(path, symbol))
+                .collect()
+        })
+        .collect();
+
+    let mut message = Vec::new();
+    let mut tool = Vec::new();
+    put_string(&mut tool, 1, "mother");
+    put_string(&mut tool, 2, env!("CARGO_PKG_VERSION"));
+    let mut metadata = Vec::new();
+    put_message(&mut metadata, 2, &tool);
+    put_string(&mut metadata, 3, &file_uri(&index.project_root));
+    put_varint_field(&mut metadata, 4, UTF8_ENCODING);
+    put_message(&mut message, 1, &metadata);
+
+    for (d, document) in index.documents.iter().enumerate() {
+        let mut doc = Vec::new();
+        put_string(&mut doc, 4, document.language);
+        put_string(
+            &mut doc,
+            1,
+            relative_path(document.path, &index.project_root),
+        );
+
+        for (symbol, scip) in document.symbols.iter().zip(&symbols[d]) {
+            let mut occurrence = Vec::new();
+            let name_end = utf8_len(symbol.name);
+            put_packed(&mut occurrence, 1, &[symbol.start_line, 0, name_end]);
+            put_string(&mut occurrence, 2, scip);
+            put_varint_field(&mut occurrence, 3, DEFINITION_ROLE);
+            let enclosing = [symbol.start_line, 0, symbol.end_line, 0];
+            put_packed(&mut occurrence, 7, &enclosing);
+            put_message(&mut doc, 2, &occurrence);
+        }
+        for reference in &document.references {
+            let target = index.target(reference);
+            let mut occurrence = Vec::new();
+            let end = reference.column + utf8_len(target.name);
+            put_packed(&mut occurrence, 1, &[reference.line, reference.column, end]);
+            put_string(
+                &mut occurrence,
+                2,
+                &symbols[reference.document][reference.symbol],
+            );
+            put_message(&mut doc, 2, &occurrence);
+        }
+
+        for (symbol, scip) in document.symbols.iter().zip(&symbols[d]) {
+            let mut information = Vec::new();
+            put_string(&mut information, 1, scip);
+            if let Some(signature) = symbol.signature {
+                let block = format!("```{}\n{signature}\n```", document.language);
+                put_string(&mut information, 3, &block);
+            }
+            put_string(&mut information, 6, symbol.name);
+            put_message(&mut doc, 3, &information);
+        }
+        put_message(&mut message, 2, &doc);
+    }
+    out.write_all(&message)
+}
+
+/// Global SCIP symbol of a graph symbol: the file path as namespaces, then
+/// the qualified name with a suffix by kind on its last part
+fn scip_symbol(path: &str, symbol: &IndexSymbol<'_>) -> String {
+    let mut descriptors = String::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        descriptors.push_str(&escape(segment));
+        descriptors.push('/');
+    }
+    let parts: Vec<&str> = symbol
+        .qualified_name
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|part| !part.is_empty())
+        .collect();
+    let Some((last, parents)) = parts.split_last() else {
+        return format!("mother . . . {descriptors}{}.", escape(symbol.name));
+    };
+    for parent in parents {
+        descriptors.push_str(&escape(parent));
+        descriptors.push('#');
+    }
+    descriptors.push_str(&escape(last));
+    descriptors.push_str(match symbol.kind {
+        "module" | "import" => "/",
+        "class" | "struct" | "enum" | "interface" | "trait" | "type_alias" => "#",
+        "function" | "method" => "().",
+        _ => ".",
+    });
+    format!("mother . . . {descriptors}")
+}
+
+/// A descriptor name, in backticks unless it is a simple identifier
+fn escape(name: &str) -> String {
+    let simple = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '$'));
+    if simple && !name.is_empty() {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+fn relative_path<'a>(path: &'a str, root: &str) -> &'a str {
+    path.strip_prefix(root)
+        .map_or(path, |rest| rest.trim_start_matches('/'))
+}
+
+fn utf8_len(text: &str) -> u32 {
+    u32::try_from(text.len()).unwrap_or(u32::MAX)
+}
+
+// ============================================================================
+// Protobuf encoding
+// ============================================================================
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u64, wire_type: u64) {
+    put_varint(buf, field << 3 | wire_type);
+}
+
+fn put_varint_field(buf: &mut Vec<u8>, field: u64, value: u64) {
+    put_key(buf, field, 0);
+    put_varint(buf, value);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_key(buf, field, 2);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn put_string(buf: &mut Vec<u8>, field: u64, text: &str) {
+    if !text.is_empty() {
+        put_bytes(buf, field, text.as_bytes());
+    }
+}
+
+fn put_message(buf: &mut Vec<u8>, field: u64, message: &[u8]) {
+    put_bytes(buf, field, message);
+}
+
+/// A packed repeated `int32` field
+fn put_packed(buf: &mut Vec<u8>, field: u64, values: &[u32]) {
+    let mut packed = Vec::new();
+    for value in values {
+        put_varint(&mut packed, u64::from(*value));
+    }
+    put_bytes(buf, field, &packed);
+}
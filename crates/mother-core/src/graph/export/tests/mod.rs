@@ -0,0 +1,8 @@
+//! Tests for export module
+
+mod tests_chunks;
+mod tests_compare;
+mod tests_delta;
+mod tests_format;
+mod tests_index;
+mod tests_writers;
@@ -0,0 +1,166 @@
+//! Tests for the chunks writer
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::export::{
+    write_graph, ExportEdge, ExportFormat, ExportNode, ExportRecord, GraphExport, RecordWriter,
+};
+
+const LIB: &str = "/// Settings\npub struct Config {\n    pub name: String,\n}\n\npub fn load() -> Config {\n    todo!()\n}\n";
+
+fn node(id: &str, label: &str, properties: Value) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties: serde_json::from_value(properties).unwrap(),
+    }
+}
+
+fn defined_in(symbol: &str) -> ExportEdge {
+    ExportEdge {
+        source: symbol.to_string(),
+        target: "file:lib".to_string(),
+        kind: "DEFINED_IN".to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+fn graph(path: &str) -> GraphExport {
+    GraphExport {
+        nodes: vec![
+            node(
+                "file:lib",
+                "File",
+                json!({ "path": path, "language": "rust" }),
+            ),
+            node(
+                "sym:config",
+                "Symbol",
+                json!({
+                    "name": "Config",
+                    "qualified_name": "lib::Config",
+                    "kind": "struct",
+                    "file_path": path,
+                    "doc_comment": "Settings",
+                    "start_line": 2,
+                    "end_line": 4,
+                }),
+            ),
+            node(
+                "sym:load",
+                "Symbol",
+                json!({
+                    "name": "load",
+                    "qualified_name": "lib::load",
+                    "kind": "function",
+                    "file_path": path,
+                    "signature": "pub fn load() -> Config",
+                    "start_line": 6,
+                    "end_line": 8,
+                }),
+            ),
+        ],
+        edges: vec![defined_in("sym:config"), defined_in("sym:load")],
+    }
+}
+
+fn chunks(graph: &GraphExport) -> Vec<Value> {
+    let mut out = Vec::new();
+    write_graph(graph, ExportFormat::Chunks, &mut out).unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_chunks_one_record_per_symbol_with_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lib.rs");
+    std::fs::write(&path, LIB).unwrap();
+    let path = path.to_str().unwrap();
+
+    let records = chunks(&graph(path));
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["qualified_name"], "lib::Config");
+    assert_eq!(records[0]["kind"], "struct");
+    assert_eq!(records[0]["doc"], "Settings");
+    assert_eq!(records[0]["file"], path);
+    assert_eq!(records[0]["language"], "rust");
+    assert_eq!(records[0]["start_line"], 2);
+    assert_eq!(records[0]["end_line"], 4);
+    assert_eq!(
+        records[0]["source"],
+        "pub struct Config {\n    pub name: String,\n}"
+    );
+    assert_eq!(records[1]["signature"], "pub fn load() -> Config");
+    assert!(records[1].get("doc").is_none());
+}
+
+#[test]
+fn test_chunks_text_joins_header_doc_and_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lib.rs");
+    std::fs::write(&path, LIB).unwrap();
+    let path = path.to_str().unwrap();
+
+    let records = chunks(&graph(path));
+
+    assert_eq!(
+        records[0]["text"],
+        format!(
+            "struct lib::Config ({path})\n\nSettings\n\npub struct Config {{\n    pub name: String,\n}}"
+        )
+    );
+}
+
+#[test]
+fn test_chunks_without_file_fall_back_to_signature() {
+    let records = chunks(&graph("/missing/lib.rs"));
+
+    assert!(records[1].get("source").is_none());
+    assert_eq!(
+        records[1]["text"],
+        "function lib::load (/missing/lib.rs)\n\npub fn load() -> Config"
+    );
+}
+
+#[test]
+fn test_chunks_skip_file_nodes_and_edges() {
+    let records = chunks(&graph("/missing/lib.rs"));
+    assert!(records.iter().all(|r| r["id"] != "file:lib"));
+}
+
+#[test]
+fn test_streamed_chunks_match_whole_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lib.rs");
+    std::fs::write(&path, LIB).unwrap();
+    let graph = graph(path.to_str().unwrap());
+
+    let mut writer = RecordWriter::new(ExportFormat::Chunks).unwrap();
+    let mut out = Vec::new();
+    let records = graph
+        .nodes
+        .iter()
+        .cloned()
+        .map(ExportRecord::Node)
+        .chain(graph.edges.iter().cloned().map(ExportRecord::Edge));
+    for record in records {
+        writer.write(&record, &mut out).unwrap();
+    }
+    let streamed: Vec<Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+
+    assert_eq!(streamed, chunks(&graph));
+    assert_eq!(streamed[0]["language"], "rust");
+}
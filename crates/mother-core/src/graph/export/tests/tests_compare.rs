@@ -0,0 +1,194 @@
+//! Tests for comparing two exports
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::export::compare::{compare, id_scheme, node_key, DiffCause};
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+
+fn file(id: &str, path: &str) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: "File".to_string(),
+        properties: [("path".to_string(), json!(path))].into_iter().collect(),
+    }
+}
+
+fn symbol(id: &str, name: &str, extra: &[(&str, Value)]) -> ExportNode {
+    let mut properties: BTreeMap<String, Value> = [
+        ("name", json!(name)),
+        ("file_path", json!("src/lib.rs")),
+        ("start_line", json!(3)),
+        ("kind", json!("function")),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+    for (k, v) in extra {
+        properties.insert((*k).to_string(), v.clone());
+    }
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties,
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+const OLD_ID: &str = "6f1c2a9e-1b2c-4d3e-8f90-0a1b2c3d4e5f";
+const NEW_ID: &str = "0b7d4e2a-9c8b-4a6f-8e21-5d4c3b2a1f0e";
+
+fn graph(nodes: Vec<ExportNode>, edges: Vec<ExportEdge>) -> GraphExport {
+    GraphExport { nodes, edges }
+}
+
+// ============================================================================
+// Keys and id schemes
+// ============================================================================
+
+#[test]
+fn test_node_key_ignores_symbol_id() {
+    assert_eq!(
+        node_key(&symbol(OLD_ID, "run", &[])),
+        node_key(&symbol(NEW_ID, "run", &[]))
+    );
+    assert_eq!(node_key(&file("file:abc", "src/lib.rs")), "File src/lib.rs");
+}
+
+#[test]
+fn test_id_scheme_shapes() {
+    assert_eq!(id_scheme(OLD_ID), "uuid");
+    assert_eq!(id_scheme("file:abc"), "file-hash");
+    assert_eq!(id_scheme("deadbeef"), "hex");
+    assert_eq!(id_scheme("sym:src/lib.rs#run"), "prefixed");
+    assert_eq!(id_scheme("run"), "other");
+}
+
+// ============================================================================
+// compare
+// ============================================================================
+
+#[test]
+fn test_regenerated_uuids_are_identical() {
+    let old = graph(
+        vec![file("file:a", "src/lib.rs"), symbol(OLD_ID, "run", &[])],
+        vec![edge(OLD_ID, "file:a", "DEFINED_IN")],
+    );
+    let new = graph(
+        vec![file("file:a", "src/lib.rs"), symbol(NEW_ID, "run", &[])],
+        vec![edge(NEW_ID, "file:a", "DEFINED_IN")],
+    );
+
+    let report = compare(&old, &new);
+    assert!(report.is_identical(), "{:?}", report.differences);
+    assert_eq!(report.baseline.nodes, 2);
+    assert_eq!(report.against.edges, 1);
+}
+
+#[test]
+fn test_detects_id_scheme_change() {
+    let old = graph(vec![symbol(OLD_ID, "run", &[])], vec![]);
+    let new = graph(vec![symbol("sym:src/lib.rs#run", "run", &[])], vec![]);
+
+    let report = compare(&old, &new);
+    let groups = report.by_cause();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[&DiffCause::IdScheme][0].detail, "uuid → prefixed");
+}
+
+#[test]
+fn test_classifies_property_changes() {
+    let old = graph(
+        vec![symbol(
+            OLD_ID,
+            "run",
+            &[
+                ("qualified_name", json!("impl Server::run")),
+                ("signature", json!("fn run()")),
+                ("end_line", json!(5)),
+            ],
+        )],
+        vec![],
+    );
+    let new = graph(
+        vec![symbol(
+            NEW_ID,
+            "run",
+            &[
+                ("kind", json!("method")),
+                ("qualified_name", json!("Server::run")),
+                ("end_line", json!(6)),
+            ],
+        )],
+        vec![],
+    );
+
+    let report = compare(&old, &new);
+    let groups = report.by_cause();
+    assert_eq!(
+        groups[&DiffCause::KindMapping][0].detail,
+        "kind: function → method"
+    );
+    assert_eq!(
+        groups[&DiffCause::QualifiedName][0].detail,
+        "qualified_name: impl Server::run → Server::run"
+    );
+    assert_eq!(
+        groups[&DiffCause::MissingEnrichment][0].detail,
+        "signature: fn run() → (none)"
+    );
+    assert_eq!(
+        groups[&DiffCause::PropertyChange][0].detail,
+        "end_line: 5 → 6"
+    );
+}
+
+#[test]
+fn test_detects_missing_and_added_nodes() {
+    let old = graph(vec![symbol(OLD_ID, "old_fn", &[])], vec![]);
+    let new = graph(vec![symbol(NEW_ID, "new_fn", &[])], vec![]);
+
+    let report = compare(&old, &new);
+    let groups = report.by_cause();
+    assert_eq!(groups[&DiffCause::NodeMissing].len(), 1);
+    assert_eq!(groups[&DiffCause::NodeAdded].len(), 1);
+    assert!(groups[&DiffCause::NodeMissing][0].key.ends_with("old_fn"));
+}
+
+#[test]
+fn test_detects_edge_count_changes() {
+    let nodes_old = vec![file("file:a", "src/lib.rs"), symbol(OLD_ID, "run", &[])];
+    let nodes_new = vec![file("file:a", "src/lib.rs"), symbol(NEW_ID, "run", &[])];
+    let old = graph(
+        nodes_old,
+        vec![
+            edge(OLD_ID, "file:a", "DEFINED_IN"),
+            edge("file:a", "file:a", "IMPORTS"),
+        ],
+    );
+    let new = graph(
+        nodes_new,
+        vec![
+            edge(NEW_ID, "file:a", "DEFINED_IN"),
+            edge(NEW_ID, NEW_ID, "REFERENCES"),
+            edge(NEW_ID, NEW_ID, "REFERENCES"),
+        ],
+    );
+
+    let report = compare(&old, &new);
+    let groups = report.by_cause();
+    assert_eq!(groups[&DiffCause::EdgeMissing].len(), 1);
+    assert!(groups[&DiffCause::EdgeMissing][0]
+        .key
+        .contains("-[IMPORTS]->"));
+    assert_eq!(groups[&DiffCause::EdgeAdded][0].detail, "×2");
+}
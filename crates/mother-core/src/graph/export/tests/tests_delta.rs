@@ -0,0 +1,141 @@
+//! Tests for export deltas
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::export::delta::{write_delta, GraphDelta};
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+
+fn node(id: &str, name: &str) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties: BTreeMap::from([("name".to_string(), json!(name))]),
+    }
+}
+
+fn edge(source: &str, target: &str, line: u32) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: "REFERENCES".to_string(),
+        properties: BTreeMap::from([("line".to_string(), json!(line))]),
+    }
+}
+
+fn base() -> GraphExport {
+    GraphExport {
+        nodes: vec![node("a", "parse"), node("b", "render"), node("c", "old")],
+        edges: vec![edge("a", "b", 3), edge("a", "c", 5)],
+    }
+}
+
+fn ids(nodes: &[ExportNode]) -> Vec<&str> {
+    nodes.iter().map(|n| n.id.as_str()).collect()
+}
+
+// ============================================================================
+// Tests for GraphDelta::between
+// ============================================================================
+
+#[test]
+fn test_identical_exports_have_an_empty_delta() {
+    assert!(GraphDelta::between(&base(), &base()).is_empty());
+}
+
+#[test]
+fn test_delta_upserts_new_and_changed_nodes_and_deletes_gone_ones() {
+    let current = GraphExport {
+        nodes: vec![node("a", "parse"), node("b", "draw"), node("d", "new")],
+        edges: vec![edge("a", "b", 3), edge("a", "d", 7)],
+    };
+
+    let delta = GraphDelta::between(&base(), &current);
+
+    assert_eq!(ids(&delta.upserted_nodes), vec!["b", "d"]);
+    assert_eq!(ids(&delta.deleted_nodes), vec!["c"]);
+    assert_eq!(delta.upserted_edges, vec![edge("a", "d", 7)]);
+    assert_eq!(delta.deleted_edges, vec![edge("a", "c", 5)]);
+}
+
+#[test]
+fn test_delta_matches_duplicate_edges_one_to_one() {
+    let current = GraphExport {
+        nodes: base().nodes,
+        edges: vec![edge("a", "b", 3), edge("a", "b", 3), edge("a", "c", 5)],
+    };
+
+    let delta = GraphDelta::between(&base(), &current);
+
+    assert_eq!(delta.upserted_edges, vec![edge("a", "b", 3)]);
+    assert!(delta.deleted_edges.is_empty());
+}
+
+// ============================================================================
+// Tests for write_delta
+// ============================================================================
+
+#[test]
+fn test_write_delta_marks_operations_and_deletes_edges_before_nodes() {
+    let current = GraphExport {
+        nodes: vec![node("a", "parse"), node("b", "render")],
+        edges: vec![edge("a", "b", 3)],
+    };
+    let delta = GraphDelta::between(&base(), &current);
+
+    let mut out = Vec::new();
+    write_delta(&delta, &mut out).unwrap();
+    let lines: Vec<Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["op"], "delete");
+    assert_eq!(lines[0]["type"], "edge");
+    assert_eq!(lines[0]["target"], "c");
+    assert_eq!(lines[1]["op"], "delete");
+    assert_eq!(lines[1]["type"], "node");
+    assert_eq!(lines[1]["id"], "c");
+    assert_eq!(lines[1]["properties"]["name"], "old");
+}
+
+#[test]
+fn test_write_delta_lists_upserts_first() {
+    let current = GraphExport {
+        nodes: vec![node("a", "parse"), node("d", "new")],
+        edges: vec![edge("a", "d", 7)],
+    };
+    let delta = GraphDelta::between(&base(), &current);
+
+    let mut out = Vec::new();
+    write_delta(&delta, &mut out).unwrap();
+    let ops: Vec<(String, String)> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let value: Value = serde_json::from_str(line).unwrap();
+            (
+                value["op"].as_str().unwrap().to_string(),
+                value["type"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+
+    let expected = [
+        ("upsert", "node"),
+        ("upsert", "edge"),
+        ("delete", "edge"),
+        ("delete", "edge"),
+        ("delete", "node"),
+        ("delete", "node"),
+    ];
+    assert_eq!(
+        ops,
+        expected.map(|(op, kind)| (op.to_string(), kind.to_string()))
+    );
+}
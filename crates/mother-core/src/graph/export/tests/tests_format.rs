@@ -0,0 +1,55 @@
+//! Tests for export format parsing
+
+use std::path::Path;
+
+use crate::graph::export::ExportFormat;
+
+#[test]
+fn test_format_from_str() {
+    assert_eq!("graphml".parse(), Ok(ExportFormat::GraphMl));
+    assert_eq!("DOT".parse(), Ok(ExportFormat::Dot));
+    assert_eq!("jsonl".parse(), Ok(ExportFormat::JsonLines));
+    assert_eq!("LSIF".parse(), Ok(ExportFormat::Lsif));
+    assert_eq!("scip".parse(), Ok(ExportFormat::Scip));
+    assert_eq!("chunks".parse(), Ok(ExportFormat::Chunks));
+    assert!("csv".parse::<ExportFormat>().is_err());
+}
+
+#[test]
+fn test_format_display_round_trips() {
+    for format in [
+        ExportFormat::GraphMl,
+        ExportFormat::Dot,
+        ExportFormat::JsonLines,
+        ExportFormat::Lsif,
+        ExportFormat::Scip,
+        ExportFormat::Chunks,
+    ] {
+        assert_eq!(format.to_string().parse(), Ok(format));
+    }
+}
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(
+        ExportFormat::from_path(Path::new("out/graph.graphml")),
+        Some(ExportFormat::GraphMl)
+    );
+    assert_eq!(
+        ExportFormat::from_path(Path::new("graph.gv")),
+        Some(ExportFormat::Dot)
+    );
+    assert_eq!(
+        ExportFormat::from_path(Path::new("graph.ndjson")),
+        Some(ExportFormat::JsonLines)
+    );
+    assert_eq!(
+        ExportFormat::from_path(Path::new("dump.lsif")),
+        Some(ExportFormat::Lsif)
+    );
+    assert_eq!(
+        ExportFormat::from_path(Path::new("index.scip")),
+        Some(ExportFormat::Scip)
+    );
+    assert_eq!(ExportFormat::from_path(Path::new("graph")), None);
+}
@@ -0,0 +1,243 @@
+//! Tests for the LSIF and SCIP writers
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Value};
+
+use crate::graph::export::{write_graph, ExportEdge, ExportFormat, ExportNode, GraphExport};
+use crate::graph::model::EdgeKind;
+use crate::import::read_lsif;
+
+const LIB: &str =
+    "pub struct Config {\n    pub name: String,\n}\n\npub fn load() -> Config {\n    todo!()\n}\n";
+const MAIN: &str = "fn main() {\n    let config = lib::load();\n}\n";
+
+fn node(id: &str, label: &str, properties: Value) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties: serde_json::from_value(properties).unwrap(),
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, properties: Value) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: serde_json::from_value(properties).unwrap(),
+    }
+}
+
+fn symbol(id: &str, name: &str, kind: &str, file: &str, lines: (u32, u32)) -> ExportNode {
+    node(
+        id,
+        "Symbol",
+        json!({
+            "name": name,
+            "qualified_name": format!("lib::{name}"),
+            "kind": kind,
+            "file_path": file,
+            "signature": format!("{kind} {name}"),
+            "start_line": lines.0,
+            "end_line": lines.1,
+        }),
+    )
+}
+
+/// `lib.rs` defines `Config` and `load`, which `main` in `main.rs` calls
+fn sample_graph(root: &str) -> GraphExport {
+    let lib = format!("{root}/src/lib.rs");
+    let main = format!("{root}/src/main.rs");
+    GraphExport {
+        nodes: vec![
+            node(
+                "file:lib",
+                "File",
+                json!({ "path": lib, "language": "rust" }),
+            ),
+            node(
+                "file:main",
+                "File",
+                json!({ "path": main, "language": "rust" }),
+            ),
+            symbol("sym:config", "Config", "struct", &lib, (1, 3)),
+            symbol("sym:load", "load", "function", &lib, (5, 7)),
+            symbol("sym:main", "main", "function", &main, (1, 3)),
+        ],
+        edges: vec![
+            edge("sym:config", "file:lib", "DEFINED_IN", json!({})),
+            edge("sym:load", "file:lib", "DEFINED_IN", json!({})),
+            edge("sym:main", "file:main", "DEFINED_IN", json!({})),
+            edge(
+                "sym:main",
+                "sym:load",
+                "CALLS",
+                json!({ "line": 1, "column": 22 }),
+            ),
+            edge("file:main", "file:lib", "IMPORTS", json!({})),
+        ],
+    }
+}
+
+fn render(graph: &GraphExport, format: ExportFormat) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_graph(graph, format, &mut out).unwrap();
+    out
+}
+
+fn lsif_lines(graph: &GraphExport) -> Vec<Value> {
+    String::from_utf8(render(graph, ExportFormat::Lsif))
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+// ============================================================================
+// LSIF
+// ============================================================================
+
+#[test]
+fn test_lsif_starts_with_metadata() {
+    let lines = lsif_lines(&sample_graph("/repo"));
+    assert_eq!(lines[0]["label"], "metaData");
+    assert_eq!(lines[0]["projectRoot"], "file:///repo/src");
+    assert_eq!(lines[0]["toolInfo"]["name"], "mother");
+}
+
+#[test]
+fn test_lsif_edges_only_use_earlier_vertices() {
+    let lines = lsif_lines(&sample_graph("/repo"));
+    let mut seen = HashSet::new();
+    for line in &lines {
+        if line["type"] == "edge" {
+            let ins = line["inVs"]
+                .as_array()
+                .cloned()
+                .unwrap_or_else(|| vec![line["inV"].clone()]);
+            assert!(seen.contains(&line["outV"]), "{line}");
+            assert!(ins.iter().all(|v| seen.contains(v)), "{line}");
+        } else {
+            seen.insert(line["id"].clone());
+        }
+    }
+}
+
+#[test]
+fn test_lsif_tags_definitions_and_references() {
+    let lines = lsif_lines(&sample_graph("/repo"));
+    let ranges: Vec<&Value> = lines.iter().filter(|l| l["label"] == "range").collect();
+    assert_eq!(ranges.len(), 4);
+
+    let load = ranges.iter().find(|r| r["tag"]["text"] == "load").unwrap();
+    assert_eq!(load["tag"]["type"], "definition");
+    assert_eq!(load["tag"]["kind"], 12);
+    assert_eq!(load["tag"]["detail"], "function load");
+    assert_eq!(load["start"], json!({ "line": 4, "character": 0 }));
+    assert_eq!(load["tag"]["fullRange"]["end"]["line"], 6);
+
+    let call = ranges
+        .iter()
+        .find(|r| r["tag"]["type"] == "reference")
+        .unwrap();
+    assert_eq!(call["start"], json!({ "line": 1, "character": 22 }));
+    assert_eq!(call["end"], json!({ "line": 1, "character": 26 }));
+    assert_eq!(
+        lines.iter().filter(|l| l["label"] == "hoverResult").count(),
+        3
+    );
+}
+
+#[test]
+fn test_lsif_round_trips_through_import() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap().to_string();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), LIB).unwrap();
+    std::fs::write(dir.path().join("src/main.rs"), MAIN).unwrap();
+
+    let dump = render(&sample_graph(&root), ExportFormat::Lsif);
+    let import = read_lsif(dump.as_slice()).unwrap();
+    assert!(import.skipped.is_empty());
+
+    let symbols: BTreeMap<&str, (u32, u32)> = import
+        .documents
+        .iter()
+        .flat_map(|d| &d.symbols)
+        .map(|s| (s.name.as_str(), (s.start_line, s.end_line)))
+        .collect();
+    assert_eq!(
+        symbols,
+        BTreeMap::from([("Config", (1, 3)), ("load", (5, 7)), ("main", (1, 3))])
+    );
+
+    let name = |id: &str| {
+        import
+            .documents
+            .iter()
+            .flat_map(|d| &d.symbols)
+            .find(|s| s.id == id)
+            .map(|s| s.name.as_str())
+    };
+    let references: Vec<_> = import
+        .edges
+        .iter()
+        .map(|e| {
+            (
+                name(&e.source_id),
+                name(&e.target_id),
+                e.kind,
+                e.line,
+                e.column,
+            )
+        })
+        .collect();
+    assert_eq!(
+        references,
+        vec![(
+            Some("main"),
+            Some("load"),
+            EdgeKind::References,
+            Some(1),
+            Some(22)
+        )]
+    );
+}
+
+// ============================================================================
+// SCIP
+// ============================================================================
+
+#[test]
+fn test_scip_starts_with_metadata() {
+    let index = render(&sample_graph("/repo"), ExportFormat::Scip);
+    assert_eq!(index[0], 0x0a);
+    assert!(contains(&index, "file:///repo/src"));
+    assert!(contains(&index, "mother"));
+}
+
+#[test]
+fn test_scip_symbols_follow_paths_and_qualified_names() {
+    let index = render(&sample_graph("/repo"), ExportFormat::Scip);
+    assert!(contains(&index, "mother . . . `lib.rs`/lib#Config#"));
+    assert!(contains(&index, "mother . . . `lib.rs`/lib#load()."));
+    assert!(contains(&index, "mother . . . `main.rs`/lib#main()."));
+    assert!(contains(&index, "```rust\nfunction load\n```"));
+}
+
+#[test]
+fn test_scip_encodes_reference_ranges() {
+    let index = render(&sample_graph("/repo"), ExportFormat::Scip);
+    // Occurrence range [1, 22, 26] as a packed field, then its symbol
+    let reference = b"\x0a\x03\x01\x16\x1a\x12\x21mother . . . `lib.rs`/lib#load().";
+    assert!(index.windows(reference.len()).any(|w| w == reference));
+}
@@ -0,0 +1,198 @@
+//! Tests for GraphML, DOT and JSON lines writers
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::export::{
+    read_jsonl, write_graph, ExportEdge, ExportFormat, ExportNode, ExportRecord, GraphExport,
+    RecordWriter,
+};
+
+fn props(pairs: &[(&str, Value)]) -> BTreeMap<String, Value> {
+    pairs
+        .iter()
+        .map(|(k, v)| ((*k).to_string(), v.clone()))
+        .collect()
+}
+
+fn sample_graph() -> GraphExport {
+    GraphExport {
+        nodes: vec![
+            ExportNode {
+                id: "file:abc".to_string(),
+                label: "File".to_string(),
+                properties: props(&[("path", json!("src/lib.rs"))]),
+            },
+            ExportNode {
+                id: "sym:1".to_string(),
+                label: "Symbol".to_string(),
+                properties: props(&[("name", json!("Vec<T>")), ("start_line", json!(3))]),
+            },
+        ],
+        edges: vec![ExportEdge {
+            source: "sym:1".to_string(),
+            target: "file:abc".to_string(),
+            kind: "DEFINED_IN".to_string(),
+            properties: BTreeMap::new(),
+        }],
+    }
+}
+
+fn render(format: ExportFormat) -> String {
+    let mut out = Vec::new();
+    write_graph(&sample_graph(), format, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// ============================================================================
+// GraphML
+// ============================================================================
+
+#[test]
+fn test_graphml_declares_typed_keys() {
+    let xml = render(ExportFormat::GraphMl);
+    assert!(xml.contains(
+        r#"<key id="n_start_line" for="node" attr.name="start_line" attr.type="long"/>"#
+    ));
+    assert!(xml.contains(r#"<key id="n_path" for="node" attr.name="path" attr.type="string"/>"#));
+}
+
+#[test]
+fn test_graphml_escapes_text() {
+    let xml = render(ExportFormat::GraphMl);
+    assert!(xml.contains(r#"<data key="n_name">Vec&lt;T&gt;</data>"#));
+    assert!(!xml.contains("Vec<T>"));
+}
+
+#[test]
+fn test_graphml_contains_nodes_and_edges() {
+    let xml = render(ExportFormat::GraphMl);
+    assert!(xml.contains(r#"<node id="file:abc">"#));
+    assert!(xml.contains(r#"<edge source="sym:1" target="file:abc">"#));
+    assert!(xml.contains(r#"<data key="e_kind">DEFINED_IN</data>"#));
+    assert!(xml.trim_end().ends_with("</graphml>"));
+}
+
+// ============================================================================
+// DOT
+// ============================================================================
+
+#[test]
+fn test_dot_uses_display_names_as_labels() {
+    let dot = render(ExportFormat::Dot);
+    assert!(dot.starts_with("digraph mother {"));
+    assert!(dot.contains(r#""file:abc" [label="src/lib.rs", shape=folder];"#));
+    assert!(dot.contains(r#""sym:1" [label="Vec<T>", shape=box];"#));
+    assert!(dot.contains(r#""sym:1" -> "file:abc" [label="DEFINED_IN"];"#));
+}
+
+#[test]
+fn test_dot_escapes_quotes() {
+    let mut graph = GraphExport::default();
+    graph.nodes.push(ExportNode {
+        id: "a\"b".to_string(),
+        label: "Symbol".to_string(),
+        properties: BTreeMap::new(),
+    });
+    let mut out = Vec::new();
+    write_graph(&graph, ExportFormat::Dot, &mut out).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains(r#""a\"b""#));
+}
+
+// ============================================================================
+// JSON lines
+// ============================================================================
+
+#[test]
+fn test_jsonl_one_object_per_line() {
+    let text = render(ExportFormat::JsonLines);
+    let lines: Vec<Value> = text
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["type"], "node");
+    assert_eq!(lines[0]["label"], "File");
+    assert_eq!(lines[1]["properties"]["start_line"], 3);
+    assert_eq!(lines[2]["type"], "edge");
+    assert_eq!(lines[2]["kind"], "DEFINED_IN");
+}
+
+#[test]
+fn test_jsonl_round_trip() {
+    let text = render(ExportFormat::JsonLines);
+    let graph = read_jsonl(text.as_bytes()).unwrap();
+    assert_eq!(graph, sample_graph());
+}
+
+#[test]
+fn test_jsonl_record_writer_matches_whole_graph() {
+    let graph = sample_graph();
+    let records = graph
+        .nodes
+        .iter()
+        .cloned()
+        .map(ExportRecord::Node)
+        .chain(graph.edges.iter().cloned().map(ExportRecord::Edge));
+    let mut writer = RecordWriter::new(ExportFormat::JsonLines).unwrap();
+    let mut out = Vec::new();
+    for record in records {
+        writer.write(&record, &mut out).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        render(ExportFormat::JsonLines)
+    );
+}
+
+#[test]
+fn test_record_writer_only_for_streamable_formats() {
+    assert!(RecordWriter::new(ExportFormat::Chunks).is_some());
+    for format in [
+        ExportFormat::GraphMl,
+        ExportFormat::Dot,
+        ExportFormat::Lsif,
+        ExportFormat::Scip,
+    ] {
+        assert!(RecordWriter::new(format).is_none());
+    }
+}
+
+#[test]
+fn test_read_jsonl_skips_blank_lines() {
+    let text = format!("\n{}\n\n", render(ExportFormat::JsonLines));
+    let graph = read_jsonl(text.as_bytes()).unwrap();
+    assert_eq!(graph.nodes.len(), 2);
+    assert_eq!(graph.edges.len(), 1);
+}
+
+#[test]
+fn test_read_jsonl_reports_bad_line() {
+    let err = read_jsonl("{\"type\":\"node\"}\n".as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().starts_with("line 1:"));
+}
+
+#[test]
+fn test_empty_graph_writes_valid_documents() {
+    let graph = GraphExport::default();
+    for format in [
+        ExportFormat::GraphMl,
+        ExportFormat::Dot,
+        ExportFormat::JsonLines,
+        ExportFormat::Lsif,
+        ExportFormat::Scip,
+    ] {
+        let mut out = Vec::new();
+        write_graph(&graph, format, &mut out).unwrap();
+        if format == ExportFormat::JsonLines {
+            assert!(out.is_empty());
+        } else {
+            assert!(!out.is_empty());
+        }
+    }
+}
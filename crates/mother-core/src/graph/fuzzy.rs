@@ -0,0 +1,114 @@
+//! Fuzzy: Typo- and case-tolerant symbol name matching
+//!
+//! Scores candidate symbols on the client, so it works the same on every
+//! backend. Names are compared after lowercasing and dropping everything but
+//! letters and digits, so `crateScnRun` is close to `create_scan_run`. The
+//! score of a name is the best of its edit-distance similarity, its trigram
+//! overlap and, if it contains the query, the share of it the query covers.
+
+use std::collections::HashSet;
+
+use super::model::SymbolNode;
+use super::queries::SymbolMatch;
+
+/// Lowest score a symbol needs to be a match
+pub const MIN_SIMILARITY: f64 = 0.6;
+
+/// Lowercase letters and digits of a name, without separators
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two character sequences
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Trigrams of a sequence padded with two leading and one trailing space
+fn trigrams(chars: &[char]) -> HashSet<[char; 3]> {
+    let padded: Vec<char> = [' ', ' ']
+        .into_iter()
+        .chain(chars.iter().copied())
+        .chain([' '])
+        .collect();
+    padded.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Ratio of two lengths as a score between 0 and 1
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// Similarity of a name to a query, from 0 (nothing alike) to 1 (equal
+/// after normalizing)
+#[must_use]
+pub fn similarity(query: &str, name: &str) -> f64 {
+    let query = normalize(query);
+    let name = normalize(name);
+    if query.is_empty() || name.is_empty() {
+        return 0.0;
+    }
+
+    let longest = query.len().max(name.len());
+    let edits = 1.0 - ratio(edit_distance(&query, &name), longest);
+
+    let (a, b) = (trigrams(&query), trigrams(&name));
+    let shared = a.intersection(&b).count();
+    let overlap = ratio(2 * shared, a.len() + b.len());
+
+    let contained = if name.windows(query.len()).any(|w| w == query.as_slice()) {
+        0.5 + 0.5 * ratio(query.len(), name.len())
+    } else {
+        0.0
+    };
+
+    edits.max(overlap).max(contained)
+}
+
+/// Symbols whose name or qualified name scores at least
+/// [`MIN_SIMILARITY`] against the query, best first, then by name, file
+/// and line
+#[must_use]
+pub fn fuzzy_matches(symbols: &[SymbolNode], query: &str) -> Vec<SymbolMatch> {
+    let mut matches: Vec<SymbolMatch> = symbols
+        .iter()
+        .filter_map(|s| {
+            let score = similarity(query, &s.name).max(similarity(query, &s.qualified_name));
+            (score >= MIN_SIMILARITY).then(|| SymbolMatch {
+                id: s.id.clone(),
+                name: s.name.clone(),
+                qualified_name: s.qualified_name.clone(),
+                kind: s.kind.to_string(),
+                file_path: s.file_path.clone(),
+                start_line: i64::from(s.start_line),
+                end_line: i64::from(s.end_line),
+                score,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then_with(|| a.start_line.cmp(&b.start_line))
+    });
+    matches
+}
@@ -0,0 +1,161 @@
+//! Symbol IDs: How a scan names the symbol versions it stores
+//!
+//! Scans ask a [`SymbolIdStrategy`] for the ID of each symbol of a new file.
+//! [`ContentHashIds`], the default, hashes the file content with the
+//! symbol's identity. [`QualifiedNameIds`] spells out the file path relative
+//! to the scanned root, the kind and the qualified name, so IDs are readable
+//! and the same on every machine that scans the same content, wherever the
+//! repository is checked out. [`UuidIds`] gives random IDs for consumers that
+//! want them opaque. [`SymbolIdScheme`] picks one by name at scan time.
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::model::{symbol_version_id, SymbolNode};
+
+/// Length of the content hash prefix in [`QualifiedNameIds`]
+const HASH_PREFIX_LEN: usize = 12;
+
+/// Derives the ID of a symbol version
+///
+/// Each version of a file's content stores its own symbol nodes, so IDs must
+/// differ between contents; symbols sharing an identity key in one file are
+/// told apart by `ordinal`, their position among them.
+pub trait SymbolIdStrategy: Send + Sync {
+    /// Name of the strategy, as taken by [`SymbolIdScheme`]
+    fn name(&self) -> &str;
+
+    /// ID of `symbol`, defined in the file at `path` (relative to the
+    /// scanned root) whose content hashes to `content_hash`
+    fn symbol_id(
+        &self,
+        symbol: &SymbolNode,
+        path: &str,
+        content_hash: &str,
+        ordinal: usize,
+    ) -> String;
+}
+
+impl std::fmt::Debug for dyn SymbolIdStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SymbolIdStrategy")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Digests of the file content and the symbol's identity key
+///
+/// Rescanning the same content yields the same IDs. The identity key holds
+/// the file path as scanned, so checkouts at different paths get different
+/// IDs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContentHashIds;
+
+impl SymbolIdStrategy for ContentHashIds {
+    fn name(&self) -> &str {
+        "content-hash"
+    }
+
+    fn symbol_id(
+        &self,
+        symbol: &SymbolNode,
+        _path: &str,
+        content_hash: &str,
+        ordinal: usize,
+    ) -> String {
+        symbol_version_id(content_hash, &symbol.identity_key(), ordinal)
+    }
+}
+
+/// Readable IDs: `path#kind#qualified_name@hash`
+///
+/// `path` is relative to the scanned root and `hash` a prefix of the file's
+/// content hash. The second and later symbols sharing a name and kind in a
+/// file get their ordinal appended to the name, as in `#1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QualifiedNameIds;
+
+impl SymbolIdStrategy for QualifiedNameIds {
+    fn name(&self) -> &str {
+        "qualified-name"
+    }
+
+    fn symbol_id(
+        &self,
+        symbol: &SymbolNode,
+        path: &str,
+        content_hash: &str,
+        ordinal: usize,
+    ) -> String {
+        let mut id = format!("{path}#{}#{}", symbol.kind, symbol.qualified_name);
+        if ordinal > 0 {
+            id.push_str(&format!("#{ordinal}"));
+        }
+        let hash = content_hash.get(..HASH_PREFIX_LEN).unwrap_or(content_hash);
+        format!("{id}@{hash}")
+    }
+}
+
+/// Random version 4 UUIDs, different on every scan
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidIds;
+
+impl SymbolIdStrategy for UuidIds {
+    fn name(&self) -> &str {
+        "uuid"
+    }
+
+    fn symbol_id(&self, _: &SymbolNode, _: &str, _: &str, _: usize) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// The built-in strategies, by name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymbolIdScheme {
+    #[default]
+    ContentHash,
+    QualifiedName,
+    Uuid,
+}
+
+impl SymbolIdScheme {
+    /// The strategy of this scheme
+    #[must_use]
+    pub fn strategy(self) -> Arc<dyn SymbolIdStrategy> {
+        match self {
+            Self::ContentHash => Arc::new(ContentHashIds),
+            Self::QualifiedName => Arc::new(QualifiedNameIds),
+            Self::Uuid => Arc::new(UuidIds),
+        }
+    }
+}
+
+impl std::str::FromStr for SymbolIdScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "content-hash" => Ok(Self::ContentHash),
+            "qualified-name" => Ok(Self::QualifiedName),
+            "uuid" => Ok(Self::Uuid),
+            _ => Err(format!(
+                "Unknown symbol ID scheme: {s} (expected content-hash, qualified-name or uuid)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SymbolIdScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ContentHash => write!(f, "content-hash"),
+            Self::QualifiedName => write!(f, "qualified-name"),
+            Self::Uuid => write!(f, "uuid"),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Integrity: Problems in a stored graph that ingestion should never produce
+//!
+//! Each [`GraphStore`](super::GraphStore) looks for these problems with its
+//! own queries. Dangling edges and files no commit contains can be repaired
+//! by deleting them; inverted line ranges and duplicate symbol IDs need a
+//! rescan of the affected commits.
+
+use serde::Serialize;
+
+/// Kind of integrity problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    /// A reference or call edge whose source or target symbol is missing
+    DanglingEdge,
+    /// A file no commit contains
+    OrphanFile,
+    /// A symbol that ends before it starts
+    InvertedRange,
+    /// A symbol ID shared by several symbols
+    DuplicateSymbolId,
+}
+
+impl IntegrityCheck {
+    /// Every check, in report order
+    pub const ALL: [Self; 4] = [
+        Self::DanglingEdge,
+        Self::OrphanFile,
+        Self::InvertedRange,
+        Self::DuplicateSymbolId,
+    ];
+
+    /// Whether `--fix` can repair problems of this kind by deleting them
+    #[must_use]
+    pub const fn repairable(self) -> bool {
+        matches!(self, Self::DanglingEdge | Self::OrphanFile)
+    }
+}
+
+impl std::fmt::Display for IntegrityCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DanglingEdge => write!(f, "dangling edge"),
+            Self::OrphanFile => write!(f, "orphan file"),
+            Self::InvertedRange => write!(f, "inverted range"),
+            Self::DuplicateSymbolId => write!(f, "duplicate symbol ID"),
+        }
+    }
+}
+
+/// One problem found in the stored graph
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct IntegrityIssue {
+    pub check: IntegrityCheck,
+    /// Edge (`source -[KIND]-> target`), file path, qualified name or symbol
+    /// ID the problem is about
+    pub subject: String,
+    /// What is wrong with the subject
+    pub detail: String,
+}
+
+/// What a repair deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct IntegrityRepair {
+    /// Dangling edges
+    pub edges: usize,
+    /// Files no commit contains
+    pub files: usize,
+    /// Symbols of those files
+    pub symbols: usize,
+}
+
+/// Describe an edge as `source -[KIND]-> target`
+#[must_use]
+pub fn edge_subject(source: &str, kind: &str, target: &str) -> String {
+    format!("{source} -[{kind}]-> {target}")
+}
+
+/// Describe which ends of a dangling edge are missing
+#[must_use]
+pub fn dangling_detail(source_missing: bool, target_missing: bool) -> String {
+    match (source_missing, target_missing) {
+        (true, true) => "source and target symbols missing",
+        (true, false) => "source symbol missing",
+        _ => "target symbol missing",
+    }
+    .to_string()
+}
+
+/// Describe an inverted line range
+#[must_use]
+pub fn range_detail(file_path: &str, start_line: i64, end_line: i64) -> String {
+    format!("{file_path}: ends at line {end_line} before it starts at line {start_line}")
+}
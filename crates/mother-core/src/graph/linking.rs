@@ -0,0 +1,202 @@
+//! Linking: Cross-language links between symbols sharing an identifier
+//!
+//! In polyglot repositories, services in different languages are coupled
+//! through identifiers they share rather than through imports: a route a
+//! Python handler serves and a TypeScript client fetches, a protobuf message
+//! both sides build. [`link_symbols`] finds symbols of different languages
+//! that share such an identifier by the configured [`LinkConventions`]:
+//!
+//! - identical qualified names, compared across the languages' separators
+//!   (`orders.v1.CreateOrder` matches `orders::v1::CreateOrder`). Names of a
+//!   single segment, like `main`, are too common to link;
+//! - patterns matched against the source of each file. The first capture
+//!   group taking part in a match, or else the whole match, is the shared
+//!   identifier. A match belongs to the innermost symbol around it, or to
+//!   the definition right below it when it annotates one (a decorator).
+//!
+//! Each linked pair gets one LINKS_TO edge, from the symbol with the smaller
+//! ID; the link itself has no direction.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use regex::Regex;
+
+use super::model::{Edge, EdgeKind, SymbolNode};
+use crate::scanner::Language;
+
+/// Most lines between an annotation and the definition it annotates
+const ANNOTATION_LINES: u32 = 3;
+
+/// How symbols of different languages are linked
+#[derive(Debug, Clone, Default)]
+pub struct LinkConventions {
+    /// Link symbols with identical qualified names
+    pub qualified_names: bool,
+    /// Link symbols whose source matches a pattern with the same identifier
+    pub patterns: Vec<LinkPattern>,
+}
+
+/// A named pattern extracting shared identifiers from source code
+#[derive(Debug, Clone)]
+pub struct LinkPattern {
+    /// Identifiers only link symbols matched by the same pattern
+    pub name: String,
+    pub regex: Regex,
+}
+
+impl LinkPattern {
+    /// Compile a pattern
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn new(name: &str, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.to_string(),
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+
+/// Link the symbols of different languages that share an identifier
+///
+/// `source` returns the contents of a file, for the patterns; files it
+/// returns `None` for are not matched. Symbols of files in no known
+/// language are never linked. Edges are ordered by source and target.
+#[must_use]
+pub fn link_symbols(
+    symbols: &[SymbolNode],
+    conventions: &LinkConventions,
+    source: impl Fn(&str) -> Option<String>,
+) -> Vec<Edge> {
+    let mut by_file: BTreeMap<&str, Vec<&SymbolNode>> = BTreeMap::new();
+    for symbol in symbols {
+        by_file.entry(&symbol.file_path).or_default().push(symbol);
+    }
+
+    // Shared identifier to the symbols carrying it, with their language
+    let mut keys: HashMap<String, Vec<(Language, &SymbolNode)>> = HashMap::new();
+    for (file_path, file_symbols) in by_file {
+        let Some(language) = Language::from_path(Path::new(file_path)) else {
+            continue;
+        };
+        if conventions.qualified_names {
+            for symbol in &file_symbols {
+                if let Some(name) = normalized_name(&symbol.qualified_name) {
+                    let key = format!("name:{name}");
+                    keys.entry(key)
+                        .or_default()
+                        .push((language.clone(), symbol));
+                }
+            }
+        }
+        if conventions.patterns.is_empty() {
+            continue;
+        }
+        let Some(text) = source(file_path) else {
+            continue;
+        };
+        for (key, symbol) in pattern_matches(&text, &file_symbols, &conventions.patterns) {
+            keys.entry(key)
+                .or_default()
+                .push((language.clone(), symbol));
+        }
+    }
+
+    let mut pairs: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for carriers in keys.values() {
+        for (i, (language, a)) in carriers.iter().enumerate() {
+            for (other, b) in &carriers[i + 1..] {
+                if language != other && a.id != b.id {
+                    pairs.insert(if a.id < b.id {
+                        (&a.id, &b.id)
+                    } else {
+                        (&b.id, &a.id)
+                    });
+                }
+            }
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|(source_id, target_id)| Edge {
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            kind: EdgeKind::LinksTo,
+            line: None,
+            column: None,
+        })
+        .collect()
+}
+
+/// A qualified name with `.` between its segments, whichever separator the
+/// language uses, or `None` for a single segment
+fn normalized_name(qualified_name: &str) -> Option<String> {
+    let replaced = qualified_name.replace("::", ".");
+    let segments: Vec<&str> = replaced
+        .split(['.', '/', '\\', '#'])
+        .filter(|s| !s.is_empty())
+        .collect();
+    (segments.len() > 1).then(|| segments.join("."))
+}
+
+/// Identifiers the patterns find in a file, each with the symbol it belongs to
+fn pattern_matches<'a>(
+    text: &str,
+    symbols: &[&'a SymbolNode],
+    patterns: &[LinkPattern],
+) -> Vec<(String, &'a SymbolNode)> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| {
+        let index = line_starts.partition_point(|&start| start <= offset);
+        u32::try_from(index).unwrap_or(u32::MAX)
+    };
+
+    let mut matches = Vec::new();
+    for pattern in patterns {
+        for captures in pattern.regex.captures_iter(text) {
+            let Some(whole) = captures.get(0) else {
+                continue;
+            };
+            let identifier = captures
+                .iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .unwrap_or(whole)
+                .as_str();
+            if identifier.is_empty() {
+                continue;
+            }
+            if let Some(symbol) = owner(symbols, line_of(whole.start())) {
+                matches.push((format!("{}:{identifier}", pattern.name), symbol));
+            }
+        }
+    }
+    matches
+}
+
+/// The symbol a match on `line` (1-indexed) belongs to
+///
+/// A definition starting just below the line wins over the symbol around
+/// it, as long as it is nested in that symbol.
+pub(crate) fn owner<'a>(symbols: &[&'a SymbolNode], line: u32) -> Option<&'a SymbolNode> {
+    let span = |s: &SymbolNode| s.end_line.saturating_sub(s.start_line);
+    let around = symbols
+        .iter()
+        .filter(|s| s.start_line <= line && line <= s.end_line)
+        .min_by_key(|s| span(s))
+        .copied();
+    let annotated = symbols
+        .iter()
+        .filter(|s| s.start_line > line && s.start_line <= line + ANNOTATION_LINES)
+        .min_by_key(|s| (s.start_line, std::cmp::Reverse(span(s))))
+        .copied()
+        .filter(|below| {
+            around.is_none_or(|a| a.start_line <= below.start_line && below.end_line <= a.end_line)
+        });
+    annotated.or(around)
+}
@@ -0,0 +1,109 @@
+//! Migrations: Ordered schema changes applied to existing graphs
+//!
+//! Each store records the schema version it was last migrated to: Neo4j in
+//! a single `SchemaVersion` node, SQLite in `PRAGMA user_version`. Stores
+//! apply the [`MIGRATIONS`] newer than their version when they connect or
+//! open, so a graph written by an earlier release picks up new properties
+//! and labels before it is read. A store whose version is newer than this
+//! release knows is refused rather than misread.
+
+use thiserror::Error;
+
+/// A schema change, with the statements each backend runs to apply it
+///
+/// Statements must be safe on an empty store: fresh stores start at
+/// version 0 and run every migration.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    /// Cypher statements for Neo4j
+    pub neo4j: &'static [&'static str],
+    /// SQL statements for SQLite
+    pub sqlite: &'static [&'static str],
+}
+
+/// Migrations shipped with this release, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Link symbols stored before the identity model to SymbolIdentity nodes",
+        neo4j: &[r#"
+            MATCH (s:Symbol)
+            WHERE NOT (s)-[:SNAPSHOT_OF]->(:SymbolIdentity)
+            WITH s, s.file_path + '#' + s.kind + '#' + s.qualified_name AS key
+            SET s.identity_key = key
+            MERGE (i:SymbolIdentity {key: key})
+            ON CREATE SET i.name = s.name,
+                          i.qualified_name = s.qualified_name,
+                          i.kind = s.kind,
+                          i.file_path = s.file_path
+            CREATE (s)-[:SNAPSHOT_OF]->(i)
+            "#],
+        // SQLite stored identities from its first release
+        sqlite: &[],
+    },
+    Migration {
+        version: 2,
+        description: "Mark scan runs recorded before scan statuses as completed",
+        neo4j: &["MATCH (r:ScanRun) WHERE r.status IS NULL SET r.status = 'completed'"],
+        // The status column defaults to completed
+        sqlite: &[],
+    },
+];
+
+/// A store migrated by a newer release than this one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "graph schema version {stored} is newer than version {supported} supported by this release; \
+     upgrade mother to use this graph"
+)]
+pub struct IncompatibleSchema {
+    pub stored: u32,
+    pub supported: u32,
+}
+
+/// Picks the migrations a store still needs
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationRunner {
+    migrations: &'static [Migration],
+}
+
+impl Default for MigrationRunner {
+    fn default() -> Self {
+        Self::new(MIGRATIONS)
+    }
+}
+
+impl MigrationRunner {
+    /// Create a runner over migrations ordered by version
+    #[must_use]
+    pub fn new(migrations: &'static [Migration]) -> Self {
+        Self { migrations }
+    }
+
+    /// Version a store has after every migration ran, 0 without migrations
+    #[must_use]
+    pub fn latest(&self) -> u32 {
+        self.migrations.last().map_or(0, |m| m.version)
+    }
+
+    /// All migrations, oldest first
+    #[must_use]
+    pub fn migrations(&self) -> &'static [Migration] {
+        self.migrations
+    }
+
+    /// Migrations newer than the `stored` version, oldest first
+    ///
+    /// # Errors
+    /// Returns an error if `stored` is newer than [`latest`](Self::latest).
+    pub fn pending(&self, stored: u32) -> Result<&'static [Migration], IncompatibleSchema> {
+        let supported = self.latest();
+        if stored > supported {
+            return Err(IncompatibleSchema { stored, supported });
+        }
+        let applied = self.migrations.partition_point(|m| m.version <= stored);
+        Ok(&self.migrations[applied..])
+    }
+}
@@ -1,15 +1,49 @@
 //! Graph module: Data models and Neo4j storage
 //!
 //! Defines the graph model for storing AST information
-//! and provides the Neo4j client for persistence.
+//! and provides the Neo4j client and embedded SQLite store for
+//! persistence behind the backend-neutral `GraphStore` trait.
 
+pub mod api_surface;
+pub mod backup;
+pub mod churn;
+pub mod complexity;
 pub mod convert;
+pub mod cycles;
+pub mod diff;
+pub mod directories;
+pub mod doc_coverage;
+pub mod export;
+pub mod fuzzy;
+pub mod ids;
+pub mod integrity;
+pub mod linking;
+pub mod migrations;
 pub mod model;
+pub mod modules;
 pub mod neo4j;
+pub mod paths;
 pub mod queries;
+pub mod read_only;
+pub mod reader;
+pub mod scope;
+pub mod sqlite;
+pub mod store;
+pub mod testing;
+pub mod trends;
 
 // Re-export query result types
-pub use queries::{FileResult, GraphStats, ReferenceResult, SymbolResult};
+pub use queries::{
+    parse_assignment, DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats,
+    ImporterResult, Page, ParamValue, QueryParams, RawRow, ReferenceDirection, ReferenceGroup,
+    ReferenceGrouping, ReferenceResult, ScanResult, ScanRunResult, SortKey, SymbolMatch,
+    SymbolResult, SymbolVersionResult, TypeUsage, TypeUsageResult, DEFAULT_LIMIT,
+};
+
+pub use reader::GraphReader;
+pub use scope::PathScope;
+pub use sqlite::SqliteStore;
+pub use store::{GraphStore, QueryLanguage, RowStream, StoreError};
 
 #[cfg(test)]
 mod tests;
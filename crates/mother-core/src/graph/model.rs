@@ -1,7 +1,10 @@
 //! Graph model types
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// Kind of symbol
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -43,6 +46,29 @@ impl std::fmt::Display for SymbolKind {
     }
 }
 
+impl std::str::FromStr for SymbolKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "module" => Ok(Self::Module),
+            "class" => Ok(Self::Class),
+            "struct" => Ok(Self::Struct),
+            "enum" => Ok(Self::Enum),
+            "interface" => Ok(Self::Interface),
+            "trait" => Ok(Self::Trait),
+            "function" => Ok(Self::Function),
+            "method" => Ok(Self::Method),
+            "variable" => Ok(Self::Variable),
+            "constant" => Ok(Self::Constant),
+            "field" => Ok(Self::Field),
+            "type_alias" => Ok(Self::TypeAlias),
+            "import" => Ok(Self::Import),
+            _ => Err(format!("Unknown symbol kind: {s}")),
+        }
+    }
+}
+
 /// A symbol node in the graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolNode {
@@ -66,6 +92,119 @@ pub struct SymbolNode {
     pub signature: Option<String>,
     /// Documentation comment
     pub doc_comment: Option<String>,
+    /// Declaration the language server shows on hover (e.g. `fn parse(s: &str) -> Ast`)
+    #[serde(default)]
+    pub type_signature: Option<String>,
+    /// Type of a value, or return type of a function, taken from the type signature
+    #[serde(default)]
+    pub resolved_type: Option<String>,
+    /// Vector embedding of the symbol's signature and docs, when an
+    /// [`Embedder`](crate::embed::Embedder) ran during the scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+impl SymbolNode {
+    /// Create a symbol spanning `start_line` to `end_line` of `file_path`
+    ///
+    /// The qualified name is `name`; the optional fields start unset and are
+    /// filled in by the enrichers.
+    #[must_use]
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        kind: SymbolKind,
+        file_path: impl Into<String>,
+        start_line: u32,
+        end_line: u32,
+    ) -> Self {
+        let name = name.into();
+        Self {
+            id: id.into(),
+            qualified_name: name.clone(),
+            name,
+            kind,
+            visibility: None,
+            file_path: file_path.into(),
+            start_line,
+            end_line,
+            signature: None,
+            doc_comment: None,
+            type_signature: None,
+            resolved_type: None,
+            embedding: None,
+        }
+    }
+
+    /// Set the declaration the language server shows on hover and the type
+    /// taken from it, if it has one
+    #[must_use]
+    pub fn with_type_signature(
+        mut self,
+        type_signature: impl Into<String>,
+        resolved_type: Option<&str>,
+    ) -> Self {
+        self.type_signature = Some(type_signature.into());
+        self.resolved_type = resolved_type.map(str::to_string);
+        self
+    }
+
+    /// Key of the `SymbolIdentity` this symbol version is a snapshot of
+    ///
+    /// A `Symbol` node is one version of a symbol, tied to a specific file
+    /// content. Versions that share file path, kind and qualified name across
+    /// commits point at the same `SymbolIdentity` node via `SNAPSHOT_OF`.
+    #[must_use]
+    pub fn identity_key(&self) -> String {
+        symbol_identity_key(
+            &self.file_path,
+            &self.kind.to_string(),
+            &self.qualified_name,
+        )
+    }
+}
+
+/// Build a symbol identity key from its components
+///
+/// Kept as a plain concatenation so the same key can be computed in Cypher
+/// (see the first of the [`MIGRATIONS`](super::migrations::MIGRATIONS)).
+#[must_use]
+pub fn symbol_identity_key(file_path: &str, kind: &str, qualified_name: &str) -> String {
+    format!("{file_path}#{kind}#{qualified_name}")
+}
+
+/// Build the ID of one symbol version
+///
+/// The ID is derived from the file content, the symbol's identity key and
+/// its position among symbols with that key in the file (`ordinal`), so
+/// scanning the same content again yields the same IDs.
+#[must_use]
+pub fn symbol_version_id(content_hash: &str, identity_key: &str, ordinal: usize) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content_hash.as_bytes());
+    hasher.update([0]);
+    hasher.update(identity_key.as_bytes());
+    hasher.update([0]);
+    hasher.update(ordinal.to_le_bytes());
+    let mut digest = format!("{:x}", hasher.finalize());
+    digest.truncate(32);
+    digest
+}
+
+/// ID of the placeholder for a definition outside the scanned files
+///
+/// Derived from the definition's location, so every call site resolving to
+/// it shares one node.
+#[must_use]
+pub fn external_symbol_id(file_path: &str, line: u32, column: u32) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(file_path.as_bytes());
+    hasher.update([0]);
+    hasher.update(line.to_le_bytes());
+    hasher.update(column.to_le_bytes());
+    let mut digest = format!("{:x}", hasher.finalize());
+    digest.truncate(32);
+    digest
 }
 
 /// Kind of edge/relationship
@@ -80,6 +219,19 @@ pub enum EdgeKind {
     Contains,
     DefinedIn,
     ScannedIn,
+    SnapshotOf,
+    /// A symbol version to the previous version of the same identity
+    SameAs,
+    /// A renamed symbol version to the version it was renamed from
+    EvolvedFrom,
+    /// A symbol version to the version that renamed it, the reverse of
+    /// `EvolvedFrom`
+    RenamedTo,
+    /// Symbols in different languages that share an identifier by a linking
+    /// convention, like a route or message name
+    LinksTo,
+    /// A test to a symbol it references or calls
+    Tests,
 }
 
 impl std::fmt::Display for EdgeKind {
@@ -93,6 +245,12 @@ impl std::fmt::Display for EdgeKind {
             Self::Contains => "CONTAINS",
             Self::DefinedIn => "DEFINED_IN",
             Self::ScannedIn => "SCANNED_IN",
+            Self::SnapshotOf => "SNAPSHOT_OF",
+            Self::SameAs => "SAME_AS",
+            Self::EvolvedFrom => "EVOLVED_FROM",
+            Self::RenamedTo => "RENAMED_TO",
+            Self::LinksTo => "LINKS_TO",
+            Self::Tests => "TESTS",
         };
         write!(f, "{s}")
     }
@@ -113,6 +271,309 @@ pub struct Edge {
     pub column: Option<u32>,
 }
 
+/// Size measures of one file's content
+///
+/// Stored on the File node, so they are shared by every commit containing
+/// the content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    /// Lines, counting a last line without a newline
+    pub line_count: usize,
+    pub byte_size: usize,
+    /// Symbols by kind, such as `function` or `struct`
+    pub symbol_counts: BTreeMap<String, usize>,
+}
+
+impl FileMetrics {
+    /// Line count and byte size of file content, with no symbols counted yet
+    #[must_use]
+    pub fn of_content(content: &str) -> Self {
+        let newlines = content.bytes().filter(|b| *b == b'\n').count();
+        Self {
+            line_count: newlines + usize::from(!content.is_empty() && !content.ends_with('\n')),
+            byte_size: content.len(),
+            symbol_counts: BTreeMap::new(),
+        }
+    }
+
+    /// Add `symbols` to the counts by kind
+    pub fn count_symbols(&mut self, symbols: &[SymbolNode]) {
+        for symbol in symbols {
+            *self
+                .symbol_counts
+                .entry(symbol.kind.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Symbols of all kinds
+    #[must_use]
+    pub fn symbol_count(&self) -> usize {
+        self.symbol_counts.values().sum()
+    }
+}
+
+/// A new file with what a scan extracted from it, stored together by
+/// [`GraphStore::write_file`](super::GraphStore::write_file)
+#[derive(Debug, Clone, Default)]
+pub struct FileWrite {
+    pub path: String,
+    pub content_hash: String,
+    pub language: String,
+    /// Commit the file is linked to
+    pub commit_sha: String,
+    /// Size measures, left unset when `None`
+    pub metrics: Option<FileMetrics>,
+    pub symbols: Vec<SymbolNode>,
+    /// Parameters and return types of the symbols
+    pub signatures: Vec<FunctionSignature>,
+    /// Edges from the symbols, such as lineage edges to earlier versions
+    pub edges: Vec<Edge>,
+}
+
+/// A resolved import between two files of the same commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportEdge {
+    /// Path of the importing file
+    pub from_path: String,
+    /// Path of the imported file
+    pub to_path: String,
+    /// Module path as written in the import statement
+    pub module: String,
+    /// Line of the import statement (1-indexed)
+    pub line: u32,
+}
+
+/// Aggregate view of one directory of a commit
+///
+/// Stats cover every scanned file in the directory and below; `files` lists
+/// only the files directly in it. Directories form a tree through their
+/// parents, rooted at the scan root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectorySummary {
+    /// Directory path
+    pub path: String,
+    /// Path of the enclosing directory, `None` for the scan root
+    pub parent: Option<String>,
+    /// Scanned files in the directory and its subdirectories
+    pub file_count: usize,
+    /// Lines in those files
+    pub line_count: usize,
+    /// Languages of those files, sorted
+    pub languages: Vec<String>,
+    /// First paragraph of the directory's README, if it has one
+    pub readme: Option<String>,
+    /// Paths of the scanned files directly in the directory
+    pub files: Vec<String>,
+}
+
+/// A module or namespace of a commit
+///
+/// Derived from the paths of the commit's files and the module symbols
+/// inside them (see [`crate::graph::modules`]). Module nodes form a tree
+/// through their parents and contain the symbols defined directly in them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleNode {
+    /// Module path, joined with the language's separator (`graph::queries::read`)
+    pub path: String,
+    /// Last segment of the path
+    pub name: String,
+    /// Path of the enclosing module; `None` for top-level modules
+    pub parent: Option<String>,
+    /// Language of the module's files
+    pub language: String,
+    /// IDs of the symbols directly in the module, sorted
+    pub symbol_ids: Vec<String>,
+}
+
+/// An endpoint or message declared in an API contract of a commit
+///
+/// Read from the OpenAPI specs and `.proto` files of the scan (see
+/// [`crate::import::contracts`]) and linked to the symbols serving it with
+/// HANDLED_BY edges. The line is 1-based like symbol lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractNode {
+    /// `endpoint` for OpenAPI operations and rpcs, `message` for schemas and
+    /// protobuf messages
+    pub kind: String,
+    /// `GET /orders/{id}`, `orders.v1.Orders/CreateOrder` or
+    /// `orders.v1.Order`
+    pub name: String,
+    /// Name code refers to the contract by: the operationId (else the
+    /// route), rpc method or message name
+    pub identifier: String,
+    pub file_path: String,
+    pub line: u32,
+    /// IDs of the symbols serving the contract, sorted
+    pub handler_ids: Vec<String>,
+}
+
+/// A scanned repository with the packages its manifests declare
+///
+/// Read from the `Cargo.toml`, `package.json` and `go.mod` files of the
+/// scan (see [`crate::import::manifests`]). One node per repository path,
+/// replaced by each scan of it. Package names carry their ecosystem, such
+/// as `cargo:serde`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryNode {
+    /// Absolute path of the scan root
+    pub path: String,
+    /// Packages the repository defines, sorted
+    pub packages: Vec<String>,
+    /// Packages the repository depends on and does not define, sorted
+    pub dependencies: Vec<String>,
+}
+
+/// A DEPENDS_ON edge between two repositories
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryDependency {
+    /// Path of the depending repository
+    pub from: String,
+    /// Path of the repository defining the packages
+    pub to: String,
+    /// Packages of `to` that `from` depends on, sorted
+    pub packages: Vec<String>,
+}
+
+/// Line and branch coverage of a symbol from a coverage report
+///
+/// Counted over the lines of the symbol's range (see
+/// [`crate::import::coverage`]). Stored on the Symbol node, so it is shared
+/// by every commit containing the content, like complexity.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolCoverage {
+    pub symbol_id: String,
+    /// Instrumented lines hit at least once
+    pub lines_covered: u32,
+    /// Instrumented lines
+    pub lines_total: u32,
+    pub branches_covered: u32,
+    pub branches_total: u32,
+}
+
+impl SymbolCoverage {
+    /// Percentage of instrumented lines covered, if the range has any
+    #[must_use]
+    pub fn line_percent(&self) -> Option<f64> {
+        percent(self.lines_covered, self.lines_total)
+    }
+
+    /// Percentage of branches covered, if the range has any
+    #[must_use]
+    pub fn branch_percent(&self) -> Option<f64> {
+        percent(self.branches_covered, self.branches_total)
+    }
+}
+
+fn percent(covered: u32, total: u32) -> Option<f64> {
+    (total > 0).then(|| f64::from(covered) * 100.0 / f64::from(total))
+}
+
+/// The typed interface of a function or method
+///
+/// Parsed from the symbol's hover type signature. Stored as Parameter nodes
+/// under the symbol and a RETURNS edge to the Type node of its return type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionSignature {
+    /// ID of the function or method symbol
+    pub symbol_id: String,
+    /// Parameters in declaration order, receivers excluded
+    pub parameters: Vec<ParameterNode>,
+    /// Declared return type, if any
+    pub return_type: Option<String>,
+}
+
+/// A parameter of a function or method
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterNode {
+    pub name: String,
+    /// Type as written in the signature, such as `&Neo4jClient`
+    pub type_name: Option<String>,
+    /// 0-based position in the parameter list
+    pub position: u32,
+}
+
+/// A problem a language server reported in a file
+///
+/// Stored against the file's content and, when the diagnostic starts inside
+/// a symbol's definition, the innermost such symbol. Lines are 1-based like
+/// symbol lines; columns are 0-based, as reported by the server.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticNode {
+    /// `error`, `warning`, `information` or `hint`
+    pub severity: String,
+    /// Diagnostic code, such as `E0308` or `unused_variables`
+    pub code: Option<String>,
+    /// Tool that produced the diagnostic, such as `rustc` or `Pyright`
+    pub source: Option<String>,
+    pub message: String,
+    pub start_line: u32,
+    pub start_col: u32,
+    pub end_line: u32,
+    pub end_col: u32,
+    /// Innermost symbol whose definition contains the start of the range
+    pub symbol_id: Option<String>,
+}
+
+/// Placeholder for a definition outside the scanned files
+///
+/// Created when a call site in a scanned symbol resolves, through
+/// go-to-definition, to code that was not scanned: the standard library, a
+/// dependency or an excluded file. The line is 1-based like symbol lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalSymbolNode {
+    /// See [`external_symbol_id`]
+    pub id: String,
+    /// Name used at the call site
+    pub name: String,
+    pub file_path: String,
+    pub line: u32,
+    /// `std`, `dependency` or `other`, guessed from the file path
+    pub origin: String,
+}
+
+/// Progress of a scan run
+///
+/// Only the commits of completed runs are picked as the latest scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    /// Files are still being written, or the scan died before finishing
+    InProgress,
+    #[default]
+    Completed,
+    /// A phase failed; the run's data may be partial
+    Failed,
+    /// The scan ran out of time; the run's data covers part of the commit
+    Partial,
+}
+
+impl std::fmt::Display for ScanStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Partial => "partial",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for ScanStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "in_progress" => Ok(Self::InProgress),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            "partial" => Ok(Self::Partial),
+            _ => Err(format!("Unknown scan status: {s}")),
+        }
+    }
+}
+
 /// A scan run representing a versioned snapshot of a repository scan
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanRun {
@@ -128,4 +589,118 @@ pub struct ScanRun {
     pub scanned_at: DateTime<Utc>,
     /// User-provided version tag
     pub version: Option<String>,
+    /// ID of the run scanned before this one by a history backfill
+    #[serde(default)]
+    pub previous_run: Option<String>,
+    /// Status the run is recorded with
+    #[serde(default)]
+    pub status: ScanStatus,
+}
+
+/// One file or symbol a scan phase failed on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanFailure {
+    /// Phase that failed, such as `Phase 2`
+    pub phase: String,
+    /// Path of the file being processed
+    pub file: String,
+    /// ID of the symbol being processed, for per-symbol failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// LSP request or step that failed, such as `textDocument/references`
+    pub request: String,
+    /// Error message with its causes
+    pub message: String,
+}
+
+/// What failed during a scan, stored with its scan run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub scan_run_id: String,
+    pub commit_sha: String,
+    /// Failures in the order they happened
+    pub failures: Vec<ScanFailure>,
+    /// Percentage of the scan done before its time budget ran out; `None`
+    /// for scans that ran to the end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion: Option<u8>,
+    /// How often the scan's phases found a file's content already read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_cache: Option<FileCacheStats>,
+    /// Discovered files left out for their content, in discovery order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// A discovered file a scan left out instead of opening it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    /// Path of the file
+    pub file: String,
+    #[serde(flatten)]
+    pub reason: SkipReason,
+}
+
+/// Why a scan left a file out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The content has NUL bytes
+    Binary,
+    /// The file is larger than the size limit, both in bytes
+    TooLarge { size: u64, limit: u64 },
+    /// A header line carries a generated-file marker
+    Generated { marker: String },
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Binary => write!(f, "binary content"),
+            Self::TooLarge { size, limit } => {
+                write!(f, "{size} bytes, over the {limit} byte limit")
+            }
+            Self::Generated { marker } => write!(f, "generated (`{marker}`)"),
+        }
+    }
+}
+
+/// Reads of file contents through a scan's file content cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCacheStats {
+    /// Reads answered from the cache
+    pub hits: u64,
+    /// Reads that went to disk
+    pub misses: u64,
+    /// Bytes read from disk
+    pub bytes_read: u64,
+    /// Files memory-mapped instead of read, for their size
+    pub mapped: u64,
+}
+
+impl FileCacheStats {
+    /// Share of reads answered from the cache, from 0 to 1
+    #[must_use]
+    pub fn hit_rate(&self) -> f64 {
+        let reads = self.hits + self.misses;
+        if reads == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / reads as f64
+    }
+}
+
+impl ScanReport {
+    /// Number of failures per phase, in the order the phases first failed
+    #[must_use]
+    pub fn failures_by_phase(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for failure in &self.failures {
+            match counts.iter_mut().find(|(phase, _)| *phase == failure.phase) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&failure.phase, 1)),
+            }
+        }
+        counts
+    }
 }
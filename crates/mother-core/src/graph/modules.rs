@@ -0,0 +1,180 @@
+//! Modules: Namespace hierarchy of a commit, derived from symbol locations
+//!
+//! A symbol's module is the module its file forms, as the language adapter
+//! derives it from the path relative to the scan root (e.g.
+//! `crates/mother-core/src/graph/queries/read.rs` is
+//! `mother_core::graph::queries::read`), followed by the module symbols of
+//! the file enclosing it (a Rust `mod tests`, a TypeScript namespace). A
+//! module symbol itself belongs to the module around it. Every prefix of a
+//! module path is a module as well, so the tree links up to its top-level
+//! modules.
+//!
+//! [`summarize`] rolls symbol counts up the tree, so questions like "how
+//! many symbols in `graph::queries` did this commit change" need no string
+//! matching on qualified names.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::model::{ModuleNode, SymbolKind, SymbolNode};
+use crate::adapters::adapter_for_path;
+use crate::scanner::Language;
+
+/// Build the module tree of a commit's symbols
+///
+/// `root` is the scan root the module paths of files are relative to.
+/// Symbols of files outside it, or whose file forms no module, belong to
+/// no module. Modules are returned sorted by path.
+#[must_use]
+pub fn module_tree(root: &Path, symbols: &[SymbolNode]) -> Vec<ModuleNode> {
+    let mut by_file: BTreeMap<&str, Vec<&SymbolNode>> = BTreeMap::new();
+    for symbol in symbols {
+        by_file.entry(&symbol.file_path).or_default().push(symbol);
+    }
+
+    let mut modules: BTreeMap<String, ModuleNode> = BTreeMap::new();
+    for (file_path, file_symbols) in by_file {
+        let path = Path::new(file_path);
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let adapter = adapter_for_path(path);
+        let separator = adapter.separator();
+        let file_module = adapter.module_path(relative);
+        if file_module.is_empty() {
+            continue;
+        }
+        let language = Language::from_path(path).map_or_else(String::new, |l| l.to_string());
+        let module_symbols: HashSet<&str> = file_symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Module)
+            .map(|s| s.qualified_name.as_str())
+            .collect();
+
+        for symbol in file_symbols {
+            let segments: Vec<&str> = symbol.qualified_name.split(separator).collect();
+            // The longest proper prefix of the name that is a module symbol
+            let enclosing = (1..segments.len())
+                .rev()
+                .find(|&len| module_symbols.contains(segments[..len].join(separator).as_str()))
+                .unwrap_or(0);
+            let mut module = file_module.clone();
+            module.extend(segments[..enclosing].iter().map(|s| (*s).to_string()));
+            add_module(&mut modules, &module, separator, &language);
+            let path = module.join(separator);
+            if let Some(node) = modules.get_mut(&path) {
+                node.symbol_ids.push(symbol.id.clone());
+            }
+            if symbol.kind == SymbolKind::Module {
+                module.extend(segments[enclosing..].iter().map(|s| (*s).to_string()));
+                add_module(&mut modules, &module, separator, &language);
+            }
+        }
+    }
+
+    modules
+        .into_values()
+        .map(|mut module| {
+            module.symbol_ids.sort();
+            module
+        })
+        .collect()
+}
+
+/// Add a module and every module enclosing it, if not there yet
+fn add_module(
+    modules: &mut BTreeMap<String, ModuleNode>,
+    segments: &[String],
+    separator: &str,
+    language: &str,
+) {
+    for len in 1..=segments.len() {
+        let path = segments[..len].join(separator);
+        modules.entry(path.clone()).or_insert_with(|| ModuleNode {
+            path,
+            name: segments[len - 1].clone(),
+            parent: (len > 1).then(|| segments[..len - 1].join(separator)),
+            language: language.to_string(),
+            symbol_ids: Vec::new(),
+        });
+    }
+}
+
+/// Symbol counts of one module, its own and those of everything below it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleSummary {
+    pub path: String,
+    pub language: String,
+    /// Modules directly inside
+    pub submodules: usize,
+    /// Symbols defined directly in the module
+    pub symbols: usize,
+    /// Symbols in the module and its submodules
+    pub total_symbols: usize,
+    /// Of `total_symbols`, those in the `modified` set
+    pub modified: usize,
+}
+
+/// Summarize modules, rolling counts up to every enclosing module
+///
+/// `modified` holds the IDs of the symbols the commit changed. With a
+/// `prefix`, only that module and the modules below it are returned.
+/// Summaries are sorted by path.
+#[must_use]
+pub fn summarize(
+    modules: &[ModuleNode],
+    modified: &HashSet<&str>,
+    prefix: Option<&str>,
+) -> Vec<ModuleSummary> {
+    let parents: HashMap<&str, &str> = modules
+        .iter()
+        .filter_map(|m| Some((m.path.as_str(), m.parent.as_deref()?)))
+        .collect();
+    let mut summaries: BTreeMap<&str, ModuleSummary> = modules
+        .iter()
+        .map(|m| {
+            let summary = ModuleSummary {
+                path: m.path.clone(),
+                language: m.language.clone(),
+                symbols: m.symbol_ids.len(),
+                ..ModuleSummary::default()
+            };
+            (m.path.as_str(), summary)
+        })
+        .collect();
+
+    for module in modules {
+        let changed = module
+            .symbol_ids
+            .iter()
+            .filter(|id| modified.contains(id.as_str()))
+            .count();
+        if let Some(parent) = module.parent.as_deref() {
+            if let Some(summary) = summaries.get_mut(parent) {
+                summary.submodules += 1;
+            }
+        }
+        let mut current = Some(module.path.as_str());
+        while let Some(path) = current {
+            if let Some(summary) = summaries.get_mut(path) {
+                summary.total_symbols += module.symbol_ids.len();
+                summary.modified += changed;
+            }
+            current = parents.get(path).copied();
+        }
+    }
+
+    summaries
+        .into_values()
+        .filter(|s| prefix.is_none_or(|prefix| within(&s.path, prefix)))
+        .collect()
+}
+
+/// Whether a module path is `prefix` or a module below it, whichever
+/// separator the language uses
+fn within(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::") || rest.starts_with('.'))
+}
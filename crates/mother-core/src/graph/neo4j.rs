@@ -1,9 +1,13 @@
 //! Neo4j client for graph storage
 
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use neo4rs::{ConfigBuilder, Graph, Query};
 use thiserror::Error;
+use tracing::Instrument;
+
+use super::migrations::{IncompatibleSchema, MigrationRunner};
 
 /// Errors that can occur during Neo4j operations
 #[derive(Debug, Error)]
@@ -16,6 +20,12 @@ pub enum Neo4jError {
 
     #[error("Neo4j error: {0}")]
     Neo4j(#[from] neo4rs::Error),
+
+    #[error(transparent)]
+    IncompatibleSchema(#[from] IncompatibleSchema),
+
+    #[error("Refusing to write in read-only mode: {0}")]
+    ReadOnly(String),
 }
 
 /// Configuration for Neo4j connection
@@ -25,6 +35,8 @@ pub struct Neo4jConfig {
     pub user: String,
     pub password: String,
     pub database: Option<String>,
+    /// Refuse writes, see [`read_only`](super::read_only)
+    pub read_only: bool,
 }
 
 impl Neo4jConfig {
@@ -40,6 +52,7 @@ impl Neo4jConfig {
             user: user.into(),
             password: password.into(),
             database: None,
+            read_only: false,
         }
     }
 
@@ -49,18 +62,71 @@ impl Neo4jConfig {
         self.database = Some(database.into());
         self
     }
+
+    /// Connect in read-only mode
+    #[must_use]
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+}
+
+/// A uniqueness constraint on node properties
+struct UniqueConstraint {
+    name: &'static str,
+    /// Index created instead where the constraint cannot be
+    index: &'static str,
+    pattern: &'static str,
+    properties: &'static str,
 }
 
+/// Keys that must stay unique even when a crashed scan is rerun
+const UNIQUE_CONSTRAINTS: [UniqueConstraint; 4] = [
+    UniqueConstraint {
+        name: "commit_sha_unique",
+        index: "commit_sha",
+        pattern: "(c:Commit)",
+        properties: "c.sha",
+    },
+    UniqueConstraint {
+        name: "scan_run_id_unique",
+        index: "scan_run_id",
+        pattern: "(r:ScanRun)",
+        properties: "r.id",
+    },
+    UniqueConstraint {
+        name: "file_path_hash_unique",
+        index: "file_path_hash",
+        pattern: "(f:File)",
+        properties: "(f.path, f.content_hash)",
+    },
+    UniqueConstraint {
+        name: "symbol_id_unique",
+        index: "symbol_id",
+        pattern: "(s:Symbol)",
+        properties: "s.id",
+    },
+];
+
 /// Client for interacting with Neo4j
 pub struct Neo4jClient {
     graph: Arc<Graph>,
+    /// Whether the vector index over symbol embeddings is known to exist
+    pub(super) vector_index: AtomicBool,
+    /// Whether writes are refused, see [`read_only`](super::read_only)
+    pub(super) read_only: bool,
 }
 
 impl Neo4jClient {
-    /// Connect to Neo4j and ensure indexes exist
+    /// Connect to Neo4j, ensure indexes exist and apply pending schema
+    /// migrations
+    ///
+    /// A read-only client leaves indexes and migrations alone and only warns
+    /// about pending migrations.
     ///
     /// # Errors
-    /// Returns an error if the connection fails.
+    /// Returns an error if the connection or a migration fails, or the graph
+    /// was migrated by a newer release.
     pub async fn connect(config: &Neo4jConfig) -> Result<Self, Neo4jError> {
         let mut builder = ConfigBuilder::default()
             .uri(&config.uri)
@@ -78,28 +144,110 @@ impl Neo4jClient {
 
         let client = Self {
             graph: Arc::new(graph),
+            vector_index: AtomicBool::new(false),
+            read_only: config.read_only,
         };
 
+        // Refuse graphs written by a newer release before touching them
+        let pending = MigrationRunner::default().pending(client.schema_version().await?)?;
+        if client.read_only {
+            if !pending.is_empty() {
+                tracing::warn!(
+                    "{} schema migrations pending; run `mother migrate` without --read-only",
+                    pending.len()
+                );
+            }
+            return Ok(client);
+        }
+
         // Ensure indexes exist for performant queries
         client.ensure_indexes().await?;
 
+        client.apply_migrations(pending).await?;
+
         Ok(client)
     }
 
-    /// Create indexes if they don't exist
+    /// Fail with [`Neo4jError::ReadOnly`] naming `operation` in read-only
+    /// mode
+    ///
+    /// # Errors
+    /// Returns an error if the client is read-only.
+    pub fn ensure_writable(&self, operation: &str) -> Result<(), Neo4jError> {
+        if self.read_only {
+            return Err(Neo4jError::ReadOnly(operation.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Create indexes and uniqueness constraints if they don't exist
     async fn ensure_indexes(&self) -> Result<(), Neo4jError> {
         let indexes = [
-            "CREATE INDEX commit_sha IF NOT EXISTS FOR (c:Commit) ON (c.sha)",
-            "CREATE INDEX file_path_hash IF NOT EXISTS FOR (f:File) ON (f.path, f.content_hash)",
             "CREATE INDEX symbol_name IF NOT EXISTS FOR (s:Symbol) ON (s.name)",
-            "CREATE INDEX symbol_id IF NOT EXISTS FOR (s:Symbol) ON (s.id)",
             "CREATE INDEX symbol_file_path IF NOT EXISTS FOR (s:Symbol) ON (s.file_path)",
+            "CREATE INDEX symbol_identity_key IF NOT EXISTS FOR (s:Symbol) ON (s.identity_key)",
+            "CREATE INDEX symbol_resolved_type IF NOT EXISTS FOR (s:Symbol) ON (s.resolved_type)",
+            // Signatures can outgrow range index keys; text indexes also serve CONTAINS
+            "CREATE TEXT INDEX symbol_type_signature IF NOT EXISTS \
+             FOR (s:Symbol) ON (s.type_signature)",
+            // Ranked, fuzzy search over names and docs for `query search`
+            "CREATE FULLTEXT INDEX symbol_search IF NOT EXISTS \
+             FOR (s:Symbol) ON EACH [s.name, s.qualified_name, s.doc_comment]",
+            "CREATE INDEX parameter_symbol IF NOT EXISTS FOR (p:Parameter) ON (p.symbol_id, p.position)",
+            // One Type node per type name, shared by parameters and return types
+            "CREATE CONSTRAINT type_name_unique IF NOT EXISTS FOR (t:Type) REQUIRE t.name IS UNIQUE",
+            // One identity node per (file path, kind, qualified name), shared across commits
+            "CREATE CONSTRAINT symbol_identity_unique IF NOT EXISTS \
+             FOR (i:SymbolIdentity) REQUIRE i.key IS UNIQUE",
+            // One placeholder per definition outside the scanned files
+            "CREATE CONSTRAINT external_symbol_unique IF NOT EXISTS \
+             FOR (x:ExternalSymbol) REQUIRE x.id IS UNIQUE",
         ];
 
         for index_stmt in indexes {
             self.graph.run(Query::new(index_stmt.to_string())).await?;
         }
 
+        for constraint in &UNIQUE_CONSTRAINTS {
+            self.ensure_unique(constraint).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a uniqueness constraint, falling back to a plain index where
+    /// the edition or duplicates already stored rule the constraint out
+    ///
+    /// Graphs from before the constraint have an index on the same
+    /// properties, which must go before the constraint can back them with its
+    /// own.
+    async fn ensure_unique(&self, constraint: &UniqueConstraint) -> Result<(), Neo4jError> {
+        let create = format!(
+            "CREATE CONSTRAINT {} IF NOT EXISTS FOR {} REQUIRE {} IS UNIQUE",
+            constraint.name, constraint.pattern, constraint.properties
+        );
+        if self.graph.run(Query::new(create.clone())).await.is_ok() {
+            return Ok(());
+        }
+        self.graph
+            .run(Query::new(format!(
+                "DROP INDEX {} IF EXISTS",
+                constraint.index
+            )))
+            .await?;
+        if let Err(e) = self.graph.run(Query::new(create)).await {
+            tracing::warn!(
+                "Uniqueness constraint {} not created, keeping a plain index: {}",
+                constraint.name,
+                e
+            );
+            self.graph
+                .run(Query::new(format!(
+                    "CREATE INDEX {} IF NOT EXISTS FOR {} ON {}",
+                    constraint.index, constraint.pattern, constraint.properties
+                )))
+                .await?;
+        }
         Ok(())
     }
 
@@ -107,4 +255,17 @@ impl Neo4jClient {
     pub(super) fn graph(&self) -> &Graph {
         &self.graph
     }
+
+    /// Run one batch write in a `neo4j_batch` span naming the batch and its
+    /// row count
+    pub(super) async fn run_batch(
+        &self,
+        batch: &'static str,
+        rows: usize,
+        query: Query,
+    ) -> Result<(), Neo4jError> {
+        let span = tracing::info_span!("neo4j_batch", batch, rows);
+        self.graph.run(query).instrument(span).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,184 @@
+//! Paths: Shortest reference and call chains between two symbols
+//!
+//! Works on a [`GraphExport`] of one commit. Symbols are matched by name or
+//! qualified name, so one end can stand for several symbols. A breadth-first
+//! search along `REFERENCES` and `CALLS` edges from every start symbol finds
+//! the shortest distance to any end symbol; every chain of that length is a
+//! result.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use serde::Serialize;
+
+use super::export::{ExportNode, GraphExport};
+
+/// Edge kinds followed from one symbol to the next
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// A symbol ID with the edge kind and line that reached it, if any
+type Step<'a> = (&'a str, Option<&'a str>, Option<i64>);
+
+/// A using symbol's ID with the edge kind and line of the use
+type Use<'a> = (&'a str, &'a str, i64);
+
+/// One symbol on a chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathHop {
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: i64,
+    /// Edge from the previous hop, `None` for the first
+    pub via: Option<String>,
+    /// Line of the previous hop's file where it uses this symbol
+    pub line: Option<i64>,
+}
+
+/// A chain of symbols, each using the next
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolPath {
+    pub hops: Vec<PathHop>,
+}
+
+/// Whether a symbol node has `name` as its name or qualified name
+fn matches(node: &ExportNode, name: &str) -> bool {
+    node.label == "Symbol"
+        && (string(node, "name") == name || string(node, "qualified_name") == name)
+}
+
+/// Find the shortest chains from symbols named `from` to symbols named `to`
+///
+/// Returns at most `limit` chains, all of the same length, ordered by the
+/// locations of their hops; the search stops collecting chains at `limit`.
+/// Empty if no chain exists or a name matches no symbol.
+#[must_use]
+pub fn shortest_paths(graph: &GraphExport, from: &str, to: &str, limit: usize) -> Vec<SymbolPath> {
+    let nodes: HashMap<&str, &ExportNode> =
+        graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut uses: HashMap<&str, BTreeSet<(&str, &str, i64)>> = HashMap::new();
+    for edge in &graph.edges {
+        if USE_EDGES.contains(&edge.kind.as_str()) && edge.source != edge.target {
+            let line = edge
+                .properties
+                .get("line")
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or(0);
+            uses.entry(&edge.source)
+                .or_default()
+                .insert((&edge.target, &edge.kind, line));
+        }
+    }
+
+    let starts: Vec<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| matches(n, from))
+        .map(|n| n.id.as_str())
+        .collect();
+    let is_end = |id: &str| nodes.get(id).is_some_and(|n| matches(n, to));
+
+    // Breadth-first search, keeping every predecessor on a shortest route
+    let mut distance: HashMap<&str, usize> = starts.iter().map(|id| (*id, 0)).collect();
+    let mut previous: HashMap<&str, Vec<Use>> = HashMap::new();
+    let mut queue: VecDeque<&str> = starts.iter().copied().collect();
+    let mut ends = Vec::new();
+    let mut found_at = None;
+    while let Some(id) = queue.pop_front() {
+        let d = distance[id];
+        if found_at.is_some_and(|found| d >= found) {
+            break;
+        }
+        for &(target, kind, line) in uses.get(id).into_iter().flatten() {
+            match distance.get(target) {
+                None => {
+                    distance.insert(target, d + 1);
+                    previous.entry(target).or_default().push((id, kind, line));
+                    if is_end(target) {
+                        found_at = Some(d + 1);
+                        ends.push(target);
+                    } else {
+                        queue.push_back(target);
+                    }
+                }
+                Some(&known) if known == d + 1 => {
+                    previous.entry(target).or_default().push((id, kind, line));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    let mut chains = Vec::new();
+    for end in ends {
+        unwind(end, &previous, limit, &mut Vec::new(), &mut chains);
+    }
+    let mut paths: Vec<SymbolPath> = chains
+        .into_iter()
+        .map(|chain| SymbolPath {
+            hops: chain
+                .into_iter()
+                .filter_map(|(id, via, line)| nodes.get(id).map(|node| hop(node, via, line)))
+                .collect(),
+        })
+        .collect();
+    paths.sort_by(|a, b| locations(a).cmp(&locations(b)));
+    paths.truncate(limit);
+    paths
+}
+
+/// Collect chains that end at `id`, following predecessors back to a start
+/// symbol, until there are `limit` chains
+fn unwind<'a>(
+    id: &'a str,
+    previous: &HashMap<&'a str, Vec<Use<'a>>>,
+    limit: usize,
+    suffix: &mut Vec<Step<'a>>,
+    chains: &mut Vec<Vec<Step<'a>>>,
+) {
+    if chains.len() >= limit {
+        return;
+    }
+    let Some(sources) = previous.get(id) else {
+        let mut chain = vec![(id, None, None)];
+        chain.extend(suffix.iter().rev().copied());
+        chains.push(chain);
+        return;
+    };
+    for &(source, kind, line) in sources {
+        suffix.push((id, Some(kind), Some(line)));
+        unwind(source, previous, limit, suffix, chains);
+        suffix.pop();
+    }
+}
+
+fn hop(node: &ExportNode, via: Option<&str>, line: Option<i64>) -> PathHop {
+    PathHop {
+        qualified_name: string(node, "qualified_name"),
+        kind: string(node, "kind"),
+        file_path: string(node, "file_path"),
+        start_line: node
+            .properties
+            .get("start_line")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(0),
+        via: via.map(str::to_string),
+        line,
+    }
+}
+
+/// Sort key of a chain: the location of each hop in turn
+fn locations(path: &SymbolPath) -> Vec<(&str, i64)> {
+    path.hops
+        .iter()
+        .map(|h| (h.file_path.as_str(), h.start_line))
+        .collect()
+}
+
+/// A string property of a node, empty if missing
+fn string(node: &ExportNode, key: &str) -> String {
+    node.properties
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
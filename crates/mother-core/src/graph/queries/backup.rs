@@ -0,0 +1,302 @@
+//! Backup Neo4j queries: Dump and reload the nodes and relationships of
+//! the labels mother writes
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, FixedOffset};
+use neo4rs::{BoltList, BoltMap, BoltString, BoltType, Query};
+use serde_json::{Map, Number, Value};
+
+use super::Neo4jClient;
+use crate::graph::backup::{BackupRecord, GraphBackup};
+use crate::graph::neo4j::Neo4jError;
+
+/// Node labels mother writes; nodes with none of them are not backed up
+const MOTHER_LABELS: [&str; 15] = [
+    "Commit",
+    "Contract",
+    "Diagnostic",
+    "DirectorySummary",
+    "Endpoint",
+    "ExternalSymbol",
+    "File",
+    "Message",
+    "Module",
+    "Parameter",
+    "Repository",
+    "ScanRun",
+    "Symbol",
+    "SymbolIdentity",
+    "Type",
+];
+
+/// Properties stored as datetimes, backed up as RFC 3339 strings
+const DATETIME_PROPERTIES: [(&str, &str); 1] = [("ScanRun", "scanned_at")];
+
+/// Label and property that tie restored nodes to their backup IDs until
+/// the relationships are restored
+const RESTORE_LABEL: &str = "MotherRestore";
+const RESTORE_ID: &str = "_backup_id";
+
+/// Records written per query while restoring
+const RESTORE_BATCH: usize = 1000;
+
+impl Neo4jClient {
+    /// Read every node of the labels mother writes and the relationships
+    /// between them
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn backup(&self) -> Result<GraphBackup, Neo4jError> {
+        let mut backup = GraphBackup {
+            backend: "neo4j".to_string(),
+            records: Vec::new(),
+        };
+        let nodes = Query::new(
+            r#"
+            MATCH (n) WHERE any(label IN labels(n) WHERE label IN $labels)
+            RETURN elementId(n) AS id, labels(n) AS labels, properties(n) AS properties
+            "#
+            .to_string(),
+        )
+        .param("labels", labels());
+        let mut result = self.graph().execute(nodes).await?;
+        while let Some(row) = result.next().await? {
+            let mut labels: Vec<String> = row.get("labels").unwrap_or_default();
+            labels.sort();
+            backup.records.push(BackupRecord::Node {
+                id: row.get("id").unwrap_or_default(),
+                labels,
+                properties: to_json_map(row.get("properties").unwrap_or_default()),
+            });
+        }
+
+        let relationships = Query::new(
+            r#"
+            MATCH (a)-[r]->(b)
+            WHERE any(label IN labels(a) WHERE label IN $labels)
+              AND any(label IN labels(b) WHERE label IN $labels)
+            RETURN elementId(a) AS source, elementId(b) AS target, type(r) AS kind,
+                   properties(r) AS properties
+            "#
+            .to_string(),
+        )
+        .param("labels", labels());
+        let mut result = self.graph().execute(relationships).await?;
+        while let Some(row) = result.next().await? {
+            backup.records.push(BackupRecord::Relationship {
+                source: row.get("source").unwrap_or_default(),
+                target: row.get("target").unwrap_or_default(),
+                kind: row.get("kind").unwrap_or_default(),
+                properties: to_json_map(row.get("properties").unwrap_or_default()),
+            });
+        }
+        Ok(backup)
+    }
+
+    /// Recreate the nodes and relationships of a backup in an empty graph
+    ///
+    /// Returns the number of records restored.
+    ///
+    /// # Errors
+    /// Returns an error if the backup was taken from another backend, holds
+    /// labels or relationship types mother does not write, the graph
+    /// already holds mother nodes, or a query fails.
+    pub async fn restore(&self, backup: &GraphBackup) -> Result<usize, Neo4jError> {
+        if backup.backend != "neo4j" {
+            return Err(Neo4jError::Query(format!(
+                "cannot restore a {} backup into Neo4j",
+                backup.backend
+            )));
+        }
+        let existing = Query::new(
+            r#"
+            MATCH (n) WHERE any(label IN labels(n) WHERE label IN $labels)
+            RETURN count(n) AS count
+            "#
+            .to_string(),
+        )
+        .param("labels", labels());
+        let mut result = self.graph().execute(existing).await?;
+        let count: i64 = match result.next().await? {
+            Some(row) => row.get("count").unwrap_or_default(),
+            None => 0,
+        };
+        if count > 0 {
+            return Err(Neo4jError::Query(
+                "the graph is not empty; restore into a fresh database".to_string(),
+            ));
+        }
+
+        let (nodes, relationships) = group_records(backup)?;
+        self.graph()
+            .run(Query::new(format!(
+                "CREATE INDEX mother_restore IF NOT EXISTS FOR (n:{RESTORE_LABEL}) ON (n.{RESTORE_ID})"
+            )))
+            .await?;
+        for (labels, rows) in &nodes {
+            let mut statement = format!(
+                "UNWIND $rows AS row CREATE (n:{}:{RESTORE_LABEL}) \
+                 SET n = row.properties SET n.{RESTORE_ID} = row.id",
+                labels.join(":")
+            );
+            for (label, property) in DATETIME_PROPERTIES {
+                if labels.contains(&label) {
+                    statement.push_str(&format!(" SET n.{property} = datetime(n.{property})"));
+                }
+            }
+            self.run_batches(&statement, rows).await?;
+        }
+        for (kind, rows) in &relationships {
+            let statement = format!(
+                "UNWIND $rows AS row \
+                 MATCH (a:{RESTORE_LABEL} {{{RESTORE_ID}: row.source}}) \
+                 MATCH (b:{RESTORE_LABEL} {{{RESTORE_ID}: row.target}}) \
+                 CREATE (a)-[r:{kind}]->(b) SET r = row.properties"
+            );
+            self.run_batches(&statement, rows).await?;
+        }
+        self.graph()
+            .run(Query::new(format!(
+                "MATCH (n:{RESTORE_LABEL}) REMOVE n:{RESTORE_LABEL}, n.{RESTORE_ID}"
+            )))
+            .await?;
+        self.graph()
+            .run(Query::new(
+                "DROP INDEX mother_restore IF EXISTS".to_string(),
+            ))
+            .await?;
+        Ok(backup.records.len())
+    }
+
+    async fn run_batches(&self, statement: &str, rows: &[BoltType]) -> Result<(), Neo4jError> {
+        for batch in rows.chunks(RESTORE_BATCH) {
+            let query = Query::new(statement.to_string()).param("rows", batch.to_vec());
+            self.run_batch("restore", batch.len(), query).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Node rows by their labels and relationship rows by their type, checked
+/// against what mother writes so both can go into query text
+#[allow(clippy::type_complexity)]
+fn group_records(
+    backup: &GraphBackup,
+) -> Result<
+    (
+        BTreeMap<Vec<&str>, Vec<BoltType>>,
+        BTreeMap<&str, Vec<BoltType>>,
+    ),
+    Neo4jError,
+> {
+    let mut nodes: BTreeMap<Vec<&str>, Vec<BoltType>> = BTreeMap::new();
+    let mut relationships: BTreeMap<&str, Vec<BoltType>> = BTreeMap::new();
+    for record in &backup.records {
+        match record {
+            BackupRecord::Node {
+                id,
+                labels,
+                properties,
+            } => {
+                if let Some(label) = labels.iter().find(|l| !MOTHER_LABELS.contains(&l.as_str())) {
+                    return Err(Neo4jError::Query(format!(
+                        "unknown label {label} in backup"
+                    )));
+                }
+                let row = bolt_row([
+                    ("id", id.clone().into()),
+                    ("properties", to_bolt_map(properties)),
+                ]);
+                nodes
+                    .entry(labels.iter().map(String::as_str).collect())
+                    .or_default()
+                    .push(row);
+            }
+            BackupRecord::Relationship {
+                source,
+                target,
+                kind,
+                properties,
+            } => {
+                let valid =
+                    !kind.is_empty() && kind.chars().all(|c| c.is_ascii_uppercase() || c == '_');
+                if !valid {
+                    return Err(Neo4jError::Query(format!(
+                        "invalid relationship type {kind} in backup"
+                    )));
+                }
+                let row = bolt_row([
+                    ("source", source.clone().into()),
+                    ("target", target.clone().into()),
+                    ("properties", to_bolt_map(properties)),
+                ]);
+                relationships.entry(kind).or_default().push(row);
+            }
+            BackupRecord::Row { table, .. } => {
+                return Err(Neo4jError::Query(format!(
+                    "unexpected {table} table row in a Neo4j backup"
+                )));
+            }
+        }
+    }
+    Ok((nodes, relationships))
+}
+
+fn labels() -> Vec<String> {
+    MOTHER_LABELS.iter().map(ToString::to_string).collect()
+}
+
+fn bolt_row<const N: usize>(fields: [(&str, BoltType); N]) -> BoltType {
+    let value: HashMap<BoltString, BoltType> = fields
+        .into_iter()
+        .map(|(key, value)| (BoltString::from(key), value))
+        .collect();
+    BoltType::Map(BoltMap { value })
+}
+
+fn to_json_map(map: BoltMap) -> Map<String, Value> {
+    map.value
+        .into_iter()
+        .map(|(key, value)| (key.value, to_json(value)))
+        .collect()
+}
+
+/// JSON value of a property; types mother does not store become null
+fn to_json(value: BoltType) -> Value {
+    match value {
+        BoltType::String(s) => Value::String(s.value),
+        BoltType::Boolean(b) => Value::Bool(b.value),
+        BoltType::Integer(i) => Value::from(i.value),
+        BoltType::Float(f) => Number::from_f64(f.value).map_or(Value::Null, Value::Number),
+        BoltType::List(list) => Value::Array(list.value.into_iter().map(to_json).collect()),
+        BoltType::Map(map) => Value::Object(to_json_map(map)),
+        BoltType::DateTime(dt) => DateTime::<FixedOffset>::try_from(&dt)
+            .map_or(Value::Null, |dt| Value::String(dt.to_rfc3339())),
+        _ => Value::Null,
+    }
+}
+
+fn to_bolt_map(map: &Map<String, Value>) -> BoltType {
+    let value = map
+        .iter()
+        .map(|(key, value)| (BoltString::from(key.as_str()), to_bolt(value)))
+        .collect();
+    BoltType::Map(BoltMap { value })
+}
+
+fn to_bolt(value: &Value) -> BoltType {
+    match value {
+        Value::Null => BoltType::Null(neo4rs::BoltNull),
+        Value::Bool(b) => BoltType::from(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => BoltType::from(i),
+            None => BoltType::from(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => BoltType::from(s.as_str()),
+        Value::Array(items) => BoltType::List(BoltList {
+            value: items.iter().map(to_bolt).collect(),
+        }),
+        Value::Object(map) => to_bolt_map(map),
+    }
+}
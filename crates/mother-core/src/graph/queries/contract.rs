@@ -0,0 +1,110 @@
+//! API contract Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+
+use super::Neo4jClient;
+use crate::graph::model::ContractNode;
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Replace the Endpoint and Message nodes of a commit
+    ///
+    /// Each contract hangs off the commit with HAS_CONTRACT and is
+    /// HANDLED_BY the symbols serving it.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), Neo4jError> {
+        let clear = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_CONTRACT]->(n)
+            DETACH DELETE n
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+        self.graph().run(clear).await?;
+        if contracts.is_empty() {
+            return Ok(());
+        }
+
+        let contract_data: Vec<HashMap<&str, BoltType>> = contracts
+            .iter()
+            .map(|c| {
+                let mut map = HashMap::new();
+                map.insert("kind", BoltType::String(c.kind.clone().into()));
+                map.insert("name", BoltType::String(c.name.clone().into()));
+                map.insert("identifier", BoltType::String(c.identifier.clone().into()));
+                map.insert("file_path", BoltType::String(c.file_path.clone().into()));
+                map.insert("line", BoltType::Integer(i64::from(c.line).into()));
+                map.insert("handler_ids", c.handler_ids.clone().into());
+                map
+            })
+            .collect();
+
+        let create = Query::new(
+            r#"
+            MATCH (c:Commit {sha: $commit_sha})
+            UNWIND $contracts AS contract
+            CREATE (c)-[:HAS_CONTRACT]->(n:Contract {commit_sha: $commit_sha, name: contract.name})
+            SET n.kind = contract.kind, n.identifier = contract.identifier,
+                n.file_path = contract.file_path, n.line = contract.line
+            FOREACH (_ IN CASE WHEN contract.kind = 'endpoint' THEN [1] ELSE [] END | SET n:Endpoint)
+            FOREACH (_ IN CASE WHEN contract.kind = 'message' THEN [1] ELSE [] END | SET n:Message)
+            WITH n, contract
+            UNWIND contract.handler_ids AS symbol_id
+            MATCH (s:Symbol {id: symbol_id})
+            MERGE (n)-[:HANDLED_BY]->(s)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("contracts", contract_data);
+        self.run_batch("contracts", contracts.len(), create).await?;
+        Ok(())
+    }
+
+    /// API contracts of a commit with the symbols serving them
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn contracts_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<ContractNode>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_CONTRACT]->(n:Contract)
+            OPTIONAL MATCH (n)-[:HANDLED_BY]->(s:Symbol)
+            WITH n, s ORDER BY s.id
+            RETURN n.kind AS kind, n.name AS name, n.identifier AS identifier,
+                   n.file_path AS file_path, n.line AS line,
+                   collect(s.id) AS handler_ids
+            ORDER BY file_path, line, name
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut contracts = Vec::new();
+        while let Some(row) = result.next().await? {
+            let line: i64 = row.get("line").unwrap_or_default();
+            contracts.push(ContractNode {
+                kind: row.get("kind").unwrap_or_default(),
+                name: row.get("name").unwrap_or_default(),
+                identifier: row.get("identifier").unwrap_or_default(),
+                file_path: row.get("file_path").unwrap_or_default(),
+                line: u32::try_from(line).unwrap_or_default(),
+                handler_ids: row.get("handler_ids").unwrap_or_default(),
+            });
+        }
+        Ok(contracts)
+    }
+}
@@ -0,0 +1,154 @@
+//! Diagnostic Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+use tracing::Instrument;
+
+use super::read::{scoped_query_on, DiagnosticResult};
+use super::Neo4jClient;
+use crate::graph::model::DiagnosticNode;
+use crate::graph::neo4j::Neo4jError;
+use crate::graph::scope::PathScope;
+
+impl Neo4jClient {
+    /// Replace the Diagnostic nodes of a file using batch UNWIND
+    ///
+    /// The file points to each diagnostic with HAS_DIAGNOSTIC, and so does
+    /// the symbol containing it, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), Neo4jError> {
+        let clear = Query::new(
+            r#"
+            MATCH (:File {content_hash: $content_hash})-[:HAS_DIAGNOSTIC]->(d:Diagnostic)
+            DETACH DELETE d
+            "#
+            .to_string(),
+        )
+        .param("content_hash", content_hash);
+        // Old diagnostics are only cleared if the new ones are stored
+        let mut txn = self.graph().start_txn().await?;
+        txn.run(clear).await?;
+
+        if diagnostics.is_empty() {
+            txn.commit().await?;
+            return Ok(());
+        }
+
+        let diag_data: Vec<HashMap<&str, BoltType>> = diagnostics
+            .iter()
+            .map(|d| {
+                let mut map = HashMap::new();
+                map.insert("severity", BoltType::String(d.severity.clone().into()));
+                map.insert(
+                    "code",
+                    BoltType::String(d.code.clone().unwrap_or_default().into()),
+                );
+                map.insert(
+                    "source",
+                    BoltType::String(d.source.clone().unwrap_or_default().into()),
+                );
+                map.insert("message", BoltType::String(d.message.clone().into()));
+                map.insert(
+                    "start_line",
+                    BoltType::Integer(i64::from(d.start_line).into()),
+                );
+                map.insert(
+                    "start_col",
+                    BoltType::Integer(i64::from(d.start_col).into()),
+                );
+                map.insert("end_line", BoltType::Integer(i64::from(d.end_line).into()));
+                map.insert("end_col", BoltType::Integer(i64::from(d.end_col).into()));
+                map.insert(
+                    "symbol_id",
+                    BoltType::String(d.symbol_id.clone().unwrap_or_default().into()),
+                );
+                map
+            })
+            .collect();
+
+        let query = Query::new(
+            r#"
+            MATCH (f:File {content_hash: $content_hash})
+            UNWIND $diagnostics AS diag
+            CREATE (f)-[:HAS_DIAGNOSTIC]->(d:Diagnostic {
+                severity: diag.severity,
+                code: diag.code,
+                source: diag.source,
+                message: diag.message,
+                file_path: f.path,
+                start_line: diag.start_line,
+                start_col: diag.start_col,
+                end_line: diag.end_line,
+                end_col: diag.end_col
+            })
+            WITH d, diag
+            OPTIONAL MATCH (s:Symbol {id: diag.symbol_id})
+            FOREACH (_ IN CASE WHEN s IS NULL THEN [] ELSE [1] END |
+                MERGE (s)-[:HAS_DIAGNOSTIC]->(d))
+            "#
+            .to_string(),
+        )
+        .param("content_hash", content_hash)
+        .param("diagnostics", diag_data);
+
+        let span = tracing::info_span!(
+            "neo4j_batch",
+            batch = "diagnostics",
+            rows = diagnostics.len()
+        );
+        txn.run(query).instrument(span).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Diagnostics in the files of a commit, ordered by file and position
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, Neo4jError> {
+        let query = scoped_query_on(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:CONTAINS]->(f:File)-[:HAS_DIAGNOSTIC]->(d:Diagnostic)
+            WHERE ($severity = '' OR d.severity = $severity)
+            {scope}
+            OPTIONAL MATCH (s:Symbol)-[:HAS_DIAGNOSTIC]->(d)
+            RETURN d.severity AS severity, d.code AS code, d.source AS source,
+                   d.message AS message, f.path AS file_path, d.start_line AS start_line,
+                   d.start_col AS start_col, coalesce(s.qualified_name, '') AS symbol
+            ORDER BY f.path, d.start_line, d.start_col
+            "#,
+            "f.path",
+            scope,
+        )
+        .param("commit_sha", commit_sha)
+        .param("severity", severity.unwrap_or_default());
+
+        let mut result = self.graph().execute(query).await?;
+        let mut diagnostics = Vec::new();
+        while let Some(row) = result.next().await? {
+            diagnostics.push(DiagnosticResult {
+                severity: row.get("severity").unwrap_or_default(),
+                code: row.get("code").unwrap_or_default(),
+                source: row.get("source").unwrap_or_default(),
+                message: row.get("message").unwrap_or_default(),
+                file_path: row.get("file_path").unwrap_or_default(),
+                start_line: row.get("start_line").unwrap_or(0),
+                start_col: row.get("start_col").unwrap_or(0),
+                symbol: row.get("symbol").unwrap_or_default(),
+            });
+        }
+        Ok(diagnostics)
+    }
+}
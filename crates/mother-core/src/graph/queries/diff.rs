@@ -0,0 +1,36 @@
+//! Diff-related Neo4j queries
+
+use neo4rs::Query;
+
+use super::symbol::symbol_node;
+use super::Neo4jClient;
+use crate::graph::model::SymbolNode;
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Every symbol in the files of a commit, ordered by file and line
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:CONTAINS]->(f:File)<-[:DEFINED_IN]-(s:Symbol)
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.visibility, s.file_path,
+                   s.start_line, s.end_line, s.signature, s.doc_comment,
+                   s.type_signature, s.resolved_type
+            ORDER BY s.file_path, s.start_line
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut symbols = Vec::new();
+        while let Some(row) = result.next().await? {
+            symbols.extend(symbol_node(&row));
+        }
+
+        Ok(symbols)
+    }
+}
@@ -0,0 +1,169 @@
+//! Directory summary Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+
+use super::Neo4jClient;
+use crate::graph::model::DirectorySummary;
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Create or update DirectorySummary nodes of a commit using batch UNWIND
+    ///
+    /// Each summary hangs off the commit with HAS_DIRECTORY and CONTAINS its
+    /// subdirectories and the commit's files directly in it.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), Neo4jError> {
+        if summaries.is_empty() {
+            return Ok(());
+        }
+
+        let dir_data: Vec<HashMap<&str, BoltType>> = summaries
+            .iter()
+            .map(|d| {
+                let mut map = HashMap::new();
+                map.insert("path", BoltType::String(d.path.clone().into()));
+                map.insert(
+                    "parent",
+                    BoltType::String(d.parent.clone().unwrap_or_default().into()),
+                );
+                map.insert("file_count", BoltType::Integer(count(d.file_count).into()));
+                map.insert("line_count", BoltType::Integer(count(d.line_count).into()));
+                map.insert("languages", d.languages.clone().into());
+                map.insert(
+                    "readme",
+                    BoltType::String(d.readme.clone().unwrap_or_default().into()),
+                );
+                map.insert("files", d.files.clone().into());
+                map
+            })
+            .collect();
+
+        let query = Query::new(
+            r#"
+            MATCH (c:Commit {sha: $commit_sha})
+            UNWIND $dirs AS dir
+            MERGE (c)-[:HAS_DIRECTORY]->(d:DirectorySummary {commit_sha: $commit_sha, path: dir.path})
+            SET d.file_count = dir.file_count,
+                d.line_count = dir.line_count,
+                d.languages = dir.languages,
+                d.readme = dir.readme
+            WITH c, d, dir
+            UNWIND dir.files AS file_path
+            MATCH (c)-[:CONTAINS]->(f:File {path: file_path})
+            MERGE (d)-[:CONTAINS]->(f)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("dirs", dir_data.clone());
+        self.run_batch("directories", summaries.len(), query)
+            .await?;
+
+        // Link parents once every directory of the batch exists
+        let query = Query::new(
+            r#"
+            UNWIND $dirs AS dir
+            WITH dir WHERE dir.parent <> ''
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_DIRECTORY]->(parent:DirectorySummary {path: dir.parent})
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_DIRECTORY]->(child:DirectorySummary {path: dir.path})
+            MERGE (parent)-[:CONTAINS]->(child)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("dirs", dir_data);
+        self.run_batch("directory_parents", summaries.len(), query)
+            .await?;
+        Ok(())
+    }
+
+    /// Summary of a directory as of a commit
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_DIRECTORY]->(d:DirectorySummary {path: $path})
+            OPTIONAL MATCH (parent:DirectorySummary)-[:CONTAINS]->(d)
+            OPTIONAL MATCH (d)-[:CONTAINS]->(f:File)
+            WITH d, parent, f ORDER BY f.path
+            RETURN d.path AS path, parent.path AS parent, d.file_count AS file_count,
+                   d.line_count AS line_count, d.languages AS languages, d.readme AS readme,
+                   collect(f.path) AS files
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("path", path);
+
+        let mut result = self.graph().execute(query).await?;
+        let Some(row) = result.next().await? else {
+            return Ok(None);
+        };
+        Ok(Some(directory_from_row(&row)))
+    }
+
+    /// Every directory summary of a commit, ordered by path
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_DIRECTORY]->(d:DirectorySummary)
+            OPTIONAL MATCH (parent:DirectorySummary)-[:CONTAINS]->(d)
+            OPTIONAL MATCH (d)-[:CONTAINS]->(f:File)
+            WITH d, parent, f ORDER BY f.path
+            WITH d, parent, collect(f.path) AS files
+            RETURN d.path AS path, parent.path AS parent, d.file_count AS file_count,
+                   d.line_count AS line_count, d.languages AS languages, d.readme AS readme,
+                   files
+            ORDER BY path
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut directories = Vec::new();
+        while let Some(row) = result.next().await? {
+            directories.push(directory_from_row(&row));
+        }
+        Ok(directories)
+    }
+}
+
+/// A directory summary from a row of the read queries
+fn directory_from_row(row: &neo4rs::Row) -> DirectorySummary {
+    let readme: String = row.get("readme").unwrap_or_default();
+    DirectorySummary {
+        path: row.get("path").unwrap_or_default(),
+        parent: row.get("parent").ok(),
+        file_count: usize::try_from(row.get::<i64>("file_count").unwrap_or(0)).unwrap_or(0),
+        line_count: usize::try_from(row.get::<i64>("line_count").unwrap_or(0)).unwrap_or(0),
+        languages: row.get("languages").unwrap_or_default(),
+        readme: (!readme.is_empty()).then_some(readme),
+        files: row.get("files").unwrap_or_default(),
+    }
+}
+
+/// A count as a Bolt integer
+fn count(value: usize) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
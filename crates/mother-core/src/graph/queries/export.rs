@@ -0,0 +1,196 @@
+//! Export queries: Read a commit's File and Symbol subgraph
+
+use std::collections::BTreeMap;
+
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use neo4rs::{Query, Row};
+use serde_json::Value;
+
+use super::Neo4jClient;
+use crate::graph::export::{ExportEdge, ExportNode, ExportRecord, GraphExport};
+use crate::graph::neo4j::Neo4jError;
+
+/// Prefix for File node ids, which are otherwise bare content hashes
+const FILE_ID_PREFIX: &str = "file:";
+
+/// One query of an export and how its rows become records
+struct Part {
+    cypher: &'static str,
+    record: fn(&Row) -> ExportRecord,
+}
+
+/// The queries of an export in stream order: nodes, then edges
+const PARTS: [Part; 5] = [
+    Part {
+        cypher: r#"
+            MATCH (:Commit {sha: $sha})-[:CONTAINS]->(f:File)
+            RETURN f.content_hash AS hash, f.path AS path, f.language AS language
+            ORDER BY f.path
+            "#,
+        record: file_node,
+    },
+    Part {
+        cypher: r#"
+            MATCH (:Commit {sha: $sha})-[:CONTAINS]->(f:File)<-[:DEFINED_IN]-(s:Symbol)
+            RETURN s.id AS id, s.name AS name, s.qualified_name AS qualified_name,
+                   s.kind AS kind, s.visibility AS visibility, s.file_path AS file_path,
+                   s.start_line AS start_line, s.end_line AS end_line,
+                   s.signature AS signature, s.doc_comment AS doc_comment,
+                   s.reference_count AS reference_count,
+                   s.is_truncated AS is_truncated
+            ORDER BY s.file_path, s.start_line
+            "#,
+        record: symbol_node,
+    },
+    Part {
+        cypher: r#"
+            MATCH (:Commit {sha: $sha})-[:CONTAINS]->(f:File)<-[:DEFINED_IN]-(s:Symbol)
+            RETURN s.id AS source, f.content_hash AS file_hash
+            ORDER BY s.file_path, s.start_line
+            "#,
+        record: defined_in_edge,
+    },
+    Part {
+        cypher: r#"
+            MATCH (c:Commit {sha: $sha})-[:CONTAINS]->(:File)<-[:DEFINED_IN]-(a:Symbol)
+            MATCH (a)-[r]->(b:Symbol)-[:DEFINED_IN]->(:File)<-[:CONTAINS]-(c)
+            RETURN a.id AS source, b.id AS target, type(r) AS kind,
+                   r.line AS line, r.column AS column
+            "#,
+        record: symbol_edge,
+    },
+    Part {
+        cypher: r#"
+            MATCH (c:Commit {sha: $sha})-[:CONTAINS]->(a:File)-[r:IMPORTS]->(b:File)<-[:CONTAINS]-(c)
+            RETURN a.content_hash AS source, b.content_hash AS target,
+                   r.module AS module, r.line AS line
+            "#,
+        record: import_edge,
+    },
+];
+
+impl Neo4jClient {
+    /// Read every File and Symbol of a commit with the edges between them
+    ///
+    /// Includes DEFINED_IN, IMPORTS and symbol-to-symbol edges (REFERENCES,
+    /// CALLS, ...) whose endpoints both belong to the commit.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, Neo4jError> {
+        self.stream_export(commit_sha)
+            .try_fold(GraphExport::default(), |mut graph, record| async move {
+                graph.push(record);
+                Ok(graph)
+            })
+            .await
+    }
+
+    /// Stream the records [`export_commit`](Self::export_commit) collects,
+    /// fetching rows from the server as the stream is polled
+    pub fn stream_export<'a>(
+        &'a self,
+        commit_sha: &'a str,
+    ) -> BoxStream<'a, Result<ExportRecord, Neo4jError>> {
+        stream::iter(&PARTS)
+            .then(move |part| async move {
+                let query = Query::new(part.cypher.to_string()).param("sha", commit_sha);
+                let rows = self.graph().execute(query).await?;
+                Ok::<_, Neo4jError>(stream::try_unfold(rows, move |mut rows| async move {
+                    Ok(rows.next().await?.map(|row| ((part.record)(&row), rows)))
+                }))
+            })
+            .try_flatten()
+            .boxed()
+    }
+}
+
+fn file_node(row: &Row) -> ExportRecord {
+    let hash: String = row.get("hash").unwrap_or_default();
+    ExportRecord::Node(ExportNode {
+        id: format!("{FILE_ID_PREFIX}{hash}"),
+        label: "File".to_string(),
+        properties: properties(row, &["path", "language"], &[], &[("content_hash", hash)]),
+    })
+}
+
+fn symbol_node(row: &Row) -> ExportRecord {
+    let mut props = properties(
+        row,
+        &[
+            "name",
+            "qualified_name",
+            "kind",
+            "visibility",
+            "file_path",
+            "signature",
+            "doc_comment",
+        ],
+        &["start_line", "end_line", "reference_count"],
+        &[],
+    );
+    if row.get::<bool>("is_truncated").unwrap_or(false) {
+        props.insert("is_truncated".to_string(), Value::Bool(true));
+    }
+    ExportRecord::Node(ExportNode {
+        id: row.get("id").unwrap_or_default(),
+        label: "Symbol".to_string(),
+        properties: props,
+    })
+}
+
+fn defined_in_edge(row: &Row) -> ExportRecord {
+    let file_hash: String = row.get("file_hash").unwrap_or_default();
+    ExportRecord::Edge(ExportEdge {
+        source: row.get("source").unwrap_or_default(),
+        target: format!("{FILE_ID_PREFIX}{file_hash}"),
+        kind: "DEFINED_IN".to_string(),
+        properties: BTreeMap::new(),
+    })
+}
+
+fn symbol_edge(row: &Row) -> ExportRecord {
+    ExportRecord::Edge(ExportEdge {
+        source: row.get("source").unwrap_or_default(),
+        target: row.get("target").unwrap_or_default(),
+        kind: row.get("kind").unwrap_or_default(),
+        properties: properties(row, &[], &["line", "column"], &[]),
+    })
+}
+
+fn import_edge(row: &Row) -> ExportRecord {
+    let source: String = row.get("source").unwrap_or_default();
+    let target: String = row.get("target").unwrap_or_default();
+    ExportRecord::Edge(ExportEdge {
+        source: format!("{FILE_ID_PREFIX}{source}"),
+        target: format!("{FILE_ID_PREFIX}{target}"),
+        kind: "IMPORTS".to_string(),
+        properties: properties(row, &["module"], &["line"], &[]),
+    })
+}
+
+/// Collect non-empty string and integer columns of a row into properties
+fn properties(
+    row: &Row,
+    strings: &[&str],
+    integers: &[&str],
+    extra: &[(&str, String)],
+) -> BTreeMap<String, Value> {
+    let mut props = BTreeMap::new();
+    for key in strings {
+        if let Ok(value) = row.get::<String>(key) {
+            if !value.is_empty() {
+                props.insert((*key).to_string(), Value::String(value));
+            }
+        }
+    }
+    for key in integers {
+        if let Ok(value) = row.get::<i64>(key) {
+            props.insert((*key).to_string(), Value::from(value));
+        }
+    }
+    for (key, value) in extra {
+        props.insert((*key).to_string(), Value::String(value.clone()));
+    }
+    props
+}
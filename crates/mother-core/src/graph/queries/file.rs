@@ -1,10 +1,19 @@
 //! File-related Neo4j queries
 
-use neo4rs::Query;
+use std::collections::HashMap;
 
+use neo4rs::{BoltType, Query};
+
+use super::read::FileMetricsResult;
+use super::signature::signature_queries;
+use super::symbol::{edge_queries, symbols_query};
 use super::Neo4jClient;
+use crate::graph::model::{FileMetrics, FileWrite, ImportEdge};
 use crate::graph::neo4j::Neo4jError;
 
+/// Prefix of the File node properties holding symbol counts by kind
+const SYMBOL_COUNT_PREFIX: &str = "symbols_";
+
 impl Neo4jClient {
     /// Create or link a file to a commit
     ///
@@ -20,55 +29,331 @@ impl Neo4jClient {
         language: &str,
         commit_sha: &str,
     ) -> Result<Option<String>, Neo4jError> {
-        // Check if file with this hash already exists
-        let check_query = Query::new(
+        // The check and the write share a transaction
+        let mut txn = self.graph().start_txn().await?;
+        let mut result = txn.execute(stored_file_query(content_hash)).await?;
+
+        if result.next(txn.handle()).await?.is_some() {
+            // File exists - just link to commit
+            txn.run(link_file_query(content_hash, commit_sha)).await?;
+            txn.commit().await?;
+            return Ok(None); // File exists, skip symbol extraction
+        }
+
+        // Create new file and link to commit
+        txn.run(create_file_query(
+            file_path,
+            content_hash,
+            language,
+            commit_sha,
+        ))
+        .await?;
+        txn.commit().await?;
+        Ok(Some(content_hash.to_string())) // New file, needs symbol extraction
+    }
+
+    /// Link stored content to a commit
+    ///
+    /// Returns `false` if no file with `content_hash` is stored.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, Neo4jError> {
+        let mut txn = self.graph().start_txn().await?;
+        let mut result = txn.execute(stored_file_query(content_hash)).await?;
+        let stored = result.next(txn.handle()).await?.is_some();
+        if stored {
+            txn.run(link_file_query(content_hash, commit_sha)).await?;
+        }
+        txn.commit().await?;
+        Ok(stored)
+    }
+
+    /// Store a new file with its metrics, symbols, signatures and edges in
+    /// one transaction, linked to its commit
+    ///
+    /// Returns `false` if the content was already stored, in which case it
+    /// is only linked.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails; nothing of the file is stored then.
+    pub async fn write_file(&self, file: &FileWrite) -> Result<bool, Neo4jError> {
+        // Schema changes cannot share a transaction with writes
+        if let Some(vector) = file.symbols.iter().find_map(|s| s.embedding.as_ref()) {
+            self.ensure_vector_index(vector.len()).await?;
+        }
+
+        let mut txn = self.graph().start_txn().await?;
+        let mut result = txn.execute(stored_file_query(&file.content_hash)).await?;
+        if result.next(txn.handle()).await?.is_some() {
+            txn.run(link_file_query(&file.content_hash, &file.commit_sha))
+                .await?;
+            txn.commit().await?;
+            return Ok(false);
+        }
+
+        let mut queries = vec![create_file_query(
+            &file.path,
+            &file.content_hash,
+            &file.language,
+            &file.commit_sha,
+        )];
+        if let Some(metrics) = &file.metrics {
+            queries.push(file_metrics_query(&file.content_hash, metrics));
+        }
+        if !file.symbols.is_empty() {
+            queries.push(symbols_query(&file.symbols, &file.content_hash));
+        }
+        queries.extend(
+            signature_queries(&file.signatures)
+                .into_iter()
+                .map(|(_, _, q)| q),
+        );
+        queries.extend(edge_queries(&file.edges).into_iter().map(|(_, q)| q));
+        txn.run_queries(queries).await?;
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    /// Remove the CONTAINS link from a commit to the file at `file_path`
+    ///
+    /// Returns `false` if the commit did not contain the path.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[r:CONTAINS]->(:File {path: $file_path})
+            DELETE r
+            RETURN count(r) as removed
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("file_path", file_path);
+
+        let mut result = self.graph().execute(query).await?;
+        let removed = match result.next().await? {
+            Some(row) => row.get::<i64>("removed").unwrap_or(0),
+            None => 0,
+        };
+        Ok(removed > 0)
+    }
+
+    /// Store the size measures of a file as File node properties
+    ///
+    /// Symbol counts become one `symbols_<kind>` property per kind.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), Neo4jError> {
+        self.graph()
+            .run(file_metrics_query(content_hash, metrics))
+            .await?;
+        Ok(())
+    }
+
+    /// Size measures of the files of a commit whose path contains `pattern`,
+    /// ordered by path
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:CONTAINS]->(f:File)
+            WHERE $pattern = '' OR f.path CONTAINS $pattern
+            WITH f, [k IN keys(f) WHERE k STARTS WITH $prefix] AS kinds
+            RETURN f.path AS path, f.language AS language,
+                   coalesce(f.line_count, 0) AS line_count,
+                   coalesce(f.byte_size, 0) AS byte_size,
+                   coalesce(f.symbol_count, 0) AS symbol_count,
+                   [k IN kinds | substring(k, size($prefix))] AS kinds,
+                   [k IN kinds | f[k]] AS counts
+            ORDER BY f.path
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("pattern", pattern.unwrap_or_default())
+        .param("prefix", SYMBOL_COUNT_PREFIX);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut files = Vec::new();
+        while let Some(row) = result.next().await? {
+            let kinds: Vec<String> = row.get("kinds").unwrap_or_default();
+            let counts: Vec<i64> = row.get("counts").unwrap_or_default();
+            files.push(FileMetricsResult {
+                path: row.get("path").unwrap_or_default(),
+                language: row.get("language").unwrap_or_default(),
+                line_count: row.get("line_count").unwrap_or(0),
+                byte_size: row.get("byte_size").unwrap_or(0),
+                symbol_count: row.get("symbol_count").unwrap_or(0),
+                symbol_counts: kinds.into_iter().zip(counts).collect(),
+            });
+        }
+        Ok(files)
+    }
+
+    /// Store the raw module paths a file imports
+    ///
+    /// Imports depend only on file content, so they live on the `File` node
+    /// and are shared by every commit containing that content.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), Neo4jError> {
+        let query = Query::new(
             r#"
             MATCH (f:File {content_hash: $content_hash})
-            RETURN f.content_hash as hash
-            LIMIT 1
+            SET f.imports = $modules
             "#
             .to_string(),
         )
-        .param("content_hash", content_hash);
+        .param("content_hash", content_hash)
+        .param("modules", modules.to_vec());
 
-        let mut result = self.graph().execute(check_query).await?;
+        self.graph().run(query).await?;
+        Ok(())
+    }
 
-        if result.next().await?.is_some() {
-            // File exists - just link to commit
-            let link_query = Query::new(
-                r#"
-                MATCH (f:File {content_hash: $content_hash})
-                MATCH (c:Commit {sha: $commit_sha})
-                MERGE (c)-[:CONTAINS]->(f)
-                "#
-                .to_string(),
-            )
-            .param("content_hash", content_hash)
-            .param("commit_sha", commit_sha);
-
-            self.graph().run(link_query).await?;
-            return Ok(None); // File exists, skip symbol extraction
+    /// Create IMPORTS edges between files of a commit using batch UNWIND
+    ///
+    /// Both endpoints are matched through the commit, so an edge always links
+    /// the file versions that were present together.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), Neo4jError> {
+        if edges.is_empty() {
+            return Ok(());
         }
 
-        // Create new file and link to commit
-        let create_query = Query::new(
+        let edge_data: Vec<HashMap<&str, BoltType>> = edges
+            .iter()
+            .map(|e| {
+                let mut map = HashMap::new();
+                map.insert("from", BoltType::String(e.from_path.clone().into()));
+                map.insert("to", BoltType::String(e.to_path.clone().into()));
+                map.insert("module", BoltType::String(e.module.clone().into()));
+                map.insert("line", BoltType::Integer(i64::from(e.line).into()));
+                map
+            })
+            .collect();
+
+        let query = Query::new(
             r#"
             MATCH (c:Commit {sha: $commit_sha})
-            CREATE (f:File {
-                content_hash: $content_hash,
-                path: $file_path,
-                language: $language
-            })
-            CREATE (c)-[:CONTAINS]->(f)
+            UNWIND $edges AS edge
+            MATCH (c)-[:CONTAINS]->(from:File {path: edge.from})
+            MATCH (c)-[:CONTAINS]->(to:File {path: edge.to})
+            MERGE (from)-[r:IMPORTS {module: edge.module}]->(to)
+            ON CREATE SET r.line = edge.line
             "#
             .to_string(),
         )
         .param("commit_sha", commit_sha)
-        .param("content_hash", content_hash)
-        .param("file_path", file_path)
-        .param("language", language);
+        .param("edges", edge_data);
 
-        self.graph().run(create_query).await?;
-        Ok(Some(content_hash.to_string())) // New file, needs symbol extraction
+        self.run_batch("import_edges", edges.len(), query).await?;
+        Ok(())
     }
 }
+
+/// The content hash of the stored file with `content_hash`, if any
+fn stored_file_query(content_hash: &str) -> Query {
+    Query::new(
+        r#"
+        MATCH (f:File {content_hash: $content_hash})
+        RETURN f.content_hash as hash
+        LIMIT 1
+        "#
+        .to_string(),
+    )
+    .param("content_hash", content_hash)
+}
+
+/// Link the stored file with `content_hash` to a commit
+fn link_file_query(content_hash: &str, commit_sha: &str) -> Query {
+    Query::new(
+        r#"
+        MATCH (f:File {content_hash: $content_hash})
+        MATCH (c:Commit {sha: $commit_sha})
+        MERGE (c)-[:CONTAINS]->(f)
+        "#
+        .to_string(),
+    )
+    .param("content_hash", content_hash)
+    .param("commit_sha", commit_sha)
+}
+
+/// Create a File node contained in a commit
+fn create_file_query(
+    file_path: &str,
+    content_hash: &str,
+    language: &str,
+    commit_sha: &str,
+) -> Query {
+    Query::new(
+        r#"
+        MATCH (c:Commit {sha: $commit_sha})
+        CREATE (f:File {
+            content_hash: $content_hash,
+            path: $file_path,
+            language: $language,
+            created_at: timestamp()
+        })
+        CREATE (c)-[:CONTAINS]->(f)
+        "#
+        .to_string(),
+    )
+    .param("commit_sha", commit_sha)
+    .param("content_hash", content_hash)
+    .param("file_path", file_path)
+    .param("language", language)
+}
+
+/// Set the size measures of a file, symbol counts as one
+/// `symbols_<kind>` property per kind
+fn file_metrics_query(content_hash: &str, metrics: &FileMetrics) -> Query {
+    let count = |n: usize| BoltType::Integer(i64::try_from(n).unwrap_or(i64::MAX).into());
+    let mut properties: HashMap<String, BoltType> = metrics
+        .symbol_counts
+        .iter()
+        .map(|(kind, n)| (format!("{SYMBOL_COUNT_PREFIX}{kind}"), count(*n)))
+        .collect();
+    properties.insert("line_count".to_string(), count(metrics.line_count));
+    properties.insert("byte_size".to_string(), count(metrics.byte_size));
+    properties.insert("symbol_count".to_string(), count(metrics.symbol_count()));
+
+    Query::new(
+        r#"
+        MATCH (f:File {content_hash: $content_hash})
+        SET f += $metrics
+        "#
+        .to_string(),
+    )
+    .param("content_hash", content_hash)
+    .param("metrics", properties)
+}
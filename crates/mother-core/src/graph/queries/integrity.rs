@@ -0,0 +1,134 @@
+//! Integrity check Neo4j queries
+
+use neo4rs::Query;
+
+use super::Neo4jClient;
+use crate::graph::integrity::{
+    dangling_detail, edge_subject, range_detail, IntegrityCheck, IntegrityIssue, IntegrityRepair,
+};
+use crate::graph::neo4j::Neo4jError;
+
+/// Reference and call edges with a source or target that is not a symbol
+/// defined in a file; external symbols are valid targets
+const DANGLING_EDGES: &str = r#"
+    MATCH (a)-[r:REFERENCES|CALLS]->(b)
+    WITH a, r, b,
+         (NOT a:Symbol OR a.id IS NULL OR NOT (a)-[:DEFINED_IN]->(:File)) AS source_missing,
+         (NOT b:ExternalSymbol
+          AND (NOT b:Symbol OR b.id IS NULL OR NOT (b)-[:DEFINED_IN]->(:File))) AS target_missing
+    WHERE source_missing OR target_missing
+"#;
+
+impl Neo4jClient {
+    /// Find problems in the stored graph, ordered by check and subject
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, Neo4jError> {
+        let mut issues = Vec::new();
+
+        let query = format!(
+            "{DANGLING_EDGES}
+            RETURN coalesce(a.id, '') AS source, type(r) AS kind, coalesce(b.id, '') AS target,
+                   source_missing, target_missing"
+        );
+        let mut result = self.graph().execute(Query::new(query)).await?;
+        while let Some(row) = result.next().await? {
+            let source: String = row.get("source").unwrap_or_default();
+            let kind: String = row.get("kind").unwrap_or_default();
+            let target: String = row.get("target").unwrap_or_default();
+            issues.push(IntegrityIssue {
+                check: IntegrityCheck::DanglingEdge,
+                subject: edge_subject(&source, &kind, &target),
+                detail: dangling_detail(
+                    row.get("source_missing").unwrap_or_default(),
+                    row.get("target_missing").unwrap_or_default(),
+                ),
+            });
+        }
+
+        let query = Query::new(
+            r#"
+            MATCH (f:File)
+            WHERE NOT (:Commit)-[:CONTAINS]->(f)
+            RETURN f.path AS path, f.content_hash AS content_hash
+            "#
+            .to_string(),
+        );
+        let mut result = self.graph().execute(query).await?;
+        while let Some(row) = result.next().await? {
+            let hash: String = row.get("content_hash").unwrap_or_default();
+            issues.push(IntegrityIssue {
+                check: IntegrityCheck::OrphanFile,
+                subject: row.get("path").unwrap_or_default(),
+                detail: format!("no commit contains content {hash}"),
+            });
+        }
+
+        let query = Query::new(
+            r#"
+            MATCH (s:Symbol)
+            WHERE s.end_line < s.start_line
+            RETURN s.qualified_name AS qualified_name, s.file_path AS file_path,
+                   s.start_line AS start_line, s.end_line AS end_line
+            "#
+            .to_string(),
+        );
+        let mut result = self.graph().execute(query).await?;
+        while let Some(row) = result.next().await? {
+            let file_path: String = row.get("file_path").unwrap_or_default();
+            issues.push(IntegrityIssue {
+                check: IntegrityCheck::InvertedRange,
+                subject: row.get("qualified_name").unwrap_or_default(),
+                detail: range_detail(
+                    &file_path,
+                    row.get("start_line").unwrap_or_default(),
+                    row.get("end_line").unwrap_or_default(),
+                ),
+            });
+        }
+
+        let query = Query::new(
+            r#"
+            MATCH (s:Symbol)
+            WHERE s.id IS NOT NULL
+            WITH s.id AS id, count(s) AS symbols
+            WHERE symbols > 1
+            RETURN id, symbols
+            "#
+            .to_string(),
+        );
+        let mut result = self.graph().execute(query).await?;
+        while let Some(row) = result.next().await? {
+            let symbols: i64 = row.get("symbols").unwrap_or_default();
+            issues.push(IntegrityIssue {
+                check: IntegrityCheck::DuplicateSymbolId,
+                subject: row.get("id").unwrap_or_default(),
+                detail: format!("shared by {symbols} symbols"),
+            });
+        }
+
+        issues.sort();
+        Ok(issues)
+    }
+
+    /// Delete dangling edges, then files no commit contains
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn repair_integrity(&self) -> Result<IntegrityRepair, Neo4jError> {
+        let edges = self
+            .delete_count(Query::new(format!(
+                "{DANGLING_EDGES}
+                DELETE r
+                RETURN count(r) AS deleted"
+            )))
+            .await?;
+        let (files, symbols) = self.delete_orphan_files().await?;
+        Ok(IntegrityRepair {
+            edges,
+            files,
+            symbols,
+        })
+    }
+}
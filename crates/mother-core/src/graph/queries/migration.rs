@@ -0,0 +1,49 @@
+//! Migration Neo4j queries: Read and advance the graph's schema version
+
+use neo4rs::Query;
+
+use super::Neo4jClient;
+use crate::graph::migrations::Migration;
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Schema version stored in the `SchemaVersion` node, 0 if the graph
+    /// was never migrated
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn schema_version(&self) -> Result<u32, Neo4jError> {
+        let query =
+            Query::new("MATCH (v:SchemaVersion) RETURN max(v.version) AS version".to_string());
+        let mut result = self.graph().execute(query).await?;
+        let version: i64 = match result.next().await? {
+            Some(row) => row.get("version").unwrap_or(0),
+            None => 0,
+        };
+        Ok(u32::try_from(version).unwrap_or(0))
+    }
+
+    /// Run migrations in order, recording each version once its statements
+    /// succeeded
+    ///
+    /// # Errors
+    /// Returns an error if a statement fails; later migrations are not run.
+    pub async fn apply_migrations(&self, migrations: &[Migration]) -> Result<(), Neo4jError> {
+        for migration in migrations {
+            tracing::info!(
+                "Applying schema migration {}: {}",
+                migration.version,
+                migration.description
+            );
+            for statement in migration.neo4j {
+                self.graph()
+                    .run(Query::new((*statement).to_string()))
+                    .await?;
+            }
+            let query = Query::new("MERGE (v:SchemaVersion) SET v.version = $version".to_string())
+                .param("version", i64::from(migration.version));
+            self.graph().run(query).await?;
+        }
+        Ok(())
+    }
+}
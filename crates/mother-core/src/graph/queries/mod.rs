@@ -1,12 +1,34 @@
 //! Neo4j query modules organized by entity
 
+mod backup;
+mod contract;
+mod diagnostic;
+mod diff;
+mod directory;
+mod export;
 mod file;
+mod integrity;
+mod migration;
+mod module;
+mod params;
 mod read;
+mod repository;
 mod scan;
+mod search;
+mod signature;
+mod similar;
+mod store;
 mod symbol;
 
 // Re-export Neo4jClient for the impl blocks
 pub(super) use super::neo4j::Neo4jClient;
 
+pub use params::{parse_assignment, ParamValue, QueryParams};
+
 // Re-export query result types
-pub use read::{FileResult, GraphStats, ReferenceResult, SymbolResult};
+pub use read::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, ImporterResult,
+    Page, RawRow, ReferenceDirection, ReferenceGroup, ReferenceGrouping, ReferenceResult,
+    ScanResult, ScanRunResult, SortKey, SymbolMatch, SymbolResult, SymbolVersionResult, TypeUsage,
+    TypeUsageResult, DEFAULT_LIMIT,
+};
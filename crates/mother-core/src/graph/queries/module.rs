@@ -0,0 +1,119 @@
+//! Module hierarchy Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+
+use super::Neo4jClient;
+use crate::graph::model::ModuleNode;
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Replace the Module nodes of a commit
+    ///
+    /// Each module hangs off the commit with HAS_MODULE and CONTAINS its
+    /// submodules and the symbols defined directly in it.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), Neo4jError> {
+        let clear = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_MODULE]->(m:Module)
+            DETACH DELETE m
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+        self.graph().run(clear).await?;
+        if modules.is_empty() {
+            return Ok(());
+        }
+
+        let module_data: Vec<HashMap<&str, BoltType>> = modules
+            .iter()
+            .map(|m| {
+                let mut map = HashMap::new();
+                map.insert("path", BoltType::String(m.path.clone().into()));
+                map.insert("name", BoltType::String(m.name.clone().into()));
+                map.insert(
+                    "parent",
+                    BoltType::String(m.parent.clone().unwrap_or_default().into()),
+                );
+                map.insert("language", BoltType::String(m.language.clone().into()));
+                map.insert("symbol_ids", m.symbol_ids.clone().into());
+                map
+            })
+            .collect();
+
+        let create = Query::new(
+            r#"
+            MATCH (c:Commit {sha: $commit_sha})
+            UNWIND $modules AS module
+            CREATE (c)-[:HAS_MODULE]->(m:Module {commit_sha: $commit_sha, path: module.path})
+            SET m.name = module.name, m.language = module.language
+            WITH m, module
+            UNWIND module.symbol_ids AS symbol_id
+            MATCH (s:Symbol {id: symbol_id})
+            MERGE (m)-[:CONTAINS]->(s)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("modules", module_data.clone());
+        self.run_batch("modules", modules.len(), create).await?;
+
+        let link = Query::new(
+            r#"
+            UNWIND $modules AS module
+            WITH module WHERE module.parent <> ''
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_MODULE]->(parent:Module {path: module.parent})
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_MODULE]->(child:Module {path: module.path})
+            MERGE (parent)-[:CONTAINS]->(child)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("modules", module_data);
+        self.run_batch("module_parents", modules.len(), link)
+            .await?;
+        Ok(())
+    }
+
+    /// Modules of a commit with the symbols directly in them
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:HAS_MODULE]->(m:Module)
+            OPTIONAL MATCH (parent:Module)-[:CONTAINS]->(m)
+            OPTIONAL MATCH (m)-[:CONTAINS]->(s:Symbol)
+            WITH m, parent, s ORDER BY s.id
+            RETURN m.path AS path, m.name AS name, m.language AS language,
+                   parent.path AS parent, collect(s.id) AS symbol_ids
+            ORDER BY path
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut modules = Vec::new();
+        while let Some(row) = result.next().await? {
+            modules.push(ModuleNode {
+                path: row.get("path").unwrap_or_default(),
+                name: row.get("name").unwrap_or_default(),
+                parent: row.get("parent").ok(),
+                language: row.get("language").unwrap_or_default(),
+                symbol_ids: row.get("symbol_ids").unwrap_or_default(),
+            });
+        }
+        Ok(modules)
+    }
+}
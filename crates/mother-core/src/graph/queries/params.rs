@@ -0,0 +1,164 @@
+//! Query parameters: Pass user input to raw queries as bound values
+//!
+//! Values given with `query raw --param key=value` are never spliced into
+//! the query text. Neo4j receives them as Bolt parameters (`$key`) and
+//! SQLite binds them to its named parameters (`$key`, `:key` or `@key`).
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use neo4rs::{BoltType, Query};
+use serde::Serialize;
+
+/// A value bound to a named query parameter
+///
+/// [Parsed](Self::from_text) from text as `null`, `true`/`false`, an integer or a float, and
+/// as a string otherwise. Quoting with `'` or `"` forces a string, so `'42'`
+/// stays text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl ParamValue {
+    /// Parse a value given as text
+    #[must_use]
+    pub fn from_text(s: &str) -> Self {
+        let quoted = ['\'', '"']
+            .into_iter()
+            .filter(|_| s.len() >= 2)
+            .find_map(|q| s.strip_prefix(q).and_then(|rest| rest.strip_suffix(q)));
+        if let Some(text) = quoted {
+            return Self::String(text.to_string());
+        }
+        match s {
+            "null" => Self::Null,
+            "true" => Self::Bool(true),
+            "false" => Self::Bool(false),
+            // Rust also parses `inf` and `NaN` as floats
+            _ if !s.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                Self::String(s.to_string())
+            }
+            _ => s
+                .parse()
+                .map(Self::Integer)
+                .or_else(|_| s.parse().map(Self::Float))
+                .unwrap_or_else(|_| Self::String(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Integer(i) => write!(f, "{i}"),
+            Self::Float(x) => write!(f, "{x}"),
+            Self::String(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<&ParamValue> for BoltType {
+    fn from(value: &ParamValue) -> Self {
+        match value {
+            ParamValue::Null => BoltType::Null(neo4rs::BoltNull),
+            ParamValue::Bool(b) => BoltType::from(*b),
+            ParamValue::Integer(i) => BoltType::from(*i),
+            ParamValue::Float(x) => BoltType::from(*x),
+            ParamValue::String(s) => BoltType::from(s.as_str()),
+        }
+    }
+}
+
+impl rusqlite::ToSql for ParamValue {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        use rusqlite::types::{ToSqlOutput, Value};
+        Ok(match self {
+            Self::Null => ToSqlOutput::Owned(Value::Null),
+            Self::Bool(b) => ToSqlOutput::Owned(Value::Integer(i64::from(*b))),
+            Self::Integer(i) => ToSqlOutput::Owned(Value::Integer(*i)),
+            Self::Float(x) => ToSqlOutput::Owned(Value::Real(*x)),
+            Self::String(s) => ToSqlOutput::from(s.as_str()),
+        })
+    }
+}
+
+/// Named parameters of a raw query
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueryParams(BTreeMap<String, ParamValue>);
+
+impl QueryParams {
+    /// No parameters
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter, replacing an earlier one of the same name
+    ///
+    /// # Errors
+    /// Returns an error if the name is not a valid parameter name.
+    pub fn insert(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        if !is_valid_name(name) {
+            return Err(format!("Invalid parameter name: '{name}'"));
+        }
+        self.0.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Value of the parameter `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ParamValue> {
+        self.0.get(name)
+    }
+
+    /// Parameters in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ParamValue)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Bind every parameter to a Neo4j query
+    #[must_use]
+    pub fn bind(&self, query: Query) -> Query {
+        self.iter().fold(query, |query, (name, value)| {
+            query.param(name, BoltType::from(value))
+        })
+    }
+}
+
+/// Parse a `key=value` assignment, as given to `--param`
+///
+/// # Errors
+/// Returns an error if there is no `=` or the key is not a valid parameter
+/// name.
+pub fn parse_assignment(assignment: &str) -> Result<(String, ParamValue), String> {
+    let (name, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| format!("Expected key=value, got '{assignment}'"))?;
+    if !is_valid_name(name) {
+        return Err(format!("Invalid parameter name: '{name}'"));
+    }
+    Ok((name.to_string(), ParamValue::from_text(value)))
+}
+
+/// Whether `name` can be used as `$name`: a letter or underscore followed
+/// by letters, digits and underscores
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
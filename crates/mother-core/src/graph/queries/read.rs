@@ -1,12 +1,42 @@
 //! Read-only query operations for Neo4j
 
-use neo4rs::Query;
+use std::collections::BTreeMap;
 
-use super::Neo4jClient;
+use neo4rs::{Query, Row};
+use serde::Serialize;
+
+use super::{Neo4jClient, QueryParams};
 use crate::graph::neo4j::Neo4jError;
+use crate::graph::read_only::write_clause;
+use crate::graph::scope::PathScope;
+
+/// Extra `WHERE` condition restricting a path property to a scope
+///
+/// `STARTS WITH` on the literal prefix is served by the `symbol_file_path`
+/// index; the pattern then checks globs exactly.
+const SCOPE_CONDITION: &str = "AND {path} STARTS WITH $scope_prefix AND {path} =~ $scope_pattern";
+
+/// Add the scope condition and its parameters to a symbol query
+pub(super) fn scoped_query(cypher: &str, scope: Option<&PathScope>) -> Query {
+    scoped_query_on(cypher, "s.file_path", scope)
+}
+
+/// Add the scope condition on the `path` property and its parameters to a
+/// query, in place of its `{scope}` placeholder
+pub(super) fn scoped_query_on(cypher: &str, path: &str, scope: Option<&PathScope>) -> Query {
+    match scope {
+        Some(scope) => {
+            let condition = SCOPE_CONDITION.replace("{path}", path);
+            Query::new(cypher.replace("{scope}", &condition))
+                .param("scope_prefix", scope.prefix())
+                .param("scope_pattern", scope.pattern())
+        }
+        None => Query::new(cypher.replace("{scope}", "")),
+    }
+}
 
 /// A symbol result from a query
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SymbolResult {
     pub id: String,
     pub name: String,
@@ -17,8 +47,23 @@ pub struct SymbolResult {
     pub end_line: i64,
 }
 
+/// A symbol found by a full-text search, with its relevance
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolMatch {
+    pub id: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: i64,
+    pub end_line: i64,
+    /// Relevance score of the backend's ranking; higher is better, but
+    /// scores are only comparable within one search
+    pub score: f64,
+}
+
 /// A reference result from a query
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReferenceResult {
     pub source_name: String,
     pub source_file: String,
@@ -28,31 +73,283 @@ pub struct ReferenceResult {
     pub target_line: i64,
 }
 
+/// Which end of `REFERENCES` edges a reference query matches by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceDirection {
+    /// References to the named symbols
+    To,
+    /// References made from the named symbols
+    From,
+}
+
+/// Where a type appears in the functions a type query matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeUsage {
+    /// Functions with a parameter of the type
+    Parameter,
+    /// Functions returning the type
+    Return,
+}
+
+/// How reference query results are aggregated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceGrouping {
+    /// One row per distinct symbol, file and line
+    Unique,
+    /// One row per symbol
+    Symbol,
+    /// One row per file
+    File,
+}
+
+/// Rows a list query returns when its [`Page`] sets no limit
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Key a list query orders its rows by
+///
+/// Keys a query has no column for fall back to its default order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Symbol name: the matched symbols, or the other end of references
+    Name,
+    /// Symbol kind, then name
+    Kind,
+    /// File path, then line
+    File,
+    /// File language, then path
+    Language,
+    /// Symbol count of a file, largest first
+    Symbols,
+}
+
+/// Window and order of the rows a list query returns
+///
+/// Applied in the query itself, so only the requested rows leave the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Page {
+    /// Most rows returned; `None` returns [`DEFAULT_LIMIT`]
+    pub limit: Option<usize>,
+    /// Rows skipped before the first one returned
+    pub offset: usize,
+    /// Order of the rows; `None` keeps the query's default order
+    pub sort: Option<SortKey>,
+}
+
+impl Page {
+    /// Most rows returned
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT)
+    }
+}
+
+/// Bind the `$skip` and `$limit` parameters of a paged query
+pub(super) fn paged(query: Query, page: Page) -> Query {
+    query
+        .param("skip", i64::try_from(page.offset).unwrap_or(i64::MAX))
+        .param("limit", i64::try_from(page.limit()).unwrap_or(i64::MAX))
+}
+
+/// Cypher order of symbols matched as `s`
+fn symbol_order(sort: Option<SortKey>) -> &'static str {
+    match sort {
+        Some(SortKey::File) => "s.file_path, s.start_line",
+        Some(SortKey::Kind) => "s.kind, s.name",
+        _ => "s.name, s.file_path, s.start_line",
+    }
+}
+
+/// References aggregated by a [`ReferenceGrouping`]
+///
+/// Describes the other end of the references: the referencing symbols for
+/// references to a symbol, the referenced ones for references from it. Like
+/// [`ReferenceResult`], `lines` are the reference lines in the former case
+/// and the definition lines in the latter. `count` is the number of
+/// references in the group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReferenceGroup {
+    pub symbols: Vec<String>,
+    pub file: String,
+    pub lines: Vec<i64>,
+    pub count: i64,
+}
+
+impl ReferenceGroup {
+    /// A group with its symbols and lines sorted
+    #[must_use]
+    pub fn new(file: String, mut symbols: Vec<String>, mut lines: Vec<i64>, count: i64) -> Self {
+        symbols.sort();
+        lines.sort_unstable();
+        Self {
+            symbols,
+            file,
+            lines,
+            count,
+        }
+    }
+}
+
+/// One version of a symbol, as seen from the commits that contain it
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolVersionResult {
+    pub id: String,
+    pub identity_key: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: i64,
+    pub end_line: i64,
+    pub signature: String,
+    pub content_hash: String,
+    pub commit_shas: Vec<String>,
+}
+
+/// A diagnostic in a file of a commit
+///
+/// `code`, `source` and `symbol` are empty when unknown; `symbol` is the
+/// qualified name of the innermost symbol containing the diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticResult {
+    pub severity: String,
+    pub code: String,
+    pub source: String,
+    pub message: String,
+    pub file_path: String,
+    pub start_line: i64,
+    pub start_col: i64,
+    pub symbol: String,
+}
+
 /// A file result from a query
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FileResult {
     pub path: String,
     pub language: String,
     pub symbol_count: i64,
 }
 
+/// Size measures of a file, returned by [`Neo4jClient::file_metrics`]
+///
+/// Measures are 0 for files whose symbols were never extracted.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMetricsResult {
+    pub path: String,
+    pub language: String,
+    pub line_count: i64,
+    pub byte_size: i64,
+    pub symbol_count: i64,
+    /// Symbols by kind
+    pub symbol_counts: BTreeMap<String, i64>,
+}
+
+/// A file importing a module, returned by [`Neo4jClient::find_importers`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ImporterResult {
+    pub path: String,
+    pub language: String,
+    /// Matching module paths as written in the file
+    pub modules: Vec<String>,
+}
+
+/// A function using a type, returned by [`Neo4jClient::find_functions_by_type`]
+///
+/// `parameter` and `position` are set for parameter types only.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeUsageResult {
+    pub qualified_name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: i64,
+    pub parameter: Option<String>,
+    pub position: Option<i64>,
+    pub type_name: String,
+}
+
+/// A scanned commit, returned by [`Neo4jClient::recent_scans`]
+///
+/// `version` and `scanned_at` come from the commit's latest scan run;
+/// `scanned_at` is an RFC 3339 timestamp.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanResult {
+    pub commit_sha: String,
+    pub branch: String,
+    pub version: String,
+    pub scanned_at: String,
+}
+
+/// A recorded scan run, returned by [`Neo4jClient::scan_runs`]
+///
+/// `scanned_at` is an RFC 3339 timestamp.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanRunResult {
+    pub id: String,
+    pub commit_sha: String,
+    pub branch: String,
+    pub version: String,
+    pub repo_path: String,
+    pub scanned_at: String,
+    /// ID of the run scanned before this one by a history backfill, empty
+    /// for standalone scans
+    pub previous_run: String,
+    /// Tags added after the scan, sorted
+    pub tags: Vec<String>,
+    /// Note added after the scan, empty if none
+    pub note: String,
+    /// `in_progress`, `completed` or `failed`
+    pub status: String,
+}
+
+/// What deleting scan runs removed, returned by
+/// [`Neo4jClient::delete_scan_run`]
+///
+/// Commits go once no run is left for them; files and their symbols once no
+/// commit contains them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DeleteSummary {
+    pub runs: usize,
+    pub commits: usize,
+    pub files: usize,
+    pub symbols: usize,
+}
+
+impl std::ops::AddAssign for DeleteSummary {
+    fn add_assign(&mut self, other: Self) {
+        self.runs += other.runs;
+        self.commits += other.commits;
+        self.files += other.files;
+        self.symbols += other.symbols;
+    }
+}
+
 impl Neo4jClient {
     /// Find symbols by name pattern (case-insensitive contains)
     ///
+    /// With a scope only symbols whose file path is inside it are returned.
+    ///
     /// # Errors
     /// Returns an error if the query fails.
-    pub async fn find_symbols(&self, pattern: &str) -> Result<Vec<SymbolResult>, Neo4jError> {
-        let query = Query::new(
-            r#"
+    pub async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, Neo4jError> {
+        let query = scoped_query(
+            &format!(
+                r#"
             MATCH (s:Symbol)
             WHERE toLower(s.name) CONTAINS toLower($pattern)
+            {{scope}}
             RETURN s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line, s.end_line
-            ORDER BY s.name
-            LIMIT 100
-            "#
-            .to_string(),
+            ORDER BY {}
+            SKIP $skip LIMIT $limit
+            "#,
+                symbol_order(page.sort)
+            ),
+            scope,
         )
         .param("pattern", pattern);
+        let query = paged(query, page);
 
         let mut result = self.graph().execute(query).await?;
         let mut symbols = Vec::new();
@@ -106,6 +403,139 @@ impl Neo4jClient {
         Ok(symbols)
     }
 
+    /// Symbols a commit changed, ordered by file and line
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn modified_symbols(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<SymbolResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (:Commit {sha: $commit_sha})-[:MODIFIED]->(s:Symbol)
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line, s.end_line
+            ORDER BY s.file_path, s.start_line
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut symbols = Vec::new();
+        while let Some(row) = result.next().await? {
+            symbols.push(SymbolResult {
+                id: row.get("s.id").unwrap_or_default(),
+                name: row.get("s.name").unwrap_or_default(),
+                qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+                kind: row.get("s.kind").unwrap_or_default(),
+                file_path: row.get("s.file_path").unwrap_or_default(),
+                start_line: row.get("s.start_line").unwrap_or(0),
+                end_line: row.get("s.end_line").unwrap_or(0),
+            });
+        }
+        Ok(symbols)
+    }
+
+    /// Find symbols by name pattern as they existed at a specific commit
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, Neo4jError> {
+        let query = scoped_query(
+            &format!(
+                r#"
+            MATCH (c:Commit {{sha: $commit_sha}})-[:CONTAINS]->(:File)<-[:DEFINED_IN]-(s:Symbol)
+            WHERE toLower(s.name) CONTAINS toLower($pattern)
+            {{scope}}
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line, s.end_line
+            ORDER BY {}
+            SKIP $skip LIMIT $limit
+            "#,
+                symbol_order(page.sort)
+            ),
+            scope,
+        )
+        .param("pattern", pattern)
+        .param("commit_sha", commit_sha);
+        let query = paged(query, page);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut symbols = Vec::new();
+
+        while let Some(row) = result.next().await? {
+            symbols.push(SymbolResult {
+                id: row.get("s.id").unwrap_or_default(),
+                name: row.get("s.name").unwrap_or_default(),
+                qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+                kind: row.get("s.kind").unwrap_or_default(),
+                file_path: row.get("s.file_path").unwrap_or_default(),
+                start_line: row.get("s.start_line").unwrap_or(0),
+                end_line: row.get("s.end_line").unwrap_or(0),
+            });
+        }
+
+        Ok(symbols)
+    }
+
+    /// List every stored version of the symbols with a given qualified name
+    ///
+    /// Versions are grouped by `SymbolIdentity`, each listing the commits whose
+    /// file snapshot contains it. Identities linked by `SAME_AS` and
+    /// `EVOLVED_FROM` edges are included, so a renamed symbol's history
+    /// covers its names before and after the rename.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (named:SymbolIdentity)
+            WHERE named.qualified_name = $qualified_name OR named.name = $qualified_name
+            MATCH (named)<-[:SNAPSHOT_OF]-(:Symbol)-[:SAME_AS|EVOLVED_FROM*0..]-(s:Symbol)
+            WITH DISTINCT s
+            MATCH (i:SymbolIdentity)<-[:SNAPSHOT_OF]-(s)-[:DEFINED_IN]->(f:File)
+            OPTIONAL MATCH (c:Commit)-[:CONTAINS]->(f)
+            WITH i, s, f, collect(DISTINCT c.sha) AS commit_shas
+            RETURN s.id, i.key, s.qualified_name, s.kind, s.file_path, s.start_line,
+                   s.end_line, s.signature, f.content_hash, commit_shas
+            ORDER BY i.key, s.start_line
+            LIMIT 100
+            "#
+            .to_string(),
+        )
+        .param("qualified_name", qualified_name);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut versions = Vec::new();
+
+        while let Some(row) = result.next().await? {
+            versions.push(SymbolVersionResult {
+                id: row.get("s.id").unwrap_or_default(),
+                identity_key: row.get("i.key").unwrap_or_default(),
+                qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+                kind: row.get("s.kind").unwrap_or_default(),
+                file_path: row.get("s.file_path").unwrap_or_default(),
+                start_line: row.get("s.start_line").unwrap_or(0),
+                end_line: row.get("s.end_line").unwrap_or(0),
+                signature: row.get("s.signature").unwrap_or_default(),
+                content_hash: row.get("f.content_hash").unwrap_or_default(),
+                commit_shas: row.get("commit_shas").unwrap_or_default(),
+            });
+        }
+
+        Ok(versions)
+    }
+
     /// Find what references a given symbol (by name)
     ///
     /// # Errors
@@ -113,18 +543,23 @@ impl Neo4jClient {
     pub async fn find_references_to(
         &self,
         symbol_name: &str,
+        page: Page,
     ) -> Result<Vec<ReferenceResult>, Neo4jError> {
-        let query = Query::new(
+        let order = match page.sort {
+            Some(SortKey::Name) => "source.name, source.file_path, r.line",
+            _ => "source.file_path, r.line",
+        };
+        let query = Query::new(format!(
             r#"
             MATCH (source:Symbol)-[r:REFERENCES]->(target:Symbol)
             WHERE target.name = $symbol_name
             RETURN source.name, source.file_path, r.line, target.name, target.file_path, target.start_line
-            ORDER BY source.file_path, r.line
-            LIMIT 100
+            ORDER BY {order}
+            SKIP $skip LIMIT $limit
             "#
-            .to_string(),
-        )
+        ))
         .param("symbol_name", symbol_name);
+        let query = paged(query, page);
 
         let mut result = self.graph().execute(query).await?;
         let mut refs = Vec::new();
@@ -150,18 +585,23 @@ impl Neo4jClient {
     pub async fn find_references_from(
         &self,
         symbol_name: &str,
+        page: Page,
     ) -> Result<Vec<ReferenceResult>, Neo4jError> {
-        let query = Query::new(
+        let order = match page.sort {
+            Some(SortKey::Name) => "target.name, target.file_path, target.start_line",
+            _ => "target.file_path, target.start_line",
+        };
+        let query = Query::new(format!(
             r#"
             MATCH (source:Symbol)-[r:REFERENCES]->(target:Symbol)
             WHERE source.name = $symbol_name
             RETURN source.name, source.file_path, r.line, target.name, target.file_path, target.start_line
-            ORDER BY target.file_path, target.start_line
-            LIMIT 100
+            ORDER BY {order}
+            SKIP $skip LIMIT $limit
             "#
-            .to_string(),
-        )
+        ))
         .param("symbol_name", symbol_name);
+        let query = paged(query, page);
 
         let mut result = self.graph().execute(query).await?;
         let mut refs = Vec::new();
@@ -180,34 +620,90 @@ impl Neo4jClient {
         Ok(refs)
     }
 
-    /// List files with symbol counts
+    /// Find references to or from symbols with a name, aggregated
+    ///
+    /// Groups are ordered by file and first line.
     ///
     /// # Errors
     /// Returns an error if the query fails.
-    pub async fn list_files(&self, pattern: Option<&str>) -> Result<Vec<FileResult>, Neo4jError> {
-        let query_str = if pattern.is_some() {
+    pub async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, Neo4jError> {
+        let (matched, other, line) = match direction {
+            ReferenceDirection::To => ("target", "source", "r.line"),
+            ReferenceDirection::From => ("source", "target", "target.start_line"),
+        };
+        let keys = match grouping {
+            ReferenceGrouping::Unique => "file, name, line",
+            ReferenceGrouping::Symbol => "file, name",
+            ReferenceGrouping::File => "file",
+        };
+        let query = Query::new(format!(
             r#"
-            MATCH (f:File)
-            WHERE f.path CONTAINS $pattern
-            OPTIONAL MATCH (s:Symbol)-[:DEFINED_IN]->(f)
-            RETURN f.path, f.language, count(s) as symbol_count
-            ORDER BY f.path
-            LIMIT 100
+            MATCH (source:Symbol)-[r:REFERENCES]->(target:Symbol)
+            WHERE {matched}.name = $symbol_name
+            WITH {other}.file_path AS file, {other}.name AS name, {line} AS line
+            WITH {keys}, collect(DISTINCT name) AS symbols, collect(DISTINCT line) AS lines,
+                 count(*) AS count, min(line) AS first_line
+            RETURN file, symbols, lines, count
+            ORDER BY file, first_line
+            SKIP $skip LIMIT $limit
             "#
-        } else {
+        ))
+        .param("symbol_name", symbol_name);
+        let query = paged(query, page);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut groups = Vec::new();
+
+        while let Some(row) = result.next().await? {
+            groups.push(ReferenceGroup::new(
+                row.get("file").unwrap_or_default(),
+                row.get("symbols").unwrap_or_default(),
+                row.get("lines").unwrap_or_default(),
+                row.get("count").unwrap_or(0),
+            ));
+        }
+
+        Ok(groups)
+    }
+
+    /// List files with symbol counts
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, Neo4jError> {
+        let filter = match pattern {
+            Some(_) => "WHERE f.path CONTAINS $pattern",
+            None => "",
+        };
+        let order = match page.sort {
+            Some(SortKey::Language) => "f.language, f.path",
+            Some(SortKey::Symbols) => "symbol_count DESC, f.path",
+            _ => "f.path",
+        };
+        let mut query = Query::new(format!(
             r#"
             MATCH (f:File)
+            {filter}
             OPTIONAL MATCH (s:Symbol)-[:DEFINED_IN]->(f)
             RETURN f.path, f.language, count(s) as symbol_count
-            ORDER BY f.path
-            LIMIT 100
+            ORDER BY {order}
+            SKIP $skip LIMIT $limit
             "#
-        };
-
-        let mut query = Query::new(query_str.to_string());
+        ));
         if let Some(p) = pattern {
             query = query.param("pattern", p);
         }
+        let query = paged(query, page);
 
         let mut result = self.graph().execute(query).await?;
         let mut files = Vec::new();
@@ -223,22 +719,129 @@ impl Neo4jClient {
         Ok(files)
     }
 
-    /// Execute a raw Cypher query and return the number of rows affected
+    /// Find files that import a module
     ///
-    /// For queries that return data, use specific query methods instead.
+    /// Matches the module path as written (`crate::graph` also matches
+    /// `crate::graph::model`) or the path of an imported repository file.
     ///
     /// # Errors
     /// Returns an error if the query fails.
-    pub async fn execute_raw(&self, cypher: &str) -> Result<usize, Neo4jError> {
-        let query = Query::new(cypher.to_string());
+    pub async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (f:File)
+            WHERE any(m IN coalesce(f.imports, []) WHERE m = $module
+                OR m STARTS WITH $module + '::'
+                OR m STARTS WITH $module + '.'
+                OR m STARTS WITH $module + '/')
+            RETURN f.path AS path, f.language AS language,
+                   [m IN f.imports WHERE m STARTS WITH $module] AS modules
+            UNION
+            MATCH (f:File)-[r:IMPORTS]->(t:File)
+            WHERE t.path ENDS WITH $module
+            RETURN f.path AS path, f.language AS language, collect(DISTINCT r.module) AS modules
+            "#
+            .to_string(),
+        )
+        .param("module", module);
+
         let mut result = self.graph().execute(query).await?;
-        let mut count = 0;
+        let mut importers: Vec<ImporterResult> = Vec::new();
 
-        while let Some(_row) = result.next().await? {
-            count += 1;
+        while let Some(row) = result.next().await? {
+            let path: String = row.get("path").unwrap_or_default();
+            let modules: Vec<String> = row.get("modules").unwrap_or_default();
+            // File versions share a path; merge them into a single entry
+            match importers.iter_mut().find(|i| i.path == path) {
+                Some(existing) => {
+                    for m in modules {
+                        if !existing.modules.contains(&m) {
+                            existing.modules.push(m);
+                        }
+                    }
+                }
+                None => importers.push(ImporterResult {
+                    path,
+                    language: row.get("language").unwrap_or_default(),
+                    modules,
+                }),
+            }
         }
 
-        Ok(count)
+        importers.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(importers)
+    }
+
+    /// Execute a raw Cypher query with `params` as its `$` parameters and
+    /// return the number of rows affected
+    ///
+    /// For queries that return data, use specific query methods instead.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails, or contains a mutating clause
+    /// and the client is read-only.
+    pub async fn execute_raw(
+        &self,
+        cypher: &str,
+        params: &QueryParams,
+    ) -> Result<usize, Neo4jError> {
+        if let Some(clause) = write_clause(cypher) {
+            self.ensure_writable(&format!("{clause} clause in raw query"))?;
+        }
+        let query = params.bind(Query::new(cypher.to_string()));
+        Ok(self.raw_rows(query).await?.len())
+    }
+
+    /// Execute a raw Cypher query with `params` as its `$` parameters and
+    /// return its rows, with columns in name order
+    ///
+    /// # Errors
+    /// Returns an error if the query fails, a row can't be converted, or the
+    /// query contains a mutating clause and the client is read-only.
+    pub async fn fetch_raw(
+        &self,
+        cypher: &str,
+        params: &QueryParams,
+    ) -> Result<Vec<RawRow>, Neo4jError> {
+        if let Some(clause) = write_clause(cypher) {
+            self.ensure_writable(&format!("{clause} clause in raw query"))?;
+        }
+        let query = params.bind(Query::new(cypher.to_string()));
+        let mut rows = Vec::new();
+
+        for row in self.raw_rows(query).await? {
+            let mut row: RawRow = row
+                .to_strict()
+                .map_err(|e| Neo4jError::Query(format!("Unreadable row: {e}")))?;
+            row.sort_keys();
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    /// Run a raw query and collect its rows
+    ///
+    /// Read-only clients run it in a transaction they roll back, so Neo4j
+    /// discards anything the query wrote in it, including writes by
+    /// procedures such as `apoc.cypher.doIt` that the clause check can't see
+    /// into.
+    async fn raw_rows(&self, query: Query) -> Result<Vec<Row>, Neo4jError> {
+        let mut rows = Vec::new();
+        if self.read_only {
+            let mut txn = self.graph().start_txn().await?;
+            let mut result = txn.execute(query).await?;
+            while let Some(row) = result.next(txn.handle()).await? {
+                rows.push(row);
+            }
+            txn.rollback().await?;
+        } else {
+            let mut result = self.graph().execute(query).await?;
+            while let Some(row) = result.next().await? {
+                rows.push(row);
+            }
+        }
+        Ok(rows)
     }
 
     /// Get graph statistics
@@ -300,8 +903,11 @@ impl Neo4jClient {
     }
 }
 
+/// A row of a raw query, keyed by column name
+pub type RawRow = serde_json::Map<String, serde_json::Value>;
+
 /// Graph statistics
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct GraphStats {
     pub commits: i64,
     pub files: i64,
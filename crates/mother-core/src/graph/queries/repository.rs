@@ -0,0 +1,129 @@
+//! Repository Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+
+use super::Neo4jClient;
+use crate::graph::model::{RepositoryDependency, RepositoryNode};
+use crate::graph::neo4j::Neo4jError;
+
+impl Neo4jClient {
+    /// Create or replace the Repository node of a path
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), Neo4jError> {
+        let query = Query::new(
+            r#"
+            MERGE (r:Repository {path: $path})
+            SET r.packages = $packages, r.dependencies = $dependencies
+            "#
+            .to_string(),
+        )
+        .param("path", repository.path.clone())
+        .param("packages", repository.packages.clone())
+        .param("dependencies", repository.dependencies.clone());
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Every Repository node, ordered by path
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn repositories(&self) -> Result<Vec<RepositoryNode>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (r:Repository)
+            RETURN r.path AS path, r.packages AS packages, r.dependencies AS dependencies
+            ORDER BY path
+            "#
+            .to_string(),
+        );
+
+        let mut result = self.graph().execute(query).await?;
+        let mut repositories = Vec::new();
+        while let Some(row) = result.next().await? {
+            repositories.push(RepositoryNode {
+                path: row.get("path").unwrap_or_default(),
+                packages: row.get("packages").unwrap_or_default(),
+                dependencies: row.get("dependencies").unwrap_or_default(),
+            });
+        }
+        Ok(repositories)
+    }
+
+    /// Replace every DEPENDS_ON edge between Repository nodes
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), Neo4jError> {
+        let clear = Query::new(
+            r#"
+            MATCH (:Repository)-[d:DEPENDS_ON]->(:Repository)
+            DELETE d
+            "#
+            .to_string(),
+        );
+        self.graph().run(clear).await?;
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+
+        let dependency_data: Vec<HashMap<&str, BoltType>> = dependencies
+            .iter()
+            .map(|d| {
+                let mut map = HashMap::new();
+                map.insert("from", BoltType::String(d.from.clone().into()));
+                map.insert("to", BoltType::String(d.to.clone().into()));
+                map.insert("packages", d.packages.clone().into());
+                map
+            })
+            .collect();
+
+        let create = Query::new(
+            r#"
+            UNWIND $dependencies AS dependency
+            MATCH (from:Repository {path: dependency.from})
+            MATCH (to:Repository {path: dependency.to})
+            CREATE (from)-[:DEPENDS_ON {packages: dependency.packages}]->(to)
+            "#
+            .to_string(),
+        )
+        .param("dependencies", dependency_data);
+        self.run_batch("repository_dependencies", dependencies.len(), create)
+            .await?;
+        Ok(())
+    }
+
+    /// Every DEPENDS_ON edge between Repository nodes, ordered by the paths
+    /// of both
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (from:Repository)-[d:DEPENDS_ON]->(to:Repository)
+            RETURN from.path AS from, to.path AS to, d.packages AS packages
+            ORDER BY from, to
+            "#
+            .to_string(),
+        );
+
+        let mut result = self.graph().execute(query).await?;
+        let mut dependencies = Vec::new();
+        while let Some(row) = result.next().await? {
+            dependencies.push(RepositoryDependency {
+                from: row.get("from").unwrap_or_default(),
+                to: row.get("to").unwrap_or_default(),
+                packages: row.get("packages").unwrap_or_default(),
+            });
+        }
+        Ok(dependencies)
+    }
+}
@@ -3,7 +3,8 @@
 use neo4rs::Query;
 
 use super::Neo4jClient;
-use crate::graph::model::ScanRun;
+use super::{DeleteSummary, ScanResult, ScanRunResult};
+use crate::graph::model::{ScanReport, ScanRun, ScanStatus};
 use crate::graph::neo4j::Neo4jError;
 
 impl Neo4jClient {
@@ -40,7 +41,8 @@ impl Neo4jClient {
                         id: $id,
                         repo_path: $repo_path,
                         scanned_at: datetime($scanned_at),
-                        version: $version
+                        version: $version,
+                        status: $status
                     })
                     CREATE (r)-[:FOR_COMMIT]->(c)
                     "#
@@ -50,9 +52,11 @@ impl Neo4jClient {
                 .param("repo_path", scan_run.repo_path.clone())
                 .param("scanned_at", scan_run.scanned_at.to_rfc3339())
                 .param("version", scan_run.version.clone().unwrap_or_default())
+                .param("status", scan_run.status.to_string())
                 .param("commit_sha", commit_sha);
 
                 self.graph().run(query).await?;
+                self.link_previous_run(scan_run).await?;
                 return Ok(false); // Commit already exists, skip file processing
             }
         }
@@ -68,7 +72,8 @@ impl Neo4jClient {
                 id: $id,
                 repo_path: $repo_path,
                 scanned_at: datetime($scanned_at),
-                version: $version
+                version: $version,
+                status: $status
             })
             CREATE (r)-[:FOR_COMMIT]->(c)
             "#
@@ -79,9 +84,348 @@ impl Neo4jClient {
         .param("commit_sha", commit_sha)
         .param("branch", scan_run.branch.clone().unwrap_or_default())
         .param("scanned_at", scan_run.scanned_at.to_rfc3339())
-        .param("version", scan_run.version.clone().unwrap_or_default());
+        .param("version", scan_run.version.clone().unwrap_or_default())
+        .param("status", scan_run.status.to_string());
 
         self.graph().run(query).await?;
+        self.link_previous_run(scan_run).await?;
         Ok(true) // New commit, needs file processing
     }
+
+    /// Link a scan run to the run scanned before it with a PREVIOUS_RUN edge
+    async fn link_previous_run(&self, scan_run: &ScanRun) -> Result<(), Neo4jError> {
+        let Some(previous) = &scan_run.previous_run else {
+            return Ok(());
+        };
+        let query = Query::new(
+            r#"
+            MATCH (r:ScanRun {id: $id}), (p:ScanRun {id: $previous})
+            MERGE (r)-[:PREVIOUS_RUN]->(p)
+            "#
+            .to_string(),
+        )
+        .param("id", scan_run.id.clone())
+        .param("previous", previous.clone());
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Resolve a scan version to the commit it scanned
+    ///
+    /// `version` matches a scan run's version tag or a commit SHA prefix, or
+    /// with a `tag:` prefix one of the tags added by `runs tag`; with no
+    /// version the most recent scan run is used. Returns `None` if nothing
+    /// matches.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn resolve_commit(
+        &self,
+        version: Option<&str>,
+    ) -> Result<Option<String>, Neo4jError> {
+        let tag = version.and_then(|v| v.strip_prefix("tag:"));
+        let query = match (version, tag) {
+            (_, Some(tag)) => Query::new(
+                r#"
+                MATCH (r:ScanRun)-[:FOR_COMMIT]->(c:Commit)
+                WHERE $tag IN coalesce(r.tags, [])
+                RETURN c.sha AS sha
+                ORDER BY r.scanned_at DESC
+                LIMIT 1
+                "#
+                .to_string(),
+            )
+            .param("tag", tag),
+            (Some(v), None) => Query::new(
+                r#"
+                MATCH (r:ScanRun)-[:FOR_COMMIT]->(c:Commit)
+                WHERE r.version = $version OR c.sha STARTS WITH $version
+                RETURN c.sha AS sha
+                ORDER BY r.scanned_at DESC
+                LIMIT 1
+                "#
+                .to_string(),
+            )
+            .param("version", v),
+            (None, _) => Query::new(
+                r#"
+                MATCH (r:ScanRun {status: 'completed'})-[:FOR_COMMIT]->(c:Commit)
+                RETURN c.sha AS sha
+                ORDER BY r.scanned_at DESC
+                LIMIT 1
+                "#
+                .to_string(),
+            ),
+        };
+
+        let mut result = self.graph().execute(query).await?;
+        Ok(result
+            .next()
+            .await?
+            .and_then(|row| row.get::<String>("sha").ok()))
+    }
+
+    /// List the most recently scanned commits of a branch, newest first
+    ///
+    /// With no branch the branch of the most recent scan run is used. Each
+    /// commit is listed once, with the version and time of its latest scan.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (latest:ScanRun {status: 'completed'})-[:FOR_COMMIT]->(lc:Commit)
+            WITH lc ORDER BY latest.scanned_at DESC LIMIT 1
+            WITH CASE $branch WHEN '' THEN lc.branch ELSE $branch END AS branch
+            MATCH (r:ScanRun {status: 'completed'})-[:FOR_COMMIT]->(c:Commit {branch: branch})
+            WITH c, r ORDER BY r.scanned_at DESC
+            WITH c, collect(r)[0] AS r
+            RETURN c.sha AS sha, c.branch AS branch, r.version AS version,
+                   toString(r.scanned_at) AS scanned_at
+            ORDER BY r.scanned_at DESC
+            LIMIT $limit
+            "#
+            .to_string(),
+        )
+        .param("branch", branch.unwrap_or_default())
+        .param("limit", i64::try_from(limit).unwrap_or(i64::MAX));
+
+        let mut result = self.graph().execute(query).await?;
+        let mut scans = Vec::new();
+        while let Some(row) = result.next().await? {
+            scans.push(ScanResult {
+                commit_sha: row.get("sha").unwrap_or_default(),
+                branch: row.get("branch").unwrap_or_default(),
+                version: row.get("version").unwrap_or_default(),
+                scanned_at: row.get("scanned_at").unwrap_or_default(),
+            });
+        }
+        Ok(scans)
+    }
+
+    /// Store the failure report of a scan run as JSON on its node
+    ///
+    /// # Errors
+    /// Returns an error if the report cannot be serialized or the query fails.
+    pub async fn set_scan_report(&self, report: &ScanReport) -> Result<(), Neo4jError> {
+        let json = serde_json::to_string(report).map_err(|e| Neo4jError::Query(e.to_string()))?;
+        let query = Query::new("MATCH (r:ScanRun {id: $id}) SET r.report = $report".to_string())
+            .param("id", report.scan_run_id.clone())
+            .param("report", json);
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Set the `status` of a scan run node
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), Neo4jError> {
+        let query = Query::new("MATCH (r:ScanRun {id: $id}) SET r.status = $status".to_string())
+            .param("id", scan_run_id)
+            .param("status", status.to_string());
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Add a tag to the `tags` list of a scan run node, keeping it sorted
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (r:ScanRun {id: $id})
+            WHERE NOT $tag IN coalesce(r.tags, [])
+            WITH r, coalesce(r.tags, []) + $tag AS tags
+            UNWIND tags AS tag
+            WITH r, tag ORDER BY tag
+            WITH r, collect(tag) AS tags
+            SET r.tags = tags
+            "#
+            .to_string(),
+        )
+        .param("id", scan_run_id)
+        .param("tag", tag);
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Set the note of a scan run node
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), Neo4jError> {
+        let query = Query::new("MATCH (r:ScanRun {id: $id}) SET r.note = $note".to_string())
+            .param("id", scan_run_id)
+            .param("note", note);
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// The failure report stored on a scan run node
+    ///
+    /// # Errors
+    /// Returns an error if the query fails or the report cannot be parsed.
+    pub async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, Neo4jError> {
+        let query = Query::new("MATCH (r:ScanRun {id: $id}) RETURN r.report AS report".to_string())
+            .param("id", scan_run_id.to_string());
+        let mut result = self.graph().execute(query).await?;
+        let Some(row) = result.next().await? else {
+            return Ok(None);
+        };
+        let json: String = row.get("report").unwrap_or_default();
+        match Some(json).filter(|json| !json.is_empty()) {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| Neo4jError::Query(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// List every scan run, newest first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (r:ScanRun)-[:FOR_COMMIT]->(c:Commit)
+            OPTIONAL MATCH (r)-[:PREVIOUS_RUN]->(p:ScanRun)
+            RETURN r.id AS id, c.sha AS sha, c.branch AS branch, r.version AS version,
+                   r.repo_path AS repo_path, toString(r.scanned_at) AS scanned_at,
+                   p.id AS previous_run, coalesce(r.tags, []) AS tags,
+                   coalesce(r.note, '') AS note, coalesce(r.status, 'completed') AS status
+            ORDER BY r.scanned_at DESC
+            "#
+            .to_string(),
+        );
+
+        let mut result = self.graph().execute(query).await?;
+        let mut runs = Vec::new();
+        while let Some(row) = result.next().await? {
+            runs.push(ScanRunResult {
+                id: row.get("id").unwrap_or_default(),
+                commit_sha: row.get("sha").unwrap_or_default(),
+                branch: row.get("branch").unwrap_or_default(),
+                version: row.get("version").unwrap_or_default(),
+                repo_path: row.get("repo_path").unwrap_or_default(),
+                scanned_at: row.get("scanned_at").unwrap_or_default(),
+                previous_run: row.get("previous_run").unwrap_or_default(),
+                tags: row.get("tags").unwrap_or_default(),
+                note: row.get("note").unwrap_or_default(),
+                status: row.get("status").unwrap_or_default(),
+            });
+        }
+        Ok(runs)
+    }
+
+    /// Delete a scan run, then its commit if no other run is left for it,
+    /// then every file no commit contains and what hangs off it
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, Neo4jError> {
+        let runs = self
+            .delete_count(
+                Query::new(
+                    r#"
+                    MATCH (r:ScanRun {id: $id})
+                    DETACH DELETE r
+                    RETURN count(r) AS deleted
+                    "#
+                    .to_string(),
+                )
+                .param("id", id),
+            )
+            .await?;
+        if runs == 0 {
+            return Ok(DeleteSummary::default());
+        }
+
+        let commits = self
+            .delete_count(Query::new(
+                r#"
+                MATCH (c:Commit)
+                WHERE NOT (:ScanRun)-[:FOR_COMMIT]->(c)
+                OPTIONAL MATCH (c)-[:HAS_DIRECTORY]->(d:DirectorySummary)
+                OPTIONAL MATCH (c)-[:HAS_MODULE]->(m:Module)
+                OPTIONAL MATCH (c)-[:HAS_CONTRACT]->(n:Contract)
+                DETACH DELETE d, m, n, c
+                RETURN count(DISTINCT c) AS deleted
+                "#
+                .to_string(),
+            ))
+            .await?;
+        let (files, symbols) = self.delete_orphan_files().await?;
+        Ok(DeleteSummary {
+            runs,
+            commits,
+            files,
+            symbols,
+        })
+    }
+
+    /// Delete every file no commit contains, with its symbols, parameters
+    /// and diagnostics, then identities, types and external symbols nothing
+    /// points to anymore
+    ///
+    /// Returns the numbers of deleted files and symbols.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub(super) async fn delete_orphan_files(&self) -> Result<(usize, usize), Neo4jError> {
+        let symbols = self
+            .delete_count(Query::new(
+                r#"
+                MATCH (s:Symbol)-[:DEFINED_IN]->(f:File)
+                WHERE NOT (:Commit)-[:CONTAINS]->(f)
+                OPTIONAL MATCH (s)-[:HAS_PARAMETER]->(p:Parameter)
+                DETACH DELETE p, s
+                RETURN count(DISTINCT s) AS deleted
+                "#
+                .to_string(),
+            ))
+            .await?;
+        let files = self
+            .delete_count(Query::new(
+                r#"
+                MATCH (f:File)
+                WHERE NOT (:Commit)-[:CONTAINS]->(f)
+                OPTIONAL MATCH (f)-[:HAS_DIAGNOSTIC]->(d:Diagnostic)
+                DETACH DELETE d, f
+                RETURN count(DISTINCT f) AS deleted
+                "#
+                .to_string(),
+            ))
+            .await?;
+
+        for orphans in [
+            "MATCH (i:SymbolIdentity) WHERE NOT (:Symbol)-[:SNAPSHOT_OF]->(i) DELETE i",
+            "MATCH (t:Type) WHERE NOT ()--(t) DELETE t",
+            "MATCH (e:ExternalSymbol) WHERE NOT ()--(e) DELETE e",
+        ] {
+            self.graph().run(Query::new(orphans.to_string())).await?;
+        }
+        Ok((files, symbols))
+    }
+
+    /// Run a deleting query that returns the number of nodes as `deleted`
+    pub(super) async fn delete_count(&self, query: Query) -> Result<usize, Neo4jError> {
+        let mut result = self.graph().execute(query).await?;
+        let deleted = match result.next().await? {
+            Some(row) => row.get::<i64>("deleted").unwrap_or_default(),
+            None => 0,
+        };
+        Ok(usize::try_from(deleted).unwrap_or_default())
+    }
 }
@@ -0,0 +1,78 @@
+//! Full-text symbol search Neo4j queries
+
+use neo4rs::Query;
+
+use super::read::{paged, Page, SymbolMatch};
+use super::Neo4jClient;
+use crate::graph::neo4j::Neo4jError;
+
+/// Characters with a meaning in Lucene query syntax
+const LUCENE_SPECIAL: &str = r#"+-&|!(){}[]^"~*?:\/"#;
+
+/// Lucene query matching any word of `text` by prefix or within a small
+/// edit distance, or `None` if the text has no words
+///
+/// Words are lowercased because prefix and fuzzy terms bypass the index
+/// analyzer.
+#[must_use]
+fn lucene_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            let mut term = String::new();
+            for c in word.to_lowercase().chars() {
+                if LUCENE_SPECIAL.contains(c) {
+                    term.push('\\');
+                }
+                term.push(c);
+            }
+            format!("({term}* OR {term}~)")
+        })
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+impl Neo4jClient {
+    /// Search symbol names, qualified names and doc comments through the
+    /// `symbol_search` full-text index, best matches first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn search_symbols(
+        &self,
+        text: &str,
+        page: Page,
+    ) -> Result<Vec<SymbolMatch>, Neo4jError> {
+        let Some(search) = lucene_query(text) else {
+            return Ok(Vec::new());
+        };
+        let query = Query::new(
+            r#"
+            CALL db.index.fulltext.queryNodes('symbol_search', $search)
+            YIELD node AS s, score
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line,
+                   s.end_line, score
+            ORDER BY score DESC, s.name
+            SKIP $skip LIMIT $limit
+            "#
+            .to_string(),
+        )
+        .param("search", search);
+
+        let mut result = self.graph().execute(paged(query, page)).await?;
+        let mut matches = Vec::new();
+        while let Some(row) = result.next().await? {
+            matches.push(SymbolMatch {
+                id: row.get("s.id").unwrap_or_default(),
+                name: row.get("s.name").unwrap_or_default(),
+                qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+                kind: row.get("s.kind").unwrap_or_default(),
+                file_path: row.get("s.file_path").unwrap_or_default(),
+                start_line: row.get("s.start_line").unwrap_or(0),
+                end_line: row.get("s.end_line").unwrap_or(0),
+                score: row.get("score").unwrap_or(0.0),
+            });
+        }
+        Ok(matches)
+    }
+}
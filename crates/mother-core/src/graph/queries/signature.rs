@@ -0,0 +1,160 @@
+//! Function signature Neo4j queries
+
+use std::collections::HashMap;
+
+use neo4rs::{BoltType, Query};
+
+use super::read::{scoped_query, TypeUsage, TypeUsageResult};
+use super::Neo4jClient;
+use crate::graph::model::FunctionSignature;
+use crate::graph::neo4j::Neo4jError;
+use crate::graph::scope::PathScope;
+
+impl Neo4jClient {
+    /// Store the parameters and return types of functions using batch UNWIND
+    ///
+    /// Each symbol points to its Parameter nodes with HAS_PARAMETER and to
+    /// the Type node of its return type with RETURNS; typed parameters point
+    /// to their Type node with OF_TYPE. Type nodes are shared by name.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), Neo4jError> {
+        for (batch, rows, query) in signature_queries(signatures) {
+            self.run_batch(batch, rows, query).await?;
+        }
+        Ok(())
+    }
+
+    /// Functions of a commit whose parameter or return type contains
+    /// `type_name`, ordered by file, line and position
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, Neo4jError> {
+        let cypher = match usage {
+            TypeUsage::Parameter => {
+                r#"
+                MATCH (:Commit {sha: $commit_sha})-[:CONTAINS]->(:File)<-[:DEFINED_IN]-(s:Symbol)
+                      -[:HAS_PARAMETER]->(p:Parameter)-[:OF_TYPE]->(t:Type)
+                WHERE t.name CONTAINS $type_name
+                {scope}
+                RETURN s.qualified_name AS qualified_name, s.kind AS kind,
+                       s.file_path AS file_path, s.start_line AS start_line,
+                       p.name AS parameter, p.position AS position, t.name AS type_name
+                ORDER BY s.file_path, s.start_line, p.position
+                "#
+            }
+            TypeUsage::Return => {
+                r#"
+                MATCH (:Commit {sha: $commit_sha})-[:CONTAINS]->(:File)<-[:DEFINED_IN]-(s:Symbol)
+                      -[:RETURNS]->(t:Type)
+                WHERE t.name CONTAINS $type_name
+                {scope}
+                RETURN s.qualified_name AS qualified_name, s.kind AS kind,
+                       s.file_path AS file_path, s.start_line AS start_line,
+                       null AS parameter, null AS position, t.name AS type_name
+                ORDER BY s.file_path, s.start_line
+                "#
+            }
+        };
+        let query = scoped_query(cypher, scope)
+            .param("commit_sha", commit_sha)
+            .param("type_name", type_name);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut functions = Vec::new();
+        while let Some(row) = result.next().await? {
+            functions.push(TypeUsageResult {
+                qualified_name: row.get("qualified_name").unwrap_or_default(),
+                kind: row.get("kind").unwrap_or_default(),
+                file_path: row.get("file_path").unwrap_or_default(),
+                start_line: row.get("start_line").unwrap_or(0),
+                parameter: row.get("parameter").ok(),
+                position: row.get("position").ok(),
+                type_name: row.get("type_name").unwrap_or_default(),
+            });
+        }
+        Ok(functions)
+    }
+}
+
+/// Queries storing the parameters and return types of functions, each with
+/// its batch name and number of rows
+pub(super) fn signature_queries(
+    signatures: &[FunctionSignature],
+) -> Vec<(&'static str, usize, Query)> {
+    let mut queries = Vec::new();
+    let param_data: Vec<HashMap<&str, BoltType>> = signatures
+        .iter()
+        .flat_map(|sig| sig.parameters.iter().map(move |p| (sig, p)))
+        .map(|(sig, p)| {
+            let mut map = HashMap::new();
+            map.insert("symbol_id", BoltType::String(sig.symbol_id.clone().into()));
+            map.insert("name", BoltType::String(p.name.clone().into()));
+            map.insert(
+                "type",
+                BoltType::String(p.type_name.clone().unwrap_or_default().into()),
+            );
+            map.insert("position", BoltType::Integer(i64::from(p.position).into()));
+            map
+        })
+        .collect();
+
+    if !param_data.is_empty() {
+        let rows = param_data.len();
+        let query = Query::new(
+            r#"
+            UNWIND $parameters AS param
+            MATCH (s:Symbol {id: param.symbol_id})
+            MERGE (s)-[:HAS_PARAMETER]->(p:Parameter {
+                symbol_id: param.symbol_id,
+                position: param.position
+            })
+            SET p.name = param.name, p.type = param.type
+            FOREACH (_ IN CASE WHEN param.type = '' THEN [] ELSE [1] END |
+                MERGE (t:Type {name: param.type})
+                MERGE (p)-[:OF_TYPE]->(t))
+            "#
+            .to_string(),
+        )
+        .param("parameters", param_data);
+        queries.push(("parameters", rows, query));
+    }
+
+    let return_data: Vec<HashMap<&str, BoltType>> = signatures
+        .iter()
+        .filter_map(|sig| {
+            let return_type = sig.return_type.clone()?;
+            let mut map = HashMap::new();
+            map.insert("symbol_id", BoltType::String(sig.symbol_id.clone().into()));
+            map.insert("type", BoltType::String(return_type.into()));
+            Some(map)
+        })
+        .collect();
+
+    if !return_data.is_empty() {
+        let rows = return_data.len();
+        let query = Query::new(
+            r#"
+            UNWIND $returns AS ret
+            MATCH (s:Symbol {id: ret.symbol_id})
+            MERGE (t:Type {name: ret.type})
+            MERGE (s)-[:RETURNS]->(t)
+            "#
+            .to_string(),
+        )
+        .param("returns", return_data);
+        queries.push(("returns", rows, query));
+    }
+    queries
+}
@@ -0,0 +1,92 @@
+//! Vector similarity Neo4j queries over symbol embeddings
+
+use std::sync::atomic::Ordering;
+
+use neo4rs::Query;
+
+use super::read::SymbolMatch;
+use super::Neo4jClient;
+use crate::graph::neo4j::Neo4jError;
+
+/// Nearest neighbours fetched from the index per result, since the index
+/// spans every commit and matches outside the requested one are dropped
+const CANDIDATES_PER_RESULT: usize = 10;
+
+impl Neo4jClient {
+    /// Create the `symbol_embedding` vector index for embeddings of
+    /// `dimensions` values, once per client
+    ///
+    /// The index keeps the dimensions it was created with; embeddings of a
+    /// different model need a new index (`DROP INDEX symbol_embedding`).
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be created.
+    pub async fn ensure_vector_index(&self, dimensions: usize) -> Result<(), Neo4jError> {
+        if self.vector_index.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let statement = format!(
+            "CREATE VECTOR INDEX symbol_embedding IF NOT EXISTS \
+             FOR (s:Symbol) ON (s.embedding) \
+             OPTIONS {{indexConfig: {{`vector.dimensions`: {dimensions}, \
+             `vector.similarity_function`: 'cosine'}}}}"
+        );
+        self.graph().run(Query::new(statement)).await?;
+        self.vector_index.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Symbols of a commit whose embeddings are closest to the embedding of
+    /// symbol `symbol_id`, most similar first, through the
+    /// `symbol_embedding` vector index
+    ///
+    /// Returns nothing if the symbol has no embedding.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (target:Symbol {id: $id})
+            WHERE target.embedding IS NOT NULL
+            CALL db.index.vector.queryNodes('symbol_embedding', $candidates, target.embedding)
+            YIELD node AS s, score
+            WHERE s <> target
+              AND EXISTS { (s)-[:DEFINED_IN]->(:File)<-[:CONTAINS]-(:Commit {sha: $sha}) }
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line,
+                   s.end_line, score
+            ORDER BY score DESC, s.name
+            LIMIT $limit
+            "#
+            .to_string(),
+        )
+        .param("id", symbol_id)
+        .param("sha", commit_sha)
+        .param(
+            "candidates",
+            i64::try_from(limit.saturating_mul(CANDIDATES_PER_RESULT)).unwrap_or(i64::MAX),
+        )
+        .param("limit", i64::try_from(limit).unwrap_or(i64::MAX));
+
+        let mut result = self.graph().execute(query).await?;
+        let mut matches = Vec::new();
+        while let Some(row) = result.next().await? {
+            matches.push(SymbolMatch {
+                id: row.get("s.id").unwrap_or_default(),
+                name: row.get("s.name").unwrap_or_default(),
+                qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+                kind: row.get("s.kind").unwrap_or_default(),
+                file_path: row.get("s.file_path").unwrap_or_default(),
+                start_line: row.get("s.start_line").unwrap_or(0),
+                end_line: row.get("s.end_line").unwrap_or(0),
+                score: row.get("score").unwrap_or(0.0),
+            });
+        }
+        Ok(matches)
+    }
+}
@@ -0,0 +1,468 @@
+//! GraphStore implementation for Neo4j
+
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+
+use super::super::backup::GraphBackup;
+use super::super::complexity::SymbolComplexity;
+use super::super::export::{ExportRecord, GraphExport};
+use super::super::integrity::{IntegrityIssue, IntegrityRepair};
+use super::super::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, ExternalSymbolNode, FileMetrics,
+    FileWrite, FunctionSignature, ImportEdge, ModuleNode, RepositoryDependency, RepositoryNode,
+    ScanReport, ScanRun, ScanStatus, SymbolCoverage, SymbolNode,
+};
+use super::super::scope::PathScope;
+use super::super::store::{GraphStore, QueryLanguage, RowStream, StoreError};
+use super::read::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, ImporterResult,
+    Page, RawRow, ReferenceDirection, ReferenceGroup, ReferenceGrouping, ReferenceResult,
+    ScanResult, ScanRunResult, SymbolMatch, SymbolResult, SymbolVersionResult, TypeUsage,
+    TypeUsageResult,
+};
+use super::{Neo4jClient, QueryParams};
+
+#[async_trait]
+impl GraphStore for Neo4jClient {
+    async fn create_scan_run(&self, scan_run: &ScanRun) -> Result<bool, StoreError> {
+        self.ensure_writable("create_scan_run")?;
+        Ok(Neo4jClient::create_scan_run(self, scan_run).await?)
+    }
+
+    async fn set_scan_report(&self, report: &ScanReport) -> Result<(), StoreError> {
+        self.ensure_writable("set_scan_report")?;
+        Ok(Neo4jClient::set_scan_report(self, report).await?)
+    }
+
+    async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("set_scan_status")?;
+        Ok(Neo4jClient::set_scan_status(self, scan_run_id, status).await?)
+    }
+
+    async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), StoreError> {
+        self.ensure_writable("tag_scan_run")?;
+        Ok(Neo4jClient::tag_scan_run(self, scan_run_id, tag).await?)
+    }
+
+    async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), StoreError> {
+        self.ensure_writable("annotate_scan_run")?;
+        Ok(Neo4jClient::annotate_scan_run(self, scan_run_id, note).await?)
+    }
+
+    async fn create_file_if_new(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+        language: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>, StoreError> {
+        self.ensure_writable("create_file_if_new")?;
+        Ok(
+            Neo4jClient::create_file_if_new(self, file_path, content_hash, language, commit_sha)
+                .await?,
+        )
+    }
+
+    async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, StoreError> {
+        self.ensure_writable("link_stored_file")?;
+        Ok(Neo4jClient::link_stored_file(self, content_hash, commit_sha).await?)
+    }
+
+    async fn write_file(&self, file: &FileWrite) -> Result<bool, StoreError> {
+        self.ensure_writable("write_file")?;
+        Ok(Neo4jClient::write_file(self, file).await?)
+    }
+
+    async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, StoreError> {
+        self.ensure_writable("unlink_file")?;
+        Ok(Neo4jClient::unlink_file(self, commit_sha, file_path).await?)
+    }
+
+    async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("set_file_metrics")?;
+        Ok(Neo4jClient::set_file_metrics(self, content_hash, metrics).await?)
+    }
+
+    async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("set_file_imports")?;
+        Ok(Neo4jClient::set_file_imports(self, content_hash, modules).await?)
+    }
+
+    async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_import_edges")?;
+        Ok(Neo4jClient::create_import_edges(self, commit_sha, edges).await?)
+    }
+
+    async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_directory_summaries")?;
+        Ok(Neo4jClient::create_directory_summaries(self, commit_sha, summaries).await?)
+    }
+
+    async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_modules")?;
+        Ok(Neo4jClient::create_modules(self, commit_sha, modules).await?)
+    }
+
+    async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_contracts")?;
+        Ok(Neo4jClient::create_contracts(self, commit_sha, contracts).await?)
+    }
+
+    async fn create_symbols_batch(
+        &self,
+        symbols: &[SymbolNode],
+        content_hash: &str,
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_symbols_batch")?;
+        Ok(Neo4jClient::create_symbols_batch(self, symbols, content_hash).await?)
+    }
+
+    async fn create_edge(&self, edge: &Edge) -> Result<(), StoreError> {
+        self.ensure_writable("create_edge")?;
+        Ok(Neo4jClient::create_edge(self, edge).await?)
+    }
+
+    async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), StoreError> {
+        self.ensure_writable("create_edges_batch")?;
+        Ok(Neo4jClient::create_edges_batch(self, edges).await?)
+    }
+
+    async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_modified_edges")?;
+        Ok(Neo4jClient::create_modified_edges(self, commit_sha, symbol_ids).await?)
+    }
+
+    async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("mark_references_truncated")?;
+        Ok(Neo4jClient::mark_references_truncated(self, symbol_id, total).await?)
+    }
+
+    async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("set_symbol_complexity")?;
+        Ok(Neo4jClient::set_symbol_complexity(self, complexity).await?)
+    }
+
+    async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), StoreError> {
+        self.ensure_writable("set_symbol_coverage")?;
+        Ok(Neo4jClient::set_symbol_coverage(self, coverage).await?)
+    }
+
+    async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), StoreError> {
+        self.ensure_writable("upsert_repository")?;
+        Ok(Neo4jClient::upsert_repository(self, repository).await?)
+    }
+
+    async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("set_repository_dependencies")?;
+        Ok(Neo4jClient::set_repository_dependencies(self, dependencies).await?)
+    }
+
+    async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, StoreError> {
+        self.ensure_writable("delete_scan_run")?;
+        Ok(Neo4jClient::delete_scan_run(self, id).await?)
+    }
+
+    async fn repair_integrity(&self) -> Result<IntegrityRepair, StoreError> {
+        self.ensure_writable("repair_integrity")?;
+        Ok(Neo4jClient::repair_integrity(self).await?)
+    }
+
+    async fn restore(&self, backup: &GraphBackup) -> Result<usize, StoreError> {
+        self.ensure_writable("restore")?;
+        Ok(Neo4jClient::restore(self, backup).await?)
+    }
+
+    async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_diagnostics")?;
+        Ok(Neo4jClient::create_diagnostics(self, content_hash, diagnostics).await?)
+    }
+
+    async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_function_signatures")?;
+        Ok(Neo4jClient::create_function_signatures(self, signatures).await?)
+    }
+
+    async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), StoreError> {
+        self.ensure_writable("create_external_reference")?;
+        Ok(Neo4jClient::create_external_reference(self, source_id, target, line, column).await?)
+    }
+
+    async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        Ok(Neo4jClient::find_symbols(self, pattern, scope, page).await?)
+    }
+
+    async fn search_symbols(&self, text: &str, page: Page) -> Result<Vec<SymbolMatch>, StoreError> {
+        Ok(Neo4jClient::search_symbols(self, text, page).await?)
+    }
+
+    async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, StoreError> {
+        Ok(Neo4jClient::similar_symbols(self, symbol_id, commit_sha, limit).await?)
+    }
+
+    async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        Ok(Neo4jClient::symbols_in_file(self, file_path).await?)
+    }
+
+    async fn modified_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        Ok(Neo4jClient::modified_symbols(self, commit_sha).await?)
+    }
+
+    async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        Ok(Neo4jClient::find_symbols_at_commit(self, pattern, commit_sha, scope, page).await?)
+    }
+
+    async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, StoreError> {
+        Ok(Neo4jClient::symbols_at_commit(self, commit_sha).await?)
+    }
+
+    async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError> {
+        Ok(Neo4jClient::symbol_history(self, qualified_name).await?)
+    }
+
+    async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, StoreError> {
+        Ok(Neo4jClient::previous_file_symbols(self, file_path, content_hash).await?)
+    }
+
+    async fn find_references_to(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        Ok(Neo4jClient::find_references_to(self, symbol_name, page).await?)
+    }
+
+    async fn find_references_from(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        Ok(Neo4jClient::find_references_from(self, symbol_name, page).await?)
+    }
+
+    async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, StoreError> {
+        Ok(
+            Neo4jClient::find_reference_groups(self, symbol_name, direction, grouping, page)
+                .await?,
+        )
+    }
+
+    async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, StoreError> {
+        Ok(Neo4jClient::directory_summary(self, path, commit_sha).await?)
+    }
+
+    async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, StoreError> {
+        Ok(Neo4jClient::directories_at_commit(self, commit_sha).await?)
+    }
+
+    async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, StoreError> {
+        Ok(Neo4jClient::modules_at_commit(self, commit_sha).await?)
+    }
+
+    async fn contracts_at_commit(&self, commit_sha: &str) -> Result<Vec<ContractNode>, StoreError> {
+        Ok(Neo4jClient::contracts_at_commit(self, commit_sha).await?)
+    }
+
+    async fn repositories(&self) -> Result<Vec<RepositoryNode>, StoreError> {
+        Ok(Neo4jClient::repositories(self).await?)
+    }
+
+    async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, StoreError> {
+        Ok(Neo4jClient::repository_dependencies(self).await?)
+    }
+
+    async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, StoreError> {
+        Ok(Neo4jClient::find_diagnostics(self, commit_sha, severity, scope).await?)
+    }
+
+    async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, StoreError> {
+        Ok(Neo4jClient::find_functions_by_type(self, commit_sha, type_name, usage, scope).await?)
+    }
+
+    async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, StoreError> {
+        Ok(Neo4jClient::list_files(self, pattern, page).await?)
+    }
+
+    async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, StoreError> {
+        Ok(Neo4jClient::file_metrics(self, commit_sha, pattern).await?)
+    }
+
+    async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError> {
+        Ok(Neo4jClient::find_importers(self, module).await?)
+    }
+
+    async fn stats(&self) -> Result<GraphStats, StoreError> {
+        Ok(Neo4jClient::stats(self).await?)
+    }
+
+    async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, StoreError> {
+        Ok(Neo4jClient::scan_runs(self).await?)
+    }
+
+    async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, StoreError> {
+        Ok(Neo4jClient::scan_report(self, scan_run_id).await?)
+    }
+
+    async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, StoreError> {
+        Ok(Neo4jClient::check_integrity(self).await?)
+    }
+
+    async fn schema_version(&self) -> Result<u32, StoreError> {
+        Ok(Neo4jClient::schema_version(self).await?)
+    }
+
+    async fn resolve_commit(&self, version: Option<&str>) -> Result<Option<String>, StoreError> {
+        Ok(Neo4jClient::resolve_commit(self, version).await?)
+    }
+
+    async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, StoreError> {
+        Ok(Neo4jClient::recent_scans(self, branch, limit).await?)
+    }
+
+    async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, StoreError> {
+        Ok(Neo4jClient::export_commit(self, commit_sha).await?)
+    }
+
+    fn stream_export<'a>(&'a self, commit_sha: &'a str) -> RowStream<'a, ExportRecord> {
+        Neo4jClient::stream_export(self, commit_sha)
+            .map_err(StoreError::from)
+            .boxed()
+    }
+
+    async fn backup(&self) -> Result<GraphBackup, StoreError> {
+        Ok(Neo4jClient::backup(self).await?)
+    }
+
+    async fn execute_raw(&self, query: &str, params: &QueryParams) -> Result<usize, StoreError> {
+        Ok(Neo4jClient::execute_raw(self, query, params).await?)
+    }
+
+    async fn fetch_raw(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> Result<Vec<RawRow>, StoreError> {
+        Ok(Neo4jClient::fetch_raw(self, query, params).await?)
+    }
+
+    fn query_language(&self) -> QueryLanguage {
+        QueryLanguage::Cypher
+    }
+}
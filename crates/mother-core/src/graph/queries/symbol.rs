@@ -3,7 +3,8 @@
 use neo4rs::Query;
 
 use super::Neo4jClient;
-use crate::graph::model::{Edge, SymbolNode};
+use crate::graph::complexity::SymbolComplexity;
+use crate::graph::model::{Edge, ExternalSymbolNode, SymbolCoverage, SymbolNode};
 use crate::graph::neo4j::Neo4jError;
 
 impl Neo4jClient {
@@ -29,9 +30,19 @@ impl Neo4jClient {
                 start_line: $start_line,
                 end_line: $end_line,
                 signature: $signature,
-                doc_comment: $doc_comment
+                doc_comment: $doc_comment,
+                type_signature: $type_signature,
+                resolved_type: $resolved_type,
+                embedding: $embedding,
+                identity_key: $identity_key
             })
             CREATE (s)-[:DEFINED_IN]->(f)
+            MERGE (i:SymbolIdentity {key: $identity_key})
+            ON CREATE SET i.name = $name,
+                          i.qualified_name = $qualified_name,
+                          i.kind = $kind,
+                          i.file_path = $file_path
+            CREATE (s)-[:SNAPSHOT_OF]->(i)
             "#
             .to_string(),
         )
@@ -48,8 +59,21 @@ impl Neo4jClient {
         .param(
             "doc_comment",
             symbol.doc_comment.clone().unwrap_or_default(),
-        );
+        )
+        .param(
+            "type_signature",
+            symbol.type_signature.clone().unwrap_or_default(),
+        )
+        .param(
+            "resolved_type",
+            symbol.resolved_type.clone().unwrap_or_default(),
+        )
+        .param("embedding", embedding(symbol))
+        .param("identity_key", symbol.identity_key());
 
+        if let Some(vector) = &symbol.embedding {
+            self.ensure_vector_index(vector.len()).await?;
+        }
         self.graph().run(query).await?;
         Ok(())
     }
@@ -69,70 +93,11 @@ impl Neo4jClient {
             return Ok(());
         }
 
-        // Convert symbols to a list of maps for UNWIND
-        let symbol_data: Vec<std::collections::HashMap<&str, neo4rs::BoltType>> = symbols
-            .iter()
-            .map(|s| {
-                let mut map = std::collections::HashMap::new();
-                map.insert("id", neo4rs::BoltType::String(s.id.clone().into()));
-                map.insert("name", neo4rs::BoltType::String(s.name.clone().into()));
-                map.insert(
-                    "qualified_name",
-                    neo4rs::BoltType::String(s.qualified_name.clone().into()),
-                );
-                map.insert("kind", neo4rs::BoltType::String(s.kind.to_string().into()));
-                map.insert(
-                    "visibility",
-                    neo4rs::BoltType::String(s.visibility.clone().unwrap_or_default().into()),
-                );
-                map.insert(
-                    "file_path",
-                    neo4rs::BoltType::String(s.file_path.clone().into()),
-                );
-                map.insert(
-                    "start_line",
-                    neo4rs::BoltType::Integer((s.start_line as i64).into()),
-                );
-                map.insert(
-                    "end_line",
-                    neo4rs::BoltType::Integer((s.end_line as i64).into()),
-                );
-                map.insert(
-                    "signature",
-                    neo4rs::BoltType::String(s.signature.clone().unwrap_or_default().into()),
-                );
-                map.insert(
-                    "doc_comment",
-                    neo4rs::BoltType::String(s.doc_comment.clone().unwrap_or_default().into()),
-                );
-                map
-            })
-            .collect();
-
-        let query = Query::new(
-            r#"
-            MATCH (f:File {content_hash: $content_hash})
-            UNWIND $symbols AS sym
-            CREATE (s:Symbol {
-                id: sym.id,
-                name: sym.name,
-                qualified_name: sym.qualified_name,
-                kind: sym.kind,
-                visibility: sym.visibility,
-                file_path: sym.file_path,
-                start_line: sym.start_line,
-                end_line: sym.end_line,
-                signature: sym.signature,
-                doc_comment: sym.doc_comment
-            })
-            CREATE (s)-[:DEFINED_IN]->(f)
-            "#
-            .to_string(),
-        )
-        .param("content_hash", content_hash)
-        .param("symbols", symbol_data);
-
-        self.graph().run(query).await?;
+        if let Some(vector) = symbols.iter().find_map(|s| s.embedding.as_ref()) {
+            self.ensure_vector_index(vector.len()).await?;
+        }
+        let query = symbols_query(symbols, content_hash);
+        self.run_batch("symbols", symbols.len(), query).await?;
         Ok(())
     }
 
@@ -159,4 +124,414 @@ impl Neo4jClient {
         self.graph().run(query).await?;
         Ok(())
     }
+
+    /// Create edges between symbols, one query per edge kind
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), Neo4jError> {
+        for (rows, query) in edge_queries(edges) {
+            self.run_batch("edges", rows, query).await?;
+        }
+        Ok(())
+    }
+
+    /// Create a REFERENCES edge from a symbol to an ExternalSymbol
+    /// placeholder, merging the placeholder on its id
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (source:Symbol {id: $source_id})
+            MERGE (target:ExternalSymbol {id: $target_id})
+            ON CREATE SET target.name = $name, target.file_path = $file_path,
+                          target.line = $target_line, target.origin = $origin
+            CREATE (source)-[:REFERENCES {line: $line, column: $column}]->(target)
+            "#
+            .to_string(),
+        )
+        .param("source_id", source_id)
+        .param("target_id", target.id.clone())
+        .param("name", target.name.clone())
+        .param("file_path", target.file_path.clone())
+        .param("target_line", i64::from(target.line))
+        .param("origin", target.origin.clone())
+        .param("line", i64::from(line))
+        .param("column", i64::from(column));
+
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Record on a symbol that only a sample of its references was stored
+    ///
+    /// Sets `reference_count` to the reported total and `is_truncated`.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (s:Symbol {id: $symbol_id})
+            SET s.reference_count = $total, s.is_truncated = true
+            "#
+            .to_string(),
+        )
+        .param("symbol_id", symbol_id)
+        .param("total", i64::try_from(total).unwrap_or(i64::MAX));
+
+        self.graph().run(query).await?;
+        Ok(())
+    }
+
+    /// Set the complexity proxies of symbols
+    ///
+    /// Stored as `fan_in`, `fan_out`, `outgoing_calls`,
+    /// `incoming_references` and `complexity` (the score).
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), Neo4jError> {
+        if complexity.is_empty() {
+            return Ok(());
+        }
+
+        let count =
+            |n: usize| neo4rs::BoltType::Integer(i64::try_from(n).unwrap_or(i64::MAX).into());
+        let rows: Vec<std::collections::HashMap<&str, neo4rs::BoltType>> = complexity
+            .iter()
+            .map(|c| {
+                let mut map = std::collections::HashMap::new();
+                map.insert("id", neo4rs::BoltType::String(c.symbol_id.clone().into()));
+                map.insert("fan_in", count(c.fan_in));
+                map.insert("fan_out", count(c.fan_out));
+                map.insert("outgoing_calls", count(c.outgoing_calls));
+                map.insert("incoming_references", count(c.incoming_references));
+                map.insert("complexity", count(c.score));
+                map
+            })
+            .collect();
+
+        let query = Query::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (s:Symbol {id: row.id})
+            SET s.fan_in = row.fan_in,
+                s.fan_out = row.fan_out,
+                s.outgoing_calls = row.outgoing_calls,
+                s.incoming_references = row.incoming_references,
+                s.complexity = row.complexity
+            "#
+            .to_string(),
+        )
+        .param("rows", rows);
+
+        self.run_batch("complexity", complexity.len(), query)
+            .await?;
+        Ok(())
+    }
+
+    /// Store line and branch coverage on symbols
+    ///
+    /// Stored as `lines_covered`, `lines_total`, `branches_covered`,
+    /// `branches_total` and the percentages `line_coverage` and
+    /// `branch_coverage`, null for ranges without instrumented lines or
+    /// branches.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), Neo4jError> {
+        if coverage.is_empty() {
+            return Ok(());
+        }
+
+        let count = |n: u32| neo4rs::BoltType::Integer(i64::from(n).into());
+        let percent = |p: Option<f64>| match p {
+            Some(p) => neo4rs::BoltType::Float(neo4rs::BoltFloat::new(p)),
+            None => neo4rs::BoltType::Null(neo4rs::BoltNull),
+        };
+        let rows: Vec<std::collections::HashMap<&str, neo4rs::BoltType>> = coverage
+            .iter()
+            .map(|c| {
+                let mut map = std::collections::HashMap::new();
+                map.insert("id", neo4rs::BoltType::String(c.symbol_id.clone().into()));
+                map.insert("lines_covered", count(c.lines_covered));
+                map.insert("lines_total", count(c.lines_total));
+                map.insert("branches_covered", count(c.branches_covered));
+                map.insert("branches_total", count(c.branches_total));
+                map.insert("line_coverage", percent(c.line_percent()));
+                map.insert("branch_coverage", percent(c.branch_percent()));
+                map
+            })
+            .collect();
+
+        let query = Query::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (s:Symbol {id: row.id})
+            SET s.lines_covered = row.lines_covered,
+                s.lines_total = row.lines_total,
+                s.branches_covered = row.branches_covered,
+                s.branches_total = row.branches_total,
+                s.line_coverage = row.line_coverage,
+                s.branch_coverage = row.branch_coverage
+            "#
+            .to_string(),
+        )
+        .param("rows", rows);
+
+        self.run_batch("coverage", coverage.len(), query).await?;
+        Ok(())
+    }
+
+    /// Link a commit to the symbols it changed with MODIFIED edges
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), Neo4jError> {
+        if symbol_ids.is_empty() {
+            return Ok(());
+        }
+        let query = Query::new(
+            r#"
+            MATCH (c:Commit {sha: $commit_sha})
+            UNWIND $ids AS id
+            MATCH (s:Symbol {id: id})
+            MERGE (c)-[:MODIFIED]->(s)
+            "#
+            .to_string(),
+        )
+        .param("commit_sha", commit_sha)
+        .param("ids", symbol_ids.to_vec());
+        self.run_batch("modified_edges", symbol_ids.len(), query)
+            .await?;
+        Ok(())
+    }
+
+    /// Symbols of the most recently created other version of a file
+    ///
+    /// Versions are ordered by the `created_at` timestamp set when a `File`
+    /// node is created; files stored before it existed sort last.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, Neo4jError> {
+        let query = Query::new(
+            r#"
+            MATCH (f:File {path: $file_path})
+            WHERE f.content_hash <> $content_hash
+            WITH f ORDER BY coalesce(f.created_at, 0) DESC LIMIT 1
+            MATCH (s:Symbol)-[:DEFINED_IN]->(f)
+            RETURN s.id, s.name, s.qualified_name, s.kind, s.visibility, s.file_path,
+                   s.start_line, s.end_line, s.signature, s.doc_comment,
+                   s.type_signature, s.resolved_type
+            ORDER BY s.start_line
+            "#
+            .to_string(),
+        )
+        .param("file_path", file_path)
+        .param("content_hash", content_hash);
+
+        let mut result = self.graph().execute(query).await?;
+        let mut symbols = Vec::new();
+        while let Some(row) = result.next().await? {
+            symbols.extend(symbol_node(&row));
+        }
+
+        Ok(symbols)
+    }
+}
+
+/// Create the symbols of a stored file in one UNWIND query, with their
+/// DEFINED_IN and SNAPSHOT_OF links
+pub(super) fn symbols_query(symbols: &[SymbolNode], content_hash: &str) -> Query {
+    // Convert symbols to a list of maps for UNWIND
+    let symbol_data: Vec<std::collections::HashMap<&str, neo4rs::BoltType>> = symbols
+        .iter()
+        .map(|s| {
+            let mut map = std::collections::HashMap::new();
+            map.insert("id", neo4rs::BoltType::String(s.id.clone().into()));
+            map.insert("name", neo4rs::BoltType::String(s.name.clone().into()));
+            map.insert(
+                "qualified_name",
+                neo4rs::BoltType::String(s.qualified_name.clone().into()),
+            );
+            map.insert("kind", neo4rs::BoltType::String(s.kind.to_string().into()));
+            map.insert(
+                "visibility",
+                neo4rs::BoltType::String(s.visibility.clone().unwrap_or_default().into()),
+            );
+            map.insert(
+                "file_path",
+                neo4rs::BoltType::String(s.file_path.clone().into()),
+            );
+            map.insert(
+                "start_line",
+                neo4rs::BoltType::Integer((s.start_line as i64).into()),
+            );
+            map.insert(
+                "end_line",
+                neo4rs::BoltType::Integer((s.end_line as i64).into()),
+            );
+            map.insert(
+                "signature",
+                neo4rs::BoltType::String(s.signature.clone().unwrap_or_default().into()),
+            );
+            map.insert(
+                "doc_comment",
+                neo4rs::BoltType::String(s.doc_comment.clone().unwrap_or_default().into()),
+            );
+            map.insert(
+                "type_signature",
+                neo4rs::BoltType::String(s.type_signature.clone().unwrap_or_default().into()),
+            );
+            map.insert(
+                "resolved_type",
+                neo4rs::BoltType::String(s.resolved_type.clone().unwrap_or_default().into()),
+            );
+            map.insert("embedding", embedding(s));
+            map.insert(
+                "identity_key",
+                neo4rs::BoltType::String(s.identity_key().into()),
+            );
+            map
+        })
+        .collect();
+
+    Query::new(
+        r#"
+        MATCH (f:File {content_hash: $content_hash})
+        UNWIND $symbols AS sym
+        CREATE (s:Symbol {
+            id: sym.id,
+            name: sym.name,
+            qualified_name: sym.qualified_name,
+            kind: sym.kind,
+            visibility: sym.visibility,
+            file_path: sym.file_path,
+            start_line: sym.start_line,
+            end_line: sym.end_line,
+            signature: sym.signature,
+            doc_comment: sym.doc_comment,
+            type_signature: sym.type_signature,
+            resolved_type: sym.resolved_type,
+            embedding: sym.embedding,
+            identity_key: sym.identity_key
+        })
+        CREATE (s)-[:DEFINED_IN]->(f)
+        MERGE (i:SymbolIdentity {key: sym.identity_key})
+        ON CREATE SET i.name = sym.name,
+                      i.qualified_name = sym.qualified_name,
+                      i.kind = sym.kind,
+                      i.file_path = sym.file_path
+        CREATE (s)-[:SNAPSHOT_OF]->(i)
+        "#
+        .to_string(),
+    )
+    .param("content_hash", content_hash)
+    .param("symbols", symbol_data)
+}
+
+/// Create edges between symbols, one UNWIND query per edge kind with its
+/// number of rows
+pub(super) fn edge_queries(edges: &[Edge]) -> Vec<(usize, Query)> {
+    let mut by_kind: std::collections::BTreeMap<String, Vec<_>> = std::collections::BTreeMap::new();
+    for edge in edges {
+        let mut row = std::collections::HashMap::new();
+        row.insert(
+            "source_id",
+            neo4rs::BoltType::String(edge.source_id.clone().into()),
+        );
+        row.insert(
+            "target_id",
+            neo4rs::BoltType::String(edge.target_id.clone().into()),
+        );
+        row.insert(
+            "line",
+            neo4rs::BoltType::Integer(i64::from(edge.line.unwrap_or(0)).into()),
+        );
+        row.insert(
+            "column",
+            neo4rs::BoltType::Integer(i64::from(edge.column.unwrap_or(0)).into()),
+        );
+        by_kind.entry(edge.kind.to_string()).or_default().push(row);
+    }
+
+    by_kind
+        .into_iter()
+        .map(|(rel_type, rows)| {
+            let row_count = rows.len();
+            let query = Query::new(format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (source:Symbol {{id: row.source_id}})
+                MATCH (target:Symbol {{id: row.target_id}})
+                CREATE (source)-[:{rel_type} {{line: row.line, column: row.column}}]->(target)
+                "#
+            ))
+            .param("rows", rows);
+            (row_count, query)
+        })
+        .collect()
+}
+
+/// Read a symbol returned as `s.id`, `s.name`, ... `s.resolved_type`
+///
+/// `None` if its kind is unknown to this version.
+pub(super) fn symbol_node(row: &neo4rs::Row) -> Option<SymbolNode> {
+    let optional = |key: &str| {
+        let value: String = row.get(key).unwrap_or_default();
+        (!value.is_empty()).then_some(value)
+    };
+    let kind: String = row.get("s.kind").unwrap_or_default();
+    Some(SymbolNode {
+        id: row.get("s.id").unwrap_or_default(),
+        name: row.get("s.name").unwrap_or_default(),
+        qualified_name: row.get("s.qualified_name").unwrap_or_default(),
+        kind: kind.parse().ok()?,
+        visibility: optional("s.visibility"),
+        file_path: row.get("s.file_path").unwrap_or_default(),
+        start_line: row.get("s.start_line").unwrap_or(0),
+        end_line: row.get("s.end_line").unwrap_or(0),
+        signature: optional("s.signature"),
+        doc_comment: optional("s.doc_comment"),
+        type_signature: optional("s.type_signature"),
+        resolved_type: optional("s.resolved_type"),
+        embedding: None,
+    })
+}
+
+/// A symbol's embedding as a list of floats, or null to leave the
+/// `embedding` property unset
+fn embedding(symbol: &SymbolNode) -> neo4rs::BoltType {
+    symbol
+        .embedding
+        .as_ref()
+        .map_or(neo4rs::BoltType::Null(neo4rs::BoltNull), |vector| {
+            let values: Vec<f64> = vector.iter().map(|x| f64::from(*x)).collect();
+            values.into()
+        })
 }
@@ -0,0 +1,127 @@
+//! Read-only mode: Keep shared credentials from changing the graph
+//!
+//! CI jobs and dashboards connect with `--read-only`. The Neo4j client then
+//! skips index creation and migrations on connect, refuses the store's write
+//! methods and runs raw Cypher in a transaction it rolls back, so Neo4j
+//! discards whatever the query wrote; SQLite databases are opened read-only
+//! and enforce it themselves.
+//!
+//! Raw Cypher containing a mutating clause is rejected before it is sent,
+//! with an error naming the clause. This check is lexical: keywords inside
+//! string literals, comments, backquoted names, property keys and labels
+//! don't count, so `MATCH (s) WHERE s.name = 'CREATE' RETURN s.set` is a
+//! read. Procedure calls count as writes when a part of the procedure name
+//! starts with a write verb, as in `CALL apoc.create.node(...)`, or the
+//! procedure runs Cypher passed as text or commits transactions of its own,
+//! as `apoc.cypher.doIt` and `apoc.periodic.iterate` do. The latter would
+//! escape the rollback, so the check is the only guard against them.
+
+/// Clauses and administration commands that change the graph or its schema
+const WRITE_KEYWORDS: &[&str] = &[
+    "CREATE", "MERGE", "SET", "DELETE", "DETACH", "REMOVE", "DROP", "LOAD", "ALTER", "GRANT",
+    "DENY", "REVOKE",
+];
+
+/// Verbs starting the name parts of procedures that write
+const WRITE_PROCEDURE_VERBS: &[&str] = &[
+    "create", "merge", "set", "delete", "remove", "drop", "refactor", "add",
+];
+
+/// Procedures that run Cypher passed as text or commit transactions of their
+/// own, as lowercase name prefixes
+const WRITE_PROCEDURES: &[&str] = &[
+    "apoc.cypher.doit",
+    "apoc.cypher.runwrite",
+    "apoc.cypher.runmany",
+    "apoc.cypher.runschema",
+    "apoc.cypher.runfile",
+    "apoc.do.",
+    "apoc.periodic.",
+    "apoc.trigger.",
+];
+
+/// The first mutating clause of a Cypher query, if any
+#[must_use]
+pub fn write_clause(cypher: &str) -> Option<&'static str> {
+    let chars: Vec<char> = cypher.chars().collect();
+    let mut i = 0;
+    let mut previous = ' ';
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => i = skip_quoted(&chars, i),
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                // Property keys, labels and parameters are names, not clauses
+                if !matches!(previous, '.' | ':' | '$') {
+                    let word: String = chars[start..i].iter().collect();
+                    let keyword = WRITE_KEYWORDS
+                        .iter()
+                        .find(|k| k.eq_ignore_ascii_case(&word));
+                    if keyword.is_some() {
+                        return keyword.copied();
+                    }
+                    if word.eq_ignore_ascii_case("CALL") && calls_write_procedure(&chars[i..]) {
+                        return Some("CALL");
+                    }
+                }
+                previous = chars[i - 1];
+                continue;
+            }
+            _ => i += 1,
+        }
+        if !c.is_whitespace() {
+            previous = c;
+        }
+    }
+    None
+}
+
+/// Whether the procedure named at the start of `rest` writes
+///
+/// `CALL { ... }` subqueries are checked clause by clause instead.
+fn calls_write_procedure(rest: &[char]) -> bool {
+    let name: String = rest
+        .iter()
+        .skip_while(|c| c.is_whitespace())
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '.'))
+        .collect();
+    let name = name.to_lowercase();
+    WRITE_PROCEDURES
+        .iter()
+        .any(|procedure| name.starts_with(procedure))
+        || name.split('.').any(|part| {
+            WRITE_PROCEDURE_VERBS
+                .iter()
+                .any(|verb| part.starts_with(verb))
+        })
+}
+
+/// Index just past the literal or backquoted name starting at `start`
+fn skip_quoted(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if quote != '`' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
@@ -0,0 +1,252 @@
+//! Graph reader: Typed queries for programs using the graph as a library
+//!
+//! [`GraphReader`] answers the questions of the `query` command - symbols,
+//! files, references, history and statistics - on any [`GraphStore`],
+//! returning the same result structs the command prints. Programs get the
+//! graph without shelling out to the CLI or writing Cypher or SQL, and the
+//! same code runs against Neo4j and SQLite. Reads of a whole commit come as
+//! streams, fetched from the store as they are polled rather than collected.
+//!
+//! ```no_run
+//! use mother_core::graph::{GraphReader, Page, SqliteStore};
+//!
+//! # async fn example() -> Result<(), mother_core::graph::StoreError> {
+//! let store = SqliteStore::open(std::path::Path::new("graph.db"))?;
+//! let reader = GraphReader::new(&store).with_page(Page {
+//!     limit: Some(10),
+//!     ..Page::default()
+//! });
+//! for symbol in reader.symbols("Parser").await? {
+//!     println!("{} {}:{}", symbol.name, symbol.file_path, symbol.start_line);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::future;
+use futures::stream::{Stream, TryStreamExt};
+
+use super::export::ExportRecord;
+use super::queries::{
+    FileResult, GraphStats, ImporterResult, Page, ReferenceResult, SymbolMatch, SymbolResult,
+    SymbolVersionResult,
+};
+use super::scope::PathScope;
+use super::store::{GraphStore, RowStream, StoreError};
+
+/// Typed read queries over a graph store
+///
+/// List queries return one [`Page`] of rows, the first
+/// [`DEFAULT_LIMIT`](super::queries::DEFAULT_LIMIT) in their default order
+/// unless [`with_page`](Self::with_page) says otherwise.
+#[derive(Clone, Copy)]
+pub struct GraphReader<'a> {
+    store: &'a dyn GraphStore,
+    page: Page,
+}
+
+impl<'a> GraphReader<'a> {
+    /// Read from `store`
+    #[must_use]
+    pub fn new(store: &'a dyn GraphStore) -> Self {
+        Self {
+            store,
+            page: Page::default(),
+        }
+    }
+
+    /// Window and order of the rows list queries return
+    #[must_use]
+    pub fn with_page(mut self, page: Page) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// The store read from, for queries the reader doesn't cover
+    #[must_use]
+    pub fn store(&self) -> &'a dyn GraphStore {
+        self.store
+    }
+
+    /// Symbols whose name contains `pattern`, ignoring case, across every
+    /// stored version
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn symbols(&self, pattern: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.store.find_symbols(pattern, None, self.page).await
+    }
+
+    /// Symbols whose name contains `pattern` in files inside `scope`
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn symbols_in_scope(
+        &self,
+        pattern: &str,
+        scope: &PathScope,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        self.store
+            .find_symbols(pattern, Some(scope), self.page)
+            .await
+    }
+
+    /// Symbols whose name contains `pattern` as of a version: a commit SHA
+    /// or prefix, `tag:<name>`, or `None` for the latest completed scan
+    ///
+    /// # Errors
+    /// Returns an error if the version matches no scanned commit or the
+    /// query fails.
+    pub async fn symbols_at(
+        &self,
+        pattern: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        let commit = self.commit(version).await?;
+        self.store
+            .find_symbols_at_commit(pattern, &commit, None, self.page)
+            .await
+    }
+
+    /// Full-text search of symbol names and doc comments, best matches first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn search(&self, text: &str) -> Result<Vec<SymbolMatch>, StoreError> {
+        self.store.search_symbols(text, self.page).await
+    }
+
+    /// Symbols in files whose path contains `file_path`, ordered by line
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.store.symbols_in_file(file_path).await
+    }
+
+    /// Every stored version of a symbol by qualified name, oldest first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError> {
+        self.store.symbol_history(qualified_name).await
+    }
+
+    /// References to symbols named `symbol_name`
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn references_to(
+        &self,
+        symbol_name: &str,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.store.find_references_to(symbol_name, self.page).await
+    }
+
+    /// References made from symbols named `symbol_name`
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn references_from(
+        &self,
+        symbol_name: &str,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.store
+            .find_references_from(symbol_name, self.page)
+            .await
+    }
+
+    /// Files whose path contains `pattern`, or every file
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn files(&self, pattern: Option<&str>) -> Result<Vec<FileResult>, StoreError> {
+        self.store.list_files(pattern, self.page).await
+    }
+
+    /// Files importing `module` or one of its submodules
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError> {
+        self.store.find_importers(module).await
+    }
+
+    /// Node counts by label
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub async fn stats(&self) -> Result<GraphStats, StoreError> {
+        self.store.stats().await
+    }
+
+    /// Every symbol of a commit by file and line, however many, fetched as
+    /// the stream is polled
+    ///
+    /// The stream ends at the first error.
+    pub fn stream_symbols(
+        &self,
+        commit_sha: &'a str,
+    ) -> impl Stream<Item = Result<SymbolResult, StoreError>> + 'a {
+        self.store
+            .stream_export(commit_sha)
+            .try_take_while(|record| future::ready(Ok(matches!(record, ExportRecord::Node(_)))))
+            .try_filter_map(|record| future::ready(Ok(symbol_result(record))))
+    }
+
+    /// Every node and then every edge of a commit, as exported, fetched as
+    /// the stream is polled
+    #[must_use]
+    pub fn stream_export(&self, commit_sha: &'a str) -> RowStream<'a, ExportRecord> {
+        self.store.stream_export(commit_sha)
+    }
+
+    /// Full SHA of a version, as taken by [`symbols_at`](Self::symbols_at)
+    ///
+    /// # Errors
+    /// Returns an error if the version matches no scanned commit or the
+    /// query fails.
+    pub async fn commit(&self, version: Option<&str>) -> Result<String, StoreError> {
+        self.store.resolve_commit(version).await?.ok_or_else(|| {
+            StoreError::Backend(match version {
+                Some(version) => format!("No scanned commit matches version '{version}'"),
+                None => "No completed scan found".to_string(),
+            })
+        })
+    }
+}
+
+/// The symbol of an exported Symbol node
+fn symbol_result(record: ExportRecord) -> Option<SymbolResult> {
+    let ExportRecord::Node(node) = record else {
+        return None;
+    };
+    if node.label != "Symbol" {
+        return None;
+    }
+    let text = |key: &str| {
+        node.properties
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let line = |key: &str| {
+        node.properties
+            .get(key)
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or_default()
+    };
+    Some(SymbolResult {
+        name: text("name"),
+        qualified_name: text("qualified_name"),
+        kind: text("kind"),
+        file_path: text("file_path"),
+        start_line: line("start_line"),
+        end_line: line("end_line"),
+        id: node.id,
+    })
+}
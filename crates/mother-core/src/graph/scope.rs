@@ -0,0 +1,153 @@
+//! Path scope: Restrict symbol queries to a directory, file or glob
+//!
+//! A scope is matched in two steps. The literal prefix (everything before
+//! the first glob character) is an indexed `STARTS WITH` / range filter in
+//! the store, and the anchored pattern then checks the exact path. Patterns
+//! only use syntax that Cypher (Java) and Rust regexes agree on.
+
+use regex::Regex;
+
+/// Characters that start a glob pattern
+const GLOB_CHARS: [char; 3] = ['*', '?', '['];
+
+/// Characters escaped when written literally into a pattern
+const META_CHARS: &str = "\\.+*?()|[]{}^$#&-~";
+
+/// Paths a scoped query is restricted to
+#[derive(Debug, Clone)]
+pub struct PathScope {
+    prefix: String,
+    pattern: String,
+    regex: Regex,
+}
+
+impl PathScope {
+    /// Build a scope from a directory, file path or glob
+    ///
+    /// A path without glob characters matches itself and everything below it.
+    /// In globs `*` and `?` stay within one path segment, `**` crosses
+    /// segments and `[...]` / `[!...]` are character classes.
+    ///
+    /// # Errors
+    /// Returns an error if the glob does not translate to a valid pattern.
+    pub fn new(scope: &str) -> Result<Self, regex::Error> {
+        let scope = normalize(scope);
+        let prefix_len = scope.find(GLOB_CHARS).unwrap_or(scope.len());
+        let prefix = scope[..prefix_len].to_string();
+
+        let pattern = if prefix_len == scope.len() {
+            directory_pattern(scope)
+        } else {
+            format!("^{}$", glob_body(scope))
+        };
+        let regex = Regex::new(&pattern)?;
+
+        Ok(Self {
+            prefix,
+            pattern,
+            regex,
+        })
+    }
+
+    /// Literal leading part every matching path starts with
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Anchored pattern a matching path must match in full
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether a path is inside the scope
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        path.starts_with(&self.prefix) && self.regex.is_match(path)
+    }
+}
+
+/// Drop a leading `./` and trailing separators
+fn normalize(scope: &str) -> &str {
+    let scope = scope.strip_prefix("./").unwrap_or(scope);
+    let trimmed = scope.trim_end_matches('/');
+    if trimmed.is_empty() && scope.starts_with('/') {
+        "/"
+    } else {
+        trimmed
+    }
+}
+
+/// Pattern for a path and everything below it
+fn directory_pattern(path: &str) -> String {
+    if path.is_empty() || path == "/" {
+        return format!("^{}.*$", escape(path));
+    }
+    format!("^{}(?:/.*)?$", escape(path))
+}
+
+/// Translate a glob to an unanchored pattern
+fn glob_body(glob: &str) -> String {
+    let mut out = String::new();
+    let mut rest = glob;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            out.push_str("(?:.*/)?");
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            out.push_str(".*");
+            rest = after;
+            continue;
+        }
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => match rest.find(']') {
+                Some(end) if end > 0 => {
+                    out.push_str(&class(&rest[..end]));
+                    rest = &rest[end + 1..];
+                }
+                _ => out.push_str("\\["),
+            },
+            _ => push_literal(&mut out, c),
+        }
+    }
+    out
+}
+
+/// Translate the inside of a `[...]` glob class
+fn class(inner: &str) -> String {
+    let (negated, inner) = match inner.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let mut out = String::from(if negated { "[^" } else { "[" });
+    for c in inner.chars() {
+        if matches!(c, '\\' | '[' | '&' | '~' | '^') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push(']');
+    out
+}
+
+fn escape(literal: &str) -> String {
+    let mut out = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        push_literal(&mut out, c);
+    }
+    out
+}
+
+fn push_literal(out: &mut String, c: char) {
+    if META_CHARS.contains(c) {
+        out.push('\\');
+    }
+    out.push(c);
+}
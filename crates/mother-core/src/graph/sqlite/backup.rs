@@ -0,0 +1,125 @@
+//! Backup and restore of every table row of a database
+
+use rusqlite::types::ValueRef;
+use rusqlite::{params_from_iter, Connection};
+use serde_json::{Map, Number, Value};
+
+use crate::graph::backup::{blob_bytes, blob_value, BackupRecord, GraphBackup};
+use crate::graph::store::StoreError;
+
+/// Tables in creation order, leaving out SQLite's own and the full-text
+/// index, which the `symbols` triggers rebuild
+const TABLES: &str = "SELECT name FROM sqlite_master
+     WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'symbol_search%'
+     ORDER BY rowid";
+
+/// Read every row of every table
+///
+/// Rows are read in rowid order so a restore keeps their relative order,
+/// which picks the latest version of a file.
+pub(super) fn backup(conn: &Connection) -> Result<GraphBackup, StoreError> {
+    let mut backup = GraphBackup {
+        backend: "sqlite".to_string(),
+        records: Vec::new(),
+    };
+    for table in tables(conn)? {
+        let mut stmt = conn.prepare(&format!("SELECT * FROM \"{table}\" ORDER BY rowid"))?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let mut values = Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                values.insert(column.clone(), to_json(row.get_ref(i)?));
+            }
+            backup.records.push(BackupRecord::Row {
+                table: table.clone(),
+                values,
+            });
+        }
+    }
+    Ok(backup)
+}
+
+/// Insert the rows of a backup into an empty database in one transaction
+///
+/// Foreign keys are checked at commit, so rows may come in any order.
+pub(super) fn restore(conn: &mut Connection, backup: &GraphBackup) -> Result<usize, StoreError> {
+    if backup.backend != "sqlite" {
+        return Err(StoreError::Backend(format!(
+            "cannot restore a {} backup into SQLite",
+            backup.backend
+        )));
+    }
+    let tables = tables(conn)?;
+    for table in &tables {
+        let count: i64 =
+            conn.query_row(&format!("SELECT count(*) FROM \"{table}\""), [], |row| {
+                row.get(0)
+            })?;
+        if count > 0 {
+            return Err(StoreError::Backend(
+                "the database is not empty; restore into a new file".to_string(),
+            ));
+        }
+    }
+
+    let tx = conn.transaction()?;
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+    for record in &backup.records {
+        let BackupRecord::Row { table, values } = record else {
+            return Err(StoreError::Backend(
+                "unexpected node or relationship in an SQLite backup".to_string(),
+            ));
+        };
+        if !tables.contains(table) {
+            return Err(StoreError::Backend(format!(
+                "unknown table {table} in backup"
+            )));
+        }
+        let columns: Vec<String> = values.keys().map(|c| format!("\"{c}\"")).collect();
+        let placeholders: Vec<String> = (1..=values.len()).map(|i| format!("?{i}")).collect();
+        let mut insert = tx.prepare_cached(&format!(
+            "INSERT INTO \"{table}\" ({}) VALUES ({})",
+            columns.join(", "),
+            placeholders.join(", ")
+        ))?;
+        insert.execute(params_from_iter(values.values().map(to_sql)))?;
+    }
+    tx.commit()?;
+    Ok(backup.records.len())
+}
+
+fn tables(conn: &Connection) -> Result<Vec<String>, StoreError> {
+    let mut stmt = conn.prepare(TABLES)?;
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(tables)
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => blob_value(bytes),
+    }
+}
+
+fn to_sql(value: &Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value as Sql;
+    match value {
+        Value::Null => Sql::Null,
+        Value::Bool(b) => Sql::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Sql::Integer(i),
+            None => Sql::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Sql::Text(s.clone()),
+        other => match blob_bytes(other) {
+            Some(bytes) => Sql::Blob(bytes),
+            None => Sql::Text(other.to_string()),
+        },
+    }
+}
@@ -0,0 +1,255 @@
+//! Export reads of a commit, streamed in batches
+//!
+//! Each query is read a batch at a time, resuming after the sort key of the
+//! last row, so a stream holds the connection lock only while a batch is
+//! read and never more than one batch of rows.
+
+use std::collections::BTreeMap;
+
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Row};
+use serde_json::Value;
+
+use super::SqliteStore;
+use crate::graph::export::{ExportEdge, ExportNode, ExportRecord};
+use crate::graph::store::{RowStream, StoreError};
+
+/// Prefix for File node ids in exports, matching the Neo4j backend
+const FILE_ID_PREFIX: &str = "file:";
+
+/// Rows read per batch
+const BATCH_SIZE: i64 = 1000;
+
+/// Sort key of a row, after which the next batch resumes
+type Key = Vec<SqlValue>;
+
+/// One query of an export, ordered by a unique sort key
+///
+/// `sql` takes the commit as `?1`, then the key of the last row read, then
+/// the batch size.
+struct Part {
+    sql: &'static str,
+    start: fn() -> Key,
+    record: fn(&Row<'_>) -> rusqlite::Result<(ExportRecord, Key)>,
+}
+
+/// The queries of an export in stream order: nodes, then edges
+const PARTS: [Part; 5] = [
+    Part {
+        sql: "SELECT f.content_hash, f.path, f.language FROM files f
+              JOIN commit_files cf ON cf.content_hash = f.content_hash
+              WHERE cf.commit_sha = ?1 AND (f.path, f.content_hash) > (?2, ?3)
+              ORDER BY f.path, f.content_hash LIMIT ?4",
+        start: text_key,
+        record: file_node,
+    },
+    Part {
+        sql: "SELECT s.id, s.name, s.qualified_name, s.kind, s.visibility, s.file_path,
+                     s.signature, s.start_line, s.end_line, t.total, s.doc_comment
+              FROM symbols s
+              JOIN commit_files cf ON cf.content_hash = s.content_hash
+              LEFT JOIN truncated_references t ON t.symbol_id = s.id
+              WHERE cf.commit_sha = ?1 AND (s.file_path, s.start_line, s.id) > (?2, ?3, ?4)
+              ORDER BY s.file_path, s.start_line, s.id LIMIT ?5",
+        start: line_key,
+        record: symbol_node,
+    },
+    Part {
+        sql: "SELECT s.id, s.content_hash, s.file_path, s.start_line FROM symbols s
+              JOIN commit_files cf ON cf.content_hash = s.content_hash
+              WHERE cf.commit_sha = ?1 AND (s.file_path, s.start_line, s.id) > (?2, ?3, ?4)
+              ORDER BY s.file_path, s.start_line, s.id LIMIT ?5",
+        start: line_key,
+        record: defined_in_edge,
+    },
+    Part {
+        sql: "SELECT e.source_id, e.target_id, e.kind, e.line, e.column, e.rowid FROM edges e
+              JOIN symbols a ON a.id = e.source_id
+              JOIN commit_files ca ON ca.content_hash = a.content_hash AND ca.commit_sha = ?1
+              JOIN symbols b ON b.id = e.target_id
+              JOIN commit_files cb ON cb.content_hash = b.content_hash AND cb.commit_sha = ?1
+              WHERE e.rowid > ?2
+              ORDER BY e.rowid LIMIT ?3",
+        start: rowid_key,
+        record: symbol_edge,
+    },
+    Part {
+        sql: "SELECT ie.from_hash, ie.to_hash, ie.module, ie.line, ie.rowid FROM import_edges ie
+              JOIN commit_files ca ON ca.content_hash = ie.from_hash AND ca.commit_sha = ?1
+              JOIN commit_files cb ON cb.content_hash = ie.to_hash AND cb.commit_sha = ?1
+              WHERE ie.rowid > ?2
+              ORDER BY ie.rowid LIMIT ?3",
+        start: rowid_key,
+        record: import_edge,
+    },
+];
+
+/// Stream the File and Symbol nodes of a commit, then the edges between them
+pub(super) fn stream_export<'a>(
+    store: &'a SqliteStore,
+    commit_sha: &'a str,
+) -> RowStream<'a, ExportRecord> {
+    stream::iter(&PARTS)
+        .flat_map(move |part| read_part(store, commit_sha, part))
+        .boxed()
+}
+
+fn read_part<'a>(
+    store: &'a SqliteStore,
+    commit_sha: &'a str,
+    part: &'static Part,
+) -> impl Stream<Item = Result<ExportRecord, StoreError>> + 'a {
+    stream::try_unfold(Some((part.start)()), move |after| async move {
+        match after {
+            Some(after) => read_batch(store, commit_sha, part, after).map(Some),
+            None => Ok(None),
+        }
+    })
+    .map_ok(|batch| stream::iter(batch.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+/// Read the batch of rows after the key `after`, holding the lock meanwhile,
+/// with the key to resume from unless it was the last batch
+fn read_batch(
+    store: &SqliteStore,
+    commit_sha: &str,
+    part: &Part,
+    after: Key,
+) -> Result<(Vec<ExportRecord>, Option<Key>), StoreError> {
+    let conn = store.conn();
+    let mut stmt = conn.prepare_cached(part.sql)?;
+    let params = std::iter::once(SqlValue::Text(commit_sha.to_string()))
+        .chain(after)
+        .chain([SqlValue::Integer(BATCH_SIZE)]);
+    let rows: Vec<(ExportRecord, Key)> = stmt
+        .query_map(params_from_iter(params), part.record)?
+        .collect::<Result<_, _>>()?;
+    let next = match i64::try_from(rows.len()) {
+        Ok(BATCH_SIZE) => rows.last().map(|(_, key)| key.clone()),
+        _ => None,
+    };
+    Ok((rows.into_iter().map(|(record, _)| record).collect(), next))
+}
+
+fn text_key() -> Key {
+    vec![SqlValue::Text(String::new()), SqlValue::Text(String::new())]
+}
+
+fn line_key() -> Key {
+    vec![
+        SqlValue::Text(String::new()),
+        SqlValue::Integer(i64::MIN),
+        SqlValue::Text(String::new()),
+    ]
+}
+
+fn rowid_key() -> Key {
+    vec![SqlValue::Integer(0)]
+}
+
+fn file_node(row: &Row<'_>) -> rusqlite::Result<(ExportRecord, Key)> {
+    let hash: String = row.get(0)?;
+    let path: String = row.get(1)?;
+    let mut props = strings(&[("path", path.clone()), ("language", row.get(2)?)]);
+    props.insert("content_hash".to_string(), Value::String(hash.clone()));
+    let node = ExportNode {
+        id: format!("{FILE_ID_PREFIX}{hash}"),
+        label: "File".to_string(),
+        properties: props,
+    };
+    let key = vec![SqlValue::Text(path), SqlValue::Text(hash)];
+    Ok((ExportRecord::Node(node), key))
+}
+
+fn symbol_node(row: &Row<'_>) -> rusqlite::Result<(ExportRecord, Key)> {
+    let id: String = row.get(0)?;
+    let file_path: String = row.get(5)?;
+    let start_line: i64 = row.get(7)?;
+    let mut props = strings(&[
+        ("name", row.get(1)?),
+        ("qualified_name", row.get(2)?),
+        ("kind", row.get(3)?),
+        ("visibility", row.get(4)?),
+        ("file_path", file_path.clone()),
+        ("signature", row.get(6)?),
+        ("doc_comment", row.get(10)?),
+    ]);
+    props.insert("start_line".to_string(), Value::from(start_line));
+    props.insert("end_line".to_string(), Value::from(row.get::<_, i64>(8)?));
+    if let Some(total) = row.get::<_, Option<i64>>(9)? {
+        props.insert("reference_count".to_string(), Value::from(total));
+        props.insert("is_truncated".to_string(), Value::Bool(true));
+    }
+    let key = vec![
+        SqlValue::Text(file_path),
+        SqlValue::Integer(start_line),
+        SqlValue::Text(id.clone()),
+    ];
+    let node = ExportNode {
+        id,
+        label: "Symbol".to_string(),
+        properties: props,
+    };
+    Ok((ExportRecord::Node(node), key))
+}
+
+fn defined_in_edge(row: &Row<'_>) -> rusqlite::Result<(ExportRecord, Key)> {
+    let id: String = row.get(0)?;
+    let file_hash: String = row.get(1)?;
+    let key = vec![
+        SqlValue::Text(row.get(2)?),
+        SqlValue::Integer(row.get(3)?),
+        SqlValue::Text(id.clone()),
+    ];
+    let edge = ExportEdge {
+        source: id,
+        target: format!("{FILE_ID_PREFIX}{file_hash}"),
+        kind: "DEFINED_IN".to_string(),
+        properties: BTreeMap::new(),
+    };
+    Ok((ExportRecord::Edge(edge), key))
+}
+
+fn symbol_edge(row: &Row<'_>) -> rusqlite::Result<(ExportRecord, Key)> {
+    let mut props = BTreeMap::new();
+    props.insert("line".to_string(), Value::from(row.get::<_, i64>(3)?));
+    props.insert("column".to_string(), Value::from(row.get::<_, i64>(4)?));
+    let edge = ExportEdge {
+        source: row.get(0)?,
+        target: row.get(1)?,
+        kind: row.get(2)?,
+        properties: props,
+    };
+    Ok((
+        ExportRecord::Edge(edge),
+        vec![SqlValue::Integer(row.get(5)?)],
+    ))
+}
+
+fn import_edge(row: &Row<'_>) -> rusqlite::Result<(ExportRecord, Key)> {
+    let source: String = row.get(0)?;
+    let target: String = row.get(1)?;
+    let mut props = strings(&[("module", row.get(2)?)]);
+    props.insert("line".to_string(), Value::from(row.get::<_, i64>(3)?));
+    let edge = ExportEdge {
+        source: format!("{FILE_ID_PREFIX}{source}"),
+        target: format!("{FILE_ID_PREFIX}{target}"),
+        kind: "IMPORTS".to_string(),
+        properties: props,
+    };
+    Ok((
+        ExportRecord::Edge(edge),
+        vec![SqlValue::Integer(row.get(4)?)],
+    ))
+}
+
+/// Collect non-empty string columns into properties, as the Neo4j export does
+fn strings(values: &[(&str, String)]) -> BTreeMap<String, Value> {
+    values
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| ((*key).to_string(), Value::String(value.clone())))
+        .collect()
+}
@@ -0,0 +1,440 @@
+//! SQLite storage: Embedded graph store for machines without Neo4j
+//!
+//! Stores the same graph as the Neo4j backend in a single database file.
+//! Nodes become tables (`commits`, `scan_runs`, `files`, `symbols`,
+//! `symbol_identities`) and relationships become link tables
+//! (`commit_files` for CONTAINS, `import_edges` for IMPORTS and `edges` for
+//! symbol-to-symbol edges). Tags added to scan runs after the fact are rows
+//! of `scan_run_tags`. MODIFIED edges from a commit to the symbols it
+//! changed are rows of `modified_symbols`. DEFINED_IN is the `content_hash`
+//! column of a symbol. A file's symbol counts by kind are a JSON object in its
+//! `symbol_counts` column. `directory_summaries` holds the DirectorySummary nodes of each
+//! commit, their parent directory a column (`''` at the scan root) and their
+//! CONTAINS edges to files rows of `directory_files`. Module nodes are
+//! rows of `modules`, their parent module a column (`''` at the top) and
+//! their CONTAINS edges to symbols rows of `module_symbols`. Endpoint and
+//! Message nodes are rows of `contracts`, their HANDLED_BY edges rows of
+//! `contract_handlers`. Repository nodes are rows of `repositories`, their
+//! package lists JSON arrays, and DEPENDS_ON edges between them rows of
+//! `repository_dependencies`. Symbols whose
+//! references were sampled have a row in
+//! `truncated_references` with the reported total, and the complexity
+//! proxies of a symbol are its row in `symbol_complexity`, its imported
+//! line and branch coverage its row in `symbol_coverage`. Diagnostic nodes are
+//! rows of `diagnostics`, linked to their file and, if any, their symbol.
+//! ExternalSymbol placeholders live in `external_symbols`, and the
+//! REFERENCES edges pointing at them in `external_references`. Parameter
+//! nodes are rows of `parameters` and RETURNS edges rows of `return_types`;
+//! their `type_name` columns stand in for the shared Type nodes. The FTS5
+//! table `symbol_search` indexes symbol names and doc comments for full-text
+//! search, kept in step with `symbols` by triggers. Symbol embeddings are
+//! rows of `symbol_embeddings`, as little-endian `f32` blobs searched by
+//! brute force.
+
+mod backup;
+mod export;
+mod store;
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::OpenFlags;
+
+use super::migrations::MigrationRunner;
+use super::store::StoreError;
+
+/// Schema created when a database is opened
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS commits (
+    sha TEXT PRIMARY KEY,
+    branch TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE IF NOT EXISTS scan_runs (
+    id TEXT PRIMARY KEY,
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    repo_path TEXT NOT NULL,
+    scanned_at TEXT NOT NULL,
+    version TEXT NOT NULL DEFAULT '',
+    report TEXT NOT NULL DEFAULT '',
+    previous_run TEXT NOT NULL DEFAULT '',
+    note TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'completed'
+);
+
+CREATE TABLE IF NOT EXISTS scan_run_tags (
+    scan_run_id TEXT NOT NULL REFERENCES scan_runs(id),
+    tag TEXT NOT NULL,
+    PRIMARY KEY (scan_run_id, tag)
+);
+
+CREATE TABLE IF NOT EXISTS files (
+    content_hash TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    language TEXT NOT NULL,
+    line_count INTEGER NOT NULL DEFAULT 0,
+    byte_size INTEGER NOT NULL DEFAULT 0,
+    symbol_counts TEXT NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE IF NOT EXISTS commit_files (
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    content_hash TEXT NOT NULL REFERENCES files(content_hash),
+    PRIMARY KEY (commit_sha, content_hash)
+);
+
+CREATE TABLE IF NOT EXISTS file_imports (
+    content_hash TEXT NOT NULL REFERENCES files(content_hash),
+    module TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (content_hash, position)
+);
+
+CREATE TABLE IF NOT EXISTS import_edges (
+    from_hash TEXT NOT NULL REFERENCES files(content_hash),
+    to_hash TEXT NOT NULL REFERENCES files(content_hash),
+    module TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    PRIMARY KEY (from_hash, to_hash, module)
+);
+
+CREATE TABLE IF NOT EXISTS symbol_identities (
+    key TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    qualified_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    file_path TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbols (
+    id TEXT PRIMARY KEY,
+    content_hash TEXT NOT NULL REFERENCES files(content_hash),
+    identity_key TEXT NOT NULL REFERENCES symbol_identities(key),
+    name TEXT NOT NULL,
+    qualified_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    visibility TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    doc_comment TEXT NOT NULL,
+    type_signature TEXT NOT NULL DEFAULT '',
+    resolved_type TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE IF NOT EXISTS edges (
+    source_id TEXT NOT NULL REFERENCES symbols(id),
+    target_id TEXT NOT NULL REFERENCES symbols(id),
+    kind TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    column INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS directory_summaries (
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    path TEXT NOT NULL,
+    parent TEXT NOT NULL DEFAULT '',
+    file_count INTEGER NOT NULL,
+    line_count INTEGER NOT NULL,
+    languages TEXT NOT NULL,
+    readme TEXT NOT NULL,
+    PRIMARY KEY (commit_sha, path)
+);
+
+CREATE TABLE IF NOT EXISTS directory_files (
+    commit_sha TEXT NOT NULL,
+    path TEXT NOT NULL,
+    content_hash TEXT NOT NULL REFERENCES files(content_hash),
+    PRIMARY KEY (commit_sha, path, content_hash),
+    FOREIGN KEY (commit_sha, path) REFERENCES directory_summaries(commit_sha, path)
+);
+
+CREATE TABLE IF NOT EXISTS modules (
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    parent TEXT NOT NULL,
+    language TEXT NOT NULL,
+    PRIMARY KEY (commit_sha, path)
+);
+
+CREATE TABLE IF NOT EXISTS module_symbols (
+    commit_sha TEXT NOT NULL,
+    path TEXT NOT NULL,
+    symbol_id TEXT NOT NULL REFERENCES symbols(id),
+    PRIMARY KEY (commit_sha, path, symbol_id),
+    FOREIGN KEY (commit_sha, path) REFERENCES modules(commit_sha, path)
+);
+
+CREATE TABLE IF NOT EXISTS contracts (
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    file_path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    identifier TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    PRIMARY KEY (commit_sha, file_path, name)
+);
+
+CREATE TABLE IF NOT EXISTS contract_handlers (
+    commit_sha TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    symbol_id TEXT NOT NULL REFERENCES symbols(id),
+    PRIMARY KEY (commit_sha, file_path, name, symbol_id),
+    FOREIGN KEY (commit_sha, file_path, name) REFERENCES contracts(commit_sha, file_path, name)
+);
+
+CREATE TABLE IF NOT EXISTS repositories (
+    path TEXT PRIMARY KEY,
+    packages TEXT NOT NULL,
+    dependencies TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS repository_dependencies (
+    from_path TEXT NOT NULL REFERENCES repositories(path),
+    to_path TEXT NOT NULL REFERENCES repositories(path),
+    packages TEXT NOT NULL,
+    PRIMARY KEY (from_path, to_path)
+);
+
+CREATE TABLE IF NOT EXISTS modified_symbols (
+    commit_sha TEXT NOT NULL REFERENCES commits(sha),
+    symbol_id TEXT NOT NULL REFERENCES symbols(id),
+    PRIMARY KEY (commit_sha, symbol_id)
+);
+
+CREATE TABLE IF NOT EXISTS truncated_references (
+    symbol_id TEXT PRIMARY KEY REFERENCES symbols(id),
+    total INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_complexity (
+    symbol_id TEXT PRIMARY KEY REFERENCES symbols(id),
+    outgoing_calls INTEGER NOT NULL,
+    fan_out INTEGER NOT NULL,
+    fan_in INTEGER NOT NULL,
+    incoming_references INTEGER NOT NULL,
+    score INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_coverage (
+    symbol_id TEXT PRIMARY KEY REFERENCES symbols(id),
+    lines_covered INTEGER NOT NULL,
+    lines_total INTEGER NOT NULL,
+    branches_covered INTEGER NOT NULL,
+    branches_total INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS diagnostics (
+    content_hash TEXT NOT NULL REFERENCES files(content_hash),
+    symbol_id TEXT REFERENCES symbols(id),
+    severity TEXT NOT NULL,
+    code TEXT NOT NULL,
+    source TEXT NOT NULL,
+    message TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    start_col INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    end_col INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS external_symbols (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    origin TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS external_references (
+    source_id TEXT NOT NULL REFERENCES symbols(id),
+    target_id TEXT NOT NULL REFERENCES external_symbols(id),
+    line INTEGER NOT NULL,
+    column INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS parameters (
+    symbol_id TEXT NOT NULL REFERENCES symbols(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    type_name TEXT NOT NULL,
+    PRIMARY KEY (symbol_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS return_types (
+    symbol_id TEXT PRIMARY KEY REFERENCES symbols(id),
+    type_name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_embeddings (
+    symbol_id TEXT PRIMARY KEY REFERENCES symbols(id),
+    embedding BLOB NOT NULL
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS symbol_search USING fts5(
+    name, qualified_name, doc_comment
+);
+
+CREATE TRIGGER IF NOT EXISTS symbol_search_insert AFTER INSERT ON symbols BEGIN
+    INSERT INTO symbol_search (rowid, name, qualified_name, doc_comment)
+    VALUES (new.rowid, new.name, new.qualified_name, new.doc_comment);
+END;
+
+CREATE TRIGGER IF NOT EXISTS symbol_search_delete AFTER DELETE ON symbols BEGIN
+    DELETE FROM symbol_search WHERE rowid = old.rowid;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_symbols_name ON symbols(name);
+CREATE INDEX IF NOT EXISTS idx_symbols_content_hash ON symbols(content_hash);
+CREATE INDEX IF NOT EXISTS idx_symbols_identity_key ON symbols(identity_key);
+CREATE INDEX IF NOT EXISTS idx_symbols_file_path ON symbols(file_path);
+CREATE INDEX IF NOT EXISTS idx_symbols_resolved_type ON symbols(resolved_type);
+CREATE INDEX IF NOT EXISTS idx_edges_source ON edges(source_id);
+CREATE INDEX IF NOT EXISTS idx_edges_target ON edges(target_id);
+CREATE INDEX IF NOT EXISTS idx_modified_symbols_symbol ON modified_symbols(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_files_path ON files(path);
+CREATE INDEX IF NOT EXISTS idx_symbol_complexity_score ON symbol_complexity(score);
+CREATE INDEX IF NOT EXISTS idx_diagnostics_content_hash ON diagnostics(content_hash);
+CREATE INDEX IF NOT EXISTS idx_external_references_source ON external_references(source_id);
+CREATE INDEX IF NOT EXISTS idx_parameters_type_name ON parameters(type_name);
+CREATE INDEX IF NOT EXISTS idx_return_types_type_name ON return_types(type_name);
+"#;
+
+/// Columns added to existing tables after their first release, as
+/// `(table, column, definition)`; databases created before get them on open
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("files", "line_count", "INTEGER NOT NULL DEFAULT 0"),
+    ("files", "byte_size", "INTEGER NOT NULL DEFAULT 0"),
+    ("files", "symbol_counts", "TEXT NOT NULL DEFAULT '{}'"),
+    ("symbols", "type_signature", "TEXT NOT NULL DEFAULT ''"),
+    ("symbols", "resolved_type", "TEXT NOT NULL DEFAULT ''"),
+    ("scan_runs", "report", "TEXT NOT NULL DEFAULT ''"),
+    ("scan_runs", "previous_run", "TEXT NOT NULL DEFAULT ''"),
+    ("scan_runs", "note", "TEXT NOT NULL DEFAULT ''"),
+    ("scan_runs", "status", "TEXT NOT NULL DEFAULT 'completed'"),
+    ("directory_summaries", "parent", "TEXT NOT NULL DEFAULT ''"),
+];
+
+/// Graph store backed by an SQLite database file
+///
+/// Queries run synchronously on the calling task while holding the
+/// connection lock; SQLite serializes writers anyway.
+pub struct SqliteStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStore {
+    /// Open or create a database file and ensure the schema exists
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or the schema cannot be
+    /// created.
+    pub fn open(path: &Path) -> Result<Self, StoreError> {
+        Self::init(rusqlite::Connection::open(path)?)
+    }
+
+    /// Open an existing database file without writing to it
+    ///
+    /// SQLite rejects every write through the store. The schema is neither
+    /// created nor migrated, so the file must be up to date.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or has migrations
+    /// pending.
+    pub fn open_read_only(path: &Path) -> Result<Self, StoreError> {
+        let conn = rusqlite::Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let pending = MigrationRunner::default().pending(schema_version(&conn)?)?;
+        if !pending.is_empty() {
+            return Err(StoreError::Backend(format!(
+                "{} has {} schema migrations pending; run `mother migrate` without --read-only",
+                path.display(),
+                pending.len()
+            )));
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open a private in-memory database, mainly for tests
+    ///
+    /// # Errors
+    /// Returns an error if the schema cannot be created.
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn init(conn: rusqlite::Connection) -> Result<Self, StoreError> {
+        // Refuse databases written by a newer release before touching them
+        let pending = MigrationRunner::default().pending(schema_version(&conn)?)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
+        add_missing_columns(&conn)?;
+        let index_symbols = !has_table(&conn, "symbol_search")?;
+        conn.execute_batch(SCHEMA)?;
+        if index_symbols {
+            conn.execute_batch(
+                "INSERT INTO symbol_search (rowid, name, qualified_name, doc_comment)
+                 SELECT rowid, name, qualified_name, doc_comment FROM symbols",
+            )?;
+        }
+        for migration in pending {
+            for statement in migration.sqlite {
+                conn.execute_batch(statement)?;
+            }
+            conn.execute_batch(&format!("PRAGMA user_version = {}", migration.version))?;
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Lock the connection, recovering it if a previous holder panicked
+    fn conn(&self) -> MutexGuard<'_, rusqlite::Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Add the [`ADDED_COLUMNS`] missing from tables of an existing database
+///
+/// Runs before the schema, whose indexes may cover the added columns. Tables
+/// that don't exist yet are left to the schema.
+fn add_missing_columns(conn: &rusqlite::Connection) -> Result<(), StoreError> {
+    for (table, column, definition) in ADDED_COLUMNS {
+        let mut stmt = conn.prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if !columns.is_empty() && !columns.iter().any(|name| name == column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Schema version the database was migrated to, kept in `user_version`
+fn schema_version(conn: &rusqlite::Connection) -> Result<u32, StoreError> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Whether a table exists, so tables added after a database was created
+/// can be filled from existing rows
+fn has_table(conn: &rusqlite::Connection, table: &str) -> Result<bool, StoreError> {
+    let count: i64 = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+#[cfg(test)]
+mod tests;
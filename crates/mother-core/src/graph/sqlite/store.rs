@@ -0,0 +1,2005 @@
+//! GraphStore implementation for SQLite
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use rusqlite::types::{Type, ValueRef};
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension, Row, Statement, Transaction};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::SqliteStore;
+use crate::embed::cosine_similarity;
+use crate::graph::backup::GraphBackup;
+use crate::graph::complexity::SymbolComplexity;
+use crate::graph::export::{ExportRecord, GraphExport};
+use crate::graph::integrity::{
+    dangling_detail, edge_subject, range_detail, IntegrityCheck, IntegrityIssue, IntegrityRepair,
+};
+use crate::graph::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, ExternalSymbolNode, FileMetrics,
+    FileWrite, FunctionSignature, ImportEdge, ModuleNode, RepositoryDependency, RepositoryNode,
+    ScanReport, ScanRun, ScanStatus, SymbolCoverage, SymbolNode,
+};
+use crate::graph::queries::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, ImporterResult,
+    Page, QueryParams, RawRow, ReferenceDirection, ReferenceGroup, ReferenceGrouping,
+    ReferenceResult, ScanResult, ScanRunResult, SortKey, SymbolMatch, SymbolResult,
+    SymbolVersionResult, TypeUsage, TypeUsageResult,
+};
+use crate::graph::scope::PathScope;
+use crate::graph::store::{GraphStore, QueryLanguage, RowStream, StoreError};
+
+/// Files no commit contains anymore
+const ORPHAN_FILES: &str =
+    "SELECT content_hash FROM files WHERE content_hash NOT IN (SELECT content_hash FROM commit_files)";
+
+/// Rows to delete, in foreign key order, once the commits without scan runs
+/// are gone: the rows of files no commit contains and of their symbols,
+/// then identities and external symbols nothing refers to anymore
+const ORPHAN_DELETES: [&str; 19] = [
+    "DELETE FROM directory_files WHERE content_hash IN ({files})",
+    "DELETE FROM file_imports WHERE content_hash IN ({files})",
+    "DELETE FROM import_edges WHERE from_hash IN ({files}) OR to_hash IN ({files})",
+    "DELETE FROM diagnostics WHERE content_hash IN ({files}) OR symbol_id IN ({symbols})",
+    "DELETE FROM edges WHERE source_id IN ({symbols}) OR target_id IN ({symbols})",
+    "DELETE FROM modified_symbols WHERE symbol_id IN ({symbols})",
+    "DELETE FROM module_symbols WHERE symbol_id IN ({symbols})",
+    "DELETE FROM contract_handlers WHERE symbol_id IN ({symbols})",
+    "DELETE FROM external_references WHERE source_id IN ({symbols})",
+    "DELETE FROM truncated_references WHERE symbol_id IN ({symbols})",
+    "DELETE FROM symbol_complexity WHERE symbol_id IN ({symbols})",
+    "DELETE FROM symbol_coverage WHERE symbol_id IN ({symbols})",
+    "DELETE FROM parameters WHERE symbol_id IN ({symbols})",
+    "DELETE FROM return_types WHERE symbol_id IN ({symbols})",
+    "DELETE FROM symbol_embeddings WHERE symbol_id IN ({symbols})",
+    "DELETE FROM symbols WHERE content_hash IN ({files})",
+    "DELETE FROM files WHERE content_hash IN ({files})",
+    "DELETE FROM symbol_identities WHERE key NOT IN (SELECT identity_key FROM symbols)",
+    "DELETE FROM external_symbols WHERE id NOT IN (SELECT target_id FROM external_references)",
+];
+
+/// Columns read by [`symbol_result`]
+const SYMBOL_COLUMNS: &str =
+    "s.id, s.name, s.qualified_name, s.kind, s.file_path, s.start_line, s.end_line";
+
+/// Largest code point, closing the prefix range of a scope
+const MAX_CHAR: char = '\u{10FFFF}';
+
+/// Columns read by [`reference_result`]
+const REFERENCE_COLUMNS: &str =
+    "src.name, src.file_path, e.line, tgt.name, tgt.file_path, tgt.start_line";
+
+#[async_trait]
+impl GraphStore for SqliteStore {
+    async fn create_scan_run(&self, scan_run: &ScanRun) -> Result<bool, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let commit_sha = scan_run.commit_sha.clone().unwrap_or_default();
+
+        let is_new = tx.execute(
+            "INSERT OR IGNORE INTO commits (sha, branch) VALUES (?1, ?2)",
+            params![commit_sha, scan_run.branch.clone().unwrap_or_default()],
+        )? > 0;
+        tx.execute(
+            "INSERT INTO scan_runs
+                 (id, commit_sha, repo_path, scanned_at, version, previous_run, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                scan_run.id,
+                commit_sha,
+                scan_run.repo_path,
+                scan_run.scanned_at.to_rfc3339(),
+                scan_run.version.clone().unwrap_or_default(),
+                scan_run.previous_run.clone().unwrap_or_default(),
+                scan_run.status.to_string(),
+            ],
+        )?;
+        tx.commit()?;
+
+        // An empty SHA (not a git repository) is always rescanned
+        Ok(is_new || commit_sha.is_empty())
+    }
+
+    async fn set_scan_report(&self, report: &ScanReport) -> Result<(), StoreError> {
+        let json = serde_json::to_string(report).map_err(|e| StoreError::Backend(e.to_string()))?;
+        self.conn().execute(
+            "UPDATE scan_runs SET report = ?2 WHERE id = ?1",
+            params![report.scan_run_id, json],
+        )?;
+        Ok(())
+    }
+
+    async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), StoreError> {
+        self.conn().execute(
+            "UPDATE scan_runs SET status = ?2 WHERE id = ?1",
+            params![scan_run_id, status.to_string()],
+        )?;
+        Ok(())
+    }
+
+    async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT OR IGNORE INTO scan_run_tags (scan_run_id, tag)
+             SELECT id, ?2 FROM scan_runs WHERE id = ?1",
+            params![scan_run_id, tag],
+        )?;
+        Ok(())
+    }
+
+    async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), StoreError> {
+        self.conn().execute(
+            "UPDATE scan_runs SET note = ?2 WHERE id = ?1",
+            params![scan_run_id, note],
+        )?;
+        Ok(())
+    }
+
+    async fn create_file_if_new(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+        language: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let is_new = insert_file(&tx, file_path, content_hash, language, commit_sha)?;
+        tx.commit()?;
+
+        Ok(is_new.then(|| content_hash.to_string()))
+    }
+
+    async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let stored = tx
+            .query_row(
+                "SELECT 1 FROM files WHERE content_hash = ?1",
+                params![content_hash],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if stored {
+            tx.execute(
+                "INSERT OR IGNORE INTO commit_files (commit_sha, content_hash) VALUES (?1, ?2)",
+                params![commit_sha, content_hash],
+            )?;
+        }
+        tx.commit()?;
+        Ok(stored)
+    }
+
+    async fn write_file(&self, file: &FileWrite) -> Result<bool, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let is_new = insert_file(
+            &tx,
+            &file.path,
+            &file.content_hash,
+            &file.language,
+            &file.commit_sha,
+        )?;
+        if is_new {
+            if let Some(metrics) = &file.metrics {
+                update_file_metrics(&tx, &file.content_hash, metrics)?;
+            }
+            insert_symbols(&tx, &file.symbols, &file.content_hash)?;
+            insert_signatures(&tx, &file.signatures)?;
+            insert_edges(&tx, &file.edges)?;
+        }
+        tx.commit()?;
+        Ok(is_new)
+    }
+
+    async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, StoreError> {
+        let removed = self.conn().execute(
+            "DELETE FROM commit_files
+             WHERE commit_sha = ?1
+               AND content_hash IN (SELECT content_hash FROM files WHERE path = ?2)",
+            params![commit_sha, file_path],
+        )?;
+        Ok(removed > 0)
+    }
+
+    async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), StoreError> {
+        update_file_metrics(&self.conn(), content_hash, metrics)
+    }
+
+    async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM file_imports WHERE content_hash = ?1",
+            params![content_hash],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO file_imports (content_hash, module, position)
+                 SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM files WHERE content_hash = ?1)",
+            )?;
+            for (position, module) in modules.iter().enumerate() {
+                insert.execute(params![content_hash, module, position as i64])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            // Endpoints are resolved by path among the commit's files
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO import_edges (from_hash, to_hash, module, line)
+                 SELECT a.content_hash, b.content_hash, ?4, ?5
+                 FROM commit_files ca JOIN files a ON a.content_hash = ca.content_hash,
+                      commit_files cb JOIN files b ON b.content_hash = cb.content_hash
+                 WHERE ca.commit_sha = ?1 AND a.path = ?2
+                   AND cb.commit_sha = ?1 AND b.path = ?3",
+            )?;
+            for edge in edges {
+                insert.execute(params![
+                    commit_sha,
+                    edge.from_path,
+                    edge.to_path,
+                    edge.module,
+                    i64::from(edge.line),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut upsert = tx.prepare(
+                "INSERT INTO directory_summaries
+                     (commit_sha, path, parent, file_count, line_count, languages, readme)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (commit_sha, path) DO UPDATE SET
+                     parent = excluded.parent,
+                     file_count = excluded.file_count,
+                     line_count = excluded.line_count,
+                     languages = excluded.languages,
+                     readme = excluded.readme",
+            )?;
+            let mut link = tx.prepare(
+                "INSERT OR IGNORE INTO directory_files (commit_sha, path, content_hash)
+                 SELECT ?1, ?2, f.content_hash FROM files f
+                 JOIN commit_files cf ON cf.content_hash = f.content_hash
+                 WHERE cf.commit_sha = ?1 AND f.path = ?3",
+            )?;
+            for dir in summaries {
+                upsert.execute(params![
+                    commit_sha,
+                    dir.path,
+                    dir.parent.clone().unwrap_or_default(),
+                    i64::try_from(dir.file_count).unwrap_or(i64::MAX),
+                    i64::try_from(dir.line_count).unwrap_or(i64::MAX),
+                    dir.languages.join(","),
+                    dir.readme.clone().unwrap_or_default(),
+                ])?;
+                for file in &dir.files {
+                    link.execute(params![commit_sha, dir.path, file])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM module_symbols WHERE commit_sha = ?1",
+            params![commit_sha],
+        )?;
+        tx.execute(
+            "DELETE FROM modules WHERE commit_sha = ?1",
+            params![commit_sha],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO modules (commit_sha, path, name, parent, language)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            let mut contain = tx.prepare(
+                "INSERT OR IGNORE INTO module_symbols (commit_sha, path, symbol_id)
+                 SELECT ?1, ?2, id FROM symbols WHERE id = ?3",
+            )?;
+            for module in modules {
+                insert.execute(params![
+                    commit_sha,
+                    module.path,
+                    module.name,
+                    module.parent.clone().unwrap_or_default(),
+                    module.language,
+                ])?;
+                for symbol_id in &module.symbol_ids {
+                    contain.execute(params![commit_sha, module.path, symbol_id])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM contract_handlers WHERE commit_sha = ?1",
+            params![commit_sha],
+        )?;
+        tx.execute(
+            "DELETE FROM contracts WHERE commit_sha = ?1",
+            params![commit_sha],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO contracts
+                     (commit_sha, file_path, name, kind, identifier, line)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            let mut handle = tx.prepare(
+                "INSERT OR IGNORE INTO contract_handlers (commit_sha, file_path, name, symbol_id)
+                 SELECT ?1, ?2, ?3, id FROM symbols WHERE id = ?4",
+            )?;
+            for contract in contracts {
+                insert.execute(params![
+                    commit_sha,
+                    contract.file_path,
+                    contract.name,
+                    contract.kind,
+                    contract.identifier,
+                    contract.line,
+                ])?;
+                for symbol_id in &contract.handler_ids {
+                    handle.execute(params![
+                        commit_sha,
+                        contract.file_path,
+                        contract.name,
+                        symbol_id
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_symbols_batch(
+        &self,
+        symbols: &[SymbolNode],
+        content_hash: &str,
+    ) -> Result<(), StoreError> {
+        if symbols.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        insert_symbols(&tx, symbols, content_hash)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_edge(&self, edge: &Edge) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO edges (source_id, target_id, kind, line, column)
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)
+               AND EXISTS (SELECT 1 FROM symbols WHERE id = ?2)",
+            params![
+                edge.source_id,
+                edge.target_id,
+                edge.kind.to_string(),
+                i64::from(edge.line.unwrap_or(0)),
+                i64::from(edge.column.unwrap_or(0)),
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        insert_edges(&tx, edges)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO modified_symbols (commit_sha, symbol_id)
+                 SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?2)",
+            )?;
+            for id in symbol_ids {
+                insert.execute(params![commit_sha, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO truncated_references (symbol_id, total)
+             SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+            params![symbol_id, i64::try_from(total).unwrap_or(i64::MAX)],
+        )?;
+        Ok(())
+    }
+
+    async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO symbol_complexity
+                     (symbol_id, outgoing_calls, fan_out, fan_in, incoming_references, score)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+            )?;
+            let count = |n: usize| i64::try_from(n).unwrap_or(i64::MAX);
+            for c in complexity {
+                insert.execute(params![
+                    c.symbol_id,
+                    count(c.outgoing_calls),
+                    count(c.fan_out),
+                    count(c.fan_in),
+                    count(c.incoming_references),
+                    count(c.score),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO symbol_coverage
+                     (symbol_id, lines_covered, lines_total, branches_covered, branches_total)
+                 SELECT ?1, ?2, ?3, ?4, ?5 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+            )?;
+            for c in coverage {
+                insert.execute(params![
+                    c.symbol_id,
+                    c.lines_covered,
+                    c.lines_total,
+                    c.branches_covered,
+                    c.branches_total,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), StoreError> {
+        let packages = serde_json::to_string(&repository.packages)
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+        let dependencies = serde_json::to_string(&repository.dependencies)
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+        self.conn().execute(
+            "INSERT OR REPLACE INTO repositories (path, packages, dependencies)
+             VALUES (?1, ?2, ?3)",
+            params![repository.path, packages, dependencies],
+        )?;
+        Ok(())
+    }
+
+    async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM repository_dependencies", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO repository_dependencies (from_path, to_path, packages)
+                 SELECT ?1, ?2, ?3
+                 WHERE EXISTS (SELECT 1 FROM repositories WHERE path = ?1)
+                   AND EXISTS (SELECT 1 FROM repositories WHERE path = ?2)",
+            )?;
+            for dependency in dependencies {
+                let packages = serde_json::to_string(&dependency.packages)
+                    .map_err(|e| StoreError::Backend(e.to_string()))?;
+                insert.execute(params![dependency.from, dependency.to, packages])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM scan_run_tags WHERE scan_run_id = ?1",
+            params![id],
+        )?;
+        let mut summary = DeleteSummary {
+            runs: tx.execute("DELETE FROM scan_runs WHERE id = ?1", params![id])?,
+            ..DeleteSummary::default()
+        };
+        if summary.runs == 0 {
+            return Ok(summary);
+        }
+        // Like the PREVIOUS_RUN edges of Neo4j, links to the run go with it
+        tx.execute(
+            "UPDATE scan_runs SET previous_run = '' WHERE previous_run = ?1",
+            params![id],
+        )?;
+
+        let orphan_commits =
+            "SELECT sha FROM commits WHERE sha NOT IN (SELECT commit_sha FROM scan_runs)";
+        for table in [
+            "directory_files",
+            "directory_summaries",
+            "module_symbols",
+            "modules",
+            "contract_handlers",
+            "contracts",
+            "commit_files",
+            "modified_symbols",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE commit_sha IN ({orphan_commits})"),
+                [],
+            )?;
+        }
+        summary.commits = tx.execute(
+            &format!("DELETE FROM commits WHERE sha IN ({orphan_commits})"),
+            [],
+        )?;
+
+        (summary.files, summary.symbols) = delete_orphan_files(&tx)?;
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    async fn repair_integrity(&self) -> Result<IntegrityRepair, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let edges = tx.execute(
+            "DELETE FROM edges
+             WHERE source_id NOT IN (SELECT id FROM symbols)
+                OR target_id NOT IN (SELECT id FROM symbols)",
+            [],
+        )? + tx.execute(
+            "DELETE FROM external_references
+             WHERE source_id NOT IN (SELECT id FROM symbols)
+                OR target_id NOT IN (SELECT id FROM external_symbols)",
+            [],
+        )?;
+        let (files, symbols) = delete_orphan_files(&tx)?;
+        tx.commit()?;
+        Ok(IntegrityRepair {
+            edges,
+            files,
+            symbols,
+        })
+    }
+
+    async fn restore(&self, backup: &GraphBackup) -> Result<usize, StoreError> {
+        super::backup::restore(&mut self.conn(), backup)
+    }
+
+    async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM diagnostics WHERE content_hash = ?1",
+            params![content_hash],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO diagnostics (content_hash, symbol_id, severity, code, source, message,
+                                          start_line, start_col, end_line, end_col)
+                 SELECT ?1, (SELECT id FROM symbols WHERE id = ?2), ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                 WHERE EXISTS (SELECT 1 FROM files WHERE content_hash = ?1)",
+            )?;
+            for d in diagnostics {
+                insert.execute(params![
+                    content_hash,
+                    d.symbol_id,
+                    d.severity,
+                    d.code.clone().unwrap_or_default(),
+                    d.source.clone().unwrap_or_default(),
+                    d.message,
+                    i64::from(d.start_line),
+                    i64::from(d.start_col),
+                    i64::from(d.end_line),
+                    i64::from(d.end_col),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        insert_signatures(&tx, signatures)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO external_symbols (id, name, file_path, line, origin)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                target.id,
+                target.name,
+                target.file_path,
+                i64::from(target.line),
+                target.origin,
+            ],
+        )?;
+        tx.execute(
+            "INSERT INTO external_references (source_id, target_id, line, column)
+             SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+            params![source_id, target.id, i64::from(line), i64::from(column)],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS} FROM symbols s
+             WHERE instr(lower(s.name), lower(?1)) > 0 {}
+             ORDER BY {}",
+            scope_condition(scope, 2),
+            symbol_order(page.sort)
+        ))?;
+        let (low, high) = scope_range(scope);
+        let rows = if scope.is_some() {
+            stmt.query_map(params![pattern, low, high], symbol_result)?
+        } else {
+            stmt.query_map(params![pattern], symbol_result)?
+        };
+        collect_in_scope(rows, scope, page)
+    }
+
+    async fn search_symbols(&self, text: &str, page: Page) -> Result<Vec<SymbolMatch>, StoreError> {
+        let Some(search) = fts_query(text) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.conn();
+        // bm25 is lower for better matches; names weigh most, docs least
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS}, -bm25(symbol_search, 10.0, 5.0, 1.0) AS score
+             FROM symbol_search
+             JOIN symbols s ON s.rowid = symbol_search.rowid
+             WHERE symbol_search MATCH ?1
+             ORDER BY score DESC, s.name
+             {}",
+            page_clause(page)
+        ))?;
+        let rows = stmt.query_map(params![search], |row| {
+            let symbol = symbol_result(row)?;
+            Ok(SymbolMatch {
+                id: symbol.id,
+                name: symbol.name,
+                qualified_name: symbol.qualified_name,
+                kind: symbol.kind,
+                file_path: symbol.file_path,
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+                score: row.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, StoreError> {
+        let conn = self.conn();
+        let target: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT embedding FROM symbol_embeddings WHERE symbol_id = ?1",
+                params![symbol_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(target) = target.map(|bytes| bytes_vector(&bytes)) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS}, e.embedding FROM symbols s
+             JOIN commit_files cf ON cf.content_hash = s.content_hash
+             JOIN symbol_embeddings e ON e.symbol_id = s.id
+             WHERE cf.commit_sha = ?1 AND s.id <> ?2"
+        ))?;
+        let rows = stmt.query_map(params![commit_sha, symbol_id], |row| {
+            let symbol = symbol_result(row)?;
+            let embedding: Vec<u8> = row.get(7)?;
+            Ok(SymbolMatch {
+                id: symbol.id,
+                name: symbol.name,
+                qualified_name: symbol.qualified_name,
+                kind: symbol.kind,
+                file_path: symbol.file_path,
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+                score: f64::from(cosine_similarity(&target, &bytes_vector(&embedding))),
+            })
+        })?;
+        let mut matches = rows.collect::<Result<Vec<_>, _>>()?;
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS} FROM symbols s
+             WHERE instr(s.file_path, ?1) > 0
+             ORDER BY s.start_line"
+        ))?;
+        let rows = stmt.query_map(params![file_path], symbol_result)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn modified_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS} FROM symbols s
+             JOIN modified_symbols m ON m.symbol_id = s.id
+             WHERE m.commit_sha = ?1
+             ORDER BY s.file_path, s.start_line"
+        ))?;
+        let rows = stmt.query_map(params![commit_sha], symbol_result)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYMBOL_COLUMNS} FROM symbols s
+             JOIN commit_files cf ON cf.content_hash = s.content_hash
+             WHERE cf.commit_sha = ?2 AND instr(lower(s.name), lower(?1)) > 0 {}
+             ORDER BY {}",
+            scope_condition(scope, 3),
+            symbol_order(page.sort)
+        ))?;
+        let (low, high) = scope_range(scope);
+        let rows = if scope.is_some() {
+            stmt.query_map(params![pattern, commit_sha, low, high], symbol_result)?
+        } else {
+            stmt.query_map(params![pattern, commit_sha], symbol_result)?
+        };
+        collect_in_scope(rows, scope, page)
+    }
+
+    async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.name, s.qualified_name, s.kind, s.visibility, s.file_path,
+                    s.start_line, s.end_line, s.signature, s.doc_comment,
+                    s.type_signature, s.resolved_type
+             FROM symbols s
+             JOIN commit_files cf ON cf.content_hash = s.content_hash
+             WHERE cf.commit_sha = ?1
+             ORDER BY s.file_path, s.start_line",
+        )?;
+        let rows = stmt.query_map(params![commit_sha], symbol_node)?;
+        let mut symbols = Vec::new();
+        for row in rows {
+            symbols.extend(row?);
+        }
+        Ok(symbols)
+    }
+
+    async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE lineage(id) AS (
+                 SELECT s.id FROM symbol_identities i
+                 JOIN symbols s ON s.identity_key = i.key
+                 WHERE i.qualified_name = ?1 OR i.name = ?1
+                 UNION
+                 SELECT CASE WHEN e.source_id = l.id THEN e.target_id ELSE e.source_id END
+                 FROM lineage l
+                 JOIN edges e ON e.kind IN ('SAME_AS', 'EVOLVED_FROM')
+                     AND (e.source_id = l.id OR e.target_id = l.id)
+             )
+             SELECT s.id, i.key, s.qualified_name, s.kind, s.file_path, s.start_line,
+                    s.end_line, s.signature, s.content_hash,
+                    (SELECT group_concat(cf.commit_sha, char(10)) FROM commit_files cf
+                     WHERE cf.content_hash = s.content_hash)
+             FROM lineage l
+             JOIN symbols s ON s.id = l.id
+             JOIN symbol_identities i ON i.key = s.identity_key
+             ORDER BY i.key, s.start_line
+             LIMIT 100",
+        )?;
+        let rows = stmt.query_map(params![qualified_name], |row| {
+            let shas: Option<String> = row.get(9)?;
+            Ok(SymbolVersionResult {
+                id: row.get(0)?,
+                identity_key: row.get(1)?,
+                qualified_name: row.get(2)?,
+                kind: row.get(3)?,
+                file_path: row.get(4)?,
+                start_line: row.get(5)?,
+                end_line: row.get(6)?,
+                signature: row.get(7)?,
+                content_hash: row.get(8)?,
+                commit_shas: shas
+                    .map(|s| s.lines().map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, StoreError> {
+        let conn = self.conn();
+        // Files are never deleted, so the highest rowid is the latest version
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.name, s.qualified_name, s.kind, s.visibility, s.file_path,
+                    s.start_line, s.end_line, s.signature, s.doc_comment,
+                    s.type_signature, s.resolved_type
+             FROM symbols s
+             WHERE s.content_hash = (
+                 SELECT content_hash FROM files
+                 WHERE path = ?1 AND content_hash != ?2
+                 ORDER BY rowid DESC LIMIT 1
+             )
+             ORDER BY s.start_line, s.rowid",
+        )?;
+        let rows = stmt.query_map(params![file_path, content_hash], symbol_node)?;
+        let mut symbols = Vec::new();
+        for row in rows {
+            symbols.extend(row?);
+        }
+        Ok(symbols)
+    }
+
+    async fn find_references_to(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        let order = match page.sort {
+            Some(SortKey::Name) => "src.name, src.file_path, e.line",
+            _ => "src.file_path, e.line",
+        };
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {REFERENCE_COLUMNS} FROM edges e
+             JOIN symbols src ON src.id = e.source_id
+             JOIN symbols tgt ON tgt.id = e.target_id
+             WHERE e.kind = 'REFERENCES' AND tgt.name = ?1
+             ORDER BY {order}
+             {}",
+            page_clause(page)
+        ))?;
+        let rows = stmt.query_map(params![symbol_name], reference_result)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn find_references_from(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        let order = match page.sort {
+            Some(SortKey::Name) => "tgt.name, tgt.file_path, tgt.start_line",
+            _ => "tgt.file_path, tgt.start_line",
+        };
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {REFERENCE_COLUMNS} FROM edges e
+             JOIN symbols src ON src.id = e.source_id
+             JOIN symbols tgt ON tgt.id = e.target_id
+             WHERE e.kind = 'REFERENCES' AND src.name = ?1
+             ORDER BY {order}
+             {}",
+            page_clause(page)
+        ))?;
+        let rows = stmt.query_map(params![symbol_name], reference_result)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, StoreError> {
+        let (matched, other, line) = match direction {
+            ReferenceDirection::To => ("tgt", "src", "e.line"),
+            ReferenceDirection::From => ("src", "tgt", "tgt.start_line"),
+        };
+        let keys = match grouping {
+            ReferenceGrouping::Unique => "file, name, line",
+            ReferenceGrouping::Symbol => "file, name",
+            ReferenceGrouping::File => "file",
+        };
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT file, json_group_array(DISTINCT name), json_group_array(DISTINCT line),
+                    COUNT(*)
+             FROM (
+                 SELECT {other}.file_path AS file, {other}.name AS name, {line} AS line
+                 FROM edges e
+                 JOIN symbols src ON src.id = e.source_id
+                 JOIN symbols tgt ON tgt.id = e.target_id
+                 WHERE e.kind = 'REFERENCES' AND {matched}.name = ?1
+             )
+             GROUP BY {keys}
+             ORDER BY file, MIN(line)
+             {}",
+            page_clause(page)
+        ))?;
+        let rows = stmt.query_map(params![symbol_name], |row| {
+            Ok(ReferenceGroup::new(
+                row.get(0)?,
+                json_column(row, 1)?,
+                json_column(row, 2)?,
+                row.get(3)?,
+            ))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, StoreError> {
+        let conn = self.conn();
+        let summary = conn
+            .query_row(
+                &format!("{DIRECTORY_SELECT} WHERE d.commit_sha = ?1 AND d.path = ?2"),
+                params![commit_sha, path],
+                directory_summary,
+            )
+            .optional()?;
+        Ok(summary)
+    }
+
+    async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "{DIRECTORY_SELECT} WHERE d.commit_sha = ?1 ORDER BY d.path"
+        ))?;
+        let rows = stmt.query_map(params![commit_sha], directory_summary)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT m.path, m.name, m.parent, m.language,
+                    (SELECT json_group_array(symbol_id) FROM
+                        (SELECT ms.symbol_id FROM module_symbols ms
+                         WHERE ms.commit_sha = m.commit_sha AND ms.path = m.path
+                         ORDER BY ms.symbol_id))
+             FROM modules m WHERE m.commit_sha = ?1
+             ORDER BY m.path",
+        )?;
+        let rows = stmt.query_map(params![commit_sha], |row| {
+            let parent: String = row.get(2)?;
+            Ok(ModuleNode {
+                path: row.get(0)?,
+                name: row.get(1)?,
+                parent: (!parent.is_empty()).then_some(parent),
+                language: row.get(3)?,
+                symbol_ids: json_column(row, 4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn contracts_at_commit(&self, commit_sha: &str) -> Result<Vec<ContractNode>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT c.kind, c.name, c.identifier, c.file_path, c.line,
+                    (SELECT json_group_array(symbol_id) FROM
+                        (SELECT h.symbol_id FROM contract_handlers h
+                         WHERE h.commit_sha = c.commit_sha AND h.file_path = c.file_path
+                           AND h.name = c.name
+                         ORDER BY h.symbol_id))
+             FROM contracts c WHERE c.commit_sha = ?1
+             ORDER BY c.file_path, c.line, c.name",
+        )?;
+        let rows = stmt.query_map(params![commit_sha], |row| {
+            Ok(ContractNode {
+                kind: row.get(0)?,
+                name: row.get(1)?,
+                identifier: row.get(2)?,
+                file_path: row.get(3)?,
+                line: row.get(4)?,
+                handler_ids: json_column(row, 5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn repositories(&self) -> Result<Vec<RepositoryNode>, StoreError> {
+        let conn = self.conn();
+        let mut stmt =
+            conn.prepare("SELECT path, packages, dependencies FROM repositories ORDER BY path")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(RepositoryNode {
+                path: row.get(0)?,
+                packages: json_column(row, 1)?,
+                dependencies: json_column(row, 2)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT from_path, to_path, packages FROM repository_dependencies
+             ORDER BY from_path, to_path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(RepositoryDependency {
+                from: row.get(0)?,
+                to: row.get(1)?,
+                packages: json_column(row, 2)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT d.severity, d.code, d.source, d.message, f.path, d.start_line, d.start_col,
+                    coalesce(s.qualified_name, '')
+             FROM diagnostics d
+             JOIN files f ON f.content_hash = d.content_hash
+             JOIN commit_files cf ON cf.content_hash = d.content_hash
+             LEFT JOIN symbols s ON s.id = d.symbol_id
+             WHERE cf.commit_sha = ?1 AND (?2 = '' OR d.severity = ?2)
+             ORDER BY f.path, d.start_line, d.start_col",
+        )?;
+        let rows = stmt.query_map(params![commit_sha, severity.unwrap_or_default()], |row| {
+            Ok(DiagnosticResult {
+                severity: row.get(0)?,
+                code: row.get(1)?,
+                source: row.get(2)?,
+                message: row.get(3)?,
+                file_path: row.get(4)?,
+                start_line: row.get(5)?,
+                start_col: row.get(6)?,
+                symbol: row.get(7)?,
+            })
+        })?;
+        let mut diagnostics = Vec::new();
+        for row in rows {
+            let diagnostic = row?;
+            if scope.is_none_or(|scope| scope.matches(&diagnostic.file_path)) {
+                diagnostics.push(diagnostic);
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, StoreError> {
+        let conn = self.conn();
+        let sql = match usage {
+            TypeUsage::Parameter => {
+                "SELECT s.qualified_name, s.kind, s.file_path, s.start_line,
+                        p.name, p.position, p.type_name
+                 FROM parameters p
+                 JOIN symbols s ON s.id = p.symbol_id
+                 JOIN commit_files cf ON cf.content_hash = s.content_hash
+                 WHERE cf.commit_sha = ?1 AND p.type_name != '' AND instr(p.type_name, ?2) > 0
+                 ORDER BY s.file_path, s.start_line, p.position"
+            }
+            TypeUsage::Return => {
+                "SELECT s.qualified_name, s.kind, s.file_path, s.start_line,
+                        NULL, NULL, r.type_name
+                 FROM return_types r
+                 JOIN symbols s ON s.id = r.symbol_id
+                 JOIN commit_files cf ON cf.content_hash = s.content_hash
+                 WHERE cf.commit_sha = ?1 AND instr(r.type_name, ?2) > 0
+                 ORDER BY s.file_path, s.start_line"
+            }
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![commit_sha, type_name], |row| {
+            Ok(TypeUsageResult {
+                qualified_name: row.get(0)?,
+                kind: row.get(1)?,
+                file_path: row.get(2)?,
+                start_line: row.get(3)?,
+                parameter: row.get(4)?,
+                position: row.get(5)?,
+                type_name: row.get(6)?,
+            })
+        })?;
+        let mut functions = Vec::new();
+        for row in rows {
+            let function = row?;
+            if scope.is_none_or(|scope| scope.matches(&function.file_path)) {
+                functions.push(function);
+            }
+        }
+        Ok(functions)
+    }
+
+    async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, StoreError> {
+        let order = match page.sort {
+            Some(SortKey::Language) => "f.language, f.path",
+            Some(SortKey::Symbols) => "symbol_count DESC, f.path",
+            _ => "f.path",
+        };
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT f.path, f.language,
+                    (SELECT count(*) FROM symbols s WHERE s.content_hash = f.content_hash)
+                        AS symbol_count
+             FROM files f
+             WHERE ?1 IS NULL OR instr(f.path, ?1) > 0
+             ORDER BY {order}
+             {}",
+            page_clause(page)
+        ))?;
+        let rows = stmt.query_map(params![pattern], |row| {
+            Ok(FileResult {
+                path: row.get(0)?,
+                language: row.get(1)?,
+                symbol_count: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT f.path, f.language, f.line_count, f.byte_size, f.symbol_counts
+             FROM files f
+             JOIN commit_files cf ON cf.content_hash = f.content_hash
+             WHERE cf.commit_sha = ?1 AND (?2 IS NULL OR instr(f.path, ?2) > 0)
+             ORDER BY f.path",
+        )?;
+        let rows = stmt.query_map(params![commit_sha, pattern], |row| {
+            let symbol_counts: BTreeMap<String, i64> = json_column(row, 4)?;
+            Ok(FileMetricsResult {
+                path: row.get(0)?,
+                language: row.get(1)?,
+                line_count: row.get(2)?,
+                byte_size: row.get(3)?,
+                symbol_count: symbol_counts.values().sum(),
+                symbol_counts,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError> {
+        let conn = self.conn();
+        let mut importers: Vec<ImporterResult> = Vec::new();
+
+        // Module paths as written, including submodules of `module`
+        let mut stmt = conn.prepare(
+            "SELECT f.path, f.language, fi.module FROM file_imports fi
+             JOIN files f ON f.content_hash = fi.content_hash
+             WHERE substr(fi.module, 1, length(?1)) = ?1
+             ORDER BY fi.position",
+        )?;
+        let written = stmt
+            .query_map(params![module], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<Result<Vec<(String, String, String)>, _>>()?;
+        for (path, language, imported) in &written {
+            let is_match = written
+                .iter()
+                .any(|(p, _, m)| p == path && is_module_or_submodule(m, module));
+            if is_match {
+                merge_importer(&mut importers, path, language, imported);
+            }
+        }
+
+        // Resolved imports of repository files whose path ends with `module`
+        let mut stmt = conn.prepare(
+            "SELECT a.path, a.language, ie.module FROM import_edges ie
+             JOIN files a ON a.content_hash = ie.from_hash
+             JOIN files b ON b.content_hash = ie.to_hash
+             WHERE length(b.path) >= length(?1)
+               AND substr(b.path, length(b.path) - length(?1) + 1) = ?1",
+        )?;
+        let resolved = stmt
+            .query_map(params![module], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<Result<Vec<(String, String, String)>, _>>()?;
+        for (path, language, imported) in &resolved {
+            merge_importer(&mut importers, path, language, imported);
+        }
+
+        importers.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(importers)
+    }
+
+    async fn stats(&self) -> Result<GraphStats, StoreError> {
+        let conn = self.conn();
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0));
+        Ok(GraphStats {
+            commits: count("SELECT count(*) FROM commits")?,
+            files: count("SELECT count(*) FROM files")?,
+            symbols: count("SELECT count(*) FROM symbols")?,
+            scan_runs: count("SELECT count(*) FROM scan_runs")?,
+            references: count(
+                "SELECT (SELECT count(*) FROM edges WHERE kind = 'REFERENCES')
+                      + (SELECT count(*) FROM external_references)",
+            )?,
+            defined_in: count("SELECT count(*) FROM symbols")?,
+            contains: count("SELECT count(*) FROM commit_files")?,
+        })
+    }
+
+    async fn schema_version(&self) -> Result<u32, StoreError> {
+        super::schema_version(&self.conn())
+    }
+
+    async fn resolve_commit(&self, version: Option<&str>) -> Result<Option<String>, StoreError> {
+        let conn = self.conn();
+        let sha = match version.and_then(|v| v.strip_prefix("tag:")) {
+            Some(tag) => conn.query_row(
+                "SELECT r.commit_sha FROM scan_runs r
+                 JOIN scan_run_tags t ON t.scan_run_id = r.id
+                 WHERE t.tag = ?1
+                 ORDER BY r.scanned_at DESC
+                 LIMIT 1",
+                params![tag],
+                |row| row.get(0),
+            ),
+            None => conn.query_row(
+                "SELECT commit_sha FROM scan_runs
+                 WHERE (?1 IS NULL AND status = 'completed')
+                    OR version = ?1 OR substr(commit_sha, 1, length(?1)) = ?1
+                 ORDER BY scanned_at DESC
+                 LIMIT 1",
+                params![version],
+                |row| row.get(0),
+            ),
+        };
+        Ok(sha.optional()?)
+    }
+
+    async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT r.id, c.sha, c.branch, r.version, r.repo_path, r.scanned_at, r.previous_run,
+                    r.note, r.status,
+                    (SELECT json_group_array(tag)
+                     FROM (SELECT tag FROM scan_run_tags WHERE scan_run_id = r.id ORDER BY tag))
+             FROM scan_runs r
+             JOIN commits c ON c.sha = r.commit_sha
+             ORDER BY r.scanned_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ScanRunResult {
+                id: row.get(0)?,
+                commit_sha: row.get(1)?,
+                branch: row.get(2)?,
+                version: row.get(3)?,
+                repo_path: row.get(4)?,
+                scanned_at: row.get(5)?,
+                previous_run: row.get(6)?,
+                tags: json_column(row, 9)?,
+                note: row.get(7)?,
+                status: row.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, StoreError> {
+        let report: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT report FROM scan_runs WHERE id = ?1",
+                params![scan_run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match report.filter(|json| !json.is_empty()) {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| StoreError::Backend(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, StoreError> {
+        let conn = self.conn();
+        let mut issues = Vec::new();
+
+        // Foreign keys keep these out unless rows were written without them
+        let mut stmt = conn.prepare(
+            "SELECT source_id, kind, target_id, source_missing, target_missing FROM (
+                 SELECT source_id, kind, target_id,
+                        source_id NOT IN (SELECT id FROM symbols) AS source_missing,
+                        target_id NOT IN (SELECT id FROM symbols) AS target_missing
+                 FROM edges
+                 UNION ALL
+                 SELECT source_id, 'REFERENCES', target_id,
+                        source_id NOT IN (SELECT id FROM symbols),
+                        target_id NOT IN (SELECT id FROM external_symbols)
+                 FROM external_references
+             )
+             WHERE source_missing OR target_missing",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(IntegrityIssue {
+                check: IntegrityCheck::DanglingEdge,
+                subject: edge_subject(
+                    &row.get::<_, String>(0)?,
+                    &row.get::<_, String>(1)?,
+                    &row.get::<_, String>(2)?,
+                ),
+                detail: dangling_detail(row.get(3)?, row.get(4)?),
+            })
+        })?;
+        for issue in rows {
+            issues.push(issue?);
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT path, content_hash FROM files WHERE content_hash IN ({ORPHAN_FILES})"
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok(IntegrityIssue {
+                check: IntegrityCheck::OrphanFile,
+                subject: row.get(0)?,
+                detail: format!("no commit contains content {}", row.get::<_, String>(1)?),
+            })
+        })?;
+        for issue in rows {
+            issues.push(issue?);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT qualified_name, file_path, start_line, end_line
+             FROM symbols WHERE end_line < start_line",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(IntegrityIssue {
+                check: IntegrityCheck::InvertedRange,
+                subject: row.get(0)?,
+                detail: range_detail(&row.get::<_, String>(1)?, row.get(2)?, row.get(3)?),
+            })
+        })?;
+        for issue in rows {
+            issues.push(issue?);
+        }
+
+        // Symbol IDs are the primary key, so they cannot be duplicated here
+
+        issues.sort();
+        Ok(issues)
+    }
+
+    async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, StoreError> {
+        let conn = self.conn();
+        // The bare columns of an aggregate query come from the MAX row, so
+        // each commit reports the version of its latest scan run
+        let mut stmt = conn.prepare(
+            "SELECT c.sha, c.branch, r.version, MAX(r.scanned_at)
+             FROM scan_runs r
+             JOIN commits c ON c.sha = r.commit_sha
+             WHERE r.status = 'completed' AND c.branch = COALESCE(?1, (
+                 SELECT c2.branch FROM scan_runs r2
+                 JOIN commits c2 ON c2.sha = r2.commit_sha
+                 WHERE r2.status = 'completed'
+                 ORDER BY r2.scanned_at DESC
+                 LIMIT 1
+             ))
+             GROUP BY c.sha
+             ORDER BY MAX(r.scanned_at) DESC
+             LIMIT ?2",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![branch, limit], |row| {
+            Ok(ScanResult {
+                commit_sha: row.get(0)?,
+                branch: row.get(1)?,
+                version: row.get(2)?,
+                scanned_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, StoreError> {
+        self.stream_export(commit_sha)
+            .try_fold(GraphExport::default(), |mut graph, record| async move {
+                graph.push(record);
+                Ok(graph)
+            })
+            .await
+    }
+
+    fn stream_export<'a>(&'a self, commit_sha: &'a str) -> RowStream<'a, ExportRecord> {
+        super::export::stream_export(self, commit_sha)
+    }
+
+    async fn backup(&self) -> Result<GraphBackup, StoreError> {
+        super::backup::backup(&self.conn())
+    }
+
+    async fn execute_raw(&self, query: &str, params: &QueryParams) -> Result<usize, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(query)?;
+        if !stmt.readonly() && conn.is_readonly(DatabaseName::Main)? {
+            return Err(StoreError::ReadOnly("raw query writes".to_string()));
+        }
+        bind_raw_params(&mut stmt, params)?;
+        if stmt.column_count() == 0 {
+            return Ok(stmt.raw_execute()?);
+        }
+        let mut rows = stmt.raw_query();
+        let mut count = 0;
+        while rows.next()?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    async fn fetch_raw(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> Result<Vec<RawRow>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(query)?;
+        if !stmt.readonly() && conn.is_readonly(DatabaseName::Main)? {
+            return Err(StoreError::ReadOnly("raw query writes".to_string()));
+        }
+        bind_raw_params(&mut stmt, params)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = stmt.raw_query();
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut fields = RawRow::new();
+            for (index, column) in columns.iter().enumerate() {
+                fields.insert(column.clone(), json_value(row.get_ref(index)?));
+            }
+            result.push(fields);
+        }
+        Ok(result)
+    }
+
+    fn query_language(&self) -> QueryLanguage {
+        QueryLanguage::Sql
+    }
+}
+
+/// JSON value of a column in a raw query row
+///
+/// Blobs become text, replacing invalid UTF-8.
+fn json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(x) => Value::from(x),
+        ValueRef::Text(text) | ValueRef::Blob(text) => {
+            Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+    }
+}
+
+/// Bind `params` to the named parameters of a raw statement
+///
+/// SQLite names keep their `$`, `:` or `@` prefix, which is stripped for the
+/// lookup; positional `?` parameters can't be bound by name.
+fn bind_raw_params(stmt: &mut Statement<'_>, params: &QueryParams) -> Result<(), StoreError> {
+    for index in 1..=stmt.parameter_count() {
+        let name = stmt
+            .parameter_name(index)
+            .map(|name| name.trim_start_matches(['$', ':', '@']).to_string())
+            .ok_or_else(|| StoreError::Backend(format!("Unnamed parameter at {index}")))?;
+        let value = params
+            .get(&name)
+            .ok_or_else(|| StoreError::Backend(format!("Missing parameter: {name}")))?;
+        stmt.raw_bind_parameter(index, value)?;
+    }
+    Ok(())
+}
+
+/// Insert a file unless its content is stored, and link it to a commit
+///
+/// Returns `true` if the file is new.
+fn insert_file(
+    tx: &Transaction<'_>,
+    file_path: &str,
+    content_hash: &str,
+    language: &str,
+    commit_sha: &str,
+) -> Result<bool, StoreError> {
+    let is_new = tx.execute(
+        "INSERT OR IGNORE INTO files (content_hash, path, language) VALUES (?1, ?2, ?3)",
+        params![content_hash, file_path, language],
+    )? > 0;
+    tx.execute(
+        "INSERT OR IGNORE INTO commit_files (commit_sha, content_hash) VALUES (?1, ?2)",
+        params![commit_sha, content_hash],
+    )?;
+    Ok(is_new)
+}
+
+/// Store the size measures of a file
+fn update_file_metrics(
+    conn: &Connection,
+    content_hash: &str,
+    metrics: &FileMetrics,
+) -> Result<(), StoreError> {
+    let symbol_counts = serde_json::to_string(&metrics.symbol_counts)
+        .map_err(|e| StoreError::Backend(e.to_string()))?;
+    conn.execute(
+        "UPDATE files SET line_count = ?2, byte_size = ?3, symbol_counts = ?4
+         WHERE content_hash = ?1",
+        params![
+            content_hash,
+            i64::try_from(metrics.line_count).unwrap_or(i64::MAX),
+            i64::try_from(metrics.byte_size).unwrap_or(i64::MAX),
+            symbol_counts,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Insert the symbols of a stored file, with their identities and
+/// embeddings
+fn insert_symbols(
+    tx: &Transaction<'_>,
+    symbols: &[SymbolNode],
+    content_hash: &str,
+) -> Result<(), StoreError> {
+    let mut identity = tx.prepare(
+        "INSERT OR IGNORE INTO symbol_identities (key, name, qualified_name, kind, file_path)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let mut symbol = tx.prepare(
+        "INSERT INTO symbols (id, content_hash, identity_key, name, qualified_name, kind,
+                              visibility, file_path, start_line, end_line, signature,
+                              doc_comment, type_signature, resolved_type)
+         SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14
+         WHERE EXISTS (SELECT 1 FROM files WHERE content_hash = ?2)",
+    )?;
+    let mut embedding = tx.prepare(
+        "INSERT OR REPLACE INTO symbol_embeddings (symbol_id, embedding)
+         SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+    )?;
+
+    for s in symbols {
+        let key = s.identity_key();
+        let kind = s.kind.to_string();
+        identity.execute(params![key, s.name, s.qualified_name, kind, s.file_path])?;
+        symbol.execute(params![
+            s.id,
+            content_hash,
+            key,
+            s.name,
+            s.qualified_name,
+            kind,
+            s.visibility.clone().unwrap_or_default(),
+            s.file_path,
+            i64::from(s.start_line),
+            i64::from(s.end_line),
+            s.signature.clone().unwrap_or_default(),
+            s.doc_comment.clone().unwrap_or_default(),
+            s.type_signature.clone().unwrap_or_default(),
+            s.resolved_type.clone().unwrap_or_default(),
+        ])?;
+        if let Some(vector) = &s.embedding {
+            embedding.execute(params![s.id, vector_bytes(vector)])?;
+        }
+    }
+    Ok(())
+}
+
+/// Insert the parameters and return types of stored symbols
+fn insert_signatures(
+    tx: &Transaction<'_>,
+    signatures: &[FunctionSignature],
+) -> Result<(), StoreError> {
+    let mut insert_param = tx.prepare(
+        "INSERT OR REPLACE INTO parameters (symbol_id, position, name, type_name)
+         SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+    )?;
+    let mut insert_return = tx.prepare(
+        "INSERT OR REPLACE INTO return_types (symbol_id, type_name)
+         SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)",
+    )?;
+    for sig in signatures {
+        for p in &sig.parameters {
+            insert_param.execute(params![
+                sig.symbol_id,
+                i64::from(p.position),
+                p.name,
+                p.type_name.clone().unwrap_or_default(),
+            ])?;
+        }
+        if let Some(return_type) = &sig.return_type {
+            insert_return.execute(params![sig.symbol_id, return_type])?;
+        }
+    }
+    Ok(())
+}
+
+/// Insert edges between stored symbols, skipping those with an unknown end
+fn insert_edges(tx: &Transaction<'_>, edges: &[Edge]) -> Result<(), StoreError> {
+    let mut insert = tx.prepare(
+        "INSERT INTO edges (source_id, target_id, kind, line, column)
+         SELECT ?1, ?2, ?3, ?4, ?5
+         WHERE EXISTS (SELECT 1 FROM symbols WHERE id = ?1)
+           AND EXISTS (SELECT 1 FROM symbols WHERE id = ?2)",
+    )?;
+    for edge in edges {
+        insert.execute(params![
+            edge.source_id,
+            edge.target_id,
+            edge.kind.to_string(),
+            i64::from(edge.line.unwrap_or(0)),
+            i64::from(edge.column.unwrap_or(0)),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Delete every file no commit contains, with its symbols and everything
+/// that refers to them, then identities and external symbols nothing refers
+/// to anymore
+///
+/// Returns the numbers of deleted files and symbols.
+fn delete_orphan_files(tx: &Transaction<'_>) -> Result<(usize, usize), StoreError> {
+    let symbols = format!("SELECT id FROM symbols WHERE content_hash IN ({ORPHAN_FILES})");
+    let count = |query: &str| -> Result<usize, StoreError> {
+        let n: i64 = tx.query_row(&format!("SELECT COUNT(*) FROM ({query})"), [], |row| {
+            row.get(0)
+        })?;
+        Ok(usize::try_from(n).unwrap_or_default())
+    };
+    let counts = (count(ORPHAN_FILES)?, count(&symbols)?);
+    for delete in ORPHAN_DELETES {
+        let sql = delete
+            .replace("{files}", ORPHAN_FILES)
+            .replace("{symbols}", &symbols);
+        tx.execute(&sql, [])?;
+    }
+    Ok(counts)
+}
+
+/// Indexed range condition on `s.file_path` for a scope's prefix, using
+/// parameters `first` and `first + 1`
+fn scope_condition(scope: Option<&PathScope>, first: usize) -> String {
+    match scope {
+        Some(_) => format!(
+            "AND s.file_path >= ?{first} AND s.file_path < ?{}",
+            first + 1
+        ),
+        None => String::new(),
+    }
+}
+
+/// Bounds of the paths starting with a scope's prefix
+fn scope_range(scope: Option<&PathScope>) -> (String, String) {
+    let prefix = scope.map(PathScope::prefix).unwrap_or_default();
+    (prefix.to_string(), format!("{prefix}{MAX_CHAR}"))
+}
+
+/// Keep the page of symbols whose path is inside the scope
+///
+/// Globs are matched here rather than in SQL, so the page is cut from the
+/// ordered rows as they are read; reading stops at the end of the page.
+fn collect_in_scope(
+    rows: impl Iterator<Item = rusqlite::Result<SymbolResult>>,
+    scope: Option<&PathScope>,
+    page: Page,
+) -> Result<Vec<SymbolResult>, StoreError> {
+    let mut symbols = Vec::new();
+    let mut skipped = 0;
+    for row in rows {
+        let symbol = row?;
+        if !scope.is_none_or(|scope| scope.matches(&symbol.file_path)) {
+            continue;
+        }
+        if skipped < page.offset {
+            skipped += 1;
+            continue;
+        }
+        symbols.push(symbol);
+        if symbols.len() == page.limit() {
+            break;
+        }
+    }
+    Ok(symbols)
+}
+
+/// Order of symbols selected as `s`
+fn symbol_order(sort: Option<SortKey>) -> &'static str {
+    match sort {
+        Some(SortKey::File) => "s.file_path, s.start_line",
+        Some(SortKey::Kind) => "s.kind, s.name",
+        _ => "s.name, s.file_path, s.start_line",
+    }
+}
+
+/// FTS5 query matching any word of `text` as a prefix, or `None` if the
+/// text has no words
+///
+/// Words are quoted so FTS5 operators and punctuation in them match
+/// literally.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// `LIMIT`/`OFFSET` clause of a page
+fn page_clause(page: Page) -> String {
+    format!("LIMIT {} OFFSET {}", page.limit(), page.offset)
+}
+
+fn symbol_result(row: &Row<'_>) -> rusqlite::Result<SymbolResult> {
+    Ok(SymbolResult {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        qualified_name: row.get(2)?,
+        kind: row.get(3)?,
+        file_path: row.get(4)?,
+        start_line: row.get(5)?,
+        end_line: row.get(6)?,
+    })
+}
+
+/// Read a stored symbol; `None` if its kind is unknown to this version
+fn symbol_node(row: &Row<'_>) -> rusqlite::Result<Option<SymbolNode>> {
+    let kind: String = row.get(3)?;
+    let Ok(kind) = kind.parse() else {
+        return Ok(None);
+    };
+    let optional = |i: usize| -> rusqlite::Result<Option<String>> {
+        let value: String = row.get(i)?;
+        Ok((!value.is_empty()).then_some(value))
+    };
+    Ok(Some(SymbolNode {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        qualified_name: row.get(2)?,
+        kind,
+        visibility: optional(4)?,
+        file_path: row.get(5)?,
+        start_line: row.get(6)?,
+        end_line: row.get(7)?,
+        signature: optional(8)?,
+        doc_comment: optional(9)?,
+        type_signature: optional(10)?,
+        resolved_type: optional(11)?,
+        embedding: None,
+    }))
+}
+
+fn reference_result(row: &Row<'_>) -> rusqlite::Result<ReferenceResult> {
+    Ok(ReferenceResult {
+        source_name: row.get(0)?,
+        source_file: row.get(1)?,
+        source_line: row.get(2)?,
+        target_name: row.get(3)?,
+        target_file: row.get(4)?,
+        target_line: row.get(5)?,
+    })
+}
+
+/// A column holding a JSON array built by `json_group_array`
+/// Columns of a [`DirectorySummary`], read by [`directory_summary`]
+const DIRECTORY_SELECT: &str = "SELECT d.path, d.parent, d.file_count, d.line_count, d.languages,
+        d.readme,
+        (SELECT json_group_array(path) FROM
+            (SELECT f.path FROM directory_files df
+             JOIN files f ON f.content_hash = df.content_hash
+             WHERE df.commit_sha = d.commit_sha AND df.path = d.path
+             ORDER BY f.path))
+     FROM directory_summaries d";
+
+fn directory_summary(row: &Row<'_>) -> rusqlite::Result<DirectorySummary> {
+    let parent: String = row.get(1)?;
+    let languages: String = row.get(4)?;
+    let readme: String = row.get(5)?;
+    Ok(DirectorySummary {
+        path: row.get(0)?,
+        parent: (!parent.is_empty()).then_some(parent),
+        file_count: usize::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+        line_count: usize::try_from(row.get::<_, i64>(3)?).unwrap_or(0),
+        languages: languages
+            .split(',')
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect(),
+        readme: (!readme.is_empty()).then_some(readme),
+        files: json_column(row, 6)?,
+    })
+}
+
+fn json_column<T: DeserializeOwned>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(idx)?;
+    serde_json::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
+
+/// Whether an import is `module` itself or one of its submodules
+fn is_module_or_submodule(imported: &str, module: &str) -> bool {
+    imported == module
+        || ["::", ".", "/"]
+            .iter()
+            .any(|sep| imported.starts_with(&format!("{module}{sep}")))
+}
+
+/// Add an imported module to a file's entry; file versions share a path
+fn merge_importer(importers: &mut Vec<ImporterResult>, path: &str, language: &str, module: &str) {
+    match importers.iter_mut().find(|i| i.path == path) {
+        Some(existing) => {
+            if !existing.modules.iter().any(|m| m == module) {
+                existing.modules.push(module.to_string());
+            }
+        }
+        None => importers.push(ImporterResult {
+            path: path.to_string(),
+            language: language.to_string(),
+            modules: vec![module.to_string()],
+        }),
+    }
+}
+
+/// An embedding as little-endian `f32` bytes
+fn vector_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// An embedding read back from [`vector_bytes`]
+fn bytes_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
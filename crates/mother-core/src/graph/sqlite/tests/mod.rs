@@ -0,0 +1,3 @@
+//! Tests for sqlite module
+
+mod tests_sqlite_store;
@@ -0,0 +1,1905 @@
+//! Tests for the SQLite graph store
+
+#![allow(clippy::unwrap_used)]
+
+use futures::TryStreamExt;
+
+use crate::graph::complexity::SymbolComplexity;
+use crate::graph::export::{ExportRecord, GraphExport};
+use crate::graph::integrity::{IntegrityCheck, IntegrityRepair};
+use crate::graph::migrations::{IncompatibleSchema, MigrationRunner};
+use crate::graph::model::{
+    external_symbol_id, DiagnosticNode, DirectorySummary, Edge, EdgeKind, ExternalSymbolNode,
+    FileCacheStats, FileMetrics, FileWrite, FunctionSignature, ImportEdge, ParameterNode,
+    RepositoryDependency, RepositoryNode, ScanFailure, ScanReport, ScanRun, ScanStatus, SkipReason,
+    SkippedFile, SymbolCoverage, SymbolKind, SymbolNode,
+};
+use crate::graph::queries::{
+    Page, ParamValue, QueryParams, ReferenceDirection, ReferenceGrouping, SortKey, TypeUsage,
+};
+use crate::graph::scope::PathScope;
+use crate::graph::sqlite::SqliteStore;
+use crate::graph::store::{GraphStore, QueryLanguage, StoreError};
+
+const SHA: &str = "abc123def456";
+
+fn symbol(id: &str, name: &str, kind: SymbolKind, file_path: &str, line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("crate::{name}"),
+        visibility: Some("public".to_string()),
+        signature: Some(format!("fn {name}()")),
+        ..SymbolNode::new(id, name, kind, file_path, line, line + 5)
+    }
+}
+
+/// A store holding one commit with two files, three symbols and their edges
+async fn seeded_store() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo")
+        .with_commit(SHA)
+        .with_branch("main")
+        .with_version("v1");
+    assert!(store.create_scan_run(&run).await.unwrap());
+
+    store
+        .create_file_if_new("src/main.rs", "hash-main", "rust", SHA)
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("src/graph.rs", "hash-graph", "rust", SHA)
+        .await
+        .unwrap();
+    store
+        .set_file_imports("hash-main", &["crate::graph::Store".to_string()])
+        .await
+        .unwrap();
+    store
+        .create_import_edges(
+            SHA,
+            &[ImportEdge {
+                from_path: "src/main.rs".to_string(),
+                to_path: "src/graph.rs".to_string(),
+                module: "crate::graph::Store".to_string(),
+                line: 1,
+            }],
+        )
+        .await
+        .unwrap();
+
+    store
+        .create_symbols_batch(
+            &[symbol(
+                "s-main",
+                "main",
+                SymbolKind::Function,
+                "src/main.rs",
+                3,
+            )],
+            "hash-main",
+        )
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(
+            &[
+                symbol("s-store", "Store", SymbolKind::Struct, "src/graph.rs", 1),
+                symbol(
+                    "s-open",
+                    "open_store",
+                    SymbolKind::Function,
+                    "src/graph.rs",
+                    10,
+                ),
+            ],
+            "hash-graph",
+        )
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "s-main".to_string(),
+            target_id: "s-store".to_string(),
+            kind: EdgeKind::References,
+            line: Some(4),
+            column: Some(8),
+        })
+        .await
+        .unwrap();
+    store
+}
+
+// ============================================================================
+// Writes
+// ============================================================================
+
+#[tokio::test]
+async fn test_rescanning_a_commit_skips_file_processing() {
+    let store = seeded_store().await;
+    let rerun = ScanRun::new("/repo").with_commit(SHA);
+
+    assert!(!store.create_scan_run(&rerun).await.unwrap());
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.commits, 1);
+    assert_eq!(stats.scan_runs, 2);
+}
+
+#[tokio::test]
+async fn test_unchanged_file_content_is_reused() {
+    let store = seeded_store().await;
+    let next = ScanRun::new("/repo").with_commit("fff000");
+    store.create_scan_run(&next).await.unwrap();
+
+    let reused = store
+        .create_file_if_new("src/main.rs", "hash-main", "rust", "fff000")
+        .await
+        .unwrap();
+    let created = store
+        .create_file_if_new("src/new.rs", "hash-new", "rust", "fff000")
+        .await
+        .unwrap();
+
+    assert_eq!(reused, None);
+    assert_eq!(created, Some("hash-new".to_string()));
+    assert_eq!(store.stats().await.unwrap().contains, 4);
+}
+
+/// A new file at `src/lib.rs` of commit `fff000` with one symbol
+fn lib_file(symbols: Vec<SymbolNode>) -> FileWrite {
+    FileWrite {
+        path: "src/lib.rs".to_string(),
+        content_hash: "hash-lib".to_string(),
+        language: "rust".to_string(),
+        commit_sha: "fff000".to_string(),
+        metrics: Some(FileMetrics::of_content("pub fn lib() {}\n")),
+        symbols,
+        ..FileWrite::default()
+    }
+}
+
+#[tokio::test]
+async fn test_write_file_stores_the_file_with_its_symbols_and_edges() {
+    let store = seeded_store().await;
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("fff000"))
+        .await
+        .unwrap();
+    let lib = symbol("s-lib", "lib", SymbolKind::Function, "src/lib.rs", 1);
+    let file = FileWrite {
+        edges: vec![Edge {
+            source_id: "s-lib".to_string(),
+            target_id: "s-main".to_string(),
+            kind: EdgeKind::EvolvedFrom,
+            line: None,
+            column: None,
+        }],
+        ..lib_file(vec![lib])
+    };
+
+    assert!(store.write_file(&file).await.unwrap());
+    // Storing the same content again only links it
+    assert!(!store.write_file(&file).await.unwrap());
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!((stats.files, stats.symbols, stats.contains), (3, 4, 3));
+    let metrics = store.file_metrics("fff000", None).await.unwrap();
+    assert_eq!((metrics[0].line_count, metrics[0].symbol_count), (1, 0));
+    assert!(store.link_stored_file("hash-main", "fff000").await.unwrap());
+    assert!(!store.link_stored_file("hash-gone", "fff000").await.unwrap());
+    assert_eq!(store.stats().await.unwrap().contains, 4);
+}
+
+#[tokio::test]
+async fn test_write_file_stores_nothing_when_a_write_fails() {
+    let store = seeded_store().await;
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("fff000"))
+        .await
+        .unwrap();
+    let lib = symbol("s-lib", "lib", SymbolKind::Function, "src/lib.rs", 1);
+
+    // Symbol IDs are unique, so the second insert fails
+    let file = lib_file(vec![lib.clone(), lib]);
+    assert!(store.write_file(&file).await.is_err());
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!((stats.files, stats.symbols, stats.contains), (2, 3, 2));
+    assert!(!store.link_stored_file("hash-lib", "fff000").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_truncated_references_are_exported_on_the_symbol() {
+    let store = seeded_store().await;
+    store
+        .mark_references_truncated("s-store", 25_000)
+        .await
+        .unwrap();
+    store.mark_references_truncated("missing", 1).await.unwrap();
+
+    let export = store.export_commit(SHA).await.unwrap();
+    let node = export.nodes.iter().find(|n| n.id == "s-store").unwrap();
+    assert_eq!(node.properties["reference_count"], 25_000);
+    assert_eq!(node.properties["is_truncated"], true);
+
+    let untouched = export.nodes.iter().find(|n| n.id == "s-open").unwrap();
+    assert!(!untouched.properties.contains_key("is_truncated"));
+}
+
+#[tokio::test]
+async fn test_symbol_complexity_is_replaced_and_skips_unknown_symbols() {
+    let store = seeded_store().await;
+    let complexity = |symbol_id: &str, score: usize| SymbolComplexity {
+        symbol_id: symbol_id.to_string(),
+        fan_in: score,
+        score,
+        ..SymbolComplexity::default()
+    };
+    store
+        .set_symbol_complexity(&[complexity("s-store", 1), complexity("missing", 1)])
+        .await
+        .unwrap();
+    store
+        .set_symbol_complexity(&[complexity("s-store", 7)])
+        .await
+        .unwrap();
+
+    let params = QueryParams::new();
+    let count = |query: &'static str| store.execute_raw(query, &params);
+    assert_eq!(count("SELECT * FROM symbol_complexity").await.unwrap(), 1);
+    assert_eq!(
+        count("SELECT * FROM symbol_complexity WHERE symbol_id = 's-store' AND score = 7")
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_symbol_coverage_is_replaced_and_skips_unknown_symbols() {
+    let store = seeded_store().await;
+    let coverage = |symbol_id: &str, lines_covered: u32| SymbolCoverage {
+        symbol_id: symbol_id.to_string(),
+        lines_covered,
+        lines_total: 4,
+        ..SymbolCoverage::default()
+    };
+    store
+        .set_symbol_coverage(&[coverage("s-store", 1), coverage("missing", 1)])
+        .await
+        .unwrap();
+    store
+        .set_symbol_coverage(&[coverage("s-store", 3)])
+        .await
+        .unwrap();
+
+    let params = QueryParams::new();
+    let count = |query: &'static str| store.execute_raw(query, &params);
+    assert_eq!(count("SELECT * FROM symbol_coverage").await.unwrap(), 1);
+    assert_eq!(
+        count("SELECT * FROM symbol_coverage WHERE symbol_id = 's-store' AND lines_covered = 3")
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_repositories_are_replaced_and_dependencies_skip_unknown_paths() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let repository = |path: &str, packages: &[&str]| RepositoryNode {
+        path: path.to_string(),
+        packages: packages.iter().map(ToString::to_string).collect(),
+        dependencies: Vec::new(),
+    };
+    let dependency = |from: &str, to: &str| RepositoryDependency {
+        from: from.to_string(),
+        to: to.to_string(),
+        packages: vec!["cargo:lib".to_string()],
+    };
+    store
+        .upsert_repository(&repository("/app", &[]))
+        .await
+        .unwrap();
+    store
+        .upsert_repository(&repository("/lib", &["cargo:old"]))
+        .await
+        .unwrap();
+    store
+        .upsert_repository(&repository("/lib", &["cargo:lib"]))
+        .await
+        .unwrap();
+    store
+        .set_repository_dependencies(&[dependency("/app", "/lib"), dependency("/app", "/gone")])
+        .await
+        .unwrap();
+
+    let repositories = store.repositories().await.unwrap();
+    assert_eq!(
+        repositories,
+        [repository("/app", &[]), repository("/lib", &["cargo:lib"])]
+    );
+    assert_eq!(
+        store.repository_dependencies().await.unwrap(),
+        [dependency("/app", "/lib")]
+    );
+
+    store.set_repository_dependencies(&[]).await.unwrap();
+    assert!(store.repository_dependencies().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_modified_symbols_skip_unknown_ids_and_order_by_file_and_line() {
+    let store = seeded_store().await;
+    let ids = ["s-open", "missing", "s-main", "s-store", "s-open"].map(String::from);
+    store.create_modified_edges(SHA, &ids).await.unwrap();
+
+    let modified = store.modified_symbols(SHA).await.unwrap();
+
+    let ids: Vec<_> = modified.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["s-store", "s-open", "s-main"]);
+    assert!(store.modified_symbols("fff000").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_deleting_the_only_run_removes_data_no_other_commit_contains() {
+    let store = seeded_store().await;
+    let next = ScanRun::new("/repo").with_commit("fff000");
+    store.create_scan_run(&next).await.unwrap();
+    store
+        .create_file_if_new("src/main.rs", "hash-main", "rust", "fff000")
+        .await
+        .unwrap();
+    store
+        .create_directory_summaries(
+            SHA,
+            &[DirectorySummary {
+                path: "src".to_string(),
+                files: vec!["src/graph.rs".to_string()],
+                ..DirectorySummary::default()
+            }],
+        )
+        .await
+        .unwrap();
+    store
+        .create_function_signatures(&[FunctionSignature {
+            symbol_id: "s-open".to_string(),
+            parameters: vec![ParameterNode {
+                name: "path".to_string(),
+                type_name: Some("&Path".to_string()),
+                position: 0,
+            }],
+            return_type: Some("Store".to_string()),
+        }])
+        .await
+        .unwrap();
+    store
+        .create_diagnostics(
+            "hash-graph",
+            &[DiagnosticNode {
+                severity: "warning".to_string(),
+                message: "unused".to_string(),
+                symbol_id: Some("s-open".to_string()),
+                ..DiagnosticNode::default()
+            }],
+        )
+        .await
+        .unwrap();
+    store
+        .create_modified_edges(SHA, &["s-open".to_string()])
+        .await
+        .unwrap();
+    let first = store.scan_runs().await.unwrap().pop().unwrap();
+    assert_eq!(first.commit_sha, SHA);
+
+    let summary = store.delete_scan_run(&first.id).await.unwrap();
+
+    assert_eq!(
+        (
+            summary.runs,
+            summary.commits,
+            summary.files,
+            summary.symbols
+        ),
+        (1, 1, 1, 2)
+    );
+    let stats = store.stats().await.unwrap();
+    assert_eq!((stats.commits, stats.files, stats.symbols), (1, 1, 1));
+    assert_eq!(stats.references, 0);
+    for table in [
+        "parameters",
+        "return_types",
+        "diagnostics",
+        "directory_summaries",
+        "modified_symbols",
+    ] {
+        let rows = store
+            .execute_raw(&format!("SELECT * FROM {table}"), &QueryParams::new())
+            .await
+            .unwrap();
+        assert_eq!(rows, 0, "{table}");
+    }
+    let indexed = store
+        .execute_raw("SELECT * FROM symbol_search", &QueryParams::new())
+        .await
+        .unwrap();
+    assert_eq!(indexed, 1);
+    assert_eq!(store.resolve_commit(Some("v1")).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_deleting_one_of_two_runs_keeps_the_commit() {
+    let store = seeded_store().await;
+    let rerun = ScanRun::new("/repo").with_commit(SHA);
+    store.create_scan_run(&rerun).await.unwrap();
+
+    let summary = store.delete_scan_run(&rerun.id).await.unwrap();
+    let missing = store.delete_scan_run(&rerun.id).await.unwrap();
+
+    assert_eq!((summary.runs, summary.commits, summary.files), (1, 0, 0));
+    assert_eq!(missing.runs, 0);
+    let runs = store.scan_runs().await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].version, "v1");
+    assert_eq!(store.stats().await.unwrap().symbols, 3);
+}
+
+#[tokio::test]
+async fn test_tags_resolve_to_the_newest_tagged_run() {
+    let store = seeded_store().await;
+    let first = store.scan_runs().await.unwrap().pop().unwrap();
+    let mut later = ScanRun::new("/repo").with_commit("fff000");
+    later.scanned_at += chrono::Duration::seconds(1);
+    store.create_scan_run(&later).await.unwrap();
+
+    store.tag_scan_run(&first.id, "release-1.1").await.unwrap();
+    store.tag_scan_run(&first.id, "lts").await.unwrap();
+    store.tag_scan_run(&later.id, "lts").await.unwrap();
+    store.tag_scan_run("missing", "lts").await.unwrap();
+    store
+        .annotate_scan_run(&first.id, "first draft")
+        .await
+        .unwrap();
+    store
+        .annotate_scan_run(&first.id, "released")
+        .await
+        .unwrap();
+
+    let resolve = |version: &'static str| store.resolve_commit(Some(version));
+    assert_eq!(
+        resolve("tag:release-1.1").await.unwrap().as_deref(),
+        Some(SHA)
+    );
+    assert_eq!(resolve("tag:lts").await.unwrap().as_deref(), Some("fff000"));
+    assert_eq!(resolve("tag:v1").await.unwrap(), None);
+    let runs = store.scan_runs().await.unwrap();
+    assert_eq!(runs[1].tags, ["lts", "release-1.1"]);
+    assert_eq!(runs[1].note, "released");
+    assert!(runs[0].note.is_empty());
+
+    store.delete_scan_run(&first.id).await.unwrap();
+    assert_eq!(resolve("tag:release-1.1").await.unwrap(), None);
+    let tags = store
+        .execute_raw("SELECT * FROM scan_run_tags", &QueryParams::new())
+        .await
+        .unwrap();
+    assert_eq!(tags, 1);
+}
+
+#[tokio::test]
+async fn test_scan_report_is_stored_with_its_run() {
+    let store = seeded_store().await;
+    let run = store.scan_runs().await.unwrap().pop().unwrap();
+    assert_eq!(store.scan_report(&run.id).await.unwrap(), None);
+
+    let report = ScanReport {
+        scan_run_id: run.id.clone(),
+        commit_sha: SHA.to_string(),
+        failures: vec![ScanFailure {
+            phase: "Phase 3".to_string(),
+            file: "src/graph.rs".to_string(),
+            symbol: Some("s-open".to_string()),
+            request: "textDocument/references".to_string(),
+            message: "request timed out".to_string(),
+        }],
+        completion: Some(42),
+        file_cache: Some(FileCacheStats {
+            hits: 3,
+            misses: 7,
+            bytes_read: 4096,
+            mapped: 1,
+        }),
+        skipped: vec![SkippedFile {
+            file: "src/schema.rs".to_string(),
+            reason: SkipReason::Generated {
+                marker: "@generated".to_string(),
+            },
+        }],
+    };
+    store.set_scan_report(&report).await.unwrap();
+    store
+        .set_scan_report(&ScanReport {
+            scan_run_id: "missing".to_string(),
+            ..ScanReport::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(store.scan_report(&run.id).await.unwrap(), Some(report));
+    assert_eq!(store.scan_report("missing").await.unwrap(), None);
+}
+
+/// A seeded store with a dangling edge, a file no commit contains and a
+/// symbol that ends before it starts
+async fn damaged_store() -> SqliteStore {
+    let store = seeded_store().await;
+    for sql in [
+        "PRAGMA foreign_keys = OFF",
+        "INSERT INTO edges (source_id, target_id, kind, line, column)
+         VALUES ('s-main', 's-gone', 'CALLS', 5, 4)",
+        "PRAGMA foreign_keys = ON",
+        "INSERT INTO files (content_hash, path, language) VALUES ('hash-old', 'src/old.rs', 'rust')",
+        "UPDATE symbols SET end_line = 2 WHERE id = 's-open'",
+    ] {
+        store.execute_raw(sql, &QueryParams::new()).await.unwrap();
+    }
+    store
+        .create_symbols_batch(
+            &[symbol(
+                "s-old",
+                "old",
+                SymbolKind::Function,
+                "src/old.rs",
+                1,
+            )],
+            "hash-old",
+        )
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_integrity_check_finds_every_problem() {
+    assert!(seeded_store()
+        .await
+        .check_integrity()
+        .await
+        .unwrap()
+        .is_empty());
+
+    let issues = damaged_store().await.check_integrity().await.unwrap();
+    let found: Vec<(IntegrityCheck, &str, &str)> = issues
+        .iter()
+        .map(|i| (i.check, i.subject.as_str(), i.detail.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                IntegrityCheck::DanglingEdge,
+                "s-main -[CALLS]-> s-gone",
+                "target symbol missing"
+            ),
+            (
+                IntegrityCheck::OrphanFile,
+                "src/old.rs",
+                "no commit contains content hash-old"
+            ),
+            (
+                IntegrityCheck::InvertedRange,
+                "crate::open_store",
+                "src/graph.rs: ends at line 2 before it starts at line 10"
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_integrity_repair_deletes_dangling_edges_and_orphan_files() {
+    let store = damaged_store().await;
+
+    let repair = store.repair_integrity().await.unwrap();
+    assert_eq!(
+        repair,
+        IntegrityRepair {
+            edges: 1,
+            files: 1,
+            symbols: 1
+        }
+    );
+    let left: Vec<IntegrityCheck> = store
+        .check_integrity()
+        .await
+        .unwrap()
+        .iter()
+        .map(|i| i.check)
+        .collect();
+    assert_eq!(left, [IntegrityCheck::InvertedRange]);
+    assert_eq!(
+        store
+            .execute_raw("SELECT * FROM edges", &QueryParams::new())
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_unlink_file_removes_it_from_the_commit_only() {
+    let store = seeded_store().await;
+
+    assert!(store.unlink_file(SHA, "src/main.rs").await.unwrap());
+    assert!(!store.unlink_file(SHA, "src/main.rs").await.unwrap());
+
+    assert!(store
+        .find_symbols_at_commit("main", SHA, None, Page::default())
+        .await
+        .unwrap()
+        .is_empty());
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.contains, 1);
+    assert_eq!(stats.files, 2);
+}
+
+#[tokio::test]
+async fn test_directory_summaries_link_files_and_update_in_place() {
+    let store = seeded_store().await;
+    let mut summary = DirectorySummary {
+        path: "src".to_string(),
+        parent: None,
+        file_count: 2,
+        line_count: 40,
+        languages: vec!["rust".to_string()],
+        readme: Some("Core sources.".to_string()),
+        files: vec![
+            "src/main.rs".to_string(),
+            "src/graph.rs".to_string(),
+            "src/missing.rs".to_string(),
+        ],
+    };
+    store
+        .create_directory_summaries(SHA, std::slice::from_ref(&summary))
+        .await
+        .unwrap();
+    summary.line_count = 41;
+    store
+        .create_directory_summaries(SHA, std::slice::from_ref(&summary))
+        .await
+        .unwrap();
+
+    let stored = store.directory_summary("src", SHA).await.unwrap().unwrap();
+    assert_eq!(stored.line_count, 41);
+    assert_eq!(stored.languages, vec!["rust"]);
+    assert_eq!(stored.readme.as_deref(), Some("Core sources."));
+    assert_eq!(stored.files, vec!["src/graph.rs", "src/main.rs"]);
+
+    assert!(store
+        .directory_summary("src", "other")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store.directory_summary("lib", SHA).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_directories_at_commit_link_parents() {
+    let store = seeded_store().await;
+    let directory = |path: &str, parent: Option<&str>| DirectorySummary {
+        path: path.to_string(),
+        parent: parent.map(str::to_string),
+        ..DirectorySummary::default()
+    };
+    store
+        .create_directory_summaries(
+            SHA,
+            &[directory("src/graph", Some("src")), directory("src", None)],
+        )
+        .await
+        .unwrap();
+
+    let directories = store.directories_at_commit(SHA).await.unwrap();
+    let tree: Vec<_> = directories
+        .iter()
+        .map(|d| (d.path.as_str(), d.parent.as_deref()))
+        .collect();
+    assert_eq!(tree, vec![("src", None), ("src/graph", Some("src"))]);
+    let graph = store.directory_summary("src/graph", SHA).await.unwrap();
+    assert_eq!(graph.unwrap().parent.as_deref(), Some("src"));
+    assert!(store
+        .directories_at_commit("other")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+fn diagnostic(severity: &str, line: u32, symbol_id: Option<&str>) -> DiagnosticNode {
+    DiagnosticNode {
+        severity: severity.to_string(),
+        code: Some("E0308".to_string()),
+        source: Some("rustc".to_string()),
+        message: format!("{severity} on line {line}"),
+        start_line: line,
+        end_line: line,
+        symbol_id: symbol_id.map(str::to_string),
+        ..DiagnosticNode::default()
+    }
+}
+
+#[tokio::test]
+async fn test_diagnostics_replace_earlier_ones_and_link_symbols() {
+    let store = seeded_store().await;
+    store
+        .create_diagnostics("hash-graph", &[diagnostic("error", 2, None)])
+        .await
+        .unwrap();
+    store
+        .create_diagnostics(
+            "hash-graph",
+            &[
+                diagnostic("warning", 12, Some("s-open")),
+                diagnostic("error", 3, Some("missing")),
+            ],
+        )
+        .await
+        .unwrap();
+    store
+        .create_diagnostics("hash-main", &[diagnostic("hint", 4, Some("s-main"))])
+        .await
+        .unwrap();
+
+    let all = store.find_diagnostics(SHA, None, None).await.unwrap();
+    let found: Vec<(&str, i64, &str)> = all
+        .iter()
+        .map(|d| (d.file_path.as_str(), d.start_line, d.symbol.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("src/graph.rs", 3, ""),
+            ("src/graph.rs", 12, "crate::open_store"),
+            ("src/main.rs", 4, "crate::main"),
+        ]
+    );
+    assert_eq!(all[0].code, "E0308");
+
+    let errors = store
+        .find_diagnostics(SHA, Some("error"), None)
+        .await
+        .unwrap();
+    assert_eq!(errors.len(), 1);
+
+    let scope = PathScope::new("src/main.rs").unwrap();
+    let scoped = store
+        .find_diagnostics(SHA, None, Some(&scope))
+        .await
+        .unwrap();
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].severity, "hint");
+
+    assert!(store
+        .find_diagnostics("other", None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_edge_to_unknown_symbol_is_ignored() {
+    let store = seeded_store().await;
+    store
+        .create_edge(&Edge {
+            source_id: "s-main".to_string(),
+            target_id: "missing".to_string(),
+            kind: EdgeKind::References,
+            line: None,
+            column: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(store.stats().await.unwrap().references, 1);
+}
+
+#[tokio::test]
+async fn test_edges_batch_skips_edges_to_unknown_symbols() {
+    let store = seeded_store().await;
+    let edge = |source: &str, target: &str, kind| Edge {
+        source_id: source.to_string(),
+        target_id: target.to_string(),
+        kind,
+        line: Some(11),
+        column: Some(4),
+    };
+    store
+        .create_edges_batch(&[
+            edge("s-open", "s-store", EdgeKind::References),
+            edge("s-main", "s-open", EdgeKind::Calls),
+            edge("s-main", "missing", EdgeKind::References),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(store.stats().await.unwrap().references, 2);
+    let refs = store
+        .find_references_to("Store", Page::default())
+        .await
+        .unwrap();
+    assert_eq!(refs.len(), 2);
+    assert!(store.create_edges_batch(&[]).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_external_references_share_one_placeholder() {
+    let store = seeded_store().await;
+    let path = "/rust/lib/rustlib/src/rust/library/std/src/fs.rs";
+    let target = ExternalSymbolNode {
+        id: external_symbol_id(path, 400, 7),
+        name: "read_to_string".to_string(),
+        file_path: path.to_string(),
+        line: 401,
+        origin: "std".to_string(),
+    };
+
+    for (source_id, line) in [("s-main", 5), ("s-open", 11), ("missing", 2)] {
+        store
+            .create_external_reference(source_id, &target, line, 4)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        store
+            .execute_raw("SELECT id FROM external_symbols", &QueryParams::new())
+            .await
+            .unwrap(),
+        1
+    );
+    // Two external edges plus the seeded reference; the unknown source is ignored
+    assert_eq!(store.stats().await.unwrap().references, 3);
+}
+
+// ============================================================================
+// Reads
+// ============================================================================
+
+#[tokio::test]
+async fn test_find_symbols_is_case_insensitive() {
+    let store = seeded_store().await;
+    let symbols = store
+        .find_symbols("STORE", None, Page::default())
+        .await
+        .unwrap();
+
+    let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Store", "open_store"]);
+}
+
+#[tokio::test]
+async fn test_find_symbols_in_directory_scope() {
+    let store = seeded_store().await;
+    let scope = PathScope::new("src/graph.rs").unwrap();
+    let symbols = store
+        .find_symbols("", Some(&scope), Page::default())
+        .await
+        .unwrap();
+
+    let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Store", "open_store"]);
+
+    let outside = PathScope::new("lib").unwrap();
+    assert!(store
+        .find_symbols("", Some(&outside), Page::default())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_find_symbols_in_glob_scope() {
+    let store = seeded_store().await;
+    let scope = PathScope::new("src/m*.rs").unwrap();
+
+    let symbols = store
+        .find_symbols("", Some(&scope), Page::default())
+        .await
+        .unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "main");
+
+    let at_commit = store
+        .find_symbols_at_commit("store", SHA, Some(&scope), Page::default())
+        .await
+        .unwrap();
+    assert!(at_commit.is_empty());
+}
+
+#[tokio::test]
+async fn test_find_symbols_pages_in_sort_order() {
+    let store = seeded_store().await;
+    let names = |symbols: Vec<crate::graph::SymbolResult>| -> Vec<String> {
+        symbols.into_iter().map(|s| s.name).collect()
+    };
+
+    let by_file = Page {
+        sort: Some(SortKey::File),
+        ..Page::default()
+    };
+    let symbols = store.find_symbols("", None, by_file).await.unwrap();
+    assert_eq!(names(symbols), ["Store", "open_store", "main"]);
+
+    let by_kind = Page {
+        sort: Some(SortKey::Kind),
+        ..Page::default()
+    };
+    let symbols = store.find_symbols("", None, by_kind).await.unwrap();
+    assert_eq!(names(symbols), ["main", "open_store", "Store"]);
+
+    let second = Page {
+        limit: Some(1),
+        offset: 1,
+        sort: None,
+    };
+    let symbols = store.find_symbols("", None, second).await.unwrap();
+    assert_eq!(names(symbols), ["main"]);
+    let symbols = store
+        .find_symbols_at_commit("", SHA, None, second)
+        .await
+        .unwrap();
+    assert_eq!(names(symbols), ["main"]);
+}
+
+#[tokio::test]
+async fn test_find_symbols_pages_after_glob_scope() {
+    let store = seeded_store().await;
+    let scope = PathScope::new("src/g*.rs").unwrap();
+    let page = Page {
+        limit: Some(5),
+        offset: 1,
+        sort: Some(SortKey::File),
+    };
+
+    // The offset counts only symbols inside the scope
+    let symbols = store.find_symbols("", Some(&scope), page).await.unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "open_store");
+}
+
+#[tokio::test]
+async fn test_symbols_in_file_ordered_by_line() {
+    let store = seeded_store().await;
+    let symbols = store.symbols_in_file("graph.rs").await.unwrap();
+
+    let lines: Vec<_> = symbols.iter().map(|s| s.start_line).collect();
+    assert_eq!(lines, vec![1, 10]);
+}
+
+#[tokio::test]
+async fn test_find_symbols_at_commit() {
+    let store = seeded_store().await;
+
+    assert_eq!(
+        store
+            .find_symbols_at_commit("main", SHA, None, Page::default())
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(store
+        .find_symbols_at_commit("main", "other", None, Page::default())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_symbols_at_commit_ordered_by_file_and_line() {
+    let store = seeded_store().await;
+    store_renamed_version(&store).await;
+
+    let symbols = store.symbols_at_commit(SHA).await.unwrap();
+    let ids: Vec<&str> = symbols.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["s-store", "s-open", "s-main"]);
+
+    let renamed = store.symbols_at_commit("fff000").await.unwrap();
+    assert_eq!(renamed.len(), 1);
+    assert_eq!(renamed[0].name, "open");
+}
+
+#[tokio::test]
+async fn test_symbol_history_lists_commits() {
+    let store = seeded_store().await;
+    let versions = store.symbol_history("crate::Store").await.unwrap();
+
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].content_hash, "hash-graph");
+    assert_eq!(versions[0].commit_shas, vec![SHA.to_string()]);
+}
+
+/// Store a second version of `src/graph.rs` in which `open_store` was renamed
+async fn store_renamed_version(store: &SqliteStore) {
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("fff000"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("src/graph.rs", "hash-graph-2", "rust", "fff000")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(
+            &[symbol(
+                "s-open-2",
+                "open",
+                SymbolKind::Function,
+                "src/graph.rs",
+                10,
+            )],
+            "hash-graph-2",
+        )
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "s-open-2".to_string(),
+            target_id: "s-open".to_string(),
+            kind: EdgeKind::EvolvedFrom,
+            line: None,
+            column: None,
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_previous_file_symbols_returns_latest_other_version() {
+    let store = seeded_store().await;
+    store_renamed_version(&store).await;
+
+    let previous = store
+        .previous_file_symbols("src/graph.rs", "hash-graph-2")
+        .await
+        .unwrap();
+    let ids: Vec<&str> = previous.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["s-store", "s-open"]);
+    assert_eq!(previous[1].kind, SymbolKind::Function);
+    assert_eq!(previous[1].signature.as_deref(), Some("fn open_store()"));
+    assert_eq!(previous[1].doc_comment, None);
+
+    assert!(store
+        .previous_file_symbols("src/new.rs", "hash-new")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_symbol_history_follows_renames() {
+    let store = seeded_store().await;
+    store_renamed_version(&store).await;
+
+    for name in ["crate::open", "crate::open_store"] {
+        let versions = store.symbol_history(name).await.unwrap();
+        let ids: Vec<&str> = versions.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, ["s-open-2", "s-open"], "history of {name}");
+    }
+}
+
+#[tokio::test]
+async fn test_references_in_both_directions() {
+    let store = seeded_store().await;
+
+    let to = store
+        .find_references_to("Store", Page::default())
+        .await
+        .unwrap();
+    assert_eq!(to.len(), 1);
+    assert_eq!(to[0].source_name, "main");
+    assert_eq!(to[0].source_line, 4);
+
+    let from = store
+        .find_references_from("main", Page::default())
+        .await
+        .unwrap();
+    assert_eq!(from.len(), 1);
+    assert_eq!(from[0].target_file, "src/graph.rs");
+}
+
+/// The seeded store plus more references to `Store`: a second one on the
+/// same line of `main`, one further down and one from `open_store`
+async fn store_with_repeated_references() -> SqliteStore {
+    let store = seeded_store().await;
+    for (source, line, column) in [("s-main", 4, 20), ("s-main", 6, 4), ("s-open", 12, 8)] {
+        store
+            .create_edge(&Edge {
+                source_id: source.to_string(),
+                target_id: "s-store".to_string(),
+                kind: EdgeKind::References,
+                line: Some(line),
+                column: Some(column),
+            })
+            .await
+            .unwrap();
+    }
+    store
+}
+
+#[tokio::test]
+async fn test_references_page_in_sort_order() {
+    let store = store_with_repeated_references().await;
+
+    let all = store
+        .find_references_to("Store", Page::default())
+        .await
+        .unwrap();
+    let lines: Vec<(&str, i64)> = all
+        .iter()
+        .map(|r| (r.source_name.as_str(), r.source_line))
+        .collect();
+    assert_eq!(
+        lines,
+        [("open_store", 12), ("main", 4), ("main", 4), ("main", 6)]
+    );
+
+    let last_by_name = Page {
+        limit: Some(2),
+        offset: 3,
+        sort: Some(SortKey::Name),
+    };
+    let refs = store
+        .find_references_to("Store", last_by_name)
+        .await
+        .unwrap();
+    assert_eq!(refs.len(), 1);
+    assert_eq!(refs[0].source_name, "open_store");
+
+    let past_the_end = Page {
+        offset: 3,
+        ..Page::default()
+    };
+    assert!(store
+        .find_references_from("main", past_the_end)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_reference_groups_merge_identical_references() {
+    let store = store_with_repeated_references().await;
+
+    let groups = store
+        .find_reference_groups(
+            "Store",
+            ReferenceDirection::To,
+            ReferenceGrouping::Unique,
+            Page::default(),
+        )
+        .await
+        .unwrap();
+
+    let rows: Vec<(&str, &[i64], i64)> = groups
+        .iter()
+        .map(|g| (g.symbols[0].as_str(), g.lines.as_slice(), g.count))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("open_store", &[12][..], 1),
+            ("main", &[4][..], 2),
+            ("main", &[6][..], 1)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_reference_groups_by_symbol_and_file() {
+    let store = store_with_repeated_references().await;
+
+    let by_symbol = store
+        .find_reference_groups(
+            "Store",
+            ReferenceDirection::To,
+            ReferenceGrouping::Symbol,
+            Page::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_symbol.len(), 2);
+    assert_eq!(by_symbol[1].symbols, ["main"]);
+    assert_eq!(by_symbol[1].file, "src/main.rs");
+    assert_eq!(by_symbol[1].lines, [4, 6]);
+    assert_eq!(by_symbol[1].count, 3);
+
+    let by_file = store
+        .find_reference_groups(
+            "main",
+            ReferenceDirection::From,
+            ReferenceGrouping::File,
+            Page::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_file.len(), 1);
+    assert_eq!(by_file[0].symbols, ["Store"]);
+    assert_eq!(by_file[0].file, "src/graph.rs");
+    assert_eq!(by_file[0].lines, [1]);
+    assert_eq!(by_file[0].count, 3);
+}
+
+#[tokio::test]
+async fn test_list_files_with_symbol_counts() {
+    let store = seeded_store().await;
+
+    let all = store.list_files(None, Page::default()).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].path, "src/graph.rs");
+    assert_eq!(all[0].symbol_count, 2);
+
+    let filtered = store
+        .list_files(Some("main"), Page::default())
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+}
+
+#[tokio::test]
+async fn test_list_files_pages_in_sort_order() {
+    let store = seeded_store().await;
+
+    let by_count = Page {
+        sort: Some(SortKey::Symbols),
+        ..Page::default()
+    };
+    let files = store.list_files(None, by_count).await.unwrap();
+    let counts: Vec<(&str, i64)> = files
+        .iter()
+        .map(|f| (f.path.as_str(), f.symbol_count))
+        .collect();
+    assert_eq!(counts, [("src/graph.rs", 2), ("src/main.rs", 1)]);
+
+    let second = Page {
+        limit: Some(1),
+        offset: 1,
+        sort: None,
+    };
+    let files = store.list_files(None, second).await.unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, "src/main.rs");
+}
+
+#[tokio::test]
+async fn test_find_importers_by_module_and_path() {
+    let store = seeded_store().await;
+
+    let by_module = store.find_importers("crate::graph").await.unwrap();
+    assert_eq!(by_module.len(), 1);
+    assert_eq!(
+        by_module[0].modules,
+        vec!["crate::graph::Store".to_string()]
+    );
+
+    let by_path = store.find_importers("graph.rs").await.unwrap();
+    assert_eq!(by_path.len(), 1);
+    assert_eq!(by_path[0].path, "src/main.rs");
+
+    assert!(store.find_importers("crate::gra").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_stats_counts_nodes_and_edges() {
+    let store = seeded_store().await;
+    let stats = store.stats().await.unwrap();
+
+    assert_eq!(stats.commits, 1);
+    assert_eq!(stats.files, 2);
+    assert_eq!(stats.symbols, 3);
+    assert_eq!(stats.references, 1);
+    assert_eq!(stats.defined_in, 3);
+    assert_eq!(stats.contains, 2);
+}
+
+#[tokio::test]
+async fn test_recent_scans_lists_commits_of_a_branch() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let start = chrono::Utc::now() - chrono::Duration::hours(1);
+    let runs = [
+        ("c1", "main", "v1"),
+        ("c2", "main", "v2"),
+        ("c2", "main", "v2-rescan"),
+        ("f1", "feature", "f"),
+        ("c3", "main", "v3"),
+    ];
+    for (minutes, (sha, branch, version)) in (0..).zip(runs) {
+        let mut run = ScanRun::new("/repo")
+            .with_commit(sha)
+            .with_branch(branch)
+            .with_version(version);
+        run.scanned_at = start + chrono::Duration::minutes(minutes);
+        store.create_scan_run(&run).await.unwrap();
+    }
+
+    let scans = store.recent_scans(None, 2).await.unwrap();
+    let listed: Vec<_> = scans
+        .iter()
+        .map(|s| (s.commit_sha.as_str(), s.version.as_str()))
+        .collect();
+    assert_eq!(listed, vec![("c3", "v3"), ("c2", "v2-rescan")]);
+    assert!(scans.iter().all(|s| s.branch == "main"));
+
+    let feature = store.recent_scans(Some("feature"), 5).await.unwrap();
+    assert_eq!(feature.len(), 1);
+    assert_eq!(feature[0].commit_sha, "f1");
+    assert!(store
+        .recent_scans(Some("missing"), 5)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_latest_commit_skips_incomplete_scan_runs() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let start = chrono::Utc::now() - chrono::Duration::hours(1);
+    let runs = [
+        ("c1", ScanStatus::Completed),
+        ("c2", ScanStatus::Failed),
+        ("c3", ScanStatus::InProgress),
+    ];
+    for (minutes, (sha, status)) in (0..).zip(runs) {
+        let mut run = ScanRun::new("/repo")
+            .with_commit(sha)
+            .with_branch("main")
+            .with_version(sha)
+            .with_status(status);
+        run.scanned_at = start + chrono::Duration::minutes(minutes);
+        store.create_scan_run(&run).await.unwrap();
+    }
+
+    assert_eq!(
+        store.resolve_commit(None).await.unwrap().as_deref(),
+        Some("c1")
+    );
+    let scans = store.recent_scans(None, 5).await.unwrap();
+    let listed: Vec<_> = scans.iter().map(|s| s.commit_sha.as_str()).collect();
+    assert_eq!(listed, vec!["c1"]);
+    // An explicit version still resolves to its commit
+    assert_eq!(
+        store.resolve_commit(Some("c3")).await.unwrap().as_deref(),
+        Some("c3")
+    );
+    let statuses: Vec<_> = store
+        .scan_runs()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.status)
+        .collect();
+    assert_eq!(statuses, vec!["in_progress", "failed", "completed"]);
+}
+
+#[tokio::test]
+async fn test_resolve_commit_by_version_and_prefix() {
+    let store = seeded_store().await;
+
+    assert_eq!(
+        store.resolve_commit(None).await.unwrap().as_deref(),
+        Some(SHA)
+    );
+    assert_eq!(
+        store.resolve_commit(Some("v1")).await.unwrap().as_deref(),
+        Some(SHA)
+    );
+    assert_eq!(
+        store.resolve_commit(Some("abc1")).await.unwrap().as_deref(),
+        Some(SHA)
+    );
+    assert_eq!(store.resolve_commit(Some("zzz")).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_export_commit_matches_neo4j_shape() {
+    let store = seeded_store().await;
+    let export = store.export_commit(SHA).await.unwrap();
+
+    assert_eq!(export.nodes.len(), 5);
+    assert_eq!(export.nodes[0].id, "file:hash-graph");
+    let kinds: Vec<_> = export.edges.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(kinds.iter().filter(|k| **k == "DEFINED_IN").count(), 3);
+    assert!(kinds.contains(&"REFERENCES"));
+    assert!(kinds.contains(&"IMPORTS"));
+}
+
+#[tokio::test]
+async fn test_stream_export_yields_nodes_then_edges() {
+    let store = seeded_store().await;
+    let records: Vec<ExportRecord> = store.stream_export(SHA).try_collect().await.unwrap();
+
+    let first_edge = records
+        .iter()
+        .position(|r| matches!(r, ExportRecord::Edge(_)))
+        .unwrap();
+    assert_eq!(first_edge, 5);
+    assert!(records[first_edge..]
+        .iter()
+        .all(|r| matches!(r, ExportRecord::Edge(_))));
+    let mut streamed = GraphExport::default();
+    records.into_iter().for_each(|r| streamed.push(r));
+    assert_eq!(streamed, store.export_commit(SHA).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_stream_export_reads_past_a_batch() {
+    let store = seeded_store().await;
+    let symbols: Vec<SymbolNode> = (0..2500)
+        .map(|i| {
+            symbol(
+                &format!("s-gen-{i}"),
+                "generated",
+                SymbolKind::Function,
+                "src/gen.rs",
+                i,
+            )
+        })
+        .collect();
+    store
+        .create_file_if_new("src/gen.rs", "hash-gen", "rust", SHA)
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(&symbols, "hash-gen")
+        .await
+        .unwrap();
+
+    let export: GraphExport = store
+        .stream_export(SHA)
+        .try_fold(GraphExport::default(), |mut graph, record| async move {
+            graph.push(record);
+            Ok(graph)
+        })
+        .await
+        .unwrap();
+
+    let lines: Vec<u64> = export
+        .nodes
+        .iter()
+        .filter(|n| n.id.starts_with("s-gen-"))
+        .map(|n| n.properties["start_line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, (0..2500).collect::<Vec<u64>>());
+    let defined_in = export.edges.iter().filter(|e| e.kind == "DEFINED_IN");
+    assert_eq!(defined_in.count(), 2503);
+}
+
+#[tokio::test]
+async fn test_export_commit_includes_doc_comments() {
+    let store = seeded_store().await;
+    let mut documented = symbol("s-load", "load", SymbolKind::Function, "src/main.rs", 20);
+    documented.doc_comment = Some("Open the graph store".to_string());
+    store
+        .create_symbols_batch(&[documented], "hash-main")
+        .await
+        .unwrap();
+
+    let export = store.export_commit(SHA).await.unwrap();
+    let doc = |id: &str| {
+        let node = export.nodes.iter().find(|n| n.id == id).unwrap();
+        node.properties.get("doc_comment").cloned()
+    };
+    assert_eq!(doc("s-load"), Some("Open the graph store".into()));
+    assert_eq!(doc("s-main"), None);
+}
+
+#[tokio::test]
+async fn test_execute_raw_counts_rows() {
+    let store = seeded_store().await;
+
+    assert_eq!(
+        store
+            .execute_raw("SELECT id FROM symbols", &QueryParams::new())
+            .await
+            .unwrap(),
+        3
+    );
+    assert!(store
+        .execute_raw("NOT SQL", &QueryParams::new())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_fetch_raw_returns_rows_in_column_order() {
+    let store = seeded_store().await;
+    let mut params = QueryParams::new();
+    params
+        .insert("name", ParamValue::String("Store".to_string()))
+        .unwrap();
+
+    let rows = store
+        .fetch_raw(
+            "SELECT start_line AS line, name, NULL AS missing FROM symbols WHERE name = $name",
+            &params,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    let columns: Vec<&str> = rows[0].keys().map(String::as_str).collect();
+    assert_eq!(columns, ["line", "name", "missing"]);
+    assert_eq!(rows[0]["line"], serde_json::json!(1));
+    assert_eq!(rows[0]["name"], serde_json::json!("Store"));
+    assert!(rows[0]["missing"].is_null());
+    assert_eq!(store.query_language(), QueryLanguage::Sql);
+}
+
+#[tokio::test]
+async fn test_execute_raw_binds_named_parameters() {
+    let store = seeded_store().await;
+    let mut params = QueryParams::new();
+    params
+        .insert("name", ParamValue::String("x' OR '1'='1".to_string()))
+        .unwrap();
+    let by_name = "SELECT id FROM symbols WHERE name = $name";
+    assert_eq!(store.execute_raw(by_name, &params).await.unwrap(), 0);
+
+    params
+        .insert("name", ParamValue::String("Store".to_string()))
+        .unwrap();
+    params.insert("line", ParamValue::Integer(1)).unwrap();
+    let both = "SELECT id FROM symbols WHERE name = :name AND start_line >= @line";
+    assert_eq!(store.execute_raw(both, &params).await.unwrap(), 1);
+
+    let missing = "SELECT id FROM symbols WHERE name = $other";
+    assert!(store.execute_raw(missing, &params).await.is_err());
+}
+
+#[tokio::test]
+async fn test_open_persists_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+
+    {
+        let store = SqliteStore::open(&path).unwrap();
+        store
+            .create_scan_run(&ScanRun::new("/repo").with_commit(SHA))
+            .await
+            .unwrap();
+    }
+
+    let reopened = SqliteStore::open(&path).unwrap();
+    assert_eq!(reopened.stats().await.unwrap().commits, 1);
+}
+
+#[tokio::test]
+async fn test_hover_type_fields_round_trip() {
+    let store = seeded_store().await;
+    let typed = symbol("s-limit", "LIMIT", SymbolKind::Constant, "src/main.rs", 30)
+        .with_type_signature("const LIMIT: usize = 10", Some("usize"));
+    store
+        .create_symbols_batch(&[typed], "hash-main")
+        .await
+        .unwrap();
+
+    let symbols = store.symbols_at_commit(SHA).await.unwrap();
+    let limit = symbols.iter().find(|s| s.id == "s-limit").unwrap();
+    assert_eq!(
+        limit.type_signature.as_deref(),
+        Some("const LIMIT: usize = 10")
+    );
+    assert_eq!(limit.resolved_type.as_deref(), Some("usize"));
+    let main = symbols.iter().find(|s| s.id == "s-main").unwrap();
+    assert!(main.type_signature.is_none() && main.resolved_type.is_none());
+}
+
+#[tokio::test]
+async fn test_search_ranks_name_matches_above_doc_matches() {
+    let store = seeded_store().await;
+    let mut documented = symbol("s-load", "load", SymbolKind::Function, "src/main.rs", 20);
+    documented.doc_comment = Some("Open the graph store from disk".to_string());
+    store
+        .create_symbols_batch(&[documented], "hash-main")
+        .await
+        .unwrap();
+
+    let matches = store
+        .search_symbols("store", Page::default())
+        .await
+        .unwrap();
+    let names: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names.len(), 3);
+    assert_eq!(names[2], "load");
+    assert!(matches[0].score >= matches[1].score && matches[1].score > matches[2].score);
+
+    // Words match by prefix, and any word is enough
+    let matches = store
+        .search_symbols("ope nothing", Page::default())
+        .await
+        .unwrap();
+    let names: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["open_store", "load"]);
+
+    let second = Page {
+        limit: Some(1),
+        offset: 1,
+        sort: None,
+    };
+    let matches = store.search_symbols("ope", second).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].name, "load");
+}
+
+#[tokio::test]
+async fn test_search_treats_query_syntax_literally() {
+    let store = seeded_store().await;
+
+    for text in ["", "   ", "\"", "NOT main", "s*", "main:("] {
+        assert!(
+            store.search_symbols(text, Page::default()).await.is_ok(),
+            "{text:?}"
+        );
+    }
+    // Punctuation around a word is not part of it
+    let matches = store
+        .search_symbols("\"main\"", Page::default())
+        .await
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].name, "main");
+}
+
+#[tokio::test]
+async fn test_open_indexes_symbols_of_older_databases_for_search() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    {
+        let store = SqliteStore::open(&path).unwrap();
+        store
+            .create_scan_run(&ScanRun::new("/repo").with_commit(SHA))
+            .await
+            .unwrap();
+        store
+            .create_file_if_new("src/main.rs", "hash-main", "rust", SHA)
+            .await
+            .unwrap();
+        let main = symbol("s-main", "main", SymbolKind::Function, "src/main.rs", 3);
+        store
+            .create_symbols_batch(&[main], "hash-main")
+            .await
+            .unwrap();
+    }
+    // A database from before search existed
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch(
+            "DROP TRIGGER symbol_search_insert;
+             DROP TRIGGER symbol_search_delete;
+             DROP TABLE symbol_search;",
+        )
+        .unwrap();
+
+    let store = SqliteStore::open(&path).unwrap();
+    let matches = store.search_symbols("main", Page::default()).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].id, "s-main");
+}
+
+#[tokio::test]
+async fn test_open_adds_columns_missing_from_older_databases() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE symbols (id TEXT PRIMARY KEY, content_hash TEXT NOT NULL,
+                 identity_key TEXT NOT NULL, name TEXT NOT NULL, qualified_name TEXT NOT NULL,
+                 kind TEXT NOT NULL, visibility TEXT NOT NULL, file_path TEXT NOT NULL,
+                 start_line INTEGER NOT NULL, end_line INTEGER NOT NULL,
+                 signature TEXT NOT NULL, doc_comment TEXT NOT NULL);",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStore::open(&path).unwrap();
+    assert_eq!(
+        store
+            .execute_raw(
+                "SELECT type_signature, resolved_type FROM symbols",
+                &QueryParams::new()
+            )
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_find_functions_by_parameter_and_return_type() {
+    let store = seeded_store().await;
+    let signature = FunctionSignature {
+        symbol_id: "s-main".to_string(),
+        parameters: vec![
+            ParameterNode {
+                name: "client".to_string(),
+                type_name: Some("&Neo4jClient".to_string()),
+                position: 0,
+            },
+            ParameterNode {
+                name: "depth".to_string(),
+                type_name: None,
+                position: 1,
+            },
+        ],
+        return_type: Some("Result<()>".to_string()),
+    };
+    let unknown = FunctionSignature {
+        symbol_id: "s-missing".to_string(),
+        return_type: Some("Neo4jClient".to_string()),
+        ..FunctionSignature::default()
+    };
+    store
+        .create_function_signatures(&[signature, unknown])
+        .await
+        .unwrap();
+
+    let taking = store
+        .find_functions_by_type(SHA, "Neo4jClient", TypeUsage::Parameter, None)
+        .await
+        .unwrap();
+    assert_eq!(taking.len(), 1);
+    assert_eq!(taking[0].qualified_name, "crate::main");
+    assert_eq!(taking[0].parameter.as_deref(), Some("client"));
+    assert_eq!(taking[0].position, Some(0));
+    assert_eq!(taking[0].type_name, "&Neo4jClient");
+
+    let returning = store
+        .find_functions_by_type(SHA, "Result", TypeUsage::Return, None)
+        .await
+        .unwrap();
+    assert_eq!(returning.len(), 1);
+    assert!(returning[0].parameter.is_none() && returning[0].position.is_none());
+    // Signatures of symbols that were never stored are dropped
+    assert!(store
+        .find_functions_by_type(SHA, "Neo4jClient", TypeUsage::Return, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let elsewhere = PathScope::new("lib").unwrap();
+    assert!(store
+        .find_functions_by_type(SHA, "Neo4jClient", TypeUsage::Parameter, Some(&elsewhere))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_file_metrics_round_trip() {
+    let store = seeded_store().await;
+    let mut metrics = FileMetrics::of_content("fn main() {}\n");
+    metrics.count_symbols(&[symbol(
+        "s-main",
+        "main",
+        SymbolKind::Function,
+        "src/main.rs",
+        1,
+    )]);
+    store.set_file_metrics("hash-main", &metrics).await.unwrap();
+
+    let files = store.file_metrics(SHA, None).await.unwrap();
+    assert_eq!(files.len(), 2);
+    let main = files.iter().find(|f| f.path == "src/main.rs").unwrap();
+    assert_eq!(
+        (main.line_count, main.byte_size, main.symbol_count),
+        (1, 13, 1)
+    );
+    assert_eq!(main.symbol_counts.get("function"), Some(&1));
+    // Files whose metrics were never set report zeros
+    let graph = files.iter().find(|f| f.path == "src/graph.rs").unwrap();
+    assert_eq!((graph.line_count, graph.symbol_count), (0, 0));
+
+    let filtered = store.file_metrics(SHA, Some("graph")).await.unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert!(store.file_metrics("other", None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_similar_symbols_rank_embedded_symbols_of_the_commit() {
+    let store = seeded_store().await;
+    let embedded = |id: &str, name: &str, line: u32, embedding: [f32; 2]| SymbolNode {
+        embedding: Some(embedding.to_vec()),
+        ..symbol(id, name, SymbolKind::Function, "src/lib.rs", line)
+    };
+    store
+        .create_file_if_new("src/lib.rs", "hash-lib", "rust", SHA)
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(
+            &[
+                embedded("s-load", "load", 1, [1.0, 0.0]),
+                embedded("s-read", "read", 10, [0.9, 0.1]),
+                embedded("s-draw", "draw", 20, [0.0, 1.0]),
+                symbol("s-plain", "plain", SymbolKind::Function, "src/lib.rs", 30),
+            ],
+            "hash-lib",
+        )
+        .await
+        .unwrap();
+
+    let similar = store.similar_symbols("s-load", SHA, 10).await.unwrap();
+    let names: Vec<&str> = similar.iter().map(|m| m.name.as_str()).collect();
+    // The symbol itself and symbols without embeddings are left out
+    assert_eq!(names, vec!["read", "draw"]);
+    assert!(similar[0].score > 0.9 && similar[1].score.abs() < 1e-6);
+
+    let top = store.similar_symbols("s-load", SHA, 1).await.unwrap();
+    assert_eq!(top.len(), 1);
+    assert!(store
+        .similar_symbols("s-plain", SHA, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(store
+        .similar_symbols("s-load", "other", 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_open_refuses_databases_of_newer_schema_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let latest = MigrationRunner::default().latest();
+    {
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().await.unwrap(), latest);
+    }
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch(&format!("PRAGMA user_version = {}", latest + 1))
+        .unwrap();
+
+    let error = SqliteStore::open(&path).err().unwrap();
+    assert!(matches!(
+        error,
+        StoreError::IncompatibleSchema(IncompatibleSchema { stored, .. }) if stored == latest + 1
+    ));
+}
+
+#[tokio::test]
+async fn test_read_only_store_refuses_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    {
+        let store = SqliteStore::open(&path).unwrap();
+        store
+            .create_scan_run(&ScanRun::new("/repo").with_commit(SHA))
+            .await
+            .unwrap();
+    }
+
+    let store = SqliteStore::open_read_only(&path).unwrap();
+    assert_eq!(store.scan_runs().await.unwrap().len(), 1);
+    assert_eq!(
+        store
+            .execute_raw("SELECT * FROM commits", &QueryParams::new())
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(matches!(
+        store
+            .execute_raw("DELETE FROM scan_runs", &QueryParams::new())
+            .await,
+        Err(StoreError::ReadOnly(_))
+    ));
+    let run = ScanRun::new("/repo").with_commit("def456");
+    assert!(store.create_scan_run(&run).await.is_err());
+    assert_eq!(store.scan_runs().await.unwrap().len(), 1);
+}
+
+#[test]
+fn test_read_only_store_needs_an_existing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(SqliteStore::open_read_only(&dir.path().join("missing.db")).is_err());
+}
@@ -0,0 +1,522 @@
+//! Graph store: Backend-neutral interface for graph persistence
+//!
+//! The scan pipeline and query commands talk to a [`GraphStore`] rather than a
+//! concrete database client. [`Neo4jClient`](super::neo4j::Neo4jClient) is the
+//! primary implementation; [`SqliteStore`](super::sqlite::SqliteStore) is an
+//! embedded alternative for machines without a Neo4j server.
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use thiserror::Error;
+
+use super::backup::GraphBackup;
+use super::complexity::SymbolComplexity;
+use super::export::{ExportRecord, GraphExport};
+use super::integrity::{IntegrityIssue, IntegrityRepair};
+use super::migrations::IncompatibleSchema;
+use super::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, ExternalSymbolNode, FileMetrics,
+    FileWrite, FunctionSignature, ImportEdge, ModuleNode, RepositoryDependency, RepositoryNode,
+    ScanReport, ScanRun, ScanStatus, SymbolCoverage, SymbolNode,
+};
+use super::neo4j::Neo4jError;
+use super::queries::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, ImporterResult,
+    Page, QueryParams, RawRow, ReferenceDirection, ReferenceGroup, ReferenceGrouping,
+    ReferenceResult, ScanResult, ScanRunResult, SymbolMatch, SymbolResult, SymbolVersionResult,
+    TypeUsage, TypeUsageResult,
+};
+use super::scope::PathScope;
+
+/// Errors that can occur in a graph store
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Neo4j(#[from] Neo4jError),
+
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Storage error: {0}")]
+    Backend(String),
+
+    #[error(transparent)]
+    IncompatibleSchema(#[from] IncompatibleSchema),
+
+    #[error("Refusing to write in read-only mode: {0}")]
+    ReadOnly(String),
+}
+
+/// Rows of a read, yielded as the store fetches them
+pub type RowStream<'a, T> = BoxStream<'a, Result<T, StoreError>>;
+
+/// Native query language of a store backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLanguage {
+    Cypher,
+    Sql,
+}
+
+impl std::fmt::Display for QueryLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cypher => write!(f, "cypher"),
+            Self::Sql => write!(f, "sql"),
+        }
+    }
+}
+
+/// Storage backend for the code graph
+///
+/// Write methods are called by the scan phases in order: scan run, files,
+/// imports, symbols, then edges. Read methods back the query and export
+/// commands.
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    // ------------------------------------------------------------------------
+    // Writes
+    // ------------------------------------------------------------------------
+
+    /// Record a scan run and its commit
+    ///
+    /// Returns `false` if the commit was already scanned, in which case the
+    /// run is linked to the existing data and nothing else needs writing.
+    async fn create_scan_run(&self, scan_run: &ScanRun) -> Result<bool, StoreError>;
+
+    /// Store what failed during a scan with its scan run, replacing any
+    /// earlier report; unknown runs are ignored
+    async fn set_scan_report(&self, report: &ScanReport) -> Result<(), StoreError>;
+
+    /// Record how a scan run ended; unknown runs are ignored
+    async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), StoreError>;
+
+    /// Add a tag to a scan run after the fact; tagging twice has no effect
+    /// and unknown runs are ignored
+    async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), StoreError>;
+
+    /// Set the free-form note of a scan run, replacing any earlier one;
+    /// unknown runs are ignored
+    async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), StoreError>;
+
+    /// Create a file unless identical content is already stored
+    ///
+    /// Returns the content hash for new files, or `None` if the file content
+    /// was reused and only linked to the commit.
+    async fn create_file_if_new(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+        language: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>, StoreError>;
+
+    /// Link stored content to a commit
+    ///
+    /// Returns `false` if no file with `content_hash` is stored; the file is
+    /// then new and has to be written with [`write_file`](Self::write_file).
+    async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, StoreError>;
+
+    /// Store a new file with its metrics, symbols, signatures and edges in
+    /// one transaction, linked to its commit
+    ///
+    /// A scan that dies partway leaves no file half written, so stored
+    /// content can always be reused. Returns `false` if identical content
+    /// was stored in the meantime, in which case it is only linked.
+    async fn write_file(&self, file: &FileWrite) -> Result<bool, StoreError>;
+
+    /// Remove the file at `file_path` from a commit
+    ///
+    /// Only the link is removed; the content-addressed file and its symbols
+    /// stay for other commits. Returns `false` if the commit did not contain
+    /// the path.
+    async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, StoreError>;
+
+    /// Store the size measures of a file
+    async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), StoreError>;
+
+    /// Store the raw module paths a file imports
+    async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), StoreError>;
+
+    /// Link files of a commit that import each other
+    async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), StoreError>;
+
+    /// Store the directory summaries of a commit, replacing earlier ones
+    /// with the same path
+    async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), StoreError>;
+
+    /// Store the module tree of a commit, replacing the one stored before
+    async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), StoreError>;
+
+    /// Store the API contracts of a commit with their HANDLED_BY links,
+    /// replacing the ones stored before
+    async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), StoreError>;
+
+    /// Store the symbols of a file
+    async fn create_symbols_batch(
+        &self,
+        symbols: &[SymbolNode],
+        content_hash: &str,
+    ) -> Result<(), StoreError>;
+
+    /// Create an edge between two symbols
+    async fn create_edge(&self, edge: &Edge) -> Result<(), StoreError>;
+
+    /// Create edges between symbols in one write
+    ///
+    /// Edges whose source or target symbol is unknown are skipped.
+    async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), StoreError>;
+
+    /// Link a commit to the symbols it changed with MODIFIED edges
+    ///
+    /// Unknown symbol IDs are ignored.
+    async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), StoreError>;
+
+    /// Record that only a sample of a symbol's references was stored
+    ///
+    /// `total` is the number of references the language server reported, so
+    /// the graph keeps the magnitude of heavily referenced symbols.
+    async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), StoreError>;
+
+    /// Store complexity proxies on symbols, replacing earlier values
+    ///
+    /// Symbols are shared by commits with the same file content, so they keep
+    /// the values of the last commit computed.
+    async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), StoreError>;
+
+    /// Store line and branch coverage on symbols, replacing earlier values
+    ///
+    /// Like complexity, symbols shared by several commits keep the coverage
+    /// last imported for any of them.
+    async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), StoreError>;
+
+    /// Store the repository scanned at a path, replacing the one stored
+    /// before
+    async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), StoreError>;
+
+    /// Replace every DEPENDS_ON edge between repositories
+    ///
+    /// Edges between paths without a stored repository are skipped.
+    async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), StoreError>;
+
+    /// Delete a scan run and the data only it kept alive
+    ///
+    /// The run's commit goes once no other run is left for it. Files that no
+    /// remaining commit contains are then deleted with their symbols,
+    /// parameters and diagnostics, as are identities, types and external
+    /// symbols nothing points to anymore. Nothing is deleted if no run has
+    /// the ID; the summary then counts no runs.
+    async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, StoreError>;
+
+    /// Delete the integrity problems that are safe to remove
+    ///
+    /// Dangling reference and call edges go first, then files no commit
+    /// contains with everything [`delete_scan_run`](Self::delete_scan_run)
+    /// would delete along with them. Other problems are left alone.
+    async fn repair_integrity(&self) -> Result<IntegrityRepair, StoreError>;
+
+    /// Load a backup into the empty store of the backend it was taken from
+    ///
+    /// Returns the number of records restored. Fails without writing if the
+    /// store already holds a graph.
+    async fn restore(&self, backup: &GraphBackup) -> Result<usize, StoreError>;
+
+    /// Store the diagnostics reported for a file, replacing earlier ones
+    async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), StoreError>;
+
+    /// Store the parameters and return types of functions whose symbols are
+    /// already stored
+    async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), StoreError>;
+
+    /// Record a reference from a symbol to a definition outside the scanned
+    /// files, creating its ExternalSymbol placeholder unless it exists
+    async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), StoreError>;
+
+    // ------------------------------------------------------------------------
+    // Reads
+    // ------------------------------------------------------------------------
+
+    /// Find symbols whose name contains a pattern (case-insensitive),
+    /// optionally only in files inside a scope, ordered by name unless
+    /// `page` sorts them otherwise
+    async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError>;
+
+    /// Full-text search of symbol names, qualified names and doc comments,
+    /// best matches first
+    ///
+    /// Words of `text` match by prefix or, in Neo4j, within a small edit
+    /// distance. The sort key of `page` is ignored.
+    async fn search_symbols(&self, text: &str, page: Page) -> Result<Vec<SymbolMatch>, StoreError>;
+
+    /// Symbols of a commit whose embeddings are closest to the embedding of
+    /// symbol `symbol_id` by cosine similarity, most similar first, without
+    /// the symbol itself
+    ///
+    /// Empty if the symbol has no embedding, i.e. it was scanned without an
+    /// embedder.
+    async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, StoreError>;
+
+    /// Find symbols in files whose path contains `file_path`
+    async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError>;
+
+    /// Symbols a commit changed relative to the commit scanned before it,
+    /// ordered by file and line
+    async fn modified_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolResult>, StoreError>;
+
+    /// Find symbols matching a pattern as of a commit, optionally only in
+    /// files inside a scope, ordered like [`GraphStore::find_symbols`]
+    async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError>;
+
+    /// Every symbol in the files of a commit, ordered by file and line
+    async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, StoreError>;
+
+    /// Every stored version of a symbol, oldest first
+    ///
+    /// Follows `SAME_AS` and `EVOLVED_FROM` edges, so versions under a
+    /// symbol's earlier or later names are included.
+    async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError>;
+
+    /// Symbols of the most recently stored other version of a file
+    ///
+    /// Looks up files at `file_path` whose content differs from
+    /// `content_hash`; the scan links new symbol versions to these with
+    /// `SAME_AS` and `EVOLVED_FROM` edges. Empty if the path is new.
+    async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, StoreError>;
+
+    /// Find references to symbols with a name, ordered by the referencing
+    /// file and line unless `page` sorts them by referencing symbol
+    async fn find_references_to(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError>;
+
+    /// Find references made from symbols with a name, ordered by the
+    /// referenced file and line unless `page` sorts them by referenced symbol
+    async fn find_references_from(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError>;
+
+    /// Find references to or from symbols with a name, aggregated in the
+    /// store and ordered by file and first line
+    async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, StoreError>;
+
+    /// Summary of a directory as of a commit
+    async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, StoreError>;
+
+    /// Every directory summary of a commit, ordered by path
+    async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, StoreError>;
+
+    /// Modules of a commit with the symbols directly in them, ordered by
+    /// path
+    async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, StoreError>;
+
+    /// API contracts of a commit with the symbols serving them, ordered by
+    /// file, line and name
+    async fn contracts_at_commit(&self, commit_sha: &str) -> Result<Vec<ContractNode>, StoreError>;
+
+    /// Every scanned repository, ordered by path
+    async fn repositories(&self) -> Result<Vec<RepositoryNode>, StoreError>;
+
+    /// DEPENDS_ON edges between repositories, ordered by the paths of both
+    async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, StoreError>;
+
+    /// Diagnostics in the files of a commit, ordered by file and position
+    ///
+    /// Optionally only diagnostics of one severity, or in files inside a
+    /// scope.
+    async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, StoreError>;
+
+    /// Functions in the files of a commit with a parameter or return type
+    /// containing `type_name`, ordered by file, line and parameter position
+    ///
+    /// Optionally only functions in files inside a scope.
+    async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, StoreError>;
+
+    /// List files, optionally filtered by a path pattern, ordered by path
+    /// unless `page` sorts them by language or symbol count
+    async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, StoreError>;
+
+    /// Size measures of the files of a commit, optionally only those whose
+    /// path contains a pattern, ordered by path
+    async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, StoreError>;
+
+    /// Find files importing a module or one of its submodules
+    async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError>;
+
+    /// Node counts by label
+    async fn stats(&self) -> Result<GraphStats, StoreError>;
+
+    /// Every scan run, newest first
+    async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, StoreError>;
+
+    /// The failure report stored with a scan run, `None` if the run has none
+    async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, StoreError>;
+
+    /// Problems in the stored graph, ordered by check and subject
+    async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, StoreError>;
+
+    /// Schema version the store was migrated to
+    async fn schema_version(&self) -> Result<u32, StoreError>;
+
+    /// Resolve a version tag or commit SHA prefix to a full commit SHA
+    ///
+    /// `tag:<name>` resolves to the commit of the newest run tagged `<name>`
+    /// with `runs tag`. `None` resolves to the commit of the most recent
+    /// completed run; runs still in progress or failed are skipped.
+    async fn resolve_commit(&self, version: Option<&str>) -> Result<Option<String>, StoreError>;
+
+    /// The most recently scanned commits of a branch, newest first
+    ///
+    /// `None` uses the branch of the most recent scan. Only completed runs
+    /// count.
+    async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, StoreError>;
+
+    /// Collect the nodes and edges of a commit for export
+    async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, StoreError>;
+
+    /// Stream the nodes and edges of a commit for export, in the order
+    /// [`export_commit`](Self::export_commit) collects them
+    ///
+    /// Nodes come first: files by path, then symbols by file and line. The
+    /// store fetches rows as the stream is polled, so memory stays bounded
+    /// however large the commit.
+    fn stream_export<'a>(&'a self, commit_sha: &'a str) -> RowStream<'a, ExportRecord>;
+
+    /// Everything the store holds, for [`restore`](Self::restore)
+    async fn backup(&self) -> Result<GraphBackup, StoreError>;
+
+    /// Execute a query in the backend's native query language, binding
+    /// `params` to its named parameters
+    ///
+    /// Returns the number of rows produced.
+    async fn execute_raw(&self, query: &str, params: &QueryParams) -> Result<usize, StoreError>;
+
+    /// Execute a query in the backend's native query language, binding
+    /// `params` to its named parameters, and return its rows
+    async fn fetch_raw(&self, query: &str, params: &QueryParams)
+        -> Result<Vec<RawRow>, StoreError>;
+
+    /// The backend's native query language, as taken by
+    /// [`execute_raw`](Self::execute_raw)
+    fn query_language(&self) -> QueryLanguage;
+}
@@ -0,0 +1,116 @@
+//! Testing: Which symbols the tests of a commit exercise
+//!
+//! Test symbols are recognized by their language adapter, from naming
+//! conventions and the location of their file under the scan root (see
+//! [`crate::adapters::LanguageAdapter::is_test`]). [`test_edges`] links each
+//! test to the production symbols it references or calls with TESTS edges,
+//! and [`untested`] lists the public functions and methods no test reaches
+//! that way.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::api_surface::is_public;
+use super::export::GraphExport;
+use super::model::{Edge, EdgeKind, SymbolKind, SymbolNode};
+use crate::adapters::adapter_for_path;
+
+/// Edges by which a test exercises a symbol
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// A public function or method no test exercises
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UntestedSymbol {
+    pub id: String,
+    pub qualified_name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    pub start_line: u32,
+    /// Incoming references and calls, to rank the most used first
+    pub references: usize,
+}
+
+/// Whether a symbol of a commit scanned under `root` is a test
+#[must_use]
+pub fn is_test_symbol(root: &Path, symbol: &SymbolNode) -> bool {
+    let path = Path::new(&symbol.file_path);
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    adapter_for_path(path).is_test(symbol, relative)
+}
+
+/// TESTS edges from the tests among `symbols` to the other symbols they
+/// reference or call in `graph`, the export of the commit
+///
+/// One edge per pair, ordered by source and target.
+#[must_use]
+pub fn test_edges(root: &Path, symbols: &[SymbolNode], graph: &GraphExport) -> Vec<Edge> {
+    let tests: HashMap<&str, bool> = symbols
+        .iter()
+        .map(|s| (s.id.as_str(), is_test_symbol(root, s)))
+        .collect();
+    let pairs: BTreeSet<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| USE_EDGES.contains(&e.kind.as_str()))
+        .filter(|e| tests.get(e.source.as_str()) == Some(&true))
+        .filter(|e| tests.get(e.target.as_str()) == Some(&false))
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+    pairs
+        .into_iter()
+        .map(|(source_id, target_id)| Edge {
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            kind: EdgeKind::Tests,
+            line: None,
+            column: None,
+        })
+        .collect()
+}
+
+/// Public functions and methods among `symbols` without an incoming TESTS
+/// edge in `graph`, tests themselves left out
+///
+/// Sorted by incoming references, most used first, then by location.
+#[must_use]
+pub fn untested(root: &Path, symbols: &[SymbolNode], graph: &GraphExport) -> Vec<UntestedSymbol> {
+    let tests = EdgeKind::Tests.to_string();
+    let tested: HashSet<&str> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == tests)
+        .map(|e| e.target.as_str())
+        .collect();
+    let mut references: HashMap<&str, usize> = HashMap::new();
+    for edge in graph
+        .edges
+        .iter()
+        .filter(|e| USE_EDGES.contains(&e.kind.as_str()))
+    {
+        *references.entry(edge.target.as_str()).or_default() += 1;
+    }
+
+    let mut untested: Vec<UntestedSymbol> = symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .filter(|s| s.visibility.as_deref().is_some_and(is_public))
+        .filter(|s| !tested.contains(s.id.as_str()) && !is_test_symbol(root, s))
+        .map(|s| UntestedSymbol {
+            id: s.id.clone(),
+            qualified_name: s.qualified_name.clone(),
+            kind: s.kind,
+            file_path: s.file_path.clone(),
+            start_line: s.start_line,
+            references: references.get(s.id.as_str()).copied().unwrap_or_default(),
+        })
+        .collect();
+    untested.sort_by(|a, b| {
+        b.references
+            .cmp(&a.references)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then(a.start_line.cmp(&b.start_line))
+    });
+    untested
+}
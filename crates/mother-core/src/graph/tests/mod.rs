@@ -1,5 +1,26 @@
 //! Tests for graph module
 
+mod tests_api_surface;
+mod tests_backup;
+mod tests_churn;
+mod tests_complexity;
+mod tests_cycles;
+mod tests_diff;
+mod tests_directories;
+mod tests_doc_coverage;
+mod tests_fuzzy;
+mod tests_ids;
+mod tests_linking;
+mod tests_migrations;
 mod tests_model;
+mod tests_modules;
 mod tests_neo4j_client;
 mod tests_neo4jconfig;
+mod tests_params;
+mod tests_paths;
+mod tests_read_only;
+mod tests_reader;
+mod tests_scope;
+mod tests_store;
+mod tests_testing;
+mod tests_trends;
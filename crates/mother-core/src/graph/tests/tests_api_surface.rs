@@ -0,0 +1,195 @@
+//! Tests for API surface analysis
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::graph::api_surface::{api_surface, is_public, ApiSymbol};
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+use crate::graph::scope::PathScope;
+
+#[allow(clippy::too_many_arguments)]
+fn node(
+    id: &str,
+    name: &str,
+    kind: &str,
+    visibility: &str,
+    file: &str,
+    lines: (i64, i64),
+    signature: &str,
+) -> ExportNode {
+    let mut properties = BTreeMap::new();
+    for (key, value) in [
+        ("name", name),
+        ("qualified_name", name),
+        ("kind", kind),
+        ("visibility", visibility),
+        ("file_path", file),
+        ("signature", signature),
+    ] {
+        properties.insert(key.to_string(), Value::from(value));
+    }
+    properties.insert("start_line".to_string(), Value::from(lines.0));
+    properties.insert("end_line".to_string(), Value::from(lines.1));
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties,
+    }
+}
+
+fn reference(source: &str, target: &str) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: "REFERENCES".to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+/// `lib.rs`: a public struct with a public field of a private type, a public
+/// function whose body (not signature) uses a private helper, and a public
+/// type alias of a crate-visible type with a public field of another private
+/// type. `other.rs` is outside the scope.
+fn graph() -> GraphExport {
+    let lib = "/repo/src/lib.rs";
+    GraphExport {
+        nodes: vec![
+            node(
+                "config",
+                "Config",
+                "struct",
+                "pub",
+                lib,
+                (1, 4),
+                "pub struct Config",
+            ),
+            node("inner", "inner", "field", "pub", lib, (2, 2), "Inner"),
+            node(
+                "inner-t",
+                "Inner",
+                "struct",
+                "private",
+                lib,
+                (6, 6),
+                "struct Inner",
+            ),
+            node(
+                "run",
+                "run",
+                "function",
+                "pub",
+                lib,
+                (8, 10),
+                "pub fn run()",
+            ),
+            node(
+                "helper",
+                "helper",
+                "function",
+                "private",
+                lib,
+                (12, 12),
+                "fn helper()",
+            ),
+            node(
+                "alias",
+                "Handle",
+                "type_alias",
+                "pub",
+                lib,
+                (14, 14),
+                "pub type Handle",
+            ),
+            node(
+                "state",
+                "State",
+                "struct",
+                "pub(crate)",
+                lib,
+                (16, 19),
+                "pub(crate) struct State",
+            ),
+            node("slot", "slot", "field", "pub", lib, (17, 17), "Slot"),
+            node(
+                "slot-t",
+                "Slot",
+                "struct",
+                "private",
+                lib,
+                (21, 21),
+                "struct Slot",
+            ),
+            node(
+                "other",
+                "Other",
+                "struct",
+                "pub",
+                "/repo/src/other.rs",
+                (1, 1),
+                "pub struct Other",
+            ),
+        ],
+        edges: vec![
+            reference("inner", "inner-t"),
+            reference("run", "helper"),
+            reference("alias", "state"),
+            reference("slot", "slot-t"),
+        ],
+    }
+}
+
+fn names(symbols: &[ApiSymbol]) -> Vec<&str> {
+    symbols.iter().map(|s| s.qualified_name.as_str()).collect()
+}
+
+#[test]
+fn test_is_public() {
+    assert!(is_public("pub"));
+    assert!(is_public("public"));
+    assert!(!is_public("pub(crate)"));
+    assert!(!is_public("private"));
+    assert!(!is_public(""));
+}
+
+#[test]
+fn test_public_symbols_in_scope_come_first() {
+    let scope = PathScope::new("/repo/src/lib.rs").unwrap();
+    let surface = api_surface(&graph(), &scope);
+
+    assert_eq!(
+        names(&surface),
+        ["Config", "inner", "run", "Handle", "slot", "Inner", "State", "Slot"]
+    );
+    assert!(surface[..5].iter().all(ApiSymbol::is_public));
+    assert!(!surface[5..].iter().any(ApiSymbol::is_public));
+}
+
+#[test]
+fn test_body_references_are_not_leaks() {
+    let scope = PathScope::new("/repo/src").unwrap();
+    let surface = api_surface(&graph(), &scope);
+
+    let run = surface.iter().find(|s| s.qualified_name == "run").unwrap();
+    assert!(run.exposes.is_empty());
+    assert!(!names(&surface).contains(&"helper"));
+    assert!(names(&surface).contains(&"Other"));
+}
+
+#[test]
+fn test_leaks_are_followed_through_public_members() {
+    let scope = PathScope::new("/repo/src/lib.rs").unwrap();
+    let surface = api_surface(&graph(), &scope);
+
+    let alias = surface
+        .iter()
+        .find(|s| s.qualified_name == "Handle")
+        .unwrap();
+    assert_eq!(alias.exposes, ["State", "Slot"]);
+
+    let slot = surface.iter().find(|s| s.qualified_name == "Slot").unwrap();
+    assert_eq!(slot.exposed_by, ["Handle", "slot"]);
+    assert_eq!(slot.start_line, 21);
+}
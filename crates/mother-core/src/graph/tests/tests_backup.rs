@@ -0,0 +1,63 @@
+//! Tests for graph backups
+
+#![allow(clippy::unwrap_used)]
+
+use serde_json::{json, Map, Value};
+
+use crate::graph::backup::{
+    blob_bytes, blob_value, read_backup, write_backup, BackupRecord, GraphBackup,
+};
+
+fn properties(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_backup_round_trips_through_json_lines() {
+    let backup = GraphBackup {
+        backend: "neo4j".to_string(),
+        records: vec![
+            BackupRecord::Node {
+                id: "4:abc:0".to_string(),
+                labels: vec!["Contract".to_string(), "Endpoint".to_string()],
+                properties: properties(json!({"name": "GET /orders", "line": 3, "score": 1.5})),
+            },
+            BackupRecord::Relationship {
+                source: "4:abc:0".to_string(),
+                target: "4:abc:1".to_string(),
+                kind: "HANDLED_BY".to_string(),
+                properties: Map::new(),
+            },
+        ],
+    };
+
+    let mut out = Vec::new();
+    write_backup(&backup, &mut out).unwrap();
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert_eq!(text.lines().count(), 3);
+    assert!(text.starts_with(r#"{"format":"mother-backup","version":1,"backend":"neo4j"}"#));
+
+    assert_eq!(read_backup(out.as_slice()).unwrap(), backup);
+}
+
+#[test]
+fn test_read_backup_rejects_other_files_and_newer_versions() {
+    let errors = [
+        "",
+        "{\"nodes\": []}\n",
+        "{\"format\":\"mother-backup\",\"version\":99,\"backend\":\"sqlite\"}\n",
+        "{\"format\":\"mother-backup\",\"version\":1,\"backend\":\"sqlite\"}\n{\"type\":\"edge\"}\n",
+    ];
+    for text in errors {
+        assert!(read_backup(text.as_bytes()).is_err(), "{text}");
+    }
+}
+
+#[test]
+fn test_blobs_round_trip_as_hex() {
+    let value = blob_value(&[0, 15, 255]);
+    assert_eq!(value, json!({"$blob": "000fff"}));
+    assert_eq!(blob_bytes(&value), Some(vec![0, 15, 255]));
+    assert_eq!(blob_bytes(&json!("000fff")), None);
+    assert_eq!(blob_bytes(&json!({"$blob": "0g"})), None);
+}
@@ -0,0 +1,97 @@
+//! Tests for symbol and file churn across scans
+
+use crate::graph::churn::{by_file, by_symbol, FileChurn, ScanSymbols, SymbolChurn};
+use crate::graph::model::{SymbolKind, SymbolNode};
+use crate::graph::queries::SymbolResult;
+
+fn symbol(name: &str, file: &str, line: u32, signature: &str) -> SymbolNode {
+    SymbolNode {
+        visibility: Some("pub".to_string()),
+        signature: Some(signature.to_string()),
+        ..SymbolNode::new(
+            format!("{file}:{name}:{line}:{signature}"),
+            name,
+            SymbolKind::Function,
+            file,
+            line,
+            line + 2,
+        )
+    }
+}
+
+fn scan(symbols: Vec<SymbolNode>, modified: &[&str]) -> ScanSymbols {
+    let modified = symbols
+        .iter()
+        .filter(|s| modified.contains(&s.name.as_str()))
+        .map(|s| SymbolResult {
+            id: s.id.clone(),
+            name: s.name.clone(),
+            qualified_name: s.qualified_name.clone(),
+            kind: s.kind.to_string(),
+            file_path: s.file_path.clone(),
+            start_line: i64::from(s.start_line),
+            end_line: i64::from(s.end_line),
+        })
+        .collect();
+    ScanSymbols { symbols, modified }
+}
+
+fn churn(name: &str, inserts: usize, updates: usize) -> SymbolChurn {
+    SymbolChurn {
+        qualified_name: name.to_string(),
+        kind: "function".to_string(),
+        file_path: "src/lib.rs".to_string(),
+        inserts,
+        updates,
+    }
+}
+
+#[test]
+fn test_inserts_and_updates_follow_renames() {
+    let scans = [
+        scan(
+            vec![
+                symbol("a", "src/lib.rs", 1, "fn a()"),
+                symbol("b", "src/lib.rs", 5, "fn b()"),
+            ],
+            &["a", "b"],
+        ),
+        scan(
+            vec![
+                symbol("a", "src/lib.rs", 1, "fn a()"),
+                symbol("b", "src/lib.rs", 5, "fn b()"),
+                symbol("c", "src/lib.rs", 9, "fn c()"),
+            ],
+            &["a", "c"],
+        ),
+        scan(
+            vec![
+                symbol("a2", "src/lib.rs", 1, "fn a2()"),
+                symbol("b", "src/lib.rs", 5, "fn b()"),
+                symbol("c", "src/lib.rs", 9, "fn c(x: u8)"),
+            ],
+            &["a2"],
+        ),
+    ];
+
+    let symbols = by_symbol(&scans);
+
+    // The oldest scan is only compared against, and a new symbol's
+    // MODIFIED edge is not an update on top of its insert
+    assert_eq!(symbols, [churn("a2", 0, 2), churn("c", 1, 1)]);
+    assert_eq!(
+        by_file(&symbols),
+        [FileChurn {
+            path: "src/lib.rs".to_string(),
+            symbols: 2,
+            inserts: 1,
+            updates: 3,
+        }]
+    );
+}
+
+#[test]
+fn test_single_scan_has_no_churn() {
+    let scans = [scan(vec![symbol("a", "src/lib.rs", 1, "fn a()")], &["a"])];
+    assert!(by_symbol(&scans).is_empty());
+}
@@ -0,0 +1,110 @@
+//! Tests for per-symbol complexity proxies
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::complexity::symbol_complexity;
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+
+fn symbol(id: &str, line: i64, reference_count: Option<i64>) -> ExportNode {
+    let mut properties: BTreeMap<String, Value> = BTreeMap::from([
+        ("qualified_name".to_string(), json!(format!("crate::{id}"))),
+        ("kind".to_string(), json!("function")),
+        ("file_path".to_string(), json!("/repo/src/lib.rs")),
+        ("start_line".to_string(), json!(line)),
+    ]);
+    if let Some(count) = reference_count {
+        properties.insert("reference_count".to_string(), json!(count));
+    }
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties,
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+/// `run` uses `parse` twice and `check` once, `check` uses `parse`,
+/// `walk` only recurses and `report` has sampled references
+fn graph() -> GraphExport {
+    GraphExport {
+        nodes: vec![
+            ExportNode {
+                id: "file:1".to_string(),
+                label: "File".to_string(),
+                properties: BTreeMap::new(),
+            },
+            symbol("run", 1, None),
+            symbol("parse", 10, None),
+            symbol("check", 20, None),
+            symbol("walk", 30, None),
+            symbol("report", 40, Some(500)),
+        ],
+        edges: vec![
+            edge("run", "file:1", "DEFINED_IN"),
+            edge("run", "parse", "REFERENCES"),
+            edge("run", "parse", "CALLS"),
+            edge("run", "check", "REFERENCES"),
+            edge("check", "parse", "REFERENCES"),
+            edge("walk", "walk", "REFERENCES"),
+            edge("check", "report", "REFERENCES"),
+        ],
+    }
+}
+
+#[test]
+fn test_symbol_complexity_counts_distinct_neighbours_and_edges() {
+    let complexity = symbol_complexity(&graph());
+
+    let run = complexity.iter().find(|c| c.symbol_id == "run").unwrap();
+    assert_eq!(run.outgoing_calls, 3);
+    assert_eq!(run.fan_out, 2);
+    assert_eq!(run.fan_in, 0);
+    assert_eq!(run.score, 2);
+
+    let parse = complexity.iter().find(|c| c.symbol_id == "parse").unwrap();
+    assert_eq!(parse.fan_in, 2);
+    assert_eq!(parse.incoming_references, 3);
+    assert_eq!(parse.qualified_name, "crate::parse");
+    assert_eq!(parse.start_line, 10);
+}
+
+#[test]
+fn test_symbol_complexity_ignores_self_references_and_non_symbols() {
+    let complexity = symbol_complexity(&graph());
+
+    assert_eq!(complexity.len(), 5);
+    let walk = complexity.iter().find(|c| c.symbol_id == "walk").unwrap();
+    assert_eq!(walk.score, 0);
+    assert_eq!(walk.outgoing_calls, 0);
+}
+
+#[test]
+fn test_symbol_complexity_keeps_reported_reference_totals() {
+    let complexity = symbol_complexity(&graph());
+
+    let report = complexity.iter().find(|c| c.symbol_id == "report").unwrap();
+    assert_eq!(report.fan_in, 1);
+    assert_eq!(report.incoming_references, 500);
+}
+
+#[test]
+fn test_symbol_complexity_orders_by_score_then_location() {
+    let ids: Vec<_> = symbol_complexity(&graph())
+        .into_iter()
+        .map(|c| c.symbol_id)
+        .collect();
+
+    assert_eq!(ids, vec!["check", "run", "parse", "report", "walk"]);
+}
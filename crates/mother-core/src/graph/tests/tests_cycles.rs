@@ -0,0 +1,120 @@
+//! Tests for dependency cycle detection
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::graph::cycles::{dependency_cycles, CycleLevel};
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+
+fn node(id: &str, label: &str, properties: &[(&str, Value)]) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties: properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, properties: &[(&str, Value)]) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+/// `src/a/a.rs` imports `src/b/b.rs`, whose `b::load` calls `a::run` back.
+/// `src/b/c.rs` and `src/b/d.rs` reference each other inside one directory,
+/// and `src/b/c.rs` depends on `src/b/b.rs` without being used by it.
+fn graph() -> GraphExport {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for (file, path) in [
+        ("file:a", "/repo/src/a/a.rs"),
+        ("file:b", "/repo/src/b/b.rs"),
+        ("file:c", "/repo/src/b/c.rs"),
+        ("file:d", "/repo/src/b/d.rs"),
+    ] {
+        nodes.push(node(file, "File", &[("path", json!(path))]));
+    }
+    for (id, name, file) in [
+        ("run", "a::run", "file:a"),
+        ("load", "b::load", "file:b"),
+        ("parse", "c::parse", "file:c"),
+        ("check", "d::check", "file:d"),
+    ] {
+        nodes.push(node(id, "Symbol", &[("qualified_name", json!(name))]));
+        edges.push(edge(id, file, "DEFINED_IN", &[]));
+    }
+    edges.extend([
+        edge(
+            "file:a",
+            "file:b",
+            "IMPORTS",
+            &[("module", json!("crate::b")), ("line", json!(1))],
+        ),
+        edge("load", "run", "CALLS", &[("line", json!(7))]),
+        edge("parse", "check", "REFERENCES", &[("line", json!(3))]),
+        edge("check", "parse", "REFERENCES", &[("line", json!(4))]),
+        edge("parse", "load", "REFERENCES", &[("line", json!(5))]),
+        edge("run", "run", "REFERENCES", &[("line", json!(2))]),
+    ]);
+    GraphExport { nodes, edges }
+}
+
+#[test]
+fn test_file_cycles_are_strongly_connected_files() {
+    let cycles = dependency_cycles(&graph(), CycleLevel::File);
+
+    let members: Vec<_> = cycles.iter().map(|c| c.members.clone()).collect();
+    assert_eq!(
+        members,
+        vec![
+            vec!["/repo/src/a/a.rs", "/repo/src/b/b.rs"],
+            vec!["/repo/src/b/c.rs", "/repo/src/b/d.rs"],
+        ]
+    );
+}
+
+#[test]
+fn test_cycle_links_name_the_imports_and_symbols_involved() {
+    let cycles = dependency_cycles(&graph(), CycleLevel::File);
+
+    let links: Vec<String> = cycles[0].links.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        links,
+        vec![
+            "/repo/src/a/a.rs -[IMPORTS]-> crate::b",
+            "b::load -[CALLS]-> a::run",
+        ]
+    );
+    assert_eq!(cycles[0].links[1].line, 7);
+}
+
+#[test]
+fn test_module_cycles_ignore_dependencies_within_a_directory() {
+    let cycles = dependency_cycles(&graph(), CycleLevel::Module);
+
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].members, vec!["/repo/src/a", "/repo/src/b"]);
+    assert_eq!(cycles[0].links.len(), 2);
+}
+
+#[test]
+fn test_acyclic_graph_has_no_cycles() {
+    let mut graph = graph();
+    graph
+        .edges
+        .retain(|e| e.kind != "CALLS" && e.source != "check");
+
+    assert!(dependency_cycles(&graph, CycleLevel::File).is_empty());
+}
@@ -0,0 +1,209 @@
+//! Tests for symbol diffs between commits
+
+use crate::graph::diff::{diff_symbols, ChangeKind, SymbolChange};
+use crate::graph::model::{SymbolKind, SymbolNode};
+
+fn symbol(name: &str, file: &str, line: u32, signature: &str, visibility: &str) -> SymbolNode {
+    SymbolNode {
+        visibility: Some(visibility.to_string()),
+        signature: Some(signature.to_string()),
+        ..SymbolNode::new(
+            format!("{file}:{name}:{line}"),
+            name,
+            SymbolKind::Function,
+            file,
+            line,
+            line + 2,
+        )
+    }
+}
+
+fn summary<'a>(changes: &[&'a SymbolChange]) -> Vec<(ChangeKind, &'a str, &'a str)> {
+    changes
+        .iter()
+        .map(|c| (c.change, c.qualified_name.as_str(), c.file_path.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_unchanged_symbols_and_line_shifts_are_not_changes() {
+    let from = [symbol("parse", "src/a.rs", 1, "fn parse()", "pub")];
+    let to = [symbol("parse", "src/a.rs", 40, "fn parse()", "pub")];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+    assert!(diff.is_empty());
+    assert_eq!((diff.from.as_str(), diff.to.as_str()), ("old", "new"));
+}
+
+#[test]
+fn test_added_removed_and_modified_grouped_per_file() {
+    let from = [
+        symbol("parse", "src/a.rs", 1, "fn parse()", "pub"),
+        symbol("helper", "src/a.rs", 5, "fn helper()", "private"),
+        symbol("gone", "src/b.rs", 1, "fn gone()", "pub"),
+    ];
+    let to = [
+        symbol("parse", "src/a.rs", 1, "fn parse(input: &str)", "pub"),
+        symbol("helper", "src/a.rs", 5, "fn helper()", "pub"),
+        symbol("fresh", "src/a.rs", 9, "fn fresh()", "pub"),
+    ];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+    assert_eq!(
+        (diff.added, diff.removed, diff.modified, diff.moved),
+        (1, 1, 2, 0)
+    );
+    let paths: Vec<&str> = diff.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["src/a.rs", "src/b.rs"]);
+
+    let changes: Vec<&SymbolChange> = diff.changes().collect();
+    assert_eq!(
+        summary(&changes),
+        [
+            (ChangeKind::Modified, "parse", "src/a.rs"),
+            (ChangeKind::Modified, "helper", "src/a.rs"),
+            (ChangeKind::Added, "fresh", "src/a.rs"),
+            (ChangeKind::Removed, "gone", "src/b.rs"),
+        ]
+    );
+    assert_eq!(
+        changes[0].new_signature.as_deref(),
+        Some("fn parse(input: &str)")
+    );
+    assert_eq!(changes[0].old_visibility, None);
+    assert_eq!(changes[1].old_visibility.as_deref(), Some("private"));
+    assert_eq!(changes[1].new_signature, None);
+}
+
+#[test]
+fn test_symbol_in_another_file_is_a_move() {
+    let from = [symbol("parse", "src/a.rs", 1, "fn parse()", "pub")];
+    let to = [symbol("parse", "src/parser.rs", 3, "fn parse()", "pub")];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+    let changes: Vec<&SymbolChange> = diff.changes().collect();
+    assert_eq!(
+        summary(&changes),
+        [(ChangeKind::Moved, "parse", "src/parser.rs")]
+    );
+    assert_eq!(changes[0].old_file_path.as_deref(), Some("src/a.rs"));
+    assert_eq!(diff.moved, 1);
+}
+
+#[test]
+fn test_unknown_fields_are_not_changes() {
+    let from = [symbol("parse", "src/a.rs", 1, "fn parse()", "pub")];
+    let mut unenriched = symbol("parse", "src/a.rs", 1, "fn parse()", "pub");
+    unenriched.visibility = None;
+    unenriched.signature = None;
+
+    assert!(diff_symbols("old", &from, "new", &[unenriched]).is_empty());
+}
+
+#[test]
+fn test_breaking_changes() {
+    let from = [
+        symbol("parse", "src/a.rs", 1, "fn parse()", "pub"),
+        symbol("render", "src/a.rs", 5, "fn render()", "pub"),
+        symbol("gone", "src/a.rs", 9, "fn gone()", "pub"),
+        symbol(
+            "private_gone",
+            "src/a.rs",
+            12,
+            "fn private_gone()",
+            "private",
+        ),
+        symbol("helper", "src/a.rs", 15, "fn helper()", "private"),
+        symbol("widened", "src/a.rs", 20, "fn widened()", "pub(crate)"),
+    ];
+    let to = [
+        symbol("parse", "src/a.rs", 1, "fn parse(input: &str)", "pub"),
+        symbol("render", "src/a.rs", 5, "fn render()", "pub(crate)"),
+        symbol("helper", "src/a.rs", 15, "fn helper(x: u8)", "private"),
+        symbol("widened", "src/a.rs", 20, "fn widened()", "pub"),
+        symbol("fresh", "src/a.rs", 25, "fn fresh()", "pub"),
+    ];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+    let breaking: Vec<&str> = diff
+        .changes()
+        .filter(|c| c.breaking)
+        .map(|c| c.qualified_name.as_str())
+        .collect();
+
+    assert_eq!(breaking, ["parse", "render", "gone"]);
+    assert_eq!(diff.breaking, 3);
+}
+
+#[test]
+fn test_breaking_only_keeps_breaking_changes() {
+    let from = [
+        symbol("gone", "src/a.rs", 1, "fn gone()", "pub"),
+        symbol("helper", "src/b.rs", 1, "fn helper()", "private"),
+    ];
+    let to = [symbol("fresh", "src/c.rs", 1, "fn fresh()", "pub")];
+
+    let breaking = diff_symbols("old", &from, "new", &to).breaking_only();
+
+    assert_eq!(breaking.files.len(), 1);
+    assert_eq!(breaking.files[0].path, "src/a.rs");
+    assert_eq!(
+        (breaking.removed, breaking.added, breaking.breaking),
+        (1, 0, 1)
+    );
+    assert_eq!(
+        (breaking.from.as_str(), breaking.to.as_str()),
+        ("old", "new")
+    );
+}
+
+#[test]
+fn test_moved_public_symbol_with_new_signature_is_breaking() {
+    let from = [symbol("parse", "src/a.rs", 1, "fn parse()", "pub")];
+    let to = [symbol(
+        "parse",
+        "src/parser.rs",
+        3,
+        "fn parse(x: u8)",
+        "pub",
+    )];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+
+    assert_eq!(diff.moved, 1);
+    assert_eq!(diff.breaking, 1);
+}
+
+#[test]
+fn test_symbol_taking_the_place_of_another_is_a_rename() {
+    let from = [
+        symbol("parse", "src/a.rs", 1, "fn parse(input: &str)", "pub"),
+        symbol("gone", "src/b.rs", 1, "fn gone()", "pub"),
+    ];
+    let to = [
+        symbol(
+            "parse_input",
+            "src/a.rs",
+            1,
+            "fn parse_input(input: &str)",
+            "pub",
+        ),
+        symbol("fresh", "src/c.rs", 1, "fn fresh()", "pub"),
+    ];
+
+    let diff = diff_symbols("old", &from, "new", &to);
+    let changes: Vec<&SymbolChange> = diff.changes().collect();
+
+    assert_eq!(
+        summary(&changes),
+        [
+            (ChangeKind::Renamed, "parse_input", "src/a.rs"),
+            (ChangeKind::Removed, "gone", "src/b.rs"),
+            (ChangeKind::Added, "fresh", "src/c.rs"),
+        ]
+    );
+    assert_eq!(changes[0].old_name.as_deref(), Some("parse"));
+    assert_eq!(changes[0].new_signature, None);
+    assert!(changes[0].breaking);
+    assert_eq!((diff.renamed, diff.added, diff.removed), (1, 1, 1));
+}
@@ -0,0 +1,154 @@
+//! Tests for directory roll-ups
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Value};
+
+use crate::graph::directories::{rollup, root, DirectoryDependency};
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+use crate::graph::model::{DirectorySummary, SymbolKind, SymbolNode};
+
+fn directory(path: &str, parent: Option<&str>, file_count: usize) -> DirectorySummary {
+    DirectorySummary {
+        path: path.to_string(),
+        parent: parent.map(str::to_string),
+        file_count,
+        line_count: file_count * 10,
+        ..DirectorySummary::default()
+    }
+}
+
+fn symbol(id: &str, file_path: &str) -> SymbolNode {
+    SymbolNode::new(id, id, SymbolKind::Function, file_path, 1, 2)
+}
+
+fn node(id: &str, label: &str, key: &str, value: &str) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties: BTreeMap::from([(key.to_string(), json!(value))]),
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, properties: &[(&str, Value)]) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+    }
+}
+
+/// `/repo` holds `cli` (with `cli/commands`), `core` and `build.rs`
+fn directories() -> Vec<DirectorySummary> {
+    vec![
+        directory("/repo", None, 5),
+        directory("/repo/cli", Some("/repo"), 2),
+        directory("/repo/cli/commands", Some("/repo/cli"), 1),
+        directory("/repo/core", Some("/repo"), 2),
+    ]
+}
+
+fn symbols() -> Vec<SymbolNode> {
+    vec![
+        symbol("main", "/repo/cli/main.rs"),
+        symbol("scan", "/repo/cli/commands/scan.rs"),
+        symbol("store", "/repo/core/store.rs"),
+        symbol("model", "/repo/core/model.rs"),
+        symbol("build", "/repo/build.rs"),
+    ]
+}
+
+/// `cli/commands/scan.rs` imports `core/store.rs` and `scan` calls `store`
+/// twice; `main` calls `scan` within `cli`, `build` calls `store`
+fn graph() -> GraphExport {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for symbol in symbols() {
+        let file = format!("file:{}", symbol.file_path);
+        nodes.push(node(&file, "File", "path", &symbol.file_path));
+        nodes.push(node(&symbol.id, "Symbol", "qualified_name", &symbol.id));
+        edges.push(edge(&symbol.id, &file, "DEFINED_IN", &[]));
+    }
+    edges.extend([
+        edge(
+            "file:/repo/cli/commands/scan.rs",
+            "file:/repo/core/store.rs",
+            "IMPORTS",
+            &[("module", json!("core::store")), ("line", json!(1))],
+        ),
+        edge("scan", "store", "CALLS", &[("line", json!(4))]),
+        edge("scan", "store", "CALLS", &[("line", json!(9))]),
+        edge("main", "scan", "CALLS", &[("line", json!(2))]),
+        edge("build", "store", "CALLS", &[("line", json!(3))]),
+    ]);
+    GraphExport { nodes, edges }
+}
+
+#[test]
+fn test_rollup_totals_symbols_below_each_subdirectory() {
+    let modified = HashSet::from(["scan", "build"]);
+
+    let rollups = rollup("/repo", &directories(), &symbols(), &modified, &graph());
+
+    let totals: Vec<_> = rollups
+        .iter()
+        .map(|r| (r.path.as_str(), r.file_count, r.symbols, r.modified))
+        .collect();
+    assert_eq!(
+        totals,
+        vec![("/repo/cli", 2, 2, 1), ("/repo/core", 2, 2, 0)]
+    );
+    assert_eq!(rollups[0].line_count, 20);
+}
+
+#[test]
+fn test_rollup_counts_dependencies_between_siblings() {
+    let rollups = rollup(
+        "/repo",
+        &directories(),
+        &symbols(),
+        &HashSet::new(),
+        &graph(),
+    );
+
+    assert_eq!(
+        rollups[0].depends_on,
+        vec![DirectoryDependency {
+            path: "/repo/core".to_string(),
+            links: 3,
+        }]
+    );
+    assert!(rollups[1].depends_on.is_empty());
+}
+
+#[test]
+fn test_rollup_of_nested_directory_and_leaf() {
+    let directories = directories();
+    let symbols = symbols();
+    let graph = graph();
+
+    let nested = rollup("/repo/cli", &directories, &symbols, &HashSet::new(), &graph);
+    let paths: Vec<_> = nested.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(paths, vec!["/repo/cli/commands"]);
+    assert_eq!(nested[0].symbols, 1);
+    assert!(nested[0].depends_on.is_empty());
+
+    assert!(rollup(
+        "/repo/core",
+        &directories,
+        &symbols,
+        &HashSet::new(),
+        &graph
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_root_is_the_directory_without_parent() {
+    assert_eq!(root(&directories()).map(|d| d.path.as_str()), Some("/repo"));
+    assert!(root(&[]).is_none());
+}
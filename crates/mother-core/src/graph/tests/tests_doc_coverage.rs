@@ -0,0 +1,68 @@
+//! Tests for documentation coverage
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::doc_coverage::{by_file, by_module, overall_percent};
+use crate::graph::model::{ModuleNode, SymbolKind, SymbolNode};
+
+fn symbol(id: &str, file_path: &str, visibility: Option<&str>, doc: Option<&str>) -> SymbolNode {
+    SymbolNode {
+        visibility: visibility.map(str::to_string),
+        doc_comment: doc.map(str::to_string),
+        ..SymbolNode::new(id, id, SymbolKind::Function, file_path, 1, 2)
+    }
+}
+
+fn symbols() -> Vec<SymbolNode> {
+    vec![
+        symbol(
+            "parse",
+            "/repo/src/lib.rs",
+            Some("pub"),
+            Some("Parse input"),
+        ),
+        symbol("render", "/repo/src/lib.rs", Some("pub"), Some("  ")),
+        symbol("helper", "/repo/src/lib.rs", Some("pub(crate)"), None),
+        symbol("serve", "/repo/src/server.rs", Some("public"), None),
+        symbol("main", "/repo/src/main.rs", None, None),
+    ]
+}
+
+fn summary(rows: &[crate::graph::doc_coverage::DocCoverage]) -> Vec<(&str, usize, usize)> {
+    rows.iter()
+        .map(|r| (r.path.as_str(), r.documented, r.public))
+        .collect()
+}
+
+#[test]
+fn test_by_file_counts_public_symbols_with_docs() {
+    let rows = by_file(&symbols());
+
+    assert_eq!(
+        summary(&rows),
+        vec![("/repo/src/server.rs", 0, 1), ("/repo/src/lib.rs", 1, 2)]
+    );
+    assert_eq!(rows[1].percent, 50.0);
+    assert!((overall_percent(&rows) - 100.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_by_module_groups_direct_members() {
+    let module = |path: &str, ids: &[&str]| ModuleNode {
+        path: path.to_string(),
+        name: path.to_string(),
+        parent: None,
+        language: "rust".to_string(),
+        symbol_ids: ids.iter().map(|id| (*id).to_string()).collect(),
+    };
+    let modules = [module("lib", &["parse", "render", "helper"])];
+
+    let rows = by_module(&symbols(), &modules);
+
+    assert_eq!(summary(&rows), vec![("lib", 1, 2)]);
+}
+
+#[test]
+fn test_overall_percent_without_public_symbols() {
+    assert_eq!(overall_percent(&[]), 100.0);
+}
@@ -0,0 +1,47 @@
+//! Tests for fuzzy symbol name matching
+
+use crate::graph::fuzzy::{fuzzy_matches, similarity, MIN_SIMILARITY};
+use crate::graph::model::{SymbolKind, SymbolNode};
+
+fn symbol(name: &str, line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("crate::graph::{name}"),
+        ..SymbolNode::new(
+            format!("s-{name}"),
+            name,
+            SymbolKind::Function,
+            "src/graph.rs",
+            line,
+            line + 3,
+        )
+    }
+}
+
+#[test]
+fn test_similarity_ignores_case_and_separators() {
+    assert!((similarity("CreateScanRun", "create_scan_run") - 1.0).abs() < f64::EPSILON);
+    assert!(similarity("crateScnRun", "create_scan_run") >= MIN_SIMILARITY);
+    assert!(similarity("scan_run", "create_scan_run") >= MIN_SIMILARITY);
+    assert!(similarity("main", "create_scan_run") < MIN_SIMILARITY);
+    assert!(similarity("", "main") < f64::EPSILON);
+    assert!(similarity("::", "main") < f64::EPSILON);
+}
+
+#[test]
+fn test_fuzzy_matches_rank_closest_names_first() {
+    let symbols = vec![
+        symbol("create_scan_run", 10),
+        symbol("create_scan_runs", 20),
+        symbol("delete_scan_run", 30),
+        symbol("main", 40),
+    ];
+
+    let matches = fuzzy_matches(&symbols, "crateScnRun");
+    let names: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+
+    assert_eq!(names[..2], ["create_scan_run", "create_scan_runs"]);
+    assert!(!names.contains(&"main"));
+    assert!(matches.windows(2).all(|w| w[0].score >= w[1].score));
+    assert_eq!(matches[0].kind, "function");
+    assert_eq!(matches[0].start_line, 10);
+}
@@ -0,0 +1,90 @@
+//! Tests for symbol ID strategies
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::convert::assign_symbol_ids;
+use crate::graph::ids::{
+    ContentHashIds, QualifiedNameIds, SymbolIdScheme, SymbolIdStrategy, UuidIds,
+};
+use crate::graph::model::{symbol_version_id, SymbolKind, SymbolNode};
+
+const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+fn symbol(name: &str, line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("Parser::{name}"),
+        ..SymbolNode::new(
+            String::new(),
+            name,
+            SymbolKind::Method,
+            "/home/dev/repo/src/parser.rs",
+            line,
+            line + 5,
+        )
+    }
+}
+
+#[test]
+fn test_content_hash_ids_match_version_ids() {
+    let parse = symbol("parse", 1);
+    assert_eq!(
+        ContentHashIds.symbol_id(&parse, "src/parser.rs", HASH, 0),
+        symbol_version_id(HASH, &parse.identity_key(), 0)
+    );
+}
+
+#[test]
+fn test_qualified_name_ids_are_readable_and_path_relative() {
+    let parse = symbol("parse", 1);
+    assert_eq!(
+        QualifiedNameIds.symbol_id(&parse, "src/parser.rs", HASH, 0),
+        "src/parser.rs#method#Parser::parse@9f86d081884c"
+    );
+    assert_eq!(
+        QualifiedNameIds.symbol_id(&parse, "src/parser.rs", HASH, 2),
+        "src/parser.rs#method#Parser::parse#2@9f86d081884c"
+    );
+    assert_eq!(
+        QualifiedNameIds.symbol_id(&parse, "src/parser.rs", "abc", 0),
+        "src/parser.rs#method#Parser::parse@abc"
+    );
+}
+
+#[test]
+fn test_uuid_ids_are_unique() {
+    let parse = symbol("parse", 1);
+    let first = UuidIds.symbol_id(&parse, "src/parser.rs", HASH, 0);
+    let second = UuidIds.symbol_id(&parse, "src/parser.rs", HASH, 0);
+    assert_ne!(first, second);
+    assert!(uuid::Uuid::parse_str(&first).is_ok());
+}
+
+#[test]
+fn test_assign_symbol_ids_tells_overloads_apart() {
+    let mut symbols = vec![symbol("parse", 1), symbol("parse", 10), symbol("lex", 20)];
+    assign_symbol_ids(&mut symbols, &QualifiedNameIds, "src/parser.rs", HASH);
+
+    let ids: Vec<&str> = symbols.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "src/parser.rs#method#Parser::parse@9f86d081884c",
+            "src/parser.rs#method#Parser::parse#1@9f86d081884c",
+            "src/parser.rs#method#Parser::lex@9f86d081884c",
+        ]
+    );
+}
+
+#[test]
+fn test_scheme_names_round_trip() {
+    for scheme in [
+        SymbolIdScheme::ContentHash,
+        SymbolIdScheme::QualifiedName,
+        SymbolIdScheme::Uuid,
+    ] {
+        let name = scheme.to_string();
+        assert_eq!(name.parse::<SymbolIdScheme>().unwrap(), scheme);
+        assert_eq!(scheme.strategy().name(), name);
+    }
+    assert!("sequential".parse::<SymbolIdScheme>().is_err());
+}
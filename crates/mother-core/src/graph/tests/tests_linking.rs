@@ -0,0 +1,158 @@
+//! Tests for cross-language symbol linking
+
+#![allow(clippy::expect_used)]
+
+use std::collections::HashMap;
+
+use crate::graph::linking::{link_symbols, LinkConventions, LinkPattern};
+use crate::graph::model::{Edge, EdgeKind, SymbolKind, SymbolNode};
+
+fn symbol(id: &str, qualified_name: &str, file_path: &str, lines: (u32, u32)) -> SymbolNode {
+    SymbolNode {
+        qualified_name: qualified_name.to_string(),
+        ..SymbolNode::new(id, id, SymbolKind::Function, file_path, lines.0, lines.1)
+    }
+}
+
+fn pairs(edges: &[Edge]) -> Vec<(&str, &str)> {
+    assert!(edges.iter().all(|e| e.kind == EdgeKind::LinksTo));
+    edges
+        .iter()
+        .map(|e| (e.source_id.as_str(), e.target_id.as_str()))
+        .collect()
+}
+
+fn no_source(_: &str) -> Option<String> {
+    None
+}
+
+// ============================================================================
+// Qualified names
+// ============================================================================
+
+#[test]
+fn test_identical_qualified_names_link_across_languages() {
+    let symbols = [
+        symbol(
+            "py",
+            "orders.v1.CreateOrder",
+            "/repo/svc/orders_pb2.py",
+            (1, 9),
+        ),
+        symbol(
+            "rs",
+            "orders::v1::CreateOrder",
+            "/repo/api/orders.rs",
+            (1, 9),
+        ),
+        symbol("ts", "orders.v1.Cancel", "/repo/web/orders.ts", (1, 9)),
+    ];
+    let conventions = LinkConventions {
+        qualified_names: true,
+        ..LinkConventions::default()
+    };
+
+    let edges = link_symbols(&symbols, &conventions, no_source);
+
+    assert_eq!(pairs(&edges), vec![("py", "rs")]);
+}
+
+#[test]
+fn test_qualified_names_skip_same_language_and_single_segments() {
+    let symbols = [
+        symbol("a", "orders.Create", "/repo/a.py", (1, 2)),
+        symbol("b", "orders.Create", "/repo/b.py", (1, 2)),
+        symbol("main-py", "main", "/repo/main.py", (1, 2)),
+        symbol("main-go", "main", "/repo/main.go", (1, 2)),
+        symbol("unknown", "orders.Create", "/repo/orders.unknown", (1, 2)),
+    ];
+    let conventions = LinkConventions {
+        qualified_names: true,
+        ..LinkConventions::default()
+    };
+
+    assert!(link_symbols(&symbols, &conventions, no_source).is_empty());
+    assert!(link_symbols(&symbols, &LinkConventions::default(), no_source).is_empty());
+}
+
+// ============================================================================
+// Patterns
+// ============================================================================
+
+#[test]
+fn test_patterns_link_symbols_sharing_an_identifier() {
+    let python = "\
+class Orders:
+    @app.route(\"/api/orders\")
+    def list_orders(self):
+        return []
+";
+    let typescript = "\
+export async function loadOrders() {
+  return fetch(\"/api/orders\");
+}
+
+export async function loadUsers() {
+  return fetch(\"/api/users\");
+}
+";
+    let sources = HashMap::from([
+        ("/repo/svc/app.py", python),
+        ("/repo/web/client.ts", typescript),
+    ]);
+    let symbols = [
+        symbol("Orders", "Orders", "/repo/svc/app.py", (1, 4)),
+        symbol(
+            "list_orders",
+            "Orders.list_orders",
+            "/repo/svc/app.py",
+            (3, 4),
+        ),
+        symbol("loadOrders", "loadOrders", "/repo/web/client.ts", (1, 3)),
+        symbol("loadUsers", "loadUsers", "/repo/web/client.ts", (5, 7)),
+    ];
+    let conventions = LinkConventions {
+        qualified_names: false,
+        patterns: vec![
+            LinkPattern::new("route", r#"route\("([^"]+)"\)|fetch\("([^"]+)"\)"#)
+                .expect("valid pattern"),
+        ],
+    };
+
+    let edges = link_symbols(&symbols, &conventions, |path| {
+        sources.get(path).map(|text| (*text).to_string())
+    });
+
+    // The decorator annotates the method below it, not the class around it
+    assert_eq!(pairs(&edges), vec![("list_orders", "loadOrders")]);
+}
+
+#[test]
+fn test_identifiers_of_different_patterns_do_not_link() {
+    let sources = HashMap::from([
+        ("/repo/a.py", "TOPIC = 'orders'\n"),
+        ("/repo/b.ts", "const QUEUE = 'orders';\n"),
+    ]);
+    let symbols = [
+        symbol("topic", "TOPIC", "/repo/a.py", (1, 1)),
+        symbol("queue", "QUEUE", "/repo/b.ts", (1, 1)),
+    ];
+    let conventions = LinkConventions {
+        qualified_names: false,
+        patterns: vec![
+            LinkPattern::new("topic", r"TOPIC = '(\w+)'").expect("valid pattern"),
+            LinkPattern::new("queue", r"QUEUE = '(\w+)'").expect("valid pattern"),
+        ],
+    };
+
+    let edges = link_symbols(&symbols, &conventions, |path| {
+        sources.get(path).map(|text| (*text).to_string())
+    });
+
+    assert!(edges.is_empty());
+}
+
+#[test]
+fn test_invalid_pattern_is_rejected() {
+    assert!(LinkPattern::new("broken", "route(").is_err());
+}
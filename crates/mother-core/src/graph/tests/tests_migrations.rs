@@ -0,0 +1,59 @@
+//! Tests for schema migrations
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::migrations::{IncompatibleSchema, Migration, MigrationRunner, MIGRATIONS};
+
+const TEST_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "first",
+        neo4j: &[],
+        sqlite: &[],
+    },
+    Migration {
+        version: 2,
+        description: "second",
+        neo4j: &[],
+        sqlite: &[],
+    },
+];
+
+#[test]
+fn test_shipped_migrations_are_ordered_by_version() {
+    assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+    assert!(MIGRATIONS.iter().all(|m| m.version > 0));
+    assert_eq!(
+        MigrationRunner::default().latest(),
+        MIGRATIONS.last().unwrap().version
+    );
+}
+
+#[test]
+fn test_pending_skips_applied_migrations() {
+    let runner = MigrationRunner::new(TEST_MIGRATIONS);
+    let versions = |stored| -> Vec<u32> {
+        runner
+            .pending(stored)
+            .unwrap()
+            .iter()
+            .map(|m| m.version)
+            .collect()
+    };
+    assert_eq!(versions(0), [1, 2]);
+    assert_eq!(versions(1), [2]);
+    assert!(versions(2).is_empty());
+}
+
+#[test]
+fn test_pending_refuses_newer_schemas() {
+    let runner = MigrationRunner::new(TEST_MIGRATIONS);
+    assert_eq!(
+        runner.pending(3).unwrap_err(),
+        IncompatibleSchema {
+            stored: 3,
+            supported: 2
+        }
+    );
+    assert_eq!(MigrationRunner::new(&[]).latest(), 0);
+}
@@ -1,6 +1,25 @@
 //! Tests for graph model types
 
-use crate::graph::model::{EdgeKind, SymbolKind};
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::model::{
+    symbol_identity_key, symbol_version_id, EdgeKind, FileMetrics, ScanFailure, ScanReport,
+    SymbolKind, SymbolNode,
+};
+
+fn make_symbol(id: &str, start_line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: "Parser::parse".to_string(),
+        ..SymbolNode::new(
+            id,
+            "parse",
+            SymbolKind::Method,
+            "src/parser.rs",
+            start_line,
+            start_line + 10,
+        )
+    }
+}
 
 #[test]
 fn test_symbol_kind_display() {
@@ -16,4 +35,105 @@ fn test_edge_kind_display() {
     assert_eq!(format!("{}", EdgeKind::Inherits), "INHERITS");
     assert_eq!(format!("{}", EdgeKind::Implements), "IMPLEMENTS");
     assert_eq!(format!("{}", EdgeKind::DefinedIn), "DEFINED_IN");
+    assert_eq!(format!("{}", EdgeKind::SnapshotOf), "SNAPSHOT_OF");
+    assert_eq!(format!("{}", EdgeKind::SameAs), "SAME_AS");
+    assert_eq!(format!("{}", EdgeKind::EvolvedFrom), "EVOLVED_FROM");
+}
+
+#[test]
+fn test_symbol_kind_round_trips_through_display() {
+    for kind in [
+        SymbolKind::Function,
+        SymbolKind::TypeAlias,
+        SymbolKind::Import,
+    ] {
+        assert_eq!(kind.to_string().parse::<SymbolKind>(), Ok(kind));
+    }
+    assert!("widget".parse::<SymbolKind>().is_err());
+}
+
+#[test]
+fn test_new_symbol_is_qualified_by_its_name_alone() {
+    let symbol = SymbolNode::new("s1", "parse", SymbolKind::Function, "src/lib.rs", 3, 8);
+
+    assert_eq!(symbol.qualified_name, "parse");
+    assert_eq!((symbol.start_line, symbol.end_line), (3, 8));
+    assert!(symbol.visibility.is_none() && symbol.signature.is_none());
+    assert!(symbol.type_signature.is_none() && symbol.embedding.is_none());
+}
+
+#[test]
+fn test_symbol_version_id_is_deterministic() {
+    let key = symbol_identity_key("src/parser.rs", "method", "Parser::parse");
+    let id = symbol_version_id("hash1", &key, 0);
+
+    assert_eq!(id.len(), 32);
+    assert_eq!(id, symbol_version_id("hash1", &key, 0));
+    assert_ne!(id, symbol_version_id("hash1", &key, 1));
+    assert_ne!(id, symbol_version_id("hash2", &key, 0));
+}
+
+#[test]
+fn test_identity_key_format() {
+    assert_eq!(
+        symbol_identity_key("src/parser.rs", "method", "Parser::parse"),
+        "src/parser.rs#method#Parser::parse"
+    );
+}
+
+#[test]
+fn test_identity_key_stable_across_versions() {
+    // Two versions of the same symbol in different file contents share an identity
+    let v1 = make_symbol("hash1:Parser::parse:10", 10);
+    let v2 = make_symbol("hash2:Parser::parse:42", 42);
+    assert_ne!(v1.id, v2.id);
+    assert_eq!(v1.identity_key(), v2.identity_key());
+}
+
+#[test]
+fn test_identity_key_differs_by_kind() {
+    let method = make_symbol("a", 1);
+    let mut function = make_symbol("b", 1);
+    function.kind = SymbolKind::Function;
+    assert_ne!(method.identity_key(), function.identity_key());
+}
+
+#[test]
+fn test_file_metrics_of_content() {
+    let metrics = FileMetrics::of_content("fn a() {}\n\nfn b() {}");
+    assert_eq!(metrics.line_count, 3);
+    assert_eq!(metrics.byte_size, 20);
+    assert_eq!(FileMetrics::of_content("one\n").line_count, 1);
+    assert_eq!(FileMetrics::of_content("").line_count, 0);
+}
+
+#[test]
+fn test_file_metrics_count_symbols_by_kind() {
+    let mut metrics = FileMetrics::default();
+    let mut function = make_symbol("c", 20);
+    function.kind = SymbolKind::Function;
+    metrics.count_symbols(&[make_symbol("a", 1), make_symbol("b", 10), function]);
+
+    assert_eq!(metrics.symbol_counts.get("method"), Some(&2));
+    assert_eq!(metrics.symbol_counts.get("function"), Some(&1));
+    assert_eq!(metrics.symbol_count(), 3);
+}
+
+#[test]
+fn test_scan_report_counts_failures_by_phase_in_order() {
+    let failure = |phase: &str| ScanFailure {
+        phase: phase.to_string(),
+        file: "src/lib.rs".to_string(),
+        symbol: None,
+        request: "textDocument/documentSymbol".to_string(),
+        message: "server crashed".to_string(),
+    };
+    let report = ScanReport {
+        failures: vec![failure("Phase 2"), failure("Phase 1"), failure("Phase 2")],
+        ..ScanReport::default()
+    };
+
+    assert_eq!(report.failures_by_phase(), [("Phase 2", 2), ("Phase 1", 1)]);
+    let json = serde_json::to_string(&report.failures[0]).unwrap();
+    assert!(!json.contains("symbol"));
 }
@@ -0,0 +1,163 @@
+//! Tests for module trees and their summaries
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::graph::model::{ModuleNode, SymbolKind, SymbolNode};
+use crate::graph::modules::{module_tree, summarize};
+
+fn symbol(id: &str, qualified_name: &str, kind: SymbolKind, file_path: &str) -> SymbolNode {
+    SymbolNode {
+        qualified_name: qualified_name.to_string(),
+        ..SymbolNode::new(id, id, kind, file_path, 1, 2)
+    }
+}
+
+fn module(path: &str, parent: Option<&str>, symbol_ids: &[&str]) -> ModuleNode {
+    ModuleNode {
+        path: path.to_string(),
+        name: path.rsplit("::").next().unwrap_or(path).to_string(),
+        parent: parent.map(str::to_string),
+        language: "rust".to_string(),
+        symbol_ids: symbol_ids.iter().map(|id| (*id).to_string()).collect(),
+    }
+}
+
+// ============================================================================
+// Module tree
+// ============================================================================
+
+#[test]
+fn test_module_tree_links_every_prefix() {
+    let symbols = [
+        symbol(
+            "read",
+            "Reader::read",
+            SymbolKind::Method,
+            "/repo/src/graph/read.rs",
+        ),
+        symbol(
+            "store",
+            "Store",
+            SymbolKind::Struct,
+            "/repo/src/graph/mod.rs",
+        ),
+    ];
+
+    let modules = module_tree(Path::new("/repo"), &symbols);
+
+    assert_eq!(
+        modules,
+        vec![
+            module("crate", None, &[]),
+            module("crate::graph", Some("crate"), &["store"]),
+            module("crate::graph::read", Some("crate::graph"), &["read"]),
+        ]
+    );
+}
+
+#[test]
+fn test_module_symbols_open_modules_inside_files() {
+    let file = "/repo/src/lib.rs";
+    let symbols = [
+        symbol("tests", "tests", SymbolKind::Module, file),
+        symbol("inner", "tests::inner", SymbolKind::Module, file),
+        symbol("case", "tests::inner::case", SymbolKind::Function, file),
+        // A type's members stay in the type's module
+        symbol("new", "Config::new", SymbolKind::Method, file),
+    ];
+
+    let modules = module_tree(Path::new("/repo"), &symbols);
+
+    let contents: Vec<(&str, Vec<&str>)> = modules
+        .iter()
+        .map(|m| {
+            let ids = m.symbol_ids.iter().map(String::as_str).collect();
+            (m.path.as_str(), ids)
+        })
+        .collect();
+    assert_eq!(
+        contents,
+        vec![
+            ("crate", vec!["new", "tests"]),
+            ("crate::tests", vec!["inner"]),
+            ("crate::tests::inner", vec!["case"]),
+        ]
+    );
+}
+
+#[test]
+fn test_module_tree_uses_the_language_separator() {
+    let symbols = [symbol(
+        "view",
+        "view",
+        SymbolKind::Function,
+        "/repo/app/views.py",
+    )];
+
+    let modules = module_tree(Path::new("/repo"), &symbols);
+
+    let paths: Vec<&str> = modules.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["app", "app.views"]);
+    assert_eq!(modules[1].language, "python");
+}
+
+#[test]
+fn test_module_tree_skips_files_outside_the_root() {
+    let symbols = [symbol(
+        "f",
+        "f",
+        SymbolKind::Function,
+        "/elsewhere/src/lib.rs",
+    )];
+    assert!(module_tree(Path::new("/repo"), &symbols).is_empty());
+}
+
+// ============================================================================
+// Summaries
+// ============================================================================
+
+#[test]
+fn test_summarize_rolls_counts_up_the_tree() {
+    let modules = [
+        module("crate", None, &["main"]),
+        module("crate::graph", Some("crate"), &["a", "b"]),
+        module("crate::graph::read", Some("crate::graph"), &["c"]),
+        module("crate::graphs", Some("crate"), &["d"]),
+    ];
+    let modified = HashSet::from(["b", "c"]);
+
+    let summaries = summarize(&modules, &modified, None);
+
+    let counts: Vec<(&str, usize, usize, usize, usize)> = summaries
+        .iter()
+        .map(|s| {
+            let path = s.path.as_str();
+            (path, s.submodules, s.symbols, s.total_symbols, s.modified)
+        })
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            ("crate", 2, 1, 5, 2),
+            ("crate::graph", 1, 2, 3, 2),
+            ("crate::graph::read", 0, 1, 1, 1),
+            ("crate::graphs", 0, 1, 1, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_summarize_prefix_keeps_the_module_and_those_below() {
+    let modules = [
+        module("crate", None, &[]),
+        module("crate::graph", Some("crate"), &[]),
+        module("crate::graph::read", Some("crate::graph"), &[]),
+        module("crate::graphs", Some("crate"), &[]),
+    ];
+
+    let summaries = summarize(&modules, &HashSet::new(), Some("crate::graph"));
+
+    let paths: Vec<&str> = summaries.iter().map(|s| s.path.as_str()).collect();
+    assert_eq!(paths, vec!["crate::graph", "crate::graph::read"]);
+}
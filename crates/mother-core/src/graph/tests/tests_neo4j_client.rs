@@ -6,7 +6,7 @@
 use chrono::Utc;
 use serial_test::serial;
 
-use crate::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use crate::graph::model::{Edge, EdgeKind, ScanRun, ScanStatus, SymbolKind, SymbolNode};
 use crate::graph::neo4j::{Neo4jClient, Neo4jConfig};
 
 /// Helper to create a test Neo4j client connected to the test database
@@ -116,6 +116,41 @@ async fn test_ensure_indexes_creates_indexes() {
     assert!(index_names.iter().any(|n| n.contains("symbol_name")));
     assert!(index_names.iter().any(|n| n.contains("symbol_id")));
     assert!(index_names.iter().any(|n| n.contains("symbol_file_path")));
+    assert!(index_names.iter().any(|n| n == "symbol_search"));
+}
+
+#[tokio::test]
+#[ignore = "requires running Neo4j"]
+#[serial]
+async fn test_ensure_indexes_creates_uniqueness_constraints() {
+    let client = create_test_client().await;
+    cleanup_test_data(&client).await;
+
+    use neo4rs::Query;
+    let mut result = client
+        .graph()
+        .execute(Query::new("SHOW CONSTRAINTS YIELD name".to_string()))
+        .await
+        .expect("Failed to query constraints");
+    let mut names = Vec::new();
+    while let Ok(Some(row)) = result.next().await {
+        names.push(row.get::<String>("name").unwrap_or_default());
+    }
+    for name in [
+        "commit_sha_unique",
+        "scan_run_id_unique",
+        "file_path_hash_unique",
+        "symbol_id_unique",
+    ] {
+        assert!(names.iter().any(|n| n == name), "missing {name}");
+    }
+
+    // A second commit node with the same SHA is rejected
+    let create = || Query::new("CREATE (:Commit {sha: 'dup-sha'})".to_string());
+    client.graph().run(create()).await.unwrap();
+    assert!(client.graph().run(create()).await.is_err());
+
+    cleanup_test_data(&client).await;
 }
 
 #[tokio::test]
@@ -132,6 +167,8 @@ async fn test_create_scan_run_new_commit() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     let result = client.create_scan_run(&scan_run).await;
@@ -155,6 +192,8 @@ async fn test_create_scan_run_existing_commit() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     // First scan - should create new commit
@@ -170,6 +209,8 @@ async fn test_create_scan_run_existing_commit() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     let result2 = client.create_scan_run(&scan_run2).await;
@@ -193,6 +234,8 @@ async fn test_create_scan_run_empty_commit_sha() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     let result = client.create_scan_run(&scan_run).await;
@@ -217,6 +260,8 @@ async fn test_create_file_if_new() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -247,6 +292,8 @@ async fn test_create_file_if_existing() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client
@@ -268,6 +315,8 @@ async fn test_create_file_if_existing() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client
@@ -301,6 +350,8 @@ async fn test_create_symbol() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -316,16 +367,18 @@ async fn test_create_symbol() {
 
     // Create symbol
     let symbol = SymbolNode {
-        id: "symbol-1".to_string(),
-        name: "test_function".to_string(),
         qualified_name: "module::test_function".to_string(),
-        kind: SymbolKind::Function,
         visibility: Some("pub".to_string()),
-        file_path: "/test/file.rs".to_string(),
-        start_line: 10,
-        end_line: 20,
         signature: Some("fn test_function()".to_string()),
         doc_comment: Some("Test function".to_string()),
+        ..SymbolNode::new(
+            "symbol-1",
+            "test_function",
+            SymbolKind::Function,
+            "/test/file.rs",
+            10,
+            20,
+        )
     };
 
     let result = client.create_symbol(&symbol, "symbol_hash_123").await;
@@ -349,6 +402,8 @@ async fn test_create_symbol_minimal() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -363,18 +418,14 @@ async fn test_create_symbol_minimal() {
         .unwrap();
 
     // Create symbol with minimal fields
-    let symbol = SymbolNode {
-        id: "symbol-2".to_string(),
-        name: "test_var".to_string(),
-        qualified_name: "test_var".to_string(),
-        kind: SymbolKind::Variable,
-        visibility: None,
-        file_path: "/test/file.rs".to_string(),
-        start_line: 5,
-        end_line: 5,
-        signature: None,
-        doc_comment: None,
-    };
+    let symbol = SymbolNode::new(
+        "symbol-2",
+        "test_var",
+        SymbolKind::Variable,
+        "/test/file.rs",
+        5,
+        5,
+    );
 
     let result = client.create_symbol(&symbol, "symbol_hash_456").await;
     assert!(result.is_ok());
@@ -409,6 +460,8 @@ async fn test_create_symbols_batch_single() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -424,16 +477,17 @@ async fn test_create_symbols_batch_single() {
 
     // Create single symbol via batch
     let symbols = vec![SymbolNode {
-        id: "batch-symbol-1".to_string(),
-        name: "function1".to_string(),
         qualified_name: "module::function1".to_string(),
-        kind: SymbolKind::Function,
         visibility: Some("pub".to_string()),
-        file_path: "/test/file.rs".to_string(),
-        start_line: 10,
-        end_line: 20,
         signature: Some("fn function1()".to_string()),
-        doc_comment: None,
+        ..SymbolNode::new(
+            "batch-symbol-1",
+            "function1",
+            SymbolKind::Function,
+            "/test/file.rs",
+            10,
+            20,
+        )
     }];
 
     let result = client
@@ -459,6 +513,8 @@ async fn test_create_symbols_batch_multiple() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -475,40 +531,40 @@ async fn test_create_symbols_batch_multiple() {
     // Create multiple symbols
     let symbols = vec![
         SymbolNode {
-            id: "batch-symbol-2".to_string(),
-            name: "Class1".to_string(),
-            qualified_name: "Class1".to_string(),
-            kind: SymbolKind::Class,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 1,
-            end_line: 10,
-            signature: None,
             doc_comment: Some("Class documentation".to_string()),
+            ..SymbolNode::new(
+                "batch-symbol-2",
+                "Class1",
+                SymbolKind::Class,
+                "/test/file.rs",
+                1,
+                10,
+            )
         },
         SymbolNode {
-            id: "batch-symbol-3".to_string(),
-            name: "method1".to_string(),
             qualified_name: "Class1::method1".to_string(),
-            kind: SymbolKind::Method,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 5,
-            end_line: 8,
             signature: Some("fn method1(&self)".to_string()),
-            doc_comment: None,
+            ..SymbolNode::new(
+                "batch-symbol-3",
+                "method1",
+                SymbolKind::Method,
+                "/test/file.rs",
+                5,
+                8,
+            )
         },
         SymbolNode {
-            id: "batch-symbol-4".to_string(),
-            name: "CONSTANT".to_string(),
-            qualified_name: "CONSTANT".to_string(),
-            kind: SymbolKind::Constant,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 15,
-            end_line: 15,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "batch-symbol-4",
+                "CONSTANT",
+                SymbolKind::Constant,
+                "/test/file.rs",
+                15,
+                15,
+            )
         },
     ];
 
@@ -535,6 +591,8 @@ async fn test_create_edge_calls() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -545,28 +603,26 @@ async fn test_create_edge_calls() {
 
     let symbols = vec![
         SymbolNode {
-            id: "edge-symbol-1".to_string(),
-            name: "caller".to_string(),
-            qualified_name: "caller".to_string(),
-            kind: SymbolKind::Function,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 1,
-            end_line: 5,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-symbol-1",
+                "caller",
+                SymbolKind::Function,
+                "/test/file.rs",
+                1,
+                5,
+            )
         },
         SymbolNode {
-            id: "edge-symbol-2".to_string(),
-            name: "callee".to_string(),
-            qualified_name: "callee".to_string(),
-            kind: SymbolKind::Function,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 10,
-            end_line: 15,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-symbol-2",
+                "callee",
+                SymbolKind::Function,
+                "/test/file.rs",
+                10,
+                15,
+            )
         },
     ];
 
@@ -605,6 +661,8 @@ async fn test_create_edge_references() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -614,29 +672,24 @@ async fn test_create_edge_references() {
         .unwrap();
 
     let symbols = vec![
+        SymbolNode::new(
+            "edge-symbol-3",
+            "variable",
+            SymbolKind::Variable,
+            "/test/file.rs",
+            1,
+            1,
+        ),
         SymbolNode {
-            id: "edge-symbol-3".to_string(),
-            name: "variable".to_string(),
-            qualified_name: "variable".to_string(),
-            kind: SymbolKind::Variable,
-            visibility: None,
-            file_path: "/test/file.rs".to_string(),
-            start_line: 1,
-            end_line: 1,
-            signature: None,
-            doc_comment: None,
-        },
-        SymbolNode {
-            id: "edge-symbol-4".to_string(),
-            name: "function".to_string(),
-            qualified_name: "function".to_string(),
-            kind: SymbolKind::Function,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 5,
-            end_line: 10,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-symbol-4",
+                "function",
+                SymbolKind::Function,
+                "/test/file.rs",
+                5,
+                10,
+            )
         },
     ];
 
@@ -675,6 +728,8 @@ async fn test_create_edge_no_location() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -685,28 +740,26 @@ async fn test_create_edge_no_location() {
 
     let symbols = vec![
         SymbolNode {
-            id: "edge-symbol-5".to_string(),
-            name: "Parent".to_string(),
-            qualified_name: "Parent".to_string(),
-            kind: SymbolKind::Class,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 1,
-            end_line: 5,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-symbol-5",
+                "Parent",
+                SymbolKind::Class,
+                "/test/file.rs",
+                1,
+                5,
+            )
         },
         SymbolNode {
-            id: "edge-symbol-6".to_string(),
-            name: "Child".to_string(),
-            qualified_name: "Child".to_string(),
-            kind: SymbolKind::Class,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 10,
-            end_line: 15,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-symbol-6",
+                "Child",
+                SymbolKind::Class,
+                "/test/file.rs",
+                10,
+                15,
+            )
         },
     ];
 
@@ -745,6 +798,8 @@ async fn test_create_edge_multiple_kinds() {
         branch: Some("main".to_string()),
         scanned_at: Utc::now(),
         version: Some("v1.0.0".to_string()),
+        previous_run: None,
+        status: ScanStatus::Completed,
     };
 
     client.create_scan_run(&scan_run).await.unwrap();
@@ -760,52 +815,48 @@ async fn test_create_edge_multiple_kinds() {
 
     let symbols = vec![
         SymbolNode {
-            id: "edge-multi-1".to_string(),
-            name: "module1".to_string(),
-            qualified_name: "module1".to_string(),
-            kind: SymbolKind::Module,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 1,
-            end_line: 1,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-multi-1",
+                "module1",
+                SymbolKind::Module,
+                "/test/file.rs",
+                1,
+                1,
+            )
         },
         SymbolNode {
-            id: "edge-multi-2".to_string(),
-            name: "module2".to_string(),
-            qualified_name: "module2".to_string(),
-            kind: SymbolKind::Module,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 5,
-            end_line: 5,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-multi-2",
+                "module2",
+                SymbolKind::Module,
+                "/test/file.rs",
+                5,
+                5,
+            )
         },
         SymbolNode {
-            id: "edge-multi-3".to_string(),
-            name: "Trait1".to_string(),
-            qualified_name: "Trait1".to_string(),
-            kind: SymbolKind::Trait,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 10,
-            end_line: 15,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-multi-3",
+                "Trait1",
+                SymbolKind::Trait,
+                "/test/file.rs",
+                10,
+                15,
+            )
         },
         SymbolNode {
-            id: "edge-multi-4".to_string(),
-            name: "Struct1".to_string(),
-            qualified_name: "Struct1".to_string(),
-            kind: SymbolKind::Struct,
             visibility: Some("pub".to_string()),
-            file_path: "/test/file.rs".to_string(),
-            start_line: 20,
-            end_line: 25,
-            signature: None,
-            doc_comment: None,
+            ..SymbolNode::new(
+                "edge-multi-4",
+                "Struct1",
+                SymbolKind::Struct,
+                "/test/file.rs",
+                20,
+                25,
+            )
         },
     ];
 
@@ -251,3 +251,11 @@ fn test_with_database_long_name() {
 
     assert_eq!(config.database, Some(long_db_name));
 }
+
+#[test]
+fn test_with_read_only() {
+    let config = Neo4jConfig::new("bolt://localhost:7687", "neo4j", "password");
+    assert!(!config.read_only);
+
+    assert!(config.with_read_only(true).read_only);
+}
@@ -0,0 +1,79 @@
+//! Tests for raw query parameters
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::queries::{parse_assignment, ParamValue, QueryParams};
+
+#[test]
+fn test_values_are_parsed_by_type() {
+    let cases = [
+        ("null", ParamValue::Null),
+        ("true", ParamValue::Bool(true)),
+        ("false", ParamValue::Bool(false)),
+        ("42", ParamValue::Integer(42)),
+        ("-7", ParamValue::Integer(-7)),
+        ("1.5", ParamValue::Float(1.5)),
+        ("parse", ParamValue::String("parse".to_string())),
+        ("", ParamValue::String(String::new())),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(ParamValue::from_text(text), expected, "{text}");
+    }
+}
+
+#[test]
+fn test_quotes_force_a_string() {
+    assert_eq!(
+        ParamValue::from_text("'42'"),
+        ParamValue::String("42".to_string())
+    );
+    assert_eq!(
+        ParamValue::from_text("\"true\""),
+        ParamValue::String("true".to_string())
+    );
+    assert_eq!(
+        ParamValue::from_text("'"),
+        ParamValue::String("'".to_string())
+    );
+}
+
+#[test]
+fn test_words_rust_parses_as_floats_stay_strings() {
+    for text in ["inf", "NaN", "infinity"] {
+        assert_eq!(
+            ParamValue::from_text(text),
+            ParamValue::String(text.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_parse_assignment_splits_at_the_first_equals() {
+    assert_eq!(
+        parse_assignment("query=a=b").unwrap(),
+        ("query".to_string(), ParamValue::String("a=b".to_string()))
+    );
+    assert_eq!(
+        parse_assignment("limit=10").unwrap(),
+        ("limit".to_string(), ParamValue::Integer(10))
+    );
+}
+
+#[test]
+fn test_parse_assignment_rejects_invalid_input() {
+    assert!(parse_assignment("name").is_err());
+    assert!(parse_assignment("=value").is_err());
+    assert!(parse_assignment("1st=value").is_err());
+    assert!(parse_assignment("name}) DETACH DELETE (n=value").is_err());
+}
+
+#[test]
+fn test_insert_validates_names_and_replaces_values() {
+    let mut params = QueryParams::new();
+    assert!(params.is_empty());
+    params.insert("name", ParamValue::Integer(1)).unwrap();
+    params.insert("name", ParamValue::Integer(2)).unwrap();
+    assert!(params.insert("bad name", ParamValue::Null).is_err());
+    assert_eq!(params.get("name"), Some(&ParamValue::Integer(2)));
+    assert_eq!(params.iter().count(), 1);
+}
@@ -0,0 +1,100 @@
+//! Tests for shortest paths between symbols
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::json;
+
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+use crate::graph::paths::shortest_paths;
+
+fn symbol(id: &str, qualified_name: &str, line: i64) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: "Symbol".to_string(),
+        properties: BTreeMap::from([
+            ("name".to_string(), json!(id)),
+            ("qualified_name".to_string(), json!(qualified_name)),
+            ("kind".to_string(), json!("function")),
+            ("file_path".to_string(), json!("/repo/src/server.rs")),
+            ("start_line".to_string(), json!(line)),
+        ]),
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, line: i64) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: BTreeMap::from([("line".to_string(), json!(line))]),
+    }
+}
+
+/// `handle` reaches `query` through `load` or `save`, and directly through a
+/// `DEFINED_IN` edge that must not count; `audit` uses `query` but is never
+/// reached
+fn graph() -> GraphExport {
+    GraphExport {
+        nodes: vec![
+            symbol("handle", "server::handle", 1),
+            symbol("load", "server::load", 10),
+            symbol("save", "server::save", 20),
+            symbol("query", "db::query", 30),
+            symbol("audit", "server::audit", 40),
+        ],
+        edges: vec![
+            edge("handle", "load", "CALLS", 2),
+            edge("handle", "save", "CALLS", 3),
+            edge("load", "query", "CALLS", 11),
+            edge("save", "query", "REFERENCES", 21),
+            edge("handle", "query", "DEFINED_IN", 0),
+            edge("audit", "query", "CALLS", 41),
+            edge("query", "query", "CALLS", 31),
+        ],
+    }
+}
+
+#[test]
+fn test_shortest_paths_lists_every_chain_of_minimal_length() {
+    let paths = shortest_paths(&graph(), "handle", "db::query", 10);
+
+    let chains: Vec<Vec<&str>> = paths
+        .iter()
+        .map(|p| p.hops.iter().map(|h| h.qualified_name.as_str()).collect())
+        .collect();
+    assert_eq!(
+        chains,
+        vec![
+            vec!["server::handle", "server::load", "db::query"],
+            vec!["server::handle", "server::save", "db::query"],
+        ]
+    );
+}
+
+#[test]
+fn test_shortest_paths_records_the_edge_into_each_hop() {
+    let paths = shortest_paths(&graph(), "server::handle", "query", 10);
+
+    let hops = &paths[1].hops;
+    assert_eq!(hops[0].via, None);
+    assert_eq!(hops[0].line, None);
+    assert_eq!(hops[1].via.as_deref(), Some("CALLS"));
+    assert_eq!(hops[1].line, Some(3));
+    assert_eq!(hops[2].via.as_deref(), Some("REFERENCES"));
+    assert_eq!(hops[2].line, Some(21));
+    assert_eq!(hops[2].start_line, 30);
+}
+
+#[test]
+fn test_shortest_paths_respects_limit() {
+    assert_eq!(shortest_paths(&graph(), "handle", "query", 1).len(), 1);
+}
+
+#[test]
+fn test_shortest_paths_follows_edge_direction() {
+    assert!(shortest_paths(&graph(), "query", "handle", 10).is_empty());
+    assert!(shortest_paths(&graph(), "handle", "audit", 10).is_empty());
+    assert!(shortest_paths(&graph(), "missing", "query", 10).is_empty());
+}
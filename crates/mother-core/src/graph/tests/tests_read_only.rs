@@ -0,0 +1,69 @@
+//! Tests for detecting mutating Cypher in read-only mode
+
+use crate::graph::read_only::write_clause;
+
+#[test]
+fn test_reads_have_no_write_clause() {
+    for query in [
+        "MATCH (s:Symbol) RETURN s.name LIMIT 10",
+        "MATCH (c:Commit)<-[:FOR_COMMIT]-(r:ScanRun) RETURN c.sha, count(r)",
+        "CALL db.labels() YIELD label RETURN label",
+        "CALL { MATCH (s:Symbol) RETURN s } RETURN count(s)",
+    ] {
+        assert_eq!(write_clause(query), None, "{query}");
+    }
+}
+
+#[test]
+fn test_write_clauses_are_found_in_any_case() {
+    let cases = [
+        ("CREATE (:Symbol {name: 'x'})", "CREATE"),
+        ("match (s) detach delete s", "DETACH"),
+        ("MATCH (s:Symbol) SET s.name = 'x'", "SET"),
+        ("MERGE (c:Commit {sha: $sha})", "MERGE"),
+        ("MATCH (s) REMOVE s.doc_comment", "REMOVE"),
+        ("DROP INDEX symbol_name", "DROP"),
+        ("LOAD CSV FROM 'file:///x.csv' AS row RETURN row", "LOAD"),
+        ("CALL apoc.create.node(['Symbol'], {})", "CALL"),
+        (
+            "MATCH (s) WITH s CALL apoc.refactor.rename.label('A', 'B')",
+            "CALL",
+        ),
+    ];
+    for (query, clause) in cases {
+        assert_eq!(write_clause(query), Some(clause), "{query}");
+    }
+}
+
+#[test]
+fn test_keywords_in_literals_names_and_comments_are_ignored() {
+    for query in [
+        "MATCH (s) WHERE s.name = 'CREATE' RETURN s",
+        r#"MATCH (s) WHERE s.doc_comment CONTAINS "delete \"all\"" RETURN s"#,
+        "MATCH (s) RETURN s.set, s.delete",
+        "MATCH (s:Merge) RETURN s.`create`",
+        "MATCH (s) RETURN s // then SET it\n",
+        "MATCH /* CREATE */ (s) RETURN $set",
+    ] {
+        assert_eq!(write_clause(query), None, "{query}");
+    }
+}
+
+#[test]
+fn test_apoc_procedures_running_their_own_cypher_are_writes() {
+    for query in [
+        "CALL apoc.cypher.doIt('CREATE (:Symbol {name: $name})', {name: 'x'})",
+        "CALL apoc.cypher.runWrite('MATCH (s) DETACH DELETE s', {})",
+        "CALL apoc.periodic.iterate('MATCH (s) RETURN s', 'DELETE s', {batchSize: 100})",
+        "CALL apoc.periodic.commit('MATCH (s) WITH s LIMIT 10 DELETE s', {})",
+        "CALL apoc.do.when(true, 'CREATE (:A)', '', {})",
+        "CALL apoc.trigger.install('neo4j', 't', 'RETURN 1', {})",
+    ] {
+        assert_eq!(write_clause(query), Some("CALL"), "{query}");
+    }
+    // Read-only APOC procedures stay allowed
+    assert_eq!(
+        write_clause("CALL apoc.cypher.run('MATCH (s) RETURN s', {}) YIELD value"),
+        None
+    );
+}
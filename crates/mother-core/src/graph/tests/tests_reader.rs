@@ -0,0 +1,151 @@
+//! Tests for the typed graph reader
+
+#![allow(clippy::unwrap_used)]
+
+use futures::TryStreamExt;
+
+use crate::graph::export::ExportRecord;
+use crate::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use crate::graph::{GraphReader, GraphStore, Page, PathScope, SqliteStore};
+
+const SHA: &str = "abc123def456";
+
+fn symbol(id: &str, name: &str, file_path: &str, line: u32) -> SymbolNode {
+    SymbolNode {
+        qualified_name: format!("crate::{name}"),
+        visibility: Some("pub".to_string()),
+        ..SymbolNode::new(id, name, SymbolKind::Function, file_path, line, line + 2)
+    }
+}
+
+async fn seeded_store() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit(SHA).with_version("v1");
+    store.create_scan_run(&run).await.unwrap();
+    for (path, hash) in [("src/main.rs", "hash-main"), ("src/parse.rs", "hash-parse")] {
+        store
+            .create_file_if_new(path, hash, "rust", SHA)
+            .await
+            .unwrap();
+    }
+    store
+        .create_symbols_batch(&[symbol("s-main", "main", "src/main.rs", 1)], "hash-main")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(
+            &[
+                symbol("s-parse", "parse", "src/parse.rs", 1),
+                symbol("s-parse-all", "parse_all", "src/parse.rs", 10),
+            ],
+            "hash-parse",
+        )
+        .await
+        .unwrap();
+    store
+        .create_edge(&Edge {
+            source_id: "s-main".to_string(),
+            target_id: "s-parse".to_string(),
+            kind: EdgeKind::References,
+            line: Some(2),
+            column: Some(4),
+        })
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_reader_answers_symbol_and_file_queries() {
+    let store = seeded_store().await;
+    let reader = GraphReader::new(&store);
+
+    let names: Vec<String> = reader
+        .symbols("parse")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, ["parse", "parse_all"]);
+    assert_eq!(reader.symbols_at("main", None).await.unwrap().len(), 1);
+    assert_eq!(
+        reader.symbols_at("main", Some("v1")).await.unwrap().len(),
+        1
+    );
+    assert_eq!(reader.symbols_in_file("parse.rs").await.unwrap().len(), 2);
+    let scope = PathScope::new("src/main.rs").unwrap();
+    assert!(reader
+        .symbols_in_scope("parse", &scope)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(reader.files(None).await.unwrap().len(), 2);
+    assert_eq!(reader.stats().await.unwrap().symbols, 3);
+}
+
+#[tokio::test]
+async fn test_reader_answers_reference_queries() {
+    let store = seeded_store().await;
+    let reader = GraphReader::new(&store);
+
+    let to = reader.references_to("parse").await.unwrap();
+    assert_eq!(to.len(), 1);
+    assert_eq!(to[0].source_name, "main");
+    let from = reader.references_from("main").await.unwrap();
+    assert_eq!(from[0].target_name, "parse");
+}
+
+#[tokio::test]
+async fn test_reader_pages_list_queries() {
+    let store = seeded_store().await;
+    let page = Page {
+        limit: Some(1),
+        offset: 1,
+        sort: None,
+    };
+    let reader = GraphReader::new(&store).with_page(page);
+
+    let symbols = reader.symbols("parse").await.unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "parse_all");
+}
+
+#[tokio::test]
+async fn test_reader_rejects_unknown_versions() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let reader = GraphReader::new(&store);
+    assert!(reader.commit(None).await.is_err());
+
+    let store = seeded_store().await;
+    let reader = GraphReader::new(&store);
+    assert_eq!(reader.commit(None).await.unwrap(), SHA);
+    let error = reader.symbols_at("main", Some("v9")).await.unwrap_err();
+    assert!(error.to_string().contains("v9"), "{error}");
+}
+
+#[tokio::test]
+async fn test_reader_streams_symbols_by_file_and_line() {
+    let store = seeded_store().await;
+    let reader = GraphReader::new(&store);
+
+    let symbols: Vec<_> = reader.stream_symbols(SHA).try_collect().await.unwrap();
+    let ids: Vec<&str> = symbols.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["s-main", "s-parse", "s-parse-all"]);
+    assert_eq!(symbols[2].qualified_name, "crate::parse_all");
+    assert_eq!((symbols[2].start_line, symbols[2].end_line), (10, 12));
+}
+
+#[tokio::test]
+async fn test_reader_streams_export_records() {
+    let store = seeded_store().await;
+    let reader = GraphReader::new(&store);
+
+    let records: Vec<_> = reader.stream_export(SHA).try_collect().await.unwrap();
+    let edges = records
+        .iter()
+        .filter(|r| matches!(r, ExportRecord::Edge(_)))
+        .count();
+    assert_eq!(records.len() - edges, 5);
+    assert_eq!(edges, 4);
+}
@@ -0,0 +1,114 @@
+//! Tests for path scopes
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::scope::PathScope;
+
+// ============================================================================
+// Directories and files
+// ============================================================================
+
+#[test]
+fn test_directory_scope_matches_everything_below() {
+    let scope = PathScope::new("/repo/src/graph").unwrap();
+    assert_eq!(scope.prefix(), "/repo/src/graph");
+    assert!(scope.matches("/repo/src/graph"));
+    assert!(scope.matches("/repo/src/graph/mod.rs"));
+    assert!(scope.matches("/repo/src/graph/sqlite/store.rs"));
+}
+
+#[test]
+fn test_directory_scope_stops_at_segment_boundary() {
+    let scope = PathScope::new("/repo/src/graph").unwrap();
+    assert!(!scope.matches("/repo/src/graphs/mod.rs"));
+    assert!(!scope.matches("/repo/src/graph.rs"));
+}
+
+#[test]
+fn test_directory_scope_ignores_trailing_slash_and_dot() {
+    let scope = PathScope::new("./src/").unwrap();
+    assert_eq!(scope.prefix(), "src");
+    assert!(scope.matches("src/main.rs"));
+}
+
+#[test]
+fn test_root_scope_matches_all_absolute_paths() {
+    let scope = PathScope::new("/").unwrap();
+    assert!(scope.matches("/anything/at/all.rs"));
+}
+
+#[test]
+fn test_file_scope_matches_the_file() {
+    let scope = PathScope::new("src/main.rs").unwrap();
+    assert!(scope.matches("src/main.rs"));
+    assert!(!scope.matches("src/main.rs.bak"));
+}
+
+#[test]
+fn test_literal_metacharacters_are_escaped() {
+    let scope = PathScope::new("src/a.b+c").unwrap();
+    assert_eq!(scope.pattern(), r"^src/a\.b\+c(?:/.*)?$");
+    assert!(scope.matches("src/a.b+c/x.rs"));
+    assert!(!scope.matches("src/aXb+c/x.rs"));
+}
+
+// ============================================================================
+// Globs
+// ============================================================================
+
+#[test]
+fn test_glob_prefix_stops_at_first_glob_character() {
+    let scope = PathScope::new("/repo/src/**/handler*.rs").unwrap();
+    assert_eq!(scope.prefix(), "/repo/src/");
+}
+
+#[test]
+fn test_single_star_stays_within_segment() {
+    let scope = PathScope::new("src/*.rs").unwrap();
+    assert!(scope.matches("src/main.rs"));
+    assert!(!scope.matches("src/graph/mod.rs"));
+}
+
+#[test]
+fn test_double_star_crosses_segments() {
+    let scope = PathScope::new("src/**/handler*.rs").unwrap();
+    assert!(scope.matches("src/handler.rs"));
+    assert!(scope.matches("src/http/handler_get.rs"));
+    assert!(scope.matches("src/http/v1/handlers.rs"));
+    assert!(!scope.matches("lib/http/handler.rs"));
+}
+
+#[test]
+fn test_trailing_double_star_matches_everything_below() {
+    let scope = PathScope::new("src/**").unwrap();
+    assert!(scope.matches("src/a/b/c.rs"));
+    assert!(!scope.matches("test/a.rs"));
+}
+
+#[test]
+fn test_question_mark_and_classes() {
+    let scope = PathScope::new("src/v?/[ab]*.rs").unwrap();
+    assert!(scope.matches("src/v1/a.rs"));
+    assert!(scope.matches("src/v2/bcd.rs"));
+    assert!(!scope.matches("src/v1/c.rs"));
+    assert!(!scope.matches("src/v10/a.rs"));
+}
+
+#[test]
+fn test_negated_class() {
+    let scope = PathScope::new("src/[!t]*.rs").unwrap();
+    assert!(scope.matches("src/main.rs"));
+    assert!(!scope.matches("src/tests.rs"));
+}
+
+#[test]
+fn test_unclosed_class_is_literal() {
+    let scope = PathScope::new("src/[oops").unwrap();
+    assert!(scope.matches("src/[oops"));
+}
+
+#[test]
+fn test_pattern_uses_portable_syntax() {
+    let scope = PathScope::new("src/**/*.rs").unwrap();
+    assert_eq!(scope.pattern(), r"^src/(?:.*/)?[^/]*\.rs$");
+}
@@ -0,0 +1,66 @@
+//! Tests for the GraphStore trait
+
+#![allow(clippy::unwrap_used)]
+
+use serial_test::serial;
+
+use crate::graph::model::ScanRun;
+use crate::graph::neo4j::{Neo4jClient, Neo4jConfig, Neo4jError};
+use crate::graph::sqlite::SqliteStore;
+use crate::graph::store::{GraphStore, StoreError};
+
+fn assert_graph_store<T: GraphStore>() {}
+
+// ============================================================================
+// Trait shape
+// ============================================================================
+
+#[test]
+fn test_neo4j_client_is_graph_store() {
+    assert_graph_store::<Neo4jClient>();
+}
+
+#[test]
+fn test_sqlite_store_is_graph_store() {
+    assert_graph_store::<SqliteStore>();
+}
+
+#[test]
+fn test_graph_store_is_object_safe() {
+    fn takes_store(_store: Option<&dyn GraphStore>) {}
+    takes_store(None);
+}
+
+// ============================================================================
+// StoreError
+// ============================================================================
+
+#[test]
+fn test_store_error_wraps_neo4j_error() {
+    let err: StoreError = Neo4jError::Query("bad cypher".to_string()).into();
+    assert!(matches!(err, StoreError::Neo4j(_)));
+    assert_eq!(err.to_string(), "Query error: bad cypher");
+}
+
+#[test]
+fn test_store_error_backend_message() {
+    let err = StoreError::Backend("disk full".to_string());
+    assert_eq!(err.to_string(), "Storage error: disk full");
+}
+
+// ============================================================================
+// Neo4j through the trait
+// ============================================================================
+
+#[tokio::test]
+#[ignore] // Requires Neo4j instance
+#[serial]
+async fn test_scan_run_through_trait_object() {
+    let config = Neo4jConfig::new("bolt://localhost:7687", "neo4j", "mother_dev_password");
+    let client = Neo4jClient::connect(&config).await.unwrap();
+    let store: &dyn GraphStore = &client;
+
+    let scan_run = ScanRun::new("/tmp/graph-store-test");
+    assert!(store.create_scan_run(&scan_run).await.unwrap());
+    assert!(store.stats().await.is_ok());
+}
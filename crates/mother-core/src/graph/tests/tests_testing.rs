@@ -0,0 +1,99 @@
+//! Tests for test-to-code relationships
+
+use std::path::Path;
+
+use crate::graph::export::{ExportEdge, GraphExport};
+use crate::graph::model::{EdgeKind, SymbolKind, SymbolNode};
+use crate::graph::testing::{is_test_symbol, test_edges, untested};
+
+fn symbol(id: &str, file_path: &str, visibility: &str) -> SymbolNode {
+    SymbolNode {
+        visibility: Some(visibility.to_string()),
+        ..SymbolNode::new(id, id, SymbolKind::Function, file_path, 1, 5)
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: Default::default(),
+    }
+}
+
+/// `test_load` calls `load` twice and `helper`; `load` calls `parse`, which
+/// `main` also calls; `serve` is used by nothing
+fn symbols() -> Vec<SymbolNode> {
+    vec![
+        symbol("test_load", "/repo/tests/test_config.py", "public"),
+        symbol("helper", "/repo/tests/test_config.py", "public"),
+        symbol("load", "/repo/app/config.py", "public"),
+        symbol("parse", "/repo/app/config.py", "public"),
+        symbol("_cache", "/repo/app/config.py", "private"),
+        symbol("serve", "/repo/app/server.py", "public"),
+        symbol("main", "/repo/app/server.py", "public"),
+    ]
+}
+
+fn graph() -> GraphExport {
+    GraphExport {
+        nodes: Vec::new(),
+        edges: vec![
+            edge("test_load", "load", "CALLS"),
+            edge("test_load", "load", "REFERENCES"),
+            edge("test_load", "helper", "CALLS"),
+            edge("load", "parse", "CALLS"),
+            edge("main", "parse", "CALLS"),
+            edge("main", "load", "CALLS"),
+        ],
+    }
+}
+
+#[test]
+fn test_is_test_symbol_uses_path_below_root() {
+    let root = Path::new("/repo");
+    let symbols = symbols();
+
+    assert!(is_test_symbol(root, &symbols[0]));
+    assert!(!is_test_symbol(root, &symbols[1]));
+    assert!(!is_test_symbol(root, &symbols[2]));
+    // Above the root, `tests` is not a test directory of the repository
+    let nested = symbol("test_run", "/tests/repo/app/run.py", "public");
+    assert!(!is_test_symbol(Path::new("/tests/repo"), &nested));
+}
+
+#[test]
+fn test_edges_link_tests_to_production_symbols_once() {
+    let edges = test_edges(Path::new("/repo"), &symbols(), &graph());
+
+    let pairs: Vec<_> = edges
+        .iter()
+        .map(|e| (e.source_id.as_str(), e.target_id.as_str(), e.kind))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("test_load", "helper", EdgeKind::Tests),
+            ("test_load", "load", EdgeKind::Tests),
+        ]
+    );
+}
+
+#[test]
+fn test_untested_lists_public_functions_by_usage() {
+    let mut graph = graph();
+    graph.edges.extend([
+        edge("test_load", "helper", "TESTS"),
+        edge("test_load", "load", "TESTS"),
+    ]);
+
+    let untested = untested(Path::new("/repo"), &symbols(), &graph);
+
+    let listed: Vec<_> = untested
+        .iter()
+        .map(|s| (s.id.as_str(), s.references))
+        .collect();
+    // Ties at the same location keep the order of `symbols`
+    assert_eq!(listed, vec![("parse", 2), ("serve", 0), ("main", 0)]);
+}
@@ -0,0 +1,103 @@
+//! Tests for graph metric trends
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::graph::export::{ExportEdge, ExportNode, GraphExport};
+use crate::graph::trends::{sparkline, GraphMetrics};
+
+fn node(id: &str, label: &str, properties: &[(&str, &str)]) -> ExportNode {
+    ExportNode {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties: properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::from(*value)))
+            .collect(),
+    }
+}
+
+fn symbol(id: &str, name: &str, kind: &str, visibility: &str) -> ExportNode {
+    node(
+        id,
+        "Symbol",
+        &[("name", name), ("kind", kind), ("visibility", visibility)],
+    )
+}
+
+fn edge(source: &str, target: &str, kind: &str) -> ExportEdge {
+    ExportEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+/// One file with a used helper, an unused helper, a recursive helper, an
+/// unused public function, `main`, a test and a struct
+fn graph() -> GraphExport {
+    let mut nodes = vec![node("file:1", "File", &[("path", "/repo/src/main.rs")])];
+    nodes.extend([
+        symbol("main", "main", "function", ""),
+        symbol("used", "parse", "function", ""),
+        symbol("unused", "legacy", "method", "private"),
+        symbol("recursive", "walk", "function", ""),
+        symbol("api", "open", "function", "pub"),
+        symbol("test", "test_parse", "function", ""),
+        symbol("config", "Config", "struct", "pub"),
+    ]);
+    let mut edges: Vec<ExportEdge> = nodes[1..]
+        .iter()
+        .map(|n| edge(&n.id, "file:1", "DEFINED_IN"))
+        .collect();
+    edges.extend([
+        edge("main", "used", "REFERENCES"),
+        edge("main", "config", "REFERENCES"),
+        edge("recursive", "recursive", "REFERENCES"),
+        edge("test", "used", "CALLS"),
+    ]);
+    GraphExport { nodes, edges }
+}
+
+#[test]
+fn test_metrics_count_files_symbols_and_edges_by_kind() {
+    let metrics = GraphMetrics::of(&graph());
+
+    assert_eq!(metrics.files, 1);
+    assert_eq!(metrics.symbols["function"], 5);
+    assert_eq!(metrics.symbols["method"], 1);
+    assert_eq!(metrics.symbols["struct"], 1);
+    assert_eq!(metrics.symbol_count(), 7);
+    // DEFINED_IN mirrors the symbol count and is left out
+    assert_eq!(metrics.edges.get("DEFINED_IN"), None);
+    assert_eq!(metrics.edges["REFERENCES"], 3);
+    assert_eq!(metrics.edges["CALLS"], 1);
+    assert_eq!(metrics.edge_count(), 4);
+}
+
+#[test]
+fn test_dead_code_is_private_callables_without_references() {
+    let metrics = GraphMetrics::of(&graph());
+
+    // `legacy` and `walk`, which only references itself
+    assert_eq!(metrics.dead_code, 2);
+}
+
+#[test]
+fn test_metrics_of_empty_graph() {
+    assert_eq!(
+        GraphMetrics::of(&GraphExport::default()),
+        GraphMetrics::default()
+    );
+}
+
+#[test]
+fn test_sparkline_scales_between_min_and_max() {
+    assert_eq!(sparkline(&[10, 15, 20]), "▁▄█");
+    assert_eq!(sparkline(&[3, 3]), "▁▁");
+    assert_eq!(sparkline(&[]), "");
+}
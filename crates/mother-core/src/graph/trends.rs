@@ -0,0 +1,122 @@
+//! Trends: How graph metrics evolve from scan to scan
+//!
+//! Works on [`GraphExport`]s of successive commits. Each export is reduced to
+//! [`GraphMetrics`]: file count, symbol counts by kind, edge counts by kind
+//! dead code and module cycles. Dead code is the private functions and methods no other
+//! symbol references; `main` and tests are entry points and never count.
+//! References from outside the scan are invisible, hence public symbols are
+//! left out. Module cycles are counted as in [`dependency_cycles`].
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use super::api_surface::is_public;
+use super::cycles::{dependency_cycles, CycleLevel};
+use super::export::{ExportNode, GraphExport};
+
+/// Symbol kinds that can be dead code
+const CALLABLE_KINDS: [&str; 2] = ["function", "method"];
+
+/// Edge kinds that make a symbol used
+const USE_EDGES: [&str; 2] = ["REFERENCES", "CALLS"];
+
+/// Bars of a sparkline, lowest first
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Metrics of one scanned commit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GraphMetrics {
+    pub files: usize,
+    /// Symbol counts by kind
+    pub symbols: BTreeMap<String, usize>,
+    /// Edge counts by relationship type, without `DEFINED_IN`
+    pub edges: BTreeMap<String, usize>,
+    /// Private functions and methods without references
+    pub dead_code: usize,
+    /// Dependency cycles between directories
+    pub module_cycles: usize,
+}
+
+impl GraphMetrics {
+    /// Count the metrics of an exported commit
+    #[must_use]
+    pub fn of(graph: &GraphExport) -> Self {
+        let mut metrics = Self::default();
+        for node in &graph.nodes {
+            match node.label.as_str() {
+                "File" => metrics.files += 1,
+                "Symbol" => *metrics.symbols.entry(property(node, "kind")).or_default() += 1,
+                _ => {}
+            }
+        }
+
+        let mut used = HashSet::new();
+        for edge in &graph.edges {
+            if edge.kind == "DEFINED_IN" {
+                continue;
+            }
+            *metrics.edges.entry(edge.kind.clone()).or_default() += 1;
+            if USE_EDGES.contains(&edge.kind.as_str()) && edge.source != edge.target {
+                used.insert(edge.target.as_str());
+            }
+        }
+
+        metrics.dead_code = graph
+            .nodes
+            .iter()
+            .filter(|node| is_dead_code_candidate(node) && !used.contains(node.id.as_str()))
+            .count();
+        metrics.module_cycles = dependency_cycles(graph, CycleLevel::Module).len();
+        metrics
+    }
+
+    /// Number of symbols of all kinds
+    #[must_use]
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.values().sum()
+    }
+
+    /// Number of edges of all kinds
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().sum()
+    }
+}
+
+/// Whether a node is a symbol that is dead code unless something uses it
+fn is_dead_code_candidate(node: &ExportNode) -> bool {
+    let name = property(node, "name");
+    node.label == "Symbol"
+        && CALLABLE_KINDS.contains(&property(node, "kind").as_str())
+        && !is_public(&property(node, "visibility"))
+        && name != "main"
+        && !name.starts_with("test")
+}
+
+/// A string property of a node, empty if missing
+fn property(node: &ExportNode, key: &str) -> String {
+    node.properties
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Draw values as a bar per value, scaled between their minimum and maximum
+///
+/// Equal values draw as the lowest bar.
+#[must_use]
+pub fn sparkline(values: &[usize]) -> String {
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let top = BARS.len() - 1;
+    values
+        .iter()
+        .map(|&v| match max - min {
+            0 => BARS[0],
+            range => BARS[(v - min) * top / range],
+        })
+        .collect()
+}
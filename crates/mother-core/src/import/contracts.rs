@@ -0,0 +1,329 @@
+//! Contracts reader: Endpoints and messages of OpenAPI specs and `.proto` files
+//!
+//! OpenAPI specs, and Swagger 2 specs before them, are YAML or JSON files
+//! with a top-level `openapi` or `swagger` key. Each operation under
+//! `paths` is an endpoint named `METHOD /route`, each schema under
+//! `components.schemas` (`definitions` in Swagger 2) a message. In `.proto`
+//! files each rpc of a service is an endpoint named `package.Service/Method`
+//! and each message, nested ones included, a message.
+//!
+//! [`link_handlers`] then finds the symbols serving each contract:
+//!
+//! - symbols named like the contract's identifier, compared ignoring case,
+//!   `_` and `-` (`create_order` serves the rpc `CreateOrder`). Endpoints
+//!   are served by functions and methods, messages by types;
+//! - functions and methods whose source holds the route of an operation as
+//!   a string literal, with path parameters in any framework's syntax
+//!   (`"/orders/{id}"`, `'/orders/:id'`, `"/orders/<int:id>"`), on a line
+//!   naming the operation's method or none. The literal belongs to the
+//!   function around it, or to the one right below it when it is a
+//!   decorator.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+
+use crate::graph::linking::owner;
+use crate::graph::model::{ContractNode, SymbolKind, SymbolNode};
+
+/// Extensions of the files contracts are read from
+pub const CONTRACT_EXTENSIONS: [&str; 4] = ["proto", "yaml", "yml", "json"];
+
+/// Operation keys of an OpenAPI path item
+const HTTP_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Declarations of a `.proto` file, and the braces and semicolons ending them
+const PROTO_TOKEN: &str =
+    r"\b(package|message|service|enum|oneof|extend|rpc)\s+([A-Za-z_][\w.]*)|[{};]";
+
+/// Comments of a `.proto` file
+const PROTO_COMMENT: &str = r"(?s)//[^\n]*|/\*.*?\*/";
+
+/// Read the contracts a file declares
+///
+/// `.proto` files are read by their extension. Other files are read as
+/// OpenAPI specs and declare nothing unless they are one.
+///
+/// # Errors
+/// Returns an error if a YAML or JSON file does not parse.
+pub fn read_contracts(path: &str, text: &str) -> Result<Vec<ContractNode>, serde_yaml::Error> {
+    if path.to_lowercase().ends_with(".proto") {
+        return Ok(read_proto(path, text));
+    }
+    let spec: Value = serde_yaml::from_str(text)?;
+    Ok(read_openapi(path, text, &spec))
+}
+
+fn read_openapi(path: &str, text: &str, spec: &Value) -> Vec<ContractNode> {
+    if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+        return Vec::new();
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let mut contracts = Vec::new();
+
+    let paths = spec.get("paths").and_then(Value::as_mapping);
+    for (route, item) in paths.into_iter().flat_map(Mapping::iter) {
+        let (Some(route), Some(item)) = (route.as_str(), item.as_mapping()) else {
+            continue;
+        };
+        let route_line = key_line(&lines, route, 0).unwrap_or(0);
+        for (method, operation) in item {
+            let Some(method) = method.as_str().filter(|m| HTTP_METHODS.contains(m)) else {
+                continue;
+            };
+            let identifier = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .unwrap_or(route);
+            let line = key_line(&lines, method, route_line).unwrap_or(route_line);
+            contracts.push(contract(
+                "endpoint",
+                format!("{} {route}", method.to_uppercase()),
+                identifier,
+                path,
+                line,
+            ));
+        }
+    }
+
+    let (section, schemas) = match spec.get("swagger") {
+        Some(_) => ("definitions", spec.get("definitions")),
+        None => (
+            "schemas",
+            spec.get("components").and_then(|c| c.get("schemas")),
+        ),
+    };
+    let section_line = key_line(&lines, section, 0).unwrap_or(0);
+    for name in schemas
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flat_map(Mapping::keys)
+        .filter_map(Value::as_str)
+    {
+        let line = key_line(&lines, name, section_line).unwrap_or(section_line);
+        contracts.push(contract("message", name.to_string(), name, path, line));
+    }
+    contracts
+}
+
+/// Index of the first line from `from` on holding `key` as a mapping key:
+/// starting with it, as in YAML, or with it quoted anywhere, as in JSON
+fn key_line(lines: &[&str], key: &str, from: usize) -> Option<usize> {
+    let quoted = Regex::new(&format!(r#""{}"\s*:"#, regex::escape(key))).ok();
+    lines
+        .iter()
+        .enumerate()
+        .skip(from)
+        .find(|(_, line)| {
+            let starts = line
+                .trim_start()
+                .trim_start_matches(['"', '\''])
+                .strip_prefix(key)
+                .is_some_and(|rest| {
+                    rest.trim_start_matches(['"', '\''])
+                        .trim_start()
+                        .starts_with(':')
+                });
+            starts || quoted.as_ref().is_some_and(|quoted| quoted.is_match(line))
+        })
+        .map(|(index, _)| index)
+}
+
+fn read_proto(path: &str, text: &str) -> Vec<ContractNode> {
+    let (Ok(token), Ok(comment)) = (Regex::new(PROTO_TOKEN), Regex::new(PROTO_COMMENT)) else {
+        return Vec::new();
+    };
+    // Blank out comments, keeping offsets and newlines
+    let text = comment.replace_all(text, |caps: &regex::Captures<'_>| {
+        caps[0].replace(|c: char| c != '\n', " ")
+    });
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+    let mut package = String::new();
+    let mut contracts = Vec::new();
+    // Enclosing declarations, `None` for blocks of anything else
+    let mut scopes: Vec<Option<(String, String)>> = Vec::new();
+    let mut pending: Option<(String, String)> = None;
+    for captures in token.captures_iter(&text) {
+        let (Some(keyword), Some(name)) = (captures.get(1), captures.get(2)) else {
+            match &captures[0] {
+                "{" => scopes.push(pending.take()),
+                "}" => {
+                    scopes.pop();
+                }
+                _ => pending = None,
+            }
+            continue;
+        };
+        let line = line_of(keyword.start());
+        let (keyword, name) = (keyword.as_str(), name.as_str());
+        match keyword {
+            "package" => package = format!("{name}."),
+            "message" => {
+                let outer: Vec<&str> = scopes
+                    .iter()
+                    .flatten()
+                    .filter(|(kind, _)| kind == "message")
+                    .map(|(_, name)| name.as_str())
+                    .collect();
+                let qualified = format!("{package}{}", [outer, vec![name]].concat().join("."));
+                contracts.push(contract("message", qualified, name, path, line));
+            }
+            "rpc" => {
+                let service = scopes
+                    .iter()
+                    .flatten()
+                    .rfind(|(kind, _)| kind == "service")
+                    .map(|(_, service)| service.as_str())
+                    .unwrap_or_default();
+                let qualified = format!("{package}{service}/{name}");
+                contracts.push(contract("endpoint", qualified, name, path, line));
+            }
+            _ => {}
+        }
+        pending = Some((keyword.to_string(), name.to_string()));
+    }
+    contracts
+}
+
+fn contract(kind: &str, name: String, identifier: &str, path: &str, line: usize) -> ContractNode {
+    ContractNode {
+        kind: kind.to_string(),
+        name,
+        identifier: identifier.to_string(),
+        file_path: path.to_string(),
+        line: u32::try_from(line + 1).unwrap_or(u32::MAX),
+        handler_ids: Vec::new(),
+    }
+}
+
+/// Link each contract to the symbols serving it
+///
+/// Sets the `handler_ids` of every contract, sorted. `source` returns the
+/// contents of a file, for the routes; files it returns `None` for are only
+/// matched by name.
+pub fn link_handlers(
+    contracts: &mut [ContractNode],
+    symbols: &[SymbolNode],
+    source: impl Fn(&str) -> Option<String>,
+) {
+    let mut by_identifier: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, contract) in contracts.iter().enumerate() {
+        by_identifier
+            .entry(normalized(&contract.identifier))
+            .or_default()
+            .push(index);
+    }
+    let mut handlers: Vec<BTreeSet<&str>> = vec![BTreeSet::new(); contracts.len()];
+    for symbol in symbols {
+        for &index in by_identifier
+            .get(&normalized(&symbol.name))
+            .into_iter()
+            .flatten()
+        {
+            if serves(&contracts[index].kind, symbol.kind) {
+                handlers[index].insert(&symbol.id);
+            }
+        }
+    }
+
+    let routes: Vec<(usize, String, Regex)> = contracts
+        .iter()
+        .enumerate()
+        .filter(|(_, contract)| contract.kind == "endpoint")
+        .filter_map(|(index, contract)| {
+            let (method, route) = contract.name.split_once(' ')?;
+            Some((index, method.to_lowercase(), route_literal(route)?))
+        })
+        .collect();
+    if !routes.is_empty() {
+        let mut functions: BTreeMap<&str, Vec<&SymbolNode>> = BTreeMap::new();
+        for symbol in symbols.iter().filter(|s| serves("endpoint", s.kind)) {
+            functions.entry(&symbol.file_path).or_default().push(symbol);
+        }
+        for (file_path, file_symbols) in functions {
+            let Some(text) = source(file_path) else {
+                continue;
+            };
+            let line_starts: Vec<usize> = std::iter::once(0)
+                .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+                .collect();
+            for (index, method, regex) in &routes {
+                for found in regex.find_iter(&text) {
+                    let line = line_starts.partition_point(|&start| start <= found.start());
+                    let end = line_starts.get(line).copied().unwrap_or(text.len());
+                    if !serves_method(&text[line_starts[line - 1]..end], method) {
+                        continue;
+                    }
+                    let line = u32::try_from(line).unwrap_or(u32::MAX);
+                    if let Some(symbol) = owner(&file_symbols, line) {
+                        handlers[*index].insert(&symbol.id);
+                    }
+                }
+            }
+        }
+    }
+
+    for (contract, ids) in contracts.iter_mut().zip(handlers) {
+        contract.handler_ids = ids.into_iter().map(str::to_string).collect();
+    }
+}
+
+/// Whether a symbol of `kind` can serve a contract of `contract_kind`
+fn serves(contract_kind: &str, kind: SymbolKind) -> bool {
+    match contract_kind {
+        "endpoint" => matches!(kind, SymbolKind::Function | SymbolKind::Method),
+        _ => matches!(
+            kind,
+            SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface | SymbolKind::TypeAlias
+        ),
+    }
+}
+
+/// Whether a line holding a route can serve `method`: it names that method
+/// (`app.get`, `@GetMapping`) or no method at all (`fetch`)
+fn serves_method(line: &str, method: &str) -> bool {
+    let mut named = line
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|word| {
+            let word = word.to_lowercase();
+            word.strip_suffix("mapping")
+                .map(str::to_string)
+                .unwrap_or(word)
+        })
+        .filter(|word| HTTP_METHODS.contains(&word.as_str()))
+        .peekable();
+    named.peek().is_none() || named.any(|word| word == method)
+}
+
+/// An identifier in lower case, without `_` and `-`
+fn normalized(identifier: &str) -> String {
+    identifier
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// A regex matching `route` as a quoted string literal, path parameters
+/// written as `{id}`, `:id`, `<id>` or `${id}`
+fn route_literal(route: &str) -> Option<Regex> {
+    const PARAMETER: &str = r#"(?:\{[^}/]+\}|:[^/"'`]+|<[^>/]+>|\$\{[^}]+\})"#;
+    let segments: Vec<String> = route
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with('{') && segment.ends_with('}') {
+                PARAMETER.to_string()
+            } else {
+                regex::escape(segment)
+            }
+        })
+        .collect();
+    Regex::new(&format!(r#"["'`]{}["'`]"#, segments.join("/"))).ok()
+}
@@ -0,0 +1,226 @@
+//! Coverage reader: Line and branch coverage from lcov and Cobertura reports
+//!
+//! lcov tracefiles (`SF:`, `DA:` and `BRDA:` records) and Cobertura XML
+//! (`<class filename>` elements with their `<line>` hits and
+//! `condition-coverage`) are read into the hit count and branches of each
+//! instrumented line per file. A line listed more than once, by several
+//! records of the same file or by a Cobertura class and its methods, keeps
+//! its highest counts.
+//!
+//! [`symbol_coverage`] then computes the coverage of each symbol over the
+//! lines of its range. Report paths are matched against the end of symbol
+//! file paths, so paths relative to the repository or a source root match
+//! the absolute paths a scan stores.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read};
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::graph::model::{SymbolCoverage, SymbolNode};
+
+/// Elements of a Cobertura report coverage is read from
+const COBERTURA_ELEMENT: &str = r"<(class|line)\s([^>]*)>";
+
+/// Attributes of a Cobertura element
+const COBERTURA_ATTRIBUTE: &str = r#"([\w-]+)\s*=\s*"([^"]*)""#;
+
+/// Covered and total branches of a Cobertura `condition-coverage`, as in
+/// `50% (1/2)`
+const CONDITION_COVERAGE: &str = r"\((\d+)/(\d+)\)";
+
+/// Coverage of one file of a report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// Path as the report lists it, relative or absolute
+    pub path: String,
+    /// Hit count of each instrumented line
+    pub lines: BTreeMap<u32, u64>,
+    /// Covered and total branches of each line with branches
+    pub branches: BTreeMap<u32, (u32, u32)>,
+}
+
+impl FileCoverage {
+    fn hit(&mut self, line: u32, hits: u64) {
+        let count = self.lines.entry(line).or_default();
+        *count = (*count).max(hits);
+    }
+
+    fn branch(&mut self, line: u32, covered: u32, total: u32) {
+        let (c, t) = self.branches.entry(line).or_default();
+        *c = (*c).max(covered);
+        *t = (*t).max(total);
+    }
+}
+
+/// Coverage read from a report, one entry per file in report order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    /// The coverage of `path`, added at the end if the report has none yet
+    fn file(&mut self, path: &str) -> &mut FileCoverage {
+        let index = match self.files.iter().position(|f| f.path == path) {
+            Some(index) => index,
+            None => {
+                self.files.push(FileCoverage {
+                    path: path.to_string(),
+                    ..FileCoverage::default()
+                });
+                self.files.len() - 1
+            }
+        };
+        &mut self.files[index]
+    }
+}
+
+/// Read an lcov tracefile or a Cobertura XML report
+///
+/// Input starting with `<` is read as Cobertura, anything else as lcov.
+///
+/// # Errors
+/// Returns an error if the input cannot be read, or a line or element the
+/// coverage is read from has an invalid number.
+pub fn read_coverage(mut input: impl Read) -> io::Result<CoverageReport> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    if text.trim_start().starts_with('<') {
+        read_cobertura(&text)
+    } else {
+        read_lcov(&text)
+    }
+}
+
+fn read_lcov(text: &str) -> io::Result<CoverageReport> {
+    let mut report = CoverageReport::default();
+    let mut current: Option<String> = None;
+    // Branches of the current record, merged into its file at the end
+    let mut branches: BTreeMap<u32, (u32, u32)> = BTreeMap::new();
+    for (index, line) in text.lines().chain(["end_of_record"]).enumerate() {
+        let invalid = || invalid_data(format!("line {}: {line}", index + 1));
+        let line = line.trim();
+        if line == "end_of_record" {
+            if let Some(path) = current.take() {
+                let file = report.file(&path);
+                for (number, (covered, total)) in std::mem::take(&mut branches) {
+                    file.branch(number, covered, total);
+                }
+            }
+            continue;
+        }
+        let Some((record, value)) = line.split_once(':') else {
+            continue;
+        };
+        let fields: Vec<&str> = value.split(',').collect();
+        match (record, &current) {
+            ("SF", _) => {
+                report.file(value);
+                current = Some(value.to_string());
+            }
+            ("DA", Some(path)) => {
+                let number = fields[0].parse().map_err(|_| invalid())?;
+                let hits = fields
+                    .get(1)
+                    .and_then(|h| h.parse().ok())
+                    .ok_or_else(invalid)?;
+                report.file(path).hit(number, hits);
+            }
+            ("BRDA", Some(_)) => {
+                let number = fields[0].parse().map_err(|_| invalid())?;
+                // Branches never reached are taken `-` times
+                let taken = fields.get(3).ok_or_else(invalid)?;
+                let (covered, total) = branches.entry(number).or_default();
+                *covered += u32::from(*taken != "-" && *taken != "0");
+                *total += 1;
+            }
+            _ => {}
+        }
+    }
+    Ok(report)
+}
+
+fn read_cobertura(text: &str) -> io::Result<CoverageReport> {
+    let (Ok(element), Ok(attribute), Ok(condition)) = (
+        Regex::new(COBERTURA_ELEMENT),
+        Regex::new(COBERTURA_ATTRIBUTE),
+        Regex::new(CONDITION_COVERAGE),
+    ) else {
+        return Ok(CoverageReport::default());
+    };
+    let mut report = CoverageReport::default();
+    let mut current: Option<String> = None;
+    for captures in element.captures_iter(text) {
+        let attributes: HashMap<&str, &str> = attribute
+            .captures_iter(&captures[2])
+            .filter_map(|a| Some((a.get(1)?.as_str(), a.get(2)?.as_str())))
+            .collect();
+        if &captures[1] == "class" {
+            current = attributes.get("filename").map(|path| (*path).to_string());
+            continue;
+        }
+        let Some(path) = &current else {
+            continue;
+        };
+        let invalid = || invalid_data(format!("invalid line element <line {}>", &captures[2]));
+        let number = attributes.get("number").and_then(|n| n.parse().ok());
+        let hits = attributes.get("hits").and_then(|h| h.parse().ok());
+        let (Some(number), Some(hits)) = (number, hits) else {
+            return Err(invalid());
+        };
+        let file = report.file(path);
+        file.hit(number, hits);
+        if let Some(coverage) = attributes.get("condition-coverage") {
+            let branches = condition.captures(coverage).ok_or_else(invalid)?;
+            let covered = branches[1].parse().map_err(|_| invalid())?;
+            let total = branches[2].parse().map_err(|_| invalid())?;
+            file.branch(number, covered, total);
+        }
+    }
+    Ok(report)
+}
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Coverage of each of `symbols` whose file the report covers
+///
+/// A symbol counts the instrumented lines and the branches within its
+/// range, nested symbols included. A file matches the report entry whose
+/// path its own path ends with, the longest such path if several do.
+#[must_use]
+pub fn symbol_coverage(report: &CoverageReport, symbols: &[SymbolNode]) -> Vec<SymbolCoverage> {
+    let mut by_file: HashMap<&str, Option<&FileCoverage>> = HashMap::new();
+    let mut coverage = Vec::new();
+    for symbol in symbols {
+        let file = *by_file.entry(&symbol.file_path).or_insert_with(|| {
+            let path = Path::new(&symbol.file_path);
+            report
+                .files
+                .iter()
+                .filter(|f| path.ends_with(&f.path))
+                .max_by_key(|f| f.path.len())
+        });
+        let Some(file) = file else {
+            continue;
+        };
+        let range = symbol.start_line..=symbol.end_line;
+        let lines = file.lines.range(range.clone());
+        let branches = file.branches.range(range).map(|(_, b)| *b);
+        let (covered, total) = branches.fold((0, 0), |(c, t), (bc, bt)| (c + bc, t + bt));
+        let (lines_covered, lines_total) = lines.fold((0, 0), |(c, t), (_, hits)| {
+            (c + u32::from(*hits > 0), t + 1)
+        });
+        coverage.push(SymbolCoverage {
+            symbol_id: symbol.id.clone(),
+            lines_covered,
+            lines_total,
+            branches_covered: covered,
+            branches_total: total,
+        });
+    }
+    coverage
+}
@@ -0,0 +1,601 @@
+//! LSIF reader: Convert an LSIF dump into files, symbols and reference edges
+//!
+//! An LSIF dump is a graph of vertices (documents, ranges, result sets and
+//! results) and edges between them, written one JSON element per line or as
+//! a single JSON array. Symbols come from each document's
+//! `textDocument/documentSymbol` result or, in dumps without one, from the
+//! definition ranges of the document, nested by their full ranges.
+//! REFERENCES edges link the innermost symbol around each reference range to
+//! the symbols defined by the same result set.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+use async_lsp::lsp_types::{DocumentSymbol, Position, Range, SymbolKind, Url};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::graph::convert::{assign_version_ids, convert_symbols};
+use crate::graph::model::{Edge, EdgeKind, FileMetrics, SymbolNode};
+use crate::lsp::{convert_document_symbol, convert_symbol_kind, LspSymbol};
+use crate::scanner::Language;
+
+/// A document of an LSIF dump, converted to graph data
+#[derive(Debug, Clone)]
+pub struct LsifDocument {
+    /// Path of the document, taken from its URI
+    pub path: String,
+    pub language: Language,
+    /// SHA-256 of the document content
+    pub content_hash: String,
+    /// Size measures, with the document's symbols counted
+    pub metrics: FileMetrics,
+    /// Symbols, with IDs derived from the content hash like scanned symbols
+    pub symbols: Vec<SymbolNode>,
+}
+
+/// Graph data read from an LSIF dump
+#[derive(Debug, Clone, Default)]
+pub struct LsifImport {
+    /// Root URI of the indexed project, from the `metaData` vertex
+    pub project_root: Option<String>,
+    /// Documents in the order the dump lists them
+    pub documents: Vec<LsifDocument>,
+    /// REFERENCES edges between symbols of the documents
+    pub edges: Vec<Edge>,
+    /// URIs of documents whose content was neither embedded nor on disk
+    pub skipped: Vec<String>,
+}
+
+/// Read an LSIF dump in JSON lines or JSON array form
+///
+/// Document content is taken from the base64 `contents` of the document
+/// vertex when the dump embeds it, and read from the document's path
+/// otherwise. Documents with neither are listed in
+/// [`LsifImport::skipped`].
+///
+/// # Errors
+/// Returns an error if the input cannot be read or an element is not valid
+/// LSIF JSON.
+pub fn read_lsif(mut input: impl BufRead) -> io::Result<LsifImport> {
+    let mut dump = Dump::default();
+    let first = loop {
+        let buf = input.fill_buf()?;
+        match buf.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b) => break Some(*b),
+            None if buf.is_empty() => break None,
+            None => {
+                let len = buf.len();
+                input.consume(len);
+            }
+        }
+    };
+    if first == Some(b'[') {
+        let elements: Vec<Value> = serde_json::from_reader(input).map_err(invalid_data)?;
+        for (index, element) in elements.into_iter().enumerate() {
+            let element = serde_json::from_value(element)
+                .map_err(|e| invalid_data(format!("element {}: {e}", index + 1)))?;
+            dump.add(element);
+        }
+    } else {
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let element = serde_json::from_str(&line)
+                .map_err(|e| invalid_data(format!("line {}: {e}", index + 1)))?;
+            dump.add(element);
+        }
+    }
+    Ok(dump.convert(|path| std::fs::read(path).ok()))
+}
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+// ============================================================================
+// Dump elements
+// ============================================================================
+
+/// A vertex or edge, with the fields its label defines kept as JSON
+#[derive(Deserialize)]
+struct Element {
+    id: Value,
+    #[serde(rename = "type")]
+    element_type: String,
+    label: String,
+    #[serde(flatten)]
+    fields: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentVertex {
+    uri: String,
+    #[serde(default)]
+    language_id: String,
+    /// Base64 content, if the dump embeds it
+    contents: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RangeVertex {
+    start: Position,
+    end: Position,
+    tag: Option<RangeTag>,
+}
+
+/// What a range stands for; only definition tags name a symbol
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RangeTag {
+    #[serde(rename = "type")]
+    tag_type: String,
+    text: Option<String>,
+    kind: Option<SymbolKind>,
+    full_range: Option<Range>,
+    detail: Option<String>,
+}
+
+/// An entry of a `documentSymbolResult`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SymbolEntry {
+    /// A definition range vertex, by ID
+    Range(RangeSymbol),
+    /// A symbol spelled out as in a `textDocument/documentSymbol` response
+    Literal(DocumentSymbol),
+}
+
+#[derive(Deserialize)]
+struct RangeSymbol {
+    id: Value,
+    #[serde(default)]
+    children: Vec<RangeSymbol>,
+}
+
+/// The ranges an `item` edge adds to a result
+struct Item {
+    ranges: Vec<String>,
+    /// Document the ranges are in
+    document: Option<String>,
+    /// `definitions` or `references` for items of a reference result
+    property: Option<String>,
+}
+
+/// Vertices and edges of a dump, indexed by ID
+#[derive(Default)]
+struct Dump {
+    project_root: Option<String>,
+    documents: Vec<(String, DocumentVertex)>,
+    ranges: HashMap<String, RangeVertex>,
+    /// Document of each range, from `contains` edges
+    range_documents: HashMap<String, String>,
+    /// Ranges of each document, from `contains` edges
+    document_ranges: HashMap<String, Vec<String>>,
+    /// `documentSymbolResult` of each document
+    document_symbols: HashMap<String, String>,
+    symbol_results: HashMap<String, Vec<SymbolEntry>>,
+    /// Result set each range or result set continues in
+    next: HashMap<String, String>,
+    definition_results: HashMap<String, String>,
+    /// Ranges or result sets with their `referenceResult`
+    reference_results: Vec<(String, String)>,
+    items: HashMap<String, Vec<Item>>,
+}
+
+/// JSON ID as a string; LSIF allows both numbers and strings
+fn id_of(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn field_id(fields: &Map<String, Value>, name: &str) -> Option<String> {
+    fields.get(name).map(id_of)
+}
+
+fn field_ids(fields: &Map<String, Value>) -> Vec<String> {
+    match (fields.get("inVs"), fields.get("inV")) {
+        (Some(Value::Array(ids)), _) => ids.iter().map(id_of).collect(),
+        (_, Some(id)) => vec![id_of(id)],
+        _ => Vec::new(),
+    }
+}
+
+impl Dump {
+    fn add(&mut self, element: Element) {
+        if element.element_type == "vertex" {
+            let fields = Value::Object(element.fields);
+            self.add_vertex(id_of(&element.id), &element.label, fields);
+        } else {
+            self.add_edge(&element.label, &element.fields);
+        }
+    }
+
+    /// Keep the vertices the conversion uses; malformed ones are ignored
+    fn add_vertex(&mut self, id: String, label: &str, fields: Value) {
+        match label {
+            "metaData" => {
+                self.project_root = fields
+                    .get("projectRoot")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            "document" => {
+                if let Ok(document) = serde_json::from_value(fields) {
+                    self.documents.push((id, document));
+                }
+            }
+            "range" => {
+                if let Ok(range) = serde_json::from_value(fields) {
+                    self.ranges.insert(id, range);
+                }
+            }
+            "documentSymbolResult" => {
+                let result = fields.get("result").cloned().unwrap_or_default();
+                if let Ok(entries) = serde_json::from_value(result) {
+                    self.symbol_results.insert(id, entries);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_edge(&mut self, label: &str, fields: &Map<String, Value>) {
+        let Some(out_v) = field_id(fields, "outV") else {
+            return;
+        };
+        let in_vs = field_ids(fields);
+        match (label, in_vs.first().cloned()) {
+            ("contains", _) => {
+                for range in &in_vs {
+                    self.range_documents.insert(range.clone(), out_v.clone());
+                }
+                self.document_ranges.entry(out_v).or_default().extend(in_vs);
+            }
+            ("item", _) => {
+                let item = Item {
+                    ranges: in_vs,
+                    document: field_id(fields, "document").or_else(|| field_id(fields, "shard")),
+                    property: fields
+                        .get("property")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                };
+                self.items.entry(out_v).or_default().push(item);
+            }
+            ("next", Some(in_v)) => {
+                self.next.insert(out_v, in_v);
+            }
+            ("textDocument/documentSymbol", Some(in_v)) => {
+                self.document_symbols.insert(out_v, in_v);
+            }
+            ("textDocument/definition", Some(in_v)) => {
+                self.definition_results.insert(out_v, in_v);
+            }
+            ("textDocument/references", Some(in_v)) => {
+                self.reference_results.push((out_v, in_v));
+            }
+            _ => {}
+        }
+    }
+
+    /// Document a range is in, from `contains` edges or the item adding it
+    fn document_of(&self, range: &str, item: &Item) -> Option<String> {
+        self.range_documents
+            .get(range)
+            .cloned()
+            .or_else(|| item.document.clone())
+    }
+
+    /// Definition result of a range or result set, following `next` edges
+    fn definition_result(&self, id: &str) -> Option<&String> {
+        let mut current = id;
+        for _ in 0..=self.next.len() {
+            if let Some(result) = self.definition_results.get(current) {
+                return Some(result);
+            }
+            current = self.next.get(current)?;
+        }
+        None
+    }
+
+    /// Ranges a result's items add, with their documents, optionally only
+    /// those of one item property
+    fn item_ranges(&self, result: &str, property: Option<&str>) -> Vec<(String, &str)> {
+        let Some(items) = self.items.get(result) else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter(|item| property.is_none() || item.property.as_deref() == property)
+            .flat_map(|item| {
+                item.ranges
+                    .iter()
+                    .filter_map(move |range| Some((self.document_of(range, item)?, range.as_str())))
+            })
+            .collect()
+    }
+
+    // ------------------------------------------------------------------------
+    // Conversion
+    // ------------------------------------------------------------------------
+
+    /// Convert the dump to graph data, reading document content that is not
+    /// embedded with `read_file`
+    fn convert(self, read_file: impl Fn(&Path) -> Option<Vec<u8>>) -> LsifImport {
+        let mut import = LsifImport {
+            project_root: self.project_root.clone(),
+            ..LsifImport::default()
+        };
+        // Symbol ID at the start of each symbol's definition range, per document
+        let mut anchors: HashMap<(String, u32, u32), String> = HashMap::new();
+        let mut document_symbols: HashMap<String, Vec<SymbolNode>> = HashMap::new();
+
+        for (id, vertex) in &self.documents {
+            let path = uri_to_path(&vertex.uri);
+            let content = match &vertex.contents {
+                Some(encoded) => base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .ok(),
+                None => read_file(&path),
+            };
+            let Some(content) = content else {
+                import.skipped.push(vertex.uri.clone());
+                continue;
+            };
+            let content_hash = format!("{:x}", Sha256::digest(&content));
+
+            let (lsp_symbols, positions) = self.symbols_of(id);
+            let mut symbols = convert_symbols(&lsp_symbols, &path);
+            assign_version_ids(&mut symbols, &content_hash);
+            for (symbol, position) in symbols.iter().zip(positions) {
+                let key = (id.clone(), position.line, position.character);
+                anchors.entry(key).or_insert_with(|| symbol.id.clone());
+            }
+
+            let mut metrics = FileMetrics::of_content(&String::from_utf8_lossy(&content));
+            metrics.count_symbols(&symbols);
+            document_symbols.insert(id.clone(), symbols.clone());
+            import.documents.push(LsifDocument {
+                language: document_language(&vertex.language_id, &path),
+                path: path.display().to_string(),
+                content_hash,
+                metrics,
+                symbols,
+            });
+        }
+
+        import.edges = self.reference_edges(&anchors, &document_symbols);
+        import
+    }
+
+    /// Symbols of a document in pre-order, with the start of each one's
+    /// definition range
+    fn symbols_of(&self, document: &str) -> (Vec<LspSymbol>, Vec<Position>) {
+        let mut positions = Vec::new();
+        let entries = self
+            .document_symbols
+            .get(document)
+            .and_then(|result| self.symbol_results.get(result));
+        let symbols = match entries {
+            Some(entries) => entries
+                .iter()
+                .flat_map(|entry| match entry {
+                    SymbolEntry::Range(symbol) => self.range_symbol(symbol, &mut positions),
+                    SymbolEntry::Literal(symbol) => {
+                        literal_positions(symbol, &mut positions);
+                        vec![convert_document_symbol(symbol)]
+                    }
+                })
+                .collect(),
+            None => self.definition_symbols(document, &mut positions),
+        };
+        (symbols, positions)
+    }
+
+    /// Convert a range-based document symbol; a range without a definition
+    /// tag is left out and its children take its place
+    fn range_symbol(&self, entry: &RangeSymbol, positions: &mut Vec<Position>) -> Vec<LspSymbol> {
+        let definition = self
+            .ranges
+            .get(&id_of(&entry.id))
+            .and_then(|range| Some((range, definition_symbol(range)?)));
+        let Some((range, mut symbol)) = definition else {
+            return entry
+                .children
+                .iter()
+                .flat_map(|child| self.range_symbol(child, positions))
+                .collect();
+        };
+        positions.push(range.start);
+        symbol.children = entry
+            .children
+            .iter()
+            .flat_map(|child| self.range_symbol(child, positions))
+            .collect();
+        vec![symbol]
+    }
+
+    /// Symbols of a document without a `documentSymbolResult`, from its
+    /// tagged definition ranges
+    ///
+    /// A definition whose full range lies within another's becomes its child.
+    fn definition_symbols(&self, document: &str, positions: &mut Vec<Position>) -> Vec<LspSymbol> {
+        let mut definitions: Vec<(&RangeVertex, LspSymbol)> = self
+            .document_ranges
+            .get(document)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.ranges.get(id))
+            .filter_map(|range| Some((range, definition_symbol(range)?)))
+            .collect();
+        definitions.sort_by_key(|(range, symbol)| {
+            (
+                symbol.start_line,
+                symbol.start_col,
+                std::cmp::Reverse((symbol.end_line, symbol.end_col)),
+                range.start.line,
+                range.start.character,
+            )
+        });
+
+        // Parent of each definition: the nearest earlier one still open
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); definitions.len()];
+        let mut roots = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+        for (index, (_, symbol)) in definitions.iter().enumerate() {
+            while let Some(&last) = open.last() {
+                if contains(&definitions[last].1, symbol) {
+                    break;
+                }
+                open.pop();
+            }
+            match open.last() {
+                Some(&parent) => children[parent].push(index),
+                None => roots.push(index),
+            }
+            open.push(index);
+        }
+
+        // Sorted by start, the definitions are already in pre-order
+        positions.extend(definitions.iter().map(|(range, _)| range.start));
+        let mut symbols: Vec<Option<LspSymbol>> =
+            definitions.into_iter().map(|(_, s)| Some(s)).collect();
+        roots
+            .into_iter()
+            .filter_map(|root| nest(root, &mut symbols, &children))
+            .collect()
+    }
+
+    /// REFERENCES edges from the innermost symbol around each reference to
+    /// the symbols defined by its result set
+    fn reference_edges(
+        &self,
+        anchors: &HashMap<(String, u32, u32), String>,
+        document_symbols: &HashMap<String, Vec<SymbolNode>>,
+    ) -> Vec<Edge> {
+        let anchor = |(document, range): &(String, &str)| {
+            let start = self.ranges.get(*range)?.start;
+            anchors.get(&(document.clone(), start.line, start.character))
+        };
+        let mut edges = Vec::new();
+        for (source, result) in &self.reference_results {
+            let mut definitions = self.item_ranges(result, Some("definitions"));
+            if let Some(definition) = self.definition_result(source) {
+                definitions.extend(self.item_ranges(definition, None));
+            }
+            let mut targets: Vec<&String> = definitions.iter().filter_map(anchor).collect();
+            targets.sort();
+            targets.dedup();
+            if targets.is_empty() {
+                continue;
+            }
+
+            for (document, range) in self.item_ranges(result, Some("references")) {
+                let Some(start) = self.ranges.get(range).map(|r| r.start) else {
+                    continue;
+                };
+                let symbols = document_symbols.get(&document).map(Vec::as_slice);
+                let Some(from) = innermost_symbol(symbols.unwrap_or_default(), start.line + 1)
+                else {
+                    continue;
+                };
+                for target in targets.iter().filter(|t| ***t != from.id) {
+                    edges.push(Edge {
+                        source_id: from.id.clone(),
+                        target_id: (*target).clone(),
+                        kind: EdgeKind::References,
+                        line: Some(start.line),
+                        column: Some(start.character),
+                    });
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// The symbol a definition-tagged range names, spanning its full range
+fn definition_symbol(range: &RangeVertex) -> Option<LspSymbol> {
+    let tag = range
+        .tag
+        .as_ref()
+        .filter(|tag| tag.tag_type == "definition")?;
+    let full = tag.full_range.unwrap_or(Range::new(range.start, range.end));
+    Some(LspSymbol {
+        name: tag.text.clone()?,
+        kind: convert_symbol_kind(tag.kind.unwrap_or(SymbolKind::VARIABLE)),
+        detail: tag.detail.clone(),
+        container_name: None,
+        file: PathBuf::new(),
+        start_line: full.start.line,
+        end_line: full.end.line,
+        start_col: full.start.character,
+        end_col: full.end.character,
+        children: Vec::new(),
+    })
+}
+
+/// Push the selection start of a literal document symbol and its
+/// descendants in pre-order
+fn literal_positions(symbol: &DocumentSymbol, positions: &mut Vec<Position>) {
+    positions.push(symbol.selection_range.start);
+    for child in symbol.children.iter().flatten() {
+        literal_positions(child, positions);
+    }
+}
+
+/// Whether the span of `outer` contains the span of `inner`
+fn contains(outer: &LspSymbol, inner: &LspSymbol) -> bool {
+    (outer.start_line, outer.start_col) <= (inner.start_line, inner.start_col)
+        && (inner.end_line, inner.end_col) <= (outer.end_line, outer.end_col)
+}
+
+/// Take the symbol at `index` with its nested children
+fn nest(
+    index: usize,
+    symbols: &mut [Option<LspSymbol>],
+    children: &[Vec<usize>],
+) -> Option<LspSymbol> {
+    let mut symbol = symbols[index].take()?;
+    symbol.children = children[index]
+        .iter()
+        .filter_map(|&child| nest(child, symbols, children))
+        .collect();
+    Some(symbol)
+}
+
+/// The symbol with the smallest line span containing a 1-indexed line
+fn innermost_symbol(symbols: &[SymbolNode], line: u32) -> Option<&SymbolNode> {
+    symbols
+        .iter()
+        .filter(|s| s.start_line <= line && line <= s.end_line)
+        .min_by_key(|s| s.end_line - s.start_line)
+}
+
+/// File path of a document URI; URIs that are not `file:` URLs are kept
+/// as they are
+fn uri_to_path(uri: &str) -> PathBuf {
+    Url::parse(uri)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+        .unwrap_or_else(|| PathBuf::from(uri))
+}
+
+/// Language of a document from its LSIF language ID, falling back to the
+/// file extension
+fn document_language(language_id: &str, path: &Path) -> Language {
+    language_id
+        .parse()
+        .ok()
+        .or_else(|| Language::from_path(path))
+        .unwrap_or_else(|| Language::Other(language_id.to_string()))
+}
@@ -0,0 +1,186 @@
+//! Manifests reader: Packages a repository publishes and depends on
+//!
+//! Package names are read from `Cargo.toml` (`[package]` and the
+//! `dependencies` tables, target-specific and workspace ones included),
+//! `package.json` (`name` and the `*dependencies` objects) and `go.mod`
+//! (`module` and `require`). Names are prefixed with their ecosystem
+//! (`cargo:serde`, `npm:react`, `go:github.com/pkg/errors`) so packages of
+//! different registries sharing a name never match.
+//!
+//! [`repository_dependencies`] matches the dependencies of each ingested
+//! repository against the packages of the others.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde_json::Value as Json;
+use toml::Value as Toml;
+
+use crate::graph::model::{RepositoryDependency, RepositoryNode};
+
+/// File names packages are read from
+pub const MANIFEST_NAMES: [&str; 3] = ["Cargo.toml", "package.json", "go.mod"];
+
+/// Package names declared by one manifest file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// Packages the manifest defines
+    pub packages: BTreeSet<String>,
+    /// Packages the manifest depends on
+    pub dependencies: BTreeSet<String>,
+}
+
+/// Read a manifest by its file name
+///
+/// Files with other names, and files that do not parse, declare nothing.
+#[must_use]
+pub fn read_manifest(path: &Path, text: &str) -> Manifest {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some("Cargo.toml") => text.parse().map(|t| read_cargo(&t)).unwrap_or_default(),
+        Some("package.json") => serde_json::from_str(text)
+            .map(|j| read_npm(&j))
+            .unwrap_or_default(),
+        Some("go.mod") => read_go(text),
+        _ => Manifest::default(),
+    }
+}
+
+/// The repository a scan of `path` ingested, from the manifests in it
+///
+/// Packages the repository defines itself are not dependencies.
+#[must_use]
+pub fn repository(path: &str, manifests: &[Manifest]) -> RepositoryNode {
+    let packages: BTreeSet<String> = manifests
+        .iter()
+        .flat_map(|m| m.packages.iter().cloned())
+        .collect();
+    let dependencies = manifests
+        .iter()
+        .flat_map(|m| m.dependencies.iter())
+        .filter(|name| !packages.contains(*name))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    RepositoryNode {
+        path: path.to_string(),
+        packages: packages.into_iter().collect(),
+        dependencies: dependencies.into_iter().collect(),
+    }
+}
+
+/// DEPENDS_ON links between repositories whose dependencies are packages
+/// of another, ordered by the paths of both
+#[must_use]
+pub fn repository_dependencies(repositories: &[RepositoryNode]) -> Vec<RepositoryDependency> {
+    let mut publishers: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for repository in repositories {
+        for package in &repository.packages {
+            publishers
+                .entry(package)
+                .or_default()
+                .push(&repository.path);
+        }
+    }
+
+    let mut links: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for repository in repositories {
+        for package in &repository.dependencies {
+            for publisher in publishers.get(package.as_str()).into_iter().flatten() {
+                if *publisher != repository.path {
+                    links
+                        .entry((&repository.path, publisher))
+                        .or_default()
+                        .push(package.clone());
+                }
+            }
+        }
+    }
+    links
+        .into_iter()
+        .map(|((from, to), packages)| RepositoryDependency {
+            from: from.to_string(),
+            to: to.to_string(),
+            packages,
+        })
+        .collect()
+}
+
+fn read_cargo(manifest: &Toml) -> Manifest {
+    let mut result = Manifest::default();
+    if let Some(name) = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(Toml::as_str)
+    {
+        result.packages.insert(format!("cargo:{name}"));
+    }
+
+    let targets = manifest
+        .get("target")
+        .and_then(Toml::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values());
+    let tables = std::iter::once(manifest)
+        .chain(targets)
+        .chain(manifest.get("workspace"));
+    for table in tables {
+        for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            let dependencies = table.get(section).and_then(Toml::as_table);
+            for (key, spec) in dependencies.into_iter().flatten() {
+                // Renamed dependencies name the real package
+                let name = spec.get("package").and_then(Toml::as_str).unwrap_or(key);
+                result.dependencies.insert(format!("cargo:{name}"));
+            }
+        }
+    }
+    result
+}
+
+fn read_npm(manifest: &Json) -> Manifest {
+    let mut result = Manifest::default();
+    if let Some(name) = manifest.get("name").and_then(Json::as_str) {
+        result.packages.insert(format!("npm:{name}"));
+    }
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ] {
+        let dependencies = manifest.get(section).and_then(Json::as_object);
+        for name in dependencies.into_iter().flat_map(|d| d.keys()) {
+            result.dependencies.insert(format!("npm:{name}"));
+        }
+    }
+    result
+}
+
+fn read_go(text: &str) -> Manifest {
+    let mut result = Manifest::default();
+    let mut in_require = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let mut words = line.split_whitespace();
+        match (in_require, words.next()) {
+            (false, Some("module")) => {
+                if let Some(module) = words.next() {
+                    result
+                        .packages
+                        .insert(format!("go:{}", module.trim_matches('"')));
+                }
+            }
+            (false, Some("require")) => match words.next() {
+                Some("(") => in_require = true,
+                Some(module) => {
+                    result.dependencies.insert(format!("go:{module}"));
+                }
+                None => {}
+            },
+            (true, Some(")")) => in_require = false,
+            (true, Some(module)) => {
+                result.dependencies.insert(format!("go:{module}"));
+            }
+            _ => {}
+        }
+    }
+    result
+}
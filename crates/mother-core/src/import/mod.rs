@@ -0,0 +1,20 @@
+//! Import module: Build graph data from code intelligence dumps, API
+//! contracts, coverage reports and package manifests
+//!
+//! Readers here turn indexes other tools produced into the same files,
+//! symbols and edges a scan stores, without running language servers. The
+//! contracts reader turns OpenAPI specs and `.proto` files into the
+//! endpoints and messages the scanned code serves, the coverage reader
+//! lcov and Cobertura reports into the coverage of scanned symbols, and the
+//! manifests reader package manifests into the dependencies between
+//! scanned repositories.
+
+pub mod contracts;
+pub mod coverage;
+pub mod lsif;
+pub mod manifests;
+
+pub use lsif::{read_lsif, LsifDocument, LsifImport};
+
+#[cfg(test)]
+mod tests;
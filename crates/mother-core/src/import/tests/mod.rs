@@ -0,0 +1,6 @@
+//! Tests for import module
+
+mod tests_contracts;
+mod tests_coverage;
+mod tests_lsif;
+mod tests_manifests;
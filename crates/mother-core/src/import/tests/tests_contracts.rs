@@ -0,0 +1,203 @@
+//! Tests for the contracts reader
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use crate::graph::model::{ContractNode, SymbolKind, SymbolNode};
+use crate::import::contracts::{link_handlers, read_contracts};
+
+const OPENAPI: &str = "\
+openapi: 3.0.0
+info:
+  title: Orders
+paths:
+  /orders:
+    get:
+      operationId: listOrders
+    post:
+      summary: Create an order
+  \"/orders/{id}\":
+    parameters: []
+    get:
+      operationId: getOrder
+components:
+  schemas:
+    Order:
+      type: object
+";
+
+const PROTO: &str = "\
+syntax = \"proto3\";
+package orders.v1;
+
+// message Ignored {}
+service Orders {
+  rpc CreateOrder (CreateOrderRequest) returns (Order);
+  rpc Watch (WatchRequest) returns (stream Order) {
+    option deprecated = true;
+  }
+}
+
+message Order {
+  message Line {
+    string sku = 1;
+  }
+  repeated Line lines = 1;
+}
+";
+
+fn symbol(id: &str, kind: SymbolKind, file_path: &str, lines: (u32, u32)) -> SymbolNode {
+    SymbolNode::new(id, id, kind, file_path, lines.0, lines.1)
+}
+
+fn summary(contracts: &[ContractNode]) -> Vec<(&str, &str, &str, u32)> {
+    contracts
+        .iter()
+        .map(|c| {
+            (
+                c.kind.as_str(),
+                c.name.as_str(),
+                c.identifier.as_str(),
+                c.line,
+            )
+        })
+        .collect()
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+#[test]
+fn test_read_openapi_operations_and_schemas() {
+    let contracts = read_contracts("/repo/api.yaml", OPENAPI).unwrap();
+
+    assert_eq!(
+        summary(&contracts),
+        vec![
+            ("endpoint", "GET /orders", "listOrders", 6),
+            ("endpoint", "POST /orders", "/orders", 8),
+            ("endpoint", "GET /orders/{id}", "getOrder", 12),
+            ("message", "Order", "Order", 16),
+        ]
+    );
+    assert!(contracts.iter().all(|c| c.file_path == "/repo/api.yaml"));
+}
+
+#[test]
+fn test_read_swagger_json() {
+    let spec = r#"{
+  "swagger": "2.0",
+  "paths": { "/health": { "get": { "operationId": "health" } } },
+  "definitions": {
+    "Status": { "type": "object" }
+  }
+}"#;
+
+    let contracts = read_contracts("/repo/swagger.json", spec).unwrap();
+
+    assert_eq!(
+        summary(&contracts),
+        vec![
+            ("endpoint", "GET /health", "health", 3),
+            ("message", "Status", "Status", 5),
+        ]
+    );
+}
+
+#[test]
+fn test_other_yaml_declares_nothing() {
+    let config = "name: ci\non:\n  push: {}\n";
+
+    assert!(read_contracts("/repo/.github/ci.yml", config)
+        .unwrap()
+        .is_empty());
+    assert!(read_contracts("/repo/broken.yaml", "key: [unclosed").is_err());
+}
+
+#[test]
+fn test_read_proto_rpcs_and_nested_messages() {
+    let contracts = read_contracts("/repo/orders.proto", PROTO).unwrap();
+
+    assert_eq!(
+        summary(&contracts),
+        vec![
+            ("endpoint", "orders.v1.Orders/CreateOrder", "CreateOrder", 6),
+            ("endpoint", "orders.v1.Orders/Watch", "Watch", 7),
+            ("message", "orders.v1.Order", "Order", 12),
+            ("message", "orders.v1.Order.Line", "Line", 13),
+        ]
+    );
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+#[test]
+fn test_handlers_match_identifiers_by_symbol_kind() {
+    let mut contracts = read_contracts("/repo/orders.proto", PROTO).unwrap();
+    let symbols = [
+        symbol(
+            "create_order",
+            SymbolKind::Method,
+            "/repo/server.rs",
+            (1, 9),
+        ),
+        symbol("Order", SymbolKind::Struct, "/repo/model.rs", (1, 4)),
+        symbol("watch", SymbolKind::Variable, "/repo/server.rs", (3, 3)),
+    ];
+
+    link_handlers(&mut contracts, &symbols, |_| None);
+
+    let handlers: HashMap<&str, Vec<String>> = contracts
+        .iter()
+        .map(|c| (c.name.as_str(), c.handler_ids.clone()))
+        .collect();
+    assert_eq!(
+        handlers["orders.v1.Orders/CreateOrder"],
+        vec!["create_order"]
+    );
+    assert_eq!(handlers["orders.v1.Order"], vec!["Order"]);
+    assert!(handlers["orders.v1.Orders/Watch"].is_empty());
+}
+
+#[test]
+fn test_handlers_match_route_literals() {
+    let mut contracts = read_contracts("/repo/api.yaml", OPENAPI).unwrap();
+    let python = "\
+class OrderViews:
+    @app.post('/orders')
+    def create(self):
+        pass
+
+    @app.get(\"/orders/<int:order_id>\")
+    def show(self, order_id):
+        pass
+";
+    let sources = HashMap::from([("/repo/views.py", python)]);
+    let symbols = [
+        symbol("OrderViews", SymbolKind::Class, "/repo/views.py", (1, 8)),
+        symbol("create", SymbolKind::Method, "/repo/views.py", (3, 4)),
+        symbol("show", SymbolKind::Method, "/repo/views.py", (7, 8)),
+    ];
+
+    link_handlers(&mut contracts, &symbols, |path| {
+        sources.get(path).map(|text| (*text).to_string())
+    });
+
+    let handlers: Vec<(&str, Vec<String>)> = contracts
+        .iter()
+        .map(|c| (c.name.as_str(), c.handler_ids.clone()))
+        .collect();
+    assert_eq!(
+        handlers,
+        vec![
+            ("GET /orders", vec![]),
+            ("POST /orders", vec!["create".to_string()]),
+            ("GET /orders/{id}", vec!["show".to_string()]),
+            ("Order", vec![]),
+        ]
+    );
+}
@@ -0,0 +1,119 @@
+//! Tests for the coverage reader
+
+#![allow(clippy::unwrap_used)]
+
+use crate::graph::model::{SymbolCoverage, SymbolKind, SymbolNode};
+use crate::import::coverage::{read_coverage, symbol_coverage};
+
+const LCOV: &str = "\
+TN:
+SF:src/lib.rs
+FN:1,parse
+DA:1,3
+DA:2,3
+DA:3,0
+BRDA:2,0,0,3
+BRDA:2,0,1,-
+end_of_record
+SF:src/lib.rs
+DA:3,1
+BRDA:2,0,0,1
+BRDA:2,0,1,0
+end_of_record
+SF:src/main.rs
+DA:1,0
+end_of_record
+";
+
+const COBERTURA: &str = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.5" branch-rate="0.5" version="7.4">
+  <sources><source>/repo</source></sources>
+  <packages><package name="app">
+    <classes>
+      <class name="views.py" filename="app/views.py" line-rate="0.5">
+        <methods/>
+        <lines>
+          <line number="1" hits="1"/>
+          <line number="2" hits="1" branch="true" condition-coverage="50% (1/2)"/>
+          <line number="3" hits="0"/>
+        </lines>
+      </class>
+    </classes>
+  </package></packages>
+</coverage>
+"#;
+
+fn symbol(id: &str, file_path: &str, lines: (u32, u32)) -> SymbolNode {
+    SymbolNode::new(id, id, SymbolKind::Function, file_path, lines.0, lines.1)
+}
+
+fn coverage(id: &str, lines: (u32, u32), branches: (u32, u32)) -> SymbolCoverage {
+    SymbolCoverage {
+        symbol_id: id.to_string(),
+        lines_covered: lines.0,
+        lines_total: lines.1,
+        branches_covered: branches.0,
+        branches_total: branches.1,
+    }
+}
+
+#[test]
+fn test_read_lcov_merges_records_of_a_file() {
+    let report = read_coverage(LCOV.as_bytes()).unwrap();
+
+    let paths: Vec<&str> = report.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/lib.rs", "src/main.rs"]);
+    let lib = &report.files[0];
+    assert_eq!(
+        lib.lines.iter().map(|(l, h)| (*l, *h)).collect::<Vec<_>>(),
+        vec![(1, 3), (2, 3), (3, 1)]
+    );
+    assert_eq!(lib.branches.get(&2), Some(&(1, 2)));
+}
+
+#[test]
+fn test_read_cobertura_lines_and_conditions() {
+    let report = read_coverage(COBERTURA.as_bytes()).unwrap();
+
+    assert_eq!(report.files.len(), 1);
+    let views = &report.files[0];
+    assert_eq!(views.path, "app/views.py");
+    assert_eq!(views.lines.len(), 3);
+    assert_eq!(views.lines.get(&3), Some(&0));
+    assert_eq!(views.branches.get(&2), Some(&(1, 2)));
+}
+
+#[test]
+fn test_read_coverage_rejects_invalid_numbers() {
+    assert!(read_coverage("SF:a.rs\nDA:x,1\n".as_bytes()).is_err());
+    assert!(
+        read_coverage(r#"<coverage><class filename="a.py"><line number="1"/>"#.as_bytes()).is_err()
+    );
+}
+
+#[test]
+fn test_symbol_coverage_over_ranges() {
+    let report = read_coverage(COBERTURA.as_bytes()).unwrap();
+    let symbols = [
+        symbol("module", "/repo/app/views.py", (1, 3)),
+        symbol("index", "/repo/app/views.py", (2, 2)),
+        symbol("unused", "/repo/app/views.py", (3, 3)),
+        symbol("constant", "/repo/app/views.py", (9, 9)),
+        symbol("other", "/repo/app/models.py", (1, 3)),
+    ];
+
+    assert_eq!(
+        symbol_coverage(&report, &symbols),
+        vec![
+            coverage("module", (2, 3), (1, 2)),
+            coverage("index", (1, 1), (1, 2)),
+            coverage("unused", (0, 1), (0, 0)),
+            coverage("constant", (0, 0), (0, 0)),
+        ]
+    );
+    assert_eq!(
+        symbol_coverage(&report, &symbols)[0].line_percent(),
+        Some(200.0 / 3.0)
+    );
+    assert_eq!(symbol_coverage(&report, &symbols)[3].line_percent(), None);
+}
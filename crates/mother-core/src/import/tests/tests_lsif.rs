@@ -0,0 +1,295 @@
+//! Tests for the LSIF reader
+
+#![allow(clippy::unwrap_used)]
+
+use base64::Engine;
+use serde_json::{json, Value};
+
+use crate::graph::model::{EdgeKind, SymbolKind};
+use crate::import::lsif::read_lsif;
+use crate::scanner::Language;
+
+const LIB: &str =
+    "pub struct Config {\n    pub name: String,\n}\n\npub fn load() -> Config {\n    todo!()\n}\n";
+const MAIN: &str = "fn main() {\n    let config = lib::load();\n}\n";
+
+fn encode(content: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(content)
+}
+
+fn vertex(id: u32, label: &str, fields: Value) -> Value {
+    let mut element = json!({ "id": id, "type": "vertex", "label": label });
+    element
+        .as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().unwrap().clone());
+    element
+}
+
+fn edge(id: u32, label: &str, fields: Value) -> Value {
+    let mut element = json!({ "id": id, "type": "edge", "label": label });
+    element
+        .as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().unwrap().clone());
+    element
+}
+
+fn pos(line: u32, character: u32) -> Value {
+    json!({ "line": line, "character": character })
+}
+
+/// A definition-tagged range of a symbol spanning `full` lines
+fn definition(id: u32, name: &str, kind: u32, line: u32, col: u32, full: (u32, u32)) -> Value {
+    vertex(
+        id,
+        "range",
+        json!({
+            "start": pos(line, col),
+            "end": pos(line, col + name.len() as u32),
+            "tag": {
+                "type": "definition",
+                "text": name,
+                "kind": kind,
+                "fullRange": { "start": pos(full.0, 0), "end": pos(full.1, 1) },
+            },
+        }),
+    )
+}
+
+/// `lib.rs` defines `Config` (with field `name`) and `load`; `main.rs`
+/// calls `load`. `lib.rs` lists its symbols in a `documentSymbolResult`,
+/// `main.rs` only through its definition range.
+fn dump() -> Vec<Value> {
+    vec![
+        vertex(
+            1,
+            "metaData",
+            json!({ "version": "0.5.0", "projectRoot": "file:///repo" }),
+        ),
+        vertex(
+            2,
+            "document",
+            json!({ "uri": "file:///repo/src/lib.rs", "languageId": "rust", "contents": encode(LIB) }),
+        ),
+        vertex(
+            3,
+            "document",
+            json!({ "uri": "file:///repo/src/main.rs", "languageId": "rust", "contents": encode(MAIN) }),
+        ),
+        definition(10, "Config", 23, 0, 11, (0, 2)),
+        definition(11, "name", 8, 1, 8, (1, 1)),
+        definition(12, "load", 12, 4, 7, (4, 6)),
+        definition(13, "main", 12, 0, 3, (0, 2)),
+        vertex(
+            14,
+            "range",
+            json!({ "start": pos(1, 22), "end": pos(1, 26) }),
+        ),
+        vertex(
+            15,
+            "range",
+            json!({ "start": pos(4, 17), "end": pos(4, 23) }),
+        ),
+        edge(
+            20,
+            "contains",
+            json!({ "outV": 2, "inVs": [10, 11, 12, 15] }),
+        ),
+        edge(21, "contains", json!({ "outV": 3, "inVs": [13, 14] })),
+        vertex(
+            30,
+            "documentSymbolResult",
+            json!({ "result": [{ "id": 10, "children": [{ "id": 11 }] }, { "id": 12 }] }),
+        ),
+        edge(
+            31,
+            "textDocument/documentSymbol",
+            json!({ "outV": 2, "inV": 30 }),
+        ),
+        // `load`: defined in lib.rs, referenced from main.rs
+        vertex(40, "resultSet", json!({})),
+        edge(41, "next", json!({ "outV": 12, "inV": 40 })),
+        edge(42, "next", json!({ "outV": 14, "inV": 40 })),
+        vertex(43, "definitionResult", json!({})),
+        edge(
+            44,
+            "textDocument/definition",
+            json!({ "outV": 40, "inV": 43 }),
+        ),
+        edge(
+            45,
+            "item",
+            json!({ "outV": 43, "inVs": [12], "document": 2 }),
+        ),
+        vertex(46, "referenceResult", json!({})),
+        edge(
+            47,
+            "textDocument/references",
+            json!({ "outV": 40, "inV": 46 }),
+        ),
+        edge(
+            48,
+            "item",
+            json!({ "outV": 46, "inVs": [12], "document": 2, "property": "definitions" }),
+        ),
+        edge(
+            49,
+            "item",
+            json!({ "outV": 46, "inVs": [14], "document": 3, "property": "references" }),
+        ),
+        // `Config`: referenced from the return type of `load`
+        vertex(50, "resultSet", json!({})),
+        edge(51, "next", json!({ "outV": 10, "inV": 50 })),
+        vertex(52, "referenceResult", json!({})),
+        edge(
+            53,
+            "textDocument/references",
+            json!({ "outV": 50, "inV": 52 }),
+        ),
+        edge(
+            54,
+            "item",
+            json!({ "outV": 52, "inVs": [10], "document": 2, "property": "definitions" }),
+        ),
+        edge(
+            55,
+            "item",
+            json!({ "outV": 52, "inVs": [15], "document": 2, "property": "references" }),
+        ),
+    ]
+}
+
+fn as_lines(elements: &[Value]) -> String {
+    elements
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_read_lsif_documents_and_symbols() {
+    let import = read_lsif(as_lines(&dump()).as_bytes()).unwrap();
+
+    assert_eq!(import.project_root.as_deref(), Some("file:///repo"));
+    assert!(import.skipped.is_empty());
+    assert_eq!(import.documents.len(), 2);
+
+    let lib = &import.documents[0];
+    assert_eq!(lib.path, "/repo/src/lib.rs");
+    assert_eq!(lib.language, Language::Rust);
+    assert_eq!(lib.metrics.line_count, 7);
+    assert_eq!(lib.metrics.symbol_count(), 3);
+    let names: Vec<_> = lib
+        .symbols
+        .iter()
+        .map(|s| (s.qualified_name.as_str(), s.kind, s.start_line, s.end_line))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("Config", SymbolKind::Struct, 1, 3),
+            ("Config::name", SymbolKind::Field, 2, 2),
+            ("load", SymbolKind::Function, 5, 7),
+        ]
+    );
+
+    let main = &import.documents[1];
+    assert_eq!(main.symbols.len(), 1);
+    assert_eq!(main.symbols[0].name, "main");
+    assert_ne!(lib.content_hash, main.content_hash);
+}
+
+#[test]
+fn test_read_lsif_reference_edges() {
+    let import = read_lsif(as_lines(&dump()).as_bytes()).unwrap();
+    let id = |doc: usize, name: &str| {
+        import.documents[doc]
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap()
+            .id
+            .clone()
+    };
+
+    let edges: Vec<_> = import
+        .edges
+        .iter()
+        .map(|e| (e.source_id.clone(), e.target_id.clone(), e.kind, e.line))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            (id(1, "main"), id(0, "load"), EdgeKind::References, Some(1)),
+            (
+                id(0, "load"),
+                id(0, "Config"),
+                EdgeKind::References,
+                Some(4)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_read_lsif_json_array_matches_lines() {
+    let elements = dump();
+    let from_lines = read_lsif(as_lines(&elements).as_bytes()).unwrap();
+    let array = Value::Array(elements).to_string();
+    let from_array = read_lsif(format!("\n  {array}").as_bytes()).unwrap();
+
+    let ids = |import: &crate::import::LsifImport| {
+        import
+            .documents
+            .iter()
+            .flat_map(|d| d.symbols.iter().map(|s| s.id.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&from_lines), ids(&from_array));
+    assert_eq!(from_lines.edges.len(), from_array.edges.len());
+}
+
+#[test]
+fn test_read_lsif_nests_definition_ranges_without_document_symbols() {
+    let mut elements = dump();
+    elements.retain(|e| e["label"] != "textDocument/documentSymbol");
+
+    let import = read_lsif(as_lines(&elements).as_bytes()).unwrap();
+
+    let names: Vec<_> = import.documents[0]
+        .symbols
+        .iter()
+        .map(|s| s.qualified_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Config", "Config::name", "load"]);
+    assert_eq!(import.edges.len(), 2);
+}
+
+#[test]
+fn test_read_lsif_skips_documents_without_content() {
+    let elements = vec![vertex(
+        1,
+        "document",
+        json!({ "uri": "file:///nonexistent/mother/src/lib.rs", "languageId": "rust" }),
+    )];
+
+    let import = read_lsif(as_lines(&elements).as_bytes()).unwrap();
+
+    assert!(import.documents.is_empty());
+    assert_eq!(
+        import.skipped,
+        vec!["file:///nonexistent/mother/src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_read_lsif_rejects_invalid_lines() {
+    let input = format!("{}\nnot json\n", vertex(1, "metaData", json!({})));
+
+    let error = read_lsif(input.as_bytes()).unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().starts_with("line 2:"), "{error}");
+}
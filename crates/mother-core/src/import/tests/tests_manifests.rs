@@ -0,0 +1,108 @@
+//! Tests for the manifests reader
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use crate::graph::model::RepositoryDependency;
+use crate::import::manifests::{read_manifest, repository, repository_dependencies};
+
+const CARGO: &str = r#"
+[package]
+name = "orders"
+
+[dependencies]
+serde = "1"
+billing-client = { package = "billing", path = "../billing" }
+
+[target.'cfg(unix)'.dev-dependencies]
+nix = "0.27"
+"#;
+
+const NPM: &str = r#"{
+  "name": "@shop/web",
+  "dependencies": { "react": "^18.0.0" },
+  "devDependencies": { "@shop/ui": "workspace:*" }
+}"#;
+
+const GO_MOD: &str = "\
+module github.com/shop/orders
+
+go 1.21
+
+require github.com/shop/billing v1.2.0
+
+require (
+\tgithub.com/pkg/errors v0.9.1 // indirect
+)
+";
+
+fn names(set: &std::collections::BTreeSet<String>) -> Vec<&str> {
+    set.iter().map(String::as_str).collect()
+}
+
+#[test]
+fn test_manifests_declare_packages_and_dependencies_per_ecosystem() {
+    let cargo = read_manifest(Path::new("orders/Cargo.toml"), CARGO);
+    assert_eq!(names(&cargo.packages), ["cargo:orders"]);
+    assert_eq!(
+        names(&cargo.dependencies),
+        ["cargo:billing", "cargo:nix", "cargo:serde"]
+    );
+
+    let npm = read_manifest(Path::new("web/package.json"), NPM);
+    assert_eq!(names(&npm.packages), ["npm:@shop/web"]);
+    assert_eq!(names(&npm.dependencies), ["npm:@shop/ui", "npm:react"]);
+
+    let go = read_manifest(Path::new("go.mod"), GO_MOD);
+    assert_eq!(names(&go.packages), ["go:github.com/shop/orders"]);
+    assert_eq!(
+        names(&go.dependencies),
+        ["go:github.com/pkg/errors", "go:github.com/shop/billing"]
+    );
+}
+
+#[test]
+fn test_unparsable_and_unknown_manifests_declare_nothing() {
+    assert!(read_manifest(Path::new("Cargo.toml"), "[package")
+        .packages
+        .is_empty());
+    assert!(read_manifest(Path::new("package.json"), "{")
+        .dependencies
+        .is_empty());
+    assert!(read_manifest(Path::new("Makefile"), CARGO)
+        .packages
+        .is_empty());
+}
+
+#[test]
+fn test_repositories_depend_on_repositories_defining_their_packages() {
+    let workspace = [
+        read_manifest(Path::new("Cargo.toml"), CARGO),
+        read_manifest(
+            Path::new("billing/Cargo.toml"),
+            "[package]\nname = \"billing\"\n[dependencies]\nserde = \"1\"\n",
+        ),
+    ];
+    let shop = repository("/src/shop", &workspace);
+    // The workspace's own crates are not dependencies
+    assert_eq!(shop.dependencies, ["cargo:nix", "cargo:serde"]);
+
+    let serde = repository(
+        "/src/serde",
+        &[read_manifest(
+            Path::new("Cargo.toml"),
+            "[package]\nname = \"serde\"\n",
+        )],
+    );
+    let web = repository("/src/web", &[read_manifest(Path::new("package.json"), NPM)]);
+
+    assert_eq!(
+        repository_dependencies(&[serde, shop, web]),
+        [RepositoryDependency {
+            from: "/src/shop".to_string(),
+            to: "/src/serde".to_string(),
+            packages: vec!["cargo:serde".to_string()],
+        }]
+    );
+}
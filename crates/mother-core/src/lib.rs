@@ -4,6 +4,9 @@
 //! information from codebases, including fully resolved types, references,
 //! and cross-file analysis, then stores results in a Neo4j graph database.
 //!
+//! Library users should depend on the `mother` facade crate, which
+//! re-exports the stable subset of this API together with the scan pipeline.
+//!
 //! # Supported LSP Servers
 //!
 //! - **rust-analyzer** - Rust
@@ -11,13 +14,24 @@
 //! - **typescript-language-server** - TypeScript/JavaScript
 //! - **syster-lsp** - SysML/KerML
 
+pub mod adapters;
+pub mod embed;
+pub mod enrich;
 pub mod graph;
+pub mod import;
 pub mod lsp;
+pub mod progress;
 pub mod scanner;
 
 // Re-export commonly used types
+pub use enrich::{Enricher, EnrichmentConfig, EnrichmentPipeline};
 pub use graph::convert::convert_symbols;
-pub use graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+pub use graph::model::{Edge, EdgeKind, ScanFailure, ScanReport, ScanRun, SymbolKind, SymbolNode};
 pub use graph::neo4j::Neo4jClient;
+pub use graph::reader::GraphReader;
+pub use graph::sqlite::SqliteStore;
+pub use graph::store::{GraphStore, StoreError};
+pub use graph::{FileResult, GraphStats, Page, ReferenceResult, SymbolResult};
 pub use lsp::{LspClient, LspServerManager};
+pub use progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
 pub use scanner::{DiscoveredFile, Scanner};
@@ -1,33 +1,69 @@
 //! LSP Client: Core struct and lifecycle management
 
-use std::process::Stdio;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_lsp::concurrency::ConcurrencyLayer;
 use async_lsp::lsp_types::{
-    ClientCapabilities, DidOpenTextDocumentParams, InitializeParams, InitializedParams,
-    TextDocumentItem, Url, WindowClientCapabilities, WorkspaceFolder,
+    ClientCapabilities, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, InitializeParams, InitializedParams, TextDocumentContentChangeEvent,
+    TextDocumentIdentifier, TextDocumentItem, Url, VersionedTextDocumentIdentifier,
+    WindowClientCapabilities, WorkspaceFolder,
 };
 use async_lsp::panic::CatchUnwindLayer;
 use async_lsp::tracing::TracingLayer;
 use async_lsp::{LanguageServer, ServerSocket};
 use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use tokio::sync::{watch, Mutex, Semaphore};
 use tower::ServiceBuilder;
+use tracing::Instrument;
 
-use super::state::{ClientState, Stop};
-use super::types::LspServerConfig;
+use super::metrics::RequestMetrics;
+use super::state::{ClientState, ProgressStatus, PublishedDiagnostics, ReloadStatus, Stop};
+use super::trace::{Direction, LspTrace, Traced};
+use super::transport::{Connection, LspTransport, StdioTransport};
+use super::types::{LspDiagnostic, LspServerConfig};
+
+/// How long a re-open waits for a running workspace reload to finish
+const RELOAD_WAIT: Duration = Duration::from_secs(30);
+
+/// A document the server has been told is open
+#[derive(Debug, Clone)]
+struct OpenDocument {
+    language_id: String,
+    version: i32,
+    /// Reload generation the document was (re-)opened in
+    generation: u64,
+}
 
 /// Client for communicating with an LSP server using async-lsp
+///
+/// Once started and initialized, a client can be shared: requests and
+/// document notifications take `&self`, so several tasks can have requests
+/// in flight at once, up to the [request limit](Self::with_request_limit).
 pub struct LspClient {
     server: ServerSocket,
     #[allow(dead_code)]
     mainloop_handle: tokio::task::JoinHandle<()>,
+    /// Server process, if the transport started one; killed on drop
     #[allow(dead_code)]
-    child: async_process::Child,
+    process: Option<async_process::Child>,
     indexed_rx: Option<oneshot::Receiver<()>>,
+    reload_rx: watch::Receiver<ReloadStatus>,
+    progress_rx: watch::Receiver<ProgressStatus>,
+    /// Held across a re-open, so concurrent requests re-open a document once
+    open_documents: Mutex<HashMap<Url, OpenDocument>>,
+    diagnostics: PublishedDiagnostics,
     #[allow(dead_code)]
     config: LspServerConfig,
+    metrics: Arc<RequestMetrics>,
+    /// Permits for requests in flight; `None` leaves them unbounded
+    request_limit: Option<Semaphore>,
 }
 
 impl LspClient {
@@ -36,38 +72,70 @@ impl LspClient {
     /// # Errors
     /// Returns an error if the server cannot be started.
     pub async fn start(config: LspServerConfig) -> Result<Self> {
+        Self::start_traced(config, None).await
+    }
+
+    /// Start an LSP server and create a client, logging the JSON-RPC traffic
+    /// with the server to `trace_file` as JSON lines when given
+    ///
+    /// Secrets in the traffic, such as API keys in init options, are
+    /// redacted from the trace.
+    ///
+    /// # Errors
+    /// Returns an error if the trace file cannot be created or the server
+    /// cannot be started.
+    pub async fn start_traced(config: LspServerConfig, trace_file: Option<&Path>) -> Result<Self> {
+        Self::connect(config, &StdioTransport, trace_file).await
+    }
+
+    /// Create a client talking to the server of `config` through a
+    /// transport, tracing the traffic to `trace_file` when given
+    ///
+    /// # Errors
+    /// Returns an error if the trace file cannot be created or the transport
+    /// cannot connect.
+    pub async fn connect(
+        config: LspServerConfig,
+        transport: &dyn LspTransport,
+        trace_file: Option<&Path>,
+    ) -> Result<Self> {
+        let trace = trace_file
+            .map(|path| {
+                LspTrace::create(path)
+                    .map(Arc::new)
+                    .with_context(|| format!("Failed to create LSP trace {}", path.display()))
+            })
+            .transpose()?;
         let (indexed_tx, indexed_rx) = oneshot::channel();
+        let (reload_tx, reload_rx) = watch::channel(ReloadStatus::default());
+        let (progress_tx, progress_rx) = watch::channel(ProgressStatus::default());
+        let diagnostics = PublishedDiagnostics::default();
+        let published = diagnostics.clone();
 
         let (mainloop, server) = async_lsp::MainLoop::new_client(|_server| {
             ServiceBuilder::new()
                 .layer(TracingLayer::default())
                 .layer(CatchUnwindLayer::default())
                 .layer(ConcurrencyLayer::default())
-                .service(ClientState::new_router(indexed_tx))
+                .service(ClientState::new_router(
+                    indexed_tx,
+                    reload_tx,
+                    progress_tx,
+                    published,
+                ))
         });
 
-        // Spawn the LSP server process
-        let mut child = async_process::Command::new(&config.command)
-            .args(&config.args)
-            .current_dir(&config.root_path)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::inherit())
-            .kill_on_drop(true)
-            .spawn()?;
-
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get stdout from LSP process"))?;
-        let stdin = child
-            .stdin
-            .take()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get stdin from LSP process"))?;
+        let Connection {
+            input,
+            output,
+            process,
+        } = transport.connect(&config)?;
 
         // Run the mainloop in a background task
         let mainloop_handle = tokio::spawn(async move {
-            if let Err(e) = mainloop.run_buffered(stdout, stdin).await {
+            let input = Traced::new(input, trace.clone(), Direction::Received);
+            let output = Traced::new(output, trace, Direction::Sent);
+            if let Err(e) = mainloop.run_buffered(input, output).await {
                 tracing::warn!("LSP mainloop error: {}", e);
             }
         });
@@ -75,12 +143,63 @@ impl LspClient {
         Ok(Self {
             server,
             mainloop_handle,
-            child,
+            process,
             indexed_rx: Some(indexed_rx),
+            reload_rx,
+            progress_rx,
+            open_documents: Mutex::default(),
+            diagnostics,
             config,
+            metrics: Arc::default(),
+            request_limit: None,
         })
     }
 
+    /// Record requests in shared metrics instead of the client's own
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Arc<RequestMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Allow at most `limit` requests in flight on the server at once
+    ///
+    /// Further requests wait for a slot, which keeps servers that degrade
+    /// under load (pyright, tsserver) responsive when the client is shared.
+    #[must_use]
+    pub fn with_request_limit(mut self, limit: NonZeroUsize) -> Self {
+        self.request_limit = Some(Semaphore::new(limit.get()));
+        self
+    }
+
+    /// Request metrics of this client
+    #[must_use]
+    pub fn metrics(&self) -> &RequestMetrics {
+        &self.metrics
+    }
+
+    /// Remove and return the diagnostics the server last published for a
+    /// document
+    ///
+    /// Servers publish diagnostics on their own schedule after a document is
+    /// opened, so callers should wait for the server to settle first.
+    ///
+    /// # Errors
+    /// Returns an error if the URI is invalid.
+    pub fn take_diagnostics(&self, file_uri: &str) -> Result<Vec<LspDiagnostic>> {
+        Ok(self.diagnostics.take(&Url::parse(file_uri)?))
+    }
+
+    /// Work-done progress the server has reported, whatever its token
+    pub(super) fn progress(&self) -> watch::Receiver<ProgressStatus> {
+        self.progress_rx.clone()
+    }
+
+    /// Record the outcome of a request started at `started`
+    pub(super) fn record_request<T, E>(&self, started: Instant, result: &Result<T, E>) {
+        self.metrics.record(started, result.is_ok());
+    }
+
     /// Initialize the LSP server
     ///
     /// # Errors
@@ -141,36 +260,192 @@ impl LspClient {
 
     /// Notify the server that a file was opened
     ///
+    /// The client remembers open documents so it can re-open them after the
+    /// server reloads its workspace. Opening a document that is already open
+    /// sends its new text as a full-content change instead.
+    ///
     /// # Errors
     /// Returns an error if the notification fails.
-    pub async fn did_open(&mut self, file_uri: &str, language_id: &str, text: &str) -> Result<()> {
+    pub async fn did_open(&self, file_uri: &str, language_id: &str, text: &str) -> Result<()> {
         let url = Url::parse(file_uri)?;
+        let mut open_documents = self.open_documents.lock().await;
+        if let Some(doc) = open_documents.get_mut(&url) {
+            doc.version += 1;
+            self.server
+                .clone()
+                .did_change(DidChangeTextDocumentParams {
+                    text_document: VersionedTextDocumentIdentifier {
+                        uri: url,
+                        version: doc.version,
+                    },
+                    content_changes: vec![TextDocumentContentChangeEvent {
+                        range: None,
+                        range_length: None,
+                        text: text.into(),
+                    }],
+                })?;
+            return Ok(());
+        }
 
-        self.server.did_open(DidOpenTextDocumentParams {
+        self.server.clone().did_open(DidOpenTextDocumentParams {
             text_document: TextDocumentItem {
-                uri: url,
+                uri: url.clone(),
                 language_id: language_id.into(),
                 version: 1,
                 text: text.into(),
             },
         })?;
 
+        let generation = self.reload_rx.borrow().generation;
+        open_documents.insert(
+            url,
+            OpenDocument {
+                language_id: language_id.into(),
+                version: 1,
+                generation,
+            },
+        );
+
         Ok(())
     }
 
-    /// Shutdown the LSP server
+    /// Notify the server that a file was closed, e.g. because it was deleted
+    ///
+    /// Does nothing if the document is not open.
     ///
     /// # Errors
-    /// Returns an error if shutdown fails.
-    pub async fn shutdown(&mut self) -> Result<()> {
-        self.server.shutdown(()).await?;
-        self.server.exit(())?;
-        self.server.emit(Stop)?;
+    /// Returns an error if the notification fails.
+    pub async fn did_close(&self, file_uri: &str) -> Result<()> {
+        let url = Url::parse(file_uri)?;
+        if self.open_documents.lock().await.remove(&url).is_none() {
+            return Ok(());
+        }
+        self.server.clone().did_close(DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri: url },
+        })?;
         Ok(())
     }
 
-    /// Get mutable access to the server socket (for requests module)
-    pub(super) fn server(&mut self) -> &mut ServerSocket {
-        &mut self.server
+    /// Send a request about a document, recovering from workspace reloads
+    ///
+    /// A document opened before the last reload is re-opened first. If a
+    /// reload starts while the request is in flight, the document is
+    /// re-opened and the request retried once.
+    pub(super) async fn send_request<T>(
+        &self,
+        method: &'static str,
+        url: &Url,
+        request: impl Fn(&mut ServerSocket) -> BoxFuture<'static, async_lsp::Result<T>>,
+    ) -> Result<T> {
+        if self.is_stale(url).await {
+            self.reopen(url).await?;
+        }
+
+        let generation = self.reload_rx.borrow().generation;
+        let result = self.send_timed(method, &request).await;
+        if self.reload_rx.borrow().generation == generation || !self.is_stale(url).await {
+            return result;
+        }
+
+        tracing::debug!("Workspace reloaded during request for {url}, retrying once");
+        self.reopen(url).await?;
+        self.send_timed(method, &request).await
+    }
+
+    /// Send a request and record its timing in the metrics
+    ///
+    /// Waits for a slot under the request limit first; the time spent
+    /// waiting is not part of the recorded latency. The request itself runs
+    /// in an `lsp_request` span naming its `method`.
+    pub(super) async fn send_timed<T>(
+        &self,
+        method: &'static str,
+        request: &impl Fn(&mut ServerSocket) -> BoxFuture<'static, async_lsp::Result<T>>,
+    ) -> Result<T> {
+        let _permit = match &self.request_limit {
+            Some(limit) => Some(limit.acquire().await?),
+            None => None,
+        };
+        let span = tracing::info_span!(
+            "lsp_request",
+            method,
+            language = %self.config.language
+        );
+        let started = Instant::now();
+        let result = request(&mut self.server.clone()).instrument(span).await;
+        self.record_request(started, &result);
+        Ok(result?)
+    }
+
+    /// Whether an open document predates the latest workspace reload
+    async fn is_stale(&self, url: &Url) -> bool {
+        let generation = self.reload_rx.borrow().generation;
+        self.open_documents
+            .lock()
+            .await
+            .get(url)
+            .is_some_and(|doc| doc.generation < generation)
+    }
+
+    /// Close and re-open a document with its current contents on disk
+    ///
+    /// Waits (up to [`RELOAD_WAIT`]) for a running reload to finish first, so
+    /// the server sees the document in its new workspace. Does nothing if a
+    /// concurrent request re-opened the document in the meantime.
+    async fn reopen(&self, url: &Url) -> Result<()> {
+        let mut reload_rx = self.reload_rx.clone();
+        let finished = reload_rx.wait_for(|status| !status.in_progress());
+        if tokio::time::timeout(RELOAD_WAIT, finished).await.is_err() {
+            tracing::debug!("Workspace reload still running, re-opening anyway");
+        }
+
+        let mut open_documents = self.open_documents.lock().await;
+        let generation = self.reload_rx.borrow().generation;
+        let Some(doc) = open_documents.get(url).cloned() else {
+            return Ok(());
+        };
+        if doc.generation >= generation {
+            return Ok(());
+        }
+        let Ok(path) = url.to_file_path() else {
+            return Ok(());
+        };
+        let text = tokio::fs::read_to_string(&path).await?;
+
+        let mut server = self.server.clone();
+        server.did_close(DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri: url.clone() },
+        })?;
+        server.did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: url.clone(),
+                language_id: doc.language_id.clone(),
+                version: doc.version + 1,
+                text,
+            },
+        })?;
+
+        tracing::debug!("Re-opened {url} after workspace reload");
+        open_documents.insert(
+            url.clone(),
+            OpenDocument {
+                version: doc.version + 1,
+                generation,
+                ..doc
+            },
+        );
+        Ok(())
+    }
+
+    /// Shutdown the LSP server
+    ///
+    /// # Errors
+    /// Returns an error if shutdown fails.
+    pub async fn shutdown(&self) -> Result<()> {
+        let mut server = self.server.clone();
+        server.shutdown(()).await?;
+        server.exit(())?;
+        server.emit(Stop)?;
+        Ok(())
     }
 }
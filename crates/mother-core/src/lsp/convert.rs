@@ -1,14 +1,16 @@
 //! LSP type conversion utilities
 //!
-//! Converts between `async_lsp::lsp_types` and our internal `LspSymbol` types.
+//! Converts between `async_lsp::lsp_types` and our internal `LspSymbol` and
+//! `LspDiagnostic` types.
 
 use std::path::Path;
 
 use async_lsp::lsp_types::{
-    DocumentSymbol, DocumentSymbolResponse, MarkedString, SymbolInformation, SymbolKind,
+    Diagnostic, DiagnosticSeverity, DocumentSymbol, DocumentSymbolResponse, MarkedString,
+    NumberOrString, OneOf, SymbolInformation, SymbolKind, WorkspaceSymbol, WorkspaceSymbolResponse,
 };
 
-use super::types::{LspSymbol, LspSymbolKind};
+use super::types::{LspDiagnostic, LspSymbol, LspSymbolKind};
 
 /// Convert a `DocumentSymbolResponse` to a list of `LspSymbol`.
 pub fn convert_symbol_response(response: Option<DocumentSymbolResponse>) -> Vec<LspSymbol> {
@@ -65,6 +67,44 @@ pub fn convert_symbol_information(symbol: &SymbolInformation) -> LspSymbol {
     }
 }
 
+/// Convert a `WorkspaceSymbolResponse` to a list of `LspSymbol`.
+pub fn convert_workspace_symbol_response(
+    response: Option<WorkspaceSymbolResponse>,
+) -> Vec<LspSymbol> {
+    match response {
+        Some(WorkspaceSymbolResponse::Flat(symbols)) => {
+            symbols.iter().map(convert_symbol_information).collect()
+        }
+        Some(WorkspaceSymbolResponse::Nested(symbols)) => {
+            symbols.iter().map(convert_workspace_symbol).collect()
+        }
+        None => vec![],
+    }
+}
+
+/// Convert a `WorkspaceSymbol` to our `LspSymbol` type.
+///
+/// A symbol located by file only (the range is resolved lazily) starts at
+/// the top of the file.
+pub fn convert_workspace_symbol(symbol: &WorkspaceSymbol) -> LspSymbol {
+    let (uri, range) = match &symbol.location {
+        OneOf::Left(location) => (&location.uri, location.range),
+        OneOf::Right(location) => (&location.uri, Default::default()),
+    };
+    LspSymbol {
+        name: symbol.name.clone(),
+        kind: convert_symbol_kind(symbol.kind),
+        detail: None,
+        container_name: symbol.container_name.clone(),
+        file: Path::new(uri.path()).to_path_buf(),
+        start_line: range.start.line,
+        end_line: range.end.line,
+        start_col: range.start.character,
+        end_col: range.end.character,
+        children: vec![],
+    }
+}
+
 /// Convert an LSP `SymbolKind` to our `LspSymbolKind` enum.
 pub fn convert_symbol_kind(kind: SymbolKind) -> LspSymbolKind {
     // Use a simple mapping - the SymbolKind values are sequential integers
@@ -100,6 +140,33 @@ pub fn convert_symbol_kind(kind: SymbolKind) -> LspSymbolKind {
     }
 }
 
+/// Convert a published `Diagnostic` to our `LspDiagnostic` type.
+///
+/// A diagnostic without a severity is treated as an error, as editors do.
+pub fn convert_diagnostic(diagnostic: &Diagnostic) -> LspDiagnostic {
+    let severity = match diagnostic.severity {
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "information",
+        Some(DiagnosticSeverity::HINT) => "hint",
+        _ => "error",
+    };
+    let code = diagnostic.code.as_ref().map(|code| match code {
+        NumberOrString::Number(n) => n.to_string(),
+        NumberOrString::String(s) => s.clone(),
+    });
+
+    LspDiagnostic {
+        severity: severity.to_string(),
+        code,
+        source: diagnostic.source.clone(),
+        message: diagnostic.message.clone(),
+        start_line: diagnostic.range.start.line,
+        start_col: diagnostic.range.start.character,
+        end_line: diagnostic.range.end.line,
+        end_col: diagnostic.range.end.character,
+    }
+}
+
 /// Convert a `MarkedString` to a plain `String`.
 ///
 /// Used for extracting hover content.
@@ -141,6 +208,43 @@ mod tests {
         );
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_convert_workspace_symbol_response() {
+        let uri = Url::parse("file:///repo/src/lib.rs").unwrap();
+        let located = WorkspaceSymbol {
+            name: "Parser".to_string(),
+            kind: SymbolKind::STRUCT,
+            tags: None,
+            container_name: Some("parse".to_string()),
+            location: OneOf::Left(Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(4, 0), Position::new(9, 1)),
+            }),
+            data: None,
+        };
+        let file_only = WorkspaceSymbol {
+            name: "parse".to_string(),
+            kind: SymbolKind::FUNCTION,
+            location: OneOf::Right(async_lsp::lsp_types::WorkspaceLocation { uri }),
+            container_name: None,
+            ..located.clone()
+        };
+
+        let symbols =
+            convert_workspace_symbol_response(Some(WorkspaceSymbolResponse::Nested(vec![
+                located, file_only,
+            ])));
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].kind, LspSymbolKind::Struct);
+        assert_eq!(symbols[0].container_name.as_deref(), Some("parse"));
+        assert_eq!(symbols[0].file, Path::new("/repo/src/lib.rs"));
+        assert_eq!((symbols[0].start_line, symbols[0].end_line), (4, 9));
+        assert_eq!(symbols[1].start_line, 0);
+        assert!(convert_workspace_symbol_response(None).is_empty());
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_convert_document_symbol() {
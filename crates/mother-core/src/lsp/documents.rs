@@ -0,0 +1,74 @@
+//! Open documents: The documents a server has open, by when they were used
+//!
+//! Servers keep every open document in memory. [`OpenDocuments`] remembers
+//! which documents the manager opened on one server, least recently used
+//! first, and picks the ones to close once more are open than its cap.
+
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroUsize;
+
+/// Documents open on one server, with an optional cap on how many stay open
+#[derive(Debug, Default)]
+pub(super) struct OpenDocuments {
+    limit: Option<NonZeroUsize>,
+    /// Use counter, increasing with each touch
+    clock: u64,
+    /// Last use of each open document
+    used: HashMap<String, u64>,
+    /// Open documents by last use, least recent first
+    by_use: BTreeMap<u64, String>,
+}
+
+impl OpenDocuments {
+    /// Track documents, keeping at most `limit` open; `None` keeps all
+    pub(super) fn new(limit: Option<NonZeroUsize>) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Record that a document was opened or used
+    ///
+    /// Returns the least recently used documents to close to get back under
+    /// the cap, which are no longer tracked; never `uri` itself.
+    pub(super) fn touch(&mut self, uri: &str) -> Vec<String> {
+        self.clock += 1;
+        if let Some(previous) = self.used.insert(uri.to_string(), self.clock) {
+            self.by_use.remove(&previous);
+        }
+        self.by_use.insert(self.clock, uri.to_string());
+
+        let limit = self.limit.map_or(usize::MAX, NonZeroUsize::get);
+        let mut evicted = Vec::new();
+        while self.used.len() > limit {
+            let Some((_, oldest)) = self.by_use.pop_first() else {
+                break;
+            };
+            self.used.remove(&oldest);
+            evicted.push(oldest);
+        }
+        evicted
+    }
+
+    /// Stop tracking a document, returning whether it was open
+    pub(super) fn remove(&mut self, uri: &str) -> bool {
+        match self.used.remove(uri) {
+            Some(used) => {
+                self.by_use.remove(&used);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a document is open
+    pub(super) fn contains(&self, uri: &str) -> bool {
+        self.used.contains_key(uri)
+    }
+
+    /// Number of open documents
+    pub(super) fn len(&self) -> usize {
+        self.used.len()
+    }
+}
@@ -1,25 +1,37 @@
 //! LSP Server Manager: Manages multiple LSP servers
 
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use tokio::sync::Mutex;
 
 use super::client::LspClient;
+use super::documents::OpenDocuments;
+use super::metrics::{RequestMetrics, RequestStats};
+use super::readiness::Readiness;
+use super::transport::{LspTransport, StdioTransport};
 use super::types::LspServerConfig;
 use crate::scanner::Language;
 
+/// Longest a newly started server is waited for before it is used anyway
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Default LSP server commands for each language
 pub struct LspServerDefaults;
 
 impl LspServerDefaults {
     /// Get the default server config for a language
     #[must_use]
-    pub fn for_language(language: Language, root_path: &Path) -> LspServerConfig {
+    pub fn for_language(language: &Language, root_path: &Path) -> LspServerConfig {
         let root = root_path.to_path_buf();
+        let language = language.clone();
 
-        match language {
+        match &language {
             Language::Rust => LspServerConfig {
                 language,
                 command: "rust-analyzer".to_string(),
@@ -48,6 +60,13 @@ impl LspServerDefaults {
                 root_path: root,
                 init_options: None,
             },
+            Language::Ruby => LspServerConfig {
+                language,
+                command: "solargraph".to_string(),
+                args: vec!["stdio".to_string()],
+                root_path: root,
+                init_options: None,
+            },
             Language::SysML | Language::KerML => {
                 // Find sysml.library in the project or use system default
                 let stdlib_path = root
@@ -81,15 +100,54 @@ impl LspServerDefaults {
                     init_options,
                 }
             }
+            // Registered languages bring their own server; without one, try
+            // an executable named like the language
+            Language::Other(id) => LspServerConfig {
+                command: id.clone(),
+                language: language.clone(),
+                args: vec![],
+                root_path: root,
+                init_options: None,
+            },
         }
     }
 }
 
+/// Fault injection for resilience testing
+///
+/// Consulted each time a client is requested from the manager, so an
+/// injected error takes the place of the LSP work the caller was about to do.
+pub trait LspFaults: Send + Sync {
+    /// An error to fail the next use of `language`'s server with, if any
+    fn inject(&self, language: &Language) -> Option<anyhow::Error>;
+}
+
+/// Per-language client slot; `None` until the server has been started
+type ClientSlot = Arc<Mutex<Option<Arc<LspClient>>>>;
+
 /// Manages multiple LSP server instances
+///
+/// Each language gets one client, started on first use and shared by every
+/// task that asks for it, so the manager can be shared by reference across
+/// concurrent tasks. Requests to a server run concurrently up to its
+/// [request limit](Self::set_request_limit). Documents opened through the
+/// manager stay open until closed, or until more than a server's
+/// [open document limit](Self::set_open_document_limit) are open;
+/// [`ensure_document_open`](Self::ensure_document_open) opens them again
+/// when they are needed.
 pub struct LspServerManager {
     root_path: PathBuf,
-    clients: HashMap<Language, LspClient>,
+    clients: StdMutex<HashMap<Language, ClientSlot>>,
     custom_configs: HashMap<Language, LspServerConfig>,
+    request_limits: HashMap<Language, NonZeroUsize>,
+    open_document_limits: HashMap<Language, NonZeroUsize>,
+    readiness: HashMap<Language, Readiness>,
+    trace_dir: Option<PathBuf>,
+    transport: Arc<dyn LspTransport>,
+    documents: StdMutex<HashMap<Language, OpenDocuments>>,
+    metrics: Arc<RequestMetrics>,
+    faults: Option<Arc<dyn LspFaults>>,
+    deadline: Option<Instant>,
 }
 
 impl LspServerManager {
@@ -98,53 +156,309 @@ impl LspServerManager {
     pub fn new(root_path: impl Into<PathBuf>) -> Self {
         Self {
             root_path: root_path.into(),
-            clients: HashMap::new(),
+            clients: StdMutex::new(HashMap::new()),
             custom_configs: HashMap::new(),
+            request_limits: HashMap::new(),
+            open_document_limits: HashMap::new(),
+            readiness: HashMap::new(),
+            trace_dir: None,
+            transport: Arc::new(StdioTransport),
+            documents: StdMutex::new(HashMap::new()),
+            metrics: Arc::default(),
+            faults: None,
+            deadline: None,
         }
     }
 
+    /// Totals of the requests sent by all clients of this manager
+    #[must_use]
+    pub fn request_stats(&self) -> RequestStats {
+        self.metrics.snapshot()
+    }
+
     /// Register a custom server config for a language
     pub fn register_server(&mut self, config: LspServerConfig) {
-        self.custom_configs.insert(config.language, config);
+        self.custom_configs.insert(config.language.clone(), config);
+    }
+
+    /// Cap the requests in flight on a language's server
+    ///
+    /// Applies to servers started afterwards; without a limit requests are
+    /// unbounded.
+    pub fn set_request_limit(&mut self, language: Language, limit: NonZeroUsize) {
+        self.request_limits.insert(language, limit);
+    }
+
+    /// Cap the documents open on a language's server
+    ///
+    /// Opening a document beyond the cap closes the least recently used
+    /// one; without a limit documents stay open until closed.
+    pub fn set_open_document_limit(&mut self, language: Language, limit: NonZeroUsize) {
+        self.open_document_limits.insert(language, limit);
+    }
+
+    /// Choose how a language's server is waited for after it starts
+    ///
+    /// Without one the server waits the [`Readiness::for_language`] way.
+    pub fn set_readiness(&mut self, language: Language, readiness: Readiness) {
+        self.readiness.insert(language, readiness);
+    }
+
+    /// Log the JSON-RPC traffic of servers started afterwards to
+    /// `<dir>/<language>.jsonl`, with secrets redacted
+    pub fn set_trace_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.trace_dir = Some(dir.into());
+    }
+
+    /// Reach servers started afterwards through `transport` instead of
+    /// running their commands, e.g. to replay recorded traffic
+    pub fn set_transport(&mut self, transport: Arc<dyn LspTransport>) {
+        self.transport = transport;
+    }
+
+    /// Count the requests of clients started afterwards into `metrics`,
+    /// e.g. to total them across managers
+    pub fn set_request_metrics(&mut self, metrics: Arc<RequestMetrics>) {
+        self.metrics = metrics;
+    }
+
+    /// Inject faults into client requests, for resilience testing
+    pub fn set_faults(&mut self, faults: Arc<dyn LspFaults>) {
+        self.faults = Some(faults);
+    }
+
+    /// Stop new work at `deadline`, e.g. for a scan with a time budget
+    ///
+    /// Requests are still served afterwards; callers check
+    /// [`Self::past_deadline`] before starting more, so work in flight can
+    /// finish.
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Whether the [deadline](Self::set_deadline) has passed
+    #[must_use]
+    pub fn past_deadline(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
     }
 
     /// Get or start an LSP client for a language
     ///
+    /// The client is shared: callers asking for the same language while its
+    /// server starts wait for it, and get the same client afterwards.
+    ///
     /// # Errors
-    /// Returns an error if the server cannot be started.
-    pub async fn get_client(&mut self, language: Language) -> Result<&mut LspClient> {
-        if !self.clients.contains_key(&language) {
-            let config = self
-                .custom_configs
-                .get(&language)
-                .cloned()
-                .unwrap_or_else(|| LspServerDefaults::for_language(language, &self.root_path));
+    /// Returns an error if the server cannot be started, or a fault was
+    /// injected.
+    pub async fn get_client(&self, language: &Language) -> Result<Arc<LspClient>> {
+        if let Some(error) = self.faults.as_ref().and_then(|f| f.inject(language)) {
+            return Err(error);
+        }
+        let slot = self.slot(language);
+        let mut guard = slot.lock().await;
 
-            let mut client = LspClient::start(config).await?;
+        if let Some(client) = guard.as_ref() {
+            return Ok(Arc::clone(client));
+        }
+        let client = Arc::new(self.start_client(language).await?);
+        *guard = Some(Arc::clone(&client));
+        Ok(client)
+    }
 
-            let root_uri = format!("file://{}", self.root_path.display());
-            client.initialize(&root_uri).await?;
+    /// Run an async operation with a language's client
+    ///
+    /// Starts the server on first use. The closure returns a boxed future so
+    /// it can borrow the client across awaits; anything else it captures
+    /// must be owned:
+    ///
+    /// ```ignore
+    /// let uri = file_uri.to_string();
+    /// let hover = manager
+    ///     .with_client(&Language::Rust, |client| {
+    ///         Box::pin(async move { client.hover(&uri, line, col).await })
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the server cannot be started or the operation fails.
+    pub async fn with_client<F, T>(&self, language: &Language, f: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c LspClient) -> BoxFuture<'c, Result<T>>,
+    {
+        let client = self.get_client(language).await?;
+        f(&client).await
+    }
 
-            // Wait for the LSP server to finish initial indexing
-            // This uses async-lsp's proper notification handling
-            client.wait_for_indexing(Duration::from_secs(30)).await?;
+    /// Open a document on its language's server, or send its new text if
+    /// it is open
+    ///
+    /// Starts the server on first use. If more documents are then open on
+    /// the server than its [limit](Self::set_open_document_limit), the least
+    /// recently used are closed.
+    ///
+    /// # Errors
+    /// Returns an error if the server cannot be started or a notification
+    /// fails.
+    pub async fn open_document(
+        &self,
+        language: &Language,
+        file_uri: &str,
+        text: &str,
+    ) -> Result<()> {
+        let client = self.get_client(language).await?;
+        client
+            .did_open(file_uri, &language.to_string(), text)
+            .await?;
+        let evicted = self
+            .lock_documents()
+            .entry(language.clone())
+            .or_insert_with(|| OpenDocuments::new(self.open_document_limits.get(language).copied()))
+            .touch(file_uri);
+        for uri in evicted {
+            tracing::debug!("Closing {uri} to stay under the open document limit");
+            client.did_close(&uri).await?;
+        }
+        Ok(())
+    }
 
-            self.clients.insert(language, client);
+    /// Make sure a document is open before a request about it
+    ///
+    /// A document open through the manager counts as used, so it is closed
+    /// last. One that is not, because it was closed to stay under the
+    /// [open document limit](Self::set_open_document_limit) or never
+    /// opened, is opened with the text `load` returns.
+    ///
+    /// # Errors
+    /// Returns an error if `load` fails, or the server cannot be started or
+    /// a notification fails.
+    pub async fn ensure_document_open<F>(
+        &self,
+        language: &Language,
+        file_uri: &str,
+        load: F,
+    ) -> Result<()>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        let open = self
+            .lock_documents()
+            .get_mut(language)
+            .filter(|documents| documents.contains(file_uri))
+            .map(|documents| documents.touch(file_uri))
+            .is_some();
+        if open {
+            return Ok(());
+        }
+        self.open_document(language, file_uri, &load()?).await
+    }
+
+    /// Whether documents of a language are closed once more than a limit
+    /// are open, so they have to be opened as they are needed
+    #[must_use]
+    pub fn limits_open_documents(&self, language: &Language) -> bool {
+        self.open_document_limits.contains_key(language)
+    }
+
+    /// Tell a running server that a document was closed
+    ///
+    /// Unlike [`get_client`](Self::get_client) this never starts a server.
+    ///
+    /// # Errors
+    /// Returns an error if the notification fails.
+    pub async fn close_document(&self, language: &Language, file_uri: &str) -> Result<()> {
+        if let Some(documents) = self.lock_documents().get_mut(language) {
+            documents.remove(file_uri);
+        }
+        let client = self.slot(language).lock().await.clone();
+        match client {
+            Some(client) => client.did_close(file_uri).await,
+            None => Ok(()),
         }
+    }
 
-        self.clients
-            .get_mut(&language)
-            .ok_or_else(|| anyhow::anyhow!("Failed to get LSP client for {:?}", language))
+    /// Number of documents opened through the manager that are still open
+    /// on a language's server
+    #[must_use]
+    pub fn open_document_count(&self, language: &Language) -> usize {
+        self.lock_documents()
+            .get(language)
+            .map_or(0, OpenDocuments::len)
     }
 
     /// Shutdown all LSP servers
     ///
+    /// Waits for servers still starting; operations in flight on a client
+    /// should be finished first.
+    ///
     /// # Errors
     /// Returns an error if any server fails to shutdown.
-    pub async fn shutdown_all(&mut self) -> Result<()> {
-        for (_, mut client) in self.clients.drain() {
-            let _ = client.shutdown().await;
+    pub async fn shutdown_all(&self) -> Result<()> {
+        let slots: Vec<ClientSlot> = self.lock_clients().drain().map(|(_, slot)| slot).collect();
+        self.lock_documents().clear();
+        for slot in slots {
+            if let Some(client) = slot.lock().await.take() {
+                let _ = client.shutdown().await;
+            }
         }
         Ok(())
     }
+
+    /// Get or create the client slot for a language
+    fn slot(&self, language: &Language) -> ClientSlot {
+        Arc::clone(self.lock_clients().entry(language.clone()).or_default())
+    }
+
+    fn lock_clients(&self) -> MutexGuard<'_, HashMap<Language, ClientSlot>> {
+        // The map is only touched in short non-panicking sections
+        self.clients.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_documents(&self) -> MutexGuard<'_, HashMap<Language, OpenDocuments>> {
+        // Only touched in short non-panicking sections, like the clients
+        self.documents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start and initialize the server for a language
+    async fn start_client(&self, language: &Language) -> Result<LspClient> {
+        let config = self
+            .custom_configs
+            .get(language)
+            .cloned()
+            .unwrap_or_else(|| LspServerDefaults::for_language(language, &self.root_path));
+
+        let trace_file = match &self.trace_dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir).with_context(|| {
+                    format!("Failed to create LSP trace directory {}", dir.display())
+                })?;
+                Some(dir.join(format!("{language}.jsonl")))
+            }
+            None => None,
+        };
+
+        let mut client = LspClient::connect(config, self.transport.as_ref(), trace_file.as_deref())
+            .await?
+            .with_metrics(Arc::clone(&self.metrics));
+        if let Some(&limit) = self.request_limits.get(language) {
+            client = client.with_request_limit(limit);
+        }
+
+        let root_uri = format!("file://{}", self.root_path.display());
+        client.initialize(&root_uri).await?;
+
+        // Wait for the LSP server to load the workspace, so early requests
+        // don't miss symbols it has not indexed yet
+        let readiness = self
+            .readiness
+            .get(language)
+            .copied()
+            .unwrap_or_else(|| Readiness::for_language(language));
+        client.wait_until_ready(readiness, READY_TIMEOUT).await?;
+
+        Ok(client)
+    }
 }
@@ -0,0 +1,67 @@
+//! Request metrics: Count LSP requests and their latency
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Shared counters for the requests sent by one or more clients
+///
+/// A [`super::LspServerManager`] hands the same counters to every client it
+/// starts, so callers can measure throughput across languages.
+#[derive(Debug, Default)]
+pub struct RequestMetrics {
+    requests: AtomicU64,
+    failures: AtomicU64,
+    latency_micros: AtomicU64,
+}
+
+impl RequestMetrics {
+    /// Record a completed request that started at `started`
+    pub fn record(&self, started: Instant, succeeded: bool) {
+        let micros = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.latency_micros.fetch_add(micros, Ordering::Relaxed);
+        if !succeeded {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Current totals
+    #[must_use]
+    pub fn snapshot(&self) -> RequestStats {
+        RequestStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            total_latency: Duration::from_micros(self.latency_micros.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Point-in-time request totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    pub requests: u64,
+    pub failures: u64,
+    /// Sum of the latencies of all requests
+    pub total_latency: Duration,
+}
+
+impl RequestStats {
+    /// Requests made since an earlier snapshot
+    #[must_use]
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            requests: self.requests.saturating_sub(earlier.requests),
+            failures: self.failures.saturating_sub(earlier.failures),
+            total_latency: self.total_latency.saturating_sub(earlier.total_latency),
+        }
+    }
+
+    /// Mean request latency, or zero without requests
+    #[must_use]
+    pub fn average_latency(&self) -> Duration {
+        u32::try_from(self.requests)
+            .ok()
+            .filter(|n| *n > 0)
+            .map_or(Duration::ZERO, |n| self.total_latency / n)
+    }
+}
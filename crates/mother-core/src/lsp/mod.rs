@@ -1,24 +1,37 @@
 //! LSP module: Extract semantic info via Language Server Protocol
 //!
 //! Uses existing LSP servers to get rich semantic information
-//! including resolved types, references, and cross-file analysis.
+//! including resolved types, references, and cross-file analysis. Diagnostics
+//! the servers publish are kept per document until taken.
 
 mod client;
 mod convert;
+mod documents;
 mod manager;
+mod metrics;
+mod readiness;
+mod replay;
 mod requests;
 mod state;
+mod trace;
+mod transport;
 mod types;
 
+pub use async_lsp::lsp_types::Url;
 pub use client::LspClient;
 pub use convert::{
-    convert_document_symbol, convert_symbol_information, convert_symbol_kind,
-    convert_symbol_response, marked_string_to_string,
+    convert_diagnostic, convert_document_symbol, convert_symbol_information, convert_symbol_kind,
+    convert_symbol_response, convert_workspace_symbol, convert_workspace_symbol_response,
+    marked_string_to_string,
 };
-pub use manager::{LspServerDefaults, LspServerManager};
+pub use manager::{LspFaults, LspServerDefaults, LspServerManager};
+pub use metrics::{RequestMetrics, RequestStats};
+pub use readiness::Readiness;
+pub use replay::ReplayTransport;
+pub use transport::{Connection, LspTransport, StdioTransport};
 pub use types::{
-    collect_symbol_positions, flatten_symbols, LspReference, LspServerConfig, LspSymbol,
-    LspSymbolKind,
+    collect_symbol_positions, flatten_symbols, LspDiagnostic, LspReference, LspServerConfig,
+    LspSymbol, LspSymbolKind,
 };
 
 #[cfg(test)]
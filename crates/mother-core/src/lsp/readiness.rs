@@ -0,0 +1,122 @@
+//! Readiness: When a language server has loaded the workspace
+//!
+//! Servers answer requests while they are still indexing, with results that
+//! silently miss symbols. Each server signals the end of indexing its own
+//! way, so a [`Readiness`] strategy says how a client waits for it.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use super::client::LspClient;
+use crate::scanner::Language;
+
+/// How long the server must report no progress for [`Readiness::Progress`]
+const PROGRESS_QUIET: Duration = Duration::from_secs(1);
+
+/// Query polled by [`Readiness::Symbols`]; empty asks for every symbol
+const SYMBOL_QUERY: &str = "";
+
+/// Time between workspace symbol polls
+const SYMBOL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls in a row that must return the same symbol count
+const STABLE_SYMBOL_POLLS: usize = 3;
+
+/// How a client waits for its server to finish loading the workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Readiness {
+    /// Don't wait
+    Immediate,
+    /// Wait for the end of rust-analyzer's indexing progress
+    Indexing,
+    /// Wait until no work-done progress has run for a quiet period
+    Progress,
+    /// Poll workspace symbols until several polls in a row return the same
+    /// non-zero count, for servers that report no progress
+    Symbols,
+}
+
+impl Readiness {
+    /// The strategy that suits the default server of a language
+    #[must_use]
+    pub fn for_language(language: &Language) -> Self {
+        match language {
+            Language::Rust => Self::Indexing,
+            _ => Self::Progress,
+        }
+    }
+}
+
+impl LspClient {
+    /// Wait until the server is ready, or `timeout` has passed
+    ///
+    /// A server that is not ready in time is used anyway.
+    ///
+    /// # Errors
+    /// Returns an error if waiting fails.
+    pub async fn wait_until_ready(
+        &mut self,
+        readiness: Readiness,
+        timeout: Duration,
+    ) -> Result<()> {
+        let ready = match readiness {
+            Readiness::Immediate => return Ok(()),
+            Readiness::Indexing => return self.wait_for_indexing(timeout).await,
+            Readiness::Progress => tokio::time::timeout(timeout, self.progress_quiet()).await,
+            Readiness::Symbols => tokio::time::timeout(timeout, self.symbols_stable()).await,
+        };
+        match ready {
+            Ok(()) => tracing::info!("LSP server ready ({readiness:?})"),
+            Err(_) => tracing::debug!("Readiness wait timed out, proceeding anyway"),
+        }
+        Ok(())
+    }
+
+    /// Wait until no progress is running and none was reported for
+    /// [`PROGRESS_QUIET`]
+    async fn progress_quiet(&self) {
+        let mut progress = self.progress();
+        loop {
+            let active = progress.borrow_and_update().active > 0;
+            let changed = if active {
+                progress.changed().await
+            } else {
+                match tokio::time::timeout(PROGRESS_QUIET, progress.changed()).await {
+                    Ok(changed) => changed,
+                    Err(_) => return,
+                }
+            };
+            if changed.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Wait until [`STABLE_SYMBOL_POLLS`] polls in a row return the same
+    /// non-zero number of workspace symbols
+    async fn symbols_stable(&self) {
+        let mut last = 0;
+        let mut stable = 0;
+        loop {
+            match self.workspace_symbols(SYMBOL_QUERY).await {
+                Ok(symbols) if !symbols.is_empty() && symbols.len() == last => stable += 1,
+                Ok(symbols) => {
+                    last = symbols.len();
+                    stable = 1;
+                }
+                Err(e) => {
+                    tracing::debug!("Workspace symbol poll failed: {e}");
+                    last = 0;
+                    stable = 0;
+                }
+            }
+            if last > 0 && stable >= STABLE_SYMBOL_POLLS {
+                return;
+            }
+            tokio::time::sleep(SYMBOL_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Replay: A stand-in server that answers from recorded traffic
+//!
+//! [`ReplayTransport`] reads the traces `--lsp-trace` writes, one
+//! `<language>.jsonl` per server, and answers each request with the response
+//! recorded for the same method and params, falling back to the method's
+//! recorded responses in turn. Notifications and requests the server sent
+//! after a recorded message are sent again after it is replayed, so progress
+//! and diagnostics arrive as they did. Hand-written fixtures in the same
+//! format let clients run without installed servers.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anyhow::{Context as _, Result};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{AsyncWrite, StreamExt, TryStreamExt};
+use serde_json::{json, Value};
+
+use super::trace::{Direction, Framer};
+use super::transport::{Connection, LspTransport};
+use super::types::LspServerConfig;
+
+/// Error code of requests with no recorded response (JSON-RPC internal error)
+const NOT_RECORDED: i64 = -32603;
+
+/// Serves the traffic recorded in `<dir>/<language>.jsonl` in place of a
+/// server
+#[derive(Debug, Clone)]
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    /// Replay the traces in `dir`, as written by an LSP trace
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl LspTransport for ReplayTransport {
+    fn connect(&self, config: &LspServerConfig) -> Result<Connection> {
+        let path = self.dir.join(format!("{}.jsonl", config.language));
+        let recording = Recording::load(&path)?;
+
+        let (to_client, input) = mpsc::unbounded();
+        let (output, from_client) = mpsc::unbounded();
+        tokio::spawn(recording.serve(from_client, to_client));
+
+        Ok(Connection {
+            input: Box::new(input.into_async_read()),
+            output: Box::new(ChannelWriter(output)),
+            process: None,
+        })
+    }
+}
+
+/// A message the client sent, with what the server answered
+#[derive(Debug, Default)]
+struct Exchange {
+    params: Value,
+    /// Response to a request; `None` for notifications
+    response: Option<Value>,
+    /// Messages the server sent on its own before the client's next message
+    followups: Vec<Value>,
+}
+
+/// Recorded traffic of one server
+#[derive(Debug, Default)]
+pub(super) struct Recording {
+    /// Server messages sent before the client's first message
+    preamble: Vec<Value>,
+    exchanges: HashMap<String, Vec<Exchange>>,
+    /// Next exchange of each method to fall back to
+    next: HashMap<String, usize>,
+}
+
+impl Recording {
+    /// Read a trace file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or a line is not a trace
+    /// record.
+    pub(super) fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("No recorded LSP traffic at {}", path.display()))?;
+        let records = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                parse_record(line).with_context(|| {
+                    format!("Invalid trace record {}:{}", path.display(), index + 1)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::from_records(records))
+    }
+
+    /// Pair the recorded requests with their responses
+    pub(super) fn from_records(records: Vec<(Direction, Value)>) -> Self {
+        let mut recording = Self::default();
+        let mut pending: HashMap<String, (String, usize)> = HashMap::new();
+        let mut last: Option<(String, usize)> = None;
+
+        for (direction, message) in records {
+            let method = message
+                .get("method")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            match (direction, method) {
+                (Direction::Sent, Some(method)) => {
+                    let exchanges = recording.exchanges.entry(method.clone()).or_default();
+                    exchanges.push(Exchange {
+                        params: message.get("params").cloned().unwrap_or(Value::Null),
+                        ..Exchange::default()
+                    });
+                    let key = (method, exchanges.len() - 1);
+                    if let Some(id) = message.get("id") {
+                        pending.insert(id.to_string(), key.clone());
+                    }
+                    last = Some(key);
+                }
+                // The client answering a server request
+                (Direction::Sent, None) => {}
+                (Direction::Received, None) => {
+                    let id = message.get("id").map(Value::to_string).unwrap_or_default();
+                    if let Some(exchange) = pending
+                        .remove(&id)
+                        .and_then(|key| recording.exchange_mut(&key))
+                    {
+                        exchange.response = Some(message);
+                    }
+                }
+                (Direction::Received, Some(_)) => match last.as_ref() {
+                    Some(key) => {
+                        if let Some(exchange) = recording.exchange_mut(key) {
+                            exchange.followups.push(message);
+                        }
+                    }
+                    None => recording.preamble.push(message),
+                },
+            }
+        }
+        recording
+    }
+
+    fn exchange_mut(&mut self, (method, index): &(String, usize)) -> Option<&mut Exchange> {
+        self.exchanges.get_mut(method)?.get_mut(*index)
+    }
+
+    /// The server's messages in answer to one client message
+    pub(super) fn answer(&mut self, message: &Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // The client answering a server request
+            return Vec::new();
+        };
+        let id = message.get("id");
+        let params = message.get("params").unwrap_or(&Value::Null);
+
+        let Some(exchange) = self.find(method, params) else {
+            return id
+                .map(|id| vec![unrecorded_response(method, id)])
+                .unwrap_or_default();
+        };
+        let response = id.map(|id| match &exchange.response {
+            Some(response) => {
+                let mut response = response.clone();
+                response["id"] = id.clone();
+                response
+            }
+            None => unrecorded_response(method, id),
+        });
+        response
+            .into_iter()
+            .chain(exchange.followups.iter().cloned())
+            .collect()
+    }
+
+    /// The exchange recorded for the same params, else the method's next
+    fn find(&mut self, method: &str, params: &Value) -> Option<&Exchange> {
+        let exchanges = self.exchanges.get(method)?;
+        let index = match exchanges.iter().position(|e| e.params == *params) {
+            Some(index) => index,
+            None => {
+                let next = self.next.entry(method.to_string()).or_default();
+                let index = *next % exchanges.len();
+                *next += 1;
+                index
+            }
+        };
+        exchanges.get(index)
+    }
+
+    /// Answer the client until it disconnects or exits
+    async fn serve(
+        mut self,
+        mut from_client: UnboundedReceiver<Vec<u8>>,
+        to_client: UnboundedSender<io::Result<Vec<u8>>>,
+    ) {
+        let send = |message: &Value| {
+            let body = message.to_string();
+            let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+            to_client.unbounded_send(Ok(frame.into_bytes())).is_ok()
+        };
+        for message in std::mem::take(&mut self.preamble) {
+            send(&message);
+        }
+
+        let mut framer = Framer::default();
+        while let Some(bytes) = from_client.next().await {
+            for body in framer.push(&bytes) {
+                let Ok(message) = serde_json::from_slice::<Value>(&body) else {
+                    tracing::debug!("Replay ignored an invalid message of {} bytes", body.len());
+                    continue;
+                };
+                if message.get("method").and_then(Value::as_str) == Some("exit") {
+                    return;
+                }
+                for answer in self.answer(&message) {
+                    if !send(&answer) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse one line of a trace
+fn parse_record(line: &str) -> Result<(Direction, Value)> {
+    let mut record: Value = serde_json::from_str(line)?;
+    let direction = match record.get("direction").and_then(Value::as_str) {
+        Some("sent") => Direction::Sent,
+        Some("received") => Direction::Received,
+        other => anyhow::bail!("Unknown direction {other:?}"),
+    };
+    let message = record
+        .get_mut("message")
+        .map(Value::take)
+        .ok_or_else(|| anyhow::anyhow!("Missing message"))?;
+    Ok((direction, message))
+}
+
+/// Error response to a request with nothing recorded to answer it
+///
+/// `shutdown` gets its usual empty result instead, so replayed clients shut
+/// down cleanly whether or not the trace recorded it.
+fn unrecorded_response(method: &str, id: &Value) -> Value {
+    if method == "shutdown" {
+        return json!({"jsonrpc": "2.0", "id": id, "result": null});
+    }
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": NOT_RECORDED, "message": format!("No recorded response to {method}")},
+    })
+}
+
+/// Client output, sent to the replaying task
+struct ChannelWriter(UnboundedSender<Vec<u8>>);
+
+impl AsyncWrite for ChannelWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(
+            self.0
+                .unbounded_send(buf.to_vec())
+                .map(|()| buf.len())
+                .map_err(|_| io::ErrorKind::BrokenPipe.into()),
+        )
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
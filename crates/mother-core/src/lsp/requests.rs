@@ -1,17 +1,24 @@
-//! LSP request methods (document_symbols, references, definition, hover)
+//! LSP request methods (document_symbols, references, definition, hover,
+//! workspace_symbols)
 
 use std::path::Path;
 
 use anyhow::Result;
+use async_lsp::lsp_types::request::{
+    DocumentSymbolRequest, GotoDefinition, HoverRequest, References, Request,
+    WorkspaceSymbolRequest,
+};
 use async_lsp::lsp_types::{
     DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
     HoverContents, HoverParams, Position, ReferenceContext, ReferenceParams,
-    TextDocumentIdentifier, TextDocumentPositionParams, Url,
+    TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkspaceSymbolParams,
 };
 use async_lsp::LanguageServer;
 
 use super::client::LspClient;
-use super::convert::{convert_symbol_response, marked_string_to_string};
+use super::convert::{
+    convert_symbol_response, convert_workspace_symbol_response, marked_string_to_string,
+};
 use super::types::{LspReference, LspSymbol};
 
 impl LspClient {
@@ -19,16 +26,13 @@ impl LspClient {
     ///
     /// # Errors
     /// Returns an error if the request fails.
-    pub async fn document_symbols(&mut self, file_uri: &str) -> Result<Vec<LspSymbol>> {
+    pub async fn document_symbols(&self, file_uri: &str) -> Result<Vec<LspSymbol>> {
         let url = Url::parse(file_uri)?;
         let symbols = self.fetch_document_symbols(&url).await?;
         Ok(convert_symbol_response(symbols))
     }
 
-    async fn fetch_document_symbols(
-        &mut self,
-        url: &Url,
-    ) -> Result<Option<DocumentSymbolResponse>> {
+    async fn fetch_document_symbols(&self, url: &Url) -> Result<Option<DocumentSymbolResponse>> {
         let params = DocumentSymbolParams {
             text_document: TextDocumentIdentifier { uri: url.clone() },
             work_done_progress_params: Default::default(),
@@ -36,17 +40,44 @@ impl LspClient {
         };
 
         tracing::debug!("Requesting document symbols for: {}", url);
-        let response = self.server().document_symbol(params).await?;
+        let response = self
+            .send_request(DocumentSymbolRequest::METHOD, url, |server| {
+                server.document_symbol(params.clone())
+            })
+            .await?;
         tracing::debug!("Got response for {}: {:?}", url, response.is_some());
         Ok(response)
     }
 
+    /// Search the whole workspace for symbols matching a query
+    ///
+    /// Matching is up to the server, typically fuzzy on the name. Some
+    /// servers only search projects they have loaded, so open a document of
+    /// the workspace first.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails.
+    pub async fn workspace_symbols(&self, query: &str) -> Result<Vec<LspSymbol>> {
+        let params = WorkspaceSymbolParams {
+            query: query.to_string(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let response = self
+            .send_timed(WorkspaceSymbolRequest::METHOD, &|server| {
+                server.symbol(params.clone())
+            })
+            .await?;
+        Ok(convert_workspace_symbol_response(response))
+    }
+
     /// Find all references to a symbol at a position
     ///
     /// # Errors
     /// Returns an error if the request fails.
     pub async fn references(
-        &mut self,
+        &self,
         file_uri: &str,
         line: u32,
         character: u32,
@@ -56,7 +87,7 @@ impl LspClient {
 
         let params = ReferenceParams {
             text_document_position: TextDocumentPositionParams {
-                text_document: TextDocumentIdentifier { uri: url },
+                text_document: TextDocumentIdentifier { uri: url.clone() },
                 position: Position::new(line, character),
             },
             work_done_progress_params: Default::default(),
@@ -66,7 +97,11 @@ impl LspClient {
             },
         };
 
-        let response = self.server().references(params).await?;
+        let response = self
+            .send_request(References::METHOD, &url, |server| {
+                server.references(params.clone())
+            })
+            .await?;
 
         let refs = response
             .unwrap_or_default()
@@ -90,7 +125,7 @@ impl LspClient {
     /// # Errors
     /// Returns an error if the request fails.
     pub async fn definition(
-        &mut self,
+        &self,
         file_uri: &str,
         line: u32,
         character: u32,
@@ -99,14 +134,18 @@ impl LspClient {
 
         let params = GotoDefinitionParams {
             text_document_position_params: TextDocumentPositionParams {
-                text_document: TextDocumentIdentifier { uri: url },
+                text_document: TextDocumentIdentifier { uri: url.clone() },
                 position: Position::new(line, character),
             },
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         };
 
-        let response = self.server().definition(params).await?;
+        let response = self
+            .send_request(GotoDefinition::METHOD, &url, |server| {
+                server.definition(params.clone())
+            })
+            .await?;
 
         let locations = match response {
             Some(GotoDefinitionResponse::Scalar(loc)) => vec![loc],
@@ -143,23 +182,22 @@ impl LspClient {
     ///
     /// # Errors
     /// Returns an error if the request fails.
-    pub async fn hover(
-        &mut self,
-        file_uri: &str,
-        line: u32,
-        character: u32,
-    ) -> Result<Option<String>> {
+    pub async fn hover(&self, file_uri: &str, line: u32, character: u32) -> Result<Option<String>> {
         let url = Url::parse(file_uri)?;
 
         let params = HoverParams {
             text_document_position_params: TextDocumentPositionParams {
-                text_document: TextDocumentIdentifier { uri: url },
+                text_document: TextDocumentIdentifier { uri: url.clone() },
                 position: Position::new(line, character),
             },
             work_done_progress_params: Default::default(),
         };
 
-        let response = self.server().hover(params).await?;
+        let response = self
+            .send_request(HoverRequest::METHOD, &url, |server| {
+                server.hover(params.clone())
+            })
+            .await?;
 
         let content = response.and_then(|hover| match hover.contents {
             HoverContents::Scalar(marked) => Some(marked_string_to_string(marked)),
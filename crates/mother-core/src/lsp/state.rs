@@ -1,28 +1,162 @@
 //! LSP client state and notification handling
 
+use std::collections::HashMap;
 use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex, PoisonError};
 
 use async_lsp::lsp_types::{
     LogMessageParams, NumberOrString, ProgressParams, ProgressParamsValue,
-    PublishDiagnosticsParams, ShowMessageParams, WorkDoneProgress, WorkDoneProgressCreateParams,
+    PublishDiagnosticsParams, ShowMessageParams, Url, WorkDoneProgress,
+    WorkDoneProgressCreateParams,
 };
 use async_lsp::router::Router;
 use async_lsp::{LanguageClient, ResponseError};
 use futures::channel::oneshot;
+use tokio::sync::watch;
+
+use super::convert::convert_diagnostic;
+use super::types::LspDiagnostic;
 
 /// Known rust-analyzer indexing progress tokens
 const RA_INDEXING_TOKENS: &[&str] = &["rustAnalyzer/Indexing", "rustAnalyzer/cachePriming"];
 
+/// rust-analyzer progress tokens reported while (re)loading the workspace
+const RA_RELOAD_TOKENS: &[&str] = &[
+    "rustAnalyzer/Fetching",
+    "rustAnalyzer/Loading",
+    "rustAnalyzer/Roots Scanned",
+];
+
+/// Workspace reloads seen by a client
+///
+/// `generation` counts reloads started; documents opened in an earlier
+/// generation may return stale or empty results until they are re-opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct ReloadStatus {
+    pub generation: u64,
+    /// Reload progress tokens that have begun but not ended
+    pub active: usize,
+}
+
+impl ReloadStatus {
+    /// Whether a reload is still running
+    pub fn in_progress(&self) -> bool {
+        self.active > 0
+    }
+}
+
+/// Work-done progress seen by a client, whatever its token
+///
+/// `changes` counts every begin, report and end, so a waiter can tell a
+/// server that went quiet from one still reporting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct ProgressStatus {
+    pub changes: u64,
+    /// Progress tokens that have begun but not ended
+    pub active: usize,
+}
+
+/// Latest diagnostics published for each document
+///
+/// Each publish replaces the document's earlier diagnostics, as the protocol
+/// specifies.
+#[derive(Debug, Clone, Default)]
+pub(super) struct PublishedDiagnostics(Arc<Mutex<HashMap<Url, Vec<LspDiagnostic>>>>);
+
+impl PublishedDiagnostics {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Url, Vec<LspDiagnostic>>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<LspDiagnostic>) {
+        let mut documents = self.lock();
+        if diagnostics.is_empty() {
+            documents.remove(&uri);
+        } else {
+            documents.insert(uri, diagnostics);
+        }
+    }
+
+    /// Remove and return the diagnostics of a document
+    pub fn take(&self, uri: &Url) -> Vec<LspDiagnostic> {
+        self.lock().remove(uri).unwrap_or_default()
+    }
+}
+
 /// Client state for handling LSP notifications
 pub(super) struct ClientState {
     indexed_tx: Option<oneshot::Sender<()>>,
+    reload_tx: watch::Sender<ReloadStatus>,
+    progress_tx: watch::Sender<ProgressStatus>,
+    diagnostics: PublishedDiagnostics,
 }
 
 impl ClientState {
     /// Create a new ClientState for testing
     #[cfg(test)]
     pub(super) fn new_for_test(indexed_tx: Option<oneshot::Sender<()>>) -> Self {
-        ClientState { indexed_tx }
+        ClientState {
+            indexed_tx,
+            reload_tx: watch::Sender::new(ReloadStatus::default()),
+            progress_tx: watch::Sender::new(ProgressStatus::default()),
+            diagnostics: PublishedDiagnostics::default(),
+        }
+    }
+
+    /// Diagnostics published so far, for testing
+    #[cfg(test)]
+    pub(super) fn diagnostics(&self) -> PublishedDiagnostics {
+        self.diagnostics.clone()
+    }
+
+    /// Current workspace reload status, for testing
+    #[cfg(test)]
+    pub(super) fn reload_status(&self) -> ReloadStatus {
+        *self.reload_tx.borrow()
+    }
+
+    /// Current work-done progress status, for testing
+    #[cfg(test)]
+    pub(super) fn progress_status(&self) -> ProgressStatus {
+        *self.progress_tx.borrow()
+    }
+
+    /// Track the start, reports and end of any work-done progress
+    fn track_progress(&self, value: &ProgressParamsValue) {
+        self.progress_tx.send_modify(|status| {
+            status.changes += 1;
+            match value {
+                ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(_)) => status.active += 1,
+                ProgressParamsValue::WorkDone(WorkDoneProgress::End(_)) => {
+                    status.active = status.active.saturating_sub(1);
+                }
+                ProgressParamsValue::WorkDone(WorkDoneProgress::Report(_)) => {}
+            }
+        });
+    }
+
+    /// Track the start and end of workspace reload progress
+    fn track_reload(&self, token: &str, value: &ProgressParamsValue) {
+        if !RA_RELOAD_TOKENS.contains(&token) {
+            return;
+        }
+        match value {
+            ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(_)) => {
+                self.reload_tx.send_modify(|status| {
+                    if status.active == 0 {
+                        status.generation += 1;
+                        tracing::debug!("Workspace reload {} started", status.generation);
+                    }
+                    status.active += 1;
+                });
+            }
+            ProgressParamsValue::WorkDone(WorkDoneProgress::End(_)) => {
+                self.reload_tx.send_modify(|status| {
+                    status.active = status.active.saturating_sub(1);
+                });
+            }
+            ProgressParamsValue::WorkDone(WorkDoneProgress::Report(_)) => {}
+        }
     }
 }
 
@@ -34,6 +168,11 @@ impl LanguageClient for ClientState {
     type NotifyResult = ControlFlow<async_lsp::Result<()>>;
 
     fn progress(&mut self, params: ProgressParams) -> Self::NotifyResult {
+        self.track_progress(&params.value);
+        if let NumberOrString::String(token) = &params.token {
+            self.track_reload(token, &params.value);
+        }
+
         // Check if indexing is complete
         let is_indexing_token = matches!(&params.token, NumberOrString::String(s) if RA_INDEXING_TOKENS.contains(&&**s));
         let is_end_progress = matches!(
@@ -49,7 +188,9 @@ impl LanguageClient for ClientState {
         ControlFlow::Continue(())
     }
 
-    fn publish_diagnostics(&mut self, _: PublishDiagnosticsParams) -> Self::NotifyResult {
+    fn publish_diagnostics(&mut self, params: PublishDiagnosticsParams) -> Self::NotifyResult {
+        let diagnostics = params.diagnostics.iter().map(convert_diagnostic).collect();
+        self.diagnostics.publish(params.uri, diagnostics);
         ControlFlow::Continue(())
     }
 
@@ -74,9 +215,17 @@ impl LanguageClient for ClientState {
 }
 
 impl ClientState {
-    pub fn new_router(indexed_tx: oneshot::Sender<()>) -> Router<Self> {
+    pub fn new_router(
+        indexed_tx: oneshot::Sender<()>,
+        reload_tx: watch::Sender<ReloadStatus>,
+        progress_tx: watch::Sender<ProgressStatus>,
+        diagnostics: PublishedDiagnostics,
+    ) -> Router<Self> {
         let mut router = Router::from_language_client(ClientState {
             indexed_tx: Some(indexed_tx),
+            reload_tx,
+            progress_tx,
+            diagnostics,
         });
         router.request::<async_lsp::lsp_types::request::WorkDoneProgressCreate, _>(
             Self::work_done_progress_create,
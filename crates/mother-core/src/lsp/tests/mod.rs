@@ -1,6 +1,11 @@
 //! Tests for LSP module
 
 mod tests_client;
+mod tests_documents;
 mod tests_manager;
+mod tests_metrics;
+mod tests_readiness;
+mod tests_replay;
 mod tests_state_clientstate;
+mod tests_trace;
 mod tests_types;
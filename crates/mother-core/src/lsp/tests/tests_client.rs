@@ -433,3 +433,44 @@ fn test_lsp_client_type_exists() {
         }
     }
 }
+
+/// Most requests in flight at once when `requests` are sent concurrently
+/// through a client limited to `limit`
+async fn peak_in_flight(limit: usize, requests: usize) -> usize {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let mut config = test_config_with_command("sleep", vec!["30".to_string()]);
+    config.root_path = std::env::temp_dir();
+    let client = LspClient::start(config)
+        .await
+        .unwrap()
+        .with_request_limit(limit.try_into().unwrap());
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    // Stands in for a server round trip; the placeholder process never answers
+    let request = |_: &mut async_lsp::ServerSocket| {
+        let (in_flight, peak) = (Arc::clone(&in_flight), Arc::clone(&peak));
+        Box::pin(async move {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }) as futures::future::BoxFuture<'static, async_lsp::Result<()>>
+    };
+
+    let sends = (0..requests).map(|_| client.send_timed("test/request", &request));
+    for result in futures::future::join_all(sends).await {
+        result.unwrap();
+    }
+    assert_eq!(client.metrics().snapshot().requests, requests as u64);
+    peak.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn test_request_limit_caps_requests_in_flight() {
+    assert_eq!(peak_in_flight(1, 4).await, 1);
+    assert_eq!(peak_in_flight(2, 4).await, 2);
+}
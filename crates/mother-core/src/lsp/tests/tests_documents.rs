@@ -0,0 +1,43 @@
+//! Tests for tracking open documents under a cap
+
+use std::num::NonZeroUsize;
+
+use crate::lsp::documents::OpenDocuments;
+
+fn capped(limit: usize) -> OpenDocuments {
+    OpenDocuments::new(NonZeroUsize::new(limit))
+}
+
+#[test]
+fn test_least_recently_used_documents_are_evicted_above_the_cap() {
+    let mut documents = capped(2);
+    assert!(documents.touch("file:///a.rs").is_empty());
+    assert!(documents.touch("file:///b.rs").is_empty());
+    // Using a again makes b the least recently used
+    assert!(documents.touch("file:///a.rs").is_empty());
+
+    assert_eq!(documents.touch("file:///c.rs"), vec!["file:///b.rs"]);
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents.touch("file:///d.rs"), vec!["file:///a.rs"]);
+}
+
+#[test]
+fn test_documents_without_a_cap_stay_open() {
+    let mut documents = OpenDocuments::new(None);
+    for i in 0..100 {
+        assert!(documents.touch(&format!("file:///{i}.rs")).is_empty());
+    }
+    assert_eq!(documents.len(), 100);
+}
+
+#[test]
+fn test_removed_documents_free_their_slot() {
+    let mut documents = capped(2);
+    documents.touch("file:///a.rs");
+    documents.touch("file:///b.rs");
+
+    assert!(documents.remove("file:///a.rs"));
+    assert!(!documents.remove("file:///a.rs"));
+    assert!(documents.touch("file:///c.rs").is_empty());
+    assert_eq!(documents.touch("file:///d.rs"), vec!["file:///b.rs"]);
+}
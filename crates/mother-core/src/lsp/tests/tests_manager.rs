@@ -1,9 +1,10 @@
 //! Tests for LSP Server Manager
 
-use crate::lsp::manager::{LspServerDefaults, LspServerManager};
+use crate::lsp::manager::{LspFaults, LspServerDefaults, LspServerManager};
 use crate::lsp::types::LspServerConfig;
 use crate::scanner::Language;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tempfile::TempDir;
 
 // ============================================================================
@@ -126,6 +127,141 @@ fn test_register_server_overwrites_existing_language() -> anyhow::Result<()> {
     Ok(())
 }
 
+// ============================================================================
+// Tests for LspServerManager::get_client / with_client
+// ============================================================================
+
+fn missing_server_config(root: &std::path::Path) -> LspServerConfig {
+    LspServerConfig {
+        language: Language::Rust,
+        command: "mother-test-nonexistent-lsp-server".to_string(),
+        args: vec![],
+        root_path: root.to_path_buf(),
+        init_options: None,
+    }
+}
+
+#[tokio::test]
+async fn test_get_client_fails_when_server_missing() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+
+    assert!(manager.get_client(&Language::Rust).await.is_err());
+    Ok(())
+}
+
+/// Fails every request for one language
+struct FailLanguage(Language);
+
+impl LspFaults for FailLanguage {
+    fn inject(&self, language: &Language) -> Option<anyhow::Error> {
+        (*language == self.0).then(|| anyhow::anyhow!("injected timeout"))
+    }
+}
+
+#[tokio::test]
+async fn test_get_client_returns_injected_fault() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+    manager.register_server(LspServerConfig {
+        language: Language::Python,
+        ..missing_server_config(temp.path())
+    });
+    manager.set_faults(Arc::new(FailLanguage(Language::Rust)));
+
+    let error = manager.get_client(&Language::Rust).await.err();
+    assert_eq!(
+        error.map(|e| e.to_string()).as_deref(),
+        Some("injected timeout")
+    );
+    // Other languages are unaffected and fail for their own reasons
+    let error = manager.get_client(&Language::Python).await.err();
+    assert!(error.is_some_and(|e| e.to_string() != "injected timeout"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_client_retries_after_failed_start() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+
+    // A failed start must not leave the language slot locked or poisoned
+    assert!(manager.get_client(&Language::Rust).await.is_err());
+    assert!(manager.get_client(&Language::Rust).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_with_client_propagates_start_error() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+
+    let mut called = false;
+    let result = manager
+        .with_client(&Language::Rust, |_client| {
+            called = true;
+            Box::pin(async { Ok(()) })
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert!(!called, "closure should not run without a client");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_close_document_does_not_start_a_server() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+
+    // Starting the missing server would fail, so success means no start
+    manager
+        .close_document(&Language::Rust, "file:///repo/src/lib.rs")
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_open_document_fails_without_a_server_and_tracks_nothing() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+    manager.set_open_document_limit(Language::Rust, std::num::NonZeroUsize::MIN);
+
+    let result = manager
+        .open_document(&Language::Rust, "file:///repo/src/lib.rs", "fn main() {}")
+        .await;
+    assert!(result.is_err());
+    assert_eq!(manager.open_document_count(&Language::Rust), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_manager_shared_across_tasks() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let mut manager = LspServerManager::new(temp.path());
+    manager.register_server(missing_server_config(temp.path()));
+    let manager = std::sync::Arc::new(manager);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let manager = std::sync::Arc::clone(&manager);
+            tokio::spawn(async move { manager.get_client(&Language::Rust).await.is_err() })
+        })
+        .collect();
+
+    for handle in handles {
+        assert!(handle.await?);
+    }
+    manager.shutdown_all().await?;
+    Ok(())
+}
+
 // ============================================================================
 // Tests for LspServerManager::shutdown_all
 // ============================================================================
@@ -133,7 +269,7 @@ fn test_register_server_overwrites_existing_language() -> anyhow::Result<()> {
 #[tokio::test]
 async fn test_shutdown_all_with_no_clients() -> anyhow::Result<()> {
     let temp = TempDir::new()?;
-    let mut manager = LspServerManager::new(temp.path());
+    let manager = LspServerManager::new(temp.path());
 
     // Shutdown with no clients should succeed
     let result = manager.shutdown_all().await;
@@ -147,7 +283,7 @@ async fn test_shutdown_all_with_no_clients() -> anyhow::Result<()> {
 #[tokio::test]
 async fn test_shutdown_all_is_idempotent() -> anyhow::Result<()> {
     let temp = TempDir::new()?;
-    let mut manager = LspServerManager::new(temp.path());
+    let manager = LspServerManager::new(temp.path());
 
     // First shutdown
     let result1 = manager.shutdown_all().await;
@@ -162,7 +298,7 @@ async fn test_shutdown_all_is_idempotent() -> anyhow::Result<()> {
 #[tokio::test]
 async fn test_shutdown_all_clears_clients() -> anyhow::Result<()> {
     let temp = TempDir::new()?;
-    let mut manager = LspServerManager::new(temp.path());
+    let manager = LspServerManager::new(temp.path());
 
     // Shutdown
     manager.shutdown_all().await?;
@@ -184,7 +320,7 @@ async fn test_shutdown_all_clears_clients() -> anyhow::Result<()> {
 #[test]
 fn test_defaults_for_rust() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::Rust, &root);
+    let config = LspServerDefaults::for_language(&Language::Rust, &root);
 
     assert_eq!(config.language, Language::Rust);
     assert_eq!(config.command, "rust-analyzer");
@@ -196,7 +332,7 @@ fn test_defaults_for_rust() {
 #[test]
 fn test_defaults_for_python() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::Python, &root);
+    let config = LspServerDefaults::for_language(&Language::Python, &root);
 
     assert_eq!(config.language, Language::Python);
     assert_eq!(config.command, "pyright-langserver");
@@ -208,7 +344,7 @@ fn test_defaults_for_python() {
 #[test]
 fn test_defaults_for_typescript() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::TypeScript, &root);
+    let config = LspServerDefaults::for_language(&Language::TypeScript, &root);
 
     assert_eq!(config.language, Language::TypeScript);
     assert_eq!(config.command, "typescript-language-server");
@@ -220,7 +356,7 @@ fn test_defaults_for_typescript() {
 #[test]
 fn test_defaults_for_javascript() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::JavaScript, &root);
+    let config = LspServerDefaults::for_language(&Language::JavaScript, &root);
 
     assert_eq!(config.language, Language::JavaScript);
     assert_eq!(config.command, "typescript-language-server");
@@ -232,7 +368,7 @@ fn test_defaults_for_javascript() {
 #[test]
 fn test_defaults_for_go() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::Go, &root);
+    let config = LspServerDefaults::for_language(&Language::Go, &root);
 
     assert_eq!(config.language, Language::Go);
     assert_eq!(config.command, "gopls");
@@ -244,7 +380,7 @@ fn test_defaults_for_go() {
 #[test]
 fn test_defaults_for_sysml() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     assert_eq!(config.language, Language::SysML);
     assert_eq!(config.command, "syster-lsp");
@@ -262,7 +398,7 @@ fn test_defaults_for_sysml() {
 #[test]
 fn test_defaults_for_kerml() {
     let root = PathBuf::from("/tmp/test");
-    let config = LspServerDefaults::for_language(Language::KerML, &root);
+    let config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     assert_eq!(config.language, Language::KerML);
     assert_eq!(config.command, "syster-lsp");
@@ -282,8 +418,8 @@ fn test_defaults_preserves_root_path() {
     let root1 = PathBuf::from("/path/one");
     let root2 = PathBuf::from("/path/two");
 
-    let config1 = LspServerDefaults::for_language(Language::Rust, &root1);
-    let config2 = LspServerDefaults::for_language(Language::Rust, &root2);
+    let config1 = LspServerDefaults::for_language(&Language::Rust, &root1);
+    let config2 = LspServerDefaults::for_language(&Language::Rust, &root2);
 
     assert_eq!(config1.root_path, root1);
     assert_eq!(config2.root_path, root2);
@@ -411,7 +547,7 @@ fn test_defaults_for_all_languages() {
     ];
 
     for language in languages {
-        let config = LspServerDefaults::for_language(language, &root);
+        let config = LspServerDefaults::for_language(&language, &root);
         assert_eq!(config.language, language);
         assert!(
             !config.command.is_empty(),
@@ -425,7 +561,7 @@ fn test_defaults_for_all_languages() {
 #[tokio::test]
 async fn test_multiple_shutdowns() -> anyhow::Result<()> {
     let temp = TempDir::new()?;
-    let mut manager = LspServerManager::new(temp.path());
+    let manager = LspServerManager::new(temp.path());
 
     // Multiple shutdowns should all succeed
     for _ in 0..5 {
@@ -493,7 +629,7 @@ fn test_defaults_with_different_root_paths() {
     ];
 
     for root in roots {
-        let config = LspServerDefaults::for_language(Language::Rust, &root);
+        let config = LspServerDefaults::for_language(&Language::Rust, &root);
         assert_eq!(config.root_path, root);
     }
 }
@@ -513,7 +649,7 @@ fn test_sysml_stdlib_path_with_existing_project_path() -> anyhow::Result<()> {
     let sysml_library = syster_base.join("sysml.library");
     std::fs::create_dir_all(&sysml_library)?;
 
-    let config = LspServerDefaults::for_language(Language::SysML, temp.path());
+    let config = LspServerDefaults::for_language(&Language::SysML, temp.path());
 
     assert_eq!(config.language, Language::SysML);
     assert_eq!(config.command, "syster-lsp");
@@ -537,7 +673,7 @@ fn test_sysml_stdlib_path_with_existing_project_path() -> anyhow::Result<()> {
 fn test_sysml_stdlib_path_when_project_path_not_exists() {
     // Use a non-existent directory
     let root = PathBuf::from("/nonexistent/test/path");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     assert_eq!(config.language, Language::SysML);
     assert_eq!(config.command, "syster-lsp");
@@ -564,7 +700,7 @@ fn test_kerml_stdlib_path_with_existing_project_path() -> anyhow::Result<()> {
     let sysml_library = syster_base.join("sysml.library");
     std::fs::create_dir_all(&sysml_library)?;
 
-    let config = LspServerDefaults::for_language(Language::KerML, temp.path());
+    let config = LspServerDefaults::for_language(&Language::KerML, temp.path());
 
     assert_eq!(config.language, Language::KerML);
     assert_eq!(config.command, "syster-lsp");
@@ -588,7 +724,7 @@ fn test_kerml_stdlib_path_with_existing_project_path() -> anyhow::Result<()> {
 fn test_kerml_stdlib_path_when_project_path_not_exists() {
     // Use a non-existent directory
     let root = PathBuf::from("/nonexistent/test/path");
-    let config = LspServerDefaults::for_language(Language::KerML, &root);
+    let config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     assert_eq!(config.language, Language::KerML);
     assert_eq!(config.command, "syster-lsp");
@@ -608,7 +744,7 @@ fn test_sysml_kerml_stdlib_path_closure_handles_canonicalize_failure() {
     // Test the closure that handles .canonicalize().ok() returning None
     // This happens when the path exists but cannot be canonicalized
     let root = PathBuf::from("/tmp");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     // Should succeed even if canonicalization fails
     assert_eq!(config.language, Language::SysML);
@@ -620,7 +756,7 @@ fn test_sysml_kerml_stdlib_path_closure_handles_canonicalize_failure() {
 fn test_sysml_stdlib_fallback_to_exe_relative_path() {
     // This tests the or_else closure that tries to find stdlib relative to exe
     let root = PathBuf::from("/nonexistent/path/without/stdlib");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     // Should still create valid config with init_options
     assert_eq!(config.language, Language::SysML);
@@ -637,7 +773,7 @@ fn test_sysml_stdlib_fallback_to_exe_relative_path() {
 fn test_kerml_stdlib_fallback_to_exe_relative_path() {
     // This tests the or_else closure that tries to find stdlib relative to exe
     let root = PathBuf::from("/nonexistent/path/without/stdlib");
-    let config = LspServerDefaults::for_language(Language::KerML, &root);
+    let config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     // Should still create valid config with init_options
     assert_eq!(config.language, Language::KerML);
@@ -657,7 +793,7 @@ fn test_kerml_stdlib_fallback_to_exe_relative_path() {
 #[test]
 fn test_defaults_for_language_with_empty_path() {
     let root = PathBuf::from("");
-    let config = LspServerDefaults::for_language(Language::Rust, &root);
+    let config = LspServerDefaults::for_language(&Language::Rust, &root);
 
     assert_eq!(config.language, Language::Rust);
     assert_eq!(config.command, "rust-analyzer");
@@ -667,7 +803,7 @@ fn test_defaults_for_language_with_empty_path() {
 #[test]
 fn test_defaults_for_language_with_current_dir() {
     let root = PathBuf::from(".");
-    let config = LspServerDefaults::for_language(Language::Python, &root);
+    let config = LspServerDefaults::for_language(&Language::Python, &root);
 
     assert_eq!(config.language, Language::Python);
     assert_eq!(config.command, "pyright-langserver");
@@ -677,7 +813,7 @@ fn test_defaults_for_language_with_current_dir() {
 #[test]
 fn test_defaults_for_language_with_parent_dir() {
     let root = PathBuf::from("..");
-    let config = LspServerDefaults::for_language(Language::Go, &root);
+    let config = LspServerDefaults::for_language(&Language::Go, &root);
 
     assert_eq!(config.language, Language::Go);
     assert_eq!(config.command, "gopls");
@@ -687,7 +823,7 @@ fn test_defaults_for_language_with_parent_dir() {
 #[test]
 fn test_sysml_with_relative_path() {
     let root = PathBuf::from("./some/relative/path");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     assert_eq!(config.language, Language::SysML);
     assert_eq!(config.command, "syster-lsp");
@@ -698,7 +834,7 @@ fn test_sysml_with_relative_path() {
 #[test]
 fn test_kerml_with_relative_path() {
     let root = PathBuf::from("./some/relative/path");
-    let config = LspServerDefaults::for_language(Language::KerML, &root);
+    let config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     assert_eq!(config.language, Language::KerML);
     assert_eq!(config.command, "syster-lsp");
@@ -711,8 +847,8 @@ fn test_typescript_javascript_shared_server() {
     // Both TypeScript and JavaScript use the same server
     let root = PathBuf::from("/test");
 
-    let ts_config = LspServerDefaults::for_language(Language::TypeScript, &root);
-    let js_config = LspServerDefaults::for_language(Language::JavaScript, &root);
+    let ts_config = LspServerDefaults::for_language(&Language::TypeScript, &root);
+    let js_config = LspServerDefaults::for_language(&Language::JavaScript, &root);
 
     assert_eq!(ts_config.command, js_config.command);
     assert_eq!(ts_config.args, js_config.args);
@@ -725,8 +861,8 @@ fn test_sysml_kerml_shared_server() {
     // Both SysML and KerML use the same server
     let root = PathBuf::from("/test");
 
-    let sysml_config = LspServerDefaults::for_language(Language::SysML, &root);
-    let kerml_config = LspServerDefaults::for_language(Language::KerML, &root);
+    let sysml_config = LspServerDefaults::for_language(&Language::SysML, &root);
+    let kerml_config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     assert_eq!(sysml_config.command, kerml_config.command);
     assert_eq!(sysml_config.args, kerml_config.args);
@@ -743,25 +879,25 @@ fn test_config_language_preservation() {
     // Ensure that the language field is correctly set for each variant
     let root = PathBuf::from("/test");
 
-    let rust = LspServerDefaults::for_language(Language::Rust, &root);
+    let rust = LspServerDefaults::for_language(&Language::Rust, &root);
     assert_eq!(rust.language, Language::Rust);
 
-    let python = LspServerDefaults::for_language(Language::Python, &root);
+    let python = LspServerDefaults::for_language(&Language::Python, &root);
     assert_eq!(python.language, Language::Python);
 
-    let ts = LspServerDefaults::for_language(Language::TypeScript, &root);
+    let ts = LspServerDefaults::for_language(&Language::TypeScript, &root);
     assert_eq!(ts.language, Language::TypeScript);
 
-    let js = LspServerDefaults::for_language(Language::JavaScript, &root);
+    let js = LspServerDefaults::for_language(&Language::JavaScript, &root);
     assert_eq!(js.language, Language::JavaScript);
 
-    let go = LspServerDefaults::for_language(Language::Go, &root);
+    let go = LspServerDefaults::for_language(&Language::Go, &root);
     assert_eq!(go.language, Language::Go);
 
-    let sysml = LspServerDefaults::for_language(Language::SysML, &root);
+    let sysml = LspServerDefaults::for_language(&Language::SysML, &root);
     assert_eq!(sysml.language, Language::SysML);
 
-    let kerml = LspServerDefaults::for_language(Language::KerML, &root);
+    let kerml = LspServerDefaults::for_language(&Language::KerML, &root);
     assert_eq!(kerml.language, Language::KerML);
 }
 
@@ -779,7 +915,7 @@ fn test_defaults_with_very_long_path() {
     );
     let root = PathBuf::from(long_path);
 
-    let config = LspServerDefaults::for_language(Language::Rust, &root);
+    let config = LspServerDefaults::for_language(&Language::Rust, &root);
     assert_eq!(config.root_path, root);
 }
 
@@ -795,7 +931,7 @@ fn test_defaults_with_special_characters_in_path() {
     ];
 
     for root in special_paths {
-        let config = LspServerDefaults::for_language(Language::Python, &root);
+        let config = LspServerDefaults::for_language(&Language::Python, &root);
         assert_eq!(config.root_path, root);
     }
 }
@@ -804,7 +940,7 @@ fn test_defaults_with_special_characters_in_path() {
 fn test_sysml_init_options_structure() {
     // Test that SysML init_options have the correct structure
     let root = PathBuf::from("/test");
-    let config = LspServerDefaults::for_language(Language::SysML, &root);
+    let config = LspServerDefaults::for_language(&Language::SysML, &root);
 
     assert!(config.init_options.is_some());
     if let Some(options) = config.init_options {
@@ -827,7 +963,7 @@ fn test_sysml_init_options_structure() {
 fn test_kerml_init_options_structure() {
     // Test that KerML init_options have the correct structure
     let root = PathBuf::from("/test");
-    let config = LspServerDefaults::for_language(Language::KerML, &root);
+    let config = LspServerDefaults::for_language(&Language::KerML, &root);
 
     assert!(config.init_options.is_some());
     if let Some(options) = config.init_options {
@@ -860,7 +996,7 @@ fn test_non_sysml_languages_no_init_options() {
     ];
 
     for language in languages {
-        let config = LspServerDefaults::for_language(language, &root);
+        let config = LspServerDefaults::for_language(&language, &root);
         assert!(
             config.init_options.is_none(),
             "Language {:?} should not have init_options",
@@ -885,7 +1021,7 @@ fn test_all_languages_have_nonempty_command() {
     ];
 
     for language in languages {
-        let config = LspServerDefaults::for_language(language, &root);
+        let config = LspServerDefaults::for_language(&language, &root);
         assert!(
             !config.command.is_empty(),
             "Language {:?} should have a non-empty command",
@@ -917,7 +1053,7 @@ fn test_all_languages_preserve_exact_root_path() {
 
     for path in test_paths {
         for language in &languages {
-            let config = LspServerDefaults::for_language(*language, &path);
+            let config = LspServerDefaults::for_language(language, &path);
             assert_eq!(
                 config.root_path, path,
                 "Root path should be preserved exactly for {:?}",
@@ -932,8 +1068,8 @@ fn test_rust_config_consistency() {
     // Test multiple calls return consistent results
     let root = PathBuf::from("/test");
 
-    let config1 = LspServerDefaults::for_language(Language::Rust, &root);
-    let config2 = LspServerDefaults::for_language(Language::Rust, &root);
+    let config1 = LspServerDefaults::for_language(&Language::Rust, &root);
+    let config2 = LspServerDefaults::for_language(&Language::Rust, &root);
 
     assert_eq!(config1.language, config2.language);
     assert_eq!(config1.command, config2.command);
@@ -946,8 +1082,8 @@ fn test_python_config_consistency() {
     // Test multiple calls return consistent results
     let root = PathBuf::from("/test");
 
-    let config1 = LspServerDefaults::for_language(Language::Python, &root);
-    let config2 = LspServerDefaults::for_language(Language::Python, &root);
+    let config1 = LspServerDefaults::for_language(&Language::Python, &root);
+    let config2 = LspServerDefaults::for_language(&Language::Python, &root);
 
     assert_eq!(config1.language, config2.language);
     assert_eq!(config1.command, config2.command);
@@ -0,0 +1,47 @@
+//! Tests for LSP request metrics
+
+use std::time::{Duration, Instant};
+
+use crate::lsp::{LspServerManager, RequestMetrics, RequestStats};
+
+#[test]
+fn test_record_counts_requests_and_failures() {
+    let metrics = RequestMetrics::default();
+    metrics.record(Instant::now(), true);
+    metrics.record(Instant::now(), false);
+
+    let stats = metrics.snapshot();
+    assert_eq!(stats.requests, 2);
+    assert_eq!(stats.failures, 1);
+}
+
+#[test]
+fn test_since_subtracts_earlier_snapshot() {
+    let earlier = RequestStats {
+        requests: 3,
+        failures: 1,
+        total_latency: Duration::from_millis(30),
+    };
+    let later = RequestStats {
+        requests: 7,
+        failures: 1,
+        total_latency: Duration::from_millis(110),
+    };
+
+    let delta = later.since(&earlier);
+    assert_eq!(delta.requests, 4);
+    assert_eq!(delta.failures, 0);
+    assert_eq!(delta.total_latency, Duration::from_millis(80));
+    assert_eq!(delta.average_latency(), Duration::from_millis(20));
+}
+
+#[test]
+fn test_average_latency_without_requests_is_zero() {
+    assert_eq!(RequestStats::default().average_latency(), Duration::ZERO);
+}
+
+#[test]
+fn test_manager_starts_with_no_requests() {
+    let manager = LspServerManager::new("/tmp");
+    assert_eq!(manager.request_stats(), RequestStats::default());
+}
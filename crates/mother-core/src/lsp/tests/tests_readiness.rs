@@ -0,0 +1,24 @@
+//! Tests for the readiness module
+
+use crate::lsp::Readiness;
+use crate::scanner::Language;
+
+#[test]
+fn test_rust_waits_for_indexing() {
+    assert_eq!(
+        Readiness::for_language(&Language::Rust),
+        Readiness::Indexing
+    );
+}
+
+#[test]
+fn test_other_languages_wait_for_progress_to_settle() {
+    for language in [
+        Language::Python,
+        Language::TypeScript,
+        Language::JavaScript,
+        Language::Go,
+    ] {
+        assert_eq!(Readiness::for_language(&language), Readiness::Progress);
+    }
+}
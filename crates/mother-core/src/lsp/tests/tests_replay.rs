@@ -0,0 +1,116 @@
+//! Tests for answering from recorded traffic
+
+use serde_json::{json, Value};
+
+use crate::lsp::replay::Recording;
+use crate::lsp::trace::Direction;
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+fn response(id: u64, result: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "result": result})
+}
+
+fn hover_recording() -> Recording {
+    Recording::from_records(vec![
+        (
+            Direction::Sent,
+            request(1, "textDocument/hover", json!({"line": 1})),
+        ),
+        (
+            Direction::Sent,
+            request(2, "textDocument/hover", json!({"line": 2})),
+        ),
+        // Answered out of order, as concurrent requests are
+        (Direction::Received, response(2, json!("two"))),
+        (Direction::Received, response(1, json!("one"))),
+    ])
+}
+
+#[test]
+fn test_answer_uses_response_recorded_for_same_params() {
+    let mut recording = hover_recording();
+
+    let answers = recording.answer(&request(7, "textDocument/hover", json!({"line": 2})));
+    assert_eq!(answers, vec![response(7, json!("two"))]);
+    let answers = recording.answer(&request(8, "textDocument/hover", json!({"line": 1})));
+    assert_eq!(answers, vec![response(8, json!("one"))]);
+}
+
+#[test]
+fn test_answer_falls_back_to_method_responses_in_turn() {
+    let mut recording = hover_recording();
+
+    let answers: Vec<Value> = (0..3)
+        .flat_map(|id| recording.answer(&request(id, "textDocument/hover", json!({"line": 9}))))
+        .collect();
+    assert_eq!(
+        answers,
+        vec![
+            response(0, json!("one")),
+            response(1, json!("two")),
+            response(2, json!("one")),
+        ]
+    );
+}
+
+#[test]
+fn test_unrecorded_request_gets_error() {
+    let mut recording = hover_recording();
+
+    let answers = recording.answer(&request(3, "textDocument/definition", json!({})));
+    assert_eq!(answers.len(), 1);
+    assert_eq!(answers[0]["id"], 3);
+    assert!(answers[0]["error"]["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("textDocument/definition"));
+}
+
+#[test]
+fn test_unrecorded_shutdown_succeeds() {
+    let mut recording = Recording::default();
+
+    let answers = recording.answer(&request(4, "shutdown", Value::Null));
+    assert_eq!(answers, vec![response(4, Value::Null)]);
+}
+
+#[test]
+fn test_server_messages_follow_the_client_message_before_them() {
+    let diagnostics = notification("textDocument/publishDiagnostics", json!({"uri": "a"}));
+    let mut recording = Recording::from_records(vec![
+        (
+            Direction::Sent,
+            notification("textDocument/didOpen", json!({"uri": "a"})),
+        ),
+        (Direction::Received, diagnostics.clone()),
+    ]);
+
+    let answers = recording.answer(&notification("textDocument/didOpen", json!({"uri": "a"})));
+    assert_eq!(answers, vec![diagnostics]);
+    assert!(recording
+        .answer(&notification("textDocument/didClose", json!({"uri": "a"})))
+        .is_empty());
+}
+
+#[test]
+fn test_client_responses_are_not_answered() {
+    let create = json!({"jsonrpc": "2.0", "id": 0, "method": "window/workDoneProgress/create"});
+    let mut recording = Recording::from_records(vec![
+        (Direction::Sent, notification("initialized", json!({}))),
+        (Direction::Received, create.clone()),
+        (Direction::Sent, response(0, Value::Null)),
+    ]);
+
+    assert_eq!(
+        recording.answer(&notification("initialized", json!({}))),
+        vec![create]
+    );
+    assert!(recording.answer(&response(0, Value::Null)).is_empty());
+}
@@ -3,14 +3,16 @@
 use std::ops::ControlFlow;
 
 use async_lsp::lsp_types::{
-    LogMessageParams, MessageType, ProgressParams, ProgressParamsValue, ProgressToken,
-    PublishDiagnosticsParams, ShowMessageParams, WorkDoneProgress, WorkDoneProgressBegin,
-    WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    Diagnostic, DiagnosticSeverity, LogMessageParams, MessageType, NumberOrString, Position,
+    ProgressParams, ProgressParamsValue, ProgressToken, PublishDiagnosticsParams, Range,
+    ShowMessageParams, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams,
+    WorkDoneProgressEnd, WorkDoneProgressReport,
 };
 use async_lsp::LanguageClient;
 use futures::channel::oneshot;
+use tokio::sync::watch;
 
-use crate::lsp::state::ClientState;
+use crate::lsp::state::{ClientState, ProgressStatus, PublishedDiagnostics, ReloadStatus};
 
 #[test]
 #[allow(clippy::expect_used)]
@@ -198,6 +200,42 @@ fn test_publish_diagnostics_returns_continue() {
     assert!(matches!(result, ControlFlow::Continue(())));
 }
 
+#[test]
+#[allow(clippy::expect_used)]
+fn test_publish_diagnostics_stores_latest_per_document() {
+    // Test that each publish replaces the document's diagnostics and take drains them
+    let mut state = ClientState::new_for_test(None);
+    let uri: async_lsp::lsp_types::Url = "file:///test.rs".parse().expect("valid URI");
+    let diagnostic = |message: &str| Diagnostic {
+        range: Range::new(Position::new(2, 4), Position::new(2, 9)),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::Number(6133)),
+        source: Some("ts".to_string()),
+        message: message.to_string(),
+        ..Diagnostic::default()
+    };
+
+    for message in ["first", "second"] {
+        let _ = state.publish_diagnostics(PublishDiagnosticsParams {
+            uri: uri.clone(),
+            diagnostics: vec![diagnostic(message)],
+            version: None,
+        });
+    }
+
+    let published = state.diagnostics();
+    let diagnostics = published.take(&uri);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "second");
+    assert_eq!(diagnostics[0].severity, "warning");
+    assert_eq!(diagnostics[0].code.as_deref(), Some("6133"));
+    assert_eq!(
+        (diagnostics[0].start_line, diagnostics[0].start_col),
+        (2, 4)
+    );
+    assert!(published.take(&uri).is_empty());
+}
+
 #[test]
 fn test_show_message_returns_continue() {
     // Test that show_message returns Continue
@@ -300,7 +338,10 @@ async fn test_work_done_progress_create_returns_ok() {
 fn test_new_router_creates_router() {
     // Test that new_router creates a router with the correct state
     let (tx, _rx) = oneshot::channel();
-    let _router = ClientState::new_router(tx);
+    let (reload_tx, _reload_rx) = watch::channel(ReloadStatus::default());
+    let (progress_tx, _progress_rx) = watch::channel(ProgressStatus::default());
+    let _router =
+        ClientState::new_router(tx, reload_tx, progress_tx, PublishedDiagnostics::default());
 
     // Router should be created successfully
     // We can't directly inspect the router internals, but we can verify it compiles
@@ -315,8 +356,148 @@ fn test_on_stop_through_router_creation() {
 
     // Create a router to ensure on_stop is properly registered
     let (tx, _rx) = oneshot::channel();
-    let _router = ClientState::new_router(tx);
+    let (reload_tx, _reload_rx) = watch::channel(ReloadStatus::default());
+    let (progress_tx, _progress_rx) = watch::channel(ProgressStatus::default());
+    let _router =
+        ClientState::new_router(tx, reload_tx, progress_tx, PublishedDiagnostics::default());
 
     // The on_stop handler is registered in new_router via router.event(Self::on_stop)
     // This test ensures the code compiles and the router can be created with the handler
 }
+
+// ============================================================================
+// Workspace reload tracking
+// ============================================================================
+
+fn begin(token: &str) -> ProgressParams {
+    ProgressParams {
+        token: ProgressToken::String(token.to_string()),
+        value: ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: token.to_string(),
+            cancellable: None,
+            message: None,
+            percentage: None,
+        })),
+    }
+}
+
+fn end(token: &str) -> ProgressParams {
+    ProgressParams {
+        token: ProgressToken::String(token.to_string()),
+        value: ProgressParamsValue::WorkDone(WorkDoneProgress::End(WorkDoneProgressEnd {
+            message: None,
+        })),
+    }
+}
+
+#[test]
+fn test_reload_begin_starts_new_generation() {
+    let mut state = ClientState::new_for_test(None);
+    assert_eq!(state.reload_status(), ReloadStatus::default());
+
+    let _ = state.progress(begin("rustAnalyzer/Fetching"));
+    let status = state.reload_status();
+    assert_eq!(status.generation, 1);
+    assert!(status.in_progress());
+
+    let _ = state.progress(end("rustAnalyzer/Fetching"));
+    let status = state.reload_status();
+    assert_eq!(status.generation, 1);
+    assert!(!status.in_progress());
+}
+
+#[test]
+fn test_overlapping_reload_tokens_are_one_reload() {
+    let mut state = ClientState::new_for_test(None);
+
+    let _ = state.progress(begin("rustAnalyzer/Fetching"));
+    let _ = state.progress(begin("rustAnalyzer/Loading"));
+    let _ = state.progress(end("rustAnalyzer/Fetching"));
+    assert!(state.reload_status().in_progress());
+
+    let _ = state.progress(end("rustAnalyzer/Loading"));
+    assert_eq!(
+        state.reload_status(),
+        ReloadStatus {
+            generation: 1,
+            active: 0
+        }
+    );
+}
+
+#[test]
+fn test_each_reload_bumps_generation() {
+    let mut state = ClientState::new_for_test(None);
+
+    for _ in 0..3 {
+        let _ = state.progress(begin("rustAnalyzer/Fetching"));
+        let _ = state.progress(end("rustAnalyzer/Fetching"));
+    }
+    assert_eq!(state.reload_status().generation, 3);
+}
+
+#[test]
+fn test_indexing_progress_is_not_a_reload() {
+    let mut state = ClientState::new_for_test(None);
+
+    let _ = state.progress(begin("rustAnalyzer/Indexing"));
+    let _ = state.progress(begin("some/other/token"));
+    assert_eq!(state.reload_status(), ReloadStatus::default());
+}
+
+#[test]
+fn test_unmatched_reload_end_is_ignored() {
+    let mut state = ClientState::new_for_test(None);
+
+    let _ = state.progress(end("rustAnalyzer/Loading"));
+    assert_eq!(state.reload_status(), ReloadStatus::default());
+}
+
+// ============================================================================
+// Work-done progress tracking
+// ============================================================================
+
+fn report(token: &str) -> ProgressParams {
+    ProgressParams {
+        token: ProgressToken::String(token.to_string()),
+        value: ProgressParamsValue::WorkDone(WorkDoneProgress::Report(
+            WorkDoneProgressReport::default(),
+        )),
+    }
+}
+
+#[test]
+fn test_progress_of_any_token_is_tracked() {
+    let mut state = ClientState::new_for_test(None);
+    assert_eq!(state.progress_status(), ProgressStatus::default());
+
+    let _ = state.progress(begin("pyright/analysis"));
+    let _ = state.progress(begin("gopls/loading"));
+    let _ = state.progress(report("gopls/loading"));
+    assert_eq!(
+        state.progress_status(),
+        ProgressStatus {
+            changes: 3,
+            active: 2
+        }
+    );
+
+    let _ = state.progress(end("pyright/analysis"));
+    let _ = state.progress(end("gopls/loading"));
+    assert_eq!(
+        state.progress_status(),
+        ProgressStatus {
+            changes: 5,
+            active: 0
+        }
+    );
+}
+
+#[test]
+fn test_unmatched_progress_end_keeps_none_active() {
+    let mut state = ClientState::new_for_test(None);
+
+    let _ = state.progress(end("tsserver/initializing"));
+    assert_eq!(state.progress_status().active, 0);
+    assert_eq!(state.progress_status().changes, 1);
+}
@@ -0,0 +1,121 @@
+//! Tests for the trace module
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use futures::io::Cursor;
+use futures::{AsyncReadExt, AsyncWriteExt};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+use crate::lsp::trace::{redact, Direction, Framer, LspTrace, Traced};
+
+fn frame(body: &str) -> Vec<u8> {
+    format!("Content-Length: {}\r\n\r\n{body}", body.len()).into_bytes()
+}
+
+fn read_trace(path: &std::path::Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_framer_splits_messages() {
+    let mut bytes = frame(r#"{"id":1}"#);
+    bytes.extend(frame(r#"{"id":2}"#));
+
+    let bodies = Framer::default().push(&bytes);
+    assert_eq!(
+        bodies,
+        vec![br#"{"id":1}"#.to_vec(), br#"{"id":2}"#.to_vec()]
+    );
+}
+
+#[test]
+fn test_framer_waits_for_the_rest_of_a_message() {
+    let body = r#"{"jsonrpc":"2.0","id":1}"#;
+    let bytes = frame(body);
+    let mut framer = Framer::default();
+
+    assert!(framer.push(&bytes[..10]).is_empty());
+    assert!(framer.push(&bytes[10..30]).is_empty());
+    assert_eq!(framer.push(&bytes[30..]), vec![body.as_bytes().to_vec()]);
+}
+
+#[test]
+fn test_framer_reads_headers_in_any_case_and_order() {
+    let bytes =
+        b"content-type: application/vscode-jsonrpc; charset=utf-8\r\ncontent-length: 2\r\n\r\n{}";
+    assert_eq!(Framer::default().push(bytes), vec![b"{}".to_vec()]);
+}
+
+#[test]
+fn test_redact_replaces_secret_values_at_any_depth() {
+    let mut message = json!({
+        "params": {
+            "initializationOptions": {
+                "apiKey": "sk-123",
+                "registry": {"password": "hunter2", "user": "me"},
+                "servers": [{"Authorization": "Bearer abc"}],
+            },
+            "token": "rustAnalyzer/Indexing",
+        }
+    });
+    redact(&mut message);
+
+    assert_eq!(
+        message,
+        json!({
+            "params": {
+                "initializationOptions": {
+                    "apiKey": "[redacted]",
+                    "registry": {"password": "[redacted]", "user": "me"},
+                    "servers": [{"Authorization": "[redacted]"}],
+                },
+                "token": "rustAnalyzer/Indexing",
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_traced_streams_log_both_directions() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("rust.jsonl");
+    let trace = Arc::new(LspTrace::create(&path).unwrap());
+
+    let response = frame(r#"{"jsonrpc":"2.0","id":1,"result":null}"#);
+    let mut input = Traced::new(
+        Cursor::new(response.clone()),
+        Some(Arc::clone(&trace)),
+        Direction::Received,
+    );
+    let mut output = Traced::new(Vec::new(), Some(trace), Direction::Sent);
+
+    let request = frame(r#"{"jsonrpc":"2.0","id":1,"method":"shutdown","params":{"secret":"s"}}"#);
+    output.write_all(&request).await.unwrap();
+    let mut received = Vec::new();
+    input.read_to_end(&mut received).await.unwrap();
+    assert_eq!(received, response);
+
+    let lines = read_trace(&path);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["direction"], "sent");
+    assert_eq!(lines[0]["message"]["method"], "shutdown");
+    assert_eq!(lines[0]["message"]["params"]["secret"], "[redacted]");
+    assert_eq!(lines[1]["direction"], "received");
+    assert_eq!(lines[1]["message"]["id"], 1);
+    assert!(lines[1]["ms"].is_u64());
+}
+
+#[tokio::test]
+async fn test_untraced_streams_pass_bytes_through() {
+    let mut input = Traced::new(Cursor::new(frame("{}")), None, Direction::Received);
+    let mut received = Vec::new();
+    input.read_to_end(&mut received).await.unwrap();
+    assert_eq!(received, frame("{}"));
+}
@@ -0,0 +1,227 @@
+//! Trace: JSON-RPC traffic between a client and its server, logged to a file
+//!
+//! Each message sent or received is written as one JSON line with its
+//! direction and the milliseconds since the trace began. Values of keys that
+//! name secrets (passwords, API keys, access tokens) are redacted, so traces
+//! of servers given credentials in their init options can be shared.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::{AsyncRead, AsyncWrite};
+use serde_json::{json, Value};
+
+/// Replacement for redacted values
+const REDACTED: &str = "[redacted]";
+
+/// Key fragments, lowercase, of values redacted from traces
+///
+/// A bare `token` is left alone: progress and work-done tokens are not
+/// secrets, and are what a trace is read for.
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "apikey",
+    "api_key",
+    "accesstoken",
+    "access_token",
+    "authtoken",
+    "auth_token",
+    "authorization",
+    "credential",
+    "privatekey",
+    "private_key",
+];
+
+/// Which way a message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Direction {
+    /// From the client to the server
+    Sent,
+    /// From the server to the client
+    Received,
+}
+
+impl Direction {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Received => "received",
+        }
+    }
+}
+
+/// Trace file shared by both directions of one server's traffic
+pub(super) struct LspTrace {
+    started: Instant,
+    file: Mutex<BufWriter<File>>,
+}
+
+impl LspTrace {
+    /// Create, or truncate, the trace file at `path`
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created.
+    pub(super) fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            started: Instant::now(),
+            file: Mutex::new(BufWriter::new(File::create(path)?)),
+        })
+    }
+
+    /// Write one message body as a trace line
+    fn record(&self, direction: Direction, body: &[u8]) {
+        let message = match serde_json::from_slice::<Value>(body) {
+            Ok(mut message) => {
+                redact(&mut message);
+                message
+            }
+            Err(e) => {
+                tracing::debug!("Untraceable LSP message of {} bytes: {e}", body.len());
+                return;
+            }
+        };
+        let line = json!({
+            "ms": u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            "direction": direction.name(),
+            "message": message,
+        });
+
+        // Only touched in short non-panicking sections
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        let written = serde_json::to_writer(&mut *file, &line)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(file))
+            .and_then(|()| file.flush());
+        if let Err(e) = written {
+            tracing::warn!("Failed to write LSP trace: {e}");
+        }
+    }
+}
+
+/// Replace the values of secret-named keys, at any depth
+pub(super) fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Splits a byte stream into message bodies by their `Content-Length`
+#[derive(Debug, Default)]
+pub(super) struct Framer {
+    buffer: Vec<u8>,
+}
+
+impl Framer {
+    /// Add bytes from the stream, returning the bodies they complete
+    pub(super) fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut bodies = Vec::new();
+        while let Some(header_end) = find(&self.buffer, b"\r\n\r\n") {
+            let Some(length) = content_length(&self.buffer[..header_end]) else {
+                // Not a header we understand; resynchronise on the next one
+                self.buffer.drain(..header_end + 4);
+                continue;
+            };
+            let body_start = header_end + 4;
+            if self.buffer.len() < body_start + length {
+                break;
+            }
+            bodies.push(self.buffer[body_start..body_start + length].to_vec());
+            self.buffer.drain(..body_start + length);
+        }
+        bodies
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn content_length(header: &[u8]) -> Option<usize> {
+    std::str::from_utf8(header).ok()?.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("content-length")
+            .then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Server stdin or stdout, with the traffic through it copied to a trace
+pub(super) struct Traced<T> {
+    inner: T,
+    trace: Option<(Arc<LspTrace>, Direction, Framer)>,
+}
+
+impl<T> Traced<T> {
+    /// Wrap a stream; without a trace, bytes pass through untouched
+    pub(super) fn new(inner: T, trace: Option<Arc<LspTrace>>, direction: Direction) -> Self {
+        Self {
+            inner,
+            trace: trace.map(|trace| (trace, direction, Framer::default())),
+        }
+    }
+
+    fn record(&mut self, bytes: &[u8]) {
+        if let Some((trace, direction, framer)) = &mut self.trace {
+            for body in framer.push(bytes) {
+                trace.record(*direction, &body);
+            }
+        }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Traced<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let read = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = read {
+            self.record(&buf[..n]);
+        }
+        read
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Traced<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let written = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = written {
+            self.record(&buf[..n]);
+        }
+        written
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
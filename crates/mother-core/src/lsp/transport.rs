@@ -0,0 +1,65 @@
+//! Transport: How a client reaches its language server
+//!
+//! [`StdioTransport`] spawns the server and talks over its stdin and stdout,
+//! the way every client runs by default. Other transports, such as
+//! [`ReplayTransport`](super::ReplayTransport), stand in for the server
+//! process behind the same [`LspTransport`] trait.
+
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use futures::{AsyncRead, AsyncWrite};
+
+use super::types::LspServerConfig;
+
+/// Streams to and from a language server
+pub struct Connection {
+    /// Messages from the server
+    pub input: Box<dyn AsyncRead + Send + Unpin>,
+    /// Messages to the server
+    pub output: Box<dyn AsyncWrite + Send + Unpin>,
+    /// The server process, killed when the connection is dropped
+    pub process: Option<async_process::Child>,
+}
+
+/// Connects clients to the server of a config
+pub trait LspTransport: Send + Sync {
+    /// Open a connection to the server of `config`
+    ///
+    /// # Errors
+    /// Returns an error if the server cannot be started or reached.
+    fn connect(&self, config: &LspServerConfig) -> Result<Connection>;
+}
+
+/// Runs the configured server command and talks over its stdin and stdout
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdioTransport;
+
+impl LspTransport for StdioTransport {
+    fn connect(&self, config: &LspServerConfig) -> Result<Connection> {
+        let mut child = async_process::Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(&config.root_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start LSP server {}", config.command))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get stdout from LSP process"))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get stdin from LSP process"))?;
+
+        Ok(Connection {
+            input: Box::new(stdout),
+            output: Box::new(stdin),
+            process: Some(child),
+        })
+    }
+}
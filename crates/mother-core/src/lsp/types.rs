@@ -73,6 +73,26 @@ pub struct LspReference {
     pub end_col: u32,
 }
 
+/// A diagnostic published by an LSP server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspDiagnostic {
+    /// `error`, `warning`, `information` or `hint`
+    pub severity: String,
+    /// Diagnostic code, as text
+    pub code: Option<String>,
+    /// Tool that produced the diagnostic
+    pub source: Option<String>,
+    pub message: String,
+    /// Start line (0-indexed)
+    pub start_line: u32,
+    /// Start column
+    pub start_col: u32,
+    /// End line (0-indexed)
+    pub end_line: u32,
+    /// End column
+    pub end_col: u32,
+}
+
 /// Configuration for an LSP server
 #[derive(Debug, Clone)]
 pub struct LspServerConfig {
@@ -0,0 +1,134 @@
+//! Progress module: UI-agnostic progress events for long-running scans
+//!
+//! Scan phases report what they are doing through a [`ProgressReporter`].
+//! The library only emits [`ProgressEvent`]s; front ends decide how (and
+//! whether) to show them, e.g. as terminal progress bars.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A stage of a scan that reports progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScanPhase {
+    /// Phase 1: linking stored files and opening new ones in the language
+    /// server
+    Files,
+    /// Phase 2: extracting symbols and storing new files with them
+    Symbols,
+    /// Linking files through their imports
+    Imports,
+    /// Phase 3: resolving references between symbols
+    References,
+}
+
+impl ScanPhase {
+    /// Short human-readable description
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Files => "Opening files",
+            Self::Imports => "Linking imports",
+            Self::Symbols => "Extracting symbols",
+            Self::References => "Resolving references",
+        }
+    }
+
+    /// What one unit of work in the phase is
+    #[must_use]
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Files | Self::Imports | Self::Symbols => "files",
+            Self::References => "symbols",
+        }
+    }
+}
+
+impl fmt::Display for ScanPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// A progress update from a scan phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A phase began with `total` units of work
+    Started { phase: ScanPhase, total: u64 },
+    /// One unit of work finished, producing symbols or references
+    Advanced {
+        phase: ScanPhase,
+        symbols: u64,
+        references: u64,
+    },
+    /// A phase finished
+    Finished { phase: ScanPhase },
+}
+
+/// Receives progress events
+///
+/// Events arrive on the scanning task, so implementations should return
+/// quickly.
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: ProgressEvent);
+}
+
+/// Reporter that ignores all events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _event: ProgressEvent) {}
+}
+
+/// Shared handle to a reporter, cheap to clone into scan options
+#[derive(Clone)]
+pub struct Progress(Arc<dyn ProgressReporter>);
+
+impl Progress {
+    #[must_use]
+    pub fn new(reporter: impl ProgressReporter + 'static) -> Self {
+        Self(Arc::new(reporter))
+    }
+
+    /// Report that a phase started with `total` units of work
+    pub fn start(&self, phase: ScanPhase, total: usize) {
+        self.0.report(ProgressEvent::Started {
+            phase,
+            total: total as u64,
+        });
+    }
+
+    /// Report one finished unit of work
+    pub fn advance(&self, phase: ScanPhase, symbols: usize, references: usize) {
+        self.0.report(ProgressEvent::Advanced {
+            phase,
+            symbols: symbols as u64,
+            references: references as u64,
+        });
+    }
+
+    /// Report that a phase finished
+    pub fn finish(&self, phase: ScanPhase) {
+        self.0.report(ProgressEvent::Finished { phase });
+    }
+
+    /// Pass on an event, e.g. from a reporter wrapping this one
+    pub fn report(&self, event: ProgressEvent) {
+        self.0.report(event);
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new(NoProgress)
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,77 @@
+//! Tests for progress events and reporters
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::{Arc, Mutex};
+
+use crate::progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
+
+/// Reporter recording every event it receives
+#[derive(Default, Clone)]
+struct Recorder(Arc<Mutex<Vec<ProgressEvent>>>);
+
+impl ProgressReporter for Recorder {
+    fn report(&self, event: ProgressEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+fn test_progress_forwards_events_in_order() {
+    let recorder = Recorder::default();
+    let progress = Progress::new(recorder.clone());
+
+    progress.start(ScanPhase::Symbols, 2);
+    progress.advance(ScanPhase::Symbols, 5, 0);
+    progress.advance(ScanPhase::Symbols, 3, 0);
+    progress.finish(ScanPhase::Symbols);
+
+    let events = recorder.0.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            ProgressEvent::Started {
+                phase: ScanPhase::Symbols,
+                total: 2
+            },
+            ProgressEvent::Advanced {
+                phase: ScanPhase::Symbols,
+                symbols: 5,
+                references: 0
+            },
+            ProgressEvent::Advanced {
+                phase: ScanPhase::Symbols,
+                symbols: 3,
+                references: 0
+            },
+            ProgressEvent::Finished {
+                phase: ScanPhase::Symbols
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_clones_share_the_reporter() {
+    let recorder = Recorder::default();
+    let progress = Progress::new(recorder.clone());
+
+    progress.clone().finish(ScanPhase::Files);
+    assert_eq!(recorder.0.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_default_progress_ignores_events() {
+    let progress = Progress::default();
+    progress.start(ScanPhase::Files, 10);
+    progress.advance(ScanPhase::Files, 0, 0);
+    progress.finish(ScanPhase::Files);
+    assert_eq!(format!("{progress:?}"), "Progress { .. }");
+}
+
+#[test]
+fn test_phase_labels_and_units() {
+    assert_eq!(ScanPhase::Files.to_string(), "Opening files");
+    assert_eq!(ScanPhase::References.unit(), "symbols");
+    assert_eq!(ScanPhase::Symbols.unit(), "files");
+}
@@ -0,0 +1,222 @@
+//! File content cache: One read of a file for every phase that needs it
+//!
+//! Phase 1 hashes each file and opens it on its language server, and the
+//! imports pass then extracts its import statements. A [`FileContentCache`]
+//! reads a file once and hands the same content to each of them, keeping
+//! the most recently used files up to a size budget. Files at or above a
+//! size threshold are memory-mapped instead of read, so the OS pages them
+//! in rather than the scan copying them.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use memmap2::Mmap;
+
+use super::walker::content_hash;
+use crate::graph::model::FileCacheStats;
+
+/// Bytes of file content kept by default
+pub const DEFAULT_CACHE_CAPACITY: usize = 256 * 1024 * 1024;
+
+/// Size from which files are memory-mapped by default
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// The content of a file, read or memory-mapped
+#[derive(Debug)]
+pub struct FileContent {
+    data: Data,
+}
+
+#[derive(Debug)]
+enum Data {
+    Read(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl FileContent {
+    /// The raw bytes
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        match &self.data {
+            Data::Read(bytes) => bytes,
+            Data::Mapped(map) => map,
+        }
+    }
+
+    /// The content as text
+    ///
+    /// # Errors
+    /// Returns an `InvalidData` error if the content is not UTF-8, as
+    /// [`std::fs::read_to_string`] does.
+    pub fn text(&self) -> io::Result<&str> {
+        std::str::from_utf8(self.bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// SHA-256 hash of the content, as stored with File nodes
+    #[must_use]
+    pub fn hash(&self) -> String {
+        content_hash(self.bytes())
+    }
+
+    /// Size in bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes().len()
+    }
+
+    /// Whether the file is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Size-bounded cache of file contents, shared by the phases of a scan
+#[derive(Debug)]
+pub struct FileContentCache {
+    /// Most bytes of content kept
+    capacity: usize,
+    /// Size from which files are memory-mapped
+    mmap_threshold: u64,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bytes_read: AtomicU64,
+    mapped: AtomicU64,
+}
+
+/// Cached files, with the tick each was last used at
+#[derive(Debug, Default)]
+struct Entries {
+    files: HashMap<PathBuf, (Arc<FileContent>, u64)>,
+    size: usize,
+    tick: u64,
+}
+
+impl Default for FileContentCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY, DEFAULT_MMAP_THRESHOLD)
+    }
+}
+
+impl FileContentCache {
+    /// A cache keeping up to `capacity` bytes, memory-mapping files of
+    /// `mmap_threshold` bytes or more
+    #[must_use]
+    pub fn new(capacity: usize, mmap_threshold: u64) -> Self {
+        Self {
+            capacity,
+            mmap_threshold,
+            entries: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bytes_read: AtomicU64::new(0),
+            mapped: AtomicU64::new(0),
+        }
+    }
+
+    /// The content of the file at `path`, from the cache if it was read
+    /// before
+    ///
+    /// Files larger than the whole cache are returned without being kept.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read.
+    pub fn read(&self, path: &Path) -> io::Result<Arc<FileContent>> {
+        if let Some(content) = self.entries().get(path) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(content);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let content = Arc::new(self.load(path)?);
+        if content.len() <= self.capacity {
+            self.entries().insert(path, &content, self.capacity);
+        }
+        Ok(content)
+    }
+
+    /// The text of the file at `path`, from the cache if it was read before
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not UTF-8, as
+    /// [`std::fs::read_to_string`] does.
+    pub fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        Ok(self.read(path)?.text()?.to_string())
+    }
+
+    /// Counts of the reads so far
+    #[must_use]
+    pub fn stats(&self) -> FileCacheStats {
+        FileCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            mapped: self.mapped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Read or map a file from disk
+    fn load(&self, path: &Path) -> io::Result<FileContent> {
+        let mut file = File::open(path)?;
+        let size = file.metadata()?.len();
+        self.bytes_read.fetch_add(size, Ordering::Relaxed);
+        if size > 0 && size >= self.mmap_threshold {
+            // SAFETY: The map is only read. Scans treat the files under
+            // their root as unchanging while they run; a file truncated
+            // while mapped can fault on access, like any mapped file.
+            let map = unsafe { Mmap::map(&file)? };
+            self.mapped.fetch_add(1, Ordering::Relaxed);
+            return Ok(FileContent {
+                data: Data::Mapped(map),
+            });
+        }
+        let mut bytes = Vec::with_capacity(usize::try_from(size).unwrap_or_default());
+        file.read_to_end(&mut bytes)?;
+        Ok(FileContent {
+            data: Data::Read(bytes),
+        })
+    }
+}
+
+impl Entries {
+    fn get(&mut self, path: &Path) -> Option<Arc<FileContent>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.files.get_mut(path).map(|(content, used)| {
+            *used = tick;
+            Arc::clone(content)
+        })
+    }
+
+    /// Keep `content`, evicting the least recently used files until the
+    /// cache is within `capacity` again
+    fn insert(&mut self, path: &Path, content: &Arc<FileContent>, capacity: usize) {
+        self.tick += 1;
+        let entry = (Arc::clone(content), self.tick);
+        if let Some((previous, _)) = self.files.insert(path.to_path_buf(), entry) {
+            self.size -= previous.len();
+        }
+        self.size += content.len();
+        while self.size > capacity {
+            let Some(oldest) = self
+                .files
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            if let Some((evicted, _)) = self.files.remove(&oldest) {
+                self.size -= evicted.len();
+            }
+        }
+    }
+}
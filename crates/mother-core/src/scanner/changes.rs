@@ -0,0 +1,91 @@
+//! Changed lines: Which lines of which files a commit changed
+//!
+//! Diffs the trees of two commits without context lines, keeping the line
+//! ranges each hunk covers on the new side. Lines removed between two
+//! remaining lines count as a change to both of them, so a symbol that only
+//! lost lines is still changed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use git2::{DiffOptions, Repository};
+
+use super::checkout::CheckoutError;
+
+/// Lines changed between two commits, per file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    /// 1-based inclusive line ranges by absolute file path
+    files: HashMap<PathBuf, Vec<(u32, u32)>>,
+}
+
+impl ChangedLines {
+    /// Whether a changed line of `path` lies in `start..=end` (1-based)
+    #[must_use]
+    pub fn touches(&self, path: &Path, start: u32, end: u32) -> bool {
+        self.files.get(path).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|(first, last)| *first <= end && start <= *last)
+        })
+    }
+
+    /// Number of files with changed lines
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+}
+
+/// Lines of the files under `scan_root` that changed from commit `from` to
+/// commit `to` of the repository at or above `repo`
+///
+/// Paths are resolved against the repository's worktree if `scan_root` lies
+/// in it, and against `scan_root` otherwise, as for a checkout of a bare
+/// repository.
+///
+/// # Errors
+/// Returns an error if the repository cannot be opened, a revision does not
+/// name a commit, or the diff fails.
+pub fn changed_lines(
+    repo: &Path,
+    scan_root: &Path,
+    from: &str,
+    to: &str,
+) -> Result<ChangedLines, CheckoutError> {
+    let repository = Repository::discover(repo)?;
+    let old = repository.revparse_single(from)?.peel_to_tree()?;
+    let new = repository.revparse_single(to)?.peel_to_tree()?;
+    let base = repository
+        .workdir()
+        .and_then(|dir| dir.canonicalize().ok())
+        .filter(|dir| scan_root.starts_with(dir))
+        .unwrap_or_else(|| scan_root.to_path_buf());
+
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    let diff = repository.diff_tree_to_tree(Some(&old), Some(&new), Some(&mut options))?;
+
+    let mut changes = ChangedLines::default();
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |delta, hunk| {
+            if let Some(path) = delta.new_file().path() {
+                let start = hunk.new_start();
+                let range = match hunk.new_lines() {
+                    0 => (start.max(1), start + 1),
+                    lines => (start, start + lines - 1),
+                };
+                changes
+                    .files
+                    .entry(base.join(path))
+                    .or_default()
+                    .push(range);
+            }
+            true
+        }),
+        None,
+    )?;
+    Ok(changes)
+}
@@ -0,0 +1,174 @@
+//! Commit checkout: Materialize a commit of a (bare) repository for scanning
+//!
+//! The scan pipeline works on files on disk, since language servers read
+//! them from there. Repositories without a worktree, such as bare mirrors,
+//! are scanned by writing the tree of one commit to a temporary directory
+//! that is removed again when the [`CommitCheckout`] is dropped.
+//!
+//! Stored file paths include the checkout directory, so it is derived from
+//! the repository path rather than random: scans of different commits of the
+//! same repository then store the same paths, and diffs and symbol history
+//! line up across them.
+//!
+//! [`commit_history`] lists the commits between two revisions, for scanning
+//! a stretch of history one commit at a time.
+
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, Repository};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::graph::model::ScanRun;
+
+/// Errors that can occur while materializing a commit
+#[derive(Debug, Error)]
+pub enum CheckoutError {
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(
+        "Checkout directory {0} already exists; another scan of this repository \
+         may be running, otherwise remove it"
+    )]
+    InUse(PathBuf),
+
+    #[error("{from} is not an ancestor of {to} along first parents")]
+    NotAncestor { from: String, to: String },
+}
+
+/// The tree of one commit, written to a temporary directory
+///
+/// The directory is removed when the checkout is dropped.
+#[derive(Debug)]
+pub struct CommitCheckout {
+    repo_path: PathBuf,
+    dir: PathBuf,
+    commit_sha: String,
+    branch: Option<String>,
+}
+
+impl CommitCheckout {
+    /// Write the tree of `rev` in the repository at `repo` to a temporary directory
+    ///
+    /// `rev` is anything git can resolve to a commit: a SHA or SHA prefix, a
+    /// branch or a tag. The repository's index and references are left
+    /// untouched; submodules are not checked out.
+    ///
+    /// # Errors
+    /// Returns an error if the repository cannot be opened, `rev` does not
+    /// name a commit, the checkout directory is in use or writing fails.
+    pub fn materialize(repo: &Path, rev: &str) -> Result<Self, CheckoutError> {
+        let repo_path = repo.canonicalize()?;
+        let repository = Repository::open(&repo_path)?;
+        let commit = repository.revparse_single(rev)?.peel_to_commit()?;
+        let branch = repository
+            .find_branch(rev, BranchType::Local)
+            .ok()
+            .map(|_| rev.to_string());
+
+        let dir = checkout_dir(&repo_path);
+        if let Err(e) = fs::create_dir(&dir) {
+            return Err(match e.kind() {
+                std::io::ErrorKind::AlreadyExists => CheckoutError::InUse(dir),
+                _ => e.into(),
+            });
+        }
+        let checkout = Self {
+            repo_path,
+            dir,
+            commit_sha: commit.id().to_string(),
+            branch,
+        };
+
+        let mut options = CheckoutBuilder::new();
+        options
+            .target_dir(&checkout.dir)
+            .update_index(false)
+            .force();
+        repository.checkout_tree(commit.as_object(), Some(&mut options))?;
+        Ok(checkout)
+    }
+
+    /// Directory holding the commit's files
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Full SHA of the checked out commit
+    #[must_use]
+    pub fn commit_sha(&self) -> &str {
+        &self.commit_sha
+    }
+
+    /// A scan run for this commit, recorded against the repository path
+    #[must_use]
+    pub fn scan_run(&self) -> ScanRun {
+        let run = ScanRun::new(self.repo_path.display().to_string()).with_commit(&self.commit_sha);
+        match &self.branch {
+            Some(branch) => run.with_branch(branch),
+            None => run,
+        }
+    }
+}
+
+impl Drop for CommitCheckout {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            tracing::warn!("Failed to remove checkout {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+/// The commits from `from` to `to`, oldest first, keeping every `step`th
+///
+/// History is followed along first parents, so merged branches contribute
+/// their merge commit only. `from` is always kept, as is `to` even when the
+/// step skips over it.
+///
+/// # Errors
+/// Returns an error if the repository cannot be opened, a revision does not
+/// name a commit, or `from` is not a first-parent ancestor of `to`.
+pub fn commit_history(
+    repo: &Path,
+    from: &str,
+    to: &str,
+    step: NonZeroUsize,
+) -> Result<Vec<String>, CheckoutError> {
+    let repository = Repository::open(repo)?;
+    let first = repository.revparse_single(from)?.peel_to_commit()?.id();
+    let mut commit = repository.revparse_single(to)?.peel_to_commit()?;
+
+    let mut commits = vec![commit.id()];
+    while commit.id() != first {
+        commit = commit.parent(0).map_err(|_| CheckoutError::NotAncestor {
+            from: from.to_string(),
+            to: to.to_string(),
+        })?;
+        commits.push(commit.id());
+    }
+    commits.reverse();
+
+    let last = commits.len() - 1;
+    Ok(commits
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % step.get() == 0 || *i == last)
+        .map(|(_, id)| id.to_string())
+        .collect())
+}
+
+/// Checkout directory for a repository, stable across runs
+fn checkout_dir(repo_path: &Path) -> PathBuf {
+    let digest = Sha256::digest(repo_path.to_string_lossy().as_bytes());
+    let hex = format!("{:x}", digest);
+    let short = hex.get(..16).unwrap_or(&hex);
+    std::env::temp_dir().join(format!("mother-checkout-{short}"))
+}
@@ -0,0 +1,516 @@
+//! Import extraction: Find import/use statements and resolve them to files
+//!
+//! Extraction is textual and per language, so it works without an LSP
+//! server. Resolution is best-effort: only imports that point at another
+//! discovered file in the repository resolve, external packages do not.
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use super::Language;
+
+/// A single import found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportStatement {
+    /// Imported module path as written (e.g. `crate::graph::model`, `./utils`, `os.path`)
+    pub module: String,
+    /// Line of the import statement (1-indexed)
+    pub line: u32,
+}
+
+impl ImportStatement {
+    fn new(module: impl Into<String>, line: usize) -> Self {
+        Self {
+            module: module.into(),
+            line: u32::try_from(line + 1).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// Extract the imports of a file from its content
+#[must_use]
+pub fn extract_imports(language: &Language, content: &str) -> Vec<ImportStatement> {
+    match language {
+        Language::Rust => extract_rust(content),
+        Language::Python => extract_python(content),
+        Language::TypeScript | Language::JavaScript => extract_js(content),
+        Language::Go => extract_go(content),
+        Language::Ruby => extract_ruby(content),
+        Language::SysML | Language::KerML => extract_sysml(content),
+        Language::Other(_) => Vec::new(),
+    }
+}
+
+/// Resolve an import to one of the known repository files
+///
+/// Returns `None` for imports of external packages or paths that don't
+/// match a known file.
+#[must_use]
+pub fn resolve_import(
+    language: &Language,
+    from: &Path,
+    module: &str,
+    files: &HashSet<PathBuf>,
+) -> Option<PathBuf> {
+    match language {
+        Language::Rust => resolve_rust(from, module, files),
+        Language::Python => resolve_python(from, module, files),
+        Language::TypeScript | Language::JavaScript => resolve_js(from, module, files),
+        Language::Ruby => resolve_ruby(from, module, files),
+        Language::Go | Language::SysML | Language::KerML | Language::Other(_) => None,
+    }
+}
+
+// ============================================================================
+// Rust
+// ============================================================================
+
+fn extract_rust(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = strip_line_comment(raw, "//");
+
+        if let Some((start, mut text)) = pending.take() {
+            text.push(' ');
+            text.push_str(line);
+            match text.find(';') {
+                Some(end) => push_rust_use(&mut imports, &text[..end], start),
+                None => pending = Some((start, text)),
+            }
+            continue;
+        }
+
+        let stmt = strip_rust_visibility(line.trim());
+        if let Some(rest) = stmt.strip_prefix("use ") {
+            match rest.find(';') {
+                Some(end) => push_rust_use(&mut imports, &rest[..end], i),
+                None => pending = Some((i, rest.to_string())),
+            }
+        } else if let Some(name) = stmt
+            .strip_prefix("mod ")
+            .and_then(|rest| rest.trim().strip_suffix(';'))
+        {
+            imports.push(ImportStatement::new(format!("self::{}", name.trim()), i));
+        } else if let Some(name) = stmt
+            .strip_prefix("extern crate ")
+            .and_then(|rest| rest.split([' ', ';']).next())
+        {
+            imports.push(ImportStatement::new(name, i));
+        }
+    }
+
+    imports
+}
+
+fn push_rust_use(imports: &mut Vec<ImportStatement>, tree: &str, line: usize) {
+    for path in expand_use_tree(tree.trim()) {
+        imports.push(ImportStatement::new(path, line));
+    }
+}
+
+fn strip_rust_visibility(stmt: &str) -> &str {
+    let Some(rest) = stmt.strip_prefix("pub") else {
+        return stmt;
+    };
+    if rest.starts_with('(') {
+        return rest
+            .find(')')
+            .map_or(stmt, |end| rest[end + 1..].trim_start());
+    }
+    if rest.starts_with(char::is_whitespace) {
+        return rest.trim_start();
+    }
+    stmt
+}
+
+/// Expand a `use` tree into the module paths it imports
+///
+/// `a::{b, c::{d, self}}` expands to `a::b`, `a::c::d` and `a::c`. Globs and
+/// renames are dropped (`a::*` is `a`, `a::B as C` is `a::B`).
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree = tree.trim().trim_start_matches("::");
+
+    let Some(open) = tree.find('{') else {
+        let path = tree.split(" as ").next().unwrap_or(tree).trim();
+        let path = path.trim_end_matches("::*").trim_end_matches('*');
+        return if path.is_empty() {
+            Vec::new()
+        } else {
+            vec![path.to_string()]
+        };
+    };
+
+    let prefix = tree[..open].trim().trim_end_matches("::");
+    let close = tree.rfind('}').unwrap_or(tree.len());
+    let inner = &tree[open + 1..close.max(open + 1)];
+
+    let mut paths = Vec::new();
+    for item in split_top_level(inner) {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        if item == "self" || item == "*" {
+            paths.push(prefix.to_string());
+            continue;
+        }
+        for sub in expand_use_tree(item) {
+            if prefix.is_empty() {
+                paths.push(sub);
+            } else {
+                paths.push(format!("{prefix}::{sub}"));
+            }
+        }
+    }
+    paths
+}
+
+/// Split on commas that are not nested inside braces
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn resolve_rust(from: &Path, module: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let mut segments: Vec<&str> = module.split("::").collect();
+    let base = match segments.first().copied()? {
+        "crate" => rust_crate_root(from, files)?,
+        "self" => rust_module_dir(from),
+        "super" => {
+            let mut dir = rust_module_dir(from);
+            while segments.get(1) == Some(&"super") {
+                segments.remove(0);
+                dir = dir.parent()?.to_path_buf();
+            }
+            dir.parent()?.to_path_buf()
+        }
+        _ => return None,
+    };
+    let segments = &segments[1..];
+
+    // The longest prefix that names a module file wins; trailing segments are items
+    let submodule = (1..=segments.len()).rev().find_map(|len| {
+        let rel: PathBuf = segments[..len].iter().collect();
+        let file = base.join(&rel).with_extension("rs");
+        let mod_file = base.join(&rel).join("mod.rs");
+        [file, mod_file].into_iter().find(|p| files.contains(p))
+    });
+
+    // Otherwise the items live in the base module itself
+    submodule.or_else(|| {
+        [
+            base.with_extension("rs"),
+            base.join("mod.rs"),
+            base.join("lib.rs"),
+            base.join("main.rs"),
+        ]
+        .into_iter()
+        .find(|p| files.contains(p))
+    })
+}
+
+/// Directory holding the child modules of a Rust source file
+fn rust_module_dir(file: &Path) -> PathBuf {
+    let dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("mod" | "lib" | "main") | None => dir,
+        Some(stem) => dir.join(stem),
+    }
+}
+
+/// Source directory of the crate containing `file`
+fn rust_crate_root(file: &Path, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| files.contains(&dir.join("lib.rs")) || files.contains(&dir.join("main.rs")))
+        .map(Path::to_path_buf)
+}
+
+// ============================================================================
+// Python
+// ============================================================================
+
+fn extract_python(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = strip_line_comment(raw, "#").trim();
+
+        if let Some(rest) = line.strip_prefix("import ") {
+            for item in rest.split(',') {
+                let module = item.split(" as ").next().unwrap_or(item).trim();
+                if !module.is_empty() {
+                    imports.push(ImportStatement::new(module, i));
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("from ") {
+            let Some((module, names)) = rest.split_once(" import ") else {
+                continue;
+            };
+            let module = module.trim();
+            if module.chars().all(|c| c == '.') {
+                // `from . import sibling` imports modules relative to the package
+                let names = names.trim().trim_start_matches('(').trim_end_matches(')');
+                for name in names.split(',') {
+                    let name = name.split(" as ").next().unwrap_or(name).trim();
+                    if !name.is_empty() {
+                        imports.push(ImportStatement::new(format!("{module}{name}"), i));
+                    }
+                }
+            } else {
+                imports.push(ImportStatement::new(module, i));
+            }
+        }
+    }
+
+    imports
+}
+
+fn resolve_python(from: &Path, module: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let dots = module.chars().take_while(|c| *c == '.').count();
+    let rel: PathBuf = module[dots..]
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let try_base = |base: &Path| {
+        let target = base.join(&rel);
+        [target.with_extension("py"), target.join("__init__.py")]
+            .into_iter()
+            .find(|p| files.contains(p))
+    };
+
+    if dots > 0 {
+        let mut base = from.parent()?;
+        for _ in 1..dots {
+            base = base.parent()?;
+        }
+        return try_base(base);
+    }
+
+    // Absolute imports resolve against the nearest ancestor that contains them
+    from.ancestors().skip(1).find_map(try_base)
+}
+
+// ============================================================================
+// TypeScript / JavaScript
+// ============================================================================
+
+const JS_SPECIFIER_PREFIXES: &[&str] = &["from ", "import ", "require(", "import("];
+
+fn extract_js(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = strip_line_comment(raw, "//");
+        let trimmed = line.trim_start();
+        let is_import_line = trimmed.starts_with("import")
+            || trimmed.starts_with("export")
+            || trimmed.starts_with('}')
+            || line.contains("require(")
+            || line.contains("import(");
+        if !is_import_line {
+            continue;
+        }
+
+        for prefix in JS_SPECIFIER_PREFIXES {
+            let mut rest = line;
+            while let Some(pos) = rest.find(prefix) {
+                rest = &rest[pos + prefix.len()..];
+                if let Some(specifier) = leading_string_literal(rest) {
+                    imports.push(ImportStatement::new(specifier, i));
+                }
+            }
+        }
+    }
+
+    imports
+}
+
+/// Return the contents of a quoted string at the start of `s`
+fn leading_string_literal(s: &str) -> Option<&str> {
+    let s = s.trim_start();
+    let quote = s.chars().next().filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    let body = &s[1..];
+    body.find(quote).map(|end| &body[..end])
+}
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+fn resolve_js(from: &Path, module: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    if !module.starts_with('.') {
+        return None;
+    }
+    let target = normalize(&from.parent()?.join(module));
+
+    if files.contains(&target) {
+        return Some(target);
+    }
+    let file_name = target.file_name()?.to_str()?.to_string();
+    JS_EXTENSIONS
+        .iter()
+        .map(|ext| target.with_file_name(format!("{file_name}.{ext}")))
+        .chain(
+            JS_EXTENSIONS
+                .iter()
+                .map(|ext| target.join(format!("index.{ext}"))),
+        )
+        .find(|p| files.contains(p))
+}
+
+/// Lexically resolve `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Go
+// ============================================================================
+
+fn extract_go(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+    let mut in_block = false;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = strip_line_comment(raw, "//").trim();
+
+        if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+            } else if let Some(path) = go_import_path(line) {
+                imports.push(ImportStatement::new(path, i));
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("import") {
+            let rest = rest.trim_start();
+            if rest.starts_with('(') {
+                in_block = true;
+            } else if let Some(path) = go_import_path(rest) {
+                imports.push(ImportStatement::new(path, i));
+            }
+        }
+    }
+
+    imports
+}
+
+/// Parse `"path"` or `alias "path"` from a Go import spec
+fn go_import_path(spec: &str) -> Option<&str> {
+    let start = spec.find('"')?;
+    leading_string_literal(&spec[start..])
+}
+
+// ============================================================================
+// Ruby
+// ============================================================================
+
+/// `require 'x'` and `require_relative 'x'`; relative requires are recorded
+/// as `./x` so they resolve like relative JavaScript imports
+fn extract_ruby(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = strip_line_comment(raw, "#").trim();
+        let (rest, relative) = match line.strip_prefix("require_relative") {
+            Some(rest) => (rest, true),
+            None => match line.strip_prefix("require") {
+                Some(rest) => (rest, false),
+                None => continue,
+            },
+        };
+        if !rest.starts_with([' ', '(']) {
+            continue;
+        }
+        let Some(path) = leading_string_literal(rest.trim_start().trim_start_matches('(')) else {
+            continue;
+        };
+        if relative && !path.starts_with('.') {
+            imports.push(ImportStatement::new(format!("./{path}"), i));
+        } else {
+            imports.push(ImportStatement::new(path, i));
+        }
+    }
+
+    imports
+}
+
+/// Relative requires resolve from the requiring file; others from a `lib`
+/// directory, as on the load path of gems and Rails apps
+fn resolve_ruby(from: &Path, module: &str, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let file_name = format!("{module}.rb");
+    if module.starts_with('.') {
+        let target = normalize(&from.parent()?.join(&file_name));
+        return files.contains(&target).then_some(target);
+    }
+    let suffix = Path::new("lib").join(file_name);
+    files.iter().filter(|f| f.ends_with(&suffix)).min().cloned()
+}
+
+// ============================================================================
+// SysML / KerML
+// ============================================================================
+
+fn extract_sysml(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        let mut line = strip_line_comment(raw, "//").trim();
+        for visibility in ["private ", "public ", "protected "] {
+            line = line.strip_prefix(visibility).unwrap_or(line);
+        }
+        let Some(rest) = line.strip_prefix("import ") else {
+            continue;
+        };
+        let rest = rest.trim_start().strip_prefix("all ").unwrap_or(rest);
+        let target = rest.split([';', ' ', '{']).next().unwrap_or_default();
+        let module = target.trim_end_matches("::**").trim_end_matches("::*");
+        if !module.is_empty() {
+            imports.push(ImportStatement::new(module, i));
+        }
+    }
+
+    imports
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Remove a trailing line comment (naively, ignoring string literals)
+fn strip_line_comment<'a>(line: &'a str, marker: &str) -> &'a str {
+    if line.trim_start().starts_with(marker) {
+        return "";
+    }
+    match line.find(&format!(" {marker}")) {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
@@ -3,15 +3,21 @@
 use std::path::Path;
 
 /// Supported programming languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// [`Language::Other`] carries the LSP language id of a language whose
+/// server is registered in the config rather than built in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Language {
     Rust,
     Python,
     TypeScript,
     JavaScript,
     Go,
+    Ruby,
     SysML,
     KerML,
+    /// Language registered in the config, by LSP language id
+    Other(String),
 }
 
 impl Language {
@@ -24,6 +30,7 @@ impl Language {
             "ts" | "tsx" => Some(Self::TypeScript),
             "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
             "go" => Some(Self::Go),
+            "rb" | "rake" => Some(Self::Ruby),
             "sysml" => Some(Self::SysML),
             "kerml" => Some(Self::KerML),
             _ => None,
@@ -47,8 +54,29 @@ impl Language {
             Self::TypeScript => &["ts", "tsx"],
             Self::JavaScript => &["js", "jsx", "mjs", "cjs"],
             Self::Go => &["go"],
+            Self::Ruby => &["rb", "rake"],
             Self::SysML => &["sysml"],
             Self::KerML => &["kerml"],
+            Self::Other(_) => &[],
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    /// Parse a language from its display name (e.g. `rust`, `typescript`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rust" => Ok(Self::Rust),
+            "python" => Ok(Self::Python),
+            "typescript" => Ok(Self::TypeScript),
+            "javascript" => Ok(Self::JavaScript),
+            "go" => Ok(Self::Go),
+            "ruby" => Ok(Self::Ruby),
+            "sysml" => Ok(Self::SysML),
+            "kerml" => Ok(Self::KerML),
+            _ => Err(format!("Unknown language: {s}")),
         }
     }
 }
@@ -61,8 +89,10 @@ impl std::fmt::Display for Language {
             Self::TypeScript => write!(f, "typescript"),
             Self::JavaScript => write!(f, "javascript"),
             Self::Go => write!(f, "go"),
+            Self::Ruby => write!(f, "ruby"),
             Self::SysML => write!(f, "sysml"),
             Self::KerML => write!(f, "kerml"),
+            Self::Other(id) => write!(f, "{id}"),
         }
     }
 }
@@ -1,14 +1,30 @@
 //! Scanner module: File discovery and language detection
 //!
 //! Responsible for walking directories, respecting .gitignore,
-//! and detecting the programming language of each file.
+//! and detecting the programming language of each file. Also extracts
+//! import statements so files can be linked by IMPORTS edges, writes commits
+//! of bare repositories to disk so they can be scanned, one at a time when
+//! scanning a stretch of history, and finds the lines a commit changed.
+//! Files whose content is binary, generated or too large are left out, with
+//! the reason recorded. The phases of a scan read file contents through a
+//! shared cache. Paths listed in .motherignore files are skipped as well.
 
+mod cache;
+mod changes;
+mod checkout;
+mod imports;
 mod language;
 mod run;
+mod skip;
 mod walker;
 
+pub use cache::{FileContent, FileContentCache, DEFAULT_CACHE_CAPACITY, DEFAULT_MMAP_THRESHOLD};
+pub use changes::{changed_lines, ChangedLines};
+pub use checkout::{commit_history, CheckoutError, CommitCheckout};
+pub use imports::{extract_imports, resolve_import, ImportStatement};
 pub use language::Language;
-pub use walker::{DiscoveredFile, Scanner};
+pub use skip::{parse_size, ContentFilter, DEFAULT_GENERATED_MARKERS};
+pub use walker::{DiscoveredFile, Discovery, Scanner, IGNORE_FILE_NAME};
 
 #[cfg(test)]
 mod tests;
@@ -3,7 +3,7 @@
 use chrono::Utc;
 use uuid::Uuid;
 
-use crate::graph::model::ScanRun;
+use crate::graph::model::{ScanRun, ScanStatus};
 
 impl ScanRun {
     /// Create a new scan run
@@ -16,6 +16,8 @@ impl ScanRun {
             branch: None,
             scanned_at: Utc::now(),
             version: None,
+            previous_run: None,
+            status: ScanStatus::Completed,
         }
     }
 
@@ -40,6 +42,20 @@ impl ScanRun {
         self
     }
 
+    /// Link the run to the run scanned before it
+    #[must_use]
+    pub fn with_previous_run(mut self, id: impl Into<String>) -> Self {
+        self.previous_run = Some(id.into());
+        self
+    }
+
+    /// Set the status the run is recorded with
+    #[must_use]
+    pub fn with_status(mut self, status: ScanStatus) -> Self {
+        self.status = status;
+        self
+    }
+
     /// Try to populate git info from the repository
     #[must_use]
     pub fn with_git_info(mut self) -> Self {
@@ -0,0 +1,120 @@
+//! Content checks: Files discovery leaves out for what they contain
+//!
+//! The walker picks files by extension, which lets through binaries that
+//! share an extension with source code, vendored minified bundles and code
+//! other tools generated. Language servers time out on those or index them
+//! for nothing. A [`ContentFilter`] looks at a file's size and its first
+//! bytes and gives the [`SkipReason`] of files that should not be scanned.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::graph::model::SkipReason;
+
+/// Markers of generated files used unless configured otherwise
+pub const DEFAULT_GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT"];
+
+/// Bytes read from the start of each file to check its content
+const HEADER_BYTES: u64 = 8 * 1024;
+
+/// Lines at the start of a file searched for generated-file markers, which
+/// code generators put in a header comment
+const HEADER_LINES: usize = 5;
+
+/// Decides which discovered files are left out of a scan
+///
+/// Files are skipped when they are larger than the size limit, have a NUL
+/// byte in their first 8 KiB, as git decides a file is binary, or carry a
+/// generated-file marker in their first few lines.
+#[derive(Debug, Clone)]
+pub struct ContentFilter {
+    /// Largest file scanned, in bytes; unbounded when unset
+    max_file_size: Option<u64>,
+    generated_markers: Vec<String>,
+}
+
+impl Default for ContentFilter {
+    fn default() -> Self {
+        Self {
+            max_file_size: None,
+            generated_markers: DEFAULT_GENERATED_MARKERS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl ContentFilter {
+    /// Skip files larger than `limit` bytes
+    #[must_use]
+    pub fn with_max_file_size(mut self, limit: Option<u64>) -> Self {
+        self.max_file_size = limit;
+        self
+    }
+
+    /// Skip files carrying one of these markers instead of the defaults; an
+    /// empty list keeps generated files
+    #[must_use]
+    pub fn with_generated_markers(mut self, markers: Vec<String>) -> Self {
+        self.generated_markers = markers;
+        self
+    }
+
+    /// Why the file at `path` should be skipped, if it should
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read.
+    pub fn check(&self, path: &Path) -> io::Result<Option<SkipReason>> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        if let Some(limit) = self.max_file_size.filter(|limit| size > *limit) {
+            return Ok(Some(SkipReason::TooLarge { size, limit }));
+        }
+        let mut header = Vec::new();
+        file.take(HEADER_BYTES).read_to_end(&mut header)?;
+        Ok(self.check_header(&header))
+    }
+
+    /// Why a file starting with `header` should be skipped, if it should
+    #[must_use]
+    pub fn check_header(&self, header: &[u8]) -> Option<SkipReason> {
+        if header.contains(&0) {
+            return Some(SkipReason::Binary);
+        }
+        let text = String::from_utf8_lossy(header);
+        text.lines().take(HEADER_LINES).find_map(|line| {
+            self.generated_markers
+                .iter()
+                .find(|marker| !marker.is_empty() && line.contains(marker.as_str()))
+                .map(|marker| SkipReason::Generated {
+                    marker: marker.clone(),
+                })
+        })
+    }
+}
+
+/// Parse a file size such as `512KB` or `2MB`; a bare number is bytes
+///
+/// Units are powers of 1024 and case-insensitive.
+///
+/// # Errors
+/// Returns an error if the text is not a size.
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid size '{text}': expected e.g. 500000, 512KB or 2MB");
+    let trimmed = text.trim();
+    let digits = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(digits);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let shift = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" => 10,
+        "M" | "MB" => 20,
+        "G" | "GB" => 30,
+        _ => return Err(invalid()),
+    };
+    number.checked_mul(1 << shift).ok_or_else(invalid)
+}
@@ -1,5 +1,10 @@
 //! Tests for scanner module
 
+mod tests_cache;
+mod tests_changes;
+mod tests_checkout;
+mod tests_imports;
 mod tests_language;
 mod tests_run;
+mod tests_skip;
 mod tests_walker;
@@ -0,0 +1,132 @@
+//! Tests for the file content cache
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+use std::sync::Arc;
+
+use tempfile::TempDir;
+
+use crate::graph::model::FileCacheStats;
+use crate::scanner::{DiscoveredFile, FileContentCache, Language};
+
+#[test]
+fn test_second_read_is_a_hit() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("lib.rs");
+    fs::write(&path, "fn main() {}").unwrap();
+    let cache = FileContentCache::new(1024, 1024);
+
+    let first = cache.read(&path).unwrap();
+    let second = cache.read(&path).unwrap();
+
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(second.text().unwrap(), "fn main() {}");
+    assert_eq!(
+        cache.stats(),
+        FileCacheStats {
+            hits: 1,
+            misses: 1,
+            bytes_read: 12,
+            mapped: 0,
+        }
+    );
+    assert!((cache.stats().hit_rate() - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_hash_matches_the_discovered_file() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("lib.rs");
+    fs::write(&path, "pub mod foo;").unwrap();
+    let file = DiscoveredFile {
+        path: path.clone(),
+        language: Language::Rust,
+    };
+
+    let content = FileContentCache::default().read(&path).unwrap();
+    assert_eq!(content.hash(), file.compute_hash().unwrap());
+}
+
+#[test]
+fn test_large_files_are_mapped() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("big.rs");
+    let text = "// padding\n".repeat(100);
+    fs::write(&path, &text).unwrap();
+    let cache = FileContentCache::new(4096, 512);
+
+    let content = cache.read(&path).unwrap();
+
+    assert_eq!(content.text().unwrap(), text);
+    assert_eq!(cache.stats().mapped, 1);
+}
+
+#[test]
+fn test_least_recently_used_files_are_evicted() {
+    let temp = TempDir::new().unwrap();
+    let paths: Vec<_> = ["a.rs", "b.rs", "c.rs"]
+        .iter()
+        .map(|name| {
+            let path = temp.path().join(name);
+            fs::write(&path, "0123456789").unwrap();
+            path
+        })
+        .collect();
+    let cache = FileContentCache::new(20, 1024);
+
+    cache.read(&paths[0]).unwrap();
+    cache.read(&paths[1]).unwrap();
+    // a.rs is now more recently used than b.rs, which c.rs evicts
+    cache.read(&paths[0]).unwrap();
+    cache.read(&paths[2]).unwrap();
+    cache.read(&paths[0]).unwrap();
+    cache.read(&paths[1]).unwrap();
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (2, 4));
+}
+
+#[test]
+fn test_files_larger_than_the_cache_are_not_kept() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("big.rs");
+    fs::write(&path, "0123456789").unwrap();
+    let cache = FileContentCache::new(5, 1024);
+
+    cache.read(&path).unwrap();
+    cache.read(&path).unwrap();
+
+    assert_eq!(cache.stats().misses, 2);
+}
+
+#[test]
+fn test_invalid_utf8_is_invalid_data() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("bin.rs");
+    fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+
+    let content = FileContentCache::default().read(&path).unwrap();
+    let error = content.text().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_missing_files_are_errors() {
+    let temp = TempDir::new().unwrap();
+    let cache = FileContentCache::default();
+    assert!(cache.read(&temp.path().join("missing.rs")).is_err());
+    assert_eq!(cache.stats().misses, 1);
+}
+
+#[test]
+fn test_read_to_string_reads_through_the_cache() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("README.md");
+    fs::write(&path, "# Title").unwrap();
+    let cache = FileContentCache::default();
+
+    assert_eq!(cache.read_to_string(&path).unwrap(), "# Title");
+    assert_eq!(cache.read_to_string(&path).unwrap(), "# Title");
+    assert_eq!((cache.stats().misses, cache.stats().hits), (1, 1));
+}
@@ -0,0 +1,76 @@
+//! Tests for the lines changed between commits
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::scanner::changed_lines;
+
+/// Create a bare repository with one commit per `src/lib.rs` content
+///
+/// Returns the commit SHAs in order.
+fn bare_repo(dir: &Path, contents: &[&str]) -> Vec<String> {
+    let repo = git2::Repository::init_bare(dir).unwrap();
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let mut shas: Vec<String> = Vec::new();
+
+    for content in contents {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut src = repo.treebuilder(None).unwrap();
+        src.insert("lib.rs", blob, 0o100_644).unwrap();
+        let mut root = repo.treebuilder(None).unwrap();
+        root.insert("src", src.write().unwrap(), 0o040_000).unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+        let parent = shas
+            .last()
+            .map(|sha| repo.find_commit(git2::Oid::from_str(sha).unwrap()).unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo
+            .commit(
+                Some("refs/heads/main"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        shas.push(oid.to_string());
+    }
+    shas
+}
+
+#[test]
+fn test_changed_lines_cover_edits_and_removals() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(
+        temp.path(),
+        &[
+            "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\n",
+            "fn a() {}\nfn b() { 1 }\nfn c() {}\nfn e() {}\n",
+        ],
+    );
+    let lib = Path::new("/scan/src/lib.rs");
+
+    let changes = changed_lines(temp.path(), Path::new("/scan"), &shas[0], &shas[1]).unwrap();
+
+    assert_eq!(changes.file_count(), 1);
+    assert!(!changes.touches(lib, 1, 1));
+    assert!(changes.touches(lib, 2, 2));
+    // `d` was removed between `c` and `e`
+    assert!(changes.touches(lib, 3, 3));
+    assert!(changes.touches(lib, 4, 4));
+    assert!(!changes.touches(Path::new("/other/src/lib.rs"), 1, 10));
+}
+
+#[test]
+fn test_unchanged_commits_have_no_changed_lines() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &["fn a() {}\n"]);
+
+    let changes = changed_lines(temp.path(), Path::new("/scan"), &shas[0], &shas[0]).unwrap();
+
+    assert_eq!(changes.file_count(), 0);
+}
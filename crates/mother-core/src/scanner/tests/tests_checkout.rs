@@ -0,0 +1,208 @@
+//! Tests for commit checkouts
+
+#![allow(clippy::unwrap_used)]
+
+use crate::scanner::{commit_history, CheckoutError, CommitCheckout};
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Create a bare repository with one commit per `(path, content)` list
+///
+/// Returns the commit SHAs in order; `main` points at the last commit.
+fn bare_repo(dir: &Path, commits: &[&[(&str, &str)]]) -> Vec<String> {
+    let repo = git2::Repository::init_bare(dir).unwrap();
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let mut shas = Vec::new();
+    let mut parent: Option<git2::Oid> = None;
+
+    for files in commits {
+        let mut builder = git2::build::TreeUpdateBuilder::new();
+        for (path, content) in *files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.upsert(*path, blob, git2::FileMode::Blob);
+        }
+        let base = match parent {
+            Some(oid) => repo.find_commit(oid).unwrap().tree().unwrap(),
+            None => repo
+                .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                .unwrap(),
+        };
+        let tree = repo
+            .find_tree(builder.create_updated(&repo, &base).unwrap())
+            .unwrap();
+        let parents: Vec<git2::Commit> = parent
+            .map(|oid| repo.find_commit(oid).unwrap())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let oid = repo
+            .commit(
+                Some("refs/heads/main"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parent_refs,
+            )
+            .unwrap();
+        shas.push(oid.to_string());
+        parent = Some(oid);
+    }
+    shas
+}
+
+#[test]
+fn test_materialize_writes_the_commit_tree() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(
+        temp.path(),
+        &[
+            &[("src/lib.rs", "pub fn one() {}")],
+            &[("src/lib.rs", "pub fn two() {}"), ("README.md", "# Demo")],
+        ],
+    );
+
+    let checkout = CommitCheckout::materialize(temp.path(), &shas[0]).unwrap();
+
+    assert_eq!(checkout.commit_sha(), shas[0]);
+    let lib = fs::read_to_string(checkout.path().join("src/lib.rs")).unwrap();
+    assert_eq!(lib, "pub fn one() {}");
+    assert!(!checkout.path().join("README.md").exists());
+}
+
+#[test]
+fn test_materialize_resolves_branches_and_prefixes() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(
+        temp.path(),
+        &[&[("a.rs", "fn a() {}")], &[("b.rs", "fn b() {}")]],
+    );
+
+    let checkout = CommitCheckout::materialize(temp.path(), "main").unwrap();
+    assert_eq!(checkout.commit_sha(), shas[1]);
+    let run = checkout.scan_run();
+    assert_eq!(run.commit_sha.as_deref(), Some(shas[1].as_str()));
+    assert_eq!(run.branch.as_deref(), Some("main"));
+    assert_eq!(
+        run.repo_path,
+        temp.path().canonicalize().unwrap().display().to_string()
+    );
+    drop(checkout);
+
+    let checkout = CommitCheckout::materialize(temp.path(), &shas[0][..10]).unwrap();
+    assert_eq!(checkout.commit_sha(), shas[0]);
+    assert!(checkout.scan_run().branch.is_none());
+}
+
+#[test]
+fn test_materialize_leaves_the_repository_untouched() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[&[("a.rs", "fn a() {}")]]);
+
+    let checkout = CommitCheckout::materialize(temp.path(), &shas[0]).unwrap();
+
+    assert!(checkout.path().join("a.rs").exists());
+    assert!(!temp.path().join("index").exists());
+    assert!(!temp.path().join("a.rs").exists());
+}
+
+#[test]
+fn test_checkout_is_removed_on_drop() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[&[("a.rs", "fn a() {}")]]);
+
+    let checkout = CommitCheckout::materialize(temp.path(), &shas[0]).unwrap();
+    let dir = checkout.path().to_path_buf();
+    assert!(dir.exists());
+    drop(checkout);
+
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_checkout_dir_is_stable_per_repository() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[&[("a.rs", "1")], &[("a.rs", "2")]]);
+
+    let first = CommitCheckout::materialize(temp.path(), &shas[0]).unwrap();
+    let first_dir = first.path().to_path_buf();
+    drop(first);
+    let second = CommitCheckout::materialize(temp.path(), &shas[1]).unwrap();
+
+    assert_eq!(second.path(), first_dir);
+}
+
+#[test]
+fn test_concurrent_checkout_of_same_repository_is_rejected() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[&[("a.rs", "fn a() {}")]]);
+
+    let first = CommitCheckout::materialize(temp.path(), &shas[0]).unwrap();
+    let second = CommitCheckout::materialize(temp.path(), &shas[0]);
+
+    assert!(matches!(second, Err(CheckoutError::InUse(_))));
+    // The rejected checkout must not remove the first one's files
+    assert!(first.path().join("a.rs").exists());
+}
+
+#[test]
+fn test_materialize_unknown_revision() {
+    let temp = TempDir::new().unwrap();
+    bare_repo(temp.path(), &[&[("a.rs", "fn a() {}")]]);
+
+    let result = CommitCheckout::materialize(temp.path(), "does-not-exist");
+
+    assert!(matches!(result, Err(CheckoutError::Git(_))));
+}
+
+#[test]
+fn test_materialize_missing_repository() {
+    let result = CommitCheckout::materialize(Path::new("/this/path/does/not/exist"), "main");
+
+    assert!(matches!(result, Err(CheckoutError::Io(_))));
+}
+
+/// Five commits, each changing `src/lib.rs`
+fn five_commits(dir: &Path) -> Vec<String> {
+    let contents: Vec<String> = (1..=5).map(|i| format!("pub fn v{i}() {{}}")).collect();
+    let commits: Vec<[(&str, &str); 1]> = contents
+        .iter()
+        .map(|content| [("src/lib.rs", content.as_str())])
+        .collect();
+    let commits: Vec<&[(&str, &str)]> = commits.iter().map(|c| c.as_slice()).collect();
+    bare_repo(dir, &commits)
+}
+
+#[test]
+fn test_commit_history_lists_commits_oldest_first() {
+    let temp = TempDir::new().unwrap();
+    let shas = five_commits(temp.path());
+    let step = |n| NonZeroUsize::new(n).unwrap();
+
+    let all = commit_history(temp.path(), &shas[1], "main", step(1)).unwrap();
+    assert_eq!(all, shas[1..]);
+
+    // Every second commit, plus the last one the step skips
+    let sparse = commit_history(temp.path(), &shas[0], &shas[3], step(2)).unwrap();
+    assert_eq!(sparse, [shas[0].clone(), shas[2].clone(), shas[3].clone()]);
+
+    let single = commit_history(temp.path(), "main", "main", step(3)).unwrap();
+    assert_eq!(single, [shas[4].clone()]);
+}
+
+#[test]
+fn test_commit_history_rejects_a_later_from() {
+    let temp = TempDir::new().unwrap();
+    let shas = five_commits(temp.path());
+
+    let result = commit_history(
+        temp.path(),
+        &shas[3],
+        &shas[1],
+        NonZeroUsize::new(1).unwrap(),
+    );
+
+    assert!(matches!(result, Err(CheckoutError::NotAncestor { .. })));
+}
@@ -0,0 +1,277 @@
+//! Tests for import extraction and resolution
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::scanner::{extract_imports, resolve_import, Language};
+
+fn modules(language: Language, content: &str) -> Vec<String> {
+    extract_imports(&language, content)
+        .into_iter()
+        .map(|i| i.module)
+        .collect()
+}
+
+fn known(paths: &[&str]) -> HashSet<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+// ============================================================================
+// Rust
+// ============================================================================
+
+#[test]
+fn test_rust_simple_use() {
+    let imports = extract_imports(&Language::Rust, "use std::path::Path;\n\nfn main() {}");
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].module, "std::path::Path");
+    assert_eq!(imports[0].line, 1);
+}
+
+#[test]
+fn test_rust_use_tree_expands() {
+    assert_eq!(
+        modules(
+            Language::Rust,
+            "use crate::graph::{model::{Edge, self}, neo4j::Neo4jClient};"
+        ),
+        vec![
+            "crate::graph::model::Edge",
+            "crate::graph::model",
+            "crate::graph::neo4j::Neo4jClient",
+        ]
+    );
+}
+
+#[test]
+fn test_rust_multiline_use_and_visibility() {
+    let content = "pub(crate) use super::{\n    a,\n    b as c,\n};\npub use self::d::*;";
+    let imports = extract_imports(&Language::Rust, content);
+    let names: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+    assert_eq!(names, vec!["super::a", "super::b", "self::d"]);
+    assert_eq!(imports[0].line, 1);
+    assert_eq!(imports[2].line, 5);
+}
+
+#[test]
+fn test_rust_mod_declarations_and_extern_crate() {
+    let content = "mod phase1;\npub mod phase2;\nmod tests {\n}\nextern crate serde;";
+    assert_eq!(
+        modules(Language::Rust, content),
+        vec!["self::phase1", "self::phase2", "serde"]
+    );
+}
+
+#[test]
+fn test_rust_ignores_comments() {
+    assert!(modules(Language::Rust, "// use foo::bar;").is_empty());
+}
+
+#[test]
+fn test_rust_resolves_mod_declaration() {
+    let files = known(&[
+        "/r/src/lib.rs",
+        "/r/src/scan.rs",
+        "/r/src/scan/phase1/mod.rs",
+    ]);
+    assert_eq!(
+        resolve_import(
+            &Language::Rust,
+            Path::new("/r/src/lib.rs"),
+            "self::scan",
+            &files
+        ),
+        Some(PathBuf::from("/r/src/scan.rs"))
+    );
+    assert_eq!(
+        resolve_import(
+            &Language::Rust,
+            Path::new("/r/src/scan.rs"),
+            "self::phase1",
+            &files
+        ),
+        Some(PathBuf::from("/r/src/scan/phase1/mod.rs"))
+    );
+}
+
+#[test]
+fn test_rust_resolves_crate_and_super_paths() {
+    let files = known(&[
+        "/r/src/main.rs",
+        "/r/src/graph/model.rs",
+        "/r/src/graph/mod.rs",
+    ]);
+    assert_eq!(
+        resolve_import(
+            &Language::Rust,
+            Path::new("/r/src/main.rs"),
+            "crate::graph::model::Edge",
+            &files
+        ),
+        Some(PathBuf::from("/r/src/graph/model.rs"))
+    );
+    assert_eq!(
+        resolve_import(
+            &Language::Rust,
+            Path::new("/r/src/graph/model.rs"),
+            "super::Thing",
+            &files
+        ),
+        Some(PathBuf::from("/r/src/graph/mod.rs"))
+    );
+    assert_eq!(
+        resolve_import(
+            &Language::Rust,
+            Path::new("/r/src/main.rs"),
+            "std::io",
+            &files
+        ),
+        None
+    );
+}
+
+// ============================================================================
+// Python
+// ============================================================================
+
+#[test]
+fn test_python_imports() {
+    let content = "import os, sys as system\nfrom app.models import User\nfrom . import utils, helpers\nfrom ..core import x";
+    assert_eq!(
+        modules(Language::Python, content),
+        vec!["os", "sys", "app.models", ".utils", ".helpers", "..core"]
+    );
+}
+
+#[test]
+fn test_python_resolves_absolute_and_relative() {
+    let files = known(&[
+        "/r/app/main.py",
+        "/r/app/models.py",
+        "/r/app/core/__init__.py",
+        "/r/app/sub/view.py",
+    ]);
+    assert_eq!(
+        resolve_import(
+            &Language::Python,
+            Path::new("/r/app/main.py"),
+            "app.models",
+            &files
+        ),
+        Some(PathBuf::from("/r/app/models.py"))
+    );
+    assert_eq!(
+        resolve_import(
+            &Language::Python,
+            Path::new("/r/app/sub/view.py"),
+            "..core",
+            &files
+        ),
+        Some(PathBuf::from("/r/app/core/__init__.py"))
+    );
+    assert_eq!(
+        resolve_import(&Language::Python, Path::new("/r/app/main.py"), "os", &files),
+        None
+    );
+}
+
+// ============================================================================
+// TypeScript / JavaScript
+// ============================================================================
+
+#[test]
+fn test_js_import_forms() {
+    let content = r#"import React from "react";
+import {
+  a,
+} from './a';
+import './side-effect';
+export * from "../b";
+const c = require('./c');
+const d = await import("./d");
+const s = "from './nope'";"#;
+    assert_eq!(
+        modules(Language::TypeScript, content),
+        vec!["react", "./a", "./side-effect", "../b", "./c", "./d"]
+    );
+}
+
+#[test]
+fn test_js_resolves_relative_with_extension_and_index() {
+    let files = known(&["/r/src/app.ts", "/r/src/util.ts", "/r/src/lib/index.tsx"]);
+    let from = Path::new("/r/src/app.ts");
+    assert_eq!(
+        resolve_import(&Language::TypeScript, from, "./util", &files),
+        Some(PathBuf::from("/r/src/util.ts"))
+    );
+    assert_eq!(
+        resolve_import(&Language::TypeScript, from, "./lib", &files),
+        Some(PathBuf::from("/r/src/lib/index.tsx"))
+    );
+    assert_eq!(
+        resolve_import(&Language::TypeScript, from, "react", &files),
+        None
+    );
+}
+
+// ============================================================================
+// Ruby
+// ============================================================================
+
+#[test]
+fn test_ruby_require_forms() {
+    let content = "require 'json'\nrequire(\"billing/invoice\")\n\
+        require_relative '../models/user'\nrequire_relative \"helpers\" # local\n\
+        # require 'commented'\nrequired = true\n";
+    assert_eq!(
+        modules(Language::Ruby, content),
+        vec!["json", "billing/invoice", "../models/user", "./helpers"]
+    );
+}
+
+#[test]
+fn test_ruby_resolves_relative_and_lib_requires() {
+    let files = known(&[
+        "/app/models/user.rb",
+        "/app/services/helpers.rb",
+        "/app/lib/billing/invoice.rb",
+    ]);
+    let from = Path::new("/app/services/sync.rb");
+
+    assert_eq!(
+        resolve_import(&Language::Ruby, from, "../models/user", &files),
+        Some(PathBuf::from("/app/models/user.rb"))
+    );
+    assert_eq!(
+        resolve_import(&Language::Ruby, from, "./helpers", &files),
+        Some(PathBuf::from("/app/services/helpers.rb"))
+    );
+    assert_eq!(
+        resolve_import(&Language::Ruby, from, "billing/invoice", &files),
+        Some(PathBuf::from("/app/lib/billing/invoice.rb"))
+    );
+    assert_eq!(resolve_import(&Language::Ruby, from, "json", &files), None);
+}
+
+// ============================================================================
+// Go and SysML
+// ============================================================================
+
+#[test]
+fn test_go_single_and_block_imports() {
+    let content =
+        "package main\n\nimport \"fmt\"\nimport (\n\t\"os\"\n\tlog \"github.com/x/log\"\n)\n";
+    assert_eq!(
+        modules(Language::Go, content),
+        vec!["fmt", "os", "github.com/x/log"]
+    );
+}
+
+#[test]
+fn test_sysml_imports() {
+    let content = "package P {\n  private import ScalarValues::*;\n  import Vehicles::Car;\n}";
+    assert_eq!(
+        modules(Language::SysML, content),
+        vec!["ScalarValues", "Vehicles::Car"]
+    );
+}
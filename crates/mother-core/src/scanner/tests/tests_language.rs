@@ -12,6 +12,8 @@ fn test_language_from_extension() {
     assert_eq!(Language::from_extension("js"), Some(Language::JavaScript));
     assert_eq!(Language::from_extension("jsx"), Some(Language::JavaScript));
     assert_eq!(Language::from_extension("go"), Some(Language::Go));
+    assert_eq!(Language::from_extension("rb"), Some(Language::Ruby));
+    assert_eq!(Language::from_extension("rake"), Some(Language::Ruby));
     assert_eq!(Language::from_extension("sysml"), Some(Language::SysML));
     assert_eq!(Language::from_extension("kerml"), Some(Language::KerML));
     assert_eq!(Language::from_extension("txt"), None);
@@ -53,6 +55,32 @@ fn test_language_display() {
     assert_eq!(format!("{}", Language::TypeScript), "typescript");
     assert_eq!(format!("{}", Language::JavaScript), "javascript");
     assert_eq!(format!("{}", Language::Go), "go");
+    assert_eq!(format!("{}", Language::Ruby), "ruby");
     assert_eq!(format!("{}", Language::SysML), "sysml");
     assert_eq!(format!("{}", Language::KerML), "kerml");
 }
+
+#[test]
+fn test_language_from_str_round_trips_display() {
+    for language in [
+        Language::Rust,
+        Language::Python,
+        Language::TypeScript,
+        Language::JavaScript,
+        Language::Go,
+        Language::Ruby,
+        Language::SysML,
+        Language::KerML,
+    ] {
+        assert_eq!(language.to_string().parse::<Language>(), Ok(language));
+    }
+    assert!("cobol".parse::<Language>().is_err());
+}
+
+#[test]
+fn test_other_language_displays_its_id() {
+    let language = Language::Other("elixir".to_string());
+    assert_eq!(language.to_string(), "elixir");
+    assert!(language.extensions().is_empty());
+    assert!("elixir".parse::<Language>().is_err());
+}
@@ -0,0 +1,111 @@
+//! Tests for content checks
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::graph::model::SkipReason;
+use crate::scanner::{parse_size, ContentFilter};
+
+#[test]
+fn test_source_code_is_kept() {
+    let filter = ContentFilter::default();
+    assert_eq!(
+        filter.check_header(b"fn main() {\n    println!(\"hi\");\n}\n"),
+        None
+    );
+}
+
+#[test]
+fn test_nul_bytes_mark_binary_content() {
+    let filter = ContentFilter::default();
+    assert_eq!(
+        filter.check_header(b"\x7fELF\x02\x01\x01\0\0\0"),
+        Some(SkipReason::Binary)
+    );
+}
+
+#[test]
+fn test_default_markers_in_header_mark_generated_files() {
+    let filter = ContentFilter::default();
+    let go = b"// Code generated by protoc-gen-go. DO NOT EDIT.\n\npackage pb\n";
+    let rust = b"// This file is @generated by prost-build.\npub struct A;\n";
+    assert_eq!(
+        filter.check_header(go),
+        Some(SkipReason::Generated {
+            marker: "DO NOT EDIT".to_string()
+        })
+    );
+    assert_eq!(
+        filter.check_header(rust),
+        Some(SkipReason::Generated {
+            marker: "@generated".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_markers_below_the_header_are_ignored() {
+    let filter = ContentFilter::default();
+    let text = "fn a() {}\n".repeat(5) + "// DO NOT EDIT below\n";
+    assert_eq!(filter.check_header(text.as_bytes()), None);
+}
+
+#[test]
+fn test_configured_markers_replace_the_defaults() {
+    let filter = ContentFilter::default().with_generated_markers(vec!["Autogenerated".into()]);
+    assert_eq!(filter.check_header(b"// @generated\n"), None);
+    assert_eq!(
+        filter.check_header(b"# Autogenerated by thrift\n"),
+        Some(SkipReason::Generated {
+            marker: "Autogenerated".to_string()
+        })
+    );
+    let none = ContentFilter::default().with_generated_markers(Vec::new());
+    assert_eq!(none.check_header(b"// @generated\n"), None);
+}
+
+#[test]
+fn test_files_over_the_size_limit_are_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("bundle.min.js");
+    fs::write(&path, "var a=1;".repeat(200)).unwrap();
+
+    let filter = ContentFilter::default().with_max_file_size(Some(1024));
+    assert_eq!(
+        filter.check(&path).unwrap(),
+        Some(SkipReason::TooLarge {
+            size: 1600,
+            limit: 1024
+        })
+    );
+    let larger = ContentFilter::default().with_max_file_size(Some(1600));
+    assert_eq!(larger.check(&path).unwrap(), None);
+}
+
+#[test]
+fn test_check_of_missing_file_fails() {
+    let filter = ContentFilter::default();
+    assert!(filter
+        .check(std::path::Path::new("/nonexistent/a.rs"))
+        .is_err());
+}
+
+#[test]
+fn test_parse_size_units() {
+    assert_eq!(parse_size("500000").unwrap(), 500_000);
+    assert_eq!(parse_size("512KB").unwrap(), 512 * 1024);
+    assert_eq!(parse_size("2mb").unwrap(), 2 * 1024 * 1024);
+    assert_eq!(parse_size("1G").unwrap(), 1 << 30);
+    assert_eq!(parse_size("10 B").unwrap(), 10);
+}
+
+#[test]
+fn test_parse_size_rejects_invalid_text() {
+    for text in ["", "MB", "2TB", "1.5MB", "-1", "99999999999999999999KB"] {
+        let error = parse_size(text).unwrap_err();
+        assert!(error.contains("Invalid size"), "{text}: {error}");
+    }
+}
@@ -2,7 +2,8 @@
 
 #![allow(clippy::expect_used)]
 
-use crate::scanner::{Language, Scanner};
+use crate::graph::model::SkipReason;
+use crate::scanner::{ContentFilter, Language, Scanner};
 use std::fs;
 use tempfile::TempDir;
 
@@ -24,6 +25,18 @@ fn test_scanner_finds_rust_files() {
     assert!(files.iter().all(|f| f.language == Language::Rust));
 }
 
+#[test]
+fn test_scanner_finds_ruby_files_by_default() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("user.rb"), "class User; end").expect("Failed to write file");
+    fs::write(temp_dir.path().join("Rakefile.rake"), "task :x").expect("Failed to write file");
+
+    let files: Vec<_> = Scanner::new(temp_dir.path()).scan().collect();
+
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|f| f.language == Language::Ruby));
+}
+
 #[test]
 #[allow(clippy::expect_used)]
 fn test_scanner_with_language_filter() {
@@ -91,3 +104,210 @@ fn test_scanner_root_with_path_buf() {
 
     assert_eq!(scanner.root(), path_buf.as_path());
 }
+
+#[test]
+fn test_scanner_include_filter() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let src_dir = temp_dir.path().join("src");
+    fs::create_dir(&src_dir).expect("Failed to create src dir");
+    fs::write(src_dir.join("lib.rs"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("build.rs"), "").expect("Failed to write file");
+
+    let scanner = Scanner::new(temp_dir.path())
+        .with_filters(&["src/**".to_string()], &[])
+        .expect("Invalid globs");
+    let files: Vec<_> = scanner.scan().collect();
+
+    assert_eq!(files.len(), 1);
+    assert!(files[0].path.ends_with("src/lib.rs"));
+}
+
+#[test]
+fn test_scanner_exclude_filter() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let gen_dir = temp_dir.path().join("generated");
+    fs::create_dir(&gen_dir).expect("Failed to create dir");
+    fs::write(gen_dir.join("api.rs"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("main.rs"), "").expect("Failed to write file");
+
+    let scanner = Scanner::new(temp_dir.path())
+        .with_filters(&[], &["generated/**".to_string()])
+        .expect("Invalid globs");
+    let files: Vec<_> = scanner.scan().collect();
+
+    assert_eq!(files.len(), 1);
+    assert!(files[0].path.ends_with("main.rs"));
+}
+
+#[test]
+fn test_scanner_invalid_glob() {
+    let result = Scanner::new(".").with_filters(&["src/[".to_string()], &[]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_scanner_custom_language_extensions() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("app.ex"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("notes.txt"), "").expect("Failed to write file");
+
+    let elixir = Language::Other("elixir".to_string());
+    let scanner =
+        Scanner::new(temp_dir.path()).with_custom_language(elixir.clone(), &[".EX".to_string()]);
+    let files: Vec<_> = scanner.scan().collect();
+
+    assert_eq!(files.len(), 1);
+    assert!(files[0].path.ends_with("app.ex"));
+    assert_eq!(files[0].language, elixir);
+}
+
+#[test]
+fn test_scanner_contract_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let gen_dir = temp_dir.path().join("generated");
+    fs::create_dir(&gen_dir).expect("Failed to create dir");
+    fs::write(temp_dir.path().join("orders.proto"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("openapi.YAML"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("main.rs"), "").expect("Failed to write file");
+    fs::write(gen_dir.join("spec.json"), "").expect("Failed to write file");
+
+    let scanner = Scanner::new(temp_dir.path())
+        .with_filters(&[], &["generated/**".to_string()])
+        .expect("Invalid globs");
+    let mut files: Vec<_> = scanner
+        .contract_files()
+        .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+        .collect();
+    files.sort();
+
+    assert_eq!(files, vec!["openapi.YAML", "orders.proto"]);
+}
+
+#[test]
+fn test_scanner_manifest_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let web_dir = temp_dir.path().join("web");
+    fs::create_dir(&web_dir).expect("Failed to create dir");
+    fs::write(temp_dir.path().join("Cargo.toml"), "").expect("Failed to write file");
+    fs::write(temp_dir.path().join("Cargo.lock"), "").expect("Failed to write file");
+    fs::write(web_dir.join("package.json"), "").expect("Failed to write file");
+
+    let mut files: Vec<_> = Scanner::new(temp_dir.path())
+        .manifest_files()
+        .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+        .collect();
+    files.sort();
+
+    assert_eq!(files, vec!["Cargo.toml", "package.json"]);
+}
+
+#[test]
+fn test_scanner_discover_records_skipped_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::write(root.join("main.rs"), "fn main() {}").expect("Failed to write file");
+    fs::write(root.join("data.rs"), b"\0\x01\x02").expect("Failed to write file");
+    fs::write(root.join("pb.rs"), "// @generated\npub struct A;").expect("Failed to write file");
+    fs::write(root.join("big.rs"), "//".repeat(100)).expect("Failed to write file");
+    fs::write(root.join("notes.txt"), b"\0").expect("Failed to write file");
+
+    let filter = ContentFilter::default().with_max_file_size(Some(100));
+    let scanner = Scanner::new(root).with_content_filter(filter);
+    let discovery = scanner.discover();
+
+    assert_eq!(discovery.files.len(), 1);
+    assert!(discovery.files[0].path.ends_with("main.rs"));
+    let mut skipped: Vec<_> = discovery
+        .skipped
+        .iter()
+        .map(|s| {
+            (
+                s.file.rsplit('/').next().unwrap_or_default(),
+                s.reason.clone(),
+            )
+        })
+        .collect();
+    skipped.sort_by_key(|(file, _)| *file);
+    assert_eq!(
+        skipped,
+        vec![
+            (
+                "big.rs",
+                SkipReason::TooLarge {
+                    size: 200,
+                    limit: 100
+                }
+            ),
+            ("data.rs", SkipReason::Binary),
+            (
+                "pb.rs",
+                SkipReason::Generated {
+                    marker: "@generated".to_string()
+                }
+            ),
+        ]
+    );
+    assert_eq!(scanner.scan().count(), 1);
+}
+
+#[test]
+fn test_scanner_honors_motherignore_with_gitignore() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    // .gitignore files only apply inside git repositories
+    git2::Repository::init(root).expect("Failed to init repository");
+    fs::create_dir_all(root.join("vendor")).expect("Failed to create dir");
+    fs::create_dir_all(root.join("build")).expect("Failed to create dir");
+    fs::write(root.join("main.rs"), "fn main() {}").expect("Failed to write file");
+    fs::write(root.join("vendor/dep.rs"), "fn dep() {}").expect("Failed to write file");
+    fs::write(root.join("build/out.rs"), "fn out() {}").expect("Failed to write file");
+    fs::write(root.join(".gitignore"), "build/\n").expect("Failed to write file");
+    fs::write(root.join(".motherignore"), "vendor/\n").expect("Failed to write file");
+
+    let files: Vec<_> = Scanner::new(root).scan().collect();
+
+    assert_eq!(files.len(), 1);
+    assert!(files[0].path.ends_with("main.rs"));
+}
+
+#[test]
+fn test_scanner_honors_nested_ignore_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    let api = root.join("services/api");
+    fs::create_dir_all(api.join("gen")).expect("Failed to create dir");
+    fs::write(api.join("server.py"), "def serve(): pass").expect("Failed to write file");
+    fs::write(api.join("gen/stub.py"), "def stub(): pass").expect("Failed to write file");
+    fs::write(api.join("fixtures.py"), "DATA = 1").expect("Failed to write file");
+    fs::write(root.join("fixtures.py"), "DATA = 2").expect("Failed to write file");
+    fs::write(api.join(".motherignore"), "gen/\nfixtures.py\n").expect("Failed to write file");
+
+    let mut files: Vec<_> = Scanner::new(root)
+        .scan()
+        .map(|f| f.path.strip_prefix(root).expect("under root").to_path_buf())
+        .collect();
+    files.sort();
+
+    assert_eq!(
+        files,
+        vec![
+            std::path::PathBuf::from("fixtures.py"),
+            std::path::PathBuf::from("services/api/server.py"),
+        ]
+    );
+}
+
+#[test]
+fn test_motherignore_can_reinclude_gitignored_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    // .gitignore files only apply inside git repositories
+    git2::Repository::init(root).expect("Failed to init repository");
+    fs::write(root.join("schema.rs"), "pub struct Schema;").expect("Failed to write file");
+    fs::write(root.join(".gitignore"), "schema.rs\n").expect("Failed to write file");
+    fs::write(root.join(".motherignore"), "!schema.rs\n").expect("Failed to write file");
+
+    let files: Vec<_> = Scanner::new(root).scan().collect();
+
+    assert_eq!(files.len(), 1);
+}
@@ -1,12 +1,18 @@
 //! File walker: Discovers files in a directory tree
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::WalkBuilder;
 use sha2::{Digest, Sha256};
 
+use super::skip::ContentFilter;
 use super::Language;
+use crate::graph::model::{SkipReason, SkippedFile};
+use crate::import::contracts::CONTRACT_EXTENSIONS;
+use crate::import::manifests::MANIFEST_NAMES;
 
 /// A file discovered during scanning
 #[derive(Debug, Clone)]
@@ -21,19 +27,39 @@ impl DiscoveredFile {
     /// # Errors
     /// Returns an error if the file cannot be read.
     pub fn compute_hash(&self) -> std::io::Result<String> {
-        let contents = fs::read(&self.path)?;
-        let mut hasher = Sha256::new();
-        hasher.update(&contents);
-        let result = hasher.finalize();
-        Ok(format!("{:x}", result))
+        Ok(content_hash(&fs::read(&self.path)?))
     }
 }
 
+/// SHA-256 hash of a file's contents, in hex
+pub(super) fn content_hash(contents: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(contents);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Ignore files with gitignore syntax that exclude paths from scans only
+pub const IGNORE_FILE_NAME: &str = ".motherignore";
+
 /// Scanner for discovering source files in a directory
 #[derive(Debug)]
 pub struct Scanner {
     root: PathBuf,
     languages: Vec<Language>,
+    /// Extensions of languages registered in the config
+    custom_extensions: HashMap<String, Language>,
+    overrides: Option<Override>,
+    /// Decides which files are left out for their content
+    content: ContentFilter,
+}
+
+/// Files found by [`Scanner::discover`]
+#[derive(Debug, Clone, Default)]
+pub struct Discovery {
+    /// Files to scan
+    pub files: Vec<DiscoveredFile>,
+    /// Files of a scanned language left out for their content
+    pub skipped: Vec<SkippedFile>,
 }
 
 impl Scanner {
@@ -47,9 +73,13 @@ impl Scanner {
                 Language::Python,
                 Language::TypeScript,
                 Language::JavaScript,
+                Language::Ruby,
                 Language::SysML,
                 Language::KerML,
             ],
+            custom_extensions: HashMap::new(),
+            overrides: None,
+            content: ContentFilter::default(),
         }
     }
 
@@ -60,22 +90,160 @@ impl Scanner {
         self
     }
 
+    /// Also scan files with these extensions as a language registered in
+    /// the config
+    ///
+    /// Custom extensions take precedence over built-in ones, so a server can
+    /// take over e.g. `.h` files.
+    #[must_use]
+    pub fn with_custom_language(mut self, language: Language, extensions: &[String]) -> Self {
+        for ext in extensions {
+            let ext = ext.trim_start_matches('.').to_lowercase();
+            self.custom_extensions.insert(ext, language.clone());
+        }
+        if !self.languages.contains(&language) {
+            self.languages.push(language);
+        }
+        self
+    }
+
+    /// Restrict scanning with include/exclude globs relative to the root
+    ///
+    /// When `include` is non-empty only matching files are scanned. Files
+    /// matching any `exclude` glob are always skipped.
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub fn with_filters(
+        mut self,
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Self, ignore::Error> {
+        if include.is_empty() && exclude.is_empty() {
+            self.overrides = None;
+            return Ok(self);
+        }
+
+        let mut builder = OverrideBuilder::new(&self.root);
+        for glob in include {
+            builder.add(glob)?;
+        }
+        for glob in exclude {
+            builder.add(&format!("!{glob}"))?;
+        }
+        self.overrides = Some(builder.build()?);
+        Ok(self)
+    }
+
+    /// Leave out files by size and content with `filter` instead of the
+    /// default [`ContentFilter`]
+    #[must_use]
+    pub fn with_content_filter(mut self, filter: ContentFilter) -> Self {
+        self.content = filter;
+        self
+    }
+
     /// Scan the directory and return discovered files
+    ///
+    /// Files the content filter rejects are left out; see
+    /// [`Scanner::discover`] for why.
     pub fn scan(&self) -> impl Iterator<Item = DiscoveredFile> + '_ {
-        WalkBuilder::new(&self.root)
+        self.candidates()
+            .filter_map(|(file, skipped)| skipped.is_none().then_some(file))
+    }
+
+    /// Scan the directory, returning the files left out for their content
+    /// with the reason next to the discovered files
+    #[must_use]
+    pub fn discover(&self) -> Discovery {
+        let mut discovery = Discovery::default();
+        for (file, skipped) in self.candidates() {
+            match skipped {
+                Some(reason) => discovery.skipped.push(SkippedFile {
+                    file: file.path.display().to_string(),
+                    reason,
+                }),
+                None => discovery.files.push(file),
+            }
+        }
+        discovery
+    }
+
+    /// Files of the scanned languages, with the reason to skip each, if any
+    ///
+    /// A file that cannot be read is kept, so the scan reports why it
+    /// failed.
+    fn candidates(&self) -> impl Iterator<Item = (DiscoveredFile, Option<SkipReason>)> + '_ {
+        self.walk().filter_map(|path| {
+            let language = self
+                .detect(&path)
+                .filter(|lang| self.languages.contains(lang))?;
+            let skipped = self.content.check(&path).unwrap_or_else(|e| {
+                tracing::debug!("Failed to check {}: {e}", path.display());
+                None
+            });
+            if let Some(reason) = &skipped {
+                tracing::debug!("Skipping {}: {reason}", path.display());
+            }
+            Some((DiscoveredFile { path, language }, skipped))
+        })
+    }
+
+    /// Scan the directory for files API contracts may be read from
+    ///
+    /// Returns `.proto`, YAML and JSON files passing the same filters as
+    /// [`Scanner::scan`]; see [`crate::import::contracts`].
+    pub fn contract_files(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.walk().filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| CONTRACT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+    }
+
+    /// Scan the directory for package manifests
+    ///
+    /// Returns `Cargo.toml`, `package.json` and `go.mod` files passing the
+    /// same filters as [`Scanner::scan`]; see [`crate::import::manifests`].
+    pub fn manifest_files(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.walk().filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| MANIFEST_NAMES.contains(&name))
+        })
+    }
+
+    /// Files under the root, honoring ignore files and the filters
+    ///
+    /// `.gitignore` and [`IGNORE_FILE_NAME`] files apply in the directory
+    /// they are in and below, deeper files and `.motherignore` rules taking
+    /// precedence.
+    fn walk(&self) -> impl Iterator<Item = PathBuf> {
+        let mut walker = WalkBuilder::new(&self.root);
+        walker
             .hidden(false)
             .git_ignore(true)
             .git_global(true)
             .git_exclude(true)
+            .add_custom_ignore_filename(IGNORE_FILE_NAME);
+        if let Some(overrides) = &self.overrides {
+            walker.overrides(overrides.clone());
+        }
+
+        walker
             .build()
             .filter_map(|entry| entry.ok())
             .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
-            .filter_map(|entry| {
-                let path = entry.into_path();
-                Language::from_path(&path)
-                    .filter(|lang| self.languages.contains(lang))
-                    .map(|language| DiscoveredFile { path, language })
-            })
+            .map(ignore::DirEntry::into_path)
+    }
+
+    /// Language of a file, by custom extensions first
+    fn detect(&self, path: &Path) -> Option<Language> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        self.custom_extensions
+            .get(&ext)
+            .cloned()
+            .or_else(|| Language::from_extension(&ext))
     }
 
     /// Get the root directory being scanned
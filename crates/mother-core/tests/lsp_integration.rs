@@ -11,10 +11,13 @@ use std::fs;
 use std::path::Path;
 use std::time::Duration;
 
-use mother_core::lsp::{LspClient, LspServerDefaults};
+use mother_core::lsp::{LspClient, LspServerDefaults, Readiness};
 use mother_core::scanner::Language;
 use tempfile::TempDir;
 
+/// Longest a server is waited for before a test uses it anyway
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Helper to check if a command exists on PATH and is functional
 fn command_exists(cmd: &str) -> bool {
     // First check if the command exists
@@ -91,7 +94,7 @@ pub fn create_user(name: &str) -> User {
     fs::write(&file_path, rust_code)?;
 
     // Start rust-analyzer
-    let config = LspServerDefaults::for_language(Language::Rust, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Rust, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
@@ -114,7 +117,7 @@ pub fn create_user(name: &str) -> User {
                 symbols = s;
                 break;
             }
-            Err(e) if attempt < 2 => {
+            Err(_) if attempt < 2 => {
                 // Retry on failure
                 continue;
             }
@@ -172,7 +175,7 @@ pub fn caller_two() -> i32 {
     let file_path = src_dir.join("lib.rs");
     fs::write(&file_path, rust_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Rust, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Rust, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
@@ -241,7 +244,7 @@ pub fn use_point() {
     let file_path = src_dir.join("lib.rs");
     fs::write(&file_path, rust_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Rust, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Rust, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
@@ -324,14 +327,19 @@ export function createUser(name: string, age: number): User {
     let tsconfig = r#"{ "compilerOptions": { "target": "es2020" } }"#;
     fs::write(temp.path().join("tsconfig.json"), tsconfig)?;
 
-    let config = LspServerDefaults::for_language(Language::TypeScript, temp.path());
+    let config = LspServerDefaults::for_language(&Language::TypeScript, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    // TypeScript server doesn't have explicit indexing
-    tokio::time::sleep(Duration::from_secs(2)).await;
+    // Wait for the server to settle instead of a fixed delay
+    client
+        .wait_until_ready(
+            Readiness::for_language(&Language::TypeScript),
+            READY_TIMEOUT,
+        )
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "typescript", ts_code).await?;
@@ -388,13 +396,18 @@ function caller2(): number {
     let tsconfig = r#"{ "compilerOptions": { "target": "es2020" } }"#;
     fs::write(temp.path().join("tsconfig.json"), tsconfig)?;
 
-    let config = LspServerDefaults::for_language(Language::TypeScript, temp.path());
+    let config = LspServerDefaults::for_language(&Language::TypeScript, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(2)).await;
+    client
+        .wait_until_ready(
+            Readiness::for_language(&Language::TypeScript),
+            READY_TIMEOUT,
+        )
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "typescript", ts_code).await?;
@@ -441,13 +454,15 @@ PI = 3.14159
     let file_path = temp.path().join("user.py");
     fs::write(&file_path, python_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Python, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Python, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(2)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Python), READY_TIMEOUT)
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "python", python_code).await?;
@@ -491,13 +506,15 @@ def caller2() -> int:
     let file_path = temp.path().join("refs.py");
     fs::write(&file_path, python_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Python, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Python, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(2)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Python), READY_TIMEOUT)
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "python", python_code).await?;
@@ -555,13 +572,15 @@ func createUser(name string) *User {
     let file_path = temp.path().join("main.go");
     fs::write(&file_path, go_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Go, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Go, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Go), READY_TIMEOUT)
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "go", go_code).await?;
@@ -614,13 +633,15 @@ func caller2() int {
     let file_path = temp.path().join("main.go");
     fs::write(&file_path, go_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Go, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Go, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Go), READY_TIMEOUT)
+        .await?;
 
     let file_uri = format!("file://{}", file_path.display());
     client.did_open(&file_uri, "go", go_code).await?;
@@ -682,7 +703,7 @@ pub fn internal_caller() -> i32 {
     let utils_path = src_dir.join("utils.rs");
     fs::write(&utils_path, utils_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Rust, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Rust, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
@@ -775,13 +796,18 @@ export function anotherCaller(): number {
     let main_path = temp.path().join("main.ts");
     fs::write(&main_path, main_code)?;
 
-    let config = LspServerDefaults::for_language(Language::TypeScript, temp.path());
+    let config = LspServerDefaults::for_language(&Language::TypeScript, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    client
+        .wait_until_ready(
+            Readiness::for_language(&Language::TypeScript),
+            READY_TIMEOUT,
+        )
+        .await?;
 
     let utils_uri = format!("file://{}", utils_path.display());
     let main_uri = format!("file://{}", main_path.display());
@@ -845,13 +871,15 @@ def another_caller() -> int:
     let main_path = temp.path().join("main.py");
     fs::write(&main_path, main_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Python, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Python, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Python), READY_TIMEOUT)
+        .await?;
 
     let utils_uri = format!("file://{}", utils_path.display());
     let main_uri = format!("file://{}", main_path.display());
@@ -927,13 +955,15 @@ func main() {
     let main_path = temp.path().join("main.go");
     fs::write(&main_path, main_code)?;
 
-    let config = LspServerDefaults::for_language(Language::Go, temp.path());
+    let config = LspServerDefaults::for_language(&Language::Go, temp.path());
     let mut client = LspClient::start(config).await?;
 
     let root_uri = format!("file://{}", temp.path().display());
     client.initialize(&root_uri).await?;
 
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    client
+        .wait_until_ready(Readiness::for_language(&Language::Go), READY_TIMEOUT)
+        .await?;
 
     let utils_uri = format!("file://{}", utils_path.display());
     let main_uri = format!("file://{}", main_path.display());
@@ -0,0 +1,88 @@
+//! Tests for LSP clients replaying recorded server traffic
+//!
+//! `fixtures/replay` holds traces in the format `--lsp-trace` writes, so these
+//! run without any language server installed.
+
+#![allow(clippy::unwrap_used)]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use mother_core::lsp::{LspClient, LspServerDefaults, Readiness, ReplayTransport};
+use mother_core::scanner::Language;
+use tempfile::TempDir;
+
+const FILE: &str = "file:///project/src/lib.rs";
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay")
+}
+
+async fn replay(dir: &Path, trace: Option<&Path>) -> anyhow::Result<LspClient> {
+    let config = LspServerDefaults::for_language(&Language::Rust, Path::new("/project"));
+    let mut client = LspClient::connect(config, &ReplayTransport::new(dir), trace).await?;
+    client.initialize("file:///project").await?;
+    client
+        .wait_until_ready(Readiness::Indexing, Duration::from_secs(5))
+        .await?;
+    Ok(client)
+}
+
+async fn scan_file(client: &LspClient) -> anyhow::Result<(Vec<String>, Vec<u32>)> {
+    client
+        .did_open(
+            FILE,
+            "rust",
+            "pub fn parse() {}\n\nfn main() { parse(); }\n",
+        )
+        .await?;
+    let symbols = client.document_symbols(FILE).await?;
+    let references = client.references(FILE, 0, 7, false).await?;
+    Ok((
+        symbols.into_iter().map(|s| s.name).collect(),
+        references.into_iter().map(|r| r.line).collect(),
+    ))
+}
+
+#[tokio::test]
+async fn test_replay_serves_recorded_responses() -> anyhow::Result<()> {
+    let client = replay(&fixtures(), None).await?;
+
+    let (symbols, reference_lines) = scan_file(&client).await?;
+    assert_eq!(symbols, vec!["parse", "main"]);
+    assert_eq!(reference_lines, vec![2]);
+
+    client.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unrecorded_requests_fail() -> anyhow::Result<()> {
+    let client = replay(&fixtures(), None).await?;
+
+    assert!(client.hover(FILE, 0, 7).await.is_err());
+
+    client.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_recording_fails_to_connect() {
+    let temp = TempDir::new().unwrap();
+    let config = LspServerDefaults::for_language(&Language::Go, temp.path());
+
+    let result = LspClient::connect(config, &ReplayTransport::new(temp.path()), None).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_traced_replay_replays_again() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let client = replay(&fixtures(), Some(&temp.path().join("rust.jsonl"))).await?;
+    let recorded = scan_file(&client).await?;
+    client.shutdown().await?;
+
+    let client = replay(temp.path(), None).await?;
+    assert_eq!(scan_file(&client).await?, recorded);
+    client.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+//! Time budget: Stop a scan that runs out of time with what it stored
+//!
+//! With `scan --max-duration`, the LSP manager gets a deadline. Phases 1, 2
+//! and 3 check it before each file or symbol and stop starting new ones
+//! once it passed, while the work in flight and its pending writes finish;
+//! external references and diagnostics are skipped. The passes that only
+//! use the store still run over what was stored. The run is then marked
+//! partial with a completion percentage, and the scan ends without an error,
+//! so CI jobs with hard time limits are not killed mid-write.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use mother_core::lsp::LspServerManager;
+use mother_core::progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
+use tracing::warn;
+
+use super::ScanOptions;
+
+/// Phases whose work the completion percentage measures, each weighing the
+/// same
+const LSP_PHASES: [ScanPhase; 3] = [ScanPhase::Files, ScanPhase::Symbols, ScanPhase::References];
+
+/// Parse a duration such as `90s`, `30m` or `1h30m`; a bare number is
+/// seconds
+///
+/// # Errors
+/// Returns an error if the text is not a positive duration.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration '{text}': expected e.g. 90s, 30m or 1h30m");
+    if let Ok(secs) = text.parse::<u64>() {
+        return Some(secs)
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid);
+    }
+    let mut secs = 0u64;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(digits);
+        let mut units = tail.chars();
+        let unit = match units.next() {
+            Some('h') => 3600,
+            Some('m') => 60,
+            Some('s') => 1,
+            _ => return Err(invalid()),
+        };
+        let number: u64 = number.parse().map_err(|_| invalid())?;
+        secs = number
+            .checked_mul(unit)
+            .and_then(|part| secs.checked_add(part))
+            .ok_or_else(invalid)?;
+        rest = units.as_str();
+    }
+    Some(secs)
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+/// Units of work a phase started with and finished
+#[derive(Debug, Clone, Copy, Default)]
+struct Work {
+    total: u64,
+    done: u64,
+}
+
+impl Work {
+    fn share(self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.done as f64 / self.total as f64).min(1.0)
+    }
+}
+
+type PhaseWork = Arc<Mutex<HashMap<ScanPhase, Work>>>;
+
+/// The deadline of a scan, and how far its phases got
+pub(crate) struct ScanBudget {
+    limit: Duration,
+    deadline: Instant,
+    work: PhaseWork,
+}
+
+impl ScanBudget {
+    /// A budget of `limit` for a scan that started at `started`
+    pub(crate) fn new(limit: Duration, started: Instant) -> Self {
+        Self {
+            limit,
+            deadline: started + limit,
+            work: PhaseWork::default(),
+        }
+    }
+
+    /// When new work stops
+    pub(crate) fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// `options` with their progress measured for the completion
+    pub(crate) fn options(&self, options: &ScanOptions) -> ScanOptions {
+        ScanOptions {
+            progress: self.progress(options.progress.clone()),
+            ..options.clone()
+        }
+    }
+
+    /// A progress handle that measures the phases' work, then passes each
+    /// event on to `inner`
+    pub(crate) fn progress(&self, inner: Progress) -> Progress {
+        Progress::new(MeasuringProgress {
+            work: Arc::clone(&self.work),
+            inner,
+        })
+    }
+
+    /// Percentage of the LSP phases' work done, rounded down
+    ///
+    /// A phase after one that stopped early counts as not done, since it
+    /// only saw part of its input.
+    pub(crate) fn completion(&self) -> u8 {
+        let work = self.work.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut done = 0.0;
+        for phase in LSP_PHASES {
+            let share = work.get(&phase).map_or(0.0, |work| work.share());
+            done += share;
+            if share < 1.0 {
+                break;
+            }
+        }
+        (done * 100.0 / LSP_PHASES.len() as f64) as u8
+    }
+
+    /// The completion of a scan whose LSP work ended after the deadline,
+    /// logging that it stopped early; `None` for scans that finished in time
+    pub(crate) fn finish(&self, lsp_manager: &LspServerManager) -> Option<u8> {
+        if !lsp_manager.past_deadline() {
+            return None;
+        }
+        let completion = self.completion();
+        warn!(
+            "Time budget of {} ran out: stopped after {completion}% of the scan and marked the \
+             run partial",
+            format_duration(self.limit)
+        );
+        Some(completion)
+    }
+}
+
+/// `1h30m`-style text of a whole number of seconds
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let parts = [(hours, "h"), (minutes, "m"), (secs, "s")];
+    let text: String = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect();
+    if text.is_empty() {
+        "0s".to_string()
+    } else {
+        text
+    }
+}
+
+/// Counts the phases' work before passing events on
+struct MeasuringProgress {
+    work: PhaseWork,
+    inner: Progress,
+}
+
+impl ProgressReporter for MeasuringProgress {
+    fn report(&self, event: ProgressEvent) {
+        {
+            let mut work = self.work.lock().unwrap_or_else(PoisonError::into_inner);
+            match event {
+                ProgressEvent::Started { phase, total } => {
+                    work.entry(phase).or_default().total += total;
+                }
+                ProgressEvent::Advanced { phase, .. } => {
+                    work.entry(phase).or_default().done += 1;
+                }
+                ProgressEvent::Finished { .. } => {}
+            }
+        }
+        self.inner.report(event);
+    }
+}
+
+#[cfg(test)]
+mod tests;
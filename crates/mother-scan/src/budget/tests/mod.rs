@@ -0,0 +1,3 @@
+//! Tests for budget module
+
+mod tests_budget;
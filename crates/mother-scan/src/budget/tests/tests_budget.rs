@@ -0,0 +1,109 @@
+//! Tests for the scan time budget
+
+#![allow(clippy::unwrap_used)]
+
+use std::time::{Duration, Instant};
+
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::LspServerManager;
+use mother_core::progress::{Progress, ScanPhase};
+use tempfile::TempDir;
+
+use super::super::{format_duration, parse_duration, ScanBudget};
+use crate::{run_with_store, ScanOptions};
+
+#[test]
+fn test_parse_duration_units() {
+    assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+    assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+    assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+    assert_eq!(parse_duration("2h5s").unwrap(), Duration::from_secs(7205));
+    assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+}
+
+#[test]
+fn test_parse_duration_rejects_invalid_text() {
+    for text in ["", "0", "0m", "m", "1d", "1.5h", "-5m", "30 m"] {
+        let error = parse_duration(text).unwrap_err();
+        assert!(error.contains("expected e.g."), "{text}: {error}");
+    }
+}
+
+#[test]
+fn test_format_duration_round_trips() {
+    for text in ["90s", "30m", "1h30m", "2h5s"] {
+        let formatted = format_duration(parse_duration(text).unwrap());
+        let expected = if text == "90s" { "1m30s" } else { text };
+        assert_eq!(formatted, expected);
+    }
+}
+
+#[test]
+fn test_completion_weighs_lsp_phases_equally() {
+    let budget = ScanBudget::new(Duration::from_secs(60), Instant::now());
+    let progress = budget.progress(Progress::default());
+    assert_eq!(budget.completion(), 0);
+
+    progress.start(ScanPhase::Files, 4);
+    for _ in 0..4 {
+        progress.advance(ScanPhase::Files, 0, 0);
+    }
+    progress.start(ScanPhase::Imports, 4);
+    progress.start(ScanPhase::Symbols, 4);
+    progress.advance(ScanPhase::Symbols, 3, 0);
+    assert_eq!(budget.completion(), 41);
+
+    for _ in 0..3 {
+        progress.advance(ScanPhase::Symbols, 3, 0);
+    }
+    progress.start(ScanPhase::References, 0);
+    assert_eq!(budget.completion(), 100);
+}
+
+#[test]
+fn test_completion_stops_at_the_first_phase_cut_short() {
+    let budget = ScanBudget::new(Duration::from_secs(60), Instant::now());
+    let progress = budget.progress(Progress::default());
+
+    progress.start(ScanPhase::Files, 2);
+    progress.advance(ScanPhase::Files, 0, 0);
+    progress.start(ScanPhase::Symbols, 0);
+    progress.start(ScanPhase::References, 0);
+    assert_eq!(budget.completion(), 16);
+}
+
+#[test]
+fn test_finish_reports_completion_only_past_the_deadline() {
+    let budget = ScanBudget::new(Duration::from_secs(60), Instant::now());
+    let mut lsp_manager = LspServerManager::new("/repo");
+    assert_eq!(budget.finish(&lsp_manager), None);
+
+    lsp_manager.set_deadline(budget.deadline());
+    assert_eq!(budget.finish(&lsp_manager), None);
+
+    let expired = ScanBudget::new(
+        Duration::from_secs(1),
+        Instant::now() - Duration::from_secs(2),
+    );
+    lsp_manager.set_deadline(expired.deadline());
+    assert_eq!(expired.finish(&lsp_manager), Some(0));
+}
+
+#[tokio::test]
+async fn test_scan_out_of_time_ends_partial_without_starting_servers() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+    let options = ScanOptions {
+        max_duration: Some(Duration::ZERO),
+        ..ScanOptions::default()
+    };
+
+    run_with_store(temp.path(), &store, &options).await.unwrap();
+
+    let runs = store.scan_runs().await.unwrap();
+    assert_eq!(runs[0].status, "partial");
+    let report = store.scan_report(&runs[0].id).await.unwrap().unwrap();
+    assert_eq!(report.completion, Some(0));
+    assert_eq!(store.stats().await.unwrap().files, 0);
+}
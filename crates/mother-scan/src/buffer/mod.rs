@@ -0,0 +1,196 @@
+//! Symbol buffer: The symbols Phase 2 hands on to Phase 3
+//!
+//! Phase 2 adds each file's [`SymbolInfo`]s to a [`SymbolBuffer`]. Without a
+//! limit they all stay in memory. With one, the buffer spills its symbols to
+//! a temporary file whenever it holds that many, so peak memory depends on
+//! the limit rather than the size of the repository. The phases after it
+//! read the symbols back a chunk at a time through [`SymbolBuffer::chunks`];
+//! a chunk always holds whole files.
+
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroUsize;
+
+use anyhow::{Context, Result};
+use mother_core::scanner::Language;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+use super::SymbolInfo;
+
+/// Symbols extracted by Phase 2, held in memory up to a limit and spilled to
+/// disk beyond it
+#[derive(Default)]
+pub struct SymbolBuffer {
+    /// Most symbols held in memory; unbounded when unset
+    limit: Option<NonZeroUsize>,
+    memory: Vec<SymbolInfo>,
+    spill: Option<Spill>,
+    len: usize,
+}
+
+impl SymbolBuffer {
+    /// An empty buffer holding at most `limit` symbols in memory
+    #[must_use]
+    pub fn new(limit: Option<NonZeroUsize>) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Add the symbols of one file, spilling the buffer once it holds its
+    /// limit
+    ///
+    /// If the spill file cannot be written, the symbols stay in memory and
+    /// the buffer stops spilling.
+    pub fn extend(&mut self, symbols: Vec<SymbolInfo>) {
+        self.len += symbols.len();
+        self.memory.extend(symbols);
+        if self
+            .limit
+            .is_none_or(|limit| self.memory.len() < limit.get())
+        {
+            return;
+        }
+        if let Err(e) = self.spill() {
+            tracing::warn!("{e:#}, keeping the remaining symbols in memory");
+            self.limit = None;
+        }
+    }
+
+    /// Write the symbols in memory to the spill file as one chunk
+    fn spill(&mut self) -> Result<()> {
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => self.spill.insert(Spill::create()?),
+        };
+        spill.write(&self.memory)?;
+        tracing::debug!(
+            "Spilled {} symbols to {}",
+            self.memory.len(),
+            spill.file.path().display()
+        );
+        self.memory.clear();
+        Ok(())
+    }
+
+    /// Number of symbols in the buffer, spilled or not
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the buffer holds no symbols
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The symbols in the order they were added: the spilled chunks read
+    /// back from disk, then the symbols still in memory
+    ///
+    /// Each call reads the spill file again, so phases can go over the
+    /// symbols as often as they need.
+    pub fn chunks(&self) -> impl Iterator<Item = Result<Cow<'_, [SymbolInfo]>>> + '_ {
+        let spilled = self.spill.iter().flat_map(Spill::chunks);
+        let memory = (!self.memory.is_empty()).then_some(Ok(Cow::Borrowed(&self.memory[..])));
+        spilled.map(|chunk| chunk.map(Cow::Owned)).chain(memory)
+    }
+}
+
+impl From<Vec<SymbolInfo>> for SymbolBuffer {
+    fn from(symbols: Vec<SymbolInfo>) -> Self {
+        let mut buffer = Self::default();
+        buffer.extend(symbols);
+        buffer
+    }
+}
+
+/// Temporary file of spilled chunks, one JSON array of symbols per line
+struct Spill {
+    file: NamedTempFile,
+    /// Bytes of the chunks written so far
+    size: u64,
+}
+
+impl Spill {
+    fn create() -> Result<Self> {
+        let file = NamedTempFile::new().context("Failed to create a file to spill symbols to")?;
+        Ok(Self { file, size: 0 })
+    }
+
+    /// Append a chunk, truncating a partly written one
+    fn write(&mut self, symbols: &[SymbolInfo]) -> Result<()> {
+        let records: Vec<Record<'_>> = symbols.iter().map(Record::from).collect();
+        let mut line = serde_json::to_vec(&records)?;
+        line.push(b'\n');
+        let file = self.file.as_file_mut();
+        if let Err(e) = file.write_all(&line) {
+            let _ = file.set_len(self.size);
+            return Err(e).context("Failed to spill symbols");
+        }
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// The chunks written so far
+    fn chunks(&self) -> impl Iterator<Item = Result<Vec<SymbolInfo>>> {
+        let (lines, error) = match self.file.reopen() {
+            Ok(file) => (Some(BufReader::new(file).lines()), None),
+            Err(e) => (None, Some(Err(e).context("Failed to read spilled symbols"))),
+        };
+        error
+            .into_iter()
+            .chain(lines.into_iter().flatten().map(|line| {
+                let line = line.context("Failed to read spilled symbols")?;
+                let records: Vec<Record<'_>> =
+                    serde_json::from_str(&line).context("Invalid spilled symbols")?;
+                Ok(records.into_iter().map(SymbolInfo::from).collect())
+            }))
+    }
+}
+
+/// A symbol as written to the spill file
+#[derive(Serialize, Deserialize)]
+struct Record<'a> {
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+    #[serde(borrow)]
+    file_uri: Cow<'a, str>,
+    start_line: u32,
+    end_line: u32,
+    start_col: u32,
+    #[serde(borrow)]
+    language: Cow<'a, str>,
+}
+
+impl<'a> From<&'a SymbolInfo> for Record<'a> {
+    fn from(symbol: &'a SymbolInfo) -> Self {
+        Self {
+            id: Cow::Borrowed(&symbol.id),
+            file_uri: Cow::Borrowed(&symbol.file_uri),
+            start_line: symbol.start_line,
+            end_line: symbol.end_line,
+            start_col: symbol.start_col,
+            language: Cow::Owned(symbol.language.to_string()),
+        }
+    }
+}
+
+impl From<Record<'_>> for SymbolInfo {
+    fn from(record: Record<'_>) -> Self {
+        let language = record.language.into_owned();
+        Self {
+            id: record.id.into_owned(),
+            file_uri: record.file_uri.into_owned(),
+            start_line: record.start_line,
+            end_line: record.end_line,
+            start_col: record.start_col,
+            language: language.parse().unwrap_or(Language::Other(language)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for buffer module
+
+mod tests_buffer;
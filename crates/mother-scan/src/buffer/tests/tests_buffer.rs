@@ -0,0 +1,115 @@
+//! Tests for the symbol buffer
+
+#![allow(clippy::unwrap_used)]
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use mother_core::scanner::Language;
+
+use super::super::SymbolBuffer;
+use crate::SymbolInfo;
+
+fn symbol(file: &str, index: u32, language: Language) -> SymbolInfo {
+    SymbolInfo {
+        id: format!("{file}#{index}"),
+        file_uri: format!("file:///repo/{file}"),
+        start_line: index,
+        end_line: index + 2,
+        start_col: 4,
+        language,
+    }
+}
+
+fn file_symbols(file: &str, count: u32) -> Vec<SymbolInfo> {
+    (0..count)
+        .map(|i| symbol(file, i, Language::Rust))
+        .collect()
+}
+
+fn ids(buffer: &SymbolBuffer) -> Vec<String> {
+    buffer
+        .chunks()
+        .flat_map(|chunk| chunk.unwrap().into_owned())
+        .map(|s| s.id)
+        .collect()
+}
+
+#[test]
+fn test_unlimited_buffer_keeps_symbols_in_one_chunk() {
+    let mut buffer = SymbolBuffer::new(None);
+    buffer.extend(file_symbols("a.rs", 3));
+    buffer.extend(file_symbols("b.rs", 2));
+
+    let chunks: Vec<_> = buffer.chunks().map(Result::unwrap).collect();
+    assert_eq!(buffer.len(), 5);
+    assert_eq!(chunks.len(), 1);
+    assert!(matches!(chunks[0], Cow::Borrowed(_)));
+}
+
+#[test]
+fn test_empty_buffer_has_no_chunks() {
+    let buffer = SymbolBuffer::new(NonZeroUsize::new(2));
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.chunks().count(), 0);
+}
+
+#[test]
+fn test_buffer_spills_whole_files_once_it_holds_its_limit() {
+    let mut buffer = SymbolBuffer::new(NonZeroUsize::new(3));
+    for file in ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"] {
+        buffer.extend(file_symbols(file, 2));
+    }
+
+    let chunks: Vec<_> = buffer.chunks().map(Result::unwrap).collect();
+    let sizes: Vec<_> = chunks.iter().map(|chunk| chunk.len()).collect();
+    assert_eq!(sizes, vec![4, 4, 2]);
+    assert!(matches!(chunks[0], Cow::Owned(_)));
+    assert!(matches!(chunks[2], Cow::Borrowed(_)));
+    let mut seen = HashSet::new();
+    for chunk in &chunks {
+        let files: HashSet<_> = chunk.iter().map(|s| s.file_uri.clone()).collect();
+        assert!(files.is_disjoint(&seen), "a file was split across chunks");
+        seen.extend(files);
+    }
+    assert_eq!(buffer.len(), 10);
+}
+
+#[test]
+fn test_spilled_symbols_read_back_in_order_and_intact() {
+    let mut buffer = SymbolBuffer::new(NonZeroUsize::new(1));
+    buffer.extend(file_symbols("a.rs", 2));
+    buffer.extend(vec![symbol("b.sysml", 7, Language::SysML)]);
+    buffer.extend(vec![symbol("c.sql", 1, Language::Other("sql".to_string()))]);
+
+    let symbols: Vec<_> = buffer
+        .chunks()
+        .flat_map(|chunk| chunk.unwrap().into_owned())
+        .collect();
+    assert_eq!(symbols.len(), 4);
+    assert_eq!(symbols[2].id, "b.sysml#7");
+    assert_eq!(symbols[2].file_uri, "file:///repo/b.sysml");
+    assert_eq!(
+        (
+            symbols[2].start_line,
+            symbols[2].end_line,
+            symbols[2].start_col
+        ),
+        (7, 9, 4)
+    );
+    assert_eq!(symbols[2].language, Language::SysML);
+    assert_eq!(symbols[3].language, Language::Other("sql".to_string()));
+}
+
+#[test]
+fn test_spilled_symbols_can_be_read_more_than_once() {
+    let mut buffer = SymbolBuffer::new(NonZeroUsize::new(2));
+    for file in ["a.rs", "b.rs", "c.rs"] {
+        buffer.extend(file_symbols(file, 1));
+    }
+
+    let first = ids(&buffer);
+    assert_eq!(first, vec!["a.rs#0", "b.rs#0", "c.rs#0"]);
+    assert_eq!(ids(&buffer), first);
+}
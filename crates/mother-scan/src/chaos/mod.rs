@@ -0,0 +1,632 @@
+//! Chaos mode: Inject faults into a scan for resilience testing
+//!
+//! The hidden `scan --chaos` option takes a [`ChaosConfig`] such as
+//! `lsp-timeout=0.05,store-failure=0.01,kill=0.0001,seed=7`. A
+//! [`FaultInjector`] built from it is wrapped around the LSP manager (see
+//! [`LspFaults`]) and the graph store (see [`ChaosStore`]):
+//!
+//! - `lsp-timeout`: share of LSP client requests that fail with a timeout;
+//! - `store-failure`: share of store writes that fail;
+//! - `kill`: chance per store write that the process aborts, as if killed.
+//!
+//! Faults are drawn from a seeded generator, so a run can be repeated with
+//! the seed it logs. Reads are never failed.
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use mother_core::graph::backup::GraphBackup;
+use mother_core::graph::complexity::SymbolComplexity;
+use mother_core::graph::export::{ExportRecord, GraphExport};
+use mother_core::graph::integrity::{IntegrityIssue, IntegrityRepair};
+use mother_core::graph::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, ExternalSymbolNode, FileMetrics,
+    FileWrite, FunctionSignature, ImportEdge, ModuleNode, RepositoryDependency, RepositoryNode,
+    ScanReport, ScanRun, ScanStatus, SymbolCoverage, SymbolNode,
+};
+use mother_core::graph::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, GraphStore,
+    ImporterResult, Page, PathScope, QueryLanguage, QueryParams, RawRow, ReferenceDirection,
+    ReferenceGroup, ReferenceGrouping, ReferenceResult, RowStream, ScanResult, ScanRunResult,
+    StoreError, SymbolMatch, SymbolResult, SymbolVersionResult, TypeUsage, TypeUsageResult,
+};
+use mother_core::lsp::LspFaults;
+use mother_core::scanner::Language;
+use tracing::{error, warn};
+
+use super::rng::SplitMix64;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Fault rates for a chaos scan, each from 0.0 to 1.0
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Share of LSP client requests that time out
+    pub lsp_timeout: f64,
+    /// Share of store writes that fail
+    pub store_failure: f64,
+    /// Chance per store write that the process is killed
+    pub kill: f64,
+    /// Generator seed; a time-based seed is used and logged when unset
+    pub seed: Option<u64>,
+}
+
+impl FromStr for ChaosConfig {
+    type Err = String;
+
+    /// Parse comma-separated `key=value` pairs; unset rates are 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got '{pair}'"))?;
+            match key.trim() {
+                "lsp-timeout" => config.lsp_timeout = rate(key, value)?,
+                "store-failure" => config.store_failure = rate(key, value)?,
+                "kill" => config.kill = rate(key, value)?,
+                "seed" => {
+                    let seed = value.trim().parse().map_err(|e| format!("seed: {e}"))?;
+                    config.seed = Some(seed);
+                }
+                other => return Err(format!("unknown fault '{other}'")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn rate(key: &str, value: &str) -> Result<f64, String> {
+    let rate: f64 = value.trim().parse().map_err(|e| format!("{key}: {e}"))?;
+    if (0.0..=1.0).contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(format!("{key}: rate must be between 0 and 1, got {rate}"))
+    }
+}
+
+// ============================================================================
+// Fault injection
+// ============================================================================
+
+/// Draws faults at the configured rates
+pub struct FaultInjector {
+    config: ChaosConfig,
+    rng: Mutex<SplitMix64>,
+}
+
+impl FaultInjector {
+    /// Create an injector, logging the seed so the run can be repeated
+    #[must_use]
+    pub fn new(config: ChaosConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        });
+        warn!(
+            "Chaos mode: lsp-timeout={}, store-failure={}, kill={}, seed={}",
+            config.lsp_timeout, config.store_failure, config.kill, seed
+        );
+        Self {
+            config,
+            rng: Mutex::new(SplitMix64::from_seed(seed)),
+        }
+    }
+
+    fn roll(&self, rate: f64) -> bool {
+        rate > 0.0
+            && self
+                .rng
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .chance(rate)
+    }
+
+    /// Kill the process or fail the write `operation`, at the configured rates
+    ///
+    /// # Errors
+    /// Returns a storage error when a write failure is injected.
+    pub fn store_write(&self, operation: &str) -> Result<(), StoreError> {
+        if self.roll(self.config.kill) {
+            error!("Chaos mode: killing process during {operation}");
+            std::process::abort();
+        }
+        if self.roll(self.config.store_failure) {
+            return Err(StoreError::Backend(format!(
+                "injected write failure in {operation}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl LspFaults for FaultInjector {
+    fn inject(&self, language: &Language) -> Option<anyhow::Error> {
+        self.roll(self.config.lsp_timeout)
+            .then(|| anyhow::anyhow!("injected timeout waiting for the {language:?} server"))
+    }
+}
+
+// ============================================================================
+// Store wrapper
+// ============================================================================
+
+/// Graph store wrapper injecting write failures and process kills
+pub struct ChaosStore<'a> {
+    inner: &'a dyn GraphStore,
+    faults: Arc<FaultInjector>,
+}
+
+impl<'a> ChaosStore<'a> {
+    #[must_use]
+    pub fn new(inner: &'a dyn GraphStore, faults: Arc<FaultInjector>) -> Self {
+        Self { inner, faults }
+    }
+}
+
+#[async_trait]
+impl GraphStore for ChaosStore<'_> {
+    async fn create_scan_run(&self, scan_run: &ScanRun) -> Result<bool, StoreError> {
+        self.faults.store_write("create_scan_run")?;
+        self.inner.create_scan_run(scan_run).await
+    }
+
+    async fn set_scan_report(&self, report: &ScanReport) -> Result<(), StoreError> {
+        self.faults.store_write("set_scan_report")?;
+        self.inner.set_scan_report(report).await
+    }
+
+    async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("set_scan_status")?;
+        self.inner.set_scan_status(scan_run_id, status).await
+    }
+
+    async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), StoreError> {
+        self.faults.store_write("tag_scan_run")?;
+        self.inner.tag_scan_run(scan_run_id, tag).await
+    }
+
+    async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), StoreError> {
+        self.faults.store_write("annotate_scan_run")?;
+        self.inner.annotate_scan_run(scan_run_id, note).await
+    }
+
+    async fn create_file_if_new(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+        language: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>, StoreError> {
+        self.faults.store_write("create_file_if_new")?;
+        self.inner
+            .create_file_if_new(file_path, content_hash, language, commit_sha)
+            .await
+    }
+
+    async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, StoreError> {
+        self.faults.store_write("link_stored_file")?;
+        self.inner.link_stored_file(content_hash, commit_sha).await
+    }
+
+    async fn write_file(&self, file: &FileWrite) -> Result<bool, StoreError> {
+        self.faults.store_write("write_file")?;
+        self.inner.write_file(file).await
+    }
+
+    async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, StoreError> {
+        self.faults.store_write("unlink_file")?;
+        self.inner.unlink_file(commit_sha, file_path).await
+    }
+
+    async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("set_file_metrics")?;
+        self.inner.set_file_metrics(content_hash, metrics).await
+    }
+
+    async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("set_file_imports")?;
+        self.inner.set_file_imports(content_hash, modules).await
+    }
+
+    async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_import_edges")?;
+        self.inner.create_import_edges(commit_sha, edges).await
+    }
+
+    async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_directory_summaries")?;
+        self.inner
+            .create_directory_summaries(commit_sha, summaries)
+            .await
+    }
+
+    async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_modules")?;
+        self.inner.create_modules(commit_sha, modules).await
+    }
+
+    async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_contracts")?;
+        self.inner.create_contracts(commit_sha, contracts).await
+    }
+
+    async fn create_symbols_batch(
+        &self,
+        symbols: &[SymbolNode],
+        content_hash: &str,
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_symbols_batch")?;
+        self.inner.create_symbols_batch(symbols, content_hash).await
+    }
+
+    async fn create_edge(&self, edge: &Edge) -> Result<(), StoreError> {
+        self.faults.store_write("create_edge")?;
+        self.inner.create_edge(edge).await
+    }
+
+    async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), StoreError> {
+        self.faults.store_write("create_edges_batch")?;
+        self.inner.create_edges_batch(edges).await
+    }
+
+    async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_modified_edges")?;
+        self.inner
+            .create_modified_edges(commit_sha, symbol_ids)
+            .await
+    }
+
+    async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("mark_references_truncated")?;
+        self.inner.mark_references_truncated(symbol_id, total).await
+    }
+
+    async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("set_symbol_complexity")?;
+        self.inner.set_symbol_complexity(complexity).await
+    }
+
+    async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), StoreError> {
+        self.faults.store_write("set_symbol_coverage")?;
+        self.inner.set_symbol_coverage(coverage).await
+    }
+
+    async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), StoreError> {
+        self.faults.store_write("upsert_repository")?;
+        self.inner.upsert_repository(repository).await
+    }
+
+    async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("set_repository_dependencies")?;
+        self.inner.set_repository_dependencies(dependencies).await
+    }
+
+    async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, StoreError> {
+        self.faults.store_write("delete_scan_run")?;
+        self.inner.delete_scan_run(id).await
+    }
+
+    async fn repair_integrity(&self) -> Result<IntegrityRepair, StoreError> {
+        self.faults.store_write("repair_integrity")?;
+        self.inner.repair_integrity().await
+    }
+
+    async fn restore(&self, backup: &GraphBackup) -> Result<usize, StoreError> {
+        self.faults.store_write("restore")?;
+        self.inner.restore(backup).await
+    }
+
+    async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_diagnostics")?;
+        self.inner
+            .create_diagnostics(content_hash, diagnostics)
+            .await
+    }
+
+    async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_function_signatures")?;
+        self.inner.create_function_signatures(signatures).await
+    }
+
+    async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), StoreError> {
+        self.faults.store_write("create_external_reference")?;
+        self.inner
+            .create_external_reference(source_id, target, line, column)
+            .await
+    }
+
+    async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.find_symbols(pattern, scope, page).await
+    }
+
+    async fn search_symbols(&self, text: &str, page: Page) -> Result<Vec<SymbolMatch>, StoreError> {
+        self.inner.search_symbols(text, page).await
+    }
+
+    async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, StoreError> {
+        self.inner
+            .similar_symbols(symbol_id, commit_sha, limit)
+            .await
+    }
+
+    async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.symbols_in_file(file_path).await
+    }
+
+    async fn modified_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.modified_symbols(commit_sha).await
+    }
+
+    async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner
+            .find_symbols_at_commit(pattern, commit_sha, scope, page)
+            .await
+    }
+
+    async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, StoreError> {
+        self.inner.symbols_at_commit(commit_sha).await
+    }
+
+    async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError> {
+        self.inner.symbol_history(qualified_name).await
+    }
+
+    async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, StoreError> {
+        self.inner
+            .previous_file_symbols(file_path, content_hash)
+            .await
+    }
+
+    async fn find_references_to(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.inner.find_references_to(symbol_name, page).await
+    }
+
+    async fn find_references_from(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.inner.find_references_from(symbol_name, page).await
+    }
+
+    async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, StoreError> {
+        self.inner
+            .find_reference_groups(symbol_name, direction, grouping, page)
+            .await
+    }
+
+    async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, StoreError> {
+        self.inner.directory_summary(path, commit_sha).await
+    }
+
+    async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, StoreError> {
+        self.inner.directories_at_commit(commit_sha).await
+    }
+
+    async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, StoreError> {
+        self.inner.modules_at_commit(commit_sha).await
+    }
+
+    async fn contracts_at_commit(&self, commit_sha: &str) -> Result<Vec<ContractNode>, StoreError> {
+        self.inner.contracts_at_commit(commit_sha).await
+    }
+
+    async fn repositories(&self) -> Result<Vec<RepositoryNode>, StoreError> {
+        self.inner.repositories().await
+    }
+
+    async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, StoreError> {
+        self.inner.repository_dependencies().await
+    }
+
+    async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, StoreError> {
+        self.inner
+            .find_diagnostics(commit_sha, severity, scope)
+            .await
+    }
+
+    async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, StoreError> {
+        self.inner
+            .find_functions_by_type(commit_sha, type_name, usage, scope)
+            .await
+    }
+
+    async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, StoreError> {
+        self.inner.list_files(pattern, page).await
+    }
+
+    async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, StoreError> {
+        self.inner.file_metrics(commit_sha, pattern).await
+    }
+
+    async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError> {
+        self.inner.find_importers(module).await
+    }
+
+    async fn stats(&self) -> Result<GraphStats, StoreError> {
+        self.inner.stats().await
+    }
+
+    async fn resolve_commit(&self, version: Option<&str>) -> Result<Option<String>, StoreError> {
+        self.inner.resolve_commit(version).await
+    }
+
+    async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, StoreError> {
+        self.inner.scan_runs().await
+    }
+
+    async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, StoreError> {
+        self.inner.scan_report(scan_run_id).await
+    }
+
+    async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, StoreError> {
+        self.inner.check_integrity().await
+    }
+
+    async fn schema_version(&self) -> Result<u32, StoreError> {
+        self.inner.schema_version().await
+    }
+
+    async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, StoreError> {
+        self.inner.recent_scans(branch, limit).await
+    }
+
+    async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, StoreError> {
+        self.inner.export_commit(commit_sha).await
+    }
+
+    fn stream_export<'a>(&'a self, commit_sha: &'a str) -> RowStream<'a, ExportRecord> {
+        self.inner.stream_export(commit_sha)
+    }
+
+    async fn backup(&self) -> Result<GraphBackup, StoreError> {
+        self.inner.backup().await
+    }
+
+    async fn execute_raw(&self, query: &str, params: &QueryParams) -> Result<usize, StoreError> {
+        self.inner.execute_raw(query, params).await
+    }
+
+    async fn fetch_raw(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> Result<Vec<RawRow>, StoreError> {
+        self.inner.fetch_raw(query, params).await
+    }
+
+    fn query_language(&self) -> QueryLanguage {
+        self.inner.query_language()
+    }
+}
+
+#[cfg(test)]
+mod tests;
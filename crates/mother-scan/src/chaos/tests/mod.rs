@@ -0,0 +1,3 @@
+//! Tests for chaos module
+
+mod tests_chaos;
@@ -0,0 +1,142 @@
+//! Tests for fault injection
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::LspFaults;
+use mother_core::scanner::Language;
+use tempfile::TempDir;
+
+use super::super::{ChaosConfig, ChaosStore, FaultInjector};
+use crate::{run_with_store, ScanOptions};
+
+fn config(lsp_timeout: f64, store_failure: f64) -> ChaosConfig {
+    ChaosConfig {
+        lsp_timeout,
+        store_failure,
+        kill: 0.0,
+        seed: Some(7),
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[test]
+fn test_parse_all_faults() {
+    let parsed: ChaosConfig = "lsp-timeout=0.05, store-failure=0.01,kill=0.0001,seed=7"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        parsed,
+        ChaosConfig {
+            lsp_timeout: 0.05,
+            store_failure: 0.01,
+            kill: 0.0001,
+            seed: Some(7),
+        }
+    );
+}
+
+#[test]
+fn test_parse_unset_rates_are_zero() {
+    let parsed: ChaosConfig = "store-failure=0.5".parse().unwrap();
+
+    assert_eq!(parsed.store_failure, 0.5);
+    assert_eq!(parsed.lsp_timeout, 0.0);
+    assert_eq!(parsed.kill, 0.0);
+    assert!(parsed.seed.is_none());
+}
+
+#[test]
+fn test_parse_rejects_invalid_input() {
+    for input in [
+        "lsp-timeout",
+        "latency=0.1",
+        "kill=1.5",
+        "store-failure=-0.1",
+        "lsp-timeout=often",
+        "seed=-1",
+    ] {
+        assert!(input.parse::<ChaosConfig>().is_err(), "accepted '{input}'");
+    }
+}
+
+// ============================================================================
+// Fault injection
+// ============================================================================
+
+#[test]
+fn test_rates_of_zero_and_one() {
+    let never = FaultInjector::new(config(0.0, 0.0));
+    let always = FaultInjector::new(config(1.0, 1.0));
+
+    for _ in 0..100 {
+        assert!(never.store_write("create_edge").is_ok());
+        assert!(never.inject(&Language::Rust).is_none());
+        assert!(always.store_write("create_edge").is_err());
+        assert!(always.inject(&Language::Rust).is_some());
+    }
+}
+
+#[test]
+fn test_same_seed_injects_same_faults() {
+    let draws = |injector: &FaultInjector| -> Vec<bool> {
+        (0..64)
+            .map(|_| injector.store_write("create_edge").is_err())
+            .collect()
+    };
+
+    let first = draws(&FaultInjector::new(config(0.0, 0.5)));
+    let second = draws(&FaultInjector::new(config(0.0, 0.5)));
+
+    assert_eq!(first, second);
+    assert!(first.contains(&true) && first.contains(&false));
+}
+
+#[test]
+fn test_injected_errors_name_the_fault() {
+    let always = FaultInjector::new(config(1.0, 1.0));
+
+    let store_error = always.store_write("create_edge").unwrap_err().to_string();
+    assert!(store_error.contains("injected write failure in create_edge"));
+    let lsp_error = always.inject(&Language::Python).unwrap().to_string();
+    assert!(lsp_error.contains("timeout"));
+}
+
+// ============================================================================
+// Store wrapper
+// ============================================================================
+
+#[tokio::test]
+async fn test_chaos_store_fails_writes_but_not_reads() {
+    let sqlite = SqliteStore::open_in_memory().unwrap();
+    let store = ChaosStore::new(&sqlite, Arc::new(FaultInjector::new(config(0.0, 1.0))));
+
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    assert!(store.create_scan_run(&run).await.is_err());
+    assert!(store.resolve_commit(None).await.unwrap().is_none());
+    assert!(store.stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_scan_with_failing_store_writes_is_an_error() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("README.md"), "# Demo").unwrap();
+    let sqlite = SqliteStore::open_in_memory().unwrap();
+    let options = ScanOptions {
+        chaos: Some(config(0.0, 1.0)),
+        ..ScanOptions::default()
+    };
+
+    // The directory phase writes summaries even without source files
+    let result = run_with_store(temp.path(), &sqlite, &options).await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("injected write failure"), "{error}");
+}
@@ -0,0 +1,42 @@
+//! Complexity: Per-symbol connectivity proxies for spotting hotspots
+//!
+//! Runs after Phase 3, once the commit's reference edges are stored. The
+//! commit is exported and every symbol gets its fan-in, fan-out, outgoing
+//! calls, incoming references and complexity score, as computed by
+//! [`mother_core::graph::complexity`].
+
+use anyhow::Result;
+use mother_core::graph::complexity::symbol_complexity;
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the complexity pass
+#[derive(Debug, Default)]
+pub struct ComplexityResult {
+    pub symbol_count: usize,
+}
+
+impl ComplexityResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: self.symbol_count,
+            attempted: self.symbol_count,
+            errors: 0,
+        }
+    }
+}
+
+/// Compute and store the complexity proxies of every symbol of a commit
+pub async fn run(client: &dyn GraphStore, commit_sha: &str) -> Result<ComplexityResult> {
+    let graph = client.export_commit(commit_sha).await?;
+    let complexity = symbol_complexity(&graph);
+    client.set_symbol_complexity(&complexity).await?;
+    info!("Stored complexity of {} symbols", complexity.len());
+    Ok(ComplexityResult {
+        symbol_count: complexity.len(),
+    })
+}
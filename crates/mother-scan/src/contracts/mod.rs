@@ -0,0 +1,90 @@
+//! Contracts: API endpoints and messages the commit's code serves
+//!
+//! Runs after the module pass. The OpenAPI specs and `.proto` files the
+//! scanner found are read by [`mother_core::import::contracts`] into
+//! Endpoint and Message nodes, each linked to the commit's symbols serving
+//! it, which replace the contracts stored for the commit before. YAML and
+//! JSON files that do not parse, such as templates, are skipped.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use mother_core::graph::GraphStore;
+use mother_core::import::contracts::{link_handlers, read_contracts};
+use mother_core::scanner::FileContentCache;
+use tracing::{debug, info};
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the contracts pass
+#[derive(Debug, Default)]
+pub struct ContractsResult {
+    /// Files that declared at least one contract
+    pub file_count: usize,
+    pub contract_count: usize,
+    /// Contracts with at least one handler
+    pub handled_count: usize,
+    /// Files that could not be read or parsed, such as YAML templates
+    pub skipped_count: usize,
+}
+
+impl ContractsResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self, candidates: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: self.file_count,
+            symbols: self.contract_count,
+            attempted: candidates,
+            errors: 0,
+        }
+    }
+}
+
+/// Read the contracts in `files` and store them for a commit
+pub async fn run(
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    files: &[PathBuf],
+    cache: &FileContentCache,
+) -> Result<ContractsResult> {
+    let mut result = ContractsResult::default();
+    let mut contracts = Vec::new();
+    for file in files {
+        let path = file.display().to_string();
+        let read = cache
+            .read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|text| read_contracts(&path, &text).map_err(|e| e.to_string()));
+        match read {
+            Ok(declared) if declared.is_empty() => {}
+            Ok(declared) => {
+                result.file_count += 1;
+                contracts.extend(declared);
+            }
+            Err(e) => {
+                debug!("Skipping contracts in {}: {}", path, e);
+                result.skipped_count += 1;
+            }
+        }
+    }
+
+    let symbols = client.symbols_at_commit(commit_sha).await?;
+    link_handlers(&mut contracts, &symbols, |path| {
+        cache.read_to_string(Path::new(path)).ok()
+    });
+    client.create_contracts(commit_sha, &contracts).await?;
+
+    result.contract_count = contracts.len();
+    result.handled_count = contracts
+        .iter()
+        .filter(|c| !c.handler_ids.is_empty())
+        .count();
+    info!(
+        "Stored {} contracts from {} files ({} with handlers, {} files skipped)",
+        result.contract_count, result.file_count, result.handled_count, result.skipped_count
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for contracts module
+
+mod tests_contracts;
@@ -0,0 +1,100 @@
+//! Tests for storing the API contracts of a commit
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::scanner::FileContentCache;
+use tempfile::TempDir;
+
+use super::super::run;
+
+fn symbol(id: &str, kind: SymbolKind, file_path: &str, lines: (u32, u32)) -> SymbolNode {
+    SymbolNode::new(id, id, kind, file_path, lines.0, lines.1)
+}
+
+#[tokio::test]
+async fn test_run_stores_contracts_with_handlers() {
+    let repo = TempDir::new().unwrap();
+    let proto = repo.path().join("orders.proto");
+    let spec = repo.path().join("openapi.yaml");
+    let server = repo.path().join("server.py");
+    fs::write(
+        &proto,
+        "package orders;\nservice Orders {\n  rpc CreateOrder (Order) returns (Order);\n}\nmessage Order {}\n",
+    )
+    .unwrap();
+    fs::write(
+        &spec,
+        "openapi: 3.0.0\npaths:\n  /health:\n    get:\n      summary: Health\n",
+    )
+    .unwrap();
+    fs::write(
+        &server,
+        "def create_order(request):\n    pass\n\n@app.get(\"/health\")\ndef health():\n    pass\n",
+    )
+    .unwrap();
+    let broken = repo.path().join("template.yaml");
+    fs::write(&broken, "key: {{ value }\n").unwrap();
+    let server = server.display().to_string();
+
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new(&server, "hash-server", "python", "abc123")
+        .await
+        .unwrap();
+    let symbols = [
+        symbol("create_order", SymbolKind::Function, &server, (1, 2)),
+        symbol("health", SymbolKind::Function, &server, (5, 6)),
+    ];
+    store
+        .create_symbols_batch(&symbols, "hash-server")
+        .await
+        .unwrap();
+
+    let cache = FileContentCache::default();
+    let result = run(&store, "abc123", &[proto, spec, broken], &cache)
+        .await
+        .unwrap();
+    assert_eq!(
+        (
+            result.file_count,
+            result.contract_count,
+            result.handled_count,
+            result.skipped_count
+        ),
+        (2, 3, 2, 1)
+    );
+
+    let contracts = store.contracts_at_commit("abc123").await.unwrap();
+    let stored: Vec<_> = contracts
+        .iter()
+        .map(|c| (c.name.as_str(), c.line, c.handler_ids.clone()))
+        .collect();
+    assert_eq!(
+        stored,
+        vec![
+            ("GET /health", 4, vec!["health".to_string()]),
+            (
+                "orders.Orders/CreateOrder",
+                3,
+                vec!["create_order".to_string()]
+            ),
+            ("orders.Order", 5, vec![]),
+        ]
+    );
+
+    let rerun = run(&store, "abc123", &[], &cache).await.unwrap();
+    assert_eq!(rerun.contract_count, 0);
+    assert!(store
+        .contracts_at_commit("abc123")
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,161 @@
+//! Diagnostics: Store the problems language servers reported per file
+//!
+//! Runs after Phase 3 when `--diagnostics` is given. Servers publish
+//! diagnostics on their own schedule once a file is opened; by the end of
+//! the scan they have analyzed the workspace, so the latest diagnostics of
+//! each new file are taken from its client then. Each is stored as a
+//! Diagnostic node of the file, linked to the innermost symbol whose
+//! definition contains its start. Files are stored a chunk of symbols at a
+//! time, so spilled symbols are read back only once.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use mother_core::graph::convert::lsp_diagnostic_to_node;
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{LspDiagnostic, LspServerManager};
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+use super::{FileToProcess, SymbolBuffer, SymbolInfo};
+
+/// Results from storing diagnostics
+pub struct DiagnosticsResult {
+    pub diagnostic_count: usize,
+    pub error_count: usize,
+}
+
+impl DiagnosticsResult {
+    /// Work done on `attempted` files, for throughput metrics
+    pub(crate) fn counts(&self, attempted: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: attempted.saturating_sub(self.error_count),
+            symbols: 0,
+            attempted,
+            errors: self.error_count,
+        }
+    }
+}
+
+/// Take the published diagnostics of `files` and store them
+///
+/// # Errors
+/// Returns an error if spilled symbols cannot be read back or the
+/// diagnostics cannot be stored.
+pub async fn run(
+    files: &[FileToProcess],
+    symbols: &SymbolBuffer,
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+) -> Result<DiagnosticsResult> {
+    info!("Storing diagnostics of {} files...", files.len());
+
+    let mut result = DiagnosticsResult {
+        diagnostic_count: 0,
+        error_count: 0,
+    };
+    let mut pending: Vec<&FileToProcess> = files.iter().collect();
+    for chunk in symbols.chunks() {
+        let chunk = chunk?;
+        let symbols_by_file = symbols_by_file(&chunk);
+        let (ready, rest) = pending
+            .into_iter()
+            .partition(|file| symbols_by_file.contains_key(file.file_uri.as_str()));
+        pending = rest;
+        for file in ready {
+            let file_symbols = symbols_by_file
+                .get(file.file_uri.as_str())
+                .map_or(&[][..], Vec::as_slice);
+            take_and_store(file, file_symbols, client, lsp_manager, &mut result).await?;
+        }
+    }
+    // Files without symbols
+    for file in pending {
+        take_and_store(file, &[], client, lsp_manager, &mut result).await?;
+    }
+
+    if result.error_count > 0 {
+        tracing::warn!(
+            "Diagnostics: {} files could not be read",
+            result.error_count
+        );
+    }
+    Ok(result)
+}
+
+/// Take the diagnostics of a file and store them, counting them in `result`
+async fn take_and_store(
+    file: &FileToProcess,
+    symbols: &[&SymbolInfo],
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    result: &mut DiagnosticsResult,
+) -> Result<()> {
+    match take_diagnostics(file, lsp_manager).await {
+        Some(diagnostics) => {
+            result.diagnostic_count += store(file, &diagnostics, symbols, client).await?;
+        }
+        None => result.error_count += 1,
+    }
+    Ok(())
+}
+
+/// Store the diagnostics of a file, returning how many were stored
+async fn store(
+    file: &FileToProcess,
+    diagnostics: &[LspDiagnostic],
+    symbols: &[&SymbolInfo],
+    client: &dyn GraphStore,
+) -> Result<usize> {
+    let nodes: Vec<_> = diagnostics
+        .iter()
+        .map(|d| lsp_diagnostic_to_node(d, innermost_symbol(d, symbols)))
+        .collect();
+    client
+        .create_diagnostics(&file.content_hash, &nodes)
+        .await?;
+    Ok(nodes.len())
+}
+
+fn symbols_by_file(symbols: &[SymbolInfo]) -> HashMap<&str, Vec<&SymbolInfo>> {
+    let mut by_file: HashMap<&str, Vec<&SymbolInfo>> = HashMap::new();
+    for symbol in symbols {
+        by_file
+            .entry(symbol.file_uri.as_str())
+            .or_default()
+            .push(symbol);
+    }
+    by_file
+}
+
+/// Diagnostics the file's server published last; `None` if its server is
+/// unavailable
+async fn take_diagnostics(
+    file: &FileToProcess,
+    lsp_manager: &LspServerManager,
+) -> Option<Vec<LspDiagnostic>> {
+    let file_uri = file.file_uri.clone();
+    let result = lsp_manager
+        .with_client(&file.language, |lsp_client| {
+            Box::pin(async move { lsp_client.take_diagnostics(&file_uri) })
+        })
+        .await;
+    result
+        .inspect_err(|e| tracing::debug!("No diagnostics for {}: {}", file.path.display(), e))
+        .ok()
+}
+
+/// ID of the smallest symbol whose lines contain the diagnostic's start
+pub(crate) fn innermost_symbol(
+    diagnostic: &LspDiagnostic,
+    symbols: &[&SymbolInfo],
+) -> Option<String> {
+    symbols
+        .iter()
+        .filter(|s| (s.start_line..=s.end_line).contains(&diagnostic.start_line))
+        .min_by_key(|s| s.end_line - s.start_line)
+        .map(|s| s.id.clone())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for diagnostics module
+
+mod tests_diagnostics;
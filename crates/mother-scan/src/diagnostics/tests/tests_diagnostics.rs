@@ -0,0 +1,107 @@
+//! Tests for storing diagnostics and attaching them to symbols
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::{Path, PathBuf};
+
+use mother_core::graph::model::{FileMetrics, ScanRun};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::{LspDiagnostic, LspServerConfig, LspServerManager};
+use mother_core::scanner::Language;
+
+use super::super::{innermost_symbol, run};
+use crate::{FileToProcess, SymbolBuffer, SymbolInfo};
+
+fn symbol(id: &str, start_line: u32, end_line: u32) -> SymbolInfo {
+    SymbolInfo {
+        id: id.to_string(),
+        file_uri: "file:///repo/src/lib.rs".to_string(),
+        start_line,
+        end_line,
+        start_col: 0,
+        language: Language::Rust,
+    }
+}
+
+fn diagnostic(line: u32) -> LspDiagnostic {
+    LspDiagnostic {
+        severity: "error".to_string(),
+        code: None,
+        source: None,
+        message: "mismatched types".to_string(),
+        start_line: line,
+        start_col: 0,
+        end_line: line,
+        end_col: 4,
+    }
+}
+
+// ============================================================================
+// Tests for innermost_symbol
+// ============================================================================
+
+#[test]
+fn test_innermost_symbol_prefers_the_smallest_span() {
+    let (module, function) = (symbol("module", 0, 40), symbol("function", 10, 20));
+    let symbols = [&module, &function];
+
+    assert_eq!(
+        innermost_symbol(&diagnostic(15), &symbols).as_deref(),
+        Some("function")
+    );
+    assert_eq!(
+        innermost_symbol(&diagnostic(30), &symbols).as_deref(),
+        Some("module")
+    );
+}
+
+#[test]
+fn test_innermost_symbol_includes_both_ends() {
+    let function = symbol("function", 10, 20);
+
+    assert!(innermost_symbol(&diagnostic(10), &[&function]).is_some());
+    assert!(innermost_symbol(&diagnostic(20), &[&function]).is_some());
+    assert!(innermost_symbol(&diagnostic(21), &[&function]).is_none());
+}
+
+// ============================================================================
+// Tests for run
+// ============================================================================
+
+#[tokio::test]
+async fn test_run_counts_files_without_a_server_as_errors() {
+    let root = Path::new("/repo");
+    let mut manager = LspServerManager::new(root);
+    manager.register_server(LspServerConfig {
+        language: Language::Rust,
+        command: "mother-test-nonexistent-lsp-server".to_string(),
+        args: vec![],
+        root_path: root.to_path_buf(),
+        init_options: None,
+    });
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let files = [FileToProcess {
+        path: PathBuf::from("/repo/src/lib.rs"),
+        file_uri: "file:///repo/src/lib.rs".to_string(),
+        content_hash: "hash".to_string(),
+        language: Language::Rust,
+        metrics: FileMetrics::default(),
+        commit_sha: "abc123".to_string(),
+    }];
+
+    let result = run(&files, &SymbolBuffer::default(), &store, &manager)
+        .await
+        .unwrap();
+
+    assert_eq!(result.diagnostic_count, 0);
+    assert_eq!(result.error_count, 1);
+    assert!(store
+        .find_diagnostics("abc123", None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
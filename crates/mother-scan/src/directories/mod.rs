@@ -0,0 +1,197 @@
+//! Directory summaries: Aggregate stats and README blurbs per directory
+//!
+//! Runs after import linking. Every directory holding scanned files, and
+//! each of its ancestors up to the scan root, gets a DirectorySummary node
+//! with the file count, line count and languages of the files below it and
+//! the first paragraph of its README, so "what is this folder" can be
+//! answered without reading every file. Each directory CONTAINS its
+//! subdirectories and the files directly in it, so the store holds the
+//! directory tree and roll-ups per directory need no path matching.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use mother_core::graph::model::DirectorySummary;
+use mother_core::graph::GraphStore;
+use mother_core::scanner::{DiscoveredFile, FileContentCache};
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+
+/// README file names, in order of preference (matched case-insensitively)
+const README_NAMES: [&str; 4] = ["readme.md", "readme.rst", "readme.txt", "readme"];
+
+/// Longest README excerpt stored, in characters
+const README_MAX_CHARS: usize = 500;
+
+/// Work done on `files`, for throughput metrics
+pub(crate) fn counts(files: usize) -> PhaseCounts {
+    PhaseCounts {
+        files,
+        symbols: 0,
+        attempted: files,
+        errors: 0,
+    }
+}
+
+/// Summarize the directories of all scanned files and store the summaries
+pub async fn run(
+    root: &Path,
+    files: &[DiscoveredFile],
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    cache: &FileContentCache,
+) -> Result<()> {
+    info!("Summarizing directories of {} files...", files.len());
+    let summaries = summarize(root, files, cache);
+    client
+        .create_directory_summaries(commit_sha, &summaries)
+        .await?;
+    info!("Stored {} directory summaries", summaries.len());
+    Ok(())
+}
+
+/// Build a summary for every directory between `root` and the files
+pub(crate) fn summarize(
+    root: &Path,
+    files: &[DiscoveredFile],
+    cache: &FileContentCache,
+) -> Vec<DirectorySummary> {
+    let mut dirs: BTreeMap<PathBuf, Aggregate> = BTreeMap::new();
+
+    for file in files {
+        let lines = count_lines(cache, &file.path);
+        let language = file.language.to_string();
+        let Some(parent) = file.path.parent() else {
+            continue;
+        };
+
+        dirs.entry(parent.to_path_buf())
+            .or_default()
+            .files
+            .push(file.path.display().to_string());
+        for dir in parent.ancestors() {
+            if !dir.starts_with(root) {
+                break;
+            }
+            let aggregate = dirs.entry(dir.to_path_buf()).or_default();
+            aggregate.file_count += 1;
+            aggregate.line_count += lines;
+            aggregate.languages.insert(language.clone());
+        }
+    }
+
+    dirs.into_iter()
+        .map(|(path, aggregate)| DirectorySummary {
+            readme: read_readme(cache, &path),
+            parent: (path != root)
+                .then(|| path.parent())
+                .flatten()
+                .map(|parent| parent.display().to_string()),
+            path: path.display().to_string(),
+            file_count: aggregate.file_count,
+            line_count: aggregate.line_count,
+            languages: aggregate.languages.into_iter().collect(),
+            files: aggregate.files,
+        })
+        .collect()
+}
+
+/// Running totals for one directory
+#[derive(Default)]
+struct Aggregate {
+    file_count: usize,
+    line_count: usize,
+    languages: BTreeSet<String>,
+    files: Vec<String>,
+}
+
+/// Lines in a file, or 0 if it cannot be read
+fn count_lines(cache: &FileContentCache, path: &Path) -> usize {
+    cache
+        .read(path)
+        .map(|content| {
+            let bytes = content.bytes();
+            let newlines = bytes.iter().filter(|b| **b == b'\n').count();
+            newlines + usize::from(bytes.last().is_some_and(|b| *b != b'\n'))
+        })
+        .unwrap_or(0)
+}
+
+/// First paragraph of the README in `dir`, if there is one
+fn read_readme(cache: &FileContentCache, dir: &Path) -> Option<String> {
+    let entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+
+    README_NAMES.iter().find_map(|name| {
+        let path = entries.iter().find(|path| {
+            path.file_name()
+                .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name))
+        })?;
+        first_paragraph(&cache.read_to_string(path).ok()?)
+    })
+}
+
+/// The first block of prose in a README
+///
+/// Skips headings (including underlined titles), badges, images, HTML and
+/// front matter, joins the block's lines with spaces and caps its length.
+pub(crate) fn first_paragraph(text: &str) -> Option<String> {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_front_matter = false;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if index == 0 && line == "---" {
+            in_front_matter = true;
+            continue;
+        }
+        if in_front_matter {
+            in_front_matter = line != "---";
+            continue;
+        }
+        if is_underline(line) && paragraph.len() == 1 {
+            // The previous line was a setext / reStructuredText title
+            paragraph.clear();
+            continue;
+        }
+        if line.is_empty() || is_decoration(line) {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(line);
+    }
+
+    if paragraph.is_empty() {
+        return None;
+    }
+    let joined = paragraph.join(" ");
+    Some(match joined.char_indices().nth(README_MAX_CHARS) {
+        Some((end, _)) => format!("{}...", joined[..end].trim_end()),
+        None => joined,
+    })
+}
+
+/// Lines that are structure or markup rather than prose
+fn is_decoration(line: &str) -> bool {
+    line.starts_with('#')
+        || line.starts_with("[![")
+        || line.starts_with("![")
+        || line.starts_with('<')
+        || line.starts_with("```")
+        || is_underline(line)
+}
+
+/// A line made only of heading underline or rule characters
+fn is_underline(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| matches!(c, '=' | '-' | '~' | '*'))
+}
+
+#[cfg(test)]
+mod tests;
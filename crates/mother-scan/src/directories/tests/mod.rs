@@ -0,0 +1,3 @@
+//! Tests for directories module
+
+mod tests_directories;
@@ -0,0 +1,130 @@
+//! Tests for directory summarization and README excerpts
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+use std::path::Path;
+
+use mother_core::scanner::{DiscoveredFile, FileContentCache, Language};
+use tempfile::TempDir;
+
+use super::super::{first_paragraph, summarize};
+
+fn discovered(path: &Path, language: Language) -> DiscoveredFile {
+    DiscoveredFile {
+        path: path.to_path_buf(),
+        language,
+    }
+}
+
+// ============================================================================
+// Tests for summarize
+// ============================================================================
+
+#[test]
+fn test_summarize_aggregates_files_below_each_directory() {
+    let repo = TempDir::new().unwrap();
+    let root = repo.path();
+    fs::create_dir_all(root.join("src/graph")).unwrap();
+    fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(root.join("src/graph/store.rs"), "struct A;\nstruct B;\n").unwrap();
+    fs::write(root.join("tool.py"), "print(1)").unwrap();
+    let files = vec![
+        discovered(&root.join("src/main.rs"), Language::Rust),
+        discovered(&root.join("src/graph/store.rs"), Language::Rust),
+        discovered(&root.join("tool.py"), Language::Python),
+    ];
+
+    let summaries = summarize(root, &files, &FileContentCache::default());
+
+    let paths: Vec<_> = summaries.iter().map(|s| s.path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            root.display().to_string(),
+            root.join("src").display().to_string(),
+            root.join("src/graph").display().to_string(),
+        ]
+    );
+    let top = &summaries[0];
+    assert_eq!(top.file_count, 3);
+    assert_eq!(top.line_count, 4);
+    assert_eq!(top.languages, vec!["python", "rust"]);
+    assert_eq!(top.files, vec![root.join("tool.py").display().to_string()]);
+    assert_eq!(top.parent, None);
+    let src = &summaries[1];
+    assert_eq!(src.file_count, 2);
+    assert_eq!(src.parent, Some(root.display().to_string()));
+    assert_eq!(
+        summaries[2].parent,
+        Some(root.join("src").display().to_string())
+    );
+    assert_eq!(
+        src.files,
+        vec![root.join("src/main.rs").display().to_string()]
+    );
+}
+
+#[test]
+fn test_summarize_reads_readme_of_each_directory() {
+    let repo = TempDir::new().unwrap();
+    let root = repo.path();
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::write(root.join("lib/a.rs"), "").unwrap();
+    fs::write(root.join("lib/Readme.md"), "# Lib\n\nShared helpers.\n").unwrap();
+    let files = vec![discovered(&root.join("lib/a.rs"), Language::Rust)];
+
+    let summaries = summarize(root, &files, &FileContentCache::default());
+
+    assert_eq!(summaries[0].readme, None);
+    assert_eq!(summaries[1].readme.as_deref(), Some("Shared helpers."));
+    assert_eq!(summaries[1].line_count, 0);
+}
+
+#[test]
+fn test_summarize_without_files_is_empty() {
+    let repo = TempDir::new().unwrap();
+
+    assert!(summarize(repo.path(), &[], &FileContentCache::default()).is_empty());
+}
+
+// ============================================================================
+// Tests for first_paragraph
+// ============================================================================
+
+#[test]
+fn test_first_paragraph_skips_headings_and_badges() {
+    let text = "# mother\n\n[![CI](badge.svg)](ci)\n\nScans code\ninto a graph.\n\nMore text.";
+
+    assert_eq!(
+        first_paragraph(text).as_deref(),
+        Some("Scans code into a graph.")
+    );
+}
+
+#[test]
+fn test_first_paragraph_skips_front_matter_and_rst_underlines() {
+    let text = "---\ntitle: x\n---\nGraph\n=====\n\nThe graph module.\n";
+
+    assert_eq!(first_paragraph(text).as_deref(), Some("The graph module."));
+    assert_eq!(
+        first_paragraph("=====\nGraph\n=====\n\nBody").as_deref(),
+        Some("Body")
+    );
+}
+
+#[test]
+fn test_first_paragraph_caps_length() {
+    let text = "word ".repeat(200);
+
+    let paragraph = first_paragraph(&text).unwrap();
+
+    assert!(paragraph.ends_with("..."));
+    assert!(paragraph.chars().count() <= 503);
+}
+
+#[test]
+fn test_first_paragraph_of_markup_only_is_none() {
+    assert_eq!(first_paragraph("# Title\n\n<img src=\"x\">\n"), None);
+    assert_eq!(first_paragraph(""), None);
+}
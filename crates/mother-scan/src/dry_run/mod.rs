@@ -0,0 +1,232 @@
+//! Dry run: Run the scan pipeline without writing to Neo4j
+//!
+//! Discovers files, extracts and enriches symbols, resolves imports and looks
+//! up references exactly like a real scan, but only tallies the results. The
+//! report helps validate LSP setup before a large ingestion.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use mother_core::enrich::{EnrichContext, EnricherReport, EnrichmentPipeline};
+use mother_core::graph::convert::{convert_symbols, infer_visibilities};
+use mother_core::graph::model::SymbolNode;
+use mother_core::lsp::{LspReference, LspServerManager};
+use mother_core::scanner::{extract_imports, DiscoveredFile, FileContentCache, Language};
+use tracing::info;
+
+use super::imports::resolve_file_imports;
+use super::phase2::collect_symbol_info;
+use super::phase3::{build_symbol_lookup_table, find_containing_symbol};
+use super::{shutdown_lsp, ScanOptions, SymbolInfo};
+
+/// Summary of what a scan would write
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DryRunReport {
+    pub files_by_language: HashMap<Language, usize>,
+    pub symbols_by_kind: HashMap<String, usize>,
+    pub import_edges: usize,
+    pub reference_edges: usize,
+    pub error_count: usize,
+    /// Files an enricher failed on, per enricher
+    pub enricher_errors: HashMap<&'static str, usize>,
+}
+
+impl DryRunReport {
+    /// Total number of files discovered
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files_by_language.values().sum()
+    }
+
+    /// Total number of symbols extracted
+    #[must_use]
+    pub fn symbol_count(&self) -> usize {
+        self.symbols_by_kind.values().sum()
+    }
+
+    /// Record a file's extracted symbols
+    pub fn add_symbols(&mut self, symbols: &[SymbolNode]) {
+        for symbol in symbols {
+            *self
+                .symbols_by_kind
+                .entry(symbol.kind.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Record the enrichers that failed on a file
+    pub fn add_enrichers(&mut self, reports: &[EnricherReport]) {
+        for report in reports.iter().filter(|r| r.error.is_some()) {
+            *self.enricher_errors.entry(report.name).or_default() += 1;
+        }
+    }
+
+    /// Edges a first scan of this commit would create
+    ///
+    /// REFERENCES and IMPORTS edges, plus DEFINED_IN and SNAPSHOT_OF per
+    /// symbol, CONTAINS per file and FOR_COMMIT for the scan run.
+    #[must_use]
+    pub fn estimated_edges(&self) -> usize {
+        self.reference_edges + self.import_edges + 2 * self.symbol_count() + self.file_count() + 1
+    }
+}
+
+/// Run a scan without touching Neo4j and report what it would write
+///
+/// # Errors
+/// Returns an error if the enricher config or include/exclude globs are
+/// invalid; files the LSP cannot process are counted and skipped.
+pub async fn run(path: &Path, options: &ScanOptions) -> Result<DryRunReport> {
+    info!("Dry run for repository: {}", path.display());
+
+    let pipeline = EnrichmentPipeline::from_config(&options.enrichment)?;
+    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let files = options.discover_files(&abs_path)?;
+    info!("Found {} files to process", files.len());
+
+    let mut report = DryRunReport::default();
+    for file in &files {
+        *report
+            .files_by_language
+            .entry(file.language.clone())
+            .or_default() += 1;
+    }
+    let cache = FileContentCache::default();
+    report.import_edges = count_import_edges(&files, &cache);
+
+    let lsp_manager = options.lsp_manager(&abs_path);
+    let symbols = extract_all_symbols(&files, &lsp_manager, &cache, &pipeline, &mut report).await;
+    count_reference_edges(&symbols, &lsp_manager, &cache, &mut report).await;
+    shutdown_lsp(&lsp_manager).await;
+    Ok(report)
+}
+
+/// Count the IMPORTS edges between discovered files
+fn count_import_edges(files: &[DiscoveredFile], cache: &FileContentCache) -> usize {
+    let known: HashSet<PathBuf> = files.iter().map(|f| f.path.clone()).collect();
+    files
+        .iter()
+        .filter_map(|file| {
+            let content = cache.read(&file.path).ok()?;
+            let content = content.text().ok()?;
+            let imports = extract_imports(&file.language, content);
+            Some(resolve_file_imports(file, &imports, &known).len())
+        })
+        .sum()
+}
+
+/// Phases 1 and 2: open every file and extract its enriched symbols
+async fn extract_all_symbols(
+    files: &[DiscoveredFile],
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    pipeline: &EnrichmentPipeline,
+    report: &mut DryRunReport,
+) -> Vec<SymbolInfo> {
+    let mut symbol_infos = Vec::new();
+    for file in files {
+        match extract_file_symbols(file, lsp_manager, cache, pipeline).await {
+            Ok((symbols, infos, enrichers)) => {
+                report.add_symbols(&symbols);
+                report.add_enrichers(&enrichers);
+                symbol_infos.extend(infos);
+            }
+            Err(e) => {
+                report.error_count += 1;
+                tracing::warn!("Failed to process {}: {}", file.path.display(), e);
+            }
+        }
+    }
+    symbol_infos
+}
+
+/// Open a file in its LSP server and extract its symbols
+async fn extract_file_symbols(
+    file: &DiscoveredFile,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    pipeline: &EnrichmentPipeline,
+) -> Result<(Vec<SymbolNode>, Vec<SymbolInfo>, Vec<EnricherReport>)> {
+    let file_uri = format!("file://{}", file.path.display());
+    let content = cache.read(&file.path)?;
+
+    lsp_manager
+        .open_document(&file.language, &file_uri, content.text()?)
+        .await?;
+    let lsp_client = lsp_manager.get_client(&file.language).await?;
+    let lsp_symbols = lsp_client.document_symbols(&file_uri).await?;
+
+    let mut symbols = convert_symbols(&lsp_symbols, &file.path);
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &lsp_symbols,
+        lsp_client: &lsp_client,
+        file_uri: &file_uri,
+        language: file.language.clone(),
+    };
+    let enrichers = pipeline.run(&mut ctx).await;
+    infer_visibilities(&mut symbols, &file.language);
+
+    let mut infos = Vec::new();
+    collect_symbol_info(
+        &lsp_symbols,
+        &symbols,
+        &file_uri,
+        &file.language,
+        &mut infos,
+    );
+    Ok((symbols, infos, enrichers))
+}
+
+/// Phase 3: look up references and count the edges they would create
+async fn count_reference_edges(
+    symbols: &[SymbolInfo],
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    report: &mut DryRunReport,
+) {
+    let symbols_by_file = build_symbol_lookup_table(symbols);
+
+    for symbol_info in symbols {
+        match symbol_references(symbol_info, lsp_manager, cache).await {
+            Ok(refs) => {
+                report.reference_edges +=
+                    count_symbol_references(&refs, symbol_info, &symbols_by_file);
+            }
+            Err(_) => report.error_count += 1,
+        }
+    }
+}
+
+/// References to a symbol, opening its file again if the open document
+/// limit closed it
+async fn symbol_references(
+    symbol_info: &SymbolInfo,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+) -> Result<Vec<LspReference>> {
+    let file_uri = symbol_info.file_uri.clone();
+    let (line, col) = (symbol_info.start_line, symbol_info.start_col);
+    super::ensure_open(lsp_manager, cache, &symbol_info.language, &file_uri).await?;
+    lsp_manager
+        .with_client(&symbol_info.language, |lsp_client| {
+            Box::pin(async move { lsp_client.references(&file_uri, line, col, true).await })
+        })
+        .await
+}
+
+/// Count references that resolve to another known symbol
+fn count_symbol_references(
+    refs: &[LspReference],
+    symbol_info: &SymbolInfo,
+    symbols_by_file: &HashMap<String, Vec<(String, u32, u32)>>,
+) -> usize {
+    refs.iter()
+        .filter_map(|reference| find_containing_symbol(reference, symbols_by_file))
+        .filter(|from_id| *from_id != symbol_info.id)
+        .count()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for dry-run scans
+
+mod tests_dry_run;
@@ -0,0 +1,163 @@
+//! Tests for dry-run report tallies
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use mother_core::enrich::EnricherReport;
+use mother_core::graph::model::{SymbolKind, SymbolNode};
+use mother_core::lsp::LspReference;
+use mother_core::scanner::{DiscoveredFile, FileContentCache, Language};
+use tempfile::TempDir;
+
+use super::super::{count_import_edges, count_symbol_references, DryRunReport};
+use crate::SymbolInfo;
+
+fn make_symbol(name: &str, kind: SymbolKind) -> SymbolNode {
+    SymbolNode::new(format!("id-{name}"), name, kind, "/repo/lib.rs", 1, 10)
+}
+
+fn make_symbol_info(id: &str) -> SymbolInfo {
+    SymbolInfo {
+        id: id.to_string(),
+        file_uri: "file:///repo/lib.rs".to_string(),
+        start_line: 0,
+        end_line: 5,
+        start_col: 4,
+        language: Language::Rust,
+    }
+}
+
+fn make_reference(file: &str, line: u32) -> LspReference {
+    LspReference {
+        file: PathBuf::from(file),
+        line,
+        start_col: 0,
+        end_col: 3,
+    }
+}
+
+// ============================================================================
+// DryRunReport
+// ============================================================================
+
+#[test]
+fn test_report_default_is_empty() {
+    let report = DryRunReport::default();
+    assert_eq!(report.file_count(), 0);
+    assert_eq!(report.symbol_count(), 0);
+    // Only the FOR_COMMIT edge of the scan run
+    assert_eq!(report.estimated_edges(), 1);
+}
+
+#[test]
+fn test_add_symbols_counts_by_kind() {
+    let mut report = DryRunReport::default();
+    report.add_symbols(&[
+        make_symbol("a", SymbolKind::Function),
+        make_symbol("b", SymbolKind::Function),
+        make_symbol("C", SymbolKind::Struct),
+    ]);
+
+    assert_eq!(report.symbol_count(), 3);
+    assert_eq!(report.symbols_by_kind.get("function"), Some(&2));
+    assert_eq!(report.symbols_by_kind.get("struct"), Some(&1));
+}
+
+#[test]
+fn test_add_enrichers_counts_failures_per_enricher() {
+    let report = |name, error: Option<&str>| EnricherReport {
+        name,
+        elapsed: Duration::ZERO,
+        error: error.map(str::to_string),
+    };
+    let mut dry_run = DryRunReport::default();
+    dry_run.add_enrichers(&[report("hover", Some("timeout")), report("signature", None)]);
+    dry_run.add_enrichers(&[report("hover", Some("timeout"))]);
+
+    assert_eq!(dry_run.enricher_errors.get("hover"), Some(&2));
+    assert!(!dry_run.enricher_errors.contains_key("signature"));
+}
+
+#[test]
+fn test_estimated_edges_sums_all_edge_kinds() {
+    let mut report = DryRunReport {
+        import_edges: 4,
+        reference_edges: 10,
+        ..DryRunReport::default()
+    };
+    report.files_by_language.insert(Language::Rust, 3);
+    report.add_symbols(&[
+        make_symbol("a", SymbolKind::Function),
+        make_symbol("b", SymbolKind::Function),
+    ]);
+
+    // 10 refs + 4 imports + 2×2 symbol edges + 3 CONTAINS + 1 FOR_COMMIT
+    assert_eq!(report.estimated_edges(), 22);
+}
+
+// ============================================================================
+// count_symbol_references
+// ============================================================================
+
+#[test]
+fn test_count_symbol_references_skips_unknown_and_self() {
+    let target = make_symbol_info("target");
+    let mut symbols_by_file = HashMap::new();
+    symbols_by_file.insert(
+        "/repo/lib.rs".to_string(),
+        vec![("target".to_string(), 0, 5), ("caller".to_string(), 10, 20)],
+    );
+
+    let refs = vec![
+        make_reference("/repo/lib.rs", 2),   // inside target itself
+        make_reference("/repo/lib.rs", 12),  // inside caller
+        make_reference("/repo/lib.rs", 15),  // inside caller again
+        make_reference("/repo/other.rs", 1), // unknown file
+        make_reference("/repo/lib.rs", 30),  // outside any symbol
+    ];
+
+    assert_eq!(count_symbol_references(&refs, &target, &symbols_by_file), 2);
+}
+
+#[test]
+fn test_count_symbol_references_empty() {
+    let target = make_symbol_info("target");
+    assert_eq!(count_symbol_references(&[], &target, &HashMap::new()), 0);
+}
+
+// ============================================================================
+// count_import_edges
+// ============================================================================
+
+#[test]
+fn test_count_import_edges_resolves_repository_files() -> anyhow::Result<()> {
+    let temp = TempDir::new()?;
+    let main = temp.path().join("main.py");
+    let util = temp.path().join("util.py");
+    std::fs::write(&main, "import util\nimport os\n")?;
+    std::fs::write(&util, "x = 1\n")?;
+
+    let files = vec![
+        DiscoveredFile {
+            path: main,
+            language: Language::Python,
+        },
+        DiscoveredFile {
+            path: util,
+            language: Language::Python,
+        },
+    ];
+
+    assert_eq!(count_import_edges(&files, &FileContentCache::default()), 1);
+    Ok(())
+}
+
+#[test]
+fn test_count_import_edges_skips_unreadable_files() {
+    let files = vec![DiscoveredFile {
+        path: PathBuf::from("/nonexistent/mother/dry_run.py"),
+        language: Language::Python,
+    }];
+    assert_eq!(count_import_edges(&files, &FileContentCache::default()), 0);
+}
@@ -0,0 +1,303 @@
+//! Scan estimation: Predict graph size and scan duration from a sample
+//!
+//! Runs file discovery over the whole repository, then performs the Phase 2/3
+//! LSP work on a small, evenly spread sample of files without writing anything
+//! to Neo4j. The per-file measurements are extrapolated to the full file set
+//! with 95% confidence bounds.
+
+use std::collections::HashMap;
+use std::ops::Add;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use mother_core::graph::convert::convert_symbols;
+use mother_core::lsp::{flatten_symbols as flatten_lsp_symbols, LspServerManager};
+use mother_core::scanner::{DiscoveredFile, Language};
+use tracing::info;
+
+use super::{shutdown_lsp, ScanOptions};
+
+/// Maximum number of symbols per sampled file that get a hover/references probe
+const PROBES_PER_FILE: usize = 5;
+
+/// z-score for a two-sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Approximate on-disk bytes per node (record + typical string properties)
+const BYTES_PER_NODE: f64 = 350.0;
+
+/// Approximate on-disk bytes per relationship (record + line/column properties)
+const BYTES_PER_EDGE: f64 = 80.0;
+
+/// Measurements taken for a single sampled file
+#[derive(Debug, Clone, Default)]
+pub struct FileSample {
+    pub symbols: usize,
+    /// Symbols that were probed with hover and references
+    pub probed_symbols: usize,
+    /// References found for the probed symbols
+    pub references: usize,
+    /// Time spent opening the file and extracting symbols
+    pub extract_time: Duration,
+    /// Time spent on hover requests for the probed symbols
+    pub hover_time: Duration,
+    /// Time spent on references requests for the probed symbols
+    pub reference_time: Duration,
+}
+
+impl FileSample {
+    /// Estimated Phase 2 time for this file, scaling hover cost to all symbols
+    #[must_use]
+    pub fn phase2_secs(&self) -> f64 {
+        self.extract_time.as_secs_f64() + self.scale_to_symbols(self.hover_time.as_secs_f64())
+    }
+
+    /// Estimated Phase 3 time for this file, scaling reference cost to all symbols
+    #[must_use]
+    pub fn phase3_secs(&self) -> f64 {
+        self.scale_to_symbols(self.reference_time.as_secs_f64())
+    }
+
+    /// Estimated reference edges for this file, scaled to all symbols
+    #[must_use]
+    pub fn estimated_references(&self) -> f64 {
+        self.scale_to_symbols(self.references as f64)
+    }
+
+    fn scale_to_symbols(&self, probed_value: f64) -> f64 {
+        if self.probed_symbols == 0 {
+            return 0.0;
+        }
+        probed_value * self.symbols as f64 / self.probed_symbols as f64
+    }
+}
+
+/// A point estimate with lower and upper confidence bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounded {
+    pub low: f64,
+    pub mid: f64,
+    pub high: f64,
+}
+
+impl Bounded {
+    /// Bounds that collapse onto a single exactly known value
+    #[must_use]
+    pub fn exact(value: f64) -> Self {
+        Self {
+            low: value,
+            mid: value,
+            high: value,
+        }
+    }
+
+    /// Multiply every bound by a constant factor
+    #[must_use]
+    pub fn scale(self, factor: f64) -> Self {
+        Self {
+            low: self.low * factor,
+            mid: self.mid * factor,
+            high: self.high * factor,
+        }
+    }
+}
+
+/// Adds two independent estimates (bounds are summed conservatively)
+impl Add for Bounded {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            low: self.low + other.low,
+            mid: self.mid + other.mid,
+            high: self.high + other.high,
+        }
+    }
+}
+
+/// Full extrapolated estimate for a scan
+#[derive(Debug, Clone)]
+pub struct ScanEstimate {
+    pub total_files: usize,
+    pub sampled_files: usize,
+    pub files_by_language: HashMap<Language, usize>,
+    pub symbols: Bounded,
+    pub references: Bounded,
+    pub nodes: Bounded,
+    pub edges: Bounded,
+    pub database_bytes: Bounded,
+    pub duration_secs: Bounded,
+}
+
+/// Extrapolate a per-file total for a population of `population` files
+///
+/// Uses the sample mean with a 95% confidence interval on the mean, including the
+/// finite population correction so that sampling every file yields exact bounds.
+#[must_use]
+pub fn extrapolate_total(values: &[f64], population: usize) -> Bounded {
+    let n = values.len();
+    if n == 0 || population == 0 {
+        return Bounded::exact(0.0);
+    }
+
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let variance = if n > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+    } else {
+        0.0
+    };
+
+    let fpc = if population > 1 {
+        ((population.saturating_sub(n)) as f64 / (population - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let margin = Z_95 * (variance / n as f64).sqrt() * fpc;
+
+    let total = population as f64;
+    Bounded {
+        low: ((mean - margin) * total).max(0.0),
+        mid: mean * total,
+        high: (mean + margin) * total,
+    }
+}
+
+/// Pick up to `sample_size` files spread evenly across the discovered list
+#[must_use]
+pub fn select_sample(files: &[DiscoveredFile], sample_size: usize) -> Vec<&DiscoveredFile> {
+    if sample_size == 0 || files.is_empty() {
+        return Vec::new();
+    }
+    if sample_size >= files.len() {
+        return files.iter().collect();
+    }
+
+    let stride = files.len() as f64 / sample_size as f64;
+    (0..sample_size)
+        .map(|i| &files[((i as f64 * stride) as usize).min(files.len() - 1)])
+        .collect()
+}
+
+/// Build a scan estimate from file samples
+#[must_use]
+pub fn build_estimate(files: &[DiscoveredFile], samples: &[FileSample]) -> ScanEstimate {
+    let total_files = files.len();
+
+    let mut files_by_language: HashMap<Language, usize> = HashMap::new();
+    for file in files {
+        *files_by_language.entry(file.language.clone()).or_default() += 1;
+    }
+
+    let collect = |f: fn(&FileSample) -> f64| samples.iter().map(f).collect::<Vec<_>>();
+
+    let symbols = extrapolate_total(&collect(|s| s.symbols as f64), total_files);
+    let references = extrapolate_total(&collect(FileSample::estimated_references), total_files);
+    let phase2 = extrapolate_total(&collect(FileSample::phase2_secs), total_files);
+    let phase3 = extrapolate_total(&collect(FileSample::phase3_secs), total_files);
+
+    // Symbol plus SymbolIdentity per symbol (first scan), one Commit and one
+    // ScanRun node, one File node per file
+    let nodes = symbols.scale(2.0) + Bounded::exact(total_files as f64 + 2.0);
+    // DEFINED_IN and SNAPSHOT_OF per symbol, CONTAINS per file, FOR_COMMIT per scan run
+    let edges = references + symbols.scale(2.0) + Bounded::exact(total_files as f64 + 1.0);
+    let database_bytes = nodes.scale(BYTES_PER_NODE) + edges.scale(BYTES_PER_EDGE);
+
+    ScanEstimate {
+        total_files,
+        sampled_files: samples.len(),
+        files_by_language,
+        symbols,
+        references,
+        nodes,
+        edges,
+        database_bytes,
+        duration_secs: phase2 + phase3,
+    }
+}
+
+/// Run the scan estimation for a repository
+///
+/// # Errors
+/// Returns an error if the include/exclude globs are invalid; files that fail
+/// to sample are logged and skipped.
+pub async fn run(path: &Path, sample_size: usize, options: &ScanOptions) -> Result<ScanEstimate> {
+    info!("Estimating scan for repository: {}", path.display());
+
+    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let files = options.discover_files(&abs_path)?;
+    let sample = select_sample(&files, sample_size);
+    info!(
+        "Discovered {} files, sampling {} for estimation",
+        files.len(),
+        sample.len()
+    );
+
+    let lsp_manager = options.lsp_manager(&abs_path);
+    let samples = collect_samples(&sample, &lsp_manager).await;
+    shutdown_lsp(&lsp_manager).await;
+
+    Ok(build_estimate(&files, &samples))
+}
+
+/// Sample each selected file, skipping files the LSP cannot process
+async fn collect_samples(
+    files: &[&DiscoveredFile],
+    lsp_manager: &LspServerManager,
+) -> Vec<FileSample> {
+    let mut samples = Vec::with_capacity(files.len());
+    for file in files {
+        match sample_file(file, lsp_manager).await {
+            Ok(s) => samples.push(s),
+            Err(e) => tracing::warn!("Failed to sample {}: {}", file.path.display(), e),
+        }
+    }
+    samples
+}
+
+/// Run symbol extraction and reference probes on a single file
+async fn sample_file(file: &DiscoveredFile, lsp_manager: &LspServerManager) -> Result<FileSample> {
+    let file_uri = format!("file://{}", file.path.display());
+    let content = std::fs::read_to_string(&file.path)?;
+
+    let started = Instant::now();
+    lsp_manager
+        .open_document(&file.language, &file_uri, &content)
+        .await?;
+    let lsp_client = lsp_manager.get_client(&file.language).await?;
+    let lsp_symbols = lsp_client.document_symbols(&file_uri).await?;
+    let symbols = convert_symbols(&lsp_symbols, &file.path);
+    let extract_time = started.elapsed();
+
+    let mut sample = FileSample {
+        symbols: symbols.len(),
+        extract_time,
+        ..FileSample::default()
+    };
+
+    for lsp_sym in flatten_lsp_symbols(&lsp_symbols)
+        .into_iter()
+        .take(PROBES_PER_FILE)
+    {
+        let hover_started = Instant::now();
+        let _ = lsp_client
+            .hover(&file_uri, lsp_sym.start_line, lsp_sym.start_col)
+            .await;
+        sample.hover_time += hover_started.elapsed();
+
+        let refs_started = Instant::now();
+        if let Ok(refs) = lsp_client
+            .references(&file_uri, lsp_sym.start_line, lsp_sym.start_col, true)
+            .await
+        {
+            sample.references += refs.len();
+        }
+        sample.reference_time += refs_started.elapsed();
+        sample.probed_symbols += 1;
+    }
+
+    Ok(sample)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for scan estimation
+
+mod tests_estimate;
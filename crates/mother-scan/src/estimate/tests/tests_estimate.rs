@@ -0,0 +1,145 @@
+//! Tests for estimate extrapolation and sampling
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use mother_core::scanner::{DiscoveredFile, Language};
+
+use crate::estimate::{build_estimate, extrapolate_total, select_sample, Bounded, FileSample};
+
+fn make_files(count: usize) -> Vec<DiscoveredFile> {
+    (0..count)
+        .map(|i| DiscoveredFile {
+            path: PathBuf::from(format!("/repo/file{i}.rs")),
+            language: Language::Rust,
+        })
+        .collect()
+}
+
+fn make_sample(symbols: usize, probed: usize, references: usize) -> FileSample {
+    FileSample {
+        symbols,
+        probed_symbols: probed,
+        references,
+        extract_time: Duration::from_millis(100),
+        hover_time: Duration::from_millis(10 * probed as u64),
+        reference_time: Duration::from_millis(20 * probed as u64),
+    }
+}
+
+// ============================================================================
+// extrapolate_total
+// ============================================================================
+
+#[test]
+fn test_extrapolate_total_empty_sample() {
+    assert_eq!(extrapolate_total(&[], 100), Bounded::exact(0.0));
+}
+
+#[test]
+fn test_extrapolate_total_constant_values_has_no_margin() {
+    let bounds = extrapolate_total(&[4.0, 4.0, 4.0], 10);
+    assert_eq!(bounds, Bounded::exact(40.0));
+}
+
+#[test]
+fn test_extrapolate_total_full_population_is_exact() {
+    // Sampling every file means the finite population correction removes the margin
+    let bounds = extrapolate_total(&[1.0, 5.0, 9.0], 3);
+    assert_eq!(bounds, Bounded::exact(15.0));
+}
+
+#[test]
+fn test_extrapolate_total_bounds_surround_midpoint() {
+    let bounds = extrapolate_total(&[1.0, 5.0, 9.0, 3.0], 1000);
+    assert!((bounds.mid - 4500.0).abs() < f64::EPSILON);
+    assert!(bounds.low < bounds.mid);
+    assert!(bounds.high > bounds.mid);
+}
+
+#[test]
+fn test_extrapolate_total_low_bound_never_negative() {
+    let bounds = extrapolate_total(&[0.0, 0.0, 100.0], 10_000);
+    assert!(bounds.low >= 0.0);
+}
+
+// ============================================================================
+// select_sample
+// ============================================================================
+
+#[test]
+fn test_select_sample_smaller_than_requested() {
+    let files = make_files(3);
+    assert_eq!(select_sample(&files, 10).len(), 3);
+}
+
+#[test]
+fn test_select_sample_zero() {
+    let files = make_files(3);
+    assert!(select_sample(&files, 0).is_empty());
+}
+
+#[test]
+fn test_select_sample_spreads_across_files() {
+    let files = make_files(100);
+    let sample = select_sample(&files, 4);
+
+    let paths: Vec<_> = sample.iter().map(|f| f.path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/repo/file0.rs"),
+            PathBuf::from("/repo/file25.rs"),
+            PathBuf::from("/repo/file50.rs"),
+            PathBuf::from("/repo/file75.rs"),
+        ]
+    );
+}
+
+// ============================================================================
+// FileSample scaling
+// ============================================================================
+
+#[test]
+fn test_file_sample_scales_references_to_all_symbols() {
+    let sample = make_sample(20, 5, 10);
+    assert!((sample.estimated_references() - 40.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_file_sample_without_probes_estimates_zero_references() {
+    let sample = make_sample(20, 0, 0);
+    assert!(sample.estimated_references().abs() < f64::EPSILON);
+    assert!(sample.phase3_secs().abs() < f64::EPSILON);
+}
+
+// ============================================================================
+// build_estimate
+// ============================================================================
+
+#[test]
+fn test_build_estimate_counts_structural_nodes_and_edges() {
+    let files = make_files(10);
+    let samples = vec![make_sample(5, 5, 0); 10];
+
+    let estimate = build_estimate(&files, &samples);
+
+    assert_eq!(estimate.total_files, 10);
+    assert_eq!(estimate.sampled_files, 10);
+    assert_eq!(estimate.files_by_language.get(&Language::Rust), Some(&10));
+    // 50 symbols + 50 identities + 10 files + commit + scan run
+    assert!((estimate.nodes.mid - 112.0).abs() < 1e-9);
+    // 50 DEFINED_IN + 50 SNAPSHOT_OF + 10 CONTAINS + 1 FOR_COMMIT
+    assert!((estimate.edges.mid - 111.0).abs() < 1e-9);
+    assert!(estimate.database_bytes.mid > 0.0);
+}
+
+#[test]
+fn test_build_estimate_with_no_samples() {
+    let files = make_files(10);
+    let estimate = build_estimate(&files, &[]);
+
+    assert_eq!(estimate.sampled_files, 0);
+    assert!(estimate.symbols.mid.abs() < f64::EPSILON);
+    assert!(estimate.duration_secs.mid.abs() < f64::EPSILON);
+}
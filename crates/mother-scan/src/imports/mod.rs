@@ -0,0 +1,158 @@
+//! Import linking: Extract import statements and create IMPORTS edges
+//!
+//! Runs after Phase 2, once every file of the commit exists in Neo4j. Raw
+//! module paths are stored on new `File` nodes; imports that resolve to
+//! another repository file become `File -[:IMPORTS]-> File` edges.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use mother_core::graph::model::ImportEdge;
+use mother_core::graph::GraphStore;
+use mother_core::progress::{Progress, ScanPhase};
+use mother_core::scanner::{
+    extract_imports, resolve_import, DiscoveredFile, FileContentCache, ImportStatement,
+};
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+use super::FileToProcess;
+
+/// Results from import linking
+pub struct ImportsResult {
+    pub import_count: usize,
+    pub edge_count: usize,
+    pub error_count: usize,
+}
+
+impl ImportsResult {
+    /// Work done on `attempted` files, for throughput metrics
+    pub(crate) fn counts(&self, attempted: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: attempted.saturating_sub(self.error_count),
+            symbols: 0,
+            attempted,
+            errors: self.error_count,
+        }
+    }
+}
+
+/// Extract imports of every file and link files that import each other
+///
+/// Files are read through `cache`, so those Phase 1 just read are not read
+/// again.
+pub async fn run(
+    files: &[DiscoveredFile],
+    new_files: &[FileToProcess],
+    client: &dyn GraphStore,
+    cache: &FileContentCache,
+    commit_sha: &str,
+    progress: &Progress,
+) -> Result<ImportsResult> {
+    info!("Linking imports for {} files...", files.len());
+    progress.start(ScanPhase::Imports, files.len());
+
+    let known: HashSet<PathBuf> = files.iter().map(|f| f.path.clone()).collect();
+    let new_hashes: HashMap<&PathBuf, &str> = new_files
+        .iter()
+        .map(|f| (&f.path, f.content_hash.as_str()))
+        .collect();
+
+    let mut result = ImportsResult {
+        import_count: 0,
+        edge_count: 0,
+        error_count: 0,
+    };
+    let mut edges = Vec::new();
+
+    for file in files {
+        let hash = new_hashes.get(&file.path).copied();
+        match collect_file_imports(file, hash, &known, client, cache).await {
+            Ok((count, file_edges)) => {
+                result.import_count += count;
+                edges.extend(file_edges);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to link imports of {}: {}", file.path.display(), e);
+                result.error_count += 1;
+            }
+        }
+        progress.advance(ScanPhase::Imports, 0, 0);
+    }
+
+    client.create_import_edges(commit_sha, &edges).await?;
+    progress.finish(ScanPhase::Imports);
+    result.edge_count = edges.len();
+
+    log_imports_result(&result);
+    Ok(result)
+}
+
+fn log_imports_result(result: &ImportsResult) {
+    info!(
+        "Imports: {} statements, {} file edges",
+        result.import_count, result.edge_count
+    );
+    if result.error_count > 0 {
+        tracing::warn!("Imports: {} files failed to link", result.error_count);
+    }
+}
+
+/// Extract one file's imports, store them if the file is new, and resolve them
+///
+/// Returns the number of import statements and the resolved edges.
+async fn collect_file_imports(
+    file: &DiscoveredFile,
+    new_content_hash: Option<&str>,
+    known: &HashSet<PathBuf>,
+    client: &dyn GraphStore,
+    cache: &FileContentCache,
+) -> Result<(usize, Vec<ImportEdge>)> {
+    let content = cache.read(&file.path)?;
+    let imports = extract_imports(&file.language, content.text()?);
+
+    if let Some(hash) = new_content_hash {
+        let modules: Vec<String> = imports.iter().map(|i| i.module.clone()).collect();
+        client.set_file_imports(hash, &modules).await?;
+    }
+
+    Ok((imports.len(), resolve_file_imports(file, &imports, known)))
+}
+
+/// Resolve a file's imports to edges towards other repository files
+///
+/// Self-imports and duplicate targets for the same module are dropped.
+pub(crate) fn resolve_file_imports(
+    file: &DiscoveredFile,
+    imports: &[ImportStatement],
+    known: &HashSet<PathBuf>,
+) -> Vec<ImportEdge> {
+    let mut edges: Vec<ImportEdge> = Vec::new();
+
+    for import in imports {
+        let Some(target) = resolve_import(&file.language, &file.path, &import.module, known) else {
+            continue;
+        };
+        if target == file.path {
+            continue;
+        }
+        let edge = ImportEdge {
+            from_path: file.path.display().to_string(),
+            to_path: target.display().to_string(),
+            module: import.module.clone(),
+            line: import.line,
+        };
+        if !edges
+            .iter()
+            .any(|e| e.to_path == edge.to_path && e.module == edge.module)
+        {
+            edges.push(edge);
+        }
+    }
+
+    edges
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for import linking
+
+mod tests_resolve_file_imports;
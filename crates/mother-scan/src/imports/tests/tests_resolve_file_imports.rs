@@ -0,0 +1,80 @@
+//! Tests for resolving a file's imports into IMPORTS edges
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use mother_core::scanner::{DiscoveredFile, ImportStatement, Language};
+
+use super::super::resolve_file_imports;
+
+fn known(paths: &[&str]) -> HashSet<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+fn import(module: &str, line: u32) -> ImportStatement {
+    ImportStatement {
+        module: module.to_string(),
+        line,
+    }
+}
+
+#[test]
+fn test_resolves_crate_import_to_file() {
+    let file = DiscoveredFile {
+        path: PathBuf::from("/repo/src/main.rs"),
+        language: Language::Rust,
+    };
+    let files = known(&["/repo/src/main.rs", "/repo/src/graph/model.rs"]);
+
+    let edges = resolve_file_imports(&file, &[import("crate::graph::model::Edge", 3)], &files);
+
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].from_path, "/repo/src/main.rs");
+    assert_eq!(edges[0].to_path, "/repo/src/graph/model.rs");
+    assert_eq!(edges[0].module, "crate::graph::model::Edge");
+    assert_eq!(edges[0].line, 3);
+}
+
+#[test]
+fn test_skips_external_imports() {
+    let file = DiscoveredFile {
+        path: PathBuf::from("/repo/src/lib.rs"),
+        language: Language::Rust,
+    };
+    let files = known(&["/repo/src/lib.rs"]);
+
+    let edges = resolve_file_imports(&file, &[import("std::collections::HashMap", 1)], &files);
+
+    assert!(edges.is_empty());
+}
+
+#[test]
+fn test_skips_self_imports() {
+    let file = DiscoveredFile {
+        path: PathBuf::from("/repo/src/util.ts"),
+        language: Language::TypeScript,
+    };
+    let files = known(&["/repo/src/util.ts"]);
+
+    let edges = resolve_file_imports(&file, &[import("./util", 1)], &files);
+
+    assert!(edges.is_empty());
+}
+
+#[test]
+fn test_deduplicates_same_module_and_target() {
+    let file = DiscoveredFile {
+        path: PathBuf::from("/repo/app/main.py"),
+        language: Language::Python,
+    };
+    let files = known(&["/repo/app/main.py", "/repo/app/models.py"]);
+
+    let edges = resolve_file_imports(
+        &file,
+        &[import("app.models", 1), import("app.models", 7)],
+        &files,
+    );
+
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].line, 1);
+}
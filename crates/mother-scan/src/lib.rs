@@ -0,0 +1,1014 @@
+//! mother-scan: Scan pipeline storing a repository's graph via LSP
+//!
+//! This crate runs the scans of `mother scan` and of the `mother` facade's
+//! `Ingestor`, in 3 phases:
+//! 1. Phase 1: Discover files, link stored ones, open new ones in LSP
+//! 2. Phase 2: Extract symbols from LSP, enrich with hover, store each new
+//!    file with its symbols in Neo4j
+//! 3. Phase 3: Extract references, create symbol-to-symbol edges
+//!
+//! Discovery leaves out binary files, files over
+//! [`ScanOptions::max_file_size`] and files carrying a generated-file
+//! marker; the scan report records each with the reason.
+//!
+//! Between Phases 2 and 3, the `imports` submodule links files through
+//! IMPORTS edges based on their import/use statements, and the
+//! `directories` submodule stores a DirectorySummary per directory.
+//!
+//! After Phase 3, the `modified` submodule links the commit to the symbols
+//! it changed since the previously scanned commit with MODIFIED edges, and
+//! the `complexity` submodule stores per-symbol fan-in, fan-out and
+//! reference counts used to rank hotspots. The `testing` submodule links
+//! the commit's tests to the symbols they reference or call with TESTS
+//! edges. The `modules` submodule then
+//! stores the Module nodes the commit's symbols are grouped into, and the
+//! `contracts` submodule the endpoints and messages of the repository's
+//! OpenAPI specs and `.proto` files, linked to the symbols serving them.
+//! The `repositories` submodule stores the packages the repository's
+//! manifests define and depend on, and links it to the other scanned
+//! repositories it depends on with DEPENDS_ON edges.
+//! With a `[linking]` config section the `linking` submodule links symbols
+//! of different languages sharing an identifier with LINKS_TO edges.
+//!
+//! With `--diagnostics`, the `diagnostics` submodule stores the problems
+//! language servers reported in each new file after Phase 3.
+//!
+//! During Phase 2 the `lineage` submodule links each changed file's symbols
+//! to their previous versions with SAME_AS, EVOLVED_FROM and RENAMED_TO
+//! edges, stored together with the symbols.
+//!
+//! Bare repositories are scanned through [`run_bare`], which checks the
+//! requested commit out to a temporary directory first.
+//!
+//! The `dry_run` submodule runs the full pipeline without writing to Neo4j
+//! and reports what a scan would store.
+//!
+//! The `metrics` submodule times each phase and logs its throughput; with
+//! `--report` the rates are also written to a JSON file. With
+//! [`ScanOptions::metrics`] set, files, symbols, LSP requests and store write
+//! latencies are also counted into a registry exported for Prometheus.
+//!
+//! Phase 2 hands its symbols to Phase 3 through the `buffer` submodule,
+//! which spills them to a temporary file beyond
+//! [`ScanOptions::symbol_buffer`] so the phases after it read them a chunk
+//! at a time.
+//!
+//! The `status` submodule records each scan run as in progress before its
+//! files are written and marks it completed or failed when the scan ends.
+//!
+//! The `report` submodule collects the files and symbols each phase failed
+//! on, stores them with the scan run and summarizes them at the end; with
+//! `--report-file` they are also written to a JSON file.
+//!
+//! Phases report progress events through [`ScanOptions::progress`], which
+//! `mother scan` renders as terminal bars.
+//!
+//! With [`ScanOptions::max_duration`], the `budget` submodule stops the
+//! scan from starting new LSP work once its time is up and marks the run
+//! partial instead of letting it be killed.
+//!
+//! The `chaos` submodule injects LSP timeouts, store write failures and
+//! process kills at configurable rates, to exercise failure handling.
+//!
+//! The `estimate` submodule runs a sampled, write-free variant of the pipeline
+//! to predict graph size and scan duration before a full ingestion.
+
+mod budget;
+mod buffer;
+mod chaos;
+mod complexity;
+mod contracts;
+mod diagnostics;
+mod directories;
+mod dry_run;
+mod estimate;
+mod imports;
+mod lineage;
+mod linking;
+mod metrics;
+mod modified;
+mod modules;
+mod phase1;
+mod phase2;
+mod phase3;
+mod report;
+mod repositories;
+mod rng;
+mod status;
+mod testing;
+
+#[cfg(test)]
+mod tests;
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use mother_core::embed::{Embedder, EmbeddingEnricher};
+use mother_core::enrich::{EnrichmentConfig, EnrichmentPipeline};
+use mother_core::graph::ids::{ContentHashIds, SymbolIdStrategy};
+use mother_core::graph::linking::LinkConventions;
+use mother_core::graph::model::{
+    FileMetrics, FileWrite, ScanReport, ScanRun, ScanStatus, SkippedFile,
+};
+use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{LspServerConfig, LspServerManager, Readiness, ReplayTransport, Url};
+use mother_core::progress::Progress;
+use mother_core::scanner::{
+    CommitCheckout, ContentFilter, DiscoveredFile, Discovery, FileContentCache, Language, Scanner,
+};
+use tracing::{info, Instrument};
+
+pub use budget::parse_duration;
+use budget::ScanBudget;
+pub(crate) use buffer::SymbolBuffer;
+pub use chaos::ChaosConfig;
+use chaos::{ChaosStore, FaultInjector};
+pub use dry_run::{run as dry_run, DryRunReport};
+pub use estimate::{run as estimate, Bounded, ScanEstimate};
+use metrics::{CountingStore, PhaseMetrics, PhaseTimer, ThroughputReport};
+pub use metrics::{MetricsExporter, MetricsRegistry};
+pub(crate) use phase1::Phase1Result;
+pub(crate) use phase2::Phase2Result;
+use phase2::SymbolIds;
+pub(crate) use phase3::Phase3Result;
+pub use report::describe_failure;
+pub use status::{
+    begin as begin_scan_run, finish as finish_scan_run, is_incomplete as is_incomplete_run,
+};
+
+// ============================================================================
+// Types shared across phases
+// ============================================================================
+
+/// A file that needs symbol extraction (output from Phase 1)
+pub struct FileToProcess {
+    pub path: std::path::PathBuf,
+    pub file_uri: String,
+    pub content_hash: String,
+    pub language: Language,
+    /// Line count and byte size of the content opened in phase 1
+    pub metrics: FileMetrics,
+    /// Commit the file is linked to once stored
+    pub commit_sha: String,
+}
+
+impl FileToProcess {
+    /// The file's node alone, for a file whose symbols could not be
+    /// extracted
+    pub(crate) fn node_only(&self) -> FileWrite {
+        FileWrite {
+            path: self.path.display().to_string(),
+            content_hash: self.content_hash.clone(),
+            language: self.language.to_string(),
+            commit_sha: self.commit_sha.clone(),
+            ..FileWrite::default()
+        }
+    }
+}
+
+/// Symbol position info for reference extraction (output from Phase 2)
+#[derive(Clone)]
+pub struct SymbolInfo {
+    pub id: String,
+    pub file_uri: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub start_col: u32,
+    pub language: Language,
+}
+
+/// Options controlling a scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Version tag for this scan
+    pub version: Option<String>,
+    /// Ordered enrichers to apply in Phase 2
+    pub enrichment: EnrichmentConfig,
+    /// Globs of files to scan; empty scans everything
+    pub include: Vec<String>,
+    /// Globs of files to skip
+    pub exclude: Vec<String>,
+    /// Largest file scanned, in bytes; larger files are skipped
+    pub max_file_size: Option<u64>,
+    /// Markers in the first lines of a file that skip it as generated;
+    /// [`DEFAULT_GENERATED_MARKERS`](mother_core::scanner::DEFAULT_GENERATED_MARKERS)
+    /// when unset
+    pub generated_markers: Option<Vec<String>>,
+    /// LSP server overrides, replacing the per-language defaults
+    pub lsp_servers: Vec<LspServerConfig>,
+    /// Languages registered in the config, with the file extensions they claim
+    pub custom_languages: Vec<(Language, Vec<String>)>,
+    /// Most requests in flight per language server; unlisted are unbounded
+    pub request_limits: Vec<(Language, NonZeroUsize)>,
+    /// Most documents open per language server; unlisted keep every file
+    /// open until its phases are done
+    pub open_document_limits: Vec<(Language, NonZeroUsize)>,
+    /// How each language server is waited for after it starts; unlisted use
+    /// their language's default
+    pub readiness: Vec<(Language, Readiness)>,
+    /// Directory to log each language server's JSON-RPC traffic to
+    pub lsp_trace: Option<PathBuf>,
+    /// Directory of recorded traces to answer LSP requests from instead of
+    /// starting language servers
+    pub lsp_replay: Option<PathBuf>,
+    /// File to write per-phase throughput metrics to, as JSON
+    pub report: Option<PathBuf>,
+    /// File to write the failures of the scan to, as JSON
+    pub report_file: Option<PathBuf>,
+    /// Most references stored per symbol; above it a sample is stored
+    pub max_references: Option<usize>,
+    /// Most symbols Phase 2 holds in memory for Phase 3; beyond it they are
+    /// spilled to a temporary file. Unbounded when unset
+    pub symbol_buffer: Option<NonZeroUsize>,
+    /// Receives progress events from each phase
+    pub progress: Progress,
+    /// Registry counting the work of every scan, for a metrics exporter;
+    /// `progress` should be wrapped with [`MetricsRegistry::progress`] too
+    pub metrics: Option<Arc<MetricsRegistry>>,
+    /// Time after which the scan stops starting new LSP work and ends as
+    /// partial
+    pub max_duration: Option<Duration>,
+    /// Fault rates to inject, for resilience testing
+    pub chaos: Option<ChaosConfig>,
+    /// Store the diagnostics language servers report for each file
+    pub diagnostics: bool,
+    /// Resolve call sites outside the references through go-to-definition,
+    /// linking calls into unscanned code to ExternalSymbol placeholders
+    pub external_refs: bool,
+    /// Embeds symbols after the other enrichers, for `query similar`
+    pub embedder: Option<Arc<dyn Embedder>>,
+    /// Conventions to link symbols across languages by after the module
+    /// pass; no linking without
+    pub linking: Option<LinkConventions>,
+    /// Derives the IDs of stored symbols; content hashes when unset
+    pub id_strategy: Option<Arc<dyn SymbolIdStrategy>>,
+}
+
+impl ScanOptions {
+    /// The configured enrichers, followed by the embedding enricher when an
+    /// embedder is set
+    ///
+    /// # Errors
+    /// Returns an error if an enricher name is unknown.
+    pub(crate) fn pipeline(&self) -> Result<EnrichmentPipeline> {
+        let pipeline = EnrichmentPipeline::from_config(&self.enrichment)?;
+        Ok(match &self.embedder {
+            Some(embedder) => pipeline.with(Box::new(EmbeddingEnricher::new(Arc::clone(embedder)))),
+            None => pipeline,
+        })
+    }
+
+    /// How Phase 2 names the symbols of files under `root`
+    pub(crate) fn symbol_ids<'a>(&'a self, root: &'a Path) -> SymbolIds<'a> {
+        SymbolIds {
+            root,
+            strategy: self.id_strategy.as_deref().unwrap_or(&ContentHashIds),
+        }
+    }
+
+    /// The buffer Phase 2 collects symbols in, spilling beyond
+    /// [`ScanOptions::symbol_buffer`]
+    pub(crate) fn symbol_buffer(&self) -> SymbolBuffer {
+        SymbolBuffer::new(self.symbol_buffer)
+    }
+
+    /// Discover the files to scan under `root`, applying include/exclude
+    /// globs and leaving out binary, generated and oversized files
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub fn discover_files(&self, root: &Path) -> Result<Vec<DiscoveredFile>> {
+        Ok(self.scanner(root)?.scan().collect())
+    }
+
+    /// Like [`ScanOptions::discover_files`], with the files left out and
+    /// why
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub(crate) fn discover(&self, root: &Path) -> Result<Discovery> {
+        Ok(self.scanner(root)?.discover())
+    }
+
+    /// Discover the files under `root` to read API contracts from, applying
+    /// include/exclude globs
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub(crate) fn discover_contracts(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        Ok(self.scanner(root)?.contract_files().collect())
+    }
+
+    /// Discover the package manifests under `root`, applying
+    /// include/exclude globs
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub(crate) fn discover_manifests(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        Ok(self.scanner(root)?.manifest_files().collect())
+    }
+
+    fn scanner(&self, root: &Path) -> Result<Scanner> {
+        Ok(self
+            .custom_languages
+            .iter()
+            .fold(Scanner::new(root), |scanner, (language, extensions)| {
+                scanner.with_custom_language(language.clone(), extensions)
+            })
+            .with_filters(&self.include, &self.exclude)?
+            .with_content_filter(self.content_filter()))
+    }
+
+    fn content_filter(&self) -> ContentFilter {
+        let filter = ContentFilter::default().with_max_file_size(self.max_file_size);
+        match &self.generated_markers {
+            Some(markers) => filter.with_generated_markers(markers.clone()),
+            None => filter,
+        }
+    }
+
+    /// Create an LSP manager with the configured server overrides, request
+    /// limits, open document limits and readiness strategies registered,
+    /// traffic traced or replayed when asked, and requests counted into the
+    /// metrics registry
+    pub fn lsp_manager(&self, root: &Path) -> LspServerManager {
+        let mut manager = LspServerManager::new(root);
+        for server in &self.lsp_servers {
+            manager.register_server(server.clone());
+        }
+        for (language, limit) in &self.request_limits {
+            manager.set_request_limit(language.clone(), *limit);
+        }
+        for (language, limit) in &self.open_document_limits {
+            manager.set_open_document_limit(language.clone(), *limit);
+        }
+        for (language, readiness) in &self.readiness {
+            manager.set_readiness(language.clone(), *readiness);
+        }
+        if let Some(dir) = &self.lsp_trace {
+            manager.set_trace_dir(dir);
+        }
+        if let Some(dir) = &self.lsp_replay {
+            manager.set_transport(Arc::new(ReplayTransport::new(dir)));
+        }
+        if let Some(metrics) = &self.metrics {
+            manager.set_request_metrics(metrics.lsp_metrics());
+        }
+        manager
+    }
+}
+
+// ============================================================================
+// Main entry point
+// ============================================================================
+
+/// Run the scan command
+///
+/// # Errors
+/// Returns an error if scanning or Neo4j operations fail.
+pub async fn run(path: &Path, neo4j: &Neo4jConfig, options: &ScanOptions) -> Result<()> {
+    let client = connect_neo4j(neo4j).await?;
+    run_with_store(path, &client, options).await
+}
+
+/// Run the scan command against an open graph store
+///
+/// # Errors
+/// Returns an error if scanning or storage operations fail.
+pub async fn run_with_store(
+    path: &Path,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+) -> Result<()> {
+    info!("Scanning repository: {}", path.display());
+
+    let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let (scan_run, _) = create_scan_run(&abs_path, options.version.as_deref());
+    scan_tree(&abs_path, scan_run, client, options).await
+}
+
+/// Run the scan command on a commit of a bare repository
+///
+/// # Errors
+/// Returns an error if the commit cannot be checked out, or scanning or
+/// Neo4j operations fail.
+pub async fn run_bare(
+    repo: &Path,
+    commit: &str,
+    neo4j: &Neo4jConfig,
+    options: &ScanOptions,
+) -> Result<()> {
+    let client = connect_neo4j(neo4j).await?;
+    run_bare_with_store(repo, commit, &client, options).await
+}
+
+/// Run the scan command on a commit of a bare repository against an open
+/// graph store
+///
+/// The commit's tree is written to a temporary directory, which is scanned
+/// and removed again afterwards. The scan run records the repository path
+/// and the commit; `commit` may be a SHA, SHA prefix, branch or tag.
+///
+/// # Errors
+/// Returns an error if the commit cannot be checked out, or scanning or
+/// storage operations fail.
+pub async fn run_bare_with_store(
+    repo: &Path,
+    commit: &str,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+) -> Result<()> {
+    info!("Scanning {} of bare repository: {}", commit, repo.display());
+    scan_commit(repo, commit, client, options, None).await?;
+    Ok(())
+}
+
+/// Check a commit out to a temporary directory and scan it, returning the
+/// ID of the recorded scan run
+///
+/// `previous_run` links the run to the run scanned before it.
+///
+/// # Errors
+/// Returns an error if the commit cannot be checked out, or scanning or
+/// storage operations fail.
+pub async fn scan_commit(
+    repo: &Path,
+    commit: &str,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+    previous_run: Option<&str>,
+) -> Result<String> {
+    let checkout = CommitCheckout::materialize(repo, commit)?;
+    info!("Checked out commit to {}", checkout.path().display());
+    let mut scan_run = checkout.scan_run();
+    if let Some(v) = &options.version {
+        scan_run = scan_run.with_version(v);
+    }
+    if let Some(previous) = previous_run {
+        scan_run = scan_run.with_previous_run(previous);
+    }
+    let id = scan_run.id.clone();
+    let abs_path = checkout.path().canonicalize()?;
+    scan_tree(&abs_path, scan_run, client, options).await?;
+    Ok(id)
+}
+
+/// Record a scan run and, for a commit not scanned before, scan the files
+/// under `abs_path`
+async fn scan_tree(
+    abs_path: &Path,
+    scan_run: ScanRun,
+    client: &dyn GraphStore,
+    options: &ScanOptions,
+) -> Result<()> {
+    let pipeline = options.pipeline()?;
+    info!("Enrichers: {}", pipeline.names().join(", "));
+    let commit_sha = scan_run.commit_sha.clone().unwrap_or_default();
+
+    log_scan_run_info(&scan_run, &commit_sha);
+
+    if !status::begin(client, &scan_run).await? {
+        return Ok(());
+    }
+
+    let span = tracing::info_span!("scan", run = %scan_run.id, commit = %commit_sha);
+    let result = execute_scan(abs_path, client, &scan_run, &pipeline, options)
+        .instrument(span)
+        .await;
+    status::finish_as(client, &scan_run.id, result).await
+}
+
+/// Execute the scan workflow after determining a new commit needs scanning,
+/// returning the status to mark the run with
+async fn execute_scan(
+    abs_path: &Path,
+    client: &dyn GraphStore,
+    scan_run: &ScanRun,
+    pipeline: &EnrichmentPipeline,
+    options: &ScanOptions,
+) -> Result<ScanStatus> {
+    info!("New commit detected, scanning files...");
+    let commit_sha = scan_run.commit_sha.as_deref().unwrap_or_default();
+    let started = Instant::now();
+    let budget = options
+        .max_duration
+        .map(|limit| ScanBudget::new(limit, started));
+    let budgeted = budget.as_ref().map(|budget| budget.options(options));
+    let options = budgeted.as_ref().unwrap_or(options);
+
+    let Discovery { files, skipped } = options.discover(abs_path)?;
+    info!("Found {} files to process", files.len());
+    log_skipped(&skipped);
+
+    let faults = options
+        .chaos
+        .map(|config| Arc::new(FaultInjector::new(config)));
+    let chaos_store = faults.clone().map(|f| ChaosStore::new(client, f));
+    let store = CountingStore::new(match &chaos_store {
+        Some(chaos) => chaos,
+        None => client,
+    })
+    .with_registry(options.metrics.as_deref());
+    let lsp_manager = scan_lsp_manager(abs_path, options, faults, budget.as_ref());
+    let mut phases = Vec::new();
+
+    let timer = PhaseTimer::start("Phase 1", &lsp_manager, &store);
+    let progress = &options.progress;
+    let cache = FileContentCache::default();
+    let phase1 = phase1::run(&files, &store, &lsp_manager, &cache, commit_sha, progress)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(&lsp_manager, &store, phase1.counts()));
+
+    let new_files = &phase1.files_to_process;
+    let timer = PhaseTimer::start("Phase 2", &lsp_manager, &store);
+    let ids = options.symbol_ids(abs_path);
+    let phase2 = phase2::run(
+        new_files,
+        &store,
+        &lsp_manager,
+        &cache,
+        pipeline,
+        ids,
+        options,
+    )
+    .instrument(timer.span())
+    .await?;
+    phases.push(timer.finish(&lsp_manager, &store, phase2.counts(new_files.len())));
+
+    // Files are stored by Phase 2, so their imports and directories follow it
+    let timer = PhaseTimer::start("Imports", &lsp_manager, &store);
+    let imports = imports::run(&files, new_files, &store, &cache, commit_sha, progress)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(&lsp_manager, &store, imports.counts(files.len())));
+
+    let timer = PhaseTimer::start("Directories", &lsp_manager, &store);
+    directories::run(abs_path, &files, &store, commit_sha, &cache)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(&lsp_manager, &store, directories::counts(files.len())));
+
+    let symbols = &phase2.symbols;
+    let reuse = phase1.reused_file_count > 0;
+    let (phase3, metrics) = references_phase(
+        options,
+        commit_sha,
+        symbols,
+        reuse,
+        &store,
+        &lsp_manager,
+        &cache,
+    )
+    .await?;
+    phases.push(metrics);
+    let after = after_references(
+        options,
+        scan_run,
+        abs_path,
+        symbols,
+        &store,
+        &lsp_manager,
+        &cache,
+    );
+    phases.extend(after.await?);
+    let finish = finish_new_files(options, new_files, symbols, &store, &lsp_manager);
+    phases.extend(finish.await?);
+    let completion = budget.and_then(|budget| budget.finish(&lsp_manager));
+
+    shutdown_lsp(&lsp_manager).await;
+
+    log_scan_summary(&phase1, &phase2, &phase3);
+    let report = ScanReport {
+        scan_run_id: scan_run.id.clone(),
+        commit_sha: commit_sha.to_string(),
+        failures: [phase1.failures, phase2.failures, phase3.failures].concat(),
+        completion,
+        file_cache: Some(cache.stats()),
+        skipped,
+    };
+    report::finish(&report, &store, options.report_file.as_deref()).await?;
+    write_throughput(options, commit_sha, started, phases)?;
+    Ok(completion.map_or(ScanStatus::Completed, |_| ScanStatus::Partial))
+}
+
+/// Log how many files discovery left out for their content
+fn log_skipped(skipped: &[SkippedFile]) {
+    if skipped.is_empty() {
+        return;
+    }
+    info!(
+        "Skipped {} binary, generated or oversized files; `mother runs show` lists them",
+        skipped.len()
+    );
+}
+
+/// The LSP manager of a scan, injecting `faults` and stopping new work at
+/// the `budget`'s deadline when given
+fn scan_lsp_manager(
+    abs_path: &Path,
+    options: &ScanOptions,
+    faults: Option<Arc<FaultInjector>>,
+    budget: Option<&ScanBudget>,
+) -> LspServerManager {
+    let mut lsp_manager = options.lsp_manager(abs_path);
+    if let Some(faults) = faults {
+        lsp_manager.set_faults(faults);
+    }
+    if let Some(budget) = budget {
+        lsp_manager.set_deadline(budget.deadline());
+    }
+    lsp_manager
+}
+
+/// Write the throughput of the phases to the `--report` file, if any
+fn write_throughput(
+    options: &ScanOptions,
+    commit_sha: &str,
+    started: Instant,
+    phases: Vec<PhaseMetrics>,
+) -> Result<()> {
+    let Some(path) = &options.report else {
+        return Ok(());
+    };
+    ThroughputReport {
+        commit_sha: commit_sha.to_string(),
+        version: options.version.clone(),
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        phases,
+    }
+    .write(path)
+}
+
+/// Extract the references of the new symbols, timed as Phase 3
+///
+/// With `reuse`, phase 1 reused files from earlier commits; their stored
+/// symbols are loaded so references from them can be placed.
+async fn references_phase(
+    options: &ScanOptions,
+    commit_sha: &str,
+    symbols: &SymbolBuffer,
+    reuse: bool,
+    store: &CountingStore<'_>,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+) -> Result<(Phase3Result, PhaseMetrics)> {
+    let timer = PhaseTimer::start("Phase 3", lsp_manager, store);
+    let reused = if reuse {
+        phase3::reused_symbols(store, commit_sha, symbols)
+            .instrument(timer.span())
+            .await?
+    } else {
+        Vec::new()
+    };
+    let phase3 = phase3::run(symbols, &reused, store, lsp_manager, cache, options)
+        .instrument(timer.span())
+        .await?;
+    let metrics = timer.finish(lsp_manager, store, phase3.counts(symbols.len()));
+    Ok((phase3, metrics))
+}
+
+/// Link the commit to the symbols it changed, timed as a phase of its own
+async fn modified_phase(
+    scan_run: &ScanRun,
+    abs_path: &Path,
+    symbols: &SymbolBuffer,
+    store: &CountingStore<'_>,
+    lsp_manager: &LspServerManager,
+) -> Result<PhaseMetrics> {
+    let timer = PhaseTimer::start("Modified", lsp_manager, store);
+    let modified = modified::run(store, scan_run, abs_path, symbols)
+        .instrument(timer.span())
+        .await?;
+    Ok(timer.finish(lsp_manager, store, modified.counts(symbols.len())))
+}
+
+/// Run the phases that follow the references, each timed on its own:
+/// modified symbols, complexity, tests, modules, contracts, repositories,
+/// then linking if enabled
+async fn after_references(
+    options: &ScanOptions,
+    scan_run: &ScanRun,
+    abs_path: &Path,
+    symbols: &SymbolBuffer,
+    store: &CountingStore<'_>,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+) -> Result<Vec<PhaseMetrics>> {
+    let mut phases = vec![modified_phase(scan_run, abs_path, symbols, store, lsp_manager).await?];
+    let timer = PhaseTimer::start("Complexity", lsp_manager, store);
+    let commit_sha = scan_run.commit_sha.as_deref().unwrap_or_default();
+    let complexity = complexity::run(store, commit_sha)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(lsp_manager, store, complexity.counts()));
+    let timer = PhaseTimer::start("Tests", lsp_manager, store);
+    let tests = testing::run(abs_path, store, commit_sha)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(lsp_manager, store, tests.counts()));
+    let timer = PhaseTimer::start("Modules", lsp_manager, store);
+    let modules = modules::run(abs_path, store, commit_sha)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(lsp_manager, store, modules.counts()));
+    let timer = PhaseTimer::start("Contracts", lsp_manager, store);
+    let specs = options.discover_contracts(abs_path)?;
+    let contracts = contracts::run(store, commit_sha, &specs, cache)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(lsp_manager, store, contracts.counts(specs.len())));
+    let timer = PhaseTimer::start("Repositories", lsp_manager, store);
+    let manifests = options.discover_manifests(abs_path)?;
+    let repos = repositories::run(store, &scan_run.repo_path, &manifests)
+        .instrument(timer.span())
+        .await?;
+    phases.push(timer.finish(lsp_manager, store, repos.counts()));
+    if let Some(conventions) = &options.linking {
+        let timer = PhaseTimer::start("Linking", lsp_manager, store);
+        let linked = linking::run(store, commit_sha, conventions, cache)
+            .instrument(timer.span())
+            .await?;
+        phases.push(timer.finish(lsp_manager, store, linked.counts()));
+    }
+    Ok(phases)
+}
+
+/// Store the diagnostics of the new files if enabled, then close them
+async fn finish_new_files(
+    options: &ScanOptions,
+    files: &[FileToProcess],
+    symbols: &SymbolBuffer,
+    store: &CountingStore<'_>,
+    lsp_manager: &LspServerManager,
+) -> Result<Option<PhaseMetrics>> {
+    let diagnostics = diagnostics_phase(options, files, symbols, store, lsp_manager).await;
+    close_files(lsp_manager, files).await;
+    diagnostics
+}
+
+/// Store the diagnostics of the new files if enabled, timed as a phase of
+/// its own
+async fn diagnostics_phase(
+    options: &ScanOptions,
+    files: &[FileToProcess],
+    symbols: &SymbolBuffer,
+    store: &CountingStore<'_>,
+    lsp_manager: &LspServerManager,
+) -> Result<Option<PhaseMetrics>> {
+    if !options.diagnostics || lsp_manager.past_deadline() {
+        return Ok(None);
+    }
+    let timer = PhaseTimer::start("Diagnostics", lsp_manager, store);
+    let stored = diagnostics::run(files, symbols, store, lsp_manager)
+        .instrument(timer.span())
+        .await?;
+    info!("Stored {} diagnostics", stored.diagnostic_count);
+    Ok(Some(timer.finish(
+        lsp_manager,
+        store,
+        stored.counts(files.len()),
+    )))
+}
+
+// ============================================================================
+// Incremental rescans
+// ============================================================================
+
+/// Paths a rescan updated in the graph
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RescanSummary {
+    /// Paths whose new content was linked to the commit
+    pub updated: usize,
+    /// Paths that are gone or no longer scanned and were unlinked
+    pub removed: usize,
+}
+
+/// Re-scan changed paths of an already scanned commit in place
+///
+/// Each path is unlinked from the commit first. Paths that still exist and
+/// pass the scan filters then go through the scan phases again, so the
+/// commit links their new content, symbols and references.
+///
+/// # Errors
+/// Returns an error if file discovery or a store write fails.
+pub async fn rescan_paths(
+    abs_path: &Path,
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    lsp_manager: &LspServerManager,
+    options: &ScanOptions,
+    changed: &[PathBuf],
+) -> Result<RescanSummary> {
+    let pipeline = options.pipeline()?;
+    let files = options.discover_files(abs_path)?;
+    let store = CountingStore::new(client).with_registry(options.metrics.as_deref());
+    let client: &dyn GraphStore = &store;
+    let mut summary = RescanSummary::default();
+    let mut rescanned = Vec::new();
+
+    for path in changed {
+        let path_str = path.display().to_string();
+        let was_linked = client.unlink_file(commit_sha, &path_str).await?;
+        match files.iter().find(|file| &file.path == path) {
+            Some(file) => rescanned.push(file.clone()),
+            None => {
+                if let Some(language) = Language::from_path(path) {
+                    let file_uri = format!("file://{}", path.display());
+                    if let Err(e) = lsp_manager.close_document(&language, &file_uri).await {
+                        tracing::debug!("Failed to close {}: {}", path.display(), e);
+                    }
+                }
+                summary.removed += usize::from(was_linked);
+            }
+        }
+    }
+    summary.updated = rescanned.len();
+
+    let progress = &options.progress;
+    let cache = FileContentCache::default();
+    let phase1 = phase1::run(
+        &rescanned,
+        client,
+        lsp_manager,
+        &cache,
+        commit_sha,
+        progress,
+    )
+    .await?;
+    let new_files = &phase1.files_to_process;
+    let ids = options.symbol_ids(abs_path);
+    let phase2 = phase2::run(
+        new_files,
+        client,
+        lsp_manager,
+        &cache,
+        &pipeline,
+        ids,
+        options,
+    )
+    .await?;
+    imports::run(&files, new_files, client, &cache, commit_sha, progress).await?;
+    let symbols = &phase2.symbols;
+    let reused = phase3::reused_symbols(client, commit_sha, symbols).await?;
+    let phase3 = phase3::run(symbols, &reused, client, lsp_manager, &cache, options).await?;
+    rescan_after_references(abs_path, client, commit_sha, options, &cache).await?;
+    close_files(lsp_manager, new_files).await;
+
+    log_scan_summary(&phase1, &phase2, &phase3);
+    Ok(summary)
+}
+
+/// Rerun the commit-wide passes that follow the references after a rescan:
+/// complexity, tests, modules, contracts, repositories and linking
+async fn rescan_after_references(
+    abs_path: &Path,
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    options: &ScanOptions,
+    cache: &FileContentCache,
+) -> Result<()> {
+    complexity::run(client, commit_sha).await?;
+    testing::run(abs_path, client, commit_sha).await?;
+    modules::run(abs_path, client, commit_sha).await?;
+    let specs = options.discover_contracts(abs_path)?;
+    contracts::run(client, commit_sha, &specs, cache).await?;
+    let manifests = options.discover_manifests(abs_path)?;
+    repositories::run(client, &abs_path.display().to_string(), &manifests).await?;
+    if let Some(conventions) = &options.linking {
+        linking::run(client, commit_sha, conventions, cache).await?;
+    }
+    Ok(())
+}
+
+fn log_scan_summary(phase1: &Phase1Result, phase2: &Phase2Result, phase3: &Phase3Result) {
+    let total_errors = phase1.error_count + phase2.error_count + phase3.error_count;
+
+    if total_errors > 0 {
+        info!(
+            "✓ Scan completed: {} new files, {} reused, {} symbols, {} references ({} errors)",
+            phase1.new_file_count,
+            phase1.reused_file_count,
+            phase2.symbol_count,
+            phase3.reference_count,
+            total_errors
+        );
+    } else {
+        info!(
+            "✓ Scan completed: {} new files, {} reused, {} symbols, {} references",
+            phase1.new_file_count,
+            phase1.reused_file_count,
+            phase2.symbol_count,
+            phase3.reference_count
+        );
+    }
+    log_summary_notes(phase2, phase3);
+}
+
+/// Log the parts of the scan summary that only apply to some scans
+fn log_summary_notes(phase2: &Phase2Result, phase3: &Phase3Result) {
+    if phase3.external_count > 0 {
+        info!(
+            "  {} references to symbols outside the scan",
+            phase3.external_count
+        );
+    }
+    let enricher_errors = phase2.enrichers.error_count();
+    if enricher_errors > 0 {
+        info!(
+            "  {} enricher failures (see the scan report)",
+            enricher_errors
+        );
+    }
+}
+
+fn create_scan_run(abs_path: &Path, version: Option<&str>) -> (ScanRun, String) {
+    let mut scan_run = ScanRun::new(abs_path.display().to_string()).with_git_info();
+    if let Some(v) = version {
+        scan_run = scan_run.with_version(v);
+    }
+    let commit_sha = scan_run.commit_sha.clone().unwrap_or_default();
+    (scan_run, commit_sha)
+}
+
+fn log_scan_run_info(scan_run: &ScanRun, commit_sha: &str) {
+    info!(
+        "Created scan run: {} (commit: {}, branch: {:?})",
+        scan_run.id,
+        if commit_sha.is_empty() {
+            "none"
+        } else {
+            commit_sha
+        },
+        scan_run.branch
+    );
+}
+
+/// Open `file_uri` on its language's server unless it is open, reading its
+/// text through `cache`
+///
+/// With an open document limit Phase 1 leaves files closed and the limit
+/// closes the least recently used ones, so the later phases call this before
+/// their requests about a file.
+///
+/// # Errors
+/// Returns an error if `file_uri` is not a file URI, the file cannot be read
+/// or the server fails.
+pub(crate) async fn ensure_open(
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    language: &Language,
+    file_uri: &str,
+) -> Result<()> {
+    lsp_manager
+        .ensure_document_open(language, file_uri, || {
+            let path = Url::parse(file_uri)?
+                .to_file_path()
+                .map_err(|()| anyhow!("Not a file URI: {file_uri}"))?;
+            Ok(cache.read_to_string(&path)?)
+        })
+        .await
+}
+
+/// Store a new file without symbols, so the commit still contains it when
+/// extracting them failed
+///
+/// A failure to store it is only logged, as the file is already reported
+/// as failed.
+pub(crate) async fn store_node_only(client: &dyn GraphStore, file: &FileToProcess) {
+    if let Err(e) = client.write_file(&file.node_only()).await {
+        tracing::warn!("Failed to store {}: {}", file.path.display(), e);
+    }
+}
+
+/// Close the documents of scanned files once all their phases are done, so
+/// servers don't keep every file of the scan in memory
+async fn close_files(lsp_manager: &LspServerManager, files: &[FileToProcess]) {
+    for file in files {
+        if let Err(e) = lsp_manager
+            .close_document(&file.language, &file.file_uri)
+            .await
+        {
+            tracing::debug!("Failed to close {}: {}", file.path.display(), e);
+        }
+    }
+}
+
+/// Connect to the Neo4j database scans are stored in
+///
+/// # Errors
+/// Returns an error if the connection fails.
+pub async fn connect_neo4j(config: &Neo4jConfig) -> Result<Neo4jClient> {
+    Ok(Neo4jClient::connect(config).await?)
+}
+
+/// Shut down the language servers of a scan, logging a failure
+pub async fn shutdown_lsp(lsp_manager: &LspServerManager) {
+    if let Err(e) = lsp_manager.shutdown_all().await {
+        tracing::warn!("Failed to shutdown LSP servers: {}", e);
+    }
+}
@@ -0,0 +1,88 @@
+//! Symbol lineage: Link new symbol versions to their previous versions
+//!
+//! Runs in Phase 2 before a file is stored, so the edges are stored along
+//! with its symbols. The symbols of the file's previous version (the last
+//! other content stored at the same path) are matched against the new ones:
+//!
+//! - a symbol with the same identity key gets a `SAME_AS` edge to its
+//!   previous version;
+//! - a symbol with a new identity that takes the place of one that
+//!   disappeared (see [`find_renamed`]) is taken to be a rename and gets an
+//!   `EVOLVED_FROM` edge, and the disappeared symbol a `RENAMED_TO` edge to
+//!   it.
+//!
+//! With these edges, history queries can follow a symbol across renames,
+//! backwards and forwards.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use mother_core::graph::diff::find_renamed;
+use mother_core::graph::model::{Edge, EdgeKind, SymbolNode};
+use mother_core::graph::GraphStore;
+
+/// The edges linking the symbols of a new file version to the previous
+/// version
+pub(super) async fn edges(
+    client: &dyn GraphStore,
+    symbols: &[SymbolNode],
+    content_hash: &str,
+) -> Result<Vec<Edge>> {
+    let Some(file_path) = symbols.first().map(|s| s.file_path.as_str()) else {
+        return Ok(Vec::new());
+    };
+    let previous = client
+        .previous_file_symbols(file_path, content_hash)
+        .await?;
+    Ok(match_versions(symbols, &previous))
+}
+
+/// Match the symbols of a file version against those of its previous version
+pub(super) fn match_versions(current: &[SymbolNode], previous: &[SymbolNode]) -> Vec<Edge> {
+    let mut by_key: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (i, old) in previous.iter().enumerate() {
+        by_key.entry(old.identity_key()).or_default().push_back(i);
+    }
+    let current_keys: HashSet<String> = current.iter().map(SymbolNode::identity_key).collect();
+
+    let mut edges = Vec::new();
+    let mut unmatched = Vec::new();
+    for new in current {
+        match by_key.get_mut(&new.identity_key()) {
+            Some(candidates) => {
+                // Surplus duplicates of a known identity are not renames
+                if let Some(i) = candidates.pop_front() {
+                    edges.push(lineage_edge(new, &previous[i], EdgeKind::SameAs));
+                }
+            }
+            None => unmatched.push(new),
+        }
+    }
+
+    // Previous symbols whose identity is gone may have been renamed
+    let mut gone: Vec<&SymbolNode> = previous
+        .iter()
+        .filter(|old| !current_keys.contains(&old.identity_key()))
+        .collect();
+    for new in unmatched {
+        if let Some(i) = find_renamed(new, &gone) {
+            edges.push(lineage_edge(new, gone[i], EdgeKind::EvolvedFrom));
+            edges.push(lineage_edge(gone[i], new, EdgeKind::RenamedTo));
+            gone.remove(i);
+        }
+    }
+    edges
+}
+
+fn lineage_edge(source: &SymbolNode, target: &SymbolNode, kind: EdgeKind) -> Edge {
+    Edge {
+        source_id: source.id.clone(),
+        target_id: target.id.clone(),
+        kind,
+        line: None,
+        column: None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
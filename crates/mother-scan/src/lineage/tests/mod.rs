@@ -0,0 +1,3 @@
+//! Tests for lineage module
+
+mod tests_lineage;
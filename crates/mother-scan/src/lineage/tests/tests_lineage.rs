@@ -0,0 +1,142 @@
+//! Tests for matching symbol versions across file versions
+
+use mother_core::graph::model::{EdgeKind, SymbolKind, SymbolNode};
+
+use super::super::match_versions;
+
+fn symbol(id: &str, name: &str, kind: SymbolKind, start_line: u32, end_line: u32) -> SymbolNode {
+    SymbolNode::new(id, name, kind, "/repo/src/lib.rs", start_line, end_line)
+}
+
+fn links(current: &[SymbolNode], previous: &[SymbolNode]) -> Vec<(String, String, EdgeKind)> {
+    match_versions(current, previous)
+        .into_iter()
+        .map(|e| (e.source_id, e.target_id, e.kind))
+        .collect()
+}
+
+// ============================================================================
+// Tests for SAME_AS
+// ============================================================================
+
+#[test]
+fn test_same_identity_links_same_as() {
+    let previous = [symbol("old", "parse", SymbolKind::Function, 1, 5)];
+    let current = [symbol("new", "parse", SymbolKind::Function, 10, 20)];
+
+    assert_eq!(
+        links(&current, &previous),
+        [("new".to_string(), "old".to_string(), EdgeKind::SameAs)]
+    );
+}
+
+#[test]
+fn test_duplicate_identities_link_in_order() {
+    let previous = [
+        symbol("old1", "impl", SymbolKind::Module, 1, 5),
+        symbol("old2", "impl", SymbolKind::Module, 7, 9),
+    ];
+    let current = [
+        symbol("new1", "impl", SymbolKind::Module, 1, 5),
+        symbol("new2", "impl", SymbolKind::Module, 7, 9),
+        symbol("new3", "impl", SymbolKind::Module, 11, 12),
+    ];
+
+    assert_eq!(
+        links(&current, &previous),
+        [
+            ("new1".to_string(), "old1".to_string(), EdgeKind::SameAs),
+            ("new2".to_string(), "old2".to_string(), EdgeKind::SameAs),
+        ]
+    );
+}
+
+#[test]
+fn test_new_file_has_no_links() {
+    let current = [symbol("new", "parse", SymbolKind::Function, 1, 5)];
+    assert!(links(&current, &[]).is_empty());
+}
+
+// ============================================================================
+// Tests for EVOLVED_FROM
+// ============================================================================
+
+#[test]
+fn test_rename_in_place_links_evolved_from() {
+    let previous = [
+        symbol("a", "keep", SymbolKind::Function, 1, 3),
+        symbol("b", "parse", SymbolKind::Function, 5, 9),
+        symbol("c", "other", SymbolKind::Function, 11, 15),
+    ];
+    let current = [
+        symbol("a2", "keep", SymbolKind::Function, 1, 3),
+        symbol("b2", "parse_input", SymbolKind::Function, 5, 9),
+        symbol("c2", "other", SymbolKind::Function, 11, 15),
+    ];
+
+    let result = links(&current, &previous);
+    assert!(result.contains(&("b2".to_string(), "b".to_string(), EdgeKind::EvolvedFrom)));
+    assert!(result.contains(&("b".to_string(), "b2".to_string(), EdgeKind::RenamedTo)));
+    assert_eq!(result.len(), 4);
+}
+
+#[test]
+fn test_moved_rename_matches_unique_span() {
+    let previous = [symbol("old", "parse", SymbolKind::Function, 5, 9)];
+    let current = [symbol("new", "parse_input", SymbolKind::Function, 20, 24)];
+
+    assert_eq!(
+        links(&current, &previous),
+        [
+            ("new".to_string(), "old".to_string(), EdgeKind::EvolvedFrom),
+            ("old".to_string(), "new".to_string(), EdgeKind::RenamedTo),
+        ]
+    );
+}
+
+#[test]
+fn test_ambiguous_or_different_kind_is_not_a_rename() {
+    let previous = [
+        symbol("f", "first", SymbolKind::Function, 1, 3),
+        symbol("g", "second", SymbolKind::Function, 5, 7),
+        symbol("s", "Config", SymbolKind::Struct, 9, 12),
+    ];
+    let current = [
+        symbol("h", "third", SymbolKind::Function, 20, 22),
+        symbol("e", "Settings", SymbolKind::Enum, 9, 12),
+    ];
+
+    assert!(links(&current, &previous).is_empty());
+}
+
+#[test]
+fn test_surviving_identity_is_not_a_rename_source() {
+    let previous = [symbol("old", "parse", SymbolKind::Function, 5, 9)];
+    let current = [
+        symbol("kept", "parse", SymbolKind::Function, 30, 34),
+        symbol("new", "parse_input", SymbolKind::Function, 5, 9),
+    ];
+
+    assert_eq!(
+        links(&current, &previous),
+        [("kept".to_string(), "old".to_string(), EdgeKind::SameAs)]
+    );
+}
+
+#[test]
+fn test_rename_matches_signature_with_new_name() {
+    let mut old = symbol("old", "parse", SymbolKind::Function, 5, 9);
+    old.signature = Some("fn parse(input: &str) -> Ast".to_string());
+    let mut other = symbol("other", "render", SymbolKind::Function, 11, 15);
+    other.signature = Some("fn render(ast: &Ast) -> String".to_string());
+    let mut new = symbol("new", "parse_input", SymbolKind::Function, 20, 30);
+    new.signature = Some("fn parse_input(input: &str) -> Ast".to_string());
+
+    assert_eq!(
+        links(&[new], &[old, other]),
+        [
+            ("new".to_string(), "old".to_string(), EdgeKind::EvolvedFrom),
+            ("old".to_string(), "new".to_string(), EdgeKind::RenamedTo),
+        ]
+    );
+}
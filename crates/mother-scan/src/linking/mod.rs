@@ -0,0 +1,83 @@
+//! Linking: Cross-language links between the commit's symbols
+//!
+//! Opt-in through the `[linking]` config section. Runs after the module
+//! pass: [`mother_core::graph::linking`] matches the commit's symbols of
+//! different languages by the configured conventions, reading pattern
+//! matches from the scanned files, and each pair not linked yet gets a
+//! LINKS_TO edge. Symbols are shared between commits, so pairs linked by an
+//! earlier scan are not linked again.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::Result;
+use mother_core::graph::linking::{link_symbols, LinkConventions};
+use mother_core::graph::model::EdgeKind;
+use mother_core::graph::GraphStore;
+use mother_core::scanner::FileContentCache;
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the linking pass
+#[derive(Debug, Default)]
+pub struct LinkingResult {
+    pub symbol_count: usize,
+    /// LINKS_TO edges created
+    pub link_count: usize,
+}
+
+impl LinkingResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: self.symbol_count,
+            attempted: self.symbol_count,
+            errors: 0,
+        }
+    }
+}
+
+/// Link the symbols of a commit across languages
+pub async fn run(
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    conventions: &LinkConventions,
+    cache: &FileContentCache,
+) -> Result<LinkingResult> {
+    let symbols = client.symbols_at_commit(commit_sha).await?;
+    let links = link_symbols(&symbols, conventions, |path| {
+        cache.read_to_string(Path::new(path)).ok()
+    });
+
+    let kind = EdgeKind::LinksTo.to_string();
+    let graph = client.export_commit(commit_sha).await?;
+    let linked: HashSet<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == kind)
+        .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+        .collect();
+
+    let mut link_count = 0;
+    for link in &links {
+        if !linked.contains(&(link.source_id.as_str(), link.target_id.as_str())) {
+            client.create_edge(link).await?;
+            link_count += 1;
+        }
+    }
+    let result = LinkingResult {
+        symbol_count: symbols.len(),
+        link_count,
+    };
+    info!(
+        "Linked {} symbol pairs across languages ({} already linked)",
+        result.link_count,
+        links.len() - result.link_count
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for linking module
+
+mod tests_linking;
@@ -0,0 +1,136 @@
+//! Tests for linking the symbols of a commit across languages
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+
+use mother_core::graph::linking::{LinkConventions, LinkPattern};
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::scanner::FileContentCache;
+use tempfile::TempDir;
+
+use super::super::run;
+
+fn symbol(id: &str, qualified_name: &str, file_path: &str, lines: (u32, u32)) -> SymbolNode {
+    SymbolNode {
+        qualified_name: qualified_name.to_string(),
+        ..SymbolNode::new(id, id, SymbolKind::Function, file_path, lines.0, lines.1)
+    }
+}
+
+/// A store with one commit holding `files`, each with its symbols
+async fn store_with_files(files: &[(&str, &str, Vec<SymbolNode>)]) -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    for (path, language, symbols) in files {
+        let hash = format!("hash-{path}");
+        store
+            .create_file_if_new(path, &hash, language, "abc123")
+            .await
+            .unwrap();
+        store.create_symbols_batch(symbols, &hash).await.unwrap();
+    }
+    store
+}
+
+async fn links(store: &SqliteStore) -> Vec<(String, String)> {
+    let graph = store.export_commit("abc123").await.unwrap();
+    graph
+        .edges
+        .into_iter()
+        .filter(|edge| edge.kind == "LINKS_TO")
+        .map(|edge| (edge.source, edge.target))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_run_links_qualified_names_once() {
+    let store = store_with_files(&[
+        (
+            "/repo/svc/orders.py",
+            "python",
+            vec![symbol(
+                "py",
+                "orders.v1.Create",
+                "/repo/svc/orders.py",
+                (1, 3),
+            )],
+        ),
+        (
+            "/repo/web/orders.ts",
+            "typescript",
+            vec![symbol(
+                "ts",
+                "orders.v1.Create",
+                "/repo/web/orders.ts",
+                (1, 3),
+            )],
+        ),
+    ])
+    .await;
+    let conventions = LinkConventions {
+        qualified_names: true,
+        ..LinkConventions::default()
+    };
+
+    let result = run(&store, "abc123", &conventions, &FileContentCache::default())
+        .await
+        .unwrap();
+    assert_eq!((result.symbol_count, result.link_count), (2, 1));
+    assert_eq!(
+        links(&store).await,
+        vec![("py".to_string(), "ts".to_string())]
+    );
+
+    let rerun = run(&store, "abc123", &conventions, &FileContentCache::default())
+        .await
+        .unwrap();
+    assert_eq!(rerun.link_count, 0);
+    assert_eq!(links(&store).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_run_reads_pattern_matches_from_scanned_files() {
+    let repo = TempDir::new().unwrap();
+    let python = repo.path().join("app.py");
+    let typescript = repo.path().join("client.ts");
+    fs::write(
+        &python,
+        "@app.route(\"/orders\")\ndef orders():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(&typescript, "function load() {\n  fetch(\"/orders\");\n}\n").unwrap();
+    let python = python.display().to_string();
+    let typescript = typescript.display().to_string();
+    let store = store_with_files(&[
+        (
+            &python,
+            "python",
+            vec![symbol("orders", "orders", &python, (2, 3))],
+        ),
+        (
+            &typescript,
+            "typescript",
+            vec![symbol("load", "load", &typescript, (1, 3))],
+        ),
+    ])
+    .await;
+    let conventions = LinkConventions {
+        qualified_names: true,
+        patterns: vec![LinkPattern::new("route", r#"(?:route|fetch)\("([^"]+)"\)"#).unwrap()],
+    };
+
+    let result = run(&store, "abc123", &conventions, &FileContentCache::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.link_count, 1);
+    assert_eq!(
+        links(&store).await,
+        vec![("load".to_string(), "orders".to_string())]
+    );
+}
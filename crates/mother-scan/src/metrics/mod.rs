@@ -0,0 +1,704 @@
+//! Throughput metrics: Per-phase rates logged at the end of each phase
+//!
+//! A [`PhaseTimer`] snapshots LSP request totals and store write counts when
+//! a phase starts and turns the difference into [`PhaseMetrics`] when it
+//! ends. Store writes are counted by wrapping the store in a
+//! [`CountingStore`]; LSP requests come from the manager's shared counters.
+//!
+//! The `prometheus` submodule keeps totals across scans for long-running
+//! commands and exports them for scraping.
+
+mod prometheus;
+
+use std::fs::File;
+use std::future::Future;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mother_core::graph::backup::GraphBackup;
+use mother_core::graph::complexity::SymbolComplexity;
+use mother_core::graph::export::{ExportRecord, GraphExport};
+use mother_core::graph::integrity::{IntegrityIssue, IntegrityRepair};
+use mother_core::graph::model::{
+    ContractNode, DiagnosticNode, DirectorySummary, Edge, ExternalSymbolNode, FileMetrics,
+    FileWrite, FunctionSignature, ImportEdge, ModuleNode, RepositoryDependency, RepositoryNode,
+    ScanReport, ScanRun, ScanStatus, SymbolCoverage, SymbolNode,
+};
+use mother_core::graph::{
+    DeleteSummary, DiagnosticResult, FileMetricsResult, FileResult, GraphStats, GraphStore,
+    ImporterResult, Page, PathScope, QueryLanguage, QueryParams, RawRow, ReferenceDirection,
+    ReferenceGroup, ReferenceGrouping, ReferenceResult, RowStream, ScanResult, ScanRunResult,
+    StoreError, SymbolMatch, SymbolResult, SymbolVersionResult, TypeUsage, TypeUsageResult,
+};
+use mother_core::lsp::{LspServerManager, RequestStats};
+use serde::Serialize;
+use tracing::{info, Span};
+
+pub use prometheus::{MetricsExporter, MetricsRegistry};
+
+// ============================================================================
+// Phase metrics
+// ============================================================================
+
+/// Work done by a phase, as reported by its result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseCounts {
+    /// Files handled by the phase
+    pub files: usize,
+    /// Symbols extracted or processed by the phase
+    pub symbols: usize,
+    /// Items the phase attempted (files or symbols), the base of the error rate
+    pub attempted: usize,
+    pub errors: usize,
+}
+
+/// Throughput of one scan phase
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseMetrics {
+    pub phase: String,
+    pub elapsed_secs: f64,
+    pub files: usize,
+    pub symbols: usize,
+    pub lsp_requests: u64,
+    pub store_writes: u64,
+    pub errors: usize,
+    pub files_per_sec: f64,
+    pub symbols_per_sec: f64,
+    pub lsp_requests_per_sec: f64,
+    pub store_writes_per_sec: f64,
+    pub avg_request_latency_ms: f64,
+    /// Share of attempted items that failed, from 0.0 to 1.0
+    pub error_rate: f64,
+}
+
+impl PhaseMetrics {
+    /// Compute rates from a phase's totals
+    #[must_use]
+    pub fn new(
+        phase: &str,
+        elapsed: Duration,
+        counts: PhaseCounts,
+        lsp: RequestStats,
+        store_writes: u64,
+    ) -> Self {
+        let secs = elapsed.as_secs_f64();
+        let rate = |count: f64| if secs > 0.0 { count / secs } else { 0.0 };
+        let error_rate = if counts.attempted > 0 {
+            counts.errors as f64 / counts.attempted as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            phase: phase.to_string(),
+            elapsed_secs: secs,
+            files: counts.files,
+            symbols: counts.symbols,
+            lsp_requests: lsp.requests,
+            store_writes,
+            errors: counts.errors,
+            files_per_sec: rate(counts.files as f64),
+            symbols_per_sec: rate(counts.symbols as f64),
+            lsp_requests_per_sec: rate(lsp.requests as f64),
+            store_writes_per_sec: rate(store_writes as f64),
+            avg_request_latency_ms: lsp.average_latency().as_secs_f64() * 1000.0,
+            error_rate,
+        }
+    }
+
+    /// Log the rates as structured fields
+    pub fn log(&self) {
+        info!(
+            phase = %self.phase,
+            elapsed_secs = self.elapsed_secs,
+            files_per_sec = self.files_per_sec,
+            symbols_per_sec = self.symbols_per_sec,
+            lsp_requests_per_sec = self.lsp_requests_per_sec,
+            store_writes_per_sec = self.store_writes_per_sec,
+            avg_request_latency_ms = self.avg_request_latency_ms,
+            error_rate = self.error_rate,
+            "{} throughput: {:.1} files/s, {:.1} symbols/s, {:.1} LSP requests/s, \
+             {:.1} writes/s, {:.1} ms avg latency, {:.1}% errors ({:.2}s)",
+            self.phase,
+            self.files_per_sec,
+            self.symbols_per_sec,
+            self.lsp_requests_per_sec,
+            self.store_writes_per_sec,
+            self.avg_request_latency_ms,
+            self.error_rate * 100.0,
+            self.elapsed_secs
+        );
+    }
+}
+
+/// Measures one phase between [`PhaseTimer::start`] and [`PhaseTimer::finish`]
+pub struct PhaseTimer {
+    phase: &'static str,
+    started: Instant,
+    lsp: RequestStats,
+    writes: u64,
+    span: Span,
+}
+
+impl PhaseTimer {
+    /// Start timing a phase
+    #[must_use]
+    pub fn start(phase: &'static str, lsp: &LspServerManager, store: &CountingStore<'_>) -> Self {
+        Self {
+            phase,
+            started: Instant::now(),
+            lsp: lsp.request_stats(),
+            writes: store.writes(),
+            span: tracing::info_span!("phase", phase),
+        }
+    }
+
+    /// Span of the phase, to run its work in so traces group it by phase
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span.clone()
+    }
+
+    /// Stop timing, log the phase's throughput and return it
+    pub fn finish(
+        self,
+        lsp: &LspServerManager,
+        store: &CountingStore<'_>,
+        counts: PhaseCounts,
+    ) -> PhaseMetrics {
+        let metrics = PhaseMetrics::new(
+            self.phase,
+            self.started.elapsed(),
+            counts,
+            lsp.request_stats().since(&self.lsp),
+            store.writes().saturating_sub(self.writes),
+        );
+        metrics.log();
+        metrics
+    }
+}
+
+// ============================================================================
+// Report artifact
+// ============================================================================
+
+/// Throughput of a whole scan, written with `scan --report`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThroughputReport {
+    pub commit_sha: String,
+    pub version: Option<String>,
+    pub elapsed_secs: f64,
+    pub phases: Vec<PhaseMetrics>,
+}
+
+impl ThroughputReport {
+    /// Write the report as pretty-printed JSON
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created or written.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Wrote scan report to {}", path.display());
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Write counting
+// ============================================================================
+
+/// Graph store wrapper counting write calls and, with a registry, timing
+/// them
+pub struct CountingStore<'a> {
+    inner: &'a dyn GraphStore,
+    writes: AtomicU64,
+    registry: Option<&'a MetricsRegistry>,
+}
+
+impl<'a> CountingStore<'a> {
+    #[must_use]
+    pub fn new(inner: &'a dyn GraphStore) -> Self {
+        Self {
+            inner,
+            writes: AtomicU64::new(0),
+            registry: None,
+        }
+    }
+
+    /// Write calls made so far
+    #[must_use]
+    pub fn writes(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    /// Also time every write into `registry`, when given
+    #[must_use]
+    pub fn with_registry(mut self, registry: Option<&'a MetricsRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    async fn write<T>(&self, write: impl Future<Output = T>) -> T {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let result = write.await;
+        if let Some(registry) = self.registry {
+            registry.observe_write(started.elapsed());
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl GraphStore for CountingStore<'_> {
+    async fn create_scan_run(&self, scan_run: &ScanRun) -> Result<bool, StoreError> {
+        self.write(self.inner.create_scan_run(scan_run)).await
+    }
+
+    async fn set_scan_report(&self, report: &ScanReport) -> Result<(), StoreError> {
+        self.write(self.inner.set_scan_report(report)).await
+    }
+
+    async fn set_scan_status(
+        &self,
+        scan_run_id: &str,
+        status: ScanStatus,
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.set_scan_status(scan_run_id, status))
+            .await
+    }
+
+    async fn tag_scan_run(&self, scan_run_id: &str, tag: &str) -> Result<(), StoreError> {
+        self.write(self.inner.tag_scan_run(scan_run_id, tag)).await
+    }
+
+    async fn annotate_scan_run(&self, scan_run_id: &str, note: &str) -> Result<(), StoreError> {
+        self.write(self.inner.annotate_scan_run(scan_run_id, note))
+            .await
+    }
+
+    async fn create_file_if_new(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+        language: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>, StoreError> {
+        self.write(
+            self.inner
+                .create_file_if_new(file_path, content_hash, language, commit_sha),
+        )
+        .await
+    }
+
+    async fn link_stored_file(
+        &self,
+        content_hash: &str,
+        commit_sha: &str,
+    ) -> Result<bool, StoreError> {
+        self.write(self.inner.link_stored_file(content_hash, commit_sha))
+            .await
+    }
+
+    async fn write_file(&self, file: &FileWrite) -> Result<bool, StoreError> {
+        self.write(self.inner.write_file(file)).await
+    }
+
+    async fn unlink_file(&self, commit_sha: &str, file_path: &str) -> Result<bool, StoreError> {
+        self.write(self.inner.unlink_file(commit_sha, file_path))
+            .await
+    }
+
+    async fn set_file_metrics(
+        &self,
+        content_hash: &str,
+        metrics: &FileMetrics,
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.set_file_metrics(content_hash, metrics))
+            .await
+    }
+
+    async fn set_file_imports(
+        &self,
+        content_hash: &str,
+        modules: &[String],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.set_file_imports(content_hash, modules))
+            .await
+    }
+
+    async fn create_import_edges(
+        &self,
+        commit_sha: &str,
+        edges: &[ImportEdge],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_import_edges(commit_sha, edges))
+            .await
+    }
+
+    async fn create_directory_summaries(
+        &self,
+        commit_sha: &str,
+        summaries: &[DirectorySummary],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_directory_summaries(commit_sha, summaries))
+            .await
+    }
+
+    async fn create_modules(
+        &self,
+        commit_sha: &str,
+        modules: &[ModuleNode],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_modules(commit_sha, modules))
+            .await
+    }
+
+    async fn create_contracts(
+        &self,
+        commit_sha: &str,
+        contracts: &[ContractNode],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_contracts(commit_sha, contracts))
+            .await
+    }
+
+    async fn create_symbols_batch(
+        &self,
+        symbols: &[SymbolNode],
+        content_hash: &str,
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_symbols_batch(symbols, content_hash))
+            .await
+    }
+
+    async fn create_edge(&self, edge: &Edge) -> Result<(), StoreError> {
+        self.write(self.inner.create_edge(edge)).await
+    }
+
+    async fn create_edges_batch(&self, edges: &[Edge]) -> Result<(), StoreError> {
+        self.write(self.inner.create_edges_batch(edges)).await
+    }
+
+    async fn create_modified_edges(
+        &self,
+        commit_sha: &str,
+        symbol_ids: &[String],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_modified_edges(commit_sha, symbol_ids))
+            .await
+    }
+
+    async fn mark_references_truncated(
+        &self,
+        symbol_id: &str,
+        total: usize,
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.mark_references_truncated(symbol_id, total))
+            .await
+    }
+
+    async fn set_symbol_complexity(
+        &self,
+        complexity: &[SymbolComplexity],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.set_symbol_complexity(complexity))
+            .await
+    }
+
+    async fn set_symbol_coverage(&self, coverage: &[SymbolCoverage]) -> Result<(), StoreError> {
+        self.write(self.inner.set_symbol_coverage(coverage)).await
+    }
+
+    async fn upsert_repository(&self, repository: &RepositoryNode) -> Result<(), StoreError> {
+        self.write(self.inner.upsert_repository(repository)).await
+    }
+
+    async fn set_repository_dependencies(
+        &self,
+        dependencies: &[RepositoryDependency],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.set_repository_dependencies(dependencies))
+            .await
+    }
+
+    async fn delete_scan_run(&self, id: &str) -> Result<DeleteSummary, StoreError> {
+        self.write(self.inner.delete_scan_run(id)).await
+    }
+
+    async fn repair_integrity(&self) -> Result<IntegrityRepair, StoreError> {
+        self.write(self.inner.repair_integrity()).await
+    }
+
+    async fn restore(&self, backup: &GraphBackup) -> Result<usize, StoreError> {
+        self.write(self.inner.restore(backup)).await
+    }
+
+    async fn create_diagnostics(
+        &self,
+        content_hash: &str,
+        diagnostics: &[DiagnosticNode],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_diagnostics(content_hash, diagnostics))
+            .await
+    }
+
+    async fn create_function_signatures(
+        &self,
+        signatures: &[FunctionSignature],
+    ) -> Result<(), StoreError> {
+        self.write(self.inner.create_function_signatures(signatures))
+            .await
+    }
+
+    async fn create_external_reference(
+        &self,
+        source_id: &str,
+        target: &ExternalSymbolNode,
+        line: u32,
+        column: u32,
+    ) -> Result<(), StoreError> {
+        self.write(
+            self.inner
+                .create_external_reference(source_id, target, line, column),
+        )
+        .await
+    }
+
+    async fn find_symbols(
+        &self,
+        pattern: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.find_symbols(pattern, scope, page).await
+    }
+
+    async fn search_symbols(&self, text: &str, page: Page) -> Result<Vec<SymbolMatch>, StoreError> {
+        self.inner.search_symbols(text, page).await
+    }
+
+    async fn similar_symbols(
+        &self,
+        symbol_id: &str,
+        commit_sha: &str,
+        limit: usize,
+    ) -> Result<Vec<SymbolMatch>, StoreError> {
+        self.inner
+            .similar_symbols(symbol_id, commit_sha, limit)
+            .await
+    }
+
+    async fn symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.symbols_in_file(file_path).await
+    }
+
+    async fn modified_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner.modified_symbols(commit_sha).await
+    }
+
+    async fn find_symbols_at_commit(
+        &self,
+        pattern: &str,
+        commit_sha: &str,
+        scope: Option<&PathScope>,
+        page: Page,
+    ) -> Result<Vec<SymbolResult>, StoreError> {
+        self.inner
+            .find_symbols_at_commit(pattern, commit_sha, scope, page)
+            .await
+    }
+
+    async fn symbols_at_commit(&self, commit_sha: &str) -> Result<Vec<SymbolNode>, StoreError> {
+        self.inner.symbols_at_commit(commit_sha).await
+    }
+
+    async fn symbol_history(
+        &self,
+        qualified_name: &str,
+    ) -> Result<Vec<SymbolVersionResult>, StoreError> {
+        self.inner.symbol_history(qualified_name).await
+    }
+
+    async fn previous_file_symbols(
+        &self,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<Vec<SymbolNode>, StoreError> {
+        self.inner
+            .previous_file_symbols(file_path, content_hash)
+            .await
+    }
+
+    async fn find_references_to(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.inner.find_references_to(symbol_name, page).await
+    }
+
+    async fn find_references_from(
+        &self,
+        symbol_name: &str,
+        page: Page,
+    ) -> Result<Vec<ReferenceResult>, StoreError> {
+        self.inner.find_references_from(symbol_name, page).await
+    }
+
+    async fn find_reference_groups(
+        &self,
+        symbol_name: &str,
+        direction: ReferenceDirection,
+        grouping: ReferenceGrouping,
+        page: Page,
+    ) -> Result<Vec<ReferenceGroup>, StoreError> {
+        self.inner
+            .find_reference_groups(symbol_name, direction, grouping, page)
+            .await
+    }
+
+    async fn directory_summary(
+        &self,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<DirectorySummary>, StoreError> {
+        self.inner.directory_summary(path, commit_sha).await
+    }
+
+    async fn directories_at_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Vec<DirectorySummary>, StoreError> {
+        self.inner.directories_at_commit(commit_sha).await
+    }
+
+    async fn modules_at_commit(&self, commit_sha: &str) -> Result<Vec<ModuleNode>, StoreError> {
+        self.inner.modules_at_commit(commit_sha).await
+    }
+
+    async fn contracts_at_commit(&self, commit_sha: &str) -> Result<Vec<ContractNode>, StoreError> {
+        self.inner.contracts_at_commit(commit_sha).await
+    }
+
+    async fn repositories(&self) -> Result<Vec<RepositoryNode>, StoreError> {
+        self.inner.repositories().await
+    }
+
+    async fn repository_dependencies(&self) -> Result<Vec<RepositoryDependency>, StoreError> {
+        self.inner.repository_dependencies().await
+    }
+
+    async fn find_diagnostics(
+        &self,
+        commit_sha: &str,
+        severity: Option<&str>,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<DiagnosticResult>, StoreError> {
+        self.inner
+            .find_diagnostics(commit_sha, severity, scope)
+            .await
+    }
+
+    async fn find_functions_by_type(
+        &self,
+        commit_sha: &str,
+        type_name: &str,
+        usage: TypeUsage,
+        scope: Option<&PathScope>,
+    ) -> Result<Vec<TypeUsageResult>, StoreError> {
+        self.inner
+            .find_functions_by_type(commit_sha, type_name, usage, scope)
+            .await
+    }
+
+    async fn list_files(
+        &self,
+        pattern: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<FileResult>, StoreError> {
+        self.inner.list_files(pattern, page).await
+    }
+
+    async fn file_metrics(
+        &self,
+        commit_sha: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<FileMetricsResult>, StoreError> {
+        self.inner.file_metrics(commit_sha, pattern).await
+    }
+
+    async fn find_importers(&self, module: &str) -> Result<Vec<ImporterResult>, StoreError> {
+        self.inner.find_importers(module).await
+    }
+
+    async fn stats(&self) -> Result<GraphStats, StoreError> {
+        self.inner.stats().await
+    }
+
+    async fn resolve_commit(&self, version: Option<&str>) -> Result<Option<String>, StoreError> {
+        self.inner.resolve_commit(version).await
+    }
+
+    async fn scan_runs(&self) -> Result<Vec<ScanRunResult>, StoreError> {
+        self.inner.scan_runs().await
+    }
+
+    async fn scan_report(&self, scan_run_id: &str) -> Result<Option<ScanReport>, StoreError> {
+        self.inner.scan_report(scan_run_id).await
+    }
+
+    async fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, StoreError> {
+        self.inner.check_integrity().await
+    }
+
+    async fn schema_version(&self) -> Result<u32, StoreError> {
+        self.inner.schema_version().await
+    }
+
+    async fn recent_scans(
+        &self,
+        branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ScanResult>, StoreError> {
+        self.inner.recent_scans(branch, limit).await
+    }
+
+    async fn export_commit(&self, commit_sha: &str) -> Result<GraphExport, StoreError> {
+        self.inner.export_commit(commit_sha).await
+    }
+
+    fn stream_export<'a>(&'a self, commit_sha: &'a str) -> RowStream<'a, ExportRecord> {
+        self.inner.stream_export(commit_sha)
+    }
+
+    async fn backup(&self) -> Result<GraphBackup, StoreError> {
+        self.inner.backup().await
+    }
+
+    async fn execute_raw(&self, query: &str, params: &QueryParams) -> Result<usize, StoreError> {
+        self.inner.execute_raw(query, params).await
+    }
+
+    async fn fetch_raw(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> Result<Vec<RawRow>, StoreError> {
+        self.inner.fetch_raw(query, params).await
+    }
+
+    fn query_language(&self) -> QueryLanguage {
+        self.inner.query_language()
+    }
+}
+
+#[cfg(test)]
+mod tests;
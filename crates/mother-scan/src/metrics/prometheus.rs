@@ -0,0 +1,308 @@
+//! Prometheus metrics: Counters of long-running scans, for scraping
+//!
+//! A [`MetricsRegistry`] counts files scanned and symbols extracted from the
+//! phases' progress events, shares the LSP managers' request counters and
+//! times every store write through the [`CountingStore`](super::CountingStore).
+//! A [`MetricsExporter`] serves it at `/metrics` and/or rewrites a file for
+//! node_exporter's textfile collector while the command runs.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use mother_core::lsp::RequestMetrics;
+use mother_core::progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
+use tokio::task::JoinHandle;
+
+/// Upper bounds, in seconds, of the store write latency buckets
+const WRITE_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// How often the textfile is rewritten while the command runs
+const TEXTFILE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Counters and histograms of the scans of one process
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    files_scanned: AtomicU64,
+    symbols_extracted: AtomicU64,
+    references_resolved: AtomicU64,
+    lsp: Arc<RequestMetrics>,
+    store_writes: Histogram,
+}
+
+impl MetricsRegistry {
+    /// LSP request counters to share with every language server manager
+    #[must_use]
+    pub fn lsp_metrics(&self) -> Arc<RequestMetrics> {
+        Arc::clone(&self.lsp)
+    }
+
+    /// Record the latency of one store write
+    pub fn observe_write(&self, elapsed: Duration) {
+        self.store_writes.observe(elapsed);
+    }
+
+    /// A progress handle that counts the phases' work, then passes each
+    /// event on to `inner`
+    #[must_use]
+    pub fn progress(self: &Arc<Self>, inner: Progress) -> Progress {
+        Progress::new(CountingProgress {
+            registry: Arc::clone(self),
+            inner,
+        })
+    }
+
+    fn count(&self, event: ProgressEvent) {
+        let ProgressEvent::Advanced {
+            phase,
+            symbols,
+            references,
+        } = event
+        else {
+            return;
+        };
+        match phase {
+            ScanPhase::Files => {
+                self.files_scanned.fetch_add(1, Ordering::Relaxed);
+            }
+            ScanPhase::Symbols => {
+                self.symbols_extracted.fetch_add(symbols, Ordering::Relaxed);
+            }
+            ScanPhase::References => {
+                self.references_resolved
+                    .fetch_add(references, Ordering::Relaxed);
+            }
+            ScanPhase::Imports => {}
+        }
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let lsp = self.lsp.snapshot();
+        let mut out = String::new();
+        counter(
+            &mut out,
+            "mother_files_scanned_total",
+            "Files opened and stored by Phase 1",
+            self.files_scanned.load(Ordering::Relaxed),
+        );
+        counter(
+            &mut out,
+            "mother_symbols_extracted_total",
+            "Symbols extracted by Phase 2",
+            self.symbols_extracted.load(Ordering::Relaxed),
+        );
+        counter(
+            &mut out,
+            "mother_references_resolved_total",
+            "References resolved by Phase 3",
+            self.references_resolved.load(Ordering::Relaxed),
+        );
+        counter(
+            &mut out,
+            "mother_lsp_requests_total",
+            "Requests sent to language servers",
+            lsp.requests,
+        );
+        counter(
+            &mut out,
+            "mother_lsp_errors_total",
+            "Language server requests that failed",
+            lsp.failures,
+        );
+        self.store_writes.render(
+            &mut out,
+            "mother_store_write_duration_seconds",
+            "Latency of graph store writes (Neo4j or SQLite)",
+        );
+        out
+    }
+
+    /// Replace `path` with the current metrics
+    ///
+    /// Written to a temporary file that is renamed over `path`, so the
+    /// textfile collector never reads a partial file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn write_textfile(&self, path: &Path) -> Result<()> {
+        let temp = path.with_extension("prom.tmp");
+        std::fs::write(&temp, self.render())
+            .with_context(|| format!("Failed to write metrics to {}", temp.display()))?;
+        std::fs::rename(&temp, path)
+            .with_context(|| format!("Failed to write metrics to {}", path.display()))
+    }
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} counter");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// Cumulative latency histogram over [`WRITE_BUCKETS`]
+#[derive(Debug)]
+struct Histogram {
+    /// Observations at or below each bucket's bound, not yet cumulative
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: WRITE_BUCKETS.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(bucket) = WRITE_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .and_then(|index| self.buckets.get(index))
+        {
+            bucket.fetch_add(1, Ordering::Relaxed);
+        }
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        let mut cumulative = 0;
+        for (bound, bucket) in WRITE_BUCKETS.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let sum = Duration::from_micros(self.sum_micros.load(Ordering::Relaxed));
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}");
+        let _ = writeln!(out, "{name}_sum {}", sum.as_secs_f64());
+        let _ = writeln!(out, "{name}_count {count}");
+    }
+}
+
+/// Counts progress events into a registry before passing them on
+struct CountingProgress {
+    registry: Arc<MetricsRegistry>,
+    inner: Progress,
+}
+
+impl ProgressReporter for CountingProgress {
+    fn report(&self, event: ProgressEvent) {
+        self.registry.count(event);
+        self.inner.report(event);
+    }
+}
+
+/// Publishes a registry while a command runs
+///
+/// Dropping the exporter stops serving and refreshing; call
+/// [`MetricsExporter::finish`] to write the textfile a last time first.
+pub struct MetricsExporter {
+    registry: Arc<MetricsRegistry>,
+    textfile: Option<PathBuf>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MetricsExporter {
+    /// Serve `registry` at `http://<addr>/metrics` and/or rewrite `textfile`
+    /// every few seconds
+    ///
+    /// # Errors
+    /// Returns an error if the address cannot be bound or the textfile
+    /// cannot be written.
+    pub async fn start(
+        registry: Arc<MetricsRegistry>,
+        addr: Option<SocketAddr>,
+        textfile: Option<PathBuf>,
+    ) -> Result<Self> {
+        let mut tasks = Vec::new();
+        if let Some(addr) = addr {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Failed to bind metrics endpoint to {addr}"))?;
+            tracing::info!(
+                "Serving metrics at http://{}/metrics",
+                listener.local_addr()?
+            );
+            let app = router(Arc::clone(&registry));
+            tasks.push(tokio::spawn(async move {
+                if let Err(e) = axum::serve(listener, app).await {
+                    tracing::warn!("Metrics endpoint stopped: {e}");
+                }
+            }));
+        }
+        if let Some(path) = &textfile {
+            registry.write_textfile(path)?;
+            let (registry, path) = (Arc::clone(&registry), path.clone());
+            tasks.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(TEXTFILE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = registry.write_textfile(&path) {
+                        tracing::warn!("{e:#}");
+                    }
+                }
+            }));
+        }
+        Ok(Self {
+            registry,
+            textfile,
+            tasks,
+        })
+    }
+
+    /// Stop publishing, writing the textfile with the final counts
+    ///
+    /// # Errors
+    /// Returns an error if the textfile cannot be written.
+    pub fn finish(self) -> Result<()> {
+        match &self.textfile {
+            Some(path) => self.registry.write_textfile(path),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Routes of the metrics endpoint
+pub(crate) fn router(registry: Arc<MetricsRegistry>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(registry)
+}
+
+async fn metrics(State(registry): State<Arc<MetricsRegistry>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], registry.render())
+}
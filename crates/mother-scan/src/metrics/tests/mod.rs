@@ -0,0 +1,4 @@
+//! Tests for metrics module
+
+mod tests_metrics;
+mod tests_prometheus;
@@ -0,0 +1,150 @@
+//! Tests for per-phase throughput metrics
+
+#![allow(clippy::unwrap_used)]
+
+use std::time::Duration;
+
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, Page, SqliteStore};
+use mother_core::lsp::{LspServerManager, RequestStats};
+
+use super::super::{CountingStore, PhaseCounts, PhaseMetrics, PhaseTimer, ThroughputReport};
+
+fn counts(files: usize, symbols: usize, attempted: usize, errors: usize) -> PhaseCounts {
+    PhaseCounts {
+        files,
+        symbols,
+        attempted,
+        errors,
+    }
+}
+
+// ============================================================================
+// PhaseMetrics
+// ============================================================================
+
+#[test]
+fn test_rates_divide_by_elapsed_time() {
+    let lsp = RequestStats {
+        requests: 40,
+        failures: 0,
+        total_latency: Duration::from_millis(200),
+    };
+    let metrics = PhaseMetrics::new(
+        "Phase 2",
+        Duration::from_secs(2),
+        counts(10, 100, 10, 0),
+        lsp,
+        20,
+    );
+
+    assert_eq!(metrics.files_per_sec, 5.0);
+    assert_eq!(metrics.symbols_per_sec, 50.0);
+    assert_eq!(metrics.lsp_requests_per_sec, 20.0);
+    assert_eq!(metrics.store_writes_per_sec, 10.0);
+    assert_eq!(metrics.avg_request_latency_ms, 5.0);
+    assert_eq!(metrics.error_rate, 0.0);
+}
+
+#[test]
+fn test_error_rate_is_share_of_attempted_items() {
+    let metrics = PhaseMetrics::new(
+        "Phase 1",
+        Duration::from_secs(1),
+        counts(3, 0, 4, 1),
+        RequestStats::default(),
+        3,
+    );
+    assert_eq!(metrics.error_rate, 0.25);
+}
+
+#[test]
+fn test_empty_phase_has_zero_rates() {
+    let metrics = PhaseMetrics::new(
+        "Phase 3",
+        Duration::ZERO,
+        PhaseCounts::default(),
+        RequestStats::default(),
+        0,
+    );
+
+    assert_eq!(metrics.files_per_sec, 0.0);
+    assert_eq!(metrics.avg_request_latency_ms, 0.0);
+    assert_eq!(metrics.error_rate, 0.0);
+}
+
+// ============================================================================
+// CountingStore and PhaseTimer
+// ============================================================================
+
+#[tokio::test]
+async fn test_counting_store_counts_writes_only() {
+    let inner = SqliteStore::open_in_memory().unwrap();
+    let store = CountingStore::new(&inner);
+
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("a.rs", "hash", "rust", "abc")
+        .await
+        .unwrap();
+    store.stats().await.unwrap();
+    store.list_files(None, Page::default()).await.unwrap();
+
+    assert_eq!(store.writes(), 2);
+    assert_eq!(inner.stats().await.unwrap().files, 1);
+}
+
+#[tokio::test]
+async fn test_phase_timer_measures_writes_since_start() {
+    let inner = SqliteStore::open_in_memory().unwrap();
+    let store = CountingStore::new(&inner);
+    let manager = LspServerManager::new("/tmp");
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc"))
+        .await
+        .unwrap();
+
+    let timer = PhaseTimer::start("Phase 1", &manager, &store);
+    store
+        .create_file_if_new("a.rs", "hash", "rust", "abc")
+        .await
+        .unwrap();
+    let metrics = timer.finish(&manager, &store, counts(1, 0, 1, 0));
+
+    assert_eq!(metrics.phase, "Phase 1");
+    assert_eq!(metrics.store_writes, 1);
+    assert_eq!(metrics.lsp_requests, 0);
+}
+
+// ============================================================================
+// ThroughputReport
+// ============================================================================
+
+#[test]
+fn test_report_written_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.json");
+    let report = ThroughputReport {
+        commit_sha: "abc".to_string(),
+        version: Some("v1".to_string()),
+        elapsed_secs: 1.5,
+        phases: vec![PhaseMetrics::new(
+            "Phase 1",
+            Duration::from_secs(1),
+            counts(2, 0, 2, 0),
+            RequestStats::default(),
+            2,
+        )],
+    };
+
+    report.write(&path).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["commit_sha"], "abc");
+    assert_eq!(json["phases"][0]["phase"], "Phase 1");
+    assert_eq!(json["phases"][0]["files_per_sec"], 2.0);
+}
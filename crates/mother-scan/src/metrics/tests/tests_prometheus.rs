@@ -0,0 +1,156 @@
+//! Tests for the Prometheus registry and exporter
+
+#![allow(clippy::unwrap_used)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::super::prometheus::router;
+use super::super::{CountingStore, MetricsExporter, MetricsRegistry};
+
+/// Value of the sample `name` in rendered metrics
+fn sample(rendered: &str, name: &str) -> f64 {
+    rendered
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+struct CountEvents(Arc<AtomicUsize>);
+
+impl ProgressReporter for CountEvents {
+    fn report(&self, _event: ProgressEvent) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_progress_counts_files_and_symbols_and_forwards_events() {
+    let registry = Arc::new(MetricsRegistry::default());
+    let forwarded = Arc::new(AtomicUsize::new(0));
+    let progress = registry.progress(Progress::new(CountEvents(Arc::clone(&forwarded))));
+
+    progress.start(ScanPhase::Files, 2);
+    progress.advance(ScanPhase::Files, 0, 0);
+    progress.advance(ScanPhase::Files, 0, 0);
+    progress.finish(ScanPhase::Files);
+    progress.advance(ScanPhase::Symbols, 7, 0);
+    progress.advance(ScanPhase::Symbols, 3, 0);
+    progress.advance(ScanPhase::References, 0, 4);
+
+    let rendered = registry.render();
+    assert_eq!(sample(&rendered, "mother_files_scanned_total"), 2.0);
+    assert_eq!(sample(&rendered, "mother_symbols_extracted_total"), 10.0);
+    assert_eq!(sample(&rendered, "mother_references_resolved_total"), 4.0);
+    assert_eq!(forwarded.load(Ordering::Relaxed), 7);
+}
+
+#[test]
+fn test_lsp_requests_and_errors_come_from_shared_counters() {
+    let registry = MetricsRegistry::default();
+    let lsp = registry.lsp_metrics();
+    lsp.record(Instant::now(), true);
+    lsp.record(Instant::now(), false);
+
+    let rendered = registry.render();
+    assert_eq!(sample(&rendered, "mother_lsp_requests_total"), 2.0);
+    assert_eq!(sample(&rendered, "mother_lsp_errors_total"), 1.0);
+}
+
+#[test]
+fn test_write_histogram_buckets_are_cumulative() {
+    let registry = MetricsRegistry::default();
+    registry.observe_write(Duration::from_micros(500));
+    registry.observe_write(Duration::from_millis(20));
+    registry.observe_write(Duration::from_secs(30));
+
+    let rendered = registry.render();
+    assert!(rendered.contains("# TYPE mother_store_write_duration_seconds histogram"));
+    let bucket = |le: &str| {
+        sample(
+            &rendered,
+            &format!("mother_store_write_duration_seconds_bucket{{le=\"{le}\"}}"),
+        )
+    };
+    assert_eq!(bucket("0.001"), 1.0);
+    assert_eq!(bucket("0.01"), 1.0);
+    assert_eq!(bucket("0.025"), 2.0);
+    assert_eq!(bucket("10"), 2.0);
+    assert_eq!(bucket("+Inf"), 3.0);
+    assert_eq!(
+        sample(&rendered, "mother_store_write_duration_seconds_count"),
+        3.0
+    );
+    assert!((sample(&rendered, "mother_store_write_duration_seconds_sum") - 30.0205).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_counting_store_times_writes_into_registry() {
+    let registry = MetricsRegistry::default();
+    let inner = SqliteStore::open_in_memory().unwrap();
+    let store = CountingStore::new(&inner).with_registry(Some(&registry));
+
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc"))
+        .await
+        .unwrap();
+    store.stats().await.unwrap();
+
+    let rendered = registry.render();
+    assert_eq!(store.writes(), 1);
+    assert_eq!(
+        sample(&rendered, "mother_store_write_duration_seconds_count"),
+        1.0
+    );
+}
+
+#[tokio::test]
+async fn test_textfile_written_at_start_and_finish() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("mother.prom");
+    let registry = Arc::new(MetricsRegistry::default());
+
+    let exporter = MetricsExporter::start(Arc::clone(&registry), None, Some(path.clone()))
+        .await
+        .unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(sample(&written, "mother_files_scanned_total"), 0.0);
+
+    registry
+        .progress(Progress::default())
+        .advance(ScanPhase::Files, 0, 0);
+    exporter.finish().unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(sample(&written, "mother_files_scanned_total"), 1.0);
+    assert!(!path.with_extension("prom.tmp").exists());
+}
+
+#[tokio::test]
+async fn test_endpoint_serves_text_format() {
+    let registry = Arc::new(MetricsRegistry::default());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = router(registry);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200"));
+    assert!(head.contains("text/plain; version=0.0.4"));
+    assert!(body.contains("# TYPE mother_lsp_errors_total counter"));
+}
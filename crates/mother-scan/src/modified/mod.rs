@@ -0,0 +1,120 @@
+//! Modified: Links a commit to the symbols it changed
+//!
+//! Runs after Phase 3, once the commit's symbols are stored. The commit is
+//! diffed against the commit scanned before it in the same repository, and
+//! every new symbol whose lines overlap a change gets a MODIFIED edge from
+//! the commit. The first scan of a repository has nothing to compare with
+//! and links nothing.
+
+use std::path::Path;
+
+use anyhow::Result;
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, ScanRunResult};
+use mother_core::scanner::{changed_lines, ChangedLines};
+use tracing::{info, warn};
+
+use super::metrics::PhaseCounts;
+use super::{SymbolBuffer, SymbolInfo};
+
+/// Outcome of the modified-symbols pass
+#[derive(Debug, Default)]
+pub struct ModifiedResult {
+    pub symbol_count: usize,
+}
+
+impl ModifiedResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self, attempted: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: self.symbol_count,
+            attempted,
+            errors: 0,
+        }
+    }
+}
+
+/// Link the commit of `scan_run` to the symbols it changed since the
+/// previously scanned commit
+///
+/// A failing diff is logged and leaves the commit unlinked rather than
+/// failing the scan.
+///
+/// # Errors
+/// Returns an error if the scan runs or spilled symbols cannot be read, or
+/// the edges cannot be stored.
+pub async fn run(
+    client: &dyn GraphStore,
+    scan_run: &ScanRun,
+    scan_root: &Path,
+    symbols: &SymbolBuffer,
+) -> Result<ModifiedResult> {
+    let commit_sha = scan_run.commit_sha.as_deref().unwrap_or_default();
+    let runs = client.scan_runs().await?;
+    let Some(previous) = previous_commit(scan_run, &runs) else {
+        info!("No previously scanned commit, skipping modified symbols");
+        return Ok(ModifiedResult::default());
+    };
+    let repo = Path::new(&scan_run.repo_path);
+    let changes = match changed_lines(repo, scan_root, &previous, commit_sha) {
+        Ok(changes) => changes,
+        Err(e) => {
+            warn!("Could not diff {} against {}: {}", commit_sha, previous, e);
+            return Ok(ModifiedResult::default());
+        }
+    };
+    let mut ids = Vec::new();
+    for chunk in symbols.chunks() {
+        ids.extend(modified_ids(&changes, &chunk?));
+    }
+    client.create_modified_edges(commit_sha, &ids).await?;
+    info!(
+        "Linked {} modified symbols in {} changed files since {}",
+        ids.len(),
+        changes.file_count(),
+        previous
+    );
+    Ok(ModifiedResult {
+        symbol_count: ids.len(),
+    })
+}
+
+/// Commit to diff a scan run against: the commit of its previous run if it
+/// has one, otherwise of the newest other run of the same repository
+///
+/// `runs` are ordered newest first, as returned by
+/// [`GraphStore::scan_runs`]. Runs of the same commit are skipped.
+pub(crate) fn previous_commit(scan_run: &ScanRun, runs: &[ScanRunResult]) -> Option<String> {
+    let commit_sha = scan_run.commit_sha.as_deref().unwrap_or_default();
+    if commit_sha.is_empty() {
+        return None;
+    }
+    let usable = |run: &&ScanRunResult| !run.commit_sha.is_empty() && run.commit_sha != commit_sha;
+    let previous = match &scan_run.previous_run {
+        Some(id) => runs.iter().filter(usable).find(|run| &run.id == id),
+        None => runs
+            .iter()
+            .filter(usable)
+            .find(|run| run.repo_path == scan_run.repo_path),
+    };
+    previous.map(|run| run.commit_sha.clone())
+}
+
+/// IDs of the symbols whose lines overlap a change
+pub(crate) fn modified_ids(changes: &ChangedLines, symbols: &[SymbolInfo]) -> Vec<String> {
+    symbols
+        .iter()
+        .filter(|symbol| {
+            let path = symbol
+                .file_uri
+                .strip_prefix("file://")
+                .unwrap_or(&symbol.file_uri);
+            changes.touches(Path::new(path), symbol.start_line + 1, symbol.end_line + 1)
+        })
+        .map(|symbol| symbol.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
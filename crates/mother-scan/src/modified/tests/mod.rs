@@ -0,0 +1,3 @@
+//! Tests for modified module
+
+mod tests_modified;
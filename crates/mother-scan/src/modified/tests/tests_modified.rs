@@ -0,0 +1,200 @@
+//! Tests for linking commits to the symbols they changed
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, ScanRunResult, SqliteStore};
+use mother_core::scanner::{changed_lines, Language};
+use tempfile::TempDir;
+
+use super::super::{modified_ids, previous_commit, run};
+use crate::{SymbolBuffer, SymbolInfo};
+
+const BEFORE: &str = "fn a() {}\nfn b() {}\nfn c() {}\n";
+const AFTER: &str = "fn a() {}\nfn b() { 1 }\nfn c() {}\n";
+
+/// Create a bare repository with one commit per `lib.rs` content
+///
+/// Returns the commit SHAs in order.
+fn bare_repo(dir: &Path, contents: &[&str]) -> Vec<String> {
+    let repo = git2::Repository::init_bare(dir).unwrap();
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let mut shas: Vec<String> = Vec::new();
+
+    for content in contents {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut root = repo.treebuilder(None).unwrap();
+        root.insert("lib.rs", blob, 0o100_644).unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+        let parent = shas
+            .last()
+            .map(|sha| repo.find_commit(git2::Oid::from_str(sha).unwrap()).unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo
+            .commit(
+                Some("refs/heads/main"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        shas.push(oid.to_string());
+    }
+    shas
+}
+
+/// Symbol of `/scan/lib.rs` at 0-based LSP lines
+fn symbol(id: &str, start_line: u32, end_line: u32) -> SymbolInfo {
+    SymbolInfo {
+        id: id.to_string(),
+        file_uri: "file:///scan/lib.rs".to_string(),
+        start_line,
+        end_line,
+        start_col: 0,
+        language: Language::Rust,
+    }
+}
+
+fn run_result(id: &str, commit_sha: &str, repo_path: &str) -> ScanRunResult {
+    ScanRunResult {
+        id: id.to_string(),
+        commit_sha: commit_sha.to_string(),
+        branch: String::new(),
+        version: String::new(),
+        repo_path: repo_path.to_string(),
+        scanned_at: String::new(),
+        previous_run: String::new(),
+        tags: Vec::new(),
+        note: String::new(),
+        status: "completed".to_string(),
+    }
+}
+
+// ============================================================================
+// Tests for previous_commit
+// ============================================================================
+
+#[test]
+fn test_previous_commit_is_newest_other_commit_of_the_repo() {
+    let scan_run = ScanRun::new("/repo").with_commit("ccc");
+    let runs = [
+        run_result(&scan_run.id, "ccc", "/repo"),
+        run_result("r-rerun", "ccc", "/repo"),
+        run_result("r-other", "fff", "/other"),
+        run_result("r-b", "bbb", "/repo"),
+        run_result("r-a", "aaa", "/repo"),
+    ];
+
+    assert_eq!(previous_commit(&scan_run, &runs).as_deref(), Some("bbb"));
+}
+
+#[test]
+fn test_previous_commit_follows_the_previous_run() {
+    let scan_run = ScanRun::new("/repo")
+        .with_commit("ccc")
+        .with_previous_run("r-a");
+    let runs = [
+        run_result("r-b", "bbb", "/repo"),
+        run_result("r-a", "aaa", "/repo"),
+    ];
+
+    assert_eq!(previous_commit(&scan_run, &runs).as_deref(), Some("aaa"));
+}
+
+#[test]
+fn test_first_scan_has_no_previous_commit() {
+    let scan_run = ScanRun::new("/repo").with_commit("ccc");
+    let runs = [run_result(&scan_run.id, "ccc", "/repo")];
+
+    assert_eq!(previous_commit(&scan_run, &runs), None);
+    assert_eq!(previous_commit(&ScanRun::new("/repo"), &runs), None);
+}
+
+// ============================================================================
+// Tests for modified_ids
+// ============================================================================
+
+#[test]
+fn test_modified_ids_keep_symbols_overlapping_a_change() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[BEFORE, AFTER]);
+    let changes = changed_lines(temp.path(), Path::new("/scan"), &shas[0], &shas[1]).unwrap();
+    let symbols = [symbol("a", 0, 0), symbol("b", 1, 1), symbol("c", 2, 2)];
+
+    assert_eq!(modified_ids(&changes, &symbols), ["b"]);
+}
+
+// ============================================================================
+// Tests for run
+// ============================================================================
+
+async fn store_symbols(store: &SqliteStore, commit_sha: &str, ids: &[&str]) {
+    store
+        .create_file_if_new("/scan/lib.rs", "hash-after", "rust", commit_sha)
+        .await
+        .unwrap();
+    let nodes: Vec<_> = ids
+        .iter()
+        .zip(1..)
+        .map(|(id, line)| {
+            SymbolNode::new(*id, *id, SymbolKind::Function, "/scan/lib.rs", line, line)
+        })
+        .collect();
+    store
+        .create_symbols_batch(&nodes, "hash-after")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_run_links_the_commit_to_changed_symbols() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &[BEFORE, AFTER]);
+    let repo_path = temp.path().display().to_string();
+    let store = SqliteStore::open_in_memory().unwrap();
+    let first = ScanRun::new(&repo_path).with_commit(&shas[0]);
+    store.create_scan_run(&first).await.unwrap();
+    let second = ScanRun::new(&repo_path).with_commit(&shas[1]);
+    store.create_scan_run(&second).await.unwrap();
+    store_symbols(&store, &shas[1], &["a", "b", "c"]).await;
+    let symbols = SymbolBuffer::from(vec![
+        symbol("a", 0, 0),
+        symbol("b", 1, 1),
+        symbol("c", 2, 2),
+    ]);
+
+    let result = run(&store, &second, Path::new("/scan"), &symbols)
+        .await
+        .unwrap();
+
+    assert_eq!(result.symbol_count, 1);
+    let modified = store.modified_symbols(&shas[1]).await.unwrap();
+    let ids: Vec<_> = modified.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["b"]);
+}
+
+#[tokio::test]
+async fn test_run_skips_a_commit_git_cannot_diff() {
+    let temp = TempDir::new().unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+    let repo_path = temp.path().display().to_string();
+    let first = ScanRun::new(&repo_path).with_commit("aaa");
+    store.create_scan_run(&first).await.unwrap();
+    let second = ScanRun::new(&repo_path).with_commit("bbb");
+    store.create_scan_run(&second).await.unwrap();
+
+    let result = run(
+        &store,
+        &second,
+        temp.path(),
+        &SymbolBuffer::from(vec![symbol("a", 0, 0)]),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.symbol_count, 0);
+}
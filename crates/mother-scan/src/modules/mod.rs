@@ -0,0 +1,50 @@
+//! Modules: Namespace hierarchy of the commit
+//!
+//! Runs after the complexity pass. The commit's symbols are grouped into
+//! Module nodes, derived by [`mother_core::graph::modules`] from their file
+//! paths and enclosing module symbols, which replace the modules stored for
+//! the commit before.
+
+use std::path::Path;
+
+use anyhow::Result;
+use mother_core::graph::modules::module_tree;
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the module pass
+#[derive(Debug, Default)]
+pub struct ModulesResult {
+    pub module_count: usize,
+    pub symbol_count: usize,
+}
+
+impl ModulesResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: self.symbol_count,
+            attempted: self.symbol_count,
+            errors: 0,
+        }
+    }
+}
+
+/// Derive and store the module tree of a commit scanned under `root`
+pub async fn run(root: &Path, client: &dyn GraphStore, commit_sha: &str) -> Result<ModulesResult> {
+    let symbols = client.symbols_at_commit(commit_sha).await?;
+    let modules = module_tree(root, &symbols);
+    client.create_modules(commit_sha, &modules).await?;
+    let result = ModulesResult {
+        module_count: modules.len(),
+        symbol_count: symbols.len(),
+    };
+    info!("Stored {} modules", result.module_count);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for modules module
+
+mod tests_modules;
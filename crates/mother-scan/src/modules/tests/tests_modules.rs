@@ -0,0 +1,86 @@
+//! Tests for storing the module tree of a commit
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+use super::super::run;
+
+fn symbol(id: &str, qualified_name: &str, kind: SymbolKind, file_path: &str) -> SymbolNode {
+    SymbolNode {
+        qualified_name: qualified_name.to_string(),
+        ..SymbolNode::new(
+            id,
+            qualified_name.rsplit("::").next().unwrap(),
+            kind,
+            file_path,
+            1,
+            2,
+        )
+    }
+}
+
+async fn store_with_symbols(symbols: &[SymbolNode]) -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/repo/src/graph/read.rs", "hash-read", "rust", "abc123")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(symbols, "hash-read")
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_run_stores_modules_of_the_commit() {
+    let path = "/repo/src/graph/read.rs";
+    let store = store_with_symbols(&[
+        symbol("load", "load", SymbolKind::Function, path),
+        symbol("tests", "tests", SymbolKind::Module, path),
+        symbol("t", "tests::test_load", SymbolKind::Function, path),
+    ])
+    .await;
+
+    let result = run(Path::new("/repo"), &store, "abc123").await.unwrap();
+    assert_eq!((result.module_count, result.symbol_count), (4, 3));
+
+    let modules = store.modules_at_commit("abc123").await.unwrap();
+    let paths: Vec<&str> = modules.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "crate",
+            "crate::graph",
+            "crate::graph::read",
+            "crate::graph::read::tests"
+        ]
+    );
+    assert_eq!(modules[0].parent, None);
+    assert_eq!(modules[3].parent.as_deref(), Some("crate::graph::read"));
+    assert_eq!(modules[2].symbol_ids, vec!["load", "tests"]);
+    assert_eq!(modules[3].symbol_ids, vec!["t"]);
+}
+
+#[tokio::test]
+async fn test_run_replaces_earlier_modules() {
+    let path = "/repo/src/graph/read.rs";
+    let store = store_with_symbols(&[symbol("load", "load", SymbolKind::Function, path)]).await;
+    run(Path::new("/repo"), &store, "abc123").await.unwrap();
+
+    // Scanned from another root, the same file forms other modules
+    run(Path::new("/repo/src"), &store, "abc123").await.unwrap();
+
+    let modules = store.modules_at_commit("abc123").await.unwrap();
+    let paths: Vec<&str> = modules.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["graph", "graph::read"]);
+    assert_eq!(modules[1].symbol_ids, vec!["load"]);
+}
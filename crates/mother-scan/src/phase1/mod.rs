@@ -0,0 +1,155 @@
+//! Phase 1: Link stored files and open new ones in LSP
+//!
+//! Files whose content is stored already are linked to the commit. New
+//! files are only stored by Phase 2, together with their symbols; a file
+//! that cannot be opened is stored without them.
+//!
+//! Files of languages with an open document limit are not opened here:
+//! opening every file up front would close most of them again before their
+//! symbols are extracted.
+
+use anyhow::Result;
+use mother_core::graph::model::{FileMetrics, ScanFailure};
+use mother_core::graph::GraphStore;
+use mother_core::lsp::LspServerManager;
+use mother_core::progress::{Progress, ScanPhase};
+use mother_core::scanner::{DiscoveredFile, FileContentCache};
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+use super::report::failure;
+use super::{store_node_only, FileToProcess};
+
+/// Results from Phase 1
+pub struct Phase1Result {
+    pub(crate) files_to_process: Vec<FileToProcess>,
+    pub new_file_count: usize,
+    pub reused_file_count: usize,
+    pub error_count: usize,
+    /// The files that failed
+    pub failures: Vec<ScanFailure>,
+}
+
+impl Phase1Result {
+    /// Work done, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        let files = self.new_file_count + self.reused_file_count;
+        PhaseCounts {
+            files,
+            symbols: 0,
+            attempted: files + self.error_count,
+            errors: self.error_count,
+        }
+    }
+}
+
+/// Run Phase 1: Link stored files and open new ones in LSP
+///
+/// Each file is read through `cache`, so later phases reuse its content.
+/// Stops before the next file once the LSP manager's deadline has passed.
+pub async fn run(
+    files: &[DiscoveredFile],
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    commit_sha: &str,
+    progress: &Progress,
+) -> Result<Phase1Result> {
+    info!("Phase 1: Opening files in LSP...");
+    progress.start(ScanPhase::Files, files.len());
+
+    let mut result = Phase1Result {
+        files_to_process: Vec::new(),
+        new_file_count: 0,
+        reused_file_count: 0,
+        error_count: 0,
+        failures: Vec::new(),
+    };
+
+    for file in files {
+        if lsp_manager.past_deadline() {
+            break;
+        }
+        let outcome = process_file(file, client, lsp_manager, cache, commit_sha).await;
+        handle_file_result(outcome, file, &mut result);
+        progress.advance(ScanPhase::Files, 0, 0);
+    }
+
+    progress.finish(ScanPhase::Files);
+    log_phase1_errors(&result);
+    Ok(result)
+}
+
+/// Handle the result of processing a single file
+pub(crate) fn handle_file_result(
+    outcome: Result<Option<FileToProcess>>,
+    file: &DiscoveredFile,
+    result: &mut Phase1Result,
+) {
+    match outcome {
+        Ok(Some(file_to_process)) => {
+            result.new_file_count += 1;
+            result.files_to_process.push(file_to_process);
+        }
+        Ok(None) => {
+            result.reused_file_count += 1;
+        }
+        Err(e) => {
+            result.error_count += 1;
+            tracing::warn!("Failed to process {}: {}", file.path.display(), e);
+            let failure = failure("Phase 1", &file.path, "open file", &e);
+            result.failures.push(failure);
+        }
+    }
+}
+
+/// Log error summary for phase 1
+fn log_phase1_errors(result: &Phase1Result) {
+    if result.error_count > 0 {
+        tracing::warn!("Phase 1: {} files failed to process", result.error_count);
+    }
+}
+
+/// Process a single file for phase 1. Returns Ok(Some) for new files, Ok(None) for reused.
+async fn process_file(
+    file: &DiscoveredFile,
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    commit_sha: &str,
+) -> Result<Option<FileToProcess>> {
+    let content = cache.read(&file.path)?;
+    let hash = content.hash();
+
+    // Check if file already exists in Neo4j
+    if client.link_stored_file(&hash, commit_sha).await? {
+        return Ok(None); // File reused
+    }
+
+    let file_content = content.text()?;
+    let new_file = FileToProcess {
+        path: file.path.clone(),
+        file_uri: format!("file://{}", file.path.display()),
+        content_hash: hash,
+        language: file.language.clone(),
+        metrics: FileMetrics::of_content(file_content),
+        commit_sha: commit_sha.to_string(),
+    };
+
+    // Open the file on its language's server; with an open document limit
+    // the later phases open it when they need it
+    if !lsp_manager.limits_open_documents(&file.language) {
+        let opened = lsp_manager
+            .open_document(&file.language, &new_file.file_uri, file_content)
+            .await;
+        if let Err(e) = opened {
+            store_node_only(client, &new_file).await;
+            return Err(e);
+        }
+    }
+
+    Ok(Some(new_file))
+}
+
+#[cfg(test)]
+mod tests;
@@ -1,11 +1,12 @@
 //! Tests for handle_file_result function
 
 use anyhow::anyhow;
+use mother_core::graph::model::FileMetrics;
 use mother_core::scanner::{DiscoveredFile, Language};
 use std::path::PathBuf;
 
-use crate::commands::scan::phase1::{handle_file_result, Phase1Result};
-use crate::commands::scan::FileToProcess;
+use crate::phase1::{handle_file_result, Phase1Result};
+use crate::FileToProcess;
 
 // ============================================================================
 // Helper functions
@@ -24,6 +25,8 @@ fn create_test_file_to_process(path: &str, language: Language) -> FileToProcess
         file_uri: format!("file://{}", path),
         content_hash: "abc123".to_string(),
         language,
+        metrics: FileMetrics::default(),
+        commit_sha: "abc123".to_string(),
     }
 }
 
@@ -33,6 +36,7 @@ fn create_empty_result() -> Phase1Result {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     }
 }
 
@@ -94,6 +98,8 @@ fn test_handle_file_result_ok_some_preserves_file_metadata() {
         file_uri: "file:///test/main.py".to_string(),
         content_hash: "def456".to_string(),
         language: Language::Python,
+        metrics: FileMetrics::default(),
+        commit_sha: "abc123".to_string(),
     };
 
     handle_file_result(Ok(Some(file_to_process)), &file, &mut result);
@@ -119,8 +125,8 @@ fn test_handle_file_result_ok_some_different_languages() {
 
     for (i, lang) in languages.iter().enumerate() {
         let path = format!("/test/file{}", i);
-        let file = create_test_discovered_file(&path, *lang);
-        let file_to_process = create_test_file_to_process(&path, *lang);
+        let file = create_test_discovered_file(&path, lang.clone());
+        let file_to_process = create_test_file_to_process(&path, lang.clone());
         handle_file_result(Ok(Some(file_to_process)), &file, &mut result);
     }
 
@@ -186,6 +192,22 @@ fn test_handle_file_result_err_increments_error_count() {
     assert_eq!(result.error_count, 1);
 }
 
+#[test]
+fn test_handle_file_result_err_records_the_failure() {
+    let mut result = create_empty_result();
+    let file = create_test_discovered_file("/test/file.rs", Language::Rust);
+
+    let error = anyhow!("connection reset").context("didOpen failed");
+    handle_file_result(Err(error), &file, &mut result);
+
+    assert_eq!(result.failures.len(), 1);
+    let failure = &result.failures[0];
+    assert_eq!(failure.phase, "Phase 1");
+    assert_eq!(failure.file, "/test/file.rs");
+    assert_eq!(failure.symbol, None);
+    assert_eq!(failure.message, "didOpen failed: connection reset");
+}
+
 #[test]
 fn test_handle_file_result_err_does_not_add_to_files_to_process() {
     let mut result = create_empty_result();
@@ -407,6 +429,7 @@ fn test_handle_file_result_with_existing_counts() {
         new_file_count: 10,
         reused_file_count: 5,
         error_count: 2,
+        failures: Vec::new(),
     };
 
     let file = create_test_discovered_file("/test/file.rs", Language::Rust);
@@ -1,6 +1,6 @@
 //! Tests for Phase1Result struct
 
-use crate::commands::scan::phase1::Phase1Result;
+use crate::phase1::Phase1Result;
 
 // ============================================================================
 // Tests for Phase1Result initialization
@@ -13,6 +13,7 @@ fn test_phase1_result_initialization() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.files_to_process.len(), 0);
@@ -28,6 +29,7 @@ fn test_phase1_result_with_values() {
         new_file_count: 5,
         reused_file_count: 3,
         error_count: 2,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 5);
@@ -42,6 +44,7 @@ fn test_phase1_result_files_to_process_empty() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     assert!(result.files_to_process.is_empty());
@@ -54,6 +57,7 @@ fn test_phase1_result_counts_can_be_zero() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 0);
@@ -68,6 +72,7 @@ fn test_phase1_result_counts_can_be_large() {
         new_file_count: 1000,
         reused_file_count: 2000,
         error_count: 50,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 1000);
@@ -82,6 +87,7 @@ fn test_phase1_result_only_new_files() {
         new_file_count: 10,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 10);
@@ -96,6 +102,7 @@ fn test_phase1_result_only_reused_files() {
         new_file_count: 0,
         reused_file_count: 15,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 0);
@@ -110,6 +117,7 @@ fn test_phase1_result_only_errors() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 7,
+        failures: Vec::new(),
     };
 
     assert_eq!(result.new_file_count, 0);
@@ -124,6 +132,7 @@ fn test_phase1_result_mixed_counts() {
         new_file_count: 12,
         reused_file_count: 8,
         error_count: 3,
+        failures: Vec::new(),
     };
 
     let total_processed = result.new_file_count + result.reused_file_count + result.error_count;
@@ -141,6 +150,7 @@ fn test_phase1_result_can_increment_new_file_count() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     result.new_file_count += 1;
@@ -157,6 +167,7 @@ fn test_phase1_result_can_increment_reused_file_count() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     result.reused_file_count += 1;
@@ -173,6 +184,7 @@ fn test_phase1_result_can_increment_error_count() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     result.error_count += 1;
@@ -3,23 +3,23 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::expect_used)]
 
-use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+use mother_core::graph::model::ScanRun;
+use mother_core::graph::{GraphStore, SqliteStore};
 use mother_core::lsp::LspServerManager;
-use mother_core::scanner::{DiscoveredFile, Language};
-use serial_test::serial;
+use mother_core::progress::Progress;
+use mother_core::scanner::{DiscoveredFile, FileContentCache, Language};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
-use crate::commands::scan::phase1::run;
+use crate::phase1::run;
 
 // ============================================================================
 // Helper functions for tests
 // ============================================================================
 
-/// Helper to create a test Neo4j client
-async fn create_test_client() -> Neo4jClient {
-    let config = Neo4jConfig::new("bolt://localhost:7687", "neo4j", "mother_dev_password");
-    Neo4jClient::connect(&config).await.unwrap()
+/// Helper to create an in-memory test store
+fn create_test_client() -> SqliteStore {
+    SqliteStore::open_in_memory().unwrap()
 }
 
 /// Helper to create a test file with content
@@ -39,15 +39,21 @@ fn create_discovered_file(path: PathBuf, language: Language) -> DiscoveredFile {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
 async fn test_run_with_empty_file_list() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
-    let client = create_test_client().await;
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let client = create_test_client();
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "abc123";
 
-    let result = run(&[], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -62,19 +68,26 @@ async fn test_run_with_empty_file_list() {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_single_new_rust_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test.rs", "fn main() {}");
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "test_commit_123";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -86,19 +99,26 @@ async fn test_run_with_single_new_rust_file() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_single_new_python_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test.py", "def main(): pass");
     let discovered_file = create_discovered_file(file_path, Language::Python);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "test_commit_456";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -110,24 +130,25 @@ async fn test_run_with_single_new_python_file() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_single_reused_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "reused.rs", "fn test() {}");
     let discovered_file = create_discovered_file(file_path.clone(), Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "same_commit";
 
     // First run - file should be new
     let result1 = run(
         std::slice::from_ref(&discovered_file),
         &client,
-        &mut lsp_manager,
+        &lsp_manager,
+        &FileContentCache::default(),
         commit_sha,
+        &Progress::default(),
     )
     .await;
     assert!(result1.is_ok());
@@ -135,7 +156,15 @@ async fn test_run_with_single_reused_file() {
     assert_eq!(phase1_result1.new_file_count, 1);
 
     // Second run - file should be reused (same content and commit)
-    let result2 = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result2 = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result2.is_ok());
     let phase1_result2 = result2.unwrap();
@@ -150,8 +179,7 @@ async fn test_run_with_single_reused_file() {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_multiple_new_files() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file1 = create_test_file(&temp_dir, "file1.rs", "fn one() {}");
@@ -164,12 +192,20 @@ async fn test_run_with_multiple_new_files() {
         create_discovered_file(file3, Language::Rust),
     ];
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "multi_commit";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -180,8 +216,7 @@ async fn test_run_with_multiple_new_files() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_multiple_languages() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let rust_file = create_test_file(&temp_dir, "test.rs", "fn main() {}");
@@ -194,12 +229,20 @@ async fn test_run_with_multiple_languages() {
         create_discovered_file(ts_file, Language::TypeScript),
     ];
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "multi_lang_commit";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -212,7 +255,7 @@ async fn test_run_with_multiple_languages() {
     let languages: Vec<Language> = phase1_result
         .files_to_process
         .iter()
-        .map(|f| f.language)
+        .map(|f| f.language.clone())
         .collect();
     assert!(languages.contains(&Language::Rust));
     assert!(languages.contains(&Language::Python));
@@ -220,8 +263,7 @@ async fn test_run_with_multiple_languages() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_mixed_new_and_reused_files() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file1 = create_test_file(&temp_dir, "new.rs", "fn new_func() {}");
@@ -230,23 +272,33 @@ async fn test_run_with_mixed_new_and_reused_files() {
     let discovered_file1 = create_discovered_file(file1, Language::Rust);
     let discovered_file2 = create_discovered_file(file2.clone(), Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "mixed_commit";
 
     // First, process file2 so it will be reused later
     let _ = run(
         std::slice::from_ref(&discovered_file2),
         &client,
-        &mut lsp_manager,
+        &lsp_manager,
+        &FileContentCache::default(),
         commit_sha,
+        &Progress::default(),
     )
     .await;
 
     // Now run with both files - file1 is new, file2 is reused
     let discovered_files = vec![discovered_file1, discovered_file2];
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -261,18 +313,24 @@ async fn test_run_with_mixed_new_and_reused_files() {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
 async fn test_run_with_nonexistent_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let nonexistent_path = PathBuf::from("/nonexistent/path/file.rs");
     let discovered_file = create_discovered_file(nonexistent_path, Language::Rust);
 
-    let client = create_test_client().await;
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let client = create_test_client();
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "error_commit";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -283,8 +341,7 @@ async fn test_run_with_nonexistent_file() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_mixed_success_and_errors() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let valid_file = create_test_file(&temp_dir, "valid.rs", "fn valid() {}");
@@ -295,12 +352,20 @@ async fn test_run_with_mixed_success_and_errors() {
         create_discovered_file(nonexistent_path, Language::Rust),
     ];
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "mixed_error_commit";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -311,8 +376,6 @@ async fn test_run_with_mixed_success_and_errors() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
 async fn test_run_with_multiple_errors() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let nonexistent1 = PathBuf::from("/nonexistent/error1.rs");
@@ -325,11 +388,19 @@ async fn test_run_with_multiple_errors() {
         create_discovered_file(nonexistent3, Language::TypeScript),
     ];
 
-    let client = create_test_client().await;
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let client = create_test_client();
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "all_errors_commit";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -344,23 +415,24 @@ async fn test_run_with_multiple_errors() {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_different_commit_sha() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test.rs", "fn test() {}");
     let discovered_file = create_discovered_file(file_path.clone(), Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
 
     // First run with commit_sha1
     let result1 = run(
         std::slice::from_ref(&discovered_file),
         &client,
-        &mut lsp_manager,
+        &lsp_manager,
+        &FileContentCache::default(),
         "commit_sha_1",
+        &Progress::default(),
     )
     .await;
     assert!(result1.is_ok());
@@ -372,8 +444,10 @@ async fn test_run_with_different_commit_sha() {
     let result2 = run(
         &[discovered_file],
         &client,
-        &mut lsp_manager,
+        &lsp_manager,
+        &FileContentCache::default(),
         "commit_sha_2",
+        &Progress::default(),
     )
     .await;
 
@@ -385,18 +459,25 @@ async fn test_run_with_different_commit_sha() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_empty_commit_sha() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test.rs", "fn main() {}");
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, "").await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        "",
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -405,19 +486,26 @@ async fn test_run_with_empty_commit_sha() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_long_commit_sha() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test.rs", "fn main() {}");
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let long_sha = "a".repeat(64); // Typical git SHA length
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, &long_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        &long_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -429,8 +517,7 @@ async fn test_run_with_long_commit_sha() {
 // ============================================================================
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_large_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     // Create a large file (1MB of code)
@@ -438,12 +525,20 @@ async fn test_run_with_large_file() {
     let file_path = create_test_file(&temp_dir, "large.rs", &large_content);
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "large_file_commit";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -452,19 +547,26 @@ async fn test_run_with_large_file() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_empty_file() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "empty.rs", "");
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "empty_file_commit";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -473,19 +575,26 @@ async fn test_run_with_empty_file() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_with_special_characters_in_filename() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file_path = create_test_file(&temp_dir, "test file-name_123.rs", "fn main() {}");
     let discovered_file = create_discovered_file(file_path, Language::Rust);
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "special_chars_commit";
 
-    let result = run(&[discovered_file], &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &[discovered_file],
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -494,8 +603,7 @@ async fn test_run_with_special_characters_in_filename() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
+#[ignore = "requires LSP"]
 async fn test_run_processes_files_in_order() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let file1 = create_test_file(&temp_dir, "a.rs", "fn a() {}");
@@ -508,12 +616,20 @@ async fn test_run_processes_files_in_order() {
         create_discovered_file(file3.clone(), Language::Rust),
     ];
 
-    let client = create_test_client().await;
+    let client = create_test_client();
 
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "order_commit";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
@@ -526,8 +642,6 @@ async fn test_run_processes_files_in_order() {
 }
 
 #[tokio::test]
-#[ignore = "requires running Neo4j and LSP"]
-#[serial]
 async fn test_run_returns_ok_even_with_all_errors() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let nonexistent1 = PathBuf::from("/error/file1.rs");
@@ -538,15 +652,60 @@ async fn test_run_returns_ok_even_with_all_errors() {
         create_discovered_file(nonexistent2, Language::Rust),
     ];
 
-    let client = create_test_client().await;
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let client = create_test_client();
+    let lsp_manager = LspServerManager::new(temp_dir.path());
     let commit_sha = "all_errors";
 
-    let result = run(&discovered_files, &client, &mut lsp_manager, commit_sha).await;
+    let result = run(
+        &discovered_files,
+        &client,
+        &lsp_manager,
+        &FileContentCache::default(),
+        commit_sha,
+        &Progress::default(),
+    )
+    .await;
 
-    // run() should return Ok even when all files fail
+    // run(, &Progress::default()) should return Ok even when all files fail
     assert!(result.is_ok());
     let phase1_result = result.unwrap();
     assert_eq!(phase1_result.error_count, 2);
     assert_eq!(phase1_result.new_file_count, 0);
 }
+
+#[tokio::test]
+async fn test_run_reads_files_through_the_cache() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(&temp_dir, "stored.rs", "fn stored() {}");
+    let discovered_file = create_discovered_file(file_path.clone(), Language::Rust);
+
+    let client = create_test_client();
+    let scan_run = ScanRun::new(temp_dir.path().display().to_string()).with_commit("cached_commit");
+    client.create_scan_run(&scan_run).await.unwrap();
+    let hash = discovered_file.compute_hash().unwrap();
+    let path_str = file_path.display().to_string();
+    client
+        .create_file_if_new(&path_str, &hash, "rust", "cached_commit")
+        .await
+        .unwrap();
+
+    let lsp_manager = LspServerManager::new(temp_dir.path());
+    let cache = FileContentCache::default();
+    for _ in 0..2 {
+        let result = run(
+            std::slice::from_ref(&discovered_file),
+            &client,
+            &lsp_manager,
+            &cache,
+            "cached_commit",
+            &Progress::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.reused_file_count, 1);
+    }
+
+    let stats = cache.stats();
+    assert_eq!((stats.misses, stats.hits), (1, 1));
+    assert_eq!(stats.bytes_read, 14);
+}
@@ -1,44 +1,125 @@
 //! Phase 2: Extract symbols from files
+//!
+//! Each new file is stored with its symbols, their signatures and lineage
+//! edges and its metrics in one transaction, so a scan that dies partway
+//! leaves no file half written. A file whose symbols cannot be extracted or
+//! stored is stored without them.
+
+use std::path::Path;
 
 use anyhow::Result;
-use mother_core::graph::convert::convert_symbols;
-use mother_core::graph::model::SymbolNode;
-use mother_core::graph::neo4j::Neo4jClient;
-use mother_core::lsp::{
-    collect_symbol_positions as collect_lsp_symbol_positions,
-    flatten_symbols as flatten_lsp_symbols, LspClient, LspServerManager, LspSymbol,
+use mother_core::enrich::{EnrichContext, EnricherReport, EnricherTotals, EnrichmentPipeline};
+use mother_core::graph::convert::{
+    assign_symbol_ids, convert_symbols, function_signatures, infer_visibilities,
 };
-use mother_core::scanner::Language;
+use mother_core::graph::ids::SymbolIdStrategy;
+use mother_core::graph::model::{FileWrite, ScanFailure, SymbolNode};
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{flatten_symbols as flatten_lsp_symbols, LspServerManager, LspSymbol};
+use mother_core::progress::ScanPhase;
+use mother_core::scanner::{FileContentCache, Language};
 use tracing::info;
 
-use super::{FileToProcess, SymbolInfo};
+use super::metrics::PhaseCounts;
+use super::report::failure;
+use super::{store_node_only, FileToProcess, ScanOptions, SymbolBuffer, SymbolInfo};
 
 /// Results from Phase 2
 pub struct Phase2Result {
-    pub(crate) symbols: Vec<SymbolInfo>,
+    pub(crate) symbols: SymbolBuffer,
     pub symbol_count: usize,
     pub error_count: usize,
+    /// The files whose symbols could not be extracted or stored, and the
+    /// enrichers that failed on a file
+    pub failures: Vec<ScanFailure>,
+    /// Time and failures of each enricher
+    pub enrichers: EnricherTotals,
+}
+
+impl Phase2Result {
+    /// Work done on `attempted` files, for throughput metrics
+    pub(crate) fn counts(&self, attempted: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: attempted.saturating_sub(self.error_count),
+            symbols: self.symbol_count,
+            attempted,
+            errors: self.error_count,
+        }
+    }
+}
+
+/// How Phase 2 names the symbols it stores
+#[derive(Clone, Copy)]
+pub(crate) struct SymbolIds<'a> {
+    /// Root of the scan, which file paths in IDs are relative to
+    pub root: &'a Path,
+    pub strategy: &'a dyn SymbolIdStrategy,
+}
+
+impl SymbolIds<'_> {
+    /// Replace the random IDs of a file's converted symbols
+    fn assign(&self, symbols: &mut [SymbolNode], file_info: &FileToProcess) {
+        let path = file_info
+            .path
+            .strip_prefix(self.root)
+            .unwrap_or(&file_info.path);
+        let path = path.display().to_string();
+        assign_symbol_ids(symbols, self.strategy, &path, &file_info.content_hash);
+    }
 }
 
 /// Run Phase 2: Extract symbols from files
-pub async fn run(
+///
+/// The symbols Phase 3 needs are collected in the buffer of `options`,
+/// which spills them to disk beyond its limit. Files closed by an open
+/// document limit are read again through `cache`. Stops before the next
+/// file once the LSP manager's deadline has passed.
+pub(crate) async fn run(
     files: &[FileToProcess],
-    client: &Neo4jClient,
-    lsp_manager: &mut LspServerManager,
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    pipeline: &EnrichmentPipeline,
+    ids: SymbolIds<'_>,
+    options: &ScanOptions,
 ) -> Result<Phase2Result> {
     info!("Phase 2: Extracting symbols from {} files...", files.len());
+    let progress = &options.progress;
+    progress.start(ScanPhase::Symbols, files.len());
 
     let mut result = Phase2Result {
-        symbols: Vec::new(),
+        symbols: options.symbol_buffer(),
         symbol_count: 0,
         error_count: 0,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     for file_info in files {
-        let outcome = process_file(file_info, client, lsp_manager).await;
+        if lsp_manager.past_deadline() {
+            break;
+        }
+        let mut reports = Vec::new();
+        let outcome = process_file(
+            file_info,
+            client,
+            lsp_manager,
+            cache,
+            pipeline,
+            ids,
+            &mut reports,
+        )
+        .await;
+        if outcome.is_err() {
+            store_node_only(client, file_info).await;
+        }
+        let before = result.symbol_count;
         handle_file_result(outcome, file_info, &mut result);
+        record_enrichers(&reports, file_info, &mut result);
+        progress.advance(ScanPhase::Symbols, result.symbol_count - before, 0);
     }
 
+    progress.finish(ScanPhase::Symbols);
     log_phase2_errors(&result);
     Ok(result)
 }
@@ -61,11 +142,35 @@ fn handle_file_result(
                 file_info.path.display(),
                 e
             );
+            let request = "textDocument/documentSymbol";
+            let failure = failure("Phase 2", &file_info.path, request, &e);
+            result.failures.push(failure);
         }
     }
 }
 
-/// Log error summary for phase 2
+/// Add a file's enricher reports to the totals, recording each failed
+/// enricher as a failure of the file
+fn record_enrichers(
+    reports: &[EnricherReport],
+    file_info: &FileToProcess,
+    result: &mut Phase2Result,
+) {
+    result.enrichers.add(reports);
+    for report in reports {
+        if let Some(message) = &report.error {
+            result.failures.push(ScanFailure {
+                phase: "Phase 2".to_string(),
+                file: file_info.path.display().to_string(),
+                symbol: None,
+                request: format!("{} enricher", report.name),
+                message: message.clone(),
+            });
+        }
+    }
+}
+
+/// Log error summary and enricher timings for phase 2
 fn log_phase2_errors(result: &Phase2Result) {
     if result.error_count > 0 {
         tracing::warn!(
@@ -73,38 +178,77 @@ fn log_phase2_errors(result: &Phase2Result) {
             result.error_count
         );
     }
+    for total in result.enrichers.iter() {
+        info!(
+            "  enricher {}: {} files in {:.2?}",
+            total.name, total.files, total.elapsed
+        );
+        if total.errors > 0 {
+            tracing::warn!(
+                "Phase 2: enricher {} failed on {} files",
+                total.name,
+                total.errors
+            );
+        }
+    }
 }
 
 /// Process a single file for phase 2 (symbol extraction)
 async fn process_file(
     file_info: &FileToProcess,
-    client: &Neo4jClient,
-    lsp_manager: &mut LspServerManager,
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    pipeline: &EnrichmentPipeline,
+    ids: SymbolIds<'_>,
+    reports: &mut Vec<EnricherReport>,
 ) -> Result<(Vec<SymbolInfo>, usize)> {
-    let lsp_client = lsp_manager.get_client(file_info.language).await?;
+    let (language, file_uri) = (&file_info.language, &file_info.file_uri);
+    super::ensure_open(lsp_manager, cache, language, file_uri).await?;
+    let lsp_client = lsp_manager.get_client(&file_info.language).await?;
     let lsp_symbols = lsp_client.document_symbols(&file_info.file_uri).await?;
 
     // Convert LSP symbols to graph nodes
     let mut symbols = convert_symbols(&lsp_symbols, &file_info.path);
+    ids.assign(&mut symbols, file_info);
     let file_symbol_count = symbols.len();
 
-    // Enrich symbols with hover information
-    enrich_symbols_with_hover(&mut symbols, &lsp_symbols, lsp_client, &file_info.file_uri).await;
+    // Run the configured enrichers (hover docs, signatures, visibility, ...)
+    let mut ctx = EnrichContext {
+        symbols: &mut symbols,
+        lsp_symbols: &lsp_symbols,
+        lsp_client: &lsp_client,
+        file_uri: &file_info.file_uri,
+        language: file_info.language.clone(),
+    };
+    *reports = pipeline.run(&mut ctx).await;
+    infer_visibilities(&mut symbols, &file_info.language);
+    // Release the server for other tasks before writing to Neo4j
+    drop(lsp_client);
 
     log_file_symbols(file_info, file_symbol_count, lsp_symbols.len());
 
-    // Store symbols in Neo4j
-    client
-        .create_symbols_batch(&symbols, &file_info.content_hash)
-        .await?;
+    // Store the file with its symbols in Neo4j
+    let mut metrics = file_info.metrics.clone();
+    metrics.count_symbols(&symbols);
+    let file = FileWrite {
+        metrics: Some(metrics),
+        // Parameters and return types parsed from the hover type signatures
+        signatures: function_signatures(&symbols),
+        // Links to the previous version of the file
+        edges: super::lineage::edges(client, &symbols, &file_info.content_hash).await?,
+        symbols,
+        ..file_info.node_only()
+    };
+    client.write_file(&file).await?;
 
     // Collect symbol info for reference extraction
     let mut symbol_infos = Vec::new();
     collect_symbol_info(
         &lsp_symbols,
-        &symbols,
+        &file.symbols,
         &file_info.file_uri,
-        file_info.language,
+        &file_info.language,
         &mut symbol_infos,
     );
 
@@ -124,32 +268,12 @@ fn log_file_symbols(file_info: &FileToProcess, symbol_count: usize, lsp_count: u
     );
 }
 
-/// Enrich symbols with hover information from LSP
-async fn enrich_symbols_with_hover(
-    symbols: &mut [SymbolNode],
-    lsp_symbols: &[LspSymbol],
-    lsp_client: &mut LspClient,
-    file_uri: &str,
-) {
-    let lsp_positions = collect_lsp_symbol_positions(lsp_symbols);
-
-    for (i, symbol) in symbols.iter_mut().enumerate() {
-        let col = lsp_positions.get(i).map(|p| p.1).unwrap_or(0);
-        // Use 0-indexed line for hover (symbol.start_line is 1-indexed)
-        if let Ok(Some(hover_content)) =
-            lsp_client.hover(file_uri, symbol.start_line - 1, col).await
-        {
-            symbol.doc_comment = Some(hover_content);
-        }
-    }
-}
-
 /// Collect position info from LSP symbols, matching them to graph nodes by traversal order
-fn collect_symbol_info(
+pub(super) fn collect_symbol_info(
     lsp_symbols: &[LspSymbol],
     graph_symbols: &[SymbolNode],
     file_uri: &str,
-    language: Language,
+    language: &Language,
     out: &mut Vec<SymbolInfo>,
 ) {
     let flat_lsp = flatten_lsp_symbols(lsp_symbols);
@@ -161,7 +285,7 @@ fn collect_symbol_info(
             start_line: lsp_sym.start_line,
             end_line: lsp_sym.end_line,
             start_col: lsp_sym.start_col,
-            language,
+            language: language.clone(),
         });
     }
 }
@@ -170,7 +294,7 @@ fn collect_symbol_info(
 mod tests {
     use super::*;
     use anyhow::anyhow;
-    use mother_core::graph::model::SymbolKind;
+    use mother_core::graph::model::{FileMetrics, SymbolKind};
     use mother_core::lsp::LspSymbolKind;
     use std::path::PathBuf;
 
@@ -181,6 +305,8 @@ mod tests {
             file_uri: format!("file://{}", path),
             content_hash: "test_hash".to_string(),
             language: Language::Rust,
+            metrics: FileMetrics::default(),
+            commit_sha: "abc123".to_string(),
         }
     }
 
@@ -227,26 +353,17 @@ mod tests {
         start: u32,
         end: u32,
     ) -> SymbolNode {
-        SymbolNode {
-            id: id.to_string(),
-            name: name.to_string(),
-            qualified_name: name.to_string(),
-            kind,
-            visibility: None,
-            file_path: "/test.rs".to_string(),
-            start_line: start,
-            end_line: end,
-            signature: None,
-            doc_comment: None,
-        }
+        SymbolNode::new(id, name, kind, "/test.rs", start, end)
     }
 
     #[test]
     fn test_phase2_result_initialization() {
         let result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         assert_eq!(result.symbols.len(), 0);
@@ -257,9 +374,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_success() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file = create_test_file("/test/file.rs");
@@ -277,9 +396,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_error() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file = create_test_file("/test/file.rs");
@@ -290,14 +411,80 @@ mod tests {
         assert_eq!(result.symbols.len(), 0);
         assert_eq!(result.symbol_count, 0);
         assert_eq!(result.error_count, 1);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].file, "/test/file.rs");
+        assert_eq!(result.failures[0].request, "textDocument/documentSymbol");
+        assert_eq!(result.failures[0].message, "Test error");
+    }
+
+    struct FailingEnricher;
+
+    #[async_trait::async_trait]
+    impl mother_core::enrich::Enricher for FailingEnricher {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn enrich(&self, _ctx: &mut EnrichContext<'_>) -> Result<()> {
+            anyhow::bail!("boom")
+        }
+    }
+
+    #[tokio::test]
+    #[allow(clippy::expect_used)]
+    async fn test_failing_enricher_reaches_the_report() {
+        // A placeholder process: the enrichers here never talk to the server
+        let config = mother_core::lsp::LspServerConfig {
+            language: Language::Rust,
+            command: "sleep".to_string(),
+            args: vec!["30".to_string()],
+            root_path: std::env::temp_dir(),
+            init_options: None,
+        };
+        let lsp_client = mother_core::lsp::LspClient::start(config)
+            .await
+            .expect("Failed to start placeholder process");
+        let pipeline = EnrichmentPipeline::new()
+            .with(Box::new(FailingEnricher))
+            .with(Box::new(mother_core::enrich::VisibilityEnricher));
+        let mut symbols = vec![create_symbol_node("id1", "run", SymbolKind::Function, 1, 2)];
+        let mut ctx = EnrichContext {
+            symbols: &mut symbols,
+            lsp_symbols: &[],
+            lsp_client: &lsp_client,
+            file_uri: "file:///test/file.rs",
+            language: Language::Rust,
+        };
+        let reports = pipeline.run(&mut ctx).await;
+        let mut result = Phase2Result {
+            symbols: SymbolBuffer::default(),
+            symbol_count: 0,
+            error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
+        };
+
+        let file = create_test_file("/test/file.rs");
+        record_enrichers(&reports, &file, &mut result);
+
+        assert_eq!(result.error_count, 0);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].phase, "Phase 2");
+        assert_eq!(result.failures[0].file, "/test/file.rs");
+        assert_eq!(result.failures[0].request, "failing enricher");
+        assert_eq!(result.failures[0].message, "boom");
+        assert_eq!(result.enrichers.error_count(), 1);
+        assert_eq!(result.enrichers.iter().count(), 2);
     }
 
     #[test]
     fn test_handle_file_result_multiple_successes() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file1 = create_test_file("/test/file1.rs");
@@ -317,9 +504,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_mixed_results() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file1 = create_test_file("/test/file1.rs");
@@ -346,9 +535,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_empty_symbols() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file = create_test_file("/test/empty.rs");
@@ -364,7 +555,7 @@ mod tests {
     #[test]
     fn test_collect_symbol_info_empty() {
         let mut out = Vec::new();
-        collect_symbol_info(&[], &[], "file:///test.rs", Language::Rust, &mut out);
+        collect_symbol_info(&[], &[], "file:///test.rs", &Language::Rust, &mut out);
         assert_eq!(out.len(), 0);
     }
 
@@ -392,7 +583,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -421,7 +612,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Python,
+            &Language::Python,
             &mut out,
         );
 
@@ -456,7 +647,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -485,7 +676,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -517,7 +708,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.go",
-            Language::Go,
+            &Language::Go,
             &mut out,
         );
 
@@ -550,7 +741,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             custom_uri,
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -561,11 +752,11 @@ mod tests {
     #[test]
     fn test_collect_symbol_info_different_languages() {
         let languages = vec![
-            Language::Rust,
-            Language::Python,
-            Language::TypeScript,
-            Language::JavaScript,
-            Language::Go,
+            &Language::Rust,
+            &Language::Python,
+            &Language::TypeScript,
+            &Language::JavaScript,
+            &Language::Go,
         ];
 
         for _lang in languages {
@@ -595,16 +786,18 @@ mod tests {
             );
 
             assert_eq!(out.len(), 1);
-            assert!(matches!(out[0].language, _lang));
+            assert_eq!(&out[0].language, _lang);
         }
     }
 
     #[test]
     fn test_handle_file_result_accumulates_correctly() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         for i in 0..5 {
@@ -621,9 +814,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_error_accumulation() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         for i in 0..3 {
@@ -662,7 +857,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -696,7 +891,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -730,7 +925,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -763,7 +958,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -796,7 +991,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -832,7 +1027,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -857,7 +1052,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -890,7 +1085,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             unicode_uri,
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -901,9 +1096,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_with_zero_symbols_but_nonzero_count() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file = create_test_file("/test/file.rs");
@@ -919,9 +1116,11 @@ mod tests {
     #[test]
     fn test_handle_file_result_preserves_symbol_order() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file = create_test_file("/test/file.rs");
@@ -934,17 +1133,24 @@ mod tests {
         handle_file_result(Ok((symbols, 3)), &file, &mut result);
 
         assert_eq!(result.symbols.len(), 3);
-        assert_eq!(result.symbols[0].id, "first");
-        assert_eq!(result.symbols[1].id, "second");
-        assert_eq!(result.symbols[2].id, "third");
+        let ids: Vec<_> = result
+            .symbols
+            .chunks()
+            .flatten()
+            .flat_map(std::borrow::Cow::into_owned)
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec!["first", "second", "third"]);
     }
 
     #[test]
     fn test_handle_file_result_with_duplicate_symbol_ids() {
         let mut result = Phase2Result {
-            symbols: Vec::new(),
+            symbols: SymbolBuffer::default(),
             symbol_count: 0,
             error_count: 0,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let file1 = create_test_file("/test/file1.rs");
@@ -957,8 +1163,14 @@ mod tests {
         handle_file_result(Ok((symbols2, 1)), &file2, &mut result);
 
         assert_eq!(result.symbols.len(), 2);
-        assert_eq!(result.symbols[0].id, "duplicate_id");
-        assert_eq!(result.symbols[1].id, "duplicate_id");
+        let ids: Vec<_> = result
+            .symbols
+            .chunks()
+            .flatten()
+            .flat_map(std::borrow::Cow::into_owned)
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec!["duplicate_id", "duplicate_id"]);
     }
 
     #[test]
@@ -978,7 +1190,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -1014,7 +1226,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -1044,7 +1256,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///test.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -1056,9 +1268,11 @@ mod tests {
     #[test]
     fn test_phase2_result_with_nonzero_initial_values() {
         let result = Phase2Result {
-            symbols: vec![create_test_symbol("existing")],
+            symbols: SymbolBuffer::from(vec![create_test_symbol("existing")]),
             symbol_count: 100,
             error_count: 5,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         assert_eq!(result.symbols.len(), 1);
@@ -1069,11 +1283,11 @@ mod tests {
     #[test]
     fn test_collect_symbol_info_all_languages() {
         for test_lang in [
-            Language::Rust,
-            Language::Python,
-            Language::TypeScript,
-            Language::JavaScript,
-            Language::Go,
+            &Language::Rust,
+            &Language::Python,
+            &Language::TypeScript,
+            &Language::JavaScript,
+            &Language::Go,
         ] {
             let lsp_symbols = vec![create_lsp_symbol(
                 "test",
@@ -1102,7 +1316,7 @@ mod tests {
             );
 
             assert_eq!(out.len(), 1);
-            assert_eq!(out[0].language, test_lang);
+            assert_eq!(out[0].language, *test_lang);
         }
     }
 
@@ -1141,7 +1355,7 @@ mod tests {
             &lsp_symbols,
             &graph_symbols,
             "file:///complex.rs",
-            Language::Rust,
+            &Language::Rust,
             &mut out,
         );
 
@@ -1681,7 +1895,7 @@ mod tests {
 
         for (i, lang) in languages.iter().enumerate() {
             let mut file = create_test_file(&format!("/test{}.txt", i));
-            file.language = *lang;
+            file.language = lang.clone();
             log_file_symbols(&file, i + 1, i + 2);
         }
     }
@@ -1812,4 +2026,18 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_symbol_ids_use_paths_relative_to_root() {
+        let file = create_test_file("/repo/src/lib.rs");
+        let ids = SymbolIds {
+            root: Path::new("/repo"),
+            strategy: &mother_core::graph::ids::QualifiedNameIds,
+        };
+        let mut symbols = vec![create_symbol_node("", "run", SymbolKind::Function, 1, 3)];
+
+        ids.assign(&mut symbols, &file);
+
+        assert_eq!(symbols[0].id, "src/lib.rs#function#run@test_hash");
+    }
 }
@@ -0,0 +1,214 @@
+//! Definition fallback: Link calls Phase 3 found no reference for
+//!
+//! Phase 3 asks for the references to each scanned symbol, so calls into code
+//! that was not scanned (the standard library, dependencies, excluded files)
+//! leave no edge and the call graph silently stops at the workspace boundary.
+//! With `scan --external-refs`, every call site inside a scanned symbol that
+//! is not among the reference sites Phase 3 saw is looked up with
+//! `textDocument/definition`. Definitions outside the scanned files become
+//! ExternalSymbol placeholders, shared by every site resolving to the same
+//! location, with a REFERENCES edge from the calling symbol. Definitions in
+//! scanned files are left to Phase 3.
+//!
+//! Call sites are found textually: an identifier directly followed by `(`,
+//! other than keywords and the name being declared. Each costs a definition
+//! request, hence the opt-in flag.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use mother_core::graph::convert::lsp_definition_to_external;
+use mother_core::graph::model::ScanFailure;
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{LspReference, LspServerManager};
+use mother_core::scanner::{FileContentCache, Language};
+
+use super::{find_containing_symbol, SymbolsByFile};
+use crate::report::failure;
+use crate::SymbolBuffer;
+
+/// Words followed by `(` that are not calls
+const KEYWORDS: &[&str] = &[
+    "if", "while", "for", "match", "return", "switch", "catch", "elif", "and", "or", "not", "in",
+    "await", "loop", "yield", "with", "assert", "sizeof", "typeof",
+];
+
+/// Words introducing a declaration; the next identifier is not a call
+const DECLARATION_KEYWORDS: &[&str] = &["fn", "def", "function", "func", "class", "struct"];
+
+/// Reference sites Phase 3 already resolved, as (file path, line, column)
+pub(super) type KnownSites = HashSet<(String, u32, u32)>;
+
+/// Results of the definition fallback
+#[derive(Debug, Default)]
+pub(super) struct ExternalResult {
+    pub reference_count: usize,
+    pub error_count: usize,
+    pub failures: Vec<ScanFailure>,
+}
+
+/// Look up the unresolved call sites of the scanned symbols and link those
+/// defined outside the scanned files
+///
+/// # Errors
+/// Returns an error if symbols spilled by Phase 2 cannot be read back.
+pub(super) async fn link(
+    symbols: &SymbolBuffer,
+    symbols_by_file: &SymbolsByFile,
+    known_sites: &KnownSites,
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+) -> Result<ExternalResult> {
+    tracing::info!("Phase 3: Resolving call sites outside the references...");
+    let mut files = HashMap::new();
+    for chunk in symbols.chunks() {
+        for symbol in chunk?.iter() {
+            files
+                .entry(symbol.file_uri.clone())
+                .or_insert_with(|| symbol.language.clone());
+        }
+    }
+
+    let linker = Linker {
+        symbols_by_file,
+        known_sites,
+        client,
+        lsp_manager,
+        cache,
+    };
+    let mut result = ExternalResult::default();
+    for (file_uri, language) in &files {
+        linker.link_file(file_uri, language, &mut result).await;
+    }
+    Ok(result)
+}
+
+/// What linking a call site needs to know about the scan
+struct Linker<'a> {
+    symbols_by_file: &'a SymbolsByFile,
+    known_sites: &'a KnownSites,
+    client: &'a dyn GraphStore,
+    lsp_manager: &'a LspServerManager,
+    cache: &'a FileContentCache,
+}
+
+impl Linker<'_> {
+    /// Link the unresolved call sites of a file
+    async fn link_file(&self, file_uri: &str, language: &Language, result: &mut ExternalResult) {
+        let path = file_uri.strip_prefix("file://").unwrap_or(file_uri);
+        let text = match self.cache.read_to_string(Path::new(path)) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::debug!("Skipping call sites of {}: {}", path, e);
+                return;
+            }
+        };
+
+        for (line, text) in (0u32..).zip(text.lines()) {
+            for (column, name) in call_sites(text) {
+                if self.known_sites.contains(&(path.to_string(), line, column)) {
+                    continue;
+                }
+                let site = LspReference {
+                    file: PathBuf::from(path),
+                    line,
+                    start_col: column,
+                    end_col: column,
+                };
+                match self.link_site(file_uri, language, &site, name).await {
+                    Ok(true) => result.reference_count += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::debug!("Definition lookup failed in {}: {}", path, e);
+                        result.error_count += 1;
+                        let e = e.context(format!("line {}, column {}", line + 1, column + 1));
+                        let request = "textDocument/definition";
+                        let failure = failure("Phase 3", Path::new(path), request, &e);
+                        result.failures.push(failure);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Link a call site in a scanned symbol to its definition if that lies
+    /// outside the scanned files; returns whether an edge was stored
+    async fn link_site(
+        &self,
+        file_uri: &str,
+        language: &Language,
+        site: &LspReference,
+        name: &str,
+    ) -> Result<bool> {
+        let Some(source_id) = find_containing_symbol(site, self.symbols_by_file) else {
+            return Ok(false);
+        };
+        super::super::ensure_open(self.lsp_manager, self.cache, language, file_uri).await?;
+        let file_uri = file_uri.to_string();
+        let (line, column) = (site.line, site.start_col);
+        let definitions = self
+            .lsp_manager
+            .with_client(language, |lsp_client| {
+                Box::pin(async move { lsp_client.definition(&file_uri, line, column).await })
+            })
+            .await?;
+
+        let Some(definition) = definitions.into_iter().next() else {
+            return Ok(false);
+        };
+        let definition_path = definition.file.display().to_string();
+        if self.symbols_by_file.contains_key(&definition_path) {
+            return Ok(false);
+        }
+        let target = lsp_definition_to_external(name, &definition);
+        Ok(self
+            .client
+            .create_external_reference(&source_id, &target, line, column)
+            .await
+            .is_ok())
+    }
+}
+
+/// Call sites on a line: identifiers directly followed by `(`, with their
+/// column in UTF-16 code units as LSP positions count them
+pub(super) fn call_sites(line: &str) -> Vec<(u32, &str)> {
+    let mut sites = Vec::new();
+    let mut previous_word = "";
+    let mut column = 0u32;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_alphabetic() || c == '_') {
+            if !c.is_whitespace() {
+                previous_word = "";
+            }
+            column += c.len_utf16() as u32;
+            continue;
+        }
+
+        let start_column = column;
+        let mut end = start + c.len_utf8();
+        column += c.len_utf16() as u32;
+        while let Some(&(i, next)) = chars.peek() {
+            if !(next.is_alphanumeric() || next == '_') {
+                break;
+            }
+            end = i + next.len_utf8();
+            column += next.len_utf16() as u32;
+            chars.next();
+        }
+
+        let word = &line[start..end];
+        let is_call = line[end..].starts_with('(')
+            && !KEYWORDS.contains(&word)
+            && !DECLARATION_KEYWORDS.contains(&previous_word);
+        if is_call {
+            sites.push((start_column, word));
+        }
+        previous_word = word;
+    }
+
+    sites
+}
@@ -0,0 +1,458 @@
+//! Phase 3: Extract references and create edges
+//!
+//! Symbols are grouped by language into pipelines that run concurrently,
+//! each talking to its language's server, while a single writer stores the
+//! edges they find in batches.
+//!
+//! With a reference cap, symbols with more references than the cap store a
+//! sample (see the `sample` submodule) and are marked as truncated with
+//! their total count.
+//!
+//! Files whose content was scanned in an earlier commit are reused as is:
+//! their symbols and the reference edges from them are already stored, so
+//! their references are not queried again. Their stored symbols still join
+//! the lookup table, so references from them into the new symbols get a
+//! containing symbol.
+//!
+//! Symbols Phase 2 spilled to disk are read back a chunk at a time: the
+//! pipelines finish a chunk before the next one is read, so only one chunk
+//! of symbols is held at once. The lookup table placing references keeps
+//! the ID and lines of every symbol, by file.
+//!
+//! With `--external-refs`, the `external` submodule then resolves the call
+//! sites no reference covered through go-to-definition, linking calls into
+//! code outside the scan to ExternalSymbol placeholders.
+
+mod external;
+mod sample;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Result;
+use futures::future;
+use mother_core::graph::model::{Edge, EdgeKind, ScanFailure, SymbolNode};
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{LspReference, LspServerManager};
+use mother_core::progress::{Progress, ScanPhase};
+use mother_core::scanner::{FileContentCache, Language};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing::info;
+
+use self::external::KnownSites;
+use super::metrics::PhaseCounts;
+use super::report::failure;
+use super::{ScanOptions, SymbolBuffer, SymbolInfo};
+
+/// IDs and 0-indexed line spans of the symbols of each file, by path
+pub(super) type SymbolsByFile = HashMap<String, Vec<(String, u32, u32)>>;
+
+/// Most edges the writer stores in one batch
+const EDGE_BATCH_SIZE: usize = 500;
+
+/// Edges the pipelines queue for the writer before waiting on it
+const EDGE_QUEUE_SIZE: usize = 4 * EDGE_BATCH_SIZE;
+
+/// Results from Phase 3
+pub struct Phase3Result {
+    pub reference_count: usize,
+    /// References to ExternalSymbol placeholders, with `--external-refs`
+    pub external_count: usize,
+    pub error_count: usize,
+    /// The symbols and call sites whose lookups failed
+    pub failures: Vec<ScanFailure>,
+}
+
+impl Phase3Result {
+    /// Work done on `attempted` symbols, for throughput metrics
+    pub(crate) fn counts(&self, attempted: usize) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: attempted.saturating_sub(self.error_count),
+            attempted,
+            errors: self.error_count,
+        }
+    }
+}
+
+/// Run Phase 3: Extract references and create edges
+///
+/// [`ScanOptions::max_references`] caps the references stored per symbol;
+/// `None` stores all. [`ScanOptions::external_refs`] enables the
+/// go-to-definition fallback for the remaining call sites. `reused` are the
+/// symbols of the commit's reused files (see [`reused_symbols`]), which are
+/// only used to place references. Files closed by an open document limit
+/// are read again through `cache`.
+///
+/// Each language's symbols run in their own pipeline, concurrently with the
+/// others, querying that language's server one symbol at a time. The
+/// pipelines send their edges over a channel to a single writer, which stores
+/// them in batches. Past the LSP manager's deadline the pipelines stop
+/// before their next symbol and the writer stores the edges already sent.
+///
+/// # Errors
+/// Returns an error if symbols spilled by Phase 2 cannot be read back.
+pub async fn run(
+    symbols: &SymbolBuffer,
+    reused: &[SymbolNode],
+    client: &dyn GraphStore,
+    lsp_manager: &LspServerManager,
+    cache: &FileContentCache,
+    options: &ScanOptions,
+) -> Result<Phase3Result> {
+    let progress = &options.progress;
+    info!(
+        "Phase 3: Extracting references for {} symbols...",
+        symbols.len()
+    );
+    progress.start(ScanPhase::References, symbols.len());
+
+    let mut symbols_by_file = symbol_lookup_table(symbols)?;
+    add_reused_symbols(&mut symbols_by_file, reused);
+    let pipeline = Pipeline {
+        symbols_by_file: &symbols_by_file,
+        client,
+        lsp_manager,
+        cache,
+        max_references: options.max_references,
+        progress,
+    };
+
+    let (edges, queue) = mpsc::channel(EDGE_QUEUE_SIZE);
+    let (results, reference_count) = tokio::join!(
+        pipeline.run_chunks(symbols, edges),
+        write_edges(client, queue)
+    );
+
+    let mut known_sites = KnownSites::new();
+    let mut error_count = 0;
+    let mut failures = Vec::new();
+    for mut result in results? {
+        known_sites.extend(result.known_sites);
+        error_count += result.error_count;
+        failures.append(&mut result.failures);
+    }
+
+    let mut external = if options.external_refs && !lsp_manager.past_deadline() {
+        external::link(
+            symbols,
+            &symbols_by_file,
+            &known_sites,
+            client,
+            lsp_manager,
+            cache,
+        )
+        .await?
+    } else {
+        external::ExternalResult::default()
+    };
+    error_count += external.error_count;
+    failures.append(&mut external.failures);
+    progress.finish(ScanPhase::References);
+
+    if error_count > 0 {
+        tracing::warn!("Phase 3: {} reference lookups failed", error_count);
+    }
+
+    Ok(Phase3Result {
+        reference_count,
+        external_count: external.reference_count,
+        error_count,
+        failures,
+    })
+}
+
+/// Symbols grouped by language, in the order each language first appears
+pub(super) fn group_by_language(symbols: &[SymbolInfo]) -> Vec<Vec<&SymbolInfo>> {
+    let mut groups: Vec<Vec<&SymbolInfo>> = Vec::new();
+    let mut index: HashMap<&Language, usize> = HashMap::new();
+    for symbol in symbols {
+        let i = *index.entry(&symbol.language).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[i].push(symbol);
+    }
+    groups
+}
+
+/// What the pipeline of one language shares with the others
+#[derive(Clone, Copy)]
+struct Pipeline<'a> {
+    symbols_by_file: &'a SymbolsByFile,
+    client: &'a dyn GraphStore,
+    lsp_manager: &'a LspServerManager,
+    cache: &'a FileContentCache,
+    max_references: Option<usize>,
+    progress: &'a Progress,
+}
+
+/// Lookups of one language's pipeline
+#[derive(Default)]
+struct PipelineResult {
+    known_sites: KnownSites,
+    error_count: usize,
+    failures: Vec<ScanFailure>,
+}
+
+impl Pipeline<'_> {
+    /// Run a pipeline per language over each chunk of `symbols` in turn,
+    /// sending their edges to the writer
+    async fn run_chunks(
+        self,
+        symbols: &SymbolBuffer,
+        edges: Sender<Edge>,
+    ) -> Result<Vec<PipelineResult>> {
+        let mut results = Vec::new();
+        for chunk in symbols.chunks() {
+            let chunk = chunk?;
+            let pipelines = group_by_language(&chunk)
+                .into_iter()
+                .map(|group| self.run(group, edges.clone()));
+            results.extend(future::join_all(pipelines).await);
+        }
+        Ok(results)
+    }
+
+    /// Look up the references of `symbols`, all of one language, sending
+    /// their edges to the writer
+    async fn run(self, symbols: Vec<&SymbolInfo>, edges: Sender<Edge>) -> PipelineResult {
+        let mut result = PipelineResult::default();
+        for symbol_info in symbols {
+            if self.lsp_manager.past_deadline() {
+                break;
+            }
+            let Some(symbol_edges) = self.symbol_edges(symbol_info, &mut result).await else {
+                result.error_count += 1;
+                continue;
+            };
+            let count = symbol_edges.len();
+            for edge in symbol_edges {
+                if edges.send(edge).await.is_err() {
+                    break;
+                }
+            }
+            self.progress.advance(ScanPhase::References, 0, count);
+        }
+        result
+    }
+
+    /// Reference edges to a single symbol, or `None` if the lookup failed
+    ///
+    /// Every reference site the server reports is added to the result's
+    /// known sites, and a failed lookup to its failures.
+    async fn symbol_edges(
+        &self,
+        symbol_info: &SymbolInfo,
+        result: &mut PipelineResult,
+    ) -> Option<Vec<Edge>> {
+        let refs = match self.references(symbol_info).await {
+            Ok(r) => r,
+            Err(e) => {
+                let file = Path::new(symbol_info.file_uri.trim_start_matches("file://"));
+                result.failures.push(ScanFailure {
+                    symbol: Some(symbol_info.id.clone()),
+                    ..failure("Phase 3", file, "textDocument/references", &e)
+                });
+                return None;
+            }
+        };
+        result.known_sites.extend(
+            refs.iter()
+                .map(|r| (r.file.display().to_string(), r.line, r.start_col)),
+        );
+        let refs = cap_references(refs, symbol_info, self.client, self.max_references).await;
+        Some(reference_edges(&refs, symbol_info, self.symbols_by_file))
+    }
+
+    /// References the server reports for a symbol, opening its file again
+    /// if the open document limit closed it
+    async fn references(&self, symbol_info: &SymbolInfo) -> Result<Vec<LspReference>> {
+        let file_uri = symbol_info.file_uri.clone();
+        let (line, col) = (symbol_info.start_line, symbol_info.start_col);
+        let language = &symbol_info.language;
+        super::ensure_open(self.lsp_manager, self.cache, language, &file_uri).await?;
+        self.lsp_manager
+            .with_client(&symbol_info.language, |lsp_client| {
+                Box::pin(async move { lsp_client.references(&file_uri, line, col, true).await })
+            })
+            .await
+    }
+}
+
+/// Store the edges the pipelines send, a batch of what is queued at a time,
+/// until every pipeline is done
+///
+/// Returns the number of edges stored; a failed batch is logged and skipped.
+pub(super) async fn write_edges(client: &dyn GraphStore, mut queue: Receiver<Edge>) -> usize {
+    let mut written = 0;
+    let mut batch = Vec::with_capacity(EDGE_BATCH_SIZE);
+    while queue.recv_many(&mut batch, EDGE_BATCH_SIZE).await > 0 {
+        match client.create_edges_batch(&batch).await {
+            Ok(()) => written += batch.len(),
+            Err(e) => tracing::warn!("Failed to store {} reference edges: {}", batch.len(), e),
+        }
+        batch.clear();
+    }
+    written
+}
+
+/// Sample references above the cap and record the symbol's total
+async fn cap_references(
+    refs: Vec<LspReference>,
+    symbol_info: &SymbolInfo,
+    client: &dyn GraphStore,
+    max_references: Option<usize>,
+) -> Vec<LspReference> {
+    let Some(cap) = max_references.filter(|cap| refs.len() > *cap) else {
+        return refs;
+    };
+
+    let total = refs.len();
+    tracing::debug!(
+        "Sampling {} of {} references to {}",
+        cap,
+        total,
+        symbol_info.id
+    );
+    if let Err(e) = client
+        .mark_references_truncated(&symbol_info.id, total)
+        .await
+    {
+        tracing::warn!(
+            "Failed to record reference total of {}: {}",
+            symbol_info.id,
+            e
+        );
+    }
+    sample::sample(refs, cap, &symbol_info.id)
+}
+
+/// Build a lookup table from file path to symbols in that file
+pub(super) fn build_symbol_lookup_table(symbols: &[SymbolInfo]) -> SymbolsByFile {
+    let mut symbols_by_file = HashMap::new();
+    add_symbols(&mut symbols_by_file, symbols);
+    symbols_by_file
+}
+
+/// Build a lookup table from file path to symbols in that file, reading
+/// the buffer a chunk at a time
+///
+/// # Errors
+/// Returns an error if spilled symbols cannot be read back.
+fn symbol_lookup_table(symbols: &SymbolBuffer) -> Result<SymbolsByFile> {
+    let mut symbols_by_file = HashMap::new();
+    for chunk in symbols.chunks() {
+        add_symbols(&mut symbols_by_file, &chunk?);
+    }
+    Ok(symbols_by_file)
+}
+
+/// Add symbols to a lookup table, by the path of their file
+fn add_symbols(symbols_by_file: &mut SymbolsByFile, symbols: &[SymbolInfo]) {
+    for sym in symbols {
+        symbols_by_file
+            .entry(file_path(sym).to_string())
+            .or_default()
+            .push((sym.id.clone(), sym.start_line, sym.end_line));
+    }
+}
+
+/// Path of a symbol's file, without the `file://` scheme
+fn file_path(symbol: &SymbolInfo) -> &str {
+    symbol
+        .file_uri
+        .strip_prefix("file://")
+        .unwrap_or(&symbol.file_uri)
+}
+
+/// Symbols of the commit's files outside `symbols`, i.e. the files reused
+/// from earlier commits, or nothing if there are no new symbols to find
+/// references to
+///
+/// # Errors
+/// Returns an error if the symbols cannot be read.
+pub async fn reused_symbols(
+    client: &dyn GraphStore,
+    commit_sha: &str,
+    symbols: &SymbolBuffer,
+) -> Result<Vec<SymbolNode>> {
+    if symbols.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut new_files = HashSet::new();
+    for chunk in symbols.chunks() {
+        new_files.extend(chunk?.iter().map(|s| file_path(s).to_string()));
+    }
+    Ok(client
+        .symbols_at_commit(commit_sha)
+        .await?
+        .into_iter()
+        .filter(|s| !new_files.contains(&s.file_path))
+        .collect())
+}
+
+/// Add stored symbols to a lookup table, converting their 1-indexed lines
+/// back to the 0-indexed lines LSP references use
+pub(super) fn add_reused_symbols(symbols_by_file: &mut SymbolsByFile, reused: &[SymbolNode]) {
+    for sym in reused {
+        symbols_by_file
+            .entry(sym.file_path.clone())
+            .or_default()
+            .push((
+                sym.id.clone(),
+                sym.start_line.saturating_sub(1),
+                sym.end_line.saturating_sub(1),
+            ));
+    }
+}
+
+/// Reference edges for a symbol's references, from the symbols containing
+/// them
+fn reference_edges(
+    refs: &[mother_core::lsp::LspReference],
+    symbol_info: &SymbolInfo,
+    symbols_by_file: &SymbolsByFile,
+) -> Vec<Edge> {
+    refs.iter()
+        .filter_map(|reference| {
+            let from_id = find_containing_symbol(reference, symbols_by_file)?;
+            (from_id != symbol_info.id).then(|| reference_edge(from_id, &symbol_info.id, reference))
+        })
+        .collect()
+}
+
+/// Find the symbol that contains a reference location
+pub(super) fn find_containing_symbol(
+    reference: &mother_core::lsp::LspReference,
+    symbols_by_file: &SymbolsByFile,
+) -> Option<String> {
+    let ref_file = reference.file.display().to_string();
+    let ref_line = reference.line;
+
+    symbols_by_file.get(&ref_file).and_then(|symbols| {
+        symbols
+            .iter()
+            .filter(|(_, start, end)| ref_line >= *start && ref_line <= *end)
+            .min_by_key(|(_, start, end)| end - start)
+            .map(|(id, _, _)| id.clone())
+    })
+}
+
+/// A REFERENCES edge at a reference's location
+fn reference_edge(
+    from_id: String,
+    to_id: &str,
+    reference: &mother_core::lsp::LspReference,
+) -> Edge {
+    Edge {
+        source_id: from_id,
+        target_id: to_id.to_string(),
+        kind: EdgeKind::References,
+        line: Some(reference.line),
+        column: Some(reference.start_col),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+//! Reference sampling: Cap the references stored for one symbol
+//!
+//! Symbols with tens of thousands of references (log macros, base classes)
+//! would otherwise dominate Phase 3. Above the cap a fixed-size sample is
+//! kept with reservoir sampling, seeded from the symbol id so rescanning the
+//! same code keeps the same sample.
+
+use crate::rng::SplitMix64;
+
+/// Keep at most `cap` of `refs`, chosen uniformly by reservoir sampling
+///
+/// Sampled items stay in their original order.
+pub(crate) fn sample<T>(refs: Vec<T>, cap: usize, seed: &str) -> Vec<T> {
+    if refs.len() <= cap {
+        return refs;
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut reservoir: Vec<usize> = (0..cap).collect();
+    for i in cap..refs.len() {
+        let j = rng.below(i + 1);
+        if j < cap {
+            reservoir[j] = i;
+        }
+    }
+    reservoir.sort_unstable();
+
+    let mut keep = reservoir.into_iter().peekable();
+    refs.into_iter()
+        .enumerate()
+        .filter(|(i, _)| keep.next_if_eq(i).is_some())
+        .map(|(_, item)| item)
+        .collect()
+}
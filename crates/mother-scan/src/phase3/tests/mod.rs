@@ -1,8 +1,13 @@
 //! Tests for phase3 module
 
 mod tests_build_symbol_lookup;
+mod tests_document_limit;
 mod tests_edge_creation;
+mod tests_external;
 mod tests_find_containing_symbol;
+mod tests_pipelines;
 mod tests_process_symbol_references;
 mod tests_reference_edge_logic;
 mod tests_reference_mapping;
+mod tests_reused_symbols;
+mod tests_sample;
@@ -0,0 +1,249 @@
+//! Tests for reference lookups under an open document limit
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashSet;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{AsyncWrite, StreamExt, TryStreamExt};
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::{
+    Connection, LspServerConfig, LspServerManager, LspTransport, Readiness, Url,
+};
+use mother_core::scanner::{FileContentCache, Language};
+use serde_json::{json, Value};
+
+use super::super::super::ensure_open;
+use super::super::{run, ScanOptions, SymbolBuffer, SymbolInfo};
+
+/// A server that only finds references in documents open on it, the way
+/// servers answer for files they were never told about
+struct OpenOnlyTransport;
+
+impl LspTransport for OpenOnlyTransport {
+    fn connect(&self, _: &LspServerConfig) -> Result<Connection> {
+        let (to_client, input) = mpsc::unbounded();
+        let (output, from_client) = mpsc::unbounded();
+        tokio::spawn(serve(from_client, to_client));
+        Ok(Connection {
+            input: Box::new(input.into_async_read()),
+            output: Box::new(ChannelWriter(output)),
+            process: None,
+        })
+    }
+}
+
+async fn serve(
+    mut from_client: UnboundedReceiver<Vec<u8>>,
+    to_client: UnboundedSender<io::Result<Vec<u8>>>,
+) {
+    let mut open = HashSet::new();
+    let mut buffer = Vec::new();
+    while let Some(bytes) = from_client.next().await {
+        buffer.extend(bytes);
+        while let Some(message) = take_message(&mut buffer) {
+            let method = message["method"].as_str().unwrap_or_default();
+            let uri = message["params"]["textDocument"]["uri"].clone();
+            let result = match method {
+                "textDocument/didOpen" => {
+                    open.insert(uri.to_string());
+                    continue;
+                }
+                "textDocument/didClose" => {
+                    open.remove(&uri.to_string());
+                    continue;
+                }
+                "initialize" => json!({"capabilities": {}}),
+                // The use of each file's first symbol is on line 4
+                "textDocument/references" if open.contains(&uri.to_string()) => {
+                    json!([{"uri": uri, "range": {
+                        "start": {"line": 4, "character": 4},
+                        "end": {"line": 4, "character": 7},
+                    }}])
+                }
+                _ => Value::Null,
+            };
+            let Some(id) = message.get("id") else {
+                continue;
+            };
+            let body = json!({"jsonrpc": "2.0", "id": id, "result": result}).to_string();
+            let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+            if to_client.unbounded_send(Ok(frame.into_bytes())).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Take the first whole message the client sent off `buffer`
+fn take_message(buffer: &mut Vec<u8>) -> Option<Value> {
+    let text = String::from_utf8_lossy(buffer).into_owned();
+    let header_end = text.find("\r\n\r\n")?;
+    let length: usize = text[..header_end]
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Length: "))?
+        .trim()
+        .parse()
+        .ok()?;
+    let body_start = header_end + 4;
+    if buffer.len() < body_start + length {
+        return None;
+    }
+    let body: Vec<u8> = buffer
+        .drain(..body_start + length)
+        .skip(body_start)
+        .collect();
+    serde_json::from_slice(&body).ok()
+}
+
+struct ChannelWriter(UnboundedSender<Vec<u8>>);
+
+impl AsyncWrite for ChannelWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(
+            self.0
+                .unbounded_send(buf.to_vec())
+                .map(|()| buf.len())
+                .map_err(|_| io::ErrorKind::BrokenPipe.into()),
+        )
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn stored_symbol(id: &str, path: &Path, start_line: u32, end_line: u32) -> SymbolNode {
+    SymbolNode::new(
+        id,
+        id,
+        SymbolKind::Function,
+        path.display().to_string(),
+        start_line,
+        end_line,
+    )
+}
+
+/// A manager keeping one Rust document open at a time on an
+/// [`OpenOnlyTransport`] server
+fn open_only_manager(root: &Path) -> LspServerManager {
+    let mut lsp_manager = LspServerManager::new(root);
+    lsp_manager.register_server(LspServerConfig {
+        language: Language::Rust,
+        command: "open-only".to_string(),
+        args: vec![],
+        root_path: root.to_path_buf(),
+        init_options: None,
+    });
+    lsp_manager.set_readiness(Language::Rust, Readiness::Immediate);
+    lsp_manager.set_open_document_limit(Language::Rust, NonZeroUsize::MIN);
+    lsp_manager.set_transport(Arc::new(OpenOnlyTransport));
+    lsp_manager
+}
+
+#[tokio::test]
+async fn test_references_are_found_in_files_the_limit_closed() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+
+    let lsp_manager = open_only_manager(dir.path());
+
+    // Files are read again through the scan's cache
+    let cache = FileContentCache::default();
+    let mut symbols = Vec::new();
+    for name in ["a", "b", "c"] {
+        let path = dir.path().join(format!("{name}.rs"));
+        std::fs::write(&path, "fn f() {}\n\nfn g() {\n    f();\n}\n").unwrap();
+        let hash = format!("hash-{name}");
+        store
+            .create_file_if_new(&path.display().to_string(), &hash, "rust", "abc123")
+            .await
+            .unwrap();
+        let (f, g) = (format!("{name}::f"), format!("{name}::g"));
+        store
+            .create_symbols_batch(
+                &[
+                    stored_symbol(&f, &path, 0, 0),
+                    stored_symbol(&g, &path, 2, 4),
+                ],
+                &hash,
+            )
+            .await
+            .unwrap();
+
+        // Phase 2 opens each file as it extracts its symbols
+        let file_uri = format!("file://{}", path.display());
+        ensure_open(&lsp_manager, &cache, &Language::Rust, &file_uri)
+            .await
+            .unwrap();
+        for (id, start_line, end_line) in [(f, 0, 0), (g, 2, 4)] {
+            symbols.push(SymbolInfo {
+                id,
+                file_uri: file_uri.clone(),
+                start_line,
+                end_line,
+                start_col: 3,
+                language: Language::Rust,
+            });
+        }
+    }
+    // Only the last file is still open
+    assert_eq!(lsp_manager.open_document_count(&Language::Rust), 1);
+
+    let result = run(
+        &SymbolBuffer::from(symbols),
+        &[],
+        &store,
+        &lsp_manager,
+        &cache,
+        &ScanOptions::default(),
+    )
+    .await
+    .unwrap();
+    lsp_manager.shutdown_all().await.unwrap();
+
+    assert_eq!(result.error_count, 0);
+    assert_eq!(result.reference_count, 3);
+    assert_eq!(store.stats().await.unwrap().references, 3);
+    assert_eq!((cache.stats().misses, cache.stats().hits), (3, 3));
+}
+
+#[tokio::test]
+async fn test_files_are_opened_again_by_percent_encoded_uri() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("my src.rs");
+    std::fs::write(&path, "fn f() {}\n").unwrap();
+    let lsp_manager = open_only_manager(dir.path());
+    let cache = FileContentCache::default();
+
+    let file_uri = Url::from_file_path(&path).unwrap().to_string();
+    assert!(file_uri.ends_with("my%20src.rs"));
+    ensure_open(&lsp_manager, &cache, &Language::Rust, &file_uri)
+        .await
+        .unwrap();
+    assert_eq!(lsp_manager.open_document_count(&Language::Rust), 1);
+    lsp_manager.shutdown_all().await.unwrap();
+
+    assert_eq!(cache.stats().misses, 1);
+}
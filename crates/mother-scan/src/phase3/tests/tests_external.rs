@@ -0,0 +1,36 @@
+//! Tests for finding call sites for the definition fallback
+
+use super::super::external::call_sites;
+
+#[test]
+fn test_call_sites_finds_calls_and_methods() {
+    let sites = call_sites("    let text = fs::read_to_string(path).map(trim);");
+
+    assert_eq!(sites, vec![(19, "read_to_string"), (40, "map")]);
+}
+
+#[test]
+fn test_call_sites_skips_keywords() {
+    assert!(call_sites("    if (ready) { return (1); } while (x) {}").is_empty());
+    assert_eq!(call_sites("match (parse(x)) {"), vec![(7, "parse")]);
+}
+
+#[test]
+fn test_call_sites_skips_declared_names() {
+    assert!(call_sites("pub fn open(path: &str) -> Store {").is_empty());
+    assert!(call_sites("def open(path):").is_empty());
+    assert!(call_sites("async function open(path) {").is_empty());
+}
+
+#[test]
+fn test_call_sites_ignores_names_not_followed_by_paren() {
+    assert!(call_sites("let open = store.open ();").is_empty());
+}
+
+#[test]
+fn test_call_sites_counts_columns_in_utf16() {
+    // 'é' is one UTF-16 unit but two bytes, '😀' two units and four bytes
+    let sites = call_sites("é😀 run()");
+
+    assert_eq!(sites, vec![(4, "run")]);
+}
@@ -0,0 +1,145 @@
+//! Tests for the per-language pipelines and the batched edge writer
+
+#![allow(clippy::unwrap_used)]
+
+use std::num::NonZeroUsize;
+
+use mother_core::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::LspServerManager;
+use mother_core::scanner::{FileContentCache, Language};
+use tokio::sync::mpsc;
+
+use super::super::{group_by_language, run, write_edges, ScanOptions, SymbolBuffer, SymbolInfo};
+
+fn symbol_info(id: &str, language: Language) -> SymbolInfo {
+    SymbolInfo {
+        id: id.to_string(),
+        file_uri: format!("file:///src/{id}"),
+        start_line: 0,
+        end_line: 4,
+        start_col: 3,
+        language,
+    }
+}
+
+fn stored_symbol(id: &str) -> SymbolNode {
+    SymbolNode::new(id, id, SymbolKind::Function, "/src/a.rs", 1, 5)
+}
+
+#[test]
+fn test_symbols_are_grouped_by_language_in_order_of_appearance() {
+    let symbols = [
+        symbol_info("a", Language::Rust),
+        symbol_info("b", Language::Python),
+        symbol_info("c", Language::Rust),
+        symbol_info("d", Language::Other("zig".to_string())),
+    ];
+
+    let groups: Vec<Vec<&str>> = group_by_language(&symbols)
+        .iter()
+        .map(|group| group.iter().map(|s| s.id.as_str()).collect())
+        .collect();
+    assert_eq!(groups, vec![vec!["a", "c"], vec!["b"], vec!["d"]]);
+    assert!(group_by_language(&[]).is_empty());
+}
+
+#[tokio::test]
+async fn test_writer_stores_every_queued_edge_in_batches() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    store
+        .create_file_if_new("/src/a.rs", "hash-a", "rust", "abc123")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(
+            &[stored_symbol("caller"), stored_symbol("callee")],
+            "hash-a",
+        )
+        .await
+        .unwrap();
+
+    let (edges, queue) = mpsc::channel(10);
+    let sender = async move {
+        for line in 0..1200 {
+            let edge = Edge {
+                source_id: "caller".to_string(),
+                target_id: "callee".to_string(),
+                kind: EdgeKind::References,
+                line: Some(line),
+                column: Some(0),
+            };
+            edges.send(edge).await.unwrap();
+        }
+    };
+    let ((), written) = tokio::join!(sender, write_edges(&store, queue));
+
+    assert_eq!(written, 1200);
+    assert_eq!(store.stats().await.unwrap().references, 1200);
+}
+
+#[tokio::test]
+async fn test_failed_lookups_of_every_language_are_merged() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    // No server is registered for these languages, so every lookup fails
+    let lsp_manager = LspServerManager::new("/repo");
+    let symbols = SymbolBuffer::from(vec![
+        symbol_info("a", Language::Other("zig".to_string())),
+        symbol_info("b", Language::Other("nim".to_string())),
+        symbol_info("c", Language::Other("zig".to_string())),
+    ]);
+
+    let result = run(
+        &symbols,
+        &[],
+        &store,
+        &lsp_manager,
+        &FileContentCache::default(),
+        &ScanOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.reference_count, 0);
+    assert_eq!(result.error_count, 3);
+    let mut failed: Vec<&str> = result
+        .failures
+        .iter()
+        .filter_map(|f| f.symbol.as_deref())
+        .collect();
+    failed.sort_unstable();
+    assert_eq!(failed, vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn test_spilled_symbols_are_looked_up_a_chunk_at_a_time() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let lsp_manager = LspServerManager::new("/repo");
+    let mut symbols = SymbolBuffer::new(NonZeroUsize::new(2));
+    for id in ["a", "b", "c", "d", "e"] {
+        symbols.extend(vec![symbol_info(id, Language::Other("zig".to_string()))]);
+    }
+
+    let result = run(
+        &symbols,
+        &[],
+        &store,
+        &lsp_manager,
+        &FileContentCache::default(),
+        &ScanOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    let mut failed: Vec<&str> = result
+        .failures
+        .iter()
+        .filter_map(|f| f.symbol.as_deref())
+        .collect();
+    failed.sort_unstable();
+    assert_eq!(failed, vec!["a", "b", "c", "d", "e"]);
+}
@@ -420,7 +420,7 @@ fn test_multiple_language_support() {
             start_line: 1,
             end_line: 10,
             start_col: 0,
-            language: lang,
+            language: lang.clone(),
         };
 
         // Verify symbol can be created for any language
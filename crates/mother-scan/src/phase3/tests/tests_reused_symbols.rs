@@ -0,0 +1,97 @@
+//! Tests for placing references in symbols of reused files
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::PathBuf;
+
+use mother_core::graph::model::{ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+use mother_core::lsp::LspReference;
+use mother_core::scanner::Language;
+
+use super::super::{
+    add_reused_symbols, build_symbol_lookup_table, find_containing_symbol, reused_symbols,
+    SymbolBuffer, SymbolInfo,
+};
+
+fn stored_symbol(id: &str, file_path: &str, start_line: u32, end_line: u32) -> SymbolNode {
+    SymbolNode::new(
+        id,
+        id,
+        SymbolKind::Function,
+        file_path,
+        start_line,
+        end_line,
+    )
+}
+
+fn new_symbol(id: &str, file_uri: &str) -> SymbolInfo {
+    SymbolInfo {
+        id: id.to_string(),
+        file_uri: file_uri.to_string(),
+        start_line: 0,
+        end_line: 4,
+        start_col: 3,
+        language: Language::Rust,
+    }
+}
+
+#[test]
+fn test_reused_symbols_place_references_on_lsp_lines() {
+    let mut symbols_by_file = build_symbol_lookup_table(&[new_symbol("new", "file:///src/b.rs")]);
+    // Stored lines are 1-indexed: lines 10-20 are LSP lines 9-19
+    add_reused_symbols(
+        &mut symbols_by_file,
+        &[stored_symbol("caller", "/src/a.rs", 10, 20)],
+    );
+
+    let reference = |line| LspReference {
+        file: PathBuf::from("/src/a.rs"),
+        line,
+        start_col: 4,
+        end_col: 7,
+    };
+    assert_eq!(
+        find_containing_symbol(&reference(9), &symbols_by_file).as_deref(),
+        Some("caller")
+    );
+    assert_eq!(
+        find_containing_symbol(&reference(20), &symbols_by_file),
+        None
+    );
+    assert_eq!(symbols_by_file["/src/b.rs"].len(), 1);
+}
+
+#[tokio::test]
+async fn test_reused_symbols_are_the_commits_other_files() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    for (path, hash) in [("/src/a.rs", "hash-a"), ("/src/b.rs", "hash-b")] {
+        store
+            .create_file_if_new(path, hash, "rust", "abc123")
+            .await
+            .unwrap();
+    }
+    store
+        .create_symbols_batch(&[stored_symbol("caller", "/src/a.rs", 1, 5)], "hash-a")
+        .await
+        .unwrap();
+    store
+        .create_symbols_batch(&[stored_symbol("new", "/src/b.rs", 1, 5)], "hash-b")
+        .await
+        .unwrap();
+
+    let new = SymbolBuffer::from(vec![new_symbol("new", "file:///src/b.rs")]);
+    let reused = reused_symbols(&store, "abc123", &new).await.unwrap();
+    let ids: Vec<&str> = reused.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, vec!["caller"]);
+
+    // Without new symbols there are no references to place
+    assert!(reused_symbols(&store, "abc123", &SymbolBuffer::default())
+        .await
+        .unwrap()
+        .is_empty());
+}
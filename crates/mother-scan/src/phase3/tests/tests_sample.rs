@@ -0,0 +1,43 @@
+//! Tests for reservoir sampling of references
+
+use super::super::sample::sample;
+
+#[test]
+fn test_sample_keeps_everything_under_the_cap() {
+    let refs: Vec<u32> = (0..10).collect();
+
+    assert_eq!(sample(refs.clone(), 10, "sym"), refs);
+    assert_eq!(sample(refs.clone(), 50, "sym"), refs);
+}
+
+#[test]
+fn test_sample_caps_and_keeps_order() {
+    let refs: Vec<u32> = (0..10_000).collect();
+
+    let kept = sample(refs, 100, "sym");
+
+    assert_eq!(kept.len(), 100);
+    assert!(kept.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_sample_is_deterministic_per_seed() {
+    let refs: Vec<u32> = (0..1_000).collect();
+
+    assert_eq!(
+        sample(refs.clone(), 10, "sym-a"),
+        sample(refs.clone(), 10, "sym-a")
+    );
+    assert_ne!(sample(refs.clone(), 10, "sym-a"), sample(refs, 10, "sym-b"));
+}
+
+#[test]
+fn test_sample_spreads_over_the_whole_input() {
+    let refs: Vec<u32> = (0..10_000).collect();
+
+    let kept = sample(refs, 200, "sym");
+
+    // A uniform sample has items from both halves, not just the first ones
+    assert!(kept.iter().any(|r| *r < 5_000));
+    assert!(kept.iter().any(|r| *r >= 5_000));
+}
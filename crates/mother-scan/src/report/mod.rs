@@ -0,0 +1,114 @@
+//! Scan report: Record what failed during a scan
+//!
+//! Phases count their errors for the throughput metrics, and also record
+//! each file or symbol they failed on as a [`ScanFailure`]: the phase, the
+//! file, the request that failed and the error. At the end of a scan the
+//! failures are stored with the scan run, summarized in the log and, with
+//! `--report-file`, written to a JSON file.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mother_core::graph::model::{ScanFailure, ScanReport};
+use mother_core::graph::GraphStore;
+use tracing::{info, warn};
+
+/// Failures listed per phase in the summary
+const EXAMPLES_PER_PHASE: usize = 3;
+
+/// A failure of `request` on `file`, with the error and its causes
+pub(crate) fn failure(
+    phase: &str,
+    file: &Path,
+    request: &str,
+    error: &anyhow::Error,
+) -> ScanFailure {
+    ScanFailure {
+        phase: phase.to_string(),
+        file: file.display().to_string(),
+        symbol: None,
+        request: request.to_string(),
+        message: format!("{error:#}"),
+    }
+}
+
+/// Store the report with its scan run, log a summary of it and write it to
+/// `report_file` if given
+///
+/// # Errors
+/// Returns an error if the report cannot be stored or written.
+pub(crate) async fn finish(
+    report: &ScanReport,
+    store: &dyn GraphStore,
+    report_file: Option<&Path>,
+) -> Result<()> {
+    store.set_scan_report(report).await?;
+    log_summary(report);
+    if let Some(path) = report_file {
+        write(report, path)?;
+    }
+    Ok(())
+}
+
+/// Log the failure count of each phase with its first few failures
+pub(crate) fn log_summary(report: &ScanReport) {
+    if report.failures.is_empty() {
+        return;
+    }
+    warn!("{} failures during the scan:", report.failures.len());
+    for line in summary(report) {
+        warn!("{line}");
+    }
+    info!(
+        "Run `mother runs show {}` to list every failure",
+        report.scan_run_id
+    );
+}
+
+/// Indented summary lines: each phase's failure count, followed by its
+/// first few failures
+pub(crate) fn summary(report: &ScanReport) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (phase, count) in report.failures_by_phase() {
+        lines.push(format!("  {phase}: {count} failed"));
+        let failures = report.failures.iter().filter(|f| f.phase == phase);
+        lines.extend(
+            failures
+                .take(EXAMPLES_PER_PHASE)
+                .map(|f| format!("    {}", describe_failure(f))),
+        );
+        if count > EXAMPLES_PER_PHASE {
+            lines.push(format!("    ... and {} more", count - EXAMPLES_PER_PHASE));
+        }
+    }
+    lines
+}
+
+/// One line naming what failed and why
+pub fn describe_failure(failure: &ScanFailure) -> String {
+    match &failure.symbol {
+        Some(symbol) => format!(
+            "{} ({}) {}: {}",
+            failure.file, symbol, failure.request, failure.message
+        ),
+        None => format!("{} {}: {}", failure.file, failure.request, failure.message),
+    }
+}
+
+/// Write the report as pretty-printed JSON
+///
+/// # Errors
+/// Returns an error if the file cannot be created or written.
+pub(crate) fn write(report: &ScanReport, path: &Path) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), report)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    info!("Wrote failure report to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
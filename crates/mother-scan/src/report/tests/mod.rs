@@ -0,0 +1,3 @@
+//! Tests for the scan report module
+
+mod tests_report;
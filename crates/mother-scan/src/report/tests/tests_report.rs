@@ -0,0 +1,127 @@
+//! Tests for storing, summarizing and writing scan reports
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use mother_core::graph::model::{ScanFailure, ScanReport, ScanRun};
+use mother_core::graph::{GraphStore, SqliteStore};
+use tempfile::TempDir;
+
+use super::super::{describe_failure, failure, finish, summary};
+
+/// A report of `failures` for `scan_run_id`
+fn report(scan_run_id: &str, failures: Vec<ScanFailure>) -> ScanReport {
+    ScanReport {
+        scan_run_id: scan_run_id.to_string(),
+        commit_sha: "abc123".to_string(),
+        failures,
+        completion: None,
+        file_cache: None,
+        skipped: Vec::new(),
+    }
+}
+
+#[test]
+fn test_failure_keeps_the_error_causes() {
+    let error = anyhow!("request timed out").context("references of parse");
+    let failure = failure(
+        "Phase 3",
+        Path::new("/repo/src/parse.rs"),
+        "textDocument/references",
+        &error,
+    );
+
+    assert_eq!(failure.file, "/repo/src/parse.rs");
+    assert_eq!(failure.message, "references of parse: request timed out");
+    assert_eq!(
+        describe_failure(&failure),
+        "/repo/src/parse.rs textDocument/references: references of parse: request timed out"
+    );
+
+    let failure = ScanFailure {
+        symbol: Some("sym-1".to_string()),
+        ..failure
+    };
+    assert!(describe_failure(&failure).starts_with("/repo/src/parse.rs (sym-1) "));
+}
+
+#[tokio::test]
+async fn test_finish_stores_and_writes_the_report() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    store.create_scan_run(&run).await.unwrap();
+    let error = anyhow!("server exited");
+    let failures = (0..5)
+        .map(|i| {
+            let file = format!("/repo/src/f{i}.rs");
+            failure(
+                "Phase 2",
+                Path::new(&file),
+                "textDocument/documentSymbol",
+                &error,
+            )
+        })
+        .collect();
+    let report = report(&run.id, failures);
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.json");
+
+    finish(&report, &store, Some(&path)).await.unwrap();
+
+    assert_eq!(
+        store.scan_report(&run.id).await.unwrap(),
+        Some(report.clone())
+    );
+    let written: ScanReport =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written, report);
+}
+
+#[tokio::test]
+async fn test_finish_fails_on_an_unwritable_report_file() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("missing").join("report.json");
+
+    let err = finish(&report("run", Vec::new()), &store, Some(&path))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().starts_with("Failed to create"));
+}
+
+#[test]
+fn test_summary_lists_a_few_failures_per_phase() {
+    let error = anyhow!("timed out");
+    let mut failures: Vec<ScanFailure> = (0..5)
+        .map(|i| {
+            let file = format!("/repo/f{i}.rs");
+            failure(
+                "Phase 3",
+                Path::new(&file),
+                "textDocument/references",
+                &error,
+            )
+        })
+        .collect();
+    failures.insert(
+        0,
+        failure("Phase 1", Path::new("/repo/a.rs"), "open file", &error),
+    );
+
+    assert_eq!(
+        summary(&report("run", failures)),
+        [
+            "  Phase 1: 1 failed",
+            "    /repo/a.rs open file: timed out",
+            "  Phase 3: 5 failed",
+            "    /repo/f0.rs textDocument/references: timed out",
+            "    /repo/f1.rs textDocument/references: timed out",
+            "    /repo/f2.rs textDocument/references: timed out",
+            "    ... and 2 more",
+        ]
+    );
+    assert!(summary(&report("run", Vec::new())).is_empty());
+}
@@ -0,0 +1,74 @@
+//! Repositories: Packages the scanned repository defines and depends on
+//!
+//! Runs after the contracts pass. The package manifests the scanner found
+//! are read by [`mother_core::import::manifests`] into the repository's
+//! Repository node, replacing the one stored by an earlier scan of the same
+//! path. The DEPENDS_ON edges between all stored repositories are then
+//! recomputed, so repositories ingested in any order link up once all of
+//! them are scanned.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use mother_core::graph::GraphStore;
+use mother_core::import::manifests::{read_manifest, repository, repository_dependencies};
+use tracing::{debug, info};
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the repositories pass
+#[derive(Debug, Default)]
+pub struct RepositoriesResult {
+    pub manifest_count: usize,
+    /// Packages the repository defines
+    pub package_count: usize,
+    /// DEPENDS_ON edges between all stored repositories
+    pub dependency_count: usize,
+}
+
+impl RepositoriesResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        PhaseCounts {
+            files: self.manifest_count,
+            symbols: 0,
+            attempted: self.manifest_count,
+            errors: 0,
+        }
+    }
+}
+
+/// Store the repository at `repo_path` from its `manifests`, then relink
+/// all stored repositories
+pub async fn run(
+    client: &dyn GraphStore,
+    repo_path: &str,
+    manifests: &[PathBuf],
+) -> Result<RepositoriesResult> {
+    let mut read = Vec::new();
+    for path in manifests {
+        match std::fs::read_to_string(path) {
+            Ok(text) => read.push(read_manifest(path, &text)),
+            Err(e) => debug!("Skipping manifest {}: {}", path.display(), e),
+        }
+    }
+    let scanned = repository(repo_path, &read);
+    client.upsert_repository(&scanned).await?;
+
+    let dependencies = repository_dependencies(&client.repositories().await?);
+    client.set_repository_dependencies(&dependencies).await?;
+
+    let result = RepositoriesResult {
+        manifest_count: manifests.len(),
+        package_count: scanned.packages.len(),
+        dependency_count: dependencies.len(),
+    };
+    info!(
+        "Stored {} packages from {} manifests ({} repository dependencies)",
+        result.package_count, result.manifest_count, result.dependency_count
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,50 @@
+//! Tests for storing repositories and their dependencies
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+
+use mother_core::graph::model::RepositoryDependency;
+use mother_core::graph::{GraphStore, SqliteStore};
+use tempfile::TempDir;
+
+use super::super::run;
+
+#[tokio::test]
+async fn test_run_links_repositories_scanned_in_any_order() {
+    let app = TempDir::new().unwrap();
+    let lib = TempDir::new().unwrap();
+    fs::write(
+        app.path().join("Cargo.toml"),
+        "[package]\nname = \"app\"\n[dependencies]\nshared = \"1\"\nserde = \"1\"\n",
+    )
+    .unwrap();
+    fs::write(
+        lib.path().join("Cargo.toml"),
+        "[package]\nname = \"shared\"\n",
+    )
+    .unwrap();
+    let (app_path, lib_path) = (
+        app.path().display().to_string(),
+        lib.path().display().to_string(),
+    );
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let first = run(&store, &app_path, &[app.path().join("Cargo.toml")])
+        .await
+        .unwrap();
+    assert_eq!((first.package_count, first.dependency_count), (1, 0));
+
+    let second = run(&store, &lib_path, &[lib.path().join("Cargo.toml")])
+        .await
+        .unwrap();
+    assert_eq!(second.dependency_count, 1);
+    assert_eq!(
+        store.repository_dependencies().await.unwrap(),
+        [RepositoryDependency {
+            from: app_path,
+            to: lib_path,
+            packages: vec!["cargo:shared".to_string()],
+        }]
+    );
+}
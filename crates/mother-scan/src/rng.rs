@@ -0,0 +1,40 @@
+//! Random numbers: A small deterministic generator shared by the scan phases
+
+/// Small deterministic generator, enough for sampling and fault injection
+/// without a `rand` dependency
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Seed from the FNV-1a hash of a string
+    pub(crate) fn new(seed: &str) -> Self {
+        let hash = seed.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self(hash)
+    }
+
+    /// Seed from a number
+    pub(crate) fn from_seed(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-enough index below `n`
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        // usize always fits in u64 on supported targets, and the result is < n
+        (self.next() % n as u64) as usize
+    }
+
+    /// Whether an event with probability `rate` (0.0 to 1.0) happens
+    pub(crate) fn chance(&mut self, rate: f64) -> bool {
+        // The top 53 bits give a uniform float in [0, 1)
+        ((self.next() >> 11) as f64 / (1_u64 << 53) as f64) < rate
+    }
+}
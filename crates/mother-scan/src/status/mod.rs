@@ -0,0 +1,134 @@
+//! Scan status: Track whether a scan run finished writing its commit
+//!
+//! A run is recorded as in progress before any file is written and marked
+//! completed or failed once the scan ends, or partial when it ran out of
+//! time. Runs that never finish keep the in-progress status, so queries
+//! resolving the latest commit skip them along with failed and partial
+//! runs. A commit whose runs all ended incomplete is scanned again: its
+//! runs are deleted with the data only they kept alive first. A run still
+//! in progress is left alone until it is older than [`STALE_AFTER`], after
+//! which it is taken to have been killed.
+//!
+//! Phase 2 stores each new file with its symbols, metrics and lineage edges
+//! in one transaction, so a scan that dies partway leaves no file half
+//! written. Imports, references and the commit-wide passes after them are
+//! written separately; the run status is how a scan that stopped among them
+//! is noticed, and deleting the run, as the next scan of its commit does,
+//! is how it is undone.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use mother_core::graph::model::{ScanRun, ScanStatus};
+use mother_core::graph::{GraphStore, ScanRunResult};
+use tracing::{info, warn};
+
+/// How long a run may stay in progress before it is taken to have been
+/// killed; longer than CI jobs are commonly allowed to run
+pub(crate) const STALE_AFTER: TimeDelta = TimeDelta::hours(6);
+
+/// Whether `run` ended without completing at `now`: it failed, ran out of
+/// time, or has been in progress for longer than [`STALE_AFTER`]
+pub fn is_incomplete(run: &ScanRunResult, now: DateTime<Utc>) -> bool {
+    if run.status == ScanStatus::InProgress.to_string() {
+        return DateTime::parse_from_rfc3339(&run.scanned_at)
+            .is_ok_and(|started| now.signed_duration_since(started) > STALE_AFTER);
+    }
+    run.status != ScanStatus::Completed.to_string()
+}
+
+/// Record `scan_run` as in progress
+///
+/// Returns `true` if its commit is new and has to be scanned. A run of a
+/// commit scanned before is marked completed right away. Earlier runs of
+/// the commit that all ended incomplete are deleted first, so the commit
+/// is scanned again.
+///
+/// # Errors
+/// Returns an error if another run of the commit is still in progress, or
+/// if the store fails.
+pub async fn begin(client: &dyn GraphStore, scan_run: &ScanRun) -> Result<bool> {
+    clear_incomplete_runs(client, scan_run).await?;
+
+    let run = scan_run.clone().with_status(ScanStatus::InProgress);
+    if client.create_scan_run(&run).await? {
+        return Ok(true);
+    }
+    info!("✓ Commit already scanned, linked scan run to existing data");
+    client
+        .set_scan_status(&scan_run.id, ScanStatus::Completed)
+        .await?;
+    Ok(false)
+}
+
+/// Delete the earlier runs of `scan_run`'s commit if none of them
+/// completed
+async fn clear_incomplete_runs(client: &dyn GraphStore, scan_run: &ScanRun) -> Result<()> {
+    let commit_sha = scan_run.commit_sha.clone().unwrap_or_default();
+    if commit_sha.is_empty() {
+        return Ok(());
+    }
+    let runs = client.scan_runs().await?;
+    let earlier: Vec<_> = runs.iter().filter(|r| r.commit_sha == commit_sha).collect();
+    let completed = ScanStatus::Completed.to_string();
+    if earlier.iter().any(|r| r.status == completed) {
+        return Ok(());
+    }
+    if let Some(running) = earlier
+        .iter()
+        .find(|r| !is_incomplete(r, scan_run.scanned_at))
+    {
+        bail!(
+            "Commit {commit_sha} is being scanned by run {} since {}; scan it again once \
+             that run ends",
+            running.id,
+            running.scanned_at
+        );
+    }
+    for run in earlier {
+        warn!(
+            "Deleting {} scan run {} to scan commit {commit_sha} again",
+            run.status, run.id
+        );
+        client.delete_scan_run(&run.id).await?;
+    }
+    Ok(())
+}
+
+/// Mark the run `scan_run_id` completed or failed by the `result` of its
+/// scan, and pass the result on
+///
+/// # Errors
+/// Returns the scan's own error, or an error if a completed run cannot be
+/// marked. Failing to mark a failed run is only logged.
+pub async fn finish(client: &dyn GraphStore, scan_run_id: &str, result: Result<()>) -> Result<()> {
+    finish_as(client, scan_run_id, result.map(|()| ScanStatus::Completed)).await
+}
+
+/// Mark the run `scan_run_id` with the status its scan ended in, or failed
+/// if the scan returned an error, and pass the error on
+///
+/// # Errors
+/// Returns the scan's own error, or an error if a finished run cannot be
+/// marked. Failing to mark a failed run is only logged.
+pub(crate) async fn finish_as(
+    client: &dyn GraphStore,
+    scan_run_id: &str,
+    result: Result<ScanStatus>,
+) -> Result<()> {
+    let status = match &result {
+        Ok(status) => *status,
+        Err(_) => ScanStatus::Failed,
+    };
+    let result = result.map(|_| ());
+    match client.set_scan_status(scan_run_id, status).await {
+        Ok(()) => result,
+        Err(e) if result.is_err() => {
+            warn!("Failed to mark scan run {scan_run_id} as failed: {e}");
+            result
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3 @@
+//! Tests for the scan status module
+
+mod tests_status;
@@ -0,0 +1,113 @@
+//! Tests for recording scan runs as in progress and marking how they ended
+
+#![allow(clippy::unwrap_used)]
+
+use anyhow::anyhow;
+use mother_core::graph::model::{ScanRun, ScanStatus};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+use super::super::{begin, finish, finish_as, STALE_AFTER};
+
+/// The statuses of the runs in `store`, newest first
+async fn statuses(store: &SqliteStore) -> Vec<String> {
+    let runs = store.scan_runs().await.unwrap();
+    runs.into_iter().map(|r| r.status).collect()
+}
+
+#[tokio::test]
+async fn test_begin_records_a_new_commit_as_in_progress() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+
+    assert!(begin(&store, &run).await.unwrap());
+    assert_eq!(statuses(&store).await, vec!["in_progress"]);
+    assert_eq!(store.resolve_commit(None).await.unwrap(), None);
+
+    finish(&store, &run.id, Ok(())).await.unwrap();
+    assert_eq!(statuses(&store).await, vec!["completed"]);
+    assert_eq!(
+        store.resolve_commit(None).await.unwrap().as_deref(),
+        Some("abc123")
+    );
+}
+
+#[tokio::test]
+async fn test_begin_completes_runs_of_a_scanned_commit() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let first = ScanRun::new("/repo").with_commit("abc123");
+    begin(&store, &first).await.unwrap();
+    finish(&store, &first.id, Ok(())).await.unwrap();
+
+    let mut second = ScanRun::new("/repo").with_commit("abc123");
+    second.scanned_at += chrono::Duration::seconds(1);
+    assert!(!begin(&store, &second).await.unwrap());
+    assert_eq!(statuses(&store).await, vec!["completed", "completed"]);
+}
+
+#[tokio::test]
+async fn test_finish_marks_failed_scans_and_keeps_the_error() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    begin(&store, &run).await.unwrap();
+
+    let error = finish(&store, &run.id, Err(anyhow!("phase 2 failed")))
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "phase 2 failed");
+    assert_eq!(statuses(&store).await, vec!["failed"]);
+}
+
+#[tokio::test]
+async fn test_begin_rescans_commits_with_only_incomplete_runs() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    begin(&store, &run).await.unwrap();
+    store
+        .create_file_if_new("src/lib.rs", "hash-lib", "rust", "abc123")
+        .await
+        .unwrap();
+    finish(&store, &run.id, Err(anyhow!("killed"))).await.ok();
+
+    let retry = ScanRun::new("/repo").with_commit("abc123");
+    assert!(begin(&store, &retry).await.unwrap());
+
+    // The failed run is gone with the file only it kept
+    let runs = store.scan_runs().await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].id, retry.id);
+    assert_eq!(store.stats().await.unwrap().files, 0);
+}
+
+#[tokio::test]
+async fn test_begin_waits_for_runs_in_progress_until_they_are_stale() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    begin(&store, &run).await.unwrap();
+
+    let mut concurrent = ScanRun::new("/repo").with_commit("abc123");
+    concurrent.scanned_at = run.scanned_at + chrono::Duration::minutes(10);
+    let error = begin(&store, &concurrent).await.unwrap_err();
+    assert!(error.to_string().contains("is being scanned"), "{error}");
+
+    let mut later = ScanRun::new("/repo").with_commit("abc123");
+    later.scanned_at = run.scanned_at + STALE_AFTER + chrono::Duration::minutes(1);
+    assert!(begin(&store, &later).await.unwrap());
+    assert_eq!(statuses(&store).await, vec!["in_progress"]);
+}
+
+#[tokio::test]
+async fn test_finish_as_marks_partial_scans_as_incomplete() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let run = ScanRun::new("/repo").with_commit("abc123");
+    begin(&store, &run).await.unwrap();
+
+    finish_as(&store, &run.id, Ok(ScanStatus::Partial))
+        .await
+        .unwrap();
+    assert_eq!(statuses(&store).await, vec!["partial"]);
+    assert_eq!(store.resolve_commit(None).await.unwrap(), None);
+
+    let retry = ScanRun::new("/repo").with_commit("abc123");
+    assert!(begin(&store, &retry).await.unwrap());
+    assert_eq!(statuses(&store).await, vec!["in_progress"]);
+}
@@ -0,0 +1,73 @@
+//! Testing: TESTS edges from the commit's tests to the code they exercise
+//!
+//! Runs after the complexity pass, once the commit's reference edges are
+//! stored. [`mother_core::graph::testing`] recognizes the tests among the
+//! commit's symbols and each pair of a test and a production symbol it
+//! references or calls that is not linked yet gets a TESTS edge. Symbols
+//! are shared between commits, so pairs linked by an earlier scan are not
+//! linked again.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::Result;
+use mother_core::graph::model::EdgeKind;
+use mother_core::graph::testing::test_edges;
+use mother_core::graph::GraphStore;
+use tracing::info;
+
+use super::metrics::PhaseCounts;
+
+/// Outcome of the testing pass
+#[derive(Debug, Default)]
+pub struct TestingResult {
+    pub symbol_count: usize,
+    /// TESTS edges created
+    pub edge_count: usize,
+}
+
+impl TestingResult {
+    /// Work done by the pass, for throughput metrics
+    pub(crate) fn counts(&self) -> PhaseCounts {
+        PhaseCounts {
+            files: 0,
+            symbols: self.symbol_count,
+            attempted: self.symbol_count,
+            errors: 0,
+        }
+    }
+}
+
+/// Link the tests of a commit scanned under `root` to the symbols they
+/// exercise
+pub async fn run(root: &Path, client: &dyn GraphStore, commit_sha: &str) -> Result<TestingResult> {
+    let symbols = client.symbols_at_commit(commit_sha).await?;
+    let graph = client.export_commit(commit_sha).await?;
+    let kind = EdgeKind::Tests.to_string();
+    let linked: HashSet<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == kind)
+        .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+        .collect();
+
+    let mut edge_count = 0;
+    for edge in test_edges(root, &symbols, &graph) {
+        if !linked.contains(&(edge.source_id.as_str(), edge.target_id.as_str())) {
+            client.create_edge(&edge).await?;
+            edge_count += 1;
+        }
+    }
+    let result = TestingResult {
+        symbol_count: symbols.len(),
+        edge_count,
+    };
+    info!(
+        "Linked tests to tested symbols with {} TESTS edges",
+        result.edge_count
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
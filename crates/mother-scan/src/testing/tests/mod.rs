@@ -0,0 +1,3 @@
+//! Tests for testing module
+
+mod tests_testing;
@@ -0,0 +1,92 @@
+//! Tests for linking the tests of a commit to the symbols they exercise
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use mother_core::graph::model::{Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+use super::super::run;
+
+fn symbol(id: &str, file_path: &str) -> SymbolNode {
+    SymbolNode {
+        visibility: Some("pub".to_string()),
+        ..SymbolNode::new(id, id, SymbolKind::Function, file_path, 1, 3)
+    }
+}
+
+fn edge(source: &str, target: &str, kind: EdgeKind) -> Edge {
+    Edge {
+        source_id: source.to_string(),
+        target_id: target.to_string(),
+        kind,
+        line: None,
+        column: None,
+    }
+}
+
+/// A store with one commit holding a library and its test file
+async fn store_with_tests() -> SqliteStore {
+    let store = SqliteStore::open_in_memory().unwrap();
+    store
+        .create_scan_run(&ScanRun::new("/repo").with_commit("abc123"))
+        .await
+        .unwrap();
+    let files = [
+        (
+            "/repo/src/lib.rs",
+            vec![
+                symbol("parse", "/repo/src/lib.rs"),
+                symbol("render", "/repo/src/lib.rs"),
+            ],
+        ),
+        (
+            "/repo/tests/parse.rs",
+            vec![symbol("test_parse", "/repo/tests/parse.rs")],
+        ),
+    ];
+    for (path, symbols) in &files {
+        let hash = format!("hash-{path}");
+        store
+            .create_file_if_new(path, &hash, "rust", "abc123")
+            .await
+            .unwrap();
+        store.create_symbols_batch(symbols, &hash).await.unwrap();
+    }
+    store
+        .create_edge(&edge("test_parse", "parse", EdgeKind::Calls))
+        .await
+        .unwrap();
+    store
+        .create_edge(&edge("parse", "render", EdgeKind::Calls))
+        .await
+        .unwrap();
+    store
+}
+
+async fn tests_edges(store: &SqliteStore) -> Vec<(String, String)> {
+    let graph = store.export_commit("abc123").await.unwrap();
+    graph
+        .edges
+        .into_iter()
+        .filter(|edge| edge.kind == "TESTS")
+        .map(|edge| (edge.source, edge.target))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_run_links_tests_to_called_symbols_once() {
+    let store = store_with_tests().await;
+
+    let result = run(Path::new("/repo"), &store, "abc123").await.unwrap();
+    let again = run(Path::new("/repo"), &store, "abc123").await.unwrap();
+
+    assert_eq!(result.symbol_count, 3);
+    assert_eq!(result.edge_count, 1);
+    assert_eq!(again.edge_count, 0);
+    assert_eq!(
+        tests_edges(&store).await,
+        vec![("test_parse".to_string(), "parse".to_string())]
+    );
+}
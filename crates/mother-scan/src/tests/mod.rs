@@ -3,4 +3,5 @@
 mod tests_connect_neo4j;
 mod tests_create_scan_run;
 mod tests_execute_scan;
+mod tests_run_bare;
 mod tests_shutdown_lsp;
@@ -5,6 +5,7 @@
 //! instance are not included here, as they would require test infrastructure setup.
 
 use super::super::connect_neo4j;
+use mother_core::graph::neo4j::Neo4jConfig;
 
 // ============================================================================
 // Configuration Creation Tests
@@ -21,7 +22,7 @@ async fn test_connect_neo4j_creates_valid_config() {
     let user = "neo4j";
     let password = "testpassword";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // We expect an error since there's no Neo4j instance running
     // The important part is that the function accepts valid parameters
@@ -42,7 +43,7 @@ async fn test_connect_neo4j_with_empty_uri() {
     let user = "neo4j";
     let password = "password";
 
-    let _result = connect_neo4j(uri, user, password).await;
+    let _result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 }
 
 #[tokio::test]
@@ -52,7 +53,7 @@ async fn test_connect_neo4j_with_empty_user() {
     let user = "";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should fail - connection should require valid credentials
     assert!(result.is_err(), "Expected error with empty username");
@@ -65,7 +66,7 @@ async fn test_connect_neo4j_with_empty_password() {
     let user = "neo4j";
     let password = "";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should fail - empty password is typically invalid
     assert!(result.is_err(), "Expected error with empty password");
@@ -81,7 +82,7 @@ async fn test_connect_neo4j_with_all_empty_params() {
     let user = "";
     let password = "";
 
-    let _result = connect_neo4j(uri, user, password).await;
+    let _result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 }
 
 // ============================================================================
@@ -95,7 +96,7 @@ async fn test_connect_neo4j_with_bolt_uri() {
     let user = "testuser";
     let password = "testpass";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should attempt connection (and fail without server)
     assert!(result.is_err(), "Expected connection error without server");
@@ -108,7 +109,7 @@ async fn test_connect_neo4j_with_neo4j_uri() {
     let user = "testuser";
     let password = "testpass";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should attempt connection (and fail without server)
     assert!(result.is_err(), "Expected connection error without server");
@@ -121,7 +122,7 @@ async fn test_connect_neo4j_with_invalid_uri_format() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should fail with invalid URI
     assert!(result.is_err(), "Expected error with invalid URI format");
@@ -134,7 +135,7 @@ async fn test_connect_neo4j_with_http_uri() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should fail - HTTP is not valid for Neo4j driver
     assert!(result.is_err(), "Expected error with HTTP URI");
@@ -151,7 +152,7 @@ async fn test_connect_neo4j_with_special_chars_in_password() {
     let user = "neo4j";
     let password = "p@ssw0rd!#$%^&*()";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should accept special characters in password
     // (will fail on connection, but that's expected)
@@ -165,7 +166,7 @@ async fn test_connect_neo4j_with_unicode_in_password() {
     let user = "neo4j";
     let password = "пароль密码🔐";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should handle unicode in password
     assert!(result.is_err(), "Expected connection error without server");
@@ -178,7 +179,7 @@ async fn test_connect_neo4j_with_whitespace_in_params() {
     let user = " neo4j ";
     let password = " password ";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should fail - whitespace should not be trimmed automatically
     assert!(
@@ -198,7 +199,7 @@ async fn test_connect_neo4j_with_non_standard_port() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should accept non-standard port
     assert!(result.is_err(), "Expected connection error without server");
@@ -211,7 +212,7 @@ async fn test_connect_neo4j_without_port() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should use default port
     assert!(result.is_err(), "Expected connection error without server");
@@ -228,7 +229,7 @@ async fn test_connect_neo4j_with_ipv4_address() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should accept IPv4 address
     assert!(result.is_err(), "Expected connection error without server");
@@ -241,7 +242,7 @@ async fn test_connect_neo4j_with_ipv6_address() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should accept IPv6 address
     assert!(result.is_err(), "Expected connection error without server");
@@ -254,7 +255,7 @@ async fn test_connect_neo4j_with_domain_name() {
     let user = "produser";
     let password = "prodpassword";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should accept FQDN
     assert!(result.is_err(), "Expected connection error without server");
@@ -271,7 +272,7 @@ async fn test_connect_neo4j_with_long_username() {
     let user = &"a".repeat(1000);
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should handle long username (fail on connection)
     assert!(result.is_err(), "Expected connection error without server");
@@ -284,7 +285,7 @@ async fn test_connect_neo4j_with_long_password() {
     let user = "neo4j";
     let password = &"p".repeat(1000);
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should handle long password (fail on connection)
     assert!(result.is_err(), "Expected connection error without server");
@@ -301,7 +302,7 @@ async fn test_connect_neo4j_propagates_connection_errors() {
     let user = "neo4j";
     let password = "password";
 
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Should return an error (wrapped in anyhow::Error)
     assert!(result.is_err(), "Expected error to be propagated");
@@ -333,7 +334,7 @@ async fn test_connect_neo4j_usage_documentation() {
     let password = "password";
 
     // Function accepts string slices and returns Result<Neo4jClient>
-    let result = connect_neo4j(uri, user, password).await;
+    let result = connect_neo4j(&Neo4jConfig::new(uri, user, password)).await;
 
     // Without a real server, we expect an error
     assert!(result.is_err());
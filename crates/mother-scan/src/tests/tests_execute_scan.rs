@@ -7,7 +7,8 @@ use tempfile::TempDir;
 
 // Import the parent module functions through super
 use super::super::{create_scan_run, log_scan_run_info, log_scan_summary, shutdown_lsp};
-use super::super::{Phase1Result, Phase2Result, Phase3Result};
+use super::super::{Phase1Result, Phase2Result, Phase3Result, SymbolBuffer};
+use mother_core::enrich::EnricherTotals;
 use mother_core::graph::model::ScanRun;
 use mother_core::lsp::LspServerManager;
 
@@ -91,17 +92,22 @@ fn test_log_scan_summary_no_errors() {
         new_file_count: 10,
         reused_file_count: 5,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 100,
         error_count: 0,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 50,
         error_count: 0,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     // Should not panic
@@ -115,17 +121,22 @@ fn test_log_scan_summary_with_errors() {
         new_file_count: 10,
         reused_file_count: 5,
         error_count: 2,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 100,
         error_count: 3,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 50,
         error_count: 1,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     // Should not panic with errors
@@ -139,17 +150,22 @@ fn test_log_scan_summary_all_zeros() {
         new_file_count: 0,
         reused_file_count: 0,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 0,
         error_count: 0,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 0,
         error_count: 0,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     // Should handle zero counts gracefully
@@ -163,17 +179,22 @@ fn test_log_scan_summary_large_counts() {
         new_file_count: 10000,
         reused_file_count: 5000,
         error_count: 100,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 50000,
         error_count: 200,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 100000,
         error_count: 50,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     // Should handle large counts
@@ -187,17 +208,22 @@ fn test_log_scan_summary_only_phase1_errors() {
         new_file_count: 5,
         reused_file_count: 3,
         error_count: 10,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 20,
         error_count: 0,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 15,
         error_count: 0,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     log_scan_summary(&phase1, &phase2, &phase3);
@@ -210,17 +236,22 @@ fn test_log_scan_summary_only_phase2_errors() {
         new_file_count: 5,
         reused_file_count: 3,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 20,
         error_count: 8,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 15,
         error_count: 0,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     log_scan_summary(&phase1, &phase2, &phase3);
@@ -233,17 +264,22 @@ fn test_log_scan_summary_only_phase3_errors() {
         new_file_count: 5,
         reused_file_count: 3,
         error_count: 0,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: 20,
         error_count: 0,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: 15,
         error_count: 12,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     log_scan_summary(&phase1, &phase2, &phase3);
@@ -326,20 +362,20 @@ fn test_log_scan_run_info_special_branch_names() {
 #[tokio::test]
 async fn test_shutdown_lsp_empty_manager() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
 
     // Should not panic even with no servers running
-    shutdown_lsp(&mut lsp_manager).await;
+    shutdown_lsp(&lsp_manager).await;
 }
 
 #[tokio::test]
 async fn test_shutdown_lsp_multiple_calls() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
-    let mut lsp_manager = LspServerManager::new(temp_dir.path());
+    let lsp_manager = LspServerManager::new(temp_dir.path());
 
     // Should handle multiple shutdown calls gracefully
-    shutdown_lsp(&mut lsp_manager).await;
-    shutdown_lsp(&mut lsp_manager).await;
+    shutdown_lsp(&lsp_manager).await;
+    shutdown_lsp(&lsp_manager).await;
 }
 
 // ============================================================================
@@ -394,17 +430,22 @@ fn test_log_scan_summary_max_values() {
         new_file_count: large_val,
         reused_file_count: large_val,
         error_count: large_val,
+        failures: Vec::new(),
     };
 
     let phase2 = Phase2Result {
-        symbols: vec![],
+        symbols: SymbolBuffer::default(),
         symbol_count: large_val,
         error_count: large_val,
+        failures: Vec::new(),
+        enrichers: EnricherTotals::default(),
     };
 
     let phase3 = Phase3Result {
         reference_count: large_val,
         error_count: large_val,
+        failures: Vec::new(),
+        external_count: 0,
     };
 
     // Should handle large values without overflow
@@ -462,17 +503,22 @@ fn test_log_scan_summary_mixed_success_and_errors() {
             new_file_count: new,
             reused_file_count: reused,
             error_count: e1,
+            failures: Vec::new(),
         };
 
         let phase2 = Phase2Result {
-            symbols: vec![],
+            symbols: SymbolBuffer::default(),
             symbol_count: symbols,
             error_count: e2,
+            failures: Vec::new(),
+            enrichers: EnricherTotals::default(),
         };
 
         let phase3 = Phase3Result {
             reference_count: refs,
             error_count: e3,
+            failures: Vec::new(),
+            external_count: 0,
         };
 
         log_scan_summary(&phase1, &phase2, &phase3);
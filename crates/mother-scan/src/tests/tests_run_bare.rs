@@ -0,0 +1,114 @@
+//! Tests for scanning commits of bare repositories
+//!
+//! The repositories hold no source files, so the scans need no language
+//! servers.
+
+#![allow(clippy::unwrap_used)]
+
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use super::super::{run_bare_with_store, ScanOptions};
+use mother_core::graph::{GraphStore, SqliteStore};
+
+/// Create a bare repository with one commit per README content
+///
+/// Returns the commit SHAs in order; `main` points at the last commit.
+fn bare_repo(dir: &Path, readmes: &[&str]) -> Vec<String> {
+    let repo = git2::Repository::init_bare(dir).unwrap();
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let mut shas: Vec<String> = Vec::new();
+
+    for readme in readmes {
+        let blob = repo.blob(readme.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("README.md", blob, 0o100_644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parent = shas
+            .last()
+            .map(|sha| repo.find_commit(git2::Oid::from_str(sha).unwrap()).unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo
+            .commit(
+                Some("refs/heads/main"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        shas.push(oid.to_string());
+    }
+    shas
+}
+
+fn options(version: &str) -> ScanOptions {
+    ScanOptions {
+        version: Some(version.to_string()),
+        ..ScanOptions::default()
+    }
+}
+
+#[tokio::test]
+async fn test_run_bare_records_the_requested_commit() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &["# One", "# Two"]);
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    run_bare_with_store(temp.path(), &shas[0], &store, &options("v1"))
+        .await
+        .unwrap();
+    run_bare_with_store(temp.path(), "main", &store, &options("v2"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store.resolve_commit(Some("v1")).await.unwrap(),
+        Some(shas[0].clone())
+    );
+    assert_eq!(
+        store.resolve_commit(Some("v2")).await.unwrap(),
+        Some(shas[1].clone())
+    );
+}
+
+#[tokio::test]
+async fn test_run_bare_rescan_of_same_commit_succeeds() {
+    let temp = TempDir::new().unwrap();
+    let shas = bare_repo(temp.path(), &["# One"]);
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    for version in ["v1", "v1-again"] {
+        let result = run_bare_with_store(temp.path(), &shas[0], &store, &options(version)).await;
+        assert!(result.is_ok(), "scan as {version} failed: {result:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_run_bare_unknown_commit() {
+    let temp = TempDir::new().unwrap();
+    bare_repo(temp.path(), &["# One"]);
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let result = run_bare_with_store(temp.path(), "no-such-branch", &store, &options("v1")).await;
+
+    assert!(result.is_err());
+    assert_eq!(store.resolve_commit(Some("v1")).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_run_bare_missing_repository() {
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let result = run_bare_with_store(
+        Path::new("/this/path/does/not/exist.git"),
+        "main",
+        &store,
+        &options("v1"),
+    )
+    .await;
+
+    assert!(result.is_err());
+}
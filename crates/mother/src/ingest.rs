@@ -0,0 +1,224 @@
+//! Ingestor: Run the full scan pipeline against a graph store
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use mother_core::enrich::EnrichmentConfig;
+use mother_core::graph::ids::SymbolIdStrategy;
+use mother_core::graph::GraphStore;
+use mother_core::lsp::{LspServerConfig, Readiness};
+use mother_core::progress::Progress;
+use mother_core::scanner::Language;
+use mother_scan::ScanOptions;
+
+/// Options an [`Ingestor`] scans with
+///
+/// Start from [`IngestOptions::default`], the defaults of `mother scan`, and
+/// change them with the `with_*` methods. New options can be added in any
+/// release, so the fields are not public.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct IngestOptions {
+    version: Option<String>,
+    enrichment: EnrichmentConfig,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    max_file_size: Option<u64>,
+    generated_markers: Option<Vec<String>>,
+    lsp_servers: Vec<LspServerConfig>,
+    readiness: Vec<(Language, Readiness)>,
+    max_references: Option<usize>,
+    symbol_buffer: Option<NonZeroUsize>,
+    max_duration: Option<Duration>,
+    diagnostics: bool,
+    external_refs: bool,
+    progress: Progress,
+    id_strategy: Option<Arc<dyn SymbolIdStrategy>>,
+}
+
+impl IngestOptions {
+    /// Tag the scan run with a version, such as a release number
+    #[must_use]
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Version the scan run is tagged with
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Run these enrichers in Phase 2 instead of the default ones
+    #[must_use]
+    pub fn with_enrichment(mut self, enrichment: EnrichmentConfig) -> Self {
+        self.enrichment = enrichment;
+        self
+    }
+
+    /// Only scan files matching these globs; empty scans everything
+    #[must_use]
+    pub fn with_include(mut self, globs: Vec<String>) -> Self {
+        self.include = globs;
+        self
+    }
+
+    /// Skip files matching these globs
+    #[must_use]
+    pub fn with_exclude(mut self, globs: Vec<String>) -> Self {
+        self.exclude = globs;
+        self
+    }
+
+    /// Skip files larger than `bytes`
+    #[must_use]
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Skip files with one of `markers` in their first lines as generated,
+    /// instead of the default markers; empty keeps generated files
+    #[must_use]
+    pub fn with_generated_markers(mut self, markers: Vec<String>) -> Self {
+        self.generated_markers = Some(markers);
+        self
+    }
+
+    /// Start `server` for its language instead of the default server
+    #[must_use]
+    pub fn with_lsp_server(mut self, server: LspServerConfig) -> Self {
+        self.lsp_servers.push(server);
+        self
+    }
+
+    /// Wait for the language server of `language` as `readiness` says
+    /// after it starts
+    #[must_use]
+    pub fn with_readiness(mut self, language: Language, readiness: Readiness) -> Self {
+        self.readiness.push((language, readiness));
+        self
+    }
+
+    /// Store at most `max` references per symbol, and a sample above it
+    #[must_use]
+    pub fn with_max_references(mut self, max: usize) -> Self {
+        self.max_references = Some(max);
+        self
+    }
+
+    /// Hold at most `max` symbols in memory between symbol and reference
+    /// extraction, spilling the rest to a temporary file
+    #[must_use]
+    pub fn with_symbol_buffer(mut self, max: NonZeroUsize) -> Self {
+        self.symbol_buffer = Some(max);
+        self
+    }
+
+    /// Stop starting new LSP work after `duration`, ending the scan run as
+    /// partial
+    #[must_use]
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Store the diagnostics language servers report for each file
+    #[must_use]
+    pub fn with_diagnostics(mut self, diagnostics: bool) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Link calls into code outside the scan to external symbols
+    #[must_use]
+    pub fn with_external_refs(mut self, external_refs: bool) -> Self {
+        self.external_refs = external_refs;
+        self
+    }
+
+    /// Send progress events of each phase to `progress`
+    #[must_use]
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Derive the IDs of stored symbols with `strategy` instead of content
+    /// hashes
+    #[must_use]
+    pub fn with_symbol_ids(mut self, strategy: Arc<dyn SymbolIdStrategy>) -> Self {
+        self.id_strategy = Some(strategy);
+        self
+    }
+
+    /// The options of the scan pipeline these stand for
+    pub(crate) fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            version: self.version.clone(),
+            enrichment: self.enrichment.clone(),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            max_file_size: self.max_file_size,
+            generated_markers: self.generated_markers.clone(),
+            lsp_servers: self.lsp_servers.clone(),
+            readiness: self.readiness.clone(),
+            max_references: self.max_references,
+            symbol_buffer: self.symbol_buffer,
+            max_duration: self.max_duration,
+            diagnostics: self.diagnostics,
+            external_refs: self.external_refs,
+            progress: self.progress.clone(),
+            id_strategy: self.id_strategy.clone(),
+            ..ScanOptions::default()
+        }
+    }
+}
+
+/// Scans a repository into a [`GraphStore`]
+///
+/// Runs file discovery, import linking, symbol extraction and reference
+/// extraction, the same pipeline as `mother scan`.
+pub struct Ingestor<'a> {
+    store: &'a dyn GraphStore,
+    options: IngestOptions,
+}
+
+impl<'a> Ingestor<'a> {
+    /// Create an ingestor writing to `store` with default options
+    #[must_use]
+    pub fn new(store: &'a dyn GraphStore) -> Self {
+        Self {
+            store,
+            options: IngestOptions::default(),
+        }
+    }
+
+    /// Replace the scan options
+    #[must_use]
+    pub fn with_options(mut self, options: IngestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Options the next ingestion runs with
+    #[must_use]
+    pub fn options(&self) -> &IngestOptions {
+        &self.options
+    }
+
+    /// Scan the repository at `path` into the store
+    ///
+    /// A commit that is already in the store is linked to the new scan run
+    /// instead of being scanned again.
+    ///
+    /// # Errors
+    /// Returns an error if discovery, LSP extraction or a store write fails.
+    pub async fn ingest(&self, path: &Path) -> Result<()> {
+        mother_scan::run_with_store(path, self.store, &self.options.scan_options()).await
+    }
+}
@@ -0,0 +1,73 @@
+//! mother: Stable library API for semantic graph ingestion via LSP
+//!
+//! This crate is the supported entry point for using mother as a library.
+//! It re-exports a curated set of types from `mother-core` and runs the scan
+//! pipeline of `mother-scan` behind its own [`IngestOptions`], so downstream
+//! projects do not depend on either crate's internal module layout. Items
+//! not reachable from here may change between releases.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use mother::{GraphReader, Ingestor, SqliteStore};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let store = SqliteStore::open(Path::new("graph.db"))?;
+//! Ingestor::new(&store).ingest(Path::new(".")).await?;
+//!
+//! let reader = GraphReader::new(&store);
+//! println!("{} symbols", reader.stats().await?.symbols);
+//! for caller in reader.references_to("parse").await? {
+//!     println!("{} in {}", caller.source_name, caller.source_file);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod ingest;
+
+#[cfg(test)]
+mod tests;
+
+pub use ingest::{IngestOptions, Ingestor};
+
+// Discovery
+pub use mother_core::scanner::{DiscoveredFile, Language, Scanner};
+
+// Storage
+pub use mother_core::graph::model::{
+    DirectorySummary, Edge, EdgeKind, ScanRun, SymbolKind, SymbolNode,
+};
+pub use mother_core::graph::neo4j::{Neo4jClient, Neo4jConfig};
+pub use mother_core::graph::{GraphReader, GraphStore, SqliteStore, StoreError};
+
+// Pipeline configuration
+pub use mother_core::enrich::{EnrichmentConfig, EnrichmentPipeline, HoverSettings};
+pub use mother_core::graph::ids::{
+    ContentHashIds, QualifiedNameIds, SymbolIdScheme, SymbolIdStrategy, UuidIds,
+};
+pub use mother_core::lsp::{LspServerConfig, Readiness};
+pub use mother_core::progress::{Progress, ProgressEvent, ProgressReporter, ScanPhase};
+
+/// Result types, paging and scopes for [`GraphReader`] and [`GraphStore`]
+/// read queries, and analyses over exported snapshots
+pub mod query {
+    pub use mother_core::graph::api_surface::{api_surface, ApiSymbol};
+    pub use mother_core::graph::diff::{
+        diff_symbols, ChangeKind, FileDiff, SymbolChange, SymbolDiff,
+    };
+    pub use mother_core::graph::{
+        FileResult, GraphStats, ImporterResult, Page, PathScope, ReferenceResult, SortKey,
+        SymbolMatch, SymbolResult, SymbolVersionResult,
+    };
+}
+
+/// Graph snapshots, file formats and snapshot comparison
+pub mod export {
+    pub use mother_core::graph::export::compare::{compare, CompareReport, DiffCause, Difference};
+    pub use mother_core::graph::export::{
+        read_jsonl, write_graph, ExportEdge, ExportFormat, ExportNode, GraphExport,
+    };
+}
@@ -0,0 +1,3 @@
+//! Tests for the mother facade
+
+mod tests_ingest;
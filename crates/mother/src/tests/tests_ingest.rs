@@ -0,0 +1,60 @@
+//! Tests for the Ingestor
+
+#![allow(clippy::unwrap_used)]
+
+use tempfile::TempDir;
+
+use crate::{GraphStore, IngestOptions, Ingestor, SqliteStore};
+
+#[tokio::test]
+async fn test_ingest_empty_repository_records_scan_run() {
+    let repo = TempDir::new().unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    Ingestor::new(&store).ingest(repo.path()).await.unwrap();
+
+    let stats = store.stats().await.unwrap();
+    assert_eq!(stats.scan_runs, 1);
+    assert_eq!(stats.files, 0);
+    assert_eq!(stats.symbols, 0);
+}
+
+#[tokio::test]
+async fn test_ingest_skips_excluded_files() {
+    let repo = TempDir::new().unwrap();
+    std::fs::write(repo.path().join("lib.rs"), "fn main() {}\n").unwrap();
+    let store = SqliteStore::open_in_memory().unwrap();
+    let options = IngestOptions::default().with_exclude(vec!["*.rs".to_string()]);
+
+    Ingestor::new(&store)
+        .with_options(options)
+        .ingest(repo.path())
+        .await
+        .unwrap();
+
+    assert_eq!(store.stats().await.unwrap().files, 0);
+}
+
+#[test]
+fn test_with_options_replaces_defaults() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    let options = IngestOptions::default().with_version("v1.0.0");
+
+    let ingestor = Ingestor::new(&store).with_options(options);
+
+    assert_eq!(ingestor.options().version(), Some("v1.0.0"));
+}
+
+#[test]
+fn test_options_convert_to_scan_options() {
+    let options = IngestOptions::default()
+        .with_include(vec!["src/**".to_string()])
+        .with_max_references(100)
+        .with_diagnostics(true)
+        .scan_options();
+
+    assert_eq!(options.include, vec!["src/**"]);
+    assert_eq!(options.max_references, Some(100));
+    assert!(options.diagnostics);
+    assert!(options.report_file.is_none());
+}